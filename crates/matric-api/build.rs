@@ -0,0 +1,25 @@
+//! Build script for matric-api.
+//!
+//! Compiles the gRPC protobuf definitions when the optional `grpc` feature is
+//! enabled; default builds do nothing here. Uses a vendored `protoc` so no
+//! system protobuf toolchain is required.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "grpc")]
+    compile_grpc_protos();
+}
+
+#[cfg(feature = "grpc")]
+fn compile_grpc_protos() {
+    println!("cargo:rerun-if-changed=proto");
+
+    let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc is available");
+    std::env::set_var("PROTOC", protoc);
+
+    tonic_prost_build::configure()
+        .build_client(false)
+        .compile_protos(&["proto/fortemi/ingest/v1/ingest.proto"], &["proto"])
+        .expect("compile gRPC protobuf definitions");
+}
@@ -0,0 +1,729 @@
+//! Single-file encrypted full-archive and incremental backups.
+//!
+//! A backup is one `age(zstd(tar))` stream encrypted to a PKE keyset:
+//!
+//! - `metadata.json` — the backup metadata sidecar contents
+//! - `state.json` — note and blob content hashes for incremental diffing
+//! - `database.sql` — plain `pg_dump` output (full backups)
+//! - `changes.json` — the delta against the parent backup (incremental
+//!   backups)
+//! - `collections.json`, `blobs.jsonl`, `notes.jsonl` — per-note snapshots
+//!   (every note in full backups, changed notes in incremental backups)
+//! - `note_index.json` — the notes in `notes.jsonl` and the blobs they use,
+//!   so a single note can be restored without replaying the dump
+//! - `attachments/blobs/..` — attachment blob files from file storage
+//! - `manifest.json` — always last; size and BLAKE3 digest of every entry
+//!
+//! Every layer streams, so neither writing nor restoring holds the archive
+//! in memory. Restores hash each entry as it is staged and reject archives
+//! whose contents do not match the embedded manifest.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use matric_crypto::{decrypt_age_stream, encrypt_age_stream, CryptoError, PrivateKey, PublicKey};
+use serde::{Deserialize, Serialize};
+
+pub const FULL_BACKUP_EXTENSION: &str = ".tar.zst.age";
+pub const FULL_BACKUP_FORMAT_VERSION: &str = "1";
+pub const MANIFEST_ENTRY: &str = "manifest.json";
+pub const METADATA_ENTRY: &str = "metadata.json";
+pub const DATABASE_ENTRY: &str = "database.sql";
+pub const STATE_ENTRY: &str = "state.json";
+pub const CHANGES_ENTRY: &str = "changes.json";
+pub const COLLECTIONS_ENTRY: &str = "collections.json";
+pub const BLOBS_ENTRY: &str = "blobs.jsonl";
+pub const NOTES_ENTRY: &str = "notes.jsonl";
+pub const NOTE_INDEX_ENTRY: &str = "note_index.json";
+/// Top-level entries besides the manifest and attachments.
+const NAMED_ENTRIES: [&str; 8] = [
+    METADATA_ENTRY,
+    STATE_ENTRY,
+    DATABASE_ENTRY,
+    CHANGES_ENTRY,
+    COLLECTIONS_ENTRY,
+    BLOBS_ENTRY,
+    NOTES_ENTRY,
+    NOTE_INDEX_ENTRY,
+];
+const ATTACHMENT_PREFIX: &str = "attachments/";
+const BLOB_DIR: &str = "blobs";
+const BLOB_EXTENSION: &str = "bin";
+const MAX_MANIFEST_BYTES: u64 = 64 * 1024 * 1024;
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum FullBackupError {
+    #[error(transparent)]
+    Crypto(#[from] CryptoError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("{0}")]
+    Integrity(String),
+}
+
+fn integrity(message: impl Into<String>) -> FullBackupError {
+    FullBackupError::Integrity(message.into())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+    pub blake3: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FullBackupManifest {
+    pub format_version: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl FullBackupManifest {
+    pub fn has_entry(&self, path: &str) -> bool {
+        self.entries.iter().any(|entry| entry.path == path)
+    }
+
+    pub fn attachment_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.path.starts_with(ATTACHMENT_PREFIX))
+            .count()
+    }
+}
+
+/// Contents of `note_index.json`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteIndex {
+    pub notes: Vec<NoteIndexEntry>,
+}
+
+/// Where one note's snapshot lives in `notes.jsonl`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteIndexEntry {
+    pub note_id: uuid::Uuid,
+    /// Zero-based line of the snapshot in `notes.jsonl`.
+    pub line: u64,
+    /// Attachment blobs the note uses. Rows for blobs carried by this
+    /// backup are in `blobs.jsonl`.
+    pub blob_ids: Vec<uuid::Uuid>,
+}
+
+impl NoteIndex {
+    pub fn get(&self, note_id: uuid::Uuid) -> Option<&NoteIndexEntry> {
+        self.notes.iter().find(|entry| entry.note_id == note_id)
+    }
+}
+
+/// Result of writing or verifying a full backup.
+#[derive(Debug)]
+pub struct FullBackupSummary {
+    pub manifest: FullBackupManifest,
+    /// BLAKE3 digest of the `manifest.json` bytes embedded in the archive.
+    pub manifest_blake3: String,
+}
+
+pub struct FullBackupSources<'a> {
+    pub metadata: &'a [u8],
+    /// Named entries streamed from staged files, in archive order.
+    pub files: &'a [(&'a str, &'a Path)],
+    /// File storage root; blobs are read from its `blobs/` directory.
+    pub file_storage_root: Option<&'a Path>,
+    /// Storage-relative blob paths to include; `None` includes every blob.
+    pub blob_paths: Option<&'a [String]>,
+}
+
+/// Stream a full backup to `output`, encrypted to `recipients`.
+///
+/// Returns the output writer once the final encrypted chunk is written.
+pub fn write_full_backup<W: Write>(
+    output: W,
+    recipients: &[PublicKey],
+    sources: &FullBackupSources<'_>,
+) -> Result<(W, FullBackupSummary), FullBackupError> {
+    let encrypted = encrypt_age_stream(output, recipients)?;
+    let compressed = zstd::Encoder::new(encrypted, ZSTD_LEVEL)?;
+    let mut builder = tar::Builder::new(compressed);
+    let mut entries = Vec::new();
+
+    entries.push(append_entry(
+        &mut builder,
+        METADATA_ENTRY,
+        sources.metadata.len() as u64,
+        sources.metadata,
+    )?);
+
+    for (name, path) in sources.files {
+        debug_assert!(NAMED_ENTRIES.contains(name));
+        let file = File::open(path)?;
+        let size = file.metadata()?.len();
+        entries.push(append_entry(&mut builder, name, size, file)?);
+    }
+
+    if let Some(root) = sources.file_storage_root {
+        let blob_paths = match sources.blob_paths {
+            Some(paths) => paths.to_vec(),
+            None => collect_blob_paths(root)?,
+        };
+        for relative in blob_paths {
+            if !is_blob_path(&relative) {
+                return Err(integrity("Invalid attachment blob path."));
+            }
+            let file = File::open(root.join(&relative))?;
+            let size = file.metadata()?.len();
+            let path = format!("{ATTACHMENT_PREFIX}{relative}");
+            entries.push(append_entry(&mut builder, &path, size, file)?);
+        }
+    }
+
+    let manifest = FullBackupManifest {
+        format_version: FULL_BACKUP_FORMAT_VERSION.to_string(),
+        created_at: chrono::Utc::now(),
+        entries,
+    };
+    let manifest_bytes = serde_json::to_vec_pretty(&manifest).map_err(io::Error::other)?;
+    let mut header = entry_header(manifest_bytes.len() as u64);
+    builder.append_data(&mut header, MANIFEST_ENTRY, manifest_bytes.as_slice())?;
+
+    let output = builder.into_inner()?.finish()?.finish()?;
+    Ok((
+        output,
+        FullBackupSummary {
+            manifest,
+            manifest_blake3: blake3::hash(&manifest_bytes).to_hex().to_string(),
+        },
+    ))
+}
+
+/// Stream-decrypt a full backup into `staging_dir` and verify its manifest.
+///
+/// Entries keep their archive paths under `staging_dir`, which must be empty.
+/// Nothing staged should be used unless this returns `Ok`.
+pub fn read_full_backup<R: Read>(
+    input: R,
+    private_key: &PrivateKey,
+    staging_dir: &Path,
+) -> Result<FullBackupSummary, FullBackupError> {
+    read_full_backup_selected(input, private_key, staging_dir, &mut |_| true)
+}
+
+/// Like [`read_full_backup`], but stages only the entries `keep` selects.
+///
+/// Skipped entries are still hashed, so the whole archive is verified
+/// against its manifest. `keep` sees entries in archive order, after every
+/// earlier selected entry has been staged; named entries precede
+/// attachments, so it can read them to pick which attachments to stage.
+pub fn read_full_backup_selected<R: Read>(
+    input: R,
+    private_key: &PrivateKey,
+    staging_dir: &Path,
+    keep: &mut dyn FnMut(&str) -> bool,
+) -> Result<FullBackupSummary, FullBackupError> {
+    let decrypted = decrypt_age_stream(input, private_key)?;
+    let mut archive = tar::Archive::new(zstd::Decoder::new(decrypted)?);
+    let mut staged = Vec::new();
+    let mut seen = HashSet::new();
+    let mut manifest = None;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if manifest.is_some() {
+            return Err(integrity("Backup has entries after its manifest."));
+        }
+        if entry.header().entry_type() != tar::EntryType::Regular {
+            return Err(integrity("Backup contains a non-file entry."));
+        }
+        let path = entry_path(&entry)?;
+        let size = entry.size();
+
+        if path == MANIFEST_ENTRY {
+            if size > MAX_MANIFEST_BYTES {
+                return Err(integrity("Backup manifest is too large."));
+            }
+            let mut bytes = Vec::with_capacity(size as usize);
+            entry.read_to_end(&mut bytes)?;
+            let parsed: FullBackupManifest = serde_json::from_slice(&bytes)
+                .map_err(|_| integrity("Backup manifest is not valid JSON."))?;
+            manifest = Some((parsed, blake3::hash(&bytes).to_hex().to_string()));
+            continue;
+        }
+
+        if !seen.insert(path.clone()) {
+            return Err(integrity("Backup contains a duplicate entry."));
+        }
+        let (blake3, written) = if keep(&path) {
+            let target = staging_dir.join(&path);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let file = File::options().write(true).create_new(true).open(&target)?;
+            let mut writer = HashingWriter::new(file);
+            io::copy(&mut entry, &mut writer)?;
+            let (file, blake3, written) = writer.finish();
+            file.sync_all()?;
+            (blake3, written)
+        } else {
+            let mut writer = HashingWriter::new(io::sink());
+            io::copy(&mut entry, &mut writer)?;
+            let (_, blake3, written) = writer.finish();
+            (blake3, written)
+        };
+        staged.push(ManifestEntry {
+            path,
+            size: written,
+            blake3,
+        });
+    }
+
+    // Read through the tar padding so the final encrypted chunk is
+    // authenticated; a truncated stream fails here.
+    io::copy(&mut archive.into_inner(), &mut io::sink())?;
+
+    let (manifest, manifest_blake3) =
+        manifest.ok_or_else(|| integrity("Backup has no manifest."))?;
+    if manifest.format_version != FULL_BACKUP_FORMAT_VERSION {
+        return Err(integrity("Unsupported backup format version."));
+    }
+    if manifest.entries != staged {
+        return Err(integrity("Backup contents do not match its manifest."));
+    }
+    Ok(FullBackupSummary {
+        manifest,
+        manifest_blake3,
+    })
+}
+
+fn entry_header(size: u64) -> tar::Header {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_size(size);
+    header.set_mode(0o600);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header
+}
+
+/// Append `size` bytes from `data`, hashing them on the way into the archive.
+fn append_entry<W: Write, R: Read>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    size: u64,
+    data: R,
+) -> Result<ManifestEntry, FullBackupError> {
+    let mut header = entry_header(size);
+    let mut reader = HashingReader::new(data.take(size));
+    builder.append_data(&mut header, path, &mut reader)?;
+    let (blake3, read) = reader.finish();
+    // A source that shrank after its size was taken leaves a short entry
+    // padded with zeros; refuse to record it as valid.
+    if read != size {
+        return Err(integrity(format!(
+            "Backup source changed while being archived: {path}"
+        )));
+    }
+    Ok(ManifestEntry {
+        path: path.to_string(),
+        size,
+        blake3,
+    })
+}
+
+/// Relative `blobs/..` paths of committed attachment blobs, sorted.
+///
+/// In-flight `.bin.tmp` writes and the health-check directory are skipped.
+fn collect_blob_paths(root: &Path) -> io::Result<Vec<String>> {
+    let blobs_root = root.join(BLOB_DIR);
+    let mut paths = Vec::new();
+    if !blobs_root.is_dir() {
+        return Ok(paths);
+    }
+
+    let mut stack = vec![blobs_root];
+    while let Some(dir) = stack.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                if entry.file_name() != ".health-check" {
+                    stack.push(path);
+                }
+            } else if file_type.is_file()
+                && path.extension().is_some_and(|ext| ext == BLOB_EXTENSION)
+            {
+                let relative = path
+                    .strip_prefix(root)
+                    .map_err(io::Error::other)?
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                paths.push(relative);
+            }
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Validate an archive path: only the fixed entries or attachment blobs.
+fn entry_path<R: Read>(entry: &tar::Entry<'_, R>) -> Result<String, FullBackupError> {
+    let invalid = || integrity("Backup contains an invalid entry path.");
+    let raw = entry.path().map_err(|_| invalid())?;
+    if !raw
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(invalid());
+    }
+    let path = raw.to_str().ok_or_else(invalid)?.to_string();
+    let allowed = path == MANIFEST_ENTRY
+        || NAMED_ENTRIES.contains(&path.as_str())
+        || path
+            .strip_prefix(ATTACHMENT_PREFIX)
+            .is_some_and(is_blob_path);
+    if !allowed {
+        return Err(invalid());
+    }
+    Ok(path)
+}
+
+/// Whether a storage-relative path names a blob file under `blobs/`.
+fn is_blob_path(path: &str) -> bool {
+    path.strip_prefix(BLOB_DIR)
+        .is_some_and(|rest| rest.starts_with('/'))
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        && Path::new(path)
+            .extension()
+            .is_some_and(|ext| ext == BLOB_EXTENSION)
+}
+
+/// Staged location of an attachment entry relative to the file storage root.
+pub fn attachment_storage_path(entry_path: &str) -> Option<PathBuf> {
+    entry_path
+        .strip_prefix(ATTACHMENT_PREFIX)
+        .map(PathBuf::from)
+}
+
+struct HashingReader<R> {
+    inner: R,
+    hasher: blake3::Hasher,
+    count: u64,
+}
+
+impl<R> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+            count: 0,
+        }
+    }
+
+    fn finish(self) -> (String, u64) {
+        (self.hasher.finalize().to_hex().to_string(), self.count)
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        self.count += read as u64;
+        Ok(read)
+    }
+}
+
+struct HashingWriter<W> {
+    inner: W,
+    hasher: blake3::Hasher,
+    count: u64,
+}
+
+impl<W> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+            count: 0,
+        }
+    }
+
+    fn finish(self) -> (W, String, u64) {
+        (
+            self.inner,
+            self.hasher.finalize().to_hex().to_string(),
+            self.count,
+        )
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.count += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matric_crypto::Keypair;
+
+    struct Fixture {
+        _dir: tempfile::TempDir,
+        dump: PathBuf,
+        storage: PathBuf,
+    }
+
+    fn fixture() -> Fixture {
+        let dir = tempfile::tempdir().unwrap();
+        let dump = dir.path().join("dump.sql");
+        std::fs::write(&dump, "CREATE TABLE note (id uuid);\n".repeat(5_000)).unwrap();
+        let storage = dir.path().join("files");
+        let blob_dir = storage.join("blobs/01/94");
+        std::fs::create_dir_all(&blob_dir).unwrap();
+        std::fs::write(blob_dir.join("a.bin"), [1u8; 70_000]).unwrap();
+        std::fs::write(blob_dir.join("b.bin.tmp"), b"in flight").unwrap();
+        std::fs::create_dir_all(storage.join("blobs/.health-check")).unwrap();
+        Fixture {
+            _dir: dir,
+            dump,
+            storage,
+        }
+    }
+
+    fn write(fixture: &Fixture, recipient: &PublicKey) -> (Vec<u8>, FullBackupSummary) {
+        write_full_backup(
+            Vec::new(),
+            std::slice::from_ref(recipient),
+            &FullBackupSources {
+                metadata: br#"{"title":"t"}"#,
+                files: &[(DATABASE_ENTRY, &fixture.dump)],
+                file_storage_root: Some(&fixture.storage),
+                blob_paths: None,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn roundtrip_verifies_manifest_and_stages_entries() {
+        let fixture = fixture();
+        let keypair = Keypair::generate();
+        let (archive, written) = write(&fixture, &keypair.public);
+
+        let paths: Vec<_> = written.manifest.entries.iter().map(|e| &e.path).collect();
+        assert_eq!(
+            paths,
+            [
+                METADATA_ENTRY,
+                DATABASE_ENTRY,
+                "attachments/blobs/01/94/a.bin"
+            ]
+        );
+        assert_eq!(written.manifest.attachment_count(), 1);
+
+        let staging = tempfile::tempdir().unwrap();
+        let read = read_full_backup(archive.as_slice(), &keypair.private, staging.path()).unwrap();
+        assert_eq!(read.manifest_blake3, written.manifest_blake3);
+        assert_eq!(
+            std::fs::read(staging.path().join(DATABASE_ENTRY)).unwrap(),
+            std::fs::read(&fixture.dump).unwrap()
+        );
+        assert_eq!(
+            std::fs::read(staging.path().join("attachments/blobs/01/94/a.bin")).unwrap(),
+            vec![1u8; 70_000]
+        );
+        assert_eq!(
+            attachment_storage_path("attachments/blobs/01/94/a.bin"),
+            Some(PathBuf::from("blobs/01/94/a.bin"))
+        );
+    }
+
+    #[test]
+    fn wrong_key_and_tampering_are_rejected() {
+        let fixture = fixture();
+        let keypair = Keypair::generate();
+        let (mut archive, _) = write(&fixture, &keypair.public);
+
+        let staging = tempfile::tempdir().unwrap();
+        let other = Keypair::generate();
+        assert!(matches!(
+            read_full_backup(archive.as_slice(), &other.private, staging.path()),
+            Err(FullBackupError::Crypto(CryptoError::NoMatchingRecipient))
+        ));
+
+        let middle = archive.len() / 2;
+        archive[middle] ^= 0x01;
+        assert!(read_full_backup(archive.as_slice(), &keypair.private, staging.path()).is_err());
+    }
+
+    #[test]
+    fn incremental_entries_include_only_listed_blobs() {
+        let fixture = fixture();
+        std::fs::write(fixture.storage.join("blobs/01/94/c.bin"), b"new blob").unwrap();
+        let changes = fixture.dump.with_file_name("changes.json");
+        std::fs::write(&changes, br#"{"changed_notes":[]}"#).unwrap();
+        let keypair = Keypair::generate();
+        let (archive, written) = write_full_backup(
+            Vec::new(),
+            std::slice::from_ref(&keypair.public),
+            &FullBackupSources {
+                metadata: b"{}",
+                files: &[(CHANGES_ENTRY, &changes)],
+                file_storage_root: Some(&fixture.storage),
+                blob_paths: Some(&["blobs/01/94/c.bin".to_string()]),
+            },
+        )
+        .unwrap();
+
+        assert!(written.manifest.has_entry(CHANGES_ENTRY));
+        assert!(!written.manifest.has_entry(DATABASE_ENTRY));
+        assert_eq!(written.manifest.attachment_count(), 1);
+        assert!(written.manifest.has_entry("attachments/blobs/01/94/c.bin"));
+
+        let staging = tempfile::tempdir().unwrap();
+        let read = read_full_backup(archive.as_slice(), &keypair.private, staging.path()).unwrap();
+        assert_eq!(read.manifest_blake3, written.manifest_blake3);
+
+        let escaped = write_full_backup(
+            Vec::new(),
+            std::slice::from_ref(&keypair.public),
+            &FullBackupSources {
+                metadata: b"{}",
+                files: &[],
+                file_storage_root: Some(&fixture.storage),
+                blob_paths: Some(&["blobs/../../dump.sql".to_string()]),
+            },
+        );
+        assert!(matches!(escaped, Err(FullBackupError::Integrity(_))));
+    }
+
+    #[test]
+    fn selected_read_stages_only_kept_entries() {
+        let fixture = fixture();
+        let keypair = Keypair::generate();
+        let (mut archive, written) = write(&fixture, &keypair.public);
+
+        let staging = tempfile::tempdir().unwrap();
+        let mut seen = Vec::new();
+        let read = read_full_backup_selected(
+            archive.as_slice(),
+            &keypair.private,
+            staging.path(),
+            &mut |path| {
+                seen.push(path.to_string());
+                path.starts_with(ATTACHMENT_PREFIX)
+            },
+        )
+        .unwrap();
+        assert_eq!(read.manifest_blake3, written.manifest_blake3);
+        assert_eq!(seen.len(), written.manifest.entries.len());
+        assert!(!staging.path().join(DATABASE_ENTRY).exists());
+        assert!(staging
+            .path()
+            .join("attachments/blobs/01/94/a.bin")
+            .exists());
+
+        // Skipped entries are still verified.
+        let middle = archive.len() / 2;
+        archive[middle] ^= 0x01;
+        let staging = tempfile::tempdir().unwrap();
+        assert!(read_full_backup_selected(
+            archive.as_slice(),
+            &keypair.private,
+            staging.path(),
+            &mut |_| false,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn note_index_finds_entries_by_note() {
+        let note_id = uuid::Uuid::new_v4();
+        let index = NoteIndex {
+            notes: vec![NoteIndexEntry {
+                note_id,
+                line: 3,
+                blob_ids: vec![],
+            }],
+        };
+        let parsed: NoteIndex =
+            serde_json::from_slice(&serde_json::to_vec(&index).unwrap()).unwrap();
+        assert_eq!(parsed.get(note_id).map(|entry| entry.line), Some(3));
+        assert!(parsed.get(uuid::Uuid::new_v4()).is_none());
+    }
+
+    #[test]
+    fn manifest_mismatch_is_rejected() {
+        let keypair = Keypair::generate();
+        let manifest = FullBackupManifest {
+            format_version: FULL_BACKUP_FORMAT_VERSION.to_string(),
+            created_at: chrono::Utc::now(),
+            entries: vec![ManifestEntry {
+                path: DATABASE_ENTRY.to_string(),
+                size: 4,
+                blake3: blake3::hash(b"good").to_hex().to_string(),
+            }],
+        };
+        let manifest_bytes = serde_json::to_vec(&manifest).unwrap();
+
+        let mut builder = tar::Builder::new(zstd::Encoder::new(Vec::new(), 0).unwrap());
+        builder
+            .append_data(&mut entry_header(4), DATABASE_ENTRY, &b"evil"[..])
+            .unwrap();
+        builder
+            .append_data(
+                &mut entry_header(manifest_bytes.len() as u64),
+                MANIFEST_ENTRY,
+                manifest_bytes.as_slice(),
+            )
+            .unwrap();
+        let plaintext = builder.into_inner().unwrap().finish().unwrap();
+        let mut writer =
+            encrypt_age_stream(Vec::new(), std::slice::from_ref(&keypair.public)).unwrap();
+        writer.write_all(&plaintext).unwrap();
+        let archive = writer.finish().unwrap();
+
+        let staging = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            read_full_backup(archive.as_slice(), &keypair.private, staging.path()),
+            Err(FullBackupError::Integrity(_))
+        ));
+    }
+
+    #[test]
+    fn entry_paths_outside_the_layout_are_rejected() {
+        let mut builder = tar::Builder::new(Vec::new());
+        for path in [
+            "attachments/blobs/x.bin",
+            "attachments/other/x.bin",
+            "notes.txt",
+        ] {
+            builder
+                .append_data(&mut entry_header(0), path, io::empty())
+                .unwrap();
+        }
+        let bytes = builder.into_inner().unwrap();
+        let mut archive = tar::Archive::new(bytes.as_slice());
+        let results: Vec<bool> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry_path(&entry.unwrap()).is_ok())
+            .collect();
+        assert_eq!(results, [true, false, false]);
+    }
+}
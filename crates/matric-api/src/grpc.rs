@@ -0,0 +1,409 @@
+//! gRPC ingestion service (optional `grpc` feature).
+//!
+//! Implements `fortemi.ingest.v1.IngestService` from
+//! `proto/fortemi/ingest/v1/ingest.proto`. The service is mounted on the main
+//! HTTP router, so requests pass through the same auth, scope, rate-limit and
+//! archive-routing middleware as REST; handlers read the resolved
+//! [`ArchiveContext`] and [`Caller`] from the request extensions and reuse
+//! the REST note creation paths. Search hits are filtered to the notes the
+//! caller may see, as in REST and GraphQL search.
+
+use tonic::{Request, Response, Status, Streaming};
+use uuid::Uuid;
+
+use crate::middleware::archive_routing::ArchiveContext;
+use crate::middleware::ownership::Caller;
+use crate::{
+    bulk_create_notes_inner, create_note_inner, ApiError, AppState, BulkCreateNoteItem,
+    BulkCreateNotesBody, CreateNoteBody,
+};
+
+pub mod proto {
+    tonic::include_proto!("fortemi.ingest.v1");
+}
+
+use proto::ingest_service_server::{IngestService, IngestServiceServer};
+use proto::{
+    BulkCreateNotesResponse, CreateNoteRequest, CreateNoteResponse, SearchHit, SearchRequest,
+    SearchResponse,
+};
+
+/// Notes committed per transaction during streaming ingestion. Matches the
+/// `POST /api/v1/notes/bulk` batch cap.
+const BULK_BATCH_SIZE: usize = 100;
+
+/// Upper bound on notes accepted from a single `BulkCreateNotes` stream.
+const MAX_NOTES_PER_STREAM: usize = 10_000;
+
+const DEFAULT_SEARCH_LIMIT: u32 = 20;
+const MAX_SEARCH_LIMIT: u32 = 100;
+
+/// Source recorded on notes created over gRPC when the client sends none.
+const DEFAULT_GRPC_SOURCE: &str = "grpc";
+
+/// Build the tonic service for mounting on the axum router.
+pub fn ingest_service(state: AppState) -> IngestServiceServer<GrpcIngestService> {
+    IngestServiceServer::new(GrpcIngestService { state })
+}
+
+pub struct GrpcIngestService {
+    state: AppState,
+}
+
+#[tonic::async_trait]
+impl IngestService for GrpcIngestService {
+    async fn create_note(
+        &self,
+        request: Request<CreateNoteRequest>,
+    ) -> Result<Response<CreateNoteResponse>, Status> {
+        let archive_ctx = archive_context(&request)?;
+        let caller = caller(&request);
+        let body = create_note_body(request.into_inner())?;
+        let id = create_note_inner(&self.state, &archive_ctx, caller, body)
+            .await
+            .map_err(status_from_api_error)?;
+        Ok(Response::new(CreateNoteResponse { id: id.to_string() }))
+    }
+
+    async fn bulk_create_notes(
+        &self,
+        request: Request<Streaming<CreateNoteRequest>>,
+    ) -> Result<Response<BulkCreateNotesResponse>, Status> {
+        let archive_ctx = archive_context(&request)?;
+        let caller = caller(&request);
+        let mut stream = request.into_inner();
+        let mut ids: Vec<Uuid> = Vec::new();
+        let mut batch: Vec<BulkCreateNoteItem> = Vec::with_capacity(BULK_BATCH_SIZE);
+        let mut received = 0usize;
+
+        while let Some(message) = stream.message().await? {
+            received += 1;
+            if received > MAX_NOTES_PER_STREAM {
+                return Err(partial_failure(
+                    Status::resource_exhausted(format!(
+                        "Maximum {MAX_NOTES_PER_STREAM} notes per stream"
+                    )),
+                    ids.len(),
+                ));
+            }
+            let item =
+                bulk_note_item(message).map_err(|status| partial_failure(status, ids.len()))?;
+            batch.push(item);
+            if batch.len() == BULK_BATCH_SIZE {
+                self.commit_batch(&archive_ctx, caller, &mut batch, &mut ids)
+                    .await?;
+            }
+        }
+        if !batch.is_empty() {
+            self.commit_batch(&archive_ctx, caller, &mut batch, &mut ids)
+                .await?;
+        }
+
+        Ok(Response::new(BulkCreateNotesResponse {
+            count: ids.len() as u64,
+            ids: ids.iter().map(Uuid::to_string).collect(),
+        }))
+    }
+
+    async fn search(
+        &self,
+        request: Request<SearchRequest>,
+    ) -> Result<Response<SearchResponse>, Status> {
+        let archive_ctx = archive_context(&request)?;
+        let security = caller(&request).security_filter();
+        let SearchRequest { query, limit } = request.into_inner();
+        if query.trim().is_empty() {
+            return Err(Status::invalid_argument("Search query is required"));
+        }
+        let limit = i64::from(search_limit(limit));
+        let ctx = self
+            .state
+            .db
+            .for_schema(&archive_ctx.schema)
+            .map_err(|e| status_from_api_error(e.into()))?;
+        let fts = matric_db::PgFtsSearch::new(self.state.db.pool.clone());
+        let hits = ctx
+            .query(move |tx| {
+                Box::pin(async move {
+                    let mut hits = fts.search_tx(tx, &query, limit, true).await?;
+                    matric_db::visibility::retain_visible(
+                        &mut **tx,
+                        &mut hits,
+                        security.as_ref(),
+                        |hit| Some(hit.note_id),
+                    )
+                    .await?;
+                    Ok(hits)
+                })
+            })
+            .await
+            .map_err(|e| status_from_api_error(e.into()))?;
+
+        Ok(Response::new(SearchResponse {
+            hits: hits
+                .into_iter()
+                .map(|hit| SearchHit {
+                    note_id: hit.note_id.to_string(),
+                    score: hit.score,
+                    title: hit.title,
+                    snippet: hit.snippet,
+                    tags: hit.tags,
+                })
+                .collect(),
+        }))
+    }
+}
+
+impl GrpcIngestService {
+    /// Commit the pending batch in one transaction and append the new IDs.
+    async fn commit_batch(
+        &self,
+        archive_ctx: &ArchiveContext,
+        caller: Caller,
+        batch: &mut Vec<BulkCreateNoteItem>,
+        ids: &mut Vec<Uuid>,
+    ) -> Result<(), Status> {
+        let notes = std::mem::take(batch);
+        let created = bulk_create_notes_inner(
+            &self.state,
+            archive_ctx,
+            caller,
+            BulkCreateNotesBody { notes },
+        )
+        .await
+        .map_err(|e| partial_failure(status_from_api_error(e), ids.len()))?;
+        ids.extend(created);
+        Ok(())
+    }
+}
+
+fn archive_context<T>(request: &Request<T>) -> Result<ArchiveContext, Status> {
+    request
+        .extensions()
+        .get::<ArchiveContext>()
+        .cloned()
+        .ok_or_else(|| Status::internal("Internal error"))
+}
+
+/// The user the request acts for, as resolved by the auth middleware.
+fn caller<T>(request: &Request<T>) -> Caller {
+    request
+        .extensions()
+        .get::<Caller>()
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Earlier batches are already committed when a later one fails; tell the
+/// client how many so it can resume instead of re-sending everything.
+fn partial_failure(status: Status, committed: usize) -> Status {
+    if committed == 0 {
+        return status;
+    }
+    Status::new(
+        status.code(),
+        format!("{} ({committed} notes already committed)", status.message()),
+    )
+}
+
+fn search_limit(limit: u32) -> u32 {
+    if limit == 0 {
+        DEFAULT_SEARCH_LIMIT
+    } else {
+        limit.min(MAX_SEARCH_LIMIT)
+    }
+}
+
+fn parse_collection_id(raw: Option<String>) -> Result<Option<Uuid>, Status> {
+    raw.filter(|s| !s.is_empty())
+        .map(|s| Uuid::parse_str(&s).map_err(|_| Status::invalid_argument("Invalid collection_id")))
+        .transpose()
+}
+
+fn parse_metadata(raw: Option<String>) -> Result<Option<serde_json::Value>, Status> {
+    let Some(raw) = raw.filter(|s| !s.trim().is_empty()) else {
+        return Ok(None);
+    };
+    match serde_json::from_str::<serde_json::Value>(&raw) {
+        Ok(value @ serde_json::Value::Object(_)) => Ok(Some(value)),
+        _ => Err(Status::invalid_argument(
+            "metadata_json must be a JSON object",
+        )),
+    }
+}
+
+fn non_empty_tags(tags: Vec<String>) -> Option<Vec<String>> {
+    if tags.is_empty() {
+        None
+    } else {
+        Some(tags)
+    }
+}
+
+fn create_note_body(req: CreateNoteRequest) -> Result<CreateNoteBody, Status> {
+    Ok(CreateNoteBody {
+        collection_id: parse_collection_id(req.collection_id)?,
+        metadata: parse_metadata(req.metadata_json)?,
+        content: req.content,
+        format: req.format,
+        source: Some(
+            req.source
+                .unwrap_or_else(|| DEFAULT_GRPC_SOURCE.to_string()),
+        ),
+        tags: non_empty_tags(req.tags),
+        title: req.title,
+        revision_mode: req.revision_mode,
+        document_type_id: None,
+        document_type: req.document_type,
+        model: None,
+        chunk_max_chars: None,
+        chunk_overlap: None,
+        pipeline: None,
+        encrypted: false,
+    })
+}
+
+fn bulk_note_item(req: CreateNoteRequest) -> Result<BulkCreateNoteItem, Status> {
+    Ok(BulkCreateNoteItem {
+        collection_id: parse_collection_id(req.collection_id)?,
+        metadata: parse_metadata(req.metadata_json)?,
+        content: req.content,
+        title: req.title,
+        tags: non_empty_tags(req.tags),
+        revision_mode: req.revision_mode,
+        document_type_id: None,
+        document_type: req.document_type,
+        format: req.format,
+        source: Some(
+            req.source
+                .unwrap_or_else(|| DEFAULT_GRPC_SOURCE.to_string()),
+        ),
+        chunk_max_chars: None,
+        chunk_overlap: None,
+    })
+}
+
+/// Maps API errors onto gRPC status codes. Only client-caused messages are
+/// passed through; server-side details stay in the log.
+fn status_from_api_error(err: ApiError) -> Status {
+    match err {
+        ApiError::BadRequest(msg) => Status::invalid_argument(msg),
+        ApiError::NotFound(msg) | ApiError::Gone(msg) => Status::not_found(msg),
+        ApiError::Unauthorized(msg) => Status::unauthenticated(msg),
+        ApiError::Forbidden(msg) => Status::permission_denied(msg),
+        ApiError::Conflict(msg) => Status::already_exists(msg),
+        ApiError::ServiceUnavailable(msg) => Status::unavailable(msg),
+        ApiError::Database(matric_core::Error::InvalidInput(msg)) => Status::invalid_argument(msg),
+        ApiError::Database(matric_core::Error::NotFound(msg)) => Status::not_found(msg),
+        other => {
+            tracing::error!(error = ?other, "gRPC request failed");
+            Status::internal("Internal error")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(content: &str) -> CreateNoteRequest {
+        CreateNoteRequest {
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn create_note_body_defaults_source_and_drops_empty_tags() {
+        let body = create_note_body(request("hello")).unwrap();
+        assert_eq!(body.content, "hello");
+        assert_eq!(body.source.as_deref(), Some("grpc"));
+        assert!(body.tags.is_none());
+        assert!(body.collection_id.is_none());
+        assert!(body.metadata.is_none());
+    }
+
+    #[test]
+    fn bulk_note_item_parses_collection_and_metadata() {
+        let id = Uuid::new_v4();
+        let item = bulk_note_item(CreateNoteRequest {
+            collection_id: Some(id.to_string()),
+            metadata_json: Some(r#"{"origin":"import"}"#.to_string()),
+            tags: vec!["a".to_string()],
+            source: Some("etl".to_string()),
+            ..request("body")
+        })
+        .unwrap();
+        assert_eq!(item.collection_id, Some(id));
+        assert_eq!(item.metadata.unwrap()["origin"], "import");
+        assert_eq!(item.tags, Some(vec!["a".to_string()]));
+        assert_eq!(item.source.as_deref(), Some("etl"));
+    }
+
+    #[test]
+    fn invalid_collection_id_and_metadata_are_rejected() {
+        let err = create_note_body(CreateNoteRequest {
+            collection_id: Some("not-a-uuid".to_string()),
+            ..request("x")
+        })
+        .err()
+        .unwrap();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        let err = bulk_note_item(CreateNoteRequest {
+            metadata_json: Some("[1, 2]".to_string()),
+            ..request("x")
+        })
+        .err()
+        .unwrap();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[test]
+    fn search_limit_defaults_and_clamps() {
+        assert_eq!(search_limit(0), DEFAULT_SEARCH_LIMIT);
+        assert_eq!(search_limit(5), 5);
+        assert_eq!(search_limit(10_000), MAX_SEARCH_LIMIT);
+    }
+
+    #[test]
+    fn api_errors_map_to_status_codes_without_leaking_internals() {
+        let cases = [
+            (
+                ApiError::BadRequest("bad".into()),
+                tonic::Code::InvalidArgument,
+            ),
+            (ApiError::NotFound("gone".into()), tonic::Code::NotFound),
+            (
+                ApiError::Unauthorized("no".into()),
+                tonic::Code::Unauthenticated,
+            ),
+            (
+                ApiError::Forbidden("no".into()),
+                tonic::Code::PermissionDenied,
+            ),
+            (ApiError::Conflict("dup".into()), tonic::Code::AlreadyExists),
+            (
+                ApiError::ServiceUnavailable("busy".into()),
+                tonic::Code::Unavailable,
+            ),
+        ];
+        for (err, code) in cases {
+            assert_eq!(status_from_api_error(err).code(), code);
+        }
+
+        let status = status_from_api_error(ApiError::Internal("secret dsn".into()));
+        assert_eq!(status.code(), tonic::Code::Internal);
+        assert!(!status.message().contains("secret"));
+    }
+
+    #[test]
+    fn partial_failure_reports_committed_count() {
+        let status = partial_failure(Status::invalid_argument("bad note"), 200);
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(status.message().contains("200 notes already committed"));
+
+        let status = partial_failure(Status::invalid_argument("bad note"), 0);
+        assert_eq!(status.message(), "bad note");
+    }
+}
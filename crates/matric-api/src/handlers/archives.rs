@@ -7,18 +7,31 @@
 #![allow(dead_code)]
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    body::{Body, Bytes},
+    extract::{Multipart, Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::IntoResponse,
     Json,
 };
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::middleware::ownership::Caller;
 use crate::{telemetry_text_len, ApiError, AppState};
-use matric_core::{ArchiveInfo, ArchiveRepository, ServerEvent};
+use matric_core::{
+    ArchiveInfo, ArchiveQuota, ArchiveRepository, ArchiveTranslationSetting, ArchiveUsage,
+    JobRepository, JobType, RevisionMode, ServerEvent, VersionRetentionPolicy,
+};
+use matric_db::{
+    builtin_provisioning_profile, read_mempack, ArchiveMergeMode, ArchiveMergeOptions,
+    MempackImportOptions, MempackImportReport, MempackManifest, MempackWriter, ProvisioningProfile,
+    ProvisioningReport, MEMPACK_CONTENT_TYPE, MEMPACK_DEFAULT_MAX_ENTRY_BYTES,
+};
 
 const ARCHIVE_ALREADY_EXISTS_MESSAGE: &str = "Archive already exists.";
 const ARCHIVE_NOT_FOUND_MESSAGE: &str = "Archive not found.";
+const UNKNOWN_ARCHIVE_TEMPLATE_MESSAGE: &str =
+    "Unknown archive template. Available templates: research, engineering, personal.";
 const LIVE_MEMORY_LIMIT_REACHED_MESSAGE: &str =
     "Live memory limit reached. Export and delete unused memories, or increase MAX_MEMORIES.";
 
@@ -54,6 +67,26 @@ impl fmt::Debug for CreateArchiveRequest {
     }
 }
 
+/// Query parameters for creating an archive.
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateArchiveQuery {
+    /// Built-in provisioning profile to apply (`research`, `engineering`, `personal`).
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+/// Resolve a `?template=` value to a built-in provisioning profile.
+fn provisioning_profile_for(
+    template: Option<&str>,
+) -> Result<Option<ProvisioningProfile>, ApiError> {
+    let Some(template) = template else {
+        return Ok(None);
+    };
+    builtin_provisioning_profile(template)?
+        .map(Some)
+        .ok_or_else(|| ApiError::BadRequest(UNKNOWN_ARCHIVE_TEMPLATE_MESSAGE.to_string()))
+}
+
 /// Request body for updating archive metadata.
 #[derive(Deserialize, utoipa::ToSchema)]
 pub struct UpdateArchiveRequest {
@@ -123,6 +156,95 @@ impl fmt::Debug for ArchiveStatsResponse {
     }
 }
 
+/// Note version retention policy in effect for an archive.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct ArchiveVersionPolicyResponse {
+    /// Archive name
+    pub archive: String,
+    /// `archive` when the archive overrides the default, else `default`
+    pub source: &'static str,
+    #[serde(flatten)]
+    pub policy: VersionRetentionPolicy,
+}
+
+impl fmt::Debug for ArchiveVersionPolicyResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchiveVersionPolicyResponse")
+            .field("archive_len", &telemetry_text_len(&self.archive))
+            .field("source", &self.source)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+/// Translation setting in effect for an archive.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct ArchiveTranslationResponse {
+    /// Archive name
+    pub archive: String,
+    /// `archive` when the archive has a stored setting, else `default`
+    pub source: &'static str,
+    #[serde(flatten)]
+    pub setting: ArchiveTranslationSetting,
+}
+
+impl fmt::Debug for ArchiveTranslationResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchiveTranslationResponse")
+            .field("archive_len", &telemetry_text_len(&self.archive))
+            .field("source", &self.source)
+            .field("setting", &self.setting)
+            .finish()
+    }
+}
+
+/// Quota in effect for an archive.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct ArchiveQuotaResponse {
+    /// Archive name
+    pub archive: String,
+    /// `archive` when the archive has a stored quota, else `default` (unlimited)
+    pub source: &'static str,
+    #[serde(flatten)]
+    pub quota: ArchiveQuota,
+}
+
+impl fmt::Debug for ArchiveQuotaResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchiveQuotaResponse")
+            .field("archive_len", &telemetry_text_len(&self.archive))
+            .field("source", &self.source)
+            .field("quota", &self.quota)
+            .finish()
+    }
+}
+
+/// An archive's current consumption and the quota it is held to.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct ArchiveUsageResponse {
+    /// Archive name
+    pub archive: String,
+    #[serde(flatten)]
+    pub usage: ArchiveUsage,
+    /// Limits in effect; `null` limits are unlimited
+    pub quota: ArchiveQuota,
+}
+
+impl fmt::Debug for ArchiveUsageResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchiveUsageResponse")
+            .field("archive_len", &telemetry_text_len(&self.archive))
+            .field("notes", &self.usage.notes)
+            .field("attachments", &self.usage.attachments)
+            .field("embeddings", &self.usage.embeddings)
+            .field("blob_bytes", &self.usage.blob_storage.bytes)
+            .field("table_count", &self.usage.tables.len())
+            .field("table_bytes", &self.usage.table_bytes)
+            .field("quota", &self.quota)
+            .finish()
+    }
+}
+
 // =============================================================================
 // HANDLERS
 // =============================================================================
@@ -130,7 +252,8 @@ impl fmt::Debug for ArchiveStatsResponse {
 /// List all archives.
 ///
 /// Returns all memory archives with their metadata, including note counts,
-/// size information, and default status.
+/// size information, and default status. Requests acting for a user only see
+/// the archives that user owns, has been shared, or that have no owner.
 ///
 /// # Returns
 /// - 200 OK with array of archive information
@@ -139,8 +262,13 @@ impl fmt::Debug for ArchiveStatsResponse {
     responses((status = 200, description = "Success")))]
 pub async fn list_archives(
     State(state): State<AppState>,
+    caller: Caller,
 ) -> Result<Json<Vec<ArchiveInfo>>, ApiError> {
-    let archives = state.db.archives.list_archive_schemas().await?;
+    let mut archives = state.db.archives.list_archive_schemas().await?;
+    if let Some(user_id) = caller.user_id {
+        let visible = state.db.users.visible_archive_names(user_id).await?;
+        archives.retain(|archive| visible.contains(&archive.name));
+    }
     Ok(Json(archives))
 }
 
@@ -175,21 +303,30 @@ pub async fn get_archive(
 /// memory storage. Each archive maintains its own notes, embeddings, collections,
 /// and tags.
 ///
+/// # Query Parameters
+/// - `template`: Optional built-in provisioning profile. Its SKOS schemes,
+///   collections, prompt templates, document types and embedding configs are
+///   applied in one transaction; if that fails the new schema is dropped.
+///
 /// # Request Body
 /// JSON object with archive configuration:
 /// - `name`: Unique archive name (required)
 /// - `description`: Optional description
 ///
 /// # Returns
-/// - 201 Created with `{ "id": "<uuid>", "schema_name": "..." }` on success
-/// - 400 Bad Request if validation fails
+/// - 201 Created with `{ "id": "<uuid>", "schema_name": "..." }` on success,
+///   plus `template` and a `provisioning` report when a template was applied
+/// - 400 Bad Request if validation fails or the template is unknown
 /// - 409 Conflict if archive name already exists
 /// - 500 Internal Server Error if schema creation fails
 #[utoipa::path(post, path = "/api/v1/archives", tag = "Archives",
+    params(("template" = Option<String>, Query, description = "Provisioning profile to apply: research, engineering or personal")),
     request_body = CreateArchiveRequest,
     responses((status = 201, description = "Created")))]
 pub async fn create_archive(
     State(state): State<AppState>,
+    caller: Caller,
+    Query(query): Query<CreateArchiveQuery>,
     Json(req): Json<CreateArchiveRequest>,
 ) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
     // Validate archive name
@@ -198,6 +335,7 @@ pub async fn create_archive(
             "Archive name cannot be empty".to_string(),
         ));
     }
+    let profile = provisioning_profile_for(query.template.as_deref())?;
 
     // Enforce MAX_MEMORIES limit on live (in-database) memories.
     // Users can export memories as shards, delete them to free slots, and re-import later.
@@ -224,6 +362,29 @@ pub async fn create_archive(
         .archives
         .create_archive_schema(&req.name, req.description.as_deref())
         .await?;
+    let provisioning = match profile {
+        Some(profile) => match provision_archive(&state, &archive.schema_name, &profile).await {
+            Ok(report) => Some(report),
+            Err(error) => {
+                if let Err(drop_error) = state.db.archives.drop_archive_schema(&archive.name).await
+                {
+                    tracing::error!(
+                        error = %drop_error,
+                        "Failed to drop archive after provisioning failure"
+                    );
+                }
+                return Err(error);
+            }
+        },
+        None => None,
+    };
+    if let Some(owner_id) = caller.user_id {
+        state
+            .db
+            .users
+            .set_archive_owner(&archive.name, owner_id)
+            .await?;
+    }
 
     // Emit ArchiveCreated event (Issue #455)
     state.event_bus.emit(ServerEvent::ArchiveCreated {
@@ -231,14 +392,44 @@ pub async fn create_archive(
         archive_id: Some(archive.id),
     });
 
-    Ok((
-        StatusCode::CREATED,
-        Json(serde_json::json!({
-            "id": archive.id,
-            "name": archive.name,
-            "schema_name": archive.schema_name
-        })),
-    ))
+    let mut body = serde_json::json!({
+        "id": archive.id,
+        "name": archive.name,
+        "schema_name": archive.schema_name
+    });
+    if let Some(report) = provisioning {
+        body["template"] = serde_json::json!(report.profile);
+        body["provisioning"] = serde_json::json!(report);
+    }
+
+    Ok((StatusCode::CREATED, Json(body)))
+}
+
+/// Apply a provisioning profile to a freshly created archive in one transaction.
+async fn provision_archive(
+    state: &AppState,
+    schema_name: &str,
+    profile: &ProvisioningProfile,
+) -> Result<ProvisioningReport, ApiError> {
+    let ctx = state.db.for_schema(schema_name)?;
+    let mut tx = ctx.begin_tx().await?;
+    let report = state
+        .db
+        .provisioning
+        .apply_tx(&mut tx, schema_name, profile)
+        .await?;
+    tx.commit().await.map_err(matric_db::Error::Database)?;
+    tracing::info!(
+        template = %report.profile,
+        skos_schemes = report.skos_schemes,
+        skos_concepts = report.skos_concepts,
+        collections = report.collections,
+        prompt_templates = report.prompt_templates,
+        document_types = report.document_types,
+        embedding_configs = report.embedding_configs,
+        "Provisioned archive from template"
+    );
+    Ok(report)
 }
 
 /// Update archive metadata.
@@ -452,6 +643,692 @@ pub async fn clone_archive(
     ))
 }
 
+/// Merge an archive into another archive.
+///
+/// Queues an `archive_merge` job that copies every note, tag, link,
+/// collection, attachment, SKOS concept, embedding and provenance record of
+/// `name` into `target` in one transaction. Records the target already has
+/// under the same natural key (tag name, scheme notation, blob hash) are
+/// reused. Notes present in both archives are resolved by `on_conflict`:
+/// `skip` keeps the target's note, `overwrite` replaces it, `fail` aborts the
+/// merge. With `mode: "move"` the source archive is dropped after the merge
+/// commits. Progress is reported on the job.
+///
+/// # Path Parameters
+/// - `name`: Source archive name
+/// - `target`: Target archive name
+///
+/// # Returns
+/// - 202 Accepted with `{ "job_id": "<uuid>" }`
+/// - 400 Bad Request if source and target are the same, or moving the default archive
+/// - 404 Not Found if either archive doesn't exist
+#[utoipa::path(post, path = "/api/v1/archives/{name}/merge-into/{target}", tag = "Archives",
+    params(
+        ("name" = String, Path, description = "Source archive name"),
+        ("target" = String, Path, description = "Target archive name"),
+    ),
+    request_body(content = Option<ArchiveMergeOptions>, description = "Merge mode and conflict policy (defaults: copy, skip)"),
+    responses(
+        (status = 202, description = "Merge queued"),
+        (status = 400, description = "Invalid merge"),
+        (status = 404, description = "Archive not found")
+    ))]
+pub async fn merge_archive(
+    State(state): State<AppState>,
+    Path((name, target)): Path<(String, String)>,
+    body: Option<Json<ArchiveMergeOptions>>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let options = body.map(|Json(options)| options).unwrap_or_default();
+    let source = archive_by_name(&state, &name).await?;
+    let target = archive_by_name(&state, &target).await?;
+    if source.id == target.id {
+        return Err(ApiError::BadRequest(
+            "Cannot merge an archive into itself.".to_string(),
+        ));
+    }
+    if options.mode == ArchiveMergeMode::Move && source.is_default {
+        return Err(ApiError::BadRequest(
+            "Cannot move the default archive. Set another archive as default first.".to_string(),
+        ));
+    }
+
+    let mut payload = serde_json::to_value(options)
+        .map_err(|e| ApiError::Internal(format!("Failed to encode merge options: {e}")))?;
+    payload["source"] = serde_json::json!(source.name);
+    payload["target"] = serde_json::json!(target.name);
+    let job_id = state
+        .db
+        .jobs
+        .queue(
+            None,
+            JobType::ArchiveMerge,
+            JobType::ArchiveMerge.default_priority(),
+            Some(payload),
+            JobType::ArchiveMerge.default_cost_tier(),
+        )
+        .await?;
+    state.event_bus.emit(ServerEvent::JobQueued {
+        job_id,
+        job_type: format!("{:?}", JobType::ArchiveMerge),
+        note_id: None,
+    });
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "job_id": job_id })),
+    ))
+}
+
+async fn archive_by_name(state: &AppState, name: &str) -> Result<ArchiveInfo, ApiError> {
+    state
+        .db
+        .archives
+        .get_archive_by_name(name)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(ARCHIVE_NOT_FOUND_MESSAGE.to_string()))
+}
+
+/// Queue a version_prune job so a policy change applies to existing history.
+async fn queue_version_prune(state: &AppState, schema_name: &str) -> Result<(), ApiError> {
+    let job_id = state
+        .db
+        .jobs
+        .queue(
+            None,
+            JobType::VersionPrune,
+            JobType::VersionPrune.default_priority(),
+            Some(serde_json::json!({ "schema": schema_name })),
+            JobType::VersionPrune.default_cost_tier(),
+        )
+        .await?;
+    state.event_bus.emit(ServerEvent::JobQueued {
+        job_id,
+        job_type: format!("{:?}", JobType::VersionPrune),
+        note_id: None,
+    });
+    Ok(())
+}
+
+/// Get an archive's note version retention policy.
+///
+/// Returns the archive's override, or the global default when it has none.
+///
+/// # Returns
+/// - 200 OK with the effective policy and its source
+/// - 404 Not Found if archive doesn't exist
+#[utoipa::path(get, path = "/api/v1/archives/{name}/version-policy", tag = "Archives",
+    params(("name" = String, Path, description = "Archive name")),
+    responses((status = 200, description = "Success", body = ArchiveVersionPolicyResponse)))]
+pub async fn get_archive_version_policy(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ArchiveVersionPolicyResponse>, ApiError> {
+    let archive = archive_by_name(&state, &name).await?;
+    let (source, policy) = match state
+        .db
+        .versioning
+        .archive_retention_policy(&archive.schema_name)
+        .await?
+    {
+        Some(policy) => ("archive", policy),
+        None => (
+            "default",
+            state.db.versioning.default_retention_policy().await?,
+        ),
+    };
+
+    Ok(Json(ArchiveVersionPolicyResponse {
+        archive: archive.name,
+        source,
+        policy,
+    }))
+}
+
+/// Set an archive's note version retention policy.
+///
+/// Replaces the archive's override; omitted limits are disabled and
+/// `keep_milestones` defaults to true. Existing history is pruned to the new
+/// policy by a background job.
+///
+/// # Returns
+/// - 200 OK with the stored policy
+/// - 400 Bad Request if a limit is out of range
+/// - 404 Not Found if archive doesn't exist
+#[utoipa::path(put, path = "/api/v1/archives/{name}/version-policy", tag = "Archives",
+    params(("name" = String, Path, description = "Archive name")),
+    request_body = VersionRetentionPolicy,
+    responses((status = 200, description = "Success", body = ArchiveVersionPolicyResponse)))]
+pub async fn set_archive_version_policy(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(policy): Json<VersionRetentionPolicy>,
+) -> Result<Json<ArchiveVersionPolicyResponse>, ApiError> {
+    policy.validate()?;
+    let archive = archive_by_name(&state, &name).await?;
+    state
+        .db
+        .versioning
+        .set_archive_retention_policy(&archive.schema_name, &policy)
+        .await?;
+    queue_version_prune(&state, &archive.schema_name).await?;
+
+    Ok(Json(ArchiveVersionPolicyResponse {
+        archive: archive.name,
+        source: "archive",
+        policy,
+    }))
+}
+
+/// Remove an archive's version retention override.
+///
+/// The archive follows the global default again; its history is pruned to
+/// the default by a background job.
+///
+/// # Returns
+/// - 204 No Content on success
+/// - 404 Not Found if the archive doesn't exist or has no override
+#[utoipa::path(delete, path = "/api/v1/archives/{name}/version-policy", tag = "Archives",
+    params(("name" = String, Path, description = "Archive name")),
+    responses((status = 204, description = "No Content")))]
+pub async fn delete_archive_version_policy(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let archive = archive_by_name(&state, &name).await?;
+    let deleted = state
+        .db
+        .versioning
+        .delete_archive_retention_policy(&archive.schema_name)
+        .await?;
+    if !deleted {
+        return Err(ApiError::NotFound(
+            "Archive has no version policy override.".to_string(),
+        ));
+    }
+    queue_version_prune(&state, &archive.schema_name).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get an archive's translation setting.
+///
+/// Returns the archive's stored setting, or the disabled default when it has
+/// none.
+///
+/// # Returns
+/// - 200 OK with the effective setting and its source
+/// - 404 Not Found if archive doesn't exist
+#[utoipa::path(get, path = "/api/v1/archives/{name}/translation", tag = "Archives",
+    params(("name" = String, Path, description = "Archive name")),
+    responses((status = 200, description = "Success", body = ArchiveTranslationResponse)))]
+pub async fn get_archive_translation(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ArchiveTranslationResponse>, ApiError> {
+    let archive = archive_by_name(&state, &name).await?;
+    let (source, setting) = match state
+        .db
+        .translations
+        .archive_setting(&archive.schema_name)
+        .await?
+    {
+        Some(setting) => ("archive", setting),
+        None => ("default", ArchiveTranslationSetting::default()),
+    };
+
+    Ok(Json(ArchiveTranslationResponse {
+        archive: archive.name,
+        source,
+        setting,
+    }))
+}
+
+/// Set an archive's translation setting.
+///
+/// Applies to notes created or updated afterwards; reprocess existing notes
+/// with the `translation` step to translate them.
+///
+/// # Returns
+/// - 200 OK with the stored setting
+/// - 400 Bad Request if the target language is not supported
+/// - 404 Not Found if archive doesn't exist
+#[utoipa::path(put, path = "/api/v1/archives/{name}/translation", tag = "Archives",
+    params(("name" = String, Path, description = "Archive name")),
+    request_body = ArchiveTranslationSetting,
+    responses((status = 200, description = "Success", body = ArchiveTranslationResponse)))]
+pub async fn set_archive_translation(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(setting): Json<ArchiveTranslationSetting>,
+) -> Result<Json<ArchiveTranslationResponse>, ApiError> {
+    setting.validate()?;
+    let archive = archive_by_name(&state, &name).await?;
+    state
+        .db
+        .translations
+        .set_archive_setting(&archive.schema_name, &setting)
+        .await?;
+
+    Ok(Json(ArchiveTranslationResponse {
+        archive: archive.name,
+        source: "archive",
+        setting,
+    }))
+}
+
+/// Remove an archive's translation setting, disabling translation.
+///
+/// Stored translations are kept until their notes are reprocessed.
+///
+/// # Returns
+/// - 204 No Content on success
+/// - 404 Not Found if the archive doesn't exist or has no setting
+#[utoipa::path(delete, path = "/api/v1/archives/{name}/translation", tag = "Archives",
+    params(("name" = String, Path, description = "Archive name")),
+    responses((status = 204, description = "No Content")))]
+pub async fn delete_archive_translation(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let archive = archive_by_name(&state, &name).await?;
+    let deleted = state
+        .db
+        .translations
+        .delete_archive_setting(&archive.schema_name)
+        .await?;
+    if !deleted {
+        return Err(ApiError::NotFound(
+            "Archive has no translation setting.".to_string(),
+        ));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get an archive's storage quota.
+///
+/// Returns the archive's stored quota, or the unlimited default when it has
+/// none.
+///
+/// # Returns
+/// - 200 OK with the quota and its source
+/// - 404 Not Found if archive doesn't exist
+#[utoipa::path(get, path = "/api/v1/archives/{name}/quota", tag = "Archives",
+    params(("name" = String, Path, description = "Archive name")),
+    responses((status = 200, description = "Success", body = ArchiveQuotaResponse)))]
+pub async fn get_archive_quota(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ArchiveQuotaResponse>, ApiError> {
+    let archive = archive_by_name(&state, &name).await?;
+    let (source, quota) = match state.db.archive_quotas.get(&archive.schema_name).await? {
+        Some(quota) => ("archive", quota),
+        None => ("default", ArchiveQuota::default()),
+    };
+
+    Ok(Json(ArchiveQuotaResponse {
+        archive: archive.name,
+        source,
+        quota,
+    }))
+}
+
+/// Set an archive's storage quota.
+///
+/// Limits left out or `null` are unlimited. New notes, attachments and
+/// embeddings that would exceed a limit are refused; content already stored
+/// is kept when a limit is lowered below it.
+///
+/// # Returns
+/// - 200 OK with the stored quota
+/// - 400 Bad Request if a limit is negative
+/// - 404 Not Found if archive doesn't exist
+#[utoipa::path(put, path = "/api/v1/archives/{name}/quota", tag = "Archives",
+    params(("name" = String, Path, description = "Archive name")),
+    request_body = ArchiveQuota,
+    responses((status = 200, description = "Success", body = ArchiveQuotaResponse)))]
+pub async fn set_archive_quota(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(quota): Json<ArchiveQuota>,
+) -> Result<Json<ArchiveQuotaResponse>, ApiError> {
+    quota.validate()?;
+    let archive = archive_by_name(&state, &name).await?;
+    state
+        .db
+        .archive_quotas
+        .set(&archive.schema_name, &quota)
+        .await?;
+
+    Ok(Json(ArchiveQuotaResponse {
+        archive: archive.name,
+        source: "archive",
+        quota,
+    }))
+}
+
+/// Remove an archive's storage quota, making it unlimited.
+///
+/// # Returns
+/// - 204 No Content on success
+/// - 404 Not Found if the archive doesn't exist or has no quota
+#[utoipa::path(delete, path = "/api/v1/archives/{name}/quota", tag = "Archives",
+    params(("name" = String, Path, description = "Archive name")),
+    responses((status = 204, description = "No Content")))]
+pub async fn delete_archive_quota(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let archive = archive_by_name(&state, &name).await?;
+    let deleted = state.db.archive_quotas.delete(&archive.schema_name).await?;
+    if !deleted {
+        return Err(ApiError::NotFound("Archive has no quota.".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get an archive's current storage consumption.
+///
+/// Counts notes, attachments and embeddings, sums attachment blob storage by
+/// backend, and breaks the schema down by table (row counts are planner
+/// estimates). The quota in effect is included for comparison.
+///
+/// # Returns
+/// - 200 OK with the usage report
+/// - 404 Not Found if archive doesn't exist
+#[utoipa::path(get, path = "/api/v1/archives/{name}/usage", tag = "Archives",
+    params(("name" = String, Path, description = "Archive name")),
+    responses((status = 200, description = "Success", body = ArchiveUsageResponse)))]
+pub async fn get_archive_usage(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ArchiveUsageResponse>, ApiError> {
+    let archive = archive_by_name(&state, &name).await?;
+    let usage = state.db.archive_quotas.usage(&archive.schema_name).await?;
+    let quota = state
+        .db
+        .archive_quotas
+        .get(&archive.schema_name)
+        .await?
+        .unwrap_or_default();
+
+    Ok(Json(ArchiveUsageResponse {
+        archive: archive.name,
+        usage,
+        quota,
+    }))
+}
+
+// =============================================================================
+// MEMORY PACKS
+// =============================================================================
+
+const MEMPACK_OPERATION: &str = "Memory pack";
+const MEMPACK_CHANNEL_CAPACITY: usize = 64;
+
+/// Query parameters for exporting a memory pack.
+#[derive(Debug, Deserialize)]
+pub struct MempackExportQuery {
+    /// Include chunk embeddings (default false).
+    #[serde(default)]
+    pub embeddings: bool,
+}
+
+fn mempack_operation_failed(context: &'static str, error: impl fmt::Display) -> ApiError {
+    crate::backup_operation_failed(MEMPACK_OPERATION, context, error)
+}
+
+fn mempack_download_filename(memory_name: &str, timestamp: &str) -> String {
+    format!(
+        "memory_name_len_{}_{}.mempack",
+        telemetry_text_len(memory_name),
+        timestamp
+    )
+}
+
+/// Export an archive as a portable `.mempack` file.
+///
+/// The pack holds notes, the link graph, SKOS vocabulary as Turtle and
+/// attachments, plus chunk embeddings when `embeddings=true`. Soft-deleted and
+/// encrypted notes are left out.
+///
+/// # Returns
+/// - 200 OK with the gzip-compressed pack
+/// - 404 Not Found if the archive doesn't exist
+#[utoipa::path(get, path = "/api/v1/archives/{name}/mempack", tag = "Archives",
+    params(
+        ("name" = String, Path, description = "Archive name"),
+        ("embeddings" = Option<bool>, Query, description = "Include chunk embeddings"),
+    ),
+    responses((status = 200, description = "Memory pack download", content_type = "application/vnd.fortemi.mempack+gzip")))]
+pub async fn export_mempack(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<MempackExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    use std::io::Write;
+
+    let archive = archive_by_name(&state, &name).await?;
+    let pack_file = tempfile::NamedTempFile::new()
+        .map_err(|e| mempack_operation_failed("prepare pack export", e))?;
+    let output = pack_file
+        .reopen()
+        .map_err(|e| mempack_operation_failed("open pack export", e))?;
+    let manifest = MempackManifest::new(Some(archive.name.clone()), query.embeddings);
+    let mut writer = MempackWriter::new(std::io::BufWriter::new(output), &manifest)?;
+
+    let ctx = state.db.for_schema(&archive.schema_name)?;
+    let mut tx = ctx.begin_tx().await?;
+    let summary = state
+        .db
+        .mempack
+        .export_tx(
+            &mut tx,
+            &mut writer,
+            state.db.file_storage.as_ref(),
+            query.embeddings,
+        )
+        .await?;
+    tx.commit().await.map_err(matric_db::Error::Database)?;
+    writer
+        .finish()?
+        .flush()
+        .map_err(|e| mempack_operation_failed("flush pack export", e))?;
+
+    let size = pack_file
+        .as_file()
+        .metadata()
+        .map_err(|e| mempack_operation_failed("read pack export metadata", e))?
+        .len();
+    tracing::info!(
+        notes = summary.counts.notes,
+        links = summary.counts.links,
+        attachments = summary.counts.attachments,
+        embeddings = summary.counts.embeddings,
+        encrypted_notes_skipped = summary.encrypted_notes_skipped,
+        attachments_skipped = summary.attachments_skipped,
+        size_bytes = size,
+        "Exported memory pack"
+    );
+
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string();
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(MEMPACK_CONTENT_TYPE),
+    );
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_str(&format!(
+            "attachment; filename=\"{}\"",
+            mempack_download_filename(&archive.name, &timestamp)
+        ))
+        .map_err(|e| mempack_operation_failed("build pack filename", e))?,
+    );
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(size));
+
+    let file = tokio::fs::File::open(pack_file.path())
+        .await
+        .map_err(|e| mempack_operation_failed("open completed pack export", e))?;
+    let temporary_path = pack_file.into_temp_path();
+    let stream = futures::stream::try_unfold(
+        (file, temporary_path),
+        |(mut file, temporary_path)| async move {
+            use tokio::io::AsyncReadExt;
+
+            let mut buffer = vec![0_u8; 64 * 1024];
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                Ok::<_, std::io::Error>(None)
+            } else {
+                buffer.truncate(read);
+                Ok(Some((Bytes::from(buffer), (file, temporary_path))))
+            }
+        },
+    );
+
+    Ok((StatusCode::OK, headers, Body::from_stream(stream)))
+}
+
+/// Import a `.mempack` file into an existing archive.
+///
+/// Upload the pack as multipart field `file` (or `mempack`). Every note gets a
+/// new ID; links, attachments and embeddings follow the remap. Embeddings are
+/// kept only when they come from the archive's default embedding model, and
+/// notes without them are queued for the NLP pipeline. The import runs in one
+/// transaction, so a corrupt or truncated pack changes nothing.
+///
+/// # Returns
+/// - 200 OK with the import report, including the source → new note ID map
+/// - 400 Bad Request if the pack is missing, invalid or fails its checksums
+/// - 404 Not Found if the archive doesn't exist
+#[utoipa::path(post, path = "/api/v1/archives/{name}/mempack", tag = "Archives",
+    params(("name" = String, Path, description = "Target archive name")),
+    responses((status = 200, description = "Import report", body = MempackImportReport)))]
+pub async fn import_mempack(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    mut multipart: Multipart,
+) -> Result<Json<MempackImportReport>, ApiError> {
+    use tokio::io::AsyncWriteExt;
+
+    let archive = archive_by_name(&state, &name).await?;
+
+    let mut pack_file: Option<tempfile::NamedTempFile> = None;
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| ApiError::BadRequest("Invalid multipart upload request.".to_string()))?
+    {
+        if field.name() != Some("file") && field.name() != Some("mempack") {
+            continue;
+        }
+        let upload = tempfile::NamedTempFile::new()
+            .map_err(|e| mempack_operation_failed("prepare pack upload", e))?;
+        let writer = upload
+            .reopen()
+            .map_err(|e| mempack_operation_failed("open pack upload", e))?;
+        let mut uploaded = tokio::fs::File::from_std(writer);
+        let mut uploaded_bytes = 0usize;
+        while let Some(chunk) = field
+            .chunk()
+            .await
+            .map_err(|_| ApiError::BadRequest("Invalid uploaded memory pack data.".to_string()))?
+        {
+            uploaded_bytes = uploaded_bytes.saturating_add(chunk.len());
+            if uploaded_bytes > state.max_upload_size {
+                return Err(ApiError::BadRequest(
+                    "Memory pack exceeds the upload size limit.".to_string(),
+                ));
+            }
+            uploaded
+                .write_all(&chunk)
+                .await
+                .map_err(|e| mempack_operation_failed("write pack upload", e))?;
+        }
+        uploaded
+            .flush()
+            .await
+            .map_err(|e| mempack_operation_failed("flush pack upload", e))?;
+        pack_file = Some(upload);
+        break;
+    }
+    let pack_file = pack_file.ok_or_else(|| {
+        ApiError::BadRequest("No file uploaded. Use field name 'file' or 'mempack'.".to_string())
+    })?;
+    let input = pack_file
+        .reopen()
+        .map_err(|e| mempack_operation_failed("read pack upload", e))?;
+
+    // Parse on a blocking thread and apply records as they arrive.
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(MEMPACK_CHANNEL_CAPACITY);
+    let reader = tokio::task::spawn_blocking(move || {
+        let _pack_file = pack_file;
+        let result = read_mempack(
+            std::io::BufReader::new(input),
+            MEMPACK_DEFAULT_MAX_ENTRY_BYTES,
+            |item| {
+                sender.blocking_send(Ok(item)).map_err(|_| {
+                    matric_db::Error::Internal("memory pack import stopped".to_string())
+                })
+            },
+        );
+        if let Err(error) = result {
+            let _ = sender.blocking_send(Err(error));
+        }
+    });
+
+    let ctx = state.db.for_schema(&archive.schema_name)?;
+    let mut tx = ctx.begin_tx().await?;
+    let embedding_model = state
+        .db
+        .embedding_sets
+        .get_default_config_tx(&mut tx)
+        .await?
+        .map(|config| config.model);
+    let imported = state
+        .db
+        .mempack
+        .import_tx(
+            &mut tx,
+            &mut receiver,
+            state.db.file_storage.as_ref(),
+            &MempackImportOptions { embedding_model },
+        )
+        .await;
+    drop(receiver);
+    reader
+        .await
+        .map_err(|e| mempack_operation_failed("read pack", e))?;
+    let report = imported?;
+    tx.commit().await.map_err(matric_db::Error::Database)?;
+
+    let schema_for_jobs = (archive.schema_name != "public").then_some(archive.schema_name.as_str());
+    for note_id in &report.notes_needing_embeddings {
+        crate::queue_nlp_pipeline(
+            &state.db,
+            *note_id,
+            RevisionMode::None,
+            &state.event_bus,
+            schema_for_jobs,
+            None,
+        )
+        .await;
+    }
+    tracing::info!(
+        notes = report.notes_imported,
+        links = report.links_imported,
+        links_skipped = report.links_skipped,
+        attachments = report.attachments_imported,
+        attachments_skipped = report.attachments_skipped,
+        embeddings = report.embeddings_imported,
+        embeddings_skipped = report.embeddings_skipped,
+        "Imported memory pack"
+    );
+
+    Ok(Json(report))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -474,6 +1351,17 @@ mod tests {
         assert!(req.description.is_none());
     }
 
+    #[test]
+    fn test_create_archive_template_resolves_builtin_profiles() {
+        assert!(provisioning_profile_for(None).unwrap().is_none());
+        let profile = provisioning_profile_for(Some("research")).unwrap().unwrap();
+        assert_eq!(profile.name, "research");
+        assert!(matches!(
+            provisioning_profile_for(Some("unknown")),
+            Err(ApiError::BadRequest(message)) if message == UNKNOWN_ARCHIVE_TEMPLATE_MESSAGE
+        ));
+    }
+
     #[test]
     fn test_update_archive_request_deserialization() {
         let json = r#"{"description":"Updated description"}"#;
@@ -752,4 +1640,94 @@ mod tests {
             assert!(!combined.contains(raw), "raw value leaked: {raw}");
         }
     }
+
+    #[test]
+    fn version_policy_response_flattens_policy_and_redacts_archive_name() {
+        let response = ArchiveVersionPolicyResponse {
+            archive: "tenant-alpha-private".to_string(),
+            source: "archive",
+            policy: VersionRetentionPolicy {
+                max_versions: Some(10),
+                max_age_days: None,
+                keep_milestones: true,
+            },
+        };
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["source"], "archive");
+        assert_eq!(json["max_versions"], 10);
+        assert!(json["max_age_days"].is_null());
+        assert_eq!(json["keep_milestones"], true);
+
+        let rendered = format!("{response:?}");
+        assert!(rendered.contains("archive_len"));
+        assert!(!rendered.contains("tenant-alpha"));
+    }
+
+    #[test]
+    fn translation_response_flattens_setting_and_redacts_archive_name() {
+        let response = ArchiveTranslationResponse {
+            archive: "tenant-alpha-private".to_string(),
+            source: "default",
+            setting: ArchiveTranslationSetting {
+                enabled: true,
+                target_language: "en".to_string(),
+            },
+        };
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["source"], "default");
+        assert_eq!(json["enabled"], true);
+        assert_eq!(json["target_language"], "en");
+
+        let rendered = format!("{response:?}");
+        assert!(rendered.contains("archive_len"));
+        assert!(!rendered.contains("tenant-alpha"));
+    }
+
+    #[test]
+    fn usage_response_flattens_usage_and_redacts_archive_name() {
+        let response = ArchiveUsageResponse {
+            archive: "tenant-alpha-private".to_string(),
+            usage: ArchiveUsage {
+                notes: 3,
+                attachments: 1,
+                embeddings: 12,
+                blob_storage: matric_core::ArchiveBlobUsage {
+                    blobs: 1,
+                    bytes: 2048,
+                    database_bytes: 2048,
+                    external_bytes: 0,
+                },
+                tables: vec![matric_core::ArchiveTableUsage {
+                    table: "note".to_string(),
+                    rows_estimate: 3,
+                    total_bytes: 65536,
+                }],
+                table_bytes: 65536,
+            },
+            quota: ArchiveQuota {
+                max_notes: Some(10),
+                ..Default::default()
+            },
+        };
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["notes"], 3);
+        assert_eq!(json["blob_storage"]["bytes"], 2048);
+        assert_eq!(json["tables"][0]["table"], "note");
+        assert_eq!(json["quota"]["max_notes"], 10);
+        assert!(json["quota"]["max_embeddings"].is_null());
+
+        let rendered = format!("{response:?}");
+        assert!(rendered.contains("archive_len"));
+        assert!(!rendered.contains("tenant-alpha"));
+    }
+
+    #[test]
+    fn mempack_download_filename_uses_metadata_only() {
+        let filename = mempack_download_filename("tenant-alpha-private", "20261017_120000");
+        assert_eq!(filename, "memory_name_len_20_20261017_120000.mempack");
+        assert!(!filename.contains("tenant-alpha"));
+    }
 }
@@ -0,0 +1,331 @@
+//! Scheduled backup policy HTTP handlers.
+//!
+//! Policies are run by the `scheduled_backup` job; the periodic scheduler in
+//! `main.rs` queues one whenever a policy's schedule comes due.
+//! - `GET /api/v1/backup/policies` — list policies
+//! - `POST /api/v1/backup/policies` — create a policy
+//! - `GET /api/v1/backup/policies/{id}` — get a policy
+//! - `PATCH /api/v1/backup/policies/{id}` — update a policy
+//! - `DELETE /api/v1/backup/policies/{id}` — delete a policy and its run history
+//! - `POST /api/v1/backup/policies/{id}/run` — queue a run now
+//! - `GET /api/v1/backup/policies/{id}/runs` — recent runs, newest first
+
+use std::fmt;
+
+use async_trait::async_trait;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{ApiError, AppState};
+use matric_core::{
+    BackupPolicy, BackupPolicyRun, CreateBackupPolicyRequest, CronSchedule, JobRepository, JobType,
+    ServerEvent, UpdateBackupPolicyRequest,
+};
+use matric_db::Database;
+use matric_jobs::{BackupRunner, CreatedBackup};
+
+const DEFAULT_RUNS_LIMIT: i64 = 20;
+const MAX_RUNS_LIMIT: i64 = 200;
+
+/// Writes policy backups with the same code paths as the backup endpoints.
+pub struct ApiBackupRunner {
+    db: Database,
+}
+
+impl ApiBackupRunner {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl BackupRunner for ApiBackupRunner {
+    async fn create_backup(&self, policy: &BackupPolicy) -> Result<CreatedBackup, String> {
+        let title = Some(format!("Scheduled backup ({})", policy.name));
+        let written = match policy.backup_type.as_str() {
+            "full" => {
+                let keyset =
+                    super::pke::resolve_keyset_or_active(&self.db, policy.keyset.as_deref())
+                        .await
+                        .map_err(|e| backup_run_error(&e))?;
+                crate::write_full_backup(&self.db, &keyset, Some(&policy.name), title, None, true)
+                    .await
+                    .map(|full| full.backup)
+            }
+            _ => crate::write_snapshot_backup(&self.db, Some(&policy.name), title, None).await,
+        }
+        .map_err(|e| backup_run_error(&e))?;
+
+        Ok(CreatedBackup {
+            filename: written.filename,
+            size_bytes: i64::try_from(written.size_bytes).unwrap_or(i64::MAX),
+        })
+    }
+
+    async fn delete_backup(&self, filename: &str) -> Result<(), String> {
+        if filename.contains("..") || filename.contains('/') || filename.contains('\\') {
+            return Err("Invalid backup filename".to_string());
+        }
+        let backup_dir = std::env::var("BACKUP_DEST")
+            .unwrap_or_else(|_| "/var/backups/matric-memory".to_string());
+        let backup_dir = std::path::Path::new(&backup_dir);
+
+        match tokio::fs::remove_file(backup_dir.join(filename)).await {
+            Ok(()) => {}
+            // Already removed by hand; the run is still marked as pruned.
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(_) => return Err("Failed to delete backup file".to_string()),
+        }
+        for sidecar in [".meta.json", ".state.json"] {
+            let _ = tokio::fs::remove_file(backup_dir.join(format!("{filename}{sidecar}"))).await;
+        }
+        Ok(())
+    }
+}
+
+/// Run error recorded on the run and in `BackupFailed`, without raw
+/// diagnostics such as paths or command output.
+fn backup_run_error(err: &ApiError) -> String {
+    match err {
+        ApiError::OperationFailed { operation, detail } => format!("{operation} failed: {detail}"),
+        ApiError::NotFound(message)
+        | ApiError::BadRequest(message)
+        | ApiError::Conflict(message) => message.clone(),
+        _ => "Backup failed".to_string(),
+    }
+}
+
+/// Next run of `schedule`, or none for a disabled policy.
+fn next_run_at(schedule: &CronSchedule, enabled: bool) -> Option<chrono::DateTime<chrono::Utc>> {
+    enabled.then(|| schedule.next_after(Utc::now())).flatten()
+}
+
+fn backup_policy_not_found() -> ApiError {
+    ApiError::NotFound("Backup policy not found".to_string())
+}
+
+/// Queue a `scheduled_backup` job for `policy_id`.
+pub(crate) async fn queue_backup_policy_run(
+    db: &Database,
+    event_bus: &matric_core::EventBus,
+    policy_id: Uuid,
+) -> matric_core::Result<Uuid> {
+    let job_id = db
+        .jobs
+        .queue(
+            None,
+            JobType::ScheduledBackup,
+            JobType::ScheduledBackup.default_priority(),
+            Some(json!({ "policy_id": policy_id })),
+            JobType::ScheduledBackup.default_cost_tier(),
+        )
+        .await?;
+    event_bus.emit(ServerEvent::JobQueued {
+        job_id,
+        job_type: format!("{:?}", JobType::ScheduledBackup),
+        note_id: None,
+    });
+    Ok(job_id)
+}
+
+#[derive(Deserialize)]
+pub struct BackupPolicyRunsQuery {
+    /// Maximum number of runs to return (default: 20, max: 200).
+    limit: Option<i64>,
+}
+
+impl fmt::Debug for BackupPolicyRunsQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackupPolicyRunsQuery")
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+/// List backup policies.
+#[utoipa::path(get, path = "/api/v1/backup/policies", tag = "Backup",
+    responses((status = 200, description = "Success", body = Vec<BackupPolicy>)))]
+pub async fn list_backup_policies(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<BackupPolicy>>, ApiError> {
+    Ok(Json(state.db.backup_policies.list().await?))
+}
+
+/// Create a backup policy.
+#[utoipa::path(post, path = "/api/v1/backup/policies", tag = "Backup",
+    request_body = CreateBackupPolicyRequest,
+    responses(
+        (status = 201, description = "Created", body = BackupPolicy),
+        (status = 400, description = "Invalid name, schedule, type, or retention")
+    ))]
+pub async fn create_backup_policy(
+    State(state): State<AppState>,
+    Json(req): Json<CreateBackupPolicyRequest>,
+) -> Result<(StatusCode, Json<BackupPolicy>), ApiError> {
+    let schedule = req.validate()?;
+    let policy = state
+        .db
+        .backup_policies
+        .create(&req, next_run_at(&schedule, req.enabled))
+        .await?;
+    Ok((StatusCode::CREATED, Json(policy)))
+}
+
+/// Get a backup policy.
+#[utoipa::path(get, path = "/api/v1/backup/policies/{id}", tag = "Backup",
+    params(("id" = Uuid, Path, description = "Backup policy ID")),
+    responses(
+        (status = 200, description = "Success", body = BackupPolicy),
+        (status = 404, description = "Backup policy not found")
+    ))]
+pub async fn get_backup_policy(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<BackupPolicy>, ApiError> {
+    state
+        .db
+        .backup_policies
+        .get(id)
+        .await?
+        .map(Json)
+        .ok_or_else(backup_policy_not_found)
+}
+
+/// Update a backup policy.
+///
+/// The next run is recomputed when the schedule changes or the policy is
+/// re-enabled; disabling a policy clears it.
+#[utoipa::path(patch, path = "/api/v1/backup/policies/{id}", tag = "Backup",
+    params(("id" = Uuid, Path, description = "Backup policy ID")),
+    request_body = UpdateBackupPolicyRequest,
+    responses(
+        (status = 200, description = "Updated", body = BackupPolicy),
+        (status = 400, description = "Invalid schedule, type, or retention"),
+        (status = 404, description = "Backup policy not found")
+    ))]
+pub async fn update_backup_policy(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateBackupPolicyRequest>,
+) -> Result<Json<BackupPolicy>, ApiError> {
+    let mut policy = state
+        .db
+        .backup_policies
+        .get(id)
+        .await?
+        .ok_or_else(backup_policy_not_found)?;
+    let changed_schedule = req.apply(&mut policy)?;
+
+    if !policy.enabled {
+        policy.next_run_at_utc = None;
+    } else if changed_schedule.is_some() || policy.next_run_at_utc.is_none() {
+        let schedule = match changed_schedule {
+            Some(schedule) => schedule,
+            None => CronSchedule::parse(&policy.schedule)?,
+        };
+        policy.next_run_at_utc = next_run_at(&schedule, true);
+    }
+
+    state
+        .db
+        .backup_policies
+        .update(&policy)
+        .await?
+        .map(Json)
+        .ok_or_else(backup_policy_not_found)
+}
+
+/// Delete a backup policy and its run history. Backup files are kept.
+#[utoipa::path(delete, path = "/api/v1/backup/policies/{id}", tag = "Backup",
+    params(("id" = Uuid, Path, description = "Backup policy ID")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, description = "Backup policy not found")
+    ))]
+pub async fn delete_backup_policy(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    if state.db.backup_policies.delete(id).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(backup_policy_not_found())
+    }
+}
+
+/// Queue a run of a backup policy now, outside its schedule.
+#[utoipa::path(post, path = "/api/v1/backup/policies/{id}/run", tag = "Backup",
+    params(("id" = Uuid, Path, description = "Backup policy ID")),
+    responses(
+        (status = 202, description = "Run queued"),
+        (status = 404, description = "Backup policy not found")
+    ))]
+pub async fn run_backup_policy(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    if state.db.backup_policies.get(id).await?.is_none() {
+        return Err(backup_policy_not_found());
+    }
+    let job_id = queue_backup_policy_run(&state.db, &state.event_bus, id).await?;
+    Ok((StatusCode::ACCEPTED, Json(json!({ "job_id": job_id }))))
+}
+
+/// List recent runs of a backup policy, newest first.
+#[utoipa::path(get, path = "/api/v1/backup/policies/{id}/runs", tag = "Backup",
+    params(
+        ("id" = Uuid, Path, description = "Backup policy ID"),
+        ("limit" = Option<i64>, Query, description = "Maximum runs to return (default 20, max 200)")
+    ),
+    responses(
+        (status = 200, description = "Success", body = Vec<BackupPolicyRun>),
+        (status = 404, description = "Backup policy not found")
+    ))]
+pub async fn list_backup_policy_runs(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<BackupPolicyRunsQuery>,
+) -> Result<Json<Vec<BackupPolicyRun>>, ApiError> {
+    if state.db.backup_policies.get(id).await?.is_none() {
+        return Err(backup_policy_not_found());
+    }
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_RUNS_LIMIT)
+        .clamp(1, MAX_RUNS_LIMIT);
+    Ok(Json(state.db.backup_policies.list_runs(id, limit).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_errors_omit_raw_diagnostics() {
+        let err = crate::backup_operation_failed(
+            "Full backup",
+            "spawn pg_dump",
+            "permission denied for /srv/fortemi/backups",
+        );
+        let message = backup_run_error(&err);
+        assert!(message.starts_with("Full backup failed: spawn pg_dump"));
+        assert!(!message.contains("/srv/fortemi"));
+
+        let err = ApiError::Internal("token=secret".to_string());
+        assert_eq!(backup_run_error(&err), "Backup failed");
+    }
+
+    #[test]
+    fn disabled_policies_have_no_next_run() {
+        let schedule = CronSchedule::parse("@daily").unwrap();
+        assert!(next_run_at(&schedule, false).is_none());
+        assert!(next_run_at(&schedule, true).is_some_and(|next| next > Utc::now()));
+    }
+}
@@ -0,0 +1,173 @@
+//! Calendar feed HTTP handler.
+//!
+//! - `GET /api/v1/calendar.ics` — review reminders, due tasks and note
+//!   creations as an iCalendar feed
+//!
+//! Calendar apps subscribe by URL and cannot send headers, so this route
+//! takes the bearer token as `?token=` and the memory as `?memory=` (see
+//! [`crate::route_policy::accepts_query_credentials`]). A read-only API key
+//! confined to the feed's memory or collection is the token to hand out.
+//! A user's token only sees items of the notes the user can read.
+
+use std::fmt;
+
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use chrono::{Days, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::calendar::{
+    feed_events, render_calendar, CalendarWindow, CALENDAR_CONTENT_TYPE, DEFAULT_FUTURE_DAYS,
+    DEFAULT_PAST_DAYS, MAX_EVENTS_PER_KIND, MAX_WINDOW_DAYS,
+};
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+pub struct CalendarQuery {
+    /// Only items of notes filed in this collection.
+    collection_id: Option<Uuid>,
+    /// Include a daily reminder of review cards due (default: true).
+    #[serde(default = "default_true")]
+    include_reviews: bool,
+    /// Include open tasks on their due day (default: true).
+    #[serde(default = "default_true")]
+    include_tasks: bool,
+    /// Include an event per note created (default: false).
+    #[serde(default)]
+    include_notes: bool,
+    /// Days before today covered (default: 30, max: 366).
+    past_days: Option<u32>,
+    /// Days after today covered (default: 90, max: 366).
+    future_days: Option<u32>,
+}
+
+impl fmt::Debug for CalendarQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CalendarQuery")
+            .field("collection_id_set", &self.collection_id.is_some())
+            .field("include_reviews", &self.include_reviews)
+            .field("include_tasks", &self.include_tasks)
+            .field("include_notes", &self.include_notes)
+            .field("past_days", &self.past_days)
+            .field("future_days", &self.future_days)
+            .finish()
+    }
+}
+
+/// Calendar feed.
+///
+/// An iCalendar feed of the memory's dated items, for subscribing from a
+/// calendar app: one all-day reminder per day with review cards due
+/// (overdue cards show today), one all-day event per open task on its due
+/// day, and, with `include_notes=true`, one event per note created. Days
+/// are UTC. Event UIDs are stable, so apps update events in place.
+///
+/// Calendar apps cannot send headers: pass the token as `token` and the
+/// memory as `memory`. A collection-restricted API key needs
+/// `collection_id` set to one of its collections.
+///
+/// GET /api/v1/calendar.ics
+#[utoipa::path(get, path = "/api/v1/calendar.ics", tag = "Notes",
+    params(
+        ("token" = Option<String>, Query, description = "Bearer token, for clients that cannot send headers"),
+        ("memory" = Option<String>, Query, description = "Memory (falls back to X-Fortemi-Memory, then the default memory)"),
+        ("collection_id" = Option<Uuid>, Query, description = "Only items of notes in this collection"),
+        ("include_reviews" = Option<bool>, Query, description = "Daily review reminders (default true)"),
+        ("include_tasks" = Option<bool>, Query, description = "Open tasks on their due day (default true)"),
+        ("include_notes" = Option<bool>, Query, description = "Note creation events (default false)"),
+        ("past_days" = Option<u32>, Query, description = "Days before today covered (default 30, max 366)"),
+        ("future_days" = Option<u32>, Query, description = "Days after today covered (default 90, max 366)")
+    ),
+    responses(
+        (status = 200, description = "iCalendar feed", content_type = "text/calendar"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Memory not found")
+    ))]
+pub async fn get_calendar_feed(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Query(query): Query<CalendarQuery>,
+) -> Result<Response, ApiError> {
+    let now = Utc::now();
+    let today = now.date_naive();
+    let past_days = query
+        .past_days
+        .unwrap_or(DEFAULT_PAST_DAYS)
+        .min(MAX_WINDOW_DAYS);
+    let future_days = query
+        .future_days
+        .unwrap_or(DEFAULT_FUTURE_DAYS)
+        .min(MAX_WINDOW_DAYS);
+    let window = CalendarWindow {
+        from: today
+            .checked_sub_days(Days::new(past_days.into()))
+            .unwrap_or(today),
+        until: today
+            .checked_add_days(Days::new(future_days.into()))
+            .unwrap_or(today),
+        collection_id: query.collection_id,
+    };
+
+    let security = caller.security_filter();
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let calendar = state.db.calendar.clone();
+    let (include_reviews, include_tasks, include_notes) = (
+        query.include_reviews,
+        query.include_tasks,
+        query.include_notes,
+    );
+    let (reviews, tasks, notes) = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                let security = security.as_ref();
+                let reviews = if include_reviews {
+                    let upcoming = CalendarWindow {
+                        from: today,
+                        ..window
+                    };
+                    calendar.review_days_tx(tx, &upcoming, security).await?
+                } else {
+                    Vec::new()
+                };
+                let tasks = if include_tasks {
+                    calendar
+                        .due_tasks_tx(tx, &window, security, MAX_EVENTS_PER_KIND)
+                        .await?
+                } else {
+                    Vec::new()
+                };
+                let notes = if include_notes {
+                    calendar
+                        .note_creations_tx(tx, &window, security, MAX_EVENTS_PER_KIND)
+                        .await?
+                } else {
+                    Vec::new()
+                };
+                Ok((reviews, tasks, notes))
+            })
+        })
+        .await?;
+
+    let name = match &archive_ctx.name {
+        Some(name) if !archive_ctx.is_default => format!("Fortemi: {name}"),
+        _ => "Fortemi".to_string(),
+    };
+    let events = feed_events(&reviews, &tasks, &notes, &state.issuer);
+    Ok((
+        [(header::CONTENT_TYPE, CALENDAR_CONTENT_TYPE)],
+        render_calendar(&name, &events, now),
+    )
+        .into_response())
+}
@@ -13,7 +13,7 @@ use axum::extract::State;
 use axum::http::{header, HeaderMap, StatusCode};
 use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::{IntoResponse, Response};
-use axum::Json;
+use axum::{Extension, Json};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
@@ -22,8 +22,9 @@ use tracing::{debug, info, warn};
 
 use matric_api::services::chat_stream_store::{ResumeCursor, StoredFrame};
 use matric_api::services::ChatStreamStore;
-use matric_core::GenerationBackend;
+use matric_core::{GenerationBackend, UsageCounter};
 
+use crate::middleware::usage_quota::{inference_tokens, UsageRecorder};
 use crate::{ApiError, AppState};
 use matric_inference::discovery::ModelDiscovery;
 use matric_inference::profiles::ModelRegistry;
@@ -279,6 +280,7 @@ const SYSTEM_PROMPT_OVERHEAD_TOKENS: usize = 200;
 )]
 pub async fn chat_handler(
     State(state): State<AppState>,
+    usage: Option<Extension<UsageRecorder>>,
     Json(req): Json<ChatRequest>,
 ) -> impl IntoResponse {
     // 1. Validate input
@@ -374,6 +376,14 @@ pub async fn chat_handler(
                 response_len = content.len(),
                 "Chat response generated"
             );
+            if let Some(Extension(usage)) = &usage {
+                usage
+                    .record(
+                        UsageCounter::InferenceTokens,
+                        inference_tokens(&[SYSTEM_PROMPT, &prompt, &content]),
+                    )
+                    .await;
+            }
 
             let response = ChatResponse {
                 messages: vec![ChatMessage {
@@ -971,6 +981,7 @@ fn record_disconnect(metrics: &ChatStreamMetrics, delivered: u64, dropped: u64)
 pub async fn chat_stream_handler(
     State(state): State<AppState>,
     headers: HeaderMap,
+    usage: Option<Extension<UsageRecorder>>,
     Json(req): Json<ChatRequest>,
 ) -> Response {
     // 0. Resumption: a `Last-Event-ID` header means the client is reconnecting to
@@ -1075,6 +1086,20 @@ pub async fn chat_stream_handler(
             .await
         {
             Ok(chunks) => {
+                // Count generated text as it is pumped, including tokens
+                // produced before a disconnect.
+                let output = Arc::new(std::sync::Mutex::new(String::new()));
+                let chunks = chunks.inspect({
+                    let output = output.clone();
+                    move |item| {
+                        if let Ok(content) = item {
+                            output
+                                .lock()
+                                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                                .push_str(content);
+                        }
+                    }
+                });
                 pump_chat_stream(
                     chunks,
                     tx,
@@ -1085,6 +1110,18 @@ pub async fn chat_stream_handler(
                     store,
                 )
                 .await;
+                if let Some(Extension(usage)) = usage {
+                    let output = output
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .clone();
+                    usage
+                        .record(
+                            UsageCounter::InferenceTokens,
+                            inference_tokens(&[SYSTEM_PROMPT, &prompt, &output]),
+                        )
+                        .await;
+                }
             }
             Err(e) => {
                 let diagnostic = e.to_string();
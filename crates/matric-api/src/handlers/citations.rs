@@ -0,0 +1,106 @@
+//! Citation HTTP handlers.
+//!
+//! Read access to the references stored by the citation extraction job:
+//! - `GET /api/v1/notes/{id}/citations` — the works a note cites
+//! - `GET /api/v1/collections/{id}/bibtex` — a collection's bibliography as BibTeX
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::{render_bibtex, NoteCitations};
+use matric_db::{visibility::visible_note_ids, PgNoteRepository, PgReferenceRepository};
+
+/// List a note's citations.
+///
+/// The DOIs, arXiv IDs, URLs and BibTeX entries the note cites, most cited
+/// first. Empty until the note's `citation_extraction` job has run.
+///
+/// GET /api/v1/notes/{id}/citations
+#[utoipa::path(get, path = "/api/v1/notes/{id}/citations", tag = "Notes",
+    params(("id" = Uuid, Path, description = "Note ID")),
+    responses(
+        (status = 200, description = "Citations", body = NoteCitations),
+        (status = 404, description = "Note not found")
+    ))]
+pub async fn get_note_citations(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+) -> Result<Json<NoteCitations>, ApiError> {
+    let security = caller.security_filter();
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let notes = PgNoteRepository::new(state.db.pool.clone());
+    let references = PgReferenceRepository::new(state.db.pool.clone());
+    let citations = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                let visible = match &security {
+                    Some(security) => visible_note_ids(&mut **tx, &[id], security)
+                        .await?
+                        .contains(&id),
+                    None => true,
+                };
+                if !visible || !notes.exists_tx(tx, id).await? {
+                    return Err(matric_core::Error::NotFound("Note not found".to_string()));
+                }
+                references.list_for_note_tx(tx, id).await
+            })
+        })
+        .await?;
+    Ok(Json(NoteCitations {
+        note_id: id,
+        citations,
+    }))
+}
+
+/// Export a collection's bibliography.
+///
+/// Every work cited by a note in the collection or its subcollections, once,
+/// as a BibTeX file.
+///
+/// GET /api/v1/collections/{id}/bibtex
+#[utoipa::path(get, path = "/api/v1/collections/{id}/bibtex", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    responses(
+        (status = 200, description = "BibTeX file", content_type = "application/x-bibtex", body = String),
+        (status = 404, description = "Collection not found")
+    ))]
+pub async fn export_collection_bibtex(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let security = caller.security_filter();
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let references = PgReferenceRepository::new(state.db.pool.clone());
+    let bibliography = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                references
+                    .list_for_collection_tx(tx, id, security.as_ref())
+                    .await
+            })
+        })
+        .await?;
+
+    let headers = [
+        (
+            header::CONTENT_TYPE,
+            "application/x-bibtex; charset=utf-8".to_string(),
+        ),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"collection-{id}.bib\""),
+        ),
+    ];
+    Ok((StatusCode::OK, headers, render_bibtex(&bibliography)))
+}
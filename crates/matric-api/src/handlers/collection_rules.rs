@@ -0,0 +1,163 @@
+//! Smart collection rule HTTP handlers.
+//!
+//! A rule makes a collection a smart collection: the `collection_rule_sync`
+//! job files the notes matching its filter into the collection, and the
+//! periodic scheduler in `main.rs` re-syncs enabled rules.
+//! - `GET /api/v1/collections/{id}/rules` — the collection's rule
+//! - `PUT /api/v1/collections/{id}/rules` — set or replace the rule and queue a sync
+//! - `DELETE /api/v1/collections/{id}/rules` — remove the rule; filed notes stay
+//! - `POST /api/v1/collections/{id}/rules/refresh` — queue a sync now
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::{CollectionRule, JobRepository, JobType, ServerEvent, SetCollectionRuleRequest};
+use matric_db::Database;
+
+fn collection_rule_not_found() -> ApiError {
+    ApiError::NotFound("Collection has no rule".to_string())
+}
+
+/// Queue a `collection_rule_sync` job for one collection.
+pub(crate) async fn queue_collection_rule_sync(
+    db: &Database,
+    event_bus: &matric_core::EventBus,
+    schema: &str,
+    collection_id: Uuid,
+) -> matric_core::Result<Uuid> {
+    let job_id = db
+        .jobs
+        .queue(
+            None,
+            JobType::CollectionRuleSync,
+            JobType::CollectionRuleSync.default_priority(),
+            Some(json!({ "schema": schema, "collection_id": collection_id })),
+            JobType::CollectionRuleSync.default_cost_tier(),
+        )
+        .await?;
+    event_bus.emit(ServerEvent::JobQueued {
+        job_id,
+        job_type: format!("{:?}", JobType::CollectionRuleSync),
+        note_id: None,
+    });
+    Ok(job_id)
+}
+
+/// Get a collection's smart rule.
+///
+/// GET /api/v1/collections/{id}/rules
+#[utoipa::path(get, path = "/api/v1/collections/{id}/rules", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    responses(
+        (status = 200, description = "Success", body = CollectionRule),
+        (status = 404, description = "Collection has no rule")
+    ))]
+pub async fn get_collection_rule(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<CollectionRule>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let rules = state.db.collection_rules.clone();
+    ctx.query(move |tx| Box::pin(async move { rules.get_tx(tx, id).await }))
+        .await?
+        .map(Json)
+        .ok_or_else(collection_rule_not_found)
+}
+
+/// Set or replace a collection's smart rule.
+///
+/// An enabled rule is synced right away; the response describes the rule
+/// before that sync has run.
+///
+/// PUT /api/v1/collections/{id}/rules
+#[utoipa::path(put, path = "/api/v1/collections/{id}/rules", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    request_body = SetCollectionRuleRequest,
+    responses(
+        (status = 200, description = "Rule set", body = CollectionRule),
+        (status = 400, description = "Empty filter, too many tags, overlong query or empty date range"),
+        (status = 404, description = "Collection not found")
+    ))]
+pub async fn set_collection_rule(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SetCollectionRuleRequest>,
+) -> Result<Json<CollectionRule>, ApiError> {
+    let filter = req.validated_filter()?;
+    let enabled = req.enabled;
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let rules = state.db.collection_rules.clone();
+    let rule = ctx
+        .execute(move |tx| Box::pin(async move { rules.set_tx(tx, id, &filter, enabled).await }))
+        .await?;
+
+    if rule.enabled {
+        queue_collection_rule_sync(&state.db, &state.event_bus, &archive_ctx.schema, id).await?;
+    }
+    Ok(Json(rule))
+}
+
+/// Remove a collection's smart rule. Notes it filed stay in the collection.
+///
+/// DELETE /api/v1/collections/{id}/rules
+#[utoipa::path(delete, path = "/api/v1/collections/{id}/rules", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    responses(
+        (status = 204, description = "Rule removed"),
+        (status = 404, description = "Collection has no rule")
+    ))]
+pub async fn delete_collection_rule(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let rules = state.db.collection_rules.clone();
+    if ctx
+        .execute(move |tx| Box::pin(async move { rules.delete_tx(tx, id).await }))
+        .await?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(collection_rule_not_found())
+    }
+}
+
+/// Queue a sync of a collection's smart rule.
+///
+/// Disabled rules can still be synced by hand.
+///
+/// POST /api/v1/collections/{id}/rules/refresh
+#[utoipa::path(post, path = "/api/v1/collections/{id}/rules/refresh", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    responses(
+        (status = 202, description = "Sync queued"),
+        (status = 404, description = "Collection has no rule")
+    ))]
+pub async fn refresh_collection_rule(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let rules = state.db.collection_rules.clone();
+    ctx.query(move |tx| Box::pin(async move { rules.get_tx(tx, id).await }))
+        .await?
+        .ok_or_else(collection_rule_not_found)?;
+
+    let job_id =
+        queue_collection_rule_sync(&state.db, &state.event_bus, &archive_ctx.schema, id).await?;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({ "status": "queued", "job_id": job_id })),
+    ))
+}
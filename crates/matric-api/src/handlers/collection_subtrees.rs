@@ -0,0 +1,184 @@
+//! Collection subtree HTTP handlers.
+//!
+//! Each operation carries a collection's descendants along and runs in one
+//! transaction; none may make a collection its own ancestor.
+//! - `POST /api/v1/collections/{id}/move` — move a collection under another parent
+//! - `POST /api/v1/collections/{id}/copy` — copy a subtree with its notes
+//! - `POST /api/v1/collections/reparent` — move several collections under one parent
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde_json::json;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{event_context_for, ApiError, AppState, ArchiveContext};
+use matric_core::{
+    Collection, CollectionCopyResult, CollectionReparentResult, CopyCollectionRequest,
+    JobRepository, JobType, MoveCollectionRequest, ReparentCollectionsRequest, ServerEvent,
+};
+
+/// Move a collection, with its descendants, under another parent.
+///
+/// POST /api/v1/collections/{id}/move
+#[utoipa::path(post, path = "/api/v1/collections/{id}/move", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    request_body = MoveCollectionRequest,
+    responses(
+        (status = 200, description = "Collection moved", body = Collection),
+        (status = 400, description = "The parent is the collection or one of its descendants"),
+        (status = 404, description = "Collection or parent not found")
+    ))]
+pub async fn move_collection(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<MoveCollectionRequest>,
+) -> Result<Json<Collection>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let repo = matric_db::PgCollectionRepository::new(state.db.pool.clone());
+    let collection = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                repo.move_collection_tx(tx, id, req.parent_id).await?;
+                repo.get_tx(tx, id)
+                    .await?
+                    .ok_or_else(|| matric_core::Error::NotFound("Collection not found".into()))
+            })
+        })
+        .await?;
+
+    state.event_bus.emit_with_context(
+        ServerEvent::CollectionUpdated {
+            collection_id: collection.id,
+            name: collection.name.clone(),
+        },
+        event_context_for(&archive_ctx),
+    );
+    Ok(Json(collection))
+}
+
+/// Copy a collection, its descendants and their notes.
+///
+/// The note copies are queued for embedding.
+///
+/// POST /api/v1/collections/{id}/copy
+#[utoipa::path(post, path = "/api/v1/collections/{id}/copy", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    request_body = CopyCollectionRequest,
+    responses(
+        (status = 201, description = "Subtree copied", body = CollectionCopyResult),
+        (status = 400, description = "Copy into its own subtree, name taken or too many notes"),
+        (status = 404, description = "Collection or parent not found")
+    ))]
+pub async fn copy_collection(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CopyCollectionRequest>,
+) -> Result<(StatusCode, Json<CollectionCopyResult>), ApiError> {
+    let name = req.validated_name()?;
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let repo = matric_db::PgCollectionRepository::new(state.db.pool.clone());
+    let (result, name, note_ids) = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                let (result, note_ids) = repo
+                    .copy_subtree_tx(
+                        tx,
+                        id,
+                        req.parent_id,
+                        req.mode,
+                        name.as_deref(),
+                        caller.user_id,
+                    )
+                    .await?;
+                let copy = repo
+                    .get_tx(tx, result.collection_id)
+                    .await?
+                    .ok_or_else(|| matric_core::Error::NotFound("Collection not found".into()))?;
+                Ok((result, copy.name, note_ids))
+            })
+        })
+        .await?;
+
+    state.event_bus.emit_with_context(
+        ServerEvent::CollectionCreated {
+            collection_id: result.collection_id,
+            name,
+        },
+        event_context_for(&archive_ctx),
+    );
+
+    for note_id in note_ids {
+        let queued = state
+            .db
+            .jobs
+            .queue_deduplicated(
+                Some(note_id),
+                JobType::Embedding,
+                JobType::Embedding.default_priority(),
+                Some(json!({ "schema": archive_ctx.schema })),
+                JobType::Embedding.default_cost_tier(),
+            )
+            .await;
+        match queued {
+            Ok(Some(job_id)) => state.event_bus.emit(ServerEvent::JobQueued {
+                job_id,
+                job_type: format!("{:?}", JobType::Embedding),
+                note_id: Some(note_id),
+            }),
+            Ok(None) => {}
+            Err(_) => warn!(
+                operation = "queue_copied_note_embedding",
+                "Failed to queue embedding for copied note"
+            ),
+        }
+    }
+
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Move several collections, with their descendants, under one parent.
+///
+/// Either every collection moves or none does.
+///
+/// POST /api/v1/collections/reparent
+#[utoipa::path(post, path = "/api/v1/collections/reparent", tag = "Collections",
+    request_body = ReparentCollectionsRequest,
+    responses(
+        (status = 200, description = "Collections re-parented", body = CollectionReparentResult),
+        (status = 400, description = "No or too many collections, or a move would create a cycle"),
+        (status = 404, description = "A collection or the parent was not found")
+    ))]
+pub async fn reparent_collections(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Json(req): Json<ReparentCollectionsRequest>,
+) -> Result<Json<CollectionReparentResult>, ApiError> {
+    let ids = req.validated_ids()?;
+    let parent_id = req.parent_id;
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let repo = matric_db::PgCollectionRepository::new(state.db.pool.clone());
+    let (result, moved) = ctx
+        .execute(move |tx| {
+            Box::pin(async move { repo.reparent_collections_tx(tx, &ids, parent_id).await })
+        })
+        .await?;
+
+    for collection in moved {
+        state.event_bus.emit_with_context(
+            ServerEvent::CollectionUpdated {
+                collection_id: collection.id,
+                name: collection.name,
+            },
+            event_context_for(&archive_ctx),
+        );
+    }
+    Ok(Json(result))
+}
@@ -0,0 +1,350 @@
+//! SKOS concept merge and split HTTP handlers.
+//!
+//! Governance operations on the selected archive's concepts:
+//! - `POST /api/v1/concepts/merge` — fold source concepts into a target
+//! - `POST /api/v1/concepts/{id}/split` — distribute a concept's notes over targets
+//! - `POST /api/v1/concepts/{id}/split/suggest` — ask the model for a split to review
+//! - `GET /api/v1/concepts/{id}/audit` — the concept's governance audit log
+//!
+//! Merges and splits run in one transaction and record their changes in the
+//! SKOS audit log.
+
+use std::fmt;
+use std::sync::atomic::Ordering;
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::handlers::jobs::render_job_prompt;
+use crate::{
+    emit_taxonomy_audit_event, event_context_for, taxonomy_audit_event, ApiError, AppState,
+    ArchiveContext,
+};
+use matric_core::{
+    AuditOutcome, ConceptMergeResult, ConceptSplitResult, ConceptSplitRule, ConceptSplitSuggestion,
+    ConceptSplitTarget, MergeConceptsRequest, PromptKey, ServerEvent, SkosAuditLogEntry,
+    SplitConceptRequest, MAX_SPLIT_TARGETS,
+};
+use matric_inference::{concept_split_note_list, ConstrainedConfig};
+
+const DEFAULT_AUDIT_LIMIT: i64 = 50;
+const MAX_AUDIT_LIMIT: i64 = 500;
+
+/// Most of a concept's notes shown to the model when suggesting a split.
+const SPLIT_SUGGESTION_NOTE_LIMIT: i64 = 200;
+
+const DEFAULT_SPLIT_SUGGESTION_TARGETS: usize = 5;
+
+#[derive(Deserialize)]
+pub struct ConceptAuditQuery {
+    /// Maximum number of entries to return (default: 50, max: 500).
+    limit: Option<i64>,
+}
+
+impl fmt::Debug for ConceptAuditQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConceptAuditQuery")
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+/// Options for a suggested split.
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+pub struct SuggestConceptSplitRequest {
+    /// Most narrower concepts to propose (default 5, max 20)
+    #[serde(default)]
+    pub max_targets: Option<usize>,
+}
+
+/// Turn the model's groups into a split request that selects notes by ID.
+fn split_from_groups(
+    groups: Vec<matric_inference::ConceptSplitGroup>,
+    note_ids: &[Uuid],
+    max_targets: usize,
+) -> SplitConceptRequest {
+    SplitConceptRequest {
+        targets: groups
+            .into_iter()
+            .take(max_targets)
+            .map(|group| ConceptSplitTarget {
+                concept_id: None,
+                pref_label: Some(group.label),
+                rule: ConceptSplitRule {
+                    note_ids: group.notes.iter().map(|&i| note_ids[i]).collect(),
+                    ..Default::default()
+                },
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Merge concepts into a target.
+///
+/// Notes, labels, relations, mappings and collection memberships of the
+/// sources move to the target; each source is kept as an obsolete alias.
+///
+/// POST /api/v1/concepts/merge
+#[utoipa::path(post, path = "/api/v1/concepts/merge", tag = "SKOS",
+    request_body = MergeConceptsRequest,
+    responses(
+        (status = 200, description = "Concepts merged", body = ConceptMergeResult),
+        (status = 400, description = "Invalid sources, or a concept was already merged"),
+        (status = 404, description = "Concept not found")
+    ))]
+pub async fn merge_concepts(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Json(body): Json<MergeConceptsRequest>,
+) -> Result<Json<ConceptMergeResult>, ApiError> {
+    body.validate()?;
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let skos = matric_db::PgSkosRepository::new(state.db.pool.clone());
+    let result = ctx
+        .execute(move |tx| Box::pin(async move { skos.merge_concepts_tx(tx, &body).await }))
+        .await?;
+
+    for &concept_id in result.source_ids.iter().chain([&result.target_id]) {
+        state.event_bus.emit_with_context(
+            ServerEvent::ConceptUpdated { concept_id },
+            event_context_for(&archive_ctx),
+        );
+    }
+    if result.notes_retagged > 0 {
+        state.search_cache.invalidate_all().await;
+    }
+    emit_taxonomy_audit_event(taxonomy_audit_event(
+        "concept_merge",
+        AuditOutcome::Success,
+        "taxonomy_concept",
+        result.target_id,
+        vec![
+            ("source_count", serde_json::json!(result.source_ids.len())),
+            ("notes_retagged", serde_json::json!(result.notes_retagged)),
+        ],
+        Some(&archive_ctx.schema),
+    ))
+    .await;
+
+    Ok(Json(result))
+}
+
+/// Split a concept's notes over target concepts.
+///
+/// Each note moves to the first target whose rule it matches; the rest keep
+/// the concept.
+///
+/// POST /api/v1/concepts/{id}/split
+#[utoipa::path(post, path = "/api/v1/concepts/{id}/split", tag = "SKOS",
+    params(("id" = Uuid, Path, description = "Concept to split")),
+    request_body = SplitConceptRequest,
+    responses(
+        (status = 200, description = "Concept split", body = ConceptSplitResult),
+        (status = 400, description = "Invalid targets, or the concept was already merged"),
+        (status = 404, description = "Concept not found")
+    ))]
+pub async fn split_concept(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<SplitConceptRequest>,
+) -> Result<Json<ConceptSplitResult>, ApiError> {
+    body.validate()?;
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let skos = matric_db::PgSkosRepository::new(state.db.pool.clone());
+    let result = ctx
+        .execute(move |tx| Box::pin(async move { skos.split_concept_tx(tx, id, &body).await }))
+        .await?;
+
+    state.event_bus.emit_with_context(
+        ServerEvent::ConceptUpdated { concept_id: id },
+        event_context_for(&archive_ctx),
+    );
+    for target in &result.targets {
+        let event = if target.created {
+            ServerEvent::ConceptCreated {
+                concept_id: target.concept_id,
+                scheme_id: None,
+            }
+        } else {
+            ServerEvent::ConceptUpdated {
+                concept_id: target.concept_id,
+            }
+        };
+        state
+            .event_bus
+            .emit_with_context(event, event_context_for(&archive_ctx));
+    }
+    if result.notes_moved > 0 {
+        state.search_cache.invalidate_all().await;
+    }
+    emit_taxonomy_audit_event(taxonomy_audit_event(
+        "concept_split",
+        AuditOutcome::Success,
+        "taxonomy_concept",
+        id,
+        vec![
+            ("target_count", serde_json::json!(result.targets.len())),
+            ("notes_moved", serde_json::json!(result.notes_moved)),
+        ],
+        Some(&archive_ctx.schema),
+    ))
+    .await;
+
+    Ok(Json(result))
+}
+
+/// Suggest how to split a concept.
+///
+/// Shows the model the titles of up to 200 of the concept's most recently
+/// updated notes (the `concept_split` prompt) and returns its groups as a
+/// split request to review and submit. Nothing is changed.
+///
+/// POST /api/v1/concepts/{id}/split/suggest
+#[utoipa::path(post, path = "/api/v1/concepts/{id}/split/suggest", tag = "SKOS",
+    params(("id" = Uuid, Path, description = "Concept to split")),
+    request_body = SuggestConceptSplitRequest,
+    responses(
+        (status = 200, description = "Proposed split", body = ConceptSplitSuggestion),
+        (status = 400, description = "Concept has fewer than two notes"),
+        (status = 404, description = "Concept not found"),
+        (status = 503, description = "Generation backend unavailable")
+    ))]
+pub async fn suggest_concept_split(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    body: Option<Json<SuggestConceptSplitRequest>>,
+) -> Result<Json<ConceptSplitSuggestion>, ApiError> {
+    let max_targets = body
+        .and_then(|Json(body)| body.max_targets)
+        .unwrap_or(DEFAULT_SPLIT_SUGGESTION_TARGETS)
+        .clamp(2, MAX_SPLIT_TARGETS);
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let skos = matric_db::PgSkosRepository::new(state.db.pool.clone());
+    let (label, notes) = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                let concept = skos
+                    .get_concept_with_label_tx(tx, id)
+                    .await?
+                    .ok_or_else(|| matric_core::Error::NotFound("Concept not found".to_string()))?;
+                let notes = skos
+                    .concept_note_titles_tx(tx, id, SPLIT_SUGGESTION_NOTE_LIMIT)
+                    .await?;
+                Ok((concept.pref_label.unwrap_or_default(), notes))
+            })
+        })
+        .await?;
+    if notes.len() < 2 {
+        return Err(ApiError::BadRequest(
+            "Concept has fewer than two notes to split".to_string(),
+        ));
+    }
+
+    let backend = state.generation_backend().ok_or_else(|| {
+        ApiError::ServiceUnavailable("Generation backend is not available".into())
+    })?;
+    if !state.inference_available.load(Ordering::Relaxed) {
+        return Err(ApiError::ServiceUnavailable(
+            "Generation provider is not reachable".into(),
+        ));
+    }
+
+    let note_list = concept_split_note_list(notes.iter().map(|(_, title)| title.as_str()));
+    let max_groups = max_targets.to_string();
+    let prompt = render_job_prompt(
+        &state.db,
+        &archive_ctx.schema,
+        PromptKey::ConceptSplit,
+        &[
+            ("concept", label.as_str()),
+            ("notes", note_list.as_str()),
+            ("max_groups", max_groups.as_str()),
+        ],
+    )
+    .await;
+    let groups = matric_inference::suggest_concept_split(
+        backend.as_ref(),
+        &prompt,
+        notes.len(),
+        &ConstrainedConfig::default(),
+    )
+    .await?;
+
+    let note_ids: Vec<Uuid> = notes.iter().map(|(id, _)| *id).collect();
+    Ok(Json(ConceptSplitSuggestion {
+        source_id: id,
+        model: backend.model_name().to_string(),
+        notes_considered: note_ids.len(),
+        split: split_from_groups(groups, &note_ids, max_targets),
+    }))
+}
+
+/// Governance audit log of a concept, newest first.
+///
+/// GET /api/v1/concepts/{id}/audit
+#[utoipa::path(get, path = "/api/v1/concepts/{id}/audit", tag = "SKOS",
+    params(
+        ("id" = Uuid, Path, description = "Concept ID"),
+        ("limit" = Option<i64>, Query, description = "Maximum entries to return (default 50, max 500)")
+    ),
+    responses((status = 200, description = "Success", body = Vec<SkosAuditLogEntry>)))]
+pub async fn get_concept_audit_log(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ConceptAuditQuery>,
+) -> Result<Json<Vec<SkosAuditLogEntry>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_AUDIT_LIMIT)
+        .clamp(1, MAX_AUDIT_LIMIT);
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let skos = matric_db::PgSkosRepository::new(state.db.pool.clone());
+    let entries = ctx
+        .query(move |tx| {
+            Box::pin(async move { skos.get_concept_audit_log_tx(tx, id, limit).await })
+        })
+        .await?;
+    Ok(Json(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_from_groups_maps_positions_to_note_ids() {
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let groups = vec![
+            matric_inference::ConceptSplitGroup {
+                label: "Async Runtimes".to_string(),
+                notes: vec![2, 0],
+            },
+            matric_inference::ConceptSplitGroup {
+                label: "Error Handling".to_string(),
+                notes: vec![1],
+            },
+        ];
+
+        let split = split_from_groups(groups, &ids, 1);
+
+        assert_eq!(split.targets.len(), 1);
+        assert_eq!(
+            split.targets[0].pref_label.as_deref(),
+            Some("Async Runtimes")
+        );
+        assert_eq!(split.targets[0].rule.note_ids, vec![ids[2], ids[0]]);
+        assert!(split.validate().is_ok());
+    }
+}
@@ -0,0 +1,101 @@
+//! Taxonomy health HTTP handlers.
+//!
+//! The `taxonomy_health` job checks the archive's concepts for anti-patterns
+//! and stores a report with a suggested fix for each issue:
+//! - `GET /api/v1/concepts/health` — the latest report
+//! - `POST /api/v1/concepts/health/refresh` — queue a new check
+//!
+//! Flagged concepts can also be found with `GET /api/v1/concepts?has_antipattern=...`.
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::{JobRepository, JobType, ServerEvent, TaxonomyHealthReport, TaxonomyIssueKind};
+
+/// Filters for the health report's issue list.
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct TaxonomyHealthQuery {
+    /// Only issues of this kind
+    pub kind: Option<TaxonomyIssueKind>,
+    /// Maximum issues returned (default: all)
+    pub limit: Option<usize>,
+}
+
+/// The archive's latest taxonomy health report.
+///
+/// Counts always cover the whole report; `kind` and `limit` only narrow the
+/// issue list.
+///
+/// GET /api/v1/concepts/health
+#[utoipa::path(get, path = "/api/v1/concepts/health", tag = "SKOS",
+    params(TaxonomyHealthQuery),
+    responses(
+        (status = 200, description = "Success", body = TaxonomyHealthReport),
+        (status = 404, description = "The health check has not run in this archive")
+    ))]
+pub async fn get_taxonomy_health(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Query(query): Query<TaxonomyHealthQuery>,
+) -> Result<Json<TaxonomyHealthReport>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let skos = matric_db::PgSkosRepository::new(state.db.pool.clone());
+    let mut report = ctx
+        .query(move |tx| Box::pin(async move { skos.latest_taxonomy_health_report_tx(tx).await }))
+        .await?
+        .ok_or_else(|| {
+            ApiError::NotFound(
+                "No taxonomy health report yet; POST /api/v1/concepts/health/refresh to run the check"
+                    .to_string(),
+            )
+        })?;
+
+    if let Some(kind) = query.kind {
+        report.issues.retain(|issue| issue.kind == kind);
+    }
+    if let Some(limit) = query.limit {
+        report.issues.truncate(limit);
+    }
+    Ok(Json(report))
+}
+
+/// Queue a taxonomy health check of the archive.
+///
+/// The job replaces the stored report and refreshes every concept's
+/// anti-pattern flags.
+///
+/// POST /api/v1/concepts/health/refresh
+#[utoipa::path(post, path = "/api/v1/concepts/health/refresh", tag = "SKOS",
+    responses((status = 202, description = "Health check queued")))]
+pub async fn refresh_taxonomy_health(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let job_id = state
+        .db
+        .jobs
+        .queue(
+            None,
+            JobType::TaxonomyHealth,
+            JobType::TaxonomyHealth.default_priority(),
+            Some(json!({ "schema": archive_ctx.schema })),
+            JobType::TaxonomyHealth.default_cost_tier(),
+        )
+        .await?;
+    state.event_bus.emit(ServerEvent::JobQueued {
+        job_id,
+        job_type: format!("{:?}", JobType::TaxonomyHealth),
+        note_id: None,
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({ "status": "queued", "job_id": job_id })),
+    ))
+}
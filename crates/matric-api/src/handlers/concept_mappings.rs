@@ -0,0 +1,225 @@
+//! SKOS mapping relation HTTP handlers.
+//!
+//! Mappings link a concept to concepts in external vocabularies:
+//! - `GET /api/v1/concepts/{id}/mappings` — the concept's mappings
+//! - `POST /api/v1/concepts/{id}/mappings` — map the concept to an external URI
+//! - `PATCH /api/v1/concepts/{id}/mappings/{mapping_id}` — change or validate a mapping
+//! - `DELETE /api/v1/concepts/{id}/mappings/{mapping_id}` — remove a mapping
+//!
+//! Mappings are written into the Turtle export, and exact matches reconcile
+//! concepts on knowledge shard import.
+
+use std::fmt;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    emit_taxonomy_audit_event, event_context_for, taxonomy_audit_event, ApiError, AppState,
+    ArchiveContext,
+};
+use matric_core::{
+    AuditOutcome, CreateMappingRelationRequest, ServerEvent, SkosMappingRelation,
+    SkosMappingRelationEdge, UpdateMappingRelationRequest,
+};
+
+/// A mapping to add to a concept.
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct AddConceptMappingRequest {
+    /// Absolute URI of the external concept
+    pub target_uri: String,
+    pub relation_type: SkosMappingRelation,
+    /// URI of the external concept scheme
+    #[serde(default)]
+    pub target_scheme_uri: Option<String>,
+    /// Preferred label of the external concept
+    #[serde(default)]
+    pub target_label: Option<String>,
+    /// Confidence in the mapping, 0 to 1
+    #[serde(default)]
+    pub confidence: Option<f32>,
+}
+
+impl fmt::Debug for AddConceptMappingRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AddConceptMappingRequest")
+            .field("target_uri_len", &self.target_uri.len())
+            .field("relation_type", &self.relation_type)
+            .field(
+                "target_scheme_uri_len",
+                &self.target_scheme_uri.as_ref().map(String::len),
+            )
+            .field(
+                "target_label_len",
+                &self.target_label.as_ref().map(String::len),
+            )
+            .field("confidence", &self.confidence)
+            .finish()
+    }
+}
+
+fn emit_mapping_changed(state: &AppState, archive_ctx: &ArchiveContext, concept_id: Uuid) {
+    state.event_bus.emit_with_context(
+        ServerEvent::ConceptUpdated { concept_id },
+        event_context_for(archive_ctx),
+    );
+}
+
+/// List a concept's mapping relations.
+///
+/// GET /api/v1/concepts/{id}/mappings
+#[utoipa::path(get, path = "/api/v1/concepts/{id}/mappings", tag = "SKOS",
+    params(("id" = Uuid, Path, description = "Concept ID")),
+    responses((status = 200, description = "Success", body = Vec<SkosMappingRelationEdge>)))]
+pub async fn list_concept_mappings(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<SkosMappingRelationEdge>>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let skos = matric_db::PgSkosRepository::new(state.db.pool.clone());
+    let mappings = ctx
+        .query(move |tx| Box::pin(async move { skos.get_mapping_relations_tx(tx, id).await }))
+        .await?;
+    Ok(Json(mappings))
+}
+
+/// Map a concept to a concept in an external vocabulary.
+///
+/// A concept maps to the same URI once per relation type.
+///
+/// POST /api/v1/concepts/{id}/mappings
+#[utoipa::path(post, path = "/api/v1/concepts/{id}/mappings", tag = "SKOS",
+    params(("id" = Uuid, Path, description = "Concept ID")),
+    request_body = AddConceptMappingRequest,
+    responses(
+        (status = 201, description = "Mapping added", body = SkosMappingRelationEdge),
+        (status = 400, description = "Invalid URI, label or confidence"),
+        (status = 404, description = "Concept not found"),
+        (status = 409, description = "The concept already has this mapping")
+    ))]
+pub async fn add_concept_mapping(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<AddConceptMappingRequest>,
+) -> Result<(StatusCode, Json<SkosMappingRelationEdge>), ApiError> {
+    let req = CreateMappingRelationRequest {
+        concept_id: id,
+        target_uri: body.target_uri.trim().to_string(),
+        target_scheme_uri: body.target_scheme_uri.map(|uri| uri.trim().to_string()),
+        target_label: body.target_label,
+        relation_type: body.relation_type,
+        confidence: body.confidence,
+    };
+    req.validate()?;
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let skos = matric_db::PgSkosRepository::new(state.db.pool.clone());
+    let mapping = ctx
+        .execute(move |tx| {
+            Box::pin(async move { skos.create_mapping_relation_tx(tx, &req, None).await })
+        })
+        .await?;
+
+    emit_mapping_changed(&state, &archive_ctx, id);
+    emit_taxonomy_audit_event(taxonomy_audit_event(
+        "concept_mapping_add",
+        AuditOutcome::Success,
+        "taxonomy_concept",
+        id,
+        vec![(
+            "relation_type",
+            serde_json::json!(mapping.relation_type.to_string()),
+        )],
+        Some(&archive_ctx.schema),
+    ))
+    .await;
+
+    Ok((StatusCode::CREATED, Json(mapping)))
+}
+
+/// Change or validate a concept's mapping relation.
+///
+/// PATCH /api/v1/concepts/{id}/mappings/{mapping_id}
+#[utoipa::path(patch, path = "/api/v1/concepts/{id}/mappings/{mapping_id}", tag = "SKOS",
+    params(
+        ("id" = Uuid, Path, description = "Concept ID"),
+        ("mapping_id" = Uuid, Path, description = "Mapping ID")
+    ),
+    request_body = UpdateMappingRelationRequest,
+    responses(
+        (status = 200, description = "Mapping updated", body = SkosMappingRelationEdge),
+        (status = 400, description = "No fields given, or a field is invalid"),
+        (status = 404, description = "Mapping not found"),
+        (status = 409, description = "The concept already has the mapping with that relation type")
+    ))]
+pub async fn update_concept_mapping(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path((id, mapping_id)): Path<(Uuid, Uuid)>,
+    Json(body): Json<UpdateMappingRelationRequest>,
+) -> Result<Json<SkosMappingRelationEdge>, ApiError> {
+    body.validate()?;
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let skos = matric_db::PgSkosRepository::new(state.db.pool.clone());
+    let mapping = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                let actor = body.validated_by.clone();
+                skos.update_mapping_relation_tx(tx, id, mapping_id, &body, actor.as_deref())
+                    .await
+            })
+        })
+        .await?;
+
+    emit_mapping_changed(&state, &archive_ctx, id);
+    Ok(Json(mapping))
+}
+
+/// Remove a concept's mapping relation.
+///
+/// DELETE /api/v1/concepts/{id}/mappings/{mapping_id}
+#[utoipa::path(delete, path = "/api/v1/concepts/{id}/mappings/{mapping_id}", tag = "SKOS",
+    params(
+        ("id" = Uuid, Path, description = "Concept ID"),
+        ("mapping_id" = Uuid, Path, description = "Mapping ID")
+    ),
+    responses(
+        (status = 204, description = "Mapping removed"),
+        (status = 404, description = "Mapping not found")
+    ))]
+pub async fn remove_concept_mapping(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path((id, mapping_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let skos = matric_db::PgSkosRepository::new(state.db.pool.clone());
+    ctx.execute(move |tx| {
+        Box::pin(async move {
+            skos.delete_mapping_relation_tx(tx, id, mapping_id, None)
+                .await
+        })
+    })
+    .await?;
+
+    emit_mapping_changed(&state, &archive_ctx, id);
+    emit_taxonomy_audit_event(taxonomy_audit_event(
+        "concept_mapping_remove",
+        AuditOutcome::Success,
+        "taxonomy_concept",
+        id,
+        Vec::new(),
+        Some(&archive_ctx.schema),
+    ))
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
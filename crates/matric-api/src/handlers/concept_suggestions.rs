@@ -0,0 +1,180 @@
+//! Concept suggestion review queue HTTP handlers.
+//!
+//! With `CONCEPT_REVIEW_MODE` on, concept tagging holds low-confidence
+//! suggestions for review:
+//! - `GET /api/v1/concepts/suggestions` — list suggestions with confidence and evidence
+//! - `POST /api/v1/concepts/suggestions/{id}/accept` — tag the note with the concept
+//! - `POST /api/v1/concepts/suggestions/{id}/reject` — dismiss the suggestion
+//!
+//! Each review moves the concept's review threshold, so concepts whose
+//! suggestions keep being accepted are applied directly more often.
+
+use std::fmt;
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::{ConceptSuggestion, ConceptSuggestionReview, ConceptSuggestionStatus};
+
+const DEFAULT_SUGGESTION_LIMIT: i64 = 50;
+const MAX_SUGGESTION_LIMIT: i64 = 500;
+
+#[derive(Deserialize)]
+pub struct ConceptSuggestionQuery {
+    /// Review state to list (default: pending).
+    status: Option<ConceptSuggestionStatus>,
+    /// Only suggestions of this concept.
+    concept_id: Option<Uuid>,
+    /// Only suggestions for this note.
+    note_id: Option<Uuid>,
+    /// Maximum number of suggestions to return (default: 50, max: 500).
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+impl fmt::Debug for ConceptSuggestionQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConceptSuggestionQuery")
+            .field("status", &self.status)
+            .field("concept_id_set", &self.concept_id.is_some())
+            .field("note_id_set", &self.note_id.is_some())
+            .field("limit", &self.limit)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+/// Who reviewed a suggestion.
+#[derive(Default, Deserialize, utoipa::ToSchema)]
+pub struct ReviewConceptSuggestionRequest {
+    #[serde(default)]
+    pub reviewed_by: Option<String>,
+}
+
+impl fmt::Debug for ReviewConceptSuggestionRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReviewConceptSuggestionRequest")
+            .field("reviewed_by_set", &self.reviewed_by.is_some())
+            .finish()
+    }
+}
+
+/// List concept suggestions.
+///
+/// Pending suggestions by default, most confident first, each with the
+/// concept's threshold at the time and a snippet of note text mentioning it.
+///
+/// GET /api/v1/concepts/suggestions
+#[utoipa::path(get, path = "/api/v1/concepts/suggestions", tag = "SKOS",
+    params(
+        ("status" = Option<String>, Query, description = "pending (default), accepted or rejected"),
+        ("concept_id" = Option<Uuid>, Query, description = "Only suggestions of this concept"),
+        ("note_id" = Option<Uuid>, Query, description = "Only suggestions for this note"),
+        ("limit" = Option<i64>, Query, description = "Maximum results (default 50, max 500)"),
+        ("offset" = Option<i64>, Query, description = "Pagination offset")
+    ),
+    responses((status = 200, description = "Suggestions", body = Vec<ConceptSuggestion>)))]
+pub async fn list_concept_suggestions(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Query(query): Query<ConceptSuggestionQuery>,
+) -> Result<Json<Vec<ConceptSuggestion>>, ApiError> {
+    let status = query.status.unwrap_or(ConceptSuggestionStatus::Pending);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SUGGESTION_LIMIT)
+        .clamp(1, MAX_SUGGESTION_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let (concept_id, note_id) = (query.concept_id, query.note_id);
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let suggestions = state.db.concept_suggestions.clone();
+    let list = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                suggestions
+                    .list_tx(tx, status, concept_id, note_id, limit, offset)
+                    .await
+            })
+        })
+        .await?;
+    Ok(Json(list))
+}
+
+async fn review_concept_suggestion(
+    state: AppState,
+    archive_ctx: ArchiveContext,
+    id: Uuid,
+    accepted: bool,
+    body: Option<Json<ReviewConceptSuggestionRequest>>,
+) -> Result<Json<ConceptSuggestionReview>, ApiError> {
+    let reviewed_by = body.and_then(|Json(body)| body.reviewed_by);
+    let default_threshold = matric_core::defaults::concept_review_threshold();
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let suggestions = state.db.concept_suggestions.clone();
+    let review = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                suggestions
+                    .review_tx(tx, id, accepted, reviewed_by.as_deref(), default_threshold)
+                    .await
+            })
+        })
+        .await?;
+    if accepted {
+        state.search_cache.invalidate_all().await;
+    }
+    Ok(Json(review))
+}
+
+/// Accept a concept suggestion.
+///
+/// Tags the note with the concept and lowers the concept's review threshold
+/// one step.
+///
+/// POST /api/v1/concepts/suggestions/{id}/accept
+#[utoipa::path(post, path = "/api/v1/concepts/suggestions/{id}/accept", tag = "SKOS",
+    params(("id" = Uuid, Path, description = "Suggestion ID")),
+    request_body = ReviewConceptSuggestionRequest,
+    responses(
+        (status = 200, description = "Suggestion accepted", body = ConceptSuggestionReview),
+        (status = 400, description = "Suggestion already reviewed"),
+        (status = 404, description = "Suggestion not found")
+    ))]
+pub async fn accept_concept_suggestion(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    body: Option<Json<ReviewConceptSuggestionRequest>>,
+) -> Result<Json<ConceptSuggestionReview>, ApiError> {
+    review_concept_suggestion(state, archive_ctx, id, true, body).await
+}
+
+/// Reject a concept suggestion.
+///
+/// The concept is not suggested for the note again, and the concept's
+/// review threshold rises one step.
+///
+/// POST /api/v1/concepts/suggestions/{id}/reject
+#[utoipa::path(post, path = "/api/v1/concepts/suggestions/{id}/reject", tag = "SKOS",
+    params(("id" = Uuid, Path, description = "Suggestion ID")),
+    request_body = ReviewConceptSuggestionRequest,
+    responses(
+        (status = 200, description = "Suggestion rejected", body = ConceptSuggestionReview),
+        (status = 400, description = "Suggestion already reviewed"),
+        (status = 404, description = "Suggestion not found")
+    ))]
+pub async fn reject_concept_suggestion(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    body: Option<Json<ReviewConceptSuggestionRequest>>,
+) -> Result<Json<ConceptSuggestionReview>, ApiError> {
+    review_concept_suggestion(state, archive_ctx, id, false, body).await
+}
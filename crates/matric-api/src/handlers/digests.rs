@@ -0,0 +1,233 @@
+//! Scheduled digest HTTP handlers.
+//!
+//! Digests belong to the memory archive selected by the request. They are
+//! generated by the `digest_generation` job; the periodic scheduler in
+//! `main.rs` queues one whenever a digest comes due.
+//! - `GET /api/v1/digests` — list digests
+//! - `POST /api/v1/digests` — create a digest
+//! - `GET /api/v1/digests/{id}` — get a digest
+//! - `PATCH /api/v1/digests/{id}` — update cadence, collections, or delivery
+//! - `DELETE /api/v1/digests/{id}` — delete a digest; digest notes are kept
+//! - `POST /api/v1/digests/{id}/run` — queue a digest of the period ending now
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::{
+    CreateDigestRequest, CronSchedule, DigestConfig, JobRepository, JobType, ServerEvent,
+    UpdateDigestRequest,
+};
+use matric_db::Database;
+
+/// Next run of `schedule`, or none for a disabled digest.
+fn next_run_at(schedule: &CronSchedule, enabled: bool) -> Option<DateTime<Utc>> {
+    enabled.then(|| schedule.next_after(Utc::now())).flatten()
+}
+
+fn digest_not_found() -> ApiError {
+    ApiError::NotFound("Digest not found".to_string())
+}
+
+/// Queue a `digest_generation` job covering the period that ends at `period_end`.
+pub(crate) async fn queue_digest_run(
+    db: &Database,
+    event_bus: &matric_core::EventBus,
+    schema: &str,
+    digest_id: Uuid,
+    period_end: DateTime<Utc>,
+) -> matric_core::Result<Uuid> {
+    let job_id = db
+        .jobs
+        .queue(
+            None,
+            JobType::DigestGeneration,
+            JobType::DigestGeneration.default_priority(),
+            Some(json!({
+                "digest_id": digest_id,
+                "schema": schema,
+                "period_end": period_end,
+            })),
+            JobType::DigestGeneration.default_cost_tier(),
+        )
+        .await?;
+    event_bus.emit(ServerEvent::JobQueued {
+        job_id,
+        job_type: format!("{:?}", JobType::DigestGeneration),
+        note_id: None,
+    });
+    Ok(job_id)
+}
+
+/// List the archive's digests.
+#[utoipa::path(get, path = "/api/v1/digests", tag = "Digests",
+    responses((status = 200, description = "Success", body = Vec<DigestConfig>)))]
+pub async fn list_digests(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+) -> Result<Json<Vec<DigestConfig>>, ApiError> {
+    Ok(Json(state.db.digests.list(&archive_ctx.schema).await?))
+}
+
+/// Create a digest.
+#[utoipa::path(post, path = "/api/v1/digests", tag = "Digests",
+    request_body = CreateDigestRequest,
+    responses(
+        (status = 201, description = "Created", body = DigestConfig),
+        (status = 400, description = "Invalid name, hour, collections, or delivery")
+    ))]
+pub async fn create_digest(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Json(req): Json<CreateDigestRequest>,
+) -> Result<(StatusCode, Json<DigestConfig>), ApiError> {
+    let schedule = req.validate()?;
+    let digest = state
+        .db
+        .digests
+        .create(
+            &archive_ctx.schema,
+            &req,
+            next_run_at(&schedule, req.enabled),
+        )
+        .await?;
+    Ok((StatusCode::CREATED, Json(digest)))
+}
+
+/// Get a digest.
+#[utoipa::path(get, path = "/api/v1/digests/{id}", tag = "Digests",
+    params(("id" = Uuid, Path, description = "Digest ID")),
+    responses(
+        (status = 200, description = "Success", body = DigestConfig),
+        (status = 404, description = "Digest not found")
+    ))]
+pub async fn get_digest(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DigestConfig>, ApiError> {
+    state
+        .db
+        .digests
+        .get(&archive_ctx.schema, id)
+        .await?
+        .map(Json)
+        .ok_or_else(digest_not_found)
+}
+
+/// Update a digest.
+///
+/// The next run is recomputed when the cadence or hour changes or the digest
+/// is re-enabled; disabling a digest clears it.
+#[utoipa::path(patch, path = "/api/v1/digests/{id}", tag = "Digests",
+    params(("id" = Uuid, Path, description = "Digest ID")),
+    request_body = UpdateDigestRequest,
+    responses(
+        (status = 200, description = "Updated", body = DigestConfig),
+        (status = 400, description = "Invalid name, hour, collections, or delivery"),
+        (status = 404, description = "Digest not found")
+    ))]
+pub async fn update_digest(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateDigestRequest>,
+) -> Result<Json<DigestConfig>, ApiError> {
+    let mut digest = state
+        .db
+        .digests
+        .get(&archive_ctx.schema, id)
+        .await?
+        .ok_or_else(digest_not_found)?;
+    let changed_schedule = req.apply(&mut digest)?;
+
+    if !digest.enabled {
+        digest.next_run_at_utc = None;
+    } else if changed_schedule.is_some() || digest.next_run_at_utc.is_none() {
+        let schedule = match changed_schedule {
+            Some(schedule) => schedule,
+            None => digest.cadence.schedule(digest.hour_utc)?,
+        };
+        digest.next_run_at_utc = next_run_at(&schedule, true);
+    }
+
+    state
+        .db
+        .digests
+        .update(&archive_ctx.schema, &digest)
+        .await?
+        .map(Json)
+        .ok_or_else(digest_not_found)
+}
+
+/// Delete a digest. Digest notes already written are kept.
+#[utoipa::path(delete, path = "/api/v1/digests/{id}", tag = "Digests",
+    params(("id" = Uuid, Path, description = "Digest ID")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, description = "Digest not found")
+    ))]
+pub async fn delete_digest(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    if state.db.digests.delete(&archive_ctx.schema, id).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(digest_not_found())
+    }
+}
+
+/// Queue a digest of the period ending now, outside the schedule.
+///
+/// Disabled digests can still be run by hand.
+#[utoipa::path(post, path = "/api/v1/digests/{id}/run", tag = "Digests",
+    params(("id" = Uuid, Path, description = "Digest ID")),
+    responses(
+        (status = 202, description = "Run queued"),
+        (status = 404, description = "Digest not found")
+    ))]
+pub async fn run_digest(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    if state
+        .db
+        .digests
+        .get(&archive_ctx.schema, id)
+        .await?
+        .is_none()
+    {
+        return Err(digest_not_found());
+    }
+    let job_id = queue_digest_run(
+        &state.db,
+        &state.event_bus,
+        &archive_ctx.schema,
+        id,
+        Utc::now(),
+    )
+    .await?;
+    Ok((StatusCode::ACCEPTED, Json(json!({ "job_id": job_id }))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matric_core::DigestCadence;
+
+    #[test]
+    fn disabled_digests_have_no_next_run() {
+        let schedule = DigestCadence::Weekly.schedule(7).unwrap();
+        assert!(next_run_at(&schedule, false).is_none());
+        assert!(next_run_at(&schedule, true).is_some_and(|next| next > Utc::now()));
+    }
+}
@@ -0,0 +1,146 @@
+//! Entity registry HTTP handlers.
+//!
+//! Read access to the entities found by the entity extraction job:
+//! - `GET /api/v1/entities` — list entities, most mentioned first
+//! - `GET /api/v1/entities/{id}` — an entity and the notes that mention it
+//!
+//! A user's token only sees entities, notes and counts of the notes the
+//! user can read.
+
+use std::fmt;
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::{EntityListFilter, EntityType, NamedEntityDetail, NamedEntityList};
+
+const DEFAULT_ENTITY_LIMIT: i64 = 50;
+const MAX_ENTITY_LIMIT: i64 = 500;
+
+#[derive(Deserialize)]
+pub struct ListEntitiesQuery {
+    /// Only entities of this type.
+    #[serde(rename = "type")]
+    entity_type: Option<String>,
+    /// Case-insensitive substring of the entity name.
+    q: Option<String>,
+    /// Maximum number of entities to return (default: 50, max: 500).
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+impl fmt::Debug for ListEntitiesQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListEntitiesQuery")
+            .field("entity_type", &self.entity_type)
+            .field("q_len", &self.q.as_ref().map(String::len))
+            .field("limit", &self.limit)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+fn entity_not_found_error() -> matric_core::Error {
+    matric_core::Error::NotFound("Entity not found; entity_id_present=true".to_string())
+}
+
+/// Parse an extracted entity type filter.
+fn parse_entity_type(value: &str) -> Result<EntityType, ApiError> {
+    EntityType::EXTRACTED
+        .into_iter()
+        .find(|t| t.to_string() == value)
+        .ok_or_else(|| {
+            ApiError::BadRequest(
+                "type must be one of person, organization, location, date".to_string(),
+            )
+        })
+}
+
+/// List entities mentioned by notes in the archive.
+///
+/// GET /api/v1/entities
+#[utoipa::path(get, path = "/api/v1/entities", tag = "Entities",
+    params(
+        ("type" = Option<String>, Query, description = "Entity type: person, organization, location, or date"),
+        ("q" = Option<String>, Query, description = "Case-insensitive substring of the entity name"),
+        ("limit" = Option<i64>, Query, description = "Maximum entities to return (default 50, max 500)"),
+        ("offset" = Option<i64>, Query, description = "Entities to skip")
+    ),
+    responses(
+        (status = 200, description = "Success", body = NamedEntityList),
+        (status = 400, description = "Unknown entity type")
+    ))]
+pub async fn list_entities(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Query(query): Query<ListEntitiesQuery>,
+) -> Result<Json<NamedEntityList>, ApiError> {
+    let filter = EntityListFilter {
+        entity_type: query
+            .entity_type
+            .as_deref()
+            .map(parse_entity_type)
+            .transpose()?,
+        q: query.q,
+        limit: query
+            .limit
+            .unwrap_or(DEFAULT_ENTITY_LIMIT)
+            .clamp(1, MAX_ENTITY_LIMIT),
+        offset: query.offset.unwrap_or(0).max(0),
+    };
+
+    let security = caller.security_filter();
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let entities = state.db.entities.clone();
+    let list = ctx
+        .query(move |tx| {
+            Box::pin(async move { entities.list_tx(tx, &filter, security.as_ref()).await })
+        })
+        .await?;
+    Ok(Json(list))
+}
+
+/// Get an entity and the notes that mention it, most recently updated first.
+///
+/// GET /api/v1/entities/{id}
+#[utoipa::path(get, path = "/api/v1/entities/{id}", tag = "Entities",
+    params(("id" = Uuid, Path, description = "Entity ID")),
+    responses(
+        (status = 200, description = "Success", body = NamedEntityDetail),
+        (status = 404, description = "Entity not found")
+    ))]
+pub async fn get_entity(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+) -> Result<Json<NamedEntityDetail>, ApiError> {
+    let security = caller.security_filter();
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let entities = state.db.entities.clone();
+    let detail = ctx
+        .query(move |tx| Box::pin(async move { entities.get_tx(tx, id, security.as_ref()).await }))
+        .await?
+        .ok_or_else(entity_not_found_error)?;
+    Ok(Json(detail))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_entity_type_accepts_only_extracted_types() {
+        assert_eq!(parse_entity_type("person").unwrap(), EntityType::Person);
+        assert_eq!(parse_entity_type("date").unwrap(), EntityType::Date);
+        assert!(parse_entity_type("product").is_err());
+        assert!(parse_entity_type("Person").is_err());
+    }
+}
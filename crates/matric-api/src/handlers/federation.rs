@@ -0,0 +1,600 @@
+//! Cross-instance archive sync HTTP handlers.
+//!
+//! Peers are run by the `federation_sync` job; the periodic scheduler in
+//! `main.rs` queues one whenever a peer's schedule comes due.
+//! - `GET /api/v1/sync/peers` — list peers
+//! - `POST /api/v1/sync/peers` — register a peer
+//! - `GET /api/v1/sync/peers/{id}` — get a peer
+//! - `PATCH /api/v1/sync/peers/{id}` — update a peer
+//! - `DELETE /api/v1/sync/peers/{id}` — delete a peer
+//! - `POST /api/v1/sync/peers/{id}/run` — queue a sync now
+//! - `GET /api/v1/sync/changes` — change feed of the selected memory
+//! - `POST /api/v1/sync/changes` — apply changes pushed by a peer
+//!
+//! The change endpoints are what a peer's sync job calls on this instance,
+//! so both are scoped to the memory chosen by `X-Fortemi-Memory`.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::middleware::archive_routing::{ArchiveContext, MEMORY_HEADER};
+use crate::{ApiError, AppState};
+use matric_core::federation::{DEFAULT_SYNC_PAGE_SIZE, MAX_SYNC_PAGE_SIZE};
+use matric_core::{
+    ApplySyncChangesRequest, ArchiveRepository, ConflictStrategy, CreateSyncPeerRequest,
+    CronSchedule, EventBus, JobRepository, JobType, RevisionMode, ServerEvent, SyncApplyResult,
+    SyncChange, SyncChangesPage, SyncCursor, SyncOutcome, SyncPeer, UpdateSyncPeerRequest,
+};
+use matric_db::{Database, SyncCursorKind};
+use matric_jobs::SyncRunner;
+
+/// Pages fetched or sent per direction in one run; the rest waits for the
+/// next run, which resumes from the saved cursor.
+const MAX_SYNC_PAGES_PER_RUN: usize = 50;
+
+/// Timeout for one request to a peer.
+const SYNC_REQUEST_TIMEOUT_SECS: u64 = 120;
+
+/// Runs peer syncs over the HTTP API of the remote instance.
+pub struct ApiSyncRunner {
+    db: Database,
+    event_bus: std::sync::Arc<EventBus>,
+    client: reqwest::Client,
+}
+
+impl ApiSyncRunner {
+    pub fn new(db: Database, event_bus: std::sync::Arc<EventBus>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(SYNC_REQUEST_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+        Self {
+            db,
+            event_bus,
+            client,
+        }
+    }
+
+    fn request(
+        &self,
+        method: reqwest::Method,
+        peer: &SyncPeer,
+        token: Option<&str>,
+    ) -> reqwest::RequestBuilder {
+        let mut request = self
+            .client
+            .request(method, format!("{}/api/v1/sync/changes", peer.base_url));
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(memory) = peer.remote_memory.as_deref() {
+            request = request.header(MEMORY_HEADER, memory);
+        }
+        request
+    }
+
+    async fn pull(
+        &self,
+        peer: &SyncPeer,
+        schema: &str,
+        token: Option<&str>,
+    ) -> Result<SyncApplyResult, String> {
+        let mut total = SyncApplyResult::default();
+        let mut cursor = peer.pull_cursor;
+        for _ in 0..MAX_SYNC_PAGES_PER_RUN {
+            let mut query = vec![("limit", DEFAULT_SYNC_PAGE_SIZE.to_string())];
+            if let Some(cursor) = cursor {
+                query.push(("since", cursor.changed_at.to_rfc3339()));
+                query.push(("after", cursor.note_id.to_string()));
+            }
+            let response = self
+                .request(reqwest::Method::GET, peer, token)
+                .query(&query)
+                .send()
+                .await
+                .map_err(|e| peer_request_error("Fetch changes", &e))?;
+            let page: SyncChangesPage = peer_response(response, "Fetch changes")
+                .await?
+                .json()
+                .await
+                .map_err(|_| "Fetch changes failed: invalid response body".to_string())?;
+
+            let Some(next) = page.changes.last().map(SyncChange::cursor) else {
+                break;
+            };
+            total += apply_sync_changes(
+                &self.db,
+                &self.event_bus,
+                schema,
+                &page.changes,
+                peer.strategy(),
+            )
+            .await
+            .map_err(|e| sync_run_error(&e))?;
+            self.db
+                .sync_peers
+                .save_cursor(peer.id, SyncCursorKind::Pull, next)
+                .await
+                .map_err(|_| "Failed to save pull cursor".to_string())?;
+            cursor = Some(next);
+            if !page.has_more {
+                break;
+            }
+        }
+        Ok(total)
+    }
+
+    async fn push(
+        &self,
+        peer: &SyncPeer,
+        schema: &str,
+        token: Option<&str>,
+    ) -> Result<SyncApplyResult, String> {
+        let mut total = SyncApplyResult::default();
+        let mut cursor = peer.push_cursor;
+        for _ in 0..MAX_SYNC_PAGES_PER_RUN {
+            let changes = local_changes(&self.db, schema, cursor, DEFAULT_SYNC_PAGE_SIZE)
+                .await
+                .map_err(|e| sync_run_error(&e))?;
+            let Some(next) = changes.last().map(SyncChange::cursor) else {
+                break;
+            };
+            let has_more = changes.len() as i64 == DEFAULT_SYNC_PAGE_SIZE;
+            let body = ApplySyncChangesRequest {
+                changes,
+                strategy: peer.strategy(),
+            };
+            let response = self
+                .request(reqwest::Method::POST, peer, token)
+                .json(&body)
+                .send()
+                .await
+                .map_err(|e| peer_request_error("Push changes", &e))?;
+            let applied: SyncApplyResult = peer_response(response, "Push changes")
+                .await?
+                .json()
+                .await
+                .map_err(|_| "Push changes failed: invalid response body".to_string())?;
+
+            total += applied;
+            self.db
+                .sync_peers
+                .save_cursor(peer.id, SyncCursorKind::Push, next)
+                .await
+                .map_err(|_| "Failed to save push cursor".to_string())?;
+            cursor = Some(next);
+            if !has_more {
+                break;
+            }
+        }
+        Ok(total)
+    }
+}
+
+#[async_trait]
+impl SyncRunner for ApiSyncRunner {
+    async fn sync(&self, peer: &SyncPeer) -> Result<SyncOutcome, String> {
+        let schema = local_schema(&self.db, peer.local_memory.as_deref())
+            .await
+            .map_err(|e| sync_run_error(&e))?;
+        let token = self
+            .db
+            .sync_peers
+            .api_token(peer.id)
+            .await
+            .map_err(|_| "Failed to load peer API token".to_string())?;
+
+        let mut outcome = SyncOutcome::default();
+        if peer.pulls() {
+            outcome.pulled = self.pull(peer, &schema, token.as_deref()).await?;
+        }
+        if peer.pushes() {
+            outcome.pushed = self.push(peer, &schema, token.as_deref()).await?;
+        }
+        Ok(outcome)
+    }
+}
+
+/// Schema of the local memory a peer replicates: the named memory, or the
+/// default memory when none is named.
+async fn local_schema(db: &Database, memory: Option<&str>) -> Result<String, ApiError> {
+    match memory {
+        Some(name) => db
+            .archives
+            .get_archive_by_name(name)
+            .await?
+            .map(|archive| archive.schema_name)
+            .ok_or_else(|| ApiError::NotFound("Local memory not found".to_string())),
+        None => Ok(db
+            .archives
+            .get_default_archive()
+            .await?
+            .map(|archive| archive.schema_name)
+            .unwrap_or_else(|| "public".to_string())),
+    }
+}
+
+async fn local_changes(
+    db: &Database,
+    schema: &str,
+    after: Option<SyncCursor>,
+    limit: i64,
+) -> Result<Vec<SyncChange>, ApiError> {
+    let ctx = db.for_schema(schema)?;
+    let mut tx = ctx.begin_tx().await?;
+    let changes = db
+        .sync_peers
+        .changes_since_tx(&mut tx, after, limit)
+        .await?;
+    tx.commit().await.map_err(matric_core::Error::Database)?;
+    Ok(changes)
+}
+
+/// Apply a batch of incoming changes to `schema` in one transaction, then
+/// requeue processing for every note that was written.
+async fn apply_sync_changes(
+    db: &Database,
+    event_bus: &EventBus,
+    schema: &str,
+    changes: &[SyncChange],
+    strategy: ConflictStrategy,
+) -> Result<SyncApplyResult, ApiError> {
+    let ctx = db.for_schema(schema)?;
+    let mut tx = ctx.begin_tx().await?;
+    let mut total = SyncApplyResult::default();
+    let mut written = Vec::new();
+    for change in changes {
+        let result = db
+            .sync_peers
+            .apply_change_tx(&mut tx, change, strategy)
+            .await?;
+        if result.applied > 0 {
+            written.push(change.note_id);
+        }
+        total += result;
+    }
+    tx.commit().await.map_err(matric_core::Error::Database)?;
+
+    for note_id in written {
+        crate::queue_nlp_pipeline(
+            db,
+            note_id,
+            RevisionMode::None,
+            event_bus,
+            Some(schema),
+            None,
+        )
+        .await;
+    }
+    Ok(total)
+}
+
+async fn peer_response(
+    response: reqwest::Response,
+    operation: &str,
+) -> Result<reqwest::Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        Ok(response)
+    } else {
+        Err(format!(
+            "{operation} failed: peer returned HTTP {}",
+            status.as_u16()
+        ))
+    }
+}
+
+/// Transport error recorded on the peer, without the request URL.
+fn peer_request_error(operation: &str, err: &reqwest::Error) -> String {
+    let kind = if err.is_timeout() {
+        "timed out"
+    } else if err.is_connect() {
+        "could not connect to peer"
+    } else {
+        "request failed"
+    };
+    format!("{operation} failed: {kind}")
+}
+
+/// Run error recorded on the peer, without raw diagnostics.
+fn sync_run_error(err: &ApiError) -> String {
+    match err {
+        ApiError::OperationFailed { operation, detail } => format!("{operation} failed: {detail}"),
+        ApiError::NotFound(message)
+        | ApiError::BadRequest(message)
+        | ApiError::Conflict(message) => message.clone(),
+        _ => "Sync failed".to_string(),
+    }
+}
+
+/// Next run of `schedule`, or none for an unscheduled or disabled peer.
+fn next_run_at(schedule: Option<&CronSchedule>, enabled: bool) -> Option<DateTime<Utc>> {
+    schedule
+        .filter(|_| enabled)
+        .and_then(|schedule| schedule.next_after(Utc::now()))
+}
+
+fn sync_peer_not_found() -> ApiError {
+    ApiError::NotFound("Sync peer not found".to_string())
+}
+
+/// Queue a `federation_sync` job for `peer_id`.
+pub(crate) async fn queue_sync_peer_run(
+    db: &Database,
+    event_bus: &EventBus,
+    peer_id: Uuid,
+) -> matric_core::Result<Uuid> {
+    let job_id = db
+        .jobs
+        .queue(
+            None,
+            JobType::FederationSync,
+            JobType::FederationSync.default_priority(),
+            Some(json!({ "peer_id": peer_id })),
+            JobType::FederationSync.default_cost_tier(),
+        )
+        .await?;
+    event_bus.emit(ServerEvent::JobQueued {
+        job_id,
+        job_type: format!("{:?}", JobType::FederationSync),
+        note_id: None,
+    });
+    Ok(job_id)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncChangesQuery {
+    /// Return changes after this `changed_at` (with `after`).
+    since: Option<DateTime<Utc>>,
+    /// Note ID of the last change already seen at `since`.
+    after: Option<Uuid>,
+    /// Maximum changes to return (default: 100, max: 500).
+    limit: Option<i64>,
+}
+
+/// List sync peers.
+#[utoipa::path(get, path = "/api/v1/sync/peers", tag = "Sync",
+    responses((status = 200, description = "Success", body = Vec<SyncPeer>)))]
+pub async fn list_sync_peers(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<SyncPeer>>, ApiError> {
+    Ok(Json(state.db.sync_peers.list().await?))
+}
+
+/// Register a sync peer.
+#[utoipa::path(post, path = "/api/v1/sync/peers", tag = "Sync",
+    request_body = CreateSyncPeerRequest,
+    responses(
+        (status = 201, description = "Created", body = SyncPeer),
+        (status = 400, description = "Invalid name, URL, direction, strategy, or schedule"),
+        (status = 404, description = "Local memory not found")
+    ))]
+pub async fn create_sync_peer(
+    State(state): State<AppState>,
+    Json(mut req): Json<CreateSyncPeerRequest>,
+) -> Result<(StatusCode, Json<SyncPeer>), ApiError> {
+    let schedule = req.validate()?;
+    local_schema(&state.db, req.local_memory.as_deref()).await?;
+    let peer = state
+        .db
+        .sync_peers
+        .create(&req, next_run_at(schedule.as_ref(), req.enabled))
+        .await?;
+    Ok((StatusCode::CREATED, Json(peer)))
+}
+
+/// Get a sync peer.
+#[utoipa::path(get, path = "/api/v1/sync/peers/{id}", tag = "Sync",
+    params(("id" = Uuid, Path, description = "Sync peer ID")),
+    responses(
+        (status = 200, description = "Success", body = SyncPeer),
+        (status = 404, description = "Sync peer not found")
+    ))]
+pub async fn get_sync_peer(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SyncPeer>, ApiError> {
+    state
+        .db
+        .sync_peers
+        .get(id)
+        .await?
+        .map(Json)
+        .ok_or_else(sync_peer_not_found)
+}
+
+/// Update a sync peer.
+///
+/// The next run is recomputed when the schedule changes or the peer is
+/// re-enabled; disabling a peer or clearing its schedule clears it.
+#[utoipa::path(patch, path = "/api/v1/sync/peers/{id}", tag = "Sync",
+    params(("id" = Uuid, Path, description = "Sync peer ID")),
+    request_body = UpdateSyncPeerRequest,
+    responses(
+        (status = 200, description = "Updated", body = SyncPeer),
+        (status = 400, description = "Invalid URL, direction, strategy, or schedule"),
+        (status = 404, description = "Sync peer or local memory not found")
+    ))]
+pub async fn update_sync_peer(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateSyncPeerRequest>,
+) -> Result<Json<SyncPeer>, ApiError> {
+    let mut peer = state
+        .db
+        .sync_peers
+        .get(id)
+        .await?
+        .ok_or_else(sync_peer_not_found)?;
+    let update = req.apply(&mut peer)?;
+    if req.local_memory.is_some() {
+        local_schema(&state.db, peer.local_memory.as_deref()).await?;
+    }
+
+    if !peer.enabled {
+        peer.next_run_at_utc = None;
+    } else if update.schedule.is_some() || peer.next_run_at_utc.is_none() {
+        let schedule = match update.schedule {
+            Some(schedule) => schedule,
+            None => peer
+                .schedule
+                .as_deref()
+                .map(CronSchedule::parse)
+                .transpose()?,
+        };
+        peer.next_run_at_utc = next_run_at(schedule.as_ref(), true);
+    }
+
+    state
+        .db
+        .sync_peers
+        .update(
+            &peer,
+            update.api_token.as_ref().map(Option::as_deref),
+            req.reset_cursors,
+        )
+        .await?
+        .map(Json)
+        .ok_or_else(sync_peer_not_found)
+}
+
+/// Delete a sync peer. Notes already replicated are kept.
+#[utoipa::path(delete, path = "/api/v1/sync/peers/{id}", tag = "Sync",
+    params(("id" = Uuid, Path, description = "Sync peer ID")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, description = "Sync peer not found")
+    ))]
+pub async fn delete_sync_peer(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    if state.db.sync_peers.delete(id).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(sync_peer_not_found())
+    }
+}
+
+/// Queue a sync with a peer now, outside its schedule.
+#[utoipa::path(post, path = "/api/v1/sync/peers/{id}/run", tag = "Sync",
+    params(("id" = Uuid, Path, description = "Sync peer ID")),
+    responses(
+        (status = 202, description = "Sync queued"),
+        (status = 404, description = "Sync peer not found")
+    ))]
+pub async fn run_sync_peer(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    if state.db.sync_peers.get(id).await?.is_none() {
+        return Err(sync_peer_not_found());
+    }
+    let job_id = queue_sync_peer_run(&state.db, &state.event_bus, id).await?;
+    Ok((StatusCode::ACCEPTED, Json(json!({ "job_id": job_id }))))
+}
+
+/// Change feed of the selected memory, oldest first.
+///
+/// Pass the `next` cursor of a page as `since`/`after` to read the following
+/// page. Hard-deleted notes appear as changes without a snapshot.
+#[utoipa::path(get, path = "/api/v1/sync/changes", tag = "Sync",
+    params(
+        ("since" = Option<DateTime<Utc>>, Query, description = "Cursor timestamp to read after"),
+        ("after" = Option<Uuid>, Query, description = "Cursor note ID to read after"),
+        ("limit" = Option<i64>, Query, description = "Maximum changes to return (default 100, max 500)")
+    ),
+    responses(
+        (status = 200, description = "Success", body = SyncChangesPage),
+        (status = 400, description = "Incomplete cursor")
+    ))]
+pub async fn list_sync_changes(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Query(query): Query<SyncChangesQuery>,
+) -> Result<Json<SyncChangesPage>, ApiError> {
+    let cursor = match (query.since, query.after) {
+        (Some(changed_at), note_id) => Some(SyncCursor {
+            changed_at,
+            note_id: note_id.unwrap_or_else(Uuid::nil),
+        }),
+        (None, None) => None,
+        (None, Some(_)) => return Err(ApiError::BadRequest("after requires since".to_string())),
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SYNC_PAGE_SIZE)
+        .clamp(1, MAX_SYNC_PAGE_SIZE);
+
+    let changes = local_changes(&state.db, &archive_ctx.schema, cursor, limit).await?;
+    let has_more = changes.len() as i64 == limit;
+    let next = changes.last().map(SyncChange::cursor).or(cursor);
+    Ok(Json(SyncChangesPage {
+        changes,
+        next,
+        has_more,
+    }))
+}
+
+/// Apply changes pushed by a peer to the selected memory.
+///
+/// Every change is decided with the request's conflict strategy; applied
+/// notes are requeued for processing.
+#[utoipa::path(post, path = "/api/v1/sync/changes", tag = "Sync",
+    request_body = ApplySyncChangesRequest,
+    responses(
+        (status = 200, description = "Applied", body = SyncApplyResult),
+        (status = 400, description = "Too many changes or an invalid snapshot")
+    ))]
+pub async fn apply_sync_changes_handler(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Json(req): Json<ApplySyncChangesRequest>,
+) -> Result<Json<SyncApplyResult>, ApiError> {
+    if req.changes.len() as i64 > MAX_SYNC_PAGE_SIZE {
+        return Err(ApiError::BadRequest(format!(
+            "At most {MAX_SYNC_PAGE_SIZE} changes per request"
+        )));
+    }
+    let result = apply_sync_changes(
+        &state.db,
+        &state.event_bus,
+        &archive_ctx.schema,
+        &req.changes,
+        req.strategy,
+    )
+    .await?;
+    Ok(Json(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_errors_omit_raw_diagnostics() {
+        let err = ApiError::NotFound("Local memory not found".to_string());
+        assert_eq!(sync_run_error(&err), "Local memory not found");
+
+        let err = ApiError::Internal("token=secret".to_string());
+        assert_eq!(sync_run_error(&err), "Sync failed");
+    }
+
+    #[test]
+    fn unscheduled_or_disabled_peers_have_no_next_run() {
+        let schedule = CronSchedule::parse("@hourly").unwrap();
+        assert!(next_run_at(None, true).is_none());
+        assert!(next_run_at(Some(&schedule), false).is_none());
+        assert!(next_run_at(Some(&schedule), true).is_some_and(|next| next > Utc::now()));
+    }
+}
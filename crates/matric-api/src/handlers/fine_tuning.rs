@@ -0,0 +1,392 @@
+//! Fine-tuning dataset HTTP handlers.
+//!
+//! Datasets belong to the memory archive selected by the request. Their
+//! question-answer samples are written by the `generate_fine_tuning_data`
+//! job, which creating a dataset queues.
+//! - `GET /api/v1/fine-tuning/datasets` — list datasets
+//! - `POST /api/v1/fine-tuning/datasets` — create a dataset and queue generation
+//! - `GET /api/v1/fine-tuning/datasets/{id}` — get a dataset
+//! - `PATCH /api/v1/fine-tuning/datasets/{id}` — update name, description, or config
+//! - `DELETE /api/v1/fine-tuning/datasets/{id}` — delete a dataset and its samples
+//! - `POST /api/v1/fine-tuning/datasets/{id}/generate` — queue a new generation run
+//! - `GET /api/v1/fine-tuning/datasets/{id}/samples` — page through samples
+//! - `GET /api/v1/fine-tuning/datasets/{id}/export` — download samples as JSONL
+
+use std::fmt;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::fine_tuning::MAX_FINE_TUNING_EXPORT_SAMPLES;
+use matric_core::{
+    fine_tuning_export_line, CreateFineTuningDatasetRequest, FineTuningDataset,
+    FineTuningExportFormat, FineTuningSample, FineTuningSplit, JobRepository, JobType, ServerEvent,
+    UpdateFineTuningDatasetRequest,
+};
+use matric_db::Database;
+
+const DEFAULT_SAMPLE_LIMIT: i64 = 50;
+const MAX_SAMPLE_LIMIT: i64 = 500;
+
+#[derive(Deserialize)]
+pub struct ListSamplesQuery {
+    /// `all` (default), `training`, or `validation`.
+    split: Option<String>,
+    /// Maximum number of samples to return (default: 50, max: 500).
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+impl fmt::Debug for ListSamplesQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListSamplesQuery")
+            .field("split", &self.split)
+            .field("limit", &self.limit)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    /// `openai` (default) or `llama_factory`.
+    format: Option<String>,
+    /// `all` (default), `training`, or `validation`.
+    split: Option<String>,
+    /// System prompt added to every record.
+    system: Option<String>,
+}
+
+impl fmt::Debug for ExportQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExportQuery")
+            .field("format", &self.format)
+            .field("split", &self.split)
+            .field("system_len", &self.system.as_ref().map(String::len))
+            .finish()
+    }
+}
+
+fn dataset_not_found_error() -> matric_core::Error {
+    matric_core::Error::NotFound(
+        "Fine-tuning dataset not found; dataset_id_present=true".to_string(),
+    )
+}
+
+/// Queue a `generate_fine_tuning_data` job for a dataset.
+async fn queue_generation(
+    db: &Database,
+    event_bus: &matric_core::EventBus,
+    schema: &str,
+    dataset_id: Uuid,
+) -> matric_core::Result<Uuid> {
+    let job_id = db
+        .jobs
+        .queue(
+            None,
+            JobType::GenerateFineTuningData,
+            JobType::GenerateFineTuningData.default_priority(),
+            Some(json!({ "dataset_id": dataset_id, "schema": schema })),
+            JobType::GenerateFineTuningData.default_cost_tier(),
+        )
+        .await?;
+    event_bus.emit(ServerEvent::JobQueued {
+        job_id,
+        job_type: format!("{:?}", JobType::GenerateFineTuningData),
+        note_id: None,
+    });
+    Ok(job_id)
+}
+
+/// List the archive's fine-tuning datasets, newest first.
+///
+/// GET /api/v1/fine-tuning/datasets
+#[utoipa::path(get, path = "/api/v1/fine-tuning/datasets", tag = "Fine-Tuning",
+    responses((status = 200, description = "Success", body = Vec<FineTuningDataset>)))]
+pub async fn list_datasets(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+) -> Result<Json<Vec<FineTuningDataset>>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let fine_tuning = state.db.fine_tuning.clone();
+    let list = ctx
+        .query(move |tx| Box::pin(async move { fine_tuning.list_tx(tx).await }))
+        .await?;
+    Ok(Json(list))
+}
+
+/// Create a fine-tuning dataset and queue its generation.
+///
+/// POST /api/v1/fine-tuning/datasets
+#[utoipa::path(post, path = "/api/v1/fine-tuning/datasets", tag = "Fine-Tuning",
+    request_body = CreateFineTuningDatasetRequest,
+    responses(
+        (status = 201, description = "Created; generation queued", body = FineTuningDataset),
+        (status = 400, description = "Invalid name, source, or config, or name already in use")
+    ))]
+pub async fn create_dataset(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Json(req): Json<CreateFineTuningDatasetRequest>,
+) -> Result<(StatusCode, Json<FineTuningDataset>), ApiError> {
+    req.validate()?;
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let fine_tuning = state.db.fine_tuning.clone();
+    let dataset = ctx
+        .execute(move |tx| Box::pin(async move { fine_tuning.create_tx(tx, &req).await }))
+        .await?;
+    queue_generation(&state.db, &state.event_bus, &archive_ctx.schema, dataset.id).await?;
+    Ok((StatusCode::CREATED, Json(dataset)))
+}
+
+/// Get a fine-tuning dataset.
+///
+/// GET /api/v1/fine-tuning/datasets/{id}
+#[utoipa::path(get, path = "/api/v1/fine-tuning/datasets/{id}", tag = "Fine-Tuning",
+    params(("id" = Uuid, Path, description = "Dataset ID")),
+    responses(
+        (status = 200, description = "Success", body = FineTuningDataset),
+        (status = 404, description = "Dataset not found")
+    ))]
+pub async fn get_dataset(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<FineTuningDataset>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let fine_tuning = state.db.fine_tuning.clone();
+    let dataset = ctx
+        .query(move |tx| Box::pin(async move { fine_tuning.get_tx(tx, id).await }))
+        .await?
+        .ok_or_else(dataset_not_found_error)?;
+    Ok(Json(dataset))
+}
+
+/// Update a fine-tuning dataset. A new config applies from the next run.
+///
+/// PATCH /api/v1/fine-tuning/datasets/{id}
+#[utoipa::path(patch, path = "/api/v1/fine-tuning/datasets/{id}", tag = "Fine-Tuning",
+    params(("id" = Uuid, Path, description = "Dataset ID")),
+    request_body = UpdateFineTuningDatasetRequest,
+    responses(
+        (status = 200, description = "Updated", body = FineTuningDataset),
+        (status = 400, description = "Invalid name or config, or name already in use"),
+        (status = 404, description = "Dataset not found")
+    ))]
+pub async fn update_dataset(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateFineTuningDatasetRequest>,
+) -> Result<Json<FineTuningDataset>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let fine_tuning = state.db.fine_tuning.clone();
+    let dataset = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                let Some(mut dataset) = fine_tuning.get_tx(tx, id).await? else {
+                    return Ok(None);
+                };
+                req.apply(&mut dataset)?;
+                fine_tuning.update_tx(tx, &dataset).await
+            })
+        })
+        .await?
+        .ok_or_else(dataset_not_found_error)?;
+    Ok(Json(dataset))
+}
+
+/// Delete a fine-tuning dataset and its samples.
+///
+/// DELETE /api/v1/fine-tuning/datasets/{id}
+#[utoipa::path(delete, path = "/api/v1/fine-tuning/datasets/{id}", tag = "Fine-Tuning",
+    params(("id" = Uuid, Path, description = "Dataset ID")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, description = "Dataset not found")
+    ))]
+pub async fn delete_dataset(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let fine_tuning = state.db.fine_tuning.clone();
+    let deleted = ctx
+        .execute(move |tx| Box::pin(async move { fine_tuning.delete_tx(tx, id).await }))
+        .await?;
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(dataset_not_found_error().into())
+    }
+}
+
+/// Queue a new generation run. Its samples replace the dataset's current
+/// ones.
+///
+/// POST /api/v1/fine-tuning/datasets/{id}/generate
+#[utoipa::path(post, path = "/api/v1/fine-tuning/datasets/{id}/generate", tag = "Fine-Tuning",
+    params(("id" = Uuid, Path, description = "Dataset ID")),
+    responses(
+        (status = 202, description = "Run queued"),
+        (status = 404, description = "Dataset not found"),
+        (status = 409, description = "A run is already generating the dataset")
+    ))]
+pub async fn generate_dataset(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let fine_tuning = state.db.fine_tuning.clone();
+    let (exists, marked) = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                if fine_tuning.get_tx(tx, id).await?.is_none() {
+                    return Ok((false, false));
+                }
+                Ok((true, fine_tuning.mark_pending_tx(tx, id).await?))
+            })
+        })
+        .await?;
+    if !exists {
+        return Err(dataset_not_found_error().into());
+    }
+    if !marked {
+        return Err(ApiError::Conflict(
+            "Fine-tuning dataset is already generating".to_string(),
+        ));
+    }
+    let job_id = queue_generation(&state.db, &state.event_bus, &archive_ctx.schema, id).await?;
+    Ok((StatusCode::ACCEPTED, Json(json!({ "job_id": job_id }))))
+}
+
+/// List a dataset's samples in generation order.
+///
+/// GET /api/v1/fine-tuning/datasets/{id}/samples
+#[utoipa::path(get, path = "/api/v1/fine-tuning/datasets/{id}/samples", tag = "Fine-Tuning",
+    params(
+        ("id" = Uuid, Path, description = "Dataset ID"),
+        ("split" = Option<String>, Query, description = "all (default), training, or validation"),
+        ("limit" = Option<i64>, Query, description = "Maximum samples to return (default 50, max 500)"),
+        ("offset" = Option<i64>, Query, description = "Samples to skip")
+    ),
+    responses(
+        (status = 200, description = "Success", body = Vec<FineTuningSample>),
+        (status = 400, description = "Unknown split"),
+        (status = 404, description = "Dataset not found")
+    ))]
+pub async fn list_samples(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ListSamplesQuery>,
+) -> Result<Json<Vec<FineTuningSample>>, ApiError> {
+    let split = query
+        .split
+        .as_deref()
+        .map(FineTuningSplit::parse)
+        .transpose()?
+        .unwrap_or_default();
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SAMPLE_LIMIT)
+        .clamp(1, MAX_SAMPLE_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let fine_tuning = state.db.fine_tuning.clone();
+    let samples = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                if fine_tuning.get_tx(tx, id).await?.is_none() {
+                    return Ok(None);
+                }
+                fine_tuning
+                    .samples_tx(tx, id, split, limit, offset)
+                    .await
+                    .map(Some)
+            })
+        })
+        .await?
+        .ok_or_else(dataset_not_found_error)?;
+    Ok(Json(samples))
+}
+
+/// Download a dataset's question-answer samples as JSONL.
+///
+/// GET /api/v1/fine-tuning/datasets/{id}/export
+#[utoipa::path(get, path = "/api/v1/fine-tuning/datasets/{id}/export", tag = "Fine-Tuning",
+    params(
+        ("id" = Uuid, Path, description = "Dataset ID"),
+        ("format" = Option<String>, Query, description = "openai (default) or llama_factory"),
+        ("split" = Option<String>, Query, description = "all (default), training, or validation"),
+        ("system" = Option<String>, Query, description = "System prompt added to every record")
+    ),
+    responses(
+        (status = 200, description = "One JSON record per line", body = String, content_type = "application/jsonl"),
+        (status = 400, description = "Unknown format or split"),
+        (status = 404, description = "Dataset not found")
+    ))]
+pub async fn export_dataset(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ExportQuery>,
+) -> Result<axum::response::Response, ApiError> {
+    let format = FineTuningExportFormat::parse(query.format.as_deref().unwrap_or("openai"))?;
+    let split = query
+        .split
+        .as_deref()
+        .map(FineTuningSplit::parse)
+        .transpose()?
+        .unwrap_or_default();
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let fine_tuning = state.db.fine_tuning.clone();
+    let samples = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                if fine_tuning.get_tx(tx, id).await?.is_none() {
+                    return Ok(None);
+                }
+                fine_tuning
+                    .samples_tx(tx, id, split, MAX_FINE_TUNING_EXPORT_SAMPLES, 0)
+                    .await
+                    .map(Some)
+            })
+        })
+        .await?
+        .ok_or_else(dataset_not_found_error)?;
+
+    let system = query.system.as_deref().filter(|s| !s.trim().is_empty());
+    let mut body = String::new();
+    for line in samples
+        .iter()
+        .filter_map(|sample| fine_tuning_export_line(sample, format, system))
+    {
+        body.push_str(&line);
+        body.push('\n');
+    }
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"fine-tuning-{id}.jsonl\""),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
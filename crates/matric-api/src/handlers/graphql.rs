@@ -0,0 +1,756 @@
+//! GraphQL API layer (optional `graphql` feature).
+//!
+//! Exposes a read-only GraphQL schema alongside REST so clients can fetch a
+//! note together with its links, concepts, and linked notes in one round-trip:
+//! - `POST /graphql` — queries (notes, search, concepts, collections, graph)
+//! - `GET /graphql/ws` — subscriptions over `graphql-transport-ws`/`graphql-ws`,
+//!   bridged from the [`EventBus`](matric_core::EventBus)
+//!
+//! Both routes run behind the regular auth and archive routing middleware, so
+//! every resolver reads from the archive selected for the request and only
+//! returns the notes and collections the request's user may see.
+
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use async_graphql::http::{WebSocket as GraphQLWebSocket, WebSocketProtocols, WsMessage};
+use async_graphql::{
+    ComplexObject, Context, Data, EmptyMutation, Json as GraphQLJson, Object, Schema, SimpleObject,
+    Subscription,
+};
+use axum::{
+    extract::{
+        ws::{CloseFrame, Message, WebSocketUpgrade},
+        State,
+    },
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{envelope_matches_filters, ApiError, AppState, ArchiveContext};
+use matric_core::{ListNotesRequest, NoteFull, SearchConceptsRequest, StrictSecurityFilter};
+
+/// Maximum query nesting depth; linked-note traversal recurses through types.
+const MAX_QUERY_DEPTH: usize = 10;
+/// Maximum query complexity (roughly, number of selected fields).
+const MAX_QUERY_COMPLEXITY: usize = 1000;
+const DEFAULT_LIMIT: i32 = 20;
+const MAX_LIMIT: i32 = 100;
+
+pub type FortemiSchema = Schema<QueryRoot, EmptyMutation, SubscriptionRoot>;
+
+/// Per-request resolver context: application state, the routed archive, and
+/// the user the request acts for.
+#[derive(Clone)]
+struct GraphQLContext {
+    state: AppState,
+    archive: ArchiveContext,
+    caller: Caller,
+}
+
+/// The schema is stateless; request data carries the archive, so build it once.
+pub fn schema() -> &'static FortemiSchema {
+    static SCHEMA: OnceLock<FortemiSchema> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        Schema::build(QueryRoot, EmptyMutation, SubscriptionRoot)
+            .limit_depth(MAX_QUERY_DEPTH)
+            .limit_complexity(MAX_QUERY_COMPLEXITY)
+            .finish()
+    })
+}
+
+fn clamp_limit(limit: Option<i32>) -> i64 {
+    i64::from(limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT))
+}
+
+/// Maps core errors onto client-safe GraphQL errors. Database details stay in
+/// the server log, matching what the REST error responses expose.
+fn graphql_error(err: matric_core::Error) -> async_graphql::Error {
+    match err {
+        matric_core::Error::NotFound(msg) | matric_core::Error::InvalidInput(msg) => {
+            async_graphql::Error::new(msg)
+        }
+        other => {
+            tracing::warn!(
+                error_len = other.to_string().len(),
+                "GraphQL resolver failed"
+            );
+            async_graphql::Error::new("Internal error")
+        }
+    }
+}
+
+async fn fetch_note(ctx: &Context<'_>, id: Uuid) -> async_graphql::Result<Option<GqlNote>> {
+    let gql = ctx.data::<GraphQLContext>()?;
+    let schema_ctx = gql
+        .state
+        .db
+        .for_schema(&gql.archive.schema)
+        .map_err(graphql_error)?;
+    let notes = matric_db::PgNoteRepository::new(gql.state.db.pool.clone());
+    let security = gql.caller.security_filter();
+    let note = schema_ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                if !notes.exists_tx(tx, id).await? {
+                    return Ok(None);
+                }
+                if let Some(security) = &security {
+                    if matric_db::visibility::visible_note_ids(&mut **tx, &[id], security)
+                        .await?
+                        .is_empty()
+                    {
+                        return Ok(None);
+                    }
+                }
+                fetch_visible_links_tx(&notes, tx, id, security.as_ref())
+                    .await
+                    .map(Some)
+            })
+        })
+        .await
+        .map_err(graphql_error)?;
+    Ok(note.map(GqlNote::from))
+}
+
+/// Fetch a note with its links to notes the caller cannot see removed.
+async fn fetch_visible_links_tx(
+    notes: &matric_db::PgNoteRepository,
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    id: Uuid,
+    security: Option<&StrictSecurityFilter>,
+) -> matric_core::Result<NoteFull> {
+    let mut full = notes.fetch_tx(tx, id).await?;
+    matric_db::visibility::retain_visible(&mut **tx, &mut full.links, security, |link| {
+        link.to_note_id
+    })
+    .await?;
+    Ok(full)
+}
+
+/// A note with its current content, tags, concepts, and outgoing links.
+#[derive(SimpleObject)]
+#[graphql(name = "Note")]
+pub struct GqlNote {
+    id: Uuid,
+    title: Option<String>,
+    /// Revised content, or the original when no revision exists.
+    content: String,
+    original_content: String,
+    collection_id: Option<Uuid>,
+    starred: bool,
+    archived: bool,
+    created_at_utc: DateTime<Utc>,
+    updated_at_utc: DateTime<Utc>,
+    tags: Vec<String>,
+    concepts: Vec<GqlNoteConcept>,
+    links: Vec<GqlLink>,
+}
+
+impl From<NoteFull> for GqlNote {
+    fn from(full: NoteFull) -> Self {
+        let content = if full.revised.content.is_empty() {
+            full.original.content.clone()
+        } else {
+            full.revised.content
+        };
+        Self {
+            id: full.note.id,
+            title: full.note.title,
+            content,
+            original_content: full.original.content,
+            collection_id: full.note.collection_id,
+            starred: full.note.starred,
+            archived: full.note.archived,
+            created_at_utc: full.note.created_at_utc,
+            updated_at_utc: full.note.updated_at_utc,
+            tags: full.tags,
+            concepts: full
+                .concepts
+                .into_iter()
+                .map(|c| GqlNoteConcept {
+                    concept_id: c.concept_id,
+                    notation: c.notation,
+                    pref_label: c.pref_label,
+                    relevance_score: c.relevance_score,
+                    is_primary: c.is_primary,
+                })
+                .collect(),
+            links: full
+                .links
+                .into_iter()
+                .map(|l| GqlLink {
+                    id: l.id,
+                    from_note_id: l.from_note_id,
+                    to_note_id: l.to_note_id,
+                    to_url: l.to_url,
+                    kind: l.kind,
+                    score: l.score,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A SKOS concept tag on a note.
+#[derive(SimpleObject)]
+#[graphql(name = "NoteConcept")]
+pub struct GqlNoteConcept {
+    concept_id: Uuid,
+    notation: Option<String>,
+    pref_label: Option<String>,
+    relevance_score: f32,
+    is_primary: bool,
+}
+
+/// A link from a note to another note or an external URL.
+#[derive(SimpleObject)]
+#[graphql(name = "Link", complex)]
+pub struct GqlLink {
+    id: Uuid,
+    from_note_id: Uuid,
+    to_note_id: Option<Uuid>,
+    to_url: Option<String>,
+    kind: String,
+    score: f32,
+}
+
+#[ComplexObject]
+impl GqlLink {
+    /// The linked note, resolved in the same request.
+    async fn target(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<GqlNote>> {
+        match self.to_note_id {
+            Some(id) => fetch_note(ctx, id).await,
+            None => Ok(None),
+        }
+    }
+}
+
+/// A full-text search hit.
+#[derive(SimpleObject)]
+#[graphql(name = "SearchHit", complex)]
+pub struct GqlSearchHit {
+    note_id: Uuid,
+    score: f32,
+    title: Option<String>,
+    snippet: Option<String>,
+    tags: Vec<String>,
+}
+
+#[ComplexObject]
+impl GqlSearchHit {
+    /// The matching note.
+    async fn note(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<GqlNote>> {
+        fetch_note(ctx, self.note_id).await
+    }
+}
+
+/// A SKOS concept.
+#[derive(SimpleObject)]
+#[graphql(name = "Concept")]
+pub struct GqlConcept {
+    id: Uuid,
+    scheme_id: Uuid,
+    notation: Option<String>,
+    pref_label: Option<String>,
+    note_count: i32,
+}
+
+/// A note collection (folder).
+#[derive(SimpleObject)]
+#[graphql(name = "Collection")]
+pub struct GqlCollection {
+    id: Uuid,
+    name: String,
+    description: Option<String>,
+    parent_id: Option<Uuid>,
+    note_count: i64,
+    created_at_utc: DateTime<Utc>,
+}
+
+/// A node reached by graph traversal.
+#[derive(SimpleObject)]
+#[graphql(name = "GraphNode")]
+pub struct GqlGraphNode {
+    id: Uuid,
+    title: Option<String>,
+    depth: i32,
+}
+
+/// An edge between two traversed nodes.
+#[derive(SimpleObject)]
+#[graphql(name = "GraphEdge")]
+pub struct GqlGraphEdge {
+    source: Uuid,
+    target: Uuid,
+    edge_type: String,
+    score: f32,
+}
+
+/// Result of a graph traversal.
+#[derive(SimpleObject)]
+#[graphql(name = "Graph")]
+pub struct GqlGraph {
+    nodes: Vec<GqlGraphNode>,
+    edges: Vec<GqlGraphEdge>,
+}
+
+/// A server event delivered to a subscription.
+#[derive(SimpleObject)]
+#[graphql(name = "Event")]
+pub struct GqlEvent {
+    event_id: Uuid,
+    /// Namespaced event type, e.g. `note.updated`.
+    event_type: String,
+    occurred_at: DateTime<Utc>,
+    memory: Option<String>,
+    entity_type: Option<String>,
+    entity_id: Option<String>,
+    payload: GraphQLJson<serde_json::Value>,
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Fetch one note by ID.
+    async fn note(&self, ctx: &Context<'_>, id: Uuid) -> async_graphql::Result<Option<GqlNote>> {
+        fetch_note(ctx, id).await
+    }
+
+    /// List notes, newest first.
+    async fn notes(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+        collection_id: Option<Uuid>,
+        tags: Option<Vec<String>>,
+    ) -> async_graphql::Result<Vec<GqlNote>> {
+        let gql = ctx.data::<GraphQLContext>()?;
+        let schema_ctx = gql
+            .state
+            .db
+            .for_schema(&gql.archive.schema)
+            .map_err(graphql_error)?;
+        let notes = matric_db::PgNoteRepository::new(gql.state.db.pool.clone());
+        let req = ListNotesRequest {
+            limit: Some(clamp_limit(limit)),
+            offset: Some(i64::from(offset.unwrap_or(0).max(0))),
+            collection_id,
+            tags,
+            ..Default::default()
+        };
+        let security = gql.caller.security_filter();
+        let full = schema_ctx
+            .query(move |tx| {
+                Box::pin(async move {
+                    let security = security.as_ref();
+                    let page = notes.list_visible_tx(tx, req, security).await?;
+                    let mut full = Vec::with_capacity(page.notes.len());
+                    for summary in page.notes {
+                        full.push(fetch_visible_links_tx(&notes, tx, summary.id, security).await?);
+                    }
+                    Ok(full)
+                })
+            })
+            .await
+            .map_err(graphql_error)?;
+        Ok(full.into_iter().map(GqlNote::from).collect())
+    }
+
+    /// Full-text search over note titles, tags, and content.
+    async fn search(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<GqlSearchHit>> {
+        if query.trim().is_empty() {
+            return Err(async_graphql::Error::new("Search query is required"));
+        }
+        let gql = ctx.data::<GraphQLContext>()?;
+        let schema_ctx = gql
+            .state
+            .db
+            .for_schema(&gql.archive.schema)
+            .map_err(graphql_error)?;
+        let fts = matric_db::PgFtsSearch::new(gql.state.db.pool.clone());
+        let limit = clamp_limit(limit);
+        let security = gql.caller.security_filter();
+        let hits = schema_ctx
+            .query(move |tx| {
+                Box::pin(async move {
+                    let mut hits = fts.search_tx(tx, &query, limit, true).await?;
+                    matric_db::visibility::retain_visible(
+                        &mut **tx,
+                        &mut hits,
+                        security.as_ref(),
+                        |hit| Some(hit.note_id),
+                    )
+                    .await?;
+                    Ok(hits)
+                })
+            })
+            .await
+            .map_err(graphql_error)?;
+        Ok(hits
+            .into_iter()
+            .map(|hit| GqlSearchHit {
+                note_id: hit.note_id,
+                score: hit.score,
+                title: hit.title,
+                snippet: hit.snippet,
+                tags: hit.tags,
+            })
+            .collect())
+    }
+
+    /// Search SKOS concepts by label.
+    async fn concepts(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<String>,
+        scheme_id: Option<Uuid>,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<GqlConcept>> {
+        let gql = ctx.data::<GraphQLContext>()?;
+        let schema_ctx = gql
+            .state
+            .db
+            .for_schema(&gql.archive.schema)
+            .map_err(graphql_error)?;
+        let skos = matric_db::PgSkosRepository::new(gql.state.db.pool.clone());
+        let req = SearchConceptsRequest {
+            query,
+            scheme_id,
+            limit: clamp_limit(limit),
+            ..Default::default()
+        };
+        let found = schema_ctx
+            .query(move |tx| Box::pin(async move { skos.search_concepts_tx(tx, req).await }))
+            .await
+            .map_err(graphql_error)?;
+        Ok(found
+            .concepts
+            .into_iter()
+            .map(|c| GqlConcept {
+                id: c.concept.id,
+                scheme_id: c.concept.primary_scheme_id,
+                notation: c.concept.notation,
+                pref_label: c.pref_label,
+                note_count: c.concept.note_count,
+            })
+            .collect())
+    }
+
+    /// List collections under `parent_id`, or root collections when omitted.
+    async fn collections(
+        &self,
+        ctx: &Context<'_>,
+        parent_id: Option<Uuid>,
+    ) -> async_graphql::Result<Vec<GqlCollection>> {
+        let gql = ctx.data::<GraphQLContext>()?;
+        let schema_ctx = gql
+            .state
+            .db
+            .for_schema(&gql.archive.schema)
+            .map_err(graphql_error)?;
+        let collections = matric_db::PgCollectionRepository::new(gql.state.db.pool.clone());
+        let user_id = gql.caller.user_id;
+        let found = schema_ctx
+            .query(move |tx| {
+                Box::pin(async move { collections.list_visible_tx(tx, parent_id, user_id).await })
+            })
+            .await
+            .map_err(graphql_error)?;
+        Ok(found
+            .into_iter()
+            .map(|c| GqlCollection {
+                id: c.id,
+                name: c.name,
+                description: c.description,
+                parent_id: c.parent_id,
+                note_count: c.note_count,
+                created_at_utc: c.created_at_utc,
+            })
+            .collect())
+    }
+
+    /// Traverse the link graph outward from a note.
+    async fn graph(
+        &self,
+        ctx: &Context<'_>,
+        id: Uuid,
+        #[graphql(default = 2)] depth: i32,
+        #[graphql(default = 50)] max_nodes: i32,
+        #[graphql(default = 0.0)] min_score: f64,
+    ) -> async_graphql::Result<Option<GqlGraph>> {
+        let gql = ctx.data::<GraphQLContext>()?;
+        let schema_ctx = gql
+            .state
+            .db
+            .for_schema(&gql.archive.schema)
+            .map_err(graphql_error)?;
+        let notes = matric_db::PgNoteRepository::new(gql.state.db.pool.clone());
+        let links = matric_db::PgLinkRepository::new(gql.state.db.pool.clone());
+        // Same resource clamps as GET /api/v1/graph/{id}.
+        let depth = depth.clamp(0, 10);
+        let max_nodes = i64::from(max_nodes.clamp(1, 1000));
+        let min_score = min_score.clamp(0.0, 1.0) as f32;
+        let security = gql.caller.security_filter();
+        let result = schema_ctx
+            .query(move |tx| {
+                Box::pin(async move {
+                    if !notes.exists_tx(tx, id).await? {
+                        return Ok(None);
+                    }
+                    if let Some(security) = &security {
+                        if matric_db::visibility::visible_note_ids(&mut **tx, &[id], security)
+                            .await?
+                            .is_empty()
+                        {
+                            return Ok(None);
+                        }
+                    }
+                    let mut graph = links
+                        .traverse_graph_tx(
+                            tx, id, depth, max_nodes, min_score, None, None, true, None,
+                        )
+                        .await?;
+                    matric_db::visibility::retain_visible_graph(
+                        &mut **tx,
+                        &mut graph,
+                        security.as_ref(),
+                    )
+                    .await?;
+                    Ok(Some(graph))
+                })
+            })
+            .await
+            .map_err(graphql_error)?;
+        Ok(result.map(|graph| GqlGraph {
+            nodes: graph
+                .nodes
+                .into_iter()
+                .map(|n| GqlGraphNode {
+                    id: n.id,
+                    title: n.title,
+                    depth: n.depth,
+                })
+                .collect(),
+            edges: graph
+                .edges
+                .into_iter()
+                .map(|e| GqlGraphEdge {
+                    source: e.source,
+                    target: e.target,
+                    edge_type: e.edge_type,
+                    score: e.score,
+                })
+                .collect(),
+        }))
+    }
+}
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Live server events, scoped like `GET /api/v1/events`.
+    ///
+    /// `types` takes namespaced event types or prefixes (`note` matches
+    /// `note.created`, `note.updated`, ...).
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        types: Option<Vec<String>>,
+        entity_id: Option<String>,
+    ) -> async_graphql::Result<impl Stream<Item = GqlEvent>> {
+        let gql = ctx.data::<GraphQLContext>()?;
+        let memory_filter = if gql.archive.is_default {
+            None
+        } else {
+            gql.archive.name.clone()
+        };
+        let type_filters = types.map(|types| {
+            types
+                .into_iter()
+                .map(|t| t.trim().to_lowercase())
+                .filter(|t| !t.is_empty())
+                .collect::<Vec<_>>()
+        });
+        let entity_id_filter = entity_id.map(|id| id.trim().to_string());
+
+        let rx = gql.state.event_bus.subscribe();
+        Ok(
+            tokio_stream::wrappers::BroadcastStream::new(rx).filter_map(move |item| {
+                let event = item.ok().filter(|envelope| {
+                    envelope_matches_filters(
+                        envelope,
+                        &memory_filter,
+                        &type_filters,
+                        &entity_id_filter,
+                    )
+                });
+                futures::future::ready(event.map(|envelope| GqlEvent {
+                    event_id: envelope.event_id,
+                    event_type: envelope.event_type,
+                    occurred_at: envelope.occurred_at,
+                    memory: envelope.memory,
+                    entity_type: envelope.entity_type,
+                    entity_id: envelope.entity_id,
+                    payload: GraphQLJson(
+                        serde_json::to_value(&envelope.payload).unwrap_or_default(),
+                    ),
+                }))
+            }),
+        )
+    }
+}
+
+/// Execute a GraphQL query.
+///
+/// POST /graphql
+pub async fn graphql_handler(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    let request = request.data(GraphQLContext {
+        state,
+        archive: archive_ctx,
+        caller,
+    });
+    Json(schema().execute(request).await)
+}
+
+/// Serve GraphQL subscriptions over WebSocket.
+///
+/// GET /graphql/ws
+pub async fn graphql_ws_handler(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Result<impl IntoResponse, ApiError> {
+    let protocol = headers
+        .get(header::SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            value
+                .split(',')
+                .find_map(|p| WebSocketProtocols::from_str(p.trim()).ok())
+        })
+        .ok_or_else(|| {
+            ApiError::BadRequest(
+                "Sec-WebSocket-Protocol must be graphql-transport-ws or graphql-ws".to_string(),
+            )
+        })?;
+
+    let mut data = Data::default();
+    data.insert(GraphQLContext {
+        state,
+        archive: archive_ctx,
+        caller,
+    });
+
+    Ok(ws
+        .protocols(async_graphql::http::ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |socket| async move {
+            use futures::SinkExt;
+
+            let (mut sink, stream) = socket.split();
+            let input = stream
+                .take_while(|msg| futures::future::ready(msg.is_ok()))
+                .filter_map(|msg| {
+                    futures::future::ready(match msg {
+                        Ok(Message::Text(text)) => Some(text.to_string()),
+                        Ok(Message::Binary(bytes)) => String::from_utf8(bytes.to_vec()).ok(),
+                        _ => None,
+                    })
+                });
+            let mut output = Box::pin(
+                GraphQLWebSocket::new(schema().clone(), input, protocol).connection_data(data),
+            );
+            while let Some(msg) = output.next().await {
+                let frame = match msg {
+                    WsMessage::Text(text) => Message::Text(text.into()),
+                    WsMessage::Close(code, reason) => Message::Close(Some(CloseFrame {
+                        code,
+                        reason: reason.into(),
+                    })),
+                };
+                if sink.send(frame).await.is_err() {
+                    break;
+                }
+            }
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_sdl_exposes_queries_and_subscriptions() {
+        let sdl = schema().sdl();
+        for field in [
+            "note(id: UUID!): Note",
+            "notes(",
+            "search(query: String!",
+            "concepts(",
+            "collections(",
+            "graph(id: UUID!",
+            "events(",
+        ] {
+            assert!(sdl.contains(field), "missing {field}");
+        }
+        assert!(sdl.contains("target: Note"));
+    }
+
+    #[test]
+    fn limits_are_clamped() {
+        assert_eq!(clamp_limit(None), 20);
+        assert_eq!(clamp_limit(Some(0)), 1);
+        assert_eq!(clamp_limit(Some(10_000)), 100);
+    }
+
+    #[test]
+    fn database_errors_are_not_exposed() {
+        let err = graphql_error(matric_core::Error::Internal("secret detail".to_string()));
+        assert_eq!(err.message, "Internal error");
+        let err = graphql_error(matric_core::Error::NotFound("Note not found".to_string()));
+        assert_eq!(err.message, "Note not found");
+    }
+
+    #[tokio::test]
+    async fn requests_without_context_fail_cleanly() {
+        let response = schema()
+            .execute("{ note(id: \"00000000-0000-0000-0000-000000000000\") { id } }")
+            .await;
+        assert_eq!(response.errors.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn overly_deep_queries_are_rejected() {
+        let mut query = String::from("{ note(id: \"00000000-0000-0000-0000-000000000000\") { ");
+        for _ in 0..MAX_QUERY_DEPTH {
+            query.push_str("links { target { ");
+        }
+        query.push_str("id");
+        for _ in 0..MAX_QUERY_DEPTH {
+            query.push_str(" } }");
+        }
+        query.push_str(" } }");
+        let response = schema().execute(query).await;
+        assert!(response.errors[0].message.contains("nested too deep"));
+    }
+}
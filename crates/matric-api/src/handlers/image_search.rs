@@ -0,0 +1,296 @@
+//! Image similarity search HTTP handlers.
+//!
+//! Searches the CLIP embeddings the `image_embedding` job stores for image
+//! attachments:
+//! - `GET /api/v1/search/images?q=...` — text-to-image search
+//! - `GET /api/v1/search/images?attachment_id=...` — images like a stored image
+//! - `POST /api/v1/search/images` — images like an uploaded image (multipart `file`)
+
+use std::fmt;
+
+use axum::extract::{Multipart, Query, State};
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{telemetry_text_len, ApiError, AppState, ArchiveContext};
+use matric_core::{ImageSearchHit, StrictSecurityFilter, Vector};
+use matric_inference::ImageEmbeddingBackend;
+
+const MAX_IMAGE_SEARCH_LIMIT: i64 = 100;
+const MAX_IMAGE_SEARCH_QUERY_CHARS: usize = 1_000;
+
+const IMAGE_EMBEDDING_PROVIDER_DETAIL: &str =
+    "Image embedding backend failed. Check server logs for diagnostics.";
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct ImageSearchQuery {
+    /// Text describing the images to find.
+    q: Option<String>,
+    /// Find images similar to this image attachment.
+    attachment_id: Option<Uuid>,
+    /// Maximum number of results (default: 20, max: 100).
+    limit: Option<i64>,
+}
+
+impl fmt::Debug for ImageSearchQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageSearchQuery")
+            .field("q_len", &self.q.as_deref().map(telemetry_text_len))
+            .field("attachment_id_set", &self.attachment_id.is_some())
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct ImageUploadSearchQuery {
+    /// Maximum number of results (default: 20, max: 100).
+    limit: Option<i64>,
+}
+
+/// Image attachments ranked by similarity to the query.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ImageSearchResponse {
+    pub results: Vec<ImageSearchHit>,
+    pub total: usize,
+}
+
+fn image_backend(state: &AppState) -> Result<&dyn ImageEmbeddingBackend, ApiError> {
+    state.image_embedding_backend.as_deref().ok_or_else(|| {
+        ApiError::ServiceUnavailable("Image embedding backend is not configured.".into())
+    })
+}
+
+fn image_search_limit(limit: Option<i64>) -> i64 {
+    limit
+        .unwrap_or(matric_core::defaults::PAGE_LIMIT_SEARCH)
+        .clamp(1, MAX_IMAGE_SEARCH_LIMIT)
+}
+
+fn image_embedding_failure(error: matric_core::Error) -> ApiError {
+    warn!(
+        error_len = telemetry_text_len(&error.to_string()),
+        "Image embedding backend failed"
+    );
+    ApiError::ProviderFailure {
+        capability: "Image embedding",
+        detail: IMAGE_EMBEDDING_PROVIDER_DETAIL.to_string(),
+    }
+}
+
+fn image_not_found_error() -> ApiError {
+    ApiError::NotFound("Image embedding not found; attachment_id_present=true".to_string())
+}
+
+/// Run the vector search and drop hits on notes the caller may not read.
+async fn search_images(
+    state: &AppState,
+    archive_ctx: &ArchiveContext,
+    security: Option<StrictSecurityFilter>,
+    query: Vector,
+    limit: i64,
+    exclude: Option<Uuid>,
+) -> Result<ImageSearchResponse, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let images = state.db.image_embeddings.clone();
+    let results = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                let mut hits = images.search_tx(tx, &query, limit, exclude).await?;
+                matric_db::visibility::retain_visible(
+                    &mut **tx,
+                    &mut hits,
+                    security.as_ref(),
+                    |hit| Some(hit.note_id),
+                )
+                .await?;
+                Ok(hits)
+            })
+        })
+        .await?;
+    Ok(ImageSearchResponse {
+        total: results.len(),
+        results,
+    })
+}
+
+/// Search image attachments by text or by a stored image.
+///
+/// Pass exactly one of `q` (text-to-image) or `attachment_id`
+/// (image-by-image; the query image itself is left out of the results).
+///
+/// GET /api/v1/search/images
+#[utoipa::path(get, path = "/api/v1/search/images", tag = "Search",
+    params(ImageSearchQuery),
+    responses(
+        (status = 200, description = "Success", body = ImageSearchResponse),
+        (status = 400, description = "Neither or both of q and attachment_id given"),
+        (status = 404, description = "Attachment has no image embedding"),
+        (status = 503, description = "Image embedding backend not configured")
+    ))]
+pub async fn search_images_by_query(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Query(query): Query<ImageSearchQuery>,
+) -> Result<Json<ImageSearchResponse>, ApiError> {
+    let limit = image_search_limit(query.limit);
+    let security = caller.security_filter();
+
+    let text = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    match (text, query.attachment_id) {
+        (Some(text), None) => {
+            if text.chars().count() > MAX_IMAGE_SEARCH_QUERY_CHARS {
+                return Err(ApiError::BadRequest(format!(
+                    "Query must be at most {} characters",
+                    MAX_IMAGE_SEARCH_QUERY_CHARS
+                )));
+            }
+            let backend = image_backend(&state)?;
+            let vector = backend
+                .embed_text(text)
+                .await
+                .map_err(image_embedding_failure)?;
+            let response =
+                search_images(&state, &archive_ctx, security, vector, limit, None).await?;
+            Ok(Json(response))
+        }
+        (None, Some(attachment_id)) => {
+            let ctx = state.db.for_schema(&archive_ctx.schema)?;
+            let images = state.db.image_embeddings.clone();
+            let lookup_security = security.clone();
+            let vector = ctx
+                .query(move |tx| {
+                    Box::pin(async move {
+                        let Some((note_id, vector)) = images.get_tx(tx, attachment_id).await?
+                        else {
+                            return Ok(None);
+                        };
+                        let visible = match lookup_security.as_ref() {
+                            Some(security) => matric_db::visibility::visible_note_ids(
+                                &mut **tx,
+                                &[note_id],
+                                security,
+                            )
+                            .await?
+                            .contains(&note_id),
+                            None => true,
+                        };
+                        Ok(visible.then_some(vector))
+                    })
+                })
+                .await?
+                .ok_or_else(image_not_found_error)?;
+            let response = search_images(
+                &state,
+                &archive_ctx,
+                security,
+                vector,
+                limit,
+                Some(attachment_id),
+            )
+            .await?;
+            Ok(Json(response))
+        }
+        _ => Err(ApiError::BadRequest(
+            "Provide exactly one of q or attachment_id".to_string(),
+        )),
+    }
+}
+
+/// Search image attachments by an uploaded image.
+///
+/// Accepts multipart/form-data with the query image in a `file` field.
+/// The image is embedded for the search and not stored.
+///
+/// POST /api/v1/search/images
+#[utoipa::path(post, path = "/api/v1/search/images", tag = "Search",
+    params(ImageUploadSearchQuery),
+    responses(
+        (status = 200, description = "Success", body = ImageSearchResponse),
+        (status = 400, description = "Missing, empty, or non-image file"),
+        (status = 503, description = "Image embedding backend not configured")
+    ))]
+pub async fn search_images_by_upload(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Query(query): Query<ImageUploadSearchQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<ImageSearchResponse>, ApiError> {
+    let backend = image_backend(&state)?;
+
+    let mut file: Option<(Vec<u8>, Option<String>)> = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| ApiError::BadRequest("Invalid multipart image search request.".to_string()))?
+    {
+        if field.name() == Some("file") {
+            let content_type = field.content_type().map(|c| c.to_string());
+            let data = field
+                .bytes()
+                .await
+                .map_err(|_| ApiError::BadRequest("Invalid uploaded image file.".to_string()))?;
+            file = Some((data.to_vec(), content_type));
+        }
+    }
+
+    let (image_bytes, content_type) =
+        file.ok_or_else(|| ApiError::BadRequest("Missing file in multipart form".to_string()))?;
+    if image_bytes.is_empty() {
+        return Err(ApiError::BadRequest("Image file is empty".into()));
+    }
+    let mime_type = content_type.as_deref().unwrap_or("image/png");
+    if !mime_type.starts_with("image/") {
+        return Err(ApiError::BadRequest("File must be an image".into()));
+    }
+
+    let vector = backend
+        .embed_image(&image_bytes, mime_type)
+        .await
+        .map_err(image_embedding_failure)?;
+    let response = search_images(
+        &state,
+        &archive_ctx,
+        caller.security_filter(),
+        vector,
+        image_search_limit(query.limit),
+        None,
+    )
+    .await?;
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_search_limit_defaults_and_clamps() {
+        assert_eq!(
+            image_search_limit(None),
+            matric_core::defaults::PAGE_LIMIT_SEARCH
+        );
+        assert_eq!(image_search_limit(Some(0)), 1);
+        assert_eq!(image_search_limit(Some(5)), 5);
+        assert_eq!(image_search_limit(Some(10_000)), MAX_IMAGE_SEARCH_LIMIT);
+    }
+
+    #[test]
+    fn image_search_query_debug_redacts_text() {
+        let query = ImageSearchQuery {
+            q: Some("photos of custómer@example.com".to_string()),
+            attachment_id: None,
+            limit: Some(5),
+        };
+
+        let rendered = format!("{query:?}");
+
+        assert!(rendered.contains("q_len"));
+        assert!(!rendered.contains("custómer@example.com"));
+    }
+}
@@ -12,12 +12,13 @@ use axum::extract::State;
 use axum::http::HeaderMap;
 use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::IntoResponse;
+use axum::Extension;
 use axum::Json;
 use chrono::{DateTime, Utc};
 use matric_core::{
     MeteringError, UsageAttributeKey, UsageAttributeValue, UsageAttributes, UsageClass,
-    UsageCorrelation, UsageDimension, UsageEvent, UsageMeasurement, UsageMeter, UsageOutcome,
-    UsageProducer, UsageSource, UsageSubject, UsageUnit,
+    UsageCorrelation, UsageCounter, UsageDimension, UsageEvent, UsageMeasurement, UsageMeter,
+    UsageOutcome, UsageProducer, UsageSource, UsageSubject, UsageUnit,
 };
 use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
@@ -30,6 +31,7 @@ use tracing::{debug, error, info, warn};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::middleware::usage_quota::{inference_tokens, UsageRecorder};
 use crate::{canonical_usage_request_id, usage_subject_from_auth, ApiError, AppState, Auth};
 
 const INFERENCE_COMPLETION_PROVIDER_DETAIL: &str =
@@ -335,6 +337,7 @@ pub async fn complete(
     State(state): State<AppState>,
     auth: Auth,
     headers: HeaderMap,
+    usage: Option<Extension<UsageRecorder>>,
     Json(req): Json<CompleteRequest>,
 ) -> Result<Json<CompleteResponse>, axum::response::Response> {
     let provider_id = req
@@ -399,6 +402,14 @@ pub async fn complete(
             if let Ok(context) = &metering {
                 context.record(UsageOutcome::Completed).await;
             }
+            if let Some(Extension(usage)) = &usage {
+                usage
+                    .record(
+                        UsageCounter::InferenceTokens,
+                        inference_tokens(&[&system, &prompt, &content]),
+                    )
+                    .await;
+            }
             info!(
                 provider_id_len = complete_text_len(&provider_id),
                 model_len = complete_text_len(&req.model),
@@ -556,6 +567,7 @@ pub async fn stream(
     State(state): State<AppState>,
     auth: Auth,
     headers: HeaderMap,
+    usage: Option<Extension<UsageRecorder>>,
     Json(req): Json<CompleteRequest>,
 ) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, axum::response::Response> {
     use futures::StreamExt;
@@ -606,6 +618,8 @@ pub async fn stream(
     let model_name = req.model.clone();
     let pid_clone = provider_id.clone();
 
+    let usage = usage.map(|Extension(usage)| usage);
+
     tokio::spawn(async move {
         // Ask the backend for a chunk stream. The trait default wraps
         // generate() in a one-item stream; Ollama and OpenAI overrides
@@ -618,9 +632,22 @@ pub async fn stream(
 
         match stream_result {
             Ok(mut chunks) => {
+                // Tokens generated before a disconnect or provider failure
+                // were still consumed, so they are counted on every exit.
+                let mut output = String::new();
+                let record_tokens = |output: &str| {
+                    let usage = usage.clone();
+                    let tokens = inference_tokens(&[&system, &prompt, output]);
+                    async move {
+                        if let Some(usage) = usage {
+                            usage.record(UsageCounter::InferenceTokens, tokens).await;
+                        }
+                    }
+                };
                 while let Some(chunk) = chunks.next().await {
                     match chunk {
                         Ok(content) => {
+                            output.push_str(&content);
                             let payload = serde_json::json!({"content": content}).to_string();
                             if tx
                                 .send(Ok(Event::default().event("delta").data(payload)))
@@ -630,6 +657,7 @@ pub async fn stream(
                                 if let Ok(context) = &metering {
                                     context.record(UsageOutcome::ClientInterrupted).await;
                                 }
+                                record_tokens(&output).await;
                                 return;
                             }
                         }
@@ -637,6 +665,7 @@ pub async fn stream(
                             if let Ok(context) = &metering {
                                 context.record(UsageOutcome::ProviderInterrupted).await;
                             }
+                            record_tokens(&output).await;
                             error!(
                                 provider_id_len = complete_text_len(&pid_clone),
                                 model_len = complete_text_len(&model_name),
@@ -654,6 +683,7 @@ pub async fn stream(
                 if let Ok(context) = &metering {
                     context.record(UsageOutcome::Completed).await;
                 }
+                record_tokens(&output).await;
                 let done_payload = serde_json::json!({
                     "finish_reason": "stop",
                     "model": model_name,
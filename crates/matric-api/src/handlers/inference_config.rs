@@ -1001,7 +1001,7 @@ async fn read_archive_override(
 /// — operators using the default archive interact with the global config,
 /// matching pre-#655 behavior. Per-archive overrides are opt-in by
 /// explicitly addressing a non-default archive via the header.
-fn archive_override_schema(ctx: &ArchiveContext) -> Option<&str> {
+pub(crate) fn archive_override_schema(ctx: &ArchiveContext) -> Option<&str> {
     if ctx.is_default || ctx.schema == "public" {
         None
     } else {
@@ -0,0 +1,88 @@
+//! Inference usage HTTP handler.
+//!
+//! - `GET /api/v1/inference/usage` — token and cost totals for background
+//!   jobs, overall and broken down by model and job type
+//!
+//! Requests that select a non-default archive with `X-Fortemi-Memory` report
+//! that archive's usage; all other requests report the default archive.
+
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::inference_config::archive_override_schema;
+use crate::middleware::archive_routing::ArchiveContext;
+use crate::{ApiError, AppState};
+use matric_core::{InferenceUsageFilter, InferenceUsageSummary};
+
+/// Filters for the usage report. All bounds are optional.
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct InferenceUsageQuery {
+    /// Include usage recorded at or after this time (RFC 3339)
+    pub since: Option<DateTime<Utc>>,
+    /// Include usage recorded before this time (RFC 3339)
+    pub until: Option<DateTime<Utc>>,
+    /// Report a single job
+    pub job_id: Option<Uuid>,
+}
+
+impl InferenceUsageQuery {
+    fn into_filter(self) -> Result<InferenceUsageFilter, ApiError> {
+        if let (Some(since), Some(until)) = (self.since, self.until) {
+            if since >= until {
+                return Err(ApiError::BadRequest(
+                    "since must be earlier than until".to_string(),
+                ));
+            }
+        }
+        Ok(InferenceUsageFilter {
+            since: self.since,
+            until: self.until,
+            job_id: self.job_id,
+        })
+    }
+}
+
+/// Get inference token and cost usage for background jobs.
+#[utoipa::path(get, path = "/api/v1/inference/usage", tag = "Inference",
+    params(InferenceUsageQuery),
+    responses(
+        (status = 200, description = "Usage totals", body = InferenceUsageSummary),
+        (status = 400, description = "Invalid time window")
+    ))]
+pub async fn get_inference_usage(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Query(query): Query<InferenceUsageQuery>,
+) -> Result<Json<InferenceUsageSummary>, ApiError> {
+    let filter = query.into_filter()?;
+    let schema = archive_override_schema(&archive_ctx).unwrap_or("public");
+    let summary = state.db.inference_usage.summary(schema, &filter).await?;
+    Ok(Json(summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_empty_time_window() {
+        let now = Utc::now();
+        let query = InferenceUsageQuery {
+            since: Some(now),
+            until: Some(now),
+            job_id: None,
+        };
+        assert!(matches!(query.into_filter(), Err(ApiError::BadRequest(_))));
+
+        let open = InferenceUsageQuery {
+            since: Some(now),
+            ..Default::default()
+        };
+        assert_eq!(open.into_filter().unwrap().since, Some(now));
+    }
+}
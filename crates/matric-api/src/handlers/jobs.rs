@@ -3,31 +3,51 @@
 //! Ported from HOTM's enhanced NLP pipeline for contextual note enhancement.
 //! Supports multiple revision modes to control AI enhancement aggressiveness.
 
-use std::collections::HashSet;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, LazyLock};
 use std::time::Instant;
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use tracing::{debug, info, instrument, warn};
 
+use matric_api::services::{InferenceCache, InferenceTask};
+use matric_core::digest::{
+    DIGEST_EXCERPT_CHARS, DIGEST_NOTE_SOURCE, DIGEST_NOTE_TAG, MAX_DIGEST_NOTES,
+};
+use matric_core::fine_tuning::{FINE_TUNING_CONTENT_CHARS, MAX_FINE_TUNING_SOURCE_NOTES};
+use matric_core::job_lane::JobLane;
+use matric_core::pii::{
+    llm_assist_enabled, locate_pii, mask_pii, merge_pii_matches, scan_pii, PiiMatch,
+};
+use matric_core::task::{llm_extraction_enabled, merge_action_items, parse_checkbox_tasks};
 use matric_core::{
-    AttachmentStatus, CreateFileProvenanceRequest, CreateProvDeviceRequest,
-    CreateProvLocationRequest, CreateSemanticRelationRequest, DocumentTypeRepository,
-    EmbeddingConfigProfile, EmbeddingContract, EmbeddingRepository, GenerationBackend,
-    JobRepository, JobType, LinkRepository, MeteringError, NoteRepository, ProvRelation,
-    RevisionMode, SkosSemanticRelation, UsageAttributes, UsageClass, UsageCorrelation,
-    UsageDimension, UsageEvent, UsageMeasurement, UsageMeter, UsageOutcome, UsageProducer,
-    UsageQuantity, UsageSource, UsageSubject,
+    digest_period_label, digest_prompt_notes, extract_citations, fill_prompt_template,
+    is_validation_sample, parse_topic_label, render_digest_note, topic_prompt_notes,
+    validate_prompt_template, ArchiveRepository, AttachmentStatus, CreateFileProvenanceRequest,
+    CreateNoteRequest, CreateProvDeviceRequest, CreateProvLocationRequest,
+    CreateSemanticRelationRequest, DigestConfig, DigestDelivery, DocumentTypeRepository,
+    EmbeddingConfigProfile, EmbeddingContract, EmbeddingRepository, EventBus, EventContext,
+    FineTuningDataset, GenerationBackend, JobRepository, JobType, LinkRepository, MeteringError,
+    NewTopic, NoteRepository, PiiKind, PiiRedactionMode, PromptKey, ProvRelation, RevisionMode,
+    ServerEvent, SkosSemanticRelation, TagInput, Tokenizer, UsageAttributes, UsageClass,
+    UsageCorrelation, UsageDimension, UsageEvent, UsageMeasurement, UsageMeter, UsageOutcome,
+    UsageProducer, UsageQuantity, UsageSource, UsageSubject,
 };
 use matric_db::{
-    Chunker, ChunkerConfig, Database, SchemaContext, SemanticChunker, SkosRelationRepository,
+    Chunker, ChunkerConfig, Database, NewFineTuningSample, SchemaContext, SemanticChunker,
+    SkosRelationRepository, DIGEST_RUN_EMPTY, DIGEST_RUN_FAILED, DIGEST_RUN_SUCCEEDED,
+};
+use matric_inference::{
+    concept_tags_schema, detect_pii, extract_action_items, extract_entities, generate_constrained,
+    generate_qa_pairs, map_reduce_summarize, merge_entities, ConstrainedConfig, EmbeddingBatcher,
+    NerBackend, OllamaBackend, ProviderRegistry, SummarizeConfig,
 };
-use matric_inference::{NerBackend, OllamaBackend, ProviderRegistry};
 use matric_jobs::adapters::exif::{
     extract_exif_metadata, parse_exif_datetime, prepare_attachment_metadata,
 };
 use matric_jobs::{JobContext, JobHandler, JobResult};
+use matric_search::{default_cluster_count, kmeans, KMeansConfig};
 use sqlx;
 
 #[cfg(test)]
@@ -52,6 +72,21 @@ const DOCUMENT_TYPE_INFERENCE_JOB_FAILURE: &str =
 const EMBEDDING_JOB_FAILURE: &str = "Embedding job failed. Check server logs for diagnostics.";
 const TITLE_GENERATION_JOB_FAILURE: &str =
     "Title generation failed. Check server logs for diagnostics.";
+const SUMMARIZATION_JOB_FAILURE: &str = "Summarization failed. Check server logs for diagnostics.";
+const TRANSLATION_JOB_FAILURE: &str = "Translation failed. Check server logs for diagnostics.";
+const PII_SCAN_JOB_FAILURE: &str = "PII scan failed. Check server logs for diagnostics.";
+const CITATION_EXTRACTION_JOB_FAILURE: &str =
+    "Citation extraction failed. Check server logs for diagnostics.";
+const TASK_EXTRACTION_JOB_FAILURE: &str =
+    "Task extraction failed. Check server logs for diagnostics.";
+const ENTITY_EXTRACTION_JOB_FAILURE: &str =
+    "Entity extraction failed. Check server logs for diagnostics.";
+const DIGEST_GENERATION_JOB_FAILURE: &str =
+    "Digest generation failed. Check server logs for diagnostics.";
+const TOPIC_MODELING_JOB_FAILURE: &str =
+    "Topic modeling failed. Check server logs for diagnostics.";
+const FINE_TUNING_JOB_FAILURE: &str =
+    "Fine-tuning data generation failed. Check server logs for diagnostics.";
 const CONTEXT_UPDATE_JOB_FAILURE: &str =
     "Context update failed. Check server logs for diagnostics.";
 const LINKING_JOB_FAILURE: &str = "Linking job failed. Check server logs for diagnostics.";
@@ -66,7 +101,6 @@ const REEMBED_ALL_JOB_FAILURE: &str =
     "Bulk re-embedding failed. Check server logs for diagnostics.";
 const REFRESH_EMBEDDING_SET_JOB_FAILURE: &str =
     "Embedding set refresh failed. Check server logs for diagnostics.";
-const JOB_CHUNK_MERGE_PARSE_FAILURE_DETAIL: &str = "job_chunk_merge_parse_failed";
 const JOB_AI_GENERATION_DIAGNOSTIC_FAILURE_DETAIL: &str = "job_ai_generation_diagnostic_failed";
 const JOB_AI_REVISION_DIAGNOSTIC_FAILURE_DETAIL: &str = "job_ai_revision_diagnostic_failed";
 const JOB_AI_CONTEXTUAL_REVISION_DIAGNOSTIC_FAILURE_DETAIL: &str =
@@ -83,6 +117,7 @@ const JOB_CONCEPT_TAGGING_DIAGNOSTIC_FAILURE_DETAIL: &str = "job_concept_tagging
 const JOB_REFERENCE_EXTRACTION_DIAGNOSTIC_FAILURE_DETAIL: &str =
     "job_reference_extraction_diagnostic_failed";
 const JOB_RELATED_CONCEPT_DIAGNOSTIC_FAILURE_DETAIL: &str = "job_related_concept_diagnostic_failed";
+const JOB_PROMPT_TEMPLATE_FAILURE_DETAIL: &str = "job_prompt_template_failed";
 const JOB_METADATA_DIAGNOSTIC_FAILURE_DETAIL: &str = "job_metadata_diagnostic_failed";
 const JOB_DOCUMENT_TYPE_DIAGNOSTIC_FAILURE_DETAIL: &str = "job_document_type_diagnostic_failed";
 const JOB_REEMBED_QUEUE_DIAGNOSTIC_FAILURE_DETAIL: &str = "job_reembed_queue_diagnostic_failed";
@@ -250,6 +285,30 @@ fn reembed_all_job_result(
     })
 }
 
+/// Notes queued between re-embed checkpoints.
+const REEMBED_CHECKPOINT_INTERVAL: usize = 100;
+
+/// Resumable state of a bulk re-embed. Notes are queued in id order, so a
+/// restarted job skips every note up to `last_note_id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct ReEmbedAllCheckpoint {
+    last_note_id: uuid::Uuid,
+    queued: usize,
+    failed: usize,
+}
+
+/// Notes still to queue after `checkpoint`, in id order.
+fn reembed_remaining_notes(
+    mut note_ids: Vec<uuid::Uuid>,
+    checkpoint: Option<&ReEmbedAllCheckpoint>,
+) -> Vec<uuid::Uuid> {
+    note_ids.sort_unstable();
+    if let Some(checkpoint) = checkpoint {
+        note_ids.retain(|id| *id > checkpoint.last_note_id);
+    }
+    note_ids
+}
+
 fn reembed_all_no_notes_job_result() -> serde_json::Value {
     serde_json::json!({
         "notes_queued": 0,
@@ -533,6 +592,133 @@ fn title_generation_job_failure(
     JobResult::Failed(TITLE_GENERATION_JOB_FAILURE.to_string())
 }
 
+fn summarization_job_failure(error: impl std::fmt::Display, operation: &'static str) -> JobResult {
+    let diagnostic = error.to_string();
+    warn!(
+        error_len = diagnostic.len(),
+        operation, "Summarization job failed"
+    );
+    JobResult::Failed(SUMMARIZATION_JOB_FAILURE.to_string())
+}
+
+fn translation_job_failure(error: impl std::fmt::Display, operation: &'static str) -> JobResult {
+    let diagnostic = error.to_string();
+    warn!(
+        error_len = diagnostic.len(),
+        operation, "Translation job failed"
+    );
+    JobResult::Failed(TRANSLATION_JOB_FAILURE.to_string())
+}
+
+fn pii_scan_job_failure(error: impl std::fmt::Display, operation: &'static str) -> JobResult {
+    let diagnostic = error.to_string();
+    warn!(
+        error_len = diagnostic.len(),
+        operation, "PII scan job failed"
+    );
+    JobResult::Failed(PII_SCAN_JOB_FAILURE.to_string())
+}
+
+/// Mask PII in model-written revised content when `PII_REDACTION_MODE=mask`,
+/// so a new revision does not bring back values the PII scan masked.
+fn mask_revision_pii(content: &str) -> String {
+    if !PiiRedactionMode::from_env().masks() {
+        return content.to_string();
+    }
+    let found = scan_pii(content);
+    mask_pii(content, &found)
+}
+
+fn citation_extraction_job_failure(
+    error: impl std::fmt::Display,
+    operation: &'static str,
+) -> JobResult {
+    let diagnostic = error.to_string();
+    warn!(
+        error_len = diagnostic.len(),
+        operation, "Citation extraction job failed"
+    );
+    JobResult::Failed(CITATION_EXTRACTION_JOB_FAILURE.to_string())
+}
+
+fn task_extraction_job_failure(
+    error: impl std::fmt::Display,
+    operation: &'static str,
+) -> JobResult {
+    let diagnostic = error.to_string();
+    warn!(
+        error_len = diagnostic.len(),
+        operation, "Task extraction job failed"
+    );
+    JobResult::Failed(TASK_EXTRACTION_JOB_FAILURE.to_string())
+}
+
+/// Fetch a DOI's metadata from Crossref. `Ok(None)` when Crossref does not
+/// know the DOI.
+#[cfg(feature = "crossref")]
+async fn resolve_crossref(
+    client: &reqwest::Client,
+    doi: &str,
+) -> Result<Option<matric_core::ReferenceMetadata>, reqwest::Error> {
+    let mut request = client.get(format!(
+        "{}{}",
+        matric_core::citation::CROSSREF_WORKS_URL,
+        doi
+    ));
+    if let Ok(mailto) = std::env::var(matric_core::citation::ENV_CROSSREF_MAILTO) {
+        if !mailto.trim().is_empty() {
+            request = request.query(&[("mailto", mailto.trim())]);
+        }
+    }
+    let response = request.send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let body: serde_json::Value = response.error_for_status()?.json().await?;
+    Ok(body
+        .get("message")
+        .map(matric_core::ReferenceMetadata::from_crossref))
+}
+
+fn entity_extraction_job_failure(
+    error: impl std::fmt::Display,
+    operation: &'static str,
+) -> JobResult {
+    let diagnostic = error.to_string();
+    warn!(
+        error_len = diagnostic.len(),
+        operation, "Entity extraction job failed"
+    );
+    JobResult::Failed(ENTITY_EXTRACTION_JOB_FAILURE.to_string())
+}
+
+fn digest_job_failure(error: impl std::fmt::Display, operation: &'static str) -> JobResult {
+    let diagnostic = error.to_string();
+    warn!(
+        error_len = diagnostic.len(),
+        operation, "Digest generation job failed"
+    );
+    JobResult::Failed(DIGEST_GENERATION_JOB_FAILURE.to_string())
+}
+
+fn topic_job_failure(error: impl std::fmt::Display, operation: &'static str) -> JobResult {
+    let diagnostic = error.to_string();
+    warn!(
+        error_len = diagnostic.len(),
+        operation, "Topic modeling job failed"
+    );
+    JobResult::Failed(TOPIC_MODELING_JOB_FAILURE.to_string())
+}
+
+fn fine_tuning_job_failure(error: impl std::fmt::Display, operation: &'static str) -> JobResult {
+    let diagnostic = error.to_string();
+    warn!(
+        error_len = diagnostic.len(),
+        operation, "Fine-tuning data generation job failed"
+    );
+    JobResult::Failed(FINE_TUNING_JOB_FAILURE.to_string())
+}
+
 fn context_update_job_failure(error: impl std::fmt::Display, operation: &'static str) -> JobResult {
     let diagnostic = error.to_string();
     warn!(
@@ -649,6 +835,46 @@ fn schema_context(db: &Database, schema: &str) -> Result<SchemaContext, JobResul
     })
 }
 
+/// Load the job prompt template stored for `schema`.
+///
+/// Uses the newest archive version, then the newest global version, then the
+/// built-in. A stored template that cannot be loaded or is invalid falls back
+/// to the built-in so a bad override never stops the job.
+async fn job_prompt_template(db: &Database, schema: &str, key: PromptKey) -> String {
+    match db.prompt_templates.resolve(key, schema).await {
+        Ok(Some(template)) => match validate_prompt_template(key, &template.content) {
+            Ok(()) => return template.content,
+            Err(e) => warn!(
+                error_len = diagnostic_len(&e),
+                prompt_key = key.as_str(),
+                version = template.version,
+                detail = JOB_PROMPT_TEMPLATE_FAILURE_DETAIL,
+                "Stored prompt template is invalid, using built-in"
+            ),
+        },
+        Ok(None) => {}
+        Err(e) => warn!(
+            error_len = diagnostic_len(&e),
+            prompt_key = key.as_str(),
+            detail = JOB_PROMPT_TEMPLATE_FAILURE_DETAIL,
+            "Failed to load prompt template, using built-in"
+        ),
+    }
+    key.builtin().to_string()
+}
+
+/// Render a job prompt from the template stored for `schema`.
+///
+/// See [`job_prompt_template`] for how the template is chosen.
+pub(crate) async fn render_job_prompt(
+    db: &Database,
+    schema: &str,
+    key: PromptKey,
+    values: &[(&str, &str)],
+) -> String {
+    fill_prompt_template(&job_prompt_template(db, schema, key).await, values)
+}
+
 /// Extract an optional model override from a job's payload.
 ///
 /// When present, the job handler should use this model slug instead of the
@@ -783,15 +1009,7 @@ fn chunk_for_extraction(content: &str, max_chars: usize) -> Vec<String> {
 /// profile's native context, which may exceed what VRAM can support.
 fn revision_chunk_size(backend: &OllamaBackend, running_ctx: Option<usize>) -> usize {
     if let Some(profile) = backend.gen_model_profile() {
-        // Priority: actual running context > OLLAMA_CONTEXT_LENGTH > profile native
-        let effective_ctx = running_ctx
-            .or_else(|| {
-                std::env::var("OLLAMA_CONTEXT_LENGTH")
-                    .ok()
-                    .and_then(|v| v.parse::<usize>().ok())
-                    .filter(|&v| v > 0)
-            })
-            .unwrap_or(profile.native_context);
+        let effective_ctx = effective_context_tokens(running_ctx, profile.native_context);
 
         let context_chars = effective_ctx * 4;
         let available =
@@ -811,6 +1029,170 @@ fn revision_chunk_size(backend: &OllamaBackend, running_ctx: Option<usize>) -> u
     matric_core::defaults::REVISION_CHUNK_SIZE_FALLBACK
 }
 
+/// Resolve the generation model's effective context window in tokens.
+///
+/// Priority: actual running context > OLLAMA_CONTEXT_LENGTH > profile native.
+fn effective_context_tokens(running_ctx: Option<usize>, native_context: usize) -> usize {
+    running_ctx
+        .or_else(|| {
+            std::env::var("OLLAMA_CONTEXT_LENGTH")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|&v| v > 0)
+        })
+        .unwrap_or(native_context)
+}
+
+/// Context window assumed for reference budgeting when the generation model
+/// has no profile. Matches the smallest window in `HardwareConfig`.
+const REFERENCE_FALLBACK_CONTEXT_TOKENS: usize = 8_192;
+
+/// Compute the token budget for related-note reference context in Phase 2.
+///
+/// Applies the default `ContextBudget` safety margin to the model's effective
+/// context window, then gives `REVISION_REFERENCE_CONTEXT_SHARE` of the safe
+/// window to reference context.
+fn reference_context_budget(backend: &OllamaBackend, running_ctx: Option<usize>) -> usize {
+    let context_tokens = backend
+        .gen_model_profile()
+        .map(|profile| effective_context_tokens(running_ctx, profile.native_context))
+        .unwrap_or(REFERENCE_FALLBACK_CONTEXT_TOKENS);
+    reference_budget_for_context(context_tokens)
+}
+
+fn reference_budget_for_context(context_tokens: usize) -> usize {
+    let budget = matric_core::ContextBudget::default();
+    let safe_tokens = ((context_tokens as f32 * budget.utilization_factor) as usize)
+        .saturating_sub(budget.reserved_tokens);
+    (safe_tokens as f32 * matric_core::defaults::REVISION_REFERENCE_CONTEXT_SHARE) as usize
+}
+
+/// Tokenizer used to measure reference context. The generation model's own
+/// tokenizer is not available here; cl100k_base is a close enough proxy for
+/// budgeting. `None` when initialization fails, in which case the character
+/// heuristic in `estimate_tokens` is used instead.
+static REFERENCE_TOKENIZER: LazyLock<Option<matric_core::TiktokenTokenizer>> =
+    LazyLock::new(|| matric_core::TiktokenTokenizer::for_embeddings().ok());
+
+fn count_reference_tokens(text: &str) -> usize {
+    match REFERENCE_TOKENIZER.as_ref() {
+        Some(tokenizer) => tokenizer.count_tokens(text),
+        None => matric_core::estimate_tokens(text),
+    }
+}
+
+/// A related note considered for Phase 2 reference context.
+struct ReferenceCandidate {
+    note_id: uuid::Uuid,
+    score: f32,
+    link_count: i64,
+    text: String,
+}
+
+/// Reference context assembled within a token budget.
+#[derive(Debug, Default)]
+struct ReferenceContext {
+    text: String,
+    note_ids: Vec<uuid::Uuid>,
+    tokens: usize,
+    truncated: usize,
+    dropped: usize,
+}
+
+/// Assemble reference context from related notes without exceeding `budget_tokens`.
+///
+/// Candidates are taken most-linked first, breaking ties by similarity, so the
+/// best-connected notes survive when the budget is tight. The first note that
+/// does not fit is cut at a word boundary if at least
+/// `REVISION_REFERENCE_MIN_TOKENS` of it fit; everything after it is dropped.
+fn assemble_reference_context(
+    mut candidates: Vec<ReferenceCandidate>,
+    count_tokens: &dyn Fn(&str) -> usize,
+    budget_tokens: usize,
+    max_notes: usize,
+) -> ReferenceContext {
+    candidates.sort_by(|a, b| {
+        b.link_count
+            .cmp(&a.link_count)
+            .then_with(|| b.score.total_cmp(&a.score))
+    });
+
+    let mut context = ReferenceContext::default();
+    let mut exhausted = false;
+    for candidate in candidates {
+        let text = candidate
+            .text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if text.is_empty() {
+            continue;
+        }
+        if exhausted || context.note_ids.len() >= max_notes {
+            context.dropped += 1;
+            continue;
+        }
+
+        let remaining = budget_tokens.saturating_sub(context.tokens);
+        let line = format!("- {}\n", text);
+        let line_tokens = count_tokens(&line);
+        if line_tokens <= remaining {
+            context.text.push_str(&line);
+            context.tokens += line_tokens;
+            context.note_ids.push(candidate.note_id);
+            continue;
+        }
+
+        exhausted = true;
+        if remaining < matric_core::defaults::REVISION_REFERENCE_MIN_TOKENS {
+            context.dropped += 1;
+            continue;
+        }
+        match truncate_reference_line(&text, remaining, count_tokens) {
+            Some((line, line_tokens)) => {
+                context.text.push_str(&line);
+                context.tokens += line_tokens;
+                context.note_ids.push(candidate.note_id);
+                context.truncated += 1;
+            }
+            None => context.dropped += 1,
+        }
+    }
+    context
+}
+
+/// Cut `text` to the longest word-aligned prefix whose bullet line fits in
+/// `max_tokens`. Returns the line and its token count.
+fn truncate_reference_line(
+    text: &str,
+    max_tokens: usize,
+    count_tokens: &dyn Fn(&str) -> usize,
+) -> Option<(String, usize)> {
+    let boundaries: Vec<usize> = text
+        .char_indices()
+        .filter(|(_, c)| c.is_whitespace())
+        .map(|(i, _)| i)
+        .collect();
+    let line_for = |end: usize| format!("- {}…\n", &text[..end]);
+
+    // Binary search for the last word boundary whose line still fits.
+    let (mut lo, mut hi) = (0, boundaries.len());
+    while lo < hi {
+        let mid = (lo + hi).div_ceil(2);
+        if count_tokens(&line_for(boundaries[mid - 1])) <= max_tokens {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    if lo == 0 {
+        return None;
+    }
+    let line = line_for(boundaries[lo - 1]);
+    let tokens = count_tokens(&line);
+    (tokens >= matric_core::defaults::REVISION_REFERENCE_MIN_TOKENS).then_some((line, tokens))
+}
+
 /// Compute an adaptive timeout for a generation request based on input size.
 ///
 /// Scales linearly with content length, clamped between `base_timeout` (or
@@ -897,28 +1279,14 @@ fn chunk_video_timeline(content: &str, max_chars: usize) -> Vec<String> {
     }
 }
 
-/// Merge multiple JSON array results from chunked extraction, deduplicating by string value.
-/// Skips chunks that fail to parse rather than failing the entire merge.
-fn merge_json_arrays(results: Vec<String>) -> Vec<String> {
+/// Merge concept lists from chunked extraction, deduplicating case-insensitively
+/// and keeping the first spelling seen.
+fn merge_concept_lists(results: Vec<Vec<String>>) -> Vec<String> {
     let mut seen = HashSet::new();
     let mut merged = Vec::new();
-    for raw in results {
-        match parse_json_lenient::<Vec<String>>(&raw) {
-            Ok(items) => {
-                for item in items {
-                    let key = item.to_lowercase();
-                    if seen.insert(key) {
-                        merged.push(item);
-                    }
-                }
-            }
-            Err(e) => {
-                info!(
-                    error_len = diagnostic_len(&e),
-                    detail = JOB_CHUNK_MERGE_PARSE_FAILURE_DETAIL,
-                    "Skipping unparseable chunk result in merge"
-                );
-            }
+    for item in results.into_iter().flatten() {
+        if seen.insert(item.to_lowercase()) {
+            merged.push(item);
         }
     }
     merged
@@ -1181,6 +1549,9 @@ impl JobHandler for AiRevisionHandler {
         if let Err(e) = tx.commit().await {
             return ai_revision_job_failure(e, "fetch_note_commit");
         }
+        if note.note.encrypted {
+            return JobResult::Success(Some(ai_revision_skip_job_result("encrypted_note")));
+        }
 
         let original_content = &note.original.content;
         if original_content.trim().is_empty() {
@@ -1240,6 +1611,10 @@ impl JobHandler for AiRevisionHandler {
             }
         }
 
+        // Revisions of non-English notes are written in the note's language.
+        let language_directive =
+            matric_core::language::prompt_language_directive(note.note.language.as_deref());
+
         // Look up document type for type-aware prompt building.
         // Primary: use the note's assigned document_type_id.
         // Fallback: heuristic detection from content (first 1000 chars).
@@ -1465,7 +1840,7 @@ impl JobHandler for AiRevisionHandler {
             };
 
             // Build prompt based on effective mode and document type
-            let prompt = build_type_aware_prompt(
+            let mut prompt = build_type_aware_prompt(
                 doc_type.as_ref(),
                 effective_mode,
                 chunk_content,
@@ -1474,6 +1849,9 @@ impl JobHandler for AiRevisionHandler {
                 total_chunks,
                 is_video_timeline,
             );
+            if let Some(directive) = &language_directive {
+                prompt.push_str(directive);
+            }
 
             // Adaptive timeout: scale with content size for large documents.
             // When a model override is active, use the trait (their timeout).
@@ -1557,7 +1935,12 @@ impl JobHandler for AiRevisionHandler {
         if let Err(e) = self
             .db
             .notes
-            .update_revised_tx(&mut tx, note_id, &revised, Some(revision_note))
+            .update_revised_tx(
+                &mut tx,
+                note_id,
+                &mask_revision_pii(&revised),
+                Some(revision_note),
+            )
             .await
         {
             return ai_revision_job_failure(e, "save_revision");
@@ -1824,6 +2207,62 @@ impl AiRevisionContextualHandler {
             let _ = tx.commit().await;
         }
     }
+
+    /// Gather reference context candidates for the related notes.
+    ///
+    /// Link counts and longer excerpts are best-effort: when they cannot be
+    /// loaded, candidates fall back to the similarity search snippet with no
+    /// link priority.
+    async fn reference_candidates(
+        &self,
+        schema_ctx: &matric_db::SchemaContext,
+        related_notes: &[matric_core::SearchHit],
+    ) -> Vec<ReferenceCandidate> {
+        let ids: Vec<uuid::Uuid> = related_notes.iter().map(|h| h.note_id).collect();
+        let loaded = match schema_ctx.begin_tx().await {
+            Ok(mut tx) => {
+                let loaded = match self.db.links.link_counts_tx(&mut tx, &ids).await {
+                    Ok(link_counts) => self
+                        .db
+                        .notes
+                        .content_excerpts_tx(
+                            &mut tx,
+                            &ids,
+                            matric_core::defaults::REVISION_REFERENCE_EXCERPT_CHARS as i32,
+                        )
+                        .await
+                        .map(|excerpts| (link_counts, excerpts)),
+                    Err(e) => Err(e),
+                };
+                let _ = tx.commit().await;
+                loaded
+            }
+            Err(e) => Err(e),
+        };
+        let (link_counts, mut excerpts) = loaded.unwrap_or_else(|e| {
+            warn!(
+                error_len = diagnostic_len(&e),
+                detail = JOB_CONTEXT_DISCOVERY_FAILURE_DETAIL,
+                "Failed to load reference context excerpts, using search snippets"
+            );
+            Default::default()
+        });
+
+        related_notes
+            .iter()
+            .filter_map(|hit| {
+                let text = excerpts
+                    .remove(&hit.note_id)
+                    .or_else(|| hit.snippet.clone())?;
+                Some(ReferenceCandidate {
+                    note_id: hit.note_id,
+                    score: hit.score,
+                    link_count: link_counts.get(&hit.note_id).copied().unwrap_or(0),
+                    text,
+                })
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -2073,7 +2512,6 @@ impl JobHandler for AiRevisionContextualHandler {
         };
 
         let related_count = related_notes.len();
-        let related_note_ids: Vec<uuid::Uuid> = related_notes.iter().map(|h| h.note_id).collect();
 
         if related_notes.is_empty() {
             // No related notes found — Phase 1 output stands as final.
@@ -2101,22 +2539,33 @@ impl JobHandler for AiRevisionContextualHandler {
         // --- Phase 2: Contextual re-revision with strong guardrails ---
         ctx.report_progress(60, Some("Generating contextual revision (phase 2)..."));
 
-        // Build reference context from related notes (using original content for snippets)
-        let mut reference_context = String::new();
-        for hit in related_notes.iter().take(MAX_PROMPT_SNIPPETS) {
-            if let Some(snippet) = &hit.snippet {
-                let preview: String = snippet
-                    .chars()
-                    .take(matric_core::defaults::PREVIEW_CONTEXT_SNIPPET)
-                    .collect();
-                reference_context.push_str(&format!("- {}\n", preview));
-            }
-        }
+        // Build reference context from related notes (using original content for
+        // excerpts), fitted to the model's token budget with most-linked notes first.
+        let running_ctx = self.backend.running_context_length().await;
+        let reference_budget = reference_context_budget(&self.backend, running_ctx);
+        let candidates = self.reference_candidates(&schema_ctx, &related_notes).await;
+        let reference = assemble_reference_context(
+            candidates,
+            &count_reference_tokens,
+            reference_budget,
+            MAX_PROMPT_SNIPPETS,
+        );
+        info!(
+            note_id_present = true,
+            reference_budget,
+            reference_tokens = reference.tokens,
+            included = reference.note_ids.len(),
+            truncated = reference.truncated,
+            dropped = reference.dropped,
+            operation = "assemble_reference_context",
+            "Assembled Phase 2 reference context within token budget"
+        );
+        let reference_context = reference.text;
+        let reference_note_ids = reference.note_ids;
 
         // Compute Phase 2 chunk budget, accounting for reference context overhead.
         // The reference context is included in every chunk's prompt, so it reduces
         // the space available for the primary content.
-        let running_ctx = self.backend.running_context_length().await;
         let base_chunk_size = revision_chunk_size(&self.backend, running_ctx);
         let reference_overhead = reference_context.len();
         let chunk_max_phase2 = base_chunk_size
@@ -2209,34 +2658,18 @@ impl JobHandler for AiRevisionContextualHandler {
                 String::new()
             };
 
-            let prompt = format!(
-                r#"You are an intelligent note-taking assistant performing a contextual revision.
-{continuity}{type_hint}
-## PRIMARY CONTENT (this is the note you are revising — your output MUST be a revision of this):
-{phase1}
-
-## REFERENCE CONTEXT (supplementary only — use ONLY if directly relevant to the primary content):
-{context}
-
-STRICT RULES:
-1. Your output MUST be a revision of the PRIMARY CONTENT section above
-2. NEVER replace or override the primary content with reference material
-3. Reference context is supplementary — mention connections ONLY when they genuinely clarify the primary content
-4. If no reference items are relevant to the primary content, output the primary content unchanged
-5. Preserve ALL original meaning and information from the primary content
-6. Do NOT fabricate cross-references that are not genuinely supported by the reference context
-
-What you MAY do:
-- Note genuine connections between the primary content and reference items
-- Add brief contextual annotations where a reference item directly relates
-- Improve organization if the connection adds clarity
-
-Output the revised note in clean markdown format. Do not add any labels, markers, or metadata."#,
-                continuity = continuity_note,
-                type_hint = type_hint,
-                phase1 = chunk_content,
-                context = reference_context
-            );
+            let prompt = render_job_prompt(
+                &self.db,
+                schema,
+                PromptKey::ContextualRevision,
+                &[
+                    ("continuity", &continuity_note),
+                    ("type_hint", &type_hint),
+                    ("primary_content", chunk_content),
+                    ("reference_context", &reference_context),
+                ],
+            )
+            .await;
 
             let chunk_timeout = p2_per_chunk_timeouts[chunk_idx];
             let result = match &overridden {
@@ -2308,7 +2741,12 @@ Output the revised note in clean markdown format. Do not add any labels, markers
         if let Err(e) = self
             .db
             .notes
-            .update_revised_tx(&mut tx, note_id, &revised, Some(revision_note))
+            .update_revised_tx(
+                &mut tx,
+                note_id,
+                &mask_revision_pii(&revised),
+                Some(revision_note),
+            )
             .await
         {
             return ai_contextual_revision_job_failure(e, "save_contextual_revision");
@@ -2323,11 +2761,11 @@ Output the revised note in clean markdown format. Do not add any labels, markers
         if let Ok(Some(chain)) = self.db.provenance.get_chain(note_id).await {
             let rev_id = chain.revision_id;
 
-            if !related_note_ids.is_empty() {
+            if !reference_note_ids.is_empty() {
                 if let Err(e) = self
                     .db
                     .provenance
-                    .record_edges_batch(rev_id, &related_note_ids, &ProvRelation::Used)
+                    .record_edges_batch(rev_id, &reference_note_ids, &ProvRelation::Used)
                     .await
                 {
                     warn!(
@@ -2342,6 +2780,7 @@ Output the revised note in clean markdown format. Do not add any labels, markers
                 let metadata = serde_json::json!({
                     "revision_mode_len": diagnostic_len(format!("{revision_mode:?}")),
                     "related_notes_used": related_count,
+                    "reference_notes_included": reference_note_ids.len(),
                     "revised_length": revised.len(),
                     "context_filtered": context_filter.is_some(),
                 });
@@ -2392,6 +2831,8 @@ pub struct EmbeddingHandler {
     db: Database,
     registry: Arc<ProviderRegistry>,
     usage_meter: Arc<dyn UsageMeter>,
+    /// Coalesces concurrent embedding jobs into shared backend calls.
+    batcher: Option<Arc<EmbeddingBatcher>>,
     #[cfg(test)]
     backend_override: Option<Arc<dyn EmbeddingBackend>>,
 }
@@ -2406,16 +2847,49 @@ impl EmbeddingHandler {
             db,
             registry,
             usage_meter,
+            batcher: None,
             #[cfg(test)]
             backend_override: None,
         }
     }
 
+    /// Batch this handler's backend calls with those of concurrently running
+    /// embedding jobs for the same embedding contract.
+    pub fn with_batcher(mut self, batcher: Arc<EmbeddingBatcher>) -> Self {
+        self.batcher = Some(batcher);
+        self
+    }
+
     #[cfg(test)]
     fn with_backend_override(mut self, backend: Arc<dyn EmbeddingBackend>) -> Self {
         self.backend_override = Some(backend);
         self
     }
+
+    /// Embedding set for non-English notes (`MULTILINGUAL_EMBEDDING_SET`).
+    ///
+    /// Returns `None` for English or undetected languages, when no set is
+    /// configured, and when the configured slug does not exist.
+    async fn multilingual_embedding_set(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        language: Option<&str>,
+    ) -> matric_core::Result<Option<matric_core::EmbeddingSet>> {
+        if matric_core::language::is_english_or_unknown(language) {
+            return Ok(None);
+        }
+        let Some(slug) = matric_core::language::multilingual_embedding_set() else {
+            return Ok(None);
+        };
+        let set = self.db.embedding_sets.get_by_slug_tx(tx, &slug).await?;
+        if set.is_none() {
+            warn!(
+                slug_len = diagnostic_len(&slug),
+                "Configured multilingual embedding set was not found; using the default set"
+            );
+        }
+        Ok(set)
+    }
 }
 
 fn embedding_profile_provider_id(profile: &EmbeddingConfigProfile) -> String {
@@ -2649,7 +3123,39 @@ impl JobHandler for EmbeddingHandler {
             Ok(n) => n,
             Err(e) => return embedding_job_failure(e, "fetch_note"),
         };
-        let target_set = match embedding_set_id {
+        // Encrypted notes keep their content out of the vector index.
+        if note.note.encrypted {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "encrypted_note"
+            })));
+        }
+        // So do notes whose PII findings block indexing.
+        match self.db.pii.blocks_indexing_tx(&mut tx, note_id).await {
+            Ok(true) => {
+                return JobResult::Success(Some(serde_json::json!({
+                    "skipped": true,
+                    "reason": "pii_blocked"
+                })));
+            }
+            Ok(false) => {}
+            Err(error) => return embedding_job_failure(error, "check_pii_block"),
+        }
+        // The document type policy may pin the embedding set and chunker.
+        // An explicit set in the payload (re-embedding a set) still wins.
+        let policy = match note.note.document_type_id {
+            Some(_) => match self
+                .db
+                .document_types
+                .policy_for_note_tx(&mut tx, note_id)
+                .await
+            {
+                Ok(policy) => policy,
+                Err(error) => return embedding_job_failure(error, "resolve_document_type_policy"),
+            },
+            None => matric_core::PipelinePolicy::default(),
+        };
+        let target_set = match embedding_set_id.or(policy.embedding_set_id) {
             Some(set_id) => match self.db.embedding_sets.get_by_id_tx(&mut tx, set_id).await {
                 Ok(Some(set)) => Some(set),
                 Ok(None) => {
@@ -2658,9 +3164,18 @@ impl JobHandler for EmbeddingHandler {
                 }
                 Err(error) => return embedding_job_failure(error, "resolve_embedding_set"),
             },
-            None => match self.db.embedding_sets.get_default_tx(&mut tx).await {
-                Ok(set) => set,
-                Err(error) => return embedding_job_failure(error, "resolve_default_embedding_set"),
+            None => match self
+                .multilingual_embedding_set(&mut tx, note.note.language.as_deref())
+                .await
+            {
+                Ok(Some(set)) => Some(set),
+                Ok(None) => match self.db.embedding_sets.get_default_tx(&mut tx).await {
+                    Ok(set) => set,
+                    Err(error) => {
+                        return embedding_job_failure(error, "resolve_default_embedding_set")
+                    }
+                },
+                Err(error) => return embedding_job_failure(error, "resolve_multilingual_set"),
             },
         };
         let contract_embedding_set_id = target_set.as_ref().map(|set| set.id);
@@ -2730,6 +3245,15 @@ impl JobHandler for EmbeddingHandler {
         .await
         .unwrap_or_default();
 
+        // Transcript segments of audio/video attachments, so chunks carrying
+        // a transcript can be timestamped for playback deep links.
+        let transcript_segments = self
+            .db
+            .embeddings
+            .transcript_segments_tx(&mut tx, note_id)
+            .await
+            .unwrap_or_default();
+
         if let Err(e) = tx.commit().await {
             return embedding_job_failure(e, "fetch_note_commit");
         }
@@ -2816,12 +3340,31 @@ impl JobHandler for EmbeddingHandler {
             ChunkerConfig::default()
         };
 
-        let chunker = SemanticChunker::new(chunker_config);
+        let chunker = matric_db::chunker_for_strategy(
+            policy.chunking_strategy.unwrap_or_default(),
+            chunker_config,
+        );
         let semantic_chunks = chunker.chunk(&content);
+        let media_segments = matric_db::embedding_utils::chunk_media_segments(
+            &content,
+            &semantic_chunks,
+            &transcript_segments,
+        );
         let chunks: Vec<String> = semantic_chunks.into_iter().map(|c| c.text).collect();
         if chunks.is_empty() {
             return JobResult::Success(Some(serde_json::json!({"chunks": 0})));
         }
+        // Checked before the provider is called so a full archive costs no inference.
+        match self
+            .db
+            .archive_quotas
+            .check_embeddings(schema, note_id, embedding_set_id, chunks.len() as i64)
+            .await
+        {
+            Ok(None) => {}
+            Ok(Some(exceeded)) => return JobResult::Failed(exceeded.detail()),
+            Err(error) => return embedding_job_failure(error, "check_archive_quota"),
+        }
 
         ctx.report_progress(50, Some("Generating embeddings..."));
 
@@ -2840,7 +3383,19 @@ impl JobHandler for EmbeddingHandler {
             .unwrap_or(resolved_backend.backend.as_ref());
         #[cfg(not(test))]
         let embedding_backend = resolved_backend.backend.as_ref();
-        let vectors = match embedding_backend.embed_texts(&chunks).await {
+        let embedded = match &self.batcher {
+            Some(batcher) => {
+                batcher
+                    .embed(
+                        &resolved_backend.contract.fingerprint(),
+                        embedding_backend,
+                        chunks.clone(),
+                    )
+                    .await
+            }
+            None => embedding_backend.embed_texts(&chunks).await,
+        };
+        let vectors = match embedded {
             Ok(v) => v,
             Err(e) => {
                 if let Some(usage) = &usage {
@@ -2945,6 +3500,15 @@ impl JobHandler for EmbeddingHandler {
                 )
                 .await
         };
+        let store_result = match store_result {
+            Ok(()) => {
+                self.db
+                    .embeddings
+                    .set_media_segments_tx(&mut tx, note_id, embedding_set_id, &media_segments)
+                    .await
+            }
+            failed => failed,
+        };
         if let Err(store_error) = store_result {
             let rollback_error = tx
                 .rollback()
@@ -3026,6 +3590,8 @@ pub struct TitleGenerationHandler {
     /// Fast model backend (#439).
     fast_backend: Option<OllamaBackend>,
     registry: Arc<ProviderRegistry>,
+    /// Titles already generated for the same note, model, and prompt.
+    cache: InferenceCache,
 }
 
 impl TitleGenerationHandler {
@@ -3034,12 +3600,14 @@ impl TitleGenerationHandler {
         backend: OllamaBackend,
         fast_backend: Option<OllamaBackend>,
         registry: Arc<ProviderRegistry>,
+        cache: InferenceCache,
     ) -> Self {
         Self {
             db,
             backend,
             fast_backend,
             registry,
+            cache,
         }
     }
 
@@ -3175,13 +3743,18 @@ impl JobHandler for TitleGenerationHandler {
             .take(matric_core::defaults::PREVIEW_EMBEDDING)
             .collect();
 
-        let prompt = format!(
-            r#"Generate a concise, descriptive title (3-8 words) for this content. Be specific. Avoid generic words like "Note", "Document", "Text". Output only the title, no quotes or explanation.
-
-Content:
-{}"#,
-            content_preview
-        );
+        let mut prompt = render_job_prompt(
+            &self.db,
+            schema,
+            PromptKey::TitleGeneration,
+            &[("content", &content_preview)],
+        )
+        .await;
+        if let Some(directive) =
+            matric_core::language::prompt_language_directive(note.note.language.as_deref())
+        {
+            prompt.push_str(&directive);
+        }
 
         let clean_title = |raw: String| -> String {
             let cleaned = raw
@@ -3197,7 +3770,20 @@ Content:
                 .to_string()
         };
 
-        let title = match backend.generate(&prompt).await {
+        let cache_key = self.cache.cache_key(
+            note_id,
+            InferenceTask::TitleGeneration,
+            matric_core::GenerationBackend::model_name(backend),
+            &prompt,
+        );
+        let cached: Option<String> = self.cache.get(&cache_key).await;
+        let cache_hit = cached.is_some();
+        let generated = match cached {
+            Some(title) => Ok(title),
+            None => backend.generate(&prompt).await,
+        };
+
+        let title = match generated {
             Ok(t) => {
                 let t = clean_title(t);
                 if t.is_empty() || t.len() < matric_core::defaults::TITLE_MIN_LENGTH {
@@ -3247,57 +3833,2079 @@ Content:
                 return title_generation_job_failure(e, "generate_title");
             }
         };
+        if !cache_hit {
+            self.cache.set(&cache_key, &title).await;
+        }
+
+        ctx.report_progress(80, Some("Saving title..."));
+
+        // Save the title
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return title_generation_job_failure(e, "save_title_begin_tx"),
+        };
+        if let Err(e) = self
+            .db
+            .notes
+            .update_title_tx(&mut tx, note_id, &title)
+            .await
+        {
+            return title_generation_job_failure(e, "save_title");
+        }
+        if let Err(e) = tx.commit().await {
+            return title_generation_job_failure(e, "save_title_commit");
+        }
+
+        // Complete provenance activity (#430)
+        if let Some(act_id) = activity_id {
+            let prov_metadata = serde_json::json!({
+                "title_len": diagnostic_len(&title),
+            });
+            if let Err(e) = self
+                .db
+                .provenance
+                .complete_activity(act_id, None, Some(prov_metadata))
+                .await
+            {
+                warn!(
+                    error_len = diagnostic_len(&e),
+                    detail = JOB_PROVENANCE_WRITE_FAILURE_DETAIL,
+                    "Failed to complete title generation provenance activity"
+                );
+            }
+        }
+
+        info!(
+            note_id_present = true,
+            title_len = diagnostic_len(&title),
+            duration_ms = start.elapsed().as_millis() as u64,
+            detail = JOB_PROVENANCE_WRITE_FAILURE_DETAIL,
+            operation = "complete_title_generation",
+            "Title generated"
+        );
+
+        ctx.report_progress(100, Some("Title generation completed"));
+
+        JobResult::Success(Some(title_generation_job_result(&title)))
+    }
+}
+
+/// Handler for note summarization jobs.
+///
+/// Summarizes the note with the fast model. Content longer than one
+/// extraction chunk is summarized chunk by chunk and the chunk summaries are
+/// reduced hierarchically (see [`map_reduce_summarize`]). The result replaces
+/// the note's stored summary.
+pub struct SummarizationHandler {
+    db: Database,
+    backend: OllamaBackend,
+    fast_backend: Option<OllamaBackend>,
+    registry: Arc<ProviderRegistry>,
+}
+
+impl SummarizationHandler {
+    pub fn new(
+        db: Database,
+        backend: OllamaBackend,
+        fast_backend: Option<OllamaBackend>,
+        registry: Arc<ProviderRegistry>,
+    ) -> Self {
+        Self {
+            db,
+            backend,
+            fast_backend,
+            registry,
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler for SummarizationHandler {
+    fn job_type(&self) -> JobType {
+        JobType::Summarization
+    }
+
+    #[instrument(
+        skip(self, ctx),
+        fields(subsystem = "jobs", component = "summarization", op = "execute")
+    )]
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let start = Instant::now();
+        let note_id = match ctx.note_id() {
+            Some(id) => id,
+            None => return JobResult::Failed("No note_id provided".into()),
+        };
+
+        let schema = extract_schema(&ctx);
+        let model_override = extract_model_override(&ctx);
+        let schema_ctx = match schema_context(&self.db, schema) {
+            Ok(ctx) => ctx,
+            Err(e) => return e,
+        };
+
+        let overridden = match resolve_gen_backend(&self.registry, model_override.as_deref()) {
+            Ok(b) => b,
+            Err(e) => return e,
+        };
+
+        ctx.report_progress(10, Some("Fetching note..."));
+
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return summarization_job_failure(e, "fetch_note_begin_tx"),
+        };
+        let note = match self.db.notes.fetch_tx(&mut tx, note_id).await {
+            Ok(n) => n,
+            Err(e) => return summarization_job_failure(e, "fetch_note"),
+        };
+        tx.commit().await.ok();
+
+        if note.note.encrypted {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "encrypted_note"
+            })));
+        }
+
+        let content: &str = if !note.revised.content.is_empty() {
+            &note.revised.content
+        } else {
+            &note.original.content
+        };
+        if content.trim().chars().count() < matric_core::defaults::SUMMARIZATION_MIN_CHARS {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "too_short"
+            })));
+        }
+
+        let fast = if overridden.is_none() {
+            self.fast_backend.as_ref()
+        } else {
+            None
+        };
+        let backend: &dyn GenerationBackend = match (&overridden, fast) {
+            (Some(b), _) => b.as_ref(),
+            (None, Some(f)) => f,
+            (None, None) => &self.backend,
+        };
+        let chunk_size = extraction_chunk_size(match &overridden {
+            Some(_) => None,
+            None => Some(fast.unwrap_or(&self.backend)),
+        });
+        let chunks = chunk_for_extraction(content, chunk_size);
+        let model = backend.model_name().to_string();
+
+        let activity_id = self
+            .db
+            .provenance
+            .start_activity(note_id, "summarization", Some(&model))
+            .await
+            .ok();
+
+        let map_template = job_prompt_template(&self.db, schema, PromptKey::Summarization).await;
+        let reduce_template = job_prompt_template(&self.db, schema, PromptKey::SummaryReduce).await;
+        let title_hint = note
+            .note
+            .title
+            .as_deref()
+            .map(|t| format!("Title: {t}\n\n"))
+            .unwrap_or_default();
+
+        ctx.report_progress(
+            20,
+            Some(&format!("Summarizing {} chunk(s)...", chunks.len())),
+        );
+        let output = match map_reduce_summarize(
+            backend,
+            &chunks,
+            &SummarizeConfig::default(),
+            |chunk| {
+                fill_prompt_template(
+                    &map_template,
+                    &[("content", chunk), ("title_hint", &title_hint)],
+                )
+            },
+            |summaries| {
+                fill_prompt_template(
+                    &reduce_template,
+                    &[("summaries", summaries), ("title_hint", &title_hint)],
+                )
+            },
+        )
+        .await
+        {
+            Ok(output) => output,
+            Err(e) => return summarization_job_failure(e, "generate_summary"),
+        };
+
+        ctx.report_progress(90, Some("Saving summary..."));
+
+        let chunk_count = i32::try_from(output.chunk_count).unwrap_or(i32::MAX);
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return summarization_job_failure(e, "save_summary_begin_tx"),
+        };
+        if let Err(e) = self
+            .db
+            .summaries
+            .upsert_tx(&mut tx, note_id, &output.summary, Some(&model), chunk_count)
+            .await
+        {
+            return summarization_job_failure(e, "save_summary");
+        }
+        if let Err(e) = tx.commit().await {
+            return summarization_job_failure(e, "save_summary_commit");
+        }
+
+        let result = serde_json::json!({
+            "summary_len": diagnostic_len(&output.summary),
+            "chunk_count": output.chunk_count,
+            "levels": output.levels,
+            "calls": output.calls,
+        });
+        if let Some(act_id) = activity_id {
+            if let Err(e) = self
+                .db
+                .provenance
+                .complete_activity(act_id, None, Some(result.clone()))
+                .await
+            {
+                warn!(
+                    error_len = diagnostic_len(&e),
+                    detail = JOB_PROVENANCE_WRITE_FAILURE_DETAIL,
+                    "Failed to complete summarization provenance activity"
+                );
+            }
+        }
+
+        info!(
+            note_id_present = true,
+            summary_len = diagnostic_len(&output.summary),
+            chunk_count = output.chunk_count,
+            levels = output.levels,
+            duration_ms = start.elapsed().as_millis() as u64,
+            operation = "complete_summarization",
+            "Note summarized"
+        );
+
+        ctx.report_progress(100, Some("Summarization completed"));
+
+        JobResult::Success(Some(result))
+    }
+}
+
+/// Handler for note translation jobs.
+///
+/// Translates notes whose detected language differs from their archive's
+/// target language, using the standard generation model one chunk at a time.
+/// The result replaces the note's stored translation; the original and
+/// revised content are not touched. Archives without translation enabled,
+/// and notes already in the target language, are skipped.
+pub struct TranslationHandler {
+    db: Database,
+    backend: OllamaBackend,
+    registry: Arc<ProviderRegistry>,
+}
+
+impl TranslationHandler {
+    pub fn new(db: Database, backend: OllamaBackend, registry: Arc<ProviderRegistry>) -> Self {
+        Self {
+            db,
+            backend,
+            registry,
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler for TranslationHandler {
+    fn job_type(&self) -> JobType {
+        JobType::Translation
+    }
+
+    #[instrument(
+        skip(self, ctx),
+        fields(subsystem = "jobs", component = "translation", op = "execute")
+    )]
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let start = Instant::now();
+        let note_id = match ctx.note_id() {
+            Some(id) => id,
+            None => return JobResult::Failed("No note_id provided".into()),
+        };
+
+        let schema = extract_schema(&ctx);
+        let model_override = extract_model_override(&ctx);
+        let schema_ctx = match schema_context(&self.db, schema) {
+            Ok(ctx) => ctx,
+            Err(e) => return e,
+        };
+
+        let setting = match self.db.translations.effective_setting(schema).await {
+            Ok(s) => s,
+            Err(e) => return translation_job_failure(e, "load_translation_setting"),
+        };
+        if !setting.enabled {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "translation_disabled"
+            })));
+        }
+
+        let overridden = match resolve_gen_backend(&self.registry, model_override.as_deref()) {
+            Ok(b) => b,
+            Err(e) => return e,
+        };
+
+        ctx.report_progress(10, Some("Fetching note..."));
+
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return translation_job_failure(e, "fetch_note_begin_tx"),
+        };
+        let note = match self.db.notes.fetch_tx(&mut tx, note_id).await {
+            Ok(n) => n,
+            Err(e) => return translation_job_failure(e, "fetch_note"),
+        };
+        tx.commit().await.ok();
+
+        if note.note.encrypted {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "encrypted_note"
+            })));
+        }
+
+        let source_language = match note.note.language.as_deref() {
+            Some(lang) if setting.translates(Some(lang)) => lang.to_string(),
+            language => {
+                // The note is now in the target language, or its language is
+                // unknown; a translation of an earlier version no longer applies.
+                let mut tx = match schema_ctx.begin_tx().await {
+                    Ok(t) => t,
+                    Err(e) => return translation_job_failure(e, "clear_translation_begin_tx"),
+                };
+                if let Err(e) = self.db.translations.delete_tx(&mut tx, note_id).await {
+                    return translation_job_failure(e, "clear_translation");
+                }
+                if let Err(e) = tx.commit().await {
+                    return translation_job_failure(e, "clear_translation_commit");
+                }
+                let reason = if language.is_some() {
+                    "target_language"
+                } else {
+                    "language_unknown"
+                };
+                return JobResult::Success(Some(serde_json::json!({
+                    "skipped": true,
+                    "reason": reason
+                })));
+            }
+        };
+
+        let content: &str = if !note.revised.content.is_empty() {
+            &note.revised.content
+        } else {
+            &note.original.content
+        };
+        if content.trim().is_empty() {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "empty_content"
+            })));
+        }
+
+        let backend: &dyn GenerationBackend = match &overridden {
+            Some(b) => b.as_ref(),
+            None => &self.backend,
+        };
+        let chunk_size = extraction_chunk_size(match &overridden {
+            Some(_) => None,
+            None => Some(&self.backend),
+        });
+        // No overlap: translated chunks are joined back into one text.
+        let chunks = chunk_for_revision(content, chunk_size, 0);
+        let model = backend.model_name().to_string();
+
+        let activity_id = self
+            .db
+            .provenance
+            .start_activity(note_id, "translation", Some(&model))
+            .await
+            .ok();
+
+        let template = job_prompt_template(&self.db, schema, PromptKey::Translation).await;
+        let source_name = matric_core::language::language_name(&source_language)
+            .unwrap_or("the original language");
+        let target_name = matric_core::language::language_name(&setting.target_language)
+            .unwrap_or(setting.target_language.as_str());
+
+        let mut translated = Vec::with_capacity(chunks.len());
+        for (i, chunk) in chunks.iter().enumerate() {
+            let progress = 20 + (60 * i / chunks.len()) as i32;
+            ctx.report_progress(
+                progress,
+                Some(&format!("Translating chunk {}/{}...", i + 1, chunks.len())),
+            );
+            let prompt = fill_prompt_template(
+                &template,
+                &[
+                    ("content", chunk),
+                    ("source_language", source_name),
+                    ("target_language", target_name),
+                ],
+            );
+            match backend.generate(&prompt).await {
+                Ok(text) => translated.push(text.trim().to_string()),
+                Err(e) => return translation_job_failure(e, "generate_translation"),
+            }
+        }
+        let translation = translated.join("\n\n");
+        if translation.trim().is_empty() {
+            return translation_job_failure("empty model output", "generate_translation");
+        }
+
+        ctx.report_progress(90, Some("Saving translation..."));
+
+        let chunk_count = i32::try_from(chunks.len()).unwrap_or(i32::MAX);
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return translation_job_failure(e, "save_translation_begin_tx"),
+        };
+        if let Err(e) = self
+            .db
+            .translations
+            .upsert_tx(
+                &mut tx,
+                note_id,
+                &source_language,
+                &setting.target_language,
+                &translation,
+                Some(&model),
+                chunk_count,
+            )
+            .await
+        {
+            return translation_job_failure(e, "save_translation");
+        }
+        if let Err(e) = tx.commit().await {
+            return translation_job_failure(e, "save_translation_commit");
+        }
+
+        let result = serde_json::json!({
+            "translation_len": diagnostic_len(&translation),
+            "source_language": source_language,
+            "target_language": setting.target_language,
+            "chunk_count": chunks.len(),
+        });
+        if let Some(act_id) = activity_id {
+            if let Err(e) = self
+                .db
+                .provenance
+                .complete_activity(act_id, None, Some(result.clone()))
+                .await
+            {
+                warn!(
+                    error_len = diagnostic_len(&e),
+                    detail = JOB_PROVENANCE_WRITE_FAILURE_DETAIL,
+                    "Failed to complete translation provenance activity"
+                );
+            }
+        }
+
+        info!(
+            note_id_present = true,
+            translation_len = diagnostic_len(&translation),
+            chunk_count = chunks.len(),
+            duration_ms = start.elapsed().as_millis() as u64,
+            operation = "complete_translation",
+            "Note translated"
+        );
+
+        ctx.report_progress(100, Some("Translation completed"));
+
+        JobResult::Success(Some(result))
+    }
+}
+
+/// Handler for PII scan jobs.
+///
+/// Scans a note's original content and its attachments' extracted text for
+/// email addresses, phone numbers, payment cards, and national IDs, adding
+/// the fast model's suggestions when `PII_LLM_ASSIST` is enabled, and
+/// replaces the note's findings. In `mask` mode the revised copy is rewritten
+/// with the values masked; in `block_indexing` mode a note with findings is
+/// kept out of full-text search and loses its embeddings.
+pub struct PiiScanHandler {
+    db: Database,
+    backend: OllamaBackend,
+    fast_backend: Option<OllamaBackend>,
+    registry: Arc<ProviderRegistry>,
+}
+
+impl PiiScanHandler {
+    pub fn new(
+        db: Database,
+        backend: OllamaBackend,
+        fast_backend: Option<OllamaBackend>,
+        registry: Arc<ProviderRegistry>,
+    ) -> Self {
+        Self {
+            db,
+            backend,
+            fast_backend,
+            registry,
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler for PiiScanHandler {
+    fn job_type(&self) -> JobType {
+        JobType::PiiScan
+    }
+
+    #[instrument(
+        skip(self, ctx),
+        fields(subsystem = "jobs", component = "pii_scan", op = "execute")
+    )]
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let start = Instant::now();
+        let note_id = match ctx.note_id() {
+            Some(id) => id,
+            None => return JobResult::Failed("No note_id provided".into()),
+        };
+
+        let mode = PiiRedactionMode::from_env();
+        if !mode.scans() {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "pii_scan_disabled"
+            })));
+        }
+
+        let schema = extract_schema(&ctx);
+        let model_override = extract_model_override(&ctx);
+        let schema_ctx = match schema_context(&self.db, schema) {
+            Ok(ctx) => ctx,
+            Err(e) => return e,
+        };
+
+        ctx.report_progress(10, Some("Fetching note..."));
+
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return pii_scan_job_failure(e, "fetch_note_begin_tx"),
+        };
+        let note = match self.db.notes.fetch_tx(&mut tx, note_id).await {
+            Ok(n) => n,
+            Err(e) => return pii_scan_job_failure(e, "fetch_note"),
+        };
+        let attachment_texts = match self.db.pii.attachment_texts_tx(&mut tx, note_id).await {
+            Ok(texts) => texts,
+            Err(e) => return pii_scan_job_failure(e, "fetch_attachment_text"),
+        };
+        tx.commit().await.ok();
+
+        if note.note.encrypted {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "encrypted_note"
+            })));
+        }
+
+        ctx.report_progress(30, Some("Scanning for personal data..."));
+
+        let original = note.original.content.as_str();
+        let mut content_matches = scan_pii(original);
+        let mut attachment_matches: Vec<(uuid::Uuid, Vec<PiiMatch>)> = attachment_texts
+            .iter()
+            .map(|(id, text)| (*id, scan_pii(text)))
+            .collect();
+
+        let llm_assisted = llm_assist_enabled();
+        let mut llm_values: Vec<(PiiKind, String)> = Vec::new();
+        let mut model = None;
+        if llm_assisted {
+            let overridden = match resolve_gen_backend(&self.registry, model_override.as_deref()) {
+                Ok(b) => b,
+                Err(e) => return e,
+            };
+            let fast = if overridden.is_none() {
+                self.fast_backend.as_ref()
+            } else {
+                None
+            };
+            let backend: &dyn GenerationBackend = match (&overridden, fast) {
+                (Some(b), _) => b.as_ref(),
+                (None, Some(f)) => f,
+                (None, None) => &self.backend,
+            };
+            let chunk_size = extraction_chunk_size(match &overridden {
+                Some(_) => None,
+                None => Some(fast.unwrap_or(&self.backend)),
+            });
+            model = Some(backend.model_name().to_string());
+
+            let template = job_prompt_template(&self.db, schema, PromptKey::PiiDetection).await;
+            let config = ConstrainedConfig::default();
+            let mut texts = vec![original];
+            texts.extend(attachment_texts.iter().map(|(_, t)| t.as_str()));
+            for text in texts {
+                for chunk in chunk_for_extraction(text, chunk_size) {
+                    let prompt = fill_prompt_template(&template, &[("content", &chunk)]);
+                    match detect_pii(backend, &prompt, &config).await {
+                        Ok(found) => {
+                            for value in found {
+                                if !llm_values.contains(&value) {
+                                    llm_values.push(value);
+                                }
+                            }
+                        }
+                        Err(e) => return pii_scan_job_failure(e, "detect_pii"),
+                    }
+                }
+            }
+
+            let located = |text: &str| -> Vec<PiiMatch> {
+                llm_values
+                    .iter()
+                    .flat_map(|(kind, value)| locate_pii(text, *kind, value))
+                    .collect()
+            };
+            merge_pii_matches(&mut content_matches, located(original));
+            for ((_, matches), (_, text)) in attachment_matches.iter_mut().zip(&attachment_texts) {
+                merge_pii_matches(matches, located(text));
+            }
+        }
+        attachment_matches.retain(|(_, matches)| !matches.is_empty());
+
+        let finding_count = content_matches.len()
+            + attachment_matches
+                .iter()
+                .map(|(_, matches)| matches.len())
+                .sum::<usize>();
+        let blocks_indexing = mode.blocks_indexing() && finding_count > 0;
+
+        // Mask the revised copy, which may differ from the original; values
+        // the model pointed out in the original are masked wherever they occur.
+        let masked_revision = if mode.masks() && !note.revised.content.is_empty() {
+            let revised = note.revised.content.as_str();
+            let mut matches = scan_pii(revised);
+            merge_pii_matches(
+                &mut matches,
+                llm_values
+                    .iter()
+                    .flat_map(|(kind, value)| locate_pii(revised, *kind, value))
+                    .collect::<Vec<_>>(),
+            );
+            (!matches.is_empty()).then(|| mask_pii(revised, &matches))
+        } else {
+            None
+        };
+
+        ctx.report_progress(80, Some("Saving findings..."));
+
+        let activity_id = self
+            .db
+            .provenance
+            .start_activity(note_id, "pii_scan", model.as_deref())
+            .await
+            .ok();
+
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return pii_scan_job_failure(e, "save_findings_begin_tx"),
+        };
+        let stored = match self
+            .db
+            .pii
+            .replace_note_findings_tx(
+                &mut tx,
+                note_id,
+                &content_matches,
+                &attachment_matches,
+                blocks_indexing,
+            )
+            .await
+        {
+            Ok(n) => n,
+            Err(e) => return pii_scan_job_failure(e, "save_findings"),
+        };
+        if let Some(masked) = &masked_revision {
+            if let Err(e) = self
+                .db
+                .notes
+                .update_revised_tx(&mut tx, note_id, masked, Some("Masked personal data"))
+                .await
+            {
+                return pii_scan_job_failure(e, "mask_revision");
+            }
+        }
+        if blocks_indexing {
+            if let Err(e) = self
+                .db
+                .pii
+                .delete_note_embeddings_tx(&mut tx, note_id)
+                .await
+            {
+                return pii_scan_job_failure(e, "delete_embeddings");
+            }
+        }
+        if let Err(e) = tx.commit().await {
+            return pii_scan_job_failure(e, "save_findings_commit");
+        }
+
+        let mut by_kind: BTreeMap<String, usize> = BTreeMap::new();
+        for m in content_matches.iter().chain(
+            attachment_matches
+                .iter()
+                .flat_map(|(_, matches)| matches.iter()),
+        ) {
+            *by_kind.entry(m.kind.to_string()).or_default() += 1;
+        }
+        let result = serde_json::json!({
+            "finding_count": stored,
+            "by_kind": by_kind,
+            "attachments_scanned": attachment_texts.len(),
+            "mode": mode.as_str(),
+            "llm_assisted": llm_assisted,
+            "masked": masked_revision.is_some(),
+            "blocks_indexing": blocks_indexing,
+        });
+        if let Some(act_id) = activity_id {
+            if let Err(e) = self
+                .db
+                .provenance
+                .complete_activity(act_id, None, Some(result.clone()))
+                .await
+            {
+                warn!(
+                    error_len = diagnostic_len(&e),
+                    detail = JOB_PROVENANCE_WRITE_FAILURE_DETAIL,
+                    "Failed to complete PII scan provenance activity"
+                );
+            }
+        }
+
+        info!(
+            note_id_present = true,
+            finding_count = stored,
+            masked = masked_revision.is_some(),
+            blocks_indexing,
+            duration_ms = start.elapsed().as_millis() as u64,
+            operation = "complete_pii_scan",
+            "PII scan completed"
+        );
+
+        ctx.report_progress(100, Some("PII scan completed"));
+
+        JobResult::Success(Some(result))
+    }
+}
+
+/// Handler for citation extraction jobs.
+///
+/// Finds the DOIs, arXiv IDs, URLs and BibTeX entries cited in a note's
+/// original content and replaces the note's references. When built with the
+/// `crossref` feature, DOIs not yet resolved are completed from Crossref, at
+/// most `MAX_CROSSREF_LOOKUPS_PER_JOB` per run; a failed lookup leaves the
+/// reference unresolved for a later run.
+pub struct CitationExtractionHandler {
+    db: Database,
+}
+
+impl CitationExtractionHandler {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl JobHandler for CitationExtractionHandler {
+    fn job_type(&self) -> JobType {
+        JobType::CitationExtraction
+    }
+
+    #[instrument(
+        skip(self, ctx),
+        fields(subsystem = "jobs", component = "citation_extraction", op = "execute")
+    )]
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let start = Instant::now();
+        let note_id = match ctx.note_id() {
+            Some(id) => id,
+            None => return JobResult::Failed("No note_id provided".into()),
+        };
+
+        let schema = extract_schema(&ctx);
+        let schema_ctx = match schema_context(&self.db, schema) {
+            Ok(ctx) => ctx,
+            Err(e) => return e,
+        };
+
+        ctx.report_progress(10, Some("Fetching note..."));
+
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return citation_extraction_job_failure(e, "fetch_note_begin_tx"),
+        };
+        let note = match self.db.notes.fetch_tx(&mut tx, note_id).await {
+            Ok(n) => n,
+            Err(e) => return citation_extraction_job_failure(e, "fetch_note"),
+        };
+        tx.commit().await.ok();
+
+        if note.note.encrypted {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "encrypted_note"
+            })));
+        }
+
+        ctx.report_progress(30, Some("Extracting citations..."));
+
+        let citations = extract_citations(&note.original.content);
+        let mut by_kind: BTreeMap<&str, usize> = BTreeMap::new();
+        for citation in &citations {
+            *by_kind.entry(citation.kind.as_str()).or_default() += 1;
+        }
+
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return citation_extraction_job_failure(e, "save_references_begin_tx"),
+        };
+        let stored = match self
+            .db
+            .references
+            .replace_for_note_tx(&mut tx, note_id, &citations)
+            .await
+        {
+            Ok(n) => n,
+            Err(e) => return citation_extraction_job_failure(e, "save_references"),
+        };
+        if let Err(e) = tx.commit().await {
+            return citation_extraction_job_failure(e, "save_references_commit");
+        }
+
+        #[allow(unused_mut)]
+        let mut resolved = 0usize;
+        #[cfg(feature = "crossref")]
+        if by_kind.contains_key("doi") {
+            ctx.report_progress(60, Some("Resolving DOIs..."));
+            resolved = match self.resolve_dois(&schema_ctx, note_id).await {
+                Ok(n) => n,
+                Err(e) => return citation_extraction_job_failure(e, "resolve_dois"),
+            };
+        }
+
+        let result = serde_json::json!({
+            "citation_count": stored,
+            "by_kind": by_kind,
+            "crossref_enabled": cfg!(feature = "crossref"),
+            "resolved_count": resolved,
+        });
+
+        info!(
+            note_id_present = true,
+            citation_count = stored,
+            resolved_count = resolved,
+            duration_ms = start.elapsed().as_millis() as u64,
+            operation = "complete_citation_extraction",
+            "Citation extraction completed"
+        );
+
+        ctx.report_progress(100, Some("Citation extraction completed"));
+
+        JobResult::Success(Some(result))
+    }
+}
+
+#[cfg(feature = "crossref")]
+impl CitationExtractionHandler {
+    /// Complete the note's unresolved DOI references from Crossref. Returns
+    /// how many were resolved.
+    async fn resolve_dois(
+        &self,
+        schema_ctx: &SchemaContext,
+        note_id: uuid::Uuid,
+    ) -> matric_core::Result<usize> {
+        let mut tx = schema_ctx.begin_tx().await?;
+        let pending = self
+            .db
+            .references
+            .unresolved_dois_tx(
+                &mut tx,
+                note_id,
+                matric_core::citation::MAX_CROSSREF_LOOKUPS_PER_JOB as i64,
+            )
+            .await?;
+        tx.commit().await.map_err(matric_core::Error::Database)?;
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .user_agent(concat!("fortemi/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| matric_core::Error::Internal(e.to_string()))?;
+        let mut resolved = Vec::new();
+        for (reference_id, doi) in pending {
+            match resolve_crossref(&client, &doi).await {
+                Ok(Some(metadata)) => resolved.push((reference_id, metadata)),
+                Ok(None) => {}
+                Err(e) => warn!(
+                    error_len = e.to_string().len(),
+                    operation = "crossref_lookup",
+                    "Crossref lookup failed; reference left unresolved"
+                ),
+            }
+        }
+
+        let mut tx = schema_ctx.begin_tx().await?;
+        let now = Utc::now();
+        for (reference_id, metadata) in &resolved {
+            self.db
+                .references
+                .set_resolved_tx(&mut tx, *reference_id, metadata, now)
+                .await?;
+        }
+        tx.commit().await.map_err(matric_core::Error::Database)?;
+        Ok(resolved.len())
+    }
+}
+
+/// Handler for task extraction jobs.
+///
+/// Records the Markdown checkboxes in a note's original content as tasks,
+/// adding the action items the fast model finds in prose when
+/// `TASK_LLM_EXTRACTION` is enabled, and replaces the note's tasks. Relative
+/// due dates ("by Friday") are read against the note's creation day, so they
+/// stay put when the note is processed again.
+pub struct TaskExtractionHandler {
+    db: Database,
+    backend: OllamaBackend,
+    fast_backend: Option<OllamaBackend>,
+    registry: Arc<ProviderRegistry>,
+}
+
+impl TaskExtractionHandler {
+    pub fn new(
+        db: Database,
+        backend: OllamaBackend,
+        fast_backend: Option<OllamaBackend>,
+        registry: Arc<ProviderRegistry>,
+    ) -> Self {
+        Self {
+            db,
+            backend,
+            fast_backend,
+            registry,
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler for TaskExtractionHandler {
+    fn job_type(&self) -> JobType {
+        JobType::TaskExtraction
+    }
+
+    #[instrument(
+        skip(self, ctx),
+        fields(subsystem = "jobs", component = "task_extraction", op = "execute")
+    )]
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let start = Instant::now();
+        let note_id = match ctx.note_id() {
+            Some(id) => id,
+            None => return JobResult::Failed("No note_id provided".into()),
+        };
+
+        let schema = extract_schema(&ctx);
+        let model_override = extract_model_override(&ctx);
+        let schema_ctx = match schema_context(&self.db, schema) {
+            Ok(ctx) => ctx,
+            Err(e) => return e,
+        };
+
+        ctx.report_progress(10, Some("Fetching note..."));
+
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return task_extraction_job_failure(e, "fetch_note_begin_tx"),
+        };
+        let note = match self.db.notes.fetch_tx(&mut tx, note_id).await {
+            Ok(n) => n,
+            Err(e) => return task_extraction_job_failure(e, "fetch_note"),
+        };
+        tx.commit().await.ok();
+
+        if note.note.encrypted {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "encrypted_note"
+            })));
+        }
+
+        ctx.report_progress(30, Some("Extracting tasks..."));
+
+        let content = note.original.content.as_str();
+        let written_on = note.note.created_at_utc.date_naive();
+        let mut tasks = parse_checkbox_tasks(content, written_on);
+        let checkbox_count = tasks.len();
+
+        let llm_assisted = llm_extraction_enabled() && !content.trim().is_empty();
+        let mut model = None;
+        if llm_assisted {
+            ctx.report_progress(50, Some("Finding action items..."));
+            let overridden = match resolve_gen_backend(&self.registry, model_override.as_deref()) {
+                Ok(b) => b,
+                Err(e) => return e,
+            };
+            let fast = if overridden.is_none() {
+                self.fast_backend.as_ref()
+            } else {
+                None
+            };
+            let backend: &dyn GenerationBackend = match (&overridden, fast) {
+                (Some(b), _) => b.as_ref(),
+                (None, Some(f)) => f,
+                (None, None) => &self.backend,
+            };
+            let chunk_size = extraction_chunk_size(match &overridden {
+                Some(_) => None,
+                None => Some(fast.unwrap_or(&self.backend)),
+            });
+            model = Some(backend.model_name().to_string());
+
+            let template = job_prompt_template(&self.db, schema, PromptKey::TaskExtraction).await;
+            let config = ConstrainedConfig::default();
+            let today = written_on.to_string();
+            for chunk in chunk_for_extraction(content, chunk_size) {
+                let prompt =
+                    fill_prompt_template(&template, &[("content", &chunk), ("today", &today)]);
+                match extract_action_items(backend, &prompt, &config).await {
+                    Ok(items) => merge_action_items(&mut tasks, items, written_on),
+                    Err(e) => return task_extraction_job_failure(e, "extract_action_items"),
+                }
+            }
+        }
+
+        ctx.report_progress(80, Some("Saving tasks..."));
+
+        let activity_id = self
+            .db
+            .provenance
+            .start_activity(note_id, "task_extraction", model.as_deref())
+            .await
+            .ok();
+
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return task_extraction_job_failure(e, "save_tasks_begin_tx"),
+        };
+        let stored = match self
+            .db
+            .tasks
+            .replace_for_note_tx(&mut tx, note_id, &tasks)
+            .await
+        {
+            Ok(n) => n,
+            Err(e) => return task_extraction_job_failure(e, "save_tasks"),
+        };
+        if let Err(e) = tx.commit().await {
+            return task_extraction_job_failure(e, "save_tasks_commit");
+        }
+
+        let result = serde_json::json!({
+            "task_count": stored,
+            "checkbox_count": checkbox_count,
+            "action_item_count": stored - checkbox_count,
+            "due_count": tasks.iter().filter(|t| t.due_date.is_some()).count(),
+            "llm_assisted": llm_assisted,
+        });
+        if let Some(act_id) = activity_id {
+            if let Err(e) = self
+                .db
+                .provenance
+                .complete_activity(act_id, None, Some(result.clone()))
+                .await
+            {
+                warn!(
+                    error_len = diagnostic_len(&e),
+                    detail = JOB_PROVENANCE_WRITE_FAILURE_DETAIL,
+                    "Failed to complete task extraction provenance activity"
+                );
+            }
+        }
+
+        info!(
+            note_id_present = true,
+            task_count = stored,
+            llm_assisted,
+            duration_ms = start.elapsed().as_millis() as u64,
+            operation = "complete_task_extraction",
+            "Task extraction completed"
+        );
+
+        ctx.report_progress(100, Some("Task extraction completed"));
+
+        JobResult::Success(Some(result))
+    }
+}
+
+/// Handler for LLM entity extraction jobs.
+///
+/// Asks the fast model for the people, organizations, places, and dates the
+/// note names, one extraction chunk at a time, and replaces the note's edges
+/// in the archive's entity registry with the merged result.
+pub struct EntityExtractionHandler {
+    db: Database,
+    backend: OllamaBackend,
+    fast_backend: Option<OllamaBackend>,
+    registry: Arc<ProviderRegistry>,
+}
+
+impl EntityExtractionHandler {
+    pub fn new(
+        db: Database,
+        backend: OllamaBackend,
+        fast_backend: Option<OllamaBackend>,
+        registry: Arc<ProviderRegistry>,
+    ) -> Self {
+        Self {
+            db,
+            backend,
+            fast_backend,
+            registry,
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler for EntityExtractionHandler {
+    fn job_type(&self) -> JobType {
+        JobType::EntityExtraction
+    }
+
+    #[instrument(
+        skip(self, ctx),
+        fields(subsystem = "jobs", component = "entity_extraction", op = "execute")
+    )]
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let start = Instant::now();
+        let note_id = match ctx.note_id() {
+            Some(id) => id,
+            None => return JobResult::Failed("No note_id provided".into()),
+        };
+
+        let schema = extract_schema(&ctx);
+        let model_override = extract_model_override(&ctx);
+        let schema_ctx = match schema_context(&self.db, schema) {
+            Ok(ctx) => ctx,
+            Err(e) => return e,
+        };
+
+        let overridden = match resolve_gen_backend(&self.registry, model_override.as_deref()) {
+            Ok(b) => b,
+            Err(e) => return e,
+        };
+
+        ctx.report_progress(10, Some("Fetching note..."));
+
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return entity_extraction_job_failure(e, "fetch_note_begin_tx"),
+        };
+        let note = match self.db.notes.fetch_tx(&mut tx, note_id).await {
+            Ok(n) => n,
+            Err(e) => return entity_extraction_job_failure(e, "fetch_note"),
+        };
+        tx.commit().await.ok();
+
+        if note.note.encrypted {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "encrypted_note"
+            })));
+        }
+
+        let content: &str = if !note.revised.content.is_empty() {
+            &note.revised.content
+        } else {
+            &note.original.content
+        };
+        if content.trim().is_empty() {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "empty_content"
+            })));
+        }
+
+        let fast = if overridden.is_none() {
+            self.fast_backend.as_ref()
+        } else {
+            None
+        };
+        let backend: &dyn GenerationBackend = match (&overridden, fast) {
+            (Some(b), _) => b.as_ref(),
+            (None, Some(f)) => f,
+            (None, None) => &self.backend,
+        };
+        let chunk_size = extraction_chunk_size(match &overridden {
+            Some(_) => None,
+            None => Some(fast.unwrap_or(&self.backend)),
+        });
+        let chunks = chunk_for_extraction(content, chunk_size);
+        let model = backend.model_name().to_string();
+
+        let activity_id = self
+            .db
+            .provenance
+            .start_activity(note_id, "entity_extraction", Some(&model))
+            .await
+            .ok();
+
+        let template = job_prompt_template(&self.db, schema, PromptKey::EntityExtraction).await;
+        let config = ConstrainedConfig::default();
+        let mut entities = Vec::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            ctx.report_progress(
+                20 + (60 * i / chunks.len().max(1)) as i32,
+                Some(&format!(
+                    "Extracting entities ({}/{})...",
+                    i + 1,
+                    chunks.len()
+                )),
+            );
+            let prompt = fill_prompt_template(&template, &[("content", chunk)]);
+            match extract_entities(backend, &prompt, &config).await {
+                Ok(found) => merge_entities(&mut entities, found),
+                Err(e) => return entity_extraction_job_failure(e, "extract_entities"),
+            }
+        }
+
+        ctx.report_progress(90, Some("Saving entities..."));
+
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return entity_extraction_job_failure(e, "save_entities_begin_tx"),
+        };
+        let stored = match self
+            .db
+            .entities
+            .replace_note_entities_tx(&mut tx, note_id, &entities)
+            .await
+        {
+            Ok(n) => n,
+            Err(e) => return entity_extraction_job_failure(e, "save_entities"),
+        };
+        if let Err(e) = tx.commit().await {
+            return entity_extraction_job_failure(e, "save_entities_commit");
+        }
+
+        let mut by_type = serde_json::Map::new();
+        for entity in &entities {
+            let count = by_type
+                .entry(entity.entity_type.to_string())
+                .or_insert(serde_json::json!(0));
+            *count = serde_json::json!(count.as_u64().unwrap_or(0) + 1);
+        }
+        let result = serde_json::json!({
+            "entity_count": stored,
+            "by_type": by_type,
+            "chunk_count": chunks.len(),
+        });
+        if let Some(act_id) = activity_id {
+            if let Err(e) = self
+                .db
+                .provenance
+                .complete_activity(act_id, None, Some(result.clone()))
+                .await
+            {
+                warn!(
+                    error_len = diagnostic_len(&e),
+                    detail = JOB_PROVENANCE_WRITE_FAILURE_DETAIL,
+                    "Failed to complete entity extraction provenance activity"
+                );
+            }
+        }
+
+        info!(
+            note_id_present = true,
+            entity_count = stored,
+            chunk_count = chunks.len(),
+            duration_ms = start.elapsed().as_millis() as u64,
+            operation = "complete_entity_extraction",
+            "Entities extracted"
+        );
+
+        ctx.report_progress(100, Some("Entity extraction completed"));
+
+        JobResult::Success(Some(result))
+    }
+}
+
+/// Handler for scheduled digest jobs.
+///
+/// Summarizes the notes an archive created or updated during the period that
+/// ended at the scheduled run, then writes the digest as a note, announces it
+/// with `DigestGenerated` for webhooks, or both.
+pub struct DigestGenerationHandler {
+    db: Database,
+    backend: OllamaBackend,
+    fast_backend: Option<OllamaBackend>,
+    registry: Arc<ProviderRegistry>,
+    event_bus: Arc<EventBus>,
+}
+
+impl DigestGenerationHandler {
+    pub fn new(
+        db: Database,
+        backend: OllamaBackend,
+        fast_backend: Option<OllamaBackend>,
+        registry: Arc<ProviderRegistry>,
+        event_bus: Arc<EventBus>,
+    ) -> Self {
+        Self {
+            db,
+            backend,
+            fast_backend,
+            registry,
+            event_bus,
+        }
+    }
+
+    /// Generate and deliver one digest, returning the job result.
+    async fn generate(
+        &self,
+        ctx: &JobContext,
+        schema: &str,
+        digest: &DigestConfig,
+        period_end: DateTime<Utc>,
+    ) -> Result<(serde_json::Value, Option<uuid::Uuid>, &'static str), JobResult> {
+        let schema_ctx = schema_context(&self.db, schema)?;
+        let model_override = extract_model_override(ctx);
+        let overridden = resolve_gen_backend(&self.registry, model_override.as_deref())?;
+        let (period_start, period_end) = digest.cadence.period_ending(period_end);
+
+        ctx.report_progress(10, Some("Collecting recent notes..."));
+        let mut tx = schema_ctx
+            .begin_tx()
+            .await
+            .map_err(|e| digest_job_failure(e, "activity_begin_tx"))?;
+        let notes = self
+            .db
+            .digests
+            .activity_tx(
+                &mut tx,
+                period_start,
+                period_end,
+                &digest.collection_ids,
+                MAX_DIGEST_NOTES,
+                DIGEST_EXCERPT_CHARS,
+            )
+            .await
+            .map_err(|e| digest_job_failure(e, "load_activity"))?;
+        tx.commit().await.ok();
+
+        if notes.is_empty() {
+            return Ok((
+                serde_json::json!({ "status": DIGEST_RUN_EMPTY, "note_count": 0 }),
+                None,
+                DIGEST_RUN_EMPTY,
+            ));
+        }
+
+        let backend: &dyn GenerationBackend = match (&overridden, self.fast_backend.as_ref()) {
+            (Some(b), _) => b.as_ref(),
+            (None, Some(f)) => f,
+            (None, None) => &self.backend,
+        };
+        let template = job_prompt_template(&self.db, schema, PromptKey::Digest).await;
+        let prompt = fill_prompt_template(
+            &template,
+            &[
+                ("notes", &digest_prompt_notes(&notes)),
+                ("period", &digest_period_label(period_start, period_end)),
+            ],
+        );
+
+        ctx.report_progress(40, Some(&format!("Summarizing {} note(s)...", notes.len())));
+        let summary = backend
+            .generate(&prompt)
+            .await
+            .map_err(|e| digest_job_failure(e, "generate_digest"))?;
+        let summary = summary.trim().to_string();
+        if summary.is_empty() {
+            return Err(digest_job_failure("empty model output", "generate_digest"));
+        }
+
+        let mut note_id = None;
+        if digest.delivers(DigestDelivery::Note) {
+            ctx.report_progress(80, Some("Writing digest note..."));
+            let request = CreateNoteRequest {
+                content: render_digest_note(&summary, &notes),
+                format: "markdown".to_string(),
+                source: DIGEST_NOTE_SOURCE.to_string(),
+                collection_id: digest.note_collection_id,
+                tags: Some(vec![DIGEST_NOTE_TAG.to_string()]),
+                metadata: Some(serde_json::json!({
+                    "digest_id": digest.id,
+                    "period_start": period_start,
+                    "period_end": period_end,
+                })),
+                document_type_id: None,
+                title: Some(digest.cadence.note_title(period_end)),
+            };
+            let mut tx = schema_ctx
+                .begin_tx()
+                .await
+                .map_err(|e| digest_job_failure(e, "write_note_begin_tx"))?;
+            let id = self
+                .db
+                .notes
+                .insert_tx(&mut tx, request)
+                .await
+                .map_err(|e| digest_job_failure(e, "write_note"))?;
+            tx.commit()
+                .await
+                .map_err(|e| digest_job_failure(e, "write_note_commit"))?;
+            note_id = Some(id);
+
+            match self
+                .db
+                .jobs
+                .queue_deduplicated(
+                    Some(id),
+                    JobType::Embedding,
+                    JobType::Embedding.default_priority(),
+                    Some(serde_json::json!({ "schema": schema })),
+                    None,
+                )
+                .await
+            {
+                Ok(Some(job_id)) => ctx.emit_job_queued(job_id, JobType::Embedding, Some(id)),
+                Ok(None) => {}
+                Err(e) => warn!(
+                    error_len = diagnostic_len(&e),
+                    detail = JOB_QUEUE_FOLLOWUP_FAILURE_DETAIL,
+                    operation = "queue_digest_embedding",
+                    "Failed to queue digest note embedding"
+                ),
+            }
+        }
+
+        let memory = match self.db.archives.list_archive_schemas().await {
+            Ok(archives) => archives
+                .into_iter()
+                .find(|a| a.schema_name == schema)
+                .map(|a| a.name),
+            Err(_) => None,
+        };
+        let evt_ctx = EventContext {
+            memory,
+            ..Default::default()
+        };
+        if let Some(id) = note_id {
+            self.event_bus.emit_with_context(
+                ServerEvent::NoteCreated {
+                    note_id: id,
+                    title: Some(digest.cadence.note_title(period_end)),
+                    tags: vec![DIGEST_NOTE_TAG.to_string()],
+                },
+                evt_ctx.clone(),
+            );
+        }
+        if digest.delivers(DigestDelivery::Webhook) {
+            self.event_bus.emit_with_context(
+                ServerEvent::DigestGenerated {
+                    digest_id: digest.id,
+                    note_id,
+                    period_start,
+                    period_end,
+                    note_count: notes.len(),
+                    summary: summary.clone(),
+                },
+                evt_ctx,
+            );
+        }
+
+        Ok((
+            serde_json::json!({
+                "status": DIGEST_RUN_SUCCEEDED,
+                "note_count": notes.len(),
+                "summary_len": diagnostic_len(&summary),
+                "note_id": note_id,
+            }),
+            note_id,
+            DIGEST_RUN_SUCCEEDED,
+        ))
+    }
+}
+
+#[async_trait]
+impl JobHandler for DigestGenerationHandler {
+    fn job_type(&self) -> JobType {
+        JobType::DigestGeneration
+    }
+
+    #[instrument(
+        skip(self, ctx),
+        fields(subsystem = "jobs", component = "digest_generation", op = "execute")
+    )]
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let start = Instant::now();
+        let Some(digest_id) = ctx
+            .payload()
+            .and_then(|p| p.get("digest_id"))
+            .and_then(|v| v.as_str())
+            .and_then(|s| uuid::Uuid::parse_str(s).ok())
+        else {
+            return JobResult::Failed("No digest_id provided".into());
+        };
+        let schema = extract_schema(&ctx);
+        let period_end = ctx
+            .payload()
+            .and_then(|p| p.get("period_end"))
+            .and_then(|v| serde_json::from_value::<DateTime<Utc>>(v.clone()).ok())
+            .unwrap_or_else(Utc::now);
+
+        let digest = match self.db.digests.get(schema, digest_id).await {
+            Ok(Some(digest)) => digest,
+            // Deleted after the run was queued.
+            Ok(None) => {
+                return JobResult::Success(Some(serde_json::json!({
+                    "skipped": true,
+                    "reason": "digest_not_found"
+                })))
+            }
+            Err(e) => return digest_job_failure(e, "load_digest"),
+        };
+
+        let (result, note_id, status) = match self.generate(&ctx, schema, &digest, period_end).await
+        {
+            Ok((result, note_id, status)) => (Ok(result), note_id, status),
+            Err(failure) => (Err(failure), None, DIGEST_RUN_FAILED),
+        };
+        if let Err(e) = self.db.digests.record_run(digest.id, status, note_id).await {
+            warn!(
+                error_len = diagnostic_len(&e),
+                operation = "record_digest_run",
+                "Failed to record digest run"
+            );
+        }
+
+        match result {
+            Ok(result) => {
+                info!(
+                    note_count = result["note_count"].as_u64().unwrap_or(0),
+                    note_written = note_id.is_some(),
+                    status,
+                    duration_ms = start.elapsed().as_millis() as u64,
+                    operation = "complete_digest_generation",
+                    "Digest generated"
+                );
+                ctx.report_progress(100, Some("Digest completed"));
+                JobResult::Success(Some(result))
+            }
+            Err(failure) => failure,
+        }
+    }
+}
+
+/// Handler for topic modeling jobs.
+///
+/// Clusters the archive's notes with spherical k-means over their mean
+/// embeddings, asks the fast model to name each cluster from the notes
+/// nearest its centroid, suggests each name as a candidate SKOS concept, and
+/// replaces the archive's topics with the result.
+pub struct TopicModelingHandler {
+    db: Database,
+    backend: OllamaBackend,
+    fast_backend: Option<OllamaBackend>,
+    registry: Arc<ProviderRegistry>,
+}
+
+impl TopicModelingHandler {
+    pub fn new(
+        db: Database,
+        backend: OllamaBackend,
+        fast_backend: Option<OllamaBackend>,
+        registry: Arc<ProviderRegistry>,
+    ) -> Self {
+        Self {
+            db,
+            backend,
+            fast_backend,
+            registry,
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler for TopicModelingHandler {
+    fn job_type(&self) -> JobType {
+        JobType::TopicModeling
+    }
+
+    #[instrument(
+        skip(self, ctx),
+        fields(subsystem = "jobs", component = "topic_modeling", op = "execute")
+    )]
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let start = Instant::now();
+        let schema = extract_schema(&ctx);
+        let model_override = extract_model_override(&ctx);
+        let schema_ctx = match schema_context(&self.db, schema) {
+            Ok(ctx) => ctx,
+            Err(e) => return e,
+        };
+        let overridden = match resolve_gen_backend(&self.registry, model_override.as_deref()) {
+            Ok(b) => b,
+            Err(e) => return e,
+        };
+
+        ctx.report_progress(10, Some("Loading note embeddings..."));
+
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return topic_job_failure(e, "load_vectors_begin_tx"),
+        };
+        let notes = match self
+            .db
+            .topics
+            .note_vectors_tx(&mut tx, matric_core::defaults::TOPIC_MAX_NOTES)
+            .await
+        {
+            Ok(notes) => notes,
+            Err(e) => return topic_job_failure(e, "load_vectors"),
+        };
+        tx.commit().await.ok();
+
+        if notes.len() < matric_core::defaults::TOPIC_MIN_NOTES {
+            return JobResult::Success(Some(serde_json::json!({
+                "skipped": true,
+                "reason": "too_few_notes",
+                "note_count": notes.len(),
+            })));
+        }
+
+        let k = ctx
+            .payload()
+            .and_then(|p| p.get("k"))
+            .and_then(|v| v.as_u64())
+            .map(|k| {
+                (k as usize)
+                    .clamp(2, matric_core::defaults::TOPIC_MAX_COUNT)
+                    .min(notes.len())
+            })
+            .unwrap_or_else(|| {
+                default_cluster_count(notes.len(), matric_core::defaults::TOPIC_MAX_COUNT)
+            });
+
+        ctx.report_progress(
+            20,
+            Some(&format!(
+                "Clustering {} notes into {k} topics...",
+                notes.len()
+            )),
+        );
+        let vectors: Vec<Vec<f32>> = notes.iter().map(|(_, v)| v.to_vec()).collect();
+        let clustering = match tokio::task::spawn_blocking(move || {
+            kmeans(&vectors, &KMeansConfig::new(k))
+        })
+        .await
+        {
+            Ok(result) => result,
+            Err(e) => return topic_job_failure(e, "cluster"),
+        };
+        let clusters = clustering.members();
+
+        let backend: &dyn GenerationBackend = match (&overridden, self.fast_backend.as_ref()) {
+            (Some(b), _) => b.as_ref(),
+            (None, Some(f)) => f,
+            (None, None) => &self.backend,
+        };
+        let model = backend.model_name().to_string();
+        let template = job_prompt_template(&self.db, schema, PromptKey::TopicNaming).await;
+
+        let mut topics = Vec::with_capacity(clusters.len());
+        let mut named = Vec::with_capacity(clusters.len());
+        for (i, members) in clusters.iter().enumerate() {
+            ctx.report_progress(
+                30 + (60 * i / clusters.len().max(1)) as i32,
+                Some(&format!("Naming topics ({}/{})...", i + 1, clusters.len())),
+            );
+            let sample_ids: Vec<uuid::Uuid> = members
+                .iter()
+                .take(matric_core::defaults::TOPIC_NAMING_SAMPLE)
+                .map(|&m| notes[m].0)
+                .collect();
+            let mut tx = match schema_ctx.begin_tx().await {
+                Ok(t) => t,
+                Err(e) => return topic_job_failure(e, "load_samples_begin_tx"),
+            };
+            let samples = match self
+                .db
+                .topics
+                .note_samples_tx(
+                    &mut tx,
+                    &sample_ids,
+                    matric_core::defaults::TOPIC_NAMING_EXCERPT_CHARS,
+                )
+                .await
+            {
+                Ok(samples) => samples,
+                Err(e) => return topic_job_failure(e, "load_samples"),
+            };
+            tx.commit().await.ok();
+
+            let prompt =
+                fill_prompt_template(&template, &[("notes", &topic_prompt_notes(&samples))]);
+            let label = match backend.generate(&prompt).await {
+                Ok(output) => parse_topic_label(&output),
+                Err(e) => return topic_job_failure(e, "name_topic"),
+            };
+            // Unnamed clusters are still listed but not suggested as concepts.
+            named.push(label.is_some());
+            topics.push(NewTopic {
+                concept_id: None,
+                label: label.unwrap_or_else(|| format!("Topic {}", i + 1)),
+                model: Some(model.clone()),
+                members: members
+                    .iter()
+                    .map(|&m| (notes[m].0, clustering.similarities[m]))
+                    .collect(),
+            });
+        }
+
+        ctx.report_progress(90, Some("Saving topics..."));
+
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(t) => t,
+            Err(e) => return topic_job_failure(e, "save_topics_begin_tx"),
+        };
+        let mut concepts_created = 0;
+        for (topic, _) in topics.iter_mut().zip(&named).filter(|(_, &named)| named) {
+            match self
+                .db
+                .skos
+                .resolve_or_create_tag_tx(&mut tx, &TagInput::flat(topic.label.clone()))
+                .await
+            {
+                Ok(resolved) => {
+                    concepts_created += usize::from(resolved.created);
+                    topic.concept_id = Some(resolved.concept_id);
+                }
+                Err(e) => return topic_job_failure(e, "suggest_concept"),
+            }
+        }
+        let stored = match self.db.topics.replace_all_tx(&mut tx, &topics).await {
+            Ok(n) => n,
+            Err(e) => return topic_job_failure(e, "save_topics"),
+        };
+        if let Err(e) = tx.commit().await {
+            return topic_job_failure(e, "save_topics_commit");
+        }
+
+        info!(
+            note_count = notes.len(),
+            topic_count = stored,
+            concepts_created,
+            iterations = clustering.iterations,
+            duration_ms = start.elapsed().as_millis() as u64,
+            operation = "complete_topic_modeling",
+            "Topics modeled"
+        );
+
+        ctx.report_progress(100, Some("Topic modeling completed"));
+
+        JobResult::Success(Some(serde_json::json!({
+            "note_count": notes.len(),
+            "topic_count": stored,
+            "concepts_created": concepts_created,
+            "iterations": clustering.iterations,
+        })))
+    }
+}
+
+/// Handler for fine-tuning dataset generation jobs.
+///
+/// Asks the model for question-answer pairs grounded in each note of the
+/// dataset's source, keeps those it rates at or above the dataset's minimum
+/// quality, and holds back every `1 / validation_split`-th pair for
+/// validation. A run replaces the dataset's samples. Notes the model fails
+/// on are skipped; the run fails only if every note does.
+pub struct GenerateFineTuningDataHandler {
+    db: Database,
+    backend: OllamaBackend,
+    registry: Arc<ProviderRegistry>,
+}
+
+impl GenerateFineTuningDataHandler {
+    pub fn new(db: Database, backend: OllamaBackend, registry: Arc<ProviderRegistry>) -> Self {
+        Self {
+            db,
+            backend,
+            registry,
+        }
+    }
+
+    /// Generate the dataset's samples, returning the job result.
+    async fn generate(
+        &self,
+        ctx: &JobContext,
+        schema_ctx: &SchemaContext,
+        schema: &str,
+        dataset: &FineTuningDataset,
+    ) -> Result<serde_json::Value, JobResult> {
+        // A model named on the job wins over the one configured on the dataset.
+        let model_override =
+            extract_model_override(ctx).or_else(|| dataset.config.query_generator_model.clone());
+        let overridden = resolve_gen_backend(&self.registry, model_override.as_deref())?;
+        let backend: &dyn GenerationBackend = match &overridden {
+            Some(b) => b.as_ref(),
+            None => &self.backend,
+        };
+
+        ctx.report_progress(5, Some("Collecting source notes..."));
+        let mut tx = schema_ctx
+            .begin_tx()
+            .await
+            .map_err(|e| fine_tuning_job_failure(e, "start_begin_tx"))?;
+        self.db
+            .fine_tuning
+            .start_generation_tx(&mut tx, dataset.id)
+            .await
+            .map_err(|e| fine_tuning_job_failure(e, "start_generation"))?;
+        let note_ids = self
+            .db
+            .fine_tuning
+            .source_note_ids_tx(
+                &mut tx,
+                &dataset.source_type,
+                &dataset.source_id,
+                MAX_FINE_TUNING_SOURCE_NOTES,
+            )
+            .await
+            .map_err(|e| fine_tuning_job_failure(e, "load_source"))?;
+        tx.commit()
+            .await
+            .map_err(|e| fine_tuning_job_failure(e, "start_commit"))?;
+
+        let template = job_prompt_template(&self.db, schema, PromptKey::QaGeneration).await;
+        let count = dataset.config.queries_per_doc.to_string();
+        let per_note = usize::try_from(dataset.config.queries_per_doc).unwrap_or(1);
+        let config = ConstrainedConfig::default();
+        let mut sample_index = 0;
+        let mut notes_used = 0;
+        let mut notes_failed = 0;
+        let mut pairs_rejected = 0;
+        for (i, &note_id) in note_ids.iter().enumerate() {
+            ctx.report_progress(
+                10 + (80 * i / note_ids.len().max(1)) as i32,
+                Some(&format!(
+                    "Generating question-answer pairs ({}/{})...",
+                    i + 1,
+                    note_ids.len()
+                )),
+            );
+            let mut tx = schema_ctx
+                .begin_tx()
+                .await
+                .map_err(|e| fine_tuning_job_failure(e, "fetch_note_begin_tx"))?;
+            let note = match self.db.notes.fetch_tx(&mut tx, note_id).await {
+                Ok(note) => note,
+                // Deleted since the source was listed.
+                Err(_) => continue,
+            };
+            tx.commit().await.ok();
+
+            let content: &str = if !note.revised.content.is_empty() {
+                &note.revised.content
+            } else {
+                &note.original.content
+            };
+            if note.note.encrypted || content.trim().is_empty() {
+                continue;
+            }
+            let content: String = content.chars().take(FINE_TUNING_CONTENT_CHARS).collect();
+            let prompt =
+                fill_prompt_template(&template, &[("content", &content), ("count", &count)]);
+            let pairs = match generate_qa_pairs(backend, &prompt, &config).await {
+                Ok(pairs) => pairs,
+                Err(e) => {
+                    warn!(
+                        error_len = diagnostic_len(&e),
+                        operation = "generate_qa_pairs",
+                        "Skipping note after question-answer generation failed"
+                    );
+                    notes_failed += 1;
+                    continue;
+                }
+            };
+            notes_used += 1;
+
+            let total = pairs.len();
+            let kept: Vec<_> = pairs
+                .iter()
+                .filter(|pair| pair.quality >= dataset.config.min_quality_score)
+                .take(per_note)
+                .collect();
+            pairs_rejected += total - kept.len();
+            if kept.is_empty() {
+                continue;
+            }
+            let samples: Vec<NewFineTuningSample<'_>> = kept
+                .into_iter()
+                .map(|pair| {
+                    let is_validation =
+                        is_validation_sample(sample_index, dataset.config.validation_split);
+                    sample_index += 1;
+                    NewFineTuningSample {
+                        pair,
+                        is_validation,
+                    }
+                })
+                .collect();
+            let mut tx = schema_ctx
+                .begin_tx()
+                .await
+                .map_err(|e| fine_tuning_job_failure(e, "save_samples_begin_tx"))?;
+            self.db
+                .fine_tuning
+                .insert_samples_tx(&mut tx, dataset.id, note_id, &samples)
+                .await
+                .map_err(|e| fine_tuning_job_failure(e, "save_samples"))?;
+            tx.commit()
+                .await
+                .map_err(|e| fine_tuning_job_failure(e, "save_samples_commit"))?;
+        }
 
-        ctx.report_progress(80, Some("Saving title..."));
+        if notes_failed > 0 && notes_used == 0 {
+            return Err(fine_tuning_job_failure(
+                "generation failed for every note",
+                "generate_qa_pairs",
+            ));
+        }
+
+        ctx.report_progress(95, Some("Finishing dataset..."));
+        let mut tx = schema_ctx
+            .begin_tx()
+            .await
+            .map_err(|e| fine_tuning_job_failure(e, "complete_begin_tx"))?;
+        let completed = self
+            .db
+            .fine_tuning
+            .complete_tx(&mut tx, dataset.id)
+            .await
+            .map_err(|e| fine_tuning_job_failure(e, "complete"))?;
+        tx.commit()
+            .await
+            .map_err(|e| fine_tuning_job_failure(e, "complete_commit"))?;
+
+        let (training, validation) = completed
+            .map(|d| (d.training_count, d.validation_count))
+            .unwrap_or_default();
+        Ok(serde_json::json!({
+            "dataset_id": dataset.id,
+            "note_count": note_ids.len(),
+            "notes_used": notes_used,
+            "notes_failed": notes_failed,
+            "pairs_rejected": pairs_rejected,
+            "training_count": training,
+            "validation_count": validation,
+        }))
+    }
+}
+
+#[async_trait]
+impl JobHandler for GenerateFineTuningDataHandler {
+    fn job_type(&self) -> JobType {
+        JobType::GenerateFineTuningData
+    }
+
+    #[instrument(
+        skip(self, ctx),
+        fields(
+            subsystem = "jobs",
+            component = "generate_fine_tuning_data",
+            op = "execute"
+        )
+    )]
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let start = Instant::now();
+        let Some(dataset_id) = ctx
+            .payload()
+            .and_then(|p| p.get("dataset_id"))
+            .and_then(|v| v.as_str())
+            .and_then(|s| uuid::Uuid::parse_str(s).ok())
+        else {
+            return JobResult::Failed("No dataset_id provided".into());
+        };
+        let schema = extract_schema(&ctx);
+        let schema_ctx = match schema_context(&self.db, schema) {
+            Ok(ctx) => ctx,
+            Err(e) => return e,
+        };
 
-        // Save the title
         let mut tx = match schema_ctx.begin_tx().await {
             Ok(t) => t,
-            Err(e) => return title_generation_job_failure(e, "save_title_begin_tx"),
+            Err(e) => return fine_tuning_job_failure(e, "load_dataset_begin_tx"),
         };
-        if let Err(e) = self
-            .db
-            .notes
-            .update_title_tx(&mut tx, note_id, &title)
-            .await
-        {
-            return title_generation_job_failure(e, "save_title");
-        }
-        if let Err(e) = tx.commit().await {
-            return title_generation_job_failure(e, "save_title_commit");
-        }
+        let dataset = match self.db.fine_tuning.get_tx(&mut tx, dataset_id).await {
+            Ok(Some(dataset)) => dataset,
+            // Deleted after the run was queued.
+            Ok(None) => {
+                return JobResult::Success(Some(serde_json::json!({
+                    "skipped": true,
+                    "reason": "dataset_not_found"
+                })))
+            }
+            Err(e) => return fine_tuning_job_failure(e, "load_dataset"),
+        };
+        tx.commit().await.ok();
 
-        // Complete provenance activity (#430)
-        if let Some(act_id) = activity_id {
-            let prov_metadata = serde_json::json!({
-                "title_len": diagnostic_len(&title),
-            });
-            if let Err(e) = self
-                .db
-                .provenance
-                .complete_activity(act_id, None, Some(prov_metadata))
-                .await
-            {
-                warn!(
-                    error_len = diagnostic_len(&e),
-                    detail = JOB_PROVENANCE_WRITE_FAILURE_DETAIL,
-                    "Failed to complete title generation provenance activity"
+        match self.generate(&ctx, &schema_ctx, schema, &dataset).await {
+            Ok(result) => {
+                info!(
+                    note_count = result["note_count"].as_u64().unwrap_or(0),
+                    training_count = result["training_count"].as_i64().unwrap_or(0),
+                    validation_count = result["validation_count"].as_i64().unwrap_or(0),
+                    duration_ms = start.elapsed().as_millis() as u64,
+                    operation = "complete_fine_tuning_generation",
+                    "Fine-tuning dataset generated"
                 );
+                ctx.report_progress(100, Some("Fine-tuning dataset completed"));
+                JobResult::Success(Some(result))
+            }
+            Err(failure) => {
+                let recorded = match schema_ctx.begin_tx().await {
+                    Ok(mut tx) => match self
+                        .db
+                        .fine_tuning
+                        .fail_tx(&mut tx, dataset.id, FINE_TUNING_JOB_FAILURE)
+                        .await
+                    {
+                        Ok(()) => tx.commit().await.map_err(matric_core::Error::Database),
+                        Err(e) => Err(e),
+                    },
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = recorded {
+                    warn!(
+                        error_len = diagnostic_len(&e),
+                        operation = "record_fine_tuning_failure",
+                        "Failed to record fine-tuning dataset failure"
+                    );
+                }
+                failure
             }
         }
-
-        info!(
-            note_id_present = true,
-            title_len = diagnostic_len(&title),
-            duration_ms = start.elapsed().as_millis() as u64,
-            detail = JOB_PROVENANCE_WRITE_FAILURE_DETAIL,
-            operation = "complete_title_generation",
-            "Title generated"
-        );
-
-        ctx.report_progress(100, Some("Title generation completed"));
-
-        JobResult::Success(Some(title_generation_job_result(&title)))
     }
 }
 
@@ -3308,6 +5916,9 @@ Content:
 /// - **HNSW Heuristic** (Algorithm 4, Malkov & Yashunin 2018): Diverse neighbor
 ///   selection that approximates the Relative Neighborhood Graph, preventing
 ///   star topology on clustered data.
+///
+/// Both strategies' similarity floors move with the archive's link suggestion
+/// feedback (see [`matric_core::link_suggestion`]).
 pub struct LinkingHandler {
     db: Database,
 }
@@ -3808,7 +6419,7 @@ impl JobHandler for LinkingHandler {
         let mut wiki_links_resolved = 0;
 
         // Load graph configuration from environment
-        let graph_config = matric_core::defaults::GraphConfig::from_env();
+        let mut graph_config = matric_core::defaults::GraphConfig::from_env();
 
         // First, parse wiki-style [[links]] from note content
         ctx.report_progress(10, Some("Parsing wiki-style links..."));
@@ -3835,6 +6446,31 @@ impl JobHandler for LinkingHandler {
             matric_core::defaults::SEMANTIC_LINK_THRESHOLD
         };
 
+        // Link suggestion feedback moves this archive's thresholds.
+        let tuning = match schema_ctx.begin_tx().await {
+            Ok(mut tx) => {
+                let tuning = self.db.link_suggestions.tuning_tx(&mut tx).await;
+                tx.commit().await.ok();
+                tuning
+            }
+            Err(e) => Err(e),
+        };
+        let link_threshold = match tuning {
+            Ok(tuning) => {
+                graph_config.min_similarity = tuning.apply(graph_config.min_similarity);
+                tuning.apply(link_threshold)
+            }
+            Err(e) => {
+                warn!(
+                    error_len = diagnostic_len(&e),
+                    detail = JOB_LINKING_DIAGNOSTIC_FAILURE_DETAIL,
+                    operation = "fetch_link_threshold_tuning",
+                    "Failed to fetch link threshold tuning, using defaults"
+                );
+                link_threshold
+            }
+        };
+
         // Use revised content if available, otherwise original
         let content = if !note.revised.content.is_empty() {
             &note.revised.content
@@ -4325,19 +6961,16 @@ impl JobHandler for ContextUpdateHandler {
         ctx.report_progress(60, Some("Generating context section..."));
 
         // Generate updated content with context (ported from HOTM)
-        let prompt = format!(
-            r#"You have an enhanced note that has been linked to related notes. Add a 'Related Context' section at the end that briefly mentions the connections.
-
-Current Enhanced Note:
-{}
-
-Related Notes Found:
-{}
-
-Add a brief '## Related Context' section at the end that mentions these connections naturally.
-Keep it concise (2-3 sentences). Output the full note with the new section added."#,
-            current_content, linked_context
-        );
+        let prompt = render_job_prompt(
+            &self.db,
+            schema,
+            PromptKey::ContextUpdate,
+            &[
+                ("note_content", current_content),
+                ("related_notes", &linked_context),
+            ],
+        )
+        .await;
 
         let updated_content = match backend.generate(&prompt).await {
             Ok(c) => clean_enhanced_content(c.trim(), &prompt),
@@ -4361,7 +6994,7 @@ Keep it concise (2-3 sentences). Output the full note with the new section added
             .update_revised_tx(
                 &mut tx,
                 note_id,
-                &updated_content,
+                &mask_revision_pii(&updated_content),
                 Some("Added related context section"),
             )
             .await
@@ -4402,6 +7035,14 @@ pub struct ConceptTaggingHandler {
     registry: Arc<ProviderRegistry>,
     /// Target number of concepts per note. Configurable via EXTRACTION_TARGET_CONCEPTS.
     target_concepts: usize,
+    /// Tags already generated for the same note, model, and prompt.
+    cache: InferenceCache,
+    /// Hold suggestions below their concept's review threshold for review
+    /// instead of tagging. Enabled via CONCEPT_REVIEW_MODE.
+    review_mode: bool,
+    /// Review threshold of concepts no review has adjusted yet.
+    /// Configurable via CONCEPT_REVIEW_THRESHOLD.
+    review_threshold: f32,
 }
 
 impl ConceptTaggingHandler {
@@ -4411,11 +7052,15 @@ impl ConceptTaggingHandler {
         fast_backend: Option<OllamaBackend>,
         ner_backend: Option<Arc<dyn NerBackend>>,
         registry: Arc<ProviderRegistry>,
+        cache: InferenceCache,
     ) -> Self {
         let target_concepts = std::env::var(matric_core::defaults::ENV_EXTRACTION_TARGET_CONCEPTS)
             .ok()
             .and_then(|v| v.parse::<usize>().ok())
             .unwrap_or(matric_core::defaults::EXTRACTION_TARGET_CONCEPTS);
+        let review_mode = std::env::var(matric_core::defaults::ENV_CONCEPT_REVIEW_MODE)
+            .is_ok_and(|v| v == "true" || v == "1");
+        let review_threshold = matric_core::defaults::concept_review_threshold();
         Self {
             db,
             backend,
@@ -4423,7 +7068,38 @@ impl ConceptTaggingHandler {
             ner_backend,
             registry,
             target_concepts,
+            cache,
+            review_mode,
+            review_threshold,
+        }
+    }
+
+    /// Schema-constrained concept tags for `prompt`, reusing the cached tags
+    /// when this model already tagged the same prompt for the note.
+    async fn generate_concept_tags(
+        &self,
+        note_id: uuid::Uuid,
+        backend: &dyn GenerationBackend,
+        prompt: &str,
+    ) -> matric_core::Result<Vec<String>> {
+        let cache_key = self.cache.cache_key(
+            note_id,
+            InferenceTask::ConceptTagging,
+            backend.model_name(),
+            prompt,
+        );
+        if let Some(tags) = self.cache.get(&cache_key).await {
+            return Ok(tags);
         }
+        let output = generate_constrained::<Vec<String>>(
+            backend,
+            prompt,
+            concept_tags_schema(),
+            &ConstrainedConfig::default(),
+        )
+        .await?;
+        self.cache.set(&cache_key, &output.value).await;
+        Ok(output.value)
     }
 
     /// Queue Phase 2 (RelatedConceptInference) after concept tagging completes.
@@ -4474,15 +7150,34 @@ impl ConceptTaggingHandler {
             .unwrap_or_default()
     }
 
-    /// Build the LLM prompt for concept extraction.
+    /// Build the LLM prompt for concept extraction from the archive's
+    /// `concept_tagging` template.
     ///
     /// `existing_prior`: concepts from prior tier escalation (e.g. GLiNER results).
     /// `existing_db`: concepts already tagged on this note in the database.
-    fn make_concept_prompt(
+    async fn make_concept_prompt(
+        &self,
+        schema: &str,
         text: &str,
         existing_prior: &[String],
         existing_db: &[String],
         target: usize,
+    ) -> String {
+        let context_hint = Self::concept_context_hint(existing_prior, existing_db, target);
+        render_job_prompt(
+            &self.db,
+            schema,
+            PromptKey::ConceptTagging,
+            &[("context_hint", &context_hint), ("content", text)],
+        )
+        .await
+    }
+
+    /// Describe concepts already known for the note so the model reuses them.
+    fn concept_context_hint(
+        existing_prior: &[String],
+        existing_db: &[String],
+        target: usize,
     ) -> String {
         let all_existing: Vec<&str> = existing_prior
             .iter()
@@ -4529,38 +7224,7 @@ impl ConceptTaggingHandler {
             ));
         }
 
-        format!(
-            r#"You are a knowledge organization specialist using SKOS (Simple Knowledge Organization System). Analyze the following content and suggest concept tags organized as hierarchical paths across MULTIPLE dimensions.
-
-{context_hint}Content:
-{text}
-
-REQUIRED DIMENSIONS (include at least one tag from each applicable dimension):
-1. **Domain**: Primary subject area (e.g., "science/machine-learning", "engineering/software")
-2. **Topic**: Specific topics covered (e.g., "nlp/transformers", "databases/vector-search")
-3. **Methodology**: Research/work methodology (e.g., "methodology/experimental", "methodology/survey", "methodology/case-study")
-4. **Application**: Practical applications (e.g., "application/healthcare", "application/search-engines")
-5. **Technique**: Specific techniques used (e.g., "technique/attention-mechanism", "technique/reinforcement-learning")
-6. **Content-type**: What kind of content (e.g., "content-type/research-paper", "content-type/tutorial", "content-type/documentation")
-
-OPTIONAL DIMENSIONS (include if clearly applicable):
-7. **Evaluation**: How results are evaluated (e.g., "evaluation/benchmark", "evaluation/ablation-study")
-8. **Tool/Framework**: Specific tools mentioned (e.g., "tool/pytorch", "tool/postgresql")
-9. **Era/Context**: Temporal context (e.g., "era/foundation-models", "era/pre-transformer")
-
-Guidelines:
-1. Use hierarchical paths with "/" separators
-2. Use 1-2 levels of hierarchy. Top level = dimension/domain, leaf = specific concept
-3. Use kebab-case for multi-word terms
-4. Focus on actual subject matter, not generic terms
-5. Order by relevance (most relevant first)
-6. Reuse top-level categories across notes for cross-cutting queries
-7. Aim for 5-8 tags total — breadth across dimensions is more valuable than depth in one
-8. PREFER reusing existing concept paths from the knowledge base when they are relevant
-
-Output ONLY a JSON array of tag paths, nothing else. Example:
-["science/machine-learning", "nlp/transformers", "technique/attention-mechanism", "methodology/experimental", "evaluation/benchmark", "application/translation", "tool/pytorch", "content-type/research-paper"]"#
-        )
+        context_hint
     }
 
     /// Tier-0: GLiNER NER only. Chains to tier-1 if insufficient concepts.
@@ -4705,17 +7369,20 @@ Output ONLY a JSON array of tag paths, nothing else. Example:
 
         let chunk_size = extraction_chunk_size(self.fast_backend.as_ref());
         let chunks = chunk_for_extraction(content_preview, chunk_size);
-        let mut chunk_results: Vec<String> = Vec::new();
+        let mut chunk_results: Vec<Vec<String>> = Vec::new();
 
         for (i, chunk) in chunks.iter().enumerate() {
-            let prompt = Self::make_concept_prompt(
-                chunk,
-                &concept_labels,
-                existing_db_concepts,
-                self.target_concepts,
-            );
-            match backend.generate_json(&prompt).await {
-                Ok(r) => chunk_results.push(r.trim().to_string()),
+            let prompt = self
+                .make_concept_prompt(
+                    schema,
+                    chunk,
+                    &concept_labels,
+                    existing_db_concepts,
+                    self.target_concepts,
+                )
+                .await;
+            match self.generate_concept_tags(note_id, backend, &prompt).await {
+                Ok(tags) => chunk_results.push(tags),
                 Err(e) => {
                     info!(
                         error_len = diagnostic_len(&e),
@@ -4729,7 +7396,7 @@ Output ONLY a JSON array of tag paths, nothing else. Example:
             }
         }
 
-        let llm_concepts: Vec<String> = merge_json_arrays(chunk_results);
+        let llm_concepts: Vec<String> = merge_concept_lists(chunk_results);
 
         // Merge with prior results (deduplicate)
         if !llm_concepts.is_empty() {
@@ -4791,8 +7458,8 @@ Output ONLY a JSON array of tag paths, nothing else. Example:
     async fn execute_standard(
         &self,
         ctx: &JobContext,
-        _note_id: uuid::Uuid,
-        _schema: &str,
+        note_id: uuid::Uuid,
+        schema: &str,
         content_preview: &str,
         overridden: Option<&dyn GenerationBackend>,
         existing_db_concepts: &[String],
@@ -4808,31 +7475,21 @@ Output ONLY a JSON array of tag paths, nothing else. Example:
         ctx.report_progress(30, Some("Running standard model concept extraction..."));
 
         let existing_snapshot: Vec<String> = concept_labels.clone();
-        let prompt = Self::make_concept_prompt(
-            content_preview,
-            &existing_snapshot,
-            existing_db_concepts,
-            self.target_concepts,
-        );
+        let prompt = self
+            .make_concept_prompt(
+                schema,
+                content_preview,
+                &existing_snapshot,
+                existing_db_concepts,
+                self.target_concepts,
+            )
+            .await;
 
-        match backend.generate_json(&prompt).await {
-            Ok(r) => {
-                let ai_response = r.trim().to_string();
-                let parsed: Vec<String> = match parse_json_lenient(&ai_response) {
-                    Ok(labels) => labels,
-                    Err(_) => {
-                        let cleaned = ai_response
-                            .trim()
-                            .trim_start_matches("```json")
-                            .trim_start_matches("```")
-                            .trim_end_matches("```")
-                            .trim();
-                        parse_json_lenient(cleaned).unwrap_or_default()
-                    }
-                };
+        match self.generate_concept_tags(note_id, backend, &prompt).await {
+            Ok(tags) => {
                 let mut seen: HashSet<String> =
                     concept_labels.iter().map(|l| l.to_lowercase()).collect();
-                for label in parsed {
+                for label in tags {
                     if seen.insert(label.to_lowercase()) {
                         concept_labels.push(label);
                     }
@@ -5130,6 +7787,7 @@ impl JobHandler for ConceptTaggingHandler {
         // Uses resolve_or_create_tag_tx which handles scheme resolution, hierarchy
         // wiring (broader/narrower), and notation-based deduplication.
         let mut tagged_count = 0;
+        let mut held_count = 0;
         let total = concept_labels.len();
 
         for (i, label) in concept_labels.iter().enumerate() {
@@ -5170,12 +7828,71 @@ impl JobHandler for ConceptTaggingHandler {
                 }
             };
 
+            // In review mode, suggestions scoring below the concept's
+            // threshold wait in the review queue instead of tagging the note.
+            let mut confidence = matric_core::defaults::AI_TAGGING_CONFIDENCE;
+            if self.review_mode {
+                let evidence =
+                    matric_core::concept_suggestion::evidence_snippet(&content_preview, label);
+                confidence =
+                    matric_core::concept_suggestion::suggestion_confidence(i, evidence.is_some());
+                let threshold = match self
+                    .db
+                    .concept_suggestions
+                    .threshold_tx(&mut tx, resolved.concept_id, self.review_threshold)
+                    .await
+                {
+                    Ok(t) => t,
+                    Err(e) => {
+                        warn!(
+                            error_len = diagnostic_len(&e),
+                            detail = JOB_CONCEPT_TAGGING_DIAGNOSTIC_FAILURE_DETAIL,
+                            operation = "fetch_concept_review_threshold",
+                            "Failed to fetch concept review threshold, using default"
+                        );
+                        self.review_threshold
+                    }
+                };
+                if matric_core::concept_suggestion::needs_review(confidence, threshold) {
+                    let suggestion = matric_core::NewConceptSuggestion {
+                        note_id,
+                        concept_id: resolved.concept_id,
+                        confidence,
+                        threshold,
+                        relevance_score: relevance,
+                        is_primary,
+                        evidence,
+                        extraction_method: extraction_method.to_string(),
+                    };
+                    let result = self
+                        .db
+                        .concept_suggestions
+                        .suggest_tx(&mut tx, &suggestion)
+                        .await;
+                    tx.commit().await.ok();
+                    match result {
+                        Ok(true) => held_count += 1,
+                        Ok(false) => {}
+                        Err(e) => debug!(
+                            error_len = diagnostic_len(&e),
+                            detail = JOB_CONCEPT_TAGGING_DIAGNOSTIC_FAILURE_DETAIL,
+                            operation = "hold_concept_suggestion",
+                            "Failed to hold concept suggestion for review"
+                        ),
+                    }
+                    let progress = 60 + ((i + 1) * 30 / total) as i32;
+                    let progress_message = concept_tagging_progress_message(label);
+                    ctx.report_progress(progress, Some(&progress_message));
+                    continue;
+                }
+            }
+
             // Tag the note with the leaf concept
             let tag_req = matric_core::TagNoteRequest {
                 note_id,
                 concept_id: resolved.concept_id,
                 source: "ai_auto".to_string(),
-                confidence: Some(matric_core::defaults::AI_TAGGING_CONFIDENCE),
+                confidence: Some(confidence),
                 relevance_score: relevance,
                 is_primary,
                 created_by: None,
@@ -5240,6 +7957,7 @@ impl JobHandler for ConceptTaggingHandler {
             note_id_present = true,
             result_count = tagged_count,
             concepts_suggested = concept_labels.len(),
+            concepts_held_for_review = held_count,
             extraction_method,
             duration_ms = start.elapsed().as_millis() as u64,
             detail = JOB_CONCEPT_TAGGING_DIAGNOSTIC_FAILURE_DETAIL,
@@ -5247,14 +7965,18 @@ impl JobHandler for ConceptTaggingHandler {
             "Concept tagging completed"
         );
 
-        JobResult::Success(Some(concept_tagging_summary_metadata(
+        let mut summary = concept_tagging_summary_metadata(
             tagged_count,
             concept_labels.len(),
             extraction_method,
             &concept_labels,
             self.target_concepts,
             None,
-        )))
+        );
+        if self.review_mode {
+            summary["concepts_held_for_review"] = serde_json::json!(held_count);
+        }
+        JobResult::Success(Some(summary))
     }
 }
 
@@ -7099,23 +9821,41 @@ impl JobHandler for ReEmbedAllHandler {
             return JobResult::Success(Some(reembed_all_no_notes_job_result()));
         }
 
+        // Resume after the last checkpointed note if an earlier run was interrupted
+        let checkpoint: Option<ReEmbedAllCheckpoint> = ctx.checkpoint().await;
+        let (mut queued, mut failed) = checkpoint
+            .map(|checkpoint| (checkpoint.queued, checkpoint.failed))
+            .unwrap_or((0, 0));
+        let note_ids = reembed_remaining_notes(note_ids, checkpoint.as_ref());
+        let resumed = total_notes - note_ids.len();
+        if resumed > 0 {
+            info!(
+                total_notes,
+                resumed, "Resuming bulk re-embedding from checkpoint"
+            );
+        }
+
         ctx.report_progress(
             20,
             Some(&format!(
                 "Queueing embedding jobs for {} notes...",
-                total_notes
+                note_ids.len()
             )),
         );
 
         // Queue embedding jobs for each note
-        let mut queued = 0;
-        let mut failed = 0;
-
         for (i, note_id) in note_ids.iter().enumerate() {
             match self
                 .db
                 .jobs
-                .queue(Some(*note_id), JobType::Embedding, 5, None, None)
+                .queue_in_lane(
+                    Some(*note_id),
+                    JobType::Embedding,
+                    5,
+                    None,
+                    None,
+                    JobLane::Batch,
+                )
                 .await
             {
                 Ok(_) => queued += 1,
@@ -7130,12 +9870,28 @@ impl JobHandler for ReEmbedAllHandler {
                 }
             }
 
+            let done = resumed + i + 1;
+            if (i + 1) % REEMBED_CHECKPOINT_INTERVAL == 0 {
+                let checkpoint = ReEmbedAllCheckpoint {
+                    last_note_id: *note_id,
+                    queued,
+                    failed,
+                };
+                if let Err(e) = ctx.save_checkpoint(&checkpoint).await {
+                    warn!(
+                        error_len = diagnostic_len(&e),
+                        operation = "save_checkpoint",
+                        "Failed to checkpoint bulk re-embedding"
+                    );
+                }
+            }
+
             // Update progress every 10 notes or at the end
-            if (i + 1) % 10 == 0 || i + 1 == total_notes {
-                let progress = 20 + ((i + 1) * 80 / total_notes) as i32;
+            if (i + 1) % 10 == 0 || done == total_notes {
+                let progress = 20 + (done * 80 / total_notes) as i32;
                 ctx.report_progress(
                     progress.min(99),
-                    Some(&format!("Queued {}/{} embedding jobs", i + 1, total_notes)),
+                    Some(&format!("Queued {}/{} embedding jobs", done, total_notes)),
                 );
             }
         }
@@ -7402,12 +10158,13 @@ impl JobHandler for RefreshEmbeddingSetHandler {
             match self
                 .db
                 .jobs
-                .queue(
+                .queue_in_lane(
                     Some(*note_id),
                     JobType::Embedding,
                     JobType::Embedding.default_priority(),
                     Some(payload),
                     None,
+                    JobLane::Batch,
                 )
                 .await
             {
@@ -7906,7 +10663,8 @@ impl JobHandler for ExifExtractionHandler {
 /// 2. SNN scoring (#474) — prune below threshold
 /// 3. PFNET sparsification (#476) — prune geometrically redundant edges
 /// 4. Louvain community detection (#473) — recompute community assignments
-/// 5. Save diagnostics snapshot for before/after comparison
+/// 5. Graph analytics — store PageRank, betweenness, communities and bridges
+/// 6. Save diagnostics snapshot for before/after comparison
 pub struct GraphMaintenanceHandler {
     db: Database,
 }
@@ -7955,6 +10713,7 @@ impl JobHandler for GraphMaintenanceHandler {
                     "normalize".to_string(),
                     "snn".to_string(),
                     "pfnet".to_string(),
+                    "analytics".to_string(),
                     "snapshot".to_string(),
                 ]
             });
@@ -7965,7 +10724,7 @@ impl JobHandler for GraphMaintenanceHandler {
         if steps.iter().any(|s| s == "normalize") {
             ctx.report_progress(
                 15,
-                Some("Step 1/4: Edge normalization (applied at query time)"),
+                Some("Step 1/5: Edge normalization (applied at query time)"),
             );
             results.insert(
                 "normalize".to_string(),
@@ -7979,7 +10738,7 @@ impl JobHandler for GraphMaintenanceHandler {
 
         // Step 2: SNN scoring.
         if steps.iter().any(|s| s == "snn") {
-            ctx.report_progress(30, Some("Step 2/4: Recomputing SNN scores..."));
+            ctx.report_progress(30, Some("Step 2/5: Recomputing SNN scores..."));
             let links_clone = matric_db::PgLinkRepository::new(self.db.pool.clone());
             let threshold = graph_config.snn_threshold;
             let snn_result = schema_ctx
@@ -8020,7 +10779,7 @@ impl JobHandler for GraphMaintenanceHandler {
 
         // Step 3: PFNET sparsification.
         if steps.iter().any(|s| s == "pfnet") {
-            ctx.report_progress(55, Some("Step 3/4: PFNET sparsification..."));
+            ctx.report_progress(55, Some("Step 3/5: PFNET sparsification..."));
             let links_clone = matric_db::PgLinkRepository::new(self.db.pool.clone());
             let q = graph_config.pfnet_q;
             let pfnet_result = schema_ctx
@@ -8051,9 +10810,42 @@ impl JobHandler for GraphMaintenanceHandler {
             }
         }
 
-        // Step 4: Save diagnostics snapshot for before/after comparison.
+        // Step 4: Graph analytics over the sparsified graph.
+        if steps.iter().any(|s| s == "analytics") {
+            ctx.report_progress(70, Some("Step 4/5: Computing graph analytics..."));
+            let links_clone = matric_db::PgLinkRepository::new(self.db.pool.clone());
+            let analytics_result = schema_ctx
+                .query(move |tx| {
+                    Box::pin(async move { links_clone.refresh_graph_analytics_tx(tx).await })
+                })
+                .await;
+
+            match analytics_result {
+                Ok(run) => {
+                    info!(
+                        note_count = run.note_count,
+                        community_count = run.community_count,
+                        bridge_count = run.bridge_count,
+                        betweenness_sampled = run.betweenness_sampled,
+                        "Graph analytics complete"
+                    );
+                    results.insert(
+                        "analytics".to_string(),
+                        serde_json::to_value(run).unwrap_or_default(),
+                    );
+                }
+                Err(e) => {
+                    results.insert(
+                        "analytics".to_string(),
+                        graph_maintenance_step_failure(e, "analytics"),
+                    );
+                }
+            }
+        }
+
+        // Step 5: Save diagnostics snapshot for before/after comparison.
         if steps.iter().any(|s| s == "snapshot") {
-            ctx.report_progress(80, Some("Step 4/4: Saving diagnostics snapshot..."));
+            ctx.report_progress(80, Some("Step 5/5: Saving diagnostics snapshot..."));
             let links_clone = matric_db::PgLinkRepository::new(self.db.pool.clone());
             let snapshot_result = schema_ctx
                 .query(move |tx| {
@@ -9168,12 +11960,12 @@ Quick note about the meeting discussion and action items."#;
     }
 
     #[test]
-    fn test_merge_json_arrays_dedup() {
+    fn test_merge_concept_lists_dedup() {
         let results = vec![
-            r#"["science/ml", "tool/pytorch"]"#.to_string(),
-            r#"["science/ml", "tool/tensorflow"]"#.to_string(),
+            vec!["science/ml".to_string(), "tool/pytorch".to_string()],
+            vec!["science/ml".to_string(), "tool/tensorflow".to_string()],
         ];
-        let merged = merge_json_arrays(results);
+        let merged = merge_concept_lists(results);
         assert_eq!(merged.len(), 3);
         assert!(merged.contains(&"science/ml".to_string()));
         assert!(merged.contains(&"tool/pytorch".to_string()));
@@ -9181,19 +11973,19 @@ Quick note about the meeting discussion and action items."#;
     }
 
     #[test]
-    fn test_merge_json_arrays_empty() {
-        let results = vec![r#"[]"#.to_string(), r#"[]"#.to_string()];
-        let merged = merge_json_arrays(results);
+    fn test_merge_concept_lists_empty() {
+        let results = vec![Vec::new(), Vec::new()];
+        let merged = merge_concept_lists(results);
         assert!(merged.is_empty());
     }
 
     #[test]
-    fn test_merge_json_arrays_case_insensitive_dedup() {
+    fn test_merge_concept_lists_case_insensitive_dedup() {
         let results = vec![
-            r#"["Science/ML"]"#.to_string(),
-            r#"["science/ml"]"#.to_string(),
+            vec!["Science/ML".to_string()],
+            vec!["science/ml".to_string()],
         ];
-        let merged = merge_json_arrays(results);
+        let merged = merge_concept_lists(results);
         // Should deduplicate case-insensitively, keeping the first occurrence
         assert_eq!(merged.len(), 1);
         assert_eq!(merged[0], "Science/ML");
@@ -9251,6 +12043,98 @@ Quick note about the meeting discussion and action items."#;
         assert_eq!(size, matric_core::defaults::REVISION_CHUNK_SIZE_MIN);
     }
 
+    // =========================================================================
+    // Reference Context Budget Tests
+    // =========================================================================
+
+    fn word_count(text: &str) -> usize {
+        text.split_whitespace().count()
+    }
+
+    fn reference_candidate(link_count: i64, score: f32, words: usize) -> ReferenceCandidate {
+        ReferenceCandidate {
+            note_id: uuid::Uuid::new_v4(),
+            score,
+            link_count,
+            text: vec!["word"; words].join(" "),
+        }
+    }
+
+    #[test]
+    fn test_reference_budget_for_context() {
+        // 8_192 * 0.85 = 6_963; minus 512 reserved = 6_451; 20% = 1_290
+        assert_eq!(reference_budget_for_context(8_192), 1_290);
+        assert_eq!(reference_budget_for_context(100), 0);
+    }
+
+    #[test]
+    fn test_reference_context_orders_by_link_count_then_score() {
+        let candidates = vec![
+            reference_candidate(1, 0.9, 5),
+            reference_candidate(4, 0.5, 5),
+            reference_candidate(4, 0.8, 5),
+        ];
+        let expected = vec![
+            candidates[2].note_id,
+            candidates[1].note_id,
+            candidates[0].note_id,
+        ];
+        let context = assemble_reference_context(candidates, &word_count, 1_000, 5);
+        assert_eq!(context.note_ids, expected);
+        assert_eq!(context.tokens, 18);
+        assert_eq!(context.truncated, 0);
+        assert_eq!(context.dropped, 0);
+    }
+
+    #[test]
+    fn test_reference_context_truncates_then_drops_over_budget() {
+        let min = matric_core::defaults::REVISION_REFERENCE_MIN_TOKENS;
+        let linked = reference_candidate(3, 0.5, 40);
+        let partial = reference_candidate(2, 0.5, 200);
+        let unreached = reference_candidate(1, 0.9, 5);
+        let linked_id = linked.note_id;
+        let partial_id = partial.note_id;
+
+        // 41 tokens for the first line leaves `min + 5` for the second.
+        let budget = 41 + min + 5;
+        let context =
+            assemble_reference_context(vec![unreached, partial, linked], &word_count, budget, 5);
+        assert_eq!(context.note_ids, vec![linked_id, partial_id]);
+        assert_eq!(context.truncated, 1);
+        assert_eq!(context.dropped, 1);
+        assert!(context.tokens <= budget);
+        assert!(context.text.ends_with("…\n"));
+    }
+
+    #[test]
+    fn test_reference_context_drops_remnant_below_minimum() {
+        let min = matric_core::defaults::REVISION_REFERENCE_MIN_TOKENS;
+        let first = reference_candidate(2, 0.5, 10);
+        let first_id = first.note_id;
+        let context = assemble_reference_context(
+            vec![first, reference_candidate(1, 0.5, 200)],
+            &word_count,
+            11 + min - 1,
+            5,
+        );
+        assert_eq!(context.note_ids, vec![first_id]);
+        assert_eq!(context.truncated, 0);
+        assert_eq!(context.dropped, 1);
+    }
+
+    #[test]
+    fn test_reference_context_respects_note_cap_and_skips_empty() {
+        let mut candidates: Vec<_> = (0..4).map(|i| reference_candidate(i, 0.5, 3)).collect();
+        candidates.push(ReferenceCandidate {
+            text: "  \n ".to_string(),
+            ..reference_candidate(10, 0.9, 0)
+        });
+        let context = assemble_reference_context(candidates, &word_count, 1_000, 2);
+        assert_eq!(context.note_ids.len(), 2);
+        assert_eq!(context.dropped, 2);
+        assert_eq!(context.text, "- word word word\n- word word word\n");
+    }
+
     #[test]
     fn test_chunk_for_revision_small_content() {
         let small = "A short note about Rust programming.";
@@ -9536,6 +12420,8 @@ Quick note about the meeting discussion and action items."#;
                 agent_hints: Default::default(),
                 revision_chunking: None,
             },
+            pipeline_policy: Default::default(),
+            metadata_schema: None,
         }
     }
 
@@ -9848,7 +12734,6 @@ Quick note about the meeting discussion and action items."#;
         assert_eq!(diagnostic_len(raw), raw.chars().count());
 
         for detail in [
-            JOB_CHUNK_MERGE_PARSE_FAILURE_DETAIL,
             JOB_AI_GENERATION_DIAGNOSTIC_FAILURE_DETAIL,
             JOB_AI_REVISION_DIAGNOSTIC_FAILURE_DETAIL,
             JOB_AI_CONTEXTUAL_REVISION_DIAGNOSTIC_FAILURE_DETAIL,
@@ -10306,6 +13191,27 @@ Quick note about the meeting discussion and action items."#;
         }
     }
 
+    #[test]
+    fn reembed_resumes_after_the_checkpointed_note() {
+        let ids: Vec<uuid::Uuid> = (1..=5).map(uuid::Uuid::from_u128).collect();
+        let shuffled = vec![ids[3], ids[0], ids[4], ids[2], ids[1]];
+
+        assert_eq!(reembed_remaining_notes(shuffled.clone(), None), ids);
+
+        let checkpoint = ReEmbedAllCheckpoint {
+            last_note_id: ids[2],
+            queued: 3,
+            failed: 0,
+        };
+        assert_eq!(
+            reembed_remaining_notes(shuffled, Some(&checkpoint)),
+            vec![ids[3], ids[4]]
+        );
+        let round_trip: ReEmbedAllCheckpoint =
+            serde_json::from_value(serde_json::to_value(checkpoint).unwrap()).unwrap();
+        assert_eq!(round_trip, checkpoint);
+    }
+
     #[test]
     fn refresh_embedding_set_job_failure_uses_generic_stored_message() {
         let result = refresh_embedding_set_job_failure(
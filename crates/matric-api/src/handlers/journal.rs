@@ -0,0 +1,231 @@
+//! Journal HTTP handlers.
+//!
+//! - `GET /api/v1/journal/settings` — the archive's journal settings
+//! - `PUT /api/v1/journal/settings` — replace the journal settings
+//! - `GET|POST /api/v1/journal/today` — today's journal note, created on first use
+//!
+//! Notes created on a day with a journal note are linked from it as they
+//! enter the NLP pipeline; notes created earlier that day are linked when
+//! the journal note is created.
+//!
+//! Every user has a journal note of their own, owned by them, that links
+//! only the notes they own. Requests acting for no user share a journal
+//! note without an owner, which links the notes without an owner.
+
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use chrono::Utc;
+
+use crate::middleware::ownership::Caller;
+use crate::{event_context_for, queue_nlp_pipeline_inner, ApiError, AppState, ArchiveContext};
+use matric_core::journal::{
+    default_journal_content, journal_template_values, journal_title, JOURNAL_NOTE_SOURCE,
+};
+use matric_core::note_template::{render_note_template, resolve_template_variables};
+use matric_core::{
+    CreateNoteRequest, JournalSettings, JournalSettingsRequest, JournalToday, RevisionMode,
+    ServerEvent,
+};
+use matric_db::{PgNoteRepository, PgTemplateRepository};
+
+/// Get the journal settings.
+///
+/// The defaults until settings are saved.
+///
+/// GET /api/v1/journal/settings
+#[utoipa::path(get, path = "/api/v1/journal/settings", tag = "Notes",
+    responses((status = 200, description = "Journal settings", body = JournalSettings)))]
+pub async fn get_journal_settings(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+) -> Result<Json<JournalSettings>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let journal = state.db.journal.clone();
+    let settings = ctx
+        .query(move |tx| Box::pin(async move { journal.settings_tx(tx).await }))
+        .await?;
+    Ok(Json(settings))
+}
+
+/// Replace the journal settings.
+///
+/// Unset fields take their defaults. Notes already linked into the journal
+/// stay linked when `link_notes` is turned off.
+///
+/// PUT /api/v1/journal/settings
+#[utoipa::path(put, path = "/api/v1/journal/settings", tag = "Notes",
+    request_body = JournalSettingsRequest,
+    responses(
+        (status = 200, description = "Journal settings saved", body = JournalSettings),
+        (status = 400, description = "Invalid title format or time zone, or unknown template or collection")
+    ))]
+pub async fn update_journal_settings(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Json(body): Json<JournalSettingsRequest>,
+) -> Result<Json<JournalSettings>, ApiError> {
+    let settings = body.into_settings()?;
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let journal = state.db.journal.clone();
+    let saved = ctx
+        .execute(move |tx| Box::pin(async move { journal.save_settings_tx(tx, &settings).await }))
+        .await?;
+    Ok(Json(saved))
+}
+
+/// Get today's journal note, creating it on first use.
+///
+/// Idempotent: every call for the same user on the same day returns the
+/// same note until it is deleted. A new note is made from the journal template when one is set,
+/// titled with the title format, filed in the journal collection (or the
+/// template's) and linked to the notes already created today.
+///
+/// GET /api/v1/journal/today
+#[utoipa::path(get, path = "/api/v1/journal/today", tag = "Notes",
+    responses(
+        (status = 200, description = "Today's journal note", body = JournalToday),
+        (status = 400, description = "The journal template needs variable values")
+    ))]
+pub async fn get_journal_today(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+) -> Result<Json<JournalToday>, ApiError> {
+    let today = journal_today(&state, &archive_ctx, caller).await?;
+    Ok(Json(today))
+}
+
+/// Get today's journal note, creating it on first use.
+///
+/// As `GET`, but answers 201 when this request created the note.
+///
+/// POST /api/v1/journal/today
+#[utoipa::path(post, path = "/api/v1/journal/today", tag = "Notes",
+    responses(
+        (status = 200, description = "Today's journal note already existed", body = JournalToday),
+        (status = 201, description = "Today's journal note created", body = JournalToday),
+        (status = 400, description = "The journal template needs variable values")
+    ))]
+pub async fn create_journal_today(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+) -> Result<(StatusCode, Json<JournalToday>), ApiError> {
+    let today = journal_today(&state, &archive_ctx, caller).await?;
+    let status = if today.created {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    Ok((status, Json(today)))
+}
+
+/// Find or create the caller's journal note for today under the archive's
+/// journal lock.
+async fn journal_today(
+    state: &AppState,
+    archive_ctx: &ArchiveContext,
+    caller: Caller,
+) -> Result<JournalToday, ApiError> {
+    let owner = caller.user_id;
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let journal = state.db.journal.clone();
+    let users = state.db.users.clone();
+    let templates = PgTemplateRepository::new(state.db.pool.clone());
+    let notes = PgNoteRepository::new(state.db.pool.clone());
+    let today = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                journal.lock_tx(tx).await?;
+                let settings = journal.settings_tx(tx).await?;
+                let date = journal.today_tx(tx, &settings.timezone).await?;
+                if let Some(note_id) = journal.entry_tx(tx, date, owner).await? {
+                    let note = notes.fetch_tx(tx, note_id).await?;
+                    return Ok(JournalToday {
+                        date,
+                        created: false,
+                        note,
+                    });
+                }
+
+                let title = journal_title(&settings.title_format, date);
+                let template = match settings.template_id {
+                    Some(template_id) => templates.get_tx(tx, template_id).await?,
+                    None => None,
+                };
+                let request = match template {
+                    Some(template) => {
+                        let provided = journal_template_values(&template.variables, date, &title);
+                        let values =
+                            resolve_template_variables(&template.variables, &provided, Utc::now())?;
+                        CreateNoteRequest {
+                            content: render_note_template(&template.content, &values),
+                            format: template.format,
+                            source: JOURNAL_NOTE_SOURCE.to_string(),
+                            collection_id: settings.collection_id.or(template.collection_id),
+                            tags: (!template.default_tags.is_empty())
+                                .then_some(template.default_tags),
+                            metadata: None,
+                            document_type_id: None,
+                            title: Some(title),
+                        }
+                    }
+                    None => CreateNoteRequest {
+                        content: default_journal_content(&title),
+                        format: "markdown".to_string(),
+                        source: JOURNAL_NOTE_SOURCE.to_string(),
+                        collection_id: settings.collection_id,
+                        tags: None,
+                        metadata: None,
+                        document_type_id: None,
+                        title: Some(title),
+                    },
+                };
+                let note_id = notes.insert_tx(tx, request).await?;
+                if let Some(owner_id) = owner {
+                    users.set_note_owner_tx(tx, note_id, owner_id).await?;
+                }
+                journal.set_entry_tx(tx, date, owner, note_id).await?;
+                if settings.link_notes {
+                    journal
+                        .link_day_tx(tx, note_id, date, &settings.timezone, owner)
+                        .await?;
+                }
+                let note = notes.fetch_tx(tx, note_id).await?;
+                Ok(JournalToday {
+                    date,
+                    created: true,
+                    note,
+                })
+            })
+        })
+        .await?;
+
+    if today.created {
+        let note_id = today.note.note.id;
+        let schema_for_jobs =
+            (archive_ctx.schema != "public").then_some(archive_ctx.schema.as_str());
+        queue_nlp_pipeline_inner(
+            &state.db,
+            note_id,
+            RevisionMode::None,
+            &state.event_bus,
+            schema_for_jobs,
+            None,
+            true,
+            None,
+            None,
+            None,
+        )
+        .await;
+        state.event_bus.emit_with_context(
+            ServerEvent::NoteCreated {
+                note_id,
+                title: today.note.note.title.clone(),
+                tags: today.note.tags.clone(),
+            },
+            event_context_for(archive_ctx),
+        );
+        state.search_cache.invalidate_all().await;
+    }
+    Ok(today)
+}
@@ -0,0 +1,185 @@
+//! Link suggestion HTTP handlers.
+//!
+//! - `GET /api/v1/notes/{id}/link-suggestions` — notes not yet linked, ranked with reasons
+//! - `POST /api/v1/notes/{id}/link-suggestions/{target_id}/accept` — link the notes
+//! - `POST /api/v1/notes/{id}/link-suggestions/{target_id}/reject` — stop suggesting the pair
+//!
+//! Each decision moves the archive's linking threshold, so automatic linking
+//! follows what reviewers keep accepting or rejecting.
+
+use std::fmt;
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::{
+    LinkSuggestionFeedback, LinkSuggestions, DEFAULT_LINK_SUGGESTION_LIMIT,
+    MAX_LINK_SUGGESTION_LIMIT,
+};
+
+#[derive(Deserialize)]
+pub struct LinkSuggestionQuery {
+    /// Maximum number of suggestions to return (default: 10, max: 50).
+    limit: Option<i64>,
+}
+
+impl fmt::Debug for LinkSuggestionQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkSuggestionQuery")
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+/// Who reviewed a suggestion.
+#[derive(Default, Deserialize, utoipa::ToSchema)]
+pub struct LinkSuggestionFeedbackRequest {
+    #[serde(default)]
+    pub reviewed_by: Option<String>,
+}
+
+impl fmt::Debug for LinkSuggestionFeedbackRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkSuggestionFeedbackRequest")
+            .field("reviewed_by_set", &self.reviewed_by.is_some())
+            .finish()
+    }
+}
+
+/// Suggest links for a note.
+///
+/// Notes the note is not linked to in either direction, best first, scored
+/// from embedding similarity, shared concepts and co-citation. Pairs a
+/// reviewer rejected and notes the caller cannot see are left out.
+///
+/// GET /api/v1/notes/{id}/link-suggestions
+#[utoipa::path(get, path = "/api/v1/notes/{id}/link-suggestions", tag = "Graph",
+    params(
+        ("id" = Uuid, Path, description = "Note ID"),
+        ("limit" = Option<i64>, Query, description = "Maximum results (default 10, max 50)")
+    ),
+    responses(
+        (status = 200, description = "Link suggestions", body = LinkSuggestions),
+        (status = 404, description = "Note not found")
+    ))]
+pub async fn list_link_suggestions(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+    Query(query): Query<LinkSuggestionQuery>,
+) -> Result<Json<LinkSuggestions>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_LINK_SUGGESTION_LIMIT)
+        .clamp(1, MAX_LINK_SUGGESTION_LIMIT);
+    let security = caller.security_filter();
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let suggestions = state.db.link_suggestions.clone();
+    let list = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                suggestions
+                    .suggestions_tx(tx, id, limit, security.as_ref())
+                    .await
+            })
+        })
+        .await?;
+    Ok(Json(list))
+}
+
+async fn review_link_suggestion(
+    state: AppState,
+    archive_ctx: ArchiveContext,
+    caller: Caller,
+    (note_id, target_id): (Uuid, Uuid),
+    accepted: bool,
+    body: Option<Json<LinkSuggestionFeedbackRequest>>,
+) -> Result<Json<LinkSuggestionFeedback>, ApiError> {
+    let reviewed_by = body.and_then(|Json(body)| body.reviewed_by);
+    let security = caller.security_filter();
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let suggestions = state.db.link_suggestions.clone();
+    let feedback = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                suggestions
+                    .feedback_tx(
+                        tx,
+                        note_id,
+                        target_id,
+                        accepted,
+                        reviewed_by.as_deref(),
+                        security.as_ref(),
+                    )
+                    .await
+            })
+        })
+        .await?;
+    if feedback.link_created {
+        state.search_cache.invalidate_all().await;
+    }
+    Ok(Json(feedback))
+}
+
+/// Accept a link suggestion.
+///
+/// Links the two notes both ways, unless they are already linked, and
+/// lowers the archive's linking threshold one step.
+///
+/// POST /api/v1/notes/{id}/link-suggestions/{target_id}/accept
+#[utoipa::path(post, path = "/api/v1/notes/{id}/link-suggestions/{target_id}/accept", tag = "Graph",
+    params(
+        ("id" = Uuid, Path, description = "Note ID"),
+        ("target_id" = Uuid, Path, description = "Suggested note ID")
+    ),
+    request_body = LinkSuggestionFeedbackRequest,
+    responses(
+        (status = 200, description = "Suggestion accepted", body = LinkSuggestionFeedback),
+        (status = 400, description = "Note and target are the same"),
+        (status = 404, description = "Note not found")
+    ))]
+pub async fn accept_link_suggestion(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(ids): Path<(Uuid, Uuid)>,
+    body: Option<Json<LinkSuggestionFeedbackRequest>>,
+) -> Result<Json<LinkSuggestionFeedback>, ApiError> {
+    review_link_suggestion(state, archive_ctx, caller, ids, true, body).await
+}
+
+/// Reject a link suggestion.
+///
+/// The pair is not suggested again, and the archive's linking threshold
+/// rises one step. Existing links are kept.
+///
+/// POST /api/v1/notes/{id}/link-suggestions/{target_id}/reject
+#[utoipa::path(post, path = "/api/v1/notes/{id}/link-suggestions/{target_id}/reject", tag = "Graph",
+    params(
+        ("id" = Uuid, Path, description = "Note ID"),
+        ("target_id" = Uuid, Path, description = "Suggested note ID")
+    ),
+    request_body = LinkSuggestionFeedbackRequest,
+    responses(
+        (status = 200, description = "Suggestion rejected", body = LinkSuggestionFeedback),
+        (status = 400, description = "Note and target are the same"),
+        (status = 404, description = "Note not found")
+    ))]
+pub async fn reject_link_suggestion(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(ids): Path<(Uuid, Uuid)>,
+    body: Option<Json<LinkSuggestionFeedbackRequest>>,
+) -> Result<Json<LinkSuggestionFeedback>, ApiError> {
+    review_link_suggestion(state, archive_ctx, caller, ids, false, body).await
+}
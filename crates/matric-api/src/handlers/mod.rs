@@ -4,23 +4,59 @@
 
 pub mod archives;
 pub mod audio;
+pub mod backup_policies;
+pub mod calendar;
 pub mod chat;
+pub mod citations;
+pub mod collection_rules;
+pub mod collection_subtrees;
+pub mod concept_governance;
+pub mod concept_health;
+pub mod concept_mappings;
+pub mod concept_suggestions;
+pub mod digests;
 pub mod document_types;
+pub mod entities;
+pub mod federation;
+pub mod fine_tuning;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod image_search;
 pub mod inference_complete;
 pub mod inference_config;
+pub mod inference_usage;
 pub mod ingest_stream;
 pub mod ingest_tokens;
 pub mod jobs;
+pub mod journal;
+pub mod link_suggestions;
 pub mod models;
+pub mod note_bulk;
+pub mod note_views;
+pub mod pinned;
 pub mod pke;
+pub mod prompts;
 pub mod provenance;
+pub mod publishing;
+pub mod resurface;
+pub mod review;
+pub mod sharing;
+pub mod similar;
+pub mod site_export;
+pub mod tasks;
+pub mod topics;
+pub mod trash;
+pub mod typed_links;
 pub mod vision;
 
 // Re-export job handlers for backwards compatibility
 pub use jobs::{
-    AiRevisionContextualHandler, AiRevisionHandler, ConceptTaggingHandler, ContextUpdateHandler,
-    DocumentTypeInferenceHandler, EmbeddingHandler, ExifExtractionHandler, GraphMaintenanceHandler,
-    LinkingHandler, MetadataExtractionHandler, PurgeNoteHandler, ReEmbedAllHandler,
+    AiRevisionContextualHandler, AiRevisionHandler, CitationExtractionHandler,
+    ConceptTaggingHandler, ContextUpdateHandler, DigestGenerationHandler,
+    DocumentTypeInferenceHandler, EmbeddingHandler, EntityExtractionHandler, ExifExtractionHandler,
+    GenerateFineTuningDataHandler, GraphMaintenanceHandler, LinkingHandler,
+    MetadataExtractionHandler, PiiScanHandler, PurgeNoteHandler, ReEmbedAllHandler,
     ReferenceExtractionHandler, RefreshEmbeddingSetHandler, RelatedConceptHandler,
-    TitleGenerationHandler,
+    SummarizationHandler, TaskExtractionHandler, TitleGenerationHandler, TopicModelingHandler,
+    TranslationHandler,
 };
@@ -0,0 +1,132 @@
+//! Bulk note update HTTP handler.
+//!
+//! - `PATCH /api/v1/notes/bulk` — apply one patch to every note matching a
+//!   filter, or with `dry_run` only count them
+//!
+//! The update itself runs as a `bulk_note_update` job that reports progress
+//! after each batch of notes. Requests acting for a user only match notes the
+//! user can read, and the job only changes notes the user can write.
+
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::{BulkNoteUpdateRequest, JobRepository, JobType, ServerEvent};
+
+/// Job payload applying the request's patch in an archive, acting for
+/// `user_id` when given.
+fn bulk_note_update_payload(
+    schema: &str,
+    req: &BulkNoteUpdateRequest,
+    user_id: Option<Uuid>,
+) -> serde_json::Value {
+    let mut payload = json!({ "schema": schema, "filter": req.filter, "patch": req.patch });
+    if let Some(user_id) = user_id {
+        payload["user_id"] = json!(user_id);
+    }
+    payload
+}
+
+/// Update every note matching a filter.
+///
+/// Counts the matching notes now; unless `dry_run` is set, queues a
+/// `bulk_note_update` job that re-resolves the filter and applies the patch.
+/// For a caller acting for a user, only notes the user can read match, and
+/// the job skips notes the user cannot write.
+///
+/// PATCH /api/v1/notes/bulk
+#[utoipa::path(patch, path = "/api/v1/notes/bulk", tag = "Notes",
+    request_body = BulkNoteUpdateRequest,
+    responses(
+        (status = 200, description = "Dry run: number of matching notes"),
+        (status = 202, description = "Update queued"),
+        (status = 400, description = "Empty or invalid filter or patch"),
+        (status = 404, description = "Target collection not found")
+    ))]
+pub async fn bulk_update_notes(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Json(req): Json<BulkNoteUpdateRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let req = req.validated()?;
+    let security = caller.security_filter();
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let mut tx = ctx.begin_tx().await?;
+    state
+        .db
+        .bulk_notes
+        .check_patch_tx(&mut tx, &req.patch)
+        .await?;
+    let affected = state
+        .db
+        .bulk_notes
+        .matching_note_ids_tx(&mut tx, &req.filter, security.as_ref())
+        .await?
+        .len();
+    tx.commit().await.map_err(matric_db::Error::Database)?;
+
+    if req.dry_run {
+        return Ok((
+            StatusCode::OK,
+            Json(json!({ "dry_run": true, "affected": affected })),
+        ));
+    }
+
+    let job_id = state
+        .db
+        .jobs
+        .queue(
+            None,
+            JobType::BulkNoteUpdate,
+            JobType::BulkNoteUpdate.default_priority(),
+            Some(bulk_note_update_payload(
+                &archive_ctx.schema,
+                &req,
+                caller.user_id,
+            )),
+            JobType::BulkNoteUpdate.default_cost_tier(),
+        )
+        .await?;
+    state.event_bus.emit(ServerEvent::JobQueued {
+        job_id,
+        job_type: format!("{:?}", JobType::BulkNoteUpdate),
+        note_id: None,
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({ "status": "queued", "job_id": job_id, "affected": affected })),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_names_schema_filter_and_patch() {
+        let req: BulkNoteUpdateRequest = serde_json::from_value(json!({
+            "filter": { "tags": ["project"] },
+            "patch": { "archived": true },
+            "dry_run": false,
+        }))
+        .unwrap();
+        assert_eq!(
+            bulk_note_update_payload("archive_a", &req, None),
+            json!({
+                "schema": "archive_a",
+                "filter": { "tags": ["project"] },
+                "patch": { "archived": true },
+            })
+        );
+        let user_id = Uuid::nil();
+        assert_eq!(
+            bulk_note_update_payload("archive_a", &req, Some(user_id))["user_id"],
+            json!(user_id)
+        );
+    }
+}
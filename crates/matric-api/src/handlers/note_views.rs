@@ -0,0 +1,83 @@
+//! Recently viewed notes HTTP handler.
+//!
+//! - `GET /api/v1/notes/recently-viewed` — notes the caller opened, most
+//!   recently opened first
+//!
+//! Views are recorded by `GET /api/v1/notes/{id}` for the signed-in user, API
+//! key or OAuth client making the request (see
+//! [`matric_core::note_view::view_principal`]) and written in batches.
+
+use std::fmt;
+
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+use serde::Deserialize;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext, Auth};
+use matric_core::note_view::{
+    view_principal, DEFAULT_RECENTLY_VIEWED_LIMIT, MAX_RECENTLY_VIEWED_LIMIT,
+};
+use matric_core::RecentlyViewedNote;
+
+#[derive(Deserialize)]
+pub struct RecentlyViewedParams {
+    /// Maximum number of notes to return (default: 20, max: 100).
+    limit: Option<i64>,
+}
+
+impl fmt::Debug for RecentlyViewedParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecentlyViewedParams")
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+/// List recently viewed notes.
+///
+/// Notes the caller opened with `GET /api/v1/notes/{id}`, most recently
+/// opened first, with how often and since when. Deleted notes and notes the
+/// caller may no longer read are left out. Anonymous callers have no views
+/// and always get an empty list.
+///
+/// GET /api/v1/notes/recently-viewed
+#[utoipa::path(get, path = "/api/v1/notes/recently-viewed", tag = "Notes",
+    params(
+        ("limit" = Option<i64>, Query, description = "Max notes (default 20, max 100)")
+    ),
+    responses((status = 200, description = "Recently viewed notes", body = Vec<RecentlyViewedNote>)))]
+pub async fn list_recently_viewed(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    auth: Auth,
+    caller: Caller,
+    Query(params): Query<RecentlyViewedParams>,
+) -> Result<Json<Vec<RecentlyViewedNote>>, ApiError> {
+    let Some(principal) = view_principal(&auth.principal) else {
+        return Ok(Json(Vec::new()));
+    };
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_RECENTLY_VIEWED_LIMIT)
+        .clamp(1, MAX_RECENTLY_VIEWED_LIMIT);
+    let security = caller.security_filter();
+
+    // Include views still waiting in the buffer.
+    state.note_views.flush(&state.db).await;
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let note_views = state.db.note_views.clone();
+    let notes = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                note_views
+                    .recently_viewed_tx(tx, &principal, limit, security.as_ref())
+                    .await
+            })
+        })
+        .await?;
+    Ok(Json(notes))
+}
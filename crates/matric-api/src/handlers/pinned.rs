@@ -0,0 +1,76 @@
+//! Pinned notes HTTP handler.
+//!
+//! - `GET /api/v1/notes/pinned` — pinned notes of the archive, or of one
+//!   collection, highest priority first
+//!
+//! Notes are pinned and prioritised with `PATCH /api/v1/notes/{id}` or
+//! `PATCH /api/v1/notes/{id}/status`.
+
+use std::fmt;
+
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::pinned::{DEFAULT_PINNED_LIMIT, MAX_PINNED_LIMIT};
+use matric_core::{PinnedNote, PinnedQuery};
+
+#[derive(Deserialize)]
+pub struct PinnedParams {
+    /// Only notes filed in this collection.
+    collection_id: Option<Uuid>,
+    /// Maximum number of notes to return (default: 50, max: 200).
+    limit: Option<i64>,
+}
+
+impl fmt::Debug for PinnedParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PinnedParams")
+            .field("collection_id_set", &self.collection_id.is_some())
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+/// List pinned notes.
+///
+/// Pinned notes of the archive, highest priority first and then most
+/// recently updated. With `collection_id`, only notes filed directly in that
+/// collection. Archived notes stay listed while pinned; deleted notes do not.
+///
+/// GET /api/v1/notes/pinned
+#[utoipa::path(get, path = "/api/v1/notes/pinned", tag = "Notes",
+    params(
+        ("collection_id" = Option<Uuid>, Query, description = "Only notes in this collection"),
+        ("limit" = Option<i64>, Query, description = "Max notes (default 50, max 200)")
+    ),
+    responses((status = 200, description = "Pinned notes", body = Vec<PinnedNote>)))]
+pub async fn list_pinned_notes(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Query(params): Query<PinnedParams>,
+) -> Result<Json<Vec<PinnedNote>>, ApiError> {
+    let query = PinnedQuery {
+        collection_id: params.collection_id,
+        limit: params
+            .limit
+            .unwrap_or(DEFAULT_PINNED_LIMIT)
+            .clamp(1, MAX_PINNED_LIMIT),
+    };
+    let security = caller.security_filter();
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let pinned = state.db.pinned.clone();
+    let notes = ctx
+        .query(move |tx| {
+            Box::pin(async move { pinned.list_tx(tx, &query, security.as_ref()).await })
+        })
+        .await?;
+    Ok(Json(notes))
+}
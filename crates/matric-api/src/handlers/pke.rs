@@ -7,12 +7,15 @@ use tracing::warn;
 
 use matric_core::{
     AuditEvent, AuditFailurePolicy, AuditOutcome, AuditSeverity, AuditSink, AuditSource,
-    AuditVisibilityClass, AuthPrincipal, TracingSink,
+    AuditVisibilityClass, AuthPrincipal, JobRepository, JobType, ServerEvent, TracingSink,
 };
 use matric_crypto::pke::{
-    decrypt_pke, encrypt_pke, get_pke_recipients, key_storage, Address, Keypair, PrivateKey,
-    PublicKey,
+    age_interop::AGE_ARMOR_BEGIN, decrypt_age, decrypt_pke, encrypt_age, encrypt_pke,
+    get_pke_recipients, is_age_format, key_storage, parse_age_recipient, Address, Keypair,
+    PrivateKey, PublicKey,
 };
+use matric_crypto::sss::{combine_shares, split_secret, SecretShare, MIN_SHARE_THRESHOLD};
+use matric_crypto::SigningKey;
 
 // =============================================================================
 // REQUEST/RESPONSE TYPES
@@ -82,11 +85,27 @@ impl std::fmt::Debug for PkeAddressResponse {
     }
 }
 
+/// Ciphertext container format for the encrypt/decrypt endpoints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PkeCiphertextFormat {
+    /// matric-memory MMPKE01 (records recipient addresses and filename).
+    #[default]
+    Mmpke01,
+    /// age v1 with X25519 recipient stanzas, readable by standard `age` tooling.
+    Age,
+}
+
 #[derive(Deserialize, utoipa::ToSchema)]
 pub struct PkeEncryptRequest {
     pub plaintext: String,       // base64 encoded
-    pub recipients: Vec<String>, // mm:... addresses or base64 public keys
+    pub recipients: Vec<String>, // base64 public keys or age1... recipients
     pub original_filename: Option<String>,
+    #[serde(default)]
+    pub format: PkeCiphertextFormat,
+    /// ASCII-armor the age output (only valid with `format = "age"`).
+    #[serde(default)]
+    pub armor: bool,
 }
 
 impl std::fmt::Debug for PkeEncryptRequest {
@@ -98,14 +117,17 @@ impl std::fmt::Debug for PkeEncryptRequest {
                 "original_filename_len",
                 &optional_text_len(&self.original_filename),
             )
+            .field("format", &self.format)
+            .field("armor", &self.armor)
             .finish()
     }
 }
 
 #[derive(Serialize)]
 pub struct PkeEncryptResponse {
-    pub ciphertext: String,      // base64 encoded MMPKE01 format
+    pub ciphertext: String,      // base64 encoded MMPKE01 or age file
     pub recipients: Vec<String>, // mm:... addresses
+    pub format: PkeCiphertextFormat,
 }
 
 impl std::fmt::Debug for PkeEncryptResponse {
@@ -113,15 +135,19 @@ impl std::fmt::Debug for PkeEncryptResponse {
         f.debug_struct("PkeEncryptResponse")
             .field("ciphertext_len", &self.ciphertext.chars().count())
             .field("recipient_count", &self.recipients.len())
+            .field("format", &self.format)
             .finish()
     }
 }
 
 #[derive(Deserialize, utoipa::ToSchema)]
 pub struct PkeDecryptRequest {
-    pub ciphertext: String,            // base64 MMPKE01
+    pub ciphertext: String,            // base64 MMPKE01/age, or ASCII-armored age
     pub encrypted_private_key: String, // base64
     pub passphrase: String,
+    /// Expected ciphertext format; detected from the payload when omitted.
+    #[serde(default)]
+    pub format: Option<PkeCiphertextFormat>,
 }
 
 impl std::fmt::Debug for PkeDecryptRequest {
@@ -134,6 +160,7 @@ impl std::fmt::Debug for PkeDecryptRequest {
             )
             .field("passphrase_present", &!self.passphrase.is_empty())
             .field("passphrase_len", &self.passphrase.chars().count())
+            .field("format", &self.format)
             .finish()
     }
 }
@@ -142,6 +169,7 @@ impl std::fmt::Debug for PkeDecryptRequest {
 pub struct PkeDecryptResponse {
     pub plaintext: String, // base64
     pub original_filename: Option<String>,
+    pub format: PkeCiphertextFormat,
 }
 
 impl std::fmt::Debug for PkeDecryptResponse {
@@ -152,6 +180,7 @@ impl std::fmt::Debug for PkeDecryptResponse {
                 "original_filename_len",
                 &optional_text_len(&self.original_filename),
             )
+            .field("format", &self.format)
             .finish()
     }
 }
@@ -211,6 +240,10 @@ const PKE_KEYSET_CREATION_FAILURE_DETAIL: &str =
     "PKE keyset creation failed. Check server logs for diagnostics.";
 const PKE_KEYSET_AUDIT_EMIT_FAILURE_DETAIL: &str = "pke_keyset_audit_emit_failed";
 
+/// Hours a rotated keyset is kept before it may be purged.
+const DEFAULT_KEYSET_ROTATION_GRACE_HOURS: i64 = 168;
+const MAX_KEYSET_ROTATION_GRACE_HOURS: i64 = 8_760;
+
 fn invalid_pke_public_key_length() -> ApiError {
     ApiError::BadRequest("Public key must be 32 bytes.".to_string())
 }
@@ -219,6 +252,24 @@ fn invalid_pke_recipient_public_key_length() -> ApiError {
     ApiError::BadRequest("Recipient public key must be 32 bytes.".to_string())
 }
 
+/// Decode the `ciphertext` field: base64, or an ASCII-armored age file as-is.
+fn decode_pke_ciphertext(ciphertext: &str) -> Result<Vec<u8>, ApiError> {
+    if ciphertext.trim_start().starts_with(AGE_ARMOR_BEGIN) {
+        return Ok(ciphertext.as_bytes().to_vec());
+    }
+    BASE64
+        .decode(ciphertext)
+        .map_err(|_| ApiError::BadRequest("Invalid ciphertext base64.".to_string()))
+}
+
+fn detect_pke_ciphertext_format(ciphertext: &[u8]) -> PkeCiphertextFormat {
+    if is_age_format(ciphertext) {
+        PkeCiphertextFormat::Age
+    } else {
+        PkeCiphertextFormat::Mmpke01
+    }
+}
+
 // =============================================================================
 // HANDLERS
 // =============================================================================
@@ -312,8 +363,22 @@ pub async fn pke_encrypt(
         ));
     }
 
-    // Parse recipient public keys (base64 encoded)
-    // For now, we only support base64 public keys (not mm: addresses, which require lookup)
+    match req.format {
+        PkeCiphertextFormat::Mmpke01 if req.armor => {
+            return Err(ApiError::BadRequest(
+                "armor is only supported with format \"age\".".to_string(),
+            ));
+        }
+        PkeCiphertextFormat::Age if req.original_filename.is_some() => {
+            return Err(ApiError::BadRequest(
+                "original_filename is not supported with format \"age\".".to_string(),
+            ));
+        }
+        _ => {}
+    }
+
+    // Parse recipient public keys (base64 encoded or age1... recipients)
+    // For now, we do not support mm: addresses, which require lookup
     let mut recipient_keys = Vec::new();
     let mut recipient_addresses = Vec::new();
 
@@ -324,6 +389,13 @@ pub async fn pke_encrypt(
                     .to_string(),
             ));
         }
+        if r.starts_with("age1") {
+            let pk = parse_age_recipient(r)
+                .map_err(|_| ApiError::BadRequest("Invalid age recipient.".to_string()))?;
+            recipient_addresses.push(pk.to_address().to_string());
+            recipient_keys.push(pk);
+            continue;
+        }
         let key_bytes = BASE64
             .decode(r)
             .map_err(|_| ApiError::BadRequest("Invalid recipient public key.".to_string()))?;
@@ -340,22 +412,28 @@ pub async fn pke_encrypt(
         recipient_keys.push(pk);
     }
 
-    let ciphertext =
-        encrypt_pke(&plaintext, &recipient_keys, req.original_filename).map_err(|e| {
-            let diagnostic = e.to_string();
-            warn!(
-                error_len = diagnostic.chars().count(),
-                "PKE encryption failed"
-            );
-            ApiError::OperationFailed {
-                operation: "PKE encryption",
-                detail: PKE_ENCRYPTION_FAILURE_DETAIL.to_string(),
-            }
-        })?;
+    let ciphertext = match req.format {
+        PkeCiphertextFormat::Mmpke01 => {
+            encrypt_pke(&plaintext, &recipient_keys, req.original_filename)
+        }
+        PkeCiphertextFormat::Age => encrypt_age(&plaintext, &recipient_keys, req.armor),
+    }
+    .map_err(|e| {
+        let diagnostic = e.to_string();
+        warn!(
+            error_len = diagnostic.chars().count(),
+            "PKE encryption failed"
+        );
+        ApiError::OperationFailed {
+            operation: "PKE encryption",
+            detail: PKE_ENCRYPTION_FAILURE_DETAIL.to_string(),
+        }
+    })?;
 
     Ok(Json(PkeEncryptResponse {
         ciphertext: BASE64.encode(&ciphertext),
         recipients: recipient_addresses,
+        format: req.format,
     }))
 }
 
@@ -368,9 +446,13 @@ pub async fn pke_encrypt(
 pub async fn pke_decrypt(
     Json(req): Json<PkeDecryptRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let ciphertext = BASE64
-        .decode(&req.ciphertext)
-        .map_err(|_| ApiError::BadRequest("Invalid ciphertext base64.".to_string()))?;
+    let ciphertext = decode_pke_ciphertext(&req.ciphertext)?;
+    let format = detect_pke_ciphertext_format(&ciphertext);
+    if req.format.is_some_and(|requested| requested != format) {
+        return Err(ApiError::BadRequest(
+            "Ciphertext does not match the requested format.".to_string(),
+        ));
+    }
 
     let encrypted_key = BASE64
         .decode(&req.encrypted_private_key)
@@ -384,12 +466,19 @@ pub async fn pke_decrypt(
 
     let private_key = PrivateKey::from_bytes(private_key_bytes);
 
-    let (plaintext, header) = decrypt_pke(&ciphertext, &private_key)
-        .map_err(|_| ApiError::Forbidden("Unable to decrypt PKE payload.".to_string()))?;
+    let (plaintext, original_filename) = match format {
+        PkeCiphertextFormat::Mmpke01 => decrypt_pke(&ciphertext, &private_key)
+            .map(|(plaintext, header)| (plaintext, header.original_filename)),
+        PkeCiphertextFormat::Age => {
+            decrypt_age(&ciphertext, &private_key).map(|plaintext| (plaintext, None))
+        }
+    }
+    .map_err(|_| ApiError::Forbidden("Unable to decrypt PKE payload.".to_string()))?;
 
     Ok(Json(PkeDecryptResponse {
         plaintext: BASE64.encode(&plaintext),
-        original_filename: header.original_filename,
+        original_filename,
+        format,
     }))
 }
 
@@ -470,6 +559,14 @@ pub struct KeysetResponse {
     pub label: Option<String>,
     pub is_active: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Set on keysets retired by a rotation: when the grace period ends.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retire_after: Option<chrono::DateTime<chrono::Utc>>,
+    /// Base64url Ed25519 public key the keyset signs shards with. Recorded
+    /// once the private key has been unlocked (imported keysets gain it on
+    /// their first signed export).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_public_key: Option<String>,
 }
 
 impl std::fmt::Debug for KeysetResponse {
@@ -481,6 +578,8 @@ impl std::fmt::Debug for KeysetResponse {
             .field("label_len", &optional_text_len(&self.label))
             .field("is_active", &self.is_active)
             .field("created_at", &self.created_at)
+            .field("retire_after", &self.retire_after)
+            .field("signing_key_set", &self.signing_public_key.is_some())
             .finish()
     }
 }
@@ -532,6 +631,214 @@ impl std::fmt::Debug for ImportKeysetRequest {
     }
 }
 
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct RotateKeysetRequest {
+    /// Passphrase of the keyset being rotated.
+    pub passphrase: String,
+    /// Passphrase for the new private key. Defaults to `passphrase`.
+    pub new_passphrase: Option<String>,
+    /// Name of the successor keyset. Defaults to `<name>-<UTC timestamp>`.
+    pub new_name: Option<String>,
+    /// Hours the retired keyset is kept before it is purged (default 168).
+    pub grace_period_hours: Option<i64>,
+}
+
+impl std::fmt::Debug for RotateKeysetRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RotateKeysetRequest")
+            .field("passphrase_present", &!self.passphrase.is_empty())
+            .field("passphrase_len", &self.passphrase.chars().count())
+            .field(
+                "new_passphrase_len",
+                &optional_text_len(&self.new_passphrase),
+            )
+            .field("new_name_len", &optional_text_len(&self.new_name))
+            .field("grace_period_hours", &self.grace_period_hours)
+            .finish()
+    }
+}
+
+#[derive(Serialize)]
+pub struct RotateKeysetResponse {
+    /// The successor keyset new artifacts are sealed for.
+    pub keyset: KeysetResponse,
+    pub retired_keyset_id: Uuid,
+    /// When the retired keyset may be purged (once re-encryption completes).
+    pub retire_after: chrono::DateTime<chrono::Utc>,
+    /// The `pke_key_rotation` job re-encrypting existing artifacts.
+    pub job_id: Uuid,
+}
+
+impl std::fmt::Debug for RotateKeysetResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RotateKeysetResponse")
+            .field("keyset", &self.keyset)
+            .field("retired_keyset_id_present", &true)
+            .field("retire_after", &self.retire_after)
+            .field("job_id", &self.job_id)
+            .finish()
+    }
+}
+
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct SplitKeysetRequest {
+    /// Passphrase of the keyset being split.
+    pub passphrase: String,
+    /// Shares required to reconstruct the private key (at least 2).
+    pub threshold: u8,
+    /// Shares to create (at most 255).
+    pub shares: u8,
+}
+
+impl std::fmt::Debug for SplitKeysetRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SplitKeysetRequest")
+            .field("passphrase_present", &!self.passphrase.is_empty())
+            .field("passphrase_len", &self.passphrase.chars().count())
+            .field("threshold", &self.threshold)
+            .field("shares", &self.shares)
+            .finish()
+    }
+}
+
+#[derive(Serialize)]
+pub struct SplitKeysetResponse {
+    pub keyset_id: Uuid,
+    pub address: String,
+    pub threshold: u8,
+    /// Encoded `mmss1-...` shares; hand each to a different holder.
+    pub shares: Vec<String>,
+}
+
+impl std::fmt::Debug for SplitKeysetResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SplitKeysetResponse")
+            .field("keyset_id_present", &true)
+            .field("address_len", &self.address.chars().count())
+            .field("threshold", &self.threshold)
+            .field("share_count", &self.shares.len())
+            .finish()
+    }
+}
+
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct CombineKeysetSharesRequest {
+    /// At least `threshold` shares from one split of this keyset.
+    pub shares: Vec<String>,
+    /// Passphrase the recovered private key is re-encrypted with.
+    pub new_passphrase: String,
+}
+
+impl std::fmt::Debug for CombineKeysetSharesRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CombineKeysetSharesRequest")
+            .field("share_count", &self.shares.len())
+            .field("new_passphrase_present", &!self.new_passphrase.is_empty())
+            .field("new_passphrase_len", &self.new_passphrase.chars().count())
+            .finish()
+    }
+}
+
+fn rotation_grace_period_hours(requested: Option<i64>) -> Result<i64, ApiError> {
+    match requested {
+        None => Ok(DEFAULT_KEYSET_ROTATION_GRACE_HOURS),
+        Some(hours) if (0..=MAX_KEYSET_ROTATION_GRACE_HOURS).contains(&hours) => Ok(hours),
+        Some(_) => Err(ApiError::BadRequest(format!(
+            "grace_period_hours must be between 0 and {MAX_KEYSET_ROTATION_GRACE_HOURS}."
+        ))),
+    }
+}
+
+fn rotated_keyset_name(name: &str, now: chrono::DateTime<chrono::Utc>) -> String {
+    format!("{name}-{}", now.format("%Y%m%d%H%M%S"))
+}
+
+/// Unlock a stored keyset's private key and check it matches the public key.
+pub(crate) fn unlock_keyset_private_key(
+    keyset: &matric_db::PkeKeyset,
+    passphrase: &str,
+) -> Result<PrivateKey, ApiError> {
+    let invalid =
+        || ApiError::Forbidden("Invalid passphrase or corrupted private key.".to_string());
+    let bytes = key_storage::decrypt_private_key(&keyset.encrypted_private_key, passphrase)
+        .map_err(|_| invalid())?;
+    let private_key = PrivateKey::from_bytes(bytes);
+    if private_key.public_key().as_bytes().as_slice() != keyset.public_key.as_slice() {
+        return Err(invalid());
+    }
+    Ok(private_key)
+}
+
+/// Look up a keyset by name or ID, or the active keyset when none is given.
+pub(crate) async fn resolve_keyset_or_active(
+    db: &matric_db::Database,
+    name_or_id: Option<&str>,
+) -> Result<matric_db::PkeKeyset, ApiError> {
+    match name_or_id {
+        Some(name_or_id) => if let Ok(uuid) = Uuid::parse_str(name_or_id) {
+            db.pke_keysets.get_by_id(uuid).await
+        } else {
+            db.pke_keysets.get_by_name(name_or_id).await
+        }
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::NotFound("PKE keyset not found.".to_string())),
+        None => db
+            .pke_keysets
+            .get_active()
+            .await
+            .map_err(ApiError::from)?
+            .ok_or_else(|| ApiError::BadRequest("No active PKE keyset.".to_string())),
+    }
+}
+
+/// Public key of a stored keyset.
+pub(crate) fn keyset_public_key(keyset: &matric_db::PkeKeyset) -> Result<PublicKey, ApiError> {
+    let bytes: [u8; 32] = keyset
+        .public_key
+        .as_slice()
+        .try_into()
+        .map_err(|_| ApiError::Internal("Keyset public key is invalid.".to_string()))?;
+    Ok(PublicKey::from_bytes(bytes))
+}
+
+/// Base64url Ed25519 public key of the signer identity bound to a keyset.
+fn keyset_signing_public_key(private_key: &PrivateKey) -> String {
+    SigningKey::derive_from_pke(private_key)
+        .verifying_key()
+        .to_base64url()
+}
+
+/// Unlock a keyset's signer identity for signing knowledge shards.
+///
+/// Returns the signer key id (the keyset address) and the signing key, and
+/// records the signing public key so local imports trust the keyset.
+pub(crate) async fn unlock_keyset_signer(
+    state: &AppState,
+    name_or_id: &str,
+    passphrase: &str,
+) -> Result<(String, SigningKey), ApiError> {
+    let keyset = if let Ok(uuid) = Uuid::parse_str(name_or_id) {
+        state.db.pke_keysets.get_by_id(uuid).await
+    } else {
+        state.db.pke_keysets.get_by_name(name_or_id).await
+    }
+    .map_err(ApiError::from)?
+    .ok_or_else(|| ApiError::NotFound("PKE keyset not found.".to_string()))?;
+
+    let private_key = unlock_keyset_private_key(&keyset, passphrase)?;
+    let signing_key = SigningKey::derive_from_pke(&private_key);
+    let signing_public_key = signing_key.verifying_key().to_base64url();
+    if keyset.signing_public_key.as_deref() != Some(signing_public_key.as_str()) {
+        state
+            .db
+            .pke_keysets
+            .set_signing_public_key(keyset.id, &signing_public_key)
+            .await
+            .map_err(ApiError::from)?;
+    }
+    Ok((keyset.address, signing_key))
+}
+
 /// List all PKE keysets.
 ///
 /// GET /api/v1/pke/keysets
@@ -549,6 +856,8 @@ pub async fn list_keysets(State(state): State<AppState>) -> Result<impl IntoResp
             label: k.label,
             is_active: k.is_active,
             created_at: k.created_at,
+            retire_after: k.retire_after,
+            signing_public_key: k.signing_public_key,
         })
         .collect();
 
@@ -609,6 +918,13 @@ pub async fn create_keyset(
             }
             _ => ApiError::from(e),
         })?;
+    let signing_public_key = keyset_signing_public_key(&keypair.private);
+    state
+        .db
+        .pke_keysets
+        .set_signing_public_key(keyset.id, &signing_public_key)
+        .await
+        .map_err(ApiError::from)?;
 
     emit_pke_keyset_audit_event(pke_keyset_audit_event(
         &auth,
@@ -630,6 +946,8 @@ pub async fn create_keyset(
             label: keyset.label,
             is_active: false,
             created_at: keyset.created_at,
+            retire_after: None,
+            signing_public_key: Some(signing_public_key),
         }),
     ))
 }
@@ -658,6 +976,8 @@ pub async fn get_active_keyset(
             label: k.label,
             is_active: true,
             created_at: k.created_at,
+            retire_after: None,
+            signing_public_key: k.signing_public_key,
         }),
     }))
 }
@@ -728,6 +1048,20 @@ pub async fn delete_keyset(
 
     let keyset = keyset.ok_or_else(|| ApiError::NotFound("PKE keyset not found.".to_string()))?;
 
+    // Artifacts still sealed for a rotated keyset would become unreadable.
+    if state
+        .db
+        .pke_keysets
+        .get_retirement(keyset.id)
+        .await
+        .map_err(ApiError::from)?
+        .is_some_and(|r| r.reencryption_pending())
+    {
+        return Err(ApiError::Conflict(
+            "PKE keyset re-encryption is still in progress.".to_string(),
+        ));
+    }
+
     state
         .db
         .pke_keysets
@@ -795,6 +1129,409 @@ pub async fn export_keyset(
     Ok(Json(exported))
 }
 
+/// Rotate a PKE keyset by name or ID.
+///
+/// POST /api/v1/pke/keysets/:name_or_id/rotate
+///
+/// Generates a successor keypair, retires the keyset (the successor takes
+/// over as active if it was active) and queues a `pke_key_rotation` job that
+/// re-encrypts the notes and attachment blobs sealed for it. The retired
+/// keyset is kept for the grace period and purged once re-encryption has
+/// completed. Rotating a retired keyset whose re-encryption has not finished
+/// resumes the job for its existing successor.
+#[utoipa::path(post, path = "/api/v1/pke/keysets/{name_or_id}/rotate", tag = "PKE",
+    params(("name_or_id" = String, Path, description = "Keyset name or UUID")),
+    request_body = RotateKeysetRequest,
+    responses(
+        (status = 202, description = "Rotation accepted; re-encryption queued"),
+        (status = 400, description = "Invalid grace period"),
+        (status = 403, description = "Invalid passphrase"),
+        (status = 404, description = "Keyset not found"),
+        (status = 409, description = "Keyset already rotated or successor name taken"),
+    ))]
+pub async fn rotate_keyset(
+    auth: Auth,
+    State(state): State<AppState>,
+    Path(name_or_id): Path<String>,
+    Json(req): Json<RotateKeysetRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let grace_period_hours = rotation_grace_period_hours(req.grace_period_hours)?;
+
+    // Try to parse as UUID first, otherwise treat as name
+    let keyset = if let Ok(uuid) = Uuid::parse_str(&name_or_id) {
+        state.db.pke_keysets.get_by_id(uuid).await
+    } else {
+        state.db.pke_keysets.get_by_name(&name_or_id).await
+    }
+    .map_err(ApiError::from)?;
+
+    let keyset = keyset.ok_or_else(|| ApiError::NotFound("PKE keyset not found.".to_string()))?;
+    let private_key = unlock_keyset_private_key(&keyset, &req.passphrase)?;
+    if keyset.signing_public_key.is_none() {
+        state
+            .db
+            .pke_keysets
+            .set_signing_public_key(keyset.id, &keyset_signing_public_key(&private_key))
+            .await
+            .map_err(ApiError::from)?;
+    }
+    let attachment_encryption = state
+        .db
+        .file_storage
+        .as_ref()
+        .and_then(|files| files.encryption());
+
+    let retirement = state
+        .db
+        .pke_keysets
+        .get_retirement(keyset.id)
+        .await
+        .map_err(ApiError::from)?;
+    let (successor, retire_after) = match retirement {
+        Some(retirement) => {
+            // Resume an unfinished re-encryption, e.g. after a restart lost
+            // the in-memory key of the previous attempt.
+            let already_rotated =
+                || ApiError::Conflict("PKE keyset has already been rotated.".to_string());
+            let successor_id = retirement
+                .rotated_to_id
+                .filter(|_| retirement.reencryption_pending())
+                .ok_or_else(already_rotated)?;
+            let successor = state
+                .db
+                .pke_keysets
+                .get_by_id(successor_id)
+                .await
+                .map_err(ApiError::from)?
+                .ok_or_else(already_rotated)?;
+            if let Some(encryption) =
+                attachment_encryption.filter(|e| e.keyset_id() == successor.id && e.can_decrypt())
+            {
+                encryption.add_retired_key(private_key.clone());
+            }
+            (successor, retirement.retire_after)
+        }
+        None => {
+            let new_passphrase = req.new_passphrase.as_deref().unwrap_or(&req.passphrase);
+            let keypair = Keypair::generate();
+            let encrypted_private_key =
+                key_storage::encrypt_private_key(keypair.private.as_bytes(), new_passphrase)
+                    .map_err(|e| {
+                        let diagnostic = e.to_string();
+                        warn!(
+                            error_len = diagnostic.chars().count(),
+                            "PKE keyset rotation failed"
+                        );
+                        ApiError::OperationFailed {
+                            operation: "PKE keyset rotation",
+                            detail: PKE_KEYSET_CREATION_FAILURE_DETAIL.to_string(),
+                        }
+                    })?;
+            let now = chrono::Utc::now();
+            let retire_after = now + chrono::Duration::hours(grace_period_hours);
+            let successor = state
+                .db
+                .pke_keysets
+                .rotate(
+                    keyset.id,
+                    CreateKeysetRequest {
+                        name: req
+                            .new_name
+                            .clone()
+                            .unwrap_or_else(|| rotated_keyset_name(&keyset.name, now)),
+                        public_key: keypair.public.as_bytes().to_vec(),
+                        encrypted_private_key,
+                        address: keypair.public.to_address().to_string(),
+                        label: keyset.label.clone(),
+                    },
+                    retire_after,
+                )
+                .await
+                .map_err(|e| match &e {
+                    matric_core::Error::InvalidInput(_) => ApiError::Conflict(
+                        "PKE keyset already rotated or successor name taken.".to_string(),
+                    ),
+                    _ => ApiError::from(e),
+                })?;
+            let successor_signing_key = keyset_signing_public_key(&keypair.private);
+            state
+                .db
+                .pke_keysets
+                .set_signing_public_key(successor.id, &successor_signing_key)
+                .await
+                .map_err(ApiError::from)?;
+            let successor = matric_db::PkeKeyset {
+                signing_public_key: Some(successor_signing_key),
+                ..successor
+            };
+
+            // New attachment blobs switch to the successor straight away; the
+            // old key stays loaded for blobs the job has not reached yet.
+            if let Some(encryption) = attachment_encryption.filter(|e| e.keyset_id() == keyset.id) {
+                let passphrase = encryption.can_decrypt().then_some(new_passphrase);
+                if let Err(e) = encryption.rotate_to(&successor, passphrase) {
+                    warn!(
+                        error_len = e.to_string().chars().count(),
+                        "Attachment encryption could not switch to rotated keyset"
+                    );
+                }
+            }
+            (successor, retire_after)
+        }
+    };
+
+    state.pke_rotation_keys.hold(keyset.id, private_key);
+    let job_id = match state
+        .db
+        .jobs
+        .queue(
+            None,
+            JobType::PkeKeyRotation,
+            JobType::PkeKeyRotation.default_priority(),
+            Some(serde_json::json!({
+                "keyset_id": keyset.id,
+                "successor_id": successor.id,
+            })),
+            JobType::PkeKeyRotation.default_cost_tier(),
+        )
+        .await
+    {
+        Ok(job_id) => job_id,
+        Err(e) => {
+            state.pke_rotation_keys.release(keyset.id);
+            return Err(ApiError::from(e));
+        }
+    };
+    state.event_bus.emit(ServerEvent::JobQueued {
+        job_id,
+        job_type: format!("{:?}", JobType::PkeKeyRotation),
+        note_id: None,
+    });
+
+    emit_pke_keyset_audit_event(pke_keyset_audit_event(
+        &auth,
+        "keyset_rotate",
+        AuditOutcome::Success,
+        keyset.id,
+        &keyset.name,
+        &keyset.address,
+        None,
+    ))
+    .await;
+
+    let is_active = state
+        .db
+        .pke_keysets
+        .get_active()
+        .await
+        .map_err(ApiError::from)?
+        .is_some_and(|active| active.id == successor.id);
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(RotateKeysetResponse {
+            keyset: KeysetResponse {
+                id: successor.id,
+                name: successor.name,
+                address: successor.address,
+                label: successor.label,
+                is_active,
+                created_at: successor.created_at,
+                retire_after: None,
+                signing_public_key: successor.signing_public_key,
+            },
+            retired_keyset_id: keyset.id,
+            retire_after,
+            job_id,
+        }),
+    ))
+}
+
+/// Split a keyset's private key into Shamir secret shares.
+///
+/// POST /api/v1/pke/keysets/:name_or_id/split
+///
+/// Any `threshold` of the returned shares reconstruct the private key via
+/// the combine endpoint, so a team can recover a keyset whose passphrase is
+/// lost. Shares are not stored server-side.
+#[utoipa::path(post, path = "/api/v1/pke/keysets/{name_or_id}/split", tag = "PKE",
+    params(("name_or_id" = String, Path, description = "Keyset name or UUID")),
+    request_body = SplitKeysetRequest,
+    responses(
+        (status = 200, description = "Shares created"),
+        (status = 400, description = "Invalid threshold or share count"),
+        (status = 403, description = "Invalid passphrase"),
+        (status = 404, description = "Keyset not found"),
+    ))]
+pub async fn split_keyset(
+    auth: Auth,
+    State(state): State<AppState>,
+    Path(name_or_id): Path<String>,
+    Json(req): Json<SplitKeysetRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Try to parse as UUID first, otherwise treat as name
+    let keyset = if let Ok(uuid) = Uuid::parse_str(&name_or_id) {
+        state.db.pke_keysets.get_by_id(uuid).await
+    } else {
+        state.db.pke_keysets.get_by_name(&name_or_id).await
+    }
+    .map_err(ApiError::from)?;
+
+    let keyset = keyset.ok_or_else(|| ApiError::NotFound("PKE keyset not found.".to_string()))?;
+    let private_key = unlock_keyset_private_key(&keyset, &req.passphrase)?;
+
+    let shares = split_secret(private_key.as_bytes(), req.threshold, req.shares).map_err(|_| {
+        ApiError::BadRequest(format!(
+            "threshold must be at least {MIN_SHARE_THRESHOLD} and no more than shares."
+        ))
+    })?;
+
+    emit_pke_keyset_audit_event(pke_keyset_audit_event(
+        &auth,
+        "keyset_split",
+        AuditOutcome::Success,
+        keyset.id,
+        &keyset.name,
+        &keyset.address,
+        None,
+    ))
+    .await;
+
+    Ok(Json(SplitKeysetResponse {
+        keyset_id: keyset.id,
+        address: keyset.address,
+        threshold: req.threshold,
+        shares: shares
+            .iter()
+            .map(|share| share.to_encoded().to_string())
+            .collect(),
+    }))
+}
+
+/// Recover a keyset from Shamir secret shares under a new passphrase.
+///
+/// POST /api/v1/pke/keysets/:name_or_id/combine
+///
+/// Reconstructs the private key from shares created by the split endpoint,
+/// checks it matches the keyset's public key, and re-encrypts it with
+/// `new_passphrase`. The key pair and address are unchanged.
+#[utoipa::path(post, path = "/api/v1/pke/keysets/{name_or_id}/combine", tag = "PKE",
+    params(("name_or_id" = String, Path, description = "Keyset name or UUID")),
+    request_body = CombineKeysetSharesRequest,
+    responses(
+        (status = 200, description = "Keyset recovered"),
+        (status = 400, description = "Malformed, mixed, or too few shares"),
+        (status = 403, description = "Shares do not match the keyset"),
+        (status = 404, description = "Keyset not found"),
+    ))]
+pub async fn combine_keyset_shares(
+    auth: Auth,
+    State(state): State<AppState>,
+    Path(name_or_id): Path<String>,
+    Json(req): Json<CombineKeysetSharesRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    // Try to parse as UUID first, otherwise treat as name
+    let keyset = if let Ok(uuid) = Uuid::parse_str(&name_or_id) {
+        state.db.pke_keysets.get_by_id(uuid).await
+    } else {
+        state.db.pke_keysets.get_by_name(&name_or_id).await
+    }
+    .map_err(ApiError::from)?;
+
+    let keyset = keyset.ok_or_else(|| ApiError::NotFound("PKE keyset not found.".to_string()))?;
+    let private_key = recover_keyset_private_key(&keyset, &req.shares)?;
+
+    let encrypted_private_key =
+        key_storage::encrypt_private_key(private_key.as_bytes(), &req.new_passphrase).map_err(
+            |e| {
+                let diagnostic = e.to_string();
+                warn!(
+                    error_len = diagnostic.chars().count(),
+                    "PKE keyset recovery failed"
+                );
+                ApiError::OperationFailed {
+                    operation: "PKE keyset recovery",
+                    detail: PKE_KEYSET_CREATION_FAILURE_DETAIL.to_string(),
+                }
+            },
+        )?;
+    let updated = state
+        .db
+        .pke_keysets
+        .set_encrypted_private_key(keyset.id, &encrypted_private_key)
+        .await
+        .map_err(ApiError::from)?;
+    if !updated {
+        return Err(ApiError::NotFound("PKE keyset not found.".to_string()));
+    }
+    let signing_public_key = keyset_signing_public_key(&private_key);
+    state
+        .db
+        .pke_keysets
+        .set_signing_public_key(keyset.id, &signing_public_key)
+        .await
+        .map_err(ApiError::from)?;
+
+    emit_pke_keyset_audit_event(pke_keyset_audit_event(
+        &auth,
+        "keyset_recover",
+        AuditOutcome::Success,
+        keyset.id,
+        &keyset.name,
+        &keyset.address,
+        None,
+    ))
+    .await;
+
+    let is_active = state
+        .db
+        .pke_keysets
+        .get_active()
+        .await
+        .map_err(ApiError::from)?
+        .is_some_and(|active| active.id == keyset.id);
+    let retire_after = state
+        .db
+        .pke_keysets
+        .get_retirement(keyset.id)
+        .await
+        .map_err(ApiError::from)?
+        .map(|retirement| retirement.retire_after);
+
+    Ok(Json(KeysetResponse {
+        id: keyset.id,
+        name: keyset.name,
+        address: keyset.address,
+        label: keyset.label,
+        is_active,
+        created_at: keyset.created_at,
+        retire_after,
+        signing_public_key: Some(signing_public_key),
+    }))
+}
+
+/// Reconstruct a keyset's private key from encoded shares.
+fn recover_keyset_private_key(
+    keyset: &matric_db::PkeKeyset,
+    encoded_shares: &[String],
+) -> Result<PrivateKey, ApiError> {
+    let shares = encoded_shares
+        .iter()
+        .map(|encoded| SecretShare::from_encoded(encoded))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ApiError::BadRequest("Invalid secret share.".to_string()))?;
+    let secret = combine_shares(&shares).map_err(|e| ApiError::BadRequest(format!("{e}.")))?;
+
+    let mismatch =
+        || ApiError::Forbidden("Shares do not reconstruct this keyset's private key.".to_string());
+    let bytes: [u8; 32] = secret.as_slice().try_into().map_err(|_| mismatch())?;
+    let private_key = PrivateKey::from_bytes(bytes);
+    if private_key.public_key().as_bytes().as_slice() != keyset.public_key.as_slice() {
+        return Err(mismatch());
+    }
+    Ok(private_key)
+}
+
 /// Import a PKE keyset.
 ///
 /// POST /api/v1/pke/keysets/import
@@ -838,6 +1575,8 @@ pub async fn import_keyset(
             label: keyset.label,
             is_active: false,
             created_at: keyset.created_at,
+            retire_after: None,
+            signing_public_key: keyset.signing_public_key,
         }),
     ))
 }
@@ -1012,19 +1751,24 @@ mod tests {
             plaintext: "secret-plaintext-base64".to_string(),
             recipients: vec!["recipient-public-key-secret".to_string()],
             original_filename: Some("patient-secret.pdf".to_string()),
+            format: PkeCiphertextFormat::Mmpke01,
+            armor: false,
         };
         let encrypt_response = PkeEncryptResponse {
             ciphertext: "secret-ciphertext-base64".to_string(),
             recipients: vec!["mm:recipient-address".to_string()],
+            format: PkeCiphertextFormat::Mmpke01,
         };
         let decrypt = PkeDecryptRequest {
             ciphertext: "secret-ciphertext-base64".to_string(),
             encrypted_private_key: "encrypted-private-key-secret".to_string(),
             passphrase: "secret-passphrase".to_string(),
+            format: Some(PkeCiphertextFormat::Age),
         };
         let decrypt_response = PkeDecryptResponse {
             plaintext: "secret-plaintext-base64".to_string(),
             original_filename: Some("patient-secret.pdf".to_string()),
+            format: PkeCiphertextFormat::Mmpke01,
         };
         let recipients = PkeRecipientsRequest {
             ciphertext: "secret-ciphertext-base64".to_string(),
@@ -1052,6 +1796,8 @@ mod tests {
             label: Some("private label".to_string()),
             is_active: true,
             created_at: chrono::Utc::now(),
+            retire_after: None,
+            signing_public_key: Some("signing-key-secret".to_string()),
         };
         let active_keyset_response = ActiveKeysetResponse {
             active: true,
@@ -1098,6 +1844,284 @@ mod tests {
         assert!(!rendered.contains("mm:keyset-address-secret"));
     }
 
+    #[test]
+    fn rotation_grace_period_defaults_and_bounds() {
+        assert_eq!(
+            rotation_grace_period_hours(None).unwrap(),
+            DEFAULT_KEYSET_ROTATION_GRACE_HOURS
+        );
+        assert_eq!(rotation_grace_period_hours(Some(0)).unwrap(), 0);
+        assert!(rotation_grace_period_hours(Some(-1)).is_err());
+        assert!(rotation_grace_period_hours(Some(MAX_KEYSET_ROTATION_GRACE_HOURS + 1)).is_err());
+    }
+
+    #[test]
+    fn rotated_keyset_name_appends_timestamp() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-10-16T12:30:05Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            rotated_keyset_name("primary", now),
+            "primary-20261016123005"
+        );
+    }
+
+    #[test]
+    fn unlock_keyset_private_key_checks_passphrase_and_public_key() {
+        let keypair = Keypair::generate();
+        let now = chrono::Utc::now();
+        let mut keyset = matric_db::PkeKeyset {
+            id: Uuid::new_v4(),
+            name: "primary".to_string(),
+            public_key: keypair.public.as_bytes().to_vec(),
+            encrypted_private_key: key_storage::encrypt_private_key(
+                keypair.private.as_bytes(),
+                "rotation passphrase",
+            )
+            .unwrap(),
+            address: keypair.public.to_address().to_string(),
+            label: None,
+            signing_public_key: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let private_key = unlock_keyset_private_key(&keyset, "rotation passphrase").unwrap();
+        assert_eq!(private_key.as_bytes(), keypair.private.as_bytes());
+        assert!(matches!(
+            unlock_keyset_private_key(&keyset, "wrong passphrase"),
+            Err(ApiError::Forbidden(_))
+        ));
+
+        keyset.public_key = Keypair::generate().public.as_bytes().to_vec();
+        assert!(matches!(
+            unlock_keyset_private_key(&keyset, "rotation passphrase"),
+            Err(ApiError::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn pke_age_format_roundtrips_through_encrypt_and_decrypt() {
+        let keypair = Keypair::generate();
+        let encrypted_private_key = BASE64.encode(
+            key_storage::encrypt_private_key(keypair.private.as_bytes(), "safe-passphrase-123")
+                .unwrap(),
+        );
+        let recipient = matric_crypto::pke::to_age_recipient(&keypair.public);
+
+        let encrypted = pke_encrypt(Json(PkeEncryptRequest {
+            plaintext: BASE64.encode(b"age interop"),
+            recipients: vec![recipient],
+            original_filename: None,
+            format: PkeCiphertextFormat::Age,
+            armor: true,
+        }))
+        .await
+        .unwrap()
+        .into_response();
+        let body = axum::body::to_bytes(encrypted.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let encrypted: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(encrypted["format"], "age");
+        assert_eq!(
+            encrypted["recipients"][0],
+            keypair.public.to_address().to_string()
+        );
+
+        // Armored age text is accepted as-is, without base64 wrapping.
+        let armored = String::from_utf8(
+            BASE64
+                .decode(encrypted["ciphertext"].as_str().unwrap())
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(armored.starts_with(AGE_ARMOR_BEGIN));
+
+        for ciphertext in [
+            encrypted["ciphertext"].as_str().unwrap().to_string(),
+            armored,
+        ] {
+            let decrypted = pke_decrypt(Json(PkeDecryptRequest {
+                ciphertext,
+                encrypted_private_key: encrypted_private_key.clone(),
+                passphrase: "safe-passphrase-123".to_string(),
+                format: None,
+            }))
+            .await
+            .unwrap()
+            .into_response();
+            let body = axum::body::to_bytes(decrypted.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let decrypted: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(decrypted["format"], "age");
+            assert_eq!(decrypted["plaintext"], BASE64.encode(b"age interop"));
+            assert!(decrypted["original_filename"].is_null());
+        }
+    }
+
+    #[tokio::test]
+    async fn pke_format_mismatches_are_rejected() {
+        let keypair = Keypair::generate();
+        let recipient = BASE64.encode(keypair.public.as_bytes());
+
+        let err = match pke_encrypt(Json(PkeEncryptRequest {
+            plaintext: BASE64.encode(b"data"),
+            recipients: vec![recipient.clone()],
+            original_filename: None,
+            format: PkeCiphertextFormat::Mmpke01,
+            armor: true,
+        }))
+        .await
+        {
+            Ok(_) => panic!("armor without age format should be rejected"),
+            Err(err) => err,
+        };
+        let (status, problem) = read_problem_response(err).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            problem["detail"],
+            "armor is only supported with format \"age\"."
+        );
+
+        let mmpke = encrypt_pke(b"data", std::slice::from_ref(&keypair.public), None).unwrap();
+        let err = match pke_decrypt(Json(PkeDecryptRequest {
+            ciphertext: BASE64.encode(&mmpke),
+            encrypted_private_key: "unused".to_string(),
+            passphrase: "unused".to_string(),
+            format: Some(PkeCiphertextFormat::Age),
+        }))
+        .await
+        {
+            Ok(_) => panic!("MMPKE01 payload should not decrypt as age"),
+            Err(err) => err,
+        };
+        let (status, problem) = read_problem_response(err).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            problem["detail"],
+            "Ciphertext does not match the requested format."
+        );
+    }
+
+    #[test]
+    fn pke_ciphertext_format_defaults_to_mmpke01() {
+        let request: PkeEncryptRequest =
+            serde_json::from_value(serde_json::json!({"plaintext": "", "recipients": []})).unwrap();
+        assert_eq!(request.format, PkeCiphertextFormat::Mmpke01);
+        assert!(!request.armor);
+
+        let request: PkeEncryptRequest = serde_json::from_value(
+            serde_json::json!({"plaintext": "", "recipients": [], "format": "age"}),
+        )
+        .unwrap();
+        assert_eq!(request.format, PkeCiphertextFormat::Age);
+    }
+
+    #[test]
+    fn keyset_signing_public_key_is_bound_to_private_key() {
+        let keypair = Keypair::generate();
+        let signing_public_key = keyset_signing_public_key(&keypair.private);
+
+        assert_eq!(
+            signing_public_key,
+            keyset_signing_public_key(&keypair.private.clone())
+        );
+        assert_ne!(
+            signing_public_key,
+            keyset_signing_public_key(&Keypair::generate().private)
+        );
+        assert!(matric_crypto::VerifyingKey::from_base64url(&signing_public_key).is_ok());
+    }
+
+    fn keyset_for(keypair: &Keypair) -> matric_db::PkeKeyset {
+        let now = chrono::Utc::now();
+        matric_db::PkeKeyset {
+            id: Uuid::parse_str("018fd1a0-0000-7000-8000-000000000211").unwrap(),
+            name: "team-archive".to_string(),
+            public_key: keypair.public.as_bytes().to_vec(),
+            encrypted_private_key: Vec::new(),
+            address: keypair.public.to_address().to_string(),
+            label: None,
+            signing_public_key: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn recover_keyset_private_key_from_threshold_shares() {
+        let keypair = Keypair::generate();
+        let keyset = keyset_for(&keypair);
+        let shares: Vec<String> = split_secret(keypair.private.as_bytes(), 3, 5)
+            .unwrap()
+            .iter()
+            .map(|share| share.to_encoded().to_string())
+            .collect();
+
+        let recovered = recover_keyset_private_key(&keyset, &shares[2..]).unwrap();
+        assert_eq!(recovered.as_bytes(), keypair.private.as_bytes());
+
+        let err = recover_keyset_private_key(&keyset, &shares[..2]).unwrap_err();
+        assert!(matches!(err, ApiError::BadRequest(_)));
+        let err =
+            recover_keyset_private_key(&keyset, &["mmss1-not-a-share".to_string()]).unwrap_err();
+        assert!(matches!(err, ApiError::BadRequest(_)));
+
+        // Shares of another keyset's key are rejected even when well-formed.
+        let other = keyset_for(&Keypair::generate());
+        let err = recover_keyset_private_key(&other, &shares[..3]).unwrap_err();
+        assert!(matches!(err, ApiError::Forbidden(_)));
+    }
+
+    #[test]
+    fn split_and_combine_debug_redacts_shares_and_passphrases() {
+        let split = SplitKeysetRequest {
+            passphrase: "split-secret-passphrase".to_string(),
+            threshold: 2,
+            shares: 3,
+        };
+        let split_response = SplitKeysetResponse {
+            keyset_id: Uuid::parse_str("018fd1a0-0000-7000-8000-000000000212").unwrap(),
+            address: "mm:split-address-secret".to_string(),
+            threshold: 2,
+            shares: vec!["mmss1-share-secret".to_string()],
+        };
+        let combine = CombineKeysetSharesRequest {
+            shares: vec!["mmss1-share-secret".to_string()],
+            new_passphrase: "combine-secret-passphrase".to_string(),
+        };
+        let rendered = format!("{split:?} {split_response:?} {combine:?}");
+
+        assert!(rendered.contains("threshold: 2"));
+        assert!(rendered.contains("share_count: 1"));
+        for secret in [
+            "split-secret-passphrase",
+            "split-address-secret",
+            "mmss1-share-secret",
+            "combine-secret-passphrase",
+        ] {
+            assert!(!rendered.contains(secret), "{secret} leaked");
+        }
+    }
+
+    #[test]
+    fn rotate_keyset_request_debug_redacts_passphrases() {
+        let request = RotateKeysetRequest {
+            passphrase: "old-secret-passphrase".to_string(),
+            new_passphrase: Some("new-secret-passphrase".to_string()),
+            new_name: Some("tenant-secret-keyset".to_string()),
+            grace_period_hours: Some(24),
+        };
+        let rendered = format!("{request:?}");
+        assert!(rendered.contains("passphrase_present: true"));
+        assert!(rendered.contains("grace_period_hours: Some(24)"));
+        assert!(!rendered.contains("old-secret-passphrase"));
+        assert!(!rendered.contains("new-secret-passphrase"));
+        assert!(!rendered.contains("tenant-secret-keyset"));
+    }
+
     #[test]
     fn pke_keyset_audit_event_uses_metadata_only() {
         let auth = Auth {
@@ -0,0 +1,261 @@
+//! Prompt template HTTP handlers.
+//!
+//! Background jobs render their prompts from these templates. Requests that
+//! select a non-default archive with `X-Fortemi-Memory` read and write that
+//! archive's versions; all other requests use the global versions.
+//! - `GET /api/v1/prompts` — effective template for every prompt key
+//! - `GET /api/v1/prompts/{key}` — effective template for one key
+//! - `PUT /api/v1/prompts/{key}` — save a new version
+//! - `DELETE /api/v1/prompts/{key}` — delete all versions, reverting to the
+//!   global version (for an archive) or the built-in
+//! - `GET /api/v1/prompts/{key}/versions` — stored versions, newest first
+
+use std::fmt;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::Serialize;
+
+use super::inference_config::archive_override_schema;
+use crate::middleware::archive_routing::ArchiveContext;
+use crate::{ApiError, AppState};
+use matric_core::{PromptKey, PromptTemplate, PromptVariable, SavePromptTemplateRequest};
+use matric_db::PgPromptTemplateRepository;
+
+/// The template a job would use for a prompt key.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct EffectivePrompt {
+    pub key: String,
+    /// `archive`, `global`, or `builtin`
+    pub source: String,
+    /// Stored version in use; unset for the built-in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<i32>,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub variables: Vec<PromptVariable>,
+    pub builtin: String,
+}
+
+impl fmt::Debug for EffectivePrompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EffectivePrompt")
+            .field("key", &self.key)
+            .field("source", &self.source)
+            .field("version", &self.version)
+            .field("content_len", &self.content.len())
+            .finish()
+    }
+}
+
+fn parse_prompt_key(key: &str) -> Result<PromptKey, ApiError> {
+    PromptKey::parse(key).ok_or_else(|| ApiError::NotFound("Unknown prompt key".to_string()))
+}
+
+/// Newest stored version for `key` in the request's scope: the archive's
+/// own version first, then the global one.
+async fn stored_prompt(
+    repo: &PgPromptTemplateRepository,
+    key: PromptKey,
+    archive: Option<&str>,
+) -> Result<Option<PromptTemplate>, ApiError> {
+    Ok(match archive {
+        Some(schema) => repo.resolve(key, schema).await?,
+        None => repo.list_versions(key, None).await?.into_iter().next(),
+    })
+}
+
+fn effective_prompt(key: PromptKey, stored: Option<PromptTemplate>) -> EffectivePrompt {
+    let (source, version, content, description) = match stored {
+        Some(template) => (
+            if template.schema_name.is_some() {
+                "archive"
+            } else {
+                "global"
+            },
+            Some(template.version),
+            template.content,
+            template.description,
+        ),
+        None => ("builtin", None, key.builtin().to_string(), None),
+    };
+    EffectivePrompt {
+        key: key.as_str().to_string(),
+        source: source.to_string(),
+        version,
+        content,
+        description,
+        variables: key.variables().to_vec(),
+        builtin: key.builtin().to_string(),
+    }
+}
+
+/// List the effective template for every prompt key.
+#[utoipa::path(get, path = "/api/v1/prompts", tag = "Prompts",
+    responses((status = 200, description = "Success", body = Vec<EffectivePrompt>)))]
+pub async fn list_prompts(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+) -> Result<Json<Vec<EffectivePrompt>>, ApiError> {
+    let repo = &state.db.prompt_templates;
+    let archive = archive_override_schema(&archive_ctx);
+    let global = repo.list_latest(None).await?;
+    let scoped = match archive {
+        Some(schema) => repo.list_latest(Some(schema)).await?,
+        None => Vec::new(),
+    };
+
+    let prompts = PromptKey::ALL
+        .into_iter()
+        .map(|key| {
+            let stored = scoped
+                .iter()
+                .chain(global.iter())
+                .find(|template| template.key == key.as_str())
+                .cloned();
+            effective_prompt(key, stored)
+        })
+        .collect();
+    Ok(Json(prompts))
+}
+
+/// Get the effective template for a prompt key.
+#[utoipa::path(get, path = "/api/v1/prompts/{key}", tag = "Prompts",
+    params(("key" = String, Path, description = "Prompt key, e.g. title_generation")),
+    responses(
+        (status = 200, description = "Success", body = EffectivePrompt),
+        (status = 404, description = "Unknown prompt key")
+    ))]
+pub async fn get_prompt(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(key): Path<String>,
+) -> Result<Json<EffectivePrompt>, ApiError> {
+    let key = parse_prompt_key(&key)?;
+    let archive = archive_override_schema(&archive_ctx);
+    let stored = stored_prompt(&state.db.prompt_templates, key, archive).await?;
+    Ok(Json(effective_prompt(key, stored)))
+}
+
+/// Save a new version of a prompt template.
+///
+/// The template must reference every required variable of the key and no
+/// undeclared ones.
+#[utoipa::path(put, path = "/api/v1/prompts/{key}", tag = "Prompts",
+    params(("key" = String, Path, description = "Prompt key, e.g. title_generation")),
+    request_body = SavePromptTemplateRequest,
+    responses(
+        (status = 201, description = "Version saved", body = PromptTemplate),
+        (status = 400, description = "Invalid template"),
+        (status = 404, description = "Unknown prompt key")
+    ))]
+pub async fn save_prompt(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(key): Path<String>,
+    Json(req): Json<SavePromptTemplateRequest>,
+) -> Result<(StatusCode, Json<PromptTemplate>), ApiError> {
+    let key = parse_prompt_key(&key)?;
+    req.validate(key)?;
+    let template = state
+        .db
+        .prompt_templates
+        .create_version(
+            key,
+            archive_override_schema(&archive_ctx),
+            &req.content,
+            req.description.as_deref(),
+        )
+        .await?;
+    Ok((StatusCode::CREATED, Json(template)))
+}
+
+/// Delete every stored version of a prompt key in the request's scope.
+#[utoipa::path(delete, path = "/api/v1/prompts/{key}", tag = "Prompts",
+    params(("key" = String, Path, description = "Prompt key, e.g. title_generation")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, description = "Unknown prompt key or no stored versions")
+    ))]
+pub async fn delete_prompt(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(key): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let key = parse_prompt_key(&key)?;
+    let deleted = state
+        .db
+        .prompt_templates
+        .delete(key, archive_override_schema(&archive_ctx))
+        .await?;
+    if deleted == 0 {
+        return Err(ApiError::NotFound(
+            "No stored prompt template versions".to_string(),
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List stored versions of a prompt key in the request's scope, newest first.
+#[utoipa::path(get, path = "/api/v1/prompts/{key}/versions", tag = "Prompts",
+    params(("key" = String, Path, description = "Prompt key, e.g. title_generation")),
+    responses(
+        (status = 200, description = "Success", body = Vec<PromptTemplate>),
+        (status = 404, description = "Unknown prompt key")
+    ))]
+pub async fn list_prompt_versions(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(key): Path<String>,
+) -> Result<Json<Vec<PromptTemplate>>, ApiError> {
+    let key = parse_prompt_key(&key)?;
+    let versions = state
+        .db
+        .prompt_templates
+        .list_versions(key, archive_override_schema(&archive_ctx))
+        .await?;
+    Ok(Json(versions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effective_prompt_reports_source() {
+        let builtin = effective_prompt(PromptKey::TitleGeneration, None);
+        assert_eq!(builtin.source, "builtin");
+        assert_eq!(builtin.content, PromptKey::TitleGeneration.builtin());
+        assert!(builtin.version.is_none());
+
+        let stored = PromptTemplate {
+            id: uuid::Uuid::nil(),
+            key: "title_generation".to_string(),
+            schema_name: Some("archive_x".to_string()),
+            version: 3,
+            content: "Title: {{content}}".to_string(),
+            description: None,
+            created_at_utc: chrono::Utc::now(),
+        };
+        let archive = effective_prompt(PromptKey::TitleGeneration, Some(stored));
+        assert_eq!(archive.source, "archive");
+        assert_eq!(archive.version, Some(3));
+        assert_eq!(archive.content, "Title: {{content}}");
+    }
+
+    #[test]
+    fn unknown_keys_are_not_found() {
+        assert!(matches!(
+            parse_prompt_key("revision"),
+            Err(ApiError::NotFound(_))
+        ));
+        assert_eq!(
+            parse_prompt_key("context_update").unwrap(),
+            PromptKey::ContextUpdate
+        );
+    }
+}
@@ -0,0 +1,396 @@
+//! Collection publishing HTTP handlers.
+//!
+//! Publishing a collection serves its public notes as a read-only site that
+//! needs no authentication:
+//! - `GET /api/v1/publications` — the archive's published collections
+//! - `GET /api/v1/collections/{id}/publication` — a collection's publication
+//! - `PUT /api/v1/collections/{id}/publication` — publish a collection or change its settings
+//! - `DELETE /api/v1/collections/{id}/publication` — stop publishing a collection
+//! - `GET /public/{slug}` — the site index
+//! - `GET /public/{slug}/notes/{note_id}` — a page
+//! - `GET /public/{slug}/search?q=...` — search the site's pages
+//!
+//! Site routes answer with HTML, or with JSON given `format=json`. They look
+//! the archive up from the slug rather than the request's archive header.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{telemetry_text_len, ApiError, AppState, ArchiveContext};
+use matric_core::publishing::{
+    render_markdown_html, render_page_html, render_search_html, render_site_html,
+    resolve_published_markdown, transclusion_targets, PublishedPageIndex,
+    DEFAULT_PUBLISHED_SEARCH_LIMIT, MAX_PUBLISHED_SEARCH_LIMIT, MAX_PUBLISHED_SEARCH_QUERY_CHARS,
+    MAX_TRANSCLUSION_DEPTH,
+};
+use matric_core::{
+    Publication, PublishCollectionRequest, PublishedPage, PublishedSearchResults, PublishedSite,
+};
+use matric_db::PgCollectionRepository;
+
+/// Published sites render no scripts and load only inline styles and images.
+const PUBLISHED_SITE_CSP: &str =
+    "default-src 'none'; style-src 'unsafe-inline'; img-src https: data:; frame-ancestors 'none'";
+
+/// Published sites are public; let shared caches hold them briefly.
+const PUBLISHED_SITE_CACHE_CONTROL: &str = "public, max-age=60";
+
+fn collection_not_published() -> ApiError {
+    ApiError::NotFound("Collection is not published".to_string())
+}
+
+fn site_not_found() -> ApiError {
+    ApiError::NotFound("Published site not found".to_string())
+}
+
+/// Response format of a published site route.
+#[derive(Debug, Clone, Copy, Default, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PublishedFormat {
+    #[default]
+    Html,
+    Json,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct PublishedSiteQuery {
+    /// `html` (default) or `json`.
+    #[serde(default)]
+    format: PublishedFormat,
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct PublishedSearchQuery {
+    /// Words to search for; quotes, `or` and `-` work as in web search.
+    #[serde(default)]
+    q: String,
+    /// Maximum number of results (default: 20, max: 100).
+    limit: Option<i64>,
+    /// `html` (default) or `json`.
+    #[serde(default)]
+    format: PublishedFormat,
+}
+
+impl fmt::Debug for PublishedSearchQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublishedSearchQuery")
+            .field("q_len", &telemetry_text_len(&self.q))
+            .field("limit", &self.limit)
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+/// Answer a site route in the requested format.
+fn published_response<T: Serialize>(
+    format: PublishedFormat,
+    value: T,
+    render_html: impl FnOnce(&T) -> String,
+) -> Response {
+    let mut response = match format {
+        PublishedFormat::Html => (
+            [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+            render_html(&value),
+        )
+            .into_response(),
+        PublishedFormat::Json => Json(value).into_response(),
+    };
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_SECURITY_POLICY,
+        HeaderValue::from_static(PUBLISHED_SITE_CSP),
+    );
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(PUBLISHED_SITE_CACHE_CONTROL),
+    );
+    response
+}
+
+/// The publication served at a slug and its archive schema.
+async fn load_site(state: &AppState, slug: &str) -> Result<(String, Publication), ApiError> {
+    state
+        .db
+        .publications
+        .get_site(slug)
+        .await?
+        .ok_or_else(site_not_found)
+}
+
+/// List the archive's published collections.
+///
+/// GET /api/v1/publications
+#[utoipa::path(get, path = "/api/v1/publications", tag = "Collections",
+    responses((status = 200, description = "Published collections, by slug", body = Vec<Publication>)))]
+pub async fn list_publications(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+) -> Result<Json<Vec<Publication>>, ApiError> {
+    Ok(Json(
+        state
+            .db
+            .publications
+            .list_for_archive(&archive_ctx.schema)
+            .await?,
+    ))
+}
+
+/// Get a collection's publication.
+///
+/// GET /api/v1/collections/{id}/publication
+#[utoipa::path(get, path = "/api/v1/collections/{id}/publication", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    responses(
+        (status = 200, description = "Success", body = Publication),
+        (status = 404, description = "Collection is not published")
+    ))]
+pub async fn get_collection_publication(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Publication>, ApiError> {
+    state
+        .db
+        .publications
+        .get_for_collection(&archive_ctx.schema, id)
+        .await?
+        .map(Json)
+        .ok_or_else(collection_not_published)
+}
+
+/// Publish a collection, or change how it is published.
+///
+/// The site is served at `/public/{slug}` and shows the collection's notes
+/// whose visibility is `public`. Slug and title default to ones derived from
+/// the collection name.
+///
+/// PUT /api/v1/collections/{id}/publication
+#[utoipa::path(put, path = "/api/v1/collections/{id}/publication", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    request_body = PublishCollectionRequest,
+    responses(
+        (status = 200, description = "Collection published", body = Publication),
+        (status = 400, description = "Invalid or already used slug, or overlong title or description"),
+        (status = 404, description = "Collection not found")
+    ))]
+pub async fn publish_collection(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<PublishCollectionRequest>,
+) -> Result<Json<Publication>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let collections = PgCollectionRepository::new(state.db.pool.clone());
+    let collection = ctx
+        .query(move |tx| Box::pin(async move { collections.get_tx(tx, id).await }))
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Collection not found".to_string()))?;
+    let settings = req.settings(&collection.name)?;
+    Ok(Json(
+        state
+            .db
+            .publications
+            .publish(&archive_ctx.schema, id, &settings)
+            .await?,
+    ))
+}
+
+/// Stop publishing a collection. Its site is gone at once; notes keep their
+/// visibility.
+///
+/// DELETE /api/v1/collections/{id}/publication
+#[utoipa::path(delete, path = "/api/v1/collections/{id}/publication", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    responses(
+        (status = 204, description = "Collection unpublished"),
+        (status = 404, description = "Collection is not published")
+    ))]
+pub async fn unpublish_collection(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    if state
+        .db
+        .publications
+        .unpublish(&archive_ctx.schema, id)
+        .await?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(collection_not_published())
+    }
+}
+
+/// A published site's index.
+///
+/// Lists the site's pages by title. Needs no authentication.
+///
+/// GET /public/{slug}
+#[utoipa::path(get, path = "/public/{slug}", tag = "Publishing",
+    params(("slug" = String, Path, description = "Site slug"), PublishedSiteQuery),
+    responses(
+        (status = 200, description = "Site index as HTML, or JSON with format=json", body = PublishedSite),
+        (status = 404, description = "Published site not found")
+    ))]
+pub async fn get_published_site(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<PublishedSiteQuery>,
+) -> Result<Response, ApiError> {
+    let (schema, site) = load_site(&state, &slug).await?;
+    let ctx = state.db.for_schema(&schema)?;
+    let publications = state.db.publications.clone();
+    let publication = site.clone();
+    let pages = ctx
+        .query(move |tx| Box::pin(async move { publications.pages_tx(tx, &publication).await }))
+        .await?;
+    let body = PublishedSite {
+        slug: site.slug.clone(),
+        title: site.title.clone(),
+        description: site.description.clone(),
+        pages,
+    };
+    Ok(published_response(query.format, body, |body| {
+        render_site_html(&site, &body.pages)
+    }))
+}
+
+/// A page of a published site.
+///
+/// Transclusions (`![[Title]]`) are expanded and wiki links (`[[Title]]`)
+/// point at other pages of the site; targets the site does not publish are
+/// shown as plain text. Needs no authentication.
+///
+/// GET /public/{slug}/notes/{note_id}
+#[utoipa::path(get, path = "/public/{slug}/notes/{note_id}", tag = "Publishing",
+    params(
+        ("slug" = String, Path, description = "Site slug"),
+        ("note_id" = Uuid, Path, description = "Note ID"),
+        PublishedSiteQuery
+    ),
+    responses(
+        (status = 200, description = "Page as HTML, or JSON with format=json", body = PublishedPage),
+        (status = 404, description = "Published site or page not found")
+    ))]
+pub async fn get_published_page(
+    State(state): State<AppState>,
+    Path((slug, note_id)): Path<(String, Uuid)>,
+    Query(query): Query<PublishedSiteQuery>,
+) -> Result<Response, ApiError> {
+    let (schema, site) = load_site(&state, &slug).await?;
+    let ctx = state.db.for_schema(&schema)?;
+    let publications = state.db.publications.clone();
+    let publication = site.clone();
+    let page = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                let pages = publications.pages_tx(tx, &publication).await?;
+                let Some(summary) = pages.iter().find(|page| page.id == note_id) else {
+                    return Ok(None);
+                };
+                let index = PublishedPageIndex::new(&pages);
+
+                // Fetch transcluded pages one level at a time
+                let mut contents = publications
+                    .contents_tx(tx, &publication, &[note_id])
+                    .await?;
+                let mut frontier = vec![note_id];
+                for _ in 0..MAX_TRANSCLUSION_DEPTH {
+                    let wanted: HashSet<Uuid> = frontier
+                        .iter()
+                        .filter_map(|id| contents.get(id))
+                        .flat_map(|content| transclusion_targets(content))
+                        .filter_map(|target| index.resolve(&target))
+                        .map(|page| page.id)
+                        .filter(|id| !contents.contains_key(id))
+                        .collect();
+                    if wanted.is_empty() {
+                        break;
+                    }
+                    let wanted: Vec<Uuid> = wanted.into_iter().collect();
+                    let fetched = publications.contents_tx(tx, &publication, &wanted).await?;
+                    frontier = fetched.keys().copied().collect();
+                    contents.extend(fetched);
+                }
+
+                let content = contents.get(&note_id).cloned().unwrap_or_default();
+                let markdown = resolve_published_markdown(
+                    &publication.slug,
+                    &content,
+                    note_id,
+                    &index,
+                    &contents,
+                );
+                Ok(Some(PublishedPage {
+                    id: note_id,
+                    title: summary.title.clone(),
+                    html: render_markdown_html(&markdown),
+                    markdown,
+                    updated_at_utc: summary.updated_at_utc,
+                }))
+            })
+        })
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Page not found".to_string()))?;
+    Ok(published_response(query.format, page, |page| {
+        render_page_html(&site, page)
+    }))
+}
+
+/// Search a published site.
+///
+/// Full-text search over the site's pages only. An empty query returns no
+/// results. Needs no authentication.
+///
+/// GET /public/{slug}/search
+#[utoipa::path(get, path = "/public/{slug}/search", tag = "Publishing",
+    params(("slug" = String, Path, description = "Site slug"), PublishedSearchQuery),
+    responses(
+        (status = 200, description = "Results as HTML, or JSON with format=json", body = PublishedSearchResults),
+        (status = 400, description = "Query too long"),
+        (status = 404, description = "Published site not found")
+    ))]
+pub async fn search_published_site(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<PublishedSearchQuery>,
+) -> Result<Response, ApiError> {
+    if query.q.chars().count() > MAX_PUBLISHED_SEARCH_QUERY_CHARS {
+        return Err(ApiError::BadRequest(format!(
+            "q must be at most {MAX_PUBLISHED_SEARCH_QUERY_CHARS} characters"
+        )));
+    }
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PUBLISHED_SEARCH_LIMIT)
+        .clamp(1, MAX_PUBLISHED_SEARCH_LIMIT);
+    let (schema, site) = load_site(&state, &slug).await?;
+
+    let text = query.q.trim().to_string();
+    let hits = if text.is_empty() {
+        Vec::new()
+    } else {
+        let ctx = state.db.for_schema(&schema)?;
+        let publications = state.db.publications.clone();
+        let publication = site.clone();
+        let text = text.clone();
+        ctx.query(move |tx| {
+            Box::pin(async move { publications.search_tx(tx, &publication, &text, limit).await })
+        })
+        .await?
+    };
+    let results = PublishedSearchResults { query: text, hits };
+    Ok(published_response(query.format, results, |results| {
+        render_search_html(&site, results)
+    }))
+}
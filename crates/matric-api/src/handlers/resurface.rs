@@ -0,0 +1,97 @@
+//! Resurfacing HTTP handler.
+//!
+//! - `GET /api/v1/notes/resurface` — a weighted random sample of older,
+//!   rarely opened notes, for "remember this?" prompts
+//!
+//! Being resurfaced does not count as an access; opening the note with
+//! `GET /api/v1/notes/{id}` does, so a note a user follows up on drops out
+//! of later samples once it has been opened often enough.
+
+use std::fmt;
+
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::resurface::{
+    DEFAULT_RESURFACE_LIMIT, DEFAULT_RESURFACE_MAX_ACCESS_COUNT, DEFAULT_RESURFACE_MIN_AGE_DAYS,
+    MAX_RESURFACE_LIMIT,
+};
+use matric_core::{ResurfaceQuery, ResurfacedNote};
+
+#[derive(Deserialize)]
+pub struct ResurfaceParams {
+    /// Maximum number of notes to return (default: 5, max: 50).
+    limit: Option<i64>,
+    /// Days since a note was created and last opened (default: 30).
+    min_age_days: Option<u32>,
+    /// Most times a note may have been opened (default: 3).
+    max_access_count: Option<i32>,
+    /// Only notes filed in this collection.
+    collection_id: Option<Uuid>,
+}
+
+impl fmt::Debug for ResurfaceParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResurfaceParams")
+            .field("limit", &self.limit)
+            .field("min_age_days", &self.min_age_days)
+            .field("max_access_count", &self.max_access_count)
+            .field("collection_id_set", &self.collection_id.is_some())
+            .finish()
+    }
+}
+
+/// Resurface forgotten notes.
+///
+/// A random sample of notes created and last opened at least `min_age_days`
+/// ago and opened at most `max_access_count` times. Sampling is weighted
+/// towards well-linked, tagged, longer and less-opened notes; each note
+/// carries the signals and the weight it was sampled with. Archived notes
+/// are never resurfaced. Every call returns a fresh sample.
+///
+/// GET /api/v1/notes/resurface
+#[utoipa::path(get, path = "/api/v1/notes/resurface", tag = "Notes",
+    params(
+        ("limit" = Option<i64>, Query, description = "Max notes (default 5, max 50)"),
+        ("min_age_days" = Option<u32>, Query, description = "Days since created and last opened (default 30)"),
+        ("max_access_count" = Option<i32>, Query, description = "Most times opened (default 3)"),
+        ("collection_id" = Option<Uuid>, Query, description = "Only notes in this collection")
+    ),
+    responses((status = 200, description = "Resurfaced notes", body = Vec<ResurfacedNote>)))]
+pub async fn resurface_notes(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Query(params): Query<ResurfaceParams>,
+) -> Result<Json<Vec<ResurfacedNote>>, ApiError> {
+    let query = ResurfaceQuery {
+        limit: params
+            .limit
+            .unwrap_or(DEFAULT_RESURFACE_LIMIT)
+            .clamp(1, MAX_RESURFACE_LIMIT),
+        min_age_days: params
+            .min_age_days
+            .unwrap_or(DEFAULT_RESURFACE_MIN_AGE_DAYS),
+        max_access_count: params
+            .max_access_count
+            .unwrap_or(DEFAULT_RESURFACE_MAX_ACCESS_COUNT)
+            .max(0),
+        collection_id: params.collection_id,
+    };
+    let security = caller.security_filter();
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let resurface = state.db.resurface.clone();
+    let notes = ctx
+        .query(move |tx| {
+            Box::pin(async move { resurface.sample_tx(tx, &query, security.as_ref()).await })
+        })
+        .await?;
+    Ok(Json(notes))
+}
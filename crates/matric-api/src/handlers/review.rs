@@ -0,0 +1,281 @@
+//! Spaced repetition review HTTP handlers.
+//!
+//! Provides the review queue and grading endpoints for SM-2 review cards:
+//! - `GET /api/v1/review/queue` — cards due today
+//! - `POST /api/v1/review/cards` — create a card for a note
+//! - `DELETE /api/v1/review/cards/{id}` — delete a card
+//! - `POST /api/v1/review/{card_id}` — record a grade and reschedule
+//!
+//! Requests acting for a user only see cards of notes the user can read,
+//! and only create, grade or delete cards of notes the user can write.
+
+use std::fmt;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Days, NaiveTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::{
+    validate_review_grade, AccessLevel, CreateReviewCardRequest, Error, RecordReviewRequest,
+    ReviewCard, ReviewQueue,
+};
+use matric_db::PgUserRepository;
+
+const DEFAULT_QUEUE_LIMIT: i64 = 50;
+const MAX_QUEUE_LIMIT: i64 = 500;
+
+#[derive(Deserialize)]
+pub struct ReviewQueueQuery {
+    /// Maximum number of cards to return (default: 50, max: 500).
+    limit: Option<i64>,
+}
+
+impl fmt::Debug for ReviewQueueQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReviewQueueQuery")
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+/// Start of the next UTC day; cards due before it count as "due today".
+pub(crate) fn review_due_cutoff(now: DateTime<Utc>) -> DateTime<Utc> {
+    now.date_naive()
+        .checked_add_days(Days::new(1))
+        .map(|day| day.and_time(NaiveTime::MIN).and_utc())
+        .unwrap_or(now)
+}
+
+fn review_card_not_found_error() -> matric_core::Error {
+    matric_core::Error::NotFound("Review card not found; card_id_present=true".to_string())
+}
+
+fn review_note_not_found_error() -> matric_core::Error {
+    matric_core::Error::NotFound("Note not found; note_id_present=true".to_string())
+}
+
+/// Fail unless the caller can write `note_id`. Notes a user cannot see fail
+/// with `not_found`, so their cards look missing.
+async fn require_note_write_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    users: &PgUserRepository,
+    caller: Caller,
+    note_id: Uuid,
+    not_found: fn() -> Error,
+) -> matric_core::Result<()> {
+    let Some(user_id) = caller.user_id else {
+        return Ok(());
+    };
+    match users.note_access_tx(tx, note_id, user_id).await? {
+        Some(level) if level.allows(AccessLevel::Write) => Ok(()),
+        Some(_) => Err(Error::Forbidden(
+            "Your access to this note does not allow changing its review cards".to_string(),
+        )),
+        None => Err(not_found()),
+    }
+}
+
+fn review_api_error(e: Error) -> ApiError {
+    match e {
+        Error::Forbidden(msg) => ApiError::Forbidden(msg),
+        e => ApiError::from(e),
+    }
+}
+
+/// List review cards due today.
+///
+/// GET /api/v1/review/queue
+#[utoipa::path(get, path = "/api/v1/review/queue", tag = "Review",
+    params(("limit" = Option<i64>, Query, description = "Maximum cards to return (default 50, max 500)")),
+    responses((status = 200, description = "Success", body = ReviewQueue)))]
+pub async fn list_review_queue(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Query(query): Query<ReviewQueueQuery>,
+) -> Result<Json<ReviewQueue>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_QUEUE_LIMIT)
+        .clamp(1, MAX_QUEUE_LIMIT);
+    let due_before = review_due_cutoff(Utc::now());
+    let security = caller.security_filter();
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let reviews = matric_db::PgReviewRepository::new(state.db.pool.clone());
+    let queue = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                let security = security.as_ref();
+                let cards = reviews.list_due_tx(tx, due_before, security, limit).await?;
+                let due_count = reviews.count_due_tx(tx, due_before, security).await?;
+                Ok(ReviewQueue { cards, due_count })
+            })
+        })
+        .await?;
+    Ok(Json(queue))
+}
+
+/// Create a review card for a note.
+///
+/// POST /api/v1/review/cards
+#[utoipa::path(post, path = "/api/v1/review/cards", tag = "Review",
+    request_body = CreateReviewCardRequest,
+    responses(
+        (status = 201, description = "Created"),
+        (status = 400, description = "Front or back is empty"),
+        (status = 403, description = "The caller cannot write the note"),
+        (status = 404, description = "Note not found")
+    ))]
+pub async fn create_review_card(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Json(req): Json<CreateReviewCardRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    if req.front.trim().is_empty() || req.back.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Review card front and back are required".to_string(),
+        ));
+    }
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let reviews = matric_db::PgReviewRepository::new(state.db.pool.clone());
+    let users = state.db.users.clone();
+    let id = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                require_note_write_tx(tx, &users, caller, req.note_id, review_note_not_found_error)
+                    .await?;
+                reviews.create_card_tx(tx, req).await
+            })
+        })
+        .await
+        .map_err(review_api_error)?;
+    Ok((StatusCode::CREATED, Json(json!({ "id": id }))))
+}
+
+/// Delete a review card.
+///
+/// DELETE /api/v1/review/cards/{id}
+#[utoipa::path(delete, path = "/api/v1/review/cards/{id}", tag = "Review",
+    params(("id" = Uuid, Path, description = "Review card ID")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 403, description = "The caller cannot write the card's note"),
+        (status = 404, description = "Review card not found")
+    ))]
+pub async fn delete_review_card(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let reviews = matric_db::PgReviewRepository::new(state.db.pool.clone());
+    let users = state.db.users.clone();
+    let deleted = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                let Some(card) = reviews.get_card_tx(tx, id).await? else {
+                    return Ok(false);
+                };
+                require_note_write_tx(
+                    tx,
+                    &users,
+                    caller,
+                    card.note_id,
+                    review_card_not_found_error,
+                )
+                .await?;
+                reviews.delete_card_tx(tx, id).await
+            })
+        })
+        .await
+        .map_err(review_api_error)?;
+    if !deleted {
+        return Err(review_card_not_found_error().into());
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Record a review grade and reschedule the card.
+///
+/// POST /api/v1/review/{card_id}
+#[utoipa::path(post, path = "/api/v1/review/{card_id}", tag = "Review",
+    params(("card_id" = Uuid, Path, description = "Review card ID")),
+    request_body = RecordReviewRequest,
+    responses(
+        (status = 200, description = "Updated card schedule", body = ReviewCard),
+        (status = 400, description = "Grade outside 0-5"),
+        (status = 403, description = "The caller cannot write the card's note"),
+        (status = 404, description = "Review card not found")
+    ))]
+pub async fn record_review(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(card_id): Path<Uuid>,
+    Json(req): Json<RecordReviewRequest>,
+) -> Result<Json<ReviewCard>, ApiError> {
+    validate_review_grade(req.grade)?;
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let reviews = matric_db::PgReviewRepository::new(state.db.pool.clone());
+    let users = state.db.users.clone();
+    let reviewed_at = Utc::now();
+    let card = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                let Some(card) = reviews.get_card_tx(tx, card_id).await? else {
+                    return Ok(None);
+                };
+                require_note_write_tx(
+                    tx,
+                    &users,
+                    caller,
+                    card.note_id,
+                    review_card_not_found_error,
+                )
+                .await?;
+                reviews
+                    .record_review_tx(tx, card_id, req.grade, reviewed_at)
+                    .await
+            })
+        })
+        .await
+        .map_err(review_api_error)?
+        .ok_or_else(review_card_not_found_error)?;
+    Ok(Json(card))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn due_cutoff_is_start_of_next_utc_day() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 14, 15, 9, 26).unwrap();
+        assert_eq!(
+            review_due_cutoff(now),
+            Utc.with_ymd_and_hms(2026, 3, 15, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn review_card_not_found_error_omits_raw_id() {
+        let matric_core::Error::NotFound(message) = review_card_not_found_error() else {
+            panic!("expected not-found error");
+        };
+        assert!(message.contains("card_id_present=true"));
+    }
+}
@@ -0,0 +1,369 @@
+//! User and sharing HTTP handlers.
+//!
+//! - `GET /api/v1/users/me` — the user the request acts for
+//! - `PATCH /api/v1/users/me` — set the user's display name
+//! - `GET/POST /api/v1/notes/{id}/shares` — list or grant access to a note
+//! - `DELETE /api/v1/notes/{id}/shares/{grantee_id}` — revoke a note grant
+//! - `GET/POST /api/v1/collections/{id}/shares` — list or grant access to a
+//!   collection and the notes filed in it
+//! - `DELETE /api/v1/collections/{id}/shares/{grantee_id}` — revoke it
+//! - `GET/POST /api/v1/archives/{name}/shares` — list or grant access to a
+//!   memory (also under `/api/v1/memories/{name}/shares`)
+//! - `DELETE /api/v1/archives/{name}/shares/{grantee_id}` — revoke it
+//!
+//! Only the owner may manage a resource's shares; the archive routing
+//! middleware enforces that (see `middleware::ownership`).
+
+use std::fmt;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::{ArchiveRepository, CreateShareGrantRequest, ShareGrant, ShareResource, User};
+
+/// Longest accepted display name, in characters.
+const MAX_DISPLAY_NAME_CHARS: usize = 200;
+
+fn no_user() -> ApiError {
+    ApiError::NotFound("This request does not act for a user".to_string())
+}
+
+fn grant_not_found() -> ApiError {
+    ApiError::NotFound("Share grant not found".to_string())
+}
+
+async fn require_grantee(state: &AppState, req: &CreateShareGrantRequest) -> Result<(), ApiError> {
+    match state.db.users.get(req.grantee_id).await? {
+        Some(_) => Ok(()),
+        None => Err(ApiError::BadRequest("Unknown grantee".to_string())),
+    }
+}
+
+async fn archive_id(state: &AppState, name: &str) -> Result<Uuid, ApiError> {
+    state
+        .db
+        .archives
+        .get_archive_by_name(name)
+        .await?
+        .map(|archive| archive.id)
+        .ok_or_else(|| ApiError::NotFound("Memory not found".to_string()))
+}
+
+/// Get the user the request acts for.
+#[utoipa::path(get, path = "/api/v1/users/me", tag = "Users",
+    responses(
+        (status = 200, description = "Success", body = User),
+        (status = 404, description = "The request does not act for a user")
+    ))]
+pub async fn get_current_user(
+    State(state): State<AppState>,
+    caller: Caller,
+) -> Result<Json<User>, ApiError> {
+    let user_id = caller.user_id.ok_or_else(no_user)?;
+    state
+        .db
+        .users
+        .get(user_id)
+        .await?
+        .map(Json)
+        .ok_or_else(no_user)
+}
+
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct UpdateCurrentUserRequest {
+    /// Name shown to users you share with; null clears it
+    display_name: Option<String>,
+}
+
+impl fmt::Debug for UpdateCurrentUserRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpdateCurrentUserRequest")
+            .field(
+                "display_name_len",
+                &self.display_name.as_ref().map(|name| name.chars().count()),
+            )
+            .finish()
+    }
+}
+
+/// Set the display name of the user the request acts for.
+#[utoipa::path(patch, path = "/api/v1/users/me", tag = "Users",
+    request_body = UpdateCurrentUserRequest,
+    responses(
+        (status = 200, description = "Success", body = User),
+        (status = 400, description = "Display name too long"),
+        (status = 404, description = "The request does not act for a user")
+    ))]
+pub async fn update_current_user(
+    State(state): State<AppState>,
+    caller: Caller,
+    Json(req): Json<UpdateCurrentUserRequest>,
+) -> Result<Json<User>, ApiError> {
+    let user_id = caller.user_id.ok_or_else(no_user)?;
+    let display_name = req
+        .display_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty());
+    if display_name.is_some_and(|name| name.chars().count() > MAX_DISPLAY_NAME_CHARS) {
+        return Err(ApiError::BadRequest(format!(
+            "display_name must be at most {MAX_DISPLAY_NAME_CHARS} characters"
+        )));
+    }
+    let user = state
+        .db
+        .users
+        .set_display_name(user_id, display_name)
+        .await?;
+    Ok(Json(user))
+}
+
+async fn list_shares(
+    state: &AppState,
+    archive_ctx: &ArchiveContext,
+    resource: ShareResource,
+    id: Uuid,
+) -> Result<Json<Vec<ShareGrant>>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let users = state.db.users.clone();
+    let grants = ctx
+        .query(move |tx| Box::pin(async move { users.list_grants_tx(tx, resource, id).await }))
+        .await?;
+    Ok(Json(grants))
+}
+
+async fn create_share(
+    state: &AppState,
+    archive_ctx: &ArchiveContext,
+    caller: Caller,
+    resource: ShareResource,
+    id: Uuid,
+    req: CreateShareGrantRequest,
+) -> Result<(StatusCode, Json<ShareGrant>), ApiError> {
+    req.validate(caller.user_id)?;
+    require_grantee(state, &req).await?;
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let users = state.db.users.clone();
+    let grant = ctx
+        .execute(move |tx| {
+            Box::pin(async move { users.share_tx(tx, resource, id, &req, caller.user_id).await })
+        })
+        .await?;
+    Ok((StatusCode::CREATED, Json(grant)))
+}
+
+async fn revoke_share(
+    state: &AppState,
+    archive_ctx: &ArchiveContext,
+    resource: ShareResource,
+    id: Uuid,
+    grantee_id: Uuid,
+) -> Result<StatusCode, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let users = state.db.users.clone();
+    let revoked = ctx
+        .execute(move |tx| {
+            Box::pin(async move { users.revoke_tx(tx, resource, id, grantee_id).await })
+        })
+        .await?;
+    if revoked {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(grant_not_found())
+    }
+}
+
+/// List the users a note is shared with.
+#[utoipa::path(get, path = "/api/v1/notes/{id}/shares", tag = "Notes",
+    params(("id" = Uuid, Path, description = "Note ID")),
+    responses(
+        (status = 200, description = "Success", body = Vec<ShareGrant>),
+        (status = 403, description = "Only the owner manages shares")
+    ))]
+pub async fn list_note_shares(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<ShareGrant>>, ApiError> {
+    list_shares(&state, &archive_ctx, ShareResource::Note, id).await
+}
+
+/// Share a note with a user.
+#[utoipa::path(post, path = "/api/v1/notes/{id}/shares", tag = "Notes",
+    params(("id" = Uuid, Path, description = "Note ID")),
+    request_body = CreateShareGrantRequest,
+    responses(
+        (status = 201, description = "Created", body = ShareGrant),
+        (status = 400, description = "Unknown grantee or expiry in the past"),
+        (status = 403, description = "Only the owner manages shares")
+    ))]
+pub async fn create_note_share(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CreateShareGrantRequest>,
+) -> Result<(StatusCode, Json<ShareGrant>), ApiError> {
+    create_share(&state, &archive_ctx, caller, ShareResource::Note, id, req).await
+}
+
+/// Stop sharing a note with a user.
+#[utoipa::path(delete, path = "/api/v1/notes/{id}/shares/{grantee_id}", tag = "Notes",
+    params(
+        ("id" = Uuid, Path, description = "Note ID"),
+        ("grantee_id" = Uuid, Path, description = "User the note is shared with")
+    ),
+    responses(
+        (status = 204, description = "Revoked"),
+        (status = 404, description = "Share grant not found")
+    ))]
+pub async fn delete_note_share(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path((id, grantee_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    revoke_share(&state, &archive_ctx, ShareResource::Note, id, grantee_id).await
+}
+
+/// List the users a collection is shared with.
+#[utoipa::path(get, path = "/api/v1/collections/{id}/shares", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    responses(
+        (status = 200, description = "Success", body = Vec<ShareGrant>),
+        (status = 403, description = "Only the owner manages shares")
+    ))]
+pub async fn list_collection_shares(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<ShareGrant>>, ApiError> {
+    list_shares(&state, &archive_ctx, ShareResource::Collection, id).await
+}
+
+/// Share a collection, and the notes filed in it, with a user.
+#[utoipa::path(post, path = "/api/v1/collections/{id}/shares", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    request_body = CreateShareGrantRequest,
+    responses(
+        (status = 201, description = "Created", body = ShareGrant),
+        (status = 400, description = "Unknown grantee or expiry in the past"),
+        (status = 403, description = "Only the owner manages shares")
+    ))]
+pub async fn create_collection_share(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CreateShareGrantRequest>,
+) -> Result<(StatusCode, Json<ShareGrant>), ApiError> {
+    create_share(
+        &state,
+        &archive_ctx,
+        caller,
+        ShareResource::Collection,
+        id,
+        req,
+    )
+    .await
+}
+
+/// Stop sharing a collection with a user.
+#[utoipa::path(delete, path = "/api/v1/collections/{id}/shares/{grantee_id}",
+    tag = "Collections",
+    params(
+        ("id" = Uuid, Path, description = "Collection ID"),
+        ("grantee_id" = Uuid, Path, description = "User the collection is shared with")
+    ),
+    responses(
+        (status = 204, description = "Revoked"),
+        (status = 404, description = "Share grant not found")
+    ))]
+pub async fn delete_collection_share(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path((id, grantee_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    revoke_share(
+        &state,
+        &archive_ctx,
+        ShareResource::Collection,
+        id,
+        grantee_id,
+    )
+    .await
+}
+
+/// List the users a memory is shared with.
+#[utoipa::path(get, path = "/api/v1/archives/{name}/shares", tag = "Archives",
+    params(("name" = String, Path, description = "Memory name")),
+    responses(
+        (status = 200, description = "Success", body = Vec<ShareGrant>),
+        (status = 403, description = "Only the owner manages shares")
+    ))]
+pub async fn list_archive_shares(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<Vec<ShareGrant>>, ApiError> {
+    let archive_id = archive_id(&state, &name).await?;
+    Ok(Json(state.db.users.list_archive_grants(archive_id).await?))
+}
+
+/// Share a memory with a user.
+#[utoipa::path(post, path = "/api/v1/archives/{name}/shares", tag = "Archives",
+    params(("name" = String, Path, description = "Memory name")),
+    request_body = CreateShareGrantRequest,
+    responses(
+        (status = 201, description = "Created", body = ShareGrant),
+        (status = 400, description = "Unknown grantee or expiry in the past"),
+        (status = 403, description = "Only the owner manages shares")
+    ))]
+pub async fn create_archive_share(
+    State(state): State<AppState>,
+    caller: Caller,
+    Path(name): Path<String>,
+    Json(req): Json<CreateShareGrantRequest>,
+) -> Result<(StatusCode, Json<ShareGrant>), ApiError> {
+    req.validate(caller.user_id)?;
+    require_grantee(&state, &req).await?;
+    let archive_id = archive_id(&state, &name).await?;
+    let grant = state
+        .db
+        .users
+        .share_archive(archive_id, &req, caller.user_id)
+        .await?;
+    Ok((StatusCode::CREATED, Json(grant)))
+}
+
+/// Stop sharing a memory with a user.
+#[utoipa::path(delete, path = "/api/v1/archives/{name}/shares/{grantee_id}", tag = "Archives",
+    params(
+        ("name" = String, Path, description = "Memory name"),
+        ("grantee_id" = Uuid, Path, description = "User the memory is shared with")
+    ),
+    responses(
+        (status = 204, description = "Revoked"),
+        (status = 404, description = "Share grant not found")
+    ))]
+pub async fn delete_archive_share(
+    State(state): State<AppState>,
+    Path((name, grantee_id)): Path<(String, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    let archive_id = archive_id(&state, &name).await?;
+    if state
+        .db
+        .users
+        .revoke_archive_grant(archive_id, grantee_id)
+        .await?
+    {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(grant_not_found())
+    }
+}
@@ -0,0 +1,181 @@
+//! "More like this" HTTP handler.
+//!
+//! - `GET /api/v1/notes/{id}/similar` — notes similar to a note, found by
+//!   searching with its stored embeddings and top terms
+//!
+//! Unlike `/related`, which merges nearest neighbours with the note's graph
+//! links, this runs the note through the hybrid search pipeline, so results
+//! are fused, deduplicated and filtered like search results.
+
+use std::fmt;
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{
+    embedding_set_not_found, note_not_found, search_engine_for_schema, telemetry_text_len,
+    ApiError, AppState, ArchiveContext,
+};
+use matric_db::PgNoteRepository;
+use matric_search::similar::{DEFAULT_SIMILAR_TERMS, MAX_SIMILAR_TERMS};
+use matric_search::{EnhancedSearchHit, HybridSearchConfig, SimilarOptions, SimilarStrategy};
+
+const DEFAULT_SIMILAR_LIMIT: i64 = 10;
+const MAX_SIMILAR_LIMIT: i64 = 50;
+
+#[derive(Deserialize)]
+pub struct SimilarNotesQuery {
+    /// `hybrid` (default), `semantic` or `terms`.
+    #[serde(default)]
+    strategy: SimilarStrategy,
+    /// Maximum similar notes to return (default: 10, max: 50).
+    limit: Option<i64>,
+    /// Top terms of the note searched for (default: 10, max: 25).
+    terms: Option<usize>,
+    /// Leave out notes already linked to or from the note (default: false).
+    #[serde(default)]
+    exclude_linked: bool,
+    /// Embedding set whose vectors are compared (default: the default set).
+    embedding_set: Option<String>,
+    /// MMR diversity, 0.0 (pure relevance) to 1.0 (maximum diversity).
+    diversity: Option<f32>,
+}
+
+impl fmt::Debug for SimilarNotesQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimilarNotesQuery")
+            .field("strategy", &self.strategy)
+            .field("limit", &self.limit)
+            .field("terms", &self.terms)
+            .field("exclude_linked", &self.exclude_linked)
+            .field(
+                "embedding_set_len",
+                &self.embedding_set.as_deref().map(telemetry_text_len),
+            )
+            .field("diversity", &self.diversity)
+            .finish()
+    }
+}
+
+#[derive(Serialize)]
+pub struct SimilarNotesResponse {
+    note_id: Uuid,
+    strategy: SimilarStrategy,
+    results: Vec<EnhancedSearchHit>,
+    total: usize,
+}
+
+impl fmt::Debug for SimilarNotesResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimilarNotesResponse")
+            .field("note_id_set", &true)
+            .field("strategy", &self.strategy)
+            .field("results_count", &self.results.len())
+            .field("total", &self.total)
+            .finish()
+    }
+}
+
+/// Notes similar to a note ("more like this").
+///
+/// Searches with the centroid of the note's chunk embeddings and an OR of
+/// its most frequent terms, fused and deduplicated like `/api/v1/search`
+/// results. `strategy=semantic` uses only the embeddings, `strategy=terms`
+/// only the terms. The note itself is never returned; with
+/// `exclude_linked=true` neither are notes already linked to or from it.
+/// A note with neither embeddings nor indexed terms has no similar notes.
+///
+/// GET /api/v1/notes/{id}/similar
+#[utoipa::path(get, path = "/api/v1/notes/{id}/similar", tag = "Search",
+    params(
+        ("id" = Uuid, Path, description = "Note ID"),
+        ("strategy" = Option<String>, Query, description = "hybrid (default), semantic or terms"),
+        ("limit" = Option<i64>, Query, description = "Max results (default 10, max 50)"),
+        ("terms" = Option<usize>, Query, description = "Top terms searched for (default 10, max 25)"),
+        ("exclude_linked" = Option<bool>, Query, description = "Leave out notes linked to or from the note (default false)"),
+        ("embedding_set" = Option<String>, Query, description = "Embedding set slug (default: the default set)"),
+        ("diversity" = Option<f32>, Query, description = "MMR diversity, 0.0 to 1.0")
+    ),
+    responses(
+        (status = 200, description = "Similar notes"),
+        (status = 404, description = "Note or embedding set not found")
+    ))]
+pub async fn get_similar_notes(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(note_id): Path<Uuid>,
+    Query(query): Query<SimilarNotesQuery>,
+) -> Result<Json<SimilarNotesResponse>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let notes = PgNoteRepository::new(state.db.pool.clone());
+    let exists = ctx
+        .query(move |tx| Box::pin(async move { notes.exists_tx(tx, note_id).await }))
+        .await?;
+    if !exists {
+        return Err(note_not_found());
+    }
+
+    let engine = search_engine_for_schema(&state, &archive_ctx.schema).await?;
+    let search_db = if archive_ctx.schema == "public" {
+        &state.db
+    } else {
+        engine.db()
+    };
+
+    let mut config = HybridSearchConfig::default();
+    config.security = caller.security_filter();
+    if let Some(diversity) = query.diversity {
+        config.diversity = Some(diversity.clamp(0.0, 1.0));
+    }
+    if let Some(ref set_slug) = query.embedding_set {
+        let set = search_db
+            .embedding_sets
+            .get_by_slug(set_slug)
+            .await?
+            .ok_or_else(embedding_set_not_found)?;
+        config = config.with_embedding_set(set.id);
+    }
+
+    let options = SimilarOptions {
+        strategy: query.strategy,
+        limit: query
+            .limit
+            .unwrap_or(DEFAULT_SIMILAR_LIMIT)
+            .clamp(1, MAX_SIMILAR_LIMIT),
+        terms: query
+            .terms
+            .unwrap_or(DEFAULT_SIMILAR_TERMS)
+            .clamp(1, MAX_SIMILAR_TERMS),
+        exclude_linked: query.exclude_linked,
+    };
+    let mut results = engine.more_like_this(note_id, &options, &config).await?;
+
+    // Attach stored note summaries. A lookup failure only drops them.
+    let note_ids: Vec<Uuid> = results.iter().map(|r| r.hit.note_id).collect();
+    match search_db.summaries.for_notes(&note_ids).await {
+        Ok(summaries) => {
+            for result in &mut results {
+                result.summary = summaries.get(&result.hit.note_id).cloned();
+            }
+        }
+        Err(e) => warn!(
+            error_len = telemetry_text_len(&e.to_string()),
+            operation = "load_similar_summaries",
+            "Failed to load note summaries for similar notes"
+        ),
+    }
+
+    Ok(Json(SimilarNotesResponse {
+        note_id,
+        strategy: options.strategy,
+        total: results.len(),
+        results,
+    }))
+}
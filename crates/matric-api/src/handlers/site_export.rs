@@ -0,0 +1,186 @@
+//! Static site export HTTP handler.
+//!
+//! - `POST /api/v1/collections/{id}/export-site` — a ZIP of the collection
+//!   as a static website, rendered HTML or Markdown with front-matter
+//!
+//! Page rendering lives in [`matric_core::site_export`]; this module gathers
+//! the notes and attachments and writes the archive.
+
+use std::io::{Cursor, Write};
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Extension, Json,
+};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::publishing::slugify;
+use matric_core::site_export::{
+    render_site_export, SiteExportAttachment, MAX_SITE_EXPORT_ATTACHMENT_BYTES,
+};
+use matric_core::SiteExportRequest;
+use matric_db::PgCollectionRepository;
+
+fn site_export_failed(context: &'static str, error: impl std::fmt::Display) -> ApiError {
+    let diagnostic = error.to_string();
+    ApiError::OperationFailed {
+        operation: "Site export",
+        detail: format!("{context}; error_len={}", diagnostic.len()),
+    }
+}
+
+/// Attachments of the exported notes and their bytes. Encrypted blobs and
+/// attachments that cannot be read, such as ones a virus scan holds back,
+/// are left out.
+async fn export_attachments(
+    state: &AppState,
+    schema: &str,
+    note_ids: &[Uuid],
+) -> Result<Vec<(SiteExportAttachment, Vec<u8>)>, ApiError> {
+    let Some(file_storage) = state.db.file_storage.as_ref() else {
+        return Ok(Vec::new());
+    };
+    let ctx = state.db.for_schema(schema)?;
+    let mut tx = ctx.begin_tx().await?;
+    let mut summaries = Vec::new();
+    for note_id in note_ids {
+        summaries.extend(
+            file_storage
+                .list_by_note_tx(&mut tx, *note_id)
+                .await?
+                .into_iter()
+                .filter(|attachment| !attachment.encrypted),
+        );
+    }
+    let total_bytes: i64 = summaries
+        .iter()
+        .map(|attachment| attachment.size_bytes)
+        .sum();
+    if total_bytes > MAX_SITE_EXPORT_ATTACHMENT_BYTES {
+        return Err(ApiError::BadRequest(format!(
+            "Attachments exceed {} MiB; export with include_attachments set to false",
+            MAX_SITE_EXPORT_ATTACHMENT_BYTES / (1024 * 1024)
+        )));
+    }
+
+    let mut attachments = Vec::with_capacity(summaries.len());
+    for summary in summaries {
+        match file_storage.download_file_tx(&mut tx, summary.id).await {
+            Ok((data, _content_type, _filename)) => attachments.push((
+                SiteExportAttachment {
+                    id: summary.id,
+                    note_id: summary.note_id,
+                    filename: summary.filename,
+                },
+                data,
+            )),
+            Err(error) => warn!(
+                attachment_id = %summary.id,
+                error_len = error.to_string().len(),
+                "Leaving unreadable attachment out of site export"
+            ),
+        }
+    }
+    Ok(attachments)
+}
+
+/// Export a collection as a static website.
+///
+/// Returns a ZIP with an index page, a page per note and the notes'
+/// attachments, using relative links only so it can be served from any
+/// static host. Wiki links between exported notes become links between
+/// pages and transclusions are expanded. Encrypted notes are left out.
+///
+/// POST /api/v1/collections/{id}/export-site
+#[utoipa::path(post, path = "/api/v1/collections/{id}/export-site", tag = "Collections",
+    params(("id" = Uuid, Path, description = "Collection ID")),
+    request_body = SiteExportRequest,
+    responses(
+        (status = 200, description = "ZIP of the site", content_type = "application/zip", body = Vec<u8>),
+        (status = 400, description = "Too many notes or attachment bytes"),
+        (status = 404, description = "Collection not found")
+    ))]
+pub async fn export_collection_site(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SiteExportRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let security = caller.security_filter();
+    let include_subcollections = req.include_subcollections.unwrap_or(true);
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let collections = PgCollectionRepository::new(state.db.pool.clone());
+    let (collection, notes) = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                let collection = collections
+                    .get_tx(tx, id)
+                    .await?
+                    .ok_or_else(|| matric_core::Error::NotFound("Collection not found".into()))?;
+                let notes = collections
+                    .site_export_notes_tx(tx, id, include_subcollections, security.as_ref())
+                    .await?;
+                Ok((collection, notes))
+            })
+        })
+        .await?;
+
+    let attachments = if req.include_attachments.unwrap_or(true) {
+        let note_ids: Vec<Uuid> = notes.iter().map(|note| note.id).collect();
+        export_attachments(&state, &archive_ctx.schema, &note_ids).await?
+    } else {
+        Vec::new()
+    };
+    let listed: Vec<SiteExportAttachment> = attachments
+        .iter()
+        .map(|(attachment, _)| attachment.clone())
+        .collect();
+    let pages = render_site_export(
+        &collection.name,
+        collection.description.as_deref(),
+        &notes,
+        &listed,
+        req.format,
+    );
+
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let deflated = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (path, contents) in &pages {
+        writer
+            .start_file(path.as_str(), deflated)
+            .and_then(|()| writer.write_all(contents.as_bytes()).map_err(Into::into))
+            .map_err(|error| site_export_failed("write page", error))?;
+    }
+    let stored =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (attachment, data) in &attachments {
+        writer
+            .start_file(attachment.path(), stored)
+            .and_then(|()| writer.write_all(data).map_err(Into::into))
+            .map_err(|error| site_export_failed("write attachment", error))?;
+    }
+    let bytes = writer
+        .finish()
+        .map_err(|error| site_export_failed("finish archive", error))?
+        .into_inner();
+
+    let name = match slugify(&collection.name) {
+        slug if slug.is_empty() => format!("collection-{id}"),
+        slug => slug,
+    };
+    let headers = [
+        (header::CONTENT_TYPE, "application/zip".to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{name}-site.zip\""),
+        ),
+    ];
+    Ok((StatusCode::OK, headers, bytes))
+}
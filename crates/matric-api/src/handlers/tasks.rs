@@ -0,0 +1,288 @@
+//! Task HTTP handlers.
+//!
+//! - `GET /api/v1/tasks` — tasks found in notes, soonest due first
+//! - `GET /api/v1/tasks/{id}` — one task
+//! - `POST /api/v1/tasks/{id}/complete` — mark a task done
+//! - `POST /api/v1/tasks/{id}/uncomplete` — reopen a task
+//!
+//! Completing or reopening a checkbox task ticks or clears its box in the
+//! note, in the same transaction as the status change. Requests acting for a
+//! user only see tasks of notes the user can read, and only change tasks of
+//! notes the user can write.
+
+use std::fmt;
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{event_context_for, ApiError, AppState, ArchiveContext};
+use matric_core::task::set_checkbox;
+use matric_core::{
+    AccessLevel, Error, NoteFull, ServerEvent, Task, TaskFilter, TaskListing, TaskSource,
+    TaskStatus,
+};
+use matric_db::PgNoteRepository;
+
+const DEFAULT_TASK_LIMIT: i64 = 50;
+const MAX_TASK_LIMIT: i64 = 500;
+
+#[derive(Deserialize)]
+pub struct TaskQuery {
+    /// Only tasks in this status.
+    status: Option<TaskStatus>,
+    /// Only checkbox tasks or only action items.
+    source: Option<TaskSource>,
+    /// Only tasks of this note.
+    note_id: Option<Uuid>,
+    /// Only tasks due on or before this day.
+    due_before: Option<NaiveDate>,
+    /// Only tasks due on or after this day.
+    due_after: Option<NaiveDate>,
+    /// Maximum number of tasks to return (default: 50, max: 500).
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+impl fmt::Debug for TaskQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskQuery")
+            .field("status", &self.status)
+            .field("source", &self.source)
+            .field("note_id_set", &self.note_id.is_some())
+            .field("due_before", &self.due_before)
+            .field("due_after", &self.due_after)
+            .field("limit", &self.limit)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+/// List tasks.
+///
+/// Tasks of live notes, soonest due first; tasks without a due date come
+/// last. Empty until notes have been through the `task_extraction` job.
+///
+/// GET /api/v1/tasks
+#[utoipa::path(get, path = "/api/v1/tasks", tag = "Notes",
+    params(
+        ("status" = Option<String>, Query, description = "open or done"),
+        ("source" = Option<String>, Query, description = "checkbox or llm"),
+        ("note_id" = Option<Uuid>, Query, description = "Only tasks of this note"),
+        ("due_before" = Option<String>, Query, description = "Only tasks due on or before this date (YYYY-MM-DD)"),
+        ("due_after" = Option<String>, Query, description = "Only tasks due on or after this date (YYYY-MM-DD)"),
+        ("limit" = Option<i64>, Query, description = "Maximum results (default 50, max 500)"),
+        ("offset" = Option<i64>, Query, description = "Pagination offset")
+    ),
+    responses((status = 200, description = "Tasks", body = TaskListing)))]
+pub async fn list_tasks(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Query(query): Query<TaskQuery>,
+) -> Result<Json<TaskListing>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_TASK_LIMIT)
+        .clamp(1, MAX_TASK_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let filter = TaskFilter {
+        status: query.status,
+        source: query.source,
+        note_id: query.note_id,
+        due_before: query.due_before,
+        due_after: query.due_after,
+    };
+
+    let security = caller.security_filter();
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let tasks = state.db.tasks.clone();
+    let listing = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                tasks
+                    .list_tx(tx, &filter, security.as_ref(), limit, offset)
+                    .await
+            })
+        })
+        .await?;
+    Ok(Json(listing))
+}
+
+/// Get a task.
+///
+/// GET /api/v1/tasks/{id}
+#[utoipa::path(get, path = "/api/v1/tasks/{id}", tag = "Notes",
+    params(("id" = Uuid, Path, description = "Task ID")),
+    responses(
+        (status = 200, description = "Task", body = Task),
+        (status = 404, description = "Task not found")
+    ))]
+pub async fn get_task(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Task>, ApiError> {
+    let security = caller.security_filter();
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let tasks = state.db.tasks.clone();
+    let task = ctx
+        .query(move |tx| Box::pin(async move { tasks.get_tx(tx, id, security.as_ref()).await }))
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Task not found".to_string()))?;
+    Ok(Json(task))
+}
+
+/// Mark a task done.
+///
+/// A checkbox task has its box ticked in the note's original content, and in
+/// the revised content when that has the same checkbox. Action items only
+/// change status.
+///
+/// POST /api/v1/tasks/{id}/complete
+#[utoipa::path(post, path = "/api/v1/tasks/{id}/complete", tag = "Notes",
+    params(("id" = Uuid, Path, description = "Task ID")),
+    responses(
+        (status = 200, description = "Task done", body = Task),
+        (status = 400, description = "The note no longer has the task's checkbox, or is encrypted"),
+        (status = 403, description = "The caller cannot write the task's note"),
+        (status = 404, description = "Task not found")
+    ))]
+pub async fn complete_task(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Task>, ApiError> {
+    set_task_status(state, archive_ctx, caller, id, TaskStatus::Done).await
+}
+
+/// Reopen a task.
+///
+/// A checkbox task has its box cleared in the note, as for completion.
+///
+/// POST /api/v1/tasks/{id}/uncomplete
+#[utoipa::path(post, path = "/api/v1/tasks/{id}/uncomplete", tag = "Notes",
+    params(("id" = Uuid, Path, description = "Task ID")),
+    responses(
+        (status = 200, description = "Task open", body = Task),
+        (status = 400, description = "The note no longer has the task's checkbox, or is encrypted"),
+        (status = 403, description = "The caller cannot write the task's note"),
+        (status = 404, description = "Task not found")
+    ))]
+pub async fn uncomplete_task(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Task>, ApiError> {
+    set_task_status(state, archive_ctx, caller, id, TaskStatus::Open).await
+}
+
+/// Set a task's status, writing checkbox tasks back to their note.
+///
+/// A caller acting for a user needs write access to the task's note; tasks
+/// of notes the user cannot see look missing.
+async fn set_task_status(
+    state: AppState,
+    archive_ctx: ArchiveContext,
+    caller: Caller,
+    id: Uuid,
+    status: TaskStatus,
+) -> Result<Json<Task>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let tasks = state.db.tasks.clone();
+    let users = state.db.users.clone();
+    let notes = PgNoteRepository::new(state.db.pool.clone());
+    let (task, note): (Task, Option<NoteFull>) = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                let task = tasks
+                    .get_for_update_tx(tx, id)
+                    .await?
+                    .ok_or_else(|| Error::NotFound("Task not found".to_string()))?;
+                if let Some(user_id) = caller.user_id {
+                    match users.note_access_tx(tx, task.note_id, user_id).await? {
+                        Some(level) if level.allows(AccessLevel::Write) => {}
+                        Some(_) => {
+                            return Err(Error::Forbidden(
+                                "Your access to this note does not allow changing its tasks"
+                                    .to_string(),
+                            ))
+                        }
+                        None => return Err(Error::NotFound("Task not found".to_string())),
+                    }
+                }
+                if task.source != TaskSource::Checkbox || task.status == status {
+                    let task = tasks.set_status_tx(tx, id, status, None).await?;
+                    return Ok((task, None));
+                }
+
+                let note = notes.fetch_tx(tx, task.note_id).await?;
+                if note.note.encrypted {
+                    return Err(Error::InvalidInput(
+                        "Tasks of encrypted notes cannot be changed".to_string(),
+                    ));
+                }
+                let done = status == TaskStatus::Done;
+                let line = task.line.and_then(|line| usize::try_from(line).ok());
+                let original = &note.original.content;
+                let updated = set_checkbox(original, line, &task.text, done).ok_or_else(|| {
+                    Error::InvalidInput("The note no longer has this task's checkbox".to_string())
+                })?;
+                let new_line = updated
+                    .lines()
+                    .zip(original.lines())
+                    .position(|(new, old)| new != old)
+                    .and_then(|line| i32::try_from(line).ok());
+
+                notes.update_original_tx(tx, task.note_id, &updated).await?;
+                let revised = &note.revised.content;
+                if revised.is_empty() || revised == original {
+                    notes
+                        .sync_revised_to_original_tx(tx, task.note_id, &updated)
+                        .await?;
+                } else if let Some(revised) = set_checkbox(revised, line, &task.text, done) {
+                    let rationale = if done {
+                        "Ticked completed task"
+                    } else {
+                        "Cleared reopened task"
+                    };
+                    notes
+                        .update_revised_tx(tx, task.note_id, &revised, Some(rationale))
+                        .await?;
+                }
+
+                let task = tasks.set_status_tx(tx, id, status, new_line).await?;
+                let note = notes.fetch_tx(tx, task.note_id).await?;
+                Ok((task, Some(note)))
+            })
+        })
+        .await
+        .map_err(|e| match e {
+            Error::Forbidden(msg) => ApiError::Forbidden(msg),
+            e => ApiError::from(e),
+        })?;
+
+    if let Some(note) = note {
+        state.event_bus.emit_with_context(
+            ServerEvent::NoteUpdated {
+                note_id: note.note.id,
+                title: note.note.title.clone(),
+                tags: note.tags.clone(),
+                has_ai_content: note.revised.ai_generated_at.is_some(),
+                has_links: !note.links.is_empty(),
+            },
+            event_context_for(&archive_ctx),
+        );
+        state.search_cache.invalidate_all().await;
+    }
+    Ok(Json(task))
+}
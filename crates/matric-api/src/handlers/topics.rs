@@ -0,0 +1,106 @@
+//! Topic HTTP handlers.
+//!
+//! Topics are clusters of related notes found by the `topic_modeling` job:
+//! - `GET /api/v1/topics` — list topics, largest first
+//! - `GET /api/v1/topics/{id}` — a topic and its member notes
+//! - `POST /api/v1/topics/refresh` — queue a topic modeling run
+//!
+//! A user's token only sees the member notes the user can read.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::{JobRepository, JobType, RefreshTopicsRequest, ServerEvent, Topic, TopicDetail};
+
+fn topic_not_found_error() -> matric_core::Error {
+    matric_core::Error::NotFound("Topic not found; topic_id_present=true".to_string())
+}
+
+/// List the archive's topics, largest first.
+///
+/// GET /api/v1/topics
+#[utoipa::path(get, path = "/api/v1/topics", tag = "Topics",
+    responses((status = 200, description = "Success", body = Vec<Topic>)))]
+pub async fn list_topics(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+) -> Result<Json<Vec<Topic>>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let topics = state.db.topics.clone();
+    let list = ctx
+        .query(move |tx| Box::pin(async move { topics.list_tx(tx).await }))
+        .await?;
+    Ok(Json(list))
+}
+
+/// Get a topic and its member notes, most central first.
+///
+/// GET /api/v1/topics/{id}
+#[utoipa::path(get, path = "/api/v1/topics/{id}", tag = "Topics",
+    params(("id" = Uuid, Path, description = "Topic ID")),
+    responses(
+        (status = 200, description = "Success", body = TopicDetail),
+        (status = 404, description = "Topic not found")
+    ))]
+pub async fn get_topic(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TopicDetail>, ApiError> {
+    let security = caller.security_filter();
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let topics = state.db.topics.clone();
+    let detail = ctx
+        .query(move |tx| Box::pin(async move { topics.get_tx(tx, id, security.as_ref()).await }))
+        .await?
+        .ok_or_else(topic_not_found_error)?;
+    Ok(Json(detail))
+}
+
+/// Queue a topic modeling run. Its topics replace the archive's current ones.
+///
+/// POST /api/v1/topics/refresh
+#[utoipa::path(post, path = "/api/v1/topics/refresh", tag = "Topics",
+    request_body = RefreshTopicsRequest,
+    responses(
+        (status = 202, description = "Run queued"),
+        (status = 400, description = "Invalid topic count")
+    ))]
+pub async fn refresh_topics(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    body: Option<Json<RefreshTopicsRequest>>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+    req.validate()?;
+
+    let mut payload = json!({ "schema": archive_ctx.schema });
+    if let Some(k) = req.k {
+        payload["k"] = json!(k);
+    }
+    let job_id = state
+        .db
+        .jobs
+        .queue(
+            None,
+            JobType::TopicModeling,
+            JobType::TopicModeling.default_priority(),
+            Some(payload),
+            JobType::TopicModeling.default_cost_tier(),
+        )
+        .await?;
+    state.event_bus.emit(ServerEvent::JobQueued {
+        job_id,
+        job_type: format!("{:?}", JobType::TopicModeling),
+        note_id: None,
+    });
+    Ok((StatusCode::ACCEPTED, Json(json!({ "job_id": job_id }))))
+}
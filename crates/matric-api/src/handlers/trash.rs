@@ -0,0 +1,232 @@
+//! Note trash HTTP handlers.
+//!
+//! Lists, restores and purges the soft-deleted notes of the selected archive:
+//! - `GET /api/v1/trash` — trashed notes, most recently deleted first
+//! - `POST /api/v1/trash/restore` — restore selected notes or the whole trash
+//! - `POST /api/v1/trash/purge` — queue a purge of selected notes or the whole trash
+//!
+//! Notes left in the trash are purged by the periodic `trash_purge` job once
+//! they are older than `TRASH_RETENTION_DAYS`.
+//!
+//! Requests acting for a user only see, restore and purge the trashed notes
+//! the user owns or can write; `all` means all of those.
+
+use std::fmt;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{event_context_for, queue_nlp_pipeline, ApiError, AppState, ArchiveContext};
+use matric_core::{
+    defaults, JobRepository, JobType, RevisionMode, ServerEvent, TrashListing, TrashSelection,
+};
+
+const DEFAULT_TRASH_LIMIT: i64 = 50;
+const MAX_TRASH_LIMIT: i64 = 500;
+
+#[derive(Deserialize)]
+pub struct TrashQuery {
+    /// Maximum number of notes to return (default: 50, max: 500).
+    limit: Option<i64>,
+    /// Number of notes to skip.
+    offset: Option<i64>,
+}
+
+impl fmt::Debug for TrashQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrashQuery")
+            .field("limit", &self.limit)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+/// Job payload purging the selected notes from an archive's trash, limited
+/// to the notes `user_id` may write when given.
+fn trash_purge_payload(
+    schema: &str,
+    selection: &TrashSelection,
+    user_id: Option<Uuid>,
+) -> serde_json::Value {
+    let mut payload = match selection.ids() {
+        Some(ids) => json!({ "schema": schema, "note_ids": ids }),
+        None => json!({ "schema": schema, "all": true }),
+    };
+    if let Some(user_id) = user_id {
+        payload["user_id"] = json!(user_id);
+    }
+    payload
+}
+
+/// List the archive's trashed notes.
+///
+/// GET /api/v1/trash
+#[utoipa::path(get, path = "/api/v1/trash", tag = "Notes",
+    params(
+        ("limit" = Option<i64>, Query, description = "Maximum notes to return (default 50, max 500)"),
+        ("offset" = Option<i64>, Query, description = "Notes to skip")
+    ),
+    responses((status = 200, description = "Success", body = TrashListing)))]
+pub async fn list_trash(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Query(query): Query<TrashQuery>,
+) -> Result<Json<TrashListing>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_TRASH_LIMIT)
+        .clamp(1, MAX_TRASH_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let retention_days = defaults::trash_retention_days();
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let trash = state.db.trash.clone();
+    let listing = ctx
+        .query(move |tx| {
+            Box::pin(async move {
+                trash
+                    .list_tx(tx, caller.user_id, limit, offset, retention_days)
+                    .await
+            })
+        })
+        .await?;
+    Ok(Json(listing))
+}
+
+/// Restore trashed notes.
+///
+/// Restored notes are re-indexed without AI revision. IDs that are not in
+/// the trash, or that the caller cannot write, are skipped.
+///
+/// POST /api/v1/trash/restore
+#[utoipa::path(post, path = "/api/v1/trash/restore", tag = "Notes",
+    request_body = TrashSelection,
+    responses(
+        (status = 200, description = "Restored note IDs"),
+        (status = 400, description = "Neither or both of ids and all given")
+    ))]
+pub async fn restore_trash(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Json(selection): Json<TrashSelection>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    selection.validate()?;
+
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let trash = state.db.trash.clone();
+    let restored = ctx
+        .execute(move |tx| {
+            Box::pin(async move { trash.restore_tx(tx, selection.ids(), caller.user_id).await })
+        })
+        .await?;
+
+    let schema_for_jobs = (archive_ctx.schema != "public").then_some(archive_ctx.schema.as_str());
+    for &id in &restored {
+        state.event_bus.emit_with_context(
+            ServerEvent::NoteRestored { note_id: id },
+            event_context_for(&archive_ctx),
+        );
+        queue_nlp_pipeline(
+            &state.db,
+            id,
+            RevisionMode::None,
+            &state.event_bus,
+            schema_for_jobs,
+            None,
+        )
+        .await;
+    }
+    if !restored.is_empty() {
+        state.search_cache.invalidate_all().await;
+    }
+
+    Ok(Json(
+        json!({ "count": restored.len(), "restored": restored }),
+    ))
+}
+
+/// Permanently delete trashed notes.
+///
+/// Queues a `trash_purge` job; notes not in the trash, or that the caller
+/// cannot write, are left alone.
+///
+/// POST /api/v1/trash/purge
+#[utoipa::path(post, path = "/api/v1/trash/purge", tag = "Notes",
+    request_body = TrashSelection,
+    responses(
+        (status = 202, description = "Purge queued"),
+        (status = 400, description = "Neither or both of ids and all given")
+    ))]
+pub async fn purge_trash(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Json(selection): Json<TrashSelection>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    selection.validate()?;
+
+    let job_id = state
+        .db
+        .jobs
+        .queue(
+            None,
+            JobType::TrashPurge,
+            JobType::TrashPurge.default_priority(),
+            Some(trash_purge_payload(
+                &archive_ctx.schema,
+                &selection,
+                caller.user_id,
+            )),
+            JobType::TrashPurge.default_cost_tier(),
+        )
+        .await?;
+    state.event_bus.emit(ServerEvent::JobQueued {
+        job_id,
+        job_type: format!("{:?}", JobType::TrashPurge),
+        note_id: None,
+    });
+    for &id in selection.ids().unwrap_or_default() {
+        state.inference_cache.invalidate_note(id).await;
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({ "status": "queued", "job_id": job_id })),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn purge_payload_names_schema_and_selection() {
+        let id = Uuid::nil();
+        let selected = TrashSelection {
+            ids: vec![id],
+            all: false,
+        };
+        assert_eq!(
+            trash_purge_payload("archive_a", &selected, None),
+            json!({ "schema": "archive_a", "note_ids": [id] })
+        );
+
+        let everything = TrashSelection {
+            ids: vec![],
+            all: true,
+        };
+        assert_eq!(
+            trash_purge_payload("public", &everything, Some(id)),
+            json!({ "schema": "public", "all": true, "user_id": id })
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! Typed link HTTP handlers.
+//!
+//! - `GET /api/v1/link-types` — built-in and custom link types
+//! - `POST /api/v1/link-types` — register a custom link type
+//! - `DELETE /api/v1/link-types/{name}` — remove an unused custom link type
+//! - `POST /api/v1/notes/{id}/links` — link a note to another with a typed relation
+//! - `PATCH /api/v1/links/{id}` — change a link's type, score or metadata
+//! - `DELETE /api/v1/links/{id}` — delete a link
+//!
+//! Symmetric types (`contradicts`, `related` and custom types registered as
+//! symmetric) link both directions, and edits and deletes follow both.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::middleware::ownership::Caller;
+use crate::{ApiError, AppState, ArchiveContext};
+use matric_core::{
+    CreateLinkTypeRequest, CreateTypedLinkRequest, Link, LinkTypeDefinition, UpdateTypedLinkRequest,
+};
+use matric_db::PgLinkRepository;
+
+/// List link types.
+///
+/// The built-in types, then the archive's custom types by name.
+///
+/// GET /api/v1/link-types
+#[utoipa::path(get, path = "/api/v1/link-types", tag = "Graph",
+    responses((status = 200, description = "Link types", body = Vec<LinkTypeDefinition>)))]
+pub async fn list_link_types(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+) -> Result<Json<Vec<LinkTypeDefinition>>, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let links = PgLinkRepository::new(state.db.pool.clone());
+    let types = ctx
+        .query(move |tx| Box::pin(async move { links.list_link_types_tx(tx).await }))
+        .await?;
+    Ok(Json(types))
+}
+
+/// Register a custom link type.
+///
+/// POST /api/v1/link-types
+#[utoipa::path(post, path = "/api/v1/link-types", tag = "Graph",
+    request_body = CreateLinkTypeRequest,
+    responses(
+        (status = 201, description = "Link type registered", body = LinkTypeDefinition),
+        (status = 400, description = "Invalid, reserved or built-in name"),
+        (status = 409, description = "Link type already registered")
+    ))]
+pub async fn create_link_type(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Json(body): Json<CreateLinkTypeRequest>,
+) -> Result<(StatusCode, Json<LinkTypeDefinition>), ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let links = PgLinkRepository::new(state.db.pool.clone());
+    let link_type = ctx
+        .execute(move |tx| Box::pin(async move { links.create_link_type_tx(tx, &body).await }))
+        .await?;
+    Ok((StatusCode::CREATED, Json(link_type)))
+}
+
+/// Remove a custom link type.
+///
+/// Fails while any link still uses the type.
+///
+/// DELETE /api/v1/link-types/{name}
+#[utoipa::path(delete, path = "/api/v1/link-types/{name}", tag = "Graph",
+    params(("name" = String, Path, description = "Link type name")),
+    responses(
+        (status = 204, description = "Link type removed"),
+        (status = 400, description = "Built-in type or type in use"),
+        (status = 404, description = "Link type not found")
+    ))]
+pub async fn delete_link_type(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let links = PgLinkRepository::new(state.db.pool.clone());
+    ctx.execute(move |tx| Box::pin(async move { links.delete_link_type_tx(tx, &name).await }))
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Create a typed link.
+///
+/// Returns the links created: one, or both directions for a symmetric type.
+///
+/// POST /api/v1/notes/{id}/links
+#[utoipa::path(post, path = "/api/v1/notes/{id}/links", tag = "Graph",
+    params(("id" = Uuid, Path, description = "Source note ID")),
+    request_body = CreateTypedLinkRequest,
+    responses(
+        (status = 201, description = "Links created", body = Vec<Link>),
+        (status = 400, description = "Unknown type, invalid score or metadata, or already linked"),
+        (status = 404, description = "Note not found")
+    ))]
+pub async fn create_typed_link(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+    Json(body): Json<CreateTypedLinkRequest>,
+) -> Result<(StatusCode, Json<Vec<Link>>), ApiError> {
+    let security = caller.security_filter();
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let links = PgLinkRepository::new(state.db.pool.clone());
+    let created = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                links
+                    .create_typed_link_tx(tx, id, &body, security.as_ref())
+                    .await
+            })
+        })
+        .await?;
+    state.search_cache.invalidate_all().await;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Update a link.
+///
+/// Changes the type, score or metadata of a link between notes; absent
+/// fields are kept. Any link can be retyped, including ones the linker made.
+///
+/// PATCH /api/v1/links/{id}
+#[utoipa::path(patch, path = "/api/v1/links/{id}", tag = "Graph",
+    params(("id" = Uuid, Path, description = "Link ID")),
+    request_body = UpdateTypedLinkRequest,
+    responses(
+        (status = 200, description = "Link updated", body = Link),
+        (status = 400, description = "Unknown type, invalid score or metadata, or already linked"),
+        (status = 404, description = "Link not found")
+    ))]
+pub async fn update_typed_link(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+    Json(body): Json<UpdateTypedLinkRequest>,
+) -> Result<Json<Link>, ApiError> {
+    let security = caller.security_filter();
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let links = PgLinkRepository::new(state.db.pool.clone());
+    let link = ctx
+        .execute(move |tx| {
+            Box::pin(async move {
+                links
+                    .update_typed_link_tx(tx, id, &body, security.as_ref())
+                    .await
+            })
+        })
+        .await?;
+    state.search_cache.invalidate_all().await;
+    Ok(Json(link))
+}
+
+/// Delete a link.
+///
+/// DELETE /api/v1/links/{id}
+#[utoipa::path(delete, path = "/api/v1/links/{id}", tag = "Graph",
+    params(("id" = Uuid, Path, description = "Link ID")),
+    responses(
+        (status = 204, description = "Link deleted"),
+        (status = 404, description = "Link not found")
+    ))]
+pub async fn delete_typed_link(
+    State(state): State<AppState>,
+    Extension(archive_ctx): Extension<ArchiveContext>,
+    caller: Caller,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let security = caller.security_filter();
+    let ctx = state.db.for_schema(&archive_ctx.schema)?;
+    let links = PgLinkRepository::new(state.db.pool.clone());
+    ctx.execute(move |tx| {
+        Box::pin(async move { links.delete_typed_link_tx(tx, id, security.as_ref()).await })
+    })
+    .await?;
+    state.search_cache.invalidate_all().await;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,305 @@
+//! Per-API-key resource restrictions.
+//!
+//! An API key can be confined to named memories, to collections, or to read
+//! access (see [`ApiKeyRestrictions`]). These checks run for every request
+//! made with such a key, whatever authorization policy is configured:
+//!
+//! - the auth middleware rejects what the route alone decides — writes by
+//!   read-only keys and routes naming a memory or collection outside the
+//!   key's lists — and stores the restrictions in the request extensions;
+//! - the archive routing middleware refuses to route the request to a memory
+//!   outside the key's list and checks that a note named by the route is
+//!   filed in one of the key's collections.
+//!
+//! A collection-restricted key reaches only notes and collections: note
+//! routes naming a note, `GET /api/v1/notes` and the calendar feed filtered
+//! by an allowed `collection_id`, and collection routes naming an allowed
+//! collection.
+
+use axum::http::Method;
+use uuid::Uuid;
+
+use crate::route_policy::{RoutePolicyInput, CALENDAR_FEED_PATH};
+use matric_core::ApiKeyRestrictions;
+
+/// Why a restricted key was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeDenial {
+    /// The key is read-only and the route needs more than read access.
+    ReadOnly,
+    /// The route reaches a memory outside the key's list.
+    Archive,
+    /// The route reaches data outside the key's collections.
+    Collection,
+}
+
+impl ScopeDenial {
+    /// Problem detail returned to the caller.
+    pub fn detail(self) -> &'static str {
+        match self {
+            Self::ReadOnly => "This API key is read-only.",
+            Self::Archive => "This API key is not allowed to access this memory.",
+            Self::Collection => {
+                "This API key is restricted to specific collections; this route is outside them."
+            }
+        }
+    }
+}
+
+pub(crate) fn route_param<'a>(input: &'a RoutePolicyInput, name: &str) -> Option<&'a str> {
+    input
+        .resource
+        .attrs
+        .get(&format!("route_param_{name}"))
+        .and_then(|value| value.as_str())
+}
+
+fn query_collection_id(query: Option<&str>) -> Option<Uuid> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "collection_id")
+        .and_then(|(_, value)| Uuid::parse_str(value).ok())
+}
+
+fn is_read_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Check a request against the restrictions its route alone decides.
+///
+/// `input` is the route's policy input (`None` for routes outside the
+/// inventory) and `query` the raw query string.
+pub fn check_route(
+    restrictions: &ApiKeyRestrictions,
+    method: &Method,
+    input: Option<&RoutePolicyInput>,
+    query: Option<&str>,
+) -> Result<(), ScopeDenial> {
+    let Some(input) = input else {
+        if restrictions.read_only && !is_read_method(method) {
+            return Err(ScopeDenial::ReadOnly);
+        }
+        return if restrictions.collections.is_some() {
+            Err(ScopeDenial::Collection)
+        } else {
+            Ok(())
+        };
+    };
+
+    let required = &input.action.required_scopes;
+    if restrictions.read_only && required.iter().any(|scope| scope != "read") {
+        return Err(ScopeDenial::ReadOnly);
+    }
+    // Public routes (health, OAuth, docs) need no scope and stay reachable.
+    if required.is_empty() {
+        return Ok(());
+    }
+
+    let family = input.policy.action_family;
+    if restrictions.archives.is_some() && family == "memory_management" {
+        match route_param(input, "name") {
+            Some(name) if restrictions.allows_archive(name) => {}
+            _ => return Err(ScopeDenial::Archive),
+        }
+    }
+
+    if restrictions.collections.is_some() {
+        let allowed = match family {
+            "collection" => route_param(input, "id")
+                .and_then(|id| Uuid::parse_str(id).ok())
+                .is_some_and(|id| restrictions.allows_collection(Some(id))),
+            // The note's collection is checked once the archive is routed.
+            "note" => match route_param(input, "id") {
+                Some(id) => Uuid::parse_str(id).is_ok(),
+                None => {
+                    matches!(input.policy.path, "/api/v1/notes" | CALENDAR_FEED_PATH)
+                        && is_read_method(method)
+                        && query_collection_id(query)
+                            .is_some_and(|id| restrictions.allows_collection(Some(id)))
+                }
+            },
+            _ => false,
+        };
+        if !allowed {
+            return Err(ScopeDenial::Collection);
+        }
+    }
+
+    Ok(())
+}
+
+/// The note a collection-restricted key's request names, which the archive
+/// routing middleware must find in one of the key's collections.
+pub fn restricted_note(
+    restrictions: &ApiKeyRestrictions,
+    input: Option<&RoutePolicyInput>,
+) -> Option<Uuid> {
+    restrictions.collections.as_ref()?;
+    let input = input?;
+    if input.policy.action_family != "note" {
+        return None;
+    }
+    route_param(input, "id").and_then(|id| Uuid::parse_str(id).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::route_policy::authorization_input_for_request;
+
+    fn check(
+        restrictions: &ApiKeyRestrictions,
+        method: Method,
+        path: &str,
+        query: Option<&str>,
+    ) -> Result<(), ScopeDenial> {
+        let input = authorization_input_for_request(&method, path, None);
+        check_route(restrictions, &method, input.as_ref(), query)
+    }
+
+    #[test]
+    fn unrestricted_keys_pass_every_route() {
+        let restrictions = ApiKeyRestrictions::default();
+        assert!(restrictions.is_unrestricted());
+        assert_eq!(
+            check(&restrictions, Method::POST, "/api/v1/notes", None),
+            Ok(())
+        );
+        assert_eq!(
+            check(&restrictions, Method::DELETE, "/api/v1/archives/work", None),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn read_only_keys_cannot_write() {
+        let restrictions = ApiKeyRestrictions {
+            read_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            check(&restrictions, Method::GET, "/api/v1/notes", None),
+            Ok(())
+        );
+        assert_eq!(
+            check(&restrictions, Method::POST, "/api/v1/notes", None),
+            Err(ScopeDenial::ReadOnly)
+        );
+        assert_eq!(
+            check(&restrictions, Method::POST, "/api/v1/not-a-route", None),
+            Err(ScopeDenial::ReadOnly)
+        );
+    }
+
+    #[test]
+    fn archive_restricted_keys_only_reach_their_memories() {
+        let restrictions = ApiKeyRestrictions {
+            archives: Some(vec!["work".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            check(
+                &restrictions,
+                Method::GET,
+                "/api/v1/memories/work/stats",
+                None
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check(
+                &restrictions,
+                Method::DELETE,
+                "/api/v1/archives/personal",
+                None
+            ),
+            Err(ScopeDenial::Archive)
+        );
+        assert_eq!(
+            check(&restrictions, Method::POST, "/api/v1/archives", None),
+            Err(ScopeDenial::Archive)
+        );
+        assert_eq!(
+            check(&restrictions, Method::GET, "/api/v1/notes", None),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn collection_restricted_keys_only_reach_their_collections() {
+        let allowed = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let restrictions = ApiKeyRestrictions {
+            collections: Some(vec![allowed]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            check(
+                &restrictions,
+                Method::GET,
+                &format!("/api/v1/collections/{allowed}/notes"),
+                None
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check(
+                &restrictions,
+                Method::GET,
+                &format!("/api/v1/collections/{other}"),
+                None
+            ),
+            Err(ScopeDenial::Collection)
+        );
+        let filtered = format!("limit=5&collection_id={allowed}");
+        assert_eq!(
+            check(&restrictions, Method::GET, "/api/v1/notes", Some(&filtered)),
+            Ok(())
+        );
+        assert_eq!(
+            check(&restrictions, Method::GET, "/api/v1/notes", Some("limit=5")),
+            Err(ScopeDenial::Collection)
+        );
+        assert_eq!(
+            check(
+                &restrictions,
+                Method::GET,
+                "/api/v1/calendar.ics",
+                Some(&format!("token=mm_key_x&collection_id={allowed}"))
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check(&restrictions, Method::GET, "/api/v1/calendar.ics", None),
+            Err(ScopeDenial::Collection)
+        );
+        assert_eq!(
+            check(&restrictions, Method::GET, "/api/v1/search", Some("q=x")),
+            Err(ScopeDenial::Collection)
+        );
+        assert_eq!(check(&restrictions, Method::GET, "/health", None), Ok(()));
+    }
+
+    #[test]
+    fn note_routes_defer_to_the_routed_archive() {
+        let note_id = Uuid::new_v4();
+        let restrictions = ApiKeyRestrictions {
+            collections: Some(vec![Uuid::new_v4()]),
+            ..Default::default()
+        };
+        let path = format!("/api/v1/notes/{note_id}/versions");
+        let input = authorization_input_for_request(&Method::GET, &path, None);
+
+        assert_eq!(check(&restrictions, Method::GET, &path, None), Ok(()));
+        assert_eq!(
+            restricted_note(&restrictions, input.as_ref()),
+            Some(note_id)
+        );
+        assert_eq!(
+            restricted_note(&ApiKeyRestrictions::default(), input.as_ref()),
+            None
+        );
+    }
+}
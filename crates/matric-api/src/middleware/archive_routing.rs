@@ -8,8 +8,12 @@ use chrono::{DateTime, Utc};
 use serde::Serialize;
 use std::fmt;
 
+use super::api_key_scope;
+use super::ownership::{self, Caller, OwnedTarget};
+use crate::route_policy::{self, RoutePolicyInput};
 use crate::AppState;
-use matric_core::ArchiveRepository;
+use matric_core::{AccessLevel, ApiKeyRestrictions, ArchiveRepository};
+use uuid::Uuid;
 
 /// Archive context injected into request extensions.
 ///
@@ -234,23 +238,199 @@ fn archive_problem_response(
         .into_response()
 }
 
+/// Resolve an explicitly named memory, syncing its schema on the way.
+async fn select_named_archive(
+    state: &AppState,
+    name: &str,
+) -> Result<ArchiveContext, axum::response::Response> {
+    match state.db.archives.get_archive_by_name(name).await {
+        Ok(Some(info)) => {
+            // Auto-migrate if schema is outdated (non-blocking best-effort)
+            if let Err(e) = state.db.archives.sync_archive_schema(name).await {
+                let diagnostic = e.to_string();
+                tracing::warn!(
+                    archive_name_len = telemetry_text_len(name),
+                    reason_code = archive_routing_diagnostic_reason(&diagnostic),
+                    error_len = telemetry_text_len(&diagnostic),
+                    "failed to sync selected archive schema"
+                );
+            }
+            Ok(ArchiveContext {
+                schema: info.schema_name,
+                is_default: false,
+                name: Some(name.to_string()),
+            })
+        }
+        Ok(None) => Err(memory_not_visible()),
+        Err(_) => Err(internal_error()),
+    }
+}
+
+fn memory_not_visible() -> axum::response::Response {
+    archive_problem_response(
+        axum::http::StatusCode::NOT_FOUND,
+        "not-found",
+        "Not Found",
+        "Requested memory is not present or not visible to the caller.",
+    )
+}
+
+fn internal_error() -> axum::response::Response {
+    archive_problem_response(
+        axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+        "internal-error",
+        "Internal Server Error",
+        "An internal error occurred.",
+    )
+}
+
+/// Route a request without a memory header. A key confined to memories that
+/// exclude the default is routed to its only memory, or must choose one.
+async fn select_default_archive(
+    state: &AppState,
+    restrictions: Option<&ApiKeyRestrictions>,
+) -> Result<ArchiveContext, axum::response::Response> {
+    let ctx = resolve_archive_context(state).await;
+    let Some(allowed) = restrictions.and_then(|r| r.archives.as_deref()) else {
+        return Ok(ctx);
+    };
+    if ctx
+        .name
+        .as_deref()
+        .is_some_and(|name| allowed.iter().any(|a| a == name))
+    {
+        return Ok(ctx);
+    }
+    match allowed {
+        [only] => select_named_archive(state, only).await,
+        _ => Err(archive_problem_response(
+            axum::http::StatusCode::FORBIDDEN,
+            "forbidden",
+            "Forbidden",
+            "This API key must select a memory with the X-Fortemi-Memory header.",
+        )),
+    }
+}
+
+/// Whether the note a collection-restricted key names is in one of its
+/// collections. Missing notes are treated like notes outside them.
+async fn note_in_allowed_collection(
+    state: &AppState,
+    ctx: &ArchiveContext,
+    restrictions: &ApiKeyRestrictions,
+    note_id: Uuid,
+) -> Result<bool, matric_core::Error> {
+    let schema_ctx = state.db.for_schema(&ctx.schema)?;
+    let notes = matric_db::PgNoteRepository::new(state.db.pool.clone());
+    let collection = schema_ctx
+        .query(move |tx| Box::pin(async move { notes.collection_id_tx(tx, note_id).await }))
+        .await?;
+    Ok(collection.is_some_and(|collection_id| restrictions.allows_collection(collection_id)))
+}
+
+/// A user's access to a memory, note or collection; notes and collections
+/// are looked up in the routed memory.
+async fn owned_target_access(
+    state: &AppState,
+    ctx: &ArchiveContext,
+    target: &OwnedTarget,
+    user_id: Uuid,
+) -> Result<Option<AccessLevel>, matric_core::Error> {
+    let users = state.db.users.clone();
+    match target.clone() {
+        OwnedTarget::Archive(name) => users.archive_access(&name, user_id).await,
+        OwnedTarget::Note(note_id) => {
+            state
+                .db
+                .for_schema(&ctx.schema)?
+                .query(move |tx| {
+                    Box::pin(async move { users.note_access_tx(tx, note_id, user_id).await })
+                })
+                .await
+        }
+        OwnedTarget::Collection(collection_id) => {
+            state
+                .db
+                .for_schema(&ctx.schema)?
+                .query(move |tx| {
+                    Box::pin(
+                        async move { users.collection_access_tx(tx, collection_id, user_id).await },
+                    )
+                })
+                .await
+        }
+    }
+}
+
+/// Check a user may use a resource as `needed`. Resources the user cannot
+/// see look the same as missing ones.
+async fn require_owned_access(
+    state: &AppState,
+    ctx: &ArchiveContext,
+    target: &OwnedTarget,
+    user_id: Uuid,
+    needed: AccessLevel,
+) -> Result<(), axum::response::Response> {
+    let (not_visible, not_allowed) = match target {
+        OwnedTarget::Archive(_) => (
+            "Requested memory is not present or not visible to the caller.",
+            "Your access to this memory does not allow this operation.",
+        ),
+        OwnedTarget::Note(_) => (
+            "Requested note is not present or not visible to the caller.",
+            "Your access to this note does not allow this operation.",
+        ),
+        OwnedTarget::Collection(_) => (
+            "Requested collection is not present or not visible to the caller.",
+            "Your access to this collection does not allow this operation.",
+        ),
+    };
+    match owned_target_access(state, ctx, target, user_id).await {
+        Ok(Some(level)) if level.allows(needed) => Ok(()),
+        Ok(Some(_)) => Err(archive_problem_response(
+            axum::http::StatusCode::FORBIDDEN,
+            "forbidden",
+            "Forbidden",
+            not_allowed,
+        )),
+        Ok(None) => Err(archive_problem_response(
+            axum::http::StatusCode::NOT_FOUND,
+            "not-found",
+            "Not Found",
+            not_visible,
+        )),
+        Err(_) => Err(internal_error()),
+    }
+}
+
 /// Archive routing middleware function.
 ///
 /// Injects an ArchiveContext into request extensions based on:
-/// 1. `X-Fortemi-Memory` header (explicit per-request selection)
+/// 1. `X-Fortemi-Memory` header (explicit per-request selection), or the
+///    `memory` query parameter on routes subscribed to by URL
 /// 2. Default archive setting (cached, TTL-based)
 /// 3. Fallback to public schema
 ///
 /// If the header specifies a memory that doesn't exist, returns 404.
+///
+/// Requests made with a restricted API key (see
+/// [`super::api_key_scope`]) are only routed to the key's memories, and a
+/// note the route names must be filed in one of the key's collections.
+///
+/// Requests acting for a user (see [`super::ownership`]) must be able to
+/// use the routed memory and the note, collection or memory the route names.
 pub async fn archive_routing_middleware(
     State(state): State<AppState>,
     mut req: axum::http::Request<axum::body::Body>,
     next: axum::middleware::Next,
 ) -> axum::response::Response {
-    // Check for explicit memory selection via header
-    if let Some(memory_name) = req.headers().get(MEMORY_HEADER) {
-        let name = match memory_name.to_str() {
-            Ok(n) => n.to_string(),
+    let restrictions = req.extensions().get::<ApiKeyRestrictions>().cloned();
+
+    // Check for explicit memory selection via header, or via `?memory=` on
+    // routes subscribed to by URL.
+    let requested = match req.headers().get(MEMORY_HEADER) {
+        Some(memory_name) => match memory_name.to_str() {
+            Ok(n) => Some(n.to_string()),
             Err(_) => {
                 return archive_problem_response(
                     axum::http::StatusCode::BAD_REQUEST,
@@ -259,52 +439,76 @@ pub async fn archive_routing_middleware(
                     "Invalid memory selection header.",
                 );
             }
-        };
+        },
+        None if route_policy::accepts_query_credentials(req.uri().path()) => {
+            route_policy::query_credential(req.uri().query(), "memory")
+        }
+        None => None,
+    };
+    let selected = if let Some(name) = requested {
+        // Memories outside the key's list look the same as missing ones.
+        if restrictions
+            .as_ref()
+            .is_some_and(|r| !r.allows_archive(&name))
+        {
+            return memory_not_visible();
+        }
+        select_named_archive(&state, &name).await
+    } else {
+        // No explicit selection — use default archive (cached)
+        select_default_archive(&state, restrictions.as_ref()).await
+    };
+    let ctx = match selected {
+        Ok(ctx) => ctx,
+        Err(response) => return response,
+    };
 
-        // Look up the requested memory
-        match state.db.archives.get_archive_by_name(&name).await {
-            Ok(Some(info)) => {
-                // Auto-migrate if schema is outdated (non-blocking best-effort)
-                if let Err(e) = state.db.archives.sync_archive_schema(&name).await {
-                    let diagnostic = e.to_string();
-                    tracing::warn!(
-                        archive_name_len = telemetry_text_len(&name),
-                        reason_code = archive_routing_diagnostic_reason(&diagnostic),
-                        error_len = telemetry_text_len(&diagnostic),
-                        "failed to sync selected archive schema"
+    if let Some(restrictions) = &restrictions {
+        let input = req.extensions().get::<RoutePolicyInput>();
+        if let Some(note_id) = api_key_scope::restricted_note(restrictions, input) {
+            match note_in_allowed_collection(&state, &ctx, restrictions, note_id).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    return archive_problem_response(
+                        axum::http::StatusCode::NOT_FOUND,
+                        "not-found",
+                        "Not Found",
+                        "Requested note is not present or not visible to the caller.",
                     );
                 }
-                let ctx = ArchiveContext {
-                    schema: info.schema_name,
-                    is_default: false,
-                    name: Some(name.clone()),
-                };
-                req.extensions_mut().insert(ctx);
-                return next.run(req).await;
+                Err(_) => return internal_error(),
             }
-            Ok(None) => {
-                return archive_problem_response(
-                    axum::http::StatusCode::NOT_FOUND,
-                    "not-found",
-                    "Not Found",
-                    "Requested memory is not present or not visible to the caller.",
-                );
-            }
-            Err(_) => {
-                return archive_problem_response(
-                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    "internal-error",
-                    "Internal Server Error",
-                    "An internal error occurred.",
-                );
+        }
+    }
+
+    let caller = req
+        .extensions()
+        .get::<Caller>()
+        .copied()
+        .unwrap_or_default();
+    if let Some(user_id) = caller.user_id {
+        let method = req.method().clone();
+        let mut checks = Vec::new();
+        if let Some(name) = &ctx.name {
+            checks.push((
+                OwnedTarget::Archive(name.clone()),
+                ownership::routed_archive_access(&method),
+            ));
+        }
+        checks.extend(ownership::required_access(
+            &method,
+            req.extensions().get::<RoutePolicyInput>(),
+        ));
+        for (target, needed) in &checks {
+            if let Err(response) =
+                require_owned_access(&state, &ctx, target, user_id, *needed).await
+            {
+                return response;
             }
         }
     }
 
-    // No explicit selection — use default archive (cached)
-    let ctx = resolve_archive_context(&state).await;
     req.extensions_mut().insert(ctx);
-
     next.run(req).await
 }
 
@@ -1,3 +1,7 @@
 //! Middleware modules for the matric-api.
 
+pub mod api_key_scope;
 pub mod archive_routing;
+pub mod ownership;
+pub mod rate_limit;
+pub mod usage_quota;
@@ -0,0 +1,211 @@
+//! Per-user ownership and sharing checks.
+//!
+//! Requests made with an OAuth token that names a user carry a [`Caller`]
+//! (inserted by the auth middleware). For those requests the archive routing
+//! middleware checks the user's access to the memory being routed to and to
+//! the note, collection or memory the route names, using
+//! [`required_access`] to decide what the route needs. List endpoints filter
+//! with [`Caller::security_filter`] instead.
+//!
+//! Requests without a user — API keys, anonymous callers, OAuth clients
+//! acting for themselves — are not restricted by ownership.
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::Method;
+use uuid::Uuid;
+
+use super::api_key_scope::route_param;
+use crate::route_policy::RoutePolicyInput;
+use crate::AppState;
+use matric_core::{AccessLevel, StrictSecurityFilter};
+
+/// The user a request acts for, if any.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Caller {
+    pub user_id: Option<Uuid>,
+}
+
+impl Caller {
+    /// Filter restricting note queries to what the user may read; `None`
+    /// when the request has no user.
+    pub fn security_filter(&self) -> Option<StrictSecurityFilter> {
+        self.user_id.map(StrictSecurityFilter::for_caller)
+    }
+}
+
+impl FromRequestParts<AppState> for Caller {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<Caller>()
+            .copied()
+            .unwrap_or_default())
+    }
+}
+
+/// A resource a route reaches by ID or name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OwnedTarget {
+    Note(Uuid),
+    Collection(Uuid),
+    Archive(String),
+}
+
+fn is_read_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Access needed to the resource itself at `path`: reading for safe
+/// methods, ownership to delete the resource or manage its shares, write
+/// access otherwise.
+fn level_for(method: &Method, path: &str, resource_path: &str) -> AccessLevel {
+    if path.contains("/shares") || (*method == Method::DELETE && path == resource_path) {
+        AccessLevel::Owner
+    } else if is_read_method(method) {
+        AccessLevel::Read
+    } else {
+        AccessLevel::Write
+    }
+}
+
+/// Route template prefixes whose leading parameter names an owned resource,
+/// with the template of the resource itself. Every route under a resource
+/// is checked, whatever its action family, so sub-resources such as links,
+/// versions and provenance are only reachable through a visible note.
+const OWNED_PREFIXES: &[(&str, &str)] = &[
+    ("/api/v1/notes/{id}", "id"),
+    ("/api/v1/graph/{id}", "id"),
+    ("/api/v1/collections/{id}", "id"),
+    ("/api/v1/archives/{name}", "name"),
+    ("/api/v1/memories/{name}", "name"),
+];
+
+/// Access the route needs to the resource it names, if it names one.
+pub fn required_access(
+    method: &Method,
+    input: Option<&RoutePolicyInput>,
+) -> Option<(OwnedTarget, AccessLevel)> {
+    let input = input?;
+    let path = input.policy.path;
+    let (resource_path, param) = OWNED_PREFIXES.iter().find(|(prefix, _)| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })?;
+    let value = route_param(input, param)?;
+    let target = match *param {
+        "name" => OwnedTarget::Archive(value.to_string()),
+        _ => {
+            let id = Uuid::parse_str(value).ok()?;
+            if resource_path.starts_with("/api/v1/collections/") {
+                OwnedTarget::Collection(id)
+            } else {
+                OwnedTarget::Note(id)
+            }
+        }
+    };
+    Some((target, level_for(method, path, resource_path)))
+}
+
+/// Access needed to the memory a request is routed to.
+pub fn routed_archive_access(method: &Method) -> AccessLevel {
+    if is_read_method(method) {
+        AccessLevel::Read
+    } else {
+        AccessLevel::Write
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::route_policy::authorization_input_for_request;
+
+    fn required(method: Method, path: &str) -> Option<(OwnedTarget, AccessLevel)> {
+        let input = authorization_input_for_request(&method, path, None);
+        required_access(&method, input.as_ref())
+    }
+
+    #[test]
+    fn note_routes_need_read_write_or_ownership() {
+        let id = Uuid::new_v4();
+        let note = format!("/api/v1/notes/{id}");
+
+        assert_eq!(
+            required(Method::GET, &note),
+            Some((OwnedTarget::Note(id), AccessLevel::Read))
+        );
+        assert_eq!(
+            required(Method::PATCH, &note),
+            Some((OwnedTarget::Note(id), AccessLevel::Write))
+        );
+        assert_eq!(
+            required(Method::DELETE, &note),
+            Some((OwnedTarget::Note(id), AccessLevel::Owner))
+        );
+        assert_eq!(
+            required(Method::GET, &format!("{note}/shares")),
+            Some((OwnedTarget::Note(id), AccessLevel::Owner))
+        );
+        assert_eq!(required(Method::GET, "/api/v1/notes"), None);
+    }
+
+    #[test]
+    fn note_sub_resources_check_the_note_whatever_their_family() {
+        let id = Uuid::new_v4();
+
+        for path in [
+            format!("/api/v1/notes/{id}/links"),
+            format!("/api/v1/notes/{id}/provenance"),
+            format!("/api/v1/notes/{id}/versions"),
+            format!("/api/v1/graph/{id}"),
+        ] {
+            assert_eq!(
+                required(Method::GET, &path),
+                Some((OwnedTarget::Note(id), AccessLevel::Read)),
+                "{path}"
+            );
+        }
+        assert_eq!(
+            required(Method::POST, &format!("/api/v1/notes/{id}/move")),
+            Some((OwnedTarget::Note(id), AccessLevel::Write))
+        );
+        assert_eq!(
+            required(Method::GET, &format!("/api/v1/collections/{id}/notes")),
+            Some((OwnedTarget::Collection(id), AccessLevel::Read))
+        );
+    }
+
+    #[test]
+    fn archive_routes_name_their_memory() {
+        assert_eq!(
+            required(Method::GET, "/api/v1/memories/work/stats"),
+            Some((OwnedTarget::Archive("work".to_string()), AccessLevel::Read))
+        );
+        assert_eq!(
+            required(Method::DELETE, "/api/v1/archives/work"),
+            Some((OwnedTarget::Archive("work".to_string()), AccessLevel::Owner))
+        );
+        assert_eq!(
+            required(Method::POST, "/api/v1/archives/work/clone"),
+            Some((OwnedTarget::Archive("work".to_string()), AccessLevel::Write))
+        );
+    }
+
+    #[test]
+    fn callers_without_a_user_are_not_filtered() {
+        assert_eq!(Caller::default().security_filter().map(|_| ()), None);
+        let user_id = Uuid::new_v4();
+        let filter = Caller {
+            user_id: Some(user_id),
+        }
+        .security_filter()
+        .expect("filter for user");
+        assert_eq!(filter.owner_id, Some(user_id));
+    }
+}
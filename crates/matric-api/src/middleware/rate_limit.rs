@@ -0,0 +1,374 @@
+//! Per-principal rate limiting.
+//!
+//! Requests are limited per principal — the API key, the OAuth client, or the
+//! client IP for requests without credentials — and per bucket, so a burst of
+//! searches does not use up the quota for ingestion or administration. Every
+//! bucket allows its configured number of requests per `RATE_LIMIT_PERIOD_SECS`
+//! window, and each response reports the state of the bucket it drew from in
+//! `X-RateLimit-*` headers.
+
+use std::fmt;
+use std::net::IpAddr;
+use std::num::NonZeroU32;
+use std::time::Duration;
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method};
+use governor::clock::{Clock, DefaultClock};
+use governor::middleware::StateInformationMiddleware;
+use governor::state::keyed::DefaultKeyedStateStore;
+use governor::{Quota, RateLimiter};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::route_policy::{PolicyClass, RoutePolicy};
+use matric_core::AuthPrincipal;
+
+pub const LIMIT_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+pub const REMAINING_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+pub const RESET_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+pub const BUCKET_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-bucket");
+
+/// The quota a request draws from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitBucket {
+    /// Search over note content.
+    Search,
+    /// Writes that add or change note content: notes, attachments and ingest
+    /// streams.
+    Ingestion,
+    /// Operator routes: credentials, backups, jobs, configuration.
+    Admin,
+    /// Everything else.
+    General,
+}
+
+impl RateLimitBucket {
+    pub const ALL: [Self; 4] = [Self::Search, Self::Ingestion, Self::Admin, Self::General];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Search => "search",
+            Self::Ingestion => "ingestion",
+            Self::Admin => "admin",
+            Self::General => "general",
+        }
+    }
+
+    /// The bucket for a request to the route `policy` (`None` for paths
+    /// outside the route inventory).
+    pub fn for_request(method: &Method, policy: Option<&RoutePolicy>) -> Self {
+        let Some(policy) = policy else {
+            return Self::General;
+        };
+        if policy.class == PolicyClass::AdminOperator {
+            return Self::Admin;
+        }
+        if policy.action_family == "search" || policy.path.ends_with("/search") {
+            return Self::Search;
+        }
+        let writes = !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+        if writes
+            && matches!(
+                policy.action_family,
+                "note" | "attachment" | "ingest_stream"
+            )
+        {
+            return Self::Ingestion;
+        }
+        Self::General
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::Search => 0,
+            Self::Ingestion => 1,
+            Self::Admin => 2,
+            Self::General => 3,
+        }
+    }
+}
+
+/// Who a request is limited as.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum RateLimitPrincipal {
+    ApiKey(Uuid),
+    OAuthClient(String),
+    /// Requests without credentials, by client address.
+    Ip(IpAddr),
+    /// Requests without credentials or a known client address share one
+    /// bucket.
+    Unknown,
+}
+
+impl RateLimitPrincipal {
+    /// Resolve the principal from the authenticated identity, falling back to
+    /// the client address for anonymous requests.
+    pub fn resolve(principal: Option<&AuthPrincipal>, client_ip: Option<IpAddr>) -> Self {
+        match principal {
+            Some(AuthPrincipal::ApiKey { key_id, .. }) => Self::ApiKey(*key_id),
+            Some(AuthPrincipal::OAuthClient { client_id, .. }) => {
+                Self::OAuthClient(client_id.clone())
+            }
+            Some(AuthPrincipal::Anonymous) | None => client_ip.map_or(Self::Unknown, Self::Ip),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ApiKey(_) => "api_key",
+            Self::OAuthClient(_) => "oauth_client",
+            Self::Ip(_) => "ip",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Debug for RateLimitPrincipal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitPrincipal")
+            .field("kind", &self.kind())
+            .finish()
+    }
+}
+
+/// Requests a bucket allows per window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BucketQuota {
+    pub bucket: RateLimitBucket,
+    pub requests: u32,
+    pub period_secs: u64,
+}
+
+/// The state of a principal's bucket after a request drew from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub quota: BucketQuota,
+    /// Requests still allowed right now.
+    pub remaining: u32,
+    /// Time until the bucket is full again (or, when denied, until the next
+    /// request is allowed).
+    pub reset: Duration,
+    /// Set when the request was denied.
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimitDecision {
+    /// Add the `X-RateLimit-*` headers describing this decision.
+    pub fn apply_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(LIMIT_HEADER, HeaderValue::from(self.quota.requests));
+        headers.insert(REMAINING_HEADER, HeaderValue::from(self.remaining));
+        headers.insert(RESET_HEADER, HeaderValue::from(ceil_secs(self.reset)));
+        headers.insert(
+            BUCKET_HEADER,
+            HeaderValue::from_static(self.quota.bucket.as_str()),
+        );
+    }
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    duration
+        .as_secs()
+        .saturating_add(u64::from(duration.subsec_nanos() > 0))
+}
+
+type KeyedLimiter = RateLimiter<
+    RateLimitPrincipal,
+    DefaultKeyedStateStore<RateLimitPrincipal>,
+    DefaultClock,
+    StateInformationMiddleware,
+>;
+
+/// One keyed token bucket per [`RateLimitBucket`].
+pub struct PrincipalRateLimiter {
+    buckets: Vec<(BucketQuota, KeyedLimiter)>,
+    clock: DefaultClock,
+}
+
+impl fmt::Debug for PrincipalRateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrincipalRateLimiter")
+            .field("quotas", &self.quotas().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl PrincipalRateLimiter {
+    /// Build a limiter from the per-bucket request counts, all sharing one
+    /// window. Returns `None` when a count or the window is zero.
+    pub fn new(requests: impl Fn(RateLimitBucket) -> u32, period_secs: u64) -> Option<Self> {
+        let period = Duration::from_secs(period_secs);
+        let mut buckets = Vec::with_capacity(RateLimitBucket::ALL.len());
+        for bucket in RateLimitBucket::ALL {
+            let count = requests(bucket);
+            let burst = NonZeroU32::new(count)?;
+            let quota = Quota::with_period(period / count)?.allow_burst(burst);
+            buckets.push((
+                BucketQuota {
+                    bucket,
+                    requests: count,
+                    period_secs,
+                },
+                RateLimiter::keyed(quota).with_middleware::<StateInformationMiddleware>(),
+            ));
+        }
+        Some(Self {
+            buckets,
+            clock: DefaultClock::default(),
+        })
+    }
+
+    pub fn quotas(&self) -> impl Iterator<Item = BucketQuota> + '_ {
+        self.buckets.iter().map(|(quota, _)| *quota)
+    }
+
+    /// Draw one request for `principal` from `bucket`.
+    pub fn check(
+        &self,
+        bucket: RateLimitBucket,
+        principal: &RateLimitPrincipal,
+    ) -> RateLimitDecision {
+        let (quota, limiter) = &self.buckets[bucket.index()];
+        let per_request = Duration::from_secs(quota.period_secs) / quota.requests;
+        match limiter.check_key(principal) {
+            Ok(snapshot) => {
+                let remaining = snapshot.remaining_burst_capacity();
+                RateLimitDecision {
+                    quota: *quota,
+                    remaining,
+                    reset: per_request * quota.requests.saturating_sub(remaining),
+                    retry_after: None,
+                }
+            }
+            Err(not_until) => {
+                let wait = not_until.wait_time_from(self.clock.now());
+                RateLimitDecision {
+                    quota: *quota,
+                    remaining: 0,
+                    reset: wait,
+                    retry_after: Some(wait),
+                }
+            }
+        }
+    }
+
+    /// Forget principals whose buckets have refilled, bounding memory use.
+    pub fn retain_recent(&self) {
+        for (_, limiter) in &self.buckets {
+            limiter.retain_recent();
+            limiter.shrink_to_fit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::route_policy::route_policy_for_path;
+
+    fn bucket(method: Method, path: &str) -> RateLimitBucket {
+        RateLimitBucket::for_request(&method, route_policy_for_path(path))
+    }
+
+    #[test]
+    fn requests_are_bucketed_by_route() {
+        assert_eq!(
+            bucket(Method::GET, "/api/v1/search"),
+            RateLimitBucket::Search
+        );
+        assert_eq!(
+            bucket(Method::POST, "/api/v1/memories/search"),
+            RateLimitBucket::Search
+        );
+        assert_eq!(
+            bucket(Method::POST, "/api/v1/notes"),
+            RateLimitBucket::Ingestion
+        );
+        assert_eq!(
+            bucket(Method::GET, "/api/v1/notes"),
+            RateLimitBucket::General
+        );
+        assert_eq!(
+            bucket(Method::GET, "/api/v1/api-keys"),
+            RateLimitBucket::Admin
+        );
+        assert_eq!(
+            bucket(Method::GET, "/not/a/route"),
+            RateLimitBucket::General
+        );
+    }
+
+    #[test]
+    fn anonymous_requests_are_keyed_by_client_ip() {
+        let ip: IpAddr = "203.0.113.10".parse().unwrap();
+        let key_id = Uuid::new_v4();
+        let api_key = AuthPrincipal::ApiKey {
+            key_id,
+            scope: "read".to_string(),
+        };
+        assert_eq!(
+            RateLimitPrincipal::resolve(Some(&api_key), Some(ip)),
+            RateLimitPrincipal::ApiKey(key_id)
+        );
+        assert_eq!(
+            RateLimitPrincipal::resolve(Some(&AuthPrincipal::Anonymous), Some(ip)),
+            RateLimitPrincipal::Ip(ip)
+        );
+        assert_eq!(
+            RateLimitPrincipal::resolve(None, None),
+            RateLimitPrincipal::Unknown
+        );
+    }
+
+    #[test]
+    fn principals_and_buckets_have_separate_quotas() {
+        let limiter = PrincipalRateLimiter::new(
+            |bucket| {
+                if bucket == RateLimitBucket::Search {
+                    1
+                } else {
+                    2
+                }
+            },
+            60,
+        )
+        .unwrap();
+        let first = RateLimitPrincipal::ApiKey(Uuid::new_v4());
+        let second = RateLimitPrincipal::OAuthClient("client".to_string());
+
+        let decision = limiter.check(RateLimitBucket::Search, &first);
+        assert!(decision.retry_after.is_none());
+        assert_eq!(decision.remaining, 0);
+        assert_eq!(decision.reset, Duration::from_secs(60));
+
+        let denied = limiter.check(RateLimitBucket::Search, &first);
+        assert!(denied.retry_after.is_some());
+        assert!(denied.retry_after.unwrap() <= Duration::from_secs(60));
+
+        assert!(limiter
+            .check(RateLimitBucket::Search, &second)
+            .retry_after
+            .is_none());
+        let general = limiter.check(RateLimitBucket::General, &first);
+        assert!(general.retry_after.is_none());
+        assert_eq!(general.remaining, 1);
+    }
+
+    #[test]
+    fn decision_headers_describe_the_bucket() {
+        let limiter = PrincipalRateLimiter::new(|_| 10, 60).unwrap();
+        let decision = limiter.check(RateLimitBucket::Ingestion, &RateLimitPrincipal::Unknown);
+        let mut headers = HeaderMap::new();
+        decision.apply_headers(&mut headers);
+        assert_eq!(headers["x-ratelimit-limit"], "10");
+        assert_eq!(headers["x-ratelimit-remaining"], "9");
+        assert_eq!(headers["x-ratelimit-reset"], "6");
+        assert_eq!(headers["x-ratelimit-bucket"], "ingestion");
+    }
+
+    #[test]
+    fn zero_quotas_are_rejected() {
+        assert!(PrincipalRateLimiter::new(|_| 0, 60).is_none());
+        assert!(PrincipalRateLimiter::new(|_| 10, 0).is_none());
+    }
+}
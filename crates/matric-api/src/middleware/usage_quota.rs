@@ -0,0 +1,230 @@
+//! Per-principal daily usage counting and quota enforcement.
+//!
+//! Each request is attributed to a usage principal — the API key, the OAuth
+//! client, or `anonymous` — whose daily counters live in
+//! [`PgUsageCounterRepository`]. Requests that would add to a counter whose
+//! quota is already used up are refused with a 429 until the next UTC day.
+//!
+//! Single-unit operations (a search, a note created) are counted by the
+//! middleware from the response status. Operations whose size is only known
+//! to the handler — bulk creation, inference tokens — are counted by the
+//! handler through the [`UsageRecorder`] request extension.
+
+use std::fmt;
+
+use axum::http::Method;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::warn;
+
+use crate::middleware::rate_limit::RateLimitBucket;
+use crate::route_policy::RoutePolicy;
+use matric_core::{estimate_tokens, AuthPrincipal, UsageCounter};
+use matric_db::PgUsageCounterRepository;
+
+/// Routes that create one note per successful request.
+const NOTE_CREATE_PATHS: &[&str] = &["/api/v1/notes"];
+
+/// Routes that create several notes; the handler records how many.
+const BULK_NOTE_CREATE_PATHS: &[&str] = &["/api/v1/notes/bulk"];
+
+/// Routes that run inference; the handler records the tokens used.
+const INFERENCE_PATHS: &[&str] = &[
+    "/api/v1/inference/complete",
+    "/api/v1/inference/stream",
+    "/api/v1/chat",
+    "/api/v1/chat/stream",
+];
+
+/// The principal usage is counted against.
+pub fn usage_principal(principal: Option<&AuthPrincipal>) -> String {
+    match principal {
+        Some(AuthPrincipal::ApiKey { key_id, .. }) => format!("api-key:{key_id}"),
+        Some(AuthPrincipal::OAuthClient { client_id, .. }) => format!("oauth:{client_id}"),
+        Some(AuthPrincipal::Anonymous) | None => "anonymous".to_string(),
+    }
+}
+
+/// The counter a request to the route `policy` adds to, if any.
+pub fn metered_counter(method: &Method, policy: Option<&RoutePolicy>) -> Option<UsageCounter> {
+    let policy = policy?;
+    if RateLimitBucket::for_request(method, Some(policy)) == RateLimitBucket::Search {
+        return Some(UsageCounter::Searches);
+    }
+    if *method != Method::POST {
+        return None;
+    }
+    if NOTE_CREATE_PATHS.contains(&policy.path) || BULK_NOTE_CREATE_PATHS.contains(&policy.path) {
+        return Some(UsageCounter::NotesCreated);
+    }
+    if INFERENCE_PATHS.contains(&policy.path) {
+        return Some(UsageCounter::InferenceTokens);
+    }
+    None
+}
+
+/// Whether the middleware counts a request itself once it succeeds, rather
+/// than leaving it to the handler.
+pub fn counted_by_middleware(counter: UsageCounter, policy: &RoutePolicy) -> bool {
+    match counter {
+        UsageCounter::Searches => true,
+        UsageCounter::NotesCreated => NOTE_CREATE_PATHS.contains(&policy.path),
+        UsageCounter::InferenceTokens => false,
+    }
+}
+
+/// Estimated tokens consumed by an inference call, from its prompt and
+/// completion text.
+pub fn inference_tokens(texts: &[&str]) -> i64 {
+    texts.iter().map(|text| estimate_tokens(text) as i64).sum()
+}
+
+/// Records usage for the request's principal. Inserted into request
+/// extensions by the usage quota middleware.
+#[derive(Clone)]
+pub struct UsageRecorder {
+    principal: String,
+    counters: PgUsageCounterRepository,
+}
+
+impl UsageRecorder {
+    pub fn new(principal: String, counters: PgUsageCounterRepository) -> Self {
+        Self {
+            principal,
+            counters,
+        }
+    }
+
+    pub fn principal(&self) -> &str {
+        &self.principal
+    }
+
+    /// Add `amount` to today's `counter`. Best effort: a failure is logged
+    /// and does not fail the request that incurred the usage.
+    pub async fn record(&self, counter: UsageCounter, amount: i64) {
+        if let Err(error) = self
+            .counters
+            .increment(&self.principal, counter, amount)
+            .await
+        {
+            warn!(
+                counter = counter.as_str(),
+                error_len = error.to_string().chars().count(),
+                "Best-effort usage counter update failed"
+            );
+        }
+    }
+}
+
+impl fmt::Debug for UsageRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UsageRecorder")
+            .field("principal_len", &self.principal.chars().count())
+            .finish()
+    }
+}
+
+/// The quota a refused request ran into, reported in the 429 body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+pub struct UsageQuotaExceeded {
+    pub counter: UsageCounter,
+    /// The per-day quota
+    pub limit: i64,
+    /// Usage so far today
+    pub used: i64,
+    /// When the counter resets (the next UTC midnight)
+    pub reset_at: DateTime<Utc>,
+}
+
+impl UsageQuotaExceeded {
+    pub fn detail(&self) -> String {
+        format!(
+            "Daily {} quota of {} reached; it resets at {}.",
+            self.counter.as_str().replace('_', " "),
+            self.limit,
+            self.reset_at.to_rfc3339(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::route_policy::route_policy_for_path;
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    fn counter(method: Method, path: &str) -> Option<UsageCounter> {
+        metered_counter(&method, route_policy_for_path(path))
+    }
+
+    #[test]
+    fn requests_are_metered_by_route() {
+        assert_eq!(
+            counter(Method::GET, "/api/v1/search"),
+            Some(UsageCounter::Searches)
+        );
+        assert_eq!(
+            counter(Method::POST, "/api/v1/notes"),
+            Some(UsageCounter::NotesCreated)
+        );
+        assert_eq!(
+            counter(Method::POST, "/api/v1/notes/bulk"),
+            Some(UsageCounter::NotesCreated)
+        );
+        assert_eq!(
+            counter(Method::POST, "/api/v1/chat/stream"),
+            Some(UsageCounter::InferenceTokens)
+        );
+        assert_eq!(counter(Method::GET, "/api/v1/notes"), None);
+        assert_eq!(counter(Method::GET, "/api/v1/usage"), None);
+    }
+
+    #[test]
+    fn middleware_counts_only_single_unit_routes() {
+        let policy = |path| route_policy_for_path(path).expect("route is inventoried");
+        assert!(counted_by_middleware(
+            UsageCounter::NotesCreated,
+            policy("/api/v1/notes")
+        ));
+        assert!(!counted_by_middleware(
+            UsageCounter::NotesCreated,
+            policy("/api/v1/notes/bulk")
+        ));
+        assert!(!counted_by_middleware(
+            UsageCounter::InferenceTokens,
+            policy("/api/v1/chat")
+        ));
+    }
+
+    #[test]
+    fn principals_are_keyed_by_credential() {
+        let key_id = Uuid::nil();
+        assert_eq!(
+            usage_principal(Some(&AuthPrincipal::ApiKey {
+                key_id,
+                scope: "read".to_string(),
+            })),
+            format!("api-key:{key_id}")
+        );
+        assert_eq!(
+            usage_principal(Some(&AuthPrincipal::Anonymous)),
+            "anonymous"
+        );
+        assert_eq!(usage_principal(None), "anonymous");
+    }
+
+    #[test]
+    fn quota_detail_names_counter_and_reset() {
+        let exceeded = UsageQuotaExceeded {
+            counter: UsageCounter::InferenceTokens,
+            limit: 1000,
+            used: 1200,
+            reset_at: Utc.with_ymd_and_hms(2026, 10, 17, 0, 0, 0).unwrap(),
+        };
+        assert_eq!(
+            exceeded.detail(),
+            "Daily inference tokens quota of 1000 reached; it resets at 2026-10-17T00:00:00+00:00."
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! OpenTelemetry trace export (optional `otel` feature).
+//!
+//! Spans from the `tracing` subscriber — HTTP requests, repository queries,
+//! search stages, job execution and inference calls — are exported over
+//! OTLP/HTTP. The exporter reads the standard `OTEL_*` environment
+//! variables; export is on only when `OTEL_EXPORTER_OTLP_ENDPOINT` or
+//! `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` is set and `OTEL_SDK_DISABLED` is not
+//! `true`.
+//!
+//! Incoming W3C `traceparent` headers make the HTTP span a child of the
+//! caller's trace. Every HTTP span also carries the UUIDv7 `request_id`, so a
+//! request seen in logs or a problem+json body can be looked up in the trace
+//! backend by tag.
+
+use axum::http::HeaderMap;
+use opentelemetry::propagation::Extractor;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::registry::LookupSpan;
+
+const DEFAULT_SERVICE_NAME: &str = "matric-api";
+
+/// Flushes buffered spans when dropped at the end of `main`.
+pub struct OtelGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        if let Err(error) = self.provider.shutdown() {
+            eprintln!(
+                "OpenTelemetry span flush failed (error_len={})",
+                error.to_string().chars().count()
+            );
+        }
+    }
+}
+
+/// Whether the environment asks for trace export.
+fn export_enabled<F>(env: F) -> bool
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let disabled = env("OTEL_SDK_DISABLED").is_some_and(|value| value.eq_ignore_ascii_case("true"));
+    let endpoint = [
+        "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+        "OTEL_EXPORTER_OTLP_ENDPOINT",
+    ]
+    .into_iter()
+    .any(|name| env(name).is_some_and(|value| !value.trim().is_empty()));
+    endpoint && !disabled
+}
+
+/// The tracing layer that exports spans, or `None` when export is not
+/// configured.
+pub fn trace_layer<S>(
+) -> anyhow::Result<(Option<OpenTelemetryLayer<S, SdkTracer>>, Option<OtelGuard>)>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    if !export_enabled(|name| std::env::var(name).ok()) {
+        return Ok((None, None));
+    }
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .build()
+        .map_err(|error| anyhow::anyhow!("OTLP span exporter setup failed: {error}"))?;
+    let service_name =
+        std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| DEFAULT_SERVICE_NAME.to_string());
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name).build())
+        .build();
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let tracer = provider.tracer(DEFAULT_SERVICE_NAME);
+    Ok((
+        Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+        Some(OtelGuard { provider }),
+    ))
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
+
+/// Continue the caller's trace when the request carries `traceparent`.
+pub fn set_remote_parent(span: &tracing::Span, headers: &HeaderMap) {
+    if !headers.contains_key("traceparent") {
+        return;
+    }
+    let parent = opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(headers))
+    });
+    span.set_parent(parent);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |name| {
+            pairs
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn export_requires_an_endpoint_and_respects_sdk_disabled() {
+        assert!(!export_enabled(env(&[])));
+        assert!(export_enabled(env(&[(
+            "OTEL_EXPORTER_OTLP_ENDPOINT",
+            "http://jaeger:4318"
+        )])));
+        assert!(export_enabled(env(&[(
+            "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+            "http://jaeger:4318/v1/traces"
+        )])));
+        assert!(!export_enabled(env(&[
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://jaeger:4318"),
+            ("OTEL_SDK_DISABLED", "true"),
+        ])));
+        assert!(!export_enabled(env(&[(
+            "OTEL_EXPORTER_OTLP_ENDPOINT",
+            " "
+        )])));
+    }
+}
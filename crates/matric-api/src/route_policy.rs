@@ -95,6 +95,7 @@ use PolicyClass::{
 pub const OPERATOR_DOCS_PATH: &str = "/api/v1/operator/docs";
 pub const OPERATOR_OPENAPI_PATH: &str = "/api/v1/operator/openapi.yaml";
 pub const OPERATOR_ASYNCAPI_PATH: &str = "/api/v1/operator/asyncapi.yaml";
+pub const CALENDAR_FEED_PATH: &str = "/api/v1/calendar.ics";
 
 pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
     r(
@@ -111,6 +112,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         DocsPublic,
         PublicProbe,
     ),
+    r(
+        "/api/v1/activity-feed",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/api-keys",
         AdminOperator,
@@ -146,6 +154,27 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/archives/{name}/mempack",
+        AdminOperator,
+        "backup_restore",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/archives/{name}/merge-into/{target}",
+        AdminOperator,
+        "memory_management",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/archives/{name}/quota",
+        AdminOperator,
+        "memory_management",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/archives/{name}/set-default",
         TenantObject,
@@ -153,6 +182,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/archives/{name}/shares",
+        TenantObject,
+        "memory_management",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/archives/{name}/shares/{grantee_id}",
+        TenantObject,
+        "memory_management",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/archives/{name}/stats",
         AuthenticatedRead,
@@ -160,6 +203,27 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/archives/{name}/translation",
+        TenantObject,
+        "memory_management",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/archives/{name}/usage",
+        AuthenticatedRead,
+        "memory_management",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/archives/{name}/version-policy",
+        TenantObject,
+        "memory_management",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/attachments",
         TenantObject,
@@ -258,6 +322,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/backup/full",
+        AdminOperator,
+        "backup_restore",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/backup/full/restore",
+        AdminOperator,
+        "backup_restore",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/backup/import",
         AdminOperator,
@@ -265,6 +343,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/backup/incremental",
+        AdminOperator,
+        "backup_restore",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/backup/incremental/restore",
+        AdminOperator,
+        "backup_restore",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/backup/knowledge-archive",
         AdminOperator,
@@ -328,6 +420,34 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/backup/policies",
+        AdminOperator,
+        "backup_restore",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/backup/policies/{id}",
+        AdminOperator,
+        "backup_restore",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/backup/policies/{id}/run",
+        AdminOperator,
+        "backup_restore",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/backup/policies/{id}/runs",
+        AdminOperator,
+        "backup_restore",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/backup/status",
         AdminOperator,
@@ -349,6 +469,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/backup/{filename}/restore-note",
+        AdminOperator,
+        "backup_restore",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/calendar.ics",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/calls/{id}",
         TenantObject,
@@ -384,6 +518,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/collections/reparent",
+        TenantObject,
+        "collection",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/collections/{id}",
         TenantObject,
@@ -391,6 +532,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/collections/{id}/bibtex",
+        TenantObject,
+        "collection",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/collections/{id}/copy",
+        TenantObject,
+        "collection",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/collections/{id}/export",
         TenantObject,
@@ -398,6 +553,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         NoStore,
     ),
+    r(
+        "/api/v1/collections/{id}/export-site",
+        TenantObject,
+        "collection",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/collections/{id}/move",
+        TenantObject,
+        "collection",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/collections/{id}/notes",
         TenantObject,
@@ -405,6 +574,41 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/collections/{id}/publication",
+        TenantObject,
+        "collection",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/collections/{id}/rules",
+        TenantObject,
+        "collection",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/collections/{id}/rules/refresh",
+        TenantObject,
+        "collection",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/collections/{id}/shares",
+        TenantObject,
+        "collection",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/collections/{id}/shares/{grantee_id}",
+        TenantObject,
+        "collection",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/concepts",
         TenantObject,
@@ -454,6 +658,27 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/concepts/health",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/concepts/health/refresh",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/concepts/merge",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/concepts/schemes",
         TenantObject,
@@ -489,6 +714,27 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/concepts/suggestions",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/concepts/suggestions/{id}/accept",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/concepts/suggestions/{id}/reject",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/concepts/{id}",
         TenantObject,
@@ -503,6 +749,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/concepts/{id}/audit",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/concepts/{id}/broader",
         TenantObject,
@@ -531,6 +784,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/concepts/{id}/mappings",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/concepts/{id}/mappings/{mapping_id}",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/concepts/{id}/narrower",
         TenantObject,
@@ -559,6 +826,41 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/concepts/{id}/split",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/concepts/{id}/split/suggest",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/digests",
+        TenantObject,
+        "digests",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/digests/{id}",
+        TenantObject,
+        "digests",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/digests/{id}/run",
+        TenantObject,
+        "digests",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/document-types",
         AdminOperator,
@@ -636,6 +938,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         NoStore,
     ),
+    r(
+        "/api/v1/entities",
+        TenantObject,
+        "entities",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/entities/{id}",
+        TenantObject,
+        "entities",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/events",
         RealtimeTransport,
@@ -643,6 +959,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Hidden,
         NoStore,
     ),
+    r(
+        "/api/v1/export",
+        AdminOperator,
+        "backup_restore",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/export/jsonld",
+        TenantObject,
+        "note",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/extraction/stats",
         AdminOperator,
@@ -650,6 +980,48 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/fine-tuning/datasets",
+        TenantObject,
+        "fine_tuning",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/fine-tuning/datasets/{id}",
+        TenantObject,
+        "fine_tuning",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/fine-tuning/datasets/{id}/export",
+        TenantObject,
+        "fine_tuning",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/fine-tuning/datasets/{id}/generate",
+        TenantObject,
+        "fine_tuning",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/fine-tuning/datasets/{id}/samples",
+        TenantObject,
+        "fine_tuning",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/graph/analytics",
+        TenantObject,
+        "graph_control",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/graph/cold-spots",
         TenantObject,
@@ -692,6 +1064,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/graph/diff",
+        TenantObject,
+        "graph_control",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/graph/export",
+        TenantObject,
+        "graph_control",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/graph/maintenance",
         AdminOperator,
@@ -699,6 +1085,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/graph/path",
+        TenantObject,
+        "graph_control",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/graph/pfnet/sparsify",
         AdminOperator,
@@ -748,6 +1141,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/health/pii",
+        AuthenticatedRead,
+        "health_diagnostics",
+        Operator,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/health/stale-notes",
         SystemHealth,
@@ -839,6 +1239,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/inference/usage",
+        AdminOperator,
+        "model_config",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/ingest/stream",
         RealtimeTransport,
@@ -867,6 +1274,27 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/jobs/failed",
+        AdminOperator,
+        "job_control",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/jobs/failed/discard",
+        AdminOperator,
+        "job_control",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/jobs/failed/retry",
+        AdminOperator,
+        "job_control",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/jobs/pause",
         AdminOperator,
@@ -923,6 +1351,41 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/journal/settings",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/journal/today",
+        TenantObject,
+        "note",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/link-types",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/link-types/{name}",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/links/{id}",
+        TenantObject,
+        "note",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/memories",
         AdminOperator,
@@ -958,6 +1421,27 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/memories/{name}/mempack",
+        AdminOperator,
+        "backup_restore",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/memories/{name}/merge-into/{target}",
+        AdminOperator,
+        "memory_management",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/memories/{name}/quota",
+        AdminOperator,
+        "memory_management",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/memories/{name}/set-default",
         TenantObject,
@@ -965,6 +1449,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/memories/{name}/shares",
+        TenantObject,
+        "memory_management",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/memories/{name}/shares/{grantee_id}",
+        TenantObject,
+        "memory_management",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/memories/{name}/stats",
         AuthenticatedRead,
@@ -972,6 +1470,27 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/memories/{name}/translation",
+        TenantObject,
+        "memory_management",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/memories/{name}/usage",
+        AuthenticatedRead,
+        "memory_management",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/memories/{name}/version-policy",
+        TenantObject,
+        "memory_management",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/memory/info",
         AuthenticatedRead,
@@ -1007,6 +1526,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         NoStore,
     ),
+    r(
+        "/api/v1/notes/pinned",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/notes/recently-viewed",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/notes/reprocess",
         TenantObject,
@@ -1014,6 +1547,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         NoStore,
     ),
+    r(
+        "/api/v1/notes/resurface",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/notes/timeline",
         TenantObject,
@@ -1063,6 +1603,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/notes/{id}/citations",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/notes/{id}/concepts",
         TenantObject,
@@ -1077,6 +1624,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/notes/{id}/decrypt",
+        TenantObject,
+        "note",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/notes/{id}/export",
         TenantObject,
@@ -1091,6 +1645,27 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/notes/{id}/link-suggestions",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/notes/{id}/link-suggestions/{target_id}/accept",
+        TenantObject,
+        "note",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/notes/{id}/link-suggestions/{target_id}/reject",
+        TenantObject,
+        "note",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/notes/{id}/links",
         TenantObject,
@@ -1147,6 +1722,27 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         NoStore,
     ),
+    r(
+        "/api/v1/notes/{id}/shares",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/notes/{id}/shares/{grantee_id}",
+        TenantObject,
+        "note",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/notes/{id}/similar",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/notes/{id}/status",
         TenantObject,
@@ -1180,7 +1776,14 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         TenantObject,
         "note",
         Authenticated,
-        PrivateUserData,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/notes/{id}/versions/{version}/milestone",
+        TenantObject,
+        "note",
+        Authenticated,
+        NoStore,
     ),
     r(
         "/api/v1/notes/{id}/versions/{version}/restore",
@@ -1189,6 +1792,27 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         NoStore,
     ),
+    r(
+        "/api/v1/pipelines/runs",
+        AdminOperator,
+        "job_control",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/pipelines/runs/{id}",
+        AdminOperator,
+        "job_control",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/pipelines/runs/{id}/rerun",
+        AdminOperator,
+        "job_control",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/pke/address",
         AuthenticatedWrite,
@@ -1252,6 +1876,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/pke/keysets/{name_or_id}/combine",
+        AdminOperator,
+        "pke_keyset",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/pke/keysets/{name_or_id}/export",
         AdminOperator,
@@ -1259,6 +1890,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/pke/keysets/{name_or_id}/rotate",
+        AdminOperator,
+        "pke_keyset",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/pke/keysets/{name_or_id}/split",
+        AdminOperator,
+        "pke_keyset",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/pke/recipients",
         AuthenticatedWrite,
@@ -1273,6 +1918,27 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/prompts",
+        AdminOperator,
+        "model_config",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/prompts/{key}",
+        AdminOperator,
+        "model_config",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/prompts/{key}/versions",
+        AdminOperator,
+        "model_config",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/provenance/devices",
         TenantObject,
@@ -1308,6 +1974,20 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         NoStore,
     ),
+    r(
+        "/api/v1/publications",
+        TenantObject,
+        "collection",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/quarantine",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/rate-limit/status",
         AuthenticatedRead,
@@ -1315,6 +1995,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/usage",
+        AuthenticatedRead,
+        "usage_metering",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/realtime/twilio/{provider_call_id}",
         PublicWithInlineProof,
@@ -1322,6 +2009,34 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Hidden,
         NoStore,
     ),
+    r(
+        "/api/v1/review/cards",
+        TenantObject,
+        "review",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/review/cards/{id}",
+        TenantObject,
+        "review",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/review/queue",
+        TenantObject,
+        "review",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/review/{card_id}",
+        TenantObject,
+        "review",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/search",
         TenantObject,
@@ -1336,6 +2051,41 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/search/images",
+        TenantObject,
+        "search",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/sync/changes",
+        AdminOperator,
+        "federation_sync",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/sync/peers",
+        AdminOperator,
+        "federation_sync",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/sync/peers/{id}",
+        AdminOperator,
+        "federation_sync",
+        Operator,
+        NoStore,
+    ),
+    r(
+        "/api/v1/sync/peers/{id}/run",
+        AdminOperator,
+        "federation_sync",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/tags",
         AuthenticatedRead,
@@ -1343,6 +2093,41 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         PrivateUserData,
     ),
+    r(
+        "/api/v1/tags/bulk",
+        TenantObject,
+        "taxonomy",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/tasks",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/tasks/{id}",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/tasks/{id}/complete",
+        TenantObject,
+        "note",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/tasks/{id}/uncomplete",
+        TenantObject,
+        "note",
+        Authenticated,
+        NoStore,
+    ),
     r(
         "/api/v1/templates",
         TenantObject,
@@ -1364,6 +2149,69 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Authenticated,
         NoStore,
     ),
+    r(
+        "/api/v1/templates/{id}/versions",
+        TenantObject,
+        "template",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/templates/{id}/versions/{version}",
+        TenantObject,
+        "template",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/topics",
+        TenantObject,
+        "topics",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/topics/refresh",
+        TenantObject,
+        "topics",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/topics/{id}",
+        TenantObject,
+        "topics",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/trash",
+        TenantObject,
+        "note",
+        Authenticated,
+        PrivateUserData,
+    ),
+    r(
+        "/api/v1/trash/purge",
+        TenantObject,
+        "note",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/trash/restore",
+        TenantObject,
+        "note",
+        Authenticated,
+        NoStore,
+    ),
+    r(
+        "/api/v1/users/me",
+        TenantObject,
+        "user_profile",
+        Authenticated,
+        PrivateUserData,
+    ),
     r(
         "/api/v1/vision/describe",
         AuthenticatedWrite,
@@ -1420,6 +2268,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/api/v1/workers",
+        AdminOperator,
+        "job_control",
+        Operator,
+        NoStore,
+    ),
     r(
         "/api/v1/ws",
         RealtimeTransport,
@@ -1448,6 +2303,29 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
         Operator,
         NoStore,
     ),
+    r(
+        "/fortemi.ingest.v1.IngestService/BulkCreateNotes",
+        TenantObject,
+        "note",
+        Hidden,
+        NoStore,
+    ),
+    r(
+        "/fortemi.ingest.v1.IngestService/CreateNote",
+        TenantObject,
+        "note",
+        Hidden,
+        NoStore,
+    ),
+    r(
+        "/fortemi.ingest.v1.IngestService/Search",
+        AuthenticatedRead,
+        "search",
+        Hidden,
+        NoStore,
+    ),
+    r("/graphql", AuthenticatedRead, "graphql", Hidden, NoStore),
+    r("/graphql/ws", AuthenticatedRead, "graphql", Hidden, NoStore),
     r("/health", Public, "health_probe", DocsPublic, PublicProbe),
     r(
         "/health/live",
@@ -1458,6 +2336,13 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
     ),
     r("/livez", Public, "health_probe", DocsPublic, PublicProbe),
     r("/readyz", Public, "health_probe", DocsPublic, PublicProbe),
+    r(
+        "/metrics",
+        AdminOperator,
+        "system_diagnostics",
+        Operator,
+        NoStore,
+    ),
     r("/oauth/authorize", OAuth, "oauth_flow", DocsPublic, NoStore),
     r(
         "/oauth/introspect",
@@ -1469,6 +2354,27 @@ pub const ROUTE_POLICY_INVENTORY: &[RoutePolicy] = &[
     r("/oauth/register", OAuth, "oauth_flow", DocsPublic, NoStore),
     r("/oauth/revoke", OAuth, "oauth_flow", DocsPublic, NoStore),
     r("/oauth/token", OAuth, "oauth_flow", DocsPublic, NoStore),
+    r(
+        "/public/{slug}",
+        Public,
+        "published_site",
+        DocsPublic,
+        PublicProbe,
+    ),
+    r(
+        "/public/{slug}/notes/{note_id}",
+        Public,
+        "published_site",
+        DocsPublic,
+        PublicProbe,
+    ),
+    r(
+        "/public/{slug}/search",
+        Public,
+        "published_site",
+        DocsPublic,
+        PublicProbe,
+    ),
     r("/recording.wav", Public, "test_fixture", Hidden, NoStore),
     r(
         "/api/v1/problem-contract-test",
@@ -1535,6 +2441,21 @@ pub fn is_public_without_bearer(path: &str) -> bool {
     })
 }
 
+/// Routes subscribed to by URL, whose clients cannot send headers: the
+/// bearer token may come from `?token=` and the memory from `?memory=`.
+pub fn accepts_query_credentials(path: &str) -> bool {
+    path == CALENDAR_FEED_PATH
+}
+
+/// The first non-empty value of query parameter `name`, decoded.
+pub fn query_credential(query: Option<&str>, name: &str) -> Option<String> {
+    let pairs: Vec<(String, String)> = serde_urlencoded::from_str(query?).ok()?;
+    pairs
+        .into_iter()
+        .find(|(key, value)| key == name && !value.trim().is_empty())
+        .map(|(_, value)| value.trim().to_string())
+}
+
 pub fn authorization_input_for_request(
     method: &Method,
     path: &str,
@@ -1704,9 +2625,8 @@ fn resource_kind_for_action_family(action_family: &str) -> ResourceKind {
         "event_stream" | "ingest_stream" | "realtime_call" | "realtime_provider_callback" => {
             ResourceKind::McpTool
         }
-        "oauth_discovery" | "oauth_flow" | "docs_schema" | "health_probe" | "test_fixture" => {
-            ResourceKind::PublicRoute
-        }
+        "oauth_discovery" | "oauth_flow" | "docs_schema" | "health_probe" | "published_site"
+        | "test_fixture" => ResourceKind::PublicRoute,
         other => ResourceKind::Other(other.to_string()),
     }
 }
@@ -1848,6 +2768,25 @@ mod tests {
         assert!(is_public_without_bearer("/api/v1/realtime/twilio/CA123"));
         assert!(is_public_without_bearer("/api/v1/health/streaming"));
         assert!(is_public_without_bearer("/api/v1/system/compatibility"));
+        assert!(!is_public_without_bearer("/metrics"));
+    }
+
+    #[test]
+    fn only_subscription_feeds_accept_query_credentials() {
+        assert!(accepts_query_credentials(CALENDAR_FEED_PATH));
+        assert!(!is_public_without_bearer(CALENDAR_FEED_PATH));
+        assert!(!accepts_query_credentials("/api/v1/notes"));
+        assert!(!accepts_query_credentials("/api/v1/calendar.ics/extra"));
+        assert_eq!(
+            query_credential(Some("memory=work%20notes&token=mm_key_abc"), "token").as_deref(),
+            Some("mm_key_abc")
+        );
+        assert_eq!(
+            query_credential(Some("memory=work%20notes"), "memory").as_deref(),
+            Some("work notes")
+        );
+        assert_eq!(query_credential(Some("token="), "token"), None);
+        assert_eq!(query_credential(None, "token"), None);
     }
 
     #[test]
@@ -0,0 +1,147 @@
+//! Redis cache for deterministic inference results.
+//!
+//! Title generation and concept tagging produce the same answer for the same
+//! model and prompt, so re-running them (reprocessing, retries, tier
+//! escalation of an unchanged note) can reuse the earlier result instead of
+//! calling the model again. Entries are keyed by note, task, model, and a hash
+//! of the rendered prompt, and live in the same Redis as the search cache.
+//!
+//! A changed prompt never hits a stale entry because the prompt is part of the
+//! key. Editing, restoring, or purging a note also drops its entries so they
+//! do not linger until the TTL expires.
+//!
+//! ## Configuration
+//!
+//! - `REDIS_ENABLED`, `REDIS_URL` — shared with the search cache
+//! - `MATRIC_INFERENCE_CACHE_TTL` (default: 604800 seconds) — `0` disables
+
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::info;
+use uuid::Uuid;
+
+use matric_core::defaults::{ENV_INFERENCE_CACHE_TTL, INFERENCE_CACHE_TTL_SECS};
+
+use super::SearchCache;
+
+/// Key prefix for inference result entries.
+const INFERENCE_CACHE_PREFIX: &str = "mm:inference:";
+
+/// Cached inference task. Part of the key, so tasks never share entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InferenceTask {
+    TitleGeneration,
+    ConceptTagging,
+}
+
+/// Versioned inputs that determine the identity of an inference cache entry.
+#[derive(Serialize)]
+struct InferenceCacheKeyInput<'a> {
+    version: u8,
+    task: InferenceTask,
+    model: &'a str,
+    prompt: &'a str,
+}
+
+/// Inference result cache backed by Redis.
+#[derive(Clone)]
+pub struct InferenceCache {
+    cache: SearchCache,
+}
+
+impl InferenceCache {
+    /// Create an inference cache from environment configuration.
+    pub async fn from_env() -> Self {
+        let ttl_seconds: u64 = std::env::var(ENV_INFERENCE_CACHE_TTL)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(INFERENCE_CACHE_TTL_SECS);
+        if ttl_seconds == 0 {
+            info!("Inference cache disabled via {ENV_INFERENCE_CACHE_TTL}=0");
+            return Self::disabled();
+        }
+
+        Self {
+            cache: SearchCache::from_env_with_prefix(INFERENCE_CACHE_PREFIX, ttl_seconds).await,
+        }
+    }
+
+    /// Create a disabled cache (for testing or when Redis unavailable).
+    pub fn disabled() -> Self {
+        Self {
+            cache: SearchCache::disabled(),
+        }
+    }
+
+    /// Generate the cache key for a task's result on a note.
+    ///
+    /// The note id stays readable so [`Self::invalidate_note`] can find the
+    /// note's entries; model and prompt are hashed.
+    pub fn cache_key(
+        &self,
+        note_id: Uuid,
+        task: InferenceTask,
+        model: &str,
+        prompt: &str,
+    ) -> String {
+        let input = InferenceCacheKeyInput {
+            version: 1,
+            task,
+            model,
+            prompt,
+        };
+        let payload =
+            serde_json::to_vec(&input).expect("inference cache key inputs are always serializable");
+        let hash = hex::encode(Sha256::digest(payload));
+        format!("{INFERENCE_CACHE_PREFIX}{note_id}:{hash}")
+    }
+
+    /// Get a cached result.
+    pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.cache.get(key).await
+    }
+
+    /// Store a result.
+    pub async fn set<T: Serialize>(&self, key: &str, value: &T) -> bool {
+        self.cache.set(key, value).await
+    }
+
+    /// Drop every cached result for a note.
+    pub async fn invalidate_note(&self, note_id: Uuid) -> bool {
+        self.cache.invalidate_prefixed(&format!("{note_id}:")).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_key_separates_note_task_model_and_prompt() {
+        let cache = InferenceCache::disabled();
+        let note = Uuid::nil();
+        let key = cache.cache_key(note, InferenceTask::TitleGeneration, "qwen3:8b", "prompt");
+
+        assert_eq!(
+            key,
+            cache.cache_key(note, InferenceTask::TitleGeneration, "qwen3:8b", "prompt")
+        );
+        assert!(key.starts_with(&format!("{INFERENCE_CACHE_PREFIX}{note}:")));
+        assert!(!key.contains("qwen3") && !key.contains("prompt"));
+
+        for other in [
+            cache.cache_key(
+                Uuid::max(),
+                InferenceTask::TitleGeneration,
+                "qwen3:8b",
+                "prompt",
+            ),
+            cache.cache_key(note, InferenceTask::ConceptTagging, "qwen3:8b", "prompt"),
+            cache.cache_key(note, InferenceTask::TitleGeneration, "llama3", "prompt"),
+            cache.cache_key(note, InferenceTask::TitleGeneration, "qwen3:8b", "prompt 2"),
+        ] {
+            assert_ne!(key, other);
+        }
+    }
+}
@@ -3,8 +3,10 @@
 pub mod chat_stream_store;
 pub mod chunking_service;
 pub mod idempotency_store;
+pub mod inference_cache;
 pub mod ingest_cursor_store;
 pub mod ingest_token_store;
+pub mod note_view_recorder;
 pub mod reconstruction_service;
 pub mod search_cache;
 pub mod tag_resolver;
@@ -12,8 +14,10 @@ pub mod tag_resolver;
 pub use chat_stream_store::ChatStreamStore;
 pub use chunking_service::ChunkingService;
 pub use idempotency_store::{IdempotencyRecord, IdempotencyStore};
+pub use inference_cache::{InferenceCache, InferenceTask};
 pub use ingest_cursor_store::IngestCursorStore;
 pub use ingest_token_store::IngestTokenStore;
+pub use note_view_recorder::NoteViewRecorder;
 pub use reconstruction_service::ReconstructionService;
 pub use search_cache::{SearchCache, SearchCacheKeyInput};
 pub use tag_resolver::TagResolver;
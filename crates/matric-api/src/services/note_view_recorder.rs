@@ -0,0 +1,142 @@
+//! In-process buffer for per-principal note views.
+//!
+//! `GET /api/v1/notes/{id}` calls [`NoteViewRecorder::record`], which only
+//! touches an in-memory map: repeated views of a note by one principal are
+//! folded into a single entry. A background task started at boot
+//! ([`NoteViewRecorder::run`]) writes the buffer every
+//! [`NOTE_VIEW_FLUSH_SECS`] seconds, or sooner once
+//! [`NOTE_VIEW_FLUSH_THRESHOLD`] entries are waiting. Views still buffered
+//! when the process exits are lost; they are a ranking hint, not an audit log.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use tokio::sync::Notify;
+use tracing::warn;
+use uuid::Uuid;
+
+use matric_core::note_view::{NoteView, NOTE_VIEW_FLUSH_SECS, NOTE_VIEW_FLUSH_THRESHOLD};
+use matric_db::Database;
+
+/// Buffered views keyed by archive schema, principal and note.
+type Pending = HashMap<(String, String, Uuid), (DateTime<Utc>, i32)>;
+
+/// Batches note views in memory and writes them per archive schema.
+#[derive(Clone, Default)]
+pub struct NoteViewRecorder {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    pending: Mutex<Pending>,
+    flush_now: Notify,
+}
+
+impl NoteViewRecorder {
+    /// Buffer one view of `note_id` by `principal` in archive `schema`.
+    pub fn record(&self, schema: &str, principal: &str, note_id: Uuid) {
+        let now = Utc::now();
+        let len = {
+            let mut pending = self
+                .inner
+                .pending
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let entry = pending
+                .entry((schema.to_string(), principal.to_string(), note_id))
+                .or_insert((now, 0));
+            entry.0 = entry.0.max(now);
+            entry.1 = entry.1.saturating_add(1);
+            pending.len()
+        };
+        if len >= NOTE_VIEW_FLUSH_THRESHOLD {
+            self.inner.flush_now.notify_one();
+        }
+    }
+
+    /// Write every buffered view. Batches that fail are logged and dropped.
+    pub async fn flush(&self, db: &Database) {
+        let drained = std::mem::take(
+            &mut *self
+                .inner
+                .pending
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+        if drained.is_empty() {
+            return;
+        }
+        let mut by_schema: HashMap<String, Vec<NoteView>> = HashMap::new();
+        for ((schema, principal, note_id), (viewed_at, views)) in drained {
+            by_schema.entry(schema).or_default().push(NoteView {
+                principal,
+                note_id,
+                viewed_at,
+                views,
+            });
+        }
+        for (schema, views) in by_schema {
+            let count = views.len();
+            let repo = db.note_views.clone();
+            let result = match db.for_schema(&schema) {
+                Ok(ctx) => {
+                    ctx.execute(move |tx| Box::pin(async move { repo.record_tx(tx, &views).await }))
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!(
+                    views = count,
+                    error_len = e.to_string().len(),
+                    "Note views could not be written"
+                );
+            }
+        }
+    }
+
+    /// Flush on an interval, and early when the buffer fills up. Never
+    /// returns; spawn it once at startup.
+    pub async fn run(self, db: Database) {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(NOTE_VIEW_FLUSH_SECS));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = self.inner.flush_now.notified() => {}
+            }
+            self.flush(&db).await;
+        }
+    }
+
+    /// Number of buffered principal/note entries.
+    pub fn pending(&self) -> usize {
+        self.inner
+            .pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_views_fold_into_one_entry() {
+        let recorder = NoteViewRecorder::default();
+        let note_id = Uuid::new_v4();
+        recorder.record("public", "user:a", note_id);
+        recorder.record("public", "user:a", note_id);
+        recorder.record("public", "user:b", note_id);
+        recorder.record("archive_2026", "user:a", note_id);
+        assert_eq!(recorder.pending(), 3);
+
+        let pending = recorder.inner.pending.lock().unwrap();
+        let (_, views) = pending[&("public".to_string(), "user:a".to_string(), note_id)];
+        assert_eq!(views, 2);
+    }
+}
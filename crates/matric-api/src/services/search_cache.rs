@@ -19,6 +19,9 @@ use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+/// Key prefix for search result entries.
+const SEARCH_CACHE_PREFIX: &str = "mm:search:";
+
 /// Search cache backed by Redis.
 #[derive(Clone)]
 pub struct SearchCache {
@@ -84,6 +87,18 @@ impl SearchCache {
     /// - `REDIS_URL` (default: redis://localhost:6379)
     /// - `REDIS_CACHE_TTL` (default: 300 seconds)
     pub async fn from_env() -> Self {
+        let ttl_seconds: u64 = std::env::var("REDIS_CACHE_TTL")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(300);
+
+        Self::from_env_with_prefix(SEARCH_CACHE_PREFIX, ttl_seconds).await
+    }
+
+    /// Create a cache for another kind of entry on the shared Redis
+    /// configuration (`REDIS_ENABLED`, `REDIS_URL`), with its own key prefix
+    /// and TTL.
+    pub async fn from_env_with_prefix(prefix: &str, ttl_seconds: u64) -> Self {
         let enabled = std::env::var("REDIS_ENABLED")
             .map(|v| v != "false" && v != "0")
             .unwrap_or(true);
@@ -91,11 +106,6 @@ impl SearchCache {
         let redis_url =
             std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
 
-        let ttl_seconds: u64 = std::env::var("REDIS_CACHE_TTL")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(300);
-
         let connection = if enabled {
             match redis::Client::open(redis_url.as_str()) {
                 Ok(client) => {
@@ -110,6 +120,7 @@ impl SearchCache {
                         Ok(Ok(conn)) => {
                             info!(
                                 ttl_seconds,
+                                prefix,
                                 redis_url_class = search_cache_url_class(&redis_url),
                                 redis_url_len = search_cache_text_len(&redis_url),
                                 "Redis search cache enabled"
@@ -155,7 +166,7 @@ impl SearchCache {
                 connection: RwLock::new(connection),
                 ttl_seconds,
                 enabled,
-                prefix: "mm:search:".to_string(),
+                prefix: prefix.to_string(),
             }),
         }
     }
@@ -167,7 +178,7 @@ impl SearchCache {
                 connection: RwLock::new(None),
                 ttl_seconds: 300,
                 enabled: false,
-                prefix: "mm:search:".to_string(),
+                prefix: SEARCH_CACHE_PREFIX.to_string(),
             }),
         }
     }
@@ -305,13 +316,19 @@ impl SearchCache {
 
     /// Invalidate all search cache entries (flush with prefix).
     pub async fn invalidate_all(&self) -> bool {
+        self.invalidate_prefixed("").await
+    }
+
+    /// Invalidate every entry whose key, after the cache prefix, starts with
+    /// `key_prefix`.
+    pub async fn invalidate_prefixed(&self, key_prefix: &str) -> bool {
         let mut conn_guard = self.inner.connection.write().await;
         let conn = match conn_guard.as_mut() {
             Some(c) => c,
             None => return false,
         };
 
-        let pattern = format!("{}*", self.inner.prefix);
+        let pattern = format!("{}{}*", self.inner.prefix, key_prefix);
 
         // Use SCAN to find keys, then DEL
         // Note: For production with many keys, consider UNLINK for async deletion
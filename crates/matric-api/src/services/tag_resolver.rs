@@ -273,6 +273,7 @@ impl TagResolver {
         // Copy over non-notation fields
         filter.min_tag_count = input.min_tag_count;
         filter.include_untagged = input.include_untagged;
+        filter.expand_hierarchy = input.expand_hierarchy;
 
         Ok(filter)
     }
@@ -543,6 +544,7 @@ mod tests {
             excluded_schemes: vec![],
             min_tag_count: None,
             include_untagged: true,
+            expand_hierarchy: false,
         };
 
         let filter = resolver
@@ -566,6 +568,7 @@ mod tests {
             excluded_schemes: vec![],
             min_tag_count: None,
             include_untagged: true,
+            expand_hierarchy: false,
         };
 
         let result = resolver.resolve_filter(input).await;
@@ -623,6 +626,7 @@ mod tests {
             excluded_schemes: vec![],
             min_tag_count: None,
             include_untagged: true,
+            expand_hierarchy: false,
         };
 
         let filter = resolver
@@ -653,6 +657,7 @@ mod tests {
             excluded_schemes: vec![],
             min_tag_count: None,
             include_untagged: true,
+            expand_hierarchy: false,
         };
 
         let filter = resolver
@@ -681,6 +686,7 @@ mod tests {
             excluded_schemes: vec![],
             min_tag_count: None,
             include_untagged: true,
+            expand_hierarchy: false,
         };
 
         let filter = resolver
@@ -704,6 +710,7 @@ mod tests {
             excluded_schemes: vec![],
             min_tag_count: None,
             include_untagged: true,
+            expand_hierarchy: false,
         };
 
         let result = resolver.resolve_filter(input).await;
@@ -752,6 +759,7 @@ mod tests {
             excluded_schemes: vec![scheme_notation, "nonexistent-scheme".to_string()],
             min_tag_count: None,
             include_untagged: true,
+            expand_hierarchy: false,
         };
 
         let filter = resolver
@@ -777,6 +785,7 @@ mod tests {
             excluded_schemes: vec![],
             min_tag_count: Some(3),
             include_untagged: false,
+            expand_hierarchy: false,
         };
 
         let filter = resolver
@@ -5,7 +5,11 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 pub const SIGNATURE_ENTRY: &str = "signature.json";
+/// Header carrying the keyset passphrase for signed shard exports.
+pub const SIGNING_PASSPHRASE_HEADER: &str = "x-fortemi-signing-passphrase";
 pub const MAX_SIGNATURE_ENVELOPE_BYTES: usize = 64 * 1024;
+/// Upper bound on `blob_digests` in the signature envelope schema.
+pub const MAX_SIGNED_BLOB_DIGESTS: usize = 64;
 pub const SIGNATURE_SCHEMA: &str =
     include_str!("../../../contracts/knowledge-shard/1.2.0/full-v1/signature.schema.json");
 const SIGNING_ENVELOPE_VERSION: &str = "1";
@@ -69,10 +73,36 @@ struct TrustedKey {
     revoked: bool,
 }
 
+#[derive(Default)]
 pub struct ShardTrustStore {
     keys: HashMap<String, TrustedKey>,
 }
 
+impl ShardTrustStore {
+    /// Trust a local keyset's signer identity.
+    ///
+    /// Entries from the configured allowlist take precedence, so an operator
+    /// can still revoke a local key id there. Invalid keys are ignored.
+    pub fn trust_local_signer(&mut self, key_id: String, public_key: String) {
+        if key_id.is_empty() || key_id.len() > MAX_KEY_ID_BYTES || self.keys.contains_key(&key_id) {
+            return;
+        }
+        let Some(public_key_bytes) =
+            decode_base64url(&public_key).and_then(|decoded| <[u8; 32]>::try_from(decoded).ok())
+        else {
+            return;
+        };
+        self.keys.insert(
+            key_id,
+            TrustedKey {
+                public_key,
+                public_key_bytes,
+                revoked: false,
+            },
+        );
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum ShardSignatureVerdict {
     Valid,
@@ -98,6 +128,23 @@ impl ShardSignatureVerdict {
     }
 }
 
+/// Resolve the import signature policy from `verify_signature` and the
+/// `require_signature` shorthand.
+pub fn requested_signature_policy(
+    verify_signature: Option<ShardSignaturePolicy>,
+    require_signature: bool,
+) -> Result<Option<ShardSignaturePolicy>, String> {
+    match (verify_signature, require_signature) {
+        (policy, false) => Ok(policy),
+        (None | Some(ShardSignaturePolicy::Require), true) => {
+            Ok(Some(ShardSignaturePolicy::Require))
+        }
+        (Some(_), true) => Err(
+            "require_signature conflicts with the requested verify_signature policy.".to_string(),
+        ),
+    }
+}
+
 pub fn parse_trust_store(value: Option<&str>) -> Result<Option<ShardTrustStore>, String> {
     let Some(value) = value.filter(|value| !value.trim().is_empty()) else {
         return Ok(None);
@@ -184,16 +231,15 @@ fn parse_signature_envelope(data: &[u8]) -> Result<ShardSignatureEnvelope, ()> {
     serde_json::from_value(value).map_err(|_| ())
 }
 
-#[cfg(test)]
-pub(super) fn create_test_signature_envelope(
+/// Build a `signature.json` envelope committing to the manifest and the
+/// attachment sidecar digests (hex BLAKE3, without the `blake3:` prefix).
+pub fn create_signature_envelope(
     manifest: &[u8],
     blob_digests: &[&str],
-) -> (Vec<u8>, String, String) {
-    use ring::signature::KeyPair;
-
-    let key_pair = ring::signature::Ed25519KeyPair::from_seed_unchecked(&[7_u8; 32]).unwrap();
-    let public_key =
-        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref());
+    key_id: &str,
+    signing_key: &matric_crypto::SigningKey,
+) -> Result<Vec<u8>, serde_json::Error> {
+    let public_key = signing_key.verifying_key().to_base64url();
     let manifest_digest = hex::encode(Sha256::digest(manifest));
     let mut sorted_digests = blob_digests.to_vec();
     sorted_digests.sort_unstable();
@@ -203,29 +249,39 @@ pub(super) fn create_test_signature_envelope(
         manifest_digest: &manifest_digest,
         signer: CanonicalSigner {
             algorithm: SIGNING_ALGORITHM,
-            key_id: "fortemi-fixture-1",
+            key_id,
             public_key: &public_key,
         },
-    })
-    .unwrap();
+    })?;
     let signed_digest = hex::encode(Sha256::digest(canonical));
-    let signature = base64::engine::general_purpose::URL_SAFE_NO_PAD
-        .encode(key_pair.sign(signed_digest.as_bytes()).as_ref());
-    let envelope = serde_json::json!({
+    let signature = signing_key.sign(signed_digest.as_bytes()).to_base64url();
+    serde_json::to_vec_pretty(&serde_json::json!({
         "format_version": SIGNING_ENVELOPE_VERSION,
         "signer": {
-            "key_id": "fortemi-fixture-1",
+            "key_id": key_id,
             "algorithm": SIGNING_ALGORITHM,
             "public_key": public_key,
         },
         "manifest_digest": manifest_digest,
         "blob_digests": blob_digests,
         "signature": signature,
-    });
+    }))
+}
+
+#[cfg(test)]
+pub(super) fn create_test_signature_envelope(
+    manifest: &[u8],
+    blob_digests: &[&str],
+) -> (Vec<u8>, String, String) {
+    let signing_key = matric_crypto::SigningKey::from_seed(&[7_u8; 32]);
+    let envelope =
+        create_signature_envelope(manifest, blob_digests, "fortemi-fixture-1", &signing_key)
+            .unwrap();
+    let value = serde_json::from_slice::<serde_json::Value>(&envelope).unwrap();
     (
-        serde_json::to_vec_pretty(&envelope).unwrap(),
-        public_key,
-        signature,
+        envelope,
+        value["signer"]["public_key"].as_str().unwrap().to_string(),
+        value["signature"].as_str().unwrap().to_string(),
     )
 }
 
@@ -586,6 +642,77 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn local_keyset_signatures_verify_through_trusted_local_signers() {
+        let manifest = br#"{"version":"1.1.0"}"#;
+        let digest = "c".repeat(64);
+        let signing_key = matric_crypto::SigningKey::from_seed(&[9_u8; 32]);
+        let envelope =
+            create_signature_envelope(manifest, &[&digest], "mm:local-keyset", &signing_key)
+                .unwrap();
+        let files = HashMap::from([
+            ("manifest.json".to_string(), manifest.to_vec()),
+            (SIGNATURE_ENTRY.to_string(), envelope),
+        ]);
+        let sidecars = [format!("blake3:{digest}")];
+
+        let mut trust_store = ShardTrustStore::default();
+        assert_eq!(
+            verify_shard_signature(&files, sidecars.iter(), &trust_store),
+            ShardSignatureVerdict::UnknownSigner
+        );
+        trust_store.trust_local_signer(
+            "mm:local-keyset".to_string(),
+            signing_key.verifying_key().to_base64url(),
+        );
+        assert_eq!(
+            verify_shard_signature(&files, sidecars.iter(), &trust_store),
+            ShardSignatureVerdict::Valid
+        );
+    }
+
+    #[test]
+    fn configured_trusted_keys_take_precedence_over_local_signers() {
+        let signing_key = matric_crypto::SigningKey::from_seed(&[9_u8; 32]);
+        let public_key = signing_key.verifying_key().to_base64url();
+        let mut trust_store = parse_trust_store(Some(
+            &serde_json::json!([{
+                "key_id": "mm:local-keyset",
+                "public_key": public_key,
+                "revoked": true,
+            }])
+            .to_string(),
+        ))
+        .unwrap()
+        .unwrap();
+        trust_store.trust_local_signer("mm:local-keyset".to_string(), public_key.clone());
+        assert!(trust_store.keys["mm:local-keyset"].revoked);
+
+        trust_store.trust_local_signer("mm:invalid".to_string(), "too-short".to_string());
+        assert!(!trust_store.keys.contains_key("mm:invalid"));
+    }
+
+    #[test]
+    fn require_signature_resolves_to_require_policy() {
+        assert!(matches!(requested_signature_policy(None, false), Ok(None)));
+        assert!(matches!(
+            requested_signature_policy(None, true),
+            Ok(Some(ShardSignaturePolicy::Require))
+        ));
+        assert!(matches!(
+            requested_signature_policy(Some(ShardSignaturePolicy::Prefer), false),
+            Ok(Some(ShardSignaturePolicy::Prefer))
+        ));
+        assert!(matches!(
+            requested_signature_policy(Some(ShardSignaturePolicy::Require), true),
+            Ok(Some(ShardSignaturePolicy::Require))
+        ));
+        assert!(requested_signature_policy(Some(ShardSignaturePolicy::Prefer), true).is_err());
+        assert!(
+            requested_signature_policy(Some(ShardSignaturePolicy::TrustedLocalOnly), true).is_err()
+        );
+    }
+
     #[test]
     fn trust_store_rejects_duplicate_keys_and_invalid_public_keys() {
         let duplicate = serde_json::json!([
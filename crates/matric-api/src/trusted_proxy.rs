@@ -205,6 +205,10 @@ impl ExternalRequestContext {
         self.client_ip.is_some()
     }
 
+    pub(crate) fn client_ip(&self) -> Option<IpAddr> {
+        self.client_ip
+    }
+
     pub(crate) fn proxy_trusted(&self) -> bool {
         self.proxy_trusted
     }
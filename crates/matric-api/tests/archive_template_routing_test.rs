@@ -55,6 +55,7 @@ async fn test_template_create_in_archive_schema() {
         format: Some("markdown".to_string()),
         default_tags: Some(vec!["template".to_string()]),
         collection_id: None,
+        variables: None,
     };
 
     let template_id = ctx
@@ -108,6 +109,7 @@ async fn test_template_list_isolation() {
         format: None,
         default_tags: None,
         collection_id: None,
+        variables: None,
     };
 
     ctx1.execute(move |tx| Box::pin(async move { templates1.create_tx(tx, req1).await }))
@@ -126,6 +128,7 @@ async fn test_template_list_isolation() {
         format: None,
         default_tags: None,
         collection_id: None,
+        variables: None,
     };
 
     ctx2.execute(move |tx| Box::pin(async move { templates2.create_tx(tx, req2).await }))
@@ -177,6 +180,7 @@ async fn test_template_update_in_archive_schema() {
         format: None,
         default_tags: None,
         collection_id: None,
+        variables: None,
     };
 
     let template_id = ctx
@@ -192,6 +196,7 @@ async fn test_template_update_in_archive_schema() {
         content: Some("Updated content".to_string()),
         default_tags: None,
         collection_id: None,
+        variables: None,
     };
 
     ctx.execute(move |tx| {
@@ -236,6 +241,7 @@ async fn test_template_delete_in_archive_schema() {
         format: None,
         default_tags: None,
         collection_id: None,
+        variables: None,
     };
 
     let template_id = ctx
@@ -0,0 +1,228 @@
+//! Per-archive storage quotas and usage.
+//!
+//! An archive may cap how many notes, how many embedding rows and how many
+//! bytes of attachment blobs it holds. Quotas are checked when content is
+//! ingested: a note or embedding over the cap is refused with a 409, an
+//! attachment that would push blob storage over the cap with a 413. Content
+//! already stored is never removed when a quota is lowered.
+//!
+//! Archives have no quota unless one is set; each limit left out is
+//! unlimited.
+//!
+//! ```
+//! use matric_core::{ArchiveQuota, ArchiveQuotaKind};
+//!
+//! let quota = ArchiveQuota {
+//!     max_notes: Some(100),
+//!     ..Default::default()
+//! };
+//! assert!(quota.check(ArchiveQuotaKind::Notes, 99, 1).is_ok());
+//! let exceeded = quota.check(ArchiveQuotaKind::Notes, 99, 2).unwrap_err();
+//! assert_eq!(exceeded.limit, 100);
+//! assert!(quota.check(ArchiveQuotaKind::Embeddings, 1_000_000, 1).is_ok());
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// What an archive quota limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveQuotaKind {
+    /// Notes that are not soft-deleted.
+    Notes,
+    /// Bytes of attachment blob storage.
+    AttachmentBytes,
+    /// Embedding rows across all embedding sets.
+    Embeddings,
+}
+
+impl ArchiveQuotaKind {
+    pub const ALL: [Self; 3] = [Self::Notes, Self::AttachmentBytes, Self::Embeddings];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Notes => "notes",
+            Self::AttachmentBytes => "attachment_bytes",
+            Self::Embeddings => "embeddings",
+        }
+    }
+}
+
+/// Optional caps on what one archive holds. `None` is unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ArchiveQuota {
+    /// Maximum number of notes
+    #[serde(default)]
+    pub max_notes: Option<i64>,
+    /// Maximum bytes of attachment blob storage
+    #[serde(default)]
+    pub max_attachment_bytes: Option<i64>,
+    /// Maximum number of embedding rows
+    #[serde(default)]
+    pub max_embeddings: Option<i64>,
+}
+
+impl ArchiveQuota {
+    /// Check every limit is non-negative. Zero freezes that kind of content.
+    pub fn validate(&self) -> Result<()> {
+        for kind in ArchiveQuotaKind::ALL {
+            if self.limit(kind).is_some_and(|limit| limit < 0) {
+                return Err(Error::InvalidInput(format!(
+                    "max_{} must not be negative",
+                    kind.as_str()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn limit(&self, kind: ArchiveQuotaKind) -> Option<i64> {
+        match kind {
+            ArchiveQuotaKind::Notes => self.max_notes,
+            ArchiveQuotaKind::AttachmentBytes => self.max_attachment_bytes,
+            ArchiveQuotaKind::Embeddings => self.max_embeddings,
+        }
+    }
+
+    /// Whether an archive holding `used` may take `adding` more.
+    pub fn check(
+        &self,
+        kind: ArchiveQuotaKind,
+        used: i64,
+        adding: i64,
+    ) -> std::result::Result<(), ArchiveQuotaExceeded> {
+        match self.limit(kind) {
+            Some(limit) if used.saturating_add(adding) > limit => Err(ArchiveQuotaExceeded {
+                quota: kind,
+                limit,
+                used,
+                requested: adding,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The archive quota an ingestion ran into, reported in the error body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ArchiveQuotaExceeded {
+    pub quota: ArchiveQuotaKind,
+    /// The archive's limit
+    pub limit: i64,
+    /// What the archive already holds
+    pub used: i64,
+    /// What the refused request would have added
+    pub requested: i64,
+}
+
+impl ArchiveQuotaExceeded {
+    pub fn detail(&self) -> String {
+        format!(
+            "Archive {} quota of {} exceeded: {} used, {} requested.",
+            self.quota.as_str().replace('_', " "),
+            self.limit,
+            self.used,
+            self.requested,
+        )
+    }
+}
+
+/// Size of one table in an archive schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ArchiveTableUsage {
+    pub table: String,
+    /// Planner estimate of live rows
+    pub rows_estimate: i64,
+    /// Table, index and TOAST bytes
+    pub total_bytes: i64,
+}
+
+/// Attachment blob storage held by an archive.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ArchiveBlobUsage {
+    pub blobs: i64,
+    /// Sum of blob sizes, whichever backend stores them
+    pub bytes: i64,
+    /// Bytes stored inline in the database
+    pub database_bytes: i64,
+    /// Bytes stored by the file storage backend
+    pub external_bytes: i64,
+}
+
+/// An archive's current consumption.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ArchiveUsage {
+    pub notes: i64,
+    pub attachments: i64,
+    pub embeddings: i64,
+    pub blob_storage: ArchiveBlobUsage,
+    /// Largest first
+    pub tables: Vec<ArchiveTableUsage>,
+    /// Sum of `tables[].total_bytes`
+    pub table_bytes: i64,
+}
+
+impl ArchiveUsage {
+    pub fn used(&self, kind: ArchiveQuotaKind) -> i64 {
+        match kind {
+            ArchiveQuotaKind::Notes => self.notes,
+            ArchiveQuotaKind::AttachmentBytes => self.blob_storage.bytes,
+            ArchiveQuotaKind::Embeddings => self.embeddings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_negative_limits() {
+        let quota = ArchiveQuota {
+            max_embeddings: Some(-1),
+            ..Default::default()
+        };
+        match quota.validate() {
+            Err(Error::InvalidInput(message)) => assert!(message.contains("max_embeddings")),
+            other => panic!("expected invalid input, got {other:?}"),
+        }
+        assert!(ArchiveQuota {
+            max_notes: Some(0),
+            ..Default::default()
+        }
+        .validate()
+        .is_ok());
+    }
+
+    #[test]
+    fn check_allows_up_to_the_limit() {
+        let quota = ArchiveQuota {
+            max_attachment_bytes: Some(1_000),
+            ..Default::default()
+        };
+        assert!(quota
+            .check(ArchiveQuotaKind::AttachmentBytes, 400, 600)
+            .is_ok());
+        let exceeded = quota
+            .check(ArchiveQuotaKind::AttachmentBytes, 400, 601)
+            .unwrap_err();
+        assert_eq!(exceeded.quota, ArchiveQuotaKind::AttachmentBytes);
+        assert_eq!(exceeded.used, 400);
+        assert_eq!(exceeded.requested, 601);
+        assert_eq!(
+            exceeded.detail(),
+            "Archive attachment bytes quota of 1000 exceeded: 400 used, 601 requested."
+        );
+    }
+
+    #[test]
+    fn missing_limits_are_unlimited() {
+        let quota: ArchiveQuota = serde_json::from_str(r#"{"max_notes": 5}"#).unwrap();
+        assert_eq!(quota.limit(ArchiveQuotaKind::Notes), Some(5));
+        assert!(quota
+            .check(ArchiveQuotaKind::Embeddings, i64::MAX, 1)
+            .is_ok());
+    }
+}
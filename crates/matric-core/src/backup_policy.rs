@@ -0,0 +1,668 @@
+//! Scheduled backup policies.
+//!
+//! A policy pairs a cron-style schedule with a backup type and a retention
+//! rule. Schedules use the standard five-field syntax (minute, hour, day of
+//! month, month, day of week) evaluated in UTC, with `*`, lists, ranges,
+//! steps, month/day names, and the `@hourly`/`@daily`/`@weekly`/`@monthly`/
+//! `@yearly` aliases. As in Vixie cron, when both day fields are restricted a
+//! day matches if either does.
+//!
+//! Retention keeps the newest backup from each of the most recent
+//! `keep_daily` days and `keep_weekly` ISO weeks that have backups; the
+//! newest backup is always kept.
+//!
+//! ```
+//! use chrono::{TimeZone, Utc};
+//! use matric_core::CronSchedule;
+//!
+//! let schedule = CronSchedule::parse("30 2 * * mon-fri").unwrap();
+//! let friday = Utc.with_ymd_and_hms(2026, 10, 16, 3, 0, 0).unwrap();
+//! assert_eq!(
+//!     schedule.next_after(friday),
+//!     Some(Utc.with_ymd_and_hms(2026, 10, 19, 2, 30, 0).unwrap())
+//! );
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// Daily backups kept by default.
+pub const DEFAULT_KEEP_DAILY: i32 = 7;
+
+/// Weekly backups kept by default.
+pub const DEFAULT_KEEP_WEEKLY: i32 = 4;
+
+/// Upper bound for either retention count.
+pub const MAX_BACKUP_RETENTION: i32 = 366;
+
+/// Backup types a policy can run.
+pub const BACKUP_POLICY_TYPES: [&str; 2] = ["snapshot", "full"];
+
+/// Longest accepted policy name; names become part of backup filenames.
+pub const MAX_BACKUP_POLICY_NAME_LEN: usize = 32;
+
+/// Minutes scanned before a schedule is considered unsatisfiable
+/// (e.g. `0 0 30 2 *`).
+const MAX_SCHEDULE_SCAN_MINUTES: i64 = 5 * 366 * 24 * 60;
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A parsed five-field cron schedule, evaluated in UTC.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_any: bool,
+    day_of_week_any: bool,
+}
+
+impl fmt::Debug for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CronSchedule")
+            .field("minutes", &format_args!("{:#x}", self.minutes))
+            .field("hours", &format_args!("{:#x}", self.hours))
+            .field("days_of_month", &format_args!("{:#x}", self.days_of_month))
+            .field("months", &format_args!("{:#x}", self.months))
+            .field("days_of_week", &format_args!("{:#x}", self.days_of_week))
+            .finish()
+    }
+}
+
+impl CronSchedule {
+    /// Parses a five-field cron expression or one of the `@` aliases.
+    pub fn parse(expr: &str) -> Result<Self> {
+        let expr = expr.trim();
+        let expanded = match expr.to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            _ => expr,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(invalid_schedule(
+                "expected five fields: minute hour day-of-month month day-of-week",
+            ));
+        };
+
+        let mut days_of_week = parse_cron_field(day_of_week, 0, 7, &WEEKDAY_NAMES, 0)?;
+        // 7 is an alias for Sunday.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            minutes: parse_cron_field(minute, 0, 59, &[], 0)?,
+            hours: parse_cron_field(hour, 0, 23, &[], 0)?,
+            days_of_month: parse_cron_field(day_of_month, 1, 31, &[], 0)?,
+            months: parse_cron_field(month, 1, 12, &MONTH_NAMES, 1)?,
+            days_of_week,
+            day_of_month_any: day_of_month.starts_with('*'),
+            day_of_week_any: day_of_week.starts_with('*'),
+        })
+    }
+
+    /// Returns the first matching minute strictly after `after`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after
+            .with_second(0)
+            .and_then(|t| t.with_nanosecond(0))
+            .unwrap_or(after)
+            + Duration::minutes(1);
+        let limit = t + Duration::minutes(MAX_SCHEDULE_SCAN_MINUTES);
+
+        while t < limit {
+            if !has_bit(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+                continue;
+            }
+            if !self.day_matches(t.date_naive()) {
+                t = Utc.from_utc_datetime(&t.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?);
+                continue;
+            }
+            if !has_bit(self.hours, t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !has_bit(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let dom = has_bit(self.days_of_month, date.day());
+        let dow = has_bit(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.day_of_month_any, self.day_of_week_any) {
+            (true, true) => true,
+            (true, false) => dow,
+            (false, true) => dom,
+            (false, false) => dom || dow,
+        }
+    }
+}
+
+fn has_bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+fn invalid_schedule(reason: &str) -> Error {
+    Error::InvalidInput(format!("invalid backup schedule: {reason}"))
+}
+
+/// Parses one cron field into a bitmask of allowed values.
+///
+/// `names` maps case-insensitive names to `name_base + index`.
+fn parse_cron_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    name_base: u32,
+) -> Result<u64> {
+    let value = |s: &str| -> Result<u32> {
+        if let Some(index) = names.iter().position(|n| n.eq_ignore_ascii_case(s)) {
+            return Ok(name_base + index as u32);
+        }
+        s.parse::<u32>()
+            .ok()
+            .filter(|v| (min..=max).contains(v))
+            .ok_or_else(|| invalid_schedule("field value out of range"))
+    };
+
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|&s| s > 0)
+                    .ok_or_else(|| invalid_schedule("step must be a positive integer"))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            // `5/15` means "from 5 to the end in steps of 15".
+            (start, if step > 1 { max } else { start })
+        };
+        if start > end {
+            return Err(invalid_schedule("range start is after its end"));
+        }
+        for v in (start..=end).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+/// How many scheduled backups a policy keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupRetention {
+    pub keep_daily: i32,
+    pub keep_weekly: i32,
+}
+
+impl Default for BackupRetention {
+    fn default() -> Self {
+        Self {
+            keep_daily: DEFAULT_KEEP_DAILY,
+            keep_weekly: DEFAULT_KEEP_WEEKLY,
+        }
+    }
+}
+
+impl BackupRetention {
+    /// Validates that both counts are in range and at least one is set.
+    pub fn validate(&self) -> Result<()> {
+        let range = 0..=MAX_BACKUP_RETENTION;
+        if !range.contains(&self.keep_daily) || !range.contains(&self.keep_weekly) {
+            return Err(Error::InvalidInput(format!(
+                "retention counts must be between 0 and {MAX_BACKUP_RETENTION}"
+            )));
+        }
+        if self.keep_daily == 0 && self.keep_weekly == 0 {
+            return Err(Error::InvalidInput(
+                "keep_daily or keep_weekly must be at least 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the IDs of backups that fall outside the retention window.
+    pub fn prunable(&self, backups: &[(Uuid, DateTime<Utc>)]) -> Vec<Uuid> {
+        let mut newest_first: Vec<_> = backups.to_vec();
+        newest_first.sort_by(|a, b| b.1.cmp(&a.1).then(b.0.cmp(&a.0)));
+
+        let mut days = HashSet::new();
+        let mut weeks = HashSet::new();
+        let mut prunable = Vec::new();
+        for (index, (id, created_at)) in newest_first.into_iter().enumerate() {
+            let date = created_at.date_naive();
+            let week = date.iso_week();
+            let keep_day = days.len() < self.keep_daily as usize && days.insert(date);
+            let keep_week =
+                weeks.len() < self.keep_weekly as usize && weeks.insert((week.year(), week.week()));
+            if index > 0 && !keep_day && !keep_week {
+                prunable.push(id);
+            }
+        }
+        prunable
+    }
+}
+
+fn validate_backup_policy_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name.len() > MAX_BACKUP_POLICY_NAME_LEN
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(Error::InvalidInput(format!(
+            "policy name must be 1-{MAX_BACKUP_POLICY_NAME_LEN} ASCII letters, digits, '-' or '_'"
+        )));
+    }
+    Ok(())
+}
+
+fn validate_backup_type(backup_type: &str, keyset: Option<&str>) -> Result<()> {
+    if !BACKUP_POLICY_TYPES.contains(&backup_type) {
+        return Err(Error::InvalidInput(format!(
+            "backup_type must be one of: {}",
+            BACKUP_POLICY_TYPES.join(", ")
+        )));
+    }
+    if backup_type != "full" && keyset.is_some() {
+        return Err(Error::InvalidInput(
+            "keyset only applies to full backups".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// A scheduled backup policy.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BackupPolicy {
+    pub id: Uuid,
+    pub name: String,
+    /// Five-field cron expression, evaluated in UTC
+    pub schedule: String,
+    /// `snapshot` (gzipped database dump) or `full` (encrypted archive)
+    pub backup_type: String,
+    /// PKE keyset for full backups; the active keyset when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyset: Option<String>,
+    pub keep_daily: i32,
+    pub keep_weekly: i32,
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_run_at_utc: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run_at_utc: Option<DateTime<Utc>>,
+    /// Status of the most recent run (`running`, `succeeded`, `failed`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_status: Option<String>,
+    pub created_at_utc: DateTime<Utc>,
+    pub updated_at_utc: DateTime<Utc>,
+}
+
+impl BackupPolicy {
+    /// Returns the policy's retention rule.
+    pub fn retention(&self) -> BackupRetention {
+        BackupRetention {
+            keep_daily: self.keep_daily,
+            keep_weekly: self.keep_weekly,
+        }
+    }
+}
+
+impl fmt::Debug for BackupPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackupPolicy")
+            .field("id_set", &true)
+            .field("name_len", &self.name.len())
+            .field("schedule_len", &self.schedule.len())
+            .field("backup_type", &self.backup_type)
+            .field("keyset_len", &self.keyset.as_ref().map(String::len))
+            .field("keep_daily", &self.keep_daily)
+            .field("keep_weekly", &self.keep_weekly)
+            .field("enabled", &self.enabled)
+            .field("next_run_at_utc", &self.next_run_at_utc)
+            .field("last_run_at_utc", &self.last_run_at_utc)
+            .field("last_status", &self.last_status)
+            .finish()
+    }
+}
+
+fn default_backup_type() -> String {
+    "snapshot".to_string()
+}
+
+fn default_keep_daily() -> i32 {
+    DEFAULT_KEEP_DAILY
+}
+
+fn default_keep_weekly() -> i32 {
+    DEFAULT_KEEP_WEEKLY
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Request body for creating a backup policy.
+#[derive(Clone, Deserialize, utoipa::ToSchema)]
+pub struct CreateBackupPolicyRequest {
+    pub name: String,
+    /// Five-field cron expression, evaluated in UTC
+    pub schedule: String,
+    /// `snapshot` (default) or `full`
+    #[serde(default = "default_backup_type")]
+    pub backup_type: String,
+    /// PKE keyset for full backups; the active keyset when unset
+    pub keyset: Option<String>,
+    /// Daily backups to keep (default: 7)
+    #[serde(default = "default_keep_daily")]
+    pub keep_daily: i32,
+    /// Weekly backups to keep (default: 4)
+    #[serde(default = "default_keep_weekly")]
+    pub keep_weekly: i32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+impl CreateBackupPolicyRequest {
+    /// Validates the request and returns the parsed schedule.
+    pub fn validate(&self) -> Result<CronSchedule> {
+        validate_backup_policy_name(&self.name)?;
+        validate_backup_type(&self.backup_type, self.keyset.as_deref())?;
+        BackupRetention {
+            keep_daily: self.keep_daily,
+            keep_weekly: self.keep_weekly,
+        }
+        .validate()?;
+        CronSchedule::parse(&self.schedule)
+    }
+}
+
+impl fmt::Debug for CreateBackupPolicyRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateBackupPolicyRequest")
+            .field("name_len", &self.name.len())
+            .field("schedule_len", &self.schedule.len())
+            .field("backup_type", &self.backup_type)
+            .field("keyset_len", &self.keyset.as_ref().map(String::len))
+            .field("keep_daily", &self.keep_daily)
+            .field("keep_weekly", &self.keep_weekly)
+            .field("enabled", &self.enabled)
+            .finish()
+    }
+}
+
+/// Request body for updating a backup policy; unset fields are unchanged.
+#[derive(Clone, Default, Deserialize, utoipa::ToSchema)]
+pub struct UpdateBackupPolicyRequest {
+    pub schedule: Option<String>,
+    pub backup_type: Option<String>,
+    /// PKE keyset for full backups; an empty string clears it
+    pub keyset: Option<String>,
+    pub keep_daily: Option<i32>,
+    pub keep_weekly: Option<i32>,
+    pub enabled: Option<bool>,
+}
+
+impl UpdateBackupPolicyRequest {
+    /// Applies the update to `policy` after validating the merged result.
+    ///
+    /// Returns the parsed schedule when the schedule changed.
+    pub fn apply(&self, policy: &mut BackupPolicy) -> Result<Option<CronSchedule>> {
+        let schedule = self
+            .schedule
+            .as_deref()
+            .map(CronSchedule::parse)
+            .transpose()?;
+        if let Some(expr) = &self.schedule {
+            policy.schedule = expr.trim().to_string();
+        }
+        if let Some(backup_type) = &self.backup_type {
+            policy.backup_type = backup_type.clone();
+        }
+        if let Some(keyset) = &self.keyset {
+            policy.keyset = Some(keyset.clone()).filter(|k| !k.is_empty());
+        }
+        if let Some(keep_daily) = self.keep_daily {
+            policy.keep_daily = keep_daily;
+        }
+        if let Some(keep_weekly) = self.keep_weekly {
+            policy.keep_weekly = keep_weekly;
+        }
+        if let Some(enabled) = self.enabled {
+            policy.enabled = enabled;
+        }
+        validate_backup_type(&policy.backup_type, policy.keyset.as_deref())?;
+        policy.retention().validate()?;
+        Ok(schedule)
+    }
+}
+
+impl fmt::Debug for UpdateBackupPolicyRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpdateBackupPolicyRequest")
+            .field("schedule_len", &self.schedule.as_ref().map(String::len))
+            .field("backup_type", &self.backup_type)
+            .field("keyset_len", &self.keyset.as_ref().map(String::len))
+            .field("keep_daily", &self.keep_daily)
+            .field("keep_weekly", &self.keep_weekly)
+            .field("enabled", &self.enabled)
+            .finish()
+    }
+}
+
+/// One execution of a backup policy.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BackupPolicyRun {
+    pub id: Uuid,
+    pub policy_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<Uuid>,
+    /// `running`, `succeeded`, or `failed`
+    pub status: String,
+    /// Backup filename in the backup directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub started_at_utc: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at_utc: Option<DateTime<Utc>>,
+    /// When retention deleted the backup file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pruned_at_utc: Option<DateTime<Utc>>,
+}
+
+impl fmt::Debug for BackupPolicyRun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackupPolicyRun")
+            .field("id_set", &true)
+            .field("job_id_set", &self.job_id.is_some())
+            .field("status", &self.status)
+            .field("filename_len", &self.filename.as_ref().map(String::len))
+            .field("size_bytes", &self.size_bytes)
+            .field("error_len", &self.error.as_ref().map(String::len))
+            .field("started_at_utc", &self.started_at_utc)
+            .field("finished_at_utc", &self.finished_at_utc)
+            .field("pruned_at_utc", &self.pruned_at_utc)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn daily_schedule_fires_next_day_after_passing_time() {
+        let schedule = CronSchedule::parse("0 3 * * *").unwrap();
+        assert_eq!(
+            schedule.next_after(at(2026, 10, 16, 2, 59)),
+            Some(at(2026, 10, 16, 3, 0))
+        );
+        assert_eq!(
+            schedule.next_after(at(2026, 10, 16, 3, 0)),
+            Some(at(2026, 10, 17, 3, 0))
+        );
+        assert_eq!(
+            CronSchedule::parse("@daily").unwrap(),
+            CronSchedule::parse("0 0 * * *").unwrap()
+        );
+    }
+
+    #[test]
+    fn steps_lists_and_names_are_supported() {
+        let schedule = CronSchedule::parse("*/15 9-17 * jan,jul sun").unwrap();
+        // 2026-07-05 is a Sunday.
+        assert_eq!(
+            schedule.next_after(at(2026, 6, 30, 12, 0)),
+            Some(at(2026, 7, 5, 9, 0))
+        );
+        assert_eq!(
+            schedule.next_after(at(2026, 7, 5, 9, 0)),
+            Some(at(2026, 7, 5, 9, 15))
+        );
+        assert_eq!(
+            CronSchedule::parse("0 0 * * 7").unwrap(),
+            CronSchedule::parse("0 0 * * 0").unwrap()
+        );
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        // The 1st of the month or any Monday.
+        let schedule = CronSchedule::parse("0 0 1 * mon").unwrap();
+        assert_eq!(
+            schedule.next_after(at(2026, 10, 16, 0, 0)),
+            Some(at(2026, 10, 19, 0, 0))
+        );
+        assert_eq!(
+            schedule.next_after(at(2026, 10, 27, 0, 0)),
+            Some(at(2026, 11, 1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn invalid_and_unsatisfiable_schedules() {
+        for expr in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* * * * * *",
+            "5-1 * * * *",
+            "*/0 * * * *",
+        ] {
+            assert!(
+                matches!(CronSchedule::parse(expr), Err(Error::InvalidInput(_))),
+                "accepted {expr:?}"
+            );
+        }
+        let never = CronSchedule::parse("0 0 30 feb *").unwrap();
+        assert_eq!(never.next_after(at(2026, 1, 1, 0, 0)), None);
+    }
+
+    #[test]
+    fn retention_keeps_newest_per_day_and_week() {
+        let retention = BackupRetention {
+            keep_daily: 2,
+            keep_weekly: 2,
+        };
+        let id = |n: u128| Uuid::from_u128(n);
+        let backups = [
+            (id(1), at(2026, 10, 16, 3, 0)), // Fri, week 42: newest
+            (id(2), at(2026, 10, 16, 1, 0)), // same day, older
+            (id(3), at(2026, 10, 15, 3, 0)), // Thu: second daily
+            (id(4), at(2026, 10, 14, 3, 0)), // Wed: beyond keep_daily
+            (id(5), at(2026, 10, 9, 3, 0)),  // week 41: second weekly
+            (id(6), at(2026, 10, 2, 3, 0)),  // week 40: beyond keep_weekly
+        ];
+        let mut pruned = retention.prunable(&backups);
+        pruned.sort();
+        assert_eq!(pruned, vec![id(2), id(4), id(6)]);
+    }
+
+    #[test]
+    fn retention_always_keeps_newest_and_validates_counts() {
+        let retention = BackupRetention {
+            keep_daily: 0,
+            keep_weekly: 1,
+        };
+        assert!(retention.validate().is_ok());
+        assert!(retention.prunable(&[(Uuid::nil(), Utc::now())]).is_empty());
+        assert!(BackupRetention {
+            keep_daily: 0,
+            keep_weekly: 0
+        }
+        .validate()
+        .is_err());
+        assert!(BackupRetention {
+            keep_daily: -1,
+            keep_weekly: 4
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn create_request_validation() {
+        let mut req = CreateBackupPolicyRequest {
+            name: "nightly".to_string(),
+            schedule: "0 3 * * *".to_string(),
+            backup_type: default_backup_type(),
+            keyset: None,
+            keep_daily: DEFAULT_KEEP_DAILY,
+            keep_weekly: DEFAULT_KEEP_WEEKLY,
+            enabled: true,
+        };
+        assert!(req.validate().is_ok());
+
+        req.keyset = Some("ops".to_string());
+        assert!(req.validate().is_err(), "keyset requires a full backup");
+        req.backup_type = "full".to_string();
+        assert!(req.validate().is_ok());
+
+        req.name = "../etc".to_string();
+        assert!(req.validate().is_err());
+    }
+}
@@ -0,0 +1,350 @@
+//! iCalendar (RFC 5545) feeds.
+//!
+//! `GET /api/v1/calendar.ics` renders an archive's dated items as a
+//! calendar that calendar apps can subscribe to: a daily reminder for review
+//! cards coming due, open tasks on their due day and, optionally, one event
+//! per note created. Day-long items are all-day events; note creations are
+//! instants in UTC.
+//!
+//! ```
+//! use chrono::{NaiveDate, TimeZone, Utc};
+//! use matric_core::calendar::{render_calendar, CalendarEvent, EventStart};
+//!
+//! let event = CalendarEvent {
+//!     uid: "task-1@fortemi".to_string(),
+//!     summary: "Send the budget".to_string(),
+//!     description: None,
+//!     url: None,
+//!     start: EventStart::Date(NaiveDate::from_ymd_opt(2026, 10, 20).unwrap()),
+//! };
+//! let stamp = Utc.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap();
+//! let ics = render_calendar("Fortemi", &[event], stamp);
+//! assert!(ics.contains("DTSTART;VALUE=DATE:20261020\r\n"));
+//! assert!(ics.ends_with("END:VCALENDAR\r\n"));
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use uuid::Uuid;
+
+/// Media type of rendered calendars.
+pub const CALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+/// Domain part of event UIDs.
+pub const CALENDAR_UID_DOMAIN: &str = "fortemi";
+
+/// Days before today a feed covers unless asked otherwise.
+pub const DEFAULT_PAST_DAYS: u32 = 30;
+
+/// Days after today a feed covers unless asked otherwise.
+pub const DEFAULT_FUTURE_DAYS: u32 = 90;
+
+/// Widest window, in days either side of today, a feed may cover.
+pub const MAX_WINDOW_DAYS: u32 = 366;
+
+/// Most events of one kind in a feed.
+pub const MAX_EVENTS_PER_KIND: i64 = 1000;
+
+/// Longest line of a rendered calendar, in octets, before folding.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// When an event happens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventStart {
+    /// All day.
+    Date(NaiveDate),
+    /// At an instant.
+    DateTime(DateTime<Utc>),
+}
+
+/// One `VEVENT` of a feed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CalendarEvent {
+    /// Stable across renders, so calendar apps update events in place.
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub start: EventStart,
+}
+
+impl fmt::Debug for CalendarEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CalendarEvent")
+            .field("uid", &self.uid)
+            .field("summary_len", &self.summary.len())
+            .field(
+                "description_len",
+                &self.description.as_ref().map(String::len),
+            )
+            .field("url_present", &self.url.is_some())
+            .field("start", &self.start)
+            .finish()
+    }
+}
+
+/// What a feed covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarWindow {
+    /// First day covered.
+    pub from: NaiveDate,
+    /// Last day covered.
+    pub until: NaiveDate,
+    /// Only items of notes filed in this collection.
+    pub collection_id: Option<Uuid>,
+}
+
+/// Review cards coming due on a day. Overdue cards count towards the first
+/// day of the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::FromRow)]
+pub struct ReviewDay {
+    pub day: NaiveDate,
+    pub cards: i64,
+}
+
+/// An open task with a due date.
+#[derive(Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct DueTask {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub note_title: Option<String>,
+    pub text: String,
+    pub due_date: NaiveDate,
+}
+
+impl fmt::Debug for DueTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DueTask")
+            .field("id", &self.id)
+            .field("note_id", &self.note_id)
+            .field("text_len", &self.text.len())
+            .field("due_date", &self.due_date)
+            .finish()
+    }
+}
+
+/// A note's creation.
+#[derive(Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct NoteCreation {
+    pub note_id: Uuid,
+    pub title: Option<String>,
+    pub created_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for NoteCreation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NoteCreation")
+            .field("note_id", &self.note_id)
+            .field("title_present", &self.title.is_some())
+            .field("created_at_utc", &self.created_at_utc)
+            .finish()
+    }
+}
+
+/// Events for a feed: one reminder per day with review cards due, one
+/// all-day event per due task, and one event per note creation. Events link
+/// to their note under `base_url`.
+pub fn feed_events(
+    reviews: &[ReviewDay],
+    tasks: &[DueTask],
+    notes: &[NoteCreation],
+    base_url: &str,
+) -> Vec<CalendarEvent> {
+    let base_url = base_url.trim_end_matches('/');
+    let note_url = |id: Uuid| format!("{base_url}/api/v1/notes/{id}");
+    let mut events = Vec::with_capacity(reviews.len() + tasks.len() + notes.len());
+    events.extend(reviews.iter().map(|review| CalendarEvent {
+        uid: event_uid("review", review.day.format("%Y%m%d")),
+        summary: match review.cards {
+            1 => "Review 1 card".to_string(),
+            cards => format!("Review {cards} cards"),
+        },
+        description: None,
+        url: Some(format!("{base_url}/api/v1/review/queue")),
+        start: EventStart::Date(review.day),
+    }));
+    events.extend(tasks.iter().map(|task| {
+        CalendarEvent {
+            uid: event_uid("task", task.id),
+            summary: task.text.clone(),
+            description: task
+                .note_title
+                .as_ref()
+                .map(|title| format!("From note: {title}")),
+            url: Some(note_url(task.note_id)),
+            start: EventStart::Date(task.due_date),
+        }
+    }));
+    events.extend(notes.iter().map(|note| CalendarEvent {
+        uid: event_uid("note", note.note_id),
+        summary: format!(
+            "Created: {}",
+            note.title.as_deref().unwrap_or("Untitled note")
+        ),
+        description: None,
+        url: Some(note_url(note.note_id)),
+        start: EventStart::DateTime(note.created_at_utc),
+    }));
+    events
+}
+
+/// Event UID for an item of `kind`, e.g. `task-<id>@fortemi`.
+pub fn event_uid(kind: &str, id: impl fmt::Display) -> String {
+    format!("{kind}-{id}@{CALENDAR_UID_DOMAIN}")
+}
+
+/// Render `events` as a `VCALENDAR` named `name`, stamped with `now`.
+///
+/// Lines end in CRLF and are folded at 75 octets; text values are escaped.
+pub fn render_calendar(name: &str, events: &[CalendarEvent], now: DateTime<Utc>) -> String {
+    let stamp = format_date_time(now);
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//Fortemi//Calendar Feed//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, "METHOD:PUBLISH");
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape_text(name)));
+    for event in events {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", escape_text(&event.uid)));
+        push_line(&mut out, &format!("DTSTAMP:{stamp}"));
+        match event.start {
+            EventStart::Date(day) => {
+                // DTEND is exclusive: an all-day event ends the next day.
+                let end = day.checked_add_days(Days::new(1)).unwrap_or(day);
+                push_line(
+                    &mut out,
+                    &format!("DTSTART;VALUE=DATE:{}", format_date(day)),
+                );
+                push_line(&mut out, &format!("DTEND;VALUE=DATE:{}", format_date(end)));
+                push_line(&mut out, "TRANSP:TRANSPARENT");
+            }
+            EventStart::DateTime(at) => {
+                push_line(&mut out, &format!("DTSTART:{}", format_date_time(at)));
+                push_line(&mut out, "TRANSP:TRANSPARENT");
+            }
+        }
+        push_line(
+            &mut out,
+            &format!("SUMMARY:{}", escape_text(&event.summary)),
+        );
+        if let Some(description) = &event.description {
+            push_line(
+                &mut out,
+                &format!("DESCRIPTION:{}", escape_text(description)),
+            );
+        }
+        if let Some(url) = &event.url {
+            push_line(&mut out, &format!("URL:{url}"));
+        }
+        push_line(&mut out, "END:VEVENT");
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn format_date(day: NaiveDate) -> String {
+    day.format("%Y%m%d").to_string()
+}
+
+fn format_date_time(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value (RFC 5545 §3.3.11).
+fn escape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Append a content line, folded so no line exceeds 75 octets and no UTF-8
+/// character is split.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > MAX_LINE_OCTETS {
+            // The continuation's leading space counts towards its length.
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += len;
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn stamp() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 18, 9, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn events_render_with_escaped_text_and_exclusive_end() {
+        let events = [
+            CalendarEvent {
+                uid: event_uid("task", 7),
+                summary: "Pay rent; call Bob, then\nrelax \\o/".to_string(),
+                description: Some("From: Budget".to_string()),
+                url: Some("https://example.com/api/v1/notes/7".to_string()),
+                start: EventStart::Date(NaiveDate::from_ymd_opt(2026, 12, 31).unwrap()),
+            },
+            CalendarEvent {
+                uid: event_uid("note", 8),
+                summary: "Created: Ideas".to_string(),
+                description: None,
+                url: None,
+                start: EventStart::DateTime(stamp()),
+            },
+        ];
+
+        let ics = render_calendar("Work", &events, stamp());
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.contains("X-WR-CALNAME:Work\r\n"));
+        assert!(ics.contains("UID:task-7@fortemi\r\n"));
+        assert!(ics.contains("DTSTAMP:20261018T093000Z\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20261231\r\nDTEND;VALUE=DATE:20270101\r\n"));
+        assert!(ics.contains("SUMMARY:Pay rent\\; call Bob\\, then\\nrelax \\\\o/\r\n"));
+        assert!(ics.contains("DESCRIPTION:From: Budget\r\n"));
+        assert!(ics.contains("DTSTART:20261018T093000Z\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn long_lines_fold_without_splitting_characters() {
+        let event = CalendarEvent {
+            uid: event_uid("task", 1),
+            summary: "é".repeat(100),
+            description: None,
+            url: None,
+            start: EventStart::Date(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()),
+        };
+
+        let ics = render_calendar("Fortemi", &[event], stamp());
+
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= MAX_LINE_OCTETS, "line too long: {line}");
+        }
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains(&format!("SUMMARY:{}\r\n", "é".repeat(100))));
+    }
+}
@@ -0,0 +1,825 @@
+//! Citation detection and bibliography export.
+//!
+//! The `citation_extraction` job finds what a note cites — DOIs, arXiv IDs,
+//! web URLs and BibTeX entries — with [`extract_citations`], and stores each
+//! cited work once per archive as a reference. A BibTeX entry carrying a DOI
+//! or an arXiv eprint is stored under that identifier, so the same work cited
+//! two ways is one reference. DOI references can be completed from Crossref
+//! ([`ReferenceMetadata::from_crossref`]) when the `crossref` feature is
+//! built in. [`render_bibtex`] writes references back out as BibTeX.
+//!
+//! ```
+//! use matric_core::citation::{extract_citations, CitationKind};
+//!
+//! let found = extract_citations(
+//!     "See doi:10.1145/3292500.3330701 and https://arxiv.org/abs/1706.03762v5.",
+//! );
+//! assert_eq!(found[0].kind, CitationKind::Doi);
+//! assert_eq!(found[0].identifier, "10.1145/3292500.3330701");
+//! assert_eq!(found[1].kind, CitationKind::Arxiv);
+//! assert_eq!(found[1].identifier, "1706.03762");
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// Crossref works endpoint; the DOI is appended.
+pub const CROSSREF_WORKS_URL: &str = "https://api.crossref.org/works/";
+
+/// Environment variable with a contact address sent to Crossref, which
+/// routes identified clients to its faster "polite" pool.
+pub const ENV_CROSSREF_MAILTO: &str = "CROSSREF_MAILTO";
+
+/// Most distinct citations kept per note.
+pub const MAX_CITATIONS_PER_NOTE: usize = 500;
+
+/// Most Crossref lookups one `citation_extraction` job makes.
+pub const MAX_CROSSREF_LOOKUPS_PER_JOB: usize = 20;
+
+static DOI_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\b(10\.\d{4,9}/[^\s"'<>]+)"#).expect("valid DOI pattern"));
+
+static ARXIV_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:\barxiv:\s*|arxiv\.org/(?:abs|pdf)/)(\d{4}\.\d{4,5})(?:v\d+)?")
+        .expect("valid arXiv pattern")
+});
+
+static URL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"https?://[^\s"'<>`]+"#).expect("valid URL pattern"));
+
+static BIBTEX_START_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"@([A-Za-z]+)\s*\{").expect("valid BibTeX pattern"));
+
+/// How a note cites a work.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CitationKind {
+    /// A Digital Object Identifier, lowercased (`10.1145/3292500.3330701`)
+    Doi,
+    /// A new-style arXiv identifier without its version (`1706.03762`)
+    Arxiv,
+    /// A web address other than a DOI or arXiv link
+    Url,
+    /// A BibTeX entry without a DOI or arXiv eprint, identified by its key
+    Bibtex,
+}
+
+impl CitationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Doi => "doi",
+            Self::Arxiv => "arxiv",
+            Self::Url => "url",
+            Self::Bibtex => "bibtex",
+        }
+    }
+}
+
+impl FromStr for CitationKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "doi" => Ok(Self::Doi),
+            "arxiv" => Ok(Self::Arxiv),
+            "url" => Ok(Self::Url),
+            "bibtex" => Ok(Self::Bibtex),
+            _ => Err(format!("unknown citation kind; value_len={}", value.len())),
+        }
+    }
+}
+
+/// Bibliographic details of a cited work; every field is optional.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ReferenceMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Authors as written, usually "Family, Given"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<i32>,
+    /// Journal, proceedings or book the work appeared in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub venue: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    /// BibTeX entry type (`article`, `inproceedings`, `misc`, ...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_type: Option<String>,
+}
+
+impl ReferenceMetadata {
+    /// Fill the fields that are missing here from `other`.
+    pub fn merge_missing(&mut self, other: &ReferenceMetadata) {
+        fn fill<T: Clone>(field: &mut Option<T>, other: &Option<T>) {
+            if field.is_none() {
+                field.clone_from(other);
+            }
+        }
+        fill(&mut self.title, &other.title);
+        if self.authors.is_empty() {
+            self.authors.clone_from(&other.authors);
+        }
+        fill(&mut self.year, &other.year);
+        fill(&mut self.venue, &other.venue);
+        fill(&mut self.publisher, &other.publisher);
+        fill(&mut self.url, &other.url);
+        fill(&mut self.doi, &other.doi);
+        fill(&mut self.entry_type, &other.entry_type);
+    }
+
+    /// Metadata from the `message` object of a Crossref works response.
+    pub fn from_crossref(message: &JsonValue) -> Self {
+        let first = |field: &str| {
+            message
+                .get(field)
+                .and_then(JsonValue::as_array)
+                .and_then(|values| values.first())
+                .and_then(JsonValue::as_str)
+                .and_then(clean_text)
+        };
+        let text = |field: &str| {
+            message
+                .get(field)
+                .and_then(JsonValue::as_str)
+                .and_then(clean_text)
+        };
+        let authors = message
+            .get("author")
+            .and_then(JsonValue::as_array)
+            .map(|authors| {
+                authors
+                    .iter()
+                    .filter_map(|author| {
+                        let family = author.get("family").and_then(JsonValue::as_str);
+                        let given = author.get("given").and_then(JsonValue::as_str);
+                        let name = author.get("name").and_then(JsonValue::as_str);
+                        match (family, given, name) {
+                            (Some(family), Some(given), _) => Some(format!("{family}, {given}")),
+                            (Some(family), None, _) => Some(family.to_string()),
+                            (None, _, Some(name)) => Some(name.to_string()),
+                            _ => None,
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let year = ["issued", "published-print", "published-online", "created"]
+            .iter()
+            .find_map(|field| {
+                message
+                    .get(*field)?
+                    .get("date-parts")?
+                    .get(0)?
+                    .get(0)?
+                    .as_i64()
+            })
+            .and_then(|year| i32::try_from(year).ok());
+        let entry_type = match message.get("type").and_then(JsonValue::as_str) {
+            Some("journal-article") => "article",
+            Some("proceedings-article") => "inproceedings",
+            Some("book") | Some("monograph") | Some("edited-book") => "book",
+            Some("book-chapter") | Some("book-section") => "incollection",
+            Some("dissertation") => "phdthesis",
+            Some("report") => "techreport",
+            _ => "misc",
+        };
+        Self {
+            title: first("title"),
+            authors,
+            year,
+            venue: first("container-title"),
+            publisher: text("publisher"),
+            url: text("URL"),
+            doi: text("DOI").map(|doi| doi.to_lowercase()),
+            entry_type: Some(entry_type.to_string()),
+        }
+    }
+}
+
+impl fmt::Debug for ReferenceMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReferenceMetadata")
+            .field("title_len", &self.title.as_ref().map(String::len))
+            .field("authors_count", &self.authors.len())
+            .field("year", &self.year)
+            .field("venue_len", &self.venue.as_ref().map(String::len))
+            .field("publisher_len", &self.publisher.as_ref().map(String::len))
+            .field("url_len", &self.url.as_ref().map(String::len))
+            .field("doi_set", &self.doi.is_some())
+            .field("entry_type", &self.entry_type)
+            .finish()
+    }
+}
+
+/// A work cited in a note, as found by [`extract_citations`].
+#[derive(Clone, PartialEq)]
+pub struct ExtractedCitation {
+    pub kind: CitationKind,
+    /// Normalized identifier: the DOI, arXiv ID, URL or BibTeX key
+    pub identifier: String,
+    /// Details from a BibTeX entry, if the note had one for this work
+    pub metadata: ReferenceMetadata,
+    pub bibtex_key: Option<String>,
+    /// Times the note cites the work
+    pub occurrences: i32,
+}
+
+impl fmt::Debug for ExtractedCitation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtractedCitation")
+            .field("kind", &self.kind)
+            .field("identifier_len", &self.identifier.len())
+            .field("metadata", &self.metadata)
+            .field("bibtex_key_set", &self.bibtex_key.is_some())
+            .field("occurrences", &self.occurrences)
+            .finish()
+    }
+}
+
+/// A stored reference. In a note's citations `occurrences` counts the
+/// note's mentions; in a collection's bibliography it counts citing notes.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Reference {
+    pub id: Uuid,
+    pub kind: CitationKind,
+    pub identifier: String,
+    pub metadata: ReferenceMetadata,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bibtex_key: Option<String>,
+    pub occurrences: i32,
+    /// When the metadata was completed from Crossref
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_at_utc: Option<DateTime<Utc>>,
+}
+
+impl fmt::Debug for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reference")
+            .field("id_set", &true)
+            .field("kind", &self.kind)
+            .field("identifier_len", &self.identifier.len())
+            .field("metadata", &self.metadata)
+            .field("bibtex_key_set", &self.bibtex_key.is_some())
+            .field("occurrences", &self.occurrences)
+            .field("resolved", &self.resolved_at_utc.is_some())
+            .finish()
+    }
+}
+
+/// The works a note cites.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct NoteCitations {
+    pub note_id: Uuid,
+    pub citations: Vec<Reference>,
+}
+
+impl fmt::Debug for NoteCitations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NoteCitations")
+            .field("citations", &self.citations)
+            .finish()
+    }
+}
+
+fn clean_text(value: &str) -> Option<String> {
+    let cleaned = value
+        .replace(['{', '}'], "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!cleaned.is_empty()).then_some(cleaned)
+}
+
+/// Drop trailing punctuation and closing brackets the match does not open.
+fn trim_identifier(raw: &str) -> &str {
+    let mut value = raw;
+    loop {
+        let trimmed = value.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '"', '*', '_']);
+        let unbalanced = |open: char, close: char| {
+            trimmed.ends_with(close)
+                && trimmed.matches(open).count() < trimmed.matches(close).count()
+        };
+        let trimmed = if unbalanced('(', ')') || unbalanced('[', ']') || unbalanced('{', '}') {
+            &trimmed[..trimmed.len() - 1]
+        } else {
+            trimmed
+        };
+        if trimmed == value {
+            return value;
+        }
+        value = trimmed;
+    }
+}
+
+/// Byte offset just past the brace that closes the one at `open`.
+fn closing_brace(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (offset, c) in text[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + offset + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Fields of a BibTeX entry body (`key, name = {value}, ...`), names lowercased.
+fn bibtex_fields(body: &str) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    let mut rest = body;
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq]
+            .rsplit(',')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+        let value_part = rest[eq + 1..].trim_start();
+        let (value, consumed) = if value_part.starts_with('{') {
+            match closing_brace(value_part, 0) {
+                Some(end) => (&value_part[1..end - 1], end),
+                None => break,
+            }
+        } else if let Some(quoted) = value_part.strip_prefix('"') {
+            match quoted.find('"') {
+                Some(end) => (&quoted[..end], end + 2),
+                None => break,
+            }
+        } else {
+            let end = value_part.find([',', '\n']).unwrap_or(value_part.len());
+            (value_part[..end].trim(), end)
+        };
+        if !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            fields.push((name, value.to_string()));
+        }
+        rest = &value_part[consumed..];
+    }
+    fields
+}
+
+fn bibtex_citation(entry_type: &str, key: &str, body: &str) -> ExtractedCitation {
+    let fields = bibtex_fields(body);
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .and_then(|(_, value)| clean_text(value))
+    };
+    let doi = field("doi").map(|doi| {
+        let doi = doi.to_lowercase();
+        doi.rsplit_once("doi.org/")
+            .map_or(doi.clone(), |(_, doi)| doi.to_string())
+    });
+    let arxiv = field("eprint").filter(|_| {
+        field("archiveprefix").is_some_and(|prefix| prefix.eq_ignore_ascii_case("arxiv"))
+            || field("eprinttype").is_some_and(|prefix| prefix.eq_ignore_ascii_case("arxiv"))
+    });
+    let entry_type = entry_type.to_lowercase();
+    let metadata = ReferenceMetadata {
+        title: field("title"),
+        authors: field("author")
+            .map(|authors| {
+                authors
+                    .split(" and ")
+                    .map(str::trim)
+                    .filter(|author| !author.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        year: field("year").and_then(|year| {
+            year.chars()
+                .filter(char::is_ascii_digit)
+                .take(4)
+                .collect::<String>()
+                .parse()
+                .ok()
+        }),
+        venue: field("journal").or_else(|| field("booktitle")),
+        publisher: field("publisher"),
+        url: field("url"),
+        doi: doi.clone(),
+        entry_type: Some(entry_type),
+    };
+    let (kind, identifier) = match (doi, arxiv) {
+        (Some(doi), _) => (CitationKind::Doi, doi),
+        (None, Some(arxiv)) => (
+            CitationKind::Arxiv,
+            arxiv
+                .split_once('v')
+                .map_or(arxiv.clone(), |(id, _)| id.to_string()),
+        ),
+        (None, None) => (CitationKind::Bibtex, key.to_string()),
+    };
+    ExtractedCitation {
+        kind,
+        identifier,
+        metadata,
+        bibtex_key: Some(key.to_string()),
+        occurrences: 1,
+    }
+}
+
+/// Find the works `text` cites, in order of first mention, each once.
+///
+/// BibTeX entries are read first and blanked out, so identifiers inside them
+/// are not counted again. DOIs and arXiv IDs are found bare, with a `doi:` or
+/// `arXiv:` prefix, or inside `doi.org` and `arxiv.org` links; other `http`
+/// and `https` links are URL citations. At most [`MAX_CITATIONS_PER_NOTE`]
+/// works are returned.
+pub fn extract_citations(text: &str) -> Vec<ExtractedCitation> {
+    let mut found: Vec<(usize, ExtractedCitation)> = Vec::new();
+    let mut masked = text.to_string();
+
+    let mut search_from = 0;
+    while let Some(start) = BIBTEX_START_RE.find_at(text, search_from) {
+        let entry_type = BIBTEX_START_RE
+            .captures_at(text, start.start())
+            .and_then(|captures| captures.get(1))
+            .map_or("", |m| m.as_str());
+        let open = start.end() - 1;
+        let Some(end) = closing_brace(text, open) else {
+            break;
+        };
+        search_from = end;
+        if ["comment", "string", "preamble"].contains(&entry_type.to_lowercase().as_str()) {
+            continue;
+        }
+        let inner = &text[open + 1..end - 1];
+        let Some((key, body)) = inner.split_once(',') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            continue;
+        }
+        found.push((start.start(), bibtex_citation(entry_type, key, body)));
+        masked.replace_range(start.start()..end, &" ".repeat(end - start.start()));
+    }
+
+    let mut claimed: Vec<(usize, usize)> = Vec::new();
+    for captures in ARXIV_RE.captures_iter(&masked) {
+        let whole = captures.get(0).expect("match");
+        claimed.push((whole.start(), whole.end()));
+        found.push((
+            whole.start(),
+            ExtractedCitation {
+                kind: CitationKind::Arxiv,
+                identifier: captures[1].to_string(),
+                metadata: ReferenceMetadata::default(),
+                bibtex_key: None,
+                occurrences: 1,
+            },
+        ));
+    }
+    for captures in DOI_RE.captures_iter(&masked) {
+        let doi = captures.get(1).expect("group");
+        let identifier = trim_identifier(doi.as_str());
+        claimed.push((doi.start(), doi.start() + identifier.len()));
+        found.push((
+            doi.start(),
+            ExtractedCitation {
+                kind: CitationKind::Doi,
+                identifier: identifier.to_lowercase(),
+                metadata: ReferenceMetadata::default(),
+                bibtex_key: None,
+                occurrences: 1,
+            },
+        ));
+    }
+    for url in URL_RE.find_iter(&masked) {
+        let identifier = trim_identifier(url.as_str());
+        let end = url.start() + identifier.len();
+        if claimed
+            .iter()
+            .any(|(start, stop)| *start < end && url.start() < *stop)
+            || identifier.len() <= "https://".len()
+        {
+            continue;
+        }
+        found.push((
+            url.start(),
+            ExtractedCitation {
+                kind: CitationKind::Url,
+                identifier: identifier.to_string(),
+                metadata: ReferenceMetadata::default(),
+                bibtex_key: None,
+                occurrences: 1,
+            },
+        ));
+    }
+
+    found.sort_by_key(|(position, _)| *position);
+    let mut citations: Vec<ExtractedCitation> = Vec::new();
+    for (_, citation) in found {
+        match citations
+            .iter()
+            .position(|c| c.kind == citation.kind && c.identifier == citation.identifier)
+        {
+            Some(index) => {
+                let existing = &mut citations[index];
+                existing.occurrences += 1;
+                existing.metadata.merge_missing(&citation.metadata);
+                if existing.bibtex_key.is_none() {
+                    existing.bibtex_key = citation.bibtex_key;
+                }
+            }
+            None if citations.len() < MAX_CITATIONS_PER_NOTE => citations.push(citation),
+            None => {}
+        }
+    }
+    citations
+}
+
+/// A value inside BibTeX braces; braces are dropped unless they balance.
+fn bibtex_value(value: &str) -> String {
+    let mut depth = 0i32;
+    let balanced = value.chars().all(|c| {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+        depth >= 0
+    }) && depth == 0;
+    let value = if balanced {
+        value.to_string()
+    } else {
+        value.replace(['{', '}'], "")
+    };
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A citation key from the first author's family name, the year and the
+/// first title word (`vaswani2017attention`), or `ref` when there are none.
+fn generated_key(reference: &Reference) -> String {
+    let word = |value: &str| -> String {
+        value
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase()
+    };
+    let author = reference
+        .metadata
+        .authors
+        .first()
+        .map(|author| {
+            let family = match author.split_once(',') {
+                Some((family, _)) => family,
+                None => author.split_whitespace().last().unwrap_or_default(),
+            };
+            word(family)
+        })
+        .unwrap_or_default();
+    let year = reference
+        .metadata
+        .year
+        .map(|year| year.to_string())
+        .unwrap_or_default();
+    let title = reference
+        .metadata
+        .title
+        .as_deref()
+        .and_then(|title| title.split_whitespace().map(word).find(|w| w.len() > 3))
+        .unwrap_or_default();
+    let key = format!("{author}{year}{title}");
+    if key.is_empty() {
+        "ref".to_string()
+    } else {
+        key
+    }
+}
+
+/// Render references as BibTeX, one entry each, with unique keys.
+///
+/// A reference keeps the key its BibTeX entry had; others get a key from
+/// author, year and title. Repeated keys get a letter suffix (`smith2020a`).
+pub fn render_bibtex(references: &[Reference]) -> String {
+    let mut used: BTreeSet<String> = BTreeSet::new();
+    let mut output = String::new();
+    for reference in references {
+        let base = reference
+            .bibtex_key
+            .clone()
+            .unwrap_or_else(|| generated_key(reference));
+        let mut key = base.clone();
+        let mut suffix = b'a';
+        while used.contains(&key) {
+            key = format!("{base}{}", suffix as char);
+            suffix = suffix.saturating_add(1);
+            if suffix > b'z' {
+                key = format!("{base}-{}", used.len());
+            }
+        }
+        used.insert(key.clone());
+
+        let metadata = &reference.metadata;
+        let entry_type = metadata.entry_type.clone().unwrap_or_else(|| {
+            match (reference.kind, metadata.venue.is_some()) {
+                (CitationKind::Doi, true) => "article".to_string(),
+                _ => "misc".to_string(),
+            }
+        });
+        let mut fields: Vec<(&str, String)> = Vec::new();
+        if !metadata.authors.is_empty() {
+            fields.push(("author", metadata.authors.join(" and ")));
+        }
+        if let Some(title) = &metadata.title {
+            fields.push(("title", title.clone()));
+        }
+        if let Some(venue) = &metadata.venue {
+            let field = match entry_type.as_str() {
+                "article" => "journal",
+                "inproceedings" | "incollection" => "booktitle",
+                _ => "howpublished",
+            };
+            fields.push((field, venue.clone()));
+        }
+        if let Some(publisher) = &metadata.publisher {
+            fields.push(("publisher", publisher.clone()));
+        }
+        if let Some(year) = metadata.year {
+            fields.push(("year", year.to_string()));
+        }
+        let doi = metadata.doi.clone().or_else(|| {
+            (reference.kind == CitationKind::Doi).then(|| reference.identifier.clone())
+        });
+        if let Some(doi) = doi {
+            fields.push(("doi", doi));
+        }
+        if reference.kind == CitationKind::Arxiv {
+            fields.push(("eprint", reference.identifier.clone()));
+            fields.push(("archiveprefix", "arXiv".to_string()));
+        }
+        let url = metadata.url.clone().or_else(|| {
+            (reference.kind == CitationKind::Url).then(|| reference.identifier.clone())
+        });
+        if let Some(url) = url {
+            fields.push(("url", url));
+        }
+
+        output.push_str(&format!("@{entry_type}{{{key},\n"));
+        for (name, value) in fields {
+            output.push_str(&format!("  {name} = {{{}}},\n", bibtex_value(&value)));
+        }
+        output.push_str("}\n\n");
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_identifiers_and_trims_punctuation() {
+        let text = "Per (doi:10.1000/XYZ.123), see https://doi.org/10.1000/xyz.123 and \
+                    [docs](https://example.com/a_(b)). Also arXiv:2101.00001v2.";
+        let found = extract_citations(text);
+        let pairs: Vec<(CitationKind, &str, i32)> = found
+            .iter()
+            .map(|c| (c.kind, c.identifier.as_str(), c.occurrences))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (CitationKind::Doi, "10.1000/xyz.123", 2),
+                (CitationKind::Url, "https://example.com/a_(b)", 1),
+                (CitationKind::Arxiv, "2101.00001", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn bibtex_entries_are_parsed_and_not_rescanned() {
+        let text = r#"
+Notes.
+
+@article{vaswani2017,
+  title = {Attention Is {All} You Need},
+  author = {Vaswani, Ashish and Shazeer, Noam},
+  journal = "NeurIPS",
+  year = 2017,
+  doi = {10.5555/3295222.3295349},
+  url = {https://example.org/paper}
+}
+
+@misc{blog, title = {A Post}, howpublished = {web}}
+@comment{ignored, title = {x}}
+"#;
+        let found = extract_citations(text);
+        assert_eq!(found.len(), 2);
+        let paper = &found[0];
+        assert_eq!(paper.kind, CitationKind::Doi);
+        assert_eq!(paper.identifier, "10.5555/3295222.3295349");
+        assert_eq!(paper.bibtex_key.as_deref(), Some("vaswani2017"));
+        assert_eq!(
+            paper.metadata.title.as_deref(),
+            Some("Attention Is All You Need")
+        );
+        assert_eq!(paper.metadata.authors.len(), 2);
+        assert_eq!(paper.metadata.year, Some(2017));
+        assert_eq!(paper.metadata.venue.as_deref(), Some("NeurIPS"));
+        assert_eq!(found[1].kind, CitationKind::Bibtex);
+        assert_eq!(found[1].identifier, "blog");
+    }
+
+    #[test]
+    fn crossref_message_maps_to_metadata() {
+        let message = serde_json::json!({
+            "DOI": "10.1000/ABC",
+            "type": "proceedings-article",
+            "title": ["Deep {Learning}"],
+            "author": [{"given": "Ada", "family": "Lovelace"}, {"name": "Consortium"}],
+            "container-title": ["Proc. Example"],
+            "publisher": "ACM",
+            "issued": {"date-parts": [[2019, 6]]},
+            "URL": "https://doi.org/10.1000/abc"
+        });
+        let metadata = ReferenceMetadata::from_crossref(&message);
+        assert_eq!(metadata.title.as_deref(), Some("Deep Learning"));
+        assert_eq!(metadata.authors, vec!["Lovelace, Ada", "Consortium"]);
+        assert_eq!(metadata.year, Some(2019));
+        assert_eq!(metadata.entry_type.as_deref(), Some("inproceedings"));
+        assert_eq!(metadata.doi.as_deref(), Some("10.1000/abc"));
+    }
+
+    #[test]
+    fn bibtex_rendering_generates_unique_keys() {
+        let reference = |kind, identifier: &str, metadata| Reference {
+            id: Uuid::nil(),
+            kind,
+            identifier: identifier.to_string(),
+            metadata,
+            bibtex_key: None,
+            occurrences: 1,
+            resolved_at_utc: None,
+        };
+        let metadata = ReferenceMetadata {
+            title: Some("The Art of Computing".to_string()),
+            authors: vec!["Knuth, Donald".to_string()],
+            year: Some(1968),
+            venue: Some("Journal {of Things".to_string()),
+            ..Default::default()
+        };
+        let bibtex = render_bibtex(&[
+            reference(CitationKind::Doi, "10.1/a", metadata.clone()),
+            reference(CitationKind::Doi, "10.1/b", metadata),
+            reference(
+                CitationKind::Arxiv,
+                "2101.00001",
+                ReferenceMetadata::default(),
+            ),
+        ]);
+        assert!(bibtex.contains("@article{knuth1968computing,"));
+        assert!(bibtex.contains("@article{knuth1968computinga,"));
+        assert!(bibtex.contains("journal = {Journal of Things},"));
+        assert!(bibtex.contains("doi = {10.1/b},"));
+        assert!(bibtex.contains("@misc{ref,\n  eprint = {2101.00001},"));
+    }
+
+    #[test]
+    fn debug_redacts_reference_details() {
+        let citation = &extract_citations("https://intranet.example/secret-plan")[0];
+        let debug = format!("{citation:?}");
+        assert!(debug.contains("identifier_len"));
+        assert!(!debug.contains("secret-plan"));
+    }
+}
@@ -0,0 +1,276 @@
+//! Smart collection rules.
+//!
+//! A collection can carry a rule: a stored filter over tags, document type,
+//! creation date and a full-text query. The `collection_rule_sync` job files
+//! every matching note that is not in a collection yet into the rule's
+//! collection, and takes back out the notes it filed that no longer match.
+//! Notes filed by hand, or filed elsewhere, are never moved.
+//!
+//! ```
+//! use matric_core::CollectionRuleFilter;
+//!
+//! let filter = CollectionRuleFilter {
+//!     tags: vec!["programming/rust".to_string()],
+//!     query: Some("async runtime".to_string()),
+//!     ..Default::default()
+//! };
+//! assert!(filter.validate().is_ok());
+//! assert!(CollectionRuleFilter::default().validate().is_err());
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// Most tags one rule can require.
+pub const MAX_COLLECTION_RULE_TAGS: usize = 20;
+
+/// Longest accepted rule query, in characters.
+pub const MAX_COLLECTION_RULE_QUERY_CHARS: usize = 500;
+
+/// Which notes a smart collection holds; a note must match every criterion set.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CollectionRuleFilter {
+    /// Notes must have every tag; a tag also matches its `/` children
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Name of the notes' document type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_type: Option<String>,
+    /// Notes created at or after this time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_after: Option<DateTime<Utc>>,
+    /// Notes created before this time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_before: Option<DateTime<Utc>>,
+    /// Full-text query (web search syntax) matched against title and content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+}
+
+impl CollectionRuleFilter {
+    /// Whether no criterion is set.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.document_type.is_none()
+            && self.created_after.is_none()
+            && self.created_before.is_none()
+            && self.query.is_none()
+    }
+
+    /// Trims the text criteria and drops the ones left empty.
+    pub fn normalized(mut self) -> Self {
+        self.tags = self
+            .tags
+            .iter()
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        self.document_type = self
+            .document_type
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
+        self.query = self
+            .query
+            .map(|query| query.trim().to_string())
+            .filter(|query| !query.is_empty());
+        self
+    }
+
+    /// Rejects an empty filter, too many tags, an overlong query or an
+    /// empty date range.
+    pub fn validate(&self) -> Result<()> {
+        if self.is_empty() {
+            return Err(Error::InvalidInput(
+                "a collection rule needs at least one of: tags, document_type, created_after, created_before, query".to_string(),
+            ));
+        }
+        if self.tags.len() > MAX_COLLECTION_RULE_TAGS {
+            return Err(Error::InvalidInput(format!(
+                "a collection rule can require at most {MAX_COLLECTION_RULE_TAGS} tags"
+            )));
+        }
+        if self.tags.iter().any(|tag| tag.trim().is_empty()) {
+            return Err(Error::InvalidInput(
+                "collection rule tags must not be empty".to_string(),
+            ));
+        }
+        if self
+            .query
+            .as_ref()
+            .is_some_and(|query| query.chars().count() > MAX_COLLECTION_RULE_QUERY_CHARS)
+        {
+            return Err(Error::InvalidInput(format!(
+                "collection rule query must be at most {MAX_COLLECTION_RULE_QUERY_CHARS} characters"
+            )));
+        }
+        if let (Some(after), Some(before)) = (self.created_after, self.created_before) {
+            if after >= before {
+                return Err(Error::InvalidInput(
+                    "created_after must be earlier than created_before".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CollectionRuleFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CollectionRuleFilter")
+            .field("tag_count", &self.tags.len())
+            .field(
+                "document_type_len",
+                &self.document_type.as_ref().map(String::len),
+            )
+            .field("created_after", &self.created_after)
+            .field("created_before", &self.created_before)
+            .field("query_len", &self.query.as_ref().map(String::len))
+            .finish()
+    }
+}
+
+/// A collection's smart rule.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct CollectionRule {
+    pub collection_id: Uuid,
+    #[sqlx(json)]
+    pub filter: CollectionRuleFilter,
+    /// Disabled rules are skipped by the periodic sync
+    pub enabled: bool,
+    /// Notes currently filed in the collection by the rule
+    pub member_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_synced_at_utc: Option<DateTime<Utc>>,
+    pub created_at_utc: DateTime<Utc>,
+    pub updated_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for CollectionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CollectionRule")
+            .field("collection_id_set", &true)
+            .field("filter", &self.filter)
+            .field("enabled", &self.enabled)
+            .field("member_count", &self.member_count)
+            .field("last_synced_at_utc", &self.last_synced_at_utc)
+            .finish()
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Request body for setting a collection's rule.
+#[derive(Clone, Deserialize, utoipa::ToSchema)]
+pub struct SetCollectionRuleRequest {
+    pub filter: CollectionRuleFilter,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+impl SetCollectionRuleRequest {
+    /// Normalizes and validates the filter.
+    pub fn validated_filter(&self) -> Result<CollectionRuleFilter> {
+        let filter = self.filter.clone().normalized();
+        filter.validate()?;
+        Ok(filter)
+    }
+}
+
+impl fmt::Debug for SetCollectionRuleRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SetCollectionRuleRequest")
+            .field("filter", &self.filter)
+            .field("enabled", &self.enabled)
+            .finish()
+    }
+}
+
+/// What one sync of a rule changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CollectionRuleSyncResult {
+    /// Notes matching the rule
+    pub matched: u64,
+    /// Notes filed into the collection
+    pub added: u64,
+    /// Notes the rule had filed that no longer match, taken back out
+    pub removed: u64,
+    /// Matching notes left alone because they are in another collection
+    pub skipped: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn normalized_drops_blank_criteria() {
+        let filter = CollectionRuleFilter {
+            tags: vec![" rust ".to_string(), "  ".to_string()],
+            document_type: Some(" ".to_string()),
+            query: Some("  tokio ".to_string()),
+            ..Default::default()
+        }
+        .normalized();
+
+        assert_eq!(filter.tags, vec!["rust"]);
+        assert_eq!(filter.document_type, None);
+        assert_eq!(filter.query.as_deref(), Some("tokio"));
+    }
+
+    #[test]
+    fn validate_rejects_empty_and_inverted_filters() {
+        assert!(CollectionRuleFilter::default().validate().is_err());
+
+        let after = Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap();
+        let inverted = CollectionRuleFilter {
+            created_after: Some(after),
+            created_before: Some(after),
+            ..Default::default()
+        };
+        assert!(inverted.validate().is_err());
+
+        let too_many_tags = CollectionRuleFilter {
+            tags: vec!["t".to_string(); MAX_COLLECTION_RULE_TAGS + 1],
+            ..Default::default()
+        };
+        assert!(too_many_tags.validate().is_err());
+
+        let date_only = CollectionRuleFilter {
+            created_after: Some(after),
+            ..Default::default()
+        };
+        assert!(date_only.validate().is_ok());
+    }
+
+    #[test]
+    fn set_request_defaults_to_enabled() {
+        let req: SetCollectionRuleRequest =
+            serde_json::from_str(r#"{"filter": {"document_type": "rust"}}"#).unwrap();
+        assert!(req.enabled);
+        assert_eq!(
+            req.validated_filter().unwrap().document_type.as_deref(),
+            Some("rust")
+        );
+    }
+
+    #[test]
+    fn filter_debug_redacts_values() {
+        let filter = CollectionRuleFilter {
+            tags: vec!["client/秘密".to_string()],
+            query: Some("sk-secret-query".to_string()),
+            ..Default::default()
+        };
+        let debug = format!("{filter:?}");
+        assert!(debug.contains("tag_count: 1"));
+        assert!(!debug.contains("秘密"));
+        assert!(!debug.contains("sk-secret-query"));
+    }
+}
@@ -0,0 +1,244 @@
+//! Collection subtree operations.
+//!
+//! A collection carries its descendants along when it is moved or copied.
+//! Every operation runs in one transaction and refuses to make a collection
+//! its own ancestor. Collection names are unique within an archive, so copies
+//! are named `"<name> (copy)"`, `"<name> (copy 2)"` and so on.
+//!
+//! A note belongs to a single collection, so copying a subtree copies its
+//! notes too. In [`CollectionCopyMode::Duplicate`] the copies are independent;
+//! in [`CollectionCopyMode::Link`] each copy keeps a `copied_from` link to the
+//! note it was made from.
+//!
+//! ```
+//! use matric_core::{collection_copy_name, CopyCollectionRequest};
+//!
+//! assert_eq!(collection_copy_name("Research", 1), "Research (copy)");
+//! assert_eq!(collection_copy_name("Research", 3), "Research (copy 3)");
+//!
+//! let req: CopyCollectionRequest = serde_json::from_str(r#"{"mode": "link"}"#).unwrap();
+//! assert!(req.validated_name().unwrap().is_none());
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// Most notes one subtree copy will duplicate.
+pub const MAX_COLLECTION_COPY_NOTES: i64 = 5000;
+
+/// Most collections one bulk re-parent request can move.
+pub const MAX_REPARENT_COLLECTIONS: usize = 500;
+
+/// Link kind recorded from a [`CollectionCopyMode::Link`] copy to its source note.
+pub const COPIED_FROM_LINK_KIND: &str = "copied_from";
+
+/// Name of the `attempt`-th candidate for a copy of `name`, counting from 1.
+pub fn collection_copy_name(name: &str, attempt: u32) -> String {
+    if attempt <= 1 {
+        format!("{name} (copy)")
+    } else {
+        format!("{name} (copy {attempt})")
+    }
+}
+
+/// How a subtree copy carries its notes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionCopyMode {
+    /// Copy each note and link the copy back to its source note
+    Link,
+    /// Copy each note with no tie to its source note
+    #[default]
+    Duplicate,
+}
+
+/// Request body for moving a collection and its descendants.
+#[derive(Clone, Deserialize, utoipa::ToSchema)]
+pub struct MoveCollectionRequest {
+    /// New parent; absent or null moves the collection to the root
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+}
+
+impl fmt::Debug for MoveCollectionRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MoveCollectionRequest")
+            .field("parent_id_set", &self.parent_id.is_some())
+            .finish()
+    }
+}
+
+/// Request body for copying a collection, its descendants and their notes.
+#[derive(Clone, Deserialize, utoipa::ToSchema)]
+pub struct CopyCollectionRequest {
+    /// Parent of the copy; absent or null copies to the root
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    #[serde(default)]
+    pub mode: CollectionCopyMode,
+    /// Name of the top copied collection; defaults to `"<name> (copy)"`
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl CopyCollectionRequest {
+    /// The trimmed name, `None` when absent or blank.
+    pub fn validated_name(&self) -> Result<Option<String>> {
+        let name = self
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty());
+        if name.is_some_and(|name| name.chars().count() > 255) {
+            return Err(Error::InvalidInput(
+                "collection name must be at most 255 characters".to_string(),
+            ));
+        }
+        Ok(name.map(str::to_string))
+    }
+}
+
+impl fmt::Debug for CopyCollectionRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CopyCollectionRequest")
+            .field("parent_id_set", &self.parent_id.is_some())
+            .field("mode", &self.mode)
+            .field("name_len", &self.name.as_ref().map(String::len))
+            .finish()
+    }
+}
+
+/// What a subtree copy created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CollectionCopyResult {
+    /// The copy of the requested collection
+    pub collection_id: Uuid,
+    pub mode: CollectionCopyMode,
+    /// Collections created, the top copy included
+    pub collections_copied: u64,
+    /// Notes copied into the new collections
+    pub notes_copied: u64,
+}
+
+/// Request body for moving several collections under one parent, such as
+/// the children a deleted collection left at the root.
+#[derive(Clone, Deserialize, utoipa::ToSchema)]
+pub struct ReparentCollectionsRequest {
+    pub collection_ids: Vec<Uuid>,
+    /// New parent; absent or null moves the collections to the root
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+}
+
+impl ReparentCollectionsRequest {
+    /// The collection IDs without repeats, in request order.
+    pub fn validated_ids(&self) -> Result<Vec<Uuid>> {
+        let mut ids = Vec::with_capacity(self.collection_ids.len());
+        for id in &self.collection_ids {
+            if !ids.contains(id) {
+                ids.push(*id);
+            }
+        }
+        if ids.is_empty() {
+            return Err(Error::InvalidInput(
+                "collection_ids must name at least one collection".to_string(),
+            ));
+        }
+        if ids.len() > MAX_REPARENT_COLLECTIONS {
+            return Err(Error::InvalidInput(format!(
+                "at most {MAX_REPARENT_COLLECTIONS} collections can be re-parented at once"
+            )));
+        }
+        Ok(ids)
+    }
+}
+
+impl fmt::Debug for ReparentCollectionsRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReparentCollectionsRequest")
+            .field("collection_count", &self.collection_ids.len())
+            .field("parent_id_set", &self.parent_id.is_some())
+            .finish()
+    }
+}
+
+/// What a bulk re-parent changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CollectionReparentResult {
+    /// Collections whose parent changed
+    pub moved: u64,
+    /// Collections already under the requested parent
+    pub unchanged: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copy_request_defaults_to_duplicate_at_root() {
+        let req: CopyCollectionRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(req.mode, CollectionCopyMode::Duplicate);
+        assert!(req.parent_id.is_none());
+        assert_eq!(req.validated_name().unwrap(), None);
+    }
+
+    #[test]
+    fn copy_request_trims_and_bounds_name() {
+        let req: CopyCollectionRequest =
+            serde_json::from_str(r#"{"name": "  Archive 2026 ", "mode": "link"}"#).unwrap();
+        assert_eq!(req.mode, CollectionCopyMode::Link);
+        assert_eq!(
+            req.validated_name().unwrap().as_deref(),
+            Some("Archive 2026")
+        );
+
+        let long = CopyCollectionRequest {
+            parent_id: None,
+            mode: CollectionCopyMode::Duplicate,
+            name: Some("n".repeat(256)),
+        };
+        assert!(long.validated_name().is_err());
+    }
+
+    #[test]
+    fn reparent_request_dedupes_and_bounds_ids() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let req = ReparentCollectionsRequest {
+            collection_ids: vec![a, b, a],
+            parent_id: None,
+        };
+        assert_eq!(req.validated_ids().unwrap(), vec![a, b]);
+
+        let empty = ReparentCollectionsRequest {
+            collection_ids: vec![],
+            parent_id: None,
+        };
+        assert!(empty.validated_ids().is_err());
+
+        let too_many = ReparentCollectionsRequest {
+            collection_ids: (0..=MAX_REPARENT_COLLECTIONS)
+                .map(|_| Uuid::new_v4())
+                .collect(),
+            parent_id: None,
+        };
+        assert!(too_many.validated_ids().is_err());
+    }
+
+    #[test]
+    fn copy_request_debug_redacts_name() {
+        let req = CopyCollectionRequest {
+            parent_id: None,
+            mode: CollectionCopyMode::Link,
+            name: Some("Client 秘密".to_string()),
+        };
+        let debug = format!("{req:?}");
+        assert!(debug.contains("name_len"));
+        assert!(!debug.contains("秘密"));
+    }
+}
@@ -0,0 +1,320 @@
+//! SKOS concept merge and split.
+//!
+//! A merge folds one or more source concepts into a target: their notes are
+//! retagged, their labels become alternative labels of the target, their
+//! relations, mappings and collection memberships are re-pointed, and each
+//! source is kept as an obsolete alias whose `replaced_by_id` names the
+//! target. A split distributes the notes of one concept over several target
+//! concepts, new or existing, by [`ConceptSplitRule`]. Both record entries in
+//! the SKOS audit log.
+//!
+//! ```
+//! use matric_core::SplitConceptRequest;
+//!
+//! let split: SplitConceptRequest = serde_json::from_str(
+//!     r#"{"targets": [{"pref_label": "Rust async", "rule": {"title_contains": "tokio"}}]}"#,
+//! )
+//! .unwrap();
+//! assert!(split.validate().is_ok());
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, MergeConceptsRequest, Result};
+
+/// Most source concepts one merge may fold into its target.
+pub const MAX_MERGE_SOURCE_CONCEPTS: usize = 50;
+
+/// Most target concepts one split may distribute notes over.
+pub const MAX_SPLIT_TARGETS: usize = 20;
+
+/// Most note IDs one split rule may name.
+pub const MAX_SPLIT_RULE_NOTE_IDS: usize = 1_000;
+
+impl MergeConceptsRequest {
+    /// Check at least one source is given, sources are distinct and the
+    /// target is not among them.
+    pub fn validate(&self) -> Result<()> {
+        if self.source_ids.is_empty() {
+            return Err(Error::InvalidInput(
+                "source_ids must not be empty".to_string(),
+            ));
+        }
+        if self.source_ids.len() > MAX_MERGE_SOURCE_CONCEPTS {
+            return Err(Error::InvalidInput(format!(
+                "At most {MAX_MERGE_SOURCE_CONCEPTS} source concepts may be merged at once"
+            )));
+        }
+        if self.source_ids.contains(&self.target_id) {
+            return Err(Error::InvalidInput(
+                "target_id must not be one of source_ids".to_string(),
+            ));
+        }
+        let distinct: HashSet<_> = self.source_ids.iter().collect();
+        if distinct.len() != self.source_ids.len() {
+            return Err(Error::InvalidInput(
+                "source_ids must not repeat".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What a concept merge changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ConceptMergeResult {
+    /// ID of the merge history record
+    pub merge_id: Uuid,
+    pub target_id: Uuid,
+    /// Concepts now obsolete aliases of the target
+    pub source_ids: Vec<Uuid>,
+    /// Notes that were tagged with a source concept
+    pub notes_retagged: i64,
+    /// Source labels added to the target as alternative labels
+    pub labels_added: i64,
+    /// Semantic relations, mappings and collection memberships moved to the target
+    pub relations_moved: i64,
+    /// Relations dropped because the target already had them or they would
+    /// break the hierarchy limits
+    pub relations_dropped: i64,
+}
+
+/// Which of a concept's notes a split target receives.
+///
+/// A note must match every selector given.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ConceptSplitRule {
+    /// Notes with these IDs
+    #[serde(default)]
+    pub note_ids: Vec<Uuid>,
+    /// Notes also tagged with all of these concepts
+    #[serde(default)]
+    pub concept_ids: Vec<Uuid>,
+    /// Notes whose title contains this text, ignoring case
+    #[serde(default)]
+    pub title_contains: Option<String>,
+}
+
+impl fmt::Debug for ConceptSplitRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConceptSplitRule")
+            .field("note_ids_count", &self.note_ids.len())
+            .field("concept_ids_count", &self.concept_ids.len())
+            .field(
+                "title_contains_len",
+                &self.title_contains.as_ref().map(String::len),
+            )
+            .finish()
+    }
+}
+
+impl ConceptSplitRule {
+    fn validate(&self) -> Result<()> {
+        let title_set = self
+            .title_contains
+            .as_ref()
+            .is_some_and(|text| !text.trim().is_empty());
+        if self.note_ids.is_empty() && self.concept_ids.is_empty() && !title_set {
+            return Err(Error::InvalidInput(
+                "rule requires note_ids, concept_ids or title_contains".to_string(),
+            ));
+        }
+        if self.note_ids.len() > MAX_SPLIT_RULE_NOTE_IDS {
+            return Err(Error::InvalidInput(format!(
+                "rule may name at most {MAX_SPLIT_RULE_NOTE_IDS} note_ids"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// One concept a split moves notes to.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ConceptSplitTarget {
+    /// Existing concept to move notes to
+    #[serde(default)]
+    pub concept_id: Option<Uuid>,
+    /// Preferred label of the concept to move notes to; a concept with this
+    /// label in the source's scheme is reused, otherwise one is created
+    #[serde(default)]
+    pub pref_label: Option<String>,
+    pub rule: ConceptSplitRule,
+}
+
+impl fmt::Debug for ConceptSplitTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConceptSplitTarget")
+            .field("concept_id_set", &self.concept_id.is_some())
+            .field("pref_label_len", &self.pref_label.as_ref().map(String::len))
+            .field("rule", &self.rule)
+            .finish()
+    }
+}
+
+/// Request to split a concept.
+///
+/// Each of the concept's notes moves to the first target whose rule it
+/// matches; notes matching no rule keep the source concept.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SplitConceptRequest {
+    pub targets: Vec<ConceptSplitTarget>,
+    /// Mark the source concept deprecated once its notes are distributed
+    #[serde(default)]
+    pub deprecate_source: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performed_by: Option<String>,
+}
+
+impl fmt::Debug for SplitConceptRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SplitConceptRequest")
+            .field("targets", &self.targets)
+            .field("deprecate_source", &self.deprecate_source)
+            .field("reason_len", &self.reason.as_ref().map(String::len))
+            .field(
+                "performed_by_len",
+                &self.performed_by.as_ref().map(String::len),
+            )
+            .finish()
+    }
+}
+
+impl SplitConceptRequest {
+    /// Check there are targets, each names exactly one of `concept_id` and
+    /// `pref_label`, and each rule selects something.
+    pub fn validate(&self) -> Result<()> {
+        if self.targets.is_empty() {
+            return Err(Error::InvalidInput("targets must not be empty".to_string()));
+        }
+        if self.targets.len() > MAX_SPLIT_TARGETS {
+            return Err(Error::InvalidInput(format!(
+                "A split may have at most {MAX_SPLIT_TARGETS} targets"
+            )));
+        }
+        for target in &self.targets {
+            let label_set = target
+                .pref_label
+                .as_ref()
+                .is_some_and(|label| !label.trim().is_empty());
+            if target.concept_id.is_some() == label_set {
+                return Err(Error::InvalidInput(
+                    "Each target needs exactly one of concept_id and pref_label".to_string(),
+                ));
+            }
+            target.rule.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// Notes one split target received.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ConceptSplitOutcome {
+    pub concept_id: Uuid,
+    /// Whether the split created the concept
+    pub created: bool,
+    pub note_ids: Vec<Uuid>,
+}
+
+/// What a concept split changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ConceptSplitResult {
+    pub source_id: Uuid,
+    /// One entry per request target, in request order
+    pub targets: Vec<ConceptSplitOutcome>,
+    /// Notes moved off the source concept
+    pub notes_moved: i64,
+    /// Notes still tagged with the source concept
+    pub notes_remaining: i64,
+    pub source_deprecated: bool,
+}
+
+/// A model-proposed split, ready to review and submit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ConceptSplitSuggestion {
+    pub source_id: Uuid,
+    /// Model that proposed the split
+    pub model: String,
+    /// Tagged notes shown to the model
+    pub notes_considered: usize,
+    /// Request body for the split endpoint; each target selects its notes by ID
+    pub split: SplitConceptRequest,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid_message(result: Result<()>) -> String {
+        match result {
+            Err(Error::InvalidInput(message)) => message,
+            other => panic!("expected invalid input, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn merge_rejects_target_among_sources() {
+        let target = Uuid::new_v4();
+        let req = MergeConceptsRequest {
+            source_ids: vec![Uuid::new_v4(), target],
+            target_id: target,
+            reason: None,
+            performed_by: None,
+        };
+        assert!(invalid_message(req.validate()).contains("target_id"));
+
+        let source = Uuid::new_v4();
+        let req = MergeConceptsRequest {
+            source_ids: vec![source, source],
+            target_id: target,
+            reason: None,
+            performed_by: None,
+        };
+        assert!(invalid_message(req.validate()).contains("repeat"));
+    }
+
+    #[test]
+    fn split_targets_need_one_destination_and_a_rule() {
+        let req: SplitConceptRequest = serde_json::from_str(
+            r#"{"targets": [{"pref_label": "a", "rule": {"title_contains": "  "}}]}"#,
+        )
+        .unwrap();
+        assert!(invalid_message(req.validate()).contains("rule requires"));
+
+        let req: SplitConceptRequest = serde_json::from_str(&format!(
+            r#"{{"targets": [{{"concept_id": "{}", "pref_label": "a", "rule": {{"note_ids": ["{}"]}}}}]}}"#,
+            Uuid::nil(),
+            Uuid::nil()
+        ))
+        .unwrap();
+        assert!(invalid_message(req.validate()).contains("exactly one"));
+
+        assert!(invalid_message(SplitConceptRequest::default().validate()).contains("targets"));
+    }
+
+    #[test]
+    fn split_debug_redacts_labels() {
+        let req = SplitConceptRequest {
+            targets: vec![ConceptSplitTarget {
+                concept_id: None,
+                pref_label: Some("project-falcon".to_string()),
+                rule: ConceptSplitRule {
+                    title_contains: Some("falcon".to_string()),
+                    ..Default::default()
+                },
+            }],
+            reason: Some("client request".to_string()),
+            ..Default::default()
+        };
+        let debug = format!("{req:?}");
+        assert!(!debug.contains("falcon"));
+        assert!(!debug.contains("client"));
+        assert!(debug.contains("pref_label_len: Some(14)"));
+    }
+}
@@ -0,0 +1,245 @@
+//! SKOS mapping relations to external vocabularies.
+//!
+//! A mapping links a local concept to a concept in another scheme by URI
+//! with `skos:exactMatch`, `skos:closeMatch`, `skos:broadMatch`,
+//! `skos:narrowMatch` or `skos:relatedMatch`. Mappings are written into the
+//! Turtle export, and on knowledge shard import an imported concept that
+//! shares an exact-match URI with a local concept is merged into it as a
+//! [`ConceptReconciliation`].
+//!
+//! ```
+//! use matric_core::concept_mapping::is_valid_mapping_uri;
+//!
+//! assert!(is_valid_mapping_uri("http://www.wikidata.org/entity/Q575650"));
+//! assert!(!is_valid_mapping_uri("not a uri"));
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{CreateMappingRelationRequest, Error, Result, SkosMappingRelation};
+
+/// Longest target URI accepted for a mapping.
+pub const MAX_MAPPING_URI_LEN: usize = 2_048;
+
+/// Longest target label accepted for a mapping.
+pub const MAX_MAPPING_LABEL_CHARS: usize = 500;
+
+impl SkosMappingRelation {
+    /// The SKOS property the relation is written as (`skos:exactMatch`, ...).
+    pub const fn skos_property(self) -> &'static str {
+        match self {
+            Self::ExactMatch => "skos:exactMatch",
+            Self::CloseMatch => "skos:closeMatch",
+            Self::BroadMatch => "skos:broadMatch",
+            Self::NarrowMatch => "skos:narrowMatch",
+            Self::RelatedMatch => "skos:relatedMatch",
+        }
+    }
+}
+
+/// Whether `uri` is an absolute IRI that can be written between `<` and `>`
+/// in Turtle: a scheme, a non-empty remainder, and no whitespace, control
+/// characters or IRI delimiters.
+pub fn is_valid_mapping_uri(uri: &str) -> bool {
+    if uri.is_empty() || uri.len() > MAX_MAPPING_URI_LEN {
+        return false;
+    }
+    let Some((scheme, rest)) = uri.split_once(':') else {
+        return false;
+    };
+    let mut scheme_chars = scheme.chars();
+    let scheme_ok = scheme_chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && scheme_chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    scheme_ok
+        && !rest.is_empty()
+        && !uri.chars().any(|c| {
+            c.is_whitespace()
+                || c.is_control()
+                || matches!(c, '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\')
+        })
+}
+
+fn validate_target_uri(field: &str, uri: &str) -> Result<()> {
+    if is_valid_mapping_uri(uri) {
+        Ok(())
+    } else {
+        Err(Error::InvalidInput(format!(
+            "{field} must be an absolute URI of at most {MAX_MAPPING_URI_LEN} bytes"
+        )))
+    }
+}
+
+fn validate_target_label(label: Option<&str>) -> Result<()> {
+    if label.is_some_and(|label| label.chars().count() > MAX_MAPPING_LABEL_CHARS) {
+        return Err(Error::InvalidInput(format!(
+            "target_label must be at most {MAX_MAPPING_LABEL_CHARS} characters"
+        )));
+    }
+    Ok(())
+}
+
+fn validate_confidence(confidence: Option<f32>) -> Result<()> {
+    if confidence.is_some_and(|c| !(0.0..=1.0).contains(&c)) {
+        return Err(Error::InvalidInput(
+            "confidence must be between 0 and 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl CreateMappingRelationRequest {
+    /// Check the target URIs are absolute, the label is not too long and the
+    /// confidence lies in `0..=1`.
+    pub fn validate(&self) -> Result<()> {
+        validate_target_uri("target_uri", &self.target_uri)?;
+        if let Some(scheme_uri) = &self.target_scheme_uri {
+            validate_target_uri("target_scheme_uri", scheme_uri)?;
+        }
+        validate_target_label(self.target_label.as_deref())?;
+        validate_confidence(self.confidence)
+    }
+}
+
+/// Changes to a mapping relation; omitted fields are left as they are.
+///
+/// The target URI is the mapping's identity and cannot change; remove the
+/// mapping and add a new one instead.
+#[derive(Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct UpdateMappingRelationRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation_type: Option<SkosMappingRelation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_scheme_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    /// Mark the mapping as validated by this reviewer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validated_by: Option<String>,
+}
+
+impl UpdateMappingRelationRequest {
+    /// Check at least one field is given and each given field is valid.
+    pub fn validate(&self) -> Result<()> {
+        if self.relation_type.is_none()
+            && self.target_scheme_uri.is_none()
+            && self.target_label.is_none()
+            && self.confidence.is_none()
+            && self.validated_by.is_none()
+        {
+            return Err(Error::InvalidInput(
+                "At least one mapping field must be given".to_string(),
+            ));
+        }
+        if let Some(scheme_uri) = &self.target_scheme_uri {
+            validate_target_uri("target_scheme_uri", scheme_uri)?;
+        }
+        validate_target_label(self.target_label.as_deref())?;
+        validate_confidence(self.confidence)
+    }
+}
+
+impl fmt::Debug for UpdateMappingRelationRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpdateMappingRelationRequest")
+            .field("relation_type", &self.relation_type)
+            .field(
+                "target_scheme_uri_len",
+                &self.target_scheme_uri.as_ref().map(String::len),
+            )
+            .field(
+                "target_label_len",
+                &self.target_label.as_ref().map(String::len),
+            )
+            .field("confidence", &self.confidence)
+            .field("validated_by_set", &self.validated_by.is_some())
+            .finish()
+    }
+}
+
+/// An imported concept merged into the local concept it exact-matches.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ConceptReconciliation {
+    /// The imported concept, now an obsolete alias
+    pub concept_id: Uuid,
+    /// The local concept it was merged into
+    pub reconciled_into: Uuid,
+    /// The URI both concepts identify with
+    pub match_uri: String,
+    /// ID of the merge history record
+    pub merge_id: Uuid,
+}
+
+impl fmt::Debug for ConceptReconciliation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConceptReconciliation")
+            .field("concept_id", &self.concept_id)
+            .field("reconciled_into", &self.reconciled_into)
+            .field("match_uri_len", &self.match_uri.len())
+            .field("merge_id", &self.merge_id)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping_uris_must_be_absolute_iris() {
+        assert!(is_valid_mapping_uri(
+            "https://id.loc.gov/authorities/subjects/sh85118553"
+        ));
+        assert!(is_valid_mapping_uri(
+            "urn:uuid:0191a5b2-0000-7000-8000-000000000000"
+        ));
+        for invalid in [
+            "",
+            "relative/path",
+            "1http://example.org",
+            "http:",
+            "http://example.org/a b",
+            "http://example.org/<x>",
+            "http://example.org/\"q\"",
+        ] {
+            assert!(!is_valid_mapping_uri(invalid), "{invalid:?}");
+        }
+        assert!(!is_valid_mapping_uri(&format!(
+            "http://example.org/{}",
+            "a".repeat(MAX_MAPPING_URI_LEN)
+        )));
+    }
+
+    #[test]
+    fn create_and_update_requests_validate_fields() {
+        let mut create = CreateMappingRelationRequest {
+            concept_id: Uuid::nil(),
+            target_uri: "http://www.wikidata.org/entity/Q575650".to_string(),
+            target_scheme_uri: None,
+            target_label: Some("Rust".to_string()),
+            relation_type: SkosMappingRelation::ExactMatch,
+            confidence: Some(0.9),
+        };
+        assert!(create.validate().is_ok());
+        create.confidence = Some(1.5);
+        assert!(create.validate().is_err());
+        create.confidence = None;
+        create.target_scheme_uri = Some("wikidata".to_string());
+        assert!(create.validate().is_err());
+
+        assert!(UpdateMappingRelationRequest::default().validate().is_err());
+        let update = UpdateMappingRelationRequest {
+            relation_type: Some(SkosMappingRelation::CloseMatch),
+            ..Default::default()
+        };
+        assert!(update.validate().is_ok());
+        assert_eq!(
+            SkosMappingRelation::CloseMatch.skos_property(),
+            "skos:closeMatch"
+        );
+    }
+}
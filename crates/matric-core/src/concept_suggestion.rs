@@ -0,0 +1,298 @@
+//! Concept suggestion review queue.
+//!
+//! With `CONCEPT_REVIEW_MODE` on, concept tagging scores each suggested
+//! concept and only tags the note when the score reaches the concept's review
+//! threshold. Lower-scoring suggestions are held as pending
+//! [`ConceptSuggestion`]s until a reviewer accepts or rejects them. Each
+//! review moves the concept's threshold by [`CONCEPT_REVIEW_THRESHOLD_STEP`]:
+//! down after an accept, so more of its suggestions apply directly, and up
+//! after a reject, so more are held.
+//!
+//! ```
+//! use matric_core::concept_suggestion::{adjusted_review_threshold, needs_review};
+//!
+//! assert!(needs_review(0.6, 0.7));
+//! let lowered = adjusted_review_threshold(0.7, true);
+//! assert!(!needs_review(0.65, lowered));
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::defaults::RELEVANCE_DECAY_FACTOR;
+
+/// How far one review moves a concept's threshold.
+pub const CONCEPT_REVIEW_THRESHOLD_STEP: f32 = 0.05;
+
+/// Lowest threshold reviews can bring a concept to.
+pub const MIN_CONCEPT_REVIEW_THRESHOLD: f32 = 0.05;
+
+/// Highest threshold; a concept at this threshold has every suggestion reviewed.
+pub const MAX_CONCEPT_REVIEW_THRESHOLD: f32 = 1.0;
+
+/// Scale applied to a suggestion whose label does not occur in the note.
+pub const UNEVIDENCED_CONFIDENCE_FACTOR: f32 = 0.75;
+
+/// Characters of context kept on each side of the matched label in an
+/// evidence snippet.
+const EVIDENCE_CONTEXT_CHARS: usize = 80;
+
+/// Review state of a concept suggestion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConceptSuggestionStatus {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+impl ConceptSuggestionStatus {
+    pub const ALL: [ConceptSuggestionStatus; 3] = [
+        ConceptSuggestionStatus::Pending,
+        ConceptSuggestionStatus::Accepted,
+        ConceptSuggestionStatus::Rejected,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ConceptSuggestionStatus::Pending => "pending",
+            ConceptSuggestionStatus::Accepted => "accepted",
+            ConceptSuggestionStatus::Rejected => "rejected",
+        }
+    }
+}
+
+impl fmt::Display for ConceptSuggestionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ConceptSuggestionStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == value)
+            .ok_or_else(|| format!("unknown concept suggestion status: {value}"))
+    }
+}
+
+impl TryFrom<String> for ConceptSuggestionStatus {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// A concept suggested for a note by concept tagging.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct ConceptSuggestion {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub concept_id: Uuid,
+    /// Preferred label of the suggested concept
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pref_label: Option<String>,
+    /// Score the tagger gave the suggestion, 0 to 1
+    pub confidence: f32,
+    /// The concept's review threshold when the suggestion was made
+    pub threshold: f32,
+    /// Text around the first mention of the concept's label in the note
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<String>,
+    /// Extraction method that produced the suggestion (`gliner`, `fast_model`, ...)
+    pub extraction_method: String,
+    #[sqlx(try_from = "String")]
+    pub status: ConceptSuggestionStatus,
+    pub created_at_utc: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_at_utc: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_by: Option<String>,
+}
+
+impl fmt::Debug for ConceptSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConceptSuggestion")
+            .field("id", &self.id)
+            .field("confidence", &self.confidence)
+            .field("threshold", &self.threshold)
+            .field("pref_label_len", &self.pref_label.as_ref().map(String::len))
+            .field("evidence_len", &self.evidence.as_ref().map(String::len))
+            .field("extraction_method", &self.extraction_method)
+            .field("status", &self.status)
+            .field("reviewed_by_set", &self.reviewed_by.is_some())
+            .finish()
+    }
+}
+
+/// A suggestion held for review by concept tagging.
+#[derive(Clone, PartialEq)]
+pub struct NewConceptSuggestion {
+    pub note_id: Uuid,
+    pub concept_id: Uuid,
+    pub confidence: f32,
+    /// The concept's review threshold the confidence fell short of
+    pub threshold: f32,
+    /// Relevance and primary flag the note is tagged with if the suggestion is accepted
+    pub relevance_score: f32,
+    pub is_primary: bool,
+    pub evidence: Option<String>,
+    pub extraction_method: String,
+}
+
+impl fmt::Debug for NewConceptSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NewConceptSuggestion")
+            .field("confidence", &self.confidence)
+            .field("threshold", &self.threshold)
+            .field("relevance_score", &self.relevance_score)
+            .field("is_primary", &self.is_primary)
+            .field("evidence_len", &self.evidence.as_ref().map(String::len))
+            .field("extraction_method", &self.extraction_method)
+            .finish()
+    }
+}
+
+/// A concept's review threshold and the reviews that shaped it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct ConceptReviewThreshold {
+    pub concept_id: Uuid,
+    /// Confidence a suggestion of this concept needs to be applied without review
+    pub threshold: f32,
+    pub accepted_count: i32,
+    pub rejected_count: i32,
+}
+
+/// Outcome of accepting or rejecting a suggestion.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ConceptSuggestionReview {
+    pub suggestion: ConceptSuggestion,
+    /// The concept's threshold after this review
+    pub threshold: ConceptReviewThreshold,
+}
+
+/// Score a concept suggestion from its rank in the tagger's reply and whether
+/// its label occurs in the note.
+///
+/// Taggers list the most relevant concepts first, so the score falls by
+/// [`RELEVANCE_DECAY_FACTOR`] per rank; suggestions without evidence in the
+/// text are scaled by [`UNEVIDENCED_CONFIDENCE_FACTOR`].
+pub fn suggestion_confidence(rank: usize, has_evidence: bool) -> f32 {
+    let relevance = (1.0 - rank as f32 * RELEVANCE_DECAY_FACTOR).max(0.0);
+    if has_evidence {
+        relevance
+    } else {
+        relevance * UNEVIDENCED_CONFIDENCE_FACTOR
+    }
+}
+
+/// Whether a suggestion scoring `confidence` is held for review.
+pub fn needs_review(confidence: f32, threshold: f32) -> bool {
+    threshold >= MAX_CONCEPT_REVIEW_THRESHOLD || confidence < threshold
+}
+
+/// Threshold after one review: lowered after an accept, raised after a reject.
+pub fn adjusted_review_threshold(threshold: f32, accepted: bool) -> f32 {
+    let step = if accepted {
+        -CONCEPT_REVIEW_THRESHOLD_STEP
+    } else {
+        CONCEPT_REVIEW_THRESHOLD_STEP
+    };
+    let adjusted =
+        (threshold + step).clamp(MIN_CONCEPT_REVIEW_THRESHOLD, MAX_CONCEPT_REVIEW_THRESHOLD);
+    // Keep repeated steps from drifting off the 0.05 grid.
+    (adjusted * 100.0).round() / 100.0
+}
+
+/// Lowercase a character one-for-one, so folded text keeps its char offsets.
+fn fold_char(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Text around the first mention of a concept label in `content`, ignoring
+/// case. Hierarchical labels (`science/machine-learning`) are matched by
+/// their last segment with `-` and `_` read as spaces.
+pub fn evidence_snippet(content: &str, label: &str) -> Option<String> {
+    let leaf = label.rsplit('/').next().unwrap_or(label);
+    let needle: Vec<char> = leaf
+        .trim()
+        .chars()
+        .map(|c| if c == '-' || c == '_' { ' ' } else { c })
+        .map(fold_char)
+        .collect();
+    if needle.is_empty() {
+        return None;
+    }
+    let chars: Vec<char> = content.chars().collect();
+    let folded: Vec<char> = chars.iter().copied().map(fold_char).collect();
+    let start = folded
+        .windows(needle.len())
+        .position(|window| window == needle.as_slice())?;
+    let from = start.saturating_sub(EVIDENCE_CONTEXT_CHARS);
+    let to = (start + needle.len() + EVIDENCE_CONTEXT_CHARS).min(chars.len());
+    let text: String = chars[from..to].iter().collect();
+    let mut snippet = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if from > 0 {
+        snippet.insert(0, '…');
+    }
+    if to < chars.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thresholds_step_within_bounds() {
+        assert_eq!(adjusted_review_threshold(0.7, true), 0.65);
+        assert_eq!(adjusted_review_threshold(0.7, false), 0.75);
+        assert_eq!(
+            adjusted_review_threshold(MIN_CONCEPT_REVIEW_THRESHOLD, true),
+            MIN_CONCEPT_REVIEW_THRESHOLD
+        );
+        assert_eq!(adjusted_review_threshold(0.98, false), 1.0);
+
+        let mut threshold = 0.7;
+        for _ in 0..6 {
+            threshold = adjusted_review_threshold(threshold, false);
+        }
+        assert_eq!(threshold, 1.0);
+        assert!(needs_review(1.0, threshold));
+    }
+
+    #[test]
+    fn confidence_falls_with_rank_and_missing_evidence() {
+        assert_eq!(suggestion_confidence(0, true), 1.0);
+        assert!(suggestion_confidence(2, true) > suggestion_confidence(2, false));
+        assert_eq!(suggestion_confidence(20, true), 0.0);
+    }
+
+    #[test]
+    fn evidence_matches_leaf_label_ignoring_case() {
+        let content = format!(
+            "{} Notes on Machine Learning\n  pipelines.",
+            "x".repeat(100)
+        );
+        assert_eq!(
+            evidence_snippet(&content, "science/machine-learning").unwrap(),
+            format!("…{} Notes on Machine Learning pipelines.", "x".repeat(70))
+        );
+
+        assert_eq!(evidence_snippet("nothing here", "rust"), None);
+        assert_eq!(
+            evidence_snippet("Ünïcode Rust", "rust").as_deref(),
+            Some("Ünïcode Rust")
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Dead-letter queue: jobs that ended in `failed` and wait for an operator.
+//!
+//! A job lands in the dead-letter queue when it fails permanently, exhausts
+//! its retries, or is quarantined as poison. From there it can be retried with
+//! a fresh retry budget or discarded, which cancels it for good.
+//!
+//! A payload is poison when attempts with the same job type and payload
+//! fingerprint have failed [`POISON_FAILURE_THRESHOLD`] times, across every
+//! job that carried it. The next retryable failure of such a payload goes
+//! straight to the dead-letter queue instead of being scheduled again.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{JobFailureClass, JobType};
+
+/// Failure code for jobs quarantined because their payload keeps failing.
+pub const POISON_PAYLOAD_CODE: &str = "poison_payload";
+
+/// Failure code for dead-lettered jobs an operator discarded.
+pub const DISCARDED_CODE: &str = "discarded";
+
+/// Failed attempts of one payload, counting the current one, after which it
+/// is quarantined.
+pub const POISON_FAILURE_THRESHOLD: i64 = 5;
+
+/// Upper bound on explicit job ids in one bulk request.
+pub const MAX_DEAD_LETTER_BATCH: usize = 500;
+
+/// Whether a payload that already failed `prior_failures` times is poison
+/// once the current attempt fails too.
+pub const fn is_poison(prior_failures: i64) -> bool {
+    prior_failures + 1 >= POISON_FAILURE_THRESHOLD
+}
+
+/// A job in the dead-letter queue.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DeadLetterJob {
+    pub id: Uuid,
+    pub note_id: Option<Uuid>,
+    #[schema(value_type = String)]
+    pub job_type: JobType,
+    #[schema(value_type = Option<String>)]
+    pub failure_class: Option<JobFailureClass>,
+    pub failure_code: Option<String>,
+    /// Attempts made before the job was dead-lettered.
+    pub attempts: i32,
+    /// SHA-256 of the payload, shared by jobs carrying the same payload.
+    pub payload_fingerprint: Option<String>,
+    pub pipeline_run_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub failed_at: Option<DateTime<Utc>>,
+}
+
+/// Which dead-lettered jobs a bulk retry or discard applies to. Set filters
+/// narrow each other; with none set every dead-lettered job is selected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeadLetterSelection {
+    pub job_ids: Option<Vec<Uuid>>,
+    pub job_type: Option<JobType>,
+    pub failure_class: Option<JobFailureClass>,
+}
+
+impl DeadLetterSelection {
+    /// Whether no filter is set.
+    pub fn is_unfiltered(&self) -> bool {
+        self.job_ids.is_none() && self.job_type.is_none() && self.failure_class.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_is_poison_once_the_threshold_is_reached() {
+        assert!(!is_poison(0));
+        assert!(!is_poison(POISON_FAILURE_THRESHOLD - 2));
+        assert!(is_poison(POISON_FAILURE_THRESHOLD - 1));
+        assert!(is_poison(POISON_FAILURE_THRESHOLD + 10));
+    }
+
+    #[test]
+    fn empty_selection_is_unfiltered() {
+        assert!(DeadLetterSelection::default().is_unfiltered());
+        let by_type = DeadLetterSelection {
+            job_type: Some(JobType::Embedding),
+            ..Default::default()
+        };
+        assert!(!by_type.is_unfiltered());
+    }
+}
@@ -84,6 +84,29 @@ pub const EVENT_BUS_CAPACITY: usize = 256;
 /// Default SSE replay buffer capacity (number of events retained for Last-Event-ID replay).
 pub const SSE_REPLAY_BUFFER_SIZE: usize = 1024;
 
+/// Default per-archive SSE replay buffer capacity (events retained per memory,
+/// independently of the shared buffer). 0 disables per-archive buffering.
+pub const SSE_REPLAY_ARCHIVE_BUFFER_SIZE: usize = 256;
+
+/// Default retention for the durable event history (activity feed), in days.
+pub const EVENT_HISTORY_RETENTION_DAYS: i64 = 30;
+
+/// Default days a soft-deleted note stays in the trash before it is purged.
+/// 0 keeps trashed notes until they are purged by hand.
+pub const TRASH_RETENTION_DAYS: i64 = 30;
+
+/// Read the trash retention from `TRASH_RETENTION_DAYS`, falling back to the default.
+pub fn trash_retention_days() -> i64 {
+    std::env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|&days| days >= 0)
+        .unwrap_or(TRASH_RETENTION_DAYS)
+}
+
+/// Maximum events replayed from the durable history on one SSE/WebSocket reconnect.
+pub const EVENT_HISTORY_REPLAY_LIMIT: i64 = 1000;
+
 /// Coalescing window for low-priority SSE events in milliseconds (Issue #458).
 ///
 /// Low-priority events (e.g., `job.progress`, `queue.status`) with the same
@@ -143,6 +166,36 @@ pub const EXTRACTION_CHUNK_SIZE_FALLBACK: usize = 6000;
 /// Minimum chunk size for extraction (below this, don't bother chunking).
 pub const EXTRACTION_CHUNK_SIZE_MIN: usize = 500;
 
+/// Notes shorter than this many characters are not summarized.
+pub const SUMMARIZATION_MIN_CHARS: usize = 1_000;
+
+/// Maximum characters of chunk summaries combined by one reduce call.
+pub const SUMMARIZATION_REDUCE_MAX_CHARS: usize = 8_000;
+
+/// Reduce levels before the remaining chunk summaries are combined at once.
+pub const SUMMARIZATION_MAX_LEVELS: usize = 4;
+
+/// Maximum entity facets returned with search results.
+pub const SEARCH_ENTITY_FACET_LIMIT: i64 = 20;
+
+/// Archives with fewer embedded notes than this are not clustered into topics.
+pub const TOPIC_MIN_NOTES: usize = 10;
+
+/// Upper bound on the number of topics one topic modeling run produces.
+pub const TOPIC_MAX_COUNT: usize = 50;
+
+/// Most recently updated notes clustered by one topic modeling run.
+pub const TOPIC_MAX_NOTES: i64 = 20_000;
+
+/// Notes closest to a topic's centroid shown to the model when naming it.
+pub const TOPIC_NAMING_SAMPLE: usize = 8;
+
+/// Characters of each sampled note shown to the model when naming a topic.
+pub const TOPIC_NAMING_EXCERPT_CHARS: i32 = 400;
+
+/// Maximum characters kept from a generated topic label.
+pub const TOPIC_LABEL_MAX_CHARS: usize = 80;
+
 /// Fallback chunk size for AI revision when no model profile is available.
 /// Conservative default for unknown models (~10K tokens at ~4 chars/token).
 /// Actual chunk size is computed from the model's context window at runtime.
@@ -178,6 +231,33 @@ pub const ENV_REVISION_CHUNK_OVERLAP: &str = "REVISION_CHUNK_OVERLAP";
 /// Overrides REVISION_VIDEO_CHUNK_SIZE_MAX when set.
 pub const ENV_REVISION_VIDEO_CHUNK_MAX_CHARS: &str = "REVISION_VIDEO_CHUNK_MAX_CHARS";
 
+/// Fraction of the safe context window given to related-note reference context
+/// in contextual (Phase 2) revision prompts. The rest holds instructions, the
+/// chunk being revised, and the generated output.
+pub const REVISION_REFERENCE_CONTEXT_SHARE: f32 = 0.2;
+
+/// Characters of each related note fetched as a reference context candidate.
+/// The token budget decides how much of each excerpt reaches the prompt.
+pub const REVISION_REFERENCE_EXCERPT_CHARS: usize = 2_000;
+
+/// Smallest truncated excerpt (in tokens) worth including in reference context.
+/// A note that would be cut below this is dropped instead.
+pub const REVISION_REFERENCE_MIN_TOKENS: usize = 32;
+
+/// Repair prompts sent after a JSON reply fails its output schema, before
+/// schema-constrained generation gives up on the call.
+pub const CONSTRAINED_OUTPUT_MAX_REPAIRS: u32 = 2;
+
+/// Validation problems quoted back to the model in a repair prompt.
+pub const CONSTRAINED_OUTPUT_MAX_PROBLEMS: usize = 5;
+
+/// Lifetime of cached title and concept tagging results, in seconds (7 days).
+pub const INFERENCE_CACHE_TTL_SECS: u64 = 604_800;
+
+/// Environment variable overriding [`INFERENCE_CACHE_TTL_SECS`]. `0` disables
+/// the inference cache.
+pub const ENV_INFERENCE_CACHE_TTL: &str = "MATRIC_INFERENCE_CACHE_TTL";
+
 /// Adaptive timeout: milliseconds of generation time per character of input.
 /// At ~2 tokens/sec generation and ~4 chars/token, 1K input chars needs ~2s.
 /// We use 3ms/char for safety margin (covers thinking overhead, VRAM swaps).
@@ -218,6 +298,28 @@ pub const JOB_POLL_INTERVAL_MS: u64 = 60_000;
 /// sufficient VRAM for parallel inference.
 pub const JOB_MAX_CONCURRENT: usize = 1;
 
+/// Default pending jobs per worker task when autoscaling between
+/// `JOB_MIN_CONCURRENT` and `JOB_MAX_CONCURRENT`.
+pub const JOB_SCALE_QUEUE_DEPTH: u64 = 10;
+
+/// Default worker claims given to the interactive lane per cycle while both
+/// lanes have work.
+pub const JOB_INTERACTIVE_LANE_WEIGHT: u32 = 4;
+
+/// Default worker claims given to the batch lane per cycle while both lanes
+/// have work, so bulk jobs are never starved.
+pub const JOB_BATCH_LANE_WEIGHT: u32 = 1;
+
+/// Default seconds between job worker heartbeats.
+pub const JOB_WORKER_HEARTBEAT_INTERVAL_SECS: u64 = 10;
+
+/// Default seconds without a heartbeat after which a worker is presumed dead
+/// and its running jobs are reclaimed.
+pub const JOB_WORKER_HEARTBEAT_TIMEOUT_SECS: u64 = 60;
+
+/// Hours a stopped or dead worker stays in the worker registry.
+pub const JOB_WORKER_REGISTRY_RETENTION_HOURS: i64 = 24;
+
 /// Default maximum concurrent GPU jobs (any tier that uses Ollama).
 /// Defaults to 1 (serial) to avoid VRAM contention on single-GPU systems
 /// with 6-8GB VRAM. Each Ollama call loads ~4-6GB of model weights.
@@ -434,6 +536,22 @@ pub const ENV_DIARIZATION_MODEL: &str = "DIARIZATION_MODEL";
 /// Default diarization model.
 pub const DEFAULT_DIARIZATION_MODEL: &str = "pyannote/speaker-diarization-3.1";
 
+/// Environment variable for the CLIP image embedding service URL.
+pub const ENV_CLIP_BASE_URL: &str = "CLIP_BASE_URL";
+
+/// Environment variable for the CLIP model name.
+pub const ENV_CLIP_MODEL: &str = "CLIP_MODEL";
+
+/// Default CLIP model. Its 512-dimension vectors match
+/// `attachment_embedding.clip_vector`.
+pub const DEFAULT_CLIP_MODEL: &str = "openai/clip-vit-base-patch32";
+
+/// Dimension of stored CLIP image embeddings.
+pub const CLIP_DIMENSION: usize = 512;
+
+/// Slug of the embedding set holding CLIP image embeddings.
+pub const IMAGE_EMBEDDING_SET_SLUG: &str = "images";
+
 /// Environment variable for the GLiNER NER sidecar URL.
 pub const ENV_GLINER_BASE_URL: &str = "GLINER_BASE_URL";
 
@@ -910,6 +1028,45 @@ pub const EXTRACTION_TARGET_CONCEPTS: usize = 8;
 /// Environment variable for configuring the target concept count.
 pub const ENV_EXTRACTION_TARGET_CONCEPTS: &str = "EXTRACTION_TARGET_CONCEPTS";
 
+/// Environment variable that holds low-confidence concept suggestions for
+/// review instead of tagging notes with them (`true`/`1` to enable).
+pub const ENV_CONCEPT_REVIEW_MODE: &str = "CONCEPT_REVIEW_MODE";
+
+/// Confidence a concept suggestion needs to be applied without review, for
+/// concepts whose threshold has not yet been adjusted by reviews.
+/// Configurable via `CONCEPT_REVIEW_THRESHOLD`.
+pub const CONCEPT_REVIEW_THRESHOLD: f32 = 0.7;
+
+/// Environment variable for configuring the default concept review threshold.
+pub const ENV_CONCEPT_REVIEW_THRESHOLD: &str = "CONCEPT_REVIEW_THRESHOLD";
+
+/// Read the default concept review threshold from `CONCEPT_REVIEW_THRESHOLD`,
+/// falling back to the default when unset or outside 0 to 1.
+pub fn concept_review_threshold() -> f32 {
+    std::env::var(ENV_CONCEPT_REVIEW_THRESHOLD)
+        .ok()
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|t| (0.0..=1.0).contains(t))
+        .unwrap_or(CONCEPT_REVIEW_THRESHOLD)
+}
+
+/// Label similarity, 0 to 1, at which the taxonomy health check reports two
+/// concepts as near-duplicates. Configurable via `TAXONOMY_DUPLICATE_SIMILARITY`.
+pub const TAXONOMY_DUPLICATE_SIMILARITY: f32 = 0.85;
+
+/// Environment variable for configuring the near-duplicate label similarity.
+pub const ENV_TAXONOMY_DUPLICATE_SIMILARITY: &str = "TAXONOMY_DUPLICATE_SIMILARITY";
+
+/// Read the near-duplicate label similarity from `TAXONOMY_DUPLICATE_SIMILARITY`,
+/// falling back to the default when unset or outside 0 (exclusive) to 1.
+pub fn taxonomy_duplicate_similarity() -> f32 {
+    std::env::var(ENV_TAXONOMY_DUPLICATE_SIMILARITY)
+        .ok()
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|s| *s > 0.0 && *s <= 1.0)
+        .unwrap_or(TAXONOMY_DUPLICATE_SIMILARITY)
+}
+
 /// Maximum document frequency ratio for concepts included in embedding enrichment (#475).
 /// Concepts appearing in more than this fraction of notes are treated as "stopwords"
 /// and excluded from the embedding text prefix. Range: 0.0-1.0.
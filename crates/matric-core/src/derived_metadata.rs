@@ -0,0 +1,395 @@
+//! Metadata derived from note content.
+//!
+//! A document type's pipeline policy lists the fields to derive for its
+//! notes (`"derived_fields": ["word_count", "reading_time", "language",
+//! "sentiment"]`). They are computed from the original content whenever the
+//! note's pipeline is queued and stored in the note's metadata under
+//! [`DERIVED_METADATA_KEY`], replacing earlier values:
+//!
+//! ```json
+//! {"derived": {"word_count": 412, "reading_time_minutes": 3, "language": "en", "sentiment": 0.25}}
+//! ```
+//!
+//! From there they filter like any other metadata key
+//! (`metadata.derived.word_count[gte]=300`), and list and search results can
+//! be sorted by them (`sort_by=word_count`).
+//!
+//! ```
+//! use matric_core::derived_metadata::{derive_metadata, DerivedField};
+//!
+//! let derived = derive_metadata(
+//!     &[DerivedField::WordCount, DerivedField::ReadingTime],
+//!     "A short note with seven words here.",
+//!     None,
+//! );
+//! assert_eq!(derived.word_count, Some(7));
+//! assert_eq!(derived.reading_time_minutes, Some(1));
+//! assert_eq!(derived.sentiment, None);
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Metadata key holding the derived fields.
+pub const DERIVED_METADATA_KEY: &str = "derived";
+
+/// Reading speed used for `reading_time`.
+pub const WORDS_PER_MINUTE: u64 = 200;
+
+/// A field derived from note content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DerivedField {
+    /// Whitespace-separated words.
+    WordCount,
+    /// Whole minutes to read at [`WORDS_PER_MINUTE`], rounded up.
+    ReadingTime,
+    /// The note's detected language (ISO 639-1 where one exists).
+    Language,
+    /// Lexicon-based tone of English text, from -1.0 (negative) to 1.0
+    /// (positive).
+    Sentiment,
+}
+
+impl DerivedField {
+    /// Every derivable field.
+    pub const ALL: [DerivedField; 4] = [
+        DerivedField::WordCount,
+        DerivedField::ReadingTime,
+        DerivedField::Language,
+        DerivedField::Sentiment,
+    ];
+
+    /// Key the field is stored under inside [`DERIVED_METADATA_KEY`].
+    pub fn key(self) -> &'static str {
+        match self {
+            DerivedField::WordCount => "word_count",
+            DerivedField::ReadingTime => "reading_time_minutes",
+            DerivedField::Language => "language",
+            DerivedField::Sentiment => "sentiment",
+        }
+    }
+
+    /// Parse a `sort_by` value: the field name or its stored key.
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|field| value == field.key() || value == field.name())
+    }
+
+    /// Whether the field holds a number rather than text.
+    pub fn is_numeric(self) -> bool {
+        self != DerivedField::Language
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            DerivedField::WordCount => "word_count",
+            DerivedField::ReadingTime => "reading_time",
+            DerivedField::Language => "language",
+            DerivedField::Sentiment => "sentiment",
+        }
+    }
+}
+
+/// Derived fields of one note, as stored in its metadata.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DerivedMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading_time_minutes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentiment: Option<f64>,
+}
+
+impl fmt::Debug for DerivedMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DerivedMetadata")
+            .field("word_count", &self.word_count)
+            .field("reading_time_minutes", &self.reading_time_minutes)
+            .field("language", &self.language)
+            .field("sentiment", &self.sentiment)
+            .finish()
+    }
+}
+
+impl DerivedMetadata {
+    /// Read the derived fields out of a note's metadata object.
+    pub fn from_metadata(metadata: &serde_json::Value) -> Option<Self> {
+        metadata
+            .get(DERIVED_METADATA_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// True when no field is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Compare two notes by `field`. Notes without the field sort last in
+    /// either direction.
+    pub fn compare(
+        a: Option<&Self>,
+        b: Option<&Self>,
+        field: DerivedField,
+        descending: bool,
+    ) -> Ordering {
+        let number = |d: Option<&Self>| {
+            d.and_then(|d| match field {
+                DerivedField::WordCount => d.word_count.map(|v| v as f64),
+                DerivedField::ReadingTime => d.reading_time_minutes.map(|v| v as f64),
+                DerivedField::Sentiment => d.sentiment,
+                DerivedField::Language => None,
+            })
+        };
+        let text = |d: Option<&Self>| d.and_then(|d| d.language.clone());
+        let ordering = if field.is_numeric() {
+            match (number(a), number(b)) {
+                (Some(x), Some(y)) => x.total_cmp(&y),
+                (x, y) => return y.is_none().cmp(&x.is_none()).reverse(),
+            }
+        } else {
+            match (text(a), text(b)) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (x, y) => return y.is_none().cmp(&x.is_none()).reverse(),
+            }
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// Compute `fields` for `content`. `language` is the note's stored language;
+/// when absent it is detected from the content.
+pub fn derive_metadata(
+    fields: &[DerivedField],
+    content: &str,
+    language: Option<&str>,
+) -> DerivedMetadata {
+    let mut derived = DerivedMetadata::default();
+    let words = word_count(content);
+    for field in fields {
+        match field {
+            DerivedField::WordCount => derived.word_count = Some(words),
+            DerivedField::ReadingTime => {
+                derived.reading_time_minutes = Some(reading_time_minutes(words))
+            }
+            DerivedField::Language => {
+                derived.language = language.map(str::to_string).or_else(|| {
+                    crate::language::detect_note_language(content).map(|lang| lang.code)
+                })
+            }
+            DerivedField::Sentiment => derived.sentiment = Some(sentiment_score(content)),
+        }
+    }
+    derived
+}
+
+/// Number of whitespace-separated words.
+pub fn word_count(text: &str) -> u64 {
+    text.split_whitespace().count() as u64
+}
+
+/// Whole minutes needed to read `words` words, rounded up.
+pub fn reading_time_minutes(words: u64) -> u64 {
+    words.div_ceil(WORDS_PER_MINUTE)
+}
+
+const POSITIVE_WORDS: &[&str] = &[
+    "good",
+    "great",
+    "excellent",
+    "amazing",
+    "awesome",
+    "happy",
+    "glad",
+    "love",
+    "loved",
+    "liked",
+    "nice",
+    "wonderful",
+    "fantastic",
+    "success",
+    "successful",
+    "win",
+    "won",
+    "improve",
+    "improved",
+    "benefit",
+    "helpful",
+    "easy",
+    "clear",
+    "positive",
+    "enjoy",
+    "enjoyed",
+    "pleased",
+    "excited",
+    "best",
+    "better",
+    "fast",
+    "fixed",
+    "works",
+    "resolved",
+    "thanks",
+    "thank",
+    "perfect",
+    "useful",
+    "progress",
+];
+
+const NEGATIVE_WORDS: &[&str] = &[
+    "bad",
+    "poor",
+    "terrible",
+    "awful",
+    "horrible",
+    "sad",
+    "angry",
+    "hate",
+    "hated",
+    "dislike",
+    "problem",
+    "problems",
+    "issue",
+    "issues",
+    "bug",
+    "bugs",
+    "fail",
+    "failed",
+    "failure",
+    "broken",
+    "error",
+    "errors",
+    "wrong",
+    "worse",
+    "worst",
+    "slow",
+    "difficult",
+    "hard",
+    "confusing",
+    "negative",
+    "risk",
+    "blocked",
+    "crash",
+    "crashed",
+    "lost",
+    "late",
+    "unfortunately",
+    "annoying",
+    "frustrated",
+    "worried",
+];
+
+const NEGATIONS: &[&str] = &["not", "no", "never", "without", "hardly"];
+
+/// Tone of English text from -1.0 (all negative) to 1.0 (all positive),
+/// counting words from a small lexicon. A negation ("not", "never", any
+/// "-n't" word) flips the next word. Text without sentiment words scores 0.
+pub fn sentiment_score(text: &str) -> f64 {
+    let mut positive = 0u32;
+    let mut negative = 0u32;
+    let mut negate = false;
+    for raw in text.split_whitespace() {
+        let word: String = raw
+            .trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+            .to_lowercase();
+        if word.is_empty() {
+            continue;
+        }
+        let polarity = if POSITIVE_WORDS.contains(&word.as_str()) {
+            1
+        } else if NEGATIVE_WORDS.contains(&word.as_str()) {
+            -1
+        } else {
+            0
+        };
+        match (polarity, negate) {
+            (1, false) | (-1, true) => positive += 1,
+            (-1, false) | (1, true) => negative += 1,
+            _ => {}
+        }
+        negate = NEGATIONS.contains(&word.as_str()) || word.ends_with("n't");
+    }
+    let total = positive + negative;
+    if total == 0 {
+        return 0.0;
+    }
+    let score = (f64::from(positive) - f64::from(negative)) / f64::from(total);
+    (score * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_and_reading_time() {
+        assert_eq!(word_count("  one two\nthree\tfour "), 4);
+        assert_eq!(reading_time_minutes(0), 0);
+        assert_eq!(reading_time_minutes(1), 1);
+        assert_eq!(reading_time_minutes(200), 1);
+        assert_eq!(reading_time_minutes(201), 2);
+    }
+
+    #[test]
+    fn sentiment_follows_the_lexicon_and_negation() {
+        assert_eq!(sentiment_score("The release was great, thanks!"), 1.0);
+        assert_eq!(sentiment_score("The build failed with a bug."), -1.0);
+        assert_eq!(sentiment_score("It was not bad and it isn't broken."), 1.0);
+        assert_eq!(sentiment_score("Meeting at noon."), 0.0);
+        assert_eq!(sentiment_score("Good fix, bad docs, good tests."), 0.33);
+    }
+
+    #[test]
+    fn only_requested_fields_are_derived() {
+        let derived = derive_metadata(
+            &[DerivedField::Language, DerivedField::Sentiment],
+            "Works well",
+            Some("de"),
+        );
+        assert_eq!(derived.language.as_deref(), Some("de"));
+        assert_eq!(derived.sentiment, Some(1.0));
+        assert_eq!(derived.word_count, None);
+
+        let metadata = serde_json::json!({ "derived": derived });
+        assert_eq!(DerivedMetadata::from_metadata(&metadata), Some(derived));
+    }
+
+    #[test]
+    fn missing_fields_sort_last() {
+        let short = DerivedMetadata {
+            word_count: Some(10),
+            ..Default::default()
+        };
+        let long = DerivedMetadata {
+            word_count: Some(500),
+            ..Default::default()
+        };
+        let mut notes = vec![None, Some(&short), Some(&long)];
+        notes.sort_by(|a, b| DerivedMetadata::compare(*a, *b, DerivedField::WordCount, true));
+        assert_eq!(notes, vec![Some(&long), Some(&short), None]);
+        notes.sort_by(|a, b| DerivedMetadata::compare(*a, *b, DerivedField::WordCount, false));
+        assert_eq!(notes, vec![Some(&short), Some(&long), None]);
+    }
+
+    #[test]
+    fn sort_names_parse() {
+        assert_eq!(
+            DerivedField::parse("reading_time"),
+            Some(DerivedField::ReadingTime)
+        );
+        assert_eq!(
+            DerivedField::parse("reading_time_minutes"),
+            Some(DerivedField::ReadingTime)
+        );
+        assert_eq!(DerivedField::parse("created_at"), None);
+    }
+}
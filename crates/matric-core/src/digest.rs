@@ -0,0 +1,528 @@
+//! Scheduled activity digests.
+//!
+//! A digest configuration belongs to one memory archive and runs daily or
+//! weekly at a fixed UTC hour. Each run summarizes the notes created or
+//! updated during the period that just ended, optionally restricted to some
+//! collections, and delivers the result as a new note in the archive, a
+//! `digest.generated` event (forwarded to webhooks), or both.
+//!
+//! ```
+//! use chrono::{TimeZone, Utc};
+//! use matric_core::DigestCadence;
+//!
+//! let end = Utc.with_ymd_and_hms(2026, 10, 17, 7, 0, 0).unwrap();
+//! let (start, _) = DigestCadence::Weekly.period_ending(end);
+//! assert_eq!(start, Utc.with_ymd_and_hms(2026, 10, 10, 7, 0, 0).unwrap());
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::backup_policy::CronSchedule;
+use crate::{Error, Result};
+
+/// Hour of day (UTC) digests run at unless configured otherwise.
+pub const DEFAULT_DIGEST_HOUR_UTC: i16 = 7;
+
+/// Longest accepted digest name.
+pub const MAX_DIGEST_NAME_LEN: usize = 100;
+
+/// Most collections a digest can be restricted to.
+pub const MAX_DIGEST_COLLECTIONS: usize = 50;
+
+/// Most notes one run covers; the most recently updated are kept.
+pub const MAX_DIGEST_NOTES: i64 = 200;
+
+/// Characters of note content used for a note without a stored summary.
+pub const DIGEST_EXCERPT_CHARS: i32 = 400;
+
+/// Longest list of notes sent to the model in one digest prompt.
+pub const DIGEST_PROMPT_MAX_CHARS: usize = 24_000;
+
+/// Note source recorded on digest notes; such notes never feed a digest.
+pub const DIGEST_NOTE_SOURCE: &str = "digest";
+
+/// Tag added to digest notes.
+pub const DIGEST_NOTE_TAG: &str = "digest";
+
+/// How often a digest runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestCadence {
+    Daily,
+    Weekly,
+}
+
+impl DigestCadence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DigestCadence::Daily => "daily",
+            DigestCadence::Weekly => "weekly",
+        }
+    }
+
+    /// Parse a stored cadence.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "daily" => Some(DigestCadence::Daily),
+            "weekly" => Some(DigestCadence::Weekly),
+            _ => None,
+        }
+    }
+
+    /// Length of the period one digest covers.
+    pub fn period(&self) -> Duration {
+        match self {
+            DigestCadence::Daily => Duration::days(1),
+            DigestCadence::Weekly => Duration::weeks(1),
+        }
+    }
+
+    /// The period that ends at `end`, as `(start, end)`.
+    pub fn period_ending(&self, end: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        (end - self.period(), end)
+    }
+
+    /// Run schedule: every day, or every Monday, at `hour_utc`.
+    pub fn schedule(&self, hour_utc: i16) -> Result<CronSchedule> {
+        validate_digest_hour(hour_utc)?;
+        let day_of_week = match self {
+            DigestCadence::Daily => "*",
+            DigestCadence::Weekly => "mon",
+        };
+        CronSchedule::parse(&format!("0 {hour_utc} * * {day_of_week}"))
+    }
+
+    /// Title of the digest note for the period ending at `end`.
+    pub fn note_title(&self, end: DateTime<Utc>) -> String {
+        let label = match self {
+            DigestCadence::Daily => "Daily",
+            DigestCadence::Weekly => "Weekly",
+        };
+        format!("{label} digest {}", end.format("%Y-%m-%d"))
+    }
+}
+
+/// Where a digest is delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestDelivery {
+    /// A new note in the archive
+    Note,
+    /// A `digest.generated` event, forwarded to subscribed webhooks
+    Webhook,
+}
+
+impl DigestDelivery {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DigestDelivery::Note => "note",
+            DigestDelivery::Webhook => "webhook",
+        }
+    }
+
+    /// Parse a stored delivery target.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "note" => Some(DigestDelivery::Note),
+            "webhook" => Some(DigestDelivery::Webhook),
+            _ => None,
+        }
+    }
+}
+
+fn validate_digest_hour(hour_utc: i16) -> Result<()> {
+    if !(0..=23).contains(&hour_utc) {
+        return Err(Error::InvalidInput(
+            "hour_utc must be between 0 and 23".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_digest_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() || name.chars().count() > MAX_DIGEST_NAME_LEN {
+        return Err(Error::InvalidInput(format!(
+            "digest name must be 1-{MAX_DIGEST_NAME_LEN} characters"
+        )));
+    }
+    Ok(())
+}
+
+fn validate_digest_targets(collection_ids: &[Uuid], delivery: &[DigestDelivery]) -> Result<()> {
+    if collection_ids.len() > MAX_DIGEST_COLLECTIONS {
+        return Err(Error::InvalidInput(format!(
+            "a digest can include at most {MAX_DIGEST_COLLECTIONS} collections"
+        )));
+    }
+    if delivery.is_empty() {
+        return Err(Error::InvalidInput(
+            "delivery must include at least one of: note, webhook".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// A digest configuration for one memory archive.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DigestConfig {
+    pub id: Uuid,
+    pub name: String,
+    pub cadence: DigestCadence,
+    /// Hour of day (UTC) the digest runs; weekly digests run on Mondays
+    pub hour_utc: i16,
+    /// Only notes in these collections; every note when empty
+    pub collection_ids: Vec<Uuid>,
+    pub delivery: Vec<DigestDelivery>,
+    /// Collection digest notes are filed in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note_collection_id: Option<Uuid>,
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_run_at_utc: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run_at_utc: Option<DateTime<Utc>>,
+    /// Outcome of the most recent run (`succeeded`, `empty`, `failed`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_status: Option<String>,
+    /// Digest note written by the most recent run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_note_id: Option<Uuid>,
+    pub created_at_utc: DateTime<Utc>,
+    pub updated_at_utc: DateTime<Utc>,
+}
+
+impl DigestConfig {
+    /// Whether runs deliver to `target`.
+    pub fn delivers(&self, target: DigestDelivery) -> bool {
+        self.delivery.contains(&target)
+    }
+}
+
+impl fmt::Debug for DigestConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DigestConfig")
+            .field("id_set", &true)
+            .field("name_len", &self.name.len())
+            .field("cadence", &self.cadence)
+            .field("hour_utc", &self.hour_utc)
+            .field("collection_count", &self.collection_ids.len())
+            .field("delivery", &self.delivery)
+            .field("note_collection_set", &self.note_collection_id.is_some())
+            .field("enabled", &self.enabled)
+            .field("next_run_at_utc", &self.next_run_at_utc)
+            .field("last_run_at_utc", &self.last_run_at_utc)
+            .field("last_status", &self.last_status)
+            .finish()
+    }
+}
+
+fn default_digest_hour() -> i16 {
+    DEFAULT_DIGEST_HOUR_UTC
+}
+
+fn default_digest_delivery() -> Vec<DigestDelivery> {
+    vec![DigestDelivery::Note]
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Request body for creating a digest.
+#[derive(Clone, Deserialize, utoipa::ToSchema)]
+pub struct CreateDigestRequest {
+    pub name: String,
+    pub cadence: DigestCadence,
+    /// Hour of day (UTC) the digest runs (default: 7)
+    #[serde(default = "default_digest_hour")]
+    pub hour_utc: i16,
+    /// Only notes in these collections; every note when empty (default)
+    #[serde(default)]
+    pub collection_ids: Vec<Uuid>,
+    /// Delivery targets (default: `["note"]`)
+    #[serde(default = "default_digest_delivery")]
+    pub delivery: Vec<DigestDelivery>,
+    /// Collection digest notes are filed in
+    pub note_collection_id: Option<Uuid>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+impl CreateDigestRequest {
+    /// Validates the request and returns the run schedule.
+    pub fn validate(&self) -> Result<CronSchedule> {
+        validate_digest_name(&self.name)?;
+        validate_digest_targets(&self.collection_ids, &self.delivery)?;
+        self.cadence.schedule(self.hour_utc)
+    }
+}
+
+impl fmt::Debug for CreateDigestRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateDigestRequest")
+            .field("name_len", &self.name.len())
+            .field("cadence", &self.cadence)
+            .field("hour_utc", &self.hour_utc)
+            .field("collection_count", &self.collection_ids.len())
+            .field("delivery", &self.delivery)
+            .field("note_collection_set", &self.note_collection_id.is_some())
+            .field("enabled", &self.enabled)
+            .finish()
+    }
+}
+
+/// Request body for updating a digest; unset fields are unchanged.
+#[derive(Clone, Default, Deserialize, utoipa::ToSchema)]
+pub struct UpdateDigestRequest {
+    pub name: Option<String>,
+    pub cadence: Option<DigestCadence>,
+    pub hour_utc: Option<i16>,
+    pub collection_ids: Option<Vec<Uuid>>,
+    pub delivery: Option<Vec<DigestDelivery>>,
+    /// Collection digest notes are filed in; the nil UUID clears it
+    pub note_collection_id: Option<Uuid>,
+    pub enabled: Option<bool>,
+}
+
+impl UpdateDigestRequest {
+    /// Applies the update to `config` after validating the merged result.
+    ///
+    /// Returns the run schedule when the cadence or hour changed.
+    pub fn apply(&self, config: &mut DigestConfig) -> Result<Option<CronSchedule>> {
+        if let Some(name) = &self.name {
+            validate_digest_name(name)?;
+            config.name = name.clone();
+        }
+        if let Some(cadence) = self.cadence {
+            config.cadence = cadence;
+        }
+        if let Some(hour_utc) = self.hour_utc {
+            config.hour_utc = hour_utc;
+        }
+        if let Some(collection_ids) = &self.collection_ids {
+            config.collection_ids = collection_ids.clone();
+        }
+        if let Some(delivery) = &self.delivery {
+            config.delivery = delivery.clone();
+        }
+        if let Some(collection_id) = self.note_collection_id {
+            config.note_collection_id = Some(collection_id).filter(|id| !id.is_nil());
+        }
+        if let Some(enabled) = self.enabled {
+            config.enabled = enabled;
+        }
+        validate_digest_targets(&config.collection_ids, &config.delivery)?;
+        let schedule = config.cadence.schedule(config.hour_utc)?;
+        Ok((self.cadence.is_some() || self.hour_utc.is_some()).then_some(schedule))
+    }
+}
+
+impl fmt::Debug for UpdateDigestRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpdateDigestRequest")
+            .field("name_len", &self.name.as_ref().map(String::len))
+            .field("cadence", &self.cadence)
+            .field("hour_utc", &self.hour_utc)
+            .field(
+                "collection_count",
+                &self.collection_ids.as_ref().map(Vec::len),
+            )
+            .field("delivery", &self.delivery)
+            .field("note_collection_set", &self.note_collection_id.is_some())
+            .field("enabled", &self.enabled)
+            .finish()
+    }
+}
+
+/// A note created or updated during a digest period.
+#[derive(Clone)]
+pub struct DigestNoteActivity {
+    pub note_id: Uuid,
+    pub title: Option<String>,
+    /// Whether the note was created (rather than only updated) in the period
+    pub created: bool,
+    /// Stored AI summary, when the note has one
+    pub summary: Option<String>,
+    /// Start of the note's current content
+    pub excerpt: String,
+}
+
+impl DigestNoteActivity {
+    /// One line describing the note for the digest prompt.
+    pub fn prompt_line(&self) -> String {
+        let title = self.title.as_deref().unwrap_or("Untitled");
+        let change = if self.created { "new" } else { "updated" };
+        let text = self.summary.as_deref().unwrap_or(&self.excerpt);
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        format!("- {title} ({change}): {text}")
+    }
+}
+
+impl fmt::Debug for DigestNoteActivity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DigestNoteActivity")
+            .field("note_id_set", &true)
+            .field("title_len", &self.title.as_ref().map(String::len))
+            .field("created", &self.created)
+            .field("summary_len", &self.summary.as_ref().map(String::len))
+            .field("excerpt_len", &self.excerpt.len())
+            .finish()
+    }
+}
+
+/// The `notes` prompt value: one line per note, cut off at
+/// [`DIGEST_PROMPT_MAX_CHARS`] with a count of the notes left out.
+pub fn digest_prompt_notes(notes: &[DigestNoteActivity]) -> String {
+    let mut lines = String::new();
+    for (i, note) in notes.iter().enumerate() {
+        let line = note.prompt_line();
+        if !lines.is_empty() && lines.len() + line.len() + 1 > DIGEST_PROMPT_MAX_CHARS {
+            lines.push_str(&format!("\n- ...and {} more notes", notes.len() - i));
+            break;
+        }
+        if !lines.is_empty() {
+            lines.push('\n');
+        }
+        lines.push_str(&line);
+    }
+    lines
+}
+
+/// The `period` prompt value, e.g. `between 2026-10-16 07:00 and 2026-10-17 07:00 UTC`.
+pub fn digest_period_label(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    format!(
+        "between {} and {} UTC",
+        start.format("%Y-%m-%d %H:%M"),
+        end.format("%Y-%m-%d %H:%M")
+    )
+}
+
+/// Markdown body of a digest note: the generated digest followed by the
+/// notes it covers.
+pub fn render_digest_note(summary: &str, notes: &[DigestNoteActivity]) -> String {
+    let mut body = summary.trim().to_string();
+    body.push_str("\n\n## Notes\n\n");
+    for note in notes {
+        let title = note.title.as_deref().unwrap_or("Untitled");
+        let change = if note.created { "new" } else { "updated" };
+        body.push_str(&format!("- {title} ({change}) `{}`\n", note.note_id));
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(d: u32, h: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, d, h, 0, 0).unwrap()
+    }
+
+    fn request() -> CreateDigestRequest {
+        CreateDigestRequest {
+            name: "Morning".to_string(),
+            cadence: DigestCadence::Daily,
+            hour_utc: DEFAULT_DIGEST_HOUR_UTC,
+            collection_ids: Vec::new(),
+            delivery: default_digest_delivery(),
+            note_collection_id: None,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn schedules_run_daily_or_on_mondays() {
+        // 2026-10-17 is a Saturday.
+        let daily = DigestCadence::Daily.schedule(7).unwrap();
+        assert_eq!(daily.next_after(at(17, 8)), Some(at(18, 7)));
+        let weekly = DigestCadence::Weekly.schedule(7).unwrap();
+        assert_eq!(weekly.next_after(at(17, 8)), Some(at(19, 7)));
+        assert!(DigestCadence::Daily.schedule(24).is_err());
+    }
+
+    #[test]
+    fn create_request_validation() {
+        let mut req = request();
+        assert!(req.validate().is_ok());
+
+        req.delivery.clear();
+        assert!(matches!(req.validate(), Err(Error::InvalidInput(_))));
+        req.delivery = vec![DigestDelivery::Webhook];
+        req.name = "  ".to_string();
+        assert!(req.validate().is_err());
+        req.name = "Weekly review".to_string();
+        req.hour_utc = -1;
+        assert!(req.validate().is_err());
+    }
+
+    #[test]
+    fn update_reports_schedule_changes_and_clears_collection() {
+        let now = Utc::now();
+        let mut config = DigestConfig {
+            id: Uuid::nil(),
+            name: "Morning".to_string(),
+            cadence: DigestCadence::Daily,
+            hour_utc: 7,
+            collection_ids: Vec::new(),
+            delivery: vec![DigestDelivery::Note],
+            note_collection_id: Some(Uuid::from_u128(1)),
+            enabled: true,
+            next_run_at_utc: None,
+            last_run_at_utc: None,
+            last_status: None,
+            last_note_id: None,
+            created_at_utc: now,
+            updated_at_utc: now,
+        };
+
+        let update = UpdateDigestRequest {
+            enabled: Some(false),
+            note_collection_id: Some(Uuid::nil()),
+            ..Default::default()
+        };
+        assert!(update.apply(&mut config).unwrap().is_none());
+        assert!(!config.enabled && config.note_collection_id.is_none());
+
+        let update = UpdateDigestRequest {
+            cadence: Some(DigestCadence::Weekly),
+            ..Default::default()
+        };
+        assert!(update.apply(&mut config).unwrap().is_some());
+
+        let update = UpdateDigestRequest {
+            delivery: Some(Vec::new()),
+            ..Default::default()
+        };
+        assert!(update.apply(&mut config).is_err());
+    }
+
+    #[test]
+    fn activity_lines_prefer_summaries() {
+        let note = DigestNoteActivity {
+            note_id: Uuid::nil(),
+            title: Some("Roadmap".to_string()),
+            created: true,
+            summary: Some("Plans for\nQ4.".to_string()),
+            excerpt: "raw text".to_string(),
+        };
+        assert_eq!(note.prompt_line(), "- Roadmap (new): Plans for Q4.");
+
+        let body = render_digest_note("Busy week.", std::slice::from_ref(&note));
+        assert!(body.starts_with("Busy week.\n\n## Notes\n"));
+        assert!(body.contains(&format!("- Roadmap (new) `{}`", Uuid::nil())));
+
+        let many = vec![note; DIGEST_PROMPT_MAX_CHARS / 20];
+        let lines = digest_prompt_notes(&many);
+        assert!(lines.len() <= DIGEST_PROMPT_MAX_CHARS + 40);
+        assert!(lines.ends_with("more notes"));
+        assert_eq!(
+            digest_period_label(at(16, 7), at(17, 7)),
+            "between 2026-10-16 07:00 and 2026-10-17 07:00 UTC"
+        );
+    }
+}
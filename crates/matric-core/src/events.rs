@@ -13,7 +13,7 @@
 //!
 //! Architecture: See ADR-037 (unified-event-bus)
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
@@ -290,6 +290,15 @@ pub enum ServerEvent {
         note_id: Option<Uuid>,
         error: String,
     },
+    /// A job landed in the dead-letter queue.
+    JobDeadLettered {
+        job_id: Uuid,
+        job_type: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        note_id: Option<Uuid>,
+        failure_class: String,
+        failure_code: String,
+    },
     /// A note was created, updated, or had its AI content refreshed.
     NoteUpdated {
         note_id: Uuid,
@@ -487,6 +496,49 @@ pub enum ServerEvent {
         /// `["embedding_backend"]`, `["__reset__"]` for `DELETE` events.
         changed_fields: Vec<String>,
     },
+
+    // -- Spaced repetition review --
+    /// Review cards in this memory are due. Emitted by the daily review
+    /// scheduler so clients and webhooks can nudge the user without polling
+    /// `GET /api/v1/review/queue`.
+    ReviewDue {
+        /// Number of cards due at the time of the check.
+        due_count: i64,
+    },
+
+    // -- Scheduled backups --
+    /// A scheduled backup policy run finished and retention was applied.
+    BackupCompleted {
+        policy_id: Uuid,
+        run_id: Uuid,
+        /// Backup filename in the backup directory.
+        filename: String,
+        size_bytes: i64,
+        /// Older backups deleted by the policy's retention rule.
+        pruned_count: usize,
+    },
+    /// A scheduled backup policy run failed.
+    BackupFailed {
+        policy_id: Uuid,
+        run_id: Uuid,
+        error: String,
+    },
+
+    // -- Digests --
+    /// A scheduled digest summarized this memory's activity for a period.
+    /// Emitted for digests delivered by webhook; carries the digest text so
+    /// subscribers need no follow-up request.
+    DigestGenerated {
+        digest_id: Uuid,
+        /// Digest note, when the digest is also delivered as a note.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        note_id: Option<Uuid>,
+        period_start: DateTime<Utc>,
+        period_end: DateTime<Utc>,
+        /// Notes created or updated during the period.
+        note_count: usize,
+        summary: String,
+    },
 }
 
 impl fmt::Debug for ServerEvent {
@@ -556,6 +608,20 @@ impl fmt::Debug for ServerEvent {
                     .field("note_id_present", &note_id.is_some())
                     .field("error_len", &text_len(error));
             }
+            ServerEvent::JobDeadLettered {
+                job_type,
+                note_id,
+                failure_class,
+                failure_code,
+                ..
+            } => {
+                debug
+                    .field("job_id_present", &true)
+                    .field("job_type_len", &text_len(job_type))
+                    .field("note_id_present", &note_id.is_some())
+                    .field("failure_class", failure_class)
+                    .field("failure_code", failure_code);
+            }
             ServerEvent::NoteUpdated {
                 title,
                 tags,
@@ -700,6 +766,44 @@ impl fmt::Debug for ServerEvent {
                     .field("changed_fields_count", &changed_fields.len())
                     .field("changed_field_lens", &string_lens(changed_fields));
             }
+            ServerEvent::ReviewDue { due_count } => {
+                debug.field("due_count", due_count);
+            }
+            ServerEvent::BackupCompleted {
+                filename,
+                size_bytes,
+                pruned_count,
+                ..
+            } => {
+                debug
+                    .field("policy_id_present", &true)
+                    .field("run_id_present", &true)
+                    .field("filename_len", &text_len(filename))
+                    .field("size_bytes", size_bytes)
+                    .field("pruned_count", pruned_count);
+            }
+            ServerEvent::BackupFailed { error, .. } => {
+                debug
+                    .field("policy_id_present", &true)
+                    .field("run_id_present", &true)
+                    .field("error_len", &text_len(error));
+            }
+            ServerEvent::DigestGenerated {
+                note_id,
+                period_start,
+                period_end,
+                note_count,
+                summary,
+                ..
+            } => {
+                debug
+                    .field("digest_id_present", &true)
+                    .field("note_id_present", &note_id.is_some())
+                    .field("period_start", period_start)
+                    .field("period_end", period_end)
+                    .field("note_count", note_count)
+                    .field("summary_len", &text_len(summary));
+            }
         }
 
         debug.finish()
@@ -716,6 +820,7 @@ impl ServerEvent {
             ServerEvent::JobProgress { .. } => "JobProgress",
             ServerEvent::JobCompleted { .. } => "JobCompleted",
             ServerEvent::JobFailed { .. } => "JobFailed",
+            ServerEvent::JobDeadLettered { .. } => "JobDeadLettered",
             ServerEvent::NoteUpdated { .. } => "NoteUpdated",
             ServerEvent::NoteCreated { .. } => "NoteCreated",
             ServerEvent::NoteDeleted { .. } => "NoteDeleted",
@@ -760,6 +865,10 @@ impl ServerEvent {
             ServerEvent::ReadmodelSearchReady { .. } => "ReadmodelSearchReady",
             ServerEvent::InferenceAvailabilityChanged { .. } => "InferenceAvailabilityChanged",
             ServerEvent::InferenceConfigChanged { .. } => "InferenceConfigChanged",
+            ServerEvent::ReviewDue { .. } => "ReviewDue",
+            ServerEvent::BackupCompleted { .. } => "BackupCompleted",
+            ServerEvent::BackupFailed { .. } => "BackupFailed",
+            ServerEvent::DigestGenerated { .. } => "DigestGenerated",
         }
     }
 
@@ -772,6 +881,7 @@ impl ServerEvent {
             ServerEvent::JobProgress { .. } => "job.progress",
             ServerEvent::JobCompleted { .. } => "job.completed",
             ServerEvent::JobFailed { .. } => "job.failed",
+            ServerEvent::JobDeadLettered { .. } => "job.dead_lettered",
             ServerEvent::NoteUpdated { .. } => "note.updated",
             ServerEvent::NoteCreated { .. } => "note.created",
             ServerEvent::NoteDeleted { .. } => "note.deleted",
@@ -816,6 +926,10 @@ impl ServerEvent {
             ServerEvent::ReadmodelSearchReady { .. } => "readmodel.search.ready",
             ServerEvent::InferenceAvailabilityChanged { .. } => "inference.availability.changed",
             ServerEvent::InferenceConfigChanged { .. } => "inference.config.changed",
+            ServerEvent::ReviewDue { .. } => "review.due",
+            ServerEvent::BackupCompleted { .. } => "backup.completed",
+            ServerEvent::BackupFailed { .. } => "backup.failed",
+            ServerEvent::DigestGenerated { .. } => "digest.generated",
         }
     }
 
@@ -827,7 +941,8 @@ impl ServerEvent {
             | ServerEvent::JobStarted { .. }
             | ServerEvent::JobProgress { .. }
             | ServerEvent::JobCompleted { .. }
-            | ServerEvent::JobFailed { .. } => Some("job"),
+            | ServerEvent::JobFailed { .. }
+            | ServerEvent::JobDeadLettered { .. } => Some("job"),
             ServerEvent::NoteUpdated { .. }
             | ServerEvent::NoteCreated { .. }
             | ServerEvent::NoteDeleted { .. }
@@ -869,6 +984,11 @@ impl ServerEvent {
             | ServerEvent::ReadmodelSearchReady { .. } => Some("index"),
             ServerEvent::InferenceAvailabilityChanged { .. } => Some("inference"),
             ServerEvent::InferenceConfigChanged { .. } => Some("inference"),
+            ServerEvent::ReviewDue { .. } => Some("review"),
+            ServerEvent::BackupCompleted { .. } | ServerEvent::BackupFailed { .. } => {
+                Some("backup_policy")
+            }
+            ServerEvent::DigestGenerated { .. } => Some("digest"),
         }
     }
 
@@ -880,7 +1000,8 @@ impl ServerEvent {
             | ServerEvent::JobStarted { job_id, .. }
             | ServerEvent::JobProgress { job_id, .. }
             | ServerEvent::JobCompleted { job_id, .. }
-            | ServerEvent::JobFailed { job_id, .. } => Some(*job_id),
+            | ServerEvent::JobFailed { job_id, .. }
+            | ServerEvent::JobDeadLettered { job_id, .. } => Some(*job_id),
             ServerEvent::NoteUpdated { note_id, .. }
             | ServerEvent::NoteCreated { note_id, .. }
             | ServerEvent::NoteDeleted { note_id, .. }
@@ -926,6 +1047,10 @@ impl ServerEvent {
             ServerEvent::ReadmodelGraphUpdated { note_id, .. } => *note_id,
             ServerEvent::InferenceAvailabilityChanged { .. } => None,
             ServerEvent::InferenceConfigChanged { .. } => None,
+            ServerEvent::ReviewDue { .. } => None,
+            ServerEvent::BackupCompleted { policy_id, .. }
+            | ServerEvent::BackupFailed { policy_id, .. } => Some(*policy_id),
+            ServerEvent::DigestGenerated { digest_id, .. } => Some(*digest_id),
         }
     }
 }
@@ -990,13 +1115,18 @@ impl ServerEvent {
             | ServerEvent::JobStarted { .. }
             | ServerEvent::JobCompleted { .. }
             | ServerEvent::JobFailed { .. }
+            | ServerEvent::JobDeadLettered { .. }
             | ServerEvent::IndexEmbeddingUpdated { .. }
             | ServerEvent::IndexLinkingUpdated { .. }
             | ServerEvent::IndexFtsUpdated { .. }
             | ServerEvent::ReadmodelGraphUpdated { .. }
             | ServerEvent::ReadmodelSearchReady { .. }
             | ServerEvent::InferenceAvailabilityChanged { .. }
-            | ServerEvent::InferenceConfigChanged { .. } => EventPriority::Normal,
+            | ServerEvent::InferenceConfigChanged { .. }
+            | ServerEvent::ReviewDue { .. }
+            | ServerEvent::BackupCompleted { .. }
+            | ServerEvent::BackupFailed { .. }
+            | ServerEvent::DigestGenerated { .. } => EventPriority::Normal,
 
             // Telemetry and progress — coalescable
             ServerEvent::QueueStatus { .. }
@@ -1075,6 +1205,7 @@ impl ServerEvent {
             ServerEvent::JobProgress { .. } => "Job progress update",
             ServerEvent::JobCompleted { .. } => "A job completed successfully",
             ServerEvent::JobFailed { .. } => "A job failed",
+            ServerEvent::JobDeadLettered { .. } => "A job landed in the dead-letter queue",
             ServerEvent::NoteUpdated { .. } => {
                 "A note was created, updated, or had its AI content refreshed"
             }
@@ -1137,6 +1268,14 @@ impl ServerEvent {
             ServerEvent::InferenceConfigChanged { .. } => {
                 "Inference configuration was changed by an operator (hot-swap)"
             }
+            ServerEvent::ReviewDue { .. } => "Spaced repetition review cards are due",
+            ServerEvent::BackupCompleted { .. } => {
+                "A scheduled backup completed and retention was applied"
+            }
+            ServerEvent::BackupFailed { .. } => "A scheduled backup failed",
+            ServerEvent::DigestGenerated { .. } => {
+                "A scheduled digest of recent memory activity was generated"
+            }
         }
     }
 
@@ -1181,6 +1320,13 @@ impl ServerEvent {
                 note_id: None,
                 error: String::new(),
             },
+            ServerEvent::JobDeadLettered {
+                job_id: dummy_id,
+                job_type: String::new(),
+                note_id: None,
+                failure_class: String::new(),
+                failure_code: String::new(),
+            },
             ServerEvent::NoteUpdated {
                 note_id: dummy_id,
                 title: None,
@@ -1318,6 +1464,30 @@ impl ServerEvent {
                 embedding_backend: None,
                 changed_fields: Vec::new(),
             },
+            // Spaced repetition review
+            ServerEvent::ReviewDue { due_count: 0 },
+            // Scheduled backups
+            ServerEvent::BackupCompleted {
+                policy_id: dummy_id,
+                run_id: dummy_id,
+                filename: String::new(),
+                size_bytes: 0,
+                pruned_count: 0,
+            },
+            ServerEvent::BackupFailed {
+                policy_id: dummy_id,
+                run_id: dummy_id,
+                error: String::new(),
+            },
+            // Digests
+            ServerEvent::DigestGenerated {
+                digest_id: dummy_id,
+                note_id: None,
+                period_start: Utc::now(),
+                period_end: Utc::now(),
+                note_count: 0,
+                summary: String::new(),
+            },
         ];
 
         variants
@@ -1411,12 +1581,20 @@ pub struct SseMetricsSnapshot {
 /// The bus retains the last N events in a bounded ring buffer for SSE
 /// `Last-Event-ID` replay. Clients that reconnect with a valid event ID
 /// receive all events since that ID before joining the live stream.
+///
+/// Memory-scoped events are additionally retained in a small per-archive ring
+/// buffer, so a busy archive cannot evict a quiet archive's history from the
+/// shared buffer before its clients reconnect.
 pub struct EventBus {
     tx: broadcast::Sender<EventEnvelope>,
     /// Bounded ring buffer for SSE Last-Event-ID replay (Issue #456).
     replay_buffer: Mutex<VecDeque<EventEnvelope>>,
     /// Maximum events retained in the replay buffer.
     replay_capacity: usize,
+    /// Per-archive ring buffers keyed by memory name.
+    archive_replay: Mutex<HashMap<String, VecDeque<EventEnvelope>>>,
+    /// Maximum events retained per archive; 0 disables per-archive buffering.
+    archive_replay_capacity: usize,
     /// SSE subsystem metrics (Issue #459).
     pub metrics: SseMetrics,
 }
@@ -1452,10 +1630,18 @@ impl EventBus {
             tx,
             replay_buffer: Mutex::new(VecDeque::with_capacity(replay_capacity)),
             replay_capacity,
+            archive_replay: Mutex::new(HashMap::new()),
+            archive_replay_capacity: crate::defaults::SSE_REPLAY_ARCHIVE_BUFFER_SIZE,
             metrics: SseMetrics::default(),
         }
     }
 
+    /// Set the per-archive replay capacity (0 disables per-archive buffering).
+    pub fn with_archive_replay(mut self, archive_replay_capacity: usize) -> Self {
+        self.archive_replay_capacity = archive_replay_capacity;
+        self
+    }
+
     /// Emit an event to all subscribers (system actor, no memory scope).
     ///
     /// The event is automatically wrapped in an [`EventEnvelope`] with a
@@ -1507,6 +1693,12 @@ impl EventBus {
         self.tx.receiver_count()
     }
 
+    /// Returns the number of events queued on the bus that at least one
+    /// subscriber has not yet received — how far the slowest subscriber lags.
+    pub fn backlog(&self) -> usize {
+        self.tx.len()
+    }
+
     /// Replay events since the given event ID (exclusive).
     ///
     /// Returns events emitted after `last_event_id`, in chronological order.
@@ -1521,6 +1713,43 @@ impl EventBus {
         pos.map(|idx| buffer.iter().skip(idx + 1).cloned().collect())
     }
 
+    /// Replay events since the given event ID for a memory-scoped stream.
+    ///
+    /// Tries the shared buffer first. When the cursor has been evicted there,
+    /// falls back to the archive's own buffer and merges in the system events
+    /// (no memory scope) still held by the shared buffer, in event-ID order.
+    /// Returns `None` when neither buffer holds the cursor.
+    pub fn replay_since_in_memory(
+        &self,
+        memory: &str,
+        last_event_id: Uuid,
+    ) -> Option<Vec<EventEnvelope>> {
+        if let Some(events) = self.replay_since(last_event_id) {
+            return Some(events);
+        }
+
+        let archive_events: Vec<EventEnvelope> = {
+            let buffers = self.archive_replay.lock().unwrap();
+            let buffer = buffers.get(memory)?;
+            let idx = buffer.iter().position(|e| e.event_id == last_event_id)?;
+            buffer.iter().skip(idx + 1).cloned().collect()
+        };
+
+        let system_events: Vec<EventEnvelope> = self
+            .replay_buffer
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.memory.is_none() && e.event_id > last_event_id)
+            .cloned()
+            .collect();
+
+        let mut merged = archive_events;
+        merged.extend(system_events);
+        merged.sort_by_key(|e| e.event_id);
+        Some(merged)
+    }
+
     /// Returns the number of events currently in the replay buffer.
     pub fn replay_buffer_len(&self) -> usize {
         self.replay_buffer.lock().unwrap().len()
@@ -1531,10 +1760,39 @@ impl EventBus {
         self.replay_capacity
     }
 
-    /// Push an event to the replay buffer, evicting the oldest if at capacity.
+    /// Returns the number of events retained for `memory` in its archive buffer.
+    pub fn archive_replay_len(&self, memory: &str) -> usize {
+        self.archive_replay
+            .lock()
+            .unwrap()
+            .get(memory)
+            .map_or(0, VecDeque::len)
+    }
+
+    /// Returns the per-archive replay buffer capacity.
+    pub fn archive_replay_capacity(&self) -> usize {
+        self.archive_replay_capacity
+    }
+
+    /// Push an event to the replay buffers, evicting the oldest if at capacity.
     fn push_to_replay(&self, envelope: &EventEnvelope) {
-        let mut buffer = self.replay_buffer.lock().unwrap();
-        if buffer.len() >= self.replay_capacity {
+        {
+            let mut buffer = self.replay_buffer.lock().unwrap();
+            if buffer.len() >= self.replay_capacity {
+                buffer.pop_front();
+            }
+            buffer.push_back(envelope.clone());
+        }
+
+        let Some(memory) = envelope.memory.as_deref() else {
+            return;
+        };
+        if self.archive_replay_capacity == 0 {
+            return;
+        }
+        let mut buffers = self.archive_replay.lock().unwrap();
+        let buffer = buffers.entry(memory.to_string()).or_default();
+        if buffer.len() >= self.archive_replay_capacity {
             buffer.pop_front();
         }
         buffer.push_back(envelope.clone());
@@ -1626,6 +1884,28 @@ mod tests {
         assert_eq!(bus.subscriber_count(), 1);
     }
 
+    #[tokio::test]
+    async fn test_event_bus_backlog_tracks_slowest_subscriber() {
+        let bus = EventBus::new(32);
+        let mut rx = bus.subscribe();
+        assert_eq!(bus.backlog(), 0);
+
+        bus.emit(ServerEvent::QueueStatus {
+            total_jobs: 0,
+            running: 0,
+            pending: 0,
+        });
+        bus.emit(ServerEvent::QueueStatus {
+            total_jobs: 0,
+            running: 0,
+            pending: 0,
+        });
+        assert_eq!(bus.backlog(), 2);
+
+        rx.recv().await.unwrap();
+        assert_eq!(bus.backlog(), 1);
+    }
+
     #[test]
     fn test_server_event_json_serialization() {
         let event = ServerEvent::JobStarted {
@@ -2195,6 +2475,17 @@ mod tests {
             .namespaced_event_type(),
             "job.failed"
         );
+        assert_eq!(
+            ServerEvent::JobDeadLettered {
+                job_id: Uuid::nil(),
+                job_type: String::new(),
+                note_id: None,
+                failure_class: "poison".to_string(),
+                failure_code: "poison_payload".to_string(),
+            }
+            .namespaced_event_type(),
+            "job.dead_lettered"
+        );
         assert_eq!(
             ServerEvent::NoteUpdated {
                 note_id: Uuid::nil(),
@@ -2411,6 +2702,77 @@ mod tests {
     fn test_replay_capacity_getter() {
         let bus = EventBus::with_replay(32, 512);
         assert_eq!(bus.replay_capacity(), 512);
+        assert_eq!(
+            bus.archive_replay_capacity(),
+            crate::defaults::SSE_REPLAY_ARCHIVE_BUFFER_SIZE
+        );
+        assert_eq!(bus.with_archive_replay(8).archive_replay_capacity(), 8);
+    }
+
+    fn emit_in_memory(bus: &EventBus, memory: Option<&str>) -> Uuid {
+        let envelope = EventEnvelope::with_context(
+            ServerEvent::NoteCreated {
+                note_id: Uuid::now_v7(),
+                title: None,
+                tags: vec![],
+            },
+            EventContext {
+                memory: memory.map(str::to_string),
+                ..Default::default()
+            },
+        );
+        let id = envelope.event_id;
+        bus.push_to_replay(&envelope);
+        id
+    }
+
+    #[test]
+    fn test_archive_replay_survives_shared_buffer_eviction() {
+        let bus = EventBus::with_replay(32, 3).with_archive_replay(4);
+
+        let quiet_first = emit_in_memory(&bus, Some("quiet"));
+        let quiet_second = emit_in_memory(&bus, Some("quiet"));
+        let system = emit_in_memory(&bus, None);
+        for _ in 0..3 {
+            emit_in_memory(&bus, Some("busy"));
+        }
+        let system_late = emit_in_memory(&bus, None);
+
+        // The shared buffer no longer holds the quiet archive's cursor.
+        assert!(bus.replay_since(quiet_first).is_none());
+        assert_eq!(bus.archive_replay_len("quiet"), 2);
+        assert_eq!(bus.archive_replay_len("busy"), 3);
+
+        let replayed = bus.replay_since_in_memory("quiet", quiet_first).unwrap();
+        let ids: Vec<Uuid> = replayed.iter().map(|e| e.event_id).collect();
+        // `system` was evicted from the shared buffer; only retained system
+        // events are merged back in, in event-ID order.
+        assert!(!ids.contains(&system));
+        assert_eq!(ids, vec![quiet_second, system_late]);
+
+        // Unknown archive or cursor → expired.
+        assert!(bus.replay_since_in_memory("other", quiet_first).is_none());
+        assert!(bus.replay_since_in_memory("quiet", Uuid::nil()).is_none());
+    }
+
+    #[test]
+    fn test_archive_replay_capacity_eviction_and_disable() {
+        let bus = EventBus::with_replay(32, 100).with_archive_replay(2);
+        let first = emit_in_memory(&bus, Some("research"));
+        for _ in 0..2 {
+            emit_in_memory(&bus, Some("research"));
+        }
+        assert_eq!(bus.archive_replay_len("research"), 2);
+        // Still found in the shared buffer.
+        assert_eq!(
+            bus.replay_since_in_memory("research", first).unwrap().len(),
+            2
+        );
+
+        let disabled = EventBus::with_replay(32, 100).with_archive_replay(0);
+        emit_in_memory(&disabled, Some("research"));
+        assert_eq!(disabled.archive_replay_len("research"), 0);
+        assert_eq!(disabled.replay_buffer_len(), 1);
     }
 
     // -- Priority and coalescing tests (Issue #458) --
@@ -2572,15 +2934,15 @@ mod tests {
         let meta = ServerEvent::all_variants_metadata();
         assert_eq!(
             meta.len(),
-            48,
-            "Expected 48 event variants, got {}",
+            53,
+            "Expected 53 event variants, got {}",
             meta.len()
         );
 
         // All namespaced types should be unique
         let types: std::collections::HashSet<&str> =
             meta.iter().map(|m| m.namespaced_type).collect();
-        assert_eq!(types.len(), 48, "Duplicate namespaced_type found");
+        assert_eq!(types.len(), 53, "Duplicate namespaced_type found");
 
         // All descriptions should be non-empty
         for m in &meta {
@@ -15,6 +15,8 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use uuid::Uuid;
 
+use crate::models::{NoteFull, ProvenanceActivity, ProvenanceEdge};
+
 fn debug_len(value: &str) -> usize {
     value.chars().count()
 }
@@ -194,6 +196,10 @@ impl DublinCoreExport {
 /// JSON-LD context for linked data export.
 #[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct JsonLdContext {
+    /// Default vocabulary for unprefixed terms (Dublin Core elements), so the
+    /// flattened Dublin Core fields resolve to DC IRIs.
+    #[serde(rename = "@vocab", default = "default_json_ld_vocab")]
+    pub vocab: String,
     /// Dublin Core namespace
     pub dc: String,
     /// SKOS namespace
@@ -207,6 +213,7 @@ pub struct JsonLdContext {
 impl fmt::Debug for JsonLdContext {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("JsonLdContext")
+            .field("vocab_len", &debug_len(&self.vocab))
             .field("dc_len", &debug_len(&self.dc))
             .field("skos_len", &debug_len(&self.skos))
             .field("prov_len", &debug_len(&self.prov))
@@ -215,9 +222,14 @@ impl fmt::Debug for JsonLdContext {
     }
 }
 
+fn default_json_ld_vocab() -> String {
+    "http://purl.org/dc/elements/1.1/".to_string()
+}
+
 impl Default for JsonLdContext {
     fn default() -> Self {
         Self {
+            vocab: default_json_ld_vocab(),
             dc: "http://purl.org/dc/elements/1.1/".to_string(),
             skos: "http://www.w3.org/2004/02/skos/core#".to_string(),
             prov: "http://www.w3.org/ns/prov#".to_string(),
@@ -250,12 +262,26 @@ pub struct JsonLdExport {
     /// W3C PROV generation activity
     #[serde(rename = "prov:wasGeneratedBy")]
     pub prov_generated_by: Option<String>,
+    /// schema.org last modification time
+    #[serde(
+        rename = "schema:dateModified",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub date_modified: Option<DateTime<Utc>>,
+    /// schema.org body text (revised content, or original when no revision)
+    #[serde(
+        rename = "schema:text",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub text: Option<String>,
 }
 
 impl fmt::Debug for JsonLdExport {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("JsonLdExport")
-            .field("context_namespace_count", &4)
+            .field("context_namespace_count", &5)
             .field("id_len", &debug_len(&self.id))
             .field("ld_type_len", &debug_len(&self.ld_type))
             .field("dublin_core", &self.dublin_core)
@@ -281,6 +307,8 @@ impl fmt::Debug for JsonLdExport {
                 "prov_generated_by_len",
                 &optional_debug_len(self.prov_generated_by.as_ref()),
             )
+            .field("date_modified", &self.date_modified)
+            .field("text_len", &optional_debug_len(self.text.as_ref()))
             .finish()
     }
 }
@@ -297,12 +325,136 @@ impl JsonLdExport {
         Self {
             context: JsonLdContext::default(),
             id,
-            ld_type: "schema:DigitalDocument".to_string(),
+            ld_type: "schema:CreativeWork".to_string(),
             dublin_core: dc,
             skos_concepts,
             prov_derived_from,
             prov_generated_by,
+            date_modified: None,
+            text: None,
+        }
+    }
+
+    /// Build the full JSON-LD export for a note.
+    ///
+    /// SKOS concepts and PROV sources are emitted as `urn:uuid:` references
+    /// (or the source URL for external derivations). The most recent
+    /// provenance activity becomes `prov:wasGeneratedBy`.
+    pub fn from_note_full(
+        note: &NoteFull,
+        edges: &[ProvenanceEdge],
+        activities: &[ProvenanceActivity],
+    ) -> Self {
+        let note_id = note.note.id;
+        let mut linked_note_ids: Vec<Uuid> = Vec::new();
+        for link in &note.links {
+            for id in [Some(link.from_note_id), link.to_note_id]
+                .into_iter()
+                .flatten()
+            {
+                if id != note_id && !linked_note_ids.contains(&id) {
+                    linked_note_ids.push(id);
+                }
+            }
+        }
+        let has_ai_revision =
+            note.revised.generation_count > 0 || note.revised.last_revision_id.is_some();
+        let text = if note.revised.content.is_empty() {
+            &note.original.content
+        } else {
+            &note.revised.content
+        };
+
+        let dc = DublinCoreExport::from_note(
+            note_id,
+            note.note.title.as_deref(),
+            &note.tags,
+            note.note.created_at_utc,
+            None,
+            &linked_note_ids,
+            has_ai_revision,
+        )
+        .with_description(text);
+
+        let skos_concepts = note
+            .concepts
+            .iter()
+            .map(|concept| format!("urn:uuid:{}", concept.concept_id))
+            .collect();
+
+        let mut prov_derived_from: Vec<String> = Vec::new();
+        for edge in edges {
+            let source = edge
+                .source_note_id
+                .map(|id| format!("urn:uuid:{}", id))
+                .or_else(|| edge.source_url.clone());
+            if let Some(source) = source {
+                if !prov_derived_from.contains(&source) {
+                    prov_derived_from.push(source);
+                }
+            }
         }
+
+        let prov_generated_by = activities
+            .iter()
+            .max_by_key(|activity| activity.started_at)
+            .map(|activity| format!("urn:uuid:{}", activity.id));
+
+        let mut export =
+            Self::from_dublin_core(dc, skos_concepts, prov_derived_from, prov_generated_by);
+        export.date_modified = Some(note.note.updated_at_utc);
+        export.text = Some(text.clone());
+        export
+    }
+}
+
+/// Archive-wide JSON-LD document: one shared context and a `@graph` of notes.
+#[derive(Clone, Serialize, utoipa::ToSchema)]
+pub struct JsonLdGraph {
+    #[serde(rename = "@context")]
+    pub context: JsonLdContext,
+    /// Note nodes with their per-node `@context` removed.
+    #[serde(rename = "@graph")]
+    pub graph: Vec<serde_json::Value>,
+}
+
+impl fmt::Debug for JsonLdGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonLdGraph")
+            .field("graph_count", &self.graph.len())
+            .finish()
+    }
+}
+
+/// Closes a graph document opened with [`JsonLdGraph::stream_head`].
+pub const JSON_LD_GRAPH_STREAM_TAIL: &str = "]}";
+
+impl JsonLdGraph {
+    /// Collect note exports into a single graph document.
+    pub fn from_exports(exports: Vec<JsonLdExport>) -> Self {
+        Self {
+            context: JsonLdContext::default(),
+            graph: exports.into_iter().filter_map(Self::node).collect(),
+        }
+    }
+
+    /// A note export as a `@graph` node, without its own `@context`.
+    pub fn node(export: JsonLdExport) -> Option<serde_json::Value> {
+        match serde_json::to_value(export) {
+            Ok(serde_json::Value::Object(mut node)) => {
+                node.remove("@context");
+                Some(serde_json::Value::Object(node))
+            }
+            _ => None,
+        }
+    }
+
+    /// Opening of a graph document written node by node: the shared
+    /// `@context` and the start of the `@graph` array. Nodes follow,
+    /// comma-separated, then [`JSON_LD_GRAPH_STREAM_TAIL`].
+    pub fn stream_head() -> String {
+        let context = serde_json::to_string(&JsonLdContext::default()).unwrap_or_default();
+        format!("{{\"@context\":{context},\"@graph\":[")
     }
 }
 
@@ -619,7 +771,7 @@ mod tests {
         );
 
         assert!(ld.id.starts_with("urn:uuid:"));
-        assert_eq!(ld.ld_type, "schema:DigitalDocument");
+        assert_eq!(ld.ld_type, "schema:CreativeWork");
         assert_eq!(ld.skos_concepts.len(), 1);
         assert_eq!(ld.prov_derived_from.len(), 1);
         assert!(ld.prov_generated_by.is_some());
@@ -642,7 +794,180 @@ mod tests {
         assert!(json.contains("@context"));
         assert!(json.contains("@id"));
         assert!(json.contains("@type"));
-        assert!(json.contains("schema:DigitalDocument"));
+        assert!(json.contains("schema:CreativeWork"));
+    }
+
+    fn sample_note_full(note_id: Uuid, linked_id: Uuid, concept_id: Uuid) -> NoteFull {
+        use crate::models::{Link, NoteConceptSummary, NoteMeta, NoteOriginal, NoteRevised};
+
+        let now = Utc::now();
+        NoteFull {
+            note: NoteMeta {
+                id: note_id,
+                collection_id: None,
+                format: "markdown".to_string(),
+                source: "test".to_string(),
+                created_at_utc: now,
+                updated_at_utc: now,
+                starred: false,
+                archived: false,
+                pinned: false,
+                priority: 0,
+                last_accessed_at: None,
+                access_count: 0,
+                title: Some("Ownership".to_string()),
+                metadata: serde_json::json!({}),
+                chunk_metadata: None,
+                document_type_id: None,
+                encrypted: false,
+                language: None,
+            },
+            original: NoteOriginal {
+                content: "Original body".to_string(),
+                hash: String::new(),
+                user_created_at: None,
+                user_last_edited_at: None,
+            },
+            revised: NoteRevised {
+                content: "Revised body\n\nSecond paragraph".to_string(),
+                last_revision_id: Some(Uuid::new_v4()),
+                ai_metadata: None,
+                ai_generated_at: None,
+                user_last_edited_at: None,
+                is_user_edited: false,
+                generation_count: 1,
+                model: None,
+            },
+            tags: vec!["rust".to_string()],
+            concepts: vec![NoteConceptSummary {
+                concept_id,
+                notation: None,
+                pref_label: Some("Rust".to_string()),
+                source: "manual".to_string(),
+                confidence: None,
+                relevance_score: 1.0,
+                is_primary: true,
+            }],
+            links: vec![Link {
+                id: Uuid::new_v4(),
+                from_note_id: note_id,
+                to_note_id: Some(linked_id),
+                to_url: None,
+                kind: "semantic".to_string(),
+                score: 0.9,
+                created_at_utc: now,
+                snippet: None,
+                metadata: None,
+            }],
+            summary: None,
+            translation: None,
+        }
+    }
+
+    #[test]
+    fn test_json_ld_from_note_full() {
+        let note_id = Uuid::new_v4();
+        let linked_id = Uuid::new_v4();
+        let concept_id = Uuid::new_v4();
+        let source_id = Uuid::new_v4();
+        let note = sample_note_full(note_id, linked_id, concept_id);
+        let edges = vec![ProvenanceEdge {
+            id: Uuid::new_v4(),
+            revision_id: Uuid::new_v4(),
+            source_note_id: Some(source_id),
+            source_url: None,
+            relation: "prov:wasDerivedFrom".to_string(),
+            created_at_utc: Utc::now(),
+        }];
+        let now = Utc::now();
+        let older = ProvenanceActivity {
+            id: Uuid::new_v4(),
+            note_id,
+            revision_id: None,
+            activity_type: "ai_revision".to_string(),
+            model_name: None,
+            started_at: now - chrono::Duration::hours(1),
+            ended_at: None,
+            metadata: None,
+        };
+        let newest = ProvenanceActivity {
+            id: Uuid::new_v4(),
+            started_at: now,
+            ..older.clone()
+        };
+
+        let ld = JsonLdExport::from_note_full(&note, &edges, &[older, newest.clone()]);
+
+        assert_eq!(ld.id, format!("urn:uuid:{}", note_id));
+        assert_eq!(ld.ld_type, "schema:CreativeWork");
+        assert_eq!(ld.dublin_core.title, "Ownership");
+        assert_eq!(ld.dublin_core.subject, vec!["rust".to_string()]);
+        assert_eq!(
+            ld.dublin_core.relation,
+            vec![format!("urn:uuid:{}", linked_id)]
+        );
+        assert_eq!(ld.dublin_core.description.as_deref(), Some("Revised body"));
+        assert_eq!(ld.dublin_core.contributor.len(), 1);
+        assert_eq!(ld.skos_concepts, vec![format!("urn:uuid:{}", concept_id)]);
+        assert_eq!(
+            ld.prov_derived_from,
+            vec![format!("urn:uuid:{}", source_id)]
+        );
+        assert_eq!(
+            ld.prov_generated_by,
+            Some(format!("urn:uuid:{}", newest.id))
+        );
+        assert_eq!(ld.text.as_deref(), Some("Revised body\n\nSecond paragraph"));
+
+        let json = serde_json::to_value(&ld).unwrap();
+        assert_eq!(
+            json["@context"]["@vocab"],
+            "http://purl.org/dc/elements/1.1/"
+        );
+        assert!(json.get("schema:dateModified").is_some());
+    }
+
+    #[test]
+    fn test_json_ld_graph_shares_one_context() {
+        let note = sample_note_full(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let graph = JsonLdGraph::from_exports(vec![
+            JsonLdExport::from_note_full(&note, &[], &[]),
+            JsonLdExport::from_note_full(&note, &[], &[]),
+        ]);
+
+        let json = serde_json::to_value(&graph).unwrap();
+        assert!(json.get("@context").is_some());
+        let nodes = json["@graph"].as_array().unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert!(nodes.iter().all(|node| node.get("@context").is_none()));
+        assert!(nodes
+            .iter()
+            .all(|node| node["@type"] == "schema:CreativeWork"));
+    }
+
+    #[test]
+    fn test_json_ld_graph_stream_matches_the_collected_graph() {
+        let note = sample_note_full(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let exports = vec![
+            JsonLdExport::from_note_full(&note, &[], &[]),
+            JsonLdExport::from_note_full(&note, &[], &[]),
+        ];
+        let nodes: Vec<String> = exports
+            .iter()
+            .cloned()
+            .filter_map(JsonLdGraph::node)
+            .map(|node| node.to_string())
+            .collect();
+        let streamed = format!(
+            "{}{}{}",
+            JsonLdGraph::stream_head(),
+            nodes.join(","),
+            JSON_LD_GRAPH_STREAM_TAIL
+        );
+
+        let streamed: serde_json::Value = serde_json::from_str(&streamed).unwrap();
+        let collected = serde_json::to_value(JsonLdGraph::from_exports(exports)).unwrap();
+        assert_eq!(streamed, collected);
     }
 
     #[test]
@@ -821,19 +1146,26 @@ mod tests {
         );
 
         let context = JsonLdContext {
+            vocab: "ðð".to_string(),
             dc: "êê".to_string(),
             skos: "óó".to_string(),
             prov: "úú".to_string(),
             schema: "ää".to_string(),
         };
         let context_debug = format!("{context:?}");
-        for expected in ["dc_len: 2", "skos_len: 2", "prov_len: 2", "schema_len: 2"] {
+        for expected in [
+            "vocab_len: 2",
+            "dc_len: 2",
+            "skos_len: 2",
+            "prov_len: 2",
+            "schema_len: 2",
+        ] {
             assert!(
                 context_debug.contains(expected),
                 "expected {expected:?} in {context_debug}"
             );
         }
-        assert_debug_excludes(&context_debug, &["êê", "óó", "úú", "ää"]);
+        assert_debug_excludes(&context_debug, &["ðð", "êê", "óó", "úú", "ää"]);
 
         let ld = JsonLdExport {
             context,
@@ -843,6 +1175,8 @@ mod tests {
             skos_concepts: vec!["ìì".to_string()],
             prov_derived_from: vec!["òò".to_string()],
             prov_generated_by: Some("œœ".to_string()),
+            date_modified: None,
+            text: Some("ţţ".to_string()),
         };
         let ld_debug = format!("{ld:?}");
         for expected in [
@@ -851,13 +1185,14 @@ mod tests {
             "skos_concept_lens: [2]",
             "prov_derived_from_lens: [2]",
             "prov_generated_by_len: Some(2)",
+            "text_len: Some(2)",
         ] {
             assert!(
                 ld_debug.contains(expected),
                 "expected {expected:?} in {ld_debug}"
             );
         }
-        assert_debug_excludes(&ld_debug, &["ëë", "íí", "ìì", "òò", "œœ"]);
+        assert_debug_excludes(&ld_debug, &["ëë", "íí", "ìì", "òò", "œœ", "ţţ"]);
 
         let score = FairScore {
             findable: 0.1,
@@ -0,0 +1,663 @@
+//! Cross-instance archive replication.
+//!
+//! A [`SyncPeer`] names another Fortemi instance and the memory archive to
+//! replicate with it. Each instance serves a change feed of its archive:
+//! every note whose `updated_at_utc` or `deleted_at` moved, plus tombstones
+//! for hard-deleted notes, ordered by `(changed_at, note_id)`. A sync run
+//! pulls the remote feed and pushes the local one from the cursors saved on
+//! the peer, so each run only transfers what changed since the last.
+//!
+//! The receiving side decides each change with the peer's
+//! [`ConflictStrategy`], comparing the incoming `changed_at` with when the
+//! note last changed locally.
+//!
+//! ```
+//! use chrono::{Duration, Utc};
+//! use matric_core::{ConflictStrategy, SyncDecision};
+//!
+//! let local = Utc::now();
+//! let newer = local + Duration::seconds(5);
+//! assert_eq!(ConflictStrategy::NewestWins.decide(Some(local), newer), SyncDecision::Apply);
+//! assert_eq!(ConflictStrategy::NewestWins.decide(Some(newer), local), SyncDecision::Conflict);
+//! assert_eq!(ConflictStrategy::LocalWins.decide(None, local), SyncDecision::Apply);
+//! ```
+
+use std::fmt;
+use std::ops::AddAssign;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{CronSchedule, Error, Result};
+
+/// Directions a peer can replicate in.
+pub const SYNC_DIRECTIONS: [&str; 3] = ["push", "pull", "both"];
+
+/// Longest accepted peer name.
+pub const MAX_SYNC_PEER_NAME_LEN: usize = 64;
+
+/// Longest accepted memory archive name on either side of a peer.
+pub const MAX_SYNC_MEMORY_NAME_LEN: usize = 128;
+
+/// Changes per feed page when the caller does not ask for a size.
+pub const DEFAULT_SYNC_PAGE_SIZE: i64 = 100;
+
+/// Upper bound for a feed page.
+pub const MAX_SYNC_PAGE_SIZE: i64 = 500;
+
+/// How the receiving instance resolves a change to a note it also has.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// Keep whichever side changed the note last.
+    #[default]
+    NewestWins,
+    /// Always take the incoming change.
+    IncomingWins,
+    /// Never overwrite a note the receiver already has (or deleted); only
+    /// new notes are taken.
+    LocalWins,
+}
+
+/// Outcome of comparing an incoming change with the local copy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncDecision {
+    /// Replace (or delete) the local copy.
+    Apply,
+    /// Both sides already agree on the note's last change.
+    Unchanged,
+    /// The strategy keeps the local copy.
+    Conflict,
+}
+
+impl ConflictStrategy {
+    /// Stable database and API representation.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::NewestWins => "newest_wins",
+            Self::IncomingWins => "incoming_wins",
+            Self::LocalWins => "local_wins",
+        }
+    }
+
+    /// Parses the representation returned by [`Self::as_str`].
+    pub fn parse(value: &str) -> Result<Self> {
+        [Self::NewestWins, Self::IncomingWins, Self::LocalWins]
+            .into_iter()
+            .find(|strategy| strategy.as_str() == value)
+            .ok_or_else(|| {
+                Error::InvalidInput(
+                    "conflict_strategy must be one of: newest_wins, incoming_wins, local_wins"
+                        .to_string(),
+                )
+            })
+    }
+
+    /// Decides an incoming change made at `incoming`, given when the note
+    /// last changed locally (`None` if the receiver has never had it).
+    pub fn decide(self, local: Option<DateTime<Utc>>, incoming: DateTime<Utc>) -> SyncDecision {
+        match (self, local) {
+            (_, None) => SyncDecision::Apply,
+            (_, Some(local)) if local == incoming => SyncDecision::Unchanged,
+            (Self::NewestWins, Some(local)) if incoming > local => SyncDecision::Apply,
+            (Self::IncomingWins, Some(_)) => SyncDecision::Apply,
+            (Self::NewestWins | Self::LocalWins, Some(_)) => SyncDecision::Conflict,
+        }
+    }
+}
+
+/// Position in a change feed: the last change a reader has seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SyncCursor {
+    pub changed_at: DateTime<Utc>,
+    pub note_id: Uuid,
+}
+
+/// One note in a change feed.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SyncChange {
+    pub note_id: Uuid,
+    /// When the note last changed on the sending instance
+    pub changed_at: DateTime<Utc>,
+    /// Note snapshot (note row, original and revised content, revisions,
+    /// tags, links); absent for a hard-deleted note
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub snapshot: Option<serde_json::Value>,
+}
+
+impl SyncChange {
+    /// Whether this change records a hard delete.
+    pub fn is_tombstone(&self) -> bool {
+        self.snapshot.is_none()
+    }
+
+    /// Feed position just after this change.
+    pub fn cursor(&self) -> SyncCursor {
+        SyncCursor {
+            changed_at: self.changed_at,
+            note_id: self.note_id,
+        }
+    }
+}
+
+impl fmt::Debug for SyncChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncChange")
+            .field("note_id_set", &true)
+            .field("changed_at", &self.changed_at)
+            .field("tombstone", &self.is_tombstone())
+            .finish()
+    }
+}
+
+/// A page of the change feed.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SyncChangesPage {
+    pub changes: Vec<SyncChange>,
+    /// Cursor to request the next page from; the request cursor when the
+    /// page is empty
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<SyncCursor>,
+    pub has_more: bool,
+}
+
+/// Request body for applying pushed changes.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ApplySyncChangesRequest {
+    pub changes: Vec<SyncChange>,
+    /// Conflict strategy (default: newest_wins)
+    #[serde(default)]
+    pub strategy: ConflictStrategy,
+}
+
+/// Counts of how a batch of changes was applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SyncApplyResult {
+    /// Notes created or replaced
+    pub applied: usize,
+    /// Notes removed by tombstones
+    pub deleted: usize,
+    /// Changes the receiver already had
+    pub unchanged: usize,
+    /// Changes rejected by the conflict strategy
+    pub conflicts: usize,
+}
+
+impl AddAssign for SyncApplyResult {
+    fn add_assign(&mut self, other: Self) {
+        self.applied += other.applied;
+        self.deleted += other.deleted;
+        self.unchanged += other.unchanged;
+        self.conflicts += other.conflicts;
+    }
+}
+
+/// What a sync run transferred in each direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncOutcome {
+    pub pulled: SyncApplyResult,
+    pub pushed: SyncApplyResult,
+}
+
+fn validate_sync_peer_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name.len() > MAX_SYNC_PEER_NAME_LEN
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(Error::InvalidInput(format!(
+            "peer name must be 1-{MAX_SYNC_PEER_NAME_LEN} ASCII letters, digits, '-' or '_'"
+        )));
+    }
+    Ok(())
+}
+
+/// Trims a trailing slash and requires an http(s) origin without query or
+/// fragment.
+fn normalize_base_url(base_url: &str) -> Result<String> {
+    let trimmed = base_url.trim().trim_end_matches('/');
+    let rest = trimmed
+        .strip_prefix("https://")
+        .or_else(|| trimmed.strip_prefix("http://"));
+    match rest {
+        Some(host) if !host.is_empty() && !host.contains(['?', '#', ' ']) => {
+            Ok(trimmed.to_string())
+        }
+        _ => Err(Error::InvalidInput(
+            "base_url must be an http:// or https:// URL without query or fragment".to_string(),
+        )),
+    }
+}
+
+fn validate_memory_name(field: &str, name: Option<&str>) -> Result<()> {
+    match name {
+        Some(name)
+            if name.is_empty()
+                || name.len() > MAX_SYNC_MEMORY_NAME_LEN
+                || name.chars().any(|c| c.is_control()) =>
+        {
+            Err(Error::InvalidInput(format!(
+                "{field} must be 1-{MAX_SYNC_MEMORY_NAME_LEN} printable characters"
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_direction(direction: &str) -> Result<()> {
+    if !SYNC_DIRECTIONS.contains(&direction) {
+        return Err(Error::InvalidInput(format!(
+            "direction must be one of: {}",
+            SYNC_DIRECTIONS.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+fn parse_optional_schedule(schedule: Option<&str>) -> Result<Option<CronSchedule>> {
+    schedule.map(CronSchedule::parse).transpose()
+}
+
+/// A remote instance this instance replicates an archive with.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SyncPeer {
+    pub id: Uuid,
+    pub name: String,
+    /// Origin of the remote instance, e.g. `https://fortemi.example.org`
+    pub base_url: String,
+    /// Whether an API token is stored for the remote; the token itself is
+    /// never returned
+    pub has_api_token: bool,
+    /// Archive on the remote instance; its default archive when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_memory: Option<String>,
+    /// Local archive; the default archive when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_memory: Option<String>,
+    /// `push`, `pull`, or `both`
+    pub direction: String,
+    /// `newest_wins`, `incoming_wins`, or `local_wins`
+    pub conflict_strategy: String,
+    /// Five-field cron expression, evaluated in UTC; manual runs only when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    pub enabled: bool,
+    /// Last remote change pulled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pull_cursor: Option<SyncCursor>,
+    /// Last local change pushed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub push_cursor: Option<SyncCursor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_run_at_utc: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_sync_at_utc: Option<DateTime<Utc>>,
+    /// Status of the most recent run (`running`, `succeeded`, `failed`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub created_at_utc: DateTime<Utc>,
+    pub updated_at_utc: DateTime<Utc>,
+}
+
+impl SyncPeer {
+    /// Returns the peer's conflict strategy.
+    pub fn strategy(&self) -> ConflictStrategy {
+        ConflictStrategy::parse(&self.conflict_strategy).unwrap_or_default()
+    }
+
+    /// Whether runs pull the remote change feed.
+    pub fn pulls(&self) -> bool {
+        matches!(self.direction.as_str(), "pull" | "both")
+    }
+
+    /// Whether runs push the local change feed.
+    pub fn pushes(&self) -> bool {
+        matches!(self.direction.as_str(), "push" | "both")
+    }
+}
+
+impl fmt::Debug for SyncPeer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncPeer")
+            .field("id_set", &true)
+            .field("name_len", &self.name.len())
+            .field("base_url_len", &self.base_url.len())
+            .field("has_api_token", &self.has_api_token)
+            .field(
+                "remote_memory_len",
+                &self.remote_memory.as_ref().map(String::len),
+            )
+            .field(
+                "local_memory_len",
+                &self.local_memory.as_ref().map(String::len),
+            )
+            .field("direction", &self.direction)
+            .field("conflict_strategy", &self.conflict_strategy)
+            .field("schedule_len", &self.schedule.as_ref().map(String::len))
+            .field("enabled", &self.enabled)
+            .field("next_run_at_utc", &self.next_run_at_utc)
+            .field("last_sync_at_utc", &self.last_sync_at_utc)
+            .field("last_status", &self.last_status)
+            .field("last_error_len", &self.last_error.as_ref().map(String::len))
+            .finish()
+    }
+}
+
+fn default_direction() -> String {
+    "both".to_string()
+}
+
+fn default_conflict_strategy() -> String {
+    ConflictStrategy::default().as_str().to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Request body for registering a sync peer.
+#[derive(Clone, Deserialize, utoipa::ToSchema)]
+pub struct CreateSyncPeerRequest {
+    pub name: String,
+    /// Origin of the remote instance
+    pub base_url: String,
+    /// Bearer token for the remote API (needs the admin scope there)
+    pub api_token: Option<String>,
+    /// Archive on the remote instance; its default archive when unset
+    pub remote_memory: Option<String>,
+    /// Local archive; the default archive when unset
+    pub local_memory: Option<String>,
+    /// `push`, `pull`, or `both` (default)
+    #[serde(default = "default_direction")]
+    pub direction: String,
+    /// `newest_wins` (default), `incoming_wins`, or `local_wins`
+    #[serde(default = "default_conflict_strategy")]
+    pub conflict_strategy: String,
+    /// Five-field cron expression, evaluated in UTC; manual runs only when unset
+    pub schedule: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+impl CreateSyncPeerRequest {
+    /// Validates the request, normalizing the base URL, and returns the
+    /// parsed schedule.
+    pub fn validate(&mut self) -> Result<Option<CronSchedule>> {
+        validate_sync_peer_name(&self.name)?;
+        self.base_url = normalize_base_url(&self.base_url)?;
+        validate_memory_name("remote_memory", self.remote_memory.as_deref())?;
+        validate_memory_name("local_memory", self.local_memory.as_deref())?;
+        validate_direction(&self.direction)?;
+        ConflictStrategy::parse(&self.conflict_strategy)?;
+        self.api_token = self.api_token.take().filter(|token| !token.is_empty());
+        self.schedule = self
+            .schedule
+            .take()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        parse_optional_schedule(self.schedule.as_deref())
+    }
+}
+
+impl fmt::Debug for CreateSyncPeerRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateSyncPeerRequest")
+            .field("name_len", &self.name.len())
+            .field("base_url_len", &self.base_url.len())
+            .field("api_token", &self.api_token.as_ref().map(|_| "[REDACTED]"))
+            .field(
+                "remote_memory_len",
+                &self.remote_memory.as_ref().map(String::len),
+            )
+            .field(
+                "local_memory_len",
+                &self.local_memory.as_ref().map(String::len),
+            )
+            .field("direction", &self.direction)
+            .field("conflict_strategy", &self.conflict_strategy)
+            .field("schedule_len", &self.schedule.as_ref().map(String::len))
+            .field("enabled", &self.enabled)
+            .finish()
+    }
+}
+
+/// Request body for updating a sync peer; unset fields are unchanged and an
+/// empty string clears an optional field.
+#[derive(Clone, Default, Deserialize, utoipa::ToSchema)]
+pub struct UpdateSyncPeerRequest {
+    pub base_url: Option<String>,
+    pub api_token: Option<String>,
+    pub remote_memory: Option<String>,
+    pub local_memory: Option<String>,
+    pub direction: Option<String>,
+    pub conflict_strategy: Option<String>,
+    pub schedule: Option<String>,
+    pub enabled: Option<bool>,
+    /// Forget both cursors so the next run replicates the whole archive
+    #[serde(default)]
+    pub reset_cursors: bool,
+}
+
+/// Changes [`UpdateSyncPeerRequest::apply`] made that the caller acts on.
+#[derive(Clone, Default)]
+pub struct SyncPeerUpdate {
+    /// The new schedule, when it changed (`Some(None)` when cleared)
+    pub schedule: Option<Option<CronSchedule>>,
+    /// The new API token, when it changed (`Some(None)` when cleared)
+    pub api_token: Option<Option<String>>,
+}
+
+impl UpdateSyncPeerRequest {
+    /// Applies the update to `peer` after validating each changed field.
+    pub fn apply(&self, peer: &mut SyncPeer) -> Result<SyncPeerUpdate> {
+        let cleared = |value: &String| Some(value.clone()).filter(|v| !v.is_empty());
+        let mut update = SyncPeerUpdate::default();
+
+        if let Some(base_url) = &self.base_url {
+            peer.base_url = normalize_base_url(base_url)?;
+        }
+        if let Some(remote_memory) = &self.remote_memory {
+            peer.remote_memory = cleared(remote_memory);
+            validate_memory_name("remote_memory", peer.remote_memory.as_deref())?;
+        }
+        if let Some(local_memory) = &self.local_memory {
+            peer.local_memory = cleared(local_memory);
+            validate_memory_name("local_memory", peer.local_memory.as_deref())?;
+        }
+        if let Some(direction) = &self.direction {
+            validate_direction(direction)?;
+            peer.direction = direction.clone();
+        }
+        if let Some(strategy) = &self.conflict_strategy {
+            ConflictStrategy::parse(strategy)?;
+            peer.conflict_strategy = strategy.clone();
+        }
+        if let Some(schedule) = &self.schedule {
+            let schedule = cleared(&schedule.trim().to_string());
+            update.schedule = Some(parse_optional_schedule(schedule.as_deref())?);
+            peer.schedule = schedule;
+        }
+        if let Some(token) = &self.api_token {
+            let token = cleared(token);
+            peer.has_api_token = token.is_some();
+            update.api_token = Some(token);
+        }
+        if let Some(enabled) = self.enabled {
+            peer.enabled = enabled;
+        }
+        if self.reset_cursors {
+            peer.pull_cursor = None;
+            peer.push_cursor = None;
+        }
+        Ok(update)
+    }
+}
+
+impl fmt::Debug for UpdateSyncPeerRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpdateSyncPeerRequest")
+            .field("base_url_len", &self.base_url.as_ref().map(String::len))
+            .field("api_token", &self.api_token.as_ref().map(|_| "[REDACTED]"))
+            .field(
+                "remote_memory_len",
+                &self.remote_memory.as_ref().map(String::len),
+            )
+            .field(
+                "local_memory_len",
+                &self.local_memory.as_ref().map(String::len),
+            )
+            .field("direction", &self.direction)
+            .field("conflict_strategy", &self.conflict_strategy)
+            .field("schedule_len", &self.schedule.as_ref().map(String::len))
+            .field("enabled", &self.enabled)
+            .field("reset_cursors", &self.reset_cursors)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap() + Duration::seconds(seconds)
+    }
+
+    fn create_request() -> CreateSyncPeerRequest {
+        serde_json::from_value(serde_json::json!({
+            "name": "laptop",
+            "base_url": "https://fortemi.example.org/",
+            "api_token": "secret-token",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn strategies_resolve_conflicts() {
+        use ConflictStrategy::*;
+        use SyncDecision::*;
+
+        for strategy in [NewestWins, IncomingWins, LocalWins] {
+            assert_eq!(strategy.decide(None, at(0)), Apply);
+            assert_eq!(strategy.decide(Some(at(0)), at(0)), Unchanged);
+        }
+        assert_eq!(NewestWins.decide(Some(at(0)), at(1)), Apply);
+        assert_eq!(NewestWins.decide(Some(at(1)), at(0)), Conflict);
+        assert_eq!(IncomingWins.decide(Some(at(1)), at(0)), Apply);
+        assert_eq!(LocalWins.decide(Some(at(0)), at(1)), Conflict);
+    }
+
+    #[test]
+    fn strategy_round_trips_through_its_name() {
+        for strategy in [
+            ConflictStrategy::NewestWins,
+            ConflictStrategy::IncomingWins,
+            ConflictStrategy::LocalWins,
+        ] {
+            assert_eq!(
+                ConflictStrategy::parse(strategy.as_str()).unwrap(),
+                strategy
+            );
+            assert_eq!(
+                serde_json::to_value(strategy).unwrap(),
+                serde_json::json!(strategy.as_str())
+            );
+        }
+        assert!(ConflictStrategy::parse("merge").is_err());
+    }
+
+    #[test]
+    fn create_request_defaults_and_normalizes() {
+        let mut req = create_request();
+        assert!(req.validate().unwrap().is_none());
+        assert_eq!(req.base_url, "https://fortemi.example.org");
+        assert_eq!(req.direction, "both");
+        assert_eq!(req.conflict_strategy, "newest_wins");
+        assert!(req.enabled);
+
+        for base_url in ["ftp://host", "https://", "https://host/?q=1"] {
+            let mut req = create_request();
+            req.base_url = base_url.to_string();
+            assert!(req.validate().is_err(), "{base_url} accepted");
+        }
+        let mut req = create_request();
+        req.direction = "sideways".to_string();
+        assert!(req.validate().is_err());
+    }
+
+    #[test]
+    fn update_clears_optional_fields_and_resets_cursors() {
+        let mut req = create_request();
+        req.schedule = Some("@hourly".to_string());
+        req.validate().unwrap();
+        let cursor = SyncCursor {
+            changed_at: at(0),
+            note_id: Uuid::nil(),
+        };
+        let mut peer = SyncPeer {
+            id: Uuid::nil(),
+            name: req.name,
+            base_url: req.base_url,
+            has_api_token: true,
+            remote_memory: Some("remote".to_string()),
+            local_memory: None,
+            direction: req.direction,
+            conflict_strategy: req.conflict_strategy,
+            schedule: req.schedule,
+            enabled: true,
+            pull_cursor: Some(cursor),
+            push_cursor: Some(cursor),
+            next_run_at_utc: None,
+            last_sync_at_utc: None,
+            last_status: None,
+            last_error: None,
+            created_at_utc: at(0),
+            updated_at_utc: at(0),
+        };
+
+        let update = UpdateSyncPeerRequest {
+            api_token: Some(String::new()),
+            remote_memory: Some(String::new()),
+            schedule: Some(String::new()),
+            reset_cursors: true,
+            ..Default::default()
+        }
+        .apply(&mut peer)
+        .unwrap();
+
+        assert!(matches!(update.schedule, Some(None)));
+        assert_eq!(update.api_token, Some(None));
+        assert!(!peer.has_api_token);
+        assert!(peer.remote_memory.is_none() && peer.schedule.is_none());
+        assert!(peer.pull_cursor.is_none() && peer.push_cursor.is_none());
+        assert!(UpdateSyncPeerRequest {
+            conflict_strategy: Some("merge".to_string()),
+            ..Default::default()
+        }
+        .apply(&mut peer)
+        .is_err());
+    }
+
+    #[test]
+    fn debug_output_redacts_tokens_and_content() {
+        let rendered = format!(
+            "{:?} {:?}",
+            create_request(),
+            SyncChange {
+                note_id: Uuid::nil(),
+                changed_at: at(0),
+                snapshot: Some(serde_json::json!({ "note": { "title": "private title" } })),
+            }
+        );
+        assert!(rendered.contains("[REDACTED]"));
+        assert!(!rendered.contains("secret-token"));
+        assert!(!rendered.contains("fortemi.example.org"));
+        assert!(!rendered.contains("private title"));
+    }
+}
@@ -177,14 +177,20 @@ pub fn detect_content_type(filename: &str, data: &[u8], claimed: &str) -> String
         return "audio/mpeg".to_string();
     }
 
-    // 3. Fallback: extension-based detection for text formats (no magic bytes)
+    // 3. Chat exports are plain JSON/text with no magic bytes and share their
+    //    extensions with ordinary files, so recognize them by content.
+    if let Some(mime) = detect_chat_export(data) {
+        return mime.to_string();
+    }
+
+    // 4. Fallback: extension-based detection for text formats (no magic bytes)
     if let Some(ext) = filename.rsplit('.').next() {
         if let Some(mime) = mime_from_extension(ext) {
             return mime.to_string();
         }
     }
 
-    // 4. Mismatch guard: if the claimed type is a binary format that *should*
+    // 5. Mismatch guard: if the claimed type is a binary format that *should*
     //    have recognizable magic bytes (image/*, audio/*, video/*, application/pdf,
     //    application/zip, etc.) but infer::get() returned None, the data doesn't
     //    match the claim. Downgrade to application/octet-stream to prevent wasted
@@ -195,10 +201,77 @@ pub fn detect_content_type(filename: &str, data: &[u8], claimed: &str) -> String
         return "application/octet-stream".to_string();
     }
 
-    // 5. Final fallback: trust the claimed type (text-like formats)
+    // 6. Final fallback: trust the claimed type (text-like formats)
     claimed.to_string()
 }
 
+/// MIME type assigned to Slack channel exports (JSON array of message events).
+pub const SLACK_EXPORT_MIME: &str = "application/x-slack-export+json";
+
+/// MIME type assigned to WhatsApp "Export chat" text files.
+pub const WHATSAPP_CHAT_MIME: &str = "text/x-whatsapp-chat";
+
+/// Bytes inspected when sniffing for chat exports.
+const CHAT_SNIFF_BYTES: usize = 64 * 1024;
+
+/// Recognize Slack JSON and WhatsApp TXT chat exports by their content.
+///
+/// Slack channel exports are JSON arrays of `"type": "message"` events keyed by
+/// a `"ts"` timestamp. WhatsApp exports are lines starting with a date and time
+/// followed by `] ` (iOS) or ` - ` (Android); at least two of the first lines
+/// must look like message headers.
+fn detect_chat_export(data: &[u8]) -> Option<&'static str> {
+    let head = String::from_utf8_lossy(&data[..data.len().min(CHAT_SNIFF_BYTES)]);
+    let head = head
+        .trim_start_matches(['\u{feff}', '\u{200e}'])
+        .trim_start();
+
+    if head.starts_with('[') {
+        let compact: String = head.chars().filter(|c| !c.is_whitespace()).collect();
+        if compact.starts_with("[{")
+            && compact.contains("\"type\":\"message\"")
+            && compact.contains("\"ts\":")
+        {
+            return Some(SLACK_EXPORT_MIME);
+        }
+    }
+
+    let mut checked = 0;
+    let mut matched = 0;
+    for line in head.lines().filter(|l| !l.trim().is_empty()).take(10) {
+        checked += 1;
+        if is_whatsapp_header(line.trim_start_matches('\u{200e}')) {
+            matched += 1;
+        }
+    }
+    (matched >= 2 && matched * 2 >= checked).then_some(WHATSAPP_CHAT_MIME)
+}
+
+/// Check for a WhatsApp message header: `[31/12/2023, 21:15:02] ` or `12/31/23, 9:15 PM - `.
+fn is_whatsapp_header(line: &str) -> bool {
+    let line = line.strip_prefix('[').unwrap_or(line);
+    let Some((date, rest)) = line.split_once(',') else {
+        return false;
+    };
+    let date_parts: Vec<&str> = date.split(['/', '.', '-']).collect();
+    if date_parts.len() != 3
+        || !date_parts
+            .iter()
+            .all(|p| (1..=4).contains(&p.len()) && p.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return false;
+    }
+    let rest = rest.trim_start();
+    let time_end = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == ':'))
+        .unwrap_or(rest.len());
+    let time = &rest[..time_end];
+    if time.split(':').count() < 2 || time.split(':').any(|p| p.is_empty() || p.len() > 2) {
+        return false;
+    }
+    rest[time_end..].contains("] ") || rest[time_end..].contains(" - ")
+}
+
 /// Returns true if the data starts with a valid MP3 frame sync pattern.
 ///
 /// The infer crate v0.16 only detects MPEG-1 Layer 3 (0xFF 0xFB) but not all
@@ -272,6 +345,7 @@ fn mime_from_extension(ext: &str) -> Option<&'static str> {
         "html" | "htm" => Some("text/html"),
         "xml" | "xsl" | "xslt" => Some("application/xml"),
         "json" => Some("application/json"),
+        "ipynb" => Some("application/x-ipynb+json"),
         "yaml" | "yml" => Some("application/yaml"),
         "toml" => Some("application/toml"),
         // Markdown/docs
@@ -550,6 +624,51 @@ mod tests {
         assert_eq!(result, "text/csv");
     }
 
+    #[test]
+    fn test_detect_notebook_by_extension() {
+        let result = detect_content_type(
+            "analysis.ipynb",
+            br#"{"cells": [], "nbformat": 4}"#,
+            "application/json",
+        );
+        assert_eq!(result, "application/x-ipynb+json");
+    }
+
+    #[test]
+    fn test_detect_slack_export() {
+        let slack = br#"[
+            {"type": "message", "user": "U1", "text": "hi", "ts": "1512085950.000216"}
+        ]"#;
+        assert_eq!(
+            detect_content_type("general.json", slack, "application/json"),
+            SLACK_EXPORT_MIME
+        );
+        assert_eq!(
+            detect_content_type("data.json", br#"[{"id": 1}]"#, "application/json"),
+            "application/json"
+        );
+    }
+
+    #[test]
+    fn test_detect_whatsapp_chat() {
+        let ios = "[31/12/2023, 21:15:02] Alice: hi\n[31/12/2023, 21:16:40] Bob: hey\n";
+        let android = "12/31/23, 9:15 PM - Alice: hi\n12/31/23, 9:16 PM - Bob: hey\n";
+        for chat in [ios, android] {
+            assert_eq!(
+                detect_content_type("_chat.txt", chat.as_bytes(), "text/plain"),
+                WHATSAPP_CHAT_MIME
+            );
+        }
+        assert_eq!(
+            detect_content_type(
+                "notes.txt",
+                b"Meeting on 1/2/2024, 10:00 - room 4",
+                "text/plain"
+            ),
+            "text/plain"
+        );
+    }
+
     #[test]
     fn test_blocks_exe() {
         // Extension is checked first, so .exe files get blocked by extension
@@ -0,0 +1,378 @@
+//! Fine-tuning datasets built from a memory's notes.
+//!
+//! A dataset names a source of notes (an embedding set, a tag, or a
+//! collection). The `generate_fine_tuning_data` job asks the model for
+//! question-answer pairs grounded in each note, keeps the pairs the model
+//! rates at or above the configured quality, and holds back a fixed fraction
+//! as validation samples. Samples export as JSONL in the OpenAI chat format
+//! or the Alpaca format read by llama-factory.
+//!
+//! ```
+//! use matric_core::{is_validation_sample, FineTuningExportFormat};
+//!
+//! let held_back = (0..10).filter(|&i| is_validation_sample(i, 0.2)).count();
+//! assert_eq!(held_back, 2);
+//! assert_eq!(
+//!     FineTuningExportFormat::parse("llama-factory").unwrap(),
+//!     FineTuningExportFormat::LlamaFactory
+//! );
+//! ```
+
+use std::fmt;
+
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    CreateFineTuningDatasetRequest, Error, FineTuningConfig, FineTuningDataset, FineTuningSample,
+    FineTuningStatus, Result, UpdateFineTuningDatasetRequest,
+};
+
+/// Longest accepted dataset name.
+pub const MAX_FINE_TUNING_NAME_LEN: usize = 100;
+
+/// Longest accepted dataset description.
+pub const MAX_FINE_TUNING_DESCRIPTION_LEN: usize = 1000;
+
+/// Most question-answer pairs kept per note.
+pub const MAX_FINE_TUNING_PAIRS_PER_NOTE: i32 = 20;
+
+/// Largest accepted validation split.
+pub const MAX_FINE_TUNING_VALIDATION_SPLIT: f32 = 0.5;
+
+/// Most notes one generation run reads; the most recently updated are kept.
+pub const MAX_FINE_TUNING_SOURCE_NOTES: i64 = 2_000;
+
+/// Characters of each note shown to the model when generating pairs.
+pub const FINE_TUNING_CONTENT_CHARS: usize = 8_000;
+
+/// Most samples returned by one export.
+pub const MAX_FINE_TUNING_EXPORT_SAMPLES: i64 = 100_000;
+
+/// Where a dataset's notes come from.
+pub const FINE_TUNING_SOURCE_TYPES: [&str; 3] = ["embedding_set", "tag", "collection"];
+
+impl FineTuningStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Generating => "generating",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// Parses a stored status; unknown values read as pending.
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "generating" => Self::Generating,
+            "completed" => Self::Completed,
+            "failed" => Self::Failed,
+            _ => Self::Pending,
+        }
+    }
+}
+
+/// A question-answer pair generated from one note.
+#[derive(Clone, PartialEq)]
+pub struct GeneratedQaPair {
+    pub question: String,
+    pub answer: String,
+    /// `factoid`, `conceptual`, `procedural`, or `comparative`
+    pub question_type: Option<String>,
+    /// The model's own rating of the pair, 1-5
+    pub quality: f32,
+}
+
+impl fmt::Debug for GeneratedQaPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GeneratedQaPair")
+            .field("question_len", &self.question.chars().count())
+            .field("answer_len", &self.answer.chars().count())
+            .field("question_type", &self.question_type)
+            .field("quality", &self.quality)
+            .finish()
+    }
+}
+
+/// JSONL layout of an exported dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FineTuningExportFormat {
+    /// `{"messages": [{"role": "user", ...}, {"role": "assistant", ...}]}`
+    OpenAi,
+    /// Alpaca records: `{"instruction": ..., "input": "", "output": ...}`
+    LlamaFactory,
+}
+
+impl FineTuningExportFormat {
+    /// Parses a `format` query value (`openai` or `llama_factory`/`alpaca`).
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "openai" => Ok(Self::OpenAi),
+            "llama_factory" | "llama-factory" | "alpaca" => Ok(Self::LlamaFactory),
+            _ => Err(Error::InvalidInput(
+                "export format must be one of: openai, llama_factory".to_string(),
+            )),
+        }
+    }
+
+    /// HTTP content type of an export.
+    pub fn content_type(self) -> &'static str {
+        "application/jsonl; charset=utf-8"
+    }
+}
+
+/// Which samples of a dataset to read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FineTuningSplit {
+    #[default]
+    All,
+    Training,
+    Validation,
+}
+
+impl FineTuningSplit {
+    /// Parses a `split` query value (`all`, `training`/`train`, or
+    /// `validation`/`val`).
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "all" => Ok(Self::All),
+            "training" | "train" => Ok(Self::Training),
+            "validation" | "val" => Ok(Self::Validation),
+            _ => Err(Error::InvalidInput(
+                "split must be one of: all, training, validation".to_string(),
+            )),
+        }
+    }
+
+    /// The `is_validation` value this split selects, or none for all samples.
+    pub fn is_validation(self) -> Option<bool> {
+        match self {
+            Self::All => None,
+            Self::Training => Some(false),
+            Self::Validation => Some(true),
+        }
+    }
+}
+
+/// Whether the sample at `index` in generation order is held back for
+/// validation.
+///
+/// Every `1 / split`-th sample is, so any prefix of a run keeps the split
+/// within one sample and regenerating the same pairs gives the same split.
+pub fn is_validation_sample(index: usize, split: f32) -> bool {
+    let split = f64::from(split.clamp(0.0, 1.0));
+    ((index + 1) as f64 * split).floor() > (index as f64 * split).floor()
+}
+
+/// One JSONL line for `sample`, or none for a sample without an answer.
+///
+/// `system` is added as a system message to OpenAI records and as the
+/// `system` field of Alpaca records.
+pub fn fine_tuning_export_line(
+    sample: &FineTuningSample,
+    format: FineTuningExportFormat,
+    system: Option<&str>,
+) -> Option<String> {
+    let answer = sample.answer.as_deref()?;
+    let record = match format {
+        FineTuningExportFormat::OpenAi => {
+            let mut messages = Vec::with_capacity(3);
+            if let Some(system) = system {
+                messages.push(json!({ "role": "system", "content": system }));
+            }
+            messages.push(json!({ "role": "user", "content": sample.query }));
+            messages.push(json!({ "role": "assistant", "content": answer }));
+            json!({ "messages": messages })
+        }
+        FineTuningExportFormat::LlamaFactory => {
+            let mut record = json!({
+                "instruction": sample.query,
+                "input": "",
+                "output": answer,
+            });
+            if let Some(system) = system {
+                record["system"] = json!(system);
+            }
+            record
+        }
+    };
+    Some(record.to_string())
+}
+
+fn validate_fine_tuning_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() || name.chars().count() > MAX_FINE_TUNING_NAME_LEN {
+        return Err(Error::InvalidInput(format!(
+            "dataset name must be 1-{MAX_FINE_TUNING_NAME_LEN} characters"
+        )));
+    }
+    Ok(())
+}
+
+fn validate_fine_tuning_description(description: Option<&String>) -> Result<()> {
+    if description.is_some_and(|d| d.chars().count() > MAX_FINE_TUNING_DESCRIPTION_LEN) {
+        return Err(Error::InvalidInput(format!(
+            "dataset description must be at most {MAX_FINE_TUNING_DESCRIPTION_LEN} characters"
+        )));
+    }
+    Ok(())
+}
+
+impl FineTuningConfig {
+    /// Checks the generation settings are in range.
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=MAX_FINE_TUNING_PAIRS_PER_NOTE).contains(&self.queries_per_doc) {
+            return Err(Error::InvalidInput(format!(
+                "queries_per_doc must be 1-{MAX_FINE_TUNING_PAIRS_PER_NOTE}"
+            )));
+        }
+        if !(1.0..=5.0).contains(&self.min_quality_score) {
+            return Err(Error::InvalidInput(
+                "min_quality_score must be 1-5".to_string(),
+            ));
+        }
+        if !(0.0..=MAX_FINE_TUNING_VALIDATION_SPLIT).contains(&self.validation_split) {
+            return Err(Error::InvalidInput(format!(
+                "validation_split must be 0-{MAX_FINE_TUNING_VALIDATION_SPLIT}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl CreateFineTuningDatasetRequest {
+    /// Validates the request.
+    pub fn validate(&self) -> Result<()> {
+        validate_fine_tuning_name(&self.name)?;
+        validate_fine_tuning_description(self.description.as_ref())?;
+        if !FINE_TUNING_SOURCE_TYPES.contains(&self.source_type.as_str()) {
+            return Err(Error::InvalidInput(format!(
+                "source_type must be one of: {}",
+                FINE_TUNING_SOURCE_TYPES.join(", ")
+            )));
+        }
+        if self.source_id.trim().is_empty() {
+            return Err(Error::InvalidInput("source_id is required".to_string()));
+        }
+        if self.source_type == "collection" && Uuid::parse_str(self.source_id.trim()).is_err() {
+            return Err(Error::InvalidInput(
+                "source_id must be a collection id for collection sources".to_string(),
+            ));
+        }
+        self.config.validate()
+    }
+}
+
+impl UpdateFineTuningDatasetRequest {
+    /// Applies the update to `dataset` after validating it.
+    pub fn apply(&self, dataset: &mut FineTuningDataset) -> Result<()> {
+        if let Some(name) = &self.name {
+            validate_fine_tuning_name(name)?;
+            dataset.name = name.trim().to_string();
+        }
+        if let Some(description) = &self.description {
+            validate_fine_tuning_description(Some(description))?;
+            dataset.description = Some(description.clone()).filter(|d| !d.trim().is_empty());
+        }
+        if let Some(config) = &self.config {
+            config.validate()?;
+            dataset.config = config.clone();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn sample(answer: Option<&str>) -> FineTuningSample {
+        FineTuningSample {
+            id: Uuid::nil(),
+            dataset_id: Uuid::nil(),
+            note_id: Uuid::nil(),
+            query: "What is the boiling point of water?".to_string(),
+            answer: answer.map(str::to_string),
+            query_type: Some("factoid".to_string()),
+            quality_score: Some(5.0),
+            is_validation: false,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn request(source_type: &str, source_id: &str) -> CreateFineTuningDatasetRequest {
+        CreateFineTuningDatasetRequest {
+            name: "support-qa".to_string(),
+            description: None,
+            source_type: source_type.to_string(),
+            source_id: source_id.to_string(),
+            config: FineTuningConfig {
+                queries_per_doc: 4,
+                min_quality_score: 4.0,
+                validation_split: 0.1,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn validation_samples_follow_the_split() {
+        let held_back: Vec<usize> = (0..20).filter(|&i| is_validation_sample(i, 0.25)).collect();
+        assert_eq!(held_back, vec![3, 7, 11, 15, 19]);
+        assert!(!(0..100).any(|i| is_validation_sample(i, 0.0)));
+    }
+
+    #[test]
+    fn export_lines_match_each_format() {
+        let sample = sample(Some("100 °C at sea level."));
+
+        let openai =
+            fine_tuning_export_line(&sample, FineTuningExportFormat::OpenAi, Some("Be brief."))
+                .unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&openai).unwrap(),
+            json!({ "messages": [
+                { "role": "system", "content": "Be brief." },
+                { "role": "user", "content": "What is the boiling point of water?" },
+                { "role": "assistant", "content": "100 °C at sea level." },
+            ]})
+        );
+
+        let alpaca =
+            fine_tuning_export_line(&sample, FineTuningExportFormat::LlamaFactory, None).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&alpaca).unwrap(),
+            json!({
+                "instruction": "What is the boiling point of water?",
+                "input": "",
+                "output": "100 °C at sea level.",
+            })
+        );
+        assert!(!alpaca.contains('\n'));
+    }
+
+    #[test]
+    fn query_only_samples_are_not_exported() {
+        assert!(
+            fine_tuning_export_line(&sample(None), FineTuningExportFormat::OpenAi, None).is_none()
+        );
+    }
+
+    #[test]
+    fn create_request_checks_source_and_config() {
+        assert!(request("tag", "support").validate().is_ok());
+        assert!(request("folder", "support").validate().is_err());
+        assert!(request("collection", "support").validate().is_err());
+        assert!(request("collection", &Uuid::nil().to_string())
+            .validate()
+            .is_ok());
+
+        let mut out_of_range = request("tag", "support");
+        out_of_range.config.queries_per_doc = 0;
+        assert!(out_of_range.validate().is_err());
+        out_of_range.config.queries_per_doc = 4;
+        out_of_range.config.validation_split = 0.9;
+        assert!(out_of_range.validate().is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! Graph analytics over the note link graph.
+//!
+//! The `analytics` step of the graph maintenance job scores every linked note:
+//! PageRank for importance, betweenness centrality for how many shortest paths
+//! run through it, a label propagation community, and whether it bridges
+//! communities. The scores are stored per archive; search can boost results
+//! by [`NoteGraphMetric::importance`].
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Notes listed per section of [`GraphAnalytics`] by default.
+pub const DEFAULT_GRAPH_ANALYTICS_LIMIT: i64 = 20;
+
+/// Most notes listed per section of [`GraphAnalytics`].
+pub const MAX_GRAPH_ANALYTICS_LIMIT: i64 = 200;
+
+/// Stored graph metrics of one note.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct NoteGraphMetric {
+    pub note_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub pagerank: f64,
+    /// PageRank scaled so the most important note scores 1
+    pub importance: f64,
+    /// Normalized betweenness centrality (0-1)
+    pub betweenness: f64,
+    pub community: i32,
+    /// Distinct notes linked to or from this note
+    pub degree: i32,
+    /// Whether the note links into a community other than its own
+    pub is_bridge: bool,
+}
+
+impl fmt::Debug for NoteGraphMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NoteGraphMetric")
+            .field("note_id_set", &true)
+            .field("title_len", &self.title.as_ref().map(String::len))
+            .field("pagerank", &self.pagerank)
+            .field("importance", &self.importance)
+            .field("betweenness", &self.betweenness)
+            .field("community", &self.community)
+            .field("degree", &self.degree)
+            .field("is_bridge", &self.is_bridge)
+            .finish()
+    }
+}
+
+/// One detected community.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct GraphCommunitySummary {
+    pub community: i32,
+    pub size: i64,
+    /// The member with the highest PageRank
+    pub top_note_id: Uuid,
+}
+
+/// The stored graph analytics of an archive.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct GraphAnalytics {
+    /// When the metrics were computed; absent until the first run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub computed_at_utc: Option<DateTime<Utc>>,
+    /// Linked notes with metrics
+    pub note_count: i64,
+    pub community_count: i64,
+    pub bridge_count: i64,
+    /// Notes by descending PageRank
+    pub top_notes: Vec<NoteGraphMetric>,
+    /// Bridge notes by descending betweenness
+    pub bridge_notes: Vec<NoteGraphMetric>,
+    /// Communities by descending size
+    pub communities: Vec<GraphCommunitySummary>,
+}
+
+impl fmt::Debug for GraphAnalytics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphAnalytics")
+            .field("computed_at_utc", &self.computed_at_utc)
+            .field("note_count", &self.note_count)
+            .field("community_count", &self.community_count)
+            .field("bridge_count", &self.bridge_count)
+            .field("top_notes_count", &self.top_notes.len())
+            .field("bridge_notes_count", &self.bridge_notes.len())
+            .field("communities_count", &self.communities.len())
+            .finish()
+    }
+}
+
+/// What one analytics run computed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct GraphAnalyticsRun {
+    pub note_count: u64,
+    /// Distinct directed links between live notes
+    pub edge_count: u64,
+    pub community_count: u64,
+    pub bridge_count: u64,
+    /// PageRank iterations until convergence
+    pub pagerank_iterations: u32,
+    /// Whether betweenness was estimated from a sample of source notes
+    pub betweenness_sampled: bool,
+}
+
+/// Clamp a requested section length to `1..=MAX_GRAPH_ANALYTICS_LIMIT`.
+pub fn graph_analytics_limit(limit: Option<i64>) -> i64 {
+    limit
+        .unwrap_or(DEFAULT_GRAPH_ANALYTICS_LIMIT)
+        .clamp(1, MAX_GRAPH_ANALYTICS_LIMIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_defaults_and_clamps() {
+        assert_eq!(graph_analytics_limit(None), DEFAULT_GRAPH_ANALYTICS_LIMIT);
+        assert_eq!(graph_analytics_limit(Some(0)), 1);
+        assert_eq!(
+            graph_analytics_limit(Some(10_000)),
+            MAX_GRAPH_ANALYTICS_LIMIT
+        );
+    }
+
+    #[test]
+    fn metric_debug_redacts_title() {
+        let metric = NoteGraphMetric {
+            note_id: Uuid::nil(),
+            title: Some("Private 秘密 plans".to_string()),
+            pagerank: 0.2,
+            importance: 1.0,
+            betweenness: 0.5,
+            community: 0,
+            degree: 3,
+            is_bridge: true,
+        };
+        let debug = format!("{metric:?}");
+        assert!(debug.contains("title_len"));
+        assert!(!debug.contains("秘密"));
+    }
+}
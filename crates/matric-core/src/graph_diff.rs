@@ -0,0 +1,206 @@
+//! Changes to the note graph between two points in time.
+//!
+//! The graph as of a timestamp is rebuilt from what the archive records:
+//! a note exists from its creation until it is soft-deleted, a link from its
+//! creation while both of its notes exist, and a note's tags are those saved
+//! with the first content version written at or after the timestamp (its
+//! current tags when it has not been edited since). Links deleted outright
+//! and tag changes between edits leave no history, so the graph shows what
+//! survives of each point in time.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// Items listed per section of a [`GraphDiff`] by default.
+pub const DEFAULT_GRAPH_DIFF_LIMIT: i64 = 100;
+
+/// Most items listed per section of a [`GraphDiff`].
+pub const MAX_GRAPH_DIFF_LIMIT: i64 = 1000;
+
+/// Query parameters for a graph diff.
+#[derive(Clone, Deserialize)]
+pub struct GraphDiffQuery {
+    /// Earlier point in time
+    pub from: DateTime<Utc>,
+    /// Later point in time
+    pub to: DateTime<Utc>,
+    /// Items listed per section (default: 100, max: 1000)
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+impl GraphDiffQuery {
+    /// Reject a `from` that is not before `to`.
+    pub fn validate(&self) -> Result<()> {
+        if self.from >= self.to {
+            return Err(Error::InvalidInput("from must be before to".to_string()));
+        }
+        Ok(())
+    }
+
+    /// The section length, clamped to `1..=MAX_GRAPH_DIFF_LIMIT`.
+    pub fn validated_limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_GRAPH_DIFF_LIMIT)
+            .clamp(1, MAX_GRAPH_DIFF_LIMIT)
+    }
+}
+
+impl fmt::Debug for GraphDiffQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphDiffQuery")
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+/// Size of the graph at one point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct GraphStateCounts {
+    pub at: DateTime<Utc>,
+    pub note_count: i64,
+    pub link_count: i64,
+}
+
+/// A note added or removed between the two points.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct GraphDiffNote {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// When the note was created (added) or deleted (removed)
+    pub changed_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for GraphDiffNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphDiffNote")
+            .field("id_set", &true)
+            .field("title_len", &self.title.as_ref().map(String::len))
+            .field("changed_at_utc", &self.changed_at_utc)
+            .finish()
+    }
+}
+
+/// A link added or removed between the two points.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct GraphDiffLink {
+    pub from_note_id: Uuid,
+    pub to_note_id: Uuid,
+    pub kind: String,
+    pub score: f32,
+}
+
+impl fmt::Debug for GraphDiffLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphDiffLink")
+            .field("kind_len", &self.kind.len())
+            .field("score", &self.score)
+            .finish()
+    }
+}
+
+/// A tag added to or removed from a note between the two points.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct GraphDiffTag {
+    pub note_id: Uuid,
+    pub tag: String,
+}
+
+impl fmt::Debug for GraphDiffTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphDiffTag")
+            .field("note_id_set", &true)
+            .field("tag_len", &self.tag.len())
+            .finish()
+    }
+}
+
+/// Totals of each kind of change, before the lists are cut to the limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct GraphDiffSummary {
+    pub notes_added: i64,
+    pub notes_removed: i64,
+    pub links_added: i64,
+    pub links_removed: i64,
+    pub tags_added: i64,
+    pub tags_removed: i64,
+}
+
+/// How the note graph changed between two points in time.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct GraphDiff {
+    pub from: GraphStateCounts,
+    pub to: GraphStateCounts,
+    pub summary: GraphDiffSummary,
+    /// Notes by creation time
+    pub notes_added: Vec<GraphDiffNote>,
+    /// Notes by deletion time
+    pub notes_removed: Vec<GraphDiffNote>,
+    pub links_added: Vec<GraphDiffLink>,
+    /// Links lost because one of their notes was deleted
+    pub links_removed: Vec<GraphDiffLink>,
+    /// Tag changes on notes that exist at both points
+    pub tags_added: Vec<GraphDiffTag>,
+    pub tags_removed: Vec<GraphDiffTag>,
+}
+
+impl fmt::Debug for GraphDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphDiff")
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("summary", &self.summary)
+            .field("notes_added_count", &self.notes_added.len())
+            .field("notes_removed_count", &self.notes_removed.len())
+            .field("links_added_count", &self.links_added.len())
+            .field("links_removed_count", &self.links_removed.len())
+            .field("tags_added_count", &self.tags_added.len())
+            .field("tags_removed_count", &self.tags_removed.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn query_requires_from_before_to_and_clamps_limit() {
+        let now = Utc::now();
+        let query = GraphDiffQuery {
+            from: now - Duration::days(7),
+            to: now,
+            limit: None,
+        };
+        assert!(query.validate().is_ok());
+        assert_eq!(query.validated_limit(), DEFAULT_GRAPH_DIFF_LIMIT);
+
+        let reversed = GraphDiffQuery {
+            from: now,
+            to: now - Duration::days(7),
+            limit: Some(50_000),
+        };
+        assert!(reversed.validate().is_err());
+        assert_eq!(reversed.validated_limit(), MAX_GRAPH_DIFF_LIMIT);
+    }
+
+    #[test]
+    fn tag_debug_redacts_name() {
+        let tag = GraphDiffTag {
+            note_id: Uuid::nil(),
+            tag: "client/秘密".to_string(),
+        };
+        let debug = format!("{tag:?}");
+        assert!(debug.contains("tag_len"));
+        assert!(!debug.contains("秘密"));
+    }
+}
@@ -0,0 +1,309 @@
+//! Shortest paths between notes.
+//!
+//! Two notes are one step apart when a link joins them, when both carry the
+//! same SKOS concept, or when both sit in the same collection. A path lists
+//! each step with the connection it used so a client can explain how the
+//! notes are related.
+//!
+//! ```
+//! use matric_core::{NotePathEdgeType, NotePathQuery};
+//! use uuid::Uuid;
+//!
+//! let query = NotePathQuery {
+//!     from: Uuid::nil(),
+//!     to: Uuid::max(),
+//!     max_hops: Some(3),
+//!     via: Some("link,concept".to_string()),
+//! };
+//! assert_eq!(query.validated_max_hops(), 3);
+//! assert_eq!(
+//!     query.validated_via().unwrap(),
+//!     vec![NotePathEdgeType::Link, NotePathEdgeType::Concept]
+//! );
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// Steps searched when a request names no limit.
+pub const DEFAULT_NOTE_PATH_MAX_HOPS: u32 = 4;
+
+/// Most steps a path search will take.
+pub const MAX_NOTE_PATH_MAX_HOPS: u32 = 6;
+
+/// A path search gives up after reaching this many notes.
+pub const MAX_NOTE_PATH_VISITED: usize = 10_000;
+
+/// The connection one path step follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotePathEdgeType {
+    /// A link between the two notes, in either direction
+    Link,
+    /// Both notes carry the same SKOS concept
+    Concept,
+    /// Both notes are in the same collection
+    Collection,
+}
+
+impl NotePathEdgeType {
+    pub const ALL: [Self; 3] = [Self::Link, Self::Concept, Self::Collection];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Link => "link",
+            Self::Concept => "concept",
+            Self::Collection => "collection",
+        }
+    }
+}
+
+impl std::str::FromStr for NotePathEdgeType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|edge_type| edge_type.as_str() == s)
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "unknown path edge type '{s}'; expected link, concept or collection"
+                ))
+            })
+    }
+}
+
+/// Query parameters for a note path search.
+#[derive(Clone, Deserialize)]
+pub struct NotePathQuery {
+    /// Note the path starts at
+    pub from: Uuid,
+    /// Note the path ends at
+    pub to: Uuid,
+    /// Most steps to search (default: 4, max: 6)
+    #[serde(default)]
+    pub max_hops: Option<u32>,
+    /// Comma-separated connections to follow: link, concept, collection
+    /// (default: all)
+    #[serde(default)]
+    pub via: Option<String>,
+}
+
+impl NotePathQuery {
+    /// The step limit, clamped to `1..=MAX_NOTE_PATH_MAX_HOPS`.
+    pub fn validated_max_hops(&self) -> u32 {
+        self.max_hops
+            .unwrap_or(DEFAULT_NOTE_PATH_MAX_HOPS)
+            .clamp(1, MAX_NOTE_PATH_MAX_HOPS)
+    }
+
+    /// The connections to follow without repeats; every kind when absent.
+    pub fn validated_via(&self) -> Result<Vec<NotePathEdgeType>> {
+        let Some(via) = self.via.as_deref().filter(|via| !via.trim().is_empty()) else {
+            return Ok(NotePathEdgeType::ALL.to_vec());
+        };
+        let mut edge_types = Vec::new();
+        for part in via
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            let edge_type: NotePathEdgeType = part.parse()?;
+            if !edge_types.contains(&edge_type) {
+                edge_types.push(edge_type);
+            }
+        }
+        Ok(edge_types)
+    }
+}
+
+impl fmt::Debug for NotePathQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotePathQuery")
+            .field("from_set", &true)
+            .field("to_set", &true)
+            .field("max_hops", &self.max_hops)
+            .field("via_len", &self.via.as_ref().map(String::len))
+            .finish()
+    }
+}
+
+/// A note on a path.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct NotePathNote {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl fmt::Debug for NotePathNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotePathNote")
+            .field("id_set", &true)
+            .field("title_len", &self.title.as_ref().map(String::len))
+            .finish()
+    }
+}
+
+/// One step of a path and the connection it follows.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct NotePathStep {
+    pub from_note_id: Uuid,
+    pub to_note_id: Uuid,
+    pub edge_type: NotePathEdgeType,
+    /// Link kind, for `link` steps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_kind: Option<String>,
+    /// Link score, for `link` steps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+    /// Whether the link points from `to_note_id` back to `from_note_id`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reversed: bool,
+    /// Shared concept, for `concept` steps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concept_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concept_label: Option<String>,
+    /// Shared collection, for `collection` steps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection_name: Option<String>,
+}
+
+impl fmt::Debug for NotePathStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotePathStep")
+            .field("edge_type", &self.edge_type)
+            .field("link_kind_len", &self.link_kind.as_ref().map(String::len))
+            .field("score", &self.score)
+            .field("reversed", &self.reversed)
+            .field("concept_id_set", &self.concept_id.is_some())
+            .field(
+                "concept_label_len",
+                &self.concept_label.as_ref().map(String::len),
+            )
+            .field("collection_id_set", &self.collection_id.is_some())
+            .field(
+                "collection_name_len",
+                &self.collection_name.as_ref().map(String::len),
+            )
+            .finish()
+    }
+}
+
+/// The shortest path found between two notes.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct NotePath {
+    pub from: Uuid,
+    pub to: Uuid,
+    /// Whether a path was found within the step limit
+    pub found: bool,
+    /// Steps on the path; 0 when none was found
+    pub hops: u32,
+    /// Notes on the path, from `from` to `to`; empty when none was found
+    pub notes: Vec<NotePathNote>,
+    pub steps: Vec<NotePathStep>,
+    /// Whether the search stopped early after reaching too many notes
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+impl fmt::Debug for NotePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotePath")
+            .field("found", &self.found)
+            .field("hops", &self.hops)
+            .field("notes_count", &self.notes.len())
+            .field("steps", &self.steps)
+            .field("truncated", &self.truncated)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(max_hops: Option<u32>, via: Option<&str>) -> NotePathQuery {
+        NotePathQuery {
+            from: Uuid::new_v4(),
+            to: Uuid::new_v4(),
+            max_hops,
+            via: via.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn max_hops_defaults_and_clamps() {
+        assert_eq!(
+            query(None, None).validated_max_hops(),
+            DEFAULT_NOTE_PATH_MAX_HOPS
+        );
+        assert_eq!(query(Some(0), None).validated_max_hops(), 1);
+        assert_eq!(
+            query(Some(99), None).validated_max_hops(),
+            MAX_NOTE_PATH_MAX_HOPS
+        );
+    }
+
+    #[test]
+    fn via_parses_dedupes_and_rejects_unknown() {
+        assert_eq!(
+            query(None, None).validated_via().unwrap(),
+            NotePathEdgeType::ALL.to_vec()
+        );
+        assert_eq!(
+            query(None, Some(" collection, link,collection "))
+                .validated_via()
+                .unwrap(),
+            vec![NotePathEdgeType::Collection, NotePathEdgeType::Link]
+        );
+        assert!(query(None, Some("link,tag")).validated_via().is_err());
+    }
+
+    #[test]
+    fn step_serializes_only_its_connection() {
+        let step = NotePathStep {
+            from_note_id: Uuid::nil(),
+            to_note_id: Uuid::nil(),
+            edge_type: NotePathEdgeType::Concept,
+            link_kind: None,
+            score: None,
+            reversed: false,
+            concept_id: Some(Uuid::nil()),
+            concept_label: Some("Rust".to_string()),
+            collection_id: None,
+            collection_name: None,
+        };
+        let json = serde_json::to_value(&step).unwrap();
+        assert_eq!(json["edge_type"], "concept");
+        assert_eq!(json["concept_label"], "Rust");
+        assert!(json.get("link_kind").is_none());
+        assert!(json.get("reversed").is_none());
+    }
+
+    #[test]
+    fn step_debug_redacts_labels() {
+        let step = NotePathStep {
+            from_note_id: Uuid::nil(),
+            to_note_id: Uuid::nil(),
+            edge_type: NotePathEdgeType::Collection,
+            link_kind: None,
+            score: None,
+            reversed: false,
+            concept_id: None,
+            concept_label: None,
+            collection_id: Some(Uuid::nil()),
+            collection_name: Some("Client 秘密".to_string()),
+        };
+        let debug = format!("{step:?}");
+        assert!(debug.contains("collection_name_len"));
+        assert!(!debug.contains("秘密"));
+    }
+}
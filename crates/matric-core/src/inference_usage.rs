@@ -0,0 +1,64 @@
+//! Inference token and cost accounting types.
+//!
+//! The job worker records one row per model used by a job: the number of
+//! generation calls, prompt and completion tokens, and the cost when the model
+//! has a configured price. Rows are keyed by archive schema so usage can be
+//! reported per archive, per job, per model, and per job type.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Usage by one model within a single job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InferenceUsageRecord {
+    pub model: String,
+    pub calls: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    /// Unset when the model has no configured price
+    pub cost_usd: Option<f64>,
+}
+
+/// Usage totals for a group of generation calls.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct InferenceUsageTotals {
+    pub calls: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    /// Cost of the priced calls, in US dollars
+    pub cost_usd: f64,
+    /// Calls to models without a configured price, excluded from `cost_usd`
+    pub unpriced_calls: i64,
+}
+
+/// Usage totals for one model or job type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct InferenceUsageGroup {
+    /// Model name or job type
+    pub key: String,
+    #[serde(flatten)]
+    pub totals: InferenceUsageTotals,
+}
+
+/// Inference usage for an archive over a time window.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct InferenceUsageSummary {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<Uuid>,
+    pub total: InferenceUsageTotals,
+    pub by_model: Vec<InferenceUsageGroup>,
+    pub by_job_type: Vec<InferenceUsageGroup>,
+}
+
+/// Filters for [`InferenceUsageSummary`] queries.
+#[derive(Debug, Clone, Default)]
+pub struct InferenceUsageFilter {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub job_id: Option<Uuid>,
+}
@@ -0,0 +1,535 @@
+//! Content moderation hooks for ingestion.
+//!
+//! Every note created through the API and every uploaded attachment is passed
+//! to an [`IngestionPolicyChain`] before anything else happens to it. Each
+//! [`IngestionPolicy`] in the chain answers with an [`IngestionDecision`]:
+//!
+//! - `allow` — store and process as usual
+//! - `quarantine` — store, but hold back from processing and search until a
+//!   person has looked at it
+//! - `deny` — reject the request; the denial is audited
+//!
+//! The first denial ends the evaluation. Otherwise the first quarantine wins,
+//! so a later policy cannot release what an earlier one held back. A policy
+//! that fails quarantines the item rather than letting it through unchecked.
+//!
+//! [`SizePolicy`] and [`MimePolicy`] are configured from the environment by
+//! [`IngestionPolicyChain::from_env`]; LLM content classification lives in
+//! `matric_inference::ingestion`.
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::Result;
+
+/// Environment variable capping the size of note content, in bytes.
+pub const ENV_INGESTION_MAX_NOTE_BYTES: &str = "INGESTION_MAX_NOTE_BYTES";
+
+/// Environment variable capping the size of an attachment, in bytes.
+pub const ENV_INGESTION_MAX_ATTACHMENT_BYTES: &str = "INGESTION_MAX_ATTACHMENT_BYTES";
+
+/// Environment variable listing MIME types that are rejected.
+pub const ENV_INGESTION_DENY_MIME: &str = "INGESTION_DENY_MIME";
+
+/// Environment variable listing MIME types that are quarantined.
+pub const ENV_INGESTION_QUARANTINE_MIME: &str = "INGESTION_QUARANTINE_MIME";
+
+/// Environment variable enabling LLM content classification.
+pub const ENV_INGESTION_LLM_CLASSIFICATION: &str = "INGESTION_LLM_CLASSIFICATION";
+
+/// Reason recorded when a policy fails instead of deciding.
+pub const POLICY_ERROR_REASON: &str = "policy_error";
+
+/// What is being ingested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IngestionKind {
+    Note,
+    Attachment,
+}
+
+impl IngestionKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            IngestionKind::Note => "note",
+            IngestionKind::Attachment => "attachment",
+        }
+    }
+}
+
+/// A note or attachment offered for ingestion.
+#[derive(Clone, Copy)]
+pub struct IngestionItem<'a> {
+    pub kind: IngestionKind,
+    /// Text to classify: the note content, or the attachment's bytes when
+    /// they are valid UTF-8 text.
+    pub text: Option<&'a str>,
+    pub filename: Option<&'a str>,
+    pub content_type: Option<&'a str>,
+    pub size_bytes: u64,
+}
+
+impl fmt::Debug for IngestionItem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IngestionItem")
+            .field("kind", &self.kind)
+            .field("text_len", &self.text.map(|text| text.chars().count()))
+            .field("filename_present", &self.filename.is_some())
+            .field("content_type", &self.content_type)
+            .field("size_bytes", &self.size_bytes)
+            .finish()
+    }
+}
+
+impl<'a> IngestionItem<'a> {
+    /// A note with the given content.
+    pub fn note(content: &'a str) -> Self {
+        Self {
+            kind: IngestionKind::Note,
+            text: Some(content),
+            filename: None,
+            content_type: None,
+            size_bytes: content.len() as u64,
+        }
+    }
+
+    /// An attachment. Text content types that decode as UTF-8 carry their
+    /// text for classification.
+    pub fn attachment(filename: &'a str, content_type: &'a str, data: &'a [u8]) -> Self {
+        let text = if is_text_mime(content_type) {
+            std::str::from_utf8(data).ok()
+        } else {
+            None
+        };
+        Self {
+            kind: IngestionKind::Attachment,
+            text,
+            filename: Some(filename),
+            content_type: Some(content_type),
+            size_bytes: data.len() as u64,
+        }
+    }
+}
+
+fn is_text_mime(content_type: &str) -> bool {
+    let essence = mime_essence(content_type);
+    essence.starts_with("text/")
+        || matches!(
+            essence.as_str(),
+            "application/json" | "application/xml" | "application/x-yaml" | "application/yaml"
+        )
+}
+
+/// Lowercased `type/subtype` without parameters.
+fn mime_essence(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
+
+/// The outcome of evaluating an item.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum IngestionAction {
+    Allow,
+    Quarantine,
+    Deny,
+}
+
+impl IngestionAction {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            IngestionAction::Allow => "allow",
+            IngestionAction::Quarantine => "quarantine",
+            IngestionAction::Deny => "deny",
+        }
+    }
+}
+
+/// A policy's answer, with the policy that gave it and a reason code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IngestionDecision {
+    pub action: IngestionAction,
+    /// [`IngestionPolicy::policy_id`] of the deciding policy; empty for the
+    /// default allow.
+    pub policy: String,
+    /// Short reason code such as `size_exceeded` or `mime_denied`.
+    pub reason: String,
+}
+
+impl IngestionDecision {
+    pub fn allow() -> Self {
+        Self {
+            action: IngestionAction::Allow,
+            policy: String::new(),
+            reason: String::new(),
+        }
+    }
+
+    pub fn quarantine(policy: &str, reason: impl Into<String>) -> Self {
+        Self {
+            action: IngestionAction::Quarantine,
+            policy: policy.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn deny(policy: &str, reason: impl Into<String>) -> Self {
+        Self {
+            action: IngestionAction::Deny,
+            policy: policy.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn is_allowed(&self) -> bool {
+        self.action == IngestionAction::Allow
+    }
+}
+
+/// A moderation hook evaluated when content is ingested.
+#[async_trait]
+pub trait IngestionPolicy: Send + Sync {
+    /// Stable identifier recorded with quarantines and audit events.
+    fn policy_id(&self) -> &'static str;
+
+    /// Decide what happens to `item`.
+    async fn evaluate(&self, item: &IngestionItem<'_>) -> Result<IngestionDecision>;
+}
+
+/// Rejects notes and attachments above a size limit.
+#[derive(Debug, Clone, Default)]
+pub struct SizePolicy {
+    pub max_note_bytes: Option<u64>,
+    pub max_attachment_bytes: Option<u64>,
+}
+
+impl SizePolicy {
+    /// Limits from `INGESTION_MAX_NOTE_BYTES` and `INGESTION_MAX_ATTACHMENT_BYTES`;
+    /// unset, zero, or invalid values mean no limit.
+    pub fn from_env() -> Self {
+        let limit = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .filter(|bytes| *bytes > 0)
+        };
+        Self {
+            max_note_bytes: limit(ENV_INGESTION_MAX_NOTE_BYTES),
+            max_attachment_bytes: limit(ENV_INGESTION_MAX_ATTACHMENT_BYTES),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.max_note_bytes.is_none() && self.max_attachment_bytes.is_none()
+    }
+}
+
+#[async_trait]
+impl IngestionPolicy for SizePolicy {
+    fn policy_id(&self) -> &'static str {
+        "size"
+    }
+
+    async fn evaluate(&self, item: &IngestionItem<'_>) -> Result<IngestionDecision> {
+        let limit = match item.kind {
+            IngestionKind::Note => self.max_note_bytes,
+            IngestionKind::Attachment => self.max_attachment_bytes,
+        };
+        Ok(match limit {
+            Some(max) if item.size_bytes > max => {
+                IngestionDecision::deny(self.policy_id(), "size_exceeded")
+            }
+            _ => IngestionDecision::allow(),
+        })
+    }
+}
+
+/// Denies or quarantines attachments by MIME type.
+///
+/// Patterns are `type/subtype` or `type/*`, compared without parameters and
+/// case-insensitively. Denial patterns are checked first.
+#[derive(Debug, Clone, Default)]
+pub struct MimePolicy {
+    deny: Vec<String>,
+    quarantine: Vec<String>,
+}
+
+impl MimePolicy {
+    pub fn new(deny: Vec<String>, quarantine: Vec<String>) -> Self {
+        let normalize = |patterns: Vec<String>| {
+            patterns
+                .iter()
+                .map(|pattern| mime_essence(pattern))
+                .filter(|pattern| !pattern.is_empty())
+                .collect()
+        };
+        Self {
+            deny: normalize(deny),
+            quarantine: normalize(quarantine),
+        }
+    }
+
+    /// Patterns from the comma-separated `INGESTION_DENY_MIME` and
+    /// `INGESTION_QUARANTINE_MIME`.
+    pub fn from_env() -> Self {
+        let patterns = |name: &str| {
+            std::env::var(name)
+                .unwrap_or_default()
+                .split(',')
+                .map(str::to_string)
+                .collect()
+        };
+        Self::new(
+            patterns(ENV_INGESTION_DENY_MIME),
+            patterns(ENV_INGESTION_QUARANTINE_MIME),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deny.is_empty() && self.quarantine.is_empty()
+    }
+
+    fn matches(patterns: &[String], essence: &str) -> bool {
+        patterns
+            .iter()
+            .any(|pattern| match pattern.strip_suffix("/*") {
+                Some(top) => essence
+                    .split_once('/')
+                    .is_some_and(|(item_top, _)| item_top == top),
+                None => pattern == essence,
+            })
+    }
+}
+
+#[async_trait]
+impl IngestionPolicy for MimePolicy {
+    fn policy_id(&self) -> &'static str {
+        "mime"
+    }
+
+    async fn evaluate(&self, item: &IngestionItem<'_>) -> Result<IngestionDecision> {
+        let Some(content_type) = item.content_type else {
+            return Ok(IngestionDecision::allow());
+        };
+        let essence = mime_essence(content_type);
+        Ok(if Self::matches(&self.deny, &essence) {
+            IngestionDecision::deny(self.policy_id(), "mime_denied")
+        } else if Self::matches(&self.quarantine, &essence) {
+            IngestionDecision::quarantine(self.policy_id(), "mime_quarantined")
+        } else {
+            IngestionDecision::allow()
+        })
+    }
+}
+
+/// Ordered set of ingestion policies.
+#[derive(Clone, Default)]
+pub struct IngestionPolicyChain {
+    policies: Vec<Arc<dyn IngestionPolicy>>,
+}
+
+impl fmt::Debug for IngestionPolicyChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IngestionPolicyChain")
+            .field("policies", &self.policy_ids())
+            .finish()
+    }
+}
+
+impl IngestionPolicyChain {
+    /// An empty chain, which allows everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The size and MIME policies configured in the environment.
+    pub fn from_env() -> Self {
+        let mut chain = Self::new();
+        let size = SizePolicy::from_env();
+        if !size.is_empty() {
+            chain.register(Arc::new(size));
+        }
+        let mime = MimePolicy::from_env();
+        if !mime.is_empty() {
+            chain.register(Arc::new(mime));
+        }
+        chain
+    }
+
+    /// Append a policy; policies run in registration order.
+    pub fn register(&mut self, policy: Arc<dyn IngestionPolicy>) {
+        self.policies.push(policy);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+
+    pub fn policy_ids(&self) -> Vec<&'static str> {
+        self.policies.iter().map(|p| p.policy_id()).collect()
+    }
+
+    /// Run the chain: the first denial wins, then the first quarantine.
+    pub async fn evaluate(&self, item: &IngestionItem<'_>) -> IngestionDecision {
+        let mut held: Option<IngestionDecision> = None;
+        for policy in &self.policies {
+            let decision = match policy.evaluate(item).await {
+                Ok(decision) => decision,
+                Err(_) => IngestionDecision::quarantine(policy.policy_id(), POLICY_ERROR_REASON),
+            };
+            match decision.action {
+                IngestionAction::Deny => return decision,
+                IngestionAction::Quarantine if held.is_none() => held = Some(decision),
+                _ => {}
+            }
+        }
+        held.unwrap_or_else(IngestionDecision::allow)
+    }
+}
+
+/// Whether LLM content classification runs (`INGESTION_LLM_CLASSIFICATION`,
+/// default false).
+pub fn llm_classification_enabled() -> bool {
+    std::env::var(ENV_INGESTION_LLM_CLASSIFICATION)
+        .ok()
+        .and_then(|v| v.trim().parse::<bool>().ok())
+        .unwrap_or(false)
+}
+
+/// A note or attachment held back by an ingestion policy.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct QuarantinedItem {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    /// Set when an attachment, rather than the note, was quarantined.
+    pub attachment_id: Option<Uuid>,
+    /// Policy that quarantined the item.
+    pub policy: String,
+    pub reason: String,
+    pub created_at_utc: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Failing;
+
+    #[async_trait]
+    impl IngestionPolicy for Failing {
+        fn policy_id(&self) -> &'static str {
+            "failing"
+        }
+
+        async fn evaluate(&self, _item: &IngestionItem<'_>) -> Result<IngestionDecision> {
+            Err(crate::Error::Internal("classifier down".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn size_policy_limits_each_kind_separately() {
+        let policy = SizePolicy {
+            max_note_bytes: Some(4),
+            max_attachment_bytes: None,
+        };
+
+        let note = policy
+            .evaluate(&IngestionItem::note("hello"))
+            .await
+            .unwrap();
+        assert_eq!(note, IngestionDecision::deny("size", "size_exceeded"));
+
+        let attachment = IngestionItem::attachment("a.bin", "application/octet-stream", b"hello");
+        assert!(policy.evaluate(&attachment).await.unwrap().is_allowed());
+    }
+
+    #[tokio::test]
+    async fn mime_policy_matches_wildcards_and_ignores_parameters() {
+        let policy = MimePolicy::new(
+            vec!["Application/X-Msdownload".into()],
+            vec!["text/*".into(), " ".into()],
+        );
+
+        let denied = IngestionItem::attachment("a.exe", "application/x-msdownload", b"MZ");
+        assert_eq!(
+            policy.evaluate(&denied).await.unwrap().action,
+            IngestionAction::Deny
+        );
+        let held = IngestionItem::attachment("a.txt", "text/plain; charset=utf-8", b"hi");
+        assert_eq!(
+            policy.evaluate(&held).await.unwrap().action,
+            IngestionAction::Quarantine
+        );
+        let allowed = IngestionItem::attachment("a.pdf", "application/pdf", b"%PDF");
+        assert!(policy.evaluate(&allowed).await.unwrap().is_allowed());
+        assert!(policy
+            .evaluate(&IngestionItem::note("text"))
+            .await
+            .unwrap()
+            .is_allowed());
+    }
+
+    #[tokio::test]
+    async fn chain_prefers_denial_then_first_quarantine() {
+        let mut chain = IngestionPolicyChain::new();
+        chain.register(Arc::new(MimePolicy::new(vec![], vec!["text/*".into()])));
+        chain.register(Arc::new(Failing));
+        let item = IngestionItem::attachment("a.txt", "text/plain", b"hi");
+        assert_eq!(
+            chain.evaluate(&item).await,
+            IngestionDecision::quarantine("mime", "mime_quarantined")
+        );
+
+        chain.register(Arc::new(SizePolicy {
+            max_note_bytes: None,
+            max_attachment_bytes: Some(1),
+        }));
+        assert_eq!(
+            chain.evaluate(&item).await,
+            IngestionDecision::deny("size", "size_exceeded")
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_policy_quarantines() {
+        let mut chain = IngestionPolicyChain::new();
+        assert!(chain.evaluate(&IngestionItem::note("x")).await.is_allowed());
+
+        chain.register(Arc::new(Failing));
+        assert_eq!(
+            chain.evaluate(&IngestionItem::note("x")).await,
+            IngestionDecision::quarantine("failing", POLICY_ERROR_REASON)
+        );
+    }
+
+    #[test]
+    fn attachment_text_is_only_read_from_text_types() {
+        let text = IngestionItem::attachment("a.md", "text/markdown", b"# Title");
+        assert_eq!(text.text, Some("# Title"));
+        let binary = IngestionItem::attachment("a.png", "image/png", b"# Title");
+        assert_eq!(binary.text, None);
+        let invalid = IngestionItem::attachment("a.txt", "text/plain", &[0xff, 0xfe]);
+        assert_eq!(invalid.text, None);
+    }
+}
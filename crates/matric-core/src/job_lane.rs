@@ -0,0 +1,145 @@
+//! Priority lanes: interactive jobs ahead of batch jobs.
+//!
+//! Every queued job belongs to a lane. Interactive jobs are triggered by a
+//! user waiting for the result (reprocessing a note, a new upload); batch jobs
+//! are bulk or housekeeping work (re-embedding every note, graph maintenance).
+//! When a worker slot frees up, the interactive lane gets it first, ahead of
+//! batch jobs with a higher numeric priority. [`LaneWeights`] keeps batch work
+//! from starving: while both lanes have work, batch gets `batch` of every
+//! `interactive + batch` claims.
+//!
+//! ```
+//! use matric_core::job_lane::{JobLane, LaneWeights};
+//!
+//! let weights = LaneWeights { interactive: 3, batch: 1 };
+//! let lanes: Vec<JobLane> = (0..4).map(|claim| weights.preferred(claim)).collect();
+//! assert_eq!(
+//!     lanes,
+//!     [JobLane::Interactive, JobLane::Interactive, JobLane::Interactive, JobLane::Batch]
+//! );
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::JobType;
+
+/// Queue lane of a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobLane {
+    /// A user is waiting for the result.
+    #[default]
+    Interactive,
+    /// Bulk or housekeeping work.
+    Batch,
+}
+
+impl JobLane {
+    /// Stable database representation.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Interactive => "interactive",
+            Self::Batch => "batch",
+        }
+    }
+}
+
+impl std::str::FromStr for JobLane {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "interactive" => Ok(Self::Interactive),
+            "batch" => Ok(Self::Batch),
+            _ => Err("unknown job lane".to_string()),
+        }
+    }
+}
+
+impl JobType {
+    /// Lane a job of this type is queued in unless the caller picks one.
+    pub fn default_lane(&self) -> JobLane {
+        match self {
+            JobType::ReEmbedAll
+            | JobType::RefreshEmbeddingSet
+            | JobType::BuildSetIndex
+            | JobType::GenerateFineTuningData
+            | JobType::GenerateCoarseEmbedding
+            | JobType::GraphMaintenance
+            | JobType::BlobGarbageCollection
+            | JobType::ScheduledBackup
+            | JobType::FederationSync
+            | JobType::VersionPrune
+            | JobType::ArchiveMerge
+            | JobType::TrashPurge
+            | JobType::TaxonomyHealth
+            | JobType::CollectionRuleSync
+            | JobType::BulkNoteUpdate
+            | JobType::DigestGeneration
+            | JobType::TopicModeling => JobLane::Batch,
+            _ => JobLane::Interactive,
+        }
+    }
+}
+
+/// Share of worker claims each lane gets while both have work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LaneWeights {
+    pub interactive: u32,
+    pub batch: u32,
+}
+
+impl Default for LaneWeights {
+    fn default() -> Self {
+        Self {
+            interactive: crate::defaults::JOB_INTERACTIVE_LANE_WEIGHT,
+            batch: crate::defaults::JOB_BATCH_LANE_WEIGHT,
+        }
+    }
+}
+
+impl LaneWeights {
+    /// Lane to claim from first for the `claim`-th claim of a drain. The
+    /// other lane is used when the preferred one has no claimable job.
+    pub fn preferred(&self, claim: u64) -> JobLane {
+        let interactive = u64::from(self.interactive);
+        let cycle = interactive + u64::from(self.batch);
+        if cycle == 0 || claim % cycle < interactive {
+            JobLane::Interactive
+        } else {
+            JobLane::Batch
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bulk_and_housekeeping_types_default_to_batch() {
+        assert_eq!(JobType::ReEmbedAll.default_lane(), JobLane::Batch);
+        assert_eq!(JobType::GraphMaintenance.default_lane(), JobLane::Batch);
+        assert_eq!(JobType::Embedding.default_lane(), JobLane::Interactive);
+        assert_eq!(JobType::AiRevision.default_lane(), JobLane::Interactive);
+        for lane in [JobLane::Interactive, JobLane::Batch] {
+            assert_eq!(lane.as_str().parse::<JobLane>(), Ok(lane));
+        }
+    }
+
+    #[test]
+    fn batch_gets_its_weighted_share_of_claims() {
+        let weights = LaneWeights::default();
+        let batch_claims = (0..100)
+            .filter(|claim| weights.preferred(*claim) == JobLane::Batch)
+            .count() as u32;
+        let cycle = weights.interactive + weights.batch;
+        assert_eq!(batch_claims, 100 / cycle * weights.batch);
+
+        let interactive_only = LaneWeights {
+            interactive: 1,
+            batch: 0,
+        };
+        assert!((0..10).all(|claim| interactive_only.preferred(claim) == JobLane::Interactive));
+    }
+}
@@ -0,0 +1,114 @@
+//! Distributed job workers: registry, heartbeats and orphan reclamation.
+//!
+//! Every API replica that runs a job worker registers itself in the worker
+//! registry and heartbeats while it runs. Jobs are claimed with
+//! `FOR UPDATE SKIP LOCKED`, so replicas never claim the same job, and each
+//! claim records the worker that took it.
+//!
+//! One worker at a time holds the leader lease. The leader reclaims running
+//! jobs whose worker stopped heartbeating for longer than its timeout, and
+//! prunes old registry rows. The lease expires after the leader's heartbeat
+//! timeout, so another worker takes over when the leader dies.
+//!
+//! ```
+//! use chrono::{Duration, Utc};
+//! use matric_core::job_worker::WorkerState;
+//!
+//! let now = Utc::now();
+//! let heartbeat = now - Duration::seconds(90);
+//! assert_eq!(WorkerState::classify(now, heartbeat, None, 60), WorkerState::Stale);
+//! assert_eq!(WorkerState::classify(now, heartbeat, None, 120), WorkerState::Active);
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Identity and settings a worker registers with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerRegistration {
+    pub id: Uuid,
+    pub hostname: String,
+    pub pid: u32,
+    pub version: String,
+    pub max_concurrent: usize,
+    pub heartbeat_timeout_secs: u64,
+}
+
+/// Liveness of a registered worker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkerState {
+    /// Heartbeating within its timeout.
+    Active,
+    /// Missed its heartbeat timeout; its running jobs are reclaimed.
+    Stale,
+    /// Shut down cleanly.
+    Stopped,
+}
+
+impl WorkerState {
+    /// Classify a worker from its last heartbeat as seen at `now`.
+    pub fn classify(
+        now: DateTime<Utc>,
+        last_heartbeat_at: DateTime<Utc>,
+        stopped_at: Option<DateTime<Utc>>,
+        heartbeat_timeout_secs: u64,
+    ) -> Self {
+        if stopped_at.is_some() {
+            return Self::Stopped;
+        }
+        let silent_secs = (now - last_heartbeat_at).num_seconds();
+        if u64::try_from(silent_secs).is_ok_and(|secs| secs > heartbeat_timeout_secs) {
+            Self::Stale
+        } else {
+            Self::Active
+        }
+    }
+}
+
+/// A worker in the registry, as reported by `GET /api/v1/workers`.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct WorkerInfo {
+    pub id: Uuid,
+    pub hostname: String,
+    pub pid: i32,
+    pub version: String,
+    pub state: WorkerState,
+    /// Whether this worker holds the leader lease.
+    pub leader: bool,
+    pub max_concurrent: i32,
+    /// Jobs this worker is running now.
+    pub running_jobs: i64,
+    pub heartbeat_timeout_secs: i32,
+    pub started_at: DateTime<Utc>,
+    pub last_heartbeat_at: DateTime<Utc>,
+    pub stopped_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn worker_is_stale_only_after_its_own_timeout() {
+        let now = Utc::now();
+        assert_eq!(
+            WorkerState::classify(now, now - Duration::seconds(59), None, 60),
+            WorkerState::Active
+        );
+        assert_eq!(
+            WorkerState::classify(now, now - Duration::seconds(61), None, 60),
+            WorkerState::Stale
+        );
+        assert_eq!(
+            WorkerState::classify(now, now, Some(now), 60),
+            WorkerState::Stopped
+        );
+        assert_eq!(
+            WorkerState::classify(now, now - Duration::days(1), None, u64::MAX),
+            WorkerState::Active
+        );
+    }
+}
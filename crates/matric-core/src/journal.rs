@@ -0,0 +1,268 @@
+//! Daily notes.
+//!
+//! Each archive keeps at most one journal note per day. The first request for
+//! today's note creates it, from the archive's journal template when one is
+//! set and in its journal collection, titled after the day. Every note
+//! created on a day that has a journal note is linked from it with a
+//! [`JOURNAL_LINK_KIND`] link, including notes created before the journal
+//! note. Days are calendar days in the archive's journal time zone.
+//!
+//! Journal templates can use `{{date}}` (`YYYY-MM-DD`) and `{{title}}`.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use matric_core::journal::{journal_title, validate_title_format};
+//!
+//! let day = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+//! assert_eq!(journal_title("%A, %B %-d, %Y", day), "Sunday, October 18, 2026");
+//! assert!(validate_title_format("%Q").is_err());
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::note_template::{TemplateVariable, TEMPLATE_DATE_FORMAT};
+use crate::{Error, NoteFull, Result};
+
+/// Kind of the links from a journal note to the notes created that day.
+pub const JOURNAL_LINK_KIND: &str = "journal";
+
+/// `source` of journal notes.
+pub const JOURNAL_NOTE_SOURCE: &str = "journal";
+
+/// Title format of journal notes unless one is set.
+pub const DEFAULT_JOURNAL_TITLE_FORMAT: &str = "%Y-%m-%d";
+
+/// Time zone of journal days unless one is set.
+pub const DEFAULT_JOURNAL_TIMEZONE: &str = "UTC";
+
+/// Longest journal title format.
+pub const MAX_JOURNAL_TITLE_FORMAT_LEN: usize = 100;
+
+/// An archive's journal settings.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct JournalSettings {
+    /// Template journal notes are made from
+    pub template_id: Option<Uuid>,
+    /// Collection journal notes are filed in; the template's when unset
+    pub collection_id: Option<Uuid>,
+    /// `strftime` format of journal note titles, e.g. `%Y-%m-%d`
+    pub title_format: String,
+    /// IANA time zone whose calendar days journal notes cover, e.g. `Europe/Berlin`
+    pub timezone: String,
+    /// Whether notes created on a day are linked from its journal note
+    pub link_notes: bool,
+    /// Unset until the settings are first saved
+    pub updated_at_utc: Option<DateTime<Utc>>,
+}
+
+impl Default for JournalSettings {
+    fn default() -> Self {
+        Self {
+            template_id: None,
+            collection_id: None,
+            title_format: DEFAULT_JOURNAL_TITLE_FORMAT.to_string(),
+            timezone: DEFAULT_JOURNAL_TIMEZONE.to_string(),
+            link_notes: true,
+            updated_at_utc: None,
+        }
+    }
+}
+
+impl fmt::Debug for JournalSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JournalSettings")
+            .field("template_id_set", &self.template_id.is_some())
+            .field("collection_id_set", &self.collection_id.is_some())
+            .field("title_format_len", &self.title_format.len())
+            .field("timezone_len", &self.timezone.len())
+            .field("link_notes", &self.link_notes)
+            .finish()
+    }
+}
+
+/// Replace an archive's journal settings; unset fields take their defaults.
+#[derive(Clone, Default, Deserialize, utoipa::ToSchema)]
+pub struct JournalSettingsRequest {
+    pub template_id: Option<Uuid>,
+    pub collection_id: Option<Uuid>,
+    /// Default: `%Y-%m-%d`
+    pub title_format: Option<String>,
+    /// Default: `UTC`
+    pub timezone: Option<String>,
+    /// Default: true
+    pub link_notes: Option<bool>,
+}
+
+impl fmt::Debug for JournalSettingsRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JournalSettingsRequest")
+            .field("template_id_set", &self.template_id.is_some())
+            .field("collection_id_set", &self.collection_id.is_some())
+            .field(
+                "title_format_len",
+                &self.title_format.as_ref().map(String::len),
+            )
+            .field("timezone_len", &self.timezone.as_ref().map(String::len))
+            .field("link_notes", &self.link_notes)
+            .finish()
+    }
+}
+
+impl JournalSettingsRequest {
+    /// The settings the request asks for. The title format is checked here;
+    /// the time zone is checked against the database's zone list on save.
+    pub fn into_settings(self) -> Result<JournalSettings> {
+        let title_format = self
+            .title_format
+            .map(|format| format.trim().to_string())
+            .unwrap_or_else(|| DEFAULT_JOURNAL_TITLE_FORMAT.to_string());
+        validate_title_format(&title_format)?;
+        let timezone = self
+            .timezone
+            .map(|timezone| timezone.trim().to_string())
+            .unwrap_or_else(|| DEFAULT_JOURNAL_TIMEZONE.to_string());
+        if timezone.is_empty() {
+            return Err(Error::InvalidInput(
+                "timezone must not be empty".to_string(),
+            ));
+        }
+        Ok(JournalSettings {
+            template_id: self.template_id,
+            collection_id: self.collection_id,
+            title_format,
+            timezone,
+            link_notes: self.link_notes.unwrap_or(true),
+            updated_at_utc: None,
+        })
+    }
+}
+
+/// Reject title formats that are empty, too long or use unknown `strftime`
+/// specifiers.
+pub fn validate_title_format(format: &str) -> Result<()> {
+    if format.trim().is_empty() || format.len() > MAX_JOURNAL_TITLE_FORMAT_LEN {
+        return Err(Error::InvalidInput(format!(
+            "title_format must be 1 to {MAX_JOURNAL_TITLE_FORMAT_LEN} characters"
+        )));
+    }
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(Error::InvalidInput(
+            "title_format uses an unknown strftime specifier".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Title of the journal note for `day`. Formats that cannot be applied to a
+/// date, such as ones asking for a time, fall back to `YYYY-MM-DD`.
+pub fn journal_title(format: &str, day: NaiveDate) -> String {
+    let mut title = String::new();
+    match write!(title, "{}", day.format(format)) {
+        Ok(()) if !title.trim().is_empty() => title,
+        _ => day.format(DEFAULT_JOURNAL_TITLE_FORMAT).to_string(),
+    }
+}
+
+/// Values the placeholders of a journal template are filled with: `date`
+/// and `title`, unless the template declares variables without them.
+pub fn journal_template_values(
+    variables: &[TemplateVariable],
+    day: NaiveDate,
+    title: &str,
+) -> HashMap<String, String> {
+    [
+        ("date", day.format(TEMPLATE_DATE_FORMAT).to_string()),
+        ("title", title.to_string()),
+    ]
+    .into_iter()
+    .filter(|(name, _)| variables.is_empty() || variables.iter().any(|v| v.name == *name))
+    .map(|(name, value)| (name.to_string(), value))
+    .collect()
+}
+
+/// Content of a journal note made without a template.
+pub fn default_journal_content(title: &str) -> String {
+    format!("# {title}\n")
+}
+
+/// Today's journal note.
+#[derive(Clone, Serialize, utoipa::ToSchema)]
+pub struct JournalToday {
+    /// The day, in the journal time zone
+    pub date: NaiveDate,
+    /// Whether this request created the note
+    pub created: bool,
+    pub note: NoteFull,
+}
+
+impl fmt::Debug for JournalToday {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JournalToday")
+            .field("date", &self.date)
+            .field("created", &self.created)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles_follow_the_format_and_fall_back_when_it_needs_a_time() {
+        let day = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+        assert_eq!(
+            journal_title(DEFAULT_JOURNAL_TITLE_FORMAT, day),
+            "2026-01-05"
+        );
+        assert_eq!(journal_title("Journal %d.%m.%Y", day), "Journal 05.01.2026");
+        assert_eq!(journal_title("%H:%M", day), "2026-01-05");
+    }
+
+    #[test]
+    fn template_values_only_fill_declared_variables() {
+        let day = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+        let values = journal_template_values(&[], day, "Monday");
+        assert_eq!(values["date"], "2026-01-05");
+        assert_eq!(values["title"], "Monday");
+
+        let mood: TemplateVariable = serde_json::from_value(serde_json::json!({
+            "name": "mood", "type": "string"
+        }))
+        .unwrap();
+        let date: TemplateVariable = serde_json::from_value(serde_json::json!({
+            "name": "date", "type": "date"
+        }))
+        .unwrap();
+        let values = journal_template_values(&[mood, date], day, "Monday");
+        assert_eq!(values.len(), 1);
+        assert_eq!(values["date"], "2026-01-05");
+    }
+
+    #[test]
+    fn settings_requests_take_defaults_and_reject_bad_formats() {
+        let settings = JournalSettingsRequest::default().into_settings().unwrap();
+        assert_eq!(settings.title_format, DEFAULT_JOURNAL_TITLE_FORMAT);
+        assert_eq!(settings.timezone, DEFAULT_JOURNAL_TIMEZONE);
+        assert!(settings.link_notes);
+
+        for title_format in ["", "%Q", &"x".repeat(MAX_JOURNAL_TITLE_FORMAT_LEN + 1)] {
+            let request = JournalSettingsRequest {
+                title_format: Some(title_format.to_string()),
+                ..Default::default()
+            };
+            assert!(request.into_settings().is_err());
+        }
+        let request = JournalSettingsRequest {
+            timezone: Some(" ".to_string()),
+            ..Default::default()
+        };
+        assert!(request.into_settings().is_err());
+    }
+}
@@ -0,0 +1,241 @@
+//! Natural language detection for note content.
+//!
+//! Detection runs when a note's content is stored and the result is kept on
+//! the note as an ISO 639-1 code (ISO 639-3 for languages without a two-letter
+//! code). The stored language drives:
+//!
+//! - **Full-text search** — notes in a language with a stemming configuration
+//!   (see [`fts_config_for_language`]) are also matched with that configuration
+//! - **Embedding** — non-English notes use the `MULTILINGUAL_EMBEDDING_SET`
+//!   embedding set when one is configured
+//! - **Generation prompts** — title generation and AI revision are asked to
+//!   answer in the note's language (see [`prompt_language_directive`])
+//!
+//! Script detection for search queries lives in `matric-search`; this module
+//! identifies the language itself, which script alone cannot (German and
+//! English share the Latin script).
+
+use whatlang::Lang;
+
+/// Environment variable that disables language detection when set to `false`.
+pub const ENV_LANGUAGE_DETECTION_ENABLED: &str = "LANGUAGE_DETECTION_ENABLED";
+
+/// Environment variable for the minimum detection confidence.
+pub const ENV_LANGUAGE_DETECTION_MIN_CONFIDENCE: &str = "LANGUAGE_DETECTION_MIN_CONFIDENCE";
+
+/// Environment variable naming the embedding set used for non-English notes.
+pub const ENV_MULTILINGUAL_EMBEDDING_SET: &str = "MULTILINGUAL_EMBEDDING_SET";
+
+/// Default minimum confidence (0.0–1.0) for a detection to be kept.
+pub const DEFAULT_MIN_CONFIDENCE: f64 = 0.5;
+
+/// Texts with fewer alphabetic characters than this are not classified.
+pub const MIN_DETECTION_CHARS: usize = 20;
+
+/// Only the first this-many characters are analysed.
+pub const DETECTION_SAMPLE_CHARS: usize = 4000;
+
+/// Code stored for English notes.
+pub const ENGLISH: &str = "en";
+
+/// A detected natural language.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedLanguage {
+    /// ISO 639-1 code, or ISO 639-3 when the language has no two-letter code.
+    pub code: String,
+    /// Detector confidence (0.0–1.0).
+    pub confidence: f64,
+}
+
+/// Whether language detection runs at ingestion (`LANGUAGE_DETECTION_ENABLED`, default true).
+pub fn detection_enabled() -> bool {
+    std::env::var(ENV_LANGUAGE_DETECTION_ENABLED)
+        .ok()
+        .and_then(|v| v.parse::<bool>().ok())
+        .unwrap_or(true)
+}
+
+/// Minimum confidence from `LANGUAGE_DETECTION_MIN_CONFIDENCE`, clamped to 0.0–1.0.
+pub fn min_confidence() -> f64 {
+    std::env::var(ENV_LANGUAGE_DETECTION_MIN_CONFIDENCE)
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .map(|v| v.clamp(0.0, 1.0))
+        .unwrap_or(DEFAULT_MIN_CONFIDENCE)
+}
+
+/// Slug of the embedding set for non-English notes, if configured.
+pub fn multilingual_embedding_set() -> Option<String> {
+    std::env::var(ENV_MULTILINGUAL_EMBEDDING_SET)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Detect the language of `text`.
+///
+/// Returns `None` for short texts and when the detector's confidence is
+/// below `min_confidence`.
+pub fn detect_language_with(text: &str, min_confidence: f64) -> Option<DetectedLanguage> {
+    let sample: String = text.chars().take(DETECTION_SAMPLE_CHARS).collect();
+    if sample.chars().filter(|c| c.is_alphabetic()).count() < MIN_DETECTION_CHARS {
+        return None;
+    }
+    let info = whatlang::detect(&sample)?;
+    if info.confidence() < min_confidence {
+        return None;
+    }
+    Some(DetectedLanguage {
+        code: iso_code(info.lang()).to_string(),
+        confidence: info.confidence(),
+    })
+}
+
+/// Detect the language of note content, honouring the environment settings.
+///
+/// Returns `None` when detection is disabled or inconclusive.
+pub fn detect_note_language(text: &str) -> Option<DetectedLanguage> {
+    if !detection_enabled() {
+        return None;
+    }
+    detect_language_with(text, min_confidence())
+}
+
+/// True for English and for unknown (`None`) languages.
+pub fn is_english_or_unknown(code: Option<&str>) -> bool {
+    code.is_none_or(|c| c == ENGLISH)
+}
+
+/// PostgreSQL text search configuration with stemming for a language.
+///
+/// Only languages with a `matric_*` configuration are mapped; others are
+/// searched with `public.matric_simple`.
+pub fn fts_config_for_language(code: &str) -> Option<&'static str> {
+    match code {
+        "en" => Some("public.matric_english"),
+        "de" => Some("public.matric_german"),
+        "fr" => Some("public.matric_french"),
+        "es" => Some("public.matric_spanish"),
+        "ru" => Some("public.matric_russian"),
+        "pt" => Some("public.matric_portuguese"),
+        _ => None,
+    }
+}
+
+/// English name of a language code produced by [`detect_language_with`].
+pub fn language_name(code: &str) -> Option<&'static str> {
+    LANGS
+        .iter()
+        .find(|(_, iso)| *iso == code)
+        .map(|(lang, _)| *lang)
+        .or_else(|| Lang::from_code(code))
+        .map(|lang| lang.eng_name())
+}
+
+/// Instruction appended to generation prompts for non-English notes.
+///
+/// Returns `None` for English and unknown languages, so prompts for them are
+/// unchanged.
+pub fn prompt_language_directive(code: Option<&str>) -> Option<String> {
+    let code = code.filter(|c| *c != ENGLISH)?;
+    let name = language_name(code)?;
+    Some(format!(
+        "\n\nThe note is written in {name}. Write your response in {name}."
+    ))
+}
+
+/// Languages with an ISO 639-1 code, paired with that code.
+const LANGS: &[(Lang, &str)] = &[
+    (Lang::Eng, "en"),
+    (Lang::Deu, "de"),
+    (Lang::Fra, "fr"),
+    (Lang::Spa, "es"),
+    (Lang::Por, "pt"),
+    (Lang::Ita, "it"),
+    (Lang::Nld, "nl"),
+    (Lang::Rus, "ru"),
+    (Lang::Ukr, "uk"),
+    (Lang::Pol, "pl"),
+    (Lang::Ces, "cs"),
+    (Lang::Swe, "sv"),
+    (Lang::Dan, "da"),
+    (Lang::Nob, "nb"),
+    (Lang::Fin, "fi"),
+    (Lang::Tur, "tr"),
+    (Lang::Ell, "el"),
+    (Lang::Heb, "he"),
+    (Lang::Ara, "ar"),
+    (Lang::Hin, "hi"),
+    (Lang::Jpn, "ja"),
+    (Lang::Kor, "ko"),
+    (Lang::Cmn, "zh"),
+    (Lang::Tha, "th"),
+    (Lang::Vie, "vi"),
+    (Lang::Ind, "id"),
+];
+
+fn iso_code(lang: Lang) -> &'static str {
+    LANGS
+        .iter()
+        .find(|(l, _)| *l == lang)
+        .map(|(_, code)| *code)
+        .unwrap_or_else(|| lang.code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GERMAN: &str = "Die Häuser in der Altstadt sind sehr alt und werden \
+                          von vielen Touristen besucht, die durch die Straßen gehen.";
+    const ENGLISH_TEXT: &str = "The houses in the old town are very old and are \
+                                visited by many tourists walking through the streets.";
+
+    #[test]
+    fn detects_common_languages_as_iso_639_1() {
+        assert_eq!(detect_language_with(GERMAN, 0.0).unwrap().code, "de");
+        assert_eq!(detect_language_with(ENGLISH_TEXT, 0.0).unwrap().code, "en");
+        let russian = "Книги, которые я прочитал этим летом, были очень интересными и полезными.";
+        assert_eq!(detect_language_with(russian, 0.0).unwrap().code, "ru");
+    }
+
+    #[test]
+    fn short_or_symbolic_text_is_not_classified() {
+        assert_eq!(detect_language_with("Hallo Welt", 0.0), None);
+        assert_eq!(
+            detect_language_with("1234 5678 !!! ---- ++++ 9999 0000", 0.0),
+            None
+        );
+    }
+
+    #[test]
+    fn confidence_threshold_applies() {
+        assert!(detect_language_with(GERMAN, 1.01).is_none());
+    }
+
+    #[test]
+    fn fts_configs_cover_stemmed_languages_only() {
+        assert_eq!(fts_config_for_language("de"), Some("public.matric_german"));
+        assert_eq!(fts_config_for_language("en"), Some("public.matric_english"));
+        assert_eq!(fts_config_for_language("ja"), None);
+    }
+
+    #[test]
+    fn prompt_directive_only_for_non_english() {
+        assert_eq!(prompt_language_directive(None), None);
+        assert_eq!(prompt_language_directive(Some("en")), None);
+        let directive = prompt_language_directive(Some("de")).unwrap();
+        assert!(directive.contains("Write your response in German."));
+        assert_eq!(prompt_language_directive(Some("xx")), None);
+    }
+
+    #[test]
+    fn language_names_resolve_for_three_letter_codes() {
+        assert_eq!(language_name("fr"), Some("French"));
+        assert_eq!(iso_code(Lang::Epo), "epo");
+        assert_eq!(language_name("epo"), Some("Esperanto"));
+        assert!(is_english_or_unknown(None));
+        assert!(is_english_or_unknown(Some("en")));
+        assert!(!is_english_or_unknown(Some("de")));
+    }
+}
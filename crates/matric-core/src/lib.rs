@@ -5,36 +5,125 @@
 //! This crate provides the foundational data structures and trait definitions
 //! that other matric-memory crates depend on.
 
+pub mod archive_quota;
 pub mod asyncapi;
 pub mod audit;
 pub mod authorization;
+pub mod backup_policy;
+pub mod calendar;
 pub mod captions;
+pub mod citation;
 pub mod collection_filter;
+pub mod collection_rule;
+pub mod collection_subtree;
+pub mod concept_governance;
+pub mod concept_mapping;
+pub mod concept_suggestion;
+pub mod dead_letter;
 pub mod defaults;
+pub mod derived_metadata;
+pub mod digest;
 pub mod embedding_contract;
 pub mod embedding_provider;
 pub mod error;
 pub mod events;
 pub mod exif;
 pub mod fair;
+pub mod federation;
 pub mod file_safety;
+pub mod fine_tuning;
+pub mod graph_analytics;
+pub mod graph_diff;
+pub mod graph_path;
 pub mod hardware;
+pub mod inference_usage;
+pub mod ingestion;
+pub mod job_lane;
+pub mod job_worker;
+pub mod journal;
+pub mod language;
+pub mod link_suggestion;
 pub mod logging;
+pub mod merge;
 pub mod metering;
+pub mod metrics;
 pub mod models;
+pub mod note_bulk;
+pub mod note_metadata;
+pub mod note_template;
+pub mod note_view;
+pub mod ownership;
+pub mod pii;
+pub mod pinned;
+pub mod pipeline;
+pub mod prompt_template;
+pub mod publishing;
+pub mod rdf;
+pub mod resurface;
+pub mod review;
 pub mod search;
 pub mod shard;
+pub mod site_export;
+pub mod snippet;
 pub mod strict_filter;
+pub mod tag_bulk;
 pub mod tags;
+pub mod task;
+pub mod taxonomy_health;
 pub mod temporal;
 pub mod tokenizer;
 pub mod traits;
+pub mod translation;
+pub mod trash;
+pub mod typed_link;
+pub mod usage;
 pub mod uuid_utils;
+pub mod version_retention;
+pub mod webhook_filter;
 
 // Re-export commonly used types at crate root
+pub use archive_quota::{
+    ArchiveBlobUsage, ArchiveQuota, ArchiveQuotaExceeded, ArchiveQuotaKind, ArchiveTableUsage,
+    ArchiveUsage,
+};
 pub use audit::*;
 pub use authorization::*;
+pub use backup_policy::{
+    BackupPolicy, BackupPolicyRun, BackupRetention, CreateBackupPolicyRequest, CronSchedule,
+    UpdateBackupPolicyRequest,
+};
+pub use citation::{
+    extract_citations, render_bibtex, CitationKind, ExtractedCitation, NoteCitations, Reference,
+    ReferenceMetadata,
+};
 pub use collection_filter::{CollectionPathFilter, StrictCollectionFilter};
+pub use collection_rule::{
+    CollectionRule, CollectionRuleFilter, CollectionRuleSyncResult, SetCollectionRuleRequest,
+    MAX_COLLECTION_RULE_QUERY_CHARS, MAX_COLLECTION_RULE_TAGS,
+};
+pub use collection_subtree::{
+    collection_copy_name, CollectionCopyMode, CollectionCopyResult, CollectionReparentResult,
+    CopyCollectionRequest, MoveCollectionRequest, ReparentCollectionsRequest,
+    COPIED_FROM_LINK_KIND, MAX_COLLECTION_COPY_NOTES, MAX_REPARENT_COLLECTIONS,
+};
+pub use concept_governance::{
+    ConceptMergeResult, ConceptSplitOutcome, ConceptSplitResult, ConceptSplitRule,
+    ConceptSplitSuggestion, ConceptSplitTarget, SplitConceptRequest, MAX_MERGE_SOURCE_CONCEPTS,
+    MAX_SPLIT_RULE_NOTE_IDS, MAX_SPLIT_TARGETS,
+};
+pub use concept_mapping::{
+    is_valid_mapping_uri, ConceptReconciliation, UpdateMappingRelationRequest,
+    MAX_MAPPING_LABEL_CHARS, MAX_MAPPING_URI_LEN,
+};
+pub use concept_suggestion::{
+    ConceptReviewThreshold, ConceptSuggestion, ConceptSuggestionReview, ConceptSuggestionStatus,
+    NewConceptSuggestion,
+};
+pub use derived_metadata::{DerivedField, DerivedMetadata};
+pub use digest::{
+    digest_period_label, digest_prompt_notes, render_digest_note, CreateDigestRequest,
+    DigestCadence, DigestConfig, DigestDelivery, DigestNoteActivity, UpdateDigestRequest,
+};
 pub use embedding_contract::*;
 pub use embedding_provider::*;
 pub use error::{Error, Result};
@@ -43,20 +132,107 @@ pub use events::{
     ServerEvent, SseMetrics, SseMetricsSnapshot,
 };
 pub use exif::{DeviceInfo, ExifMetadata, GpsCoordinates};
-pub use fair::{DublinCoreExport, FairScore, JsonLdContext, JsonLdExport};
+pub use fair::{
+    DublinCoreExport, FairScore, JsonLdContext, JsonLdExport, JsonLdGraph,
+    JSON_LD_GRAPH_STREAM_TAIL,
+};
+pub use federation::{
+    ApplySyncChangesRequest, ConflictStrategy, CreateSyncPeerRequest, SyncApplyResult, SyncChange,
+    SyncChangesPage, SyncCursor, SyncDecision, SyncOutcome, SyncPeer, SyncPeerUpdate,
+    UpdateSyncPeerRequest,
+};
 pub use file_safety::{
     detect_content_type, is_valid_mime_type, sanitize_filename, validate_file, ValidationResult,
+    SLACK_EXPORT_MIME, WHATSAPP_CHAT_MIME,
+};
+pub use fine_tuning::{
+    fine_tuning_export_line, is_validation_sample, FineTuningExportFormat, FineTuningSplit,
+    GeneratedQaPair,
+};
+pub use graph_analytics::{
+    graph_analytics_limit, GraphAnalytics, GraphAnalyticsRun, GraphCommunitySummary,
+    NoteGraphMetric, DEFAULT_GRAPH_ANALYTICS_LIMIT, MAX_GRAPH_ANALYTICS_LIMIT,
+};
+pub use graph_diff::{
+    GraphDiff, GraphDiffLink, GraphDiffNote, GraphDiffQuery, GraphDiffSummary, GraphDiffTag,
+    GraphStateCounts, DEFAULT_GRAPH_DIFF_LIMIT, MAX_GRAPH_DIFF_LIMIT,
+};
+pub use graph_path::{
+    NotePath, NotePathEdgeType, NotePathNote, NotePathQuery, NotePathStep,
+    DEFAULT_NOTE_PATH_MAX_HOPS, MAX_NOTE_PATH_MAX_HOPS, MAX_NOTE_PATH_VISITED,
 };
 pub use hardware::{ContextBudget, HardwareConfig};
+pub use inference_usage::{
+    InferenceUsageFilter, InferenceUsageGroup, InferenceUsageRecord, InferenceUsageSummary,
+    InferenceUsageTotals,
+};
+pub use ingestion::{
+    IngestionAction, IngestionDecision, IngestionItem, IngestionKind, IngestionPolicy,
+    IngestionPolicyChain, QuarantinedItem,
+};
+pub use journal::{JournalSettings, JournalSettingsRequest, JournalToday};
+pub use link_suggestion::{
+    LinkSuggestion, LinkSuggestionConcept, LinkSuggestionFeedback, LinkSuggestionReason,
+    LinkSuggestions, LinkThresholdTuning, DEFAULT_LINK_SUGGESTION_LIMIT, MAX_LINK_SUGGESTION_LIMIT,
+};
+pub use merge::{merge_text, MergeConflict, TextMerge};
 pub use metering::*;
 pub use models::*;
+pub use note_bulk::{
+    BulkNoteFilter, BulkNotePatch, BulkNoteUpdateRequest, BulkNoteUpdateResult,
+    BULK_NOTE_UPDATE_BATCH_SIZE,
+};
+pub use note_metadata::{MetadataFieldFilter, MetadataOp, MetadataValue};
+pub use note_template::{TemplateVariable, TemplateVariableType, TemplateVersion};
+pub use note_view::{NoteView, RecentlyViewedNote};
+pub use ownership::{
+    resolve_access, AccessLevel, CreateShareGrantRequest, ShareGrant, SharePermission,
+    ShareResource, User,
+};
+pub use pii::{PiiDetector, PiiFinding, PiiKind, PiiNoteSummary, PiiRedactionMode, PiiSummary};
+pub use pinned::{PinnedNote, PinnedQuery};
+pub use prompt_template::{
+    fill_prompt_template, render_prompt, validate_prompt_template, PromptKey, PromptTemplate,
+    PromptVariable, SavePromptTemplateRequest,
+};
+pub use publishing::{
+    Publication, PublishCollectionRequest, PublishedPage, PublishedPageSummary, PublishedSearchHit,
+    PublishedSearchResults, PublishedSite,
+};
+pub use rdf::{
+    RdfConceptRecord, RdfConceptRelationRecord, RdfFormat, RdfGraph, RdfGraphSnapshot,
+    RdfLinkRecord, RdfNoteConceptRecord, RdfNoteRecord, RdfProvenanceRecord,
+};
+pub use resurface::{ResurfaceQuery, ResurfacedNote};
+pub use review::{
+    validate_review_grade, CreateReviewCardRequest, RecordReviewRequest, ReviewCard, ReviewQueue,
+    ReviewSchedule,
+};
 pub use search::*;
 pub use shard::*;
+pub use site_export::{SiteExportFormat, SiteExportRequest};
+pub use snippet::{MarkRange, SearchSnippet, SnippetFragment, SnippetOptions, SnippetSource};
 pub use strict_filter::{
     MetadataFilter, SemanticScopeFilter, StrictFilter, StrictSecurityFilter, Visibility,
 };
+pub use tag_bulk::{BulkTagFilter, BulkTagOperation, BulkTagSummary, MAX_BULK_TAG_NOTE_IDS};
 pub use tags::*;
+pub use task::{Task, TaskFilter, TaskListing, TaskSource, TaskStatus};
+pub use taxonomy_health::{
+    TaxonomyConceptStats, TaxonomyFix, TaxonomyHealthIssue, TaxonomyHealthReport,
+    TaxonomyIssueCounts, TaxonomyIssueKind,
+};
 pub use temporal::{NamedTemporalRange, StrictTemporalFilter};
 pub use tokenizer::*;
 pub use traits::*;
+pub use translation::ArchiveTranslationSetting;
+pub use trash::{trash_purge_at, TrashListing, TrashSelection, TrashedNote, MAX_TRASH_SELECTION};
+pub use typed_link::{
+    CreateLinkTypeRequest, CreateTypedLinkRequest, LinkTypeDefinition, UpdateTypedLinkRequest,
+};
+pub use usage::{usage_day, usage_day_reset, DailyUsage, UsageCounter, UsageQuotas, UsageReport};
 pub use uuid_utils::{extract_timestamp, is_v7, new_v7, v7_from_timestamp};
+pub use version_retention::{
+    VersionRetentionPolicy, DEFAULT_MAX_VERSIONS, MAX_RETAINED_VERSIONS, MAX_VERSION_AGE_DAYS,
+};
+pub use webhook_filter::{webhook_filters_match, WebhookFilter};
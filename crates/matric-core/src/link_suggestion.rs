@@ -0,0 +1,303 @@
+//! Link suggestions and the feedback that tunes automatic linking.
+//!
+//! A note's link suggestions are notes it is not yet linked to, ranked by a
+//! blend of embedding similarity, shared SKOS concepts and co-citation (notes
+//! that link to both). Accepting a suggestion links the two notes; rejecting
+//! one keeps the pair out of later suggestions. Each decision moves the
+//! archive's linking threshold offset by [`LINK_THRESHOLD_STEP`]: down after
+//! an accept, so the linker links more readily, and up after a reject.
+//!
+//! ```
+//! use matric_core::link_suggestion::{adjusted_link_threshold_offset, LinkThresholdTuning};
+//!
+//! let tuning = LinkThresholdTuning {
+//!     offset: adjusted_link_threshold_offset(0.0, false),
+//!     accepted_count: 0,
+//!     rejected_count: 1,
+//! };
+//! assert_eq!(tuning.apply(0.7), 0.71);
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How far one decision moves the archive's linking threshold offset.
+pub const LINK_THRESHOLD_STEP: f32 = 0.01;
+
+/// Furthest feedback can move the linking threshold from its default.
+pub const MAX_LINK_THRESHOLD_OFFSET: f32 = 0.15;
+
+/// Suggestions returned when a request names no limit.
+pub const DEFAULT_LINK_SUGGESTION_LIMIT: i64 = 10;
+
+/// Most suggestions returned per request.
+pub const MAX_LINK_SUGGESTION_LIMIT: i64 = 50;
+
+/// Nearest notes by embedding considered as candidates.
+pub const LINK_SUGGESTION_NEIGHBORS: i64 = 50;
+
+/// Weight of embedding similarity in a suggestion's score.
+pub const LINK_SUGGESTION_SIMILARITY_WEIGHT: f32 = 0.6;
+
+/// Weight of shared concepts in a suggestion's score.
+pub const LINK_SUGGESTION_CONCEPT_WEIGHT: f32 = 0.25;
+
+/// Weight of co-citation in a suggestion's score.
+pub const LINK_SUGGESTION_CO_CITATION_WEIGHT: f32 = 0.15;
+
+/// Co-citing notes at which co-citation counts in full.
+pub const LINK_SUGGESTION_CO_CITATION_SATURATION: i64 = 3;
+
+/// Why a note is suggested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkSuggestionReason {
+    /// The notes' embeddings are close
+    EmbeddingSimilarity,
+    /// The notes carry the same SKOS concepts
+    SharedConcepts,
+    /// Other notes link to both
+    CoCitation,
+}
+
+/// A concept both notes carry.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct LinkSuggestionConcept {
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl fmt::Debug for LinkSuggestionConcept {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkSuggestionConcept")
+            .field("id_set", &true)
+            .field("label_len", &self.label.as_ref().map(String::len))
+            .finish()
+    }
+}
+
+/// A note suggested as a link target.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct LinkSuggestion {
+    pub note_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Blended score, 0 to 1
+    pub score: f32,
+    /// Reasons for the suggestion, strongest first
+    pub reasons: Vec<LinkSuggestionReason>,
+    /// Cosine similarity of the notes' embeddings, when both have one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shared_concepts: Vec<LinkSuggestionConcept>,
+    /// Notes linking to both
+    pub co_citations: i64,
+}
+
+impl fmt::Debug for LinkSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkSuggestion")
+            .field("note_id_set", &true)
+            .field("title_len", &self.title.as_ref().map(String::len))
+            .field("score", &self.score)
+            .field("reasons", &self.reasons)
+            .field("similarity", &self.similarity)
+            .field("shared_concepts_count", &self.shared_concepts.len())
+            .field("co_citations", &self.co_citations)
+            .finish()
+    }
+}
+
+/// The archive's linking threshold offset and the decisions that shaped it.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema,
+)]
+pub struct LinkThresholdTuning {
+    /// Added to the linker's similarity thresholds, within
+    /// ±[`MAX_LINK_THRESHOLD_OFFSET`]
+    pub offset: f32,
+    pub accepted_count: i32,
+    pub rejected_count: i32,
+}
+
+impl LinkThresholdTuning {
+    /// `threshold` moved by the offset, kept within `0..=1`.
+    pub fn apply(&self, threshold: f32) -> f32 {
+        let tuned = (threshold + self.offset).clamp(0.0, 1.0);
+        (tuned * 100.0).round() / 100.0
+    }
+}
+
+/// A note's link suggestions.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct LinkSuggestions {
+    pub note_id: Uuid,
+    pub suggestions: Vec<LinkSuggestion>,
+    /// The archive's current linking threshold tuning
+    pub threshold: LinkThresholdTuning,
+}
+
+impl fmt::Debug for LinkSuggestions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkSuggestions")
+            .field("suggestions", &self.suggestions)
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
+
+/// Outcome of accepting or rejecting a suggestion.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct LinkSuggestionFeedback {
+    pub note_id: Uuid,
+    pub target_note_id: Uuid,
+    pub accepted: bool,
+    /// The suggestion's score when the decision was made
+    pub score: f32,
+    /// Whether accepting created links; false when the notes were already linked
+    pub link_created: bool,
+    /// The archive's threshold tuning after this decision
+    pub threshold: LinkThresholdTuning,
+}
+
+impl fmt::Debug for LinkSuggestionFeedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkSuggestionFeedback")
+            .field("accepted", &self.accepted)
+            .field("score", &self.score)
+            .field("link_created", &self.link_created)
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
+
+/// Offset after one decision: lowered after an accept, raised after a reject.
+pub fn adjusted_link_threshold_offset(offset: f32, accepted: bool) -> f32 {
+    let step = if accepted {
+        -LINK_THRESHOLD_STEP
+    } else {
+        LINK_THRESHOLD_STEP
+    };
+    let adjusted = (offset + step).clamp(-MAX_LINK_THRESHOLD_OFFSET, MAX_LINK_THRESHOLD_OFFSET);
+    // Keep repeated steps from drifting off the 0.01 grid.
+    (adjusted * 100.0).round() / 100.0
+}
+
+/// Score a candidate from its embedding similarity, the concepts it shares
+/// with the note and how many notes cite both.
+///
+/// Concept overlap is `shared / max(note concepts, candidate concepts)`, and
+/// co-citation counts in full from [`LINK_SUGGESTION_CO_CITATION_SATURATION`]
+/// citing notes. Reasons are listed by their contribution, strongest first.
+pub fn link_suggestion_score(
+    similarity: Option<f32>,
+    shared_concepts: usize,
+    note_concepts: usize,
+    candidate_concepts: usize,
+    co_citations: i64,
+) -> (f32, Vec<LinkSuggestionReason>) {
+    let mut parts = Vec::with_capacity(3);
+    if let Some(similarity) = similarity.filter(|similarity| *similarity > 0.0) {
+        parts.push((
+            LinkSuggestionReason::EmbeddingSimilarity,
+            similarity.min(1.0) * LINK_SUGGESTION_SIMILARITY_WEIGHT,
+        ));
+    }
+    let concept_total = note_concepts.max(candidate_concepts);
+    if shared_concepts > 0 && concept_total > 0 {
+        let overlap = (shared_concepts as f32 / concept_total as f32).min(1.0);
+        parts.push((
+            LinkSuggestionReason::SharedConcepts,
+            overlap * LINK_SUGGESTION_CONCEPT_WEIGHT,
+        ));
+    }
+    if co_citations > 0 {
+        let support = co_citations.min(LINK_SUGGESTION_CO_CITATION_SATURATION) as f32
+            / LINK_SUGGESTION_CO_CITATION_SATURATION as f32;
+        parts.push((
+            LinkSuggestionReason::CoCitation,
+            support * LINK_SUGGESTION_CO_CITATION_WEIGHT,
+        ));
+    }
+    parts.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let score = parts.iter().map(|(_, part)| part).sum::<f32>().min(1.0);
+    (score, parts.into_iter().map(|(reason, _)| reason).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_steps_within_bounds() {
+        assert_eq!(adjusted_link_threshold_offset(0.0, true), -0.01);
+        assert_eq!(adjusted_link_threshold_offset(0.0, false), 0.01);
+
+        let mut offset = 0.0;
+        for _ in 0..40 {
+            offset = adjusted_link_threshold_offset(offset, false);
+        }
+        assert_eq!(offset, MAX_LINK_THRESHOLD_OFFSET);
+        for _ in 0..40 {
+            offset = adjusted_link_threshold_offset(offset, true);
+        }
+        assert_eq!(offset, -MAX_LINK_THRESHOLD_OFFSET);
+    }
+
+    #[test]
+    fn tuning_applies_offset_within_unit_range() {
+        let tuning = LinkThresholdTuning {
+            offset: -0.05,
+            ..Default::default()
+        };
+        assert_eq!(tuning.apply(0.7), 0.65);
+        let raised = LinkThresholdTuning {
+            offset: MAX_LINK_THRESHOLD_OFFSET,
+            ..Default::default()
+        };
+        assert_eq!(raised.apply(0.95), 1.0);
+    }
+
+    #[test]
+    fn score_blends_signals_strongest_first() {
+        let (score, reasons) = link_suggestion_score(Some(0.5), 0, 2, 0, 0);
+        assert_eq!(score, 0.3);
+        assert_eq!(reasons, vec![LinkSuggestionReason::EmbeddingSimilarity]);
+
+        let (score, reasons) = link_suggestion_score(Some(0.1), 2, 2, 4, 9);
+        assert!((score - (0.06 + 0.125 + 0.15)).abs() < 1e-6);
+        assert_eq!(
+            reasons,
+            vec![
+                LinkSuggestionReason::CoCitation,
+                LinkSuggestionReason::SharedConcepts,
+                LinkSuggestionReason::EmbeddingSimilarity,
+            ]
+        );
+
+        assert_eq!(link_suggestion_score(None, 0, 0, 0, 0), (0.0, Vec::new()));
+    }
+
+    #[test]
+    fn suggestion_debug_redacts_title() {
+        let suggestion = LinkSuggestion {
+            note_id: Uuid::nil(),
+            title: Some("Client 秘密".to_string()),
+            score: 0.5,
+            reasons: vec![LinkSuggestionReason::SharedConcepts],
+            similarity: None,
+            shared_concepts: vec![LinkSuggestionConcept {
+                id: Uuid::nil(),
+                label: Some("秘密".to_string()),
+            }],
+            co_citations: 0,
+        };
+        let debug = format!("{suggestion:?}");
+        assert!(debug.contains("title_len"));
+        assert!(!debug.contains("秘密"));
+    }
+}
@@ -0,0 +1,262 @@
+//! Three-way merge of concurrent note edits.
+//!
+//! A client that edited a note offline sends its content together with the
+//! version it started from. Instead of letting the last write win, the
+//! server diffs both the current content and the incoming content against
+//! that common base, line by line, and keeps every change that does not
+//! touch the same base lines as a change on the other side. Overlapping or
+//! adjacent changes that differ are conflicts: the merged text carries
+//! git-style markers for them and [`TextMerge::conflicts`] lists each one.
+//!
+//! ```
+//! use matric_core::merge_text;
+//!
+//! let base = "# Groceries\nmilk\neggs\nbread\n";
+//! let current = "# Groceries\nmilk\neggs\nbread\nbutter\n";
+//! let incoming = "# Groceries list\nmilk\neggs\nbread\n";
+//!
+//! let merged = merge_text(base, current, incoming);
+//! assert!(merged.is_clean());
+//! assert_eq!(merged.content, "# Groceries list\nmilk\neggs\nbread\nbutter\n");
+//! ```
+
+use std::fmt;
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+use similar::{capture_diff_slices, Algorithm, DiffTag};
+
+/// Marker opening the current side of a conflict.
+pub const CONFLICT_MARKER_CURRENT: &str = "<<<<<<< current";
+/// Marker separating the current and incoming sides of a conflict.
+pub const CONFLICT_MARKER_SEPARATOR: &str = "=======";
+/// Marker closing the incoming side of a conflict.
+pub const CONFLICT_MARKER_INCOMING: &str = ">>>>>>> incoming";
+
+/// A region both sides changed differently.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct MergeConflict {
+    /// 1-based line of the opening marker in the merged content
+    pub line: usize,
+    /// The region as it was in the common base version
+    pub base: String,
+    /// The region as it is now on the server
+    pub current: String,
+    /// The region as the client sent it
+    pub incoming: String,
+}
+
+impl fmt::Debug for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MergeConflict")
+            .field("line", &self.line)
+            .field("base_len", &self.base.len())
+            .field("current_len", &self.current.len())
+            .field("incoming_len", &self.incoming.len())
+            .finish()
+    }
+}
+
+/// Result of [`merge_text`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct TextMerge {
+    /// Merged content; contains conflict markers unless the merge is clean
+    pub content: String,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl TextMerge {
+    /// Whether every change merged without conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+impl fmt::Debug for TextMerge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextMerge")
+            .field("content_len", &self.content.len())
+            .field("conflicts", &self.conflicts)
+            .finish()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Current,
+    Incoming,
+}
+
+/// Base lines `base` replaced by `lines` on one side.
+struct Hunk<'a> {
+    side: Side,
+    base: Range<usize>,
+    lines: &'a [&'a str],
+}
+
+fn hunks<'a>(side: Side, base: &[&str], other: &'a [&'a str]) -> Vec<Hunk<'a>> {
+    capture_diff_slices(Algorithm::Myers, base, other)
+        .into_iter()
+        .map(|op| op.as_tag_tuple())
+        .filter(|(tag, _, _)| *tag != DiffTag::Equal)
+        .map(|(_, base, new)| Hunk {
+            side,
+            base,
+            lines: &other[new],
+        })
+        .collect()
+}
+
+/// One side's text for the base range `range`, applying that side's hunks.
+fn side_text(side: Side, base: &[&str], range: Range<usize>, group: &[Hunk<'_>]) -> String {
+    let mut text = String::new();
+    let mut pos = range.start;
+    for hunk in group.iter().filter(|h| h.side == side) {
+        text.extend(base[pos..hunk.base.start].iter().copied());
+        text.extend(hunk.lines.iter().copied());
+        pos = hunk.base.end;
+    }
+    text.extend(base[pos..range.end].iter().copied());
+    text
+}
+
+fn push_marked(out: &mut String, text: &str) {
+    out.push_str(text);
+    if !text.is_empty() && !text.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+/// Merge `current` and `incoming`, both edited from `base`.
+///
+/// Changes to different lines are combined; changes that overlap or touch
+/// are taken as-is when both sides made the same edit and reported as a
+/// conflict otherwise.
+pub fn merge_text(base: &str, current: &str, incoming: &str) -> TextMerge {
+    if current == incoming || base == incoming {
+        return TextMerge {
+            content: current.to_string(),
+            conflicts: Vec::new(),
+        };
+    }
+    if base == current {
+        return TextMerge {
+            content: incoming.to_string(),
+            conflicts: Vec::new(),
+        };
+    }
+
+    let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+    let current_lines: Vec<&str> = current.split_inclusive('\n').collect();
+    let incoming_lines: Vec<&str> = incoming.split_inclusive('\n').collect();
+
+    let mut all = hunks(Side::Current, &base_lines, &current_lines);
+    all.extend(hunks(Side::Incoming, &base_lines, &incoming_lines));
+    all.sort_by_key(|h| (h.base.start, h.base.end));
+
+    let mut content = String::new();
+    let mut conflicts = Vec::new();
+    let mut cursor = 0;
+    let mut i = 0;
+    while i < all.len() {
+        let start = all[i].base.start;
+        let mut end = all[i].base.end;
+        let mut j = i + 1;
+        while j < all.len() && all[j].base.start <= end {
+            end = end.max(all[j].base.end);
+            j += 1;
+        }
+        let group = &all[i..j];
+        i = j;
+
+        content.extend(base_lines[cursor..start].iter().copied());
+        cursor = end;
+
+        let ours = side_text(Side::Current, &base_lines, start..end, group);
+        let theirs = side_text(Side::Incoming, &base_lines, start..end, group);
+        let changed_by = |side| group.iter().any(|h| h.side == side);
+        if ours == theirs || !changed_by(Side::Incoming) {
+            content.push_str(&ours);
+            continue;
+        }
+        if !changed_by(Side::Current) {
+            content.push_str(&theirs);
+            continue;
+        }
+
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        conflicts.push(MergeConflict {
+            line: content.matches('\n').count() + 1,
+            base: base_lines[start..end].concat(),
+            current: ours.clone(),
+            incoming: theirs.clone(),
+        });
+        content.push_str(CONFLICT_MARKER_CURRENT);
+        content.push('\n');
+        push_marked(&mut content, &ours);
+        content.push_str(CONFLICT_MARKER_SEPARATOR);
+        content.push('\n');
+        push_marked(&mut content, &theirs);
+        content.push_str(CONFLICT_MARKER_INCOMING);
+        content.push('\n');
+    }
+    content.extend(base_lines[cursor..].iter().copied());
+
+    TextMerge { content, conflicts }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_sided_edits_fast_forward() {
+        assert_eq!(merge_text("a\n", "a\n", "b\n").content, "b\n");
+        assert_eq!(merge_text("a\n", "b\n", "a\n").content, "b\n");
+        assert_eq!(merge_text("a\n", "b\n", "b\n").content, "b\n");
+    }
+
+    #[test]
+    fn edits_to_different_lines_combine() {
+        let base = "one\ntwo\nthree\nfour\nfive\n";
+        let current = "ONE\ntwo\nthree\nfour\nfive\n";
+        let incoming = "one\ntwo\nthree\nfour\nFIVE\nsix\n";
+        let merged = merge_text(base, current, incoming);
+        assert!(merged.is_clean());
+        assert_eq!(merged.content, "ONE\ntwo\nthree\nfour\nFIVE\nsix\n");
+    }
+
+    #[test]
+    fn identical_edits_are_not_conflicts() {
+        let merged = merge_text("a\nb\nc\n", "a\nB\nc\nd\n", "a\nB\nc\n");
+        assert!(merged.is_clean());
+        assert_eq!(merged.content, "a\nB\nc\nd\n");
+    }
+
+    #[test]
+    fn overlapping_edits_conflict_with_markers() {
+        let merged = merge_text("a\nb\nc\n", "a\nmine\nc\n", "a\ntheirs\nc\n");
+        assert_eq!(merged.conflicts.len(), 1);
+        let conflict = &merged.conflicts[0];
+        assert_eq!(conflict.line, 2);
+        assert_eq!(conflict.base, "b\n");
+        assert_eq!(conflict.current, "mine\n");
+        assert_eq!(conflict.incoming, "theirs\n");
+        assert_eq!(
+            merged.content,
+            "a\n<<<<<<< current\nmine\n=======\ntheirs\n>>>>>>> incoming\nc\n"
+        );
+    }
+
+    #[test]
+    fn conflicts_without_trailing_newline_keep_markers_on_their_own_lines() {
+        let merged = merge_text("a\nb", "a\nmine", "a\ntheirs");
+        assert!(!merged.is_clean());
+        assert_eq!(
+            merged.content,
+            "a\n<<<<<<< current\nmine\n=======\ntheirs\n>>>>>>> incoming\n"
+        );
+    }
+}
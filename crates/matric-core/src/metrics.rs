@@ -0,0 +1,334 @@
+//! Process-wide Prometheus metrics.
+//!
+//! Crates record into the shared [`global`] registry, and the API renders it
+//! in the Prometheus text exposition format on `/metrics`. Metric families
+//! are declared once as [`MetricDesc`] constants so their name, help text
+//! and type stay consistent between recorders and the exposition.
+//!
+//! Label values must come from bounded sets (route templates, job types,
+//! search strategies, backend names) — never from request content.
+//!
+//! ```
+//! use std::time::Duration;
+//! use matric_core::metrics::{MetricsRegistry, SEARCH_DURATION_SECONDS};
+//!
+//! let registry = MetricsRegistry::new();
+//! registry.observe(
+//!     &SEARCH_DURATION_SECONDS,
+//!     &[("stage", "fts"), ("strategy", "fts_english")],
+//!     Duration::from_millis(12),
+//! );
+//! assert!(registry
+//!     .render()
+//!     .contains(r#"matric_search_duration_seconds_count{stage="fts",strategy="fts_english"} 1"#));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Histogram bucket upper bounds, in seconds, shared by every latency family.
+pub const LATENCY_BUCKETS_SECONDS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0,
+];
+
+/// Prometheus metric type of a family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+
+/// Name, help text and type of a metric family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricDesc {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: MetricKind,
+}
+
+const fn desc(name: &'static str, help: &'static str, kind: MetricKind) -> MetricDesc {
+    MetricDesc { name, help, kind }
+}
+
+pub const HTTP_REQUEST_DURATION_SECONDS: MetricDesc = desc(
+    "matric_http_request_duration_seconds",
+    "HTTP request latency by method, route template and status class.",
+    MetricKind::Histogram,
+);
+pub const JOB_DURATION_SECONDS: MetricDesc = desc(
+    "matric_job_duration_seconds",
+    "Background job processing time by job type and outcome.",
+    MetricKind::Histogram,
+);
+pub const JOB_QUEUE_DEPTH: MetricDesc = desc(
+    "matric_job_queue_depth",
+    "Jobs in the queue by state.",
+    MetricKind::Gauge,
+);
+pub const SEARCH_DURATION_SECONDS: MetricDesc = desc(
+    "matric_search_duration_seconds",
+    "Search latency by stage and retrieval strategy.",
+    MetricKind::Histogram,
+);
+pub const EMBEDDING_DURATION_SECONDS: MetricDesc = desc(
+    "matric_embedding_duration_seconds",
+    "Embedding backend request latency by backend and outcome.",
+    MetricKind::Histogram,
+);
+pub const DB_POOL_CONNECTIONS: MetricDesc = desc(
+    "matric_db_pool_connections",
+    "Database pool connections by state (active, idle, max).",
+    MetricKind::Gauge,
+);
+pub const EVENT_BUS_BACKLOG: MetricDesc = desc(
+    "matric_event_bus_backlog",
+    "Events queued on the event bus not yet received by every subscriber.",
+    MetricKind::Gauge,
+);
+pub const EVENT_BUS_SUBSCRIBERS: MetricDesc = desc(
+    "matric_event_bus_subscribers",
+    "Active event bus subscribers.",
+    MetricKind::Gauge,
+);
+pub const EVENT_BUS_EVENTS_EMITTED_TOTAL: MetricDesc = desc(
+    "matric_event_bus_events_emitted_total",
+    "Events emitted on the event bus since startup.",
+    MetricKind::Counter,
+);
+pub const EVENT_BUS_EVENTS_LAGGED_TOTAL: MetricDesc = desc(
+    "matric_event_bus_events_lagged_total",
+    "Events dropped for subscribers that fell behind the event bus.",
+    MetricKind::Counter,
+);
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug, Clone)]
+enum Series {
+    Value(f64),
+    Histogram {
+        buckets: Vec<u64>,
+        sum: f64,
+        count: u64,
+    },
+}
+
+#[derive(Debug)]
+struct Family {
+    desc: MetricDesc,
+    series: BTreeMap<Labels, Series>,
+}
+
+/// A set of metric families that renders to the Prometheus text format.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    families: Mutex<BTreeMap<&'static str, Family>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_series(
+        &self,
+        desc: &MetricDesc,
+        labels: &[(&'static str, &str)],
+        update: impl FnOnce(&mut Series),
+    ) {
+        let mut families = self
+            .families
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let family = families.entry(desc.name).or_insert_with(|| Family {
+            desc: *desc,
+            series: BTreeMap::new(),
+        });
+        let labels = labels
+            .iter()
+            .map(|(name, value)| (*name, (*value).to_string()))
+            .collect();
+        let series = family
+            .series
+            .entry(labels)
+            .or_insert_with(|| match desc.kind {
+                MetricKind::Histogram => Series::Histogram {
+                    buckets: vec![0; LATENCY_BUCKETS_SECONDS.len()],
+                    sum: 0.0,
+                    count: 0,
+                },
+                MetricKind::Counter | MetricKind::Gauge => Series::Value(0.0),
+            });
+        update(series);
+    }
+
+    /// Add `amount` to a counter.
+    pub fn inc_counter(&self, desc: &MetricDesc, labels: &[(&'static str, &str)], amount: u64) {
+        self.with_series(desc, labels, |series| {
+            if let Series::Value(value) = series {
+                *value += amount as f64;
+            }
+        });
+    }
+
+    /// Set a gauge, or a counter mirrored from an existing monotonic source.
+    pub fn set(&self, desc: &MetricDesc, labels: &[(&'static str, &str)], value: f64) {
+        self.with_series(desc, labels, |series| {
+            if let Series::Value(current) = series {
+                *current = value;
+            }
+        });
+    }
+
+    /// Record one observation of a latency histogram.
+    pub fn observe(&self, desc: &MetricDesc, labels: &[(&'static str, &str)], elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        self.with_series(desc, labels, |series| {
+            if let Series::Histogram {
+                buckets,
+                sum,
+                count,
+            } = series
+            {
+                for (bucket, bound) in buckets.iter_mut().zip(LATENCY_BUCKETS_SECONDS) {
+                    if seconds <= *bound {
+                        *bucket += 1;
+                    }
+                }
+                *sum += seconds;
+                *count += 1;
+            }
+        });
+    }
+
+    /// Render every family in the Prometheus text exposition format (0.0.4).
+    pub fn render(&self) -> String {
+        let families = self
+            .families
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut out = String::new();
+        for family in families.values() {
+            let name = family.desc.name;
+            let _ = writeln!(out, "# HELP {name} {}", family.desc.help);
+            let _ = writeln!(out, "# TYPE {name} {}", family.desc.kind.as_str());
+            for (labels, series) in &family.series {
+                match series {
+                    Series::Value(value) => {
+                        let _ = writeln!(out, "{name}{} {value}", render_labels(labels, None));
+                    }
+                    Series::Histogram {
+                        buckets,
+                        sum,
+                        count,
+                    } => {
+                        for (bucket, bound) in buckets.iter().zip(LATENCY_BUCKETS_SECONDS) {
+                            let le = bound.to_string();
+                            let _ = writeln!(
+                                out,
+                                "{name}_bucket{} {bucket}",
+                                render_labels(labels, Some(&le))
+                            );
+                        }
+                        let _ = writeln!(
+                            out,
+                            "{name}_bucket{} {count}",
+                            render_labels(labels, Some("+Inf"))
+                        );
+                        let _ = writeln!(out, "{name}_sum{} {sum}", render_labels(labels, None));
+                        let _ =
+                            writeln!(out, "{name}_count{} {count}", render_labels(labels, None));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn render_labels(labels: &Labels, le: Option<&str>) -> String {
+    if labels.is_empty() && le.is_none() {
+        return String::new();
+    }
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{le}\""));
+    }
+    format!("{{{}}}", pairs.join(","))
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// The registry shared by every crate in the process.
+pub fn global() -> &'static MetricsRegistry {
+    static REGISTRY: OnceLock<MetricsRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(MetricsRegistry::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let registry = MetricsRegistry::new();
+        let labels = [("job_type", "embedding"), ("outcome", "success")];
+        registry.observe(&JOB_DURATION_SECONDS, &labels, Duration::from_millis(30));
+        registry.observe(&JOB_DURATION_SECONDS, &labels, Duration::from_secs(2));
+
+        let text = registry.render();
+        assert!(text.contains("# TYPE matric_job_duration_seconds histogram"));
+        assert!(text.contains(
+            r#"matric_job_duration_seconds_bucket{job_type="embedding",outcome="success",le="0.025"} 0"#
+        ));
+        assert!(text.contains(
+            r#"matric_job_duration_seconds_bucket{job_type="embedding",outcome="success",le="0.05"} 1"#
+        ));
+        assert!(text.contains(
+            r#"matric_job_duration_seconds_bucket{job_type="embedding",outcome="success",le="+Inf"} 2"#
+        ));
+        assert!(text.contains(
+            r#"matric_job_duration_seconds_count{job_type="embedding",outcome="success"} 2"#
+        ));
+    }
+
+    #[test]
+    fn counters_accumulate_and_gauges_overwrite() {
+        let registry = MetricsRegistry::new();
+        registry.inc_counter(&EVENT_BUS_EVENTS_EMITTED_TOTAL, &[], 2);
+        registry.inc_counter(&EVENT_BUS_EVENTS_EMITTED_TOTAL, &[], 3);
+        registry.set(&JOB_QUEUE_DEPTH, &[("state", "pending")], 7.0);
+        registry.set(&JOB_QUEUE_DEPTH, &[("state", "pending")], 4.0);
+
+        let text = registry.render();
+        assert!(text.contains("matric_event_bus_events_emitted_total 5\n"));
+        assert!(text.contains("matric_job_queue_depth{state=\"pending\"} 4\n"));
+        assert!(text.contains("# TYPE matric_job_queue_depth gauge"));
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape_label_value("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
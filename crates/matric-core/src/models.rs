@@ -25,6 +25,13 @@ pub struct NoteMeta {
     pub updated_at_utc: DateTime<Utc>,
     pub starred: bool,
     pub archived: bool,
+    /// Pinned notes are listed by `GET /api/v1/notes/pinned` and keep a
+    /// place near the top of search results they match.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Order among pinned notes (0-100, higher first).
+    #[serde(default)]
+    pub priority: i32,
     pub last_accessed_at: Option<DateTime<Utc>>,
     /// Number of times this note has been accessed (read)
     #[serde(default)]
@@ -36,6 +43,14 @@ pub struct NoteMeta {
     /// Associated document type for content-aware chunking and embedding
     #[serde(skip_serializing_if = "Option::is_none")]
     pub document_type_id: Option<Uuid>,
+    /// Content is stored as PKE ciphertext and served empty; read it with
+    /// `POST /api/v1/notes/{id}/decrypt`.
+    #[serde(default)]
+    pub encrypted: bool,
+    /// Detected content language (ISO 639-1, e.g. `de`); absent when
+    /// detection was inconclusive or the note is encrypted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
 }
 
 impl fmt::Debug for NoteMeta {
@@ -49,6 +64,8 @@ impl fmt::Debug for NoteMeta {
             .field("updated_at_utc", &self.updated_at_utc)
             .field("starred", &self.starred)
             .field("archived", &self.archived)
+            .field("pinned", &self.pinned)
+            .field("priority", &self.priority)
             .field("last_accessed_at_set", &self.last_accessed_at.is_some())
             .field("access_count", &self.access_count)
             .field("title_len", &optional_debug_len(self.title.as_ref()))
@@ -63,6 +80,8 @@ impl fmt::Debug for NoteMeta {
                 &self.chunk_metadata.as_ref().map(json_serialized_len),
             )
             .field("document_type_id_set", &self.document_type_id.is_some())
+            .field("encrypted", &self.encrypted)
+            .field("language", &self.language)
             .finish()
     }
 }
@@ -141,6 +160,13 @@ pub struct NoteFull {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub concepts: Vec<NoteConceptSummary>,
     pub links: Vec<Link>,
+    /// AI-generated summary, present once the summarization job has run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<NoteGeneratedSummary>,
+    /// Machine translation into the archive's target language, present once
+    /// the translation job has run for a note in another language.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation: Option<NoteTranslation>,
 }
 
 impl fmt::Debug for NoteFull {
@@ -152,6 +178,68 @@ impl fmt::Debug for NoteFull {
             .field("tags_count", &self.tags.len())
             .field("concepts_count", &self.concepts.len())
             .field("links_count", &self.links.len())
+            .field("summary", &self.summary)
+            .field("translation", &self.translation)
+            .finish()
+    }
+}
+
+/// AI-generated summary of a note's content.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct NoteGeneratedSummary {
+    pub note_id: Uuid,
+    pub summary: String,
+    /// Model that produced the summary
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Number of content chunks summarized; above 1 the summary was
+    /// map-reduced from per-chunk summaries
+    pub chunk_count: i32,
+    pub generated_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for NoteGeneratedSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NoteGeneratedSummary")
+            .field("note_id_set", &true)
+            .field("summary_len", &debug_len(&self.summary))
+            .field("model_len", &optional_debug_len(self.model.as_ref()))
+            .field("chunk_count", &self.chunk_count)
+            .field("generated_at_utc", &self.generated_at_utc)
+            .finish()
+    }
+}
+
+/// Machine translation of a note's content.
+///
+/// Stored alongside the original and revised content, which it never
+/// replaces, and matched by full-text search.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct NoteTranslation {
+    pub note_id: Uuid,
+    /// Detected language of the translated content (e.g. `de`)
+    pub source_language: String,
+    /// Language of `content` (e.g. `en`)
+    pub target_language: String,
+    pub content: String,
+    /// Model that produced the translation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Number of content chunks translated separately
+    pub chunk_count: i32,
+    pub generated_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for NoteTranslation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NoteTranslation")
+            .field("note_id_set", &true)
+            .field("source_language", &self.source_language)
+            .field("target_language", &self.target_language)
+            .field("content_len", &debug_len(&self.content))
+            .field("model_len", &optional_debug_len(self.model.as_ref()))
+            .field("chunk_count", &self.chunk_count)
+            .field("generated_at_utc", &self.generated_at_utc)
             .finish()
     }
 }
@@ -350,6 +438,26 @@ impl fmt::Debug for SearchHit {
     }
 }
 
+/// Playback range of a search hit inside a transcribed audio or video
+/// attachment, taken from the transcript segments of the matching chunk.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct MediaSegment {
+    /// Attachment whose transcript the chunk came from.
+    pub attachment_id: Uuid,
+    pub start_secs: f64,
+    pub end_secs: f64,
+}
+
+impl fmt::Debug for MediaSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MediaSegment")
+            .field("attachment_id_set", &true)
+            .field("start_secs", &self.start_secs)
+            .field("end_secs", &self.end_secs)
+            .finish()
+    }
+}
+
 /// Search results response.
 #[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct SearchResponse {
@@ -1684,6 +1792,71 @@ impl fmt::Debug for RevisionChunkingConfig {
     }
 }
 
+/// Per-document-type processing policy.
+///
+/// Unset fields keep the pipeline's normal behaviour. The extraction job,
+/// `queue_nlp_pipeline` and the embedding job read the policy of the note's
+/// document type and apply it over their defaults; explicit per-request
+/// choices (such as a caller-supplied `revision_mode`) still win.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PipelinePolicy {
+    /// Extraction strategy for attachments, replacing MIME-based detection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction_strategy: Option<ExtractionStrategy>,
+    /// Chunker used when embedding notes of this type. Chunk size and
+    /// overlap come from the document type's `chunk_size_default` and
+    /// `chunk_overlap_default`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunking_strategy: Option<ChunkingStrategy>,
+    /// Whether AI revision runs. `false` keeps the original content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_revision: Option<bool>,
+    /// Embedding set that notes of this type are embedded into; the set's
+    /// embedding config selects the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_set_id: Option<Uuid>,
+    /// Tags applied to every note of this type.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_tags: Vec<String>,
+    /// Pipeline features to queue, using the names accepted by a note's
+    /// `pipeline` field. An empty list stores notes without AI processing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<Vec<String>>,
+    /// Metadata fields computed from content whenever the note's pipeline is
+    /// queued, stored under `metadata.derived`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derived_fields: Vec<crate::derived_metadata::DerivedField>,
+}
+
+impl PipelinePolicy {
+    /// True when no field is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl fmt::Debug for PipelinePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PipelinePolicy")
+            .field("extraction_strategy", &self.extraction_strategy)
+            .field("chunking_strategy", &self.chunking_strategy)
+            .field("ai_revision", &self.ai_revision)
+            .field("embedding_set_id_set", &self.embedding_set_id.is_some())
+            .field("default_tags_count", &self.default_tags.len())
+            .field(
+                "default_tag_lens",
+                &self
+                    .default_tags
+                    .iter()
+                    .map(|tag| debug_len(tag))
+                    .collect::<Vec<_>>(),
+            )
+            .field("pipeline", &self.pipeline)
+            .field("derived_fields", &self.derived_fields)
+            .finish()
+    }
+}
+
 /// Extraction strategy for processing file attachments (Issue #436).
 ///
 /// Determines how content is extracted from attached files for indexing and search.
@@ -1715,10 +1888,16 @@ pub enum ExtractionStrategy {
     Glb3DModel,
     /// Email parsing (.eml, .mbox) with header extraction
     Email,
-    /// Multi-sheet spreadsheet extraction (.xlsx, .xls, .ods)
+    /// Multi-sheet spreadsheet extraction (.xlsx, .xls, .ods, .csv, .tsv)
     Spreadsheet,
     /// Archive content listing and text extraction (.zip, .tar.gz, .rar, .7z)
     Archive,
+    /// Ebook extraction (.epub, .mobi) with chapters, cover and book metadata
+    Ebook,
+    /// Chat export parsing (Slack JSON, WhatsApp TXT) into messages and threads
+    ChatExport,
+    /// Jupyter notebook conversion (.ipynb) to Markdown with code declarations
+    Notebook,
 }
 
 /// Strategy for extracting keyframes from video files.
@@ -1781,6 +1960,18 @@ impl ExtractionStrategy {
             return Self::PdfText;
         }
 
+        // Jupyter notebooks (before the structured +json check)
+        if mime_lower == "application/x-ipynb+json" {
+            return Self::Notebook;
+        }
+
+        // Chat exports (sniffed from content at upload; before text/* and +json)
+        if mime_lower == crate::file_safety::SLACK_EXPORT_MIME
+            || mime_lower == crate::file_safety::WHATSAPP_CHAT_MIME
+        {
+            return Self::ChatExport;
+        }
+
         // Images
         if mime_lower.starts_with("image/") {
             return Self::Vision;
@@ -1810,6 +2001,9 @@ impl ExtractionStrategy {
         if mime_lower.contains("spreadsheetml")
             || mime_lower == "application/vnd.ms-excel"
             || mime_lower == "application/vnd.oasis.opendocument.spreadsheet"
+            || mime_lower == "text/csv"
+            || mime_lower == "application/csv"
+            || mime_lower == "text/tab-separated-values"
         {
             return Self::Spreadsheet;
         }
@@ -1833,6 +2027,14 @@ impl ExtractionStrategy {
             return Self::Email;
         }
 
+        // Ebooks (before archives: EPUB is a zip container)
+        if mime_lower == "application/epub+zip"
+            || mime_lower == "application/x-mobipocket-ebook"
+            || mime_lower == "application/vnd.amazon.ebook"
+        {
+            return Self::Ebook;
+        }
+
         // Archives
         if mime_lower == "application/zip"
             || mime_lower == "application/x-tar"
@@ -1855,7 +2057,6 @@ impl ExtractionStrategy {
                 | "text/xml"
                 | "application/yaml"
                 | "text/yaml"
-                | "text/csv"
                 | "application/toml"
                 | "application/x-bibtex"
                 | "application/x-research-info-systems"
@@ -1902,11 +2103,13 @@ impl ExtractionStrategy {
                 return match ext.to_lowercase().as_str() {
                     // Cheap text-based extraction — safe even for misidentified files
                     "pdf" => Self::PdfText,
-                    "xls" | "xlsx" | "ods" => Self::Spreadsheet,
+                    "xls" | "xlsx" | "ods" | "csv" | "tsv" => Self::Spreadsheet,
                     "doc" | "docx" | "ppt" | "pptx" | "odt" | "odp" | "rtf" => Self::OfficeConvert,
                     "eml" | "mbox" => Self::Email,
                     "zip" | "tar" | "gz" | "tgz" | "7z" | "rar" | "bz2" | "xz" => Self::Archive,
-                    "json" | "xml" | "yaml" | "yml" | "csv" | "toml" => Self::StructuredExtract,
+                    "epub" | "mobi" | "azw" | "azw3" => Self::Ebook,
+                    "ipynb" => Self::Notebook,
+                    "json" | "xml" | "yaml" | "yml" | "toml" => Self::StructuredExtract,
                     "ics" | "bib" | "geojson" | "ndjson" | "parquet" | "avro" | "mid" | "midi" => {
                         Self::StructuredExtract
                     }
@@ -1927,6 +2130,11 @@ impl ExtractionStrategy {
             }
         }
 
+        // EPUB uploads often arrive labelled as plain zip
+        if base == Self::Archive && extension.is_some_and(|ext| ext.eq_ignore_ascii_case("epub")) {
+            return Self::Ebook;
+        }
+
         // Refine code files that come as text/*
         if base == Self::TextNative {
             if let Some(ext) = extension {
@@ -1958,6 +2166,9 @@ impl std::fmt::Display for ExtractionStrategy {
             Self::Email => write!(f, "email"),
             Self::Spreadsheet => write!(f, "spreadsheet"),
             Self::Archive => write!(f, "archive"),
+            Self::Ebook => write!(f, "ebook"),
+            Self::ChatExport => write!(f, "chat_export"),
+            Self::Notebook => write!(f, "notebook"),
         }
     }
 }
@@ -1979,8 +2190,11 @@ impl std::str::FromStr for ExtractionStrategy {
             }
             "glb_3d_model" | "glb3dmodel" | "glb" | "3d_model" => Ok(Self::Glb3DModel),
             "email" | "eml" | "mbox" => Ok(Self::Email),
-            "spreadsheet" | "xlsx" | "xls" | "ods" => Ok(Self::Spreadsheet),
+            "spreadsheet" | "xlsx" | "xls" | "ods" | "csv" | "tsv" => Ok(Self::Spreadsheet),
             "archive" | "zip" | "tar" | "7z" | "rar" => Ok(Self::Archive),
+            "ebook" | "epub" | "mobi" => Ok(Self::Ebook),
+            "chat_export" | "chatexport" | "slack" | "whatsapp" => Ok(Self::ChatExport),
+            "notebook" | "ipynb" | "jupyter" => Ok(Self::Notebook),
             "none" => Ok(Self::TextNative),
             _ => Err(format!(
                 "Invalid extraction strategy; value_len={}",
@@ -2054,6 +2268,14 @@ pub struct DocumentType {
     // AI generation metadata
     #[serde(default)]
     pub agentic_config: AgenticConfig,
+
+    /// Extraction and pipeline policy for notes of this type
+    #[serde(default, skip_serializing_if = "PipelinePolicy::is_empty")]
+    pub pipeline_policy: PipelinePolicy,
+
+    /// JSON Schema that metadata of notes of this type must satisfy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_schema: Option<JsonValue>,
 }
 
 impl fmt::Debug for DocumentType {
@@ -2155,6 +2377,11 @@ impl fmt::Debug for DocumentType {
                 &optional_debug_len(self.created_by.as_ref()),
             )
             .field("agentic_config", &self.agentic_config)
+            .field("pipeline_policy", &self.pipeline_policy)
+            .field(
+                "metadata_schema_len",
+                &self.metadata_schema.as_ref().map(json_serialized_len),
+            )
             .finish()
     }
 }
@@ -2249,6 +2476,11 @@ pub struct CreateDocumentTypeRequest {
     pub requires_attachment: bool,
     #[serde(default)]
     pub attachment_generates_content: bool,
+    #[serde(default)]
+    pub pipeline_policy: Option<PipelinePolicy>,
+    /// JSON Schema that metadata of notes of this type must satisfy
+    #[serde(default)]
+    pub metadata_schema: Option<JsonValue>,
 }
 
 impl fmt::Debug for CreateDocumentTypeRequest {
@@ -2304,6 +2536,11 @@ impl fmt::Debug for CreateDocumentTypeRequest {
                 "attachment_generates_content",
                 &self.attachment_generates_content,
             )
+            .field("pipeline_policy", &self.pipeline_policy)
+            .field(
+                "metadata_schema_len",
+                &self.metadata_schema.as_ref().map(json_serialized_len),
+            )
             .finish()
     }
 }
@@ -2360,6 +2597,11 @@ pub struct UpdateDocumentTypeRequest {
     pub requires_attachment: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attachment_generates_content: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pipeline_policy: Option<PipelinePolicy>,
+    /// Replaces the metadata JSON Schema; `{}` accepts any metadata again
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata_schema: Option<JsonValue>,
 }
 
 impl fmt::Debug for UpdateDocumentTypeRequest {
@@ -2426,6 +2668,11 @@ impl fmt::Debug for UpdateDocumentTypeRequest {
                 "attachment_generates_content",
                 &self.attachment_generates_content,
             )
+            .field("pipeline_policy", &self.pipeline_policy)
+            .field(
+                "metadata_schema_len",
+                &self.metadata_schema.as_ref().map(json_serialized_len),
+            )
             .finish()
     }
 }
@@ -2483,6 +2730,68 @@ impl fmt::Debug for AttachmentBlob {
     }
 }
 
+/// Deduplication statistics for an archive's attachment blob store.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BlobStoreStats {
+    /// Distinct blobs stored (one per content hash).
+    pub blob_count: i64,
+    /// Attachments referencing a blob.
+    pub attachment_count: i64,
+    /// Bytes actually stored across all blobs.
+    pub stored_bytes: i64,
+    /// Bytes that would be stored without deduplication.
+    pub logical_bytes: i64,
+    /// Bytes saved by sharing blobs between attachments.
+    pub deduplicated_bytes: i64,
+    /// `logical_bytes / referenced stored bytes`; 1.0 means no sharing.
+    pub dedup_ratio: f64,
+    /// Blobs no attachment references, awaiting garbage collection.
+    pub orphaned_blob_count: i64,
+    /// Bytes held by orphaned blobs.
+    pub orphaned_bytes: i64,
+}
+
+impl BlobStoreStats {
+    /// Derive savings from raw totals. `stored_bytes` includes orphans, which
+    /// are excluded when comparing against `logical_bytes`.
+    pub fn from_totals(
+        blob_count: i64,
+        attachment_count: i64,
+        stored_bytes: i64,
+        logical_bytes: i64,
+        orphaned_blob_count: i64,
+        orphaned_bytes: i64,
+    ) -> Self {
+        let referenced_bytes = (stored_bytes - orphaned_bytes).max(0);
+        let dedup_ratio = if referenced_bytes > 0 {
+            logical_bytes as f64 / referenced_bytes as f64
+        } else {
+            1.0
+        };
+        Self {
+            blob_count,
+            attachment_count,
+            stored_bytes,
+            logical_bytes,
+            deduplicated_bytes: (logical_bytes - referenced_bytes).max(0),
+            dedup_ratio,
+            orphaned_blob_count,
+            orphaned_bytes,
+        }
+    }
+}
+
+/// Outcome of one blob garbage collection pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobGcReport {
+    /// Blob rows deleted.
+    pub blobs_deleted: u64,
+    /// Bytes released by the deleted blobs.
+    pub bytes_reclaimed: i64,
+    /// Stored files that could not be removed (logged, left on disk).
+    pub file_delete_failures: u64,
+}
+
 /// File attachment metadata.
 #[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct Attachment {
@@ -2512,6 +2821,9 @@ pub struct Attachment {
     pub virus_scan_signature_version: Option<String>,
     pub virus_scan_reason_code: Option<String>,
     pub virus_scan_blob_hash: Option<String>,
+    /// Whether the stored blob is encrypted at rest (MMPKE01).
+    #[serde(default)]
+    pub encrypted: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -2580,6 +2892,7 @@ impl fmt::Debug for Attachment {
                 "virus_scan_blob_hash_len",
                 &optional_debug_len(self.virus_scan_blob_hash.as_ref()),
             )
+            .field("encrypted", &self.encrypted)
             .field("created_at", &self.created_at)
             .field("updated_at", &self.updated_at)
             .finish()
@@ -2709,6 +3022,9 @@ pub struct AttachmentSummary {
     pub is_canonical_content: bool,
     pub virus_scan_status: AttachmentScanStatus,
     pub virus_scan_at: Option<DateTime<Utc>>,
+    /// Whether the stored blob is encrypted at rest (MMPKE01).
+    #[serde(default)]
+    pub encrypted: bool,
     pub created_at: DateTime<Utc>,
 }
 
@@ -2734,6 +3050,7 @@ impl fmt::Debug for AttachmentSummary {
             .field("is_canonical_content", &self.is_canonical_content)
             .field("virus_scan_status", &self.virus_scan_status)
             .field("virus_scan_at", &self.virus_scan_at)
+            .field("encrypted", &self.encrypted)
             .field("created_at", &self.created_at)
             .finish()
     }
@@ -2785,6 +3102,30 @@ impl fmt::Debug for GlobalAttachmentSummary {
             .finish()
     }
 }
+
+/// An image attachment matched by CLIP similarity search.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ImageSearchHit {
+    pub attachment_id: Uuid,
+    pub note_id: Uuid,
+    pub filename: String,
+    pub content_type: String,
+    /// Cosine similarity to the query, higher is closer
+    pub score: f32,
+}
+
+impl fmt::Debug for ImageSearchHit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageSearchHit")
+            .field("attachment_id_set", &true)
+            .field("note_id_set", &true)
+            .field("filename_len", &debug_len(&self.filename))
+            .field("content_type_len", &debug_len(&self.content_type))
+            .field("score", &self.score)
+            .finish()
+    }
+}
+
 // =============================================================================
 // TUS RESUMABLE UPLOADS
 // =============================================================================
@@ -3017,7 +3358,7 @@ impl fmt::Debug for CreateTranscriptSegmentRequest {
 // =============================================================================
 
 /// Entity types for named entity recognition.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum EntityType {
     Person,
@@ -3128,6 +3469,127 @@ impl fmt::Debug for EntityStats {
     }
 }
 
+impl EntityType {
+    /// Types produced by the entity extraction job.
+    pub const EXTRACTED: [Self; 4] = [Self::Person, Self::Organization, Self::Location, Self::Date];
+}
+
+/// Canonical form of an entity name, used to merge mentions of the same
+/// entity: whitespace collapsed, surrounding punctuation trimmed, lowercased.
+pub fn normalize_entity_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| c.is_ascii_punctuation() || c.is_whitespace())
+        .to_lowercase()
+}
+
+/// An entity found in a note by the entity extraction job.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractedEntity {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub entity_type: EntityType,
+}
+
+impl fmt::Debug for ExtractedEntity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtractedEntity")
+            .field("name_len", &debug_len(&self.name))
+            .field("entity_type", &self.entity_type)
+            .finish()
+    }
+}
+
+/// An entity in an archive's entity registry.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct NamedEntity {
+    pub id: Uuid,
+    pub entity_type: EntityType,
+    /// Display name, as first extracted
+    pub name: String,
+    /// Number of live notes that mention the entity
+    pub note_count: i64,
+    pub created_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for NamedEntity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NamedEntity")
+            .field("id_set", &true)
+            .field("entity_type", &self.entity_type)
+            .field("name_len", &debug_len(&self.name))
+            .field("note_count", &self.note_count)
+            .field("created_at_utc", &self.created_at_utc)
+            .finish()
+    }
+}
+
+/// A note that mentions an entity.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct EntityNoteRef {
+    pub note_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub updated_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for EntityNoteRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EntityNoteRef")
+            .field("note_id_set", &true)
+            .field("title_len", &optional_debug_len(self.title.as_ref()))
+            .field("updated_at_utc", &self.updated_at_utc)
+            .finish()
+    }
+}
+
+/// An entity with the notes that mention it, most recently updated first.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct NamedEntityDetail {
+    #[serde(flatten)]
+    pub entity: NamedEntity,
+    pub notes: Vec<EntityNoteRef>,
+}
+
+/// A page of registry entities.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct NamedEntityList {
+    pub entities: Vec<NamedEntity>,
+    /// Entities matching the filter across all pages
+    pub total: i64,
+}
+
+/// Filter for listing registry entities.
+#[derive(Debug, Clone, Default)]
+pub struct EntityListFilter {
+    pub entity_type: Option<EntityType>,
+    /// Case-insensitive substring of the entity name
+    pub q: Option<String>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// How many of a result set's notes mention an entity.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct EntityFacet {
+    pub entity_id: Uuid,
+    pub entity_type: EntityType,
+    pub name: String,
+    pub note_count: i64,
+}
+
+impl fmt::Debug for EntityFacet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EntityFacet")
+            .field("entity_id_set", &true)
+            .field("entity_type", &self.entity_type)
+            .field("name_len", &debug_len(&self.name))
+            .field("note_count", &self.note_count)
+            .finish()
+    }
+}
+
 /// Graph embedding for a note (aggregated entity representation).
 #[derive(Clone)]
 pub struct NoteGraphEmbedding {
@@ -3160,6 +3622,187 @@ impl fmt::Debug for NoteGraphEmbedding {
     }
 }
 
+// =============================================================================
+// TOPIC TYPES
+// =============================================================================
+
+/// A cluster of related notes found by the topic modeling job.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Topic {
+    pub id: Uuid,
+    /// Name the model gave the cluster
+    pub label: String,
+    pub note_count: i32,
+    /// Candidate SKOS concept suggested for the label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concept_id: Option<Uuid>,
+    /// Model that named the topic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub created_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Topic")
+            .field("id_set", &true)
+            .field("label_len", &debug_len(&self.label))
+            .field("note_count", &self.note_count)
+            .field("concept_id_set", &self.concept_id.is_some())
+            .field("model_len", &optional_debug_len(self.model.as_ref()))
+            .field("created_at_utc", &self.created_at_utc)
+            .finish()
+    }
+}
+
+/// A note in a topic.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TopicMember {
+    pub note_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Cosine similarity between the note and the topic centroid
+    pub similarity: f32,
+}
+
+impl fmt::Debug for TopicMember {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TopicMember")
+            .field("note_id_set", &true)
+            .field("title_len", &optional_debug_len(self.title.as_ref()))
+            .field("similarity", &self.similarity)
+            .finish()
+    }
+}
+
+/// A topic with its member notes, most central first.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TopicDetail {
+    #[serde(flatten)]
+    pub topic: Topic,
+    pub members: Vec<TopicMember>,
+}
+
+/// Request body for `POST /api/v1/topics/refresh`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct RefreshTopicsRequest {
+    /// Number of topics to find (2-50). Defaults to about `sqrt(notes / 2)`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub k: Option<usize>,
+}
+
+impl RefreshTopicsRequest {
+    /// Reject a topic count outside `2..=TOPIC_MAX_COUNT`.
+    pub fn validate(&self) -> crate::Result<()> {
+        match self.k {
+            Some(k) if !(2..=crate::defaults::TOPIC_MAX_COUNT).contains(&k) => {
+                Err(crate::Error::InvalidInput(format!(
+                    "k must be between 2 and {}",
+                    crate::defaults::TOPIC_MAX_COUNT
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A topic produced by a topic modeling run, ready to store.
+#[derive(Clone)]
+pub struct NewTopic {
+    pub label: String,
+    pub concept_id: Option<Uuid>,
+    pub model: Option<String>,
+    /// `(note_id, similarity)` pairs
+    pub members: Vec<(Uuid, f32)>,
+}
+
+impl fmt::Debug for NewTopic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NewTopic")
+            .field("label_len", &debug_len(&self.label))
+            .field("concept_id_set", &self.concept_id.is_some())
+            .field("model_len", &optional_debug_len(self.model.as_ref()))
+            .field("member_count", &self.members.len())
+            .finish()
+    }
+}
+
+/// A note near a topic's centroid, shown to the model when naming the topic.
+#[derive(Clone)]
+pub struct TopicNoteSample {
+    pub note_id: Uuid,
+    pub title: Option<String>,
+    /// Opening characters of the note content
+    pub excerpt: String,
+}
+
+impl fmt::Debug for TopicNoteSample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TopicNoteSample")
+            .field("note_id_set", &true)
+            .field("title_len", &optional_debug_len(self.title.as_ref()))
+            .field("excerpt_len", &debug_len(&self.excerpt))
+            .finish()
+    }
+}
+
+/// The `notes` value of the topic naming prompt: one line per sample with
+/// its title and a whitespace-collapsed excerpt.
+pub fn topic_prompt_notes(samples: &[TopicNoteSample]) -> String {
+    samples
+        .iter()
+        .map(|sample| {
+            let title = sample
+                .title
+                .as_deref()
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .unwrap_or("Untitled");
+            let excerpt = sample
+                .excerpt
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            if excerpt.is_empty() {
+                format!("- {title}")
+            } else {
+                format!("- {title}: {excerpt}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Clean a model reply into a topic label.
+///
+/// Takes the first non-empty line, drops list markers, a `Topic:` or `Label:`
+/// prefix, surrounding quotes and Markdown emphasis, and caps the length.
+/// Returns `None` when nothing usable remains.
+pub fn parse_topic_label(output: &str) -> Option<String> {
+    let line = output.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = line.trim_start_matches(['#', '-', '*', ' ']);
+    let lower = line.to_lowercase();
+    let line = ["topic:", "label:", "name:"]
+        .iter()
+        .find(|prefix| lower.starts_with(*prefix))
+        .map_or(line, |prefix| &line[prefix.len()..]);
+    let label = line
+        .trim()
+        .trim_matches(|c: char| matches!(c, '"' | '\'' | '`' | '*' | '_' | '.'))
+        .trim();
+    if label.is_empty() {
+        return None;
+    }
+    Some(
+        label
+            .chars()
+            .take(crate::defaults::TOPIC_LABEL_MAX_CHARS)
+            .collect::<String>()
+            .trim_end()
+            .to_string(),
+    )
+}
+
 // =============================================================================
 // FINE-TUNING TYPES
 // =============================================================================
@@ -3276,13 +3919,16 @@ impl fmt::Debug for FineTuningDataset {
     }
 }
 
-/// A query-document sample for fine-tuning.
+/// A question-answer sample for fine-tuning, grounded in one note.
 #[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct FineTuningSample {
     pub id: Uuid,
     pub dataset_id: Uuid,
     pub note_id: Uuid,
     pub query: String,
+    /// Answer to `query` drawn from the note; unset for query-only samples
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub query_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -3298,6 +3944,7 @@ impl fmt::Debug for FineTuningSample {
             .field("dataset_id_set", &true)
             .field("note_id_set", &true)
             .field("query_len", &debug_len(&self.query))
+            .field("answer_len", &optional_debug_len(self.answer.as_ref()))
             .field(
                 "query_type_len",
                 &optional_debug_len(self.query_type.as_ref()),
@@ -3338,6 +3985,33 @@ impl fmt::Debug for CreateFineTuningDatasetRequest {
     }
 }
 
+/// Request to update a fine-tuning dataset; unset fields are unchanged.
+///
+/// A changed config takes effect on the next generation run.
+#[derive(Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct UpdateFineTuningDatasetRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// New description; an empty string clears it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<FineTuningConfig>,
+}
+
+impl fmt::Debug for UpdateFineTuningDatasetRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpdateFineTuningDatasetRequest")
+            .field("name_len", &optional_debug_len(self.name.as_ref()))
+            .field(
+                "description_len",
+                &optional_debug_len(self.description.as_ref()),
+            )
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
 // =============================================================================
 // COARSE EMBEDDING TYPES (TWO-STAGE RETRIEVAL)
 // =============================================================================
@@ -3640,11 +4314,13 @@ impl std::str::FromStr for JobFailureClass {
     }
 }
 
-/// Persistence result when a retry is scheduled or its finite cap is exhausted.
+/// Persistence result when a retry is scheduled, its finite cap is exhausted,
+/// or its payload is quarantined as poison.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum JobRetryOutcome {
     Scheduled { next_attempt_at: DateTime<Utc> },
     Exhausted,
+    Quarantined,
 }
 
 /// Bounded retry timing shared by workers and stale-job recovery.
@@ -3815,11 +4491,47 @@ pub enum JobType {
     AudioTranscription,
     /// Transcribe a single audio chunk via Whisper (atomic, parallelizable) (#543)
     AudioChunkTranscription,
+    /// Reclaim unreferenced attachment blobs and their stored files
+    BlobGarbageCollection,
+    /// Re-encrypt PKE-sealed notes and attachment blobs for a rotated keyset
+    PkeKeyRotation,
+    /// Run a scheduled backup policy and prune backups outside its retention
+    ScheduledBackup,
+    /// Replicate a memory archive with a sync peer
+    FederationSync,
+    /// Prune note version history outside each archive's retention policy
+    VersionPrune,
+    /// Summarize a note, map-reducing over chunks for long documents
+    Summarization,
+    /// Summarize an archive's recent activity for a scheduled digest
+    DigestGeneration,
+    /// Cluster an archive's notes into named topics
+    TopicModeling,
+    /// Embed an image attachment with CLIP for image similarity search
+    ImageEmbedding,
+    /// Translate a note into its archive's target language
+    Translation,
+    /// Scan a note and its attachments for PII and apply the redaction mode
+    PiiScan,
+    /// Merge one memory archive into another, optionally dropping the source
+    ArchiveMerge,
+    /// Purge trashed notes, by request or once past the trash retention
+    TrashPurge,
+    /// Check an archive's taxonomy for anti-patterns and store a health report
+    TaxonomyHealth,
+    /// Sync smart collections with their rules
+    CollectionRuleSync,
+    /// Detect DOIs, arXiv IDs, URLs and BibTeX entries cited by a note
+    CitationExtraction,
+    /// Record a note's checkbox tasks and, optionally, LLM-detected action items
+    TaskExtraction,
+    /// Apply one patch to every note matching a filter, in batches
+    BulkNoteUpdate,
 }
 
 impl JobType {
     /// Every job type understood and executable by this binary.
-    pub const ALL: [Self; 37] = [
+    pub const ALL: [Self; 55] = [
         Self::AiRevision,
         Self::AiRevisionContextual,
         Self::Embedding,
@@ -3857,6 +4569,24 @@ impl JobType {
         Self::ViewAssembly,
         Self::AudioTranscription,
         Self::AudioChunkTranscription,
+        Self::BlobGarbageCollection,
+        Self::PkeKeyRotation,
+        Self::ScheduledBackup,
+        Self::FederationSync,
+        Self::VersionPrune,
+        Self::Summarization,
+        Self::DigestGeneration,
+        Self::TopicModeling,
+        Self::ImageEmbedding,
+        Self::Translation,
+        Self::PiiScan,
+        Self::ArchiveMerge,
+        Self::TrashPurge,
+        Self::TaxonomyHealth,
+        Self::CollectionRuleSync,
+        Self::CitationExtraction,
+        Self::TaskExtraction,
+        Self::BulkNoteUpdate,
     ];
 
     /// Stable database and external-envelope representation.
@@ -3899,6 +4629,24 @@ impl JobType {
             Self::ViewAssembly => "view_assembly",
             Self::AudioTranscription => "audio_transcription",
             Self::AudioChunkTranscription => "audio_chunk_transcription",
+            Self::BlobGarbageCollection => "blob_garbage_collection",
+            Self::PkeKeyRotation => "pke_key_rotation",
+            Self::ScheduledBackup => "scheduled_backup",
+            Self::FederationSync => "federation_sync",
+            Self::VersionPrune => "version_prune",
+            Self::Summarization => "summarization",
+            Self::DigestGeneration => "digest_generation",
+            Self::TopicModeling => "topic_modeling",
+            Self::ImageEmbedding => "image_embedding",
+            Self::Translation => "translation",
+            Self::PiiScan => "pii_scan",
+            Self::ArchiveMerge => "archive_merge",
+            Self::TrashPurge => "trash_purge",
+            Self::TaxonomyHealth => "taxonomy_health",
+            Self::CollectionRuleSync => "collection_rule_sync",
+            Self::CitationExtraction => "citation_extraction",
+            Self::TaskExtraction => "task_extraction",
+            Self::BulkNoteUpdate => "bulk_note_update",
         }
     }
 
@@ -3969,6 +4717,44 @@ impl JobType {
             // Audio transcription is medium-high priority (gates assembly + diarization) (#542)
             JobType::AudioTranscription => 6,
             JobType::AudioChunkTranscription => 6,
+            // Blob GC is storage housekeeping, lowest urgency
+            JobType::BlobGarbageCollection => 1,
+            // Key rotation is maintenance but holds key material in memory
+            // until it runs, so it should not sit behind routine housekeeping
+            JobType::PkeKeyRotation => 3,
+            // Scheduled backups should run close to their schedule, ahead of
+            // routine housekeeping
+            JobType::ScheduledBackup => 3,
+            // Sync runs are scheduled maintenance like backups
+            JobType::FederationSync => 3,
+            // Version pruning is storage housekeeping like blob GC
+            JobType::VersionPrune => 1,
+            // Summaries are a reading aid, queued alongside title generation
+            JobType::Summarization => 2,
+            // Digests are scheduled reading aids like note summaries
+            JobType::DigestGeneration => 2,
+            // Topic modeling re-clusters the whole archive; nothing waits on it
+            JobType::TopicModeling => 1,
+            // Image embeddings feed image search only, after extraction
+            JobType::ImageEmbedding => 3,
+            // Translations are a reading and search aid like summaries
+            JobType::Translation => 2,
+            // PII scans gate masking and indexing, so run ahead of embedding
+            JobType::PiiScan => 6,
+            // Merges are user-requested bulk copies, ahead of housekeeping
+            JobType::ArchiveMerge => 3,
+            // Trashed notes are already hidden; purging them is housekeeping
+            JobType::TrashPurge => 1,
+            // Health reports are advisory governance housekeeping
+            JobType::TaxonomyHealth => 1,
+            // Rule membership follows note changes without user waiting on it
+            JobType::CollectionRuleSync => 1,
+            // Citations feed the bibliography only; nothing downstream waits
+            JobType::CitationExtraction => 2,
+            // Task lists are a reading aid; nothing downstream waits on them
+            JobType::TaskExtraction => 2,
+            // Bulk edits are user-requested, ahead of housekeeping
+            JobType::BulkNoteUpdate => 3,
         }
     }
 
@@ -3980,11 +4766,18 @@ impl JobType {
         match self {
             // CPU/NER tier: GLiNER concept extraction and reference NER
             JobType::ConceptTagging | JobType::ReferenceExtraction => Some(cost_tier::CPU_NER),
-            // Fast GPU tier: title gen, metadata extraction
-            JobType::TitleGeneration | JobType::MetadataExtraction => Some(cost_tier::FAST_GPU),
-            // Standard GPU tier: AI revision uses the standard generation model.
-            // Serialized by gpu_concurrent (default 1) to avoid VRAM contention.
-            JobType::AiRevision | JobType::AiRevisionContextual => Some(cost_tier::STANDARD_GPU),
+            // Fast GPU tier: title gen, metadata extraction, summaries, digests, LLM NER
+            JobType::TitleGeneration
+            | JobType::MetadataExtraction
+            | JobType::Summarization
+            | JobType::DigestGeneration
+            | JobType::EntityExtraction => Some(cost_tier::FAST_GPU),
+            // Standard GPU tier: AI revision and translation use the standard
+            // generation model. Serialized by gpu_concurrent (default 1) to
+            // avoid VRAM contention.
+            JobType::AiRevision | JobType::AiRevisionContextual | JobType::Translation => {
+                Some(cost_tier::STANDARD_GPU)
+            }
             // Vision GPU tier: per-frame/per-view vision LLM description.
             // Serialized by default (VISION_MAX_CONCURRENT=1) to prevent
             // VRAM contention on single-GPU systems.
@@ -4236,7 +5029,7 @@ impl fmt::Debug for ExtractionStats {
 // =============================================================================
 
 /// A collection of notes (folder/hierarchy).
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct Collection {
     pub id: Uuid,
     pub name: String,
@@ -5092,6 +5885,80 @@ impl fmt::Debug for AuthorizationServerMetadata {
     }
 }
 
+/// Resources an API key is confined to, on top of its scope.
+///
+/// `None` leaves that dimension unrestricted. A key restricted to
+/// collections can only reach notes filed in them and those collections.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ApiKeyRestrictions {
+    /// Memory archives the key may select
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archives: Option<Vec<String>>,
+    /// Collections whose notes the key may access
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collections: Option<Vec<Uuid>>,
+    /// Reject every request that needs more than read access
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl fmt::Debug for ApiKeyRestrictions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKeyRestrictions")
+            .field("archives_count", &self.archives.as_ref().map(Vec::len))
+            .field(
+                "collections_count",
+                &self.collections.as_ref().map(Vec::len),
+            )
+            .field("read_only", &self.read_only)
+            .finish()
+    }
+}
+
+impl ApiKeyRestrictions {
+    /// Whether the key can reach everything its scope allows.
+    pub fn is_unrestricted(&self) -> bool {
+        self.archives.is_none() && self.collections.is_none() && !self.read_only
+    }
+
+    /// Whether the key may use the archive named `name`.
+    pub fn allows_archive(&self, name: &str) -> bool {
+        self.archives
+            .as_ref()
+            .is_none_or(|archives| archives.iter().any(|a| a == name))
+    }
+
+    /// Whether the key may access notes in `collection_id`; notes outside
+    /// any collection are only reachable by keys without a collection list.
+    pub fn allows_collection(&self, collection_id: Option<Uuid>) -> bool {
+        match (&self.collections, collection_id) {
+            (None, _) => true,
+            (Some(collections), Some(id)) => collections.contains(&id),
+            (Some(_), None) => false,
+        }
+    }
+
+    /// Reject empty lists, which would lock the key out entirely.
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.archives.as_ref().is_some_and(|a| a.is_empty()) {
+            return Err(crate::Error::InvalidInput(
+                "restrictions.archives must name at least one memory".to_string(),
+            ));
+        }
+        if self.archives.iter().flatten().any(|a| a.trim().is_empty()) {
+            return Err(crate::Error::InvalidInput(
+                "restrictions.archives cannot contain empty names".to_string(),
+            ));
+        }
+        if self.collections.as_ref().is_some_and(|c| c.is_empty()) {
+            return Err(crate::Error::InvalidInput(
+                "restrictions.collections must name at least one collection".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// API key for simpler authentication.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ApiKey {
@@ -5100,6 +5967,8 @@ pub struct ApiKey {
     pub name: String,
     pub description: Option<String>,
     pub scope: String,
+    #[serde(default)]
+    pub restrictions: ApiKeyRestrictions,
     pub rate_limit_per_minute: Option<i32>,
     pub rate_limit_per_hour: Option<i32>,
     pub last_used_at: Option<DateTime<Utc>>,
@@ -5120,6 +5989,7 @@ impl fmt::Debug for ApiKey {
                 &optional_debug_len(self.description.as_ref()),
             )
             .field("scope_len", &self.scope.chars().count())
+            .field("restrictions", &self.restrictions)
             .field("rate_limit_per_minute", &self.rate_limit_per_minute)
             .field("rate_limit_per_hour", &self.rate_limit_per_hour)
             .field("last_used_at_set", &self.last_used_at.is_some())
@@ -5139,6 +6009,9 @@ pub struct CreateApiKeyRequest {
     #[serde(default = "default_scope")]
     pub scope: String,
     pub expires_in_days: Option<i32>,
+    /// Confine the key to specific memories, collections, or read access
+    #[serde(default)]
+    pub restrictions: ApiKeyRestrictions,
 }
 
 impl fmt::Debug for CreateApiKeyRequest {
@@ -5151,6 +6024,7 @@ impl fmt::Debug for CreateApiKeyRequest {
             )
             .field("scope_len", &self.scope.chars().count())
             .field("expires_in_days", &self.expires_in_days)
+            .field("restrictions", &self.restrictions)
             .finish()
     }
 }
@@ -5167,6 +6041,7 @@ pub struct CreateApiKeyResponse {
     pub key_prefix: String,
     pub name: String,
     pub scope: String,
+    pub restrictions: ApiKeyRestrictions,
     pub expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
@@ -5180,6 +6055,7 @@ impl std::fmt::Debug for CreateApiKeyResponse {
             .field("key_prefix_len", &self.key_prefix.chars().count())
             .field("name_len", &self.name.chars().count())
             .field("scope_len", &self.scope.chars().count())
+            .field("restrictions", &self.restrictions)
             .field("expires_at", &self.expires_at)
             .field("created_at", &self.created_at)
             .finish()
@@ -5294,10 +6170,20 @@ pub struct NoteTemplate {
     pub default_tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub collection_id: Option<Uuid>,
+    /// Typed variables the content's placeholders take
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<crate::note_template::TemplateVariable>,
+    /// Version number, starting at 1 and raised by every update
+    #[serde(default = "first_template_version")]
+    pub version: i32,
     pub created_at_utc: DateTime<Utc>,
     pub updated_at_utc: DateTime<Utc>,
 }
 
+fn first_template_version() -> i32 {
+    1
+}
+
 impl fmt::Debug for NoteTemplate {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("NoteTemplate")
@@ -5319,6 +6205,8 @@ impl fmt::Debug for NoteTemplate {
                     .collect::<Vec<_>>(),
             )
             .field("collection_id_set", &self.collection_id.is_some())
+            .field("variable_count", &self.variables.len())
+            .field("version", &self.version)
             .field("created_at_utc", &self.created_at_utc)
             .field("updated_at_utc", &self.updated_at_utc)
             .finish()
@@ -5741,6 +6629,8 @@ mod tests {
             updated_at_utc: now,
             starred: true,
             archived: false,
+            pinned: false,
+            priority: 0,
             last_accessed_at: Some(now),
             access_count: 7,
             title: Some("éé".to_string()),
@@ -5753,6 +6643,8 @@ mod tests {
                 "snippet": "Patient said 555-1212"
             })),
             document_type_id: Some(Uuid::new_v4()),
+            encrypted: false,
+            language: Some("de".to_string()),
         };
         let original = NoteOriginal {
             content: "Original note content private@example.test sk-live-secret".to_string(),
@@ -5780,6 +6672,22 @@ mod tests {
             tags: vec!["secret-tag-private@example.test".to_string()],
             concepts: Vec::new(),
             links: Vec::new(),
+            summary: Some(NoteGeneratedSummary {
+                note_id: meta.id,
+                summary: "Patient said the summary is private".to_string(),
+                model: Some("private-model-name".to_string()),
+                chunk_count: 2,
+                generated_at_utc: now,
+            }),
+            translation: Some(NoteTranslation {
+                note_id: meta.id,
+                source_language: "de".to_string(),
+                target_language: "en".to_string(),
+                content: "Translated private@example.test text".to_string(),
+                model: Some("private-model-name".to_string()),
+                chunk_count: 1,
+                generated_at_utc: now,
+            }),
         };
 
         let debug = format!("{meta:?}{original:?}{revised:?}{full:?}");
@@ -5804,6 +6712,7 @@ mod tests {
                 "private-model-name",
                 "generated private@example.test",
                 "secret-tag-private@example.test",
+                "Translated private",
             ],
         );
 
@@ -5821,6 +6730,8 @@ mod tests {
             "tags_count",
             "concepts_count",
             "links_count",
+            "language: Some(\"de\")",
+            "target_language: \"en\"",
         ] {
             assert!(
                 debug.contains(expected),
@@ -6059,6 +6970,7 @@ mod tests {
             virus_scan_signature_version: Some("éé".to_string()),
             virus_scan_reason_code: Some("éé".to_string()),
             virus_scan_blob_hash: Some("éé".to_string()),
+            encrypted: true,
             created_at: now,
             updated_at: now,
         };
@@ -6076,6 +6988,7 @@ mod tests {
             is_canonical_content: false,
             virus_scan_status: AttachmentScanStatus::Pending,
             virus_scan_at: None,
+            encrypted: false,
             created_at: now,
         };
         let global = GlobalAttachmentSummary {
@@ -6931,6 +7844,7 @@ mod tests {
             dataset_id: Uuid::new_v4(),
             note_id: Uuid::new_v4(),
             query: "éé".to_string(),
+            answer: Some("éé".to_string()),
             query_type: Some("éé".to_string()),
             quality_score: Some(4.2),
             is_validation: true,
@@ -7338,6 +8252,11 @@ mod tests {
             updated_at: now,
             created_by: Some("éé".to_string()),
             agentic_config: agentic_config.clone(),
+            pipeline_policy: PipelinePolicy {
+                default_tags: vec!["private/policy-tag".to_string()],
+                ..Default::default()
+            },
+            metadata_schema: Some(json!({"properties": {"private-schema-key": {}}})),
         };
         let summary = DocumentTypeSummary {
             id: Uuid::new_v4(),
@@ -7377,6 +8296,8 @@ mod tests {
             })),
             requires_attachment: true,
             attachment_generates_content: false,
+            pipeline_policy: None,
+            metadata_schema: Some(json!({"required": ["create-private-key"]})),
         };
         let update = UpdateDocumentTypeRequest {
             display_name: Some("éé".to_string()),
@@ -7402,6 +8323,8 @@ mod tests {
             })),
             requires_attachment: Some(true),
             attachment_generates_content: Some(true),
+            pipeline_policy: None,
+            metadata_schema: Some(json!({"required": ["update-private-key"]})),
         };
         let detection = DetectDocumentTypeResult {
             document_type: summary.clone(),
@@ -7437,6 +8360,7 @@ mod tests {
                 "validator.example.test",
                 "/tmp/customer/validation.json",
                 "private_agent_hint",
+                "private/policy-tag",
                 "summary-private-type",
                 "Summary Private Type",
                 "summary.example.test",
@@ -7456,6 +8380,9 @@ mod tests {
                 "update-private-content",
                 "private-audio-model",
                 "recordings.example.test",
+                "private-schema-key",
+                "create-private-key",
+                "update-private-key",
                 "filename private-document",
             ],
         );
@@ -7484,6 +8411,7 @@ mod tests {
             "filename_patterns_count",
             "chunking_config_class",
             "chunking_config_len",
+            "metadata_schema_len",
             "extraction_config_class",
             "extraction_config_len",
             "generation_prompt_len",
@@ -7891,6 +8819,7 @@ mod tests {
             key_prefix: "mm_key_super".to_string(),
             name: "Production key".to_string(),
             scope: "admin".to_string(),
+            restrictions: ApiKeyRestrictions::default(),
             expires_at: Some(Utc::now()),
             created_at: Utc::now(),
         };
@@ -7959,6 +8888,7 @@ mod tests {
             name: "Private API key private@example.test".to_string(),
             description: Some("Key description includes /tmp/customer/key.txt".to_string()),
             scope: "admin private.scope.sk-live-secret".to_string(),
+            restrictions: ApiKeyRestrictions::default(),
             rate_limit_per_minute: Some(60),
             rate_limit_per_hour: Some(600),
             last_used_at: Some(now),
@@ -7971,6 +8901,7 @@ mod tests {
             name: "Create private API key".to_string(),
             description: Some("Create key for private@example.test".to_string()),
             scope: "éé".to_string(),
+            restrictions: ApiKeyRestrictions::default(),
             expires_in_days: Some(30),
         };
 
@@ -8250,6 +9181,7 @@ mod tests {
             url: webhook_url.to_string(),
             secret: Some("outbound-webhook-signing-secret".to_string()),
             events: vec!["note.created.secret".to_string()],
+            filters: vec![r#".payload.tags contains "filter-secret""#.to_string()],
             is_active: true,
             created_at: now,
             updated_at: now,
@@ -8271,6 +9203,7 @@ mod tests {
             url: create_url.to_string(),
             secret: Some("create-webhook-secret".to_string()),
             events: vec!["note.updated.secret".to_string()],
+            filters: vec![r#".payload.tags contains "filter-secret""#.to_string()],
             max_retries: 5,
         };
         let incoming = CreateIncomingWebhookReceiverRequest {
@@ -8531,12 +9464,16 @@ mod tests {
             updated_at_utc: Utc::now(),
             starred: false,
             archived: false,
+            pinned: false,
+            priority: 0,
             last_accessed_at: None,
             access_count: 0,
             title: None,
             metadata: json!({}),
             chunk_metadata: None,
             document_type_id: None,
+            encrypted: false,
+            language: None,
         };
 
         let serialized = serde_json::to_string(&note).unwrap();
@@ -8561,12 +9498,16 @@ mod tests {
             updated_at_utc: Utc::now(),
             starred: false,
             archived: false,
+            pinned: false,
+            priority: 0,
             last_accessed_at: None,
             access_count: 0,
             title: None,
             metadata: json!({}),
             chunk_metadata: Some(chunk_meta.clone()),
             document_type_id: None,
+            encrypted: false,
+            language: None,
         };
 
         let serialized = serde_json::to_string(&note).unwrap();
@@ -8585,12 +9526,16 @@ mod tests {
             updated_at_utc: Utc::now(),
             starred: false,
             archived: false,
+            pinned: false,
+            priority: 0,
             last_accessed_at: None,
             access_count: 0,
             title: None,
             metadata: json!({}),
             chunk_metadata: None,
             document_type_id: None,
+            encrypted: false,
+            language: None,
         };
 
         let json_value = serde_json::to_value(&note).unwrap();
@@ -9112,6 +10057,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn blob_store_stats_exclude_orphans_from_savings() {
+        // Two 100-byte blobs: one shared by three attachments, one orphaned.
+        let stats = BlobStoreStats::from_totals(2, 3, 200, 300, 1, 100);
+        assert_eq!(stats.deduplicated_bytes, 200);
+        assert!((stats.dedup_ratio - 3.0).abs() < f64::EPSILON);
+
+        let empty = BlobStoreStats::from_totals(0, 0, 0, 0, 0, 0);
+        assert_eq!(empty.deduplicated_bytes, 0);
+        assert!((empty.dedup_ratio - 1.0).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn test_oauth_error_constructors() {
         let err = OAuthError::invalid_request("bad param");
@@ -9312,6 +10269,8 @@ mod tests {
                 "token/sk-secret-template".to_string(),
             ],
             collection_id: Some(collection_id),
+            variables: Vec::new(),
+            version: 1,
             created_at_utc: Utc::now(),
             updated_at_utc: Utc::now(),
         };
@@ -9886,6 +10845,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pipeline_policy_serde() {
+        let policy: PipelinePolicy = serde_json::from_value(json!({
+            "extraction_strategy": "pdf_ocr",
+            "chunking_strategy": "per_section",
+            "ai_revision": false,
+            "default_tags": ["contracts"],
+            "pipeline": ["concept_tagging"]
+        }))
+        .unwrap();
+        assert_eq!(policy.extraction_strategy, Some(ExtractionStrategy::PdfOcr));
+        assert_eq!(policy.chunking_strategy, Some(ChunkingStrategy::PerSection));
+        assert_eq!(policy.ai_revision, Some(false));
+        assert_eq!(policy.embedding_set_id, None);
+        assert!(!policy.is_empty());
+
+        // Unset fields are omitted, so an empty policy serializes to `{}`
+        let empty: PipelinePolicy = serde_json::from_value(json!({})).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(serde_json::to_value(&empty).unwrap(), json!({}));
+    }
+
     #[test]
     fn test_mime_pdf() {
         assert_eq!(
@@ -10026,6 +11007,9 @@ mod tests {
             "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
             "application/vnd.ms-excel",
             "application/vnd.oasis.opendocument.spreadsheet",
+            "text/csv",
+            "application/csv",
+            "text/tab-separated-values",
         ] {
             assert_eq!(
                 ExtractionStrategy::from_mime_type(mime),
@@ -10054,6 +11038,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mime_notebook() {
+        assert_eq!(
+            ExtractionStrategy::from_mime_type("application/x-ipynb+json"),
+            ExtractionStrategy::Notebook
+        );
+        assert_eq!(
+            ExtractionStrategy::from_mime_and_extension("application/octet-stream", Some("ipynb")),
+            ExtractionStrategy::Notebook
+        );
+        assert_eq!(
+            "jupyter".parse::<ExtractionStrategy>(),
+            Ok(ExtractionStrategy::Notebook)
+        );
+        assert_eq!(ExtractionStrategy::Notebook.to_string(), "notebook");
+    }
+
+    #[test]
+    fn test_mime_chat_export() {
+        for mime in [crate::SLACK_EXPORT_MIME, crate::WHATSAPP_CHAT_MIME] {
+            assert_eq!(
+                ExtractionStrategy::from_mime_type(mime),
+                ExtractionStrategy::ChatExport,
+                "Failed for {}",
+                mime
+            );
+        }
+        assert_eq!(
+            "whatsapp".parse::<ExtractionStrategy>(),
+            Ok(ExtractionStrategy::ChatExport)
+        );
+        assert_eq!(ExtractionStrategy::ChatExport.to_string(), "chat_export");
+    }
+
+    #[test]
+    fn test_mime_ebook() {
+        for mime in [
+            "application/epub+zip",
+            "application/x-mobipocket-ebook",
+            "application/vnd.amazon.ebook",
+        ] {
+            assert_eq!(
+                ExtractionStrategy::from_mime_type(mime),
+                ExtractionStrategy::Ebook,
+                "Failed for {}",
+                mime
+            );
+        }
+        assert_eq!(
+            ExtractionStrategy::from_mime_and_extension("application/zip", Some("epub")),
+            ExtractionStrategy::Ebook
+        );
+        assert_eq!(
+            ExtractionStrategy::from_mime_and_extension("application/zip", Some("zip")),
+            ExtractionStrategy::Archive
+        );
+    }
+
     #[test]
     fn test_mime_structured_data_core() {
         for mime in [
@@ -10062,7 +11104,6 @@ mod tests {
             "text/xml",
             "application/yaml",
             "text/yaml",
-            "text/csv",
             "application/toml",
         ] {
             assert_eq!(
@@ -10182,8 +11223,8 @@ mod tests {
                 ext
             );
         }
-        // Spreadsheet (calamine)
-        for ext in ["xls", "xlsx", "ods"] {
+        // Spreadsheet (calamine, delimited text)
+        for ext in ["xls", "xlsx", "ods", "csv", "tsv"] {
             assert_eq!(
                 ExtractionStrategy::from_mime_and_extension(octet, Some(ext)),
                 ExtractionStrategy::Spreadsheet,
@@ -10209,8 +11250,17 @@ mod tests {
                 ext
             );
         }
+        // Ebook (EPUB / MOBI)
+        for ext in ["epub", "mobi", "azw", "azw3"] {
+            assert_eq!(
+                ExtractionStrategy::from_mime_and_extension(octet, Some(ext)),
+                ExtractionStrategy::Ebook,
+                "Failed for .{}",
+                ext
+            );
+        }
         // Structured data
-        for ext in ["json", "xml", "yaml", "yml", "csv", "toml"] {
+        for ext in ["json", "xml", "yaml", "yml", "toml"] {
             assert_eq!(
                 ExtractionStrategy::from_mime_and_extension(octet, Some(ext)),
                 ExtractionStrategy::StructuredExtract,
@@ -10406,7 +11456,7 @@ mod tests {
             ("text/xml", ExtractionStrategy::StructuredExtract),
             ("application/yaml", ExtractionStrategy::StructuredExtract),
             ("text/yaml", ExtractionStrategy::StructuredExtract),
-            ("text/csv", ExtractionStrategy::StructuredExtract),
+            ("text/csv", ExtractionStrategy::Spreadsheet),
             ("application/toml", ExtractionStrategy::StructuredExtract),
             // Structured data (extended)
             (
@@ -10463,6 +11513,86 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn normalize_entity_name_merges_spelling_variants() {
+        assert_eq!(normalize_entity_name("  Ada   Lovelace, "), "ada lovelace");
+        assert_eq!(normalize_entity_name("\"ACME Corp.\""), "acme corp");
+        assert_eq!(normalize_entity_name("St. Louis"), "st. louis");
+        assert_eq!(normalize_entity_name("..."), "");
+    }
+
+    #[test]
+    fn parse_topic_label_cleans_model_replies() {
+        assert_eq!(
+            parse_topic_label("\n\"Machine Learning\"\n").as_deref(),
+            Some("Machine Learning")
+        );
+        assert_eq!(
+            parse_topic_label("**Topic:** Home renovation.").as_deref(),
+            Some("Home renovation")
+        );
+        assert_eq!(
+            parse_topic_label("- Garden planning\n- Other").as_deref(),
+            Some("Garden planning")
+        );
+        assert_eq!(parse_topic_label("  \n \"\" "), None);
+        let long = "x".repeat(200);
+        assert_eq!(
+            parse_topic_label(&long).map(|l| l.chars().count()),
+            Some(crate::defaults::TOPIC_LABEL_MAX_CHARS)
+        );
+    }
+
+    #[test]
+    fn topic_prompt_notes_lists_titles_and_excerpts() {
+        let samples = [
+            TopicNoteSample {
+                note_id: Uuid::nil(),
+                title: Some("Tile grout".to_string()),
+                excerpt: "Mix the\n  grout  thin.".to_string(),
+            },
+            TopicNoteSample {
+                note_id: Uuid::nil(),
+                title: None,
+                excerpt: String::new(),
+            },
+        ];
+        assert_eq!(
+            topic_prompt_notes(&samples),
+            "- Tile grout: Mix the grout thin.\n- Untitled"
+        );
+    }
+
+    #[test]
+    fn refresh_topics_request_bounds_k() {
+        assert!(RefreshTopicsRequest::default().validate().is_ok());
+        assert!(RefreshTopicsRequest { k: Some(2) }.validate().is_ok());
+        assert!(RefreshTopicsRequest { k: Some(1) }.validate().is_err());
+        assert!(RefreshTopicsRequest {
+            k: Some(crate::defaults::TOPIC_MAX_COUNT + 1)
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn named_entity_debug_redacts_name() {
+        let entity = NamedEntity {
+            id: Uuid::nil(),
+            entity_type: EntityType::Person,
+            name: "Secret Person".to_string(),
+            note_count: 2,
+            created_at_utc: Utc::now(),
+        };
+        let facet = EntityFacet {
+            entity_id: Uuid::nil(),
+            entity_type: EntityType::Person,
+            name: "Secret Person".to_string(),
+            note_count: 1,
+        };
+        assert_debug_excludes(&format!("{entity:?} {facet:?}"), &["Secret Person"]);
+    }
 }
 
 /// Extracted temporal and spatial provenance from file metadata (EXIF, etc.)
@@ -11309,6 +12439,9 @@ pub struct Webhook {
     #[serde(skip_serializing)]
     pub secret: Option<String>,
     pub events: Vec<String>,
+    /// Payload predicates that must all hold for delivery; see
+    /// [`WebhookFilter`](crate::WebhookFilter).
+    pub filters: Vec<String>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -11324,6 +12457,7 @@ impl std::fmt::Debug for Webhook {
             .field("url_len", &debug_len(&self.url))
             .field("secret_set", &self.secret.is_some())
             .field("event_count", &self.events.len())
+            .field("filter_count", &self.filters.len())
             .field("is_active", &self.is_active)
             .field("created_at", &self.created_at)
             .field("updated_at", &self.updated_at)
@@ -11372,6 +12506,10 @@ pub struct CreateWebhookRequest {
     pub url: String,
     pub secret: Option<String>,
     pub events: Vec<String>,
+    /// Payload predicates, e.g. `.payload.tags contains "project/x"`; the
+    /// webhook is called only when all of them hold.
+    #[serde(default)]
+    pub filters: Vec<String>,
     #[serde(default = "default_max_retries")]
     pub max_retries: i32,
 }
@@ -11382,6 +12520,7 @@ impl std::fmt::Debug for CreateWebhookRequest {
             .field("url_len", &debug_len(&self.url))
             .field("secret_set", &self.secret.is_some())
             .field("event_count", &self.events.len())
+            .field("filter_count", &self.filters.len())
             .field("max_retries", &self.max_retries)
             .finish()
     }
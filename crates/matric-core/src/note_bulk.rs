@@ -0,0 +1,401 @@
+//! Bulk note updates.
+//!
+//! `PATCH /api/v1/notes/bulk` applies one [`BulkNotePatch`] to every note
+//! matched by a [`BulkNoteFilter`]. The update runs as a `bulk_note_update`
+//! job that works through the matching notes in batches and reports its
+//! progress; a dry run only counts them.
+//!
+//! Every change is idempotent (adding or removing tags, moving to a
+//! collection, merging metadata keys, setting the archived flag), so a job
+//! retried after a partial run re-applies the patch safely.
+//!
+//! ```
+//! use matric_core::BulkNoteUpdateRequest;
+//!
+//! let req: BulkNoteUpdateRequest = serde_json::from_str(
+//!     r#"{
+//!         "filter": {"tags": ["project/alpha"]},
+//!         "patch": {"add_tags": ["archive/2026"], "archived": true},
+//!         "dry_run": true
+//!     }"#,
+//! )
+//! .unwrap();
+//! assert!(req.validated().is_ok());
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::tag_bulk::validate_tag;
+use crate::{Error, Result};
+
+/// Most tags a bulk filter can require.
+pub const MAX_BULK_NOTE_FILTER_TAGS: usize = 20;
+
+/// Most tags one patch can add or remove, each.
+pub const MAX_BULK_NOTE_PATCH_TAGS: usize = 20;
+
+/// Longest accepted filter query, in characters.
+pub const MAX_BULK_NOTE_QUERY_CHARS: usize = 500;
+
+/// Most metadata keys one patch can set.
+pub const MAX_BULK_NOTE_METADATA_KEYS: usize = 50;
+
+/// Notes updated per transaction by the `bulk_note_update` job.
+pub const BULK_NOTE_UPDATE_BATCH_SIZE: usize = 100;
+
+/// Which notes a bulk update touches; a note must match every criterion set.
+/// Deleted notes never match.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BulkNoteFilter {
+    /// Notes must have every tag; a tag also matches its `/` children
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Notes filed directly in this collection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection_id: Option<Uuid>,
+    /// Notes created at or after this time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_after: Option<DateTime<Utc>>,
+    /// Notes created before this time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_before: Option<DateTime<Utc>>,
+    /// Full-text query (web search syntax) matched against title and content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+}
+
+impl BulkNoteFilter {
+    /// Whether no criterion is set.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.collection_id.is_none()
+            && self.created_after.is_none()
+            && self.created_before.is_none()
+            && self.query.is_none()
+    }
+
+    /// Trims the text criteria and drops the ones left empty.
+    pub fn normalized(mut self) -> Self {
+        self.tags = normalized_tags(self.tags);
+        self.query = self
+            .query
+            .map(|query| query.trim().to_string())
+            .filter(|query| !query.is_empty());
+        self
+    }
+
+    /// Rejects an empty filter, too many tags, an overlong query or an
+    /// empty date range.
+    pub fn validate(&self) -> Result<()> {
+        if self.is_empty() {
+            return Err(Error::InvalidInput(
+                "filter needs at least one of: tags, collection_id, created_after, created_before, query".to_string(),
+            ));
+        }
+        if self.tags.len() > MAX_BULK_NOTE_FILTER_TAGS {
+            return Err(Error::InvalidInput(format!(
+                "filter can require at most {MAX_BULK_NOTE_FILTER_TAGS} tags"
+            )));
+        }
+        if self.tags.iter().any(|tag| tag.trim().is_empty()) {
+            return Err(Error::InvalidInput(
+                "filter tags must not be empty".to_string(),
+            ));
+        }
+        if self
+            .query
+            .as_ref()
+            .is_some_and(|query| query.chars().count() > MAX_BULK_NOTE_QUERY_CHARS)
+        {
+            return Err(Error::InvalidInput(format!(
+                "filter query must be at most {MAX_BULK_NOTE_QUERY_CHARS} characters"
+            )));
+        }
+        if let (Some(after), Some(before)) = (self.created_after, self.created_before) {
+            if after >= before {
+                return Err(Error::InvalidInput(
+                    "created_after must be earlier than created_before".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BulkNoteFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BulkNoteFilter")
+            .field("tag_count", &self.tags.len())
+            .field("collection_id_set", &self.collection_id.is_some())
+            .field("created_after", &self.created_after)
+            .field("created_before", &self.created_before)
+            .field("query_len", &self.query.as_ref().map(String::len))
+            .finish()
+    }
+}
+
+/// Changes applied to every matching note.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BulkNotePatch {
+    /// Tags added to each note
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub add_tags: Vec<String>,
+    /// Tags removed from each note; exact names, children are kept
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remove_tags: Vec<String>,
+    /// Collection each note is moved into
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection_id: Option<Uuid>,
+    /// Keys merged into each note's metadata, checked against the note's
+    /// document type metadata schema
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+    /// Archive (`true`) or unarchive (`false`) each note
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived: Option<bool>,
+}
+
+impl BulkNotePatch {
+    /// Whether the patch changes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.add_tags.is_empty()
+            && self.remove_tags.is_empty()
+            && self.collection_id.is_none()
+            && self.metadata.is_none()
+            && self.archived.is_none()
+    }
+
+    /// Trims tag names and drops the ones left empty.
+    pub fn normalized(mut self) -> Self {
+        self.add_tags = normalized_tags(self.add_tags);
+        self.remove_tags = normalized_tags(self.remove_tags);
+        self
+    }
+
+    /// Rejects an empty patch, invalid or conflicting tags, and metadata
+    /// that is not a non-empty object.
+    pub fn validate(&self) -> Result<()> {
+        if self.is_empty() {
+            return Err(Error::InvalidInput(
+                "patch needs at least one of: add_tags, remove_tags, collection_id, metadata, archived".to_string(),
+            ));
+        }
+        for (field, tags) in [
+            ("add_tags", &self.add_tags),
+            ("remove_tags", &self.remove_tags),
+        ] {
+            if tags.len() > MAX_BULK_NOTE_PATCH_TAGS {
+                return Err(Error::InvalidInput(format!(
+                    "{field} can name at most {MAX_BULK_NOTE_PATCH_TAGS} tags"
+                )));
+            }
+            for tag in tags {
+                validate_tag(field, tag)?;
+            }
+        }
+        if self
+            .add_tags
+            .iter()
+            .any(|tag| self.remove_tags.iter().any(|r| r.eq_ignore_ascii_case(tag)))
+        {
+            return Err(Error::InvalidInput(
+                "a tag cannot be both added and removed".to_string(),
+            ));
+        }
+        if let Some(metadata) = &self.metadata {
+            match metadata.as_object() {
+                Some(keys) if !keys.is_empty() && keys.len() <= MAX_BULK_NOTE_METADATA_KEYS => {}
+                _ => {
+                    return Err(Error::InvalidInput(format!(
+                    "patch metadata must be an object with 1 to {MAX_BULK_NOTE_METADATA_KEYS} keys"
+                )))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BulkNotePatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BulkNotePatch")
+            .field("add_tag_count", &self.add_tags.len())
+            .field("remove_tag_count", &self.remove_tags.len())
+            .field("collection_id_set", &self.collection_id.is_some())
+            .field(
+                "metadata_key_count",
+                &self
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.as_object())
+                    .map(|m| m.len()),
+            )
+            .field("archived", &self.archived)
+            .finish()
+    }
+}
+
+/// Request body of `PATCH /api/v1/notes/bulk`.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BulkNoteUpdateRequest {
+    pub filter: BulkNoteFilter,
+    pub patch: BulkNotePatch,
+    /// Count the matching notes without changing them
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl BulkNoteUpdateRequest {
+    /// Normalizes and validates the filter and patch.
+    pub fn validated(self) -> Result<Self> {
+        let req = Self {
+            filter: self.filter.normalized(),
+            patch: self.patch.normalized(),
+            dry_run: self.dry_run,
+        };
+        req.filter.validate()?;
+        req.patch.validate()?;
+        Ok(req)
+    }
+}
+
+impl fmt::Debug for BulkNoteUpdateRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BulkNoteUpdateRequest")
+            .field("filter", &self.filter)
+            .field("patch", &self.patch)
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+/// What a bulk update changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BulkNoteUpdateResult {
+    /// Notes matching the filter
+    pub matched: u64,
+    /// Notes the patch was applied to
+    pub updated: u64,
+    /// Notes left unchanged because their merged metadata failed their
+    /// document type's schema, or because they were deleted meanwhile
+    pub failed: u64,
+}
+
+impl BulkNoteUpdateResult {
+    /// Add another batch's counts to this one.
+    pub fn add(&mut self, other: BulkNoteUpdateResult) {
+        self.matched += other.matched;
+        self.updated += other.updated;
+        self.failed += other.failed;
+    }
+}
+
+fn normalized_tags(tags: Vec<String>) -> Vec<String> {
+    tags.iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn invalid_message(result: Result<BulkNoteUpdateRequest>) -> String {
+        match result {
+            Err(Error::InvalidInput(message)) => message,
+            other => panic!("expected invalid input, got {other:?}"),
+        }
+    }
+
+    fn request(filter: serde_json::Value, patch: serde_json::Value) -> BulkNoteUpdateRequest {
+        serde_json::from_value(json!({ "filter": filter, "patch": patch })).unwrap()
+    }
+
+    #[test]
+    fn filter_and_patch_must_select_and_change_something() {
+        let message = invalid_message(request(json!({}), json!({"archived": true})).validated());
+        assert!(message.contains("filter needs"));
+
+        let message = invalid_message(
+            request(json!({"tags": ["  "]}), json!({"archived": true})).validated(),
+        );
+        assert!(message.contains("filter needs"));
+
+        let message = invalid_message(request(json!({"query": "rust"}), json!({})).validated());
+        assert!(message.contains("patch needs"));
+
+        let req = request(json!({"tags": [" rust "]}), json!({"add_tags": ["lang"]}))
+            .validated()
+            .unwrap();
+        assert_eq!(req.filter.tags, vec!["rust"]);
+        assert!(!req.dry_run);
+    }
+
+    #[test]
+    fn patch_rejects_conflicting_tags_and_non_object_metadata() {
+        let filter = json!({"query": "rust"});
+        let message = invalid_message(
+            request(
+                filter.clone(),
+                json!({"add_tags": ["Draft"], "remove_tags": ["draft"]}),
+            )
+            .validated(),
+        );
+        assert!(message.contains("both added and removed"));
+
+        for metadata in [json!([1]), json!({}), json!("x")] {
+            let message = invalid_message(
+                request(filter.clone(), json!({ "metadata": metadata })).validated(),
+            );
+            assert!(message.contains("metadata must be an object"));
+        }
+
+        let message =
+            invalid_message(request(filter, json!({"add_tags": ["a/b/c/d/e/f"]})).validated());
+        assert!(message.contains("add_tags exceeds maximum depth"));
+    }
+
+    #[test]
+    fn filter_rejects_inverted_date_range() {
+        let after = Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap();
+        let req = BulkNoteUpdateRequest {
+            filter: BulkNoteFilter {
+                created_after: Some(after),
+                created_before: Some(after),
+                ..Default::default()
+            },
+            patch: BulkNotePatch {
+                archived: Some(true),
+                ..Default::default()
+            },
+            dry_run: true,
+        };
+        assert!(invalid_message(req.validated()).contains("created_after"));
+    }
+
+    #[test]
+    fn debug_redacts_tags_query_and_metadata() {
+        let req = request(
+            json!({"tags": ["client/秘密"], "query": "sk-secret-query"}),
+            json!({"add_tags": ["secret-tag"], "metadata": {"owner": "alice@example.com"}}),
+        );
+        let debug = format!("{req:?}");
+        for raw in [
+            "秘密",
+            "sk-secret-query",
+            "secret-tag",
+            "owner",
+            "alice@example.com",
+        ] {
+            assert!(!debug.contains(raw), "raw value leaked: {raw}");
+        }
+        assert!(debug.contains("metadata_key_count: Some(1)"));
+    }
+}
@@ -0,0 +1,290 @@
+//! Typed filters over note metadata.
+//!
+//! `GET /api/v1/notes` accepts `metadata.<path>=<value>` query parameters,
+//! where `<path>` is a dot-separated key path into the note's JSON metadata,
+//! optionally followed by an operator in brackets:
+//!
+//! - `metadata.status=active` — equal
+//! - `metadata.status[ne]=done` — not equal (also matches notes without the key)
+//! - `metadata.rating[gte]=4` — `gt`, `gte`, `lt`, `lte` compare numbers
+//!   numerically and anything else as text, so ISO 8601 dates order correctly
+//!
+//! Values are typed from their spelling: `true`/`false` are booleans, finite
+//! numbers are numbers, anything else is text. Equality on a number also
+//! matches the same number stored as a JSON number with a different spelling
+//! (`4` matches `4.0`). Filters are compiled to SQL by
+//! `StrictFilterQueryBuilder` as part of a [`crate::StrictTagFilter`].
+//!
+//! ```
+//! use matric_core::note_metadata::{MetadataFieldFilter, MetadataOp, MetadataValue};
+//!
+//! let filter = MetadataFieldFilter::parse("metadata.review.score[gte]", "4").unwrap();
+//! assert_eq!(filter.path, vec!["review", "score"]);
+//! assert_eq!(filter.op, MetadataOp::Gte);
+//! assert_eq!(filter.value, MetadataValue::Number(4.0));
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// Query parameter prefix that marks a metadata filter.
+pub const METADATA_FILTER_PREFIX: &str = "metadata.";
+
+/// Most metadata filters one request may carry.
+pub const MAX_METADATA_FILTERS: usize = 20;
+
+/// Deepest key path a metadata filter may address.
+pub const MAX_METADATA_PATH_DEPTH: usize = 8;
+
+/// Longest key path segment.
+const MAX_METADATA_SEGMENT_LEN: usize = 64;
+
+/// Comparison applied by a [`MetadataFieldFilter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataOp {
+    #[default]
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl MetadataOp {
+    /// SQL comparison operator.
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            MetadataOp::Eq => "=",
+            MetadataOp::Ne => "<>",
+            MetadataOp::Gt => ">",
+            MetadataOp::Gte => ">=",
+            MetadataOp::Lt => "<",
+            MetadataOp::Lte => "<=",
+        }
+    }
+
+    /// Whether the operator orders values rather than comparing them for
+    /// equality.
+    pub fn is_range(self) -> bool {
+        matches!(
+            self,
+            MetadataOp::Gt | MetadataOp::Gte | MetadataOp::Lt | MetadataOp::Lte
+        )
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "eq" => Some(MetadataOp::Eq),
+            "ne" => Some(MetadataOp::Ne),
+            "gt" => Some(MetadataOp::Gt),
+            "gte" => Some(MetadataOp::Gte),
+            "lt" => Some(MetadataOp::Lt),
+            "lte" => Some(MetadataOp::Lte),
+            _ => None,
+        }
+    }
+}
+
+/// Value a metadata key is compared with.
+#[derive(Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(untagged)]
+pub enum MetadataValue {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl MetadataValue {
+    /// Type a query parameter value from its spelling.
+    pub fn infer(value: &str) -> Self {
+        match value {
+            "true" => return MetadataValue::Bool(true),
+            "false" => return MetadataValue::Bool(false),
+            _ => {}
+        }
+        match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => MetadataValue::Number(number),
+            _ => MetadataValue::Text(value.to_string()),
+        }
+    }
+
+    /// The value as the text `->>` extracts for an equal JSON value.
+    pub fn to_text(&self) -> String {
+        match self {
+            MetadataValue::Bool(value) => value.to_string(),
+            MetadataValue::Number(value) => value.to_string(),
+            MetadataValue::Text(value) => value.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for MetadataValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetadataValue::Bool(value) => f.debug_tuple("Bool").field(value).finish(),
+            MetadataValue::Number(_) => f.debug_struct("Number").field("value_set", &true).finish(),
+            MetadataValue::Text(value) => f
+                .debug_struct("Text")
+                .field("value_len", &value.chars().count())
+                .finish(),
+        }
+    }
+}
+
+/// One typed condition on a note metadata key.
+#[derive(Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct MetadataFieldFilter {
+    /// Key path into the metadata object, outermost key first.
+    pub path: Vec<String>,
+    #[serde(default)]
+    pub op: MetadataOp,
+    pub value: MetadataValue,
+}
+
+impl fmt::Debug for MetadataFieldFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetadataFieldFilter")
+            .field("path_depth", &self.path.len())
+            .field("op", &self.op)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl MetadataFieldFilter {
+    /// Parse a `metadata.<path>[<op>]` query parameter and its value.
+    pub fn parse(key: &str, value: &str) -> Result<Self> {
+        let rest = key.strip_prefix(METADATA_FILTER_PREFIX).ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "metadata filter must start with '{METADATA_FILTER_PREFIX}'"
+            ))
+        })?;
+        let (path, op) = match rest.strip_suffix(']').and_then(|r| r.rsplit_once('[')) {
+            Some((path, op)) => (
+                path,
+                MetadataOp::parse(op).ok_or_else(|| {
+                    Error::InvalidInput(
+                        "metadata filter operator must be one of eq, ne, gt, gte, lt, lte".into(),
+                    )
+                })?,
+            ),
+            None => (rest, MetadataOp::Eq),
+        };
+        let filter = Self {
+            path: path.split('.').map(str::to_string).collect(),
+            op,
+            value: MetadataValue::infer(value),
+        };
+        filter.validate()?;
+        Ok(filter)
+    }
+
+    /// Reject empty or overlong paths, segments outside `[A-Za-z0-9_-]`, and
+    /// ordering comparisons on booleans.
+    pub fn validate(&self) -> Result<()> {
+        if self.path.is_empty() || self.path.len() > MAX_METADATA_PATH_DEPTH {
+            return Err(Error::InvalidInput(format!(
+                "metadata filter path must have 1 to {MAX_METADATA_PATH_DEPTH} keys"
+            )));
+        }
+        let valid_segment = |segment: &String| {
+            !segment.is_empty()
+                && segment.len() <= MAX_METADATA_SEGMENT_LEN
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        };
+        if !self.path.iter().all(valid_segment) {
+            return Err(Error::InvalidInput(format!(
+                "metadata filter keys must be 1 to {MAX_METADATA_SEGMENT_LEN} letters, digits, '_' or '-'"
+            )));
+        }
+        if self.op.is_range() && matches!(self.value, MetadataValue::Bool(_)) {
+            return Err(Error::InvalidInput(
+                "metadata filter cannot order booleans; use eq or ne".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Collect the metadata filters among query parameters, ignoring every
+/// other parameter.
+pub fn parse_metadata_filters<'a>(
+    params: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Result<Vec<MetadataFieldFilter>> {
+    let filters = params
+        .into_iter()
+        .filter(|(key, _)| key.starts_with(METADATA_FILTER_PREFIX))
+        .map(|(key, value)| MetadataFieldFilter::parse(key, value))
+        .collect::<Result<Vec<_>>>()?;
+    if filters.len() > MAX_METADATA_FILTERS {
+        return Err(Error::InvalidInput(format!(
+            "at most {MAX_METADATA_FILTERS} metadata filters are allowed"
+        )));
+    }
+    Ok(filters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_typed_from_their_spelling() {
+        assert_eq!(MetadataValue::infer("true"), MetadataValue::Bool(true));
+        assert_eq!(MetadataValue::infer("-2.5"), MetadataValue::Number(-2.5));
+        assert_eq!(
+            MetadataValue::infer("active"),
+            MetadataValue::Text("active".into())
+        );
+        assert_eq!(
+            MetadataValue::infer("NaN"),
+            MetadataValue::Text("NaN".into())
+        );
+        assert_eq!(
+            MetadataValue::infer("2026-10-18"),
+            MetadataValue::Text("2026-10-18".into())
+        );
+    }
+
+    #[test]
+    fn query_parameters_parse_into_filters() {
+        let filters = parse_metadata_filters([
+            ("limit", "10"),
+            ("metadata.status", "active"),
+            ("metadata.review.score[lt]", "3"),
+        ])
+        .unwrap();
+        assert_eq!(filters.len(), 2);
+        assert_eq!(filters[0].path, vec!["status"]);
+        assert_eq!(filters[0].op, MetadataOp::Eq);
+        assert_eq!(filters[1].path, vec!["review", "score"]);
+        assert_eq!(filters[1].op, MetadataOp::Lt);
+    }
+
+    #[test]
+    fn malformed_filters_are_rejected() {
+        for (key, value) in [
+            ("metadata.", "x"),
+            ("metadata.a..b", "x"),
+            ("metadata.a b", "x"),
+            ("metadata.status[like]", "x"),
+            ("metadata.done[gt]", "true"),
+        ] {
+            assert!(
+                MetadataFieldFilter::parse(key, value).is_err(),
+                "accepted {key}"
+            );
+        }
+        let too_many: Vec<(String, &str)> = (0..=MAX_METADATA_FILTERS)
+            .map(|i| (format!("metadata.k{i}"), "v"))
+            .collect();
+        assert!(parse_metadata_filters(too_many.iter().map(|(k, v)| (k.as_str(), *v))).is_err());
+    }
+}
@@ -0,0 +1,529 @@
+//! Typed variables and versions of note templates.
+//!
+//! A note template fills `{{name}}` placeholders in its content when it is
+//! instantiated. A template may declare the variables it takes, each with a
+//! type (`string`, `date`, `enum` or `note_reference`), whether it is
+//! required and a default. Instantiating a template with declared variables
+//! rejects unknown and missing variables and values of the wrong type;
+//! templates without declarations substitute whatever they are given.
+//!
+//! Defaults may contain expressions evaluated at instantiation: `{{today}}`
+//! (`YYYY-MM-DD`), `{{today+7d}}` and `{{today-1d}}` (days from today) and
+//! `{{now}}` (RFC 3339, UTC).
+//!
+//! ```
+//! use std::collections::HashMap;
+//!
+//! use chrono::{TimeZone, Utc};
+//! use matric_core::note_template::{
+//!     render_note_template, resolve_template_variables, TemplateVariable, TemplateVariableType,
+//! };
+//!
+//! let due = TemplateVariable {
+//!     name: "due".to_string(),
+//!     kind: TemplateVariableType::Date,
+//!     description: None,
+//!     required: true,
+//!     default: Some("{{today+7d}}".to_string()),
+//!     options: Vec::new(),
+//!     max_length: None,
+//! };
+//! let now = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+//! let values = resolve_template_variables(&[due], &HashMap::new(), now).unwrap();
+//! assert_eq!(render_note_template("Due {{due}}", &values), "Due 2026-03-08");
+//! ```
+//!
+//! Every update of a template makes a new version; the versions it replaced
+//! are kept as [`TemplateVersion`]s and can still be instantiated.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use chrono::{DateTime, Duration, NaiveDate, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// Most variables a template declares.
+pub const MAX_TEMPLATE_VARIABLES: usize = 50;
+
+/// Longest variable name.
+pub const MAX_TEMPLATE_VARIABLE_NAME_LEN: usize = 64;
+
+/// Most options an `enum` variable has.
+pub const MAX_TEMPLATE_ENUM_OPTIONS: usize = 100;
+
+/// Longest variable value, in characters.
+pub const MAX_TEMPLATE_VARIABLE_VALUE_CHARS: usize = 10_000;
+
+/// Date format of `date` variables and `{{today}}`.
+pub const TEMPLATE_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Expressions a default may use; variables cannot take these names.
+const DEFAULT_EXPRESSION_NAMES: [&str; 2] = ["today", "now"];
+
+/// Most days `{{today+Nd}}` may move.
+const MAX_DEFAULT_DAY_OFFSET: i64 = 36_500;
+
+/// Type of a template variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateVariableType {
+    /// Any text
+    String,
+    /// A calendar date, `YYYY-MM-DD`
+    Date,
+    /// One of the variable's `options`
+    Enum,
+    /// The ID of a note in the archive
+    NoteReference,
+}
+
+/// A variable a template declares.
+#[derive(Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TemplateVariable {
+    /// Placeholder name: letters, digits and `_`, not starting with a digit
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: TemplateVariableType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether instantiating requires a value (given or from the default)
+    #[serde(default)]
+    pub required: bool,
+    /// Value used when none is given; may contain `{{today}}`, `{{today+Nd}}`
+    /// and `{{now}}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Allowed values of an `enum` variable
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    /// Longest value of a `string` variable, in characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+}
+
+impl fmt::Debug for TemplateVariable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TemplateVariable")
+            .field("name_len", &self.name.len())
+            .field("kind", &self.kind)
+            .field(
+                "description_len",
+                &self.description.as_ref().map(String::len),
+            )
+            .field("required", &self.required)
+            .field("default_set", &self.default.is_some())
+            .field("option_count", &self.options.len())
+            .field("max_length", &self.max_length)
+            .finish()
+    }
+}
+
+/// A version of a template, as it was when made.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TemplateVersion {
+    pub template_id: Uuid,
+    pub version: i32,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub content: String,
+    pub format: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<TemplateVariable>,
+    /// When this version was made
+    pub created_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for TemplateVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TemplateVersion")
+            .field("template_id_set", &true)
+            .field("version", &self.version)
+            .field("name_len", &self.name.len())
+            .field("content_len", &self.content.len())
+            .field("default_tags_count", &self.default_tags.len())
+            .field("variable_count", &self.variables.len())
+            .finish()
+    }
+}
+
+fn valid_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_TEMPLATE_VARIABLE_NAME_LEN
+        && name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Check a template's variable declarations: names are valid and unique,
+/// `enum` variables have options, `options` and `max_length` only appear on
+/// the types they apply to, and defaults are valid values.
+pub fn validate_template_variables(variables: &[TemplateVariable]) -> Result<()> {
+    if variables.len() > MAX_TEMPLATE_VARIABLES {
+        return Err(Error::InvalidInput(format!(
+            "A template declares at most {MAX_TEMPLATE_VARIABLES} variables"
+        )));
+    }
+    let mut names = HashSet::new();
+    for variable in variables {
+        let name = variable.name.as_str();
+        if !valid_variable_name(name) {
+            return Err(Error::InvalidInput(format!(
+                "Variable names are 1 to {MAX_TEMPLATE_VARIABLE_NAME_LEN} letters, digits and \
+                 '_', not starting with a digit"
+            )));
+        }
+        if DEFAULT_EXPRESSION_NAMES.contains(&name) {
+            return Err(Error::InvalidInput(format!(
+                "Variable name '{name}' is reserved for default expressions"
+            )));
+        }
+        if !names.insert(name) {
+            return Err(Error::InvalidInput(format!(
+                "Variable '{name}' is declared twice"
+            )));
+        }
+        match variable.kind {
+            TemplateVariableType::Enum => {
+                if variable.options.is_empty() || variable.options.len() > MAX_TEMPLATE_ENUM_OPTIONS
+                {
+                    return Err(Error::InvalidInput(format!(
+                        "Enum variable '{name}' needs 1 to {MAX_TEMPLATE_ENUM_OPTIONS} options"
+                    )));
+                }
+                let unique: HashSet<&String> = variable.options.iter().collect();
+                if unique.len() != variable.options.len()
+                    || variable.options.iter().any(|option| option.is_empty())
+                {
+                    return Err(Error::InvalidInput(format!(
+                        "Options of enum variable '{name}' must be distinct and non-empty"
+                    )));
+                }
+            }
+            _ if !variable.options.is_empty() => {
+                return Err(Error::InvalidInput(format!(
+                    "Only enum variables take options; '{name}' is not an enum"
+                )));
+            }
+            _ => {}
+        }
+        if variable.max_length.is_some() && variable.kind != TemplateVariableType::String {
+            return Err(Error::InvalidInput(format!(
+                "Only string variables take max_length; '{name}' is not a string"
+            )));
+        }
+        if let Some(default) = &variable.default {
+            let value = expand_default(default, Utc::now()).map_err(|expression| {
+                Error::InvalidInput(format!(
+                    "Default of variable '{name}' uses unknown expression '{{{{{expression}}}}}'"
+                ))
+            })?;
+            if let Some(problem) = value_problem(variable, &value) {
+                return Err(Error::InvalidInput(format!(
+                    "Default of variable '{name}' is invalid: {problem}"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Evaluate a default expression such as `today`, `today+7d` or `now`.
+fn evaluate_expression(expression: &str, now: DateTime<Utc>) -> Option<String> {
+    let expression = expression.trim();
+    if expression == "now" {
+        return Some(now.to_rfc3339_opts(SecondsFormat::Secs, true));
+    }
+    let rest = expression.strip_prefix("today")?;
+    let days = if rest.is_empty() {
+        0
+    } else {
+        let (sign, number) = match rest.split_at(1) {
+            ("+", number) => (1, number),
+            ("-", number) => (-1, number),
+            _ => return None,
+        };
+        let days: i64 = number.strip_suffix('d')?.parse().ok()?;
+        if days > MAX_DEFAULT_DAY_OFFSET {
+            return None;
+        }
+        sign * days
+    };
+    let date = now.date_naive() + Duration::days(days);
+    Some(date.format(TEMPLATE_DATE_FORMAT).to_string())
+}
+
+/// Expand the `{{...}}` expressions of a default, or return the first one
+/// that is not an expression.
+fn expand_default(default: &str, now: DateTime<Utc>) -> std::result::Result<String, String> {
+    let mut out = String::with_capacity(default.len());
+    let mut rest = default;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let expression = &rest[start + 2..start + 2 + len];
+        out.push_str(&rest[..start]);
+        out.push_str(
+            &evaluate_expression(expression, now).ok_or_else(|| expression.trim().to_string())?,
+        );
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Why `value` is not a valid value of `variable`, if it is not.
+fn value_problem(variable: &TemplateVariable, value: &str) -> Option<String> {
+    if value.chars().count() > MAX_TEMPLATE_VARIABLE_VALUE_CHARS {
+        return Some(format!(
+            "longer than {MAX_TEMPLATE_VARIABLE_VALUE_CHARS} characters"
+        ));
+    }
+    match variable.kind {
+        TemplateVariableType::String => variable
+            .max_length
+            .filter(|max| value.chars().count() > *max)
+            .map(|max| format!("longer than {max} characters")),
+        TemplateVariableType::Date => NaiveDate::parse_from_str(value, TEMPLATE_DATE_FORMAT)
+            .is_err()
+            .then(|| "expected a date as YYYY-MM-DD".to_string()),
+        TemplateVariableType::Enum => (!variable.options.iter().any(|option| option == value))
+            .then(|| format!("expected one of {}", variable.options.join(", "))),
+        TemplateVariableType::NoteReference => Uuid::parse_str(value)
+            .is_err()
+            .then(|| "expected a note ID".to_string()),
+    }
+}
+
+/// The values to fill a template's placeholders with.
+///
+/// Without declared variables, `provided` is used as given. Otherwise every
+/// provided variable must be declared, declared variables without a value
+/// (or given as empty text) take their default, required ones must end up
+/// with a value and every value must match its type; all problems are
+/// reported together. Optional variables without a value fill in as empty
+/// text.
+pub fn resolve_template_variables(
+    variables: &[TemplateVariable],
+    provided: &HashMap<String, String>,
+    now: DateTime<Utc>,
+) -> Result<HashMap<String, String>> {
+    if variables.is_empty() {
+        return Ok(provided.clone());
+    }
+    let mut problems = Vec::new();
+    let mut unknown: Vec<&str> = provided
+        .keys()
+        .filter(|key| !variables.iter().any(|variable| &variable.name == *key))
+        .map(String::as_str)
+        .collect();
+    unknown.sort_unstable();
+    problems.extend(
+        unknown
+            .into_iter()
+            .map(|key| format!("{key}: not declared")),
+    );
+
+    let mut values = HashMap::with_capacity(variables.len());
+    for variable in variables {
+        let value = provided
+            .get(&variable.name)
+            .filter(|value| !value.is_empty())
+            .cloned()
+            .or_else(|| {
+                let default = variable.default.as_deref()?;
+                expand_default(default, now).ok()
+            });
+        match value.filter(|value| !value.is_empty()) {
+            Some(value) => {
+                if let Some(problem) = value_problem(variable, &value) {
+                    problems.push(format!("{}: {problem}", variable.name));
+                }
+                values.insert(variable.name.clone(), value);
+            }
+            None if variable.required => problems.push(format!("{}: required", variable.name)),
+            None => {
+                values.insert(variable.name.clone(), String::new());
+            }
+        }
+    }
+    if !problems.is_empty() {
+        return Err(Error::InvalidInput(format!(
+            "Invalid template variables: {}",
+            problems.join("; ")
+        )));
+    }
+    Ok(values)
+}
+
+/// The notes `note_reference` variables point at, from resolved `values`,
+/// as `(variable name, note ID)` pairs.
+pub fn referenced_note_ids<'a>(
+    variables: &'a [TemplateVariable],
+    values: &HashMap<String, String>,
+) -> Vec<(&'a str, Uuid)> {
+    variables
+        .iter()
+        .filter(|variable| variable.kind == TemplateVariableType::NoteReference)
+        .filter_map(|variable| {
+            let value = values.get(&variable.name)?;
+            Some((variable.name.as_str(), Uuid::parse_str(value).ok()?))
+        })
+        .collect()
+}
+
+/// Fill the `{{name}}` placeholders of `content` with `values`.
+///
+/// Substitution is a single pass, so placeholders inside values are not
+/// expanded. Placeholders without a value are left as written.
+pub fn render_note_template(content: &str, values: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + len + 2;
+        out.push_str(&rest[..start]);
+        match values.get(&rest[start + 2..start + 2 + len]) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..end]),
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn variable(name: &str, kind: TemplateVariableType) -> TemplateVariable {
+        TemplateVariable {
+            name: name.to_string(),
+            kind,
+            description: None,
+            required: false,
+            default: None,
+            options: Vec::new(),
+            max_length: None,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 12, 30, 23, 15, 0).unwrap()
+    }
+
+    #[test]
+    fn default_expressions_expand_relative_to_now() {
+        assert_eq!(expand_default("{{today}}", now()).unwrap(), "2026-12-30");
+        assert_eq!(
+            expand_default("{{ today+3d }}", now()).unwrap(),
+            "2027-01-02"
+        );
+        assert_eq!(
+            expand_default("{{today-30d}}", now()).unwrap(),
+            "2026-11-30"
+        );
+        assert_eq!(
+            expand_default("Week of {{today}} at {{now}}", now()).unwrap(),
+            "Week of 2026-12-30 at 2026-12-30T23:15:00Z"
+        );
+        assert_eq!(
+            expand_default("{{tomorrow}}", now()).unwrap_err(),
+            "tomorrow"
+        );
+        assert!(expand_default("{{today+3w}}", now()).is_err());
+    }
+
+    #[test]
+    fn declarations_are_validated() {
+        let mut status = variable("status", TemplateVariableType::Enum);
+        assert!(validate_template_variables(&[status.clone()]).is_err());
+        status.options = vec!["open".to_string(), "closed".to_string()];
+        status.default = Some("open".to_string());
+        assert!(validate_template_variables(&[status.clone()]).is_ok());
+
+        status.default = Some("pending".to_string());
+        assert!(validate_template_variables(&[status]).is_err());
+
+        let mut due = variable("due", TemplateVariableType::Date);
+        due.default = Some("{{today+7d}}".to_string());
+        assert!(validate_template_variables(&[due.clone()]).is_ok());
+        due.default = Some("{{yesterday}}".to_string());
+        assert!(validate_template_variables(&[due]).is_err());
+
+        let title = variable("title", TemplateVariableType::String);
+        assert!(validate_template_variables(&[title.clone(), title]).is_err());
+        assert!(
+            validate_template_variables(&[variable("today", TemplateVariableType::Date)]).is_err()
+        );
+        assert!(
+            validate_template_variables(&[variable("2nd", TemplateVariableType::String)]).is_err()
+        );
+
+        let mut with_options = variable("topic", TemplateVariableType::String);
+        with_options.options = vec!["a".to_string()];
+        assert!(validate_template_variables(&[with_options]).is_err());
+    }
+
+    #[test]
+    fn resolving_checks_types_and_reports_every_problem() {
+        let mut due = variable("due", TemplateVariableType::Date);
+        due.required = true;
+        let mut status = variable("status", TemplateVariableType::Enum);
+        status.options = vec!["open".to_string(), "closed".to_string()];
+        status.default = Some("open".to_string());
+        let source = variable("source", TemplateVariableType::NoteReference);
+        let mut topic = variable("topic", TemplateVariableType::String);
+        topic.max_length = Some(5);
+        let variables = [due, status, source, topic];
+
+        let provided = HashMap::from([
+            ("status".to_string(), "pending".to_string()),
+            ("source".to_string(), "not-a-uuid".to_string()),
+            ("topic".to_string(), "far too long".to_string()),
+            ("extra".to_string(), "x".to_string()),
+        ]);
+        let Err(Error::InvalidInput(message)) =
+            resolve_template_variables(&variables, &provided, now())
+        else {
+            panic!("expected invalid input");
+        };
+        assert_eq!(
+            message,
+            "Invalid template variables: extra: not declared; due: required; status: expected \
+             one of open, closed; source: expected a note ID; topic: longer than 5 characters"
+        );
+
+        let note_id = Uuid::new_v4();
+        let provided = HashMap::from([
+            ("due".to_string(), "2027-01-15".to_string()),
+            ("source".to_string(), note_id.to_string()),
+        ]);
+        let values = resolve_template_variables(&variables, &provided, now()).unwrap();
+        assert_eq!(values["status"], "open");
+        assert_eq!(values["topic"], "");
+        assert_eq!(
+            referenced_note_ids(&variables, &values),
+            vec![("source", note_id)]
+        );
+    }
+
+    #[test]
+    fn undeclared_templates_substitute_what_they_are_given() {
+        let provided = HashMap::from([("name".to_string(), "{{other}}".to_string())]);
+        let values = resolve_template_variables(&[], &provided, now()).unwrap();
+        assert_eq!(
+            render_note_template("Hi {{name}}, {{other}} {{", &values),
+            "Hi {{other}}, {{other}} {{"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Per-principal note views.
+//!
+//! Opening a note (`GET /api/v1/notes/{id}`) records a view for the
+//! principal that opened it. Views are buffered in the API process and
+//! written in batches, one row per principal and note, so a burst of reads
+//! costs one upsert. They back `GET /api/v1/notes/recently-viewed` and the
+//! personal recency boost of search, which ranks notes the caller opened
+//! lately a little higher. Anonymous requests are not recorded.
+//!
+//! ```
+//! use chrono::{Duration, Utc};
+//! use matric_core::note_view::{recency_weight, RECENCY_HALF_LIFE_HOURS};
+//!
+//! let now = Utc::now();
+//! assert_eq!(recency_weight(now, now), 1.0);
+//! let half_life_ago = now - Duration::hours(RECENCY_HALF_LIFE_HOURS as i64);
+//! assert!((recency_weight(half_life_ago, now) - 0.5).abs() < 1e-6);
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::AuthPrincipal;
+
+/// Notes listed unless asked otherwise.
+pub const DEFAULT_RECENTLY_VIEWED_LIMIT: i64 = 20;
+
+/// Most notes a caller may list.
+pub const MAX_RECENTLY_VIEWED_LIMIT: i64 = 100;
+
+/// Seconds between writes of buffered views.
+pub const NOTE_VIEW_FLUSH_SECS: u64 = 5;
+
+/// Buffered views that trigger a write before the next interval.
+pub const NOTE_VIEW_FLUSH_THRESHOLD: usize = 500;
+
+/// Hours after which a view counts half as much towards the recency boost.
+pub const RECENCY_HALF_LIFE_HOURS: f64 = 72.0;
+
+/// Views of one note by one principal, waiting to be written.
+#[derive(Clone, PartialEq, Eq)]
+pub struct NoteView {
+    pub principal: String,
+    pub note_id: Uuid,
+    /// Latest of the views
+    pub viewed_at: DateTime<Utc>,
+    pub views: i32,
+}
+
+impl fmt::Debug for NoteView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NoteView")
+            .field("principal_len", &self.principal.len())
+            .field("note_id", &self.note_id)
+            .field("viewed_at", &self.viewed_at)
+            .field("views", &self.views)
+            .finish()
+    }
+}
+
+/// A note the caller opened, most recent first in listings.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct RecentlyViewedNote {
+    pub note_id: Uuid,
+    pub title: Option<String>,
+    pub last_viewed_at_utc: DateTime<Utc>,
+    pub first_viewed_at_utc: DateTime<Utc>,
+    /// Times the caller opened the note
+    pub view_count: i32,
+}
+
+impl fmt::Debug for RecentlyViewedNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecentlyViewedNote")
+            .field("note_id", &self.note_id)
+            .field("title_present", &self.title.is_some())
+            .field("last_viewed_at_utc", &self.last_viewed_at_utc)
+            .field("first_viewed_at_utc", &self.first_viewed_at_utc)
+            .field("view_count", &self.view_count)
+            .finish()
+    }
+}
+
+/// The principal views are recorded for: the signed-in user when there is
+/// one, otherwise the API key or OAuth client. `None` for anonymous callers.
+pub fn view_principal(principal: &AuthPrincipal) -> Option<String> {
+    match principal {
+        AuthPrincipal::OAuthClient {
+            user_id: Some(user_id),
+            ..
+        } => Some(format!("user:{user_id}")),
+        AuthPrincipal::OAuthClient { client_id, .. } => Some(format!("oauth:{client_id}")),
+        AuthPrincipal::ApiKey { key_id, .. } => Some(format!("api-key:{key_id}")),
+        AuthPrincipal::Anonymous => None,
+    }
+}
+
+/// How much a view at `viewed_at` still counts at `now`: 1.0 for a view
+/// just now, halving every [`RECENCY_HALF_LIFE_HOURS`].
+pub fn recency_weight(viewed_at: DateTime<Utc>, now: DateTime<Utc>) -> f32 {
+    let hours = (now - viewed_at).num_seconds().max(0) as f64 / 3600.0;
+    0.5f64.powf(hours / RECENCY_HALF_LIFE_HOURS) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn principals_prefer_the_user() {
+        let key_id = Uuid::nil();
+        assert_eq!(
+            view_principal(&AuthPrincipal::OAuthClient {
+                client_id: "app".to_string(),
+                scope: "read".to_string(),
+                user_id: Some("u1".to_string()),
+            })
+            .as_deref(),
+            Some("user:u1")
+        );
+        assert_eq!(
+            view_principal(&AuthPrincipal::OAuthClient {
+                client_id: "app".to_string(),
+                scope: "read".to_string(),
+                user_id: None,
+            })
+            .as_deref(),
+            Some("oauth:app")
+        );
+        assert_eq!(
+            view_principal(&AuthPrincipal::ApiKey {
+                key_id,
+                scope: "read".to_string(),
+            }),
+            Some(format!("api-key:{key_id}"))
+        );
+        assert_eq!(view_principal(&AuthPrincipal::Anonymous), None);
+    }
+
+    #[test]
+    fn recency_weight_decays() {
+        let now = Utc::now();
+        let day = recency_weight(now - chrono::Duration::days(1), now);
+        let week = recency_weight(now - chrono::Duration::days(7), now);
+        assert!(day < 1.0 && week < day && week > 0.0);
+        assert_eq!(recency_weight(now + chrono::Duration::hours(1), now), 1.0);
+    }
+}
@@ -0,0 +1,264 @@
+//! Users, resource ownership and sharing.
+//!
+//! OAuth tokens carry the `user_id` of the person who authorized them. That
+//! external identity is mapped to a [`User`], and notes, collections and
+//! memory archives record the user who created them as their owner. Owners
+//! share a resource with another user through a [`ShareGrant`] giving read
+//! or write access; a grant on a collection covers the notes filed in it.
+//!
+//! Resources without an owner — everything created before users existed,
+//! or by API keys and anonymous callers — stay open to every caller, so
+//! single-user deployments behave as before.
+//!
+//! ```
+//! use matric_core::{AccessLevel, SharePermission};
+//!
+//! let shared = AccessLevel::from(SharePermission::Read);
+//! assert!(shared.allows(AccessLevel::Read));
+//! assert!(!shared.allows(AccessLevel::Write));
+//! assert!(AccessLevel::Owner.allows(AccessLevel::Write));
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// A person known to this instance through OAuth.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct User {
+    pub id: Uuid,
+    /// The OAuth `user_id` this user signs in as
+    pub external_id: String,
+    pub display_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl fmt::Debug for User {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("User")
+            .field("id", &self.id)
+            .field("external_id_len", &self.external_id.chars().count())
+            .field(
+                "display_name_len",
+                &self.display_name.as_ref().map(|name| name.chars().count()),
+            )
+            .field("created_at", &self.created_at)
+            .field("last_seen_at", &self.last_seen_at)
+            .finish()
+    }
+}
+
+/// Access a share grant gives.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SharePermission {
+    #[default]
+    Read,
+    Write,
+}
+
+impl SharePermission {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+        }
+    }
+
+    /// Parse a stored permission. Legacy `admin` grants give write access.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read" => Some(Self::Read),
+            "write" | "admin" => Some(Self::Write),
+            _ => None,
+        }
+    }
+}
+
+/// What a caller may do with a resource, weakest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLevel {
+    Read,
+    Write,
+    /// Full control, including sharing. Unowned resources grant it to all.
+    Owner,
+}
+
+impl AccessLevel {
+    /// Whether this level covers `needed`.
+    pub fn allows(self, needed: AccessLevel) -> bool {
+        self >= needed
+    }
+}
+
+impl From<SharePermission> for AccessLevel {
+    fn from(permission: SharePermission) -> Self {
+        match permission {
+            SharePermission::Read => Self::Read,
+            SharePermission::Write => Self::Write,
+        }
+    }
+}
+
+/// A caller's access to a resource from its owner, the active grants that
+/// reach the caller, and whether its visibility opens it to every user.
+pub fn resolve_access(
+    owner_id: Option<Uuid>,
+    caller: Uuid,
+    grants: impl IntoIterator<Item = SharePermission>,
+    openly_visible: bool,
+) -> Option<AccessLevel> {
+    match owner_id {
+        None => return Some(AccessLevel::Owner),
+        Some(owner) if owner == caller => return Some(AccessLevel::Owner),
+        Some(_) => {}
+    }
+    let granted = grants.into_iter().map(AccessLevel::from).max();
+    if openly_visible {
+        granted.max(Some(AccessLevel::Read))
+    } else {
+        granted
+    }
+}
+
+/// Kind of resource a grant shares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareResource {
+    Note,
+    Collection,
+    Archive,
+}
+
+/// Access to one resource given to one user.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ShareGrant {
+    pub id: Uuid,
+    pub resource: ShareResource,
+    /// Note, collection or archive ID
+    pub resource_id: Uuid,
+    pub grantee_id: Uuid,
+    pub permission: SharePermission,
+    pub granted_by: Option<Uuid>,
+    pub granted_at: DateTime<Utc>,
+    /// The grant stops applying after this time; null never expires
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl fmt::Debug for ShareGrant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShareGrant")
+            .field("id", &self.id)
+            .field("resource", &self.resource)
+            .field("permission", &self.permission)
+            .field("granted_by_set", &self.granted_by.is_some())
+            .field("granted_at", &self.granted_at)
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+/// Request to share a resource with a user. Sharing again with the same
+/// user replaces the earlier grant.
+#[derive(Debug, Clone, Deserialize, utoipa::ToSchema)]
+pub struct CreateShareGrantRequest {
+    /// User receiving access (see `GET /api/v1/users/me`)
+    pub grantee_id: Uuid,
+    #[serde(default)]
+    pub permission: SharePermission,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl CreateShareGrantRequest {
+    /// Check the grant names someone other than the granting user and does
+    /// not expire in the past.
+    pub fn validate(&self, granted_by: Option<Uuid>) -> Result<()> {
+        if Some(self.grantee_id) == granted_by {
+            return Err(Error::InvalidInput(
+                "a resource cannot be shared with its owner".to_string(),
+            ));
+        }
+        if self.expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(Error::InvalidInput(
+                "expires_at must be in the future".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn legacy_admin_grants_parse_as_write() {
+        assert_eq!(SharePermission::parse("read"), Some(SharePermission::Read));
+        assert_eq!(
+            SharePermission::parse("admin"),
+            Some(SharePermission::Write)
+        );
+        assert_eq!(SharePermission::parse("owner"), None);
+    }
+
+    #[test]
+    fn access_levels_are_ordered() {
+        assert!(AccessLevel::Owner.allows(AccessLevel::Read));
+        assert!(AccessLevel::Write.allows(AccessLevel::Write));
+        assert!(!AccessLevel::Write.allows(AccessLevel::Owner));
+        assert!(!AccessLevel::Read.allows(AccessLevel::Write));
+    }
+
+    #[test]
+    fn access_comes_from_ownership_grants_and_visibility() {
+        let owner = Uuid::new_v4();
+        let caller = Uuid::new_v4();
+
+        assert_eq!(
+            resolve_access(None, caller, [], false),
+            Some(AccessLevel::Owner)
+        );
+        assert_eq!(
+            resolve_access(Some(caller), caller, [], false),
+            Some(AccessLevel::Owner)
+        );
+        assert_eq!(resolve_access(Some(owner), caller, [], false), None);
+        assert_eq!(
+            resolve_access(Some(owner), caller, [], true),
+            Some(AccessLevel::Read)
+        );
+        assert_eq!(
+            resolve_access(
+                Some(owner),
+                caller,
+                [SharePermission::Read, SharePermission::Write],
+                false
+            ),
+            Some(AccessLevel::Write)
+        );
+    }
+
+    #[test]
+    fn share_requests_reject_self_and_past_expiry() {
+        let owner = Uuid::new_v4();
+        let mut request = CreateShareGrantRequest {
+            grantee_id: owner,
+            permission: SharePermission::Write,
+            expires_at: None,
+        };
+        assert!(request.validate(Some(owner)).is_err());
+
+        request.grantee_id = Uuid::new_v4();
+        assert!(request.validate(Some(owner)).is_ok());
+        request.expires_at = Some(Utc::now() - Duration::hours(1));
+        assert!(request.validate(Some(owner)).is_err());
+    }
+}
@@ -0,0 +1,538 @@
+//! Personally identifiable information (PII) detection and redaction.
+//!
+//! The `pii_scan` job looks for email addresses, phone numbers, payment card
+//! numbers, and national identity numbers in a note's original content and
+//! its attachments' extracted text. Pattern matching ([`scan_pii`]) finds
+//! well-formed values; when `PII_LLM_ASSIST` is enabled, values the model
+//! points out are located in the text as well ([`locate_pii`]).
+//!
+//! Findings record the kind, position, and a hash of the value — never the
+//! value itself. What happens next depends on `PII_REDACTION_MODE`:
+//!
+//! - `off` — notes are not scanned
+//! - `flag` (default) — findings are recorded and shown on the health dashboard
+//! - `mask` — the revised copy of the note has each value replaced with a
+//!   `[redacted:<kind>]` marker; the original is kept as written
+//! - `block_indexing` — notes with findings are left out of full-text search
+//!   and are not embedded
+//!
+//! ```
+//! use matric_core::pii::{mask_pii, scan_pii, PiiKind};
+//!
+//! let text = "Mail ada@example.com or call +1 415-555-2671.";
+//! let found = scan_pii(text);
+//! assert_eq!(found[0].kind, PiiKind::Email);
+//! assert_eq!(found[1].kind, PiiKind::Phone);
+//! assert_eq!(
+//!     mask_pii(text, &found),
+//!     "Mail [redacted:email] or call [redacted:phone]."
+//! );
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Environment variable selecting the [`PiiRedactionMode`].
+pub const ENV_PII_REDACTION_MODE: &str = "PII_REDACTION_MODE";
+
+/// Environment variable enabling the LLM-assisted detection pass.
+pub const ENV_PII_LLM_ASSIST: &str = "PII_LLM_ASSIST";
+
+/// A category of personal data.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiKind {
+    Email,
+    Phone,
+    CreditCard,
+    NationalId,
+}
+
+impl PiiKind {
+    /// Every kind, in the order overlapping matches are preferred.
+    pub const ALL: [PiiKind; 4] = [
+        PiiKind::Email,
+        PiiKind::CreditCard,
+        PiiKind::NationalId,
+        PiiKind::Phone,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            PiiKind::Email => "email",
+            PiiKind::Phone => "phone",
+            PiiKind::CreditCard => "credit_card",
+            PiiKind::NationalId => "national_id",
+        }
+    }
+}
+
+impl fmt::Display for PiiKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PiiKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == value)
+            .ok_or_else(|| format!("unknown PII kind: {value}"))
+    }
+}
+
+impl TryFrom<String> for PiiKind {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// How a finding was made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiDetector {
+    /// Pattern match with format checks (e.g. the Luhn checksum for cards)
+    Regex,
+    /// Value pointed out by the generation model and located in the text
+    Llm,
+}
+
+impl PiiDetector {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            PiiDetector::Regex => "regex",
+            PiiDetector::Llm => "llm",
+        }
+    }
+}
+
+impl TryFrom<String> for PiiDetector {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "regex" => Ok(PiiDetector::Regex),
+            "llm" => Ok(PiiDetector::Llm),
+            _ => Err(format!("unknown PII detector: {value}")),
+        }
+    }
+}
+
+/// What is done with notes that contain PII.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiRedactionMode {
+    /// Do not scan
+    Off,
+    /// Record findings only
+    Flag,
+    /// Record findings and mask values in the revised copy
+    Mask,
+    /// Record findings and keep the note out of search indexes
+    BlockIndexing,
+}
+
+impl PiiRedactionMode {
+    /// Mode from `PII_REDACTION_MODE`; unset or unrecognised values mean `flag`.
+    pub fn from_env() -> Self {
+        match std::env::var(ENV_PII_REDACTION_MODE)
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase()
+            .as_str()
+        {
+            "off" | "none" | "false" => PiiRedactionMode::Off,
+            "mask" => PiiRedactionMode::Mask,
+            "block_indexing" | "block" => PiiRedactionMode::BlockIndexing,
+            _ => PiiRedactionMode::Flag,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            PiiRedactionMode::Off => "off",
+            PiiRedactionMode::Flag => "flag",
+            PiiRedactionMode::Mask => "mask",
+            PiiRedactionMode::BlockIndexing => "block_indexing",
+        }
+    }
+
+    /// Whether notes are scanned at all.
+    pub fn scans(self) -> bool {
+        self != PiiRedactionMode::Off
+    }
+
+    /// Whether values are masked in the revised copy.
+    pub fn masks(self) -> bool {
+        self == PiiRedactionMode::Mask
+    }
+
+    /// Whether notes with findings are kept out of search indexes.
+    pub fn blocks_indexing(self) -> bool {
+        self == PiiRedactionMode::BlockIndexing
+    }
+}
+
+/// Whether the LLM-assisted pass runs (`PII_LLM_ASSIST`, default false).
+pub fn llm_assist_enabled() -> bool {
+    std::env::var(ENV_PII_LLM_ASSIST)
+        .ok()
+        .and_then(|v| v.trim().parse::<bool>().ok())
+        .unwrap_or(false)
+}
+
+/// One PII value found in a text, by byte range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiiMatch {
+    pub kind: PiiKind,
+    pub detector: PiiDetector,
+    /// Byte offset of the first character
+    pub start: usize,
+    /// Byte offset just past the last character
+    pub end: usize,
+    /// SHA-256 of the normalized value; see [`pii_value_hash`]
+    pub value_hash: String,
+}
+
+/// A stored finding. Offsets are bytes into the scanned text: the note's
+/// original content, or the attachment's extracted text when
+/// `attachment_id` is set.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct PiiFinding {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment_id: Option<Uuid>,
+    #[sqlx(try_from = "String")]
+    pub kind: PiiKind,
+    #[sqlx(try_from = "String")]
+    pub detector: PiiDetector,
+    pub start_offset: i32,
+    pub end_offset: i32,
+    pub value_hash: String,
+    /// Whether the finding keeps its note out of search indexes
+    pub blocks_indexing: bool,
+    pub created_at_utc: DateTime<Utc>,
+}
+
+/// A note with findings, for the health dashboard.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct PiiNoteSummary {
+    pub note_id: Uuid,
+    /// Findings by kind
+    pub kinds: BTreeMap<String, i64>,
+    pub finding_count: i64,
+    /// Findings in attachment text rather than the note itself
+    pub attachment_findings: i64,
+    pub blocks_indexing: bool,
+    pub last_scanned_at: DateTime<Utc>,
+}
+
+/// Archive-wide PII counts for the health dashboard.
+#[derive(Debug, Clone, Default, Serialize, utoipa::ToSchema)]
+pub struct PiiSummary {
+    pub notes_with_pii: i64,
+    pub blocked_notes: i64,
+    pub total_findings: i64,
+    /// Findings by kind
+    pub by_kind: BTreeMap<String, i64>,
+}
+
+static EMAIL_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b[a-z0-9][a-z0-9._%+-]*@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}\b")
+        .expect("email pattern must compile")
+});
+
+static CARD_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").expect("card pattern must compile"));
+
+/// US Social Security numbers and UK National Insurance numbers.
+static NATIONAL_ID_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b(?:\d{3}-\d{2}-\d{4}|[A-CEGHJ-PR-TW-Z][A-CEGHJ-NPR-TW-Z] ?\d{2} ?\d{2} ?\d{2} ?[A-D])\b",
+    )
+    .expect("national id pattern must compile")
+});
+
+static PHONE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\+\d{1,3}[ .-]?)?(?:\(\d{1,4}\)[ .-]?|\d{1,4}[ .-])\d{3,4}[ .-]\d{3,4}")
+        .expect("phone pattern must compile")
+});
+
+fn digits(value: &str) -> String {
+    value.chars().filter(char::is_ascii_digit).collect()
+}
+
+/// Luhn checksum used by payment card numbers.
+fn luhn_valid(number: &str) -> bool {
+    let mut sum = 0;
+    for (i, c) in number.chars().rev().enumerate() {
+        let Some(mut d) = c.to_digit(10) else {
+            return false;
+        };
+        if i % 2 == 1 {
+            d *= 2;
+            if d > 9 {
+                d -= 9;
+            }
+        }
+        sum += d;
+    }
+    sum % 10 == 0
+}
+
+fn valid_ssn(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    let [area, group, serial] = parts.as_slice() else {
+        return false;
+    };
+    *area != "000"
+        && *area != "666"
+        && !area.starts_with('9')
+        && *group != "00"
+        && *serial != "0000"
+}
+
+/// Whether `value`, matched as `kind`, passes the kind's format checks.
+fn plausible(kind: PiiKind, value: &str) -> bool {
+    match kind {
+        PiiKind::Email => true,
+        PiiKind::CreditCard => {
+            let d = digits(value);
+            (13..=19).contains(&d.len()) && luhn_valid(&d)
+        }
+        PiiKind::NationalId => {
+            if value.contains('-') {
+                valid_ssn(value)
+            } else {
+                true
+            }
+        }
+        PiiKind::Phone => (7..=15).contains(&digits(value).len()),
+    }
+}
+
+/// Hash of a value normalized for its kind: lowercase for email, digits only
+/// for numbers, uppercase without spaces for national IDs. The same value
+/// written differently hashes the same.
+pub fn pii_value_hash(kind: PiiKind, value: &str) -> String {
+    let normalized = match kind {
+        PiiKind::Email => value.trim().to_lowercase(),
+        PiiKind::Phone | PiiKind::CreditCard => digits(value),
+        PiiKind::NationalId => value
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_uppercase(),
+    };
+    hex::encode(Sha256::digest(format!("{kind}:{normalized}").as_bytes()))
+}
+
+fn pattern(kind: PiiKind) -> &'static Regex {
+    match kind {
+        PiiKind::Email => &EMAIL_PATTERN,
+        PiiKind::CreditCard => &CARD_PATTERN,
+        PiiKind::NationalId => &NATIONAL_ID_PATTERN,
+        PiiKind::Phone => &PHONE_PATTERN,
+    }
+}
+
+/// Whether the match at `start..end` stands alone, so a phone pattern does
+/// not match part of a longer number such as `4111 1111 1111 1112`.
+fn isolated(text: &str, start: usize, end: usize) -> bool {
+    fn continues(mut chars: impl Iterator<Item = char>) -> bool {
+        match chars.next() {
+            Some(c) if c.is_ascii_alphanumeric() || c == '+' => true,
+            Some(' ' | '.' | '-') => chars.next().is_some_and(|c| c.is_ascii_digit()),
+            _ => false,
+        }
+    }
+    !continues(text[..start].chars().rev()) && !continues(text[end..].chars())
+}
+
+/// Find PII in `text` by pattern.
+///
+/// Matches are returned in text order and never overlap; where two kinds
+/// match the same characters, the first in [`PiiKind::ALL`] wins.
+pub fn scan_pii(text: &str) -> Vec<PiiMatch> {
+    let mut found = Vec::new();
+    for kind in PiiKind::ALL {
+        let candidates = pattern(kind)
+            .find_iter(text)
+            .filter(|m| isolated(text, m.start(), m.end()) && plausible(kind, m.as_str()))
+            .map(|m| PiiMatch {
+                kind,
+                detector: PiiDetector::Regex,
+                start: m.start(),
+                end: m.end(),
+                value_hash: pii_value_hash(kind, m.as_str()),
+            });
+        merge_pii_matches(&mut found, candidates);
+    }
+    found
+}
+
+/// Find every occurrence of `value`, reported as `kind`, in `text`.
+///
+/// Used for values named by the model, which are located rather than
+/// trusted: a value that does not occur in the text yields nothing.
+pub fn locate_pii(text: &str, kind: PiiKind, value: &str) -> Vec<PiiMatch> {
+    let value = value.trim();
+    if value.chars().count() < 3 {
+        return Vec::new();
+    }
+    let value_hash = pii_value_hash(kind, value);
+    text.match_indices(value)
+        .filter(|(start, _)| isolated(text, *start, start + value.len()))
+        .map(|(start, _)| PiiMatch {
+            kind,
+            detector: PiiDetector::Llm,
+            start,
+            end: start + value.len(),
+            value_hash: value_hash.clone(),
+        })
+        .collect()
+}
+
+/// Add `more` to `into`, skipping matches that overlap one already present,
+/// and keep `into` in text order.
+pub fn merge_pii_matches(into: &mut Vec<PiiMatch>, more: impl IntoIterator<Item = PiiMatch>) {
+    for candidate in more {
+        let overlaps = into
+            .iter()
+            .any(|m| candidate.start < m.end && m.start < candidate.end);
+        if !overlaps {
+            into.push(candidate);
+        }
+    }
+    into.sort_by_key(|m| m.start);
+}
+
+/// Replace each match in `text` with a `[redacted:<kind>]` marker.
+///
+/// `matches` must come from the same text and not overlap, as returned by
+/// [`scan_pii`] and [`merge_pii_matches`].
+pub fn mask_pii(text: &str, matches: &[PiiMatch]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+    for m in matches {
+        if m.start < pos || m.end > text.len() {
+            continue;
+        }
+        out.push_str(&text[pos..m.start]);
+        out.push_str("[redacted:");
+        out.push_str(m.kind.as_str());
+        out.push(']');
+        pos = m.end;
+    }
+    out.push_str(&text[pos..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<PiiKind> {
+        scan_pii(text).into_iter().map(|m| m.kind).collect()
+    }
+
+    #[test]
+    fn finds_each_kind() {
+        assert_eq!(kinds("write to Ada.L@Example.org"), vec![PiiKind::Email]);
+        assert_eq!(
+            kinds("card 4111 1111 1111 1111 on file"),
+            vec![PiiKind::CreditCard]
+        );
+        assert_eq!(kinds("SSN 123-45-6789"), vec![PiiKind::NationalId]);
+        assert_eq!(kinds("NI number AB 12 34 56 C"), vec![PiiKind::NationalId]);
+        assert_eq!(kinds("call (415) 555-2671"), vec![PiiKind::Phone]);
+        assert_eq!(kinds("call +44 20 7946 0958"), vec![PiiKind::Phone]);
+    }
+
+    #[test]
+    fn rejects_lookalikes() {
+        // Fails the Luhn check.
+        assert!(kinds("order 4111 1111 1111 1112").is_empty());
+        // Reserved SSN area.
+        assert!(kinds("ref 666-12-3456").is_empty());
+        // Dates, versions, and plain numbers.
+        assert!(kinds("released 2024-01-15 as v1.2.3, build 1234567").is_empty());
+    }
+
+    #[test]
+    fn card_wins_over_phone() {
+        let found = scan_pii("pay with 5500-0000-0000-0004 today");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, PiiKind::CreditCard);
+    }
+
+    #[test]
+    fn hash_ignores_formatting() {
+        assert_eq!(
+            pii_value_hash(PiiKind::Phone, "(415) 555-2671"),
+            pii_value_hash(PiiKind::Phone, "415.555.2671")
+        );
+        assert_eq!(
+            pii_value_hash(PiiKind::Email, "Ada@Example.org"),
+            pii_value_hash(PiiKind::Email, "ada@example.org")
+        );
+        assert_ne!(
+            pii_value_hash(PiiKind::Phone, "4155552671"),
+            pii_value_hash(PiiKind::CreditCard, "4155552671")
+        );
+    }
+
+    #[test]
+    fn locate_only_reports_values_in_text() {
+        let text = "Passport X1234567 issued to Ada.";
+        let found = locate_pii(text, PiiKind::NationalId, "X1234567");
+        assert_eq!(found.len(), 1);
+        assert_eq!(&text[found[0].start..found[0].end], "X1234567");
+        assert_eq!(found[0].detector, PiiDetector::Llm);
+        assert!(locate_pii(text, PiiKind::NationalId, "Y7654321").is_empty());
+    }
+
+    #[test]
+    fn mask_keeps_surrounding_text() {
+        let text = "é ada@example.com é";
+        let masked = mask_pii(text, &scan_pii(text));
+        assert_eq!(masked, "é [redacted:email] é");
+        // Masked text has nothing left to find.
+        assert!(scan_pii(&masked).is_empty());
+    }
+
+    #[test]
+    fn merge_skips_overlaps() {
+        let text = "call 415-555-2671";
+        let mut found = scan_pii(text);
+        merge_pii_matches(&mut found, locate_pii(text, PiiKind::Phone, "555-2671"));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].detector, PiiDetector::Regex);
+    }
+}
@@ -0,0 +1,156 @@
+//! Pinned and prioritised notes.
+//!
+//! A note can be pinned and given a priority (0-100) with
+//! `PATCH /api/v1/notes/{id}` or `PATCH /api/v1/notes/{id}/status`. Pinned
+//! notes are listed by `GET /api/v1/notes/pinned`, highest priority first,
+//! and search keeps a few places at the top of the results for pinned notes
+//! that match the query, so they are not pushed out by higher-scoring hits.
+//! Priority only orders pinned notes; starring is unrelated.
+//!
+//! ```
+//! use matric_core::pinned::validate_priority;
+//!
+//! assert!(validate_priority(0).is_ok());
+//! assert!(validate_priority(100).is_ok());
+//! assert!(validate_priority(101).is_err());
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// Highest note priority.
+pub const MAX_NOTE_PRIORITY: i32 = 100;
+
+/// Places at the top of search results kept for matching pinned notes,
+/// unless asked otherwise.
+pub const DEFAULT_PINNED_SLOTS: usize = 3;
+
+/// Most places a search may keep for pinned notes.
+pub const MAX_PINNED_SLOTS: usize = 10;
+
+/// Pinned notes listed unless asked otherwise.
+pub const DEFAULT_PINNED_LIMIT: i64 = 50;
+
+/// Most pinned notes a caller may list.
+pub const MAX_PINNED_LIMIT: i64 = 200;
+
+/// Which pinned notes to list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PinnedQuery {
+    /// Only notes filed in this collection
+    pub collection_id: Option<Uuid>,
+    pub limit: i64,
+}
+
+impl Default for PinnedQuery {
+    fn default() -> Self {
+        Self {
+            collection_id: None,
+            limit: DEFAULT_PINNED_LIMIT,
+        }
+    }
+}
+
+/// A pinned note, highest priority first in listings.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct PinnedNote {
+    pub note_id: Uuid,
+    pub title: Option<String>,
+    pub collection_id: Option<Uuid>,
+    pub priority: i32,
+    pub starred: bool,
+    pub archived: bool,
+    pub updated_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for PinnedNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PinnedNote")
+            .field("note_id", &self.note_id)
+            .field("title_present", &self.title.is_some())
+            .field("collection_id_set", &self.collection_id.is_some())
+            .field("priority", &self.priority)
+            .field("starred", &self.starred)
+            .field("archived", &self.archived)
+            .field("updated_at_utc", &self.updated_at_utc)
+            .finish()
+    }
+}
+
+/// Reject priorities outside `0..=MAX_NOTE_PRIORITY`.
+pub fn validate_priority(priority: i32) -> Result<()> {
+    if (0..=MAX_NOTE_PRIORITY).contains(&priority) {
+        Ok(())
+    } else {
+        Err(Error::InvalidInput(format!(
+            "priority must be between 0 and {MAX_NOTE_PRIORITY}"
+        )))
+    }
+}
+
+/// Move up to `slots` pinned items to the front of `items`, highest
+/// priority first and otherwise in their current order. `priorities` holds
+/// the priority of every pinned item; items not in it keep their relative
+/// order behind the promoted ones.
+pub fn promote_pinned<T>(
+    items: &mut Vec<T>,
+    id: impl Fn(&T) -> Uuid,
+    priorities: &std::collections::HashMap<Uuid, i32>,
+    slots: usize,
+) {
+    if slots == 0 || priorities.is_empty() {
+        return;
+    }
+    let mut pinned: Vec<(usize, i32)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(pos, item)| priorities.get(&id(item)).map(|&p| (pos, p)))
+        .collect();
+    // Stable sort keeps rank order among equal priorities.
+    pinned.sort_by_key(|&(_, priority)| std::cmp::Reverse(priority));
+    pinned.truncate(slots);
+    if pinned.is_empty() {
+        return;
+    }
+
+    let mut taken: Vec<Option<T>> = std::mem::take(items).into_iter().map(Some).collect();
+    for &(pos, _) in &pinned {
+        if let Some(item) = taken[pos].take() {
+            items.push(item);
+        }
+    }
+    items.extend(taken.into_iter().flatten());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn pinned_items_move_to_the_front_by_priority() {
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let mut items = ids.clone();
+        let priorities = HashMap::from([(ids[3], 10), (ids[4], 50), (ids[1], 10)]);
+
+        promote_pinned(&mut items, |id| *id, &priorities, 2);
+
+        // ids[4] has the highest priority; ids[1] outranks ids[3] at equal priority.
+        assert_eq!(items, vec![ids[4], ids[1], ids[0], ids[2], ids[3]]);
+    }
+
+    #[test]
+    fn promotion_without_slots_or_pins_keeps_order() {
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        let mut items = ids.clone();
+        promote_pinned(&mut items, |id| *id, &HashMap::from([(ids[2], 1)]), 0);
+        promote_pinned(&mut items, |id| *id, &HashMap::new(), 3);
+        promote_pinned(&mut items, |id| *id, &HashMap::from([(Uuid::nil(), 1)]), 3);
+        assert_eq!(items, ids);
+    }
+}
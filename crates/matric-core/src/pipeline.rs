@@ -0,0 +1,349 @@
+//! Job pipelines: dependency graphs of job types queued together for a note.
+//!
+//! A [`PipelineDefinition`] lists steps and the steps each one waits for. It
+//! is queued as a pipeline run: one job per step, with a dependency edge for
+//! every `depends_on` entry. The queue only claims a job once all of its
+//! dependencies have completed, cancels dependents of a job that fails, and
+//! can re-run the failed part of a run without repeating completed steps.
+//!
+//! The built-in `nlp` definition also orders ad-hoc jobs: a job queued for a
+//! note waits for pending or running jobs of its upstream types on that note,
+//! so embedding never starts before AI revision of the same note finishes.
+//!
+//! ```
+//! use matric_core::pipeline::PipelineDefinition;
+//! use matric_core::JobType;
+//!
+//! let nlp = PipelineDefinition::nlp();
+//! let order = nlp.topological_order().unwrap();
+//! let position = |job_type| order.iter().position(|t| *t == job_type).unwrap();
+//! assert!(position(JobType::AiRevision) < position(JobType::Embedding));
+//! assert!(nlp.upstream_of(JobType::Embedding).contains(&JobType::AiRevision));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, JobStatus, JobType, Result};
+
+/// Name of the built-in note-processing pipeline.
+pub const NLP_PIPELINE: &str = "nlp";
+
+/// Failure code recorded on jobs cancelled because a dependency failed.
+pub const UPSTREAM_FAILED_CODE: &str = "upstream_failed";
+
+/// Retries granted to each job returned to pending by a re-run, matching the
+/// queue's default `max_retries`.
+pub const RERUN_RETRY_BUDGET: i32 = 3;
+
+/// Upper bound on steps in a caller-supplied definition.
+pub const MAX_PIPELINE_STEPS: usize = 32;
+
+/// One step of a pipeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PipelineStep {
+    #[schema(value_type = String)]
+    pub job_type: JobType,
+    /// Steps that must complete before this one starts.
+    #[serde(default)]
+    #[schema(value_type = Vec<String>)]
+    pub depends_on: Vec<JobType>,
+}
+
+impl PipelineStep {
+    fn new(job_type: JobType, depends_on: &[JobType]) -> Self {
+        Self {
+            job_type,
+            depends_on: depends_on.to_vec(),
+        }
+    }
+}
+
+/// A named graph of job types.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PipelineDefinition {
+    pub name: String,
+    pub steps: Vec<PipelineStep>,
+}
+
+impl PipelineDefinition {
+    /// The note-processing pipeline: AI revision feeds concept tagging, related
+    /// concept inference, embedding and linking in order, while title, reference,
+    /// metadata, document-type, entity, citation and task extraction,
+    /// summarization, translation and PII scanning run independently.
+    pub fn nlp() -> Self {
+        Self {
+            name: NLP_PIPELINE.to_string(),
+            steps: vec![
+                PipelineStep::new(JobType::AiRevision, &[]),
+                PipelineStep::new(JobType::TitleGeneration, &[]),
+                PipelineStep::new(JobType::ReferenceExtraction, &[]),
+                PipelineStep::new(JobType::MetadataExtraction, &[]),
+                PipelineStep::new(JobType::DocumentTypeInference, &[]),
+                PipelineStep::new(JobType::Summarization, &[]),
+                PipelineStep::new(JobType::EntityExtraction, &[]),
+                PipelineStep::new(JobType::Translation, &[]),
+                PipelineStep::new(JobType::PiiScan, &[]),
+                PipelineStep::new(JobType::CitationExtraction, &[]),
+                PipelineStep::new(JobType::TaskExtraction, &[]),
+                PipelineStep::new(JobType::ConceptTagging, &[JobType::AiRevision]),
+                PipelineStep::new(JobType::RelatedConceptInference, &[JobType::ConceptTagging]),
+                PipelineStep::new(JobType::Embedding, &[JobType::RelatedConceptInference]),
+                PipelineStep::new(JobType::Linking, &[JobType::Embedding]),
+            ],
+        }
+    }
+
+    /// Look up a built-in definition by name.
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            NLP_PIPELINE => Some(Self::nlp()),
+            _ => None,
+        }
+    }
+
+    fn step(&self, job_type: JobType) -> Option<&PipelineStep> {
+        self.steps.iter().find(|step| step.job_type == job_type)
+    }
+
+    /// Check the definition and return its steps in dependency order.
+    ///
+    /// Rejects empty or oversized definitions, invalid names, repeated steps,
+    /// dependencies on steps that are not in the definition, and cycles.
+    pub fn topological_order(&self) -> Result<Vec<JobType>> {
+        let mut name_chars = self.name.chars();
+        let valid_name = self.name.len() <= 64
+            && matches!(name_chars.next(), Some('a'..='z'))
+            && name_chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid_name {
+            return Err(Error::InvalidInput(
+                "Pipeline name must be a lowercase identifier of at most 64 characters".to_string(),
+            ));
+        }
+        if self.steps.is_empty() || self.steps.len() > MAX_PIPELINE_STEPS {
+            return Err(Error::InvalidInput(format!(
+                "Pipeline must have between 1 and {MAX_PIPELINE_STEPS} steps"
+            )));
+        }
+
+        let mut remaining: HashMap<JobType, usize> = HashMap::new();
+        for step in &self.steps {
+            if remaining.insert(step.job_type, 0).is_some() {
+                return Err(Error::InvalidInput(format!(
+                    "Pipeline step {} appears more than once",
+                    step.job_type.as_str()
+                )));
+            }
+        }
+        for step in &self.steps {
+            let unique: HashSet<JobType> = step.depends_on.iter().copied().collect();
+            for dependency in &unique {
+                if !remaining.contains_key(dependency) {
+                    return Err(Error::InvalidInput(format!(
+                        "Pipeline step {} depends on {}, which is not a step",
+                        step.job_type.as_str(),
+                        dependency.as_str()
+                    )));
+                }
+            }
+            remaining.insert(step.job_type, unique.len());
+        }
+
+        // Kahn's algorithm in definition order so the result is stable.
+        let mut order = Vec::with_capacity(self.steps.len());
+        while order.len() < self.steps.len() {
+            let Some(ready) = self
+                .steps
+                .iter()
+                .map(|step| step.job_type)
+                .find(|job_type| remaining.get(job_type) == Some(&0))
+            else {
+                return Err(Error::InvalidInput(
+                    "Pipeline steps form a dependency cycle".to_string(),
+                ));
+            };
+            remaining.remove(&ready);
+            order.push(ready);
+            for step in &self.steps {
+                if step.depends_on.contains(&ready) {
+                    if let Some(count) = remaining.get_mut(&step.job_type) {
+                        *count -= 1;
+                    }
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// Every step `job_type` transitively waits for. Empty when the type is not
+    /// a step of this definition.
+    pub fn upstream_of(&self, job_type: JobType) -> Vec<JobType> {
+        let mut upstream = Vec::new();
+        let mut frontier = vec![job_type];
+        while let Some(current) = frontier.pop() {
+            let Some(step) = self.step(current) else {
+                continue;
+            };
+            for dependency in &step.depends_on {
+                if *dependency != job_type && !upstream.contains(dependency) {
+                    upstream.push(*dependency);
+                    frontier.push(*dependency);
+                }
+            }
+        }
+        upstream
+    }
+}
+
+/// One job of a pipeline run.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PipelineRunJob {
+    pub job_id: Uuid,
+    #[schema(value_type = String)]
+    pub job_type: JobType,
+    #[schema(value_type = String)]
+    pub status: JobStatus,
+    /// Jobs in this run that must complete first.
+    pub depends_on: Vec<Uuid>,
+    pub failure_code: Option<String>,
+}
+
+/// Overall state of a pipeline run, derived from its jobs. A failed run has a
+/// failed or cancelled job and can be re-run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineRunStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A pipeline queued for a note, with its jobs.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PipelineRun {
+    pub id: Uuid,
+    pub pipeline: String,
+    pub note_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub status: PipelineRunStatus,
+    pub jobs: Vec<PipelineRunJob>,
+}
+
+impl PipelineRunStatus {
+    /// Summarize job statuses. A run is running while any job runs or while
+    /// some steps have completed and others are still pending.
+    pub fn from_jobs<'a>(statuses: impl IntoIterator<Item = &'a JobStatus>) -> Self {
+        let (mut pending, mut running, mut completed, mut failed) = (false, false, false, false);
+        for status in statuses {
+            match status {
+                JobStatus::Pending => pending = true,
+                JobStatus::Running => running = true,
+                JobStatus::Completed => completed = true,
+                JobStatus::Failed | JobStatus::Cancelled => failed = true,
+            }
+        }
+        if running || (pending && completed && !failed) {
+            Self::Running
+        } else if failed {
+            Self::Failed
+        } else if pending {
+            Self::Pending
+        } else {
+            Self::Completed
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nlp_pipeline_orders_revision_before_embedding_and_linking() {
+        let order = PipelineDefinition::nlp().topological_order().unwrap();
+        let position = |job_type| order.iter().position(|t| *t == job_type).unwrap();
+        assert!(position(JobType::AiRevision) < position(JobType::ConceptTagging));
+        assert!(position(JobType::ConceptTagging) < position(JobType::Embedding));
+        assert!(position(JobType::Embedding) < position(JobType::Linking));
+        assert_eq!(order.len(), PipelineDefinition::nlp().steps.len());
+    }
+
+    #[test]
+    fn upstream_is_transitive_and_empty_for_roots_and_unknown_types() {
+        let nlp = PipelineDefinition::nlp();
+        let upstream = nlp.upstream_of(JobType::Linking);
+        for job_type in [
+            JobType::AiRevision,
+            JobType::ConceptTagging,
+            JobType::RelatedConceptInference,
+            JobType::Embedding,
+        ] {
+            assert!(upstream.contains(&job_type));
+        }
+        assert!(!upstream.contains(&JobType::TitleGeneration));
+        assert!(nlp.upstream_of(JobType::AiRevision).is_empty());
+        assert!(nlp.upstream_of(JobType::GraphMaintenance).is_empty());
+    }
+
+    #[test]
+    fn invalid_definitions_are_rejected() {
+        let cycle = PipelineDefinition {
+            name: "loop".to_string(),
+            steps: vec![
+                PipelineStep::new(JobType::Embedding, &[JobType::Linking]),
+                PipelineStep::new(JobType::Linking, &[JobType::Embedding]),
+            ],
+        };
+        assert!(cycle.topological_order().is_err());
+
+        let missing = PipelineDefinition {
+            name: "missing".to_string(),
+            steps: vec![PipelineStep::new(JobType::Linking, &[JobType::Embedding])],
+        };
+        assert!(missing.topological_order().is_err());
+
+        let duplicate = PipelineDefinition {
+            name: "duplicate".to_string(),
+            steps: vec![
+                PipelineStep::new(JobType::Embedding, &[]),
+                PipelineStep::new(JobType::Embedding, &[]),
+            ],
+        };
+        assert!(duplicate.topological_order().is_err());
+
+        let bad_name = PipelineDefinition {
+            name: "Bad Name".to_string(),
+            steps: vec![PipelineStep::new(JobType::Embedding, &[])],
+        };
+        assert!(bad_name.topological_order().is_err());
+    }
+
+    #[test]
+    fn run_status_summarizes_jobs() {
+        use JobStatus::*;
+        assert_eq!(
+            PipelineRunStatus::from_jobs(&[Completed, Completed]),
+            PipelineRunStatus::Completed
+        );
+        assert_eq!(
+            PipelineRunStatus::from_jobs(&[Completed, Pending]),
+            PipelineRunStatus::Running
+        );
+        assert_eq!(
+            PipelineRunStatus::from_jobs(&[Pending, Pending]),
+            PipelineRunStatus::Pending
+        );
+        assert_eq!(
+            PipelineRunStatus::from_jobs(&[Running, Cancelled]),
+            PipelineRunStatus::Running
+        );
+        assert_eq!(
+            PipelineRunStatus::from_jobs(&[Completed, Failed, Cancelled]),
+            PipelineRunStatus::Failed
+        );
+    }
+}
@@ -0,0 +1,634 @@
+//! Prompt templates for background AI jobs.
+//!
+//! Each job prompt is identified by a [`PromptKey`] and ships with a built-in
+//! template. Operators can store new versions globally or for a single
+//! archive; jobs use the newest archive version, then the newest global
+//! version, then the built-in.
+//!
+//! Templates reference job inputs as `{{name}}` placeholders. Only the
+//! variables a key declares may appear, and required ones must. Anything else
+//! in braces (such as JSON examples) is left as written. Substitution is a
+//! single pass, so placeholder syntax inside substituted values is never
+//! expanded.
+//!
+//! ```
+//! use matric_core::{render_prompt, PromptKey};
+//!
+//! let prompt = render_prompt(
+//!     PromptKey::TitleGeneration,
+//!     "Title for: {{content}}",
+//!     &[("content", "notes on {{content}}")],
+//! )
+//! .unwrap();
+//! assert_eq!(prompt, "Title for: notes on {{content}}");
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// Maximum stored template length in bytes.
+pub const MAX_PROMPT_TEMPLATE_BYTES: usize = 32 * 1024;
+
+/// Maximum description length in characters.
+pub const MAX_PROMPT_DESCRIPTION_CHARS: usize = 500;
+
+/// A job prompt that can be overridden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PromptKey {
+    /// Title generation for untitled notes.
+    TitleGeneration,
+    /// SKOS concept tag suggestion.
+    ConceptTagging,
+    /// Phase 2 revision with related-note reference context.
+    ContextualRevision,
+    /// "Related Context" section added after linking.
+    ContextUpdate,
+    /// Semantic link type classification.
+    LinkClassification,
+    /// Summary of a note, or of one chunk of a long note.
+    Summarization,
+    /// Combining chunk summaries into one summary.
+    SummaryReduce,
+    /// Named entity extraction from a note or one chunk of it.
+    EntityExtraction,
+    /// Digest of the notes created or updated during a period.
+    Digest,
+    /// Name for a cluster of related notes found by topic modeling.
+    TopicNaming,
+    /// Question-answer pairs drawn from a note for a fine-tuning dataset.
+    QaGeneration,
+    /// Translation of a note, or of one chunk of a long note.
+    Translation,
+    /// Personal data the pattern scan may miss, for the PII scan job.
+    PiiDetection,
+    /// Narrower concepts to split a broad concept's notes into.
+    ConceptSplit,
+    /// Action items written as prose, for the task extraction job.
+    TaskExtraction,
+}
+
+/// A placeholder a prompt template may use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+pub struct PromptVariable {
+    pub name: &'static str,
+    /// Whether every template for the key must reference the variable
+    pub required: bool,
+}
+
+const fn required(name: &'static str) -> PromptVariable {
+    PromptVariable {
+        name,
+        required: true,
+    }
+}
+
+const fn optional(name: &'static str) -> PromptVariable {
+    PromptVariable {
+        name,
+        required: false,
+    }
+}
+
+const TITLE_GENERATION_VARIABLES: &[PromptVariable] = &[required("content")];
+const CONCEPT_TAGGING_VARIABLES: &[PromptVariable] =
+    &[required("content"), optional("context_hint")];
+const CONTEXTUAL_REVISION_VARIABLES: &[PromptVariable] = &[
+    required("primary_content"),
+    required("reference_context"),
+    optional("continuity"),
+    optional("type_hint"),
+];
+const CONTEXT_UPDATE_VARIABLES: &[PromptVariable] =
+    &[required("note_content"), required("related_notes")];
+const LINK_CLASSIFICATION_VARIABLES: &[PromptVariable] = &[
+    required("source_excerpt"),
+    required("target_excerpt"),
+    optional("source_title"),
+    optional("target_title"),
+    optional("similarity"),
+];
+const SUMMARIZATION_VARIABLES: &[PromptVariable] = &[required("content"), optional("title_hint")];
+const SUMMARY_REDUCE_VARIABLES: &[PromptVariable] =
+    &[required("summaries"), optional("title_hint")];
+const ENTITY_EXTRACTION_VARIABLES: &[PromptVariable] = &[required("content")];
+const DIGEST_VARIABLES: &[PromptVariable] = &[required("notes"), optional("period")];
+const TOPIC_NAMING_VARIABLES: &[PromptVariable] = &[required("notes")];
+const QA_GENERATION_VARIABLES: &[PromptVariable] = &[required("content"), required("count")];
+const TRANSLATION_VARIABLES: &[PromptVariable] = &[
+    required("content"),
+    required("target_language"),
+    optional("source_language"),
+];
+const PII_DETECTION_VARIABLES: &[PromptVariable] = &[required("content")];
+const CONCEPT_SPLIT_VARIABLES: &[PromptVariable] = &[
+    required("concept"),
+    required("notes"),
+    optional("max_groups"),
+];
+const TASK_EXTRACTION_VARIABLES: &[PromptVariable] = &[required("content"), optional("today")];
+
+impl PromptKey {
+    /// Every prompt key, in display order.
+    pub const ALL: [PromptKey; 15] = [
+        PromptKey::TitleGeneration,
+        PromptKey::ConceptTagging,
+        PromptKey::ContextualRevision,
+        PromptKey::ContextUpdate,
+        PromptKey::LinkClassification,
+        PromptKey::Summarization,
+        PromptKey::SummaryReduce,
+        PromptKey::EntityExtraction,
+        PromptKey::Digest,
+        PromptKey::TopicNaming,
+        PromptKey::QaGeneration,
+        PromptKey::Translation,
+        PromptKey::PiiDetection,
+        PromptKey::ConceptSplit,
+        PromptKey::TaskExtraction,
+    ];
+
+    /// Stable identifier used in storage and the API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PromptKey::TitleGeneration => "title_generation",
+            PromptKey::ConceptTagging => "concept_tagging",
+            PromptKey::ContextualRevision => "contextual_revision",
+            PromptKey::ContextUpdate => "context_update",
+            PromptKey::LinkClassification => "link_classification",
+            PromptKey::Summarization => "summarization",
+            PromptKey::SummaryReduce => "summary_reduce",
+            PromptKey::EntityExtraction => "entity_extraction",
+            PromptKey::Digest => "digest",
+            PromptKey::TopicNaming => "topic_naming",
+            PromptKey::QaGeneration => "qa_generation",
+            PromptKey::Translation => "translation",
+            PromptKey::PiiDetection => "pii_detection",
+            PromptKey::ConceptSplit => "concept_split",
+            PromptKey::TaskExtraction => "task_extraction",
+        }
+    }
+
+    /// Parse a key from its identifier.
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|key| key.as_str() == value)
+    }
+
+    /// Placeholders the key's templates may use.
+    pub fn variables(&self) -> &'static [PromptVariable] {
+        match self {
+            PromptKey::TitleGeneration => TITLE_GENERATION_VARIABLES,
+            PromptKey::ConceptTagging => CONCEPT_TAGGING_VARIABLES,
+            PromptKey::ContextualRevision => CONTEXTUAL_REVISION_VARIABLES,
+            PromptKey::ContextUpdate => CONTEXT_UPDATE_VARIABLES,
+            PromptKey::LinkClassification => LINK_CLASSIFICATION_VARIABLES,
+            PromptKey::Summarization => SUMMARIZATION_VARIABLES,
+            PromptKey::SummaryReduce => SUMMARY_REDUCE_VARIABLES,
+            PromptKey::EntityExtraction => ENTITY_EXTRACTION_VARIABLES,
+            PromptKey::Digest => DIGEST_VARIABLES,
+            PromptKey::TopicNaming => TOPIC_NAMING_VARIABLES,
+            PromptKey::QaGeneration => QA_GENERATION_VARIABLES,
+            PromptKey::Translation => TRANSLATION_VARIABLES,
+            PromptKey::PiiDetection => PII_DETECTION_VARIABLES,
+            PromptKey::ConceptSplit => CONCEPT_SPLIT_VARIABLES,
+            PromptKey::TaskExtraction => TASK_EXTRACTION_VARIABLES,
+        }
+    }
+
+    /// Template used when no override is stored.
+    pub fn builtin(&self) -> &'static str {
+        match self {
+            PromptKey::TitleGeneration => BUILTIN_TITLE_GENERATION,
+            PromptKey::ConceptTagging => BUILTIN_CONCEPT_TAGGING,
+            PromptKey::ContextualRevision => BUILTIN_CONTEXTUAL_REVISION,
+            PromptKey::ContextUpdate => BUILTIN_CONTEXT_UPDATE,
+            PromptKey::LinkClassification => BUILTIN_LINK_CLASSIFICATION,
+            PromptKey::Summarization => BUILTIN_SUMMARIZATION,
+            PromptKey::SummaryReduce => BUILTIN_SUMMARY_REDUCE,
+            PromptKey::EntityExtraction => BUILTIN_ENTITY_EXTRACTION,
+            PromptKey::Digest => BUILTIN_DIGEST,
+            PromptKey::TopicNaming => BUILTIN_TOPIC_NAMING,
+            PromptKey::QaGeneration => BUILTIN_QA_GENERATION,
+            PromptKey::Translation => BUILTIN_TRANSLATION,
+            PromptKey::PiiDetection => BUILTIN_PII_DETECTION,
+            PromptKey::ConceptSplit => BUILTIN_CONCEPT_SPLIT,
+            PromptKey::TaskExtraction => BUILTIN_TASK_EXTRACTION,
+        }
+    }
+}
+
+impl fmt::Display for PromptKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A split of a template into literal text and placeholders.
+enum Segment<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+}
+
+/// Split `template` at `{{name}}` placeholders, where `name` is lowercase
+/// ASCII letters, digits, and underscores. Other braces stay literal.
+fn segments(template: &str) -> Vec<Segment<'_>> {
+    let mut out = Vec::new();
+    let mut rest = template;
+    let mut literal_start = 0;
+    let mut offset = 0;
+    while let Some(open) = rest.find("{{") {
+        let after = &rest[open + 2..];
+        let name_len = after
+            .bytes()
+            .take_while(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || *b == b'_')
+            .count();
+        if name_len > 0 && after[name_len..].starts_with("}}") {
+            let start = offset + open;
+            if start > literal_start {
+                out.push(Segment::Text(&template[literal_start..start]));
+            }
+            out.push(Segment::Variable(&after[..name_len]));
+            let consumed = open + 2 + name_len + 2;
+            offset += consumed;
+            literal_start = offset;
+            rest = &rest[consumed..];
+        } else {
+            offset += open + 1;
+            rest = &rest[open + 1..];
+        }
+    }
+    if literal_start < template.len() {
+        out.push(Segment::Text(&template[literal_start..]));
+    }
+    out
+}
+
+/// Check a template against its key's declared variables.
+pub fn validate_prompt_template(key: PromptKey, template: &str) -> Result<()> {
+    if template.trim().is_empty() {
+        return Err(Error::InvalidInput(
+            "Prompt template must not be empty".to_string(),
+        ));
+    }
+    if template.len() > MAX_PROMPT_TEMPLATE_BYTES {
+        return Err(Error::InvalidInput(format!(
+            "Prompt template exceeds {MAX_PROMPT_TEMPLATE_BYTES} bytes"
+        )));
+    }
+
+    let variables = key.variables();
+    let used: Vec<&str> = segments(template)
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Variable(name) => Some(name),
+            Segment::Text(_) => None,
+        })
+        .collect();
+    if let Some(unknown) = used
+        .iter()
+        .find(|name| !variables.iter().any(|v| v.name == **name))
+    {
+        return Err(Error::InvalidInput(format!(
+            "Unknown variable {{{{{unknown}}}}} for prompt {key}"
+        )));
+    }
+    if let Some(missing) = variables
+        .iter()
+        .find(|v| v.required && !used.contains(&v.name))
+    {
+        return Err(Error::InvalidInput(format!(
+            "Prompt {key} must reference {{{{{}}}}}",
+            missing.name
+        )));
+    }
+    Ok(())
+}
+
+/// Validate `template` for `key` and substitute `values` into it.
+///
+/// Declared variables without a value render as empty text.
+pub fn render_prompt(key: PromptKey, template: &str, values: &[(&str, &str)]) -> Result<String> {
+    validate_prompt_template(key, template)?;
+    Ok(fill_prompt_template(template, values))
+}
+
+/// Substitute `values` into `template` without validating it.
+///
+/// Placeholders without a value render as empty text. Use [`render_prompt`]
+/// for stored templates; this is for templates already known to be valid,
+/// such as [`PromptKey::builtin`].
+pub fn fill_prompt_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    for segment in segments(template) {
+        match segment {
+            Segment::Text(text) => out.push_str(text),
+            Segment::Variable(name) => {
+                if let Some((_, value)) = values.iter().find(|(n, _)| *n == name) {
+                    out.push_str(value);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// A stored version of a prompt template.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PromptTemplate {
+    pub id: Uuid,
+    /// Prompt key, e.g. `title_generation`
+    pub key: String,
+    /// Archive schema the version applies to; global when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_name: Option<String>,
+    /// Version number within the key and scope, starting at 1
+    pub version: i32,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for PromptTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PromptTemplate")
+            .field("id_set", &true)
+            .field("key", &self.key)
+            .field(
+                "schema_name_len",
+                &self.schema_name.as_ref().map(String::len),
+            )
+            .field("version", &self.version)
+            .field("content_len", &self.content.len())
+            .field(
+                "description_len",
+                &self.description.as_ref().map(String::len),
+            )
+            .finish()
+    }
+}
+
+/// Request body for storing a new prompt template version.
+#[derive(Clone, Deserialize, utoipa::ToSchema)]
+pub struct SavePromptTemplateRequest {
+    pub content: String,
+    pub description: Option<String>,
+}
+
+impl SavePromptTemplateRequest {
+    /// Validates the request against the key's variables.
+    pub fn validate(&self, key: PromptKey) -> Result<()> {
+        validate_prompt_template(key, &self.content)?;
+        if self
+            .description
+            .as_ref()
+            .is_some_and(|d| d.chars().count() > MAX_PROMPT_DESCRIPTION_CHARS)
+        {
+            return Err(Error::InvalidInput(format!(
+                "Description exceeds {MAX_PROMPT_DESCRIPTION_CHARS} characters"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for SavePromptTemplateRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SavePromptTemplateRequest")
+            .field("content_len", &self.content.len())
+            .field(
+                "description_len",
+                &self.description.as_ref().map(String::len),
+            )
+            .finish()
+    }
+}
+
+const BUILTIN_TITLE_GENERATION: &str = r#"Generate a concise, descriptive title (3-8 words) for this content. Be specific. Avoid generic words like "Note", "Document", "Text". Output only the title, no quotes or explanation.
+
+Content:
+{{content}}"#;
+
+const BUILTIN_CONCEPT_TAGGING: &str = r#"You are a knowledge organization specialist using SKOS (Simple Knowledge Organization System). Analyze the following content and suggest concept tags organized as hierarchical paths across MULTIPLE dimensions.
+
+{{context_hint}}Content:
+{{content}}
+
+REQUIRED DIMENSIONS (include at least one tag from each applicable dimension):
+1. **Domain**: Primary subject area (e.g., "science/machine-learning", "engineering/software")
+2. **Topic**: Specific topics covered (e.g., "nlp/transformers", "databases/vector-search")
+3. **Methodology**: Research/work methodology (e.g., "methodology/experimental", "methodology/survey", "methodology/case-study")
+4. **Application**: Practical applications (e.g., "application/healthcare", "application/search-engines")
+5. **Technique**: Specific techniques used (e.g., "technique/attention-mechanism", "technique/reinforcement-learning")
+6. **Content-type**: What kind of content (e.g., "content-type/research-paper", "content-type/tutorial", "content-type/documentation")
+
+OPTIONAL DIMENSIONS (include if clearly applicable):
+7. **Evaluation**: How results are evaluated (e.g., "evaluation/benchmark", "evaluation/ablation-study")
+8. **Tool/Framework**: Specific tools mentioned (e.g., "tool/pytorch", "tool/postgresql")
+9. **Era/Context**: Temporal context (e.g., "era/foundation-models", "era/pre-transformer")
+
+Guidelines:
+1. Use hierarchical paths with "/" separators
+2. Use 1-2 levels of hierarchy. Top level = dimension/domain, leaf = specific concept
+3. Use kebab-case for multi-word terms
+4. Focus on actual subject matter, not generic terms
+5. Order by relevance (most relevant first)
+6. Reuse top-level categories across notes for cross-cutting queries
+7. Aim for 5-8 tags total — breadth across dimensions is more valuable than depth in one
+8. PREFER reusing existing concept paths from the knowledge base when they are relevant
+
+Output ONLY a JSON array of tag paths, nothing else. Example:
+["science/machine-learning", "nlp/transformers", "technique/attention-mechanism", "methodology/experimental", "evaluation/benchmark", "application/translation", "tool/pytorch", "content-type/research-paper"]"#;
+
+const BUILTIN_CONTEXTUAL_REVISION: &str = r#"You are an intelligent note-taking assistant performing a contextual revision.
+{{continuity}}{{type_hint}}
+## PRIMARY CONTENT (this is the note you are revising — your output MUST be a revision of this):
+{{primary_content}}
+
+## REFERENCE CONTEXT (supplementary only — use ONLY if directly relevant to the primary content):
+{{reference_context}}
+
+STRICT RULES:
+1. Your output MUST be a revision of the PRIMARY CONTENT section above
+2. NEVER replace or override the primary content with reference material
+3. Reference context is supplementary — mention connections ONLY when they genuinely clarify the primary content
+4. If no reference items are relevant to the primary content, output the primary content unchanged
+5. Preserve ALL original meaning and information from the primary content
+6. Do NOT fabricate cross-references that are not genuinely supported by the reference context
+
+What you MAY do:
+- Note genuine connections between the primary content and reference items
+- Add brief contextual annotations where a reference item directly relates
+- Improve organization if the connection adds clarity
+
+Output the revised note in clean markdown format. Do not add any labels, markers, or metadata."#;
+
+const BUILTIN_CONTEXT_UPDATE: &str = r#"You have an enhanced note that has been linked to related notes. Add a 'Related Context' section at the end that briefly mentions the connections.
+
+Current Enhanced Note:
+{{note_content}}
+
+Related Notes Found:
+{{related_notes}}
+
+Add a brief '## Related Context' section at the end that mentions these connections naturally.
+Keep it concise (2-3 sentences). Output the full note with the new section added."#;
+
+const BUILTIN_LINK_CLASSIFICATION: &str = r#"Classify the semantic relationship between these two notes.
+
+Source Note: "{{source_title}}"
+Excerpt: {{source_excerpt}}
+
+Target Note: "{{target_title}}"
+Excerpt: {{target_excerpt}}
+
+Similarity Score: {{similarity}}
+
+Classify the relationship type:
+- SUPPORTS: Target provides evidence or supports source claims
+- CONTRADICTS: Target refutes or contradicts source
+- EXTENDS: Target builds upon or extends source concepts
+- IMPLEMENTS: Target applies or implements source theory/ideas
+- REFERENCES: Target is cited or referenced by source
+- RELATED: Generic topical relationship (default)
+
+Respond with ONLY a JSON object, for example:
+{"link_type": "extends", "confidence": 0.8, "reasoning": "<brief explanation>"}
+where link_type is one of "supports", "contradicts", "extends", "implements", "references", or "related"."#;
+
+const BUILTIN_SUMMARIZATION: &str = r#"Summarize the following content in one to three short paragraphs. Keep the key facts, decisions, names, and numbers. Do not add information that is not in the content. Output only the summary, with no heading or preamble.
+
+{{title_hint}}Content:
+{{content}}"#;
+
+const BUILTIN_SUMMARY_REDUCE: &str = r#"The following are summaries of consecutive sections of one document. Combine them into a single summary of the whole document in one to three short paragraphs. Keep the key facts, decisions, names, and numbers, remove repetition, and do not add information that is not in the summaries. Output only the summary, with no heading or preamble.
+
+{{title_hint}}Section summaries:
+{{summaries}}"#;
+
+const BUILTIN_ENTITY_EXTRACTION: &str = r#"Extract the named entities mentioned in the following content: people, organizations, places, and dates. Only include entities the content actually names; skip pronouns, generic nouns, and relative times such as "yesterday". Write each name as it appears in the content.
+
+Respond with ONLY a JSON array of objects with "name" and "type", where type is one of "person", "organization", "location", or "date", for example:
+[{"name": "Ada Lovelace", "type": "person"}, {"name": "London", "type": "location"}, {"name": "10 December 1815", "type": "date"}]
+Respond with [] if the content names no entities.
+
+Content:
+{{content}}"#;
+
+const BUILTIN_DIGEST: &str = r#"Write a digest of the notes below, which were created or updated {{period}}. Open with two or three sentences on the main themes, then list the most important developments, decisions, and open questions as short bullet points. Mention notes by title. Do not add information that is not in the notes. Output only the digest in Markdown, with no top-level heading or preamble.
+
+Notes:
+{{notes}}"#;
+
+const BUILTIN_TOPIC_NAMING: &str = r#"The notes below were grouped together because they are about the same topic. Name the topic in two to five words, specific enough to tell it apart from other topics in the same collection of notes. Use a noun phrase in title case, such as "Home Renovation" or "Rust Async Runtimes".
+
+Respond with ONLY the topic name, with no quotes, punctuation, or explanation.
+
+Notes:
+{{notes}}"#;
+
+const BUILTIN_QA_GENERATION: &str = r#"Write up to {{count}} question-answer pairs that someone could ask about the content below, for training an assistant on this knowledge base. Each question must make sense on its own, without seeing the content, and each answer must be fully supported by the content. Prefer questions about the most important facts, concepts, and procedures, and vary their kind.
+
+Rate each pair's quality from 1 (trivial or poorly supported) to 5 (useful and fully supported).
+
+Respond with ONLY a JSON array of objects with "question", "answer", "type", and "quality", where type is one of "factoid", "conceptual", "procedural", or "comparative", for example:
+[{"question": "How often are backups rotated?", "answer": "Backups are rotated weekly, keeping the last four.", "type": "factoid", "quality": 5}]
+Respond with [] if the content has nothing worth asking about.
+
+Content:
+{{content}}"#;
+
+const BUILTIN_TRANSLATION: &str = r#"Translate the following {{source_language}} content into {{target_language}}. Translate everything, keeping the meaning, tone, names, numbers, and Markdown formatting (headings, lists, links, code blocks) exactly as they are. Do not translate code, URLs, or file paths. Output only the translation, with no notes or preamble.
+
+Content:
+{{content}}"#;
+
+const BUILTIN_PII_DETECTION: &str = r#"Find personal data in the following content: email addresses, phone numbers, payment card numbers, and national identity numbers such as social security, national insurance, passport, or tax ID numbers. Only include values that identify a real person or account; skip example values, placeholders, and numbers such as order or version numbers. Write each value exactly as it appears in the content.
+
+Respond with ONLY a JSON array of objects with "value" and "type", where type is one of "email", "phone", "credit_card", or "national_id", for example:
+[{"value": "ada@example.org", "type": "email"}, {"value": "AB 12 34 56 C", "type": "national_id"}]
+Respond with [] if the content has no personal data.
+
+Content:
+{{content}}"#;
+
+const BUILTIN_CONCEPT_SPLIT: &str = r#"The notes below are all tagged with the concept "{{concept}}", which has become too broad. Group them into at most {{max_groups}} narrower concepts, each specific enough to stand on its own in a taxonomy. Name each group with a short noun phrase in title case, such as "Async Runtimes" or "Error Handling". Put every note in at most one group; leave out notes that fit no group well.
+
+Respond with ONLY a JSON array of objects with "label" and "notes", where notes lists the numbers of the notes in the group, for example:
+[{"label": "Async Runtimes", "notes": [1, 4]}, {"label": "Error Handling", "notes": [2, 3]}]
+
+Notes:
+{{notes}}"#;
+
+const BUILTIN_TASK_EXTRACTION: &str = r#"Find the action items in the following content: things the writer or someone else has said they will do, must do, or was asked to do. Skip Markdown checkboxes such as "- [ ] ...", which are already tracked, and skip tasks described as finished. Write each action item as a short imperative sentence, such as "Send the budget to Ana". Today is {{today}}; when the content gives a deadline, add it as a YYYY-MM-DD date.
+
+Respond with ONLY a JSON array of objects with "text" and "due", where due is a date or null, for example:
+[{"text": "Send the budget to Ana", "due": "2026-03-06"}, {"text": "Book a room for the offsite", "due": null}]
+Respond with [] if the content has no action items.
+
+Content:
+{{content}}"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtins_are_valid_for_their_keys() {
+        for key in PromptKey::ALL {
+            validate_prompt_template(key, key.builtin()).unwrap();
+            assert_eq!(PromptKey::parse(key.as_str()), Some(key));
+        }
+        assert_eq!(PromptKey::parse("unknown"), None);
+    }
+
+    #[test]
+    fn render_substitutes_once_and_keeps_other_braces() {
+        let template = r#"{"a": 1} {{content}} {{ content }} {{"x"}}"#;
+        let rendered = render_prompt(
+            PromptKey::TitleGeneration,
+            template,
+            &[("content", "{{content}}")],
+        )
+        .unwrap();
+        assert_eq!(rendered, r#"{"a": 1} {{content}} {{ content }} {{"x"}}"#);
+
+        let rendered = render_prompt(
+            PromptKey::ConceptTagging,
+            "{{context_hint}}[{{content}}]",
+            &[("content", "text")],
+        )
+        .unwrap();
+        assert_eq!(rendered, "[text]");
+    }
+
+    #[test]
+    fn validation_rejects_unknown_and_missing_variables() {
+        let unknown = validate_prompt_template(PromptKey::TitleGeneration, "{{content}} {{title}}");
+        assert!(matches!(unknown, Err(Error::InvalidInput(m)) if m.contains("{{title}}")));
+
+        let missing = validate_prompt_template(PromptKey::ContextUpdate, "{{note_content}}");
+        assert!(matches!(missing, Err(Error::InvalidInput(m)) if m.contains("{{related_notes}}")));
+
+        let empty = validate_prompt_template(PromptKey::TitleGeneration, "  ");
+        assert!(matches!(empty, Err(Error::InvalidInput(_))));
+
+        let oversized = format!("{{{{content}}}}{}", "x".repeat(MAX_PROMPT_TEMPLATE_BYTES));
+        assert!(validate_prompt_template(PromptKey::TitleGeneration, &oversized).is_err());
+    }
+
+    #[test]
+    fn save_request_limits_description() {
+        let req = SavePromptTemplateRequest {
+            content: "{{content}}".to_string(),
+            description: Some("d".repeat(MAX_PROMPT_DESCRIPTION_CHARS + 1)),
+        };
+        assert!(req.validate(PromptKey::TitleGeneration).is_err());
+        let debug = format!("{req:?}");
+        assert!(!debug.contains("{{content}}"));
+    }
+}
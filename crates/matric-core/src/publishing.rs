@@ -0,0 +1,701 @@
+//! Public, read-only sites built from published collections.
+//!
+//! A published collection is served without authentication under
+//! `/public/{slug}`. Only notes whose visibility is `public` appear, and
+//! only while they are not deleted or encrypted; pages are rendered from the
+//! current content on every request, so edits and visibility changes show
+//! up immediately.
+//!
+//! Before rendering, wiki links (`[[Title]]`, `[[Title|label]]`) become links
+//! to other published pages and transclusions (`![[Title]]`) are replaced by
+//! the transcluded page's content, up to [`MAX_TRANSCLUSION_DEPTH`] levels.
+//! Targets that are not published are shown as plain text, so a page never
+//! reveals content the site does not publish. Raw HTML in notes is escaped.
+//!
+//! ```
+//! use matric_core::publishing::{render_markdown_html, slugify};
+//!
+//! assert_eq!(slugify("Field Notes: 2026!"), "field-notes-2026");
+//! assert_eq!(
+//!     render_markdown_html("**hi** <script>x</script>"),
+//!     "<p><strong>hi</strong> &lt;script&gt;x&lt;/script&gt;</p>\n"
+//! );
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// Longest publication slug.
+pub const MAX_PUBLICATION_SLUG_LEN: usize = 80;
+
+/// Longest publication title.
+pub const MAX_PUBLICATION_TITLE_LEN: usize = 200;
+
+/// Longest publication description.
+pub const MAX_PUBLICATION_DESCRIPTION_LEN: usize = 2000;
+
+/// How many levels of transclusion are expanded.
+pub const MAX_TRANSCLUSION_DEPTH: usize = 3;
+
+/// Most pages a published site lists.
+pub const MAX_PUBLISHED_PAGES: i64 = 5000;
+
+/// Search results returned when no limit is given.
+pub const DEFAULT_PUBLISHED_SEARCH_LIMIT: i64 = 20;
+
+/// Most search results returned.
+pub const MAX_PUBLISHED_SEARCH_LIMIT: i64 = 100;
+
+/// Longest search query, in characters.
+pub const MAX_PUBLISHED_SEARCH_QUERY_CHARS: usize = 500;
+
+/// Title shown for a published note without one.
+pub const UNTITLED_PAGE: &str = "Untitled";
+
+static WIKI_REF_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(!?)\[\[([^\[\]|]+)(?:\|([^\[\]]+))?\]\]").expect("valid wiki link pattern")
+});
+
+/// Lowercase ASCII letters and digits of `name`, other runs replaced by a
+/// single `-`, cut to [`MAX_PUBLICATION_SLUG_LEN`].
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.truncate(MAX_PUBLICATION_SLUG_LEN);
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Trim a slug, rejecting one that is empty, too long, or not made of
+/// `a-z`, `0-9` and single inner `-`.
+pub fn validate_publication_slug(slug: &str) -> Result<String> {
+    let slug = slug.trim();
+    let valid = !slug.is_empty()
+        && slug.len() <= MAX_PUBLICATION_SLUG_LEN
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && !slug.contains("--");
+    if !valid {
+        return Err(Error::InvalidInput(format!(
+            "slug must be 1 to {MAX_PUBLICATION_SLUG_LEN} characters of a-z, 0-9 and single inner '-'"
+        )));
+    }
+    Ok(slug.to_string())
+}
+
+/// A published collection.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct Publication {
+    /// Path segment of the site: `/public/{slug}`
+    pub slug: String,
+    pub collection_id: Uuid,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether notes in nested collections are published too
+    pub include_subcollections: bool,
+    pub published_at_utc: DateTime<Utc>,
+    pub updated_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for Publication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Publication")
+            .field("slug_len", &self.slug.len())
+            .field("collection_id_set", &true)
+            .field("title_len", &self.title.len())
+            .field(
+                "description_len",
+                &self.description.as_ref().map(String::len),
+            )
+            .field("include_subcollections", &self.include_subcollections)
+            .finish()
+    }
+}
+
+/// Publish a collection, or change how it is published.
+#[derive(Clone, Default, Deserialize, utoipa::ToSchema)]
+pub struct PublishCollectionRequest {
+    /// Site path segment (default: derived from the collection name)
+    #[serde(default)]
+    pub slug: Option<String>,
+    /// Site title (default: the collection name)
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Also publish notes in nested collections (default: true)
+    #[serde(default)]
+    pub include_subcollections: Option<bool>,
+}
+
+impl fmt::Debug for PublishCollectionRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublishCollectionRequest")
+            .field("slug_len", &self.slug.as_ref().map(String::len))
+            .field("title_len", &self.title.as_ref().map(String::len))
+            .field(
+                "description_len",
+                &self.description.as_ref().map(String::len),
+            )
+            .field("include_subcollections", &self.include_subcollections)
+            .finish()
+    }
+}
+
+/// Validated publication settings.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PublicationSettings {
+    pub slug: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub include_subcollections: bool,
+}
+
+impl fmt::Debug for PublicationSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublicationSettings")
+            .field("slug_len", &self.slug.len())
+            .field("title_len", &self.title.len())
+            .field(
+                "description_len",
+                &self.description.as_ref().map(String::len),
+            )
+            .field("include_subcollections", &self.include_subcollections)
+            .finish()
+    }
+}
+
+impl PublishCollectionRequest {
+    /// The settings to publish with, defaulting slug and title from the
+    /// collection name.
+    pub fn settings(&self, collection_name: &str) -> Result<PublicationSettings> {
+        let slug = match &self.slug {
+            Some(slug) => validate_publication_slug(slug)?,
+            None => {
+                let slug = slugify(collection_name);
+                if slug.is_empty() {
+                    return Err(Error::InvalidInput(
+                        "collection name has no letters or digits to derive a slug from; give a slug"
+                            .to_string(),
+                    ));
+                }
+                slug
+            }
+        };
+        let title = self
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .unwrap_or(collection_name)
+            .to_string();
+        if title.chars().count() > MAX_PUBLICATION_TITLE_LEN {
+            return Err(Error::InvalidInput(format!(
+                "title must be at most {MAX_PUBLICATION_TITLE_LEN} characters"
+            )));
+        }
+        let description = self
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|description| !description.is_empty())
+            .map(str::to_string);
+        if description.as_ref().is_some_and(|description| {
+            description.chars().count() > MAX_PUBLICATION_DESCRIPTION_LEN
+        }) {
+            return Err(Error::InvalidInput(format!(
+                "description must be at most {MAX_PUBLICATION_DESCRIPTION_LEN} characters"
+            )));
+        }
+        Ok(PublicationSettings {
+            slug,
+            title,
+            description,
+            include_subcollections: self.include_subcollections.unwrap_or(true),
+        })
+    }
+}
+
+/// A page of a published site, as listed in its index.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct PublishedPageSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub updated_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for PublishedPageSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublishedPageSummary")
+            .field("title_len", &self.title.len())
+            .finish()
+    }
+}
+
+/// A published site and its pages, by title.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PublishedSite {
+    pub slug: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub pages: Vec<PublishedPageSummary>,
+}
+
+impl fmt::Debug for PublishedSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublishedSite")
+            .field("slug_len", &self.slug.len())
+            .field("title_len", &self.title.len())
+            .field("page_count", &self.pages.len())
+            .finish()
+    }
+}
+
+/// A published page with transclusions and wiki links resolved.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PublishedPage {
+    pub id: Uuid,
+    pub title: String,
+    /// Markdown after resolving transclusions and wiki links
+    pub markdown: String,
+    /// `markdown` rendered as HTML, raw HTML escaped
+    pub html: String,
+    pub updated_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for PublishedPage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublishedPage")
+            .field("title_len", &self.title.len())
+            .field("markdown_len", &self.markdown.len())
+            .field("html_len", &self.html.len())
+            .finish()
+    }
+}
+
+/// A page matching a published-site search.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct PublishedSearchHit {
+    pub id: Uuid,
+    pub title: String,
+    /// Plain-text excerpt around the matched terms
+    pub snippet: String,
+    pub score: f32,
+}
+
+impl fmt::Debug for PublishedSearchHit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublishedSearchHit")
+            .field("title_len", &self.title.len())
+            .field("snippet_len", &self.snippet.len())
+            .field("score", &self.score)
+            .finish()
+    }
+}
+
+/// Results of a published-site search, best first.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PublishedSearchResults {
+    pub query: String,
+    pub hits: Vec<PublishedSearchHit>,
+}
+
+impl fmt::Debug for PublishedSearchResults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublishedSearchResults")
+            .field("query_len", &self.query.len())
+            .field("hit_count", &self.hits.len())
+            .finish()
+    }
+}
+
+/// Resolves wiki link and transclusion targets — a page ID or a title,
+/// matched case-insensitively — against a site's pages.
+pub struct PublishedPageIndex<'a> {
+    by_key: HashMap<String, &'a PublishedPageSummary>,
+}
+
+impl<'a> PublishedPageIndex<'a> {
+    pub fn new(pages: &'a [PublishedPageSummary]) -> Self {
+        let mut by_key = HashMap::new();
+        for page in pages {
+            by_key.insert(page.id.to_string(), page);
+            by_key.entry(page.title.to_lowercase()).or_insert(page);
+        }
+        Self { by_key }
+    }
+
+    /// The published page a target names.
+    pub fn resolve(&self, target: &str) -> Option<&'a PublishedPageSummary> {
+        self.by_key.get(&target.trim().to_lowercase()).copied()
+    }
+}
+
+/// Lines outside fenced code blocks, as byte ranges of `markdown`.
+fn prose_ranges(markdown: &str) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut offset = 0;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let marker = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker));
+        match (fence, marker) {
+            (None, Some(marker)) => fence = Some(marker),
+            (Some(open), Some(marker)) if open == marker => fence = None,
+            (None, None) => ranges.push(offset..offset + line.len()),
+            _ => {}
+        }
+        offset += line.len();
+    }
+    ranges
+}
+
+/// Targets of the transclusions in `markdown`, outside code blocks.
+pub fn transclusion_targets(markdown: &str) -> Vec<String> {
+    let mut targets = Vec::new();
+    for range in prose_ranges(markdown) {
+        for captures in WIKI_REF_RE.captures_iter(&markdown[range]) {
+            let target = captures[2].trim().to_string();
+            if &captures[1] == "!" && !target.is_empty() && !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+    targets
+}
+
+/// Expand transclusions and turn wiki links into links to published pages.
+///
+/// `contents` holds the content of pages that may be transcluded, by ID. A
+/// target that is not a published page, or whose content is missing, is
+/// replaced by its label; a page transcluding itself, directly or through
+/// others, shows the label instead of recursing.
+pub fn resolve_published_markdown(
+    site_slug: &str,
+    markdown: &str,
+    page_id: Uuid,
+    index: &PublishedPageIndex<'_>,
+    contents: &HashMap<Uuid, String>,
+) -> String {
+    let href = |page: &PublishedPageSummary| format!("/public/{site_slug}/notes/{}", page.id);
+    resolve_wiki_markdown(markdown, page_id, index, contents, &href)
+}
+
+/// [`resolve_published_markdown`] with wiki links pointing at `href(page)`.
+pub fn resolve_wiki_markdown(
+    markdown: &str,
+    page_id: Uuid,
+    index: &PublishedPageIndex<'_>,
+    contents: &HashMap<Uuid, String>,
+    href: &dyn Fn(&PublishedPageSummary) -> String,
+) -> String {
+    let mut stack = HashSet::from([page_id]);
+    resolve_level(markdown, index, contents, href, &mut stack, 0)
+}
+
+fn resolve_level(
+    markdown: &str,
+    index: &PublishedPageIndex<'_>,
+    contents: &HashMap<Uuid, String>,
+    href: &dyn Fn(&PublishedPageSummary) -> String,
+    stack: &mut HashSet<Uuid>,
+    depth: usize,
+) -> String {
+    let mut output = String::with_capacity(markdown.len());
+    let mut copied = 0;
+    for range in prose_ranges(markdown) {
+        let segment = &markdown[range.clone()];
+        for captures in WIKI_REF_RE.captures_iter(segment) {
+            let whole = captures.get(0).expect("match");
+            let target = captures[2].trim();
+            let label = captures
+                .get(3)
+                .map_or(target, |label| label.as_str().trim());
+            let page = index.resolve(target);
+            let replacement = match (&captures[1], page) {
+                ("!", Some(page)) => match contents.get(&page.id) {
+                    Some(content)
+                        if depth < MAX_TRANSCLUSION_DEPTH && !stack.contains(&page.id) =>
+                    {
+                        stack.insert(page.id);
+                        let expanded =
+                            resolve_level(content, index, contents, href, stack, depth + 1);
+                        stack.remove(&page.id);
+                        format!("\n\n{}\n\n", expanded.trim())
+                    }
+                    _ => label.to_string(),
+                },
+                ("!", None) => label.to_string(),
+                (_, Some(page)) => format!("[{}]({})", escape_link_text(label), href(page)),
+                (_, None) => label.to_string(),
+            };
+            output.push_str(&markdown[copied..range.start + whole.start()]);
+            output.push_str(&replacement);
+            copied = range.start + whole.end();
+        }
+    }
+    output.push_str(&markdown[copied..]);
+    output
+}
+
+fn escape_link_text(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]")
+}
+
+/// Whether a link or image destination may be emitted as is.
+fn is_safe_destination(dest: &str) -> bool {
+    let scheme_end = dest.find([':', '/', '?', '#']);
+    match scheme_end {
+        Some(end) if dest[end..].starts_with(':') => {
+            let scheme = dest[..end].to_ascii_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+/// Render Markdown as HTML. Raw HTML is escaped and links or images with a
+/// scheme other than `http`, `https` or `mailto` point nowhere.
+pub fn render_markdown_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if !is_safe_destination(&dest_url) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: CowStr::Borrowed("#"),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if !is_safe_destination(&dest_url) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: CowStr::Borrowed("#"),
+            title,
+            id,
+        }),
+        other => other,
+    });
+    let mut output = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut output, events);
+    output
+}
+
+/// Escape text for HTML element content and attribute values.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub(crate) const SITE_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:46rem;margin:2rem auto;\
+padding:0 1rem;line-height:1.6;color:#222}header{border-bottom:1px solid #ddd;margin-bottom:1.5rem}\
+header a{color:inherit;text-decoration:none}pre{background:#f5f5f5;padding:.75rem;overflow:auto}\
+code{background:#f5f5f5}table{border-collapse:collapse}td,th{border:1px solid #ddd;padding:.25rem .5rem}\
+img{max-width:100%}footer{margin-top:2rem;color:#777;font-size:.9rem}";
+
+/// A complete page of a published site.
+fn site_document(site: &Publication, page_title: Option<&str>, query: &str, body: &str) -> String {
+    let slug = escape_html(&site.slug);
+    let site_title = escape_html(&site.title);
+    let title = match page_title {
+        Some(page_title) => format!("{} · {site_title}", escape_html(page_title)),
+        None => site_title.clone(),
+    };
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+<title>{title}</title>\n<style>{SITE_STYLE}</style>\n</head>\n<body>\n<header>\n\
+<h1><a href=\"/public/{slug}\">{site_title}</a></h1>\n\
+<form action=\"/public/{slug}/search\" method=\"get\" role=\"search\">\
+<input type=\"search\" name=\"q\" value=\"{}\" aria-label=\"Search\"> <button>Search</button></form>\n\
+</header>\n<main>\n{body}</main>\n</body>\n</html>\n",
+        escape_html(query)
+    )
+}
+
+/// The HTML index of a published site.
+pub fn render_site_html(site: &Publication, pages: &[PublishedPageSummary]) -> String {
+    let mut body = String::new();
+    if let Some(description) = &site.description {
+        body.push_str(&format!("<p>{}</p>\n", escape_html(description)));
+    }
+    if pages.is_empty() {
+        body.push_str("<p>Nothing has been published here yet.</p>\n");
+    } else {
+        body.push_str("<ul>\n");
+        for page in pages {
+            body.push_str(&format!(
+                "<li><a href=\"/public/{}/notes/{}\">{}</a></li>\n",
+                escape_html(&site.slug),
+                page.id,
+                escape_html(&page.title)
+            ));
+        }
+        body.push_str("</ul>\n");
+    }
+    site_document(site, None, "", &body)
+}
+
+/// The HTML view of a published page.
+pub fn render_page_html(site: &Publication, page: &PublishedPage) -> String {
+    let body = format!(
+        "<article>\n<h2>{}</h2>\n{}</article>\n<footer>Updated {}</footer>\n",
+        escape_html(&page.title),
+        page.html,
+        page.updated_at_utc.format("%Y-%m-%d")
+    );
+    site_document(site, Some(&page.title), "", &body)
+}
+
+/// The HTML view of published-site search results.
+pub fn render_search_html(site: &Publication, results: &PublishedSearchResults) -> String {
+    let mut body = String::new();
+    if results.query.trim().is_empty() {
+        body.push_str("<p>Enter words to search for.</p>\n");
+    } else if results.hits.is_empty() {
+        body.push_str(&format!(
+            "<p>No pages match <strong>{}</strong>.</p>\n",
+            escape_html(&results.query)
+        ));
+    } else {
+        body.push_str("<ol>\n");
+        for hit in &results.hits {
+            body.push_str(&format!(
+                "<li><a href=\"/public/{}/notes/{}\">{}</a><br>{}</li>\n",
+                escape_html(&site.slug),
+                hit.id,
+                escape_html(&hit.title),
+                escape_html(&hit.snippet)
+            ));
+        }
+        body.push_str("</ol>\n");
+    }
+    let title = format!("Search: {}", results.query);
+    site_document(site, Some(&title), &results.query, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(title: &str) -> PublishedPageSummary {
+        PublishedPageSummary {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            updated_at_utc: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn slugs_are_derived_and_checked() {
+        assert_eq!(slugify("  Hello, World  "), "hello-world");
+        assert_eq!(slugify("Ünïcode only"), "n-code-only");
+        assert_eq!(slugify("!!!"), "");
+        assert_eq!(validate_publication_slug(" my-site ").unwrap(), "my-site");
+        for bad in ["", "-a", "a-", "a--b", "A", "a b", &"a".repeat(81)] {
+            assert!(validate_publication_slug(bad).is_err(), "{bad}");
+        }
+        let request = PublishCollectionRequest::default();
+        let settings = request.settings("Research Log").unwrap();
+        assert_eq!(settings.slug, "research-log");
+        assert_eq!(settings.title, "Research Log");
+        assert!(settings.include_subcollections);
+        assert!(request.settings("???").is_err());
+    }
+
+    #[test]
+    fn wiki_links_and_transclusions_resolve_to_published_pages_only() {
+        let pages = vec![page("Alpha"), page("Beta")];
+        let index = PublishedPageIndex::new(&pages);
+        let (alpha, beta) = (pages[0].id, pages[1].id);
+        let contents = HashMap::from([
+            (alpha, "Alpha body ![[Beta]]".to_string()),
+            (beta, "Beta body ![[alpha]] [[Alpha|back]]".to_string()),
+        ]);
+        let markdown =
+            "See [[beta]], [[Secret]] and ![[Hidden]].\n\n![[Beta]]\n\n```\n[[Beta]]\n```\n";
+        let resolved = resolve_published_markdown("site", markdown, alpha, &index, &contents);
+        assert!(resolved.starts_with(&format!(
+            "See [beta](/public/site/notes/{beta}), Secret and Hidden."
+        )));
+        // Beta is expanded; its transclusion of Alpha would cycle and stays a label
+        assert!(resolved.contains("Beta body alpha [back](/public/site/notes/"));
+        assert!(resolved.contains("```\n[[Beta]]\n```"));
+        assert_eq!(transclusion_targets(markdown), vec!["Hidden", "Beta"]);
+    }
+
+    #[test]
+    fn markdown_rendering_escapes_html_and_unsafe_links() {
+        let html = render_markdown_html(
+            "<img src=x onerror=alert(1)>\n\n[a](javascript:alert(1)) [b](https://example.com) [c](/public/x)",
+        );
+        assert!(!html.contains("<img"));
+        assert!(html.contains("&lt;img"));
+        assert!(html.contains("<a href=\"#\">a</a>"));
+        assert!(html.contains("<a href=\"https://example.com\">b</a>"));
+        assert!(html.contains("<a href=\"/public/x\">c</a>"));
+    }
+
+    #[test]
+    fn site_pages_escape_titles_and_queries() {
+        let site = Publication {
+            slug: "notes".to_string(),
+            collection_id: Uuid::nil(),
+            title: "<Notes>".to_string(),
+            description: None,
+            include_subcollections: true,
+            published_at_utc: Utc::now(),
+            updated_at_utc: Utc::now(),
+        };
+        let html = render_site_html(&site, &[page("A & B")]);
+        assert!(html.contains("<title>&lt;Notes&gt;</title>"));
+        assert!(html.contains(">A &amp; B</a>"));
+        let results = PublishedSearchResults {
+            query: "\"><script>".to_string(),
+            hits: Vec::new(),
+        };
+        assert!(!render_search_html(&site, &results).contains("<script>"));
+    }
+}
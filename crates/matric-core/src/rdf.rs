@@ -0,0 +1,699 @@
+//! RDF serialization of the knowledge graph.
+//!
+//! Builds a single RDF graph from notes, note links, SKOS concepts and
+//! provenance edges, and writes it as Turtle or N-Triples for loading into an
+//! external triple store. Every internal resource is identified by a
+//! `urn:uuid:` IRI, matching the JSON-LD export, so repeated dumps of the same
+//! archive produce the same subjects.
+
+use std::fmt;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const XSD_DATE_TIME: &str = "http://www.w3.org/2001/XMLSchema#dateTime";
+
+const DCTERMS: &str = "http://purl.org/dc/terms/";
+const PROV: &str = "http://www.w3.org/ns/prov#";
+const SCHEMA: &str = "https://schema.org/";
+const SKOS: &str = "http://www.w3.org/2004/02/skos/core#";
+
+/// Namespace prefixes written in the Turtle header.
+const TURTLE_PREFIXES: &[(&str, &str)] = &[
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+    ("dcterms", DCTERMS),
+    ("prov", PROV),
+    ("schema", SCHEMA),
+    ("skos", SKOS),
+];
+
+/// PROV properties that may be emitted verbatim from `provenance_edge.relation`.
+/// Anything else falls back to `prov:wasInfluencedBy`, their common parent.
+const PROV_ENTITY_RELATIONS: &[&str] = &[
+    "wasDerivedFrom",
+    "wasQuotedFrom",
+    "wasRevisionOf",
+    "hadPrimarySource",
+];
+
+/// Serialization format for [`RdfGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RdfFormat {
+    Turtle,
+    NTriples,
+}
+
+impl RdfFormat {
+    /// Parses a `format` query value (`turtle`/`ttl` or `ntriples`/`nt`).
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "turtle" | "ttl" => Ok(Self::Turtle),
+            "ntriples" | "n-triples" | "nt" => Ok(Self::NTriples),
+            _ => Err(Error::InvalidInput(
+                "RDF format must be one of: turtle, ntriples".to_string(),
+            )),
+        }
+    }
+
+    /// HTTP content type for this format.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Turtle => "text/turtle; charset=utf-8",
+            Self::NTriples => "application/n-triples; charset=utf-8",
+        }
+    }
+
+    /// Text written once before any statements: the prefix declarations
+    /// for Turtle, nothing for N-Triples.
+    pub fn header(self) -> String {
+        match self {
+            Self::Turtle => TURTLE_PREFIXES
+                .iter()
+                .map(|(prefix, namespace)| format!("@prefix {prefix}: <{namespace}> .\n"))
+                .collect(),
+            Self::NTriples => String::new(),
+        }
+    }
+}
+
+/// An RDF term in object position. Subjects and predicates are always IRIs.
+#[derive(Clone, PartialEq)]
+pub enum RdfTerm {
+    Iri(String),
+    Literal {
+        value: String,
+        datatype: Option<&'static str>,
+        language: Option<String>,
+    },
+}
+
+impl RdfTerm {
+    fn plain(value: impl Into<String>) -> Self {
+        Self::Literal {
+            value: value.into(),
+            datatype: None,
+            language: None,
+        }
+    }
+
+    fn typed(value: impl Into<String>, datatype: &'static str) -> Self {
+        Self::Literal {
+            value: value.into(),
+            datatype: Some(datatype),
+            language: None,
+        }
+    }
+
+    fn date_time(value: DateTime<Utc>) -> Self {
+        Self::typed(
+            value.to_rfc3339_opts(SecondsFormat::Millis, true),
+            XSD_DATE_TIME,
+        )
+    }
+}
+
+impl fmt::Debug for RdfTerm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Iri(iri) => f
+                .debug_struct("Iri")
+                .field("iri_len", &iri.chars().count())
+                .finish(),
+            Self::Literal {
+                value,
+                datatype,
+                language,
+            } => f
+                .debug_struct("Literal")
+                .field("value_len", &value.chars().count())
+                .field("datatype", datatype)
+                .field("language", language)
+                .finish(),
+        }
+    }
+}
+
+/// A single RDF statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RdfTriple {
+    pub subject: String,
+    pub predicate: String,
+    pub object: RdfTerm,
+}
+
+/// Note row for the RDF export.
+#[derive(Clone)]
+pub struct RdfNoteRecord {
+    pub id: Uuid,
+    pub title: Option<String>,
+    pub created_at_utc: DateTime<Utc>,
+    pub updated_at_utc: DateTime<Utc>,
+}
+
+/// Link row for the RDF export; exactly one of `to_note_id`/`to_url` is set.
+#[derive(Clone)]
+pub struct RdfLinkRecord {
+    pub from_note_id: Uuid,
+    pub to_note_id: Option<Uuid>,
+    pub to_url: Option<String>,
+    pub kind: String,
+}
+
+/// SKOS concept row for the RDF export.
+#[derive(Clone)]
+pub struct RdfConceptRecord {
+    pub id: Uuid,
+    pub scheme_id: Uuid,
+    pub pref_label: Option<String>,
+    pub language: Option<String>,
+}
+
+/// SKOS semantic relation (`broader`, `narrower`, `related`) between concepts.
+#[derive(Debug, Clone)]
+pub struct RdfConceptRelationRecord {
+    pub subject_id: Uuid,
+    pub object_id: Uuid,
+    pub relation: String,
+}
+
+/// Concept tag on a note.
+#[derive(Debug, Clone, Copy)]
+pub struct RdfNoteConceptRecord {
+    pub note_id: Uuid,
+    pub concept_id: Uuid,
+}
+
+/// Provenance edge from a note to the note or URL it came from.
+#[derive(Clone)]
+pub struct RdfProvenanceRecord {
+    pub note_id: Uuid,
+    pub source_note_id: Option<Uuid>,
+    pub source_url: Option<String>,
+    pub relation: String,
+}
+
+/// Everything the RDF export reads from one archive.
+#[derive(Clone, Default)]
+pub struct RdfGraphSnapshot {
+    pub notes: Vec<RdfNoteRecord>,
+    pub links: Vec<RdfLinkRecord>,
+    pub concepts: Vec<RdfConceptRecord>,
+    pub concept_relations: Vec<RdfConceptRelationRecord>,
+    pub note_concepts: Vec<RdfNoteConceptRecord>,
+    pub provenance: Vec<RdfProvenanceRecord>,
+}
+
+impl fmt::Debug for RdfGraphSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RdfGraphSnapshot")
+            .field("note_count", &self.notes.len())
+            .field("link_count", &self.links.len())
+            .field("concept_count", &self.concepts.len())
+            .field("concept_relation_count", &self.concept_relations.len())
+            .field("note_concept_count", &self.note_concepts.len())
+            .field("provenance_count", &self.provenance.len())
+            .finish()
+    }
+}
+
+/// An in-memory RDF graph.
+#[derive(Debug, Clone, Default)]
+pub struct RdfGraph {
+    pub triples: Vec<RdfTriple>,
+}
+
+/// Stable IRI for an internal resource.
+pub fn urn_uuid(id: Uuid) -> String {
+    format!("urn:uuid:{id}")
+}
+
+/// True when `value` can be written inside `<...>` without escaping.
+fn is_safe_iri(value: &str) -> bool {
+    value.contains(':')
+        && !value.chars().any(|c| {
+            c.is_control()
+                || c.is_whitespace()
+                || matches!(c, '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\')
+        })
+}
+
+impl RdfGraph {
+    /// Appends a triple.
+    pub fn push(
+        &mut self,
+        subject: impl Into<String>,
+        predicate: impl Into<String>,
+        object: RdfTerm,
+    ) {
+        self.triples.push(RdfTriple {
+            subject: subject.into(),
+            predicate: predicate.into(),
+            object,
+        });
+    }
+
+    /// Builds the archive graph from a database snapshot.
+    ///
+    /// External URLs that are not valid IRIs are skipped rather than escaped,
+    /// so the output always parses.
+    pub fn from_snapshot(snapshot: &RdfGraphSnapshot) -> Self {
+        let mut graph = Self::default();
+
+        for note in &snapshot.notes {
+            let subject = urn_uuid(note.id);
+            graph.push(
+                &subject,
+                RDF_TYPE,
+                RdfTerm::Iri(format!("{SCHEMA}CreativeWork")),
+            );
+            if let Some(title) = note.title.as_deref().filter(|t| !t.is_empty()) {
+                graph.push(&subject, format!("{DCTERMS}title"), RdfTerm::plain(title));
+            }
+            graph.push(
+                &subject,
+                format!("{DCTERMS}created"),
+                RdfTerm::date_time(note.created_at_utc),
+            );
+            graph.push(
+                &subject,
+                format!("{DCTERMS}modified"),
+                RdfTerm::date_time(note.updated_at_utc),
+            );
+        }
+
+        for link in &snapshot.links {
+            let object = match (link.to_note_id, link.to_url.as_deref()) {
+                (Some(id), _) => urn_uuid(id),
+                (None, Some(url)) if is_safe_iri(url) => url.to_string(),
+                _ => continue,
+            };
+            let predicate = if link.kind == "semantic" {
+                format!("{SKOS}related")
+            } else {
+                format!("{DCTERMS}references")
+            };
+            graph.push(urn_uuid(link.from_note_id), predicate, RdfTerm::Iri(object));
+        }
+
+        for concept in &snapshot.concepts {
+            let subject = urn_uuid(concept.id);
+            graph.push(&subject, RDF_TYPE, RdfTerm::Iri(format!("{SKOS}Concept")));
+            graph.push(
+                &subject,
+                format!("{SKOS}inScheme"),
+                RdfTerm::Iri(urn_uuid(concept.scheme_id)),
+            );
+            if let Some(label) = concept.pref_label.as_deref() {
+                graph.push(
+                    &subject,
+                    format!("{SKOS}prefLabel"),
+                    RdfTerm::Literal {
+                        value: label.to_string(),
+                        datatype: None,
+                        language: concept.language.clone().filter(|l| is_language_tag(l)),
+                    },
+                );
+            }
+        }
+
+        for relation in &snapshot.concept_relations {
+            let predicate = match relation.relation.as_str() {
+                "broader" | "narrower" | "related" => format!("{SKOS}{}", relation.relation),
+                _ => continue,
+            };
+            graph.push(
+                urn_uuid(relation.subject_id),
+                predicate,
+                RdfTerm::Iri(urn_uuid(relation.object_id)),
+            );
+        }
+
+        for tag in &snapshot.note_concepts {
+            graph.push(
+                urn_uuid(tag.note_id),
+                format!("{DCTERMS}subject"),
+                RdfTerm::Iri(urn_uuid(tag.concept_id)),
+            );
+        }
+
+        for edge in &snapshot.provenance {
+            let object = match (edge.source_note_id, edge.source_url.as_deref()) {
+                (Some(id), _) => urn_uuid(id),
+                (None, Some(url)) if is_safe_iri(url) => url.to_string(),
+                _ => continue,
+            };
+            let relation = if PROV_ENTITY_RELATIONS.contains(&edge.relation.as_str()) {
+                edge.relation.as_str()
+            } else {
+                "wasInfluencedBy"
+            };
+            graph.push(
+                urn_uuid(edge.note_id),
+                format!("{PROV}{relation}"),
+                RdfTerm::Iri(object),
+            );
+        }
+
+        graph
+    }
+
+    /// Serializes the graph in the requested format.
+    pub fn serialize(&self, format: RdfFormat) -> String {
+        match format {
+            RdfFormat::Turtle => self.to_turtle(),
+            RdfFormat::NTriples => self.to_ntriples(),
+        }
+    }
+
+    /// Serializes the graph's statements without the Turtle prefix header,
+    /// so a large graph can be written in pieces after one
+    /// [`RdfFormat::header`].
+    pub fn serialize_body(&self, format: RdfFormat) -> String {
+        match format {
+            RdfFormat::Turtle => self.turtle_statements(),
+            RdfFormat::NTriples => self.to_ntriples(),
+        }
+    }
+
+    /// Serializes as N-Triples, one statement per line.
+    pub fn to_ntriples(&self) -> String {
+        let mut out = String::new();
+        for triple in &self.triples {
+            out.push_str(&format!(
+                "<{}> <{}> {} .\n",
+                triple.subject,
+                triple.predicate,
+                ntriples_term(&triple.object)
+            ));
+        }
+        out
+    }
+
+    /// Serializes as Turtle, grouping consecutive statements by subject.
+    pub fn to_turtle(&self) -> String {
+        let mut out = RdfFormat::Turtle.header();
+        out.push_str(&self.turtle_statements());
+        out
+    }
+
+    fn turtle_statements(&self) -> String {
+        let mut out = String::new();
+        let mut current: Option<&str> = None;
+        for triple in &self.triples {
+            let predicate = if triple.predicate == RDF_TYPE {
+                "a".to_string()
+            } else {
+                compact_iri(&triple.predicate)
+            };
+            let object = turtle_term(&triple.object);
+            if current == Some(triple.subject.as_str()) {
+                out.push_str(&format!(" ;\n    {predicate} {object}"));
+            } else {
+                if current.is_some() {
+                    out.push_str(" .\n");
+                }
+                out.push_str(&format!("\n<{}> {predicate} {object}", triple.subject));
+                current = Some(&triple.subject);
+            }
+        }
+        if current.is_some() {
+            out.push_str(" .\n");
+        }
+        out
+    }
+}
+
+/// BCP 47 shape check so a bad `language` column cannot break the output.
+fn is_language_tag(value: &str) -> bool {
+    !value.is_empty()
+        && value.split('-').all(|part| {
+            !part.is_empty() && part.len() <= 8 && part.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+fn compact_iri(iri: &str) -> String {
+    for (prefix, namespace) in TURTLE_PREFIXES {
+        if let Some(local) = iri.strip_prefix(namespace) {
+            if !local.is_empty() && local.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return format!("{prefix}:{local}");
+            }
+        }
+    }
+    format!("<{iri}>")
+}
+
+fn escape_literal(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn ntriples_term(term: &RdfTerm) -> String {
+    match term {
+        RdfTerm::Iri(iri) => format!("<{iri}>"),
+        RdfTerm::Literal {
+            value,
+            datatype,
+            language,
+        } => {
+            let mut out = format!("\"{}\"", escape_literal(value));
+            if let Some(language) = language {
+                out.push_str(&format!("@{language}"));
+            } else if let Some(datatype) = datatype {
+                out.push_str(&format!("^^<{datatype}>"));
+            }
+            out
+        }
+    }
+}
+
+fn turtle_term(term: &RdfTerm) -> String {
+    match term {
+        RdfTerm::Iri(iri) => compact_iri(iri),
+        RdfTerm::Literal {
+            value,
+            datatype,
+            language,
+        } => {
+            let mut out = format!("\"{}\"", escape_literal(value));
+            if let Some(language) = language {
+                out.push_str(&format!("@{language}"));
+            } else if let Some(datatype) = datatype {
+                out.push_str(&format!("^^{}", compact_iri(datatype)));
+            }
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn sample_snapshot() -> RdfGraphSnapshot {
+        let at = Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap();
+        RdfGraphSnapshot {
+            notes: vec![
+                RdfNoteRecord {
+                    id: id(1),
+                    title: Some("Say \"hi\"\nthere".to_string()),
+                    created_at_utc: at,
+                    updated_at_utc: at,
+                },
+                RdfNoteRecord {
+                    id: id(2),
+                    title: None,
+                    created_at_utc: at,
+                    updated_at_utc: at,
+                },
+            ],
+            links: vec![
+                RdfLinkRecord {
+                    from_note_id: id(1),
+                    to_note_id: Some(id(2)),
+                    to_url: None,
+                    kind: "semantic".to_string(),
+                },
+                RdfLinkRecord {
+                    from_note_id: id(1),
+                    to_note_id: None,
+                    to_url: Some("https://example.com/a b".to_string()),
+                    kind: "url".to_string(),
+                },
+            ],
+            concepts: vec![RdfConceptRecord {
+                id: id(10),
+                scheme_id: id(20),
+                pref_label: Some("Rust".to_string()),
+                language: Some("en".to_string()),
+            }],
+            concept_relations: vec![RdfConceptRelationRecord {
+                subject_id: id(10),
+                object_id: id(11),
+                relation: "broader".to_string(),
+            }],
+            note_concepts: vec![RdfNoteConceptRecord {
+                note_id: id(1),
+                concept_id: id(10),
+            }],
+            provenance: vec![
+                RdfProvenanceRecord {
+                    note_id: id(2),
+                    source_note_id: Some(id(1)),
+                    source_url: None,
+                    relation: "wasDerivedFrom".to_string(),
+                },
+                RdfProvenanceRecord {
+                    note_id: id(2),
+                    source_note_id: None,
+                    source_url: Some("https://example.com/src".to_string()),
+                    relation: "used".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn format_parse_accepts_aliases_and_rejects_unknown() {
+        assert_eq!(RdfFormat::parse("turtle").unwrap(), RdfFormat::Turtle);
+        assert_eq!(RdfFormat::parse("TTL").unwrap(), RdfFormat::Turtle);
+        assert_eq!(RdfFormat::parse("ntriples").unwrap(), RdfFormat::NTriples);
+        assert!(matches!(
+            RdfFormat::parse("rdfxml"),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn snapshot_uses_stable_urn_subjects_and_standard_vocabularies() {
+        let graph = RdfGraph::from_snapshot(&sample_snapshot());
+        let nt = graph.to_ntriples();
+        let note = urn_uuid(id(1));
+
+        assert!(nt.contains(&format!(
+            "<{note}> <{RDF_TYPE}> <https://schema.org/CreativeWork> ."
+        )));
+        assert!(nt.contains(&format!("<{note}> <{SKOS}related> <{}> .", urn_uuid(id(2)))));
+        assert!(nt.contains(&format!(
+            "<{note}> <{DCTERMS}subject> <{}> .",
+            urn_uuid(id(10))
+        )));
+        assert!(nt.contains(&format!(
+            "<{}> <{SKOS}broader> <{}> .",
+            urn_uuid(id(10)),
+            urn_uuid(id(11))
+        )));
+        assert!(nt.contains("\"Rust\"@en ."));
+        assert!(nt.contains(&format!(
+            "<{}> <{PROV}wasDerivedFrom> <{note}> .",
+            urn_uuid(id(2))
+        )));
+        assert!(nt.contains(&format!(
+            "<{PROV}wasInfluencedBy> <https://example.com/src> ."
+        )));
+        assert!(nt.contains(&format!("\"2026-01-02T03:04:05.000Z\"^^<{XSD_DATE_TIME}>")));
+        assert_eq!(graph.to_ntriples(), nt, "serialization is deterministic");
+    }
+
+    #[test]
+    fn invalid_url_targets_are_skipped() {
+        let nt = RdfGraph::from_snapshot(&sample_snapshot()).to_ntriples();
+        assert!(!nt.contains("example.com/a b"));
+    }
+
+    #[test]
+    fn literals_are_escaped() {
+        let nt = RdfGraph::from_snapshot(&sample_snapshot()).to_ntriples();
+        assert!(nt.contains(r#""Say \"hi\"\nthere""#));
+        assert!(nt.lines().all(|line| line.ends_with(" .")));
+    }
+
+    #[test]
+    fn turtle_groups_by_subject_with_prefixes() {
+        let ttl = RdfGraph::from_snapshot(&sample_snapshot()).to_turtle();
+        assert!(ttl.starts_with("@prefix rdf: "));
+        assert!(ttl.contains(&format!("<{}> a schema:CreativeWork ;", urn_uuid(id(1)))));
+        assert!(ttl.contains("    dcterms:created \"2026-01-02T03:04:05.000Z\"^^xsd:dateTime"));
+        assert!(ttl.contains("skos:prefLabel \"Rust\"@en"));
+        assert!(ttl.trim_end().ends_with(" ."));
+    }
+
+    #[test]
+    fn graph_written_in_pieces_has_the_statements_of_the_whole_graph() {
+        let whole = sample_snapshot();
+        let vocabulary = RdfGraphSnapshot {
+            concepts: whole.concepts.clone(),
+            concept_relations: whole.concept_relations.clone(),
+            ..Default::default()
+        };
+        let notes = RdfGraphSnapshot {
+            concepts: Vec::new(),
+            concept_relations: Vec::new(),
+            ..whole.clone()
+        };
+        let pieces = |format: RdfFormat| {
+            format.header()
+                + &RdfGraph::from_snapshot(&vocabulary).serialize_body(format)
+                + &RdfGraph::from_snapshot(&notes).serialize_body(format)
+        };
+        let sorted_lines = |text: &str| {
+            let mut lines: Vec<_> = text.lines().map(str::to_string).collect();
+            lines.sort();
+            lines
+        };
+
+        assert_eq!(
+            sorted_lines(&pieces(RdfFormat::NTriples)),
+            sorted_lines(&RdfGraph::from_snapshot(&whole).to_ntriples())
+        );
+        let ttl = pieces(RdfFormat::Turtle);
+        assert!(ttl.starts_with("@prefix rdf: "));
+        assert!(ttl.contains("skos:prefLabel \"Rust\"@en"));
+        assert!(ttl.trim_end().ends_with(" ."));
+    }
+
+    #[test]
+    fn empty_graph_serializes_to_prefixes_only() {
+        let graph = RdfGraph::default();
+        assert!(graph.to_ntriples().is_empty());
+        assert_eq!(
+            graph.to_turtle().lines().count(),
+            TURTLE_PREFIXES.len(),
+            "only prefix declarations"
+        );
+    }
+
+    #[test]
+    fn language_tag_check_rejects_malformed_tags() {
+        assert!(is_language_tag("en"));
+        assert!(is_language_tag("pt-BR"));
+        assert!(!is_language_tag(""));
+        assert!(!is_language_tag("en us"));
+        assert!(!is_language_tag("en--us"));
+    }
+
+    #[test]
+    fn debug_redacts_literal_values() {
+        let term = RdfTerm::plain("secret title");
+        assert!(!format!("{term:?}").contains("secret"));
+    }
+}
@@ -0,0 +1,183 @@
+//! Serendipitous resurfacing of forgotten notes.
+//!
+//! `GET /api/v1/notes/resurface` offers a few older notes nobody has looked
+//! at in a while, for "remember this?" prompts. Eligible notes were created
+//! and last opened at least `min_age_days` ago and have been opened at most
+//! `max_access_count` times (opening a note through the API counts). They are
+//! sampled at random, weighted towards notes that look worth a second look:
+//! well linked, tagged, and of some length, and rarely opened.
+//!
+//! ```
+//! use matric_core::resurface::sample_weighted;
+//!
+//! let mut draws = [0.9, 0.1, 0.5].into_iter();
+//! let picked = sample_weighted(vec!["a", "b", "c"], |_| 1.0, 2, || draws.next().unwrap());
+//! assert_eq!(picked, vec!["a", "c"]);
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Notes returned unless asked otherwise.
+pub const DEFAULT_RESURFACE_LIMIT: i64 = 5;
+
+/// Most notes a caller may ask for.
+pub const MAX_RESURFACE_LIMIT: i64 = 50;
+
+/// Days since a note was created and last opened before it is eligible,
+/// unless asked otherwise.
+pub const DEFAULT_RESURFACE_MIN_AGE_DAYS: u32 = 30;
+
+/// Most times an eligible note has been opened, unless asked otherwise.
+pub const DEFAULT_RESURFACE_MAX_ACCESS_COUNT: i32 = 3;
+
+/// Eligible notes drawn uniformly at random before weighted sampling.
+pub const RESURFACE_CANDIDATE_POOL: i64 = 500;
+
+/// Content length, in characters, from which length no longer adds weight.
+const FULL_WEIGHT_CHARS: f64 = 1500.0;
+
+/// Smallest length factor, so short notes can still come up.
+const MIN_LENGTH_FACTOR: f64 = 0.2;
+
+/// Which notes may be resurfaced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResurfaceQuery {
+    pub limit: i64,
+    pub min_age_days: u32,
+    pub max_access_count: i32,
+    /// Only notes filed in this collection
+    pub collection_id: Option<Uuid>,
+}
+
+impl Default for ResurfaceQuery {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_RESURFACE_LIMIT,
+            min_age_days: DEFAULT_RESURFACE_MIN_AGE_DAYS,
+            max_access_count: DEFAULT_RESURFACE_MAX_ACCESS_COUNT,
+            collection_id: None,
+        }
+    }
+}
+
+/// A note offered for resurfacing, with the signals it was weighted by.
+#[derive(Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct ResurfacedNote {
+    pub note_id: Uuid,
+    pub title: Option<String>,
+    /// Start of the note's current content
+    pub snippet: Option<String>,
+    pub created_at_utc: DateTime<Utc>,
+    pub last_accessed_at: Option<DateTime<Utc>>,
+    pub access_count: i32,
+    /// Links to and from the note
+    pub link_count: i64,
+    pub tag_count: i64,
+    /// Length of the note's current content, in characters
+    pub content_chars: i64,
+    /// Relative sampling weight (see [`resurface_weight`])
+    #[sqlx(default)]
+    pub weight: f64,
+}
+
+impl fmt::Debug for ResurfacedNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResurfacedNote")
+            .field("note_id", &self.note_id)
+            .field("title_present", &self.title.is_some())
+            .field("snippet_len", &self.snippet.as_ref().map(String::len))
+            .field("created_at_utc", &self.created_at_utc)
+            .field("access_count", &self.access_count)
+            .field("link_count", &self.link_count)
+            .field("tag_count", &self.tag_count)
+            .field("content_chars", &self.content_chars)
+            .field("weight", &self.weight)
+            .finish()
+    }
+}
+
+/// Sampling weight of a note: grows with its links, tags and length (up to
+/// 1500 characters) and shrinks with every time it has been opened. Always
+/// positive.
+pub fn resurface_weight(note: &ResurfacedNote) -> f64 {
+    let links = 1.0 + (note.link_count.max(0) as f64).ln_1p();
+    let tags = 1.0 + 0.5 * (note.tag_count.max(0) as f64).ln_1p();
+    let length =
+        (note.content_chars.max(0) as f64 / FULL_WEIGHT_CHARS).clamp(MIN_LENGTH_FACTOR, 1.0);
+    links * tags * length / (1.0 + note.access_count.max(0) as f64)
+}
+
+/// Up to `limit` of `items`, sampled without replacement with probability
+/// proportional to `weight`, in draw order. `uniform` yields values in
+/// `[0, 1)`; items with a weight that is not positive are never picked.
+pub fn sample_weighted<T>(
+    items: Vec<T>,
+    weight: impl Fn(&T) -> f64,
+    limit: usize,
+    mut uniform: impl FnMut() -> f64,
+) -> Vec<T> {
+    // Efraimidis–Spirakis: keep the items with the largest u^(1/w).
+    let mut keyed: Vec<(f64, T)> = items
+        .into_iter()
+        .filter_map(|item| {
+            let w = weight(&item);
+            (w > 0.0 && w.is_finite()).then(|| (uniform().powf(1.0 / w), item))
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.truncate(limit);
+    keyed.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(
+        link_count: i64,
+        tag_count: i64,
+        content_chars: i64,
+        access_count: i32,
+    ) -> ResurfacedNote {
+        ResurfacedNote {
+            note_id: Uuid::nil(),
+            title: None,
+            snippet: None,
+            created_at_utc: Utc::now(),
+            last_accessed_at: None,
+            access_count,
+            link_count,
+            tag_count,
+            content_chars,
+            weight: 0.0,
+        }
+    }
+
+    #[test]
+    fn weight_favours_connected_long_unopened_notes() {
+        let base = resurface_weight(&note(0, 0, 3000, 0));
+        assert!(resurface_weight(&note(5, 0, 3000, 0)) > base);
+        assert!(resurface_weight(&note(0, 3, 3000, 0)) > base);
+        assert!(resurface_weight(&note(0, 0, 100, 0)) < base);
+        assert!(resurface_weight(&note(0, 0, 3000, 2)) < base);
+        assert!(resurface_weight(&note(0, 0, 0, 100)) > 0.0);
+    }
+
+    #[test]
+    fn sampling_skips_weightless_items_and_stops_at_limit() {
+        let mut draws = [0.5, 0.5, 0.5].into_iter();
+        let picked = sample_weighted(
+            vec![(1, 1.0), (2, 0.0), (3, 4.0), (4, 2.0)],
+            |(_, w)| *w,
+            2,
+            || draws.next().unwrap(),
+        );
+        // With equal draws the heavier item has the larger key.
+        assert_eq!(picked, vec![(3, 4.0), (4, 2.0)]);
+        assert!(sample_weighted(Vec::<u8>::new(), |_| 1.0, 3, || 0.5).is_empty());
+    }
+}
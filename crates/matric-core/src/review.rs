@@ -0,0 +1,287 @@
+//! Spaced repetition review scheduling.
+//!
+//! Review cards are question/answer pairs attached to a note. Each card keeps
+//! its own SM-2 state (ease factor, interval, repetition count) and a due
+//! timestamp; recording a grade advances that state and pushes the due date
+//! forward. The algorithm follows the original SuperMemo SM-2 description:
+//! grades run from 0 (blackout) to 5 (perfect recall) and anything below 3
+//! counts as a lapse that restarts the repetition sequence.
+//!
+//! ```
+//! use matric_core::ReviewSchedule;
+//!
+//! let schedule = ReviewSchedule::default().apply_grade(5).unwrap();
+//! assert_eq!(schedule.interval_days, 1);
+//! let schedule = schedule.apply_grade(4).unwrap();
+//! assert_eq!(schedule.interval_days, 6);
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// Starting ease factor for a new card.
+pub const DEFAULT_EASE_FACTOR: f64 = 2.5;
+
+/// SM-2 never lets the ease factor fall below this floor.
+pub const MIN_EASE_FACTOR: f64 = 1.3;
+
+/// Highest grade accepted by [`ReviewSchedule::apply_grade`].
+pub const MAX_REVIEW_GRADE: i16 = 5;
+
+/// Grades at or above this value count as a successful recall.
+pub const PASSING_REVIEW_GRADE: i16 = 3;
+
+/// SM-2 scheduling state for a single review card.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReviewSchedule {
+    pub ease_factor: f64,
+    pub interval_days: i32,
+    pub repetitions: i32,
+    pub lapses: i32,
+}
+
+impl Default for ReviewSchedule {
+    fn default() -> Self {
+        Self {
+            ease_factor: DEFAULT_EASE_FACTOR,
+            interval_days: 0,
+            repetitions: 0,
+            lapses: 0,
+        }
+    }
+}
+
+impl ReviewSchedule {
+    /// Returns the schedule that results from recording `grade` (0-5).
+    pub fn apply_grade(self, grade: i16) -> Result<Self> {
+        validate_review_grade(grade)?;
+
+        let q = f64::from(grade);
+        let ease_factor =
+            (self.ease_factor + (0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02))).max(MIN_EASE_FACTOR);
+
+        if grade < PASSING_REVIEW_GRADE {
+            return Ok(Self {
+                ease_factor,
+                interval_days: 1,
+                repetitions: 0,
+                lapses: self.lapses.saturating_add(1),
+            });
+        }
+
+        let interval_days = match self.repetitions {
+            0 => 1,
+            1 => 6,
+            _ => {
+                let next = (f64::from(self.interval_days.max(1)) * ease_factor).round();
+                next.min(f64::from(i32::MAX)) as i32
+            }
+        };
+
+        Ok(Self {
+            ease_factor,
+            interval_days,
+            repetitions: self.repetitions.saturating_add(1),
+            lapses: self.lapses,
+        })
+    }
+
+    /// Returns when a card reviewed at `reviewed_at` becomes due again.
+    pub fn next_due(&self, reviewed_at: DateTime<Utc>) -> DateTime<Utc> {
+        reviewed_at + Duration::days(i64::from(self.interval_days))
+    }
+}
+
+/// Validates that a grade is inside the SM-2 0-5 range.
+pub fn validate_review_grade(grade: i16) -> Result<()> {
+    if !(0..=MAX_REVIEW_GRADE).contains(&grade) {
+        return Err(Error::InvalidInput(format!(
+            "review grade must be between 0 and {MAX_REVIEW_GRADE}"
+        )));
+    }
+    Ok(())
+}
+
+/// A spaced repetition card attached to a note.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ReviewCard {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub front: String,
+    pub back: String,
+    pub ease_factor: f64,
+    pub interval_days: i32,
+    pub repetitions: i32,
+    pub lapses: i32,
+    pub due_at_utc: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_reviewed_at_utc: Option<DateTime<Utc>>,
+    pub created_at_utc: DateTime<Utc>,
+}
+
+impl ReviewCard {
+    /// Returns the card's current SM-2 scheduling state.
+    pub fn schedule(&self) -> ReviewSchedule {
+        ReviewSchedule {
+            ease_factor: self.ease_factor,
+            interval_days: self.interval_days,
+            repetitions: self.repetitions,
+            lapses: self.lapses,
+        }
+    }
+}
+
+impl fmt::Debug for ReviewCard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReviewCard")
+            .field("id_set", &true)
+            .field("note_id_set", &true)
+            .field("front_len", &self.front.chars().count())
+            .field("back_len", &self.back.chars().count())
+            .field("ease_factor", &self.ease_factor)
+            .field("interval_days", &self.interval_days)
+            .field("repetitions", &self.repetitions)
+            .field("lapses", &self.lapses)
+            .field("due_at_utc", &self.due_at_utc)
+            .field("last_reviewed_at_utc", &self.last_reviewed_at_utc)
+            .field("created_at_utc", &self.created_at_utc)
+            .finish()
+    }
+}
+
+/// Request body for creating a review card.
+#[derive(Clone, Deserialize, utoipa::ToSchema)]
+pub struct CreateReviewCardRequest {
+    pub note_id: Uuid,
+    pub front: String,
+    pub back: String,
+}
+
+impl fmt::Debug for CreateReviewCardRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateReviewCardRequest")
+            .field("note_id_set", &true)
+            .field("front_len", &self.front.chars().count())
+            .field("back_len", &self.back.chars().count())
+            .finish()
+    }
+}
+
+/// Request body for recording a review grade.
+#[derive(Debug, Clone, Copy, Deserialize, utoipa::ToSchema)]
+pub struct RecordReviewRequest {
+    /// SM-2 recall quality, 0 (blackout) to 5 (perfect recall).
+    pub grade: i16,
+}
+
+/// Cards due for review.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct ReviewQueue {
+    pub cards: Vec<ReviewCard>,
+    /// Total number of due cards, which may exceed `cards.len()`.
+    pub due_count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_two_successful_reviews_use_fixed_intervals() {
+        let first = ReviewSchedule::default().apply_grade(4).unwrap();
+        assert_eq!(first.interval_days, 1);
+        assert_eq!(first.repetitions, 1);
+
+        let second = first.apply_grade(4).unwrap();
+        assert_eq!(second.interval_days, 6);
+        assert_eq!(second.repetitions, 2);
+    }
+
+    #[test]
+    fn later_intervals_grow_by_ease_factor() {
+        let schedule = ReviewSchedule {
+            ease_factor: 2.5,
+            interval_days: 6,
+            repetitions: 2,
+            lapses: 0,
+        };
+        let next = schedule.apply_grade(5).unwrap();
+        assert!((next.ease_factor - 2.6).abs() < 1e-9);
+        assert_eq!(next.interval_days, 16);
+        assert_eq!(next.repetitions, 3);
+    }
+
+    #[test]
+    fn failing_grade_resets_repetitions_and_counts_lapse() {
+        let schedule = ReviewSchedule {
+            ease_factor: 2.5,
+            interval_days: 16,
+            repetitions: 3,
+            lapses: 1,
+        };
+        let next = schedule.apply_grade(1).unwrap();
+        assert_eq!(next.interval_days, 1);
+        assert_eq!(next.repetitions, 0);
+        assert_eq!(next.lapses, 2);
+        assert!(next.ease_factor < schedule.ease_factor);
+    }
+
+    #[test]
+    fn ease_factor_never_drops_below_floor() {
+        let mut schedule = ReviewSchedule::default();
+        for _ in 0..20 {
+            schedule = schedule.apply_grade(0).unwrap();
+        }
+        assert!((schedule.ease_factor - MIN_EASE_FACTOR).abs() < 1e-9);
+    }
+
+    #[test]
+    fn out_of_range_grades_are_rejected() {
+        assert!(matches!(
+            ReviewSchedule::default().apply_grade(6),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            ReviewSchedule::default().apply_grade(-1),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn next_due_adds_interval_days() {
+        let reviewed_at = Utc::now();
+        let schedule = ReviewSchedule {
+            interval_days: 6,
+            ..ReviewSchedule::default()
+        };
+        assert_eq!(
+            schedule.next_due(reviewed_at),
+            reviewed_at + Duration::days(6)
+        );
+    }
+
+    #[test]
+    fn review_card_debug_redacts_text() {
+        let card = ReviewCard {
+            id: Uuid::nil(),
+            note_id: Uuid::nil(),
+            front: "secret question".to_string(),
+            back: "secret answer".to_string(),
+            ease_factor: DEFAULT_EASE_FACTOR,
+            interval_days: 0,
+            repetitions: 0,
+            lapses: 0,
+            due_at_utc: Utc::now(),
+            last_reviewed_at_utc: None,
+            created_at_utc: Utc::now(),
+        };
+        let debug = format!("{card:?}");
+        assert!(!debug.contains("secret"));
+        assert!(debug.contains("front_len"));
+    }
+}
@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use uuid::Uuid;
 
+use crate::note_metadata::MetadataFieldFilter;
+
 // =============================================================================
 // STRICT TAG FILTER (UUID-BASED)
 // =============================================================================
@@ -81,10 +83,19 @@ pub struct StrictTagFilter {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_tag_count: Option<i32>,
 
+    /// Typed conditions on note metadata keys (AND logic) - must match ALL.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub metadata_fields: Vec<MetadataFieldFilter>,
+
     /// Whether to include notes with no tags (default: true).
     #[serde(default = "default_true")]
     pub include_untagged: bool,
 
+    /// Whether concepts also match their transitively narrower concepts
+    /// (default: false).
+    #[serde(default)]
+    pub expand_hierarchy: bool,
+
     /// When true, the filter is unsatisfiable (e.g. any_tags requested but none
     /// resolved). Search should return empty results immediately.
     #[serde(default, skip_serializing)]
@@ -133,7 +144,9 @@ impl fmt::Debug for StrictTagFilter {
                     .collect::<Vec<_>>(),
             )
             .field("min_tag_count", &self.min_tag_count)
+            .field("metadata_fields", &self.metadata_fields)
             .field("include_untagged", &self.include_untagged)
+            .field("expand_hierarchy", &self.expand_hierarchy)
             .field("match_none", &self.match_none)
             .finish()
     }
@@ -155,7 +168,9 @@ impl Default for StrictTagFilter {
             any_string_tags: Vec::new(),
             excluded_string_tags: Vec::new(),
             min_tag_count: None,
+            metadata_fields: Vec::new(),
             include_untagged: true,
+            expand_hierarchy: false,
             match_none: false,
         }
     }
@@ -203,12 +218,24 @@ impl StrictTagFilter {
         self
     }
 
+    /// Add a typed condition on a note metadata key.
+    pub fn require_metadata(mut self, filter: MetadataFieldFilter) -> Self {
+        self.metadata_fields.push(filter);
+        self
+    }
+
     /// Set whether to include untagged notes.
     pub fn with_include_untagged(mut self, include: bool) -> Self {
         self.include_untagged = include;
         self
     }
 
+    /// Set whether concepts also match their narrower concepts.
+    pub fn with_expand_hierarchy(mut self, expand: bool) -> Self {
+        self.expand_hierarchy = expand;
+        self
+    }
+
     /// Check if the filter is empty (no constraints).
     pub fn is_empty(&self) -> bool {
         self.required_concepts.is_empty()
@@ -220,6 +247,7 @@ impl StrictTagFilter {
             && self.any_string_tags.is_empty()
             && self.excluded_string_tags.is_empty()
             && self.min_tag_count.is_none()
+            && self.metadata_fields.is_empty()
     }
 
     /// Check if the filter has scheme-level constraints.
@@ -253,7 +281,8 @@ impl StrictTagFilter {
 ///   "excluded_tags": ["archive", "draft"],
 ///   "required_schemes": ["topics"],
 ///   "min_tag_count": 2,
-///   "include_untagged": false
+///   "include_untagged": false,
+///   "expand_hierarchy": true
 /// }
 /// ```
 #[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
@@ -285,6 +314,12 @@ pub struct StrictTagFilterInput {
     /// Whether to include notes with no tags (default: true).
     #[serde(default = "default_true")]
     pub include_untagged: bool,
+
+    /// Whether a tag also matches notes tagged with its narrower concepts,
+    /// so `programming` matches notes tagged only `programming/rust`
+    /// (default: false).
+    #[serde(default)]
+    pub expand_hierarchy: bool,
 }
 
 impl fmt::Debug for StrictTagFilterInput {
@@ -337,6 +372,7 @@ impl fmt::Debug for StrictTagFilterInput {
             )
             .field("min_tag_count", &self.min_tag_count)
             .field("include_untagged", &self.include_untagged)
+            .field("expand_hierarchy", &self.expand_hierarchy)
             .finish()
     }
 }
@@ -351,6 +387,7 @@ impl Default for StrictTagFilterInput {
             excluded_schemes: Vec::new(),
             min_tag_count: None,
             include_untagged: true,
+            expand_hierarchy: false,
         }
     }
 }
@@ -617,6 +654,7 @@ mod tests {
             excluded_schemes: vec!["/srv/fortemi/private/秘密-scheme".to_string()],
             min_tag_count: Some(1),
             include_untagged: false,
+            expand_hierarchy: true,
         };
 
         let input_debug = format!("{input:?}");
@@ -692,6 +730,7 @@ mod tests {
             excluded_schemes: vec![],
             min_tag_count: Some(2),
             include_untagged: false,
+            expand_hierarchy: true,
         };
 
         let json = serde_json::to_string(&input).unwrap();
@@ -704,6 +743,7 @@ mod tests {
         assert_eq!(input.excluded_schemes, deserialized.excluded_schemes);
         assert_eq!(input.min_tag_count, deserialized.min_tag_count);
         assert_eq!(input.include_untagged, deserialized.include_untagged);
+        assert_eq!(input.expand_hierarchy, deserialized.expand_hierarchy);
     }
 
     #[test]
@@ -741,6 +781,18 @@ mod tests {
         assert!(!input.include_untagged);
     }
 
+    #[test]
+    fn test_strict_tag_filter_input_expand_hierarchy_defaults_off() {
+        let input: StrictTagFilterInput =
+            serde_json::from_str(r#"{"required_tags": ["programming"]}"#).unwrap();
+        assert!(!input.expand_hierarchy);
+
+        let input: StrictTagFilterInput =
+            serde_json::from_str(r#"{"required_tags": ["programming"], "expand_hierarchy": true}"#)
+                .unwrap();
+        assert!(input.expand_hierarchy);
+    }
+
     #[test]
     fn test_strict_tag_filter_input_default_include_untagged() {
         let json = r#"{
@@ -0,0 +1,390 @@
+//! Static site export of a collection.
+//!
+//! A collection's notes are written out as a self-contained bundle that any
+//! static host can serve: an index page, one page per note and the notes'
+//! attachments. Pages are rendered HTML or Markdown with YAML front-matter.
+//! Every link in the bundle is relative, so it works from any base path.
+//!
+//! ```text
+//! index.html                  index.md
+//! notes/<page>.html           notes/<page>.md
+//! attachments/<id>/<file>     attachments/<id>/<file>
+//! ```
+//!
+//! Wiki links and transclusions are resolved as on published sites (see
+//! [`crate::publishing`]), against the exported notes.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::publishing::{
+    escape_html, render_markdown_html, resolve_wiki_markdown, slugify, PublishedPageIndex,
+    PublishedPageSummary, SITE_STYLE,
+};
+
+/// Most notes a site export holds.
+pub const MAX_SITE_EXPORT_NOTES: i64 = 5000;
+
+/// Most attachment bytes a site export holds.
+pub const MAX_SITE_EXPORT_ATTACHMENT_BYTES: i64 = 256 * 1024 * 1024;
+
+/// Longest attachment file name in a site export, in characters.
+const MAX_EXPORT_FILE_NAME_CHARS: usize = 100;
+
+/// Page format of a site export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SiteExportFormat {
+    /// Rendered HTML pages
+    #[default]
+    Html,
+    /// Markdown pages with YAML front-matter
+    Markdown,
+}
+
+impl SiteExportFormat {
+    /// File extension of the format's pages.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+/// Export a collection as a static site.
+#[derive(Debug, Clone, Default, Deserialize, utoipa::ToSchema)]
+pub struct SiteExportRequest {
+    /// `html` (default) or `markdown`
+    #[serde(default)]
+    pub format: SiteExportFormat,
+    /// Also export notes in nested collections (default: true)
+    #[serde(default)]
+    pub include_subcollections: Option<bool>,
+    /// Bundle the notes' attachments (default: true)
+    #[serde(default)]
+    pub include_attachments: Option<bool>,
+}
+
+/// A note to export.
+#[derive(Clone)]
+pub struct SiteExportNote {
+    pub id: Uuid,
+    pub title: String,
+    pub content: String,
+    pub tags: Vec<String>,
+    pub created_at_utc: DateTime<Utc>,
+    pub updated_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for SiteExportNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SiteExportNote")
+            .field("title_len", &self.title.len())
+            .field("content_len", &self.content.len())
+            .field("tag_count", &self.tags.len())
+            .finish()
+    }
+}
+
+/// An attachment bundled with an exported note.
+#[derive(Clone)]
+pub struct SiteExportAttachment {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub filename: String,
+}
+
+impl fmt::Debug for SiteExportAttachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SiteExportAttachment")
+            .field("filename_len", &self.filename.len())
+            .finish()
+    }
+}
+
+impl SiteExportAttachment {
+    /// Path of the attachment in the bundle.
+    pub fn path(&self) -> String {
+        format!(
+            "attachments/{}/{}",
+            self.id,
+            export_file_name(&self.filename)
+        )
+    }
+}
+
+/// A file name that is safe in a ZIP entry and a URL: ASCII letters, digits,
+/// `.`, `-` and `_`, everything else replaced by `_`.
+fn export_file_name(filename: &str) -> String {
+    let name: String = filename
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_EXPORT_FILE_NAME_CHARS)
+        .collect();
+    let name = name.trim_start_matches('.');
+    if name.is_empty() {
+        "file".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Page file names without extension, by note ID: the slugged title, with
+/// `-2`, `-3` and so on added to repeats in `notes` order.
+fn page_stems(notes: &[SiteExportNote]) -> HashMap<Uuid, String> {
+    let mut taken = HashSet::new();
+    let mut stems = HashMap::with_capacity(notes.len());
+    for note in notes {
+        let base = match slugify(&note.title) {
+            slug if slug.is_empty() => "note".to_string(),
+            slug => slug,
+        };
+        let mut stem = base.clone();
+        let mut n = 2;
+        while !taken.insert(stem.clone()) {
+            stem = format!("{base}-{n}");
+            n += 1;
+        }
+        stems.insert(note.id, stem);
+    }
+    stems
+}
+
+/// A YAML scalar for front-matter; JSON strings are valid YAML.
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// A complete HTML page of an exported site.
+fn export_document(site_title: &str, page_title: Option<&str>, home: &str, body: &str) -> String {
+    let site_title = escape_html(site_title);
+    let title = match page_title {
+        Some(page_title) => format!("{} · {site_title}", escape_html(page_title)),
+        None => site_title.clone(),
+    };
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+<title>{title}</title>\n<style>{SITE_STYLE}</style>\n</head>\n<body>\n<header>\n\
+<h1><a href=\"{home}\">{site_title}</a></h1>\n</header>\n<main>\n{body}</main>\n</body>\n</html>\n"
+    )
+}
+
+/// Render the pages of a site export as `(path, contents)` pairs, the index
+/// first and then the notes in `notes` order.
+///
+/// `attachments` are listed at the end of their note's page; their bytes are
+/// bundled by the caller at [`SiteExportAttachment::path`].
+pub fn render_site_export(
+    title: &str,
+    description: Option<&str>,
+    notes: &[SiteExportNote],
+    attachments: &[SiteExportAttachment],
+    format: SiteExportFormat,
+) -> Vec<(String, String)> {
+    let ext = format.extension();
+    let stems = page_stems(notes);
+    let summaries: Vec<PublishedPageSummary> = notes
+        .iter()
+        .map(|note| PublishedPageSummary {
+            id: note.id,
+            title: note.title.clone(),
+            updated_at_utc: note.updated_at_utc,
+        })
+        .collect();
+    let index = PublishedPageIndex::new(&summaries);
+    let contents: HashMap<Uuid, String> = notes
+        .iter()
+        .map(|note| (note.id, note.content.clone()))
+        .collect();
+    let href = |page: &PublishedPageSummary| format!("{}.{ext}", stems[&page.id]);
+
+    let mut files = Vec::with_capacity(notes.len() + 1);
+    files.push((
+        format!("index.{ext}"),
+        render_index(title, description, notes, &stems, format),
+    ));
+    for note in notes {
+        let mut markdown = resolve_wiki_markdown(&note.content, note.id, &index, &contents, &href);
+        let note_attachments: Vec<&SiteExportAttachment> = attachments
+            .iter()
+            .filter(|attachment| attachment.note_id == note.id)
+            .collect();
+        if !note_attachments.is_empty() {
+            markdown.push_str("\n\n## Attachments\n\n");
+            for attachment in note_attachments {
+                markdown.push_str(&format!(
+                    "- [{}](../{})\n",
+                    attachment.filename.replace('[', "\\[").replace(']', "\\]"),
+                    attachment.path()
+                ));
+            }
+        }
+        let page = match format {
+            SiteExportFormat::Html => export_document(
+                title,
+                Some(&note.title),
+                "../index.html",
+                &format!(
+                    "<article>\n<h2>{}</h2>\n{}</article>\n<footer>Updated {}</footer>\n",
+                    escape_html(&note.title),
+                    render_markdown_html(&markdown),
+                    note.updated_at_utc.format("%Y-%m-%d")
+                ),
+            ),
+            SiteExportFormat::Markdown => {
+                let mut page = String::from("---\n");
+                page.push_str(&format!("id: {}\n", note.id));
+                page.push_str(&format!("title: {}\n", yaml_string(&note.title)));
+                page.push_str(&format!("created: {}\n", note.created_at_utc.to_rfc3339()));
+                page.push_str(&format!("updated: {}\n", note.updated_at_utc.to_rfc3339()));
+                if !note.tags.is_empty() {
+                    page.push_str("tags:\n");
+                    for tag in &note.tags {
+                        page.push_str(&format!("  - {}\n", yaml_string(tag)));
+                    }
+                }
+                page.push_str("---\n\n");
+                page.push_str(markdown.trim_start());
+                if !page.ends_with('\n') {
+                    page.push('\n');
+                }
+                page
+            }
+        };
+        files.push((format!("notes/{}.{ext}", stems[&note.id]), page));
+    }
+    files
+}
+
+fn render_index(
+    title: &str,
+    description: Option<&str>,
+    notes: &[SiteExportNote],
+    stems: &HashMap<Uuid, String>,
+    format: SiteExportFormat,
+) -> String {
+    match format {
+        SiteExportFormat::Html => {
+            let mut body = String::new();
+            if let Some(description) = description {
+                body.push_str(&format!("<p>{}</p>\n", escape_html(description)));
+            }
+            body.push_str("<ul>\n");
+            for note in notes {
+                body.push_str(&format!(
+                    "<li><a href=\"notes/{}.html\">{}</a></li>\n",
+                    stems[&note.id],
+                    escape_html(&note.title)
+                ));
+            }
+            body.push_str("</ul>\n");
+            export_document(title, None, "index.html", &body)
+        }
+        SiteExportFormat::Markdown => {
+            let mut page = format!("---\ntitle: {}\n---\n\n", yaml_string(title));
+            page.push_str(&format!("# {title}\n\n"));
+            if let Some(description) = description {
+                page.push_str(&format!("{description}\n\n"));
+            }
+            for note in notes {
+                page.push_str(&format!(
+                    "- [{}](notes/{}.md)\n",
+                    note.title.replace('[', "\\[").replace(']', "\\]"),
+                    stems[&note.id]
+                ));
+            }
+            page
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(title: &str, content: &str) -> SiteExportNote {
+        SiteExportNote {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            content: content.to_string(),
+            tags: vec!["research/ml".to_string()],
+            created_at_utc: Utc::now(),
+            updated_at_utc: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn pages_get_unique_names_and_relative_links() {
+        let notes = vec![
+            note("Alpha", "See [[Beta]] and [[Missing]].\n\n![[Gamma]]"),
+            note("Beta", "beta"),
+            note("Beta!", "second beta"),
+            note("Gamma", "gamma body"),
+            note("???", "no name"),
+        ];
+        let attachments = vec![SiteExportAttachment {
+            id: Uuid::nil(),
+            note_id: notes[0].id,
+            filename: "../Report (final).pdf".to_string(),
+        }];
+        let files = render_site_export(
+            "Field Notes",
+            None,
+            &notes,
+            &attachments,
+            SiteExportFormat::Markdown,
+        );
+        let paths: Vec<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "index.md",
+                "notes/alpha.md",
+                "notes/beta.md",
+                "notes/beta-2.md",
+                "notes/gamma.md",
+                "notes/note.md"
+            ]
+        );
+        let alpha = &files[1].1;
+        assert!(alpha.starts_with(&format!("---\nid: {}\ntitle: \"Alpha\"\n", notes[0].id)));
+        assert!(alpha.contains("tags:\n  - \"research/ml\"\n"));
+        assert!(alpha.contains("See [Beta](beta.md) and Missing."));
+        assert!(alpha.contains("gamma body"));
+        assert!(alpha.contains(
+            "- [../Report (final).pdf](../attachments/00000000-0000-0000-0000-000000000000/_Report__final_.pdf)"
+        ));
+        assert!(files[0].1.contains("- [Beta!](notes/beta-2.md)"));
+    }
+
+    #[test]
+    fn html_pages_link_back_to_the_index() {
+        let notes = vec![note("<One>", "[[Two]]"), note("Two", "two")];
+        let files = render_site_export(
+            "Site",
+            Some("About <this>"),
+            &notes,
+            &[],
+            SiteExportFormat::Html,
+        );
+        assert_eq!(files[0].0, "index.html");
+        assert!(files[0].1.contains("<p>About &lt;this&gt;</p>"));
+        assert!(files[0]
+            .1
+            .contains("<a href=\"notes/one.html\">&lt;One&gt;</a>"));
+        assert!(files[1].1.contains("<a href=\"../index.html\">Site</a>"));
+        assert!(files[1].1.contains("<a href=\"two.html\">Two</a>"));
+    }
+}
@@ -0,0 +1,227 @@
+//! Highlighted search snippets.
+//!
+//! Search results carry a [`SearchSnippet`]: excerpts of the note with the
+//! query's matches marked. Notes whose content matches the full-text query
+//! are excerpted from their content with PostgreSQL's `ts_headline`; other
+//! hits found by semantic search are excerpted from their best-matching
+//! embedding chunk. Excerpts are plain text with the marks given as
+//! character offsets, and as HTML with the marks wrapped in `<mark>`.
+//!
+//! ```
+//! use matric_core::snippet::{parse_headline, SnippetOptions, MARK_START, MARK_STOP};
+//!
+//! let raw = format!("the {MARK_START}quick{MARK_STOP} fox & co");
+//! let fragments = parse_headline(&raw);
+//! assert_eq!(fragments[0].text, "the quick fox & co");
+//! assert_eq!((fragments[0].marks[0].start, fragments[0].marks[0].end), (4, 9));
+//! assert_eq!(fragments[0].html, "the <mark>quick</mark> fox &amp; co");
+//! assert!(SnippetOptions::default().headline_options().contains("MaxWords=35"));
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Opens a match in `ts_headline` output (a private-use character, so it
+/// cannot be confused with note text).
+pub const MARK_START: char = '\u{E000}';
+
+/// Closes a match in `ts_headline` output.
+pub const MARK_STOP: char = '\u{E001}';
+
+/// Separates fragments in `ts_headline` output.
+pub const FRAGMENT_DELIMITER: char = '\u{E002}';
+
+/// Words per excerpt unless asked otherwise.
+pub const DEFAULT_SNIPPET_WORDS: u32 = 35;
+
+/// Fewest words per excerpt a caller may ask for.
+pub const MIN_SNIPPET_WORDS: u32 = 5;
+
+/// Most words per excerpt a caller may ask for.
+pub const MAX_SNIPPET_WORDS: u32 = 100;
+
+/// Excerpts per result unless asked otherwise.
+pub const DEFAULT_SNIPPET_FRAGMENTS: u32 = 1;
+
+/// Most excerpts per result a caller may ask for.
+pub const MAX_SNIPPET_FRAGMENTS: u32 = 5;
+
+/// Where a snippet's excerpts were taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetSource {
+    Content,
+    Chunk,
+}
+
+/// Character range (Unicode scalar values, end exclusive) of a match within
+/// a fragment's `text`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct MarkRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// One excerpt of a search result.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SnippetFragment {
+    /// The excerpt as plain text
+    pub text: String,
+    /// The excerpt HTML-escaped, with matches wrapped in `<mark>`
+    pub html: String,
+    /// Matches within `text`
+    pub marks: Vec<MarkRange>,
+}
+
+impl fmt::Debug for SnippetFragment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnippetFragment")
+            .field("text_len", &self.text.len())
+            .field("marks", &self.marks)
+            .finish()
+    }
+}
+
+/// Highlighted excerpts of a search result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SearchSnippet {
+    /// `content` when excerpted from the note's content, `chunk` when from
+    /// its best-matching embedding chunk
+    pub source: SnippetSource,
+    /// Chunk the excerpts came from, for `chunk` snippets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk_index: Option<i32>,
+    pub fragments: Vec<SnippetFragment>,
+}
+
+/// Length and number of excerpts per result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnippetOptions {
+    /// Longest excerpt, in words
+    pub max_words: u32,
+    /// Most excerpts per result
+    pub fragments: u32,
+}
+
+impl Default for SnippetOptions {
+    fn default() -> Self {
+        Self {
+            max_words: DEFAULT_SNIPPET_WORDS,
+            fragments: DEFAULT_SNIPPET_FRAGMENTS,
+        }
+    }
+}
+
+impl SnippetOptions {
+    /// Options from request parameters, clamped to the allowed ranges.
+    pub fn new(max_words: Option<u32>, fragments: Option<u32>) -> Self {
+        Self {
+            max_words: max_words
+                .unwrap_or(DEFAULT_SNIPPET_WORDS)
+                .clamp(MIN_SNIPPET_WORDS, MAX_SNIPPET_WORDS),
+            fragments: fragments
+                .unwrap_or(DEFAULT_SNIPPET_FRAGMENTS)
+                .clamp(1, MAX_SNIPPET_FRAGMENTS),
+        }
+    }
+
+    /// The `ts_headline` options string.
+    pub fn headline_options(&self) -> String {
+        format!(
+            "MaxWords={}, MinWords={}, MaxFragments={}, \
+             StartSel={MARK_START}, StopSel={MARK_STOP}, FragmentDelimiter={FRAGMENT_DELIMITER}",
+            self.max_words,
+            (self.max_words / 2).max(1),
+            self.fragments,
+        )
+    }
+}
+
+/// Split `ts_headline` output produced with
+/// [`SnippetOptions::headline_options`] into fragments. Empty fragments are
+/// dropped; an unclosed mark runs to the end of its fragment.
+pub fn parse_headline(raw: &str) -> Vec<SnippetFragment> {
+    raw.split(FRAGMENT_DELIMITER)
+        .filter_map(|fragment| {
+            let fragment = fragment.trim();
+            if fragment.is_empty() {
+                return None;
+            }
+            let mut text = String::with_capacity(fragment.len());
+            let mut html = String::with_capacity(fragment.len() + 16);
+            let mut marks = Vec::new();
+            let mut chars = 0;
+            let mut open: Option<usize> = None;
+            for c in fragment.chars() {
+                match c {
+                    MARK_START if open.is_none() => {
+                        open = Some(chars);
+                        html.push_str("<mark>");
+                    }
+                    MARK_STOP => {
+                        if let Some(start) = open.take() {
+                            marks.push(MarkRange { start, end: chars });
+                            html.push_str("</mark>");
+                        }
+                    }
+                    MARK_START => {}
+                    c => {
+                        text.push(c);
+                        chars += 1;
+                        match c {
+                            '&' => html.push_str("&amp;"),
+                            '<' => html.push_str("&lt;"),
+                            '>' => html.push_str("&gt;"),
+                            '"' => html.push_str("&quot;"),
+                            '\'' => html.push_str("&#39;"),
+                            c => html.push(c),
+                        }
+                    }
+                }
+            }
+            if let Some(start) = open {
+                marks.push(MarkRange { start, end: chars });
+                html.push_str("</mark>");
+            }
+            marks.retain(|mark| mark.end > mark.start);
+            Some(SnippetFragment { text, html, marks })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragments_split_and_offsets_count_characters() {
+        let raw = format!(
+            "über {MARK_START}café{MARK_STOP} <b>{FRAGMENT_DELIMITER} {FRAGMENT_DELIMITER}\
+             last {MARK_START}match"
+        );
+
+        let fragments = parse_headline(&raw);
+
+        assert_eq!(fragments.len(), 2);
+        assert_eq!(fragments[0].text, "über café <b>");
+        assert_eq!(fragments[0].marks, vec![MarkRange { start: 5, end: 9 }]);
+        assert_eq!(fragments[0].html, "über <mark>café</mark> &lt;b&gt;");
+        assert_eq!(fragments[1].text, "last match");
+        assert_eq!(fragments[1].marks, vec![MarkRange { start: 5, end: 10 }]);
+        assert_eq!(fragments[1].html, "last <mark>match</mark>");
+    }
+
+    #[test]
+    fn options_are_clamped() {
+        let options = SnippetOptions::new(Some(1000), Some(0));
+        assert_eq!(options.max_words, MAX_SNIPPET_WORDS);
+        assert_eq!(options.fragments, 1);
+        let options = SnippetOptions::new(Some(1), Some(9));
+        assert_eq!(options.max_words, MIN_SNIPPET_WORDS);
+        assert_eq!(options.fragments, MAX_SNIPPET_FRAGMENTS);
+        assert!(options
+            .headline_options()
+            .starts_with("MaxWords=5, MinWords=2, MaxFragments=5"));
+    }
+}
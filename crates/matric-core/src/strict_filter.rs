@@ -327,6 +327,14 @@ pub struct StrictSecurityFilter {
     /// Whether to include notes shared with the user.
     #[serde(default = "default_true")]
     pub include_shared: bool,
+
+    /// Whether notes without an owner pass the owner check.
+    #[serde(default)]
+    pub include_unowned: bool,
+
+    /// Visibility levels that pass the owner check whoever owns the note.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub open_visibility: Vec<Visibility>,
 }
 
 impl fmt::Debug for StrictSecurityFilter {
@@ -338,6 +346,8 @@ impl fmt::Debug for StrictSecurityFilter {
             .field("shared_with_user_set", &self.shared_with_user.is_some())
             .field("include_owned", &self.include_owned)
             .field("include_shared", &self.include_shared)
+            .field("include_unowned", &self.include_unowned)
+            .field("open_visibility", &self.open_visibility)
             .finish()
     }
 }
@@ -347,7 +357,7 @@ fn default_true() -> bool {
 }
 
 /// Visibility level for notes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Visibility {
     /// Only visible to owner.
@@ -356,10 +366,22 @@ pub enum Visibility {
     Shared,
     /// Visible to all users in tenant.
     Internal,
-    /// Visible to everyone.
+    /// Visible to everyone, including readers of published collections.
     Public,
 }
 
+impl Visibility {
+    /// The `note_visibility` value.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Private => "private",
+            Self::Shared => "shared",
+            Self::Internal => "internal",
+            Self::Public => "public",
+        }
+    }
+}
+
 impl StrictSecurityFilter {
     /// Create a new empty security filter.
     pub fn new() -> Self {
@@ -393,6 +415,16 @@ impl StrictSecurityFilter {
         self
     }
 
+    /// Everything a signed-in user may read: notes they own, notes shared
+    /// with them directly or through a collection, unowned notes, and
+    /// `internal` or `public` notes.
+    pub fn for_caller(user_id: Uuid) -> Self {
+        let mut filter = Self::new().for_user(user_id);
+        filter.include_unowned = true;
+        filter.open_visibility = vec![Visibility::Internal, Visibility::Public];
+        filter
+    }
+
     /// Check if the filter is empty.
     pub fn is_empty(&self) -> bool {
         self.owner_id.is_none()
@@ -674,6 +706,19 @@ mod tests {
         assert_eq!(filter.shared_with_user, Some(user_id));
     }
 
+    #[test]
+    fn caller_filter_admits_unowned_and_open_notes() {
+        let user_id = Uuid::new_v4();
+        let filter = StrictSecurityFilter::for_caller(user_id);
+
+        assert_eq!(filter.owner_id, Some(user_id));
+        assert!(filter.include_unowned);
+        assert_eq!(
+            filter.open_visibility,
+            vec![Visibility::Internal, Visibility::Public]
+        );
+    }
+
     #[test]
     fn test_semantic_scope_filter() {
         let set_id = Uuid::new_v4();
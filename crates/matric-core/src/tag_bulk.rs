@@ -0,0 +1,280 @@
+//! Bulk tag operations.
+//!
+//! One [`BulkTagOperation`] rewrites a tag across every note of an archive
+//! in a single transaction: rename a tag, merge one tag into another, delete
+//! a tag (optionally moving its notes to another tag first), or apply or
+//! remove a tag on the notes matched by a [`BulkTagFilter`]. The
+//! [`BulkTagSummary`] names the notes whose tags changed.
+//!
+//! Tag names match exactly; hierarchical children (`a/b` under `a`) are
+//! separate tags and are not touched by an operation on their parent.
+//!
+//! ```
+//! use matric_core::BulkTagOperation;
+//!
+//! let op: BulkTagOperation =
+//!     serde_json::from_str(r#"{"op": "merge", "source": "ml", "target": "machine-learning"}"#)
+//!         .unwrap();
+//! assert!(op.validate().is_ok());
+//! assert_eq!(op.name(), "merge");
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::defaults::TAG_NAME_MAX_LENGTH;
+use crate::tags::MAX_TAG_PATH_DEPTH;
+use crate::{Error, Result};
+
+/// Most note IDs one bulk tag filter may name.
+pub const MAX_BULK_TAG_NOTE_IDS: usize = 1_000;
+
+/// Which notes an `apply` or `remove` operation touches.
+///
+/// A note must match every selector given; deleted notes never match.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BulkTagFilter {
+    /// Notes with these IDs
+    #[serde(default)]
+    pub note_ids: Vec<Uuid>,
+    /// Notes filed directly in this collection
+    #[serde(default)]
+    pub collection_id: Option<Uuid>,
+    /// Notes carrying all of these tags (hierarchical tags match their children)
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl fmt::Debug for BulkTagFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BulkTagFilter")
+            .field("note_ids_count", &self.note_ids.len())
+            .field("collection_id_set", &self.collection_id.is_some())
+            .field("tags_count", &self.tags.len())
+            .finish()
+    }
+}
+
+impl BulkTagFilter {
+    fn validate(&self) -> Result<()> {
+        if self.note_ids.is_empty() && self.collection_id.is_none() && self.tags.is_empty() {
+            return Err(Error::InvalidInput(
+                "filter requires note_ids, collection_id or tags".to_string(),
+            ));
+        }
+        if self.note_ids.len() > MAX_BULK_TAG_NOTE_IDS {
+            return Err(Error::InvalidInput(format!(
+                "filter may name at most {MAX_BULK_TAG_NOTE_IDS} note_ids"
+            )));
+        }
+        if self.tags.iter().any(|tag| tag.trim().is_empty()) {
+            return Err(Error::InvalidInput(
+                "filter tags must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A tag rewrite applied to every matching note in one transaction.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkTagOperation {
+    /// Rename `from` to `to`, which must not exist yet.
+    Rename { from: String, to: String },
+    /// Move every note tagged `source` to `target` and drop `source`.
+    Merge { source: String, target: String },
+    /// Delete `tag`, first moving its notes to `reassign_to` when given.
+    Delete {
+        tag: String,
+        #[serde(default)]
+        reassign_to: Option<String>,
+    },
+    /// Add `tag` to every note matching `filter`.
+    Apply { tag: String, filter: BulkTagFilter },
+    /// Remove `tag` from every note matching `filter`.
+    Remove { tag: String, filter: BulkTagFilter },
+}
+
+impl fmt::Debug for BulkTagOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("BulkTagOperation");
+        s.field("op", &self.name());
+        match self {
+            Self::Rename { from, to } => {
+                s.field("from_len", &from.len()).field("to_len", &to.len())
+            }
+            Self::Merge { source, target } => s
+                .field("source_len", &source.len())
+                .field("target_len", &target.len()),
+            Self::Delete { tag, reassign_to } => s
+                .field("tag_len", &tag.len())
+                .field("reassign_to_len", &reassign_to.as_ref().map(String::len)),
+            Self::Apply { tag, filter } | Self::Remove { tag, filter } => {
+                s.field("tag_len", &tag.len()).field("filter", filter)
+            }
+        };
+        s.finish()
+    }
+}
+
+pub(crate) fn validate_tag(field: &str, tag: &str) -> Result<()> {
+    if tag.trim().is_empty() {
+        return Err(Error::InvalidInput(format!("{field} must not be empty")));
+    }
+    if tag.len() > TAG_NAME_MAX_LENGTH {
+        return Err(Error::InvalidInput(format!(
+            "{field} exceeds {TAG_NAME_MAX_LENGTH} character limit"
+        )));
+    }
+    if tag.split('/').filter(|s| !s.trim().is_empty()).count() > MAX_TAG_PATH_DEPTH {
+        return Err(Error::InvalidInput(format!(
+            "{field} exceeds maximum depth of {MAX_TAG_PATH_DEPTH} levels"
+        )));
+    }
+    Ok(())
+}
+
+fn validate_distinct(old: &str, new: &str) -> Result<()> {
+    if old == new {
+        return Err(Error::InvalidInput(
+            "source and target tags must differ".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl BulkTagOperation {
+    /// The `op` tag of this operation.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rename { .. } => "rename",
+            Self::Merge { .. } => "merge",
+            Self::Delete { .. } => "delete",
+            Self::Apply { .. } => "apply",
+            Self::Remove { .. } => "remove",
+        }
+    }
+
+    /// Check tag names are present, within limits and distinct, and that a
+    /// filter selects something.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Rename { from, to } => {
+                validate_tag("from", from)?;
+                validate_tag("to", to)?;
+                validate_distinct(from, to)
+            }
+            Self::Merge { source, target } => {
+                validate_tag("source", source)?;
+                validate_tag("target", target)?;
+                validate_distinct(source, target)
+            }
+            Self::Delete { tag, reassign_to } => {
+                validate_tag("tag", tag)?;
+                match reassign_to {
+                    Some(target) => {
+                        validate_tag("reassign_to", target)?;
+                        validate_distinct(tag, target)
+                    }
+                    None => Ok(()),
+                }
+            }
+            Self::Apply { tag, filter } | Self::Remove { tag, filter } => {
+                validate_tag("tag", tag)?;
+                filter.validate()
+            }
+        }
+    }
+}
+
+/// What a bulk tag operation changed.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BulkTagSummary {
+    /// The operation that ran
+    pub op: String,
+    /// Notes whose tags changed
+    pub notes_affected: usize,
+    pub note_ids: Vec<Uuid>,
+}
+
+impl fmt::Debug for BulkTagSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BulkTagSummary")
+            .field("op", &self.op)
+            .field("notes_affected", &self.notes_affected)
+            .finish()
+    }
+}
+
+impl BulkTagSummary {
+    pub fn new(op: &BulkTagOperation, note_ids: Vec<Uuid>) -> Self {
+        Self {
+            op: op.name().to_string(),
+            notes_affected: note_ids.len(),
+            note_ids,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid_message(op: &BulkTagOperation) -> String {
+        match op.validate() {
+            Err(Error::InvalidInput(message)) => message,
+            other => panic!("expected invalid input, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn validate_rejects_same_source_and_target() {
+        let op = BulkTagOperation::Rename {
+            from: "a".to_string(),
+            to: "a".to_string(),
+        };
+        assert!(invalid_message(&op).contains("must differ"));
+
+        let op = BulkTagOperation::Delete {
+            tag: "a".to_string(),
+            reassign_to: Some("a".to_string()),
+        };
+        assert!(invalid_message(&op).contains("must differ"));
+    }
+
+    #[test]
+    fn validate_checks_tag_depth() {
+        let op = BulkTagOperation::Merge {
+            source: "a".to_string(),
+            target: "a/b/c/d/e/f".to_string(),
+        };
+        assert!(invalid_message(&op).contains("target exceeds maximum depth"));
+    }
+
+    #[test]
+    fn apply_requires_a_filter_selector() {
+        let op: BulkTagOperation =
+            serde_json::from_str(r#"{"op": "apply", "tag": "x", "filter": {}}"#).unwrap();
+        assert!(invalid_message(&op).contains("filter requires"));
+
+        let op: BulkTagOperation = serde_json::from_str(
+            r#"{"op": "remove", "tag": "x", "filter": {"tags": ["project/alpha"]}}"#,
+        )
+        .unwrap();
+        assert!(op.validate().is_ok());
+    }
+
+    #[test]
+    fn debug_redacts_tag_names() {
+        let op = BulkTagOperation::Rename {
+            from: "client-secret".to_string(),
+            to: "client-public".to_string(),
+        };
+        let debug = format!("{op:?}");
+        assert!(!debug.contains("client-"));
+        assert!(debug.contains("from_len: 13"));
+    }
+}
@@ -377,6 +377,12 @@ pub enum TagAntipattern {
 
     /// Circular hierarchy: Detected cycle in broader/narrower chain.
     CircularHierarchy,
+
+    /// Near duplicate: Preferred label nearly matches another concept's.
+    NearDuplicate,
+
+    /// Unused: Tags no notes and has no narrower concepts.
+    Unused,
 }
 
 impl std::fmt::Display for TagAntipattern {
@@ -390,6 +396,8 @@ impl std::fmt::Display for TagAntipattern {
             Self::PolyhierarchyExcess => write!(f, "polyhierarchy_excess"),
             Self::MissingLabels => write!(f, "missing_labels"),
             Self::CircularHierarchy => write!(f, "circular_hierarchy"),
+            Self::NearDuplicate => write!(f, "near_duplicate"),
+            Self::Unused => write!(f, "unused"),
         }
     }
 }
@@ -407,6 +415,8 @@ impl std::str::FromStr for TagAntipattern {
             "polyhierarchy_excess" | "polyhierarchyexcess" => Ok(Self::PolyhierarchyExcess),
             "missing_labels" | "missinglabels" => Ok(Self::MissingLabels),
             "circular_hierarchy" | "circularhierarchy" => Ok(Self::CircularHierarchy),
+            "near_duplicate" | "nearduplicate" => Ok(Self::NearDuplicate),
+            "unused" => Ok(Self::Unused),
             _ => Err(format!(
                 "Invalid tag antipattern; value_len={}",
                 text_len(s)
@@ -3513,6 +3523,11 @@ mod tests {
             "circular_hierarchy".parse::<TagAntipattern>().unwrap(),
             TagAntipattern::CircularHierarchy
         );
+        assert_eq!(
+            "near_duplicate".parse::<TagAntipattern>().unwrap(),
+            TagAntipattern::NearDuplicate
+        );
+        assert_eq!(TagAntipattern::Unused.to_string(), "unused");
     }
 
     #[test]
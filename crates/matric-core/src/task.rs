@@ -0,0 +1,511 @@
+//! Tasks found in notes.
+//!
+//! The `task_extraction` job records a note's tasks: Markdown checkboxes
+//! (`- [ ] ...`, `- [x] ...`) found by [`parse_checkbox_tasks`] and, when
+//! `TASK_LLM_EXTRACTION` is enabled, action items the fast model points out
+//! in prose. Due dates are read from the task text by [`parse_due_date`]:
+//! an ISO date, `today`, `tomorrow`, a weekday or `in N days|weeks` after
+//! `due`, `by` or `deadline`, and ISO dates after `📅` or in `@due(...)`.
+//!
+//! Completing a checkbox task ticks its box in the note ([`set_checkbox`]);
+//! action items have no box, so only their status changes, and it is kept
+//! when the note is extracted again.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use matric_core::task::{parse_checkbox_tasks, set_checkbox, TaskStatus};
+//!
+//! let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+//! let note = "# Trip\n- [ ] Book flights due tomorrow\n- [x] Renew passport\n";
+//! let tasks = parse_checkbox_tasks(note, today);
+//! assert_eq!(tasks[0].text, "Book flights due tomorrow");
+//! assert_eq!(tasks[0].due_date, NaiveDate::from_ymd_opt(2026, 10, 19));
+//! assert_eq!(tasks[1].status, TaskStatus::Done);
+//!
+//! let ticked = set_checkbox(note, Some(1), "Book flights due tomorrow", true).unwrap();
+//! assert!(ticked.contains("- [x] Book flights"));
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Environment variable enabling LLM action item detection (default false).
+pub const ENV_TASK_LLM_EXTRACTION: &str = "TASK_LLM_EXTRACTION";
+
+/// Most tasks kept per note.
+pub const MAX_TASKS_PER_NOTE: usize = 500;
+
+/// Longest task text kept, in characters; longer text is cut.
+pub const MAX_TASK_TEXT_CHARS: usize = 500;
+
+/// Most days `in N days` may reach.
+const MAX_DUE_DAY_OFFSET: i64 = 3_650;
+
+static CHECKBOX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\s*(?:[-*+]|\d{1,9}[.)])\s+\[)([ xX])(\]\s+)(\S.*?)\s*$")
+        .expect("valid checkbox pattern")
+});
+
+static DUE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:due|by|deadline)\b\s*:?\s*(?:on\s+|in\s+)?(\d{4}-\d{2}-\d{2}|today|tomorrow|mon(?:day)?|tue(?:s|sday)?|wed(?:nesday)?|thu(?:rs|rsday)?|fri(?:day)?|sat(?:urday)?|sun(?:day)?|(\d{1,4})\s+(days?|weeks?))\b",
+    )
+    .expect("valid due date pattern")
+});
+
+static DUE_MARKER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:📅\s*|@due\(\s*)(\d{4}-\d{2}-\d{2})").expect("valid due marker pattern")
+});
+
+/// Whether a task is still to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Open,
+    Done,
+}
+
+impl TaskStatus {
+    pub const ALL: [TaskStatus; 2] = [TaskStatus::Open, TaskStatus::Done];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            TaskStatus::Open => "open",
+            TaskStatus::Done => "done",
+        }
+    }
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TaskStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == value)
+            .ok_or_else(|| format!("unknown task status: {value}"))
+    }
+}
+
+impl TryFrom<String> for TaskStatus {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// How a task was found in its note: as a Markdown checkbox, or as an action
+/// item the model found in prose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSource {
+    Checkbox,
+    Llm,
+}
+
+impl TaskSource {
+    pub const ALL: [TaskSource; 2] = [TaskSource::Checkbox, TaskSource::Llm];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            TaskSource::Checkbox => "checkbox",
+            TaskSource::Llm => "llm",
+        }
+    }
+}
+
+impl fmt::Display for TaskSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TaskSource {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|source| source.as_str() == value)
+            .ok_or_else(|| format!("unknown task source: {value}"))
+    }
+}
+
+impl TryFrom<String> for TaskSource {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// A task found in a note, before it is stored.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExtractedTask {
+    pub text: String,
+    pub status: TaskStatus,
+    pub source: TaskSource,
+    pub due_date: Option<NaiveDate>,
+    /// Line of the checkbox in the note's original content, from 0
+    pub line: Option<usize>,
+}
+
+impl fmt::Debug for ExtractedTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtractedTask")
+            .field("text_len", &self.text.len())
+            .field("status", &self.status)
+            .field("source", &self.source)
+            .field("due_date", &self.due_date)
+            .field("line", &self.line)
+            .finish()
+    }
+}
+
+/// A stored task.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct Task {
+    pub id: Uuid,
+    /// The note the task was found in
+    pub note_id: Uuid,
+    pub text: String,
+    #[sqlx(try_from = "String")]
+    pub status: TaskStatus,
+    #[sqlx(try_from = "String")]
+    pub source: TaskSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<NaiveDate>,
+    /// Line of the checkbox in the note's original content, from 0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at_utc: Option<DateTime<Utc>>,
+    pub created_at_utc: DateTime<Utc>,
+    pub updated_at_utc: DateTime<Utc>,
+}
+
+impl fmt::Debug for Task {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Task")
+            .field("id", &self.id)
+            .field("note_id", &self.note_id)
+            .field("text_len", &self.text.len())
+            .field("status", &self.status)
+            .field("source", &self.source)
+            .field("due_date", &self.due_date)
+            .field("line", &self.line)
+            .finish()
+    }
+}
+
+/// One page of tasks, soonest due first.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TaskListing {
+    pub tasks: Vec<Task>,
+    /// Matching tasks across all pages
+    pub total: i64,
+}
+
+impl fmt::Debug for TaskListing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskListing")
+            .field("task_count", &self.tasks.len())
+            .field("total", &self.total)
+            .finish()
+    }
+}
+
+/// Which tasks to list.
+#[derive(Debug, Clone, Default)]
+pub struct TaskFilter {
+    pub status: Option<TaskStatus>,
+    pub source: Option<TaskSource>,
+    pub note_id: Option<Uuid>,
+    /// Only tasks due on or before this day
+    pub due_before: Option<NaiveDate>,
+    /// Only tasks due on or after this day
+    pub due_after: Option<NaiveDate>,
+}
+
+/// Whether action items are detected by the model (`TASK_LLM_EXTRACTION`,
+/// default false).
+pub fn llm_extraction_enabled() -> bool {
+    std::env::var(ENV_TASK_LLM_EXTRACTION)
+        .ok()
+        .and_then(|v| v.trim().parse::<bool>().ok())
+        .unwrap_or(false)
+}
+
+/// Task text compared case- and whitespace-insensitively.
+pub fn normalize_task_text(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn clip_task_text(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(MAX_TASK_TEXT_CHARS) {
+        Some((end, _)) => text[..end].trim_end().to_string(),
+        None => text,
+    }
+}
+
+fn weekday_named(word: &str) -> Option<Weekday> {
+    match word.get(..3)? {
+        "mon" => Some(Weekday::Mon),
+        "tue" => Some(Weekday::Tue),
+        "wed" => Some(Weekday::Wed),
+        "thu" => Some(Weekday::Thu),
+        "fri" => Some(Weekday::Fri),
+        "sat" => Some(Weekday::Sat),
+        "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// The due date a task's text gives, relative to `today`. Weekdays mean the
+/// next such day, today included.
+pub fn parse_due_date(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    if let Some(found) = DUE_MARKER_RE.captures(text) {
+        if let Ok(date) = NaiveDate::parse_from_str(&found[1], "%Y-%m-%d") {
+            return Some(date);
+        }
+    }
+    for found in DUE_RE.captures_iter(text) {
+        if let (Some(count), Some(unit)) = (found.get(2), found.get(3)) {
+            let count: i64 = count.as_str().parse().ok()?;
+            let days = if unit.as_str().to_lowercase().starts_with("week") {
+                count * 7
+            } else {
+                count
+            };
+            if days > MAX_DUE_DAY_OFFSET {
+                return None;
+            }
+            return Some(today + Duration::days(days));
+        }
+        let word = found[1].to_lowercase();
+        if let Ok(date) = NaiveDate::parse_from_str(&word, "%Y-%m-%d") {
+            return Some(date);
+        }
+        match word.as_str() {
+            "today" => return Some(today),
+            "tomorrow" => return Some(today + Duration::days(1)),
+            _ => {}
+        }
+        if let Some(weekday) = weekday_named(&word) {
+            let ahead = (7 + weekday.num_days_from_monday() as i64
+                - today.weekday().num_days_from_monday() as i64)
+                % 7;
+            return Some(today + Duration::days(ahead));
+        }
+    }
+    None
+}
+
+/// Lines of `content` outside fenced code blocks, with their index.
+fn prose_lines(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut fenced = false;
+    content.lines().enumerate().filter(move |(_, line)| {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fenced = !fenced;
+            return false;
+        }
+        !fenced
+    })
+}
+
+/// The Markdown checkboxes of a note, in order, at most
+/// [`MAX_TASKS_PER_NOTE`]. Checkboxes inside fenced code blocks are skipped.
+pub fn parse_checkbox_tasks(content: &str, today: NaiveDate) -> Vec<ExtractedTask> {
+    prose_lines(content)
+        .filter_map(|(line, text)| {
+            let found = CHECKBOX_RE.captures(text)?;
+            let text = clip_task_text(&found[4]);
+            Some(ExtractedTask {
+                due_date: parse_due_date(&text, today),
+                status: if &found[2] == " " {
+                    TaskStatus::Open
+                } else {
+                    TaskStatus::Done
+                },
+                source: TaskSource::Checkbox,
+                line: Some(line),
+                text,
+            })
+        })
+        .take(MAX_TASKS_PER_NOTE)
+        .collect()
+}
+
+/// Add the action items the model found to a note's checkbox tasks, skipping
+/// ones that repeat a task already listed. An item's own due date wins over
+/// one read from its text.
+pub fn merge_action_items(
+    tasks: &mut Vec<ExtractedTask>,
+    items: impl IntoIterator<Item = (String, Option<NaiveDate>)>,
+    today: NaiveDate,
+) {
+    for (text, due_date) in items {
+        if tasks.len() >= MAX_TASKS_PER_NOTE {
+            break;
+        }
+        let text = clip_task_text(&text);
+        let key = normalize_task_text(&text);
+        if key.is_empty() || tasks.iter().any(|t| normalize_task_text(&t.text) == key) {
+            continue;
+        }
+        tasks.push(ExtractedTask {
+            due_date: due_date.or_else(|| parse_due_date(&text, today)),
+            status: TaskStatus::Open,
+            source: TaskSource::Llm,
+            line: None,
+            text,
+        });
+    }
+}
+
+/// Tick or untick the checkbox of a task in `content`.
+///
+/// The box is looked for on `line` first and then anywhere in the note, by
+/// its text. Returns the new content, or `None` when the note no longer has
+/// the checkbox.
+pub fn set_checkbox(content: &str, line: Option<usize>, text: &str, done: bool) -> Option<String> {
+    let key = normalize_task_text(text);
+    let matches = |candidate: &str| {
+        CHECKBOX_RE
+            .captures(candidate)
+            .is_some_and(|found| normalize_task_text(&clip_task_text(&found[4])) == key)
+    };
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let target = line
+        .filter(|&i| {
+            lines
+                .get(i)
+                .is_some_and(|l| matches(l.trim_end_matches(['\r', '\n'])))
+        })
+        .or_else(|| {
+            prose_lines(content)
+                .find(|(_, l)| matches(l))
+                .map(|(i, _)| i)
+        })?;
+
+    let current = lines[target];
+    let body = current.trim_end_matches(['\r', '\n']);
+    let ending = &current[body.len()..];
+    let found = CHECKBOX_RE.captures(body)?;
+    let mark = if done { "x" } else { " " };
+    let mark_at = found.get(2)?.range();
+    let replaced = format!(
+        "{}{mark}{}{ending}",
+        &body[..mark_at.start],
+        &body[mark_at.end..]
+    );
+
+    let mut out = String::with_capacity(content.len());
+    for (i, l) in lines.iter().enumerate() {
+        out.push_str(if i == target { &replaced } else { l });
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn due_dates_are_read_from_task_text() {
+        // A Sunday
+        let today = day(2026, 10, 18);
+        assert_eq!(
+            parse_due_date("Ship it due 2026-11-02", today),
+            Some(day(2026, 11, 2))
+        );
+        assert_eq!(
+            parse_due_date("Call Ana by Friday", today),
+            Some(day(2026, 10, 23))
+        );
+        assert_eq!(parse_due_date("Pay rent by sunday", today), Some(today));
+        assert_eq!(
+            parse_due_date("Deadline: tomorrow", today),
+            Some(day(2026, 10, 19))
+        );
+        assert_eq!(
+            parse_due_date("Review due in 2 weeks", today),
+            Some(day(2026, 11, 1))
+        );
+        assert_eq!(
+            parse_due_date("File taxes 📅 2027-04-15", today),
+            Some(day(2027, 4, 15))
+        );
+        assert_eq!(
+            parse_due_date("Renew @due(2026-12-01)", today),
+            Some(day(2026, 12, 1))
+        );
+        assert_eq!(parse_due_date("Written by Ana", today), None);
+        assert_eq!(parse_due_date("due in 99999 days", today), None);
+    }
+
+    #[test]
+    fn checkboxes_outside_code_blocks_become_tasks() {
+        let today = day(2026, 10, 18);
+        let note = "- [ ] First\n  * [X]  Second   task\n```\n- [ ] Not a task\n```\n1. [ ] Third\n- [] broken\n";
+        let tasks = parse_checkbox_tasks(note, today);
+        let texts: Vec<&str> = tasks.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, ["First", "Second task", "Third"]);
+        assert_eq!(tasks[1].status, TaskStatus::Done);
+        assert_eq!(tasks[2].line, Some(5));
+    }
+
+    #[test]
+    fn action_items_skip_repeats_of_checkboxes() {
+        let today = day(2026, 10, 18);
+        let mut tasks = parse_checkbox_tasks("- [ ] Email the landlord\n", today);
+        merge_action_items(
+            &mut tasks,
+            [
+                ("email  the Landlord".to_string(), None),
+                ("Send invoice by tomorrow".to_string(), None),
+                ("Book venue".to_string(), Some(day(2026, 12, 1))),
+            ],
+            today,
+        );
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[1].source, TaskSource::Llm);
+        assert_eq!(tasks[1].due_date, Some(day(2026, 10, 19)));
+        assert_eq!(tasks[2].due_date, Some(day(2026, 12, 1)));
+    }
+
+    #[test]
+    fn checkboxes_are_found_again_after_lines_move() {
+        let note = "Intro\r\n- [ ] Water plants\r\n- [x] Feed cat\r\n";
+        let ticked = set_checkbox(note, Some(0), "water plants", true).unwrap();
+        assert_eq!(ticked, "Intro\r\n- [x] Water plants\r\n- [x] Feed cat\r\n");
+        let unticked = set_checkbox(&ticked, Some(2), "Feed cat", false).unwrap();
+        assert_eq!(
+            unticked,
+            "Intro\r\n- [x] Water plants\r\n- [ ] Feed cat\r\n"
+        );
+        assert!(set_checkbox(note, Some(1), "Walk dog", true).is_none());
+    }
+}
@@ -0,0 +1,750 @@
+//! Taxonomy health analysis.
+//!
+//! The `taxonomy_health` job checks an archive's active concepts for
+//! structural and usage problems and stores a [`TaxonomyHealthReport`]. Each
+//! [`TaxonomyHealthIssue`] carries a [`TaxonomyFix`] that can be applied with
+//! the existing concept endpoints: add or remove a broader link, merge
+//! duplicates, or deprecate the concept. Flagged concepts also get the
+//! matching [`TagAntipattern`] so they can be found with concept search.
+//!
+//! ```
+//! use matric_core::taxonomy_health::{label_similarity, normalize_label};
+//!
+//! assert_eq!(normalize_label("  Machine-Learning "), "machine learning");
+//! assert!(label_similarity("Neural Network", "neural networks") > 0.9);
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::TagAntipattern;
+
+/// Deepest level a concept may sit at (0 = top concept). Matches the
+/// `too_deep` check of `skos_detect_antipatterns`.
+pub const TAXONOMY_MAX_DEPTH: i32 = 4;
+
+/// Neighbours each label is compared with in each sort order when looking
+/// for near-duplicates.
+const NEAR_DUPLICATE_WINDOW: usize = 8;
+
+/// Kind of problem found by the taxonomy health check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxonomyIssueKind {
+    /// No broader, narrower or related concepts.
+    Orphan,
+    /// Deeper than [`TAXONOMY_MAX_DEPTH`].
+    TooDeep,
+    /// Part of a cycle of broader links.
+    CircularHierarchy,
+    /// Preferred label nearly the same as another concept's.
+    NearDuplicate,
+    /// Tags no notes and has no narrower concepts.
+    Unused,
+}
+
+impl TaxonomyIssueKind {
+    pub const ALL: [TaxonomyIssueKind; 5] = [
+        TaxonomyIssueKind::Orphan,
+        TaxonomyIssueKind::TooDeep,
+        TaxonomyIssueKind::CircularHierarchy,
+        TaxonomyIssueKind::NearDuplicate,
+        TaxonomyIssueKind::Unused,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            TaxonomyIssueKind::Orphan => "orphan",
+            TaxonomyIssueKind::TooDeep => "too_deep",
+            TaxonomyIssueKind::CircularHierarchy => "circular_hierarchy",
+            TaxonomyIssueKind::NearDuplicate => "near_duplicate",
+            TaxonomyIssueKind::Unused => "unused",
+        }
+    }
+
+    /// The anti-pattern flag set on concepts with this issue.
+    pub const fn antipattern(self) -> TagAntipattern {
+        match self {
+            TaxonomyIssueKind::Orphan => TagAntipattern::Orphan,
+            TaxonomyIssueKind::TooDeep => TagAntipattern::TooDeep,
+            TaxonomyIssueKind::CircularHierarchy => TagAntipattern::CircularHierarchy,
+            TaxonomyIssueKind::NearDuplicate => TagAntipattern::NearDuplicate,
+            TaxonomyIssueKind::Unused => TagAntipattern::Unused,
+        }
+    }
+}
+
+impl fmt::Display for TaxonomyIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TaxonomyIssueKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == value)
+            .ok_or_else(|| format!("unknown taxonomy issue kind: {value}"))
+    }
+}
+
+/// Suggested fix for a taxonomy issue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TaxonomyFix {
+    /// Place the concept under a broader concept, or relate it to one.
+    AddBroader,
+    /// Replace the concept's broader link with one to a shallower ancestor.
+    MoveUnder { broader_id: Uuid },
+    /// Remove the broader link that closes the cycle.
+    RemoveBroader { broader_id: Uuid },
+    /// Merge the sources into the target with `POST /api/v1/concepts/merge`.
+    Merge {
+        source_ids: Vec<Uuid>,
+        target_id: Uuid,
+    },
+    /// Deprecate the concept.
+    Deprecate,
+}
+
+/// A problem found in one concept, with a suggested fix.
+#[derive(Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TaxonomyHealthIssue {
+    pub kind: TaxonomyIssueKind,
+    pub concept_id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pref_label: Option<String>,
+    /// Other concepts involved: the duplicate, or the rest of the cycle
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_concept_ids: Vec<Uuid>,
+    /// Depth of the concept, for `too_deep` issues
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<i32>,
+    /// Label similarity, 0 to 1, for `near_duplicate` issues
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f32>,
+    /// Suggested fix; absent when none can be worked out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<TaxonomyFix>,
+    /// The fix, described for a reviewer
+    pub suggestion: String,
+}
+
+impl fmt::Debug for TaxonomyHealthIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaxonomyHealthIssue")
+            .field("kind", &self.kind)
+            .field("concept_id", &self.concept_id)
+            .field("pref_label_len", &self.pref_label.as_ref().map(String::len))
+            .field("related_count", &self.related_concept_ids.len())
+            .field("depth", &self.depth)
+            .field("similarity", &self.similarity)
+            .field("fix", &self.fix)
+            .field("suggestion_len", &self.suggestion.len())
+            .finish()
+    }
+}
+
+/// Number of issues of each kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TaxonomyIssueCounts {
+    pub orphan: usize,
+    pub too_deep: usize,
+    pub circular_hierarchy: usize,
+    pub near_duplicate: usize,
+    pub unused: usize,
+}
+
+impl TaxonomyIssueCounts {
+    fn add(&mut self, kind: TaxonomyIssueKind) {
+        match kind {
+            TaxonomyIssueKind::Orphan => self.orphan += 1,
+            TaxonomyIssueKind::TooDeep => self.too_deep += 1,
+            TaxonomyIssueKind::CircularHierarchy => self.circular_hierarchy += 1,
+            TaxonomyIssueKind::NearDuplicate => self.near_duplicate += 1,
+            TaxonomyIssueKind::Unused => self.unused += 1,
+        }
+    }
+
+    /// Issues of all kinds.
+    pub fn total(&self) -> usize {
+        self.orphan + self.too_deep + self.circular_hierarchy + self.near_duplicate + self.unused
+    }
+}
+
+/// Result of a taxonomy health check of one archive.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TaxonomyHealthReport {
+    pub generated_at_utc: DateTime<Utc>,
+    /// Active (not deprecated or obsolete) concepts checked
+    pub concepts_checked: usize,
+    /// Depth beyond which concepts were reported as too deep
+    pub max_depth: i32,
+    /// Label similarity at which concepts were reported as near-duplicates
+    pub similarity_threshold: f32,
+    pub counts: TaxonomyIssueCounts,
+    pub issues: Vec<TaxonomyHealthIssue>,
+}
+
+impl TaxonomyHealthReport {
+    /// Anti-pattern flags to set, one per concept and flag.
+    ///
+    /// Near-duplicates and cycles flag every concept involved, not just the
+    /// one the issue is reported on.
+    pub fn antipattern_flags(&self) -> Vec<(Uuid, TagAntipattern)> {
+        let mut flags = Vec::new();
+        let mut seen = HashSet::new();
+        for issue in &self.issues {
+            let antipattern = issue.kind.antipattern();
+            let involved = match issue.kind {
+                TaxonomyIssueKind::NearDuplicate | TaxonomyIssueKind::CircularHierarchy => {
+                    issue.related_concept_ids.as_slice()
+                }
+                _ => &[],
+            };
+            for id in std::iter::once(&issue.concept_id).chain(involved) {
+                if seen.insert((*id, antipattern)) {
+                    flags.push((*id, antipattern));
+                }
+            }
+        }
+        flags
+    }
+}
+
+/// A concept as seen by the health check.
+#[derive(Clone, sqlx::FromRow)]
+pub struct TaxonomyConceptStats {
+    pub id: Uuid,
+    pub pref_label: Option<String>,
+    pub note_count: i32,
+    pub depth: i32,
+    pub broader_count: i32,
+    pub narrower_count: i32,
+    pub related_count: i32,
+}
+
+impl fmt::Debug for TaxonomyConceptStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaxonomyConceptStats")
+            .field("id", &self.id)
+            .field("pref_label_len", &self.pref_label.as_ref().map(String::len))
+            .field("note_count", &self.note_count)
+            .field("depth", &self.depth)
+            .field("broader_count", &self.broader_count)
+            .field("narrower_count", &self.narrower_count)
+            .field("related_count", &self.related_count)
+            .finish()
+    }
+}
+
+/// Fold a label for comparison: lowercase, with runs of punctuation and
+/// whitespace read as one space.
+pub fn normalize_label(label: &str) -> String {
+    label
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+fn char_similarity(a: &[char], b: &[char]) -> f32 {
+    let longest = a.len().max(b.len());
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    1.0 - levenshtein(a, b) as f32 / longest as f32
+}
+
+/// Similarity of two labels, 0 to 1: one minus their edit distance over the
+/// longer length, after [`normalize_label`].
+pub fn label_similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = normalize_label(a).chars().collect();
+    let b: Vec<char> = normalize_label(b).chars().collect();
+    char_similarity(&a, &b)
+}
+
+/// Pairs of labels at least `threshold` similar, most similar first.
+///
+/// Labels are compared with their nearest neighbours in sorted order and in
+/// reversed-text sorted order, so edits near either end of a label are
+/// found without comparing every pair.
+pub fn find_near_duplicates(labels: &[(Uuid, &str)], threshold: f32) -> Vec<(Uuid, Uuid, f32)> {
+    let folded: Vec<(Uuid, Vec<char>)> = labels
+        .iter()
+        .map(|(id, label)| (*id, normalize_label(label).chars().collect::<Vec<_>>()))
+        .filter(|(_, chars)| !chars.is_empty())
+        .collect();
+
+    let mut pairs: BTreeMap<(Uuid, Uuid), f32> = BTreeMap::new();
+    for reversed in [false, true] {
+        let mut order: Vec<(Vec<char>, usize)> = folded
+            .iter()
+            .enumerate()
+            .map(|(i, (_, chars))| {
+                let key = if reversed {
+                    chars.iter().rev().copied().collect()
+                } else {
+                    chars.clone()
+                };
+                (key, i)
+            })
+            .collect();
+        order.sort();
+
+        for (pos, (_, i)) in order.iter().enumerate() {
+            for (_, j) in order.iter().skip(pos + 1).take(NEAR_DUPLICATE_WINDOW) {
+                let (id_a, a) = &folded[*i];
+                let (id_b, b) = &folded[*j];
+                if id_a == id_b {
+                    continue;
+                }
+                // Edit distance is at least the length difference.
+                let (short, long) = (a.len().min(b.len()), a.len().max(b.len()));
+                if (short as f32 / long as f32) < threshold {
+                    continue;
+                }
+                let key = ((*id_a).min(*id_b), (*id_a).max(*id_b));
+                if pairs.contains_key(&key) {
+                    continue;
+                }
+                let similarity = char_similarity(a, b);
+                if similarity >= threshold {
+                    pairs.insert(key, similarity);
+                }
+            }
+        }
+    }
+
+    let mut pairs: Vec<(Uuid, Uuid, f32)> =
+        pairs.into_iter().map(|((a, b), s)| (a, b, s)).collect();
+    pairs.sort_by(|x, y| y.2.total_cmp(&x.2).then(x.0.cmp(&y.0)).then(x.1.cmp(&y.1)));
+    pairs
+}
+
+/// Cycles of broader links, as sorted sets of the concepts in each cycle.
+///
+/// `edges` are `(concept, broader)` pairs. A concept that is its own broader
+/// concept is a cycle of one.
+pub fn find_circular_hierarchys(edges: &[(Uuid, Uuid)]) -> Vec<Vec<Uuid>> {
+    let mut nodes: Vec<Uuid> = Vec::new();
+    let mut index_of: HashMap<Uuid, usize> = HashMap::new();
+    let mut adjacency: Vec<Vec<usize>> = Vec::new();
+    let mut node = |id: Uuid, nodes: &mut Vec<Uuid>, adjacency: &mut Vec<Vec<usize>>| {
+        *index_of.entry(id).or_insert_with(|| {
+            nodes.push(id);
+            adjacency.push(Vec::new());
+            nodes.len() - 1
+        })
+    };
+    for (concept, broader) in edges {
+        let from = node(*concept, &mut nodes, &mut adjacency);
+        let to = node(*broader, &mut nodes, &mut adjacency);
+        adjacency[from].push(to);
+    }
+
+    // Tarjan's strongly connected components, iteratively so a long chain
+    // cannot overflow the stack.
+    let n = nodes.len();
+    let mut index = vec![usize::MAX; n];
+    let mut lowlink = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut next_index = 0;
+    let mut cycles = Vec::new();
+    for root in 0..n {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        let mut frames = vec![(root, 0usize)];
+        while let Some(frame) = frames.last_mut() {
+            let v = frame.0;
+            if let Some(&w) = adjacency[v].get(frame.1) {
+                frame.1 += 1;
+                if index[w] == usize::MAX {
+                    index[w] = next_index;
+                    lowlink[w] = next_index;
+                    next_index += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    frames.push((w, 0));
+                } else if on_stack[w] {
+                    lowlink[v] = lowlink[v].min(index[w]);
+                }
+                continue;
+            }
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                lowlink[parent] = lowlink[parent].min(lowlink[v]);
+            }
+            if lowlink[v] == index[v] {
+                let mut component = Vec::new();
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component.push(nodes[w]);
+                    if w == v {
+                        break;
+                    }
+                }
+                if component.len() > 1 || adjacency[v].contains(&v) {
+                    component.sort();
+                    cycles.push(component);
+                }
+            }
+        }
+    }
+    cycles.sort();
+    cycles
+}
+
+/// The nearest ancestor of `id` no deeper than `max_depth - 1`, following
+/// the shallowest broader concept at each step.
+fn shallow_ancestor(
+    id: Uuid,
+    broader_of: &HashMap<Uuid, Vec<Uuid>>,
+    depth_of: &HashMap<Uuid, i32>,
+    max_depth: i32,
+) -> Option<Uuid> {
+    let mut seen = HashSet::from([id]);
+    let mut current = id;
+    loop {
+        let parent = broader_of
+            .get(&current)?
+            .iter()
+            .filter(|p| !seen.contains(*p))
+            .min_by_key(|p| (depth_of.get(*p).copied().unwrap_or(i32::MAX), **p))?;
+        if depth_of.get(parent).is_some_and(|&d| d < max_depth) {
+            return Some(*parent);
+        }
+        seen.insert(*parent);
+        current = *parent;
+    }
+}
+
+fn quoted(label: Option<&str>) -> String {
+    label.map_or_else(|| "the other concept".to_string(), |l| format!("\"{l}\""))
+}
+
+/// Check concepts for orphans, over-deep chains, hierarchy cycles,
+/// near-duplicate labels and unused concepts.
+///
+/// `concepts` are the active concepts; `broader_edges` are `(concept,
+/// broader)` pairs between any concepts.
+pub fn analyze_taxonomy(
+    concepts: &[TaxonomyConceptStats],
+    broader_edges: &[(Uuid, Uuid)],
+    max_depth: i32,
+    similarity_threshold: f32,
+) -> TaxonomyHealthReport {
+    let by_id: HashMap<Uuid, &TaxonomyConceptStats> = concepts.iter().map(|c| (c.id, c)).collect();
+    let depth_of: HashMap<Uuid, i32> = concepts.iter().map(|c| (c.id, c.depth)).collect();
+    let mut broader_of: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for (concept, broader) in broader_edges {
+        broader_of.entry(*concept).or_default().push(*broader);
+    }
+    let label_of = |id: &Uuid| by_id.get(id).and_then(|c| c.pref_label.as_deref());
+
+    let mut issues = Vec::new();
+    let issue = |kind, concept: &TaxonomyConceptStats, fix, suggestion| TaxonomyHealthIssue {
+        kind,
+        concept_id: concept.id,
+        pref_label: concept.pref_label.clone(),
+        related_concept_ids: Vec::new(),
+        depth: None,
+        similarity: None,
+        fix,
+        suggestion,
+    };
+
+    for concept in concepts {
+        if concept.broader_count == 0 && concept.narrower_count == 0 && concept.related_count == 0 {
+            issues.push(issue(
+                TaxonomyIssueKind::Orphan,
+                concept,
+                Some(TaxonomyFix::AddBroader),
+                "Place this concept under a broader concept, or relate it to another concept"
+                    .to_string(),
+            ));
+        }
+    }
+
+    for concept in concepts.iter().filter(|c| c.depth > max_depth) {
+        let target = shallow_ancestor(concept.id, &broader_of, &depth_of, max_depth);
+        let suggestion = match target {
+            Some(target) => format!(
+                "Move this concept under {} to bring it within {max_depth} levels",
+                quoted(label_of(&target))
+            ),
+            None => format!("Flatten the hierarchy above this concept to {max_depth} levels"),
+        };
+        issues.push(TaxonomyHealthIssue {
+            depth: Some(concept.depth),
+            ..issue(
+                TaxonomyIssueKind::TooDeep,
+                concept,
+                target.map(|broader_id| TaxonomyFix::MoveUnder { broader_id }),
+                suggestion,
+            )
+        });
+    }
+
+    for cycle in find_circular_hierarchys(broader_edges) {
+        let Some(concept) = cycle.iter().find_map(|id| by_id.get(id)) else {
+            continue;
+        };
+        let closing = broader_of
+            .get(&concept.id)
+            .and_then(|parents| parents.iter().filter(|p| cycle.contains(p)).min())
+            .copied();
+        issues.push(TaxonomyHealthIssue {
+            related_concept_ids: cycle
+                .iter()
+                .copied()
+                .filter(|id| *id != concept.id)
+                .collect(),
+            ..issue(
+                TaxonomyIssueKind::CircularHierarchy,
+                concept,
+                closing.map(|broader_id| TaxonomyFix::RemoveBroader { broader_id }),
+                match closing {
+                    Some(broader_id) => format!(
+                        "Remove the broader link to {} to break the cycle",
+                        quoted(label_of(&broader_id))
+                    ),
+                    None => "Remove one broader link in the cycle".to_string(),
+                },
+            )
+        });
+    }
+
+    let labels: Vec<(Uuid, &str)> = concepts
+        .iter()
+        .filter_map(|c| c.pref_label.as_deref().map(|label| (c.id, label)))
+        .collect();
+    for (a, b, similarity) in find_near_duplicates(&labels, similarity_threshold) {
+        let (Some(a), Some(b)) = (by_id.get(&a), by_id.get(&b)) else {
+            continue;
+        };
+        // Keep the concept with more notes.
+        let (source, target) = if (b.note_count, a.id) > (a.note_count, b.id) {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        issues.push(TaxonomyHealthIssue {
+            related_concept_ids: vec![target.id],
+            similarity: Some(similarity),
+            ..issue(
+                TaxonomyIssueKind::NearDuplicate,
+                source,
+                Some(TaxonomyFix::Merge {
+                    source_ids: vec![source.id],
+                    target_id: target.id,
+                }),
+                format!(
+                    "Merge this concept into {}",
+                    quoted(target.pref_label.as_deref())
+                ),
+            )
+        });
+    }
+
+    for concept in concepts {
+        if concept.note_count == 0 && concept.narrower_count == 0 {
+            issues.push(issue(
+                TaxonomyIssueKind::Unused,
+                concept,
+                Some(TaxonomyFix::Deprecate),
+                "Deprecate this concept; it tags no notes and has no narrower concepts".to_string(),
+            ));
+        }
+    }
+
+    let mut counts = TaxonomyIssueCounts::default();
+    for issue in &issues {
+        counts.add(issue.kind);
+    }
+    TaxonomyHealthReport {
+        generated_at_utc: Utc::now(),
+        concepts_checked: concepts.len(),
+        max_depth,
+        similarity_threshold,
+        counts,
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn concept(n: u128, label: &str, notes: i32, depth: i32) -> TaxonomyConceptStats {
+        TaxonomyConceptStats {
+            id: id(n),
+            pref_label: Some(label.to_string()),
+            note_count: notes,
+            depth,
+            broader_count: i32::from(depth > 0),
+            narrower_count: 1,
+            related_count: 0,
+        }
+    }
+
+    #[test]
+    fn labels_compare_after_folding() {
+        assert_eq!(normalize_label("Rust/Async__IO"), "rust async io");
+        assert_eq!(
+            label_similarity("Machine-Learning", "machine learning"),
+            1.0
+        );
+        assert!(label_similarity("colour", "color") > 0.8);
+        assert_eq!(label_similarity("", "rust"), 0.0);
+        assert!(label_similarity("rust", "python") < 0.5);
+    }
+
+    #[test]
+    fn near_duplicates_are_found_at_either_end() {
+        let labels = [
+            (id(1), "Neural Networks"),
+            (id(2), "neural network"),
+            (id(3), "Kubernetes"),
+            (id(4), "Qubernetes"),
+            (id(5), "Databases"),
+        ];
+        let pairs = find_near_duplicates(&labels, 0.85);
+        let found: Vec<(Uuid, Uuid)> = pairs.iter().map(|(a, b, _)| (*a, *b)).collect();
+        assert_eq!(found, vec![(id(1), id(2)), (id(3), id(4))]);
+        assert!(find_near_duplicates(&labels, 0.95).is_empty());
+    }
+
+    #[test]
+    fn cycles_are_strongly_connected_components() {
+        let edges = [
+            (id(1), id(2)),
+            (id(2), id(3)),
+            (id(3), id(1)),
+            (id(4), id(1)),
+            (id(5), id(5)),
+            (id(6), id(7)),
+        ];
+        assert_eq!(
+            find_circular_hierarchys(&edges),
+            vec![vec![id(1), id(2), id(3)], vec![id(5)]]
+        );
+        assert!(find_circular_hierarchys(&[(id(1), id(2))]).is_empty());
+    }
+
+    #[test]
+    fn analysis_suggests_fixes() {
+        let mut orphan = concept(1, "Loose End", 0, 0);
+        orphan.narrower_count = 0;
+        let concepts = vec![
+            orphan,
+            concept(10, "Science", 5, 0),
+            concept(11, "Physics", 5, 1),
+            concept(12, "Mechanics", 5, 2),
+            concept(13, "Fluids", 5, 3),
+            concept(14, "Turbulence", 5, 4),
+            concept(15, "Vortices", 5, 5),
+            concept(20, "Optics", 9, 1),
+            concept(21, "Optic", 2, 1),
+        ];
+        let edges = [
+            (id(11), id(10)),
+            (id(12), id(11)),
+            (id(13), id(12)),
+            (id(14), id(13)),
+            (id(15), id(14)),
+            (id(20), id(10)),
+            (id(21), id(10)),
+        ];
+        let report = analyze_taxonomy(&concepts, &edges, TAXONOMY_MAX_DEPTH, 0.8);
+        assert_eq!(report.concepts_checked, 9);
+        assert_eq!(
+            report.counts,
+            TaxonomyIssueCounts {
+                orphan: 1,
+                too_deep: 1,
+                circular_hierarchy: 0,
+                near_duplicate: 1,
+                unused: 1,
+            }
+        );
+
+        let fix_of = |kind| {
+            report
+                .issues
+                .iter()
+                .find(|i| i.kind == kind)
+                .and_then(|i| i.fix.clone())
+        };
+        assert_eq!(
+            fix_of(TaxonomyIssueKind::TooDeep),
+            Some(TaxonomyFix::MoveUnder { broader_id: id(13) })
+        );
+        assert_eq!(
+            fix_of(TaxonomyIssueKind::NearDuplicate),
+            Some(TaxonomyFix::Merge {
+                source_ids: vec![id(21)],
+                target_id: id(20),
+            })
+        );
+        assert_eq!(
+            fix_of(TaxonomyIssueKind::Unused),
+            Some(TaxonomyFix::Deprecate)
+        );
+
+        let flags = report.antipattern_flags();
+        assert!(flags.contains(&(id(20), TagAntipattern::NearDuplicate)));
+        assert!(flags.contains(&(id(1), TagAntipattern::Unused)));
+    }
+
+    #[test]
+    fn cycles_suggest_removing_the_closing_link() {
+        let concepts = vec![concept(1, "Alpha", 1, 1), concept(2, "Beta", 1, 1)];
+        let report = analyze_taxonomy(&concepts, &[(id(1), id(2)), (id(2), id(1))], 4, 0.9);
+        let cycle = &report.issues[0];
+        assert_eq!(cycle.kind, TaxonomyIssueKind::CircularHierarchy);
+        assert_eq!(cycle.related_concept_ids, vec![id(2)]);
+        assert_eq!(
+            cycle.fix,
+            Some(TaxonomyFix::RemoveBroader { broader_id: id(2) })
+        );
+        assert_eq!(
+            "circular_hierarchy".parse(),
+            Ok(TaxonomyIssueKind::CircularHierarchy)
+        );
+    }
+}
@@ -23,7 +23,7 @@ pub struct ListNotesRequest {
     pub sort_by: Option<String>,
     /// Sort order: "asc" or "desc"
     pub sort_order: Option<String>,
-    /// Filter: "all", "starred", "archived", "recent", "trash"
+    /// Filter: "all", "starred", "pinned", "archived", "recent", "trash"
     pub filter: Option<String>,
     /// Maximum results
     pub limit: Option<i64>,
@@ -41,6 +41,8 @@ pub struct ListNotesRequest {
     pub updated_after: Option<chrono::DateTime<chrono::Utc>>,
     /// Filter: notes updated before this timestamp (ISO 8601)
     pub updated_before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Filter: typed conditions on metadata keys (e.g. `metadata.status=active`)
+    pub metadata: Vec<crate::MetadataFieldFilter>,
 }
 
 impl fmt::Debug for ListNotesRequest {
@@ -67,6 +69,7 @@ impl fmt::Debug for ListNotesRequest {
             .field("created_before", &self.created_before)
             .field("updated_after", &self.updated_after)
             .field("updated_before", &self.updated_before)
+            .field("metadata", &self.metadata)
             .finish()
     }
 }
@@ -108,6 +111,9 @@ impl fmt::Debug for ListGlobalAttachmentsResponse {
 pub struct UpdateNoteStatusRequest {
     pub starred: Option<bool>,
     pub archived: Option<bool>,
+    pub pinned: Option<bool>,
+    /// 0-100, see [`crate::pinned::MAX_NOTE_PRIORITY`]
+    pub priority: Option<i32>,
     pub metadata: Option<serde_json::Value>,
 }
 
@@ -116,6 +122,8 @@ impl fmt::Debug for UpdateNoteStatusRequest {
         f.debug_struct("UpdateNoteStatusRequest")
             .field("starred", &self.starred)
             .field("archived", &self.archived)
+            .field("pinned", &self.pinned)
+            .field("priority", &self.priority)
             .field(
                 "metadata_class",
                 &self.metadata.as_ref().map(json_debug_class),
@@ -403,8 +411,9 @@ pub trait JobRepository: Send + Sync {
     /// Claim the next pending job whose type is supported by this binary.
     async fn claim_next(&self) -> Result<Option<Job>>;
 
-    /// Claim the next pending job whose type is in `job_types`.
-    /// An empty slice means "claim any supported type" (same as `claim_next`).
+    /// Claim the next pending job whose type is in `job_types`, interactive
+    /// lane first. An empty slice means "claim any supported type" (same as
+    /// `claim_next`).
     async fn claim_next_for_types(&self, job_types: &[JobType]) -> Result<Option<Job>>;
 
     /// Claim the next supported pending job for a specific cost tier group.
@@ -415,6 +424,8 @@ pub trait JobRepository: Send + Sync {
     /// - `StandardGpu`: cost_tier = 2
     /// - `RenderGpu`: cost_tier = 4
     /// - `VisionGpu`: cost_tier = 3
+    ///
+    /// Interactive-lane jobs are claimed ahead of batch-lane jobs.
     async fn claim_next_for_tier(
         &self,
         tier_group: TierGroup,
@@ -432,10 +443,18 @@ pub trait JobRepository: Send + Sync {
         message: Option<&str>,
     ) -> Result<()>;
 
-    /// Mark job as completed.
+    /// Load the checkpoint a previous run of this job saved, if any.
+    async fn get_checkpoint(&self, job_id: Uuid) -> Result<Option<JsonValue>>;
+
+    /// Replace the checkpoint of a running job. Returns false when the job
+    /// is no longer running, leaving the stored checkpoint unchanged.
+    async fn save_checkpoint(&self, job_id: Uuid, checkpoint: &JsonValue) -> Result<bool>;
+
+    /// Mark job as completed, discarding its checkpoint.
     async fn complete(&self, job_id: Uuid, result: Option<JsonValue>) -> Result<()>;
 
-    /// Schedule a retry at a future instant, or terminate it when retries are exhausted.
+    /// Schedule a retry at a future instant, or terminate it when retries are
+    /// exhausted or its payload is quarantined as poison.
     async fn retry(
         &self,
         job_id: Uuid,
@@ -782,6 +801,38 @@ impl fmt::Debug for DerivedFile {
     }
 }
 
+/// A note derived from part of an attachment's content.
+///
+/// Used by adapters that split a document into independently searchable
+/// pieces (e.g., one note per chat thread). The extraction handler creates each
+/// entry as a new note linked back to the attachment's note.
+#[derive(Clone)]
+pub struct DerivedNote {
+    /// Title for the new note.
+    pub title: String,
+    /// Markdown content of the new note.
+    pub content: String,
+    /// Tags applied to the new note.
+    pub tags: Vec<String>,
+    /// Note metadata. The handler adds the source attachment and note IDs.
+    pub metadata: JsonValue,
+}
+
+impl fmt::Debug for DerivedNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DerivedNote")
+            .field("title_len", &str_len(&self.title))
+            .field("content_len", &str_len(&self.content))
+            .field("tags_count", &self.tags.len())
+            .field("metadata_class", &json_debug_class(&self.metadata))
+            .field(
+                "metadata_serialized_len",
+                &json_serialized_len(&self.metadata),
+            )
+            .finish()
+    }
+}
+
 /// Result of content extraction from a file attachment.
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct ExtractionResult {
@@ -798,6 +849,10 @@ pub struct ExtractionResult {
     /// Each entry becomes a derived attachment linked to the parent.
     #[serde(skip)]
     pub derived_files: Vec<DerivedFile>,
+    /// Notes split out of this attachment (e.g., chat threads).
+    /// Each entry becomes a new note linked to the parent note.
+    #[serde(skip)]
+    pub derived_notes: Vec<DerivedNote>,
 }
 
 impl fmt::Debug for ExtractionResult {
@@ -829,6 +884,7 @@ impl fmt::Debug for ExtractionResult {
                     .map(|file| file.data.len())
                     .collect::<Vec<_>>(),
             )
+            .field("derived_notes_count", &self.derived_notes.len())
             .finish()
     }
 }
@@ -840,6 +896,13 @@ impl fmt::Debug for ExtractionResult {
 /// per-chunk in large documents) to give visibility into actual progress.
 pub type ProgressFn = std::sync::Arc<dyn Fn(i32, Option<&str>) + Send + Sync>;
 
+/// Checkpoint callback for extraction adapters.
+///
+/// Receives the adapter's resumable state after each completed item. The job
+/// persists the latest value; a restarted job hands it back to the adapter so
+/// completed items are not processed again.
+pub type CheckpointFn = std::sync::Arc<dyn Fn(JsonValue) + Send + Sync>;
+
 /// Adapter for extracting content from file attachments.
 ///
 /// Each adapter handles one extraction strategy (e.g., TextNative, PdfText).
@@ -878,6 +941,26 @@ pub trait ExtractionAdapter: Send + Sync {
         self.extract(data, filename, mime_type, config).await
     }
 
+    /// Extract content with progress reporting and checkpoints.
+    ///
+    /// Long-running adapters pass their state to `checkpoint` after each
+    /// completed item and read it back from `config._checkpoint` on restart.
+    ///
+    /// Default implementation delegates to [`extract_with_progress`] (no
+    /// checkpoints).
+    async fn extract_resumable(
+        &self,
+        data: &[u8],
+        filename: &str,
+        mime_type: &str,
+        config: &JsonValue,
+        progress: ProgressFn,
+        _checkpoint: CheckpointFn,
+    ) -> Result<ExtractionResult> {
+        self.extract_with_progress(data, filename, mime_type, config, progress)
+            .await
+    }
+
     /// Check if the adapter's external dependencies are available.
     async fn health_check(&self) -> Result<bool>;
 
@@ -969,6 +1052,7 @@ pub struct CreateTemplateRequest {
     pub format: Option<String>,
     pub default_tags: Option<Vec<String>>,
     pub collection_id: Option<Uuid>,
+    pub variables: Option<Vec<crate::note_template::TemplateVariable>>,
 }
 
 impl fmt::Debug for CreateTemplateRequest {
@@ -993,6 +1077,7 @@ impl fmt::Debug for CreateTemplateRequest {
                     .map(|tags| tags.iter().map(|tag| str_len(tag)).collect::<Vec<_>>()),
             )
             .field("collection_id_present", &self.collection_id.is_some())
+            .field("variables_count", &self.variables.as_ref().map(Vec::len))
             .finish()
     }
 }
@@ -1005,6 +1090,7 @@ pub struct UpdateTemplateRequest {
     pub content: Option<String>,
     pub default_tags: Option<Vec<String>>,
     pub collection_id: Option<Option<Uuid>>,
+    pub variables: Option<Vec<crate::note_template::TemplateVariable>>,
 }
 
 impl fmt::Debug for UpdateTemplateRequest {
@@ -1031,6 +1117,7 @@ impl fmt::Debug for UpdateTemplateRequest {
                 "collection_id_update_present",
                 &self.collection_id.as_ref().map(|value| value.is_some()),
             )
+            .field("variables_count", &self.variables.as_ref().map(Vec::len))
             .finish()
     }
 }
@@ -1269,6 +1356,7 @@ mod tests {
             created_before: None,
             updated_after: None,
             updated_before: None,
+            metadata: Vec::new(),
         };
 
         assert_eq!(req.sort_by.unwrap(), "created_at_utc");
@@ -1302,6 +1390,8 @@ mod tests {
         let req = UpdateNoteStatusRequest {
             starred: Some(true),
             archived: None,
+            pinned: None,
+            priority: None,
             metadata: None,
         };
         assert_eq!(req.starred, Some(true));
@@ -1394,6 +1484,7 @@ mod tests {
             format: Some("markdown".to_string()),
             default_tags: Some(vec!["daily".to_string()]),
             collection_id: None,
+            variables: None,
         };
 
         assert_eq!(req.name, "Daily Note");
@@ -1419,6 +1510,7 @@ mod tests {
             content: Some("New content".to_string()),
             default_tags: None,
             collection_id: Some(Some(Uuid::new_v4())),
+            variables: None,
         };
 
         assert_eq!(req.name.unwrap(), "Updated Name");
@@ -1481,6 +1573,7 @@ mod tests {
             created_before: None,
             updated_after: None,
             updated_before: None,
+            metadata: Vec::new(),
         };
         let list_debug = format!("{list_req:?}");
         assert!(list_debug.contains("ListNotesRequest"));
@@ -1502,6 +1595,8 @@ mod tests {
         let status_req = UpdateNoteStatusRequest {
             starred: Some(true),
             archived: Some(false),
+            pinned: None,
+            priority: None,
             metadata: Some(json!({
                 "path": "/srv/fortemi/private/status.json",
                 "token": "sk-status-secret"
@@ -1581,6 +1676,7 @@ mod tests {
             ai_description: Some("AI description includes private 診断".to_string()),
             preview_data: Some(b"preview-secret".to_vec()),
             derived_files: vec![derived_file],
+            derived_notes: vec![],
         };
         let extraction_debug = format!("{extraction:?}");
         assert!(extraction_debug.contains("ExtractionResult"));
@@ -1636,6 +1732,7 @@ mod tests {
             format: Some("秘密-template-format".to_string()),
             default_tags: Some(vec!["秘密-default-tag".to_string()]),
             collection_id: Some(Uuid::new_v4()),
+            variables: None,
         };
         let template_debug = format!("{create_template:?}");
         assert!(template_debug.contains("CreateTemplateRequest"));
@@ -1662,6 +1759,7 @@ mod tests {
             content: Some("Updated body with sk-updated-template-秘密".to_string()),
             default_tags: Some(vec!["updated-秘密-tag".to_string()]),
             collection_id: Some(Some(Uuid::new_v4())),
+            variables: None,
         };
         let update_template_debug = format!("{update_template:?}");
         assert!(update_template_debug.contains("UpdateTemplateRequest"));
@@ -1964,6 +2062,8 @@ mod tests {
         let req1 = UpdateNoteStatusRequest {
             starred: Some(true),
             archived: Some(false),
+            pinned: None,
+            priority: None,
             metadata: None,
         };
 
@@ -0,0 +1,118 @@
+//! Automatic note translation.
+//!
+//! When an archive has translation enabled, the `translation` job renders
+//! each note whose detected [`language`](crate::language) differs from the
+//! archive's target language into that target. The translation is stored
+//! next to the note's original and revised content — neither is changed —
+//! and full-text search matches it, so a German note can be found with an
+//! English query.
+//!
+//! Translation is off unless an archive enables it. The target defaults to
+//! `TRANSLATION_TARGET_LANGUAGE` (English when unset).
+//!
+//! ```
+//! use matric_core::ArchiveTranslationSetting;
+//!
+//! let setting = ArchiveTranslationSetting {
+//!     enabled: true,
+//!     target_language: "en".to_string(),
+//! };
+//! assert!(setting.validate().is_ok());
+//! assert!(setting.translates(Some("de")));
+//! assert!(!setting.translates(Some("en")));
+//! assert!(!setting.translates(None));
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::language::{language_name, ENGLISH};
+use crate::{Error, Result};
+
+/// Environment variable for the default target language.
+pub const ENV_TRANSLATION_TARGET_LANGUAGE: &str = "TRANSLATION_TARGET_LANGUAGE";
+
+/// Target language from `TRANSLATION_TARGET_LANGUAGE`, else English.
+///
+/// An unrecognised code falls back to English.
+pub fn default_target_language() -> String {
+    std::env::var(ENV_TRANSLATION_TARGET_LANGUAGE)
+        .ok()
+        .map(|v| v.trim().to_lowercase())
+        .filter(|v| language_name(v).is_some())
+        .unwrap_or_else(|| ENGLISH.to_string())
+}
+
+/// Whether and into which language an archive's notes are translated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ArchiveTranslationSetting {
+    /// Queue the translation job for new and updated notes
+    pub enabled: bool,
+    /// Language code notes are translated into (e.g. `en`)
+    #[serde(default = "default_target_language")]
+    pub target_language: String,
+}
+
+impl Default for ArchiveTranslationSetting {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_language: default_target_language(),
+        }
+    }
+}
+
+impl ArchiveTranslationSetting {
+    /// Check the target is a language code the detector can produce.
+    pub fn validate(&self) -> Result<()> {
+        if language_name(&self.target_language).is_none() {
+            return Err(Error::InvalidInput(
+                "target_language must be a supported language code such as en or de".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// True when enabled and a note in `note_language` is not already in the
+    /// target language. Notes with no detected language are not translated.
+    pub fn translates(&self, note_language: Option<&str>) -> bool {
+        self.enabled && note_language.is_some_and(|lang| lang != self.target_language)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_unknown_target() {
+        let setting = ArchiveTranslationSetting {
+            enabled: true,
+            target_language: "klingon".to_string(),
+        };
+        assert!(setting.validate().is_err());
+
+        let setting = ArchiveTranslationSetting {
+            enabled: true,
+            target_language: "de".to_string(),
+        };
+        assert!(setting.validate().is_ok());
+    }
+
+    #[test]
+    fn disabled_setting_translates_nothing() {
+        let setting = ArchiveTranslationSetting {
+            enabled: false,
+            target_language: "en".to_string(),
+        };
+        assert!(!setting.translates(Some("de")));
+        assert!(!ArchiveTranslationSetting::default().enabled);
+    }
+
+    #[test]
+    fn deserialize_defaults_target_language() {
+        let setting: ArchiveTranslationSetting =
+            serde_json::from_str(r#"{"enabled": true}"#).unwrap();
+        assert!(setting.enabled);
+        assert!(language_name(&setting.target_language).is_some());
+    }
+}
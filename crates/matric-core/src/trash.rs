@@ -0,0 +1,157 @@
+//! Note trash.
+//!
+//! Deleting a note only sets its `deleted_at`; the note stays in the
+//! archive's trash until it is restored or purged. The periodic
+//! `trash_purge` job purges notes that have been in the trash for longer
+//! than `TRASH_RETENTION_DAYS` (see [`crate::defaults::trash_retention_days`]);
+//! a retention of 0 keeps them until they are purged by hand.
+//!
+//! ```
+//! use matric_core::TrashSelection;
+//! use uuid::Uuid;
+//!
+//! let selection = TrashSelection {
+//!     ids: vec![Uuid::nil()],
+//!     all: false,
+//! };
+//! assert!(selection.validate().is_ok());
+//! assert!(TrashSelection::default().validate().is_err());
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// Most note IDs one bulk trash request may name.
+pub const MAX_TRASH_SELECTION: usize = 1_000;
+
+/// When a note deleted at `deleted_at` is due to be purged, or `None` when
+/// retention is disabled.
+pub fn trash_purge_at(deleted_at: DateTime<Utc>, retention_days: i64) -> Option<DateTime<Utc>> {
+    (retention_days > 0).then(|| deleted_at + Duration::days(retention_days))
+}
+
+/// A soft-deleted note waiting in the trash.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TrashedNote {
+    pub id: Uuid,
+    pub title: Option<String>,
+    pub created_at_utc: DateTime<Utc>,
+    pub deleted_at: DateTime<Utc>,
+    /// When the retention job will purge the note; null when retention is
+    /// disabled
+    pub purge_at: Option<DateTime<Utc>>,
+}
+
+impl fmt::Debug for TrashedNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrashedNote")
+            .field("id", &self.id)
+            .field("title_len", &self.title.as_ref().map(String::len))
+            .field("created_at_utc", &self.created_at_utc)
+            .field("deleted_at", &self.deleted_at)
+            .field("purge_at", &self.purge_at)
+            .finish()
+    }
+}
+
+/// One page of an archive's trash, most recently deleted first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TrashListing {
+    pub notes: Vec<TrashedNote>,
+    /// Notes in the trash across all pages
+    pub total: i64,
+    /// Days a note stays in the trash before it is purged; 0 when retention
+    /// is disabled
+    pub retention_days: i64,
+}
+
+/// Which trashed notes a bulk restore or purge applies to.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct TrashSelection {
+    /// Trashed notes to act on
+    #[serde(default)]
+    pub ids: Vec<Uuid>,
+    /// Act on every note in the trash instead of `ids`
+    #[serde(default)]
+    pub all: bool,
+}
+
+impl fmt::Debug for TrashSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrashSelection")
+            .field("ids_count", &self.ids.len())
+            .field("all", &self.all)
+            .finish()
+    }
+}
+
+impl TrashSelection {
+    /// Check exactly one of `ids` and `all` is given.
+    pub fn validate(&self) -> Result<()> {
+        match (self.all, self.ids.is_empty()) {
+            (true, false) => Err(Error::InvalidInput(
+                "Give either ids or all, not both".to_string(),
+            )),
+            (false, true) => Err(Error::InvalidInput(
+                "ids must not be empty unless all is true".to_string(),
+            )),
+            _ if self.ids.len() > MAX_TRASH_SELECTION => Err(Error::InvalidInput(format!(
+                "At most {MAX_TRASH_SELECTION} ids may be given"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// The selected IDs, or `None` for the whole trash.
+    pub fn ids(&self) -> Option<&[Uuid]> {
+        (!self.all).then_some(self.ids.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_requires_exactly_one_selection() {
+        let both = TrashSelection {
+            ids: vec![Uuid::nil()],
+            all: true,
+        };
+        match both.validate() {
+            Err(Error::InvalidInput(message)) => assert!(message.contains("not both")),
+            other => panic!("expected invalid input, got {other:?}"),
+        }
+        assert!(TrashSelection::default().validate().is_err());
+
+        let all: TrashSelection = serde_json::from_str(r#"{"all": true}"#).unwrap();
+        assert!(all.validate().is_ok());
+        assert_eq!(all.ids(), None);
+    }
+
+    #[test]
+    fn validate_caps_selection_size() {
+        let selection = TrashSelection {
+            ids: vec![Uuid::nil(); MAX_TRASH_SELECTION + 1],
+            all: false,
+        };
+        assert!(selection.validate().is_err());
+    }
+
+    #[test]
+    fn purge_at_follows_retention() {
+        let deleted_at = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(trash_purge_at(deleted_at, 0), None);
+        assert_eq!(
+            trash_purge_at(deleted_at, 30).unwrap().to_rfc3339(),
+            "2026-01-31T00:00:00+00:00"
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! Typed links and the per-archive link type vocabulary.
+//!
+//! A typed link is a note-to-note link whose `kind` names the relation it
+//! asserts (`supports`, `contradicts`, ...) rather than how it was made
+//! (`semantic`, `wiki`, `journal`). Every archive accepts the
+//! [`BUILTIN_LINK_TYPES`], which include each type the link classifier
+//! assigns, plus the custom types registered for it. A link of a symmetric
+//! type is created in both directions.
+//!
+//! ```
+//! use matric_core::typed_link::{is_builtin_link_type, validate_link_type_name};
+//!
+//! assert!(is_builtin_link_type("contradicts"));
+//! assert_eq!(validate_link_type_name(" Derived-From ").unwrap(), "derived-from");
+//! assert!(validate_link_type_name("semantic").is_err());
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::{Error, Result};
+
+/// Link kinds the linker and the journal create; they cannot be registered
+/// or assigned through the typed link API.
+pub const SYSTEM_LINK_KINDS: [&str; 3] = ["semantic", "wiki", "journal"];
+
+/// Link types every archive accepts: `(name, description, symmetric)`.
+pub const BUILTIN_LINK_TYPES: [(&str, &str, bool); 8] = [
+    ("supports", "Provides evidence for the target", false),
+    ("contradicts", "Conflicts with the target", true),
+    ("elaborates", "Adds detail to the target", false),
+    ("cites", "Cites the target as a source", false),
+    ("extends", "Builds on the target", false),
+    ("implements", "Applies the target's idea", false),
+    ("references", "Mentions the target", false),
+    ("related", "Is related to the target", true),
+];
+
+/// Longest link type name.
+pub const MAX_LINK_TYPE_NAME_LEN: usize = 64;
+
+/// Longest link type description.
+pub const MAX_LINK_TYPE_DESCRIPTION_LEN: usize = 500;
+
+/// Score given to a typed link created without one.
+pub const DEFAULT_TYPED_LINK_SCORE: f32 = 1.0;
+
+/// Whether `name` is one of the [`BUILTIN_LINK_TYPES`].
+pub fn is_builtin_link_type(name: &str) -> bool {
+    BUILTIN_LINK_TYPES
+        .iter()
+        .any(|(builtin, _, _)| *builtin == name)
+}
+
+/// Lowercase and trim a link type name, rejecting names that are empty, too
+/// long, not made of `a-z`, `0-9`, `-` and `_` starting with a letter, or
+/// reserved for the linker.
+pub fn validate_link_type_name(name: &str) -> Result<String> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() || name.len() > MAX_LINK_TYPE_NAME_LEN {
+        return Err(Error::InvalidInput(format!(
+            "link type must be 1 to {MAX_LINK_TYPE_NAME_LEN} characters"
+        )));
+    }
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid {
+        return Err(Error::InvalidInput(
+            "link type must start with a letter and use only a-z, 0-9, '-' and '_'".to_string(),
+        ));
+    }
+    if SYSTEM_LINK_KINDS.contains(&name.as_str()) {
+        return Err(Error::InvalidInput(format!(
+            "link type '{name}' is reserved for automatic linking"
+        )));
+    }
+    Ok(name)
+}
+
+/// Split a comma-separated link type filter into trimmed, lowercased names;
+/// `None` when it names none.
+pub fn parse_link_type_filter(filter: Option<&str>) -> Option<Vec<String>> {
+    let mut types: Vec<String> = Vec::new();
+    for name in filter?
+        .split(',')
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+    {
+        if !types.contains(&name) {
+            types.push(name);
+        }
+    }
+    (!types.is_empty()).then_some(types)
+}
+
+/// A link type an archive accepts.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct LinkTypeDefinition {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether links of this type are created in both directions
+    pub symmetric: bool,
+    /// Whether the type is built in rather than registered for the archive
+    #[sqlx(default)]
+    pub builtin: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at_utc: Option<DateTime<Utc>>,
+}
+
+impl LinkTypeDefinition {
+    /// The [`BUILTIN_LINK_TYPES`] as definitions.
+    pub fn builtins() -> Vec<Self> {
+        BUILTIN_LINK_TYPES
+            .iter()
+            .map(|(name, description, symmetric)| Self {
+                name: name.to_string(),
+                description: Some(description.to_string()),
+                symmetric: *symmetric,
+                builtin: true,
+                created_at_utc: None,
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for LinkTypeDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkTypeDefinition")
+            .field("name_len", &self.name.len())
+            .field(
+                "description_len",
+                &self.description.as_ref().map(String::len),
+            )
+            .field("symmetric", &self.symmetric)
+            .field("builtin", &self.builtin)
+            .finish()
+    }
+}
+
+/// Register a custom link type for an archive.
+#[derive(Clone, Deserialize, utoipa::ToSchema)]
+pub struct CreateLinkTypeRequest {
+    /// Lowercase name: a letter, then `a-z`, `0-9`, `-` or `_` (max 64)
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Create links of this type in both directions (default: false)
+    #[serde(default)]
+    pub symmetric: bool,
+}
+
+impl CreateLinkTypeRequest {
+    /// The normalized name, after checking it and the description.
+    pub fn validate(&self) -> Result<String> {
+        let name = validate_link_type_name(&self.name)?;
+        if is_builtin_link_type(&name) {
+            return Err(Error::InvalidInput(format!(
+                "link type '{name}' is built in"
+            )));
+        }
+        if self
+            .description
+            .as_ref()
+            .is_some_and(|description| description.chars().count() > MAX_LINK_TYPE_DESCRIPTION_LEN)
+        {
+            return Err(Error::InvalidInput(format!(
+                "description must be at most {MAX_LINK_TYPE_DESCRIPTION_LEN} characters"
+            )));
+        }
+        Ok(name)
+    }
+}
+
+impl fmt::Debug for CreateLinkTypeRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateLinkTypeRequest")
+            .field("name_len", &self.name.len())
+            .field(
+                "description_len",
+                &self.description.as_ref().map(String::len),
+            )
+            .field("symmetric", &self.symmetric)
+            .finish()
+    }
+}
+
+/// Link a note to another note with a typed relation.
+#[derive(Clone, Deserialize, utoipa::ToSchema)]
+pub struct CreateTypedLinkRequest {
+    pub to_note_id: Uuid,
+    /// A built-in or registered link type
+    pub link_type: String,
+    /// Link strength, 0 to 1 (default: 1)
+    #[serde(default)]
+    pub score: Option<f32>,
+    /// JSON object stored with the link
+    #[serde(default)]
+    pub metadata: Option<JsonValue>,
+}
+
+impl fmt::Debug for CreateTypedLinkRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateTypedLinkRequest")
+            .field("to_note_id_set", &true)
+            .field("link_type_len", &self.link_type.len())
+            .field("score", &self.score)
+            .field("metadata_set", &self.metadata.is_some())
+            .finish()
+    }
+}
+
+/// Change a link's type, score or metadata; absent fields are kept.
+#[derive(Clone, Default, Deserialize, utoipa::ToSchema)]
+pub struct UpdateTypedLinkRequest {
+    #[serde(default)]
+    pub link_type: Option<String>,
+    #[serde(default)]
+    pub score: Option<f32>,
+    /// Replaces the link's metadata; must be a JSON object
+    #[serde(default)]
+    pub metadata: Option<JsonValue>,
+}
+
+impl fmt::Debug for UpdateTypedLinkRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpdateTypedLinkRequest")
+            .field("link_type_len", &self.link_type.as_ref().map(String::len))
+            .field("score", &self.score)
+            .field("metadata_set", &self.metadata.is_some())
+            .finish()
+    }
+}
+
+/// Reject a score outside `0..=1` or metadata that is not a JSON object.
+pub fn validate_typed_link_fields(score: Option<f32>, metadata: Option<&JsonValue>) -> Result<()> {
+    if score.is_some_and(|score| !(0.0..=1.0).contains(&score)) {
+        return Err(Error::InvalidInput(
+            "score must be between 0 and 1".to_string(),
+        ));
+    }
+    if metadata.is_some_and(|metadata| !metadata.is_object()) {
+        return Err(Error::InvalidInput(
+            "metadata must be a JSON object".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_normalized_and_checked() {
+        assert_eq!(validate_link_type_name("Cites").unwrap(), "cites");
+        assert_eq!(
+            validate_link_type_name("derived_from-2").unwrap(),
+            "derived_from-2"
+        );
+        for bad in ["", "2nd", "has space", "wiki", "ünïcode", &"x".repeat(65)] {
+            assert!(validate_link_type_name(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn custom_types_cannot_shadow_builtins() {
+        let request = CreateLinkTypeRequest {
+            name: "Supports".to_string(),
+            description: None,
+            symmetric: false,
+        };
+        assert!(request.validate().is_err());
+        let request = CreateLinkTypeRequest {
+            name: "refutes".to_string(),
+            description: Some("d".repeat(MAX_LINK_TYPE_DESCRIPTION_LEN + 1)),
+            symmetric: false,
+        };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn filter_splits_and_dedupes() {
+        assert_eq!(
+            parse_link_type_filter(Some(" Supports,cites,supports ")),
+            Some(vec!["supports".to_string(), "cites".to_string()])
+        );
+        assert_eq!(parse_link_type_filter(Some(" , ")), None);
+        assert_eq!(parse_link_type_filter(None), None);
+    }
+
+    #[test]
+    fn fields_reject_out_of_range_score_and_non_object_metadata() {
+        assert!(validate_typed_link_fields(Some(0.5), Some(&serde_json::json!({}))).is_ok());
+        assert!(validate_typed_link_fields(Some(1.5), None).is_err());
+        assert!(validate_typed_link_fields(None, Some(&serde_json::json!([1]))).is_err());
+    }
+}
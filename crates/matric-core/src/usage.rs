@@ -0,0 +1,178 @@
+//! Per-principal daily usage counters and quotas.
+//!
+//! Each API key, OAuth client, and the anonymous caller has a counter per
+//! UTC day for the notes it created, the searches it ran and the inference
+//! tokens its completions consumed. Quotas are optional per-day caps on
+//! those counters; a principal that has reached one is refused further
+//! requests of that kind until the next UTC day.
+//!
+//! This is separate from the [`UsageMeter`](crate::UsageMeter) event ledger,
+//! which records individual billable events for export to external sinks.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use matric_core::{DailyUsage, UsageCounter, UsageQuotas};
+//!
+//! let day = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+//! let mut usage = DailyUsage::empty(day);
+//! usage.add(UsageCounter::Searches, 3);
+//!
+//! let quotas = UsageQuotas {
+//!     searches_per_day: Some(3),
+//!     ..Default::default()
+//! };
+//! assert!(quotas.exceeded(UsageCounter::Searches, usage.get(UsageCounter::Searches)));
+//! assert!(!quotas.exceeded(UsageCounter::NotesCreated, 0));
+//! ```
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// What a daily counter counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageCounter {
+    NotesCreated,
+    Searches,
+    /// Estimated prompt plus completion tokens.
+    InferenceTokens,
+}
+
+impl UsageCounter {
+    pub const ALL: [Self; 3] = [Self::NotesCreated, Self::Searches, Self::InferenceTokens];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NotesCreated => "notes_created",
+            Self::Searches => "searches",
+            Self::InferenceTokens => "inference_tokens",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|counter| counter.as_str() == value)
+    }
+}
+
+/// One principal's counters for one UTC day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DailyUsage {
+    pub day: NaiveDate,
+    pub notes_created: i64,
+    pub searches: i64,
+    pub inference_tokens: i64,
+}
+
+impl DailyUsage {
+    pub fn empty(day: NaiveDate) -> Self {
+        Self {
+            day,
+            notes_created: 0,
+            searches: 0,
+            inference_tokens: 0,
+        }
+    }
+
+    pub fn get(&self, counter: UsageCounter) -> i64 {
+        match counter {
+            UsageCounter::NotesCreated => self.notes_created,
+            UsageCounter::Searches => self.searches,
+            UsageCounter::InferenceTokens => self.inference_tokens,
+        }
+    }
+
+    pub fn add(&mut self, counter: UsageCounter, amount: i64) {
+        let value = match counter {
+            UsageCounter::NotesCreated => &mut self.notes_created,
+            UsageCounter::Searches => &mut self.searches,
+            UsageCounter::InferenceTokens => &mut self.inference_tokens,
+        };
+        *value = value.saturating_add(amount);
+    }
+}
+
+/// Optional per-principal caps on each daily counter. `None` is unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct UsageQuotas {
+    pub notes_created_per_day: Option<i64>,
+    pub searches_per_day: Option<i64>,
+    pub inference_tokens_per_day: Option<i64>,
+}
+
+impl UsageQuotas {
+    pub fn limit(&self, counter: UsageCounter) -> Option<i64> {
+        match counter {
+            UsageCounter::NotesCreated => self.notes_created_per_day,
+            UsageCounter::Searches => self.searches_per_day,
+            UsageCounter::InferenceTokens => self.inference_tokens_per_day,
+        }
+    }
+
+    /// Whether a principal that has used `used` today may not go further.
+    pub fn exceeded(&self, counter: UsageCounter, used: i64) -> bool {
+        self.limit(counter).is_some_and(|limit| used >= limit)
+    }
+}
+
+/// A principal's recent usage and the quotas that apply to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct UsageReport {
+    /// The principal the counters belong to, e.g. `api-key:<id>`
+    pub principal: String,
+    pub quotas: UsageQuotas,
+    /// One entry per day, today first, including days without usage
+    pub days: Vec<DailyUsage>,
+}
+
+/// The UTC day `at` falls in.
+pub fn usage_day(at: DateTime<Utc>) -> NaiveDate {
+    at.date_naive()
+}
+
+/// When the counters for the UTC day `at` falls in reset.
+pub fn usage_day_reset(at: DateTime<Utc>) -> DateTime<Utc> {
+    usage_day(at)
+        .checked_add_days(Days::new(1))
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .map_or(at, |midnight| midnight.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn counters_round_trip_through_their_names() {
+        for counter in UsageCounter::ALL {
+            assert_eq!(UsageCounter::parse(counter.as_str()), Some(counter));
+        }
+        assert_eq!(UsageCounter::parse("api_requests"), None);
+    }
+
+    #[test]
+    fn quotas_cap_only_configured_counters() {
+        let quotas = UsageQuotas {
+            notes_created_per_day: Some(2),
+            ..Default::default()
+        };
+        assert!(!quotas.exceeded(UsageCounter::NotesCreated, 1));
+        assert!(quotas.exceeded(UsageCounter::NotesCreated, 2));
+        assert!(!quotas.exceeded(UsageCounter::Searches, i64::MAX));
+    }
+
+    #[test]
+    fn usage_resets_at_the_next_utc_midnight() {
+        let at = Utc.with_ymd_and_hms(2026, 10, 16, 23, 59, 30).unwrap();
+        assert_eq!(
+            usage_day(at),
+            NaiveDate::from_ymd_opt(2026, 10, 16).unwrap()
+        );
+        assert_eq!(
+            usage_day_reset(at),
+            Utc.with_ymd_and_hms(2026, 10, 17, 0, 0, 0).unwrap()
+        );
+    }
+}
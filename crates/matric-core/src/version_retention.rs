@@ -0,0 +1,156 @@
+//! Note version retention.
+//!
+//! Every edit to a note's original content snapshots the previous version
+//! into its history. A [`VersionRetentionPolicy`] bounds that history: at
+//! most `max_versions` history entries per note, none older than
+//! `max_age_days`, and — unless `keep_milestones` is off — versions the user
+//! marked as milestones are never pruned. `None` disables a limit.
+//!
+//! The global default lives in `user_config` (`versioning_max_history`,
+//! `versioning_keep_milestones`); a memory archive can override it. The
+//! snapshot trigger applies the count limit on every edit, and the periodic
+//! `version_prune` job applies the whole policy.
+//!
+//! ```
+//! use matric_core::VersionRetentionPolicy;
+//!
+//! let policy = VersionRetentionPolicy {
+//!     max_versions: Some(10),
+//!     max_age_days: Some(90),
+//!     keep_milestones: true,
+//! };
+//! assert!(policy.validate().is_ok());
+//! assert!(policy.prunes(11, 1, false));
+//! assert!(policy.prunes(1, 91, false));
+//! assert!(!policy.prunes(11, 91, true));
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// History entries kept per note when nothing is configured.
+pub const DEFAULT_MAX_VERSIONS: i32 = 50;
+
+/// Upper bound for `max_versions`.
+pub const MAX_RETAINED_VERSIONS: i32 = 10_000;
+
+/// Upper bound for `max_age_days` (ten years).
+pub const MAX_VERSION_AGE_DAYS: i32 = 3_650;
+
+fn default_keep_milestones() -> bool {
+    true
+}
+
+/// How much note version history to keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct VersionRetentionPolicy {
+    /// History entries kept per note, newest first; null keeps every version
+    #[serde(default)]
+    pub max_versions: Option<i32>,
+    /// History entries older than this many days are pruned; null disables
+    /// the age limit
+    #[serde(default)]
+    pub max_age_days: Option<i32>,
+    /// Exempt milestone versions from both limits
+    #[serde(default = "default_keep_milestones")]
+    pub keep_milestones: bool,
+}
+
+impl Default for VersionRetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_versions: Some(DEFAULT_MAX_VERSIONS),
+            max_age_days: None,
+            keep_milestones: true,
+        }
+    }
+}
+
+impl VersionRetentionPolicy {
+    /// Check both limits are within range.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(max) = self.max_versions {
+            if !(1..=MAX_RETAINED_VERSIONS).contains(&max) {
+                return Err(Error::InvalidInput(format!(
+                    "max_versions must be between 1 and {MAX_RETAINED_VERSIONS}"
+                )));
+            }
+        }
+        if let Some(days) = self.max_age_days {
+            if !(1..=MAX_VERSION_AGE_DAYS).contains(&days) {
+                return Err(Error::InvalidInput(format!(
+                    "max_age_days must be between 1 and {MAX_VERSION_AGE_DAYS}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the policy removes nothing.
+    pub fn is_unbounded(&self) -> bool {
+        self.max_versions.is_none() && self.max_age_days.is_none()
+    }
+
+    /// Whether a history entry is pruned. `rank` is its 1-based position in
+    /// the note's history counting from the newest, `age_days` the whole days
+    /// since it was snapshotted.
+    pub fn prunes(&self, rank: i64, age_days: i64, milestone: bool) -> bool {
+        if milestone && self.keep_milestones {
+            return false;
+        }
+        self.max_versions.is_some_and(|max| rank > i64::from(max))
+            || self
+                .max_age_days
+                .is_some_and(|days| age_days > i64::from(days))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_keeps_fifty_versions_and_milestones() {
+        let policy = VersionRetentionPolicy::default();
+        assert_eq!(policy.max_versions, Some(DEFAULT_MAX_VERSIONS));
+        assert_eq!(policy.max_age_days, None);
+        assert!(policy.keep_milestones);
+        assert!(policy.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_limits() {
+        let mut policy = VersionRetentionPolicy {
+            max_versions: Some(0),
+            ..Default::default()
+        };
+        assert!(policy.validate().is_err());
+        policy.max_versions = Some(MAX_RETAINED_VERSIONS + 1);
+        assert!(policy.validate().is_err());
+        policy.max_versions = None;
+        policy.max_age_days = Some(-3);
+        assert!(policy.validate().is_err());
+        policy.max_age_days = Some(MAX_VERSION_AGE_DAYS);
+        assert!(policy.validate().is_ok());
+    }
+
+    #[test]
+    fn missing_fields_deserialize_as_unbounded_keeping_milestones() {
+        let policy: VersionRetentionPolicy = serde_json::from_str("{}").unwrap();
+        assert!(policy.is_unbounded());
+        assert!(policy.keep_milestones);
+        assert!(!policy.prunes(1_000, 10_000, false));
+    }
+
+    #[test]
+    fn milestones_are_pruned_only_when_not_kept() {
+        let policy = VersionRetentionPolicy {
+            max_versions: Some(2),
+            max_age_days: None,
+            keep_milestones: false,
+        };
+        assert!(!policy.prunes(2, 0, true));
+        assert!(policy.prunes(3, 0, true));
+    }
+}
@@ -0,0 +1,426 @@
+//! Payload predicates for outbound webhooks.
+//!
+//! A webhook may carry filter expressions in addition to its event types.
+//! Each expression is evaluated against the JSON event envelope the webhook
+//! would receive, and the event is delivered only when every expression
+//! holds. Expressions are parsed when the webhook is created or updated, so
+//! an invalid filter is rejected up front rather than silently dropping
+//! deliveries.
+//!
+//! # Syntax
+//!
+//! ```text
+//! <path> <op> <literal>
+//! <path> exists
+//! ```
+//!
+//! - `path` starts at the envelope, jq-style (`.payload.tags`) or
+//!   JSONPath-style (`$.payload.tags`). Segments are `.name`, `["name"]`,
+//!   `[0]` (array index) and `[*]` (every array element).
+//! - `op` is one of `==`, `!=`, `<`, `<=`, `>`, `>=`, `contains`,
+//!   `startswith` or `exists`.
+//! - `literal` is a JSON value: `"text"`, `42`, `true`, `null`, ...
+//!
+//! A path may select several values (through `[*]`); the predicate holds if
+//! any of them satisfies it. `!=` holds when none of them is equal, so it is
+//! the exact negation of `==`. `contains` tests array membership or, for a
+//! string and a string literal, substring; `startswith` tests string prefix;
+//! ordering operators compare numbers only.
+//!
+//! ```
+//! use matric_core::WebhookFilter;
+//! use serde_json::json;
+//!
+//! let filter = WebhookFilter::parse(r#".payload.tags contains "project/x""#).unwrap();
+//! let event = json!({"payload": {"type": "NoteCreated", "tags": ["project/x"]}});
+//! assert!(filter.matches(&event));
+//! ```
+
+use std::fmt;
+
+use serde_json::Value as JsonValue;
+
+use crate::{Error, Result};
+
+/// Most filter expressions a webhook may carry.
+pub const MAX_WEBHOOK_FILTERS: usize = 16;
+
+/// Longest accepted filter expression, in characters.
+pub const MAX_WEBHOOK_FILTER_LEN: usize = 512;
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Field(String),
+    Index(usize),
+    Wildcard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    StartsWith,
+    Exists,
+}
+
+/// A parsed webhook filter expression.
+#[derive(Clone, PartialEq)]
+pub struct WebhookFilter {
+    path: Vec<PathSegment>,
+    op: FilterOp,
+    literal: JsonValue,
+}
+
+impl fmt::Debug for WebhookFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Paths and literals may name tags or other user data.
+        f.debug_struct("WebhookFilter")
+            .field("path_depth", &self.path.len())
+            .field("op", &self.op)
+            .finish()
+    }
+}
+
+impl WebhookFilter {
+    /// Parse one expression, returning [`Error::InvalidInput`] with the
+    /// position of the problem when it is malformed.
+    pub fn parse(expression: &str) -> Result<Self> {
+        if expression.chars().count() > MAX_WEBHOOK_FILTER_LEN {
+            return Err(invalid(format!(
+                "is longer than {MAX_WEBHOOK_FILTER_LEN} characters"
+            )));
+        }
+        let mut parser = Parser {
+            input: expression,
+            pos: 0,
+        };
+        parser.skip_ws();
+        let path = parser.path()?;
+        parser.skip_ws();
+        let op = parser.op()?;
+        parser.skip_ws();
+        let literal = if op == FilterOp::Exists {
+            JsonValue::Null
+        } else {
+            parser.literal()?
+        };
+        parser.skip_ws();
+        if !parser.rest().is_empty() {
+            return Err(parser.error("unexpected trailing input"));
+        }
+        match (op, &literal) {
+            (FilterOp::StartsWith, JsonValue::String(_)) => {}
+            (FilterOp::StartsWith, _) => {
+                return Err(invalid("startswith needs a string literal".to_string()))
+            }
+            (FilterOp::Lt | FilterOp::Le | FilterOp::Gt | FilterOp::Ge, JsonValue::Number(_)) => {}
+            (FilterOp::Lt | FilterOp::Le | FilterOp::Gt | FilterOp::Ge, _) => {
+                return Err(invalid(
+                    "ordering operators need a number literal".to_string(),
+                ))
+            }
+            _ => {}
+        }
+        Ok(Self { path, op, literal })
+    }
+
+    /// Parse a webhook's filter list, rejecting too many expressions.
+    pub fn parse_all(expressions: &[String]) -> Result<Vec<Self>> {
+        if expressions.len() > MAX_WEBHOOK_FILTERS {
+            return Err(Error::InvalidInput(format!(
+                "A webhook may have at most {MAX_WEBHOOK_FILTERS} filters"
+            )));
+        }
+        expressions
+            .iter()
+            .enumerate()
+            .map(|(i, expression)| {
+                Self::parse(expression).map_err(|err| match err {
+                    Error::InvalidInput(msg) => Error::InvalidInput(format!("filters[{i}]: {msg}")),
+                    other => other,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the event satisfies this predicate.
+    pub fn matches(&self, event: &JsonValue) -> bool {
+        let mut values = vec![event];
+        for segment in &self.path {
+            values = values
+                .into_iter()
+                .flat_map(|value| select(value, segment))
+                .collect();
+        }
+        match self.op {
+            FilterOp::Exists => !values.is_empty(),
+            FilterOp::Ne => !values.iter().any(|value| **value == self.literal),
+            op => values.iter().any(|value| self.test(op, value)),
+        }
+    }
+
+    fn test(&self, op: FilterOp, value: &JsonValue) -> bool {
+        match op {
+            FilterOp::Eq => *value == self.literal,
+            FilterOp::Contains => match (value, &self.literal) {
+                (JsonValue::Array(items), literal) => items.contains(literal),
+                (JsonValue::String(text), JsonValue::String(needle)) => text.contains(needle),
+                _ => false,
+            },
+            FilterOp::StartsWith => match (value, &self.literal) {
+                (JsonValue::String(text), JsonValue::String(prefix)) => text.starts_with(prefix),
+                _ => false,
+            },
+            FilterOp::Lt | FilterOp::Le | FilterOp::Gt | FilterOp::Ge => {
+                let (Some(left), Some(right)) = (value.as_f64(), self.literal.as_f64()) else {
+                    return false;
+                };
+                match op {
+                    FilterOp::Lt => left < right,
+                    FilterOp::Le => left <= right,
+                    FilterOp::Gt => left > right,
+                    _ => left >= right,
+                }
+            }
+            FilterOp::Ne | FilterOp::Exists => unreachable!("handled in matches"),
+        }
+    }
+}
+
+/// Whether the event satisfies every filter; an empty list matches everything.
+pub fn webhook_filters_match(filters: &[WebhookFilter], event: &JsonValue) -> bool {
+    filters.iter().all(|filter| filter.matches(event))
+}
+
+fn select<'a>(value: &'a JsonValue, segment: &PathSegment) -> Vec<&'a JsonValue> {
+    match (segment, value) {
+        (PathSegment::Field(name), JsonValue::Object(map)) => map.get(name).into_iter().collect(),
+        (PathSegment::Index(i), JsonValue::Array(items)) => items.get(*i).into_iter().collect(),
+        (PathSegment::Wildcard, JsonValue::Array(items)) => items.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn invalid(detail: String) -> Error {
+    Error::InvalidInput(format!("Invalid webhook filter: {detail}"))
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn error(&self, detail: &str) -> Error {
+        invalid(format!("{detail} at offset {}", self.pos))
+    }
+
+    fn skip_ws(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn path(&mut self) -> Result<Vec<PathSegment>> {
+        self.eat("$");
+        if !self.rest().starts_with(['.', '[']) {
+            return Err(self.error("expected a path starting with '.' or '$'"));
+        }
+        let mut path = Vec::new();
+        loop {
+            if self.eat(".") {
+                let name_len = self
+                    .rest()
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                    .unwrap_or(self.rest().len());
+                if name_len == 0 {
+                    // A bare `.` (jq identity) selects the envelope itself.
+                    if path.is_empty() && !self.rest().starts_with('[') {
+                        break;
+                    }
+                    if self.rest().starts_with('[') {
+                        continue;
+                    }
+                    return Err(self.error("expected a field name"));
+                }
+                path.push(PathSegment::Field(self.rest()[..name_len].to_string()));
+                self.pos += name_len;
+            } else if self.eat("[") {
+                let segment = if self.eat("*") {
+                    PathSegment::Wildcard
+                } else if self.rest().starts_with('"') {
+                    match self.literal()? {
+                        JsonValue::String(name) => PathSegment::Field(name),
+                        _ => unreachable!("a quoted literal is a string"),
+                    }
+                } else {
+                    let digits = self
+                        .rest()
+                        .find(|c: char| !c.is_ascii_digit())
+                        .unwrap_or(self.rest().len());
+                    let index = self.rest()[..digits]
+                        .parse()
+                        .map_err(|_| self.error("expected an index, \"name\" or *"))?;
+                    self.pos += digits;
+                    PathSegment::Index(index)
+                };
+                if !self.eat("]") {
+                    return Err(self.error("expected ']'"));
+                }
+                path.push(segment);
+            } else {
+                break;
+            }
+        }
+        Ok(path)
+    }
+
+    fn op(&mut self) -> Result<FilterOp> {
+        // Two-character operators before their one-character prefixes.
+        const OPS: &[(&str, FilterOp)] = &[
+            ("==", FilterOp::Eq),
+            ("!=", FilterOp::Ne),
+            ("<=", FilterOp::Le),
+            (">=", FilterOp::Ge),
+            ("<", FilterOp::Lt),
+            (">", FilterOp::Gt),
+            ("contains", FilterOp::Contains),
+            ("startswith", FilterOp::StartsWith),
+            ("exists", FilterOp::Exists),
+        ];
+        for (token, op) in OPS {
+            if self.eat(token) {
+                return Ok(*op);
+            }
+        }
+        Err(self.error("expected an operator (==, !=, <, <=, >, >=, contains, startswith, exists)"))
+    }
+
+    fn literal(&mut self) -> Result<JsonValue> {
+        let mut stream = serde_json::Deserializer::from_str(self.rest()).into_iter::<JsonValue>();
+        match stream.next() {
+            Some(Ok(value)) => {
+                self.pos += stream.byte_offset();
+                Ok(value)
+            }
+            _ => Err(self.error("expected a JSON literal")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event() -> JsonValue {
+        json!({
+            "event_type": "note.created",
+            "memory": "work",
+            "payload": {
+                "type": "NoteCreated",
+                "tags": ["project/x", "draft"],
+                "title": "Quarterly plan",
+                "progress": 40,
+                "items": [{"kind": "a"}, {"kind": "b"}]
+            }
+        })
+    }
+
+    fn matches(expression: &str) -> bool {
+        WebhookFilter::parse(expression).unwrap().matches(&event())
+    }
+
+    #[test]
+    fn equality_and_membership() {
+        assert!(matches(r#".payload.type == "NoteCreated""#));
+        assert!(matches(r#"$.payload.type != "JobQueued""#));
+        assert!(matches(r#".payload.tags contains "project/x""#));
+        assert!(!matches(r#".payload.tags contains "project""#));
+        assert!(matches(r#".payload.title contains "plan""#));
+        assert!(matches(r#".payload.tags[*] startswith "project/""#));
+        assert!(matches(r#".payload.tags[1] == "draft""#));
+        assert!(matches(r#"$["memory"] == "work""#));
+    }
+
+    #[test]
+    fn wildcards_and_missing_paths() {
+        assert!(matches(r#".payload.items[*].kind == "b""#));
+        assert!(!matches(r#".payload.items[*].kind != "b""#));
+        assert!(matches(".payload.items[0] exists"));
+        assert!(!matches(".payload.job_type exists"));
+        assert!(!matches(r#".payload.job_type == "Embedding""#));
+        assert!(matches(r#".payload.job_type != "Embedding""#));
+    }
+
+    #[test]
+    fn numeric_comparisons() {
+        assert!(matches(".payload.progress >= 40"));
+        assert!(matches(".payload.progress < 50.5"));
+        assert!(!matches(".payload.progress > 40"));
+        assert!(!matches(".payload.title > 1"));
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        for expression in [
+            "",
+            "payload.type == \"x\"",
+            ".payload.type",
+            ".payload.type = \"x\"",
+            ".payload.type == NoteCreated",
+            ".payload.tags[x] exists",
+            ".payload.tags[0 exists",
+            ".payload.type == \"x\" extra",
+            ".payload.progress > \"10\"",
+            ".payload.title startswith 1",
+        ] {
+            assert!(
+                matches!(
+                    WebhookFilter::parse(expression),
+                    Err(Error::InvalidInput(_))
+                ),
+                "{expression:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn filter_lists_are_bounded_and_conjunctive() {
+        let filters = WebhookFilter::parse_all(&[
+            r#".payload.type == "NoteCreated""#.to_string(),
+            r#".payload.tags contains "draft""#.to_string(),
+        ])
+        .unwrap();
+        assert!(webhook_filters_match(&filters, &event()));
+        assert!(!webhook_filters_match(
+            &filters,
+            &json!({"payload": {"type": "NoteCreated", "tags": []}})
+        ));
+        assert!(webhook_filters_match(&[], &event()));
+
+        let too_many = vec![".payload exists".to_string(); MAX_WEBHOOK_FILTERS + 1];
+        assert!(WebhookFilter::parse_all(&too_many).is_err());
+        match WebhookFilter::parse_all(&[".payload exists".into(), "bad".into()]) {
+            Err(Error::InvalidInput(msg)) => assert!(msg.starts_with("filters[1]: ")),
+            other => panic!("expected InvalidInput, got {other:?}"),
+        }
+    }
+}
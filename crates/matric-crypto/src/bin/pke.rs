@@ -1,13 +1,17 @@
 //! matric-pke: Command-line tool for public-key encryption operations.
 //!
 //! This CLI provides wallet-style encryption using X25519 key exchange
-//! and AES-256-GCM symmetric encryption.
+//! and AES-256-GCM symmetric encryption, plus detached Ed25519 signatures
+//! for backups signed with the same keys. Files can also be written in age
+//! format so they open with standard `age` tooling.
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use matric_crypto::pke::{
-    decrypt_pke, encrypt_pke, get_pke_recipients, load_private_key, load_public_key,
-    save_private_key, save_public_key, Address, Keypair,
+    decrypt_age, decrypt_pke, encrypt_age, encrypt_pke, get_pke_recipients, is_age_format,
+    load_private_key, load_public_key, save_private_key, save_public_key, to_age_identity,
+    to_age_recipient, Address, Keypair,
 };
+use matric_crypto::sign::{DetachedSignature, SigningKey, DETACHED_SIGNATURE_EXTENSION};
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
@@ -68,9 +72,17 @@ enum Commands {
         /// Recipient public key files (can specify multiple)
         #[arg(short, long, required = true, num_args = 1..)]
         recipient: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = EncryptFormat::Mmpke01)]
+        format: EncryptFormat,
+
+        /// ASCII-armor age output
+        #[arg(long)]
+        armor: bool,
     },
 
-    /// Decrypt a file using your private key
+    /// Decrypt a file (MMPKE01 or age) using your private key
     Decrypt {
         /// Input file to decrypt
         #[arg(short, long)]
@@ -109,6 +121,83 @@ enum Commands {
         /// Address to verify (mm:...)
         address: String,
     },
+
+    /// Export a private key as an age identity file for standard tooling
+    AgeIdentity {
+        /// Path to your private key file
+        #[arg(short, long)]
+        key: PathBuf,
+
+        /// Output identity file (created with owner-only permissions)
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Passphrase for the private key. Prefer --passphrase-stdin or --passphrase-file.
+        #[arg(short, long)]
+        passphrase: Option<String>,
+
+        /// Read the private-key passphrase from stdin.
+        #[arg(long)]
+        passphrase_stdin: bool,
+
+        /// Read the private-key passphrase from a file.
+        #[arg(long)]
+        passphrase_file: Option<PathBuf>,
+    },
+
+    /// Create a detached Ed25519 signature for a file (e.g. a backup)
+    Sign {
+        /// File to sign
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Output file for the signature (default: <input>.sig)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Path to your private key file
+        #[arg(short, long)]
+        key: PathBuf,
+
+        /// Passphrase for the private key. Prefer --passphrase-stdin or --passphrase-file.
+        #[arg(short, long)]
+        passphrase: Option<String>,
+
+        /// Read the private-key passphrase from stdin.
+        #[arg(long)]
+        passphrase_stdin: bool,
+
+        /// Read the private-key passphrase from a file.
+        #[arg(long)]
+        passphrase_file: Option<PathBuf>,
+    },
+
+    /// Verify a detached signature for a file
+    VerifySignature {
+        /// Signed file
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Signature file (default: <input>.sig)
+        #[arg(short, long)]
+        signature: Option<PathBuf>,
+
+        /// Expected signer public key file; the signature must come from its keypair
+        #[arg(short = 'k', long)]
+        public_key: Option<PathBuf>,
+
+        /// Expected signer Ed25519 public key (base64url)
+        #[arg(long, conflicts_with = "public_key")]
+        signer: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum EncryptFormat {
+    /// matric-memory MMPKE01 (records recipients and filename)
+    Mmpke01,
+    /// age v1 with X25519 recipients
+    Age,
 }
 
 fn main() -> ExitCode {
@@ -147,8 +236,13 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
             input,
             output,
             recipient,
+            format,
+            armor,
         } => {
-            cmd_encrypt(&input, &output, &recipient)?;
+            if armor && format != EncryptFormat::Age {
+                return Err("--armor requires --format age".into());
+            }
+            cmd_encrypt(&input, &output, &recipient, format, armor)?;
         }
         Commands::Decrypt {
             input,
@@ -172,6 +266,47 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         Commands::Verify { address } => {
             cmd_verify(&address)?;
         }
+        Commands::AgeIdentity {
+            key,
+            output,
+            passphrase,
+            passphrase_stdin,
+            passphrase_file,
+        } => {
+            warn_inline_passphrase(passphrase.as_deref());
+            let passphrase = resolve_passphrase(
+                passphrase.as_deref(),
+                passphrase_stdin,
+                passphrase_file.as_deref(),
+            )?;
+            cmd_age_identity(&key, &output, &passphrase)?;
+        }
+        Commands::Sign {
+            input,
+            output,
+            key,
+            passphrase,
+            passphrase_stdin,
+            passphrase_file,
+        } => {
+            warn_inline_passphrase(passphrase.as_deref());
+            let passphrase = resolve_passphrase(
+                passphrase.as_deref(),
+                passphrase_stdin,
+                passphrase_file.as_deref(),
+            )?;
+            let output = output.unwrap_or_else(|| detached_signature_path(&input));
+            cmd_sign(&input, &output, &key, &passphrase)?;
+        }
+        Commands::VerifySignature {
+            input,
+            signature,
+            public_key,
+            signer,
+        } => {
+            let signature = signature.unwrap_or_else(|| detached_signature_path(&input));
+            cmd_verify_signature(&input, &signature, public_key.as_deref(), signer.as_deref())?;
+        }
     }
 
     Ok(())
@@ -324,6 +459,8 @@ fn cmd_encrypt(
     input_path: &Path,
     output_path: &Path,
     recipient_paths: &[PathBuf],
+    format: EncryptFormat,
+    armor: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Load all recipient public keys
     let mut recipients = Vec::new();
@@ -345,7 +482,10 @@ fn cmd_encrypt(
         .map(|n| n.to_string_lossy().to_string());
 
     // Encrypt
-    let ciphertext = encrypt_pke(&plaintext, &recipients, original_filename)?;
+    let ciphertext = match format {
+        EncryptFormat::Mmpke01 => encrypt_pke(&plaintext, &recipients, original_filename)?,
+        EncryptFormat::Age => encrypt_age(&plaintext, &recipients, armor)?,
+    };
 
     // Write output
     std::fs::write(output_path, &ciphertext)?;
@@ -356,6 +496,7 @@ fn cmd_encrypt(
         "input_size": plaintext.len(),
         "output_size": ciphertext.len(),
         "recipient_count": recipients.len(),
+        "format": format_name(format),
     });
 
     println!("{}", serde_json::to_string_pretty(&output)?);
@@ -375,8 +516,19 @@ fn cmd_decrypt(
     // Read encrypted file
     let ciphertext = std::fs::read(input_path)?;
 
-    // Decrypt
-    let (plaintext, header) = decrypt_pke(&ciphertext, &private_key)?;
+    // Decrypt; age files carry no filename or timestamp metadata
+    let (plaintext, format, original_filename, created_at) = if is_age_format(&ciphertext) {
+        let plaintext = decrypt_age(&ciphertext, &private_key)?;
+        (plaintext, EncryptFormat::Age, None, None)
+    } else {
+        let (plaintext, header) = decrypt_pke(&ciphertext, &private_key)?;
+        (
+            plaintext,
+            EncryptFormat::Mmpke01,
+            header.original_filename,
+            Some(header.created_at),
+        )
+    };
 
     // Write output
     std::fs::write(output_path, &plaintext)?;
@@ -386,8 +538,9 @@ fn cmd_decrypt(
         "output_path_metadata": path_metadata(output_path),
         "input_size": ciphertext.len(),
         "output_size": plaintext.len(),
-        "original_filename_metadata": optional_filename_metadata(header.original_filename.as_deref()),
-        "created_at": header.created_at,
+        "format": format_name(format),
+        "original_filename_metadata": optional_filename_metadata(original_filename.as_deref()),
+        "created_at": created_at,
     });
 
     println!("{}", serde_json::to_string_pretty(&output)?);
@@ -439,6 +592,134 @@ fn cmd_verify(address: &str) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+fn format_name(format: EncryptFormat) -> &'static str {
+    match format {
+        EncryptFormat::Mmpke01 => "mmpke01",
+        EncryptFormat::Age => "age",
+    }
+}
+
+fn cmd_age_identity(
+    key_path: &Path,
+    output_path: &Path,
+    passphrase: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::Write;
+
+    let private_key = load_private_key(key_path, passphrase)?;
+    let public_key = private_key.public_key();
+    let recipient = to_age_recipient(&public_key);
+    let identity = to_age_identity(&private_key);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(output_path)?;
+    write!(
+        file,
+        "# address: {}\n# public key: {recipient}\n{}\n",
+        public_key.to_address(),
+        identity.as_str()
+    )?;
+
+    let output = serde_json::json!({
+        "identity_path_metadata": path_metadata(output_path),
+        "address": public_key.to_address().to_string(),
+        "age_recipient": recipient,
+    });
+
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    Ok(())
+}
+
+fn detached_signature_path(input: &Path) -> PathBuf {
+    let mut path = input.as_os_str().to_owned();
+    path.push(".");
+    path.push(DETACHED_SIGNATURE_EXTENSION);
+    PathBuf::from(path)
+}
+
+fn cmd_sign(
+    input_path: &Path,
+    output_path: &Path,
+    key_path: &Path,
+    passphrase: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let private_key = load_private_key(key_path, passphrase)?;
+    let signing_key = SigningKey::derive_from_pke(&private_key);
+    let key_id = private_key.public_key().to_address().to_string();
+
+    let data = std::fs::read(input_path)?;
+    let signature = DetachedSignature::create(&data, &key_id, &signing_key);
+    std::fs::write(output_path, signature.to_json()?)?;
+
+    let output = serde_json::json!({
+        "input_path_metadata": path_metadata(input_path),
+        "signature_path_metadata": path_metadata(output_path),
+        "input_size": data.len(),
+        "key_id": key_id,
+        "signer_public_key": signature.public_key,
+    });
+
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    Ok(())
+}
+
+fn cmd_verify_signature(
+    input_path: &Path,
+    signature_path: &Path,
+    public_key_path: Option<&Path>,
+    signer: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let expected_signer = match (public_key_path, signer) {
+        (Some(path), _) => {
+            // Signing keys are derived from the private key, so a public key
+            // file identifies its signer by address.
+            let address = load_public_key(path)?.to_address().to_string();
+            Some(ExpectedSigner::KeyId(address))
+        }
+        (None, Some(signer)) => Some(ExpectedSigner::PublicKey(signer.to_string())),
+        (None, None) => None,
+    };
+
+    let signature = DetachedSignature::from_json(&std::fs::read(signature_path)?)?;
+    let data = std::fs::read(input_path)?;
+    let verified = signature.verify(&data);
+    let trusted = match &expected_signer {
+        Some(ExpectedSigner::KeyId(key_id)) => &signature.key_id == key_id,
+        Some(ExpectedSigner::PublicKey(public_key)) => &signature.public_key == public_key,
+        None => true,
+    };
+
+    let output = serde_json::json!({
+        "input_path_metadata": path_metadata(input_path),
+        "signature_path_metadata": path_metadata(signature_path),
+        "valid": verified.is_ok() && trusted,
+        "key_id": signature.key_id,
+        "signer_public_key": signature.public_key,
+        "signer_checked": expected_signer.is_some(),
+    });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+
+    verified?;
+    if !trusted {
+        return Err("Signature was not made by the expected signer".into());
+    }
+
+    Ok(())
+}
+
+enum ExpectedSigner {
+    KeyId(String),
+    PublicKey(String),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -538,6 +819,82 @@ mod tests {
         assert!(!rendered.contains("alice@example.com"));
     }
 
+    #[test]
+    fn test_detached_signature_path_appends_extension() {
+        assert_eq!(
+            detached_signature_path(Path::new("/backups/snapshot.tar.gz")),
+            PathBuf::from("/backups/snapshot.tar.gz.sig")
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify_signature_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("backup.tar.gz");
+        let signature = dir.path().join("backup.tar.gz.sig");
+        std::fs::write(&input, b"backup archive bytes").unwrap();
+
+        let keypair = Keypair::generate();
+        let private_path = dir.path().join("private.key.enc");
+        let public_path = dir.path().join("public.key");
+        save_private_key(&keypair.private, &private_path, "safe-passphrase-123").unwrap();
+        save_public_key(&keypair.public, &public_path, None).unwrap();
+
+        cmd_sign(&input, &signature, &private_path, "safe-passphrase-123").unwrap();
+        cmd_verify_signature(&input, &signature, Some(&public_path), None).unwrap();
+
+        let other = Keypair::generate();
+        let other_public_path = dir.path().join("other.key");
+        save_public_key(&other.public, &other_public_path, None).unwrap();
+        assert!(cmd_verify_signature(&input, &signature, Some(&other_public_path), None).is_err());
+
+        std::fs::write(&input, b"tampered archive bytes").unwrap();
+        assert!(cmd_verify_signature(&input, &signature, None, None).is_err());
+    }
+
+    #[test]
+    fn test_age_encrypt_decrypt_and_identity_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("export.json");
+        let encrypted = dir.path().join("export.json.age");
+        let decrypted = dir.path().join("export.out.json");
+        let identity = dir.path().join("identity.txt");
+        std::fs::write(&input, b"{\"notes\":[]}").unwrap();
+
+        let keypair = Keypair::generate();
+        let private_path = dir.path().join("private.key.enc");
+        let public_path = dir.path().join("public.key");
+        save_private_key(&keypair.private, &private_path, "safe-passphrase-123").unwrap();
+        save_public_key(&keypair.public, &public_path, None).unwrap();
+
+        cmd_encrypt(
+            &input,
+            &encrypted,
+            std::slice::from_ref(&public_path),
+            EncryptFormat::Age,
+            true,
+        )
+        .unwrap();
+        assert!(is_age_format(&std::fs::read(&encrypted).unwrap()));
+
+        cmd_decrypt(&encrypted, &decrypted, &private_path, "safe-passphrase-123").unwrap();
+        assert_eq!(std::fs::read(&decrypted).unwrap(), b"{\"notes\":[]}");
+
+        cmd_age_identity(&private_path, &identity, "safe-passphrase-123").unwrap();
+        let contents = std::fs::read_to_string(&identity).unwrap();
+        assert!(contents.contains(&to_age_recipient(&keypair.public)));
+        assert!(contents.contains(to_age_identity(&keypair.private).as_str()));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&identity).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // Never overwrite an existing identity file.
+        assert!(cmd_age_identity(&private_path, &identity, "safe-passphrase-123").is_err());
+    }
+
     #[test]
     fn test_address_parse_error_code_is_stable_metadata() {
         let invalid_prefix = "mm_at_secret-token"
@@ -1,14 +1,19 @@
 //! Format detection for encrypted files.
 //!
-//! Automatically detects if a file is PKE encrypted (MMPKE01).
+//! Automatically detects if a file is PKE encrypted (MMPKE01) or age encrypted.
 
 use crate::format::{FileFormat, MAGIC_PKE};
+use crate::pke::is_age_format;
 
 /// Detect the format of a file from its bytes.
 ///
 /// Returns `FileFormat::Pke` if the file is PKE encrypted,
-/// `FileFormat::Unencrypted` otherwise.
+/// `FileFormat::Age` if it is an age file, `FileFormat::Unencrypted` otherwise.
 pub fn detect_format(data: &[u8]) -> FileFormat {
+    if is_age_format(data) {
+        return FileFormat::Age;
+    }
+
     if data.len() < 8 {
         return FileFormat::Unencrypted;
     }
@@ -51,6 +56,19 @@ mod tests {
         assert!(is_pke_encrypted(&encrypted));
     }
 
+    #[test]
+    fn test_detect_age() {
+        let alice = Keypair::generate();
+        let recipients = std::slice::from_ref(&alice.public);
+
+        for armor in [false, true] {
+            let encrypted = crate::pke::encrypt_age(b"test data", recipients, armor).unwrap();
+            assert_eq!(detect_format(&encrypted), FileFormat::Age);
+            assert!(is_encrypted(&encrypted));
+            assert!(!is_pke_encrypted(&encrypted));
+        }
+    }
+
     #[test]
     fn test_detect_unencrypted() {
         let data = b"Just plain text data";
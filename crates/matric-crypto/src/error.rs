@@ -49,6 +49,10 @@ pub enum CryptoError {
     #[error("Invalid recipient ID: {0}")]
     InvalidRecipientId(String),
 
+    /// Signature verification failed or signature material is malformed.
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+
     /// Invalid address format.
     #[error("Invalid address: {0}")]
     InvalidAddress(String),
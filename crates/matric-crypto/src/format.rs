@@ -12,6 +12,8 @@ pub const MAGIC_PKE: &[u8; 8] = b"MMPKE01\n";
 pub enum FileFormat {
     /// Public-key encryption (MMPKE01) - wallet-style.
     Pke,
+    /// age v1 (binary or ASCII-armored), for interop with standard tooling.
+    Age,
     /// Unencrypted file.
     Unencrypted,
 }
@@ -55,6 +57,7 @@ mod tests {
     #[test]
     fn test_file_format_debug() {
         assert_eq!(format!("{:?}", FileFormat::Pke), "Pke");
+        assert_eq!(format!("{:?}", FileFormat::Age), "Age");
         assert_eq!(format!("{:?}", FileFormat::Unencrypted), "Unencrypted");
     }
 }
@@ -5,14 +5,19 @@
 //! This crate provides public-key encryption (PKE) for secure data sharing
 //! using wallet-style addresses. Users share their public key address (`mm:...`)
 //! and senders can encrypt data without needing to exchange passphrases.
+//! Keys also sign: detached Ed25519 signatures vouch for knowledge shards and
+//! backups produced by a keyset.
 //!
 //! ## Cryptographic Primitives
 //!
 //! - **Key exchange**: X25519 (Curve25519 ECDH)
+//! - **Signatures**: Ed25519, with signing keys derived from PKE keys via HKDF
 //! - **Symmetric cipher**: AES-256-GCM (AEAD)
 //! - **Key derivation**: HKDF-SHA256 (for KEK), Argon2id (for private key storage)
 //! - **Address format**: BLAKE3 hash with Base58Check encoding
 //! - **Random generation**: ChaCha20-based CSPRNG
+//! - **Interop**: age v1 files (X25519 recipients, binary or ASCII-armored)
+//! - **Key recovery**: Shamir secret sharing over GF(2^8)
 //!
 //! ## File Format (MMPKE01)
 //!
@@ -93,6 +98,8 @@ pub mod error;
 pub mod format;
 pub mod kdf;
 pub mod pke;
+pub mod sign;
+pub mod sss;
 
 // Re-export commonly used types
 pub use detect::{detect_format, is_encrypted, is_pke_encrypted};
@@ -102,10 +109,12 @@ pub use kdf::{derive_key, validate_passphrase, DerivedKey, KdfParams};
 
 // Re-export PKE types at crate level for convenience
 pub use pke::{
-    can_decrypt_pke, decrypt_pke, encrypt_pke, get_pke_recipients, load_private_key,
-    load_public_key, save_private_key, save_public_key, Address, Keypair, PkeHeader, PrivateKey,
-    PublicKey,
+    can_decrypt_pke, decrypt_age, decrypt_age_stream, decrypt_pke, encrypt_age, encrypt_age_stream,
+    encrypt_pke, get_pke_recipients, is_age_format, load_private_key, load_public_key,
+    save_private_key, save_public_key, Address, Keypair, PkeHeader, PrivateKey, PublicKey,
 };
+pub use sign::{DetachedSignature, Signature, SigningKey, VerifyingKey};
+pub use sss::{combine_shares, split_secret, SecretShare};
 
 #[cfg(test)]
 mod integration_tests {
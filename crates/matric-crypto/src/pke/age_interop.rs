@@ -0,0 +1,424 @@
+//! Interoperability with the age file encryption format.
+//!
+//! MMPKE01 is specific to matric-memory. To let users open exports with
+//! standard tooling (`age`, `rage`), this module encrypts and decrypts
+//! [age v1](https://age-encryption.org/v1) files using X25519 recipient
+//! stanzas derived from the same keypairs.
+//!
+//! A PKE public key maps to an age recipient (`age1...`) and a PKE private
+//! key maps to an age identity (`AGE-SECRET-KEY-1...`), so no new key
+//! material is introduced. Both the binary and the ASCII-armored encodings
+//! are supported; decryption accepts either.
+//!
+//! Unlike MMPKE01, age files do not record recipient addresses or the
+//! original filename: recipient stanzas are anonymous by design.
+//!
+//! # Example
+//!
+//! ```rust
+//! use matric_crypto::pke::{decrypt_age, encrypt_age, to_age_recipient, Keypair};
+//!
+//! let alice = Keypair::generate();
+//! assert!(to_age_recipient(&alice.public).starts_with("age1"));
+//!
+//! let armored = encrypt_age(b"Secret", std::slice::from_ref(&alice.public), true).unwrap();
+//! assert!(armored.starts_with(b"-----BEGIN AGE ENCRYPTED FILE-----"));
+//!
+//! let plaintext = decrypt_age(&armored, &alice.private).unwrap();
+//! assert_eq!(plaintext, b"Secret");
+//! ```
+
+use std::io::{Read, Write};
+
+use ::age::armor::{ArmoredReader, ArmoredWriter, Format};
+pub use ::age::stream::{StreamReader as AgeStreamReader, StreamWriter as AgeStreamWriter};
+use ::age::{x25519, DecryptError, Decryptor, Encryptor};
+use bech32::{Bech32, Hrp};
+use zeroize::Zeroizing;
+
+use crate::error::{CryptoError, CryptoResult};
+use crate::pke::keys::{PrivateKey, PublicKey};
+
+/// First line of a binary age v1 file.
+pub const AGE_MAGIC: &[u8] = b"age-encryption.org/v1\n";
+
+/// First line of an ASCII-armored age file.
+pub const AGE_ARMOR_BEGIN: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+
+/// Bech32 human-readable part of an age X25519 recipient.
+const AGE_RECIPIENT_HRP: &str = "age";
+
+/// Bech32 human-readable part of an age X25519 identity.
+const AGE_IDENTITY_HRP: &str = "age-secret-key-";
+
+/// Check whether data is an age file (binary or ASCII-armored).
+pub fn is_age_format(data: &[u8]) -> bool {
+    // Armored files may carry leading whitespace.
+    let trimmed = match data.iter().position(|b| !b.is_ascii_whitespace()) {
+        Some(start) => &data[start..],
+        None => return false,
+    };
+    data.starts_with(AGE_MAGIC) || trimmed.starts_with(AGE_ARMOR_BEGIN.as_bytes())
+}
+
+/// Encode a PKE public key as an age recipient string (`age1...`).
+pub fn to_age_recipient(public_key: &PublicKey) -> String {
+    bech32::encode::<Bech32>(age_hrp(AGE_RECIPIENT_HRP), public_key.as_bytes())
+        .expect("32-byte key fits in a Bech32 string")
+}
+
+/// Parse an age recipient string (`age1...`) into a PKE public key.
+pub fn parse_age_recipient(recipient: &str) -> CryptoResult<PublicKey> {
+    let bytes = decode_age_key(recipient, AGE_RECIPIENT_HRP)
+        .map_err(|e| CryptoError::InvalidRecipientId(format!("age recipient: {e}")))?;
+    Ok(PublicKey::from_bytes(*bytes))
+}
+
+/// Encode a PKE private key as an age identity string (`AGE-SECRET-KEY-1...`).
+///
+/// The returned string is secret material and is zeroized on drop. It can
+/// be written to an identity file for use with `age -d -i`.
+pub fn to_age_identity(private_key: &PrivateKey) -> Zeroizing<String> {
+    Zeroizing::new(
+        bech32::encode_upper::<Bech32>(age_hrp(AGE_IDENTITY_HRP), private_key.as_bytes())
+            .expect("32-byte key fits in a Bech32 string"),
+    )
+}
+
+/// Parse an age identity string (`AGE-SECRET-KEY-1...`) into a PKE private key.
+pub fn parse_age_identity(identity: &str) -> CryptoResult<PrivateKey> {
+    let bytes = decode_age_key(identity.trim(), AGE_IDENTITY_HRP)
+        .map_err(|e| CryptoError::InvalidKeyfile(format!("age identity: {e}")))?;
+    Ok(PrivateKey::from_bytes(*bytes))
+}
+
+/// Encrypt data to one or more recipients in age format.
+///
+/// # Arguments
+///
+/// * `plaintext` - The data to encrypt
+/// * `recipients` - Public keys of the recipients
+/// * `armor` - Produce ASCII-armored (PEM-style) output instead of binary
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidInput`] if no recipients are given.
+pub fn encrypt_age(
+    plaintext: &[u8],
+    recipients: &[PublicKey],
+    armor: bool,
+) -> CryptoResult<Vec<u8>> {
+    let encryptor = age_encryptor(recipients)?;
+
+    let format = if armor {
+        Format::AsciiArmor
+    } else {
+        Format::Binary
+    };
+    let mut ciphertext = Vec::with_capacity(plaintext.len() + 256);
+    let armored = ArmoredWriter::wrap_output(&mut ciphertext, format)?;
+    let mut writer = encryptor
+        .wrap_output(armored)
+        .map_err(|e| CryptoError::Encryption(e.to_string()))?;
+    writer.write_all(plaintext)?;
+    writer.finish()?.finish()?;
+
+    Ok(ciphertext)
+}
+
+/// Decrypt an age file (binary or ASCII-armored) with a PKE private key.
+///
+/// # Errors
+///
+/// - [`CryptoError::InvalidFormat`] if the data is not an age file
+/// - [`CryptoError::NoMatchingRecipient`] if the key is not a recipient
+/// - [`CryptoError::Authentication`] if the file was tampered with
+pub fn decrypt_age(ciphertext: &[u8], private_key: &PrivateKey) -> CryptoResult<Vec<u8>> {
+    let decryptor =
+        Decryptor::new_buffered(ArmoredReader::new(ciphertext)).map_err(map_decrypt_error)?;
+    let mut reader = age_decrypt(decryptor, private_key)?;
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    reader
+        .read_to_end(&mut plaintext)
+        .map_err(|_| CryptoError::Authentication)?;
+
+    Ok(plaintext)
+}
+
+/// Start encrypting a binary age stream to one or more recipients.
+///
+/// Plaintext written to the returned writer is encrypted in 64 KiB chunks
+/// and written to `output` as it arrives, so arbitrarily large inputs never
+/// need to be held in memory. [`AgeStreamWriter::finish`] must be called to
+/// write the final chunk; a stream dropped without it fails authentication
+/// on decryption.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidInput`] if no recipients are given.
+pub fn encrypt_age_stream<W: Write>(
+    output: W,
+    recipients: &[PublicKey],
+) -> CryptoResult<AgeStreamWriter<W>> {
+    age_encryptor(recipients)?
+        .wrap_output(output)
+        .map_err(|e| CryptoError::Encryption(e.to_string()))
+}
+
+/// Start decrypting a binary age stream with a PKE private key.
+///
+/// Only the header is read up front. Each chunk read from the returned
+/// reader is authenticated before it is yielded, and truncation is reported
+/// as an [`std::io::ErrorKind::InvalidData`] error at end of stream.
+///
+/// # Errors
+///
+/// Same as [`decrypt_age`]; ASCII-armored input is not accepted.
+pub fn decrypt_age_stream<R: Read>(
+    input: R,
+    private_key: &PrivateKey,
+) -> CryptoResult<AgeStreamReader<R>> {
+    let decryptor = Decryptor::new(input).map_err(map_decrypt_error)?;
+    age_decrypt(decryptor, private_key)
+}
+
+fn age_encryptor(recipients: &[PublicKey]) -> CryptoResult<Encryptor> {
+    if recipients.is_empty() {
+        return Err(CryptoError::InvalidInput(
+            "At least one recipient required".to_string(),
+        ));
+    }
+
+    let recipients = recipients
+        .iter()
+        .map(|key| {
+            to_age_recipient(key)
+                .parse::<x25519::Recipient>()
+                .map_err(|e| CryptoError::InvalidRecipientId(e.to_string()))
+        })
+        .collect::<CryptoResult<Vec<_>>>()?;
+    Encryptor::with_recipients(recipients.iter().map(|r| r as &dyn ::age::Recipient))
+        .map_err(|e| CryptoError::Encryption(e.to_string()))
+}
+
+fn age_decrypt<R: Read>(
+    decryptor: Decryptor<R>,
+    private_key: &PrivateKey,
+) -> CryptoResult<AgeStreamReader<R>> {
+    if decryptor.is_scrypt() {
+        return Err(CryptoError::InvalidFormat(
+            "passphrase-encrypted age files are not supported".to_string(),
+        ));
+    }
+
+    let identity = to_age_identity(private_key)
+        .parse::<x25519::Identity>()
+        .map_err(|e| CryptoError::InvalidKeyfile(e.to_string()))?;
+    decryptor
+        .decrypt(std::iter::once(&identity as &dyn ::age::Identity))
+        .map_err(map_decrypt_error)
+}
+
+fn age_hrp(hrp: &str) -> Hrp {
+    Hrp::parse(hrp).expect("age HRPs are valid")
+}
+
+fn decode_age_key(encoded: &str, expected_hrp: &str) -> Result<Zeroizing<[u8; 32]>, String> {
+    let (hrp, data) = bech32::decode(encoded).map_err(|e| e.to_string())?;
+    let data = Zeroizing::new(data);
+    if hrp != age_hrp(expected_hrp) {
+        return Err(format!("expected '{expected_hrp}' prefix"));
+    }
+    let bytes: [u8; 32] = data
+        .as_slice()
+        .try_into()
+        .map_err(|_| format!("expected 32 bytes, got {}", data.len()))?;
+    Ok(Zeroizing::new(bytes))
+}
+
+fn map_decrypt_error(error: DecryptError) -> CryptoError {
+    match error {
+        DecryptError::NoMatchingKeys => CryptoError::NoMatchingRecipient,
+        DecryptError::InvalidHeader | DecryptError::UnknownFormat => {
+            CryptoError::InvalidFormat("not a supported age file".to_string())
+        }
+        DecryptError::InvalidMac | DecryptError::DecryptionFailed => CryptoError::Authentication,
+        DecryptError::Io(e) => CryptoError::Io(e),
+        other => CryptoError::Decryption(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pke::Keypair;
+
+    #[test]
+    fn test_binary_roundtrip_multiple_recipients() {
+        let alice = Keypair::generate();
+        let bob = Keypair::generate();
+        let plaintext = b"Shared with standard tooling";
+
+        let encrypted = encrypt_age(
+            plaintext,
+            &[alice.public.clone(), bob.public.clone()],
+            false,
+        )
+        .unwrap();
+        assert!(encrypted.starts_with(AGE_MAGIC));
+        assert!(is_age_format(&encrypted));
+
+        assert_eq!(decrypt_age(&encrypted, &alice.private).unwrap(), plaintext);
+        assert_eq!(decrypt_age(&encrypted, &bob.private).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_armored_roundtrip() {
+        let alice = Keypair::generate();
+
+        let encrypted = encrypt_age(b"armored", std::slice::from_ref(&alice.public), true).unwrap();
+        let text = std::str::from_utf8(&encrypted).unwrap();
+        assert!(text.starts_with(AGE_ARMOR_BEGIN));
+        assert!(text
+            .trim_end()
+            .ends_with("-----END AGE ENCRYPTED FILE-----"));
+        assert!(is_age_format(format!("\n{text}").as_bytes()));
+
+        assert_eq!(decrypt_age(&encrypted, &alice.private).unwrap(), b"armored");
+    }
+
+    #[test]
+    fn test_wrong_key_is_not_a_recipient() {
+        let alice = Keypair::generate();
+        let eve = Keypair::generate();
+
+        let encrypted = encrypt_age(b"secret", std::slice::from_ref(&alice.public), false).unwrap();
+        assert!(matches!(
+            decrypt_age(&encrypted, &eve.private),
+            Err(CryptoError::NoMatchingRecipient)
+        ));
+    }
+
+    #[test]
+    fn test_tampered_payload_fails_authentication() {
+        let alice = Keypair::generate();
+
+        let mut encrypted =
+            encrypt_age(b"secret", std::slice::from_ref(&alice.public), false).unwrap();
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 0x01;
+        assert!(matches!(
+            decrypt_age(&encrypted, &alice.private),
+            Err(CryptoError::Authentication)
+        ));
+    }
+
+    #[test]
+    fn test_stream_roundtrip_spans_chunks() {
+        let alice = Keypair::generate();
+        let plaintext: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+
+        let mut writer =
+            encrypt_age_stream(Vec::new(), std::slice::from_ref(&alice.public)).unwrap();
+        for chunk in plaintext.chunks(10_000) {
+            writer.write_all(chunk).unwrap();
+        }
+        let encrypted = writer.finish().unwrap();
+        assert_eq!(decrypt_age(&encrypted, &alice.private).unwrap(), plaintext);
+
+        let mut reader = decrypt_age_stream(encrypted.as_slice(), &alice.private).unwrap();
+        let mut decrypted = Vec::new();
+        reader.read_to_end(&mut decrypted).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_stream_truncation_is_detected() {
+        let alice = Keypair::generate();
+        let mut writer =
+            encrypt_age_stream(Vec::new(), std::slice::from_ref(&alice.public)).unwrap();
+        writer.write_all(&[7u8; 150_000]).unwrap();
+        let encrypted = writer.finish().unwrap();
+
+        // Drop the final chunk: every remaining chunk still authenticates.
+        let truncated = &encrypted[..encrypted.len() - 20_000];
+        let mut reader = decrypt_age_stream(truncated, &alice.private).unwrap();
+        let mut decrypted = Vec::new();
+        assert!(reader.read_to_end(&mut decrypted).is_err());
+    }
+
+    #[test]
+    fn test_mmpke_and_plaintext_are_not_age() {
+        let alice = Keypair::generate();
+        let mmpke =
+            crate::pke::encrypt_pke(b"data", std::slice::from_ref(&alice.public), None).unwrap();
+
+        assert!(!is_age_format(&mmpke));
+        assert!(!is_age_format(b"plain text"));
+        assert!(!is_age_format(b""));
+        assert!(matches!(
+            decrypt_age(&mmpke, &alice.private),
+            Err(CryptoError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_no_recipients_rejected() {
+        assert!(matches!(
+            encrypt_age(b"data", &[], false),
+            Err(CryptoError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_strings_roundtrip() {
+        let keypair = Keypair::generate();
+
+        let recipient = to_age_recipient(&keypair.public);
+        assert!(recipient.starts_with("age1"));
+        assert_eq!(
+            parse_age_recipient(&recipient).unwrap().as_bytes(),
+            keypair.public.as_bytes()
+        );
+
+        let identity = to_age_identity(&keypair.private);
+        assert!(identity.starts_with("AGE-SECRET-KEY-1"));
+        assert_eq!(
+            parse_age_identity(&identity).unwrap().as_bytes(),
+            keypair.private.as_bytes()
+        );
+
+        // The age crate agrees on the public half of the identity.
+        let age_identity: x25519::Identity = identity.parse().unwrap();
+        assert_eq!(age_identity.to_public().to_string(), recipient);
+    }
+
+    #[test]
+    fn test_parse_rejects_wrong_prefix() {
+        let keypair = Keypair::generate();
+        let identity = to_age_identity(&keypair.private);
+
+        assert!(matches!(
+            parse_age_recipient(&identity),
+            Err(CryptoError::InvalidRecipientId(_))
+        ));
+        assert!(matches!(
+            parse_age_identity(&to_age_recipient(&keypair.public)),
+            Err(CryptoError::InvalidKeyfile(_))
+        ));
+        assert!(parse_age_recipient("age1notbech32").is_err());
+    }
+
+    /// Test keypair used by the age reference implementation.
+    #[test]
+    fn test_known_identity_maps_to_known_recipient() {
+        let identity = "AGE-SECRET-KEY-1GQ9778VQXMMJVE8SK7J6VT8UJ4HDQAJUVSFCWCM02D8GEWQ72PVQ2Y5J33";
+        let private_key = parse_age_identity(identity).unwrap();
+
+        assert_eq!(
+            to_age_recipient(&private_key.public_key()),
+            "age1t7rxyev2z3rw82stdlrrepyc39nvn86l5078zqkf5uasdy86jp6svpy7pa"
+        );
+        assert_eq!(to_age_identity(&private_key).as_str(), identity);
+    }
+}
@@ -97,6 +97,7 @@
 //! ```
 
 pub mod address;
+pub mod age_interop;
 pub mod ecdh;
 pub mod encrypt;
 pub mod format;
@@ -105,6 +106,11 @@ pub mod keys;
 
 // Re-export commonly used types
 pub use address::Address;
+pub use age_interop::{
+    decrypt_age, decrypt_age_stream, encrypt_age, encrypt_age_stream, is_age_format,
+    parse_age_identity, parse_age_recipient, to_age_identity, to_age_recipient, AgeStreamReader,
+    AgeStreamWriter,
+};
 pub use encrypt::{can_decrypt_pke, decrypt_pke, encrypt_pke, get_pke_recipients};
 pub use format::{is_pke_format, PkeHeader, RecipientBlock, MAGIC_BYTES};
 pub use keys::{
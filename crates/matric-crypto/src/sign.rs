@@ -0,0 +1,412 @@
+//! Detached Ed25519 signatures for knowledge shards and backups.
+//!
+//! A signing key is derived from a PKE private key with HKDF-SHA256, so the
+//! passphrase-protected X25519 key of a keyset doubles as its signer identity
+//! without a second secret to store. Only the Ed25519 public key needs to be
+//! published for others to verify.
+//!
+//! # Detached Signature Format
+//!
+//! A detached signature is a small JSON document stored next to the signed
+//! file (conventionally `<file>.sig`):
+//!
+//! ```text
+//! {
+//!   "format_version": "1",
+//!   "algorithm": "ed25519",
+//!   "key_id": "mm:...",
+//!   "public_key": "<base64url Ed25519 public key>",
+//!   "digest": "<hex SHA-256 of the signed file>",
+//!   "signature": "<base64url signature>"
+//! }
+//! ```
+//!
+//! The signature covers the hex SHA-256 of the canonical (sorted-key, compact)
+//! JSON of every field except `signature`, the same construction knowledge
+//! shard `signature.json` envelopes use.
+//!
+//! # Example
+//!
+//! ```rust
+//! use matric_crypto::pke::Keypair;
+//! use matric_crypto::sign::{DetachedSignature, SigningKey};
+//!
+//! let keypair = Keypair::generate();
+//! let signing_key = SigningKey::derive_from_pke(&keypair.private);
+//! let key_id = keypair.public.to_address().to_string();
+//!
+//! let backup = b"backup archive bytes";
+//! let signature = DetachedSignature::create(backup, &key_id, &signing_key);
+//! let signer = signature.verify(backup).unwrap();
+//! assert_eq!(signer, signing_key.verifying_key());
+//! ```
+
+use base64::Engine;
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{CryptoError, CryptoResult};
+use crate::pke::PrivateKey;
+
+/// Signature algorithm identifier used in envelopes.
+pub const SIGNATURE_ALGORITHM: &str = "ed25519";
+
+/// File extension for detached signature documents.
+pub const DETACHED_SIGNATURE_EXTENSION: &str = "sig";
+
+/// Current detached signature format version.
+const DETACHED_SIGNATURE_VERSION: &str = "1";
+
+/// Domain separation context for deriving signing keys from PKE keys.
+const HKDF_INFO_SIGNING: &[u8] = b"matric-memory-pke-signing-v1";
+
+/// Ed25519 signing key with automatic zeroization.
+pub struct SigningKey(ed25519_dalek::SigningKey);
+
+impl SigningKey {
+    /// Generate a new random signing key.
+    pub fn generate() -> Self {
+        Self(ed25519_dalek::SigningKey::generate(&mut rand::rngs::OsRng))
+    }
+
+    /// Create a signing key from a 32-byte seed.
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        Self(ed25519_dalek::SigningKey::from_bytes(seed))
+    }
+
+    /// Derive the signing key bound to a PKE private key.
+    ///
+    /// The derivation is deterministic, so the same keyset always signs with
+    /// the same Ed25519 identity.
+    pub fn derive_from_pke(private_key: &PrivateKey) -> Self {
+        let hkdf = Hkdf::<Sha256>::new(None, private_key.as_bytes());
+        let mut seed = zeroize::Zeroizing::new([0u8; 32]);
+        // HKDF expand cannot fail with a 32-byte output
+        hkdf.expand(HKDF_INFO_SIGNING, seed.as_mut())
+            .expect("HKDF expand failed - this should never happen with 32-byte output");
+        Self::from_seed(&seed)
+    }
+
+    /// Get the public half of this signing key.
+    pub fn verifying_key(&self) -> VerifyingKey {
+        VerifyingKey(self.0.verifying_key())
+    }
+
+    /// Sign a message.
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        use ed25519_dalek::Signer;
+
+        Signature(self.0.sign(message).to_bytes())
+    }
+}
+
+impl std::fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("key", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Ed25519 public key used to verify signatures.
+#[derive(Clone, PartialEq, Eq)]
+pub struct VerifyingKey(ed25519_dalek::VerifyingKey);
+
+impl VerifyingKey {
+    /// Create a verifying key from raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid Ed25519 point.
+    pub fn from_bytes(bytes: &[u8; 32]) -> CryptoResult<Self> {
+        ed25519_dalek::VerifyingKey::from_bytes(bytes)
+            .map(Self)
+            .map_err(|_| CryptoError::InvalidSignature("invalid public key".to_string()))
+    }
+
+    /// Get the raw bytes of the verifying key.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        self.0.as_bytes()
+    }
+
+    /// Encode as unpadded base64url, the form used in signature envelopes.
+    pub fn to_base64url(&self) -> String {
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(self.as_bytes())
+    }
+
+    /// Decode from unpadded base64url.
+    pub fn from_base64url(value: &str) -> CryptoResult<Self> {
+        let bytes: [u8; 32] = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(value)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| CryptoError::InvalidSignature("invalid public key".to_string()))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Verify a signature over a message.
+    ///
+    /// Uses strict verification, rejecting weak keys and malleable signatures.
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> CryptoResult<()> {
+        let signature = ed25519_dalek::Signature::from_bytes(&signature.0);
+        self.0
+            .verify_strict(message, &signature)
+            .map_err(|_| CryptoError::InvalidSignature("signature does not verify".to_string()))
+    }
+}
+
+impl std::fmt::Debug for VerifyingKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "VerifyingKey({})", hex_encode(&self.as_bytes()[..8]))
+    }
+}
+
+/// Ed25519 signature (64 bytes).
+#[derive(Clone, PartialEq, Eq)]
+pub struct Signature([u8; 64]);
+
+impl Signature {
+    /// Create a signature from raw bytes.
+    pub fn from_bytes(bytes: [u8; 64]) -> Self {
+        Self(bytes)
+    }
+
+    /// Get the raw bytes of the signature.
+    pub fn as_bytes(&self) -> &[u8; 64] {
+        &self.0
+    }
+
+    /// Encode as unpadded base64url.
+    pub fn to_base64url(&self) -> String {
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(self.0)
+    }
+
+    /// Decode from unpadded base64url.
+    pub fn from_base64url(value: &str) -> CryptoResult<Self> {
+        base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(value)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(Self)
+            .ok_or_else(|| CryptoError::InvalidSignature("malformed signature".to_string()))
+    }
+}
+
+impl std::fmt::Debug for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Signature({})", hex_encode(&self.0[..8]))
+    }
+}
+
+/// Detached signature document over a file such as a backup archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DetachedSignature {
+    pub format_version: String,
+    pub algorithm: String,
+    /// Signer identity, typically the keyset address (`mm:...`).
+    pub key_id: String,
+    /// Base64url Ed25519 public key of the signer.
+    pub public_key: String,
+    /// Hex SHA-256 of the signed data.
+    pub digest: String,
+    /// Base64url signature over the canonical payload.
+    pub signature: String,
+}
+
+#[derive(Serialize)]
+struct CanonicalDetachedPayload<'a> {
+    algorithm: &'a str,
+    digest: &'a str,
+    format_version: &'a str,
+    key_id: &'a str,
+    public_key: &'a str,
+}
+
+impl DetachedSignature {
+    /// Sign `data` as `key_id`.
+    pub fn create(data: &[u8], key_id: &str, key: &SigningKey) -> Self {
+        let mut signature = Self {
+            format_version: DETACHED_SIGNATURE_VERSION.to_string(),
+            algorithm: SIGNATURE_ALGORITHM.to_string(),
+            key_id: key_id.to_string(),
+            public_key: key.verifying_key().to_base64url(),
+            digest: sha256_hex(data),
+            signature: String::new(),
+        };
+        signature.signature = key.sign(&signature.signed_message()).to_base64url();
+        signature
+    }
+
+    /// Verify the signature against `data`.
+    ///
+    /// Checks that the document is self-consistent and that `data` matches the
+    /// signed digest. Returns the signer's key; deciding whether that key is
+    /// trusted is up to the caller.
+    pub fn verify(&self, data: &[u8]) -> CryptoResult<VerifyingKey> {
+        if self.format_version != DETACHED_SIGNATURE_VERSION {
+            return Err(CryptoError::InvalidFormat(
+                "unsupported signature format version".to_string(),
+            ));
+        }
+        if self.algorithm != SIGNATURE_ALGORITHM {
+            return Err(CryptoError::InvalidFormat(
+                "unsupported signature algorithm".to_string(),
+            ));
+        }
+        let public_key = VerifyingKey::from_base64url(&self.public_key)?;
+        let signature = Signature::from_base64url(&self.signature)?;
+        public_key.verify(&self.signed_message(), &signature)?;
+        if sha256_hex(data) != self.digest {
+            return Err(CryptoError::InvalidSignature(
+                "signed digest does not match data".to_string(),
+            ));
+        }
+        Ok(public_key)
+    }
+
+    /// Serialize to pretty-printed JSON.
+    pub fn to_json(&self) -> CryptoResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse from JSON.
+    pub fn from_json(data: &[u8]) -> CryptoResult<Self> {
+        Ok(serde_json::from_slice(data)?)
+    }
+
+    fn signed_message(&self) -> Vec<u8> {
+        let canonical = serde_json::to_vec(&CanonicalDetachedPayload {
+            algorithm: &self.algorithm,
+            digest: &self.digest,
+            format_version: &self.format_version,
+            key_id: &self.key_id,
+            public_key: &self.public_key,
+        })
+        .expect("canonical signature payload serializes");
+        sha256_hex(&canonical).into_bytes()
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex_encode(&Sha256::digest(data))
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pke::Keypair;
+
+    #[test]
+    fn test_derive_from_pke_is_deterministic() {
+        let keypair = Keypair::generate();
+        let first = SigningKey::derive_from_pke(&keypair.private);
+        let second = SigningKey::derive_from_pke(&keypair.private);
+        assert_eq!(first.verifying_key(), second.verifying_key());
+
+        let other = Keypair::generate();
+        assert_ne!(
+            first.verifying_key(),
+            SigningKey::derive_from_pke(&other.private).verifying_key()
+        );
+    }
+
+    #[test]
+    fn test_sign_verify_roundtrip() {
+        let key = SigningKey::generate();
+        let signature = key.sign(b"message");
+        assert!(key.verifying_key().verify(b"message", &signature).is_ok());
+        assert!(key.verifying_key().verify(b"tampered", &signature).is_err());
+    }
+
+    #[test]
+    fn test_seeded_key_matches_rfc8032_vector() {
+        // RFC 8032, section 7.1, TEST 1.
+        let seed: [u8; 32] = [
+            0x9d, 0x61, 0xb1, 0x9d, 0xef, 0xfd, 0x5a, 0x60, 0xba, 0x84, 0x4a, 0xf4, 0x92, 0xec,
+            0x2c, 0xc4, 0x44, 0x49, 0xc5, 0x69, 0x7b, 0x32, 0x69, 0x19, 0x70, 0x3b, 0xac, 0x03,
+            0x1c, 0xae, 0x7f, 0x60,
+        ];
+        let key = SigningKey::from_seed(&seed);
+        assert_eq!(
+            hex_encode(key.verifying_key().as_bytes()),
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+        assert_eq!(
+            hex_encode(key.sign(b"").as_bytes()),
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155\
+             5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+        );
+    }
+
+    #[test]
+    fn test_base64url_roundtrip() {
+        let key = SigningKey::generate();
+        let public = key.verifying_key();
+        assert_eq!(
+            VerifyingKey::from_base64url(&public.to_base64url()).unwrap(),
+            public
+        );
+        let signature = key.sign(b"data");
+        assert_eq!(
+            Signature::from_base64url(&signature.to_base64url()).unwrap(),
+            signature
+        );
+        assert!(VerifyingKey::from_base64url("too-short").is_err());
+        assert!(Signature::from_base64url("too-short").is_err());
+    }
+
+    #[test]
+    fn test_detached_signature_roundtrip() {
+        let keypair = Keypair::generate();
+        let key = SigningKey::derive_from_pke(&keypair.private);
+        let key_id = keypair.public.to_address().to_string();
+
+        let signature = DetachedSignature::create(b"backup bytes", &key_id, &key);
+        let parsed = DetachedSignature::from_json(signature.to_json().unwrap().as_bytes()).unwrap();
+        assert_eq!(parsed, signature);
+        assert_eq!(parsed.key_id, key_id);
+        assert_eq!(parsed.verify(b"backup bytes").unwrap(), key.verifying_key());
+    }
+
+    #[test]
+    fn test_detached_signature_rejects_tampering() {
+        let key = SigningKey::generate();
+        let signature = DetachedSignature::create(b"backup bytes", "signer-1", &key);
+
+        assert!(signature.verify(b"other bytes").is_err());
+
+        let mut renamed = signature.clone();
+        renamed.key_id = "signer-2".to_string();
+        assert!(renamed.verify(b"backup bytes").is_err());
+
+        let mut substituted = signature.clone();
+        substituted.public_key = SigningKey::generate().verifying_key().to_base64url();
+        assert!(substituted.verify(b"backup bytes").is_err());
+
+        let mut corrupt = signature;
+        corrupt.signature = Signature::from_bytes([0u8; 64]).to_base64url();
+        assert!(corrupt.verify(b"backup bytes").is_err());
+    }
+
+    #[test]
+    fn test_detached_signature_rejects_unknown_fields() {
+        let key = SigningKey::generate();
+        let signature = DetachedSignature::create(b"data", "signer-1", &key);
+        let mut value = serde_json::to_value(&signature).unwrap();
+        value["extra"] = serde_json::json!(true);
+        assert!(DetachedSignature::from_json(&serde_json::to_vec(&value).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_signing_key_debug_is_redacted() {
+        let key = SigningKey::from_seed(&[7u8; 32]);
+        let debug = format!("{:?}", key);
+        assert!(debug.contains("REDACTED"));
+    }
+}
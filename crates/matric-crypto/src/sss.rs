@@ -0,0 +1,397 @@
+//! Shamir secret sharing for keyset recovery.
+//!
+//! A secret (typically a PKE private key) is split into `N` shares such that
+//! any `K` of them reconstruct it and fewer reveal nothing about it. Each
+//! byte is shared independently over GF(2^8) (AES polynomial `x^8 + x^4 +
+//! x^3 + x + 1`), with share `i` evaluated at `x = i` for `i` in `1..=N`.
+//!
+//! # Share Encoding
+//!
+//! Shares are self-describing text so they can be handed to people:
+//!
+//! ```text
+//! mmss1-<base64url(threshold || index || set_id[8] || data || checksum[4])>
+//! ```
+//!
+//! `set_id` is random per split, so shares from different splits cannot be
+//! mixed by accident, and the checksum (truncated BLAKE3) catches typos.
+//!
+//! # Example
+//!
+//! ```rust
+//! use matric_crypto::sss::{combine_shares, split_secret, SecretShare};
+//!
+//! let secret = [42u8; 32];
+//! let shares = split_secret(&secret, 2, 3).unwrap();
+//!
+//! let encoded: Vec<_> = shares.iter().map(|s| s.to_encoded()).collect();
+//! let recovered: Vec<SecretShare> = encoded[1..]
+//!     .iter()
+//!     .map(|s| SecretShare::from_encoded(s).unwrap())
+//!     .collect();
+//! assert_eq!(combine_shares(&recovered).unwrap().as_slice(), &secret);
+//! ```
+
+use base64::Engine;
+use rand::RngCore;
+use zeroize::Zeroizing;
+
+use crate::error::{CryptoError, CryptoResult};
+
+/// Text prefix (and format version) of encoded shares.
+pub const SHARE_PREFIX: &str = "mmss1-";
+
+/// Smallest allowed threshold; a threshold of 1 would hand out the secret.
+pub const MIN_SHARE_THRESHOLD: u8 = 2;
+
+const SET_ID_LEN: usize = 8;
+const CHECKSUM_LEN: usize = 4;
+const HEADER_LEN: usize = 2 + SET_ID_LEN;
+
+/// One share of a split secret. The share data is zeroized on drop.
+#[derive(Clone)]
+pub struct SecretShare {
+    threshold: u8,
+    index: u8,
+    set_id: [u8; SET_ID_LEN],
+    data: Zeroizing<Vec<u8>>,
+}
+
+impl SecretShare {
+    /// Number of shares needed to reconstruct the secret.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Share index (the x coordinate, `1..=N`).
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Encode the share as `mmss1-...` text.
+    pub fn to_encoded(&self) -> Zeroizing<String> {
+        let mut payload = Zeroizing::new(Vec::with_capacity(
+            HEADER_LEN + self.data.len() + CHECKSUM_LEN,
+        ));
+        payload.push(self.threshold);
+        payload.push(self.index);
+        payload.extend_from_slice(&self.set_id);
+        payload.extend_from_slice(&self.data);
+        let checksum = share_checksum(&payload);
+        payload.extend_from_slice(&checksum);
+
+        Zeroizing::new(format!(
+            "{SHARE_PREFIX}{}",
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(payload.as_slice())
+        ))
+    }
+
+    /// Parse an encoded share, verifying its checksum.
+    pub fn from_encoded(encoded: &str) -> CryptoResult<Self> {
+        let body = encoded
+            .trim()
+            .strip_prefix(SHARE_PREFIX)
+            .ok_or_else(|| CryptoError::InvalidFormat("share must start with mmss1-".into()))?;
+        let payload = Zeroizing::new(
+            base64::engine::general_purpose::URL_SAFE_NO_PAD
+                .decode(body)
+                .map_err(|_| CryptoError::InvalidFormat("share is not valid base64url".into()))?,
+        );
+        if payload.len() <= HEADER_LEN + CHECKSUM_LEN {
+            return Err(CryptoError::InvalidFormat("share is truncated".into()));
+        }
+
+        let (content, checksum) = payload.split_at(payload.len() - CHECKSUM_LEN);
+        if share_checksum(content) != checksum {
+            return Err(CryptoError::InvalidFormat("share checksum mismatch".into()));
+        }
+
+        let threshold = content[0];
+        let index = content[1];
+        if threshold < MIN_SHARE_THRESHOLD || index == 0 {
+            return Err(CryptoError::InvalidFormat("share header is invalid".into()));
+        }
+        let mut set_id = [0u8; SET_ID_LEN];
+        set_id.copy_from_slice(&content[2..HEADER_LEN]);
+
+        Ok(Self {
+            threshold,
+            index,
+            set_id,
+            data: Zeroizing::new(content[HEADER_LEN..].to_vec()),
+        })
+    }
+}
+
+impl std::fmt::Debug for SecretShare {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretShare")
+            .field("threshold", &self.threshold)
+            .field("index", &self.index)
+            .field("data", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Split a secret into `share_count` shares, any `threshold` of which
+/// reconstruct it.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidInput`] if the secret is empty or unless
+/// `2 <= threshold <= share_count`.
+pub fn split_secret(
+    secret: &[u8],
+    threshold: u8,
+    share_count: u8,
+) -> CryptoResult<Vec<SecretShare>> {
+    if secret.is_empty() {
+        return Err(CryptoError::InvalidInput("secret must not be empty".into()));
+    }
+    if threshold < MIN_SHARE_THRESHOLD || threshold > share_count {
+        return Err(CryptoError::InvalidInput(format!(
+            "threshold must be between {MIN_SHARE_THRESHOLD} and the share count"
+        )));
+    }
+
+    let mut rng = rand::rngs::OsRng;
+    let mut set_id = [0u8; SET_ID_LEN];
+    rng.fill_bytes(&mut set_id);
+
+    // Random coefficients for x^1..x^(k-1) of each byte's polynomial.
+    let degree = usize::from(threshold) - 1;
+    let mut coefficients = Zeroizing::new(vec![0u8; degree * secret.len()]);
+    rng.fill_bytes(&mut coefficients);
+
+    let shares = (1..=share_count)
+        .map(|x| {
+            let data = secret
+                .iter()
+                .zip(coefficients.chunks_exact(degree))
+                .map(|(&constant, coefficients)| {
+                    // Horner's rule, highest coefficient first.
+                    let high = coefficients
+                        .iter()
+                        .rev()
+                        .fold(0u8, |acc, &c| gf_mul(acc, x) ^ c);
+                    gf_mul(high, x) ^ constant
+                })
+                .collect();
+            SecretShare {
+                threshold,
+                index: x,
+                set_id,
+                data: Zeroizing::new(data),
+            }
+        })
+        .collect();
+
+    Ok(shares)
+}
+
+/// Reconstruct a secret from at least `threshold` shares of the same split.
+///
+/// Extra shares beyond the threshold are ignored.
+///
+/// # Errors
+///
+/// Returns [`CryptoError::InvalidInput`] if there are too few shares, if
+/// they come from different splits, or if an index is repeated.
+pub fn combine_shares(shares: &[SecretShare]) -> CryptoResult<Zeroizing<Vec<u8>>> {
+    let first = shares
+        .first()
+        .ok_or_else(|| CryptoError::InvalidInput("no shares provided".into()))?;
+    if shares.iter().any(|share| {
+        share.set_id != first.set_id
+            || share.threshold != first.threshold
+            || share.data.len() != first.data.len()
+    }) {
+        return Err(CryptoError::InvalidInput(
+            "shares belong to different splits".into(),
+        ));
+    }
+
+    let mut selected: Vec<&SecretShare> = Vec::with_capacity(usize::from(first.threshold));
+    for share in shares {
+        if selected.iter().any(|s| s.index == share.index) {
+            return Err(CryptoError::InvalidInput(format!(
+                "share {} provided more than once",
+                share.index
+            )));
+        }
+        if selected.len() < usize::from(first.threshold) {
+            selected.push(share);
+        }
+    }
+    if selected.len() < usize::from(first.threshold) {
+        return Err(CryptoError::InvalidInput(format!(
+            "{} shares required, got {}",
+            first.threshold,
+            selected.len()
+        )));
+    }
+
+    // Lagrange basis polynomials evaluated at x = 0.
+    let weights: Vec<u8> = selected
+        .iter()
+        .map(|share| {
+            selected
+                .iter()
+                .filter(|other| other.index != share.index)
+                .fold(1u8, |acc, other| {
+                    gf_mul(acc, gf_div(other.index, other.index ^ share.index))
+                })
+        })
+        .collect();
+
+    let mut secret = Zeroizing::new(vec![0u8; first.data.len()]);
+    for (share, &weight) in selected.iter().zip(&weights) {
+        for (out, &y) in secret.iter_mut().zip(share.data.iter()) {
+            *out ^= gf_mul(y, weight);
+        }
+    }
+    Ok(secret)
+}
+
+fn share_checksum(content: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(SHARE_PREFIX.as_bytes());
+    hasher.update(content);
+    let mut checksum = [0u8; CHECKSUM_LEN];
+    checksum.copy_from_slice(&hasher.finalize().as_bytes()[..CHECKSUM_LEN]);
+    checksum
+}
+
+/// Multiply in GF(2^8) without secret-dependent branches or table lookups.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = a >> 7;
+        a <<= 1;
+        a ^= 0x1b & 0u8.wrapping_sub(carry);
+        b >>= 1;
+    }
+    product
+}
+
+/// Divide in GF(2^8); `b` must be non-zero (share indices are distinct).
+fn gf_div(a: u8, b: u8) -> u8 {
+    // b^254 = b^-1 (Fermat), computed with a fixed square-and-multiply chain.
+    let b2 = gf_mul(b, b);
+    let b4 = gf_mul(b2, b2);
+    let b8 = gf_mul(b4, b4);
+    let b16 = gf_mul(b8, b8);
+    let b32 = gf_mul(b16, b16);
+    let b64 = gf_mul(b32, b32);
+    let b128 = gf_mul(b64, b64);
+    let inverse = gf_mul(
+        gf_mul(gf_mul(b128, b64), gf_mul(b32, b16)),
+        gf_mul(gf_mul(b8, b4), b2),
+    );
+    gf_mul(a, inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gf_inverse_for_every_nonzero_element() {
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_div(1, a)), 1, "inverse of {a}");
+        }
+        // 0x53 * 0xCA = 1 in the AES field.
+        assert_eq!(gf_mul(0x53, 0xca), 1);
+    }
+
+    #[test]
+    fn test_any_threshold_subset_reconstructs() {
+        let secret: Vec<u8> = (0..32).collect();
+        let shares = split_secret(&secret, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        for a in 0..5 {
+            for b in (a + 1)..5 {
+                for c in (b + 1)..5 {
+                    let subset = [shares[c].clone(), shares[a].clone(), shares[b].clone()];
+                    assert_eq!(combine_shares(&subset).unwrap().as_slice(), secret);
+                }
+            }
+        }
+        assert_eq!(combine_shares(&shares).unwrap().as_slice(), secret);
+    }
+
+    #[test]
+    fn test_too_few_shares_rejected() {
+        let shares = split_secret(b"secret", 3, 5).unwrap();
+        assert!(matches!(
+            combine_shares(&shares[..2]),
+            Err(CryptoError::InvalidInput(_))
+        ));
+        assert!(combine_shares(&[]).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_and_mixed_shares_rejected() {
+        let shares = split_secret(b"secret", 2, 3).unwrap();
+        let other = split_secret(b"secret", 2, 3).unwrap();
+
+        assert!(combine_shares(&[shares[0].clone(), shares[0].clone()]).is_err());
+        assert!(combine_shares(&[shares[0].clone(), other[1].clone()]).is_err());
+    }
+
+    #[test]
+    fn test_split_parameters_validated() {
+        assert!(split_secret(b"", 2, 3).is_err());
+        assert!(split_secret(b"secret", 1, 3).is_err());
+        assert!(split_secret(b"secret", 4, 3).is_err());
+        assert_eq!(split_secret(b"secret", 255, 255).unwrap().len(), 255);
+    }
+
+    #[test]
+    fn test_shares_differ_from_secret_and_each_other() {
+        let secret = [0u8; 32];
+        let shares = split_secret(&secret, 2, 3).unwrap();
+        for share in &shares {
+            assert_ne!(share.data.as_slice(), &secret);
+        }
+        assert_ne!(shares[0].data, shares[1].data);
+    }
+
+    #[test]
+    fn test_encoding_roundtrip_and_checksum() {
+        let shares = split_secret(&[7u8; 32], 2, 3).unwrap();
+        let encoded = shares[1].to_encoded();
+        assert!(encoded.starts_with(SHARE_PREFIX));
+
+        let decoded = SecretShare::from_encoded(&format!(" {}\n", encoded.as_str())).unwrap();
+        assert_eq!(decoded.threshold(), 2);
+        assert_eq!(decoded.index(), 2);
+        assert_eq!(decoded.data, shares[1].data);
+
+        // Flip one character of the payload.
+        let mut tampered = encoded.to_string();
+        let pos = SHARE_PREFIX.len() + 5;
+        let replacement = if &tampered[pos..=pos] == "A" {
+            "B"
+        } else {
+            "A"
+        };
+        tampered.replace_range(pos..=pos, replacement);
+        assert!(matches!(
+            SecretShare::from_encoded(&tampered),
+            Err(CryptoError::InvalidFormat(_))
+        ));
+        assert!(SecretShare::from_encoded("mmss2-AAAA").is_err());
+        assert!(SecretShare::from_encoded("mmss1-AAAA").is_err());
+    }
+
+    #[test]
+    fn test_debug_redacts_share_data() {
+        let shares = split_secret(&[0xAB; 4], 2, 2).unwrap();
+        let debug = format!("{:?}", shares[0]);
+        assert!(debug.contains("REDACTED"));
+        assert!(!debug.contains("171"));
+    }
+}
@@ -0,0 +1,436 @@
+//! Merging one archive schema into another.
+//!
+//! A merge copies every row of the source archive into the target inside one
+//! transaction, walking tables in FK order like
+//! [`clone_archive_schema`](matric_core::ArchiveRepository::clone_archive_schema).
+//! Unlike a clone the target already holds data, so rows are first staged in
+//! temporary tables and reconciled:
+//!
+//! 1. Rows whose natural key (any plain unique index besides the primary key,
+//!    such as a tag name, SKOS scheme notation or blob content hash) already
+//!    exists in the target are dropped from the stage, and every staged FK
+//!    pointing at them is rewritten to the target's row.
+//! 2. Notes whose ID already exists in the target are resolved by the
+//!    [`ArchiveMergeConflict`] policy.
+//! 3. Staged rows are inserted parents first with `ON CONFLICT DO NOTHING`,
+//!    so rows the target already has are kept.
+//!
+//! Moving an archive is a merge followed by dropping the source archive,
+//! which the caller does once the merge has committed.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{PgConnection, Postgres, Row, Transaction};
+
+use matric_core::{Error, Result};
+
+use crate::archives::{copyable_columns, fk_ordered_tables, PgArchiveRepository};
+
+/// Whether the source archive survives a merge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveMergeMode {
+    /// Copy rows and leave the source archive untouched.
+    #[default]
+    Copy,
+    /// Copy rows, then drop the source archive.
+    Move,
+}
+
+/// How notes present in both archives (same ID) are resolved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveMergeConflict {
+    /// Keep the target's note. Tags, links and other rows the source adds
+    /// around it are still merged.
+    #[default]
+    Skip,
+    /// Replace the target's note, and everything attached to it, with the
+    /// source's.
+    Overwrite,
+    /// Abort the merge without changes if any note conflicts.
+    Fail,
+}
+
+/// Options for [`PgArchiveRepository::merge_archive_tx`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ArchiveMergeOptions {
+    #[serde(default)]
+    pub mode: ArchiveMergeMode,
+    #[serde(default)]
+    pub on_conflict: ArchiveMergeConflict,
+}
+
+/// Outcome of a merge.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveMergeReport {
+    /// Source notes inserted into the target.
+    pub notes_merged: u64,
+    /// Source notes whose ID already existed in the target.
+    pub notes_conflicting: u64,
+    /// Conflicting target notes replaced by the source's version.
+    pub notes_overwritten: u64,
+    /// Rows inserted, per table (tables with no inserted rows are omitted).
+    pub rows_inserted: BTreeMap<String, u64>,
+    /// Source rows already present in the target and left as they were.
+    pub rows_skipped: u64,
+    /// Source rows matched to an existing target row by natural key.
+    pub rows_remapped: u64,
+    /// Source tables the target schema does not have.
+    pub tables_missing: Vec<String>,
+}
+
+impl fmt::Debug for ArchiveMergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchiveMergeReport")
+            .field("notes_merged", &self.notes_merged)
+            .field("notes_conflicting", &self.notes_conflicting)
+            .field("notes_overwritten", &self.notes_overwritten)
+            .field("table_count", &self.rows_inserted.len())
+            .field("rows_inserted", &self.rows_inserted.values().sum::<u64>())
+            .field("rows_skipped", &self.rows_skipped)
+            .field("rows_remapped", &self.rows_remapped)
+            .field("tables_missing", &self.tables_missing.len())
+            .finish()
+    }
+}
+
+/// Progress callback: percent complete and a short stage label.
+pub type ArchiveMergeProgress<'a> = dyn FnMut(i32, &'static str) + Send + 'a;
+
+/// A single-column FK from `child.column` to `parent.id`.
+struct IdReference {
+    child: String,
+    column: String,
+    parent: String,
+}
+
+/// A plain unique index on a table keyed by `id`.
+struct NaturalKey {
+    table: String,
+    columns: Vec<String>,
+}
+
+/// A source table staged for merging.
+struct StagedTable {
+    name: String,
+    stage: String,
+    columns: Vec<String>,
+    rows: u64,
+}
+
+fn stage_name(index: usize) -> String {
+    format!("archive_merge_stage_{index}")
+}
+
+fn percent(done: usize, total: usize, from: i32, to: i32) -> i32 {
+    from + ((to - from) as usize * done / total.max(1)) as i32
+}
+
+async fn id_references(conn: &mut PgConnection, schema: &str) -> Result<Vec<IdReference>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT c.relname::text AS child, a.attname::text AS child_column,
+               pc.relname::text AS parent
+        FROM pg_constraint con
+        JOIN pg_class c ON con.conrelid = c.oid
+        JOIN pg_namespace n ON c.relnamespace = n.oid
+        JOIN pg_class pc ON con.confrelid = pc.oid
+        JOIN pg_namespace pn ON pc.relnamespace = pn.oid
+        JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum = con.conkey[1]
+        JOIN pg_attribute pa ON pa.attrelid = pc.oid AND pa.attnum = con.confkey[1]
+        WHERE n.nspname = $1 AND pn.nspname = $1
+          AND con.contype = 'f'
+          AND cardinality(con.conkey) = 1
+          AND pa.attname = 'id'
+        "#,
+    )
+    .bind(schema)
+    .fetch_all(conn)
+    .await
+    .map_err(Error::Database)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| IdReference {
+            child: row.get("child"),
+            column: row.get("child_column"),
+            parent: row.get("parent"),
+        })
+        .collect())
+}
+
+async fn natural_keys(conn: &mut PgConnection, schema: &str) -> Result<Vec<NaturalKey>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT c.relname::text AS table_name,
+               array_agg(a.attname::text ORDER BY k.ord) AS columns
+        FROM pg_index i
+        JOIN pg_class c ON i.indrelid = c.oid
+        JOIN pg_namespace n ON c.relnamespace = n.oid
+        CROSS JOIN LATERAL unnest(i.indkey::int2[]) WITH ORDINALITY AS k(attnum, ord)
+        JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum = k.attnum
+        WHERE n.nspname = $1
+          AND i.indisunique
+          AND NOT i.indisprimary
+          AND i.indexprs IS NULL
+          AND i.indpred IS NULL
+          AND EXISTS (
+              SELECT 1 FROM pg_index p
+              JOIN pg_attribute pa ON pa.attrelid = p.indrelid AND pa.attnum = p.indkey[0]
+              WHERE p.indrelid = c.oid AND p.indisprimary
+                AND p.indnatts = 1 AND pa.attname = 'id'
+          )
+        GROUP BY i.indexrelid, c.relname
+        ORDER BY c.relname, i.indexrelid
+        "#,
+    )
+    .bind(schema)
+    .fetch_all(conn)
+    .await
+    .map_err(Error::Database)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| NaturalKey {
+            table: row.get("table_name"),
+            columns: row.get("columns"),
+        })
+        .collect())
+}
+
+impl PgArchiveRepository {
+    /// Merge every row of `source_schema` into `target_schema`.
+    ///
+    /// Runs entirely inside `tx`: on error nothing is written. The source is
+    /// only read; dropping it for [`ArchiveMergeMode::Move`] is left to the
+    /// caller after commit.
+    pub async fn merge_archive_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        source_schema: &str,
+        target_schema: &str,
+        options: ArchiveMergeOptions,
+        progress: &mut ArchiveMergeProgress<'_>,
+    ) -> Result<ArchiveMergeReport> {
+        crate::validate_schema_name(source_schema)?;
+        crate::validate_schema_name(target_schema)?;
+        if source_schema == target_schema {
+            return Err(Error::InvalidInput(
+                "Cannot merge an archive into itself".to_string(),
+            ));
+        }
+
+        // Triggers fired by the inserts resolve unqualified names against the
+        // target, as they would for a normal write to it.
+        sqlx::query(&format!(
+            "SET LOCAL search_path TO {}, public",
+            target_schema
+        ))
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let mut report = ArchiveMergeReport::default();
+        let tables = fk_ordered_tables(tx, source_schema).await?;
+
+        // Stage 1: snapshot the source into temporary tables.
+        let mut staged: Vec<StagedTable> = Vec::with_capacity(tables.len());
+        for (index, table) in tables.iter().enumerate() {
+            progress(percent(index, tables.len(), 0, 30), "Staging source rows");
+            let source_columns = copyable_columns(tx, source_schema, table).await?;
+            let target_columns = copyable_columns(tx, target_schema, table).await?;
+            if target_columns.is_empty() {
+                report.tables_missing.push(table.clone());
+                continue;
+            }
+            let columns: Vec<String> = source_columns
+                .into_iter()
+                .filter(|column| target_columns.contains(column))
+                .collect();
+            if columns.is_empty() {
+                continue;
+            }
+
+            let stage = stage_name(index);
+            let rows = sqlx::query(&format!(
+                "CREATE TEMP TABLE {stage} ON COMMIT DROP AS SELECT {} FROM {}.{}",
+                columns.join(", "),
+                source_schema,
+                table
+            ))
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?
+            .rows_affected();
+            staged.push(StagedTable {
+                name: table.clone(),
+                stage,
+                columns,
+                rows,
+            });
+        }
+
+        // Stage 2: point staged rows at target rows that share a natural key.
+        let references = id_references(tx, source_schema).await?;
+        let keys = natural_keys(tx, target_schema).await?;
+        for (index, table) in staged.iter().enumerate() {
+            progress(
+                percent(index, staged.len(), 30, 45),
+                "Matching existing records",
+            );
+            for key in keys.iter().filter(|key| key.table == table.name) {
+                if !key.columns.iter().all(|c| table.columns.contains(c)) {
+                    continue;
+                }
+                let matches = key
+                    .columns
+                    .iter()
+                    .map(|column| format!("s.{column} = t.{column}"))
+                    .collect::<Vec<_>>()
+                    .join(" AND ");
+                sqlx::query("DROP TABLE IF EXISTS archive_merge_remap")
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(Error::Database)?;
+                let remapped = sqlx::query(&format!(
+                    "CREATE TEMP TABLE archive_merge_remap ON COMMIT DROP AS \
+                     SELECT s.id AS old_id, t.id AS new_id \
+                     FROM {} s JOIN {}.{} t ON {matches} WHERE s.id <> t.id",
+                    table.stage, target_schema, table.name
+                ))
+                .execute(&mut **tx)
+                .await
+                .map_err(Error::Database)?
+                .rows_affected();
+                if remapped == 0 {
+                    continue;
+                }
+                report.rows_remapped += remapped;
+
+                sqlx::query(&format!(
+                    "DELETE FROM {} WHERE id IN (SELECT old_id FROM archive_merge_remap)",
+                    table.stage
+                ))
+                .execute(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+                for reference in references.iter().filter(|r| r.parent == table.name) {
+                    let Some(child) = staged.iter().find(|s| s.name == reference.child) else {
+                        continue;
+                    };
+                    if !child.columns.contains(&reference.column) {
+                        continue;
+                    }
+                    sqlx::query(&format!(
+                        "UPDATE {stage} c SET {column} = m.new_id \
+                         FROM archive_merge_remap m WHERE c.{column} = m.old_id",
+                        stage = child.stage,
+                        column = reference.column
+                    ))
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(Error::Database)?;
+                }
+            }
+        }
+
+        // Stage 3: resolve notes that exist on both sides.
+        progress(45, "Resolving note conflicts");
+        if let Some(notes) = staged.iter().find(|s| s.name == "note") {
+            report.notes_conflicting = sqlx::query_scalar::<_, i64>(&format!(
+                "SELECT COUNT(*) FROM {} s JOIN {}.note t ON t.id = s.id",
+                notes.stage, target_schema
+            ))
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)? as u64;
+
+            if report.notes_conflicting > 0 {
+                match options.on_conflict {
+                    ArchiveMergeConflict::Skip => {}
+                    ArchiveMergeConflict::Fail => {
+                        return Err(Error::InvalidInput(format!(
+                            "Archive merge aborted; conflicting_notes={}",
+                            report.notes_conflicting
+                        )));
+                    }
+                    ArchiveMergeConflict::Overwrite => {
+                        // FK cascades remove everything hanging off the
+                        // replaced notes; the source's rows take their place.
+                        report.notes_overwritten = sqlx::query(&format!(
+                            "DELETE FROM {}.note WHERE id IN (SELECT id FROM {})",
+                            target_schema, notes.stage
+                        ))
+                        .execute(&mut **tx)
+                        .await
+                        .map_err(Error::Database)?
+                        .rows_affected();
+                    }
+                }
+            }
+        }
+
+        // Stage 4: insert parents first, keeping rows the target already has.
+        for (index, table) in staged.iter().enumerate() {
+            progress(percent(index, staged.len(), 50, 100), "Merging records");
+            let columns = table.columns.join(", ");
+            let inserted = sqlx::query(&format!(
+                "INSERT INTO {}.{} ({columns}) SELECT {columns} FROM {} ON CONFLICT DO NOTHING",
+                target_schema, table.name, table.stage
+            ))
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?
+            .rows_affected();
+            if inserted > 0 {
+                report.rows_inserted.insert(table.name.clone(), inserted);
+            }
+            if table.name == "note" {
+                report.notes_merged = inserted;
+            }
+        }
+
+        let staged_rows: u64 = staged.iter().map(|table| table.rows).sum();
+        let inserted_rows: u64 = report.rows_inserted.values().sum();
+        report.rows_skipped = staged_rows.saturating_sub(inserted_rows + report.rows_remapped);
+        progress(100, "Merge complete");
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_default_to_copy_and_skip() {
+        let options: ArchiveMergeOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(options.mode, ArchiveMergeMode::Copy);
+        assert_eq!(options.on_conflict, ArchiveMergeConflict::Skip);
+
+        let options: ArchiveMergeOptions =
+            serde_json::from_str(r#"{"mode":"move","on_conflict":"overwrite"}"#).unwrap();
+        assert_eq!(options.mode, ArchiveMergeMode::Move);
+        assert_eq!(options.on_conflict, ArchiveMergeConflict::Overwrite);
+    }
+
+    #[test]
+    fn percent_spans_the_stage_range() {
+        assert_eq!(percent(0, 10, 50, 100), 50);
+        assert_eq!(percent(5, 10, 50, 100), 75);
+        assert_eq!(percent(10, 10, 50, 100), 100);
+        assert_eq!(percent(0, 0, 0, 30), 0);
+    }
+
+    #[test]
+    fn report_debug_omits_table_names() {
+        let mut report = ArchiveMergeReport::default();
+        report.rows_inserted.insert("note_secret".to_string(), 3);
+        report.tables_missing.push("legacy_table".to_string());
+        let debug = format!("{report:?}");
+        assert!(!debug.contains("note_secret"));
+        assert!(!debug.contains("legacy_table"));
+        assert!(debug.contains("rows_inserted: 3"));
+    }
+}
@@ -0,0 +1,223 @@
+//! Per-archive storage quotas and usage reporting.
+//!
+//! Quotas are shared, keyed by schema name (`public` for the default
+//! archive) like the other per-archive settings. Usage is counted from the
+//! archive schema itself, so it is always current.
+
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use matric_core::{
+    ArchiveBlobUsage, ArchiveQuota, ArchiveQuotaExceeded, ArchiveQuotaKind, ArchiveTableUsage,
+    ArchiveUsage, Error, Result,
+};
+
+use crate::archives::SHARED_TABLES;
+use crate::schema_validation::validate_schema_name;
+
+/// PostgreSQL repository for archive quotas and usage.
+#[derive(Clone)]
+pub struct PgArchiveQuotaRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgArchiveQuotaRepository {
+    /// Create a new archive quota repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// An archive's stored quota, if it has one.
+    pub async fn get(&self, schema_name: &str) -> Result<Option<ArchiveQuota>> {
+        let row: Option<(Option<i64>, Option<i64>, Option<i64>)> = sqlx::query_as(
+            "SELECT max_notes, max_attachment_bytes, max_embeddings
+             FROM archive_quota
+             WHERE schema_name = $1",
+        )
+        .bind(schema_name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.map(
+            |(max_notes, max_attachment_bytes, max_embeddings)| ArchiveQuota {
+                max_notes,
+                max_attachment_bytes,
+                max_embeddings,
+            },
+        ))
+    }
+
+    /// Create or replace an archive's quota.
+    pub async fn set(&self, schema_name: &str, quota: &ArchiveQuota) -> Result<()> {
+        quota.validate()?;
+        sqlx::query(
+            "INSERT INTO archive_quota (schema_name, max_notes, max_attachment_bytes, max_embeddings)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (schema_name) DO UPDATE SET
+                 max_notes = EXCLUDED.max_notes,
+                 max_attachment_bytes = EXCLUDED.max_attachment_bytes,
+                 max_embeddings = EXCLUDED.max_embeddings,
+                 updated_at = NOW()",
+        )
+        .bind(schema_name)
+        .bind(quota.max_notes)
+        .bind(quota.max_attachment_bytes)
+        .bind(quota.max_embeddings)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Remove an archive's quota so it is unlimited again.
+    pub async fn delete(&self, schema_name: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM archive_quota WHERE schema_name = $1")
+            .bind(schema_name)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Whether the archive may take `adding` more of `kind`.
+    ///
+    /// Returns the exceeded quota, or `None` when the archive has room or no
+    /// limit on `kind`. Only the limited kind is counted.
+    pub async fn check(
+        &self,
+        schema_name: &str,
+        kind: ArchiveQuotaKind,
+        adding: i64,
+    ) -> Result<Option<ArchiveQuotaExceeded>> {
+        let Some(quota) = self.get(schema_name).await? else {
+            return Ok(None);
+        };
+        if quota.limit(kind).is_none() {
+            return Ok(None);
+        }
+        let used = self.count(schema_name, kind).await?;
+        Ok(quota.check(kind, used, adding).err())
+    }
+
+    /// Whether a note's embeddings may be replaced by `adding` rows.
+    ///
+    /// The rows the embedding job replaces — the note's rows in
+    /// `embedding_set_id`, or all of them when it is `None` — do not count
+    /// towards the archive's usage.
+    pub async fn check_embeddings(
+        &self,
+        schema_name: &str,
+        note_id: Uuid,
+        embedding_set_id: Option<Uuid>,
+        adding: i64,
+    ) -> Result<Option<ArchiveQuotaExceeded>> {
+        let Some(quota) = self.get(schema_name).await? else {
+            return Ok(None);
+        };
+        if quota.max_embeddings.is_none() {
+            return Ok(None);
+        }
+        validate_schema_name(schema_name)?;
+        let used: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM {schema_name}.embedding
+             WHERE NOT (note_id = $1 AND ($2::uuid IS NULL OR embedding_set_id = $2))"
+        ))
+        .bind(note_id)
+        .bind(embedding_set_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(quota
+            .check(ArchiveQuotaKind::Embeddings, used, adding)
+            .err())
+    }
+
+    /// What an archive currently holds, by quota kind and by table.
+    pub async fn usage(&self, schema_name: &str) -> Result<ArchiveUsage> {
+        validate_schema_name(schema_name)?;
+        let (notes, attachments, embeddings): (i64, i64, i64) = sqlx::query_as(&format!(
+            "SELECT
+                 (SELECT COUNT(*) FROM {schema_name}.note WHERE deleted_at IS NULL),
+                 (SELECT COUNT(*) FROM {schema_name}.attachment),
+                 (SELECT COUNT(*) FROM {schema_name}.embedding)"
+        ))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        let blob_storage = self.blob_usage(schema_name).await?;
+
+        let shared: Vec<String> = SHARED_TABLES.iter().map(|t| t.to_string()).collect();
+        let tables: Vec<(String, i64, i64)> = sqlx::query_as(
+            "SELECT c.relname::text,
+                    GREATEST(c.reltuples, 0)::bigint,
+                    pg_total_relation_size(c.oid)
+             FROM pg_class c
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = $1
+               AND c.relkind IN ('r', 'p')
+               AND c.relname <> ALL($2)
+             ORDER BY 3 DESC, 1",
+        )
+        .bind(schema_name)
+        .bind(&shared)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        let tables: Vec<ArchiveTableUsage> = tables
+            .into_iter()
+            .map(|(table, rows_estimate, total_bytes)| ArchiveTableUsage {
+                table,
+                rows_estimate,
+                total_bytes,
+            })
+            .collect();
+
+        Ok(ArchiveUsage {
+            notes,
+            attachments,
+            embeddings,
+            blob_storage,
+            table_bytes: tables.iter().map(|t| t.total_bytes).sum(),
+            tables,
+        })
+    }
+
+    async fn count(&self, schema_name: &str, kind: ArchiveQuotaKind) -> Result<i64> {
+        validate_schema_name(schema_name)?;
+        let sql = match kind {
+            ArchiveQuotaKind::Notes => {
+                format!("SELECT COUNT(*) FROM {schema_name}.note WHERE deleted_at IS NULL")
+            }
+            ArchiveQuotaKind::AttachmentBytes => {
+                return Ok(self.blob_usage(schema_name).await?.bytes);
+            }
+            ArchiveQuotaKind::Embeddings => {
+                format!("SELECT COUNT(*) FROM {schema_name}.embedding")
+            }
+        };
+        sqlx::query_scalar(&sql)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Error::Database)
+    }
+
+    async fn blob_usage(&self, schema_name: &str) -> Result<ArchiveBlobUsage> {
+        validate_schema_name(schema_name)?;
+        let (blobs, bytes, database_bytes): (i64, i64, i64) = sqlx::query_as(&format!(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(size_bytes), 0)::bigint,
+                    COALESCE(SUM(size_bytes) FILTER (WHERE storage_backend = 'database'), 0)::bigint
+             FROM {schema_name}.attachment_blob"
+        ))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(ArchiveBlobUsage {
+            blobs,
+            bytes,
+            database_bytes,
+            external_bytes: bytes - database_bytes,
+        })
+    }
+}
@@ -5,7 +5,7 @@
 
 use async_trait::async_trait;
 use chrono::Utc;
-use sqlx::{Pool, Postgres, Row};
+use sqlx::{PgConnection, Pool, Postgres, Row};
 use std::fmt;
 use uuid::Uuid;
 
@@ -108,6 +108,20 @@ const FTS_FIX_DEFINITIONS: &[FtsFixDefinition] = &[
             definition: "USING gin (to_tsvector('public.matric_english', content))",
         }],
     },
+    FtsFixDefinition {
+        table: "note_translation",
+        generated_column: None,
+        indexes: &[
+            FtsIndex {
+                index_name: "idx_note_translation_fts",
+                definition: "USING gin (to_tsvector('public.matric_english', content))",
+            },
+            FtsIndex {
+                index_name: "idx_note_translation_fts_simple",
+                definition: "USING gin (to_tsvector('public.matric_simple', content))",
+            },
+        ],
+    },
     FtsFixDefinition {
         table: "note",
         generated_column: None,
@@ -131,20 +145,30 @@ const FTS_FIX_DEFINITIONS: &[FtsFixDefinition] = &[
 /// These tables contain global system data and are NOT cloned per-memory.
 /// Any table in `public` NOT in this list is automatically cloned when
 /// creating a new memory, ensuring zero-drift as migrations add tables.
-const SHARED_TABLES: &[&str] = &[
+pub(crate) const SHARED_TABLES: &[&str] = &[
     "_sqlx_migrations",
     "api_key",
+    "app_user",
     "archive_registry",
     "archive_inference_override",
+    "archive_quota",
+    "archive_share_grant",
+    "archive_translation_setting",
+    "archive_version_policy",
+    "backup_policy",
+    "backup_policy_run",
     "call_sessions",
+    "digest_config",
     "document_type",
     "embedding_config",
     "event_outbox",
+    "events",
     "file_upload_audit",
     "inbound_dlq",
     "inbound_source",
     "incoming_webhook_receiver",
     "inference_config_audit",
+    "inference_usage",
     "job_attempt",
     "job_history",
     "job_queue",
@@ -152,7 +176,10 @@ const SHARED_TABLES: &[&str] = &[
     "oauth_client",
     "oauth_token",
     "pke_public_keys",
+    "prompt_template",
+    "published_site",
     "realtime_media_stream_attempt",
+    "sync_peer",
     "transcript_segments",
     "usage_event_conflict",
     "usage_event_delivery",
@@ -174,6 +201,86 @@ fn fk_action_sql(code: &str) -> &str {
     }
 }
 
+/// Tables of `schema` ordered by FK dependency, parents first, so rows can be
+/// copied table by table without disabling triggers.
+pub(crate) async fn fk_ordered_tables(
+    conn: &mut PgConnection,
+    schema: &str,
+) -> Result<Vec<String>> {
+    sqlx::query_scalar(
+        r#"
+        WITH RECURSIVE
+        fk_deps AS (
+            SELECT DISTINCT
+                c.relname::text AS child,
+                pc.relname::text AS parent
+            FROM pg_constraint con
+            JOIN pg_class c ON con.conrelid = c.oid
+            JOIN pg_namespace n ON c.relnamespace = n.oid
+            JOIN pg_class pc ON con.confrelid = pc.oid
+            JOIN pg_namespace pn ON pc.relnamespace = pn.oid
+            WHERE n.nspname = $1 AND pn.nspname = $1
+              AND con.contype = 'f'
+              AND c.oid != con.confrelid
+        ),
+        levels AS (
+            -- Level 0: tables with no FK dependencies within this schema
+            SELECT t.relname::text AS table_name, 0 AS lvl
+            FROM pg_class t
+            JOIN pg_namespace n ON t.relnamespace = n.oid
+            WHERE n.nspname = $1 AND t.relkind = 'r'
+              AND NOT EXISTS (
+                  SELECT 1 FROM fk_deps d WHERE d.child = t.relname::text
+              )
+
+            UNION ALL
+
+            -- Level N+1: tables that reference a table at level N
+            SELECT d.child, l.lvl + 1
+            FROM fk_deps d
+            JOIN levels l ON l.table_name = d.parent
+        )
+        SELECT table_name
+        FROM levels
+        GROUP BY table_name
+        ORDER BY MAX(lvl), table_name
+        "#,
+    )
+    .bind(schema)
+    .fetch_all(conn)
+    .await
+    .map_err(Error::Database)
+}
+
+/// Columns of `schema.table` that can be copied with an explicit column list:
+/// generated and identity columns are left out.
+pub(crate) async fn copyable_columns(
+    conn: &mut PgConnection,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<String>> {
+    sqlx::query_scalar(
+        r#"
+        SELECT a.attname::text
+        FROM pg_attribute a
+        JOIN pg_class c ON a.attrelid = c.oid
+        JOIN pg_namespace n ON c.relnamespace = n.oid
+        WHERE n.nspname = $1
+            AND c.relname = $2
+            AND a.attnum > 0
+            AND NOT a.attisdropped
+            AND a.attgenerated = ''
+            AND a.attidentity = ''
+        ORDER BY a.attnum
+        "#,
+    )
+    .bind(schema)
+    .bind(table)
+    .fetch_all(conn)
+    .await
+    .map_err(Error::Database)
+}
+
 /// PostgreSQL implementation of ArchiveRepository.
 pub struct PgArchiveRepository {
     pool: Pool<Postgres>,
@@ -1089,6 +1196,39 @@ impl ArchiveRepository for PgArchiveRepository {
             .await
             .map_err(Error::Database)?;
 
+        // A later archive reusing the schema name must not inherit the policy.
+        sqlx::query("DELETE FROM archive_version_policy WHERE schema_name = $1")
+            .bind(&archive.schema_name)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        sqlx::query("DELETE FROM archive_translation_setting WHERE schema_name = $1")
+            .bind(&archive.schema_name)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        sqlx::query("DELETE FROM archive_quota WHERE schema_name = $1")
+            .bind(&archive.schema_name)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        // Published sites would otherwise keep resolving to a dropped schema.
+        sqlx::query("DELETE FROM published_site WHERE schema_name = $1")
+            .bind(&archive.schema_name)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        // Digests would otherwise keep being scheduled against a dropped schema.
+        sqlx::query("DELETE FROM digest_config WHERE schema_name = $1")
+            .bind(&archive.schema_name)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
         Ok(())
     }
 
@@ -1291,55 +1431,13 @@ impl ArchiveRepository for PgArchiveRepository {
         // Create the new archive with empty tables
         let new_archive = self.create_archive_schema(new_name, description).await?;
 
-        // Order tables by FK dependency (parents first) so inserts respect referential integrity
-        // without needing superuser privileges to disable triggers.
-        let ordered_tables: Vec<String> = sqlx::query_scalar(
-            r#"
-            WITH RECURSIVE
-            fk_deps AS (
-                SELECT DISTINCT
-                    c.relname::text AS child,
-                    pc.relname::text AS parent
-                FROM pg_constraint con
-                JOIN pg_class c ON con.conrelid = c.oid
-                JOIN pg_namespace n ON c.relnamespace = n.oid
-                JOIN pg_class pc ON con.confrelid = pc.oid
-                JOIN pg_namespace pn ON pc.relnamespace = pn.oid
-                WHERE n.nspname = $1 AND pn.nspname = $1
-                  AND con.contype = 'f'
-                  AND c.oid != con.confrelid
-            ),
-            levels AS (
-                -- Level 0: tables with no FK dependencies within this schema
-                SELECT t.relname::text AS table_name, 0 AS lvl
-                FROM pg_class t
-                JOIN pg_namespace n ON t.relnamespace = n.oid
-                WHERE n.nspname = $1 AND t.relkind = 'r'
-                  AND NOT EXISTS (
-                      SELECT 1 FROM fk_deps d WHERE d.child = t.relname::text
-                  )
-
-                UNION ALL
-
-                -- Level N+1: tables that reference a table at level N
-                SELECT d.child, l.lvl + 1
-                FROM fk_deps d
-                JOIN levels l ON l.table_name = d.parent
-            )
-            SELECT table_name
-            FROM levels
-            GROUP BY table_name
-            ORDER BY MAX(lvl), table_name
-            "#,
-        )
-        .bind(&source.schema_name)
-        .fetch_all(&self.pool)
-        .await
-        .map_err(Error::Database)?;
-
         // Copy data in a single transaction
         let mut tx = self.pool.begin().await.map_err(Error::Database)?;
 
+        // Order tables by FK dependency (parents first) so inserts respect referential integrity
+        // without needing superuser privileges to disable triggers.
+        let ordered_tables = fk_ordered_tables(&mut tx, &source.schema_name).await?;
+
         // Delete the seeded default concept scheme so the source's scheme (with its UUIDs)
         // gets copied instead. Cloned concepts reference source scheme UUIDs via FK, so we
         // must preserve the source's IDs rather than using the freshly-seeded ones.
@@ -1359,26 +1457,7 @@ impl ArchiveRepository for PgArchiveRepository {
             }
 
             // Get non-generated columns to avoid "cannot insert into generated column" errors
-            let columns: Vec<String> = sqlx::query_scalar(
-                r#"
-                SELECT a.attname::text
-                FROM pg_attribute a
-                JOIN pg_class c ON a.attrelid = c.oid
-                JOIN pg_namespace n ON c.relnamespace = n.oid
-                WHERE n.nspname = $1
-                    AND c.relname = $2
-                    AND a.attnum > 0
-                    AND NOT a.attisdropped
-                    AND a.attgenerated = ''
-                    AND a.attidentity = ''
-                ORDER BY a.attnum
-                "#,
-            )
-            .bind(&source.schema_name)
-            .bind(table)
-            .fetch_all(&mut *tx)
-            .await
-            .map_err(Error::Database)?;
+            let columns = copyable_columns(&mut tx, &source.schema_name, table).await?;
 
             if columns.is_empty() {
                 continue;
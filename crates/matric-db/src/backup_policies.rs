@@ -0,0 +1,338 @@
+//! Scheduled backup policy repository.
+//!
+//! Policies and their run history live in the shared `public` schema. The
+//! scheduler claims due policies with a compare-and-set on `next_run_at_utc`,
+//! so several API instances never queue the same slot twice.
+
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgRow, Pool, Postgres, Row};
+use uuid::Uuid;
+
+use matric_core::{
+    new_v7, BackupPolicy, BackupPolicyRun, CreateBackupPolicyRequest, Error, Result,
+};
+
+const BACKUP_POLICY_COLUMNS: &str = "id, name, schedule, backup_type, keyset, keep_daily, \
+     keep_weekly, enabled, next_run_at_utc, last_run_at_utc, last_status, created_at_utc, \
+     updated_at_utc";
+
+const BACKUP_POLICY_RUN_COLUMNS: &str = "id, policy_id, job_id, status, filename, size_bytes, \
+     error, started_at_utc, finished_at_utc, pruned_at_utc";
+
+/// Run status while the backup is being written.
+pub const BACKUP_RUN_RUNNING: &str = "running";
+/// Run status once the backup file exists.
+pub const BACKUP_RUN_SUCCEEDED: &str = "succeeded";
+/// Run status when the backup could not be created.
+pub const BACKUP_RUN_FAILED: &str = "failed";
+
+/// PostgreSQL repository for scheduled backup policies.
+pub struct PgBackupPolicyRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgBackupPolicyRepository {
+    /// Create a new backup policy repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// List all policies by name.
+    pub async fn list(&self) -> Result<Vec<BackupPolicy>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {BACKUP_POLICY_COLUMNS} FROM backup_policy ORDER BY name"
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows.iter().map(policy_from_row).collect())
+    }
+
+    /// Get a policy by ID.
+    pub async fn get(&self, id: Uuid) -> Result<Option<BackupPolicy>> {
+        let row = sqlx::query(&format!(
+            "SELECT {BACKUP_POLICY_COLUMNS} FROM backup_policy WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.as_ref().map(policy_from_row))
+    }
+
+    /// Create a policy whose first run is at `next_run_at`.
+    pub async fn create(
+        &self,
+        req: &CreateBackupPolicyRequest,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<BackupPolicy> {
+        let now = Utc::now();
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO backup_policy
+                (id, name, schedule, backup_type, keyset, keep_daily, keep_weekly, enabled,
+                 next_run_at_utc, created_at_utc, updated_at_utc)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10)
+            RETURNING {BACKUP_POLICY_COLUMNS}
+            "#
+        ))
+        .bind(new_v7())
+        .bind(&req.name)
+        .bind(req.schedule.trim())
+        .bind(&req.backup_type)
+        .bind(&req.keyset)
+        .bind(req.keep_daily)
+        .bind(req.keep_weekly)
+        .bind(req.enabled)
+        .bind(next_run_at)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| policy_insert_error(e, &req.name))?;
+
+        Ok(policy_from_row(&row))
+    }
+
+    /// Persist the mutable fields of `policy`.
+    ///
+    /// Returns `None` when the policy no longer exists.
+    pub async fn update(&self, policy: &BackupPolicy) -> Result<Option<BackupPolicy>> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE backup_policy
+            SET schedule = $2, backup_type = $3, keyset = $4, keep_daily = $5,
+                keep_weekly = $6, enabled = $7, next_run_at_utc = $8, updated_at_utc = NOW()
+            WHERE id = $1
+            RETURNING {BACKUP_POLICY_COLUMNS}
+            "#
+        ))
+        .bind(policy.id)
+        .bind(&policy.schedule)
+        .bind(&policy.backup_type)
+        .bind(&policy.keyset)
+        .bind(policy.keep_daily)
+        .bind(policy.keep_weekly)
+        .bind(policy.enabled)
+        .bind(policy.next_run_at_utc)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.as_ref().map(policy_from_row))
+    }
+
+    /// Delete a policy and its run history. Backup files are left in place.
+    pub async fn delete(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM backup_policy WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Enabled policies whose next run is at or before `now`.
+    pub async fn list_due(&self, now: DateTime<Utc>) -> Result<Vec<BackupPolicy>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {BACKUP_POLICY_COLUMNS} FROM backup_policy \
+             WHERE enabled AND next_run_at_utc <= $1 ORDER BY next_run_at_utc, id"
+        ))
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows.iter().map(policy_from_row).collect())
+    }
+
+    /// Advance a due policy to its next slot.
+    ///
+    /// Succeeds only if `next_run_at_utc` still equals `claimed`, so exactly
+    /// one scheduler queues each slot.
+    pub async fn claim_due(
+        &self,
+        id: Uuid,
+        claimed: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE backup_policy SET next_run_at_utc = $3 \
+             WHERE id = $1 AND next_run_at_utc = $2",
+        )
+        .bind(id)
+        .bind(claimed)
+        .bind(next_run_at)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the start of a run.
+    pub async fn start_run(&self, policy_id: Uuid, job_id: Option<Uuid>) -> Result<Uuid> {
+        let id = new_v7();
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        sqlx::query(
+            "INSERT INTO backup_policy_run (id, policy_id, job_id, status, started_at_utc) \
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(id)
+        .bind(policy_id)
+        .bind(job_id)
+        .bind(BACKUP_RUN_RUNNING)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::Database)?;
+        sqlx::query(
+            "UPDATE backup_policy SET last_run_at_utc = $2, last_status = $3 WHERE id = $1",
+        )
+        .bind(policy_id)
+        .bind(now)
+        .bind(BACKUP_RUN_RUNNING)
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::Database)?;
+        tx.commit().await.map_err(Error::Database)?;
+        Ok(id)
+    }
+
+    /// Record a successful run.
+    pub async fn finish_run(&self, run_id: Uuid, filename: &str, size_bytes: i64) -> Result<()> {
+        self.complete_run(
+            run_id,
+            BACKUP_RUN_SUCCEEDED,
+            Some(filename),
+            Some(size_bytes),
+            None,
+        )
+        .await
+    }
+
+    /// Record a failed run.
+    pub async fn fail_run(&self, run_id: Uuid, error: &str) -> Result<()> {
+        self.complete_run(run_id, BACKUP_RUN_FAILED, None, None, Some(error))
+            .await
+    }
+
+    async fn complete_run(
+        &self,
+        run_id: Uuid,
+        status: &str,
+        filename: Option<&str>,
+        size_bytes: Option<i64>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            WITH run AS (
+                UPDATE backup_policy_run
+                SET status = $2, filename = $3, size_bytes = $4, error = $5,
+                    finished_at_utc = NOW()
+                WHERE id = $1
+                RETURNING policy_id
+            )
+            UPDATE backup_policy p SET last_status = $2
+            FROM run WHERE p.id = run.policy_id
+            "#,
+        )
+        .bind(run_id)
+        .bind(status)
+        .bind(filename)
+        .bind(size_bytes)
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Recent runs of a policy, newest first.
+    pub async fn list_runs(&self, policy_id: Uuid, limit: i64) -> Result<Vec<BackupPolicyRun>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {BACKUP_POLICY_RUN_COLUMNS} FROM backup_policy_run \
+             WHERE policy_id = $1 ORDER BY started_at_utc DESC, id DESC LIMIT $2"
+        ))
+        .bind(policy_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows.iter().map(run_from_row).collect())
+    }
+
+    /// Successful runs whose backup file has not been pruned.
+    pub async fn list_retained_runs(&self, policy_id: Uuid) -> Result<Vec<BackupPolicyRun>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {BACKUP_POLICY_RUN_COLUMNS} FROM backup_policy_run \
+             WHERE policy_id = $1 AND status = $2 AND pruned_at_utc IS NULL \
+             ORDER BY started_at_utc DESC, id DESC"
+        ))
+        .bind(policy_id)
+        .bind(BACKUP_RUN_SUCCEEDED)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows.iter().map(run_from_row).collect())
+    }
+
+    /// Mark a run's backup file as deleted by retention.
+    pub async fn mark_pruned(&self, run_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE backup_policy_run SET pruned_at_utc = NOW() WHERE id = $1")
+            .bind(run_id)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+        Ok(())
+    }
+}
+
+fn policy_insert_error(e: sqlx::Error, name: &str) -> Error {
+    if let sqlx::Error::Database(ref db_err) = e {
+        if db_err.constraint() == Some("backup_policy_name_key") {
+            return Error::InvalidInput(format!(
+                "Backup policy already exists; name_len={}",
+                name.chars().count()
+            ));
+        }
+    }
+    Error::Database(e)
+}
+
+fn policy_from_row(row: &PgRow) -> BackupPolicy {
+    BackupPolicy {
+        id: row.get("id"),
+        name: row.get("name"),
+        schedule: row.get("schedule"),
+        backup_type: row.get("backup_type"),
+        keyset: row.get("keyset"),
+        keep_daily: row.get("keep_daily"),
+        keep_weekly: row.get("keep_weekly"),
+        enabled: row.get("enabled"),
+        next_run_at_utc: row.get("next_run_at_utc"),
+        last_run_at_utc: row.get("last_run_at_utc"),
+        last_status: row.get("last_status"),
+        created_at_utc: row.get("created_at_utc"),
+        updated_at_utc: row.get("updated_at_utc"),
+    }
+}
+
+fn run_from_row(row: &PgRow) -> BackupPolicyRun {
+    BackupPolicyRun {
+        id: row.get("id"),
+        policy_id: row.get("policy_id"),
+        job_id: row.get("job_id"),
+        status: row.get("status"),
+        filename: row.get("filename"),
+        size_bytes: row.get("size_bytes"),
+        error: row.get("error"),
+        started_at_utc: row.get("started_at_utc"),
+        finished_at_utc: row.get("finished_at_utc"),
+        pruned_at_utc: row.get("pruned_at_utc"),
+    }
+}
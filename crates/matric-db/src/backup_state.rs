@@ -0,0 +1,540 @@
+//! Content-hash state for incremental backups.
+//!
+//! Every backup records a [`BackupState`]: a SHA-256 digest per note (over
+//! the note row and the rows it owns) and the content hash of every
+//! attachment blob. Diffing the state of a parent backup against the current
+//! database yields the notes and blobs an incremental backup must carry.
+//!
+//! Note snapshots are exported as JSON rows and applied back with
+//! `jsonb_populate_recordset`, so the same code follows schema changes
+//! without per-column mapping. Exported snapshots also carry the note's
+//! links in both directions (outside the hash, since they change whenever a
+//! neighbour does); other derived data (embeddings, chunks) is not captured
+//! and is rebuilt by the NLP pipeline after a restore.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::{Error, Result};
+
+/// Tables holding one row per note, keyed by `note_id`.
+const NOTE_SINGLE_TABLES: [(&str, &str); 2] = [
+    ("original", "note_original"),
+    ("revised", "note_revised_current"),
+];
+
+/// Tables holding many rows per note, keyed by `note_id`.
+const NOTE_MANY_TABLES: [(&str, &str); 3] = [
+    ("revisions", "note_revision"),
+    ("tags", "note_tag"),
+    ("attachments", "attachment"),
+];
+
+/// Note columns that change on every read and are left out of the hash.
+const VOLATILE_NOTE_COLUMNS: &str = "'last_accessed_at', 'access_count'";
+
+/// Per-note and per-blob content hashes captured with a backup.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupState {
+    pub notes: BTreeMap<Uuid, String>,
+    pub blobs: BTreeMap<Uuid, String>,
+}
+
+/// Difference between two backup states.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupDelta {
+    /// Notes added or modified since the parent state.
+    pub changed_notes: Vec<Uuid>,
+    /// Notes present in the parent state but since removed.
+    pub deleted_notes: Vec<Uuid>,
+    /// Blobs added since the parent state. Blobs are content-addressed and
+    /// immutable, so only new ones need to be carried.
+    pub new_blobs: Vec<Uuid>,
+}
+
+impl BackupDelta {
+    pub fn is_empty(&self) -> bool {
+        self.changed_notes.is_empty() && self.deleted_notes.is_empty() && self.new_blobs.is_empty()
+    }
+}
+
+impl BackupState {
+    /// Compute what changed between this (parent) state and `current`.
+    pub fn diff(&self, current: &BackupState) -> BackupDelta {
+        BackupDelta {
+            changed_notes: current
+                .notes
+                .iter()
+                .filter(|(id, hash)| self.notes.get(id) != Some(hash))
+                .map(|(id, _)| *id)
+                .collect(),
+            deleted_notes: self
+                .notes
+                .keys()
+                .filter(|id| !current.notes.contains_key(id))
+                .copied()
+                .collect(),
+            new_blobs: current
+                .blobs
+                .keys()
+                .filter(|id| !self.blobs.contains_key(id))
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+/// Which backup in a chain holds the snapshot of `note_id` as of the last
+/// backup in `chain`.
+///
+/// `chain` runs from the full base to the newest incremental. A delta holds
+/// the note if its hash there differs from the parent's; otherwise the
+/// search continues towards the base. Returns `None` if the last backup
+/// does not contain the note.
+pub fn note_snapshot_link(chain: &[BackupState], note_id: Uuid) -> Option<usize> {
+    let last = chain.len().checked_sub(1)?;
+    let hash = chain[last].notes.get(&note_id)?;
+    Some(
+        (1..=last)
+            .rev()
+            .take_while(|&i| chain[i].notes.get(&note_id) == Some(hash))
+            .find(|&i| chain[i - 1].notes.get(&note_id) != Some(hash))
+            .unwrap_or(0),
+    )
+}
+
+/// Which backup in a chain carries the row of `blob_id` as of the last
+/// backup in `chain`: the first of the run of backups ending at the last
+/// one that all contain it. Returns `None` if the last backup does not.
+pub fn blob_link(chain: &[BackupState], blob_id: Uuid) -> Option<usize> {
+    let last = chain.len().checked_sub(1)?;
+    if !chain[last].blobs.contains_key(&blob_id) {
+        return None;
+    }
+    Some(
+        (0..last)
+            .rev()
+            .take_while(|&i| chain[i].blobs.contains_key(&blob_id))
+            .last()
+            .unwrap_or(last),
+    )
+}
+
+/// Snapshot JSON for one note: the note row plus the rows it owns.
+fn note_snapshot_sql(note_alias: &str, exclude: &str) -> String {
+    let mut fields = vec![format!(
+        "'note', to_jsonb({note_alias}.*) - ARRAY[{exclude}]::text[]"
+    )];
+    for (key, table) in NOTE_SINGLE_TABLES {
+        fields.push(format!(
+            "'{key}', (SELECT to_jsonb(t.*) FROM {table} t WHERE t.note_id = {note_alias}.id)"
+        ));
+    }
+    for (key, table) in NOTE_MANY_TABLES {
+        fields.push(format!(
+            "'{key}', COALESCE((SELECT jsonb_agg(to_jsonb(t.*) ORDER BY to_jsonb(t.*)::text) \
+             FROM {table} t WHERE t.note_id = {note_alias}.id), '[]'::jsonb)"
+        ));
+    }
+    format!("jsonb_build_object({})", fields.join(", "))
+}
+
+/// Snapshots of the notes in `$1`, each with its links in both directions.
+fn export_notes_sql() -> String {
+    format!(
+        "SELECT {} || jsonb_build_object('links', COALESCE((SELECT jsonb_agg(to_jsonb(l.*)) \
+         FROM link l WHERE l.from_note_id = n.id OR l.to_note_id = n.id), '[]'::jsonb)) \
+         FROM note n WHERE n.id = ANY($1) ORDER BY n.id",
+        note_snapshot_sql("n", "")
+    )
+}
+
+/// PostgreSQL implementation of incremental backup state queries.
+pub struct PgBackupStateRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgBackupStateRepository {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Capture the content-hash state of every note and blob.
+    pub async fn current_state(&self) -> Result<BackupState> {
+        let notes: Vec<(Uuid, String)> = sqlx::query_as(&format!(
+            "SELECT n.id, encode(sha256(convert_to({}::text, 'UTF8')), 'hex') FROM note n",
+            note_snapshot_sql("n", VOLATILE_NOTE_COLUMNS)
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        let blobs: Vec<(Uuid, String)> =
+            sqlx::query_as("SELECT id, content_hash FROM attachment_blob")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(Error::Database)?;
+
+        Ok(BackupState {
+            notes: notes.into_iter().collect(),
+            blobs: blobs.into_iter().collect(),
+        })
+    }
+
+    /// Export full snapshots of the given notes, including their links.
+    pub async fn export_notes(&self, note_ids: &[Uuid]) -> Result<Vec<serde_json::Value>> {
+        sqlx::query_scalar(&export_notes_sql())
+            .bind(note_ids)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Error::Database)
+    }
+
+    /// [`Self::export_notes`] inside a (possibly schema-scoped) transaction.
+    pub async fn export_notes_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_ids: &[Uuid],
+    ) -> Result<Vec<serde_json::Value>> {
+        sqlx::query_scalar(&export_notes_sql())
+            .bind(note_ids)
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(Error::Database)
+    }
+
+    /// Whether a note row exists (including soft-deleted notes).
+    pub async fn note_exists(&self, note_id: Uuid) -> Result<bool> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM note WHERE id = $1)")
+            .bind(note_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Error::Database)
+    }
+
+    /// The subset of `blob_ids` present in `attachment_blob`.
+    pub async fn existing_blob_ids(&self, blob_ids: &[Uuid]) -> Result<Vec<Uuid>> {
+        sqlx::query_scalar("SELECT id FROM attachment_blob WHERE id = ANY($1)")
+            .bind(blob_ids)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Error::Database)
+    }
+
+    /// Export attachment blob rows (including inline data).
+    pub async fn export_blobs(&self, blob_ids: &[Uuid]) -> Result<Vec<serde_json::Value>> {
+        sqlx::query_scalar(
+            "SELECT to_jsonb(b.*) FROM attachment_blob b WHERE b.id = ANY($1) ORDER BY b.id",
+        )
+        .bind(blob_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Export every collection row; notes reference them by ID.
+    pub async fn export_collections(&self) -> Result<Vec<serde_json::Value>> {
+        sqlx::query_scalar("SELECT to_jsonb(c.*) FROM collection c ORDER BY c.id")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Error::Database)
+    }
+
+    /// Upsert collection rows exported by [`Self::export_collections`].
+    pub async fn apply_collections_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        rows: &[serde_json::Value],
+    ) -> Result<()> {
+        insert_json_rows_tx(tx, "collection", rows, Conflict::Update).await
+    }
+
+    /// Insert collection rows that are not already present, leaving
+    /// existing collections as they are.
+    pub async fn insert_missing_collections_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        rows: &[serde_json::Value],
+    ) -> Result<()> {
+        insert_json_rows_tx(tx, "collection", rows, Conflict::Skip).await
+    }
+
+    /// Insert blob rows that are not already present.
+    ///
+    /// Reference counts restart at zero; the attachment triggers recount
+    /// them as note snapshots are applied.
+    pub async fn apply_blobs_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        rows: &[serde_json::Value],
+    ) -> Result<()> {
+        let rows: Vec<serde_json::Value> = rows
+            .iter()
+            .cloned()
+            .map(|mut row| {
+                if let Some(object) = row.as_object_mut() {
+                    object.insert("reference_count".to_string(), 0.into());
+                }
+                row
+            })
+            .collect();
+        insert_json_rows_tx(tx, "attachment_blob", &rows, Conflict::Skip).await
+    }
+
+    /// Delete notes removed since the parent backup.
+    pub async fn delete_notes_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_ids: &[Uuid],
+    ) -> Result<u64> {
+        sqlx::query("DELETE FROM note WHERE id = ANY($1)")
+            .bind(note_ids)
+            .execute(&mut **tx)
+            .await
+            .map(|result| result.rows_affected())
+            .map_err(Error::Database)
+    }
+
+    /// Replace a note with a snapshot exported by [`Self::export_notes`].
+    ///
+    /// The existing note is deleted first, which also drops its derived
+    /// rows. Links are restored where the note at the other end exists.
+    /// Returns the note ID so callers can requeue processing.
+    pub async fn apply_note_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        snapshot: &serde_json::Value,
+    ) -> Result<Uuid> {
+        let invalid = || Error::InvalidInput("invalid note snapshot".to_string());
+        let note = snapshot.get("note").ok_or_else(invalid)?;
+        let note_id = note
+            .get("id")
+            .and_then(|id| id.as_str())
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(invalid)?;
+
+        sqlx::query("DELETE FROM note WHERE id = $1")
+            .bind(note_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        insert_json_rows_tx(tx, "note", std::slice::from_ref(note), Conflict::Fail).await?;
+
+        for (key, table) in NOTE_SINGLE_TABLES {
+            if let Some(row) = snapshot.get(key).filter(|row| !row.is_null()) {
+                insert_json_rows_tx(tx, table, std::slice::from_ref(row), Conflict::Fail).await?;
+            }
+        }
+
+        let tags = snapshot
+            .get("tags")
+            .and_then(|rows| rows.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        // note_tag references tag(name); recreate tags removed since.
+        sqlx::query(
+            r#"INSERT INTO tag (name, created_at_utc)
+               SELECT DISTINCT r->>'tag_name', NOW()
+               FROM jsonb_array_elements($1) r
+               ON CONFLICT (name) DO NOTHING"#,
+        )
+        .bind(serde_json::Value::from(tags.to_vec()))
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        for (key, table) in NOTE_MANY_TABLES {
+            if let Some(rows) = snapshot.get(key).and_then(|rows| rows.as_array()) {
+                insert_json_rows_tx(tx, table, rows, Conflict::Fail).await?;
+            }
+        }
+
+        if let Some(links) = snapshot.get("links").and_then(|rows| rows.as_array()) {
+            let endpoint = |row: &serde_json::Value, key: &str| {
+                row.get(key)
+                    .and_then(|id| id.as_str())
+                    .and_then(|id| Uuid::parse_str(id).ok())
+            };
+            let endpoints: Vec<Uuid> = links
+                .iter()
+                .flat_map(|row| [endpoint(row, "from_note_id"), endpoint(row, "to_note_id")])
+                .flatten()
+                .collect();
+            let existing: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM note WHERE id = ANY($1)")
+                .bind(&endpoints)
+                .fetch_all(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+            // Links to notes that no longer exist are dropped.
+            let links: Vec<serde_json::Value> = links
+                .iter()
+                .filter(|row| {
+                    ["from_note_id", "to_note_id"].iter().all(|key| {
+                        row.get(*key).is_none_or(serde_json::Value::is_null)
+                            || endpoint(row, key).is_some_and(|id| existing.contains(&id))
+                    })
+                })
+                .cloned()
+                .collect();
+            insert_json_rows_tx(tx, "link", &links, Conflict::Skip).await?;
+        }
+
+        Ok(note_id)
+    }
+
+    /// Begin a transaction for applying an incremental backup.
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>> {
+        self.pool.begin().await.map_err(Error::Database)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Conflict {
+    Fail,
+    Skip,
+    Update,
+}
+
+/// Insert JSON rows into `table`, skipping generated columns.
+///
+/// `table` is always one of this module's constants, never user input.
+async fn insert_json_rows_tx(
+    tx: &mut Transaction<'_, Postgres>,
+    table: &str,
+    rows: &[serde_json::Value],
+    conflict: Conflict,
+) -> Result<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let columns: Vec<String> = sqlx::query_scalar(
+        r#"SELECT quote_ident(column_name::text)
+           FROM information_schema.columns
+           WHERE table_schema = current_schema()
+             AND table_name = $1
+             AND is_generated = 'NEVER'
+             AND COALESCE(identity_generation, '') <> 'ALWAYS'
+           ORDER BY ordinal_position"#,
+    )
+    .bind(table)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+    let column_list = columns.join(", ");
+
+    let on_conflict = match conflict {
+        Conflict::Fail => String::new(),
+        Conflict::Skip => " ON CONFLICT DO NOTHING".to_string(),
+        Conflict::Update => format!(
+            " ON CONFLICT (id) DO UPDATE SET {}",
+            columns
+                .iter()
+                .map(|column| format!("{column} = EXCLUDED.{column}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+
+    sqlx::query(&format!(
+        "INSERT INTO {table} ({column_list}) \
+         SELECT {column_list} FROM jsonb_populate_recordset(NULL::{table}, $1){on_conflict}"
+    ))
+    .bind(serde_json::Value::from(rows.to_vec()))
+    .execute(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn state(notes: &[(u128, &str)], blobs: &[(u128, &str)]) -> BackupState {
+        BackupState {
+            notes: notes
+                .iter()
+                .map(|(n, hash)| (id(*n), hash.to_string()))
+                .collect(),
+            blobs: blobs
+                .iter()
+                .map(|(n, hash)| (id(*n), hash.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn diff_reports_changed_deleted_and_new_blobs() {
+        let parent = state(&[(1, "a"), (2, "b"), (3, "c")], &[(10, "x")]);
+        let current = state(&[(1, "a"), (2, "B"), (4, "d")], &[(10, "x"), (11, "y")]);
+
+        let delta = parent.diff(&current);
+        assert_eq!(delta.changed_notes, [id(2), id(4)]);
+        assert_eq!(delta.deleted_notes, [id(3)]);
+        assert_eq!(delta.new_blobs, [id(11)]);
+        assert!(!delta.is_empty());
+    }
+
+    #[test]
+    fn diff_of_identical_states_is_empty() {
+        let parent = state(&[(1, "a")], &[(10, "x")]);
+        assert!(parent.diff(&parent.clone()).is_empty());
+        assert!(BackupState::default()
+            .diff(&BackupState::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn note_snapshot_link_finds_last_change() {
+        let chain = [
+            state(&[(1, "a"), (2, "b")], &[]),
+            state(&[(1, "a"), (2, "B")], &[]),
+            state(&[(1, "a"), (2, "B"), (3, "c")], &[]),
+            state(&[(1, "a"), (2, "B"), (3, "c")], &[]),
+        ];
+        assert_eq!(note_snapshot_link(&chain, id(1)), Some(0));
+        assert_eq!(note_snapshot_link(&chain, id(2)), Some(1));
+        assert_eq!(note_snapshot_link(&chain, id(3)), Some(2));
+        assert_eq!(note_snapshot_link(&chain, id(4)), None);
+        assert_eq!(note_snapshot_link(&chain[..1], id(2)), Some(0));
+
+        // Deleted and re-created: the delta that re-added it holds it.
+        let chain = [
+            state(&[(1, "a")], &[]),
+            state(&[], &[]),
+            state(&[(1, "a")], &[]),
+        ];
+        assert_eq!(note_snapshot_link(&chain, id(1)), Some(2));
+        assert_eq!(note_snapshot_link(&[], id(1)), None);
+    }
+
+    #[test]
+    fn blob_link_finds_backup_that_added_it() {
+        let chain = [
+            state(&[], &[(10, "x")]),
+            state(&[], &[(10, "x"), (11, "y")]),
+            state(&[], &[(10, "x"), (11, "y"), (12, "z")]),
+        ];
+        assert_eq!(blob_link(&chain, id(10)), Some(0));
+        assert_eq!(blob_link(&chain, id(11)), Some(1));
+        assert_eq!(blob_link(&chain, id(12)), Some(2));
+        assert_eq!(blob_link(&chain, id(13)), None);
+    }
+
+    #[test]
+    fn snapshot_sql_covers_owned_tables() {
+        let sql = note_snapshot_sql("n", VOLATILE_NOTE_COLUMNS);
+        for (_, table) in NOTE_SINGLE_TABLES.iter().chain(NOTE_MANY_TABLES.iter()) {
+            assert!(sql.contains(table), "missing {table}");
+        }
+        assert!(sql.contains("'last_accessed_at'"));
+    }
+}
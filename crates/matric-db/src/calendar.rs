@@ -0,0 +1,123 @@
+//! Calendar feed repository.
+//!
+//! Reads the dated items of an archive for `GET /api/v1/calendar.ics`.
+//! Every method takes a transaction that has already been pointed at the
+//! archive schema, and only sees live notes. Days are UTC calendar days.
+//! `security`, when given, admits only items of the notes it allows.
+
+use sqlx::{Pool, Postgres, Transaction};
+
+use matric_core::calendar::{CalendarWindow, DueTask, NoteCreation, ReviewDay};
+use matric_core::{Error, Result, StrictSecurityFilter};
+
+use crate::unified_filter::{
+    bind_filter_param, security_clause, security_filter_query, QueryParam,
+};
+
+/// PostgreSQL repository for calendar feeds.
+#[derive(Clone)]
+pub struct PgCalendarRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgCalendarRepository {
+    /// Create a new calendar repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Review cards due per day up to the end of the window. Cards due before
+    /// the window count towards its first day.
+    pub async fn review_days_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        window: &CalendarWindow,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Vec<ReviewDay>> {
+        let security = security_filter_query(security, 3);
+        let sql = format!(
+            "SELECT GREATEST((r.due_at_utc AT TIME ZONE 'UTC')::date, $1) AS day,
+                    COUNT(*) AS cards
+             FROM review_card r
+             JOIN note n ON n.id = r.note_id AND n.deleted_at IS NULL
+             WHERE (r.due_at_utc AT TIME ZONE 'UTC')::date <= $2
+               AND ($3::uuid IS NULL OR n.collection_id = $3)
+               {}
+             GROUP BY 1
+             ORDER BY 1",
+            security_clause(&security)
+        );
+        let mut q = sqlx::query_as::<_, ReviewDay>(&sql)
+            .bind(window.from)
+            .bind(window.until)
+            .bind(window.collection_id);
+        for param in security.iter().flat_map(|result| &result.params) {
+            q = bind_filter_param!(q, param);
+        }
+        q.fetch_all(&mut **tx).await.map_err(Error::Database)
+    }
+
+    /// Open tasks due within the window, soonest first.
+    pub async fn due_tasks_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        window: &CalendarWindow,
+        security: Option<&StrictSecurityFilter>,
+        limit: i64,
+    ) -> Result<Vec<DueTask>> {
+        let security = security_filter_query(security, 4);
+        let sql = format!(
+            "SELECT t.id, t.note_id, n.title AS note_title, t.text, t.due_date
+             FROM task t
+             JOIN note n ON n.id = t.note_id AND n.deleted_at IS NULL
+             WHERE t.status = 'open'
+               AND t.due_date BETWEEN $1 AND $2
+               AND ($3::uuid IS NULL OR n.collection_id = $3)
+               {}
+             ORDER BY t.due_date, t.created_at_utc, t.id
+             LIMIT $4",
+            security_clause(&security)
+        );
+        let mut q = sqlx::query_as::<_, DueTask>(&sql)
+            .bind(window.from)
+            .bind(window.until)
+            .bind(window.collection_id)
+            .bind(limit);
+        for param in security.iter().flat_map(|result| &result.params) {
+            q = bind_filter_param!(q, param);
+        }
+        q.fetch_all(&mut **tx).await.map_err(Error::Database)
+    }
+
+    /// Notes created within the window, newest first.
+    pub async fn note_creations_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        window: &CalendarWindow,
+        security: Option<&StrictSecurityFilter>,
+        limit: i64,
+    ) -> Result<Vec<NoteCreation>> {
+        let security = security_filter_query(security, 4);
+        let sql = format!(
+            "SELECT n.id AS note_id, n.title, n.created_at_utc
+             FROM note n
+             WHERE n.deleted_at IS NULL
+               AND (n.created_at_utc AT TIME ZONE 'UTC')::date BETWEEN $1 AND $2
+               AND ($3::uuid IS NULL OR n.collection_id = $3)
+               {}
+             ORDER BY n.created_at_utc DESC, n.id
+             LIMIT $4",
+            security_clause(&security)
+        );
+        let mut q = sqlx::query_as::<_, NoteCreation>(&sql)
+            .bind(window.from)
+            .bind(window.until)
+            .bind(window.collection_id)
+            .bind(limit);
+        for param in security.iter().flat_map(|result| &result.params) {
+            q = bind_filter_param!(q, param);
+        }
+        q.fetch_all(&mut **tx).await.map_err(Error::Database)
+    }
+}
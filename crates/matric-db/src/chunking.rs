@@ -776,6 +776,31 @@ impl Chunker for RecursiveChunker {
     }
 }
 
+/// Chunker for a document type's chunking strategy.
+///
+/// Strategies that need a parser (syntactic, per-unit, hybrid) use the
+/// semantic chunker, which already keeps code blocks intact. `Whole` keeps
+/// the document as one chunk.
+pub fn chunker_for_strategy(
+    strategy: matric_core::ChunkingStrategy,
+    config: ChunkerConfig,
+) -> Box<dyn Chunker> {
+    use matric_core::ChunkingStrategy;
+    match strategy {
+        ChunkingStrategy::Fixed => Box::new(SlidingWindowChunker::new(config)),
+        ChunkingStrategy::PerSection => Box::new(ParagraphChunker::new(config)),
+        ChunkingStrategy::Whole => Box::new(SlidingWindowChunker::new(ChunkerConfig {
+            max_chunk_size: usize::MAX,
+            overlap: 0,
+            ..config
+        })),
+        ChunkingStrategy::Semantic
+        | ChunkingStrategy::Syntactic
+        | ChunkingStrategy::Hybrid
+        | ChunkingStrategy::PerUnit => Box::new(SemanticChunker::new(config)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1239,6 +1264,26 @@ mod tests {
     // SlidingWindowChunker tests
     // ============================================================================
 
+    #[test]
+    fn test_chunker_for_strategy() {
+        use matric_core::ChunkingStrategy;
+
+        let text = "First paragraph. ".repeat(10) + "\n\n" + &"Second one. ".repeat(10);
+        let whole = chunker_for_strategy(ChunkingStrategy::Whole, default_config());
+        assert_eq!(whole.chunk(&text).len(), 1);
+
+        let fixed = chunker_for_strategy(ChunkingStrategy::Fixed, default_config());
+        let chunks = fixed.chunk(&text);
+        assert!(chunks.len() > 2);
+        assert_eq!(
+            chunks[0].metadata.get("type").map(String::as_str),
+            Some("window")
+        );
+
+        let section = chunker_for_strategy(ChunkingStrategy::PerSection, default_config());
+        assert_eq!(section.config().max_chunk_size, 100);
+    }
+
     #[test]
     fn test_sliding_window_empty_text() {
         let chunker = SlidingWindowChunker::new(default_config());
@@ -5,6 +5,7 @@
 
 use pgvector::Vector;
 use sqlx::{Pool, Postgres, Row};
+use tracing::instrument;
 use uuid::Uuid;
 
 use matric_core::{Error, Result};
@@ -113,6 +114,14 @@ impl ColBERTRepository {
     /// Retrieve token embeddings for a note.
     ///
     /// Returns tokens ordered by position.
+    #[instrument(
+        skip_all,
+        fields(
+            subsystem = "database",
+            component = "colbert",
+            op = "get_token_embeddings"
+        )
+    )]
     pub async fn get_token_embeddings(&self, note_id: Uuid) -> Result<Vec<TokenEmbedding>> {
         let rows = sqlx::query(
             r#"
@@ -0,0 +1,237 @@
+//! Smart collection rule repository.
+//!
+//! Rules and their members are archive-scoped; every method takes a
+//! transaction that has already been pointed at the archive schema.
+//! [`PgCollectionRuleRepository::sync_tx`] files matching notes that are in
+//! no collection and records them as rule members, so only notes the rule
+//! filed are ever taken back out.
+
+use chrono::Utc;
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::{CollectionRule, CollectionRuleFilter, CollectionRuleSyncResult, Error, Result};
+
+/// Columns of [`CollectionRule`], for queries aliasing `collection_rule AS r`.
+const RULE_COLUMNS: &str = "r.collection_id, r.filter, r.enabled, \
+     (SELECT COUNT(*) FROM collection_rule_member m WHERE m.collection_id = r.collection_id) AS member_count, \
+     r.last_synced_at_utc, r.created_at_utc, r.updated_at_utc";
+
+/// PostgreSQL repository for smart collection rules.
+#[derive(Clone)]
+pub struct PgCollectionRuleRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgCollectionRuleRepository {
+    /// Create a new collection rule repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// The collection's rule, if it has one.
+    pub async fn get_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        collection_id: Uuid,
+    ) -> Result<Option<CollectionRule>> {
+        sqlx::query_as::<_, CollectionRule>(&format!(
+            "SELECT {RULE_COLUMNS} FROM collection_rule r WHERE r.collection_id = $1"
+        ))
+        .bind(collection_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Rules the periodic sync runs, oldest sync first.
+    pub async fn list_enabled_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<CollectionRule>> {
+        sqlx::query_as::<_, CollectionRule>(&format!(
+            "SELECT {RULE_COLUMNS} FROM collection_rule r
+             WHERE r.enabled
+             ORDER BY r.last_synced_at_utc NULLS FIRST, r.collection_id"
+        ))
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Give the collection a rule, replacing any it had. Notes the old rule
+    /// filed stay members until the next sync re-checks them.
+    pub async fn set_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        collection_id: Uuid,
+        filter: &CollectionRuleFilter,
+        enabled: bool,
+    ) -> Result<CollectionRule> {
+        let body = serde_json::to_value(filter).map_err(|e| Error::Internal(e.to_string()))?;
+        let now = Utc::now();
+        let stored = sqlx::query(
+            "INSERT INTO collection_rule (collection_id, filter, enabled, created_at_utc, updated_at_utc)
+             SELECT c.id, $2, $3, $4, $4 FROM collection c WHERE c.id = $1
+             ON CONFLICT (collection_id) DO UPDATE
+             SET filter = EXCLUDED.filter, enabled = EXCLUDED.enabled,
+                 updated_at_utc = EXCLUDED.updated_at_utc",
+        )
+        .bind(collection_id)
+        .bind(body)
+        .bind(enabled)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        if stored.rows_affected() == 0 {
+            return Err(Error::NotFound("Collection not found".to_string()));
+        }
+        self.get_tx(tx, collection_id)
+            .await?
+            .ok_or_else(|| Error::Internal("collection rule vanished after upsert".to_string()))
+    }
+
+    /// Remove the collection's rule. Notes it filed stay in the collection.
+    /// Returns whether the collection had a rule.
+    pub async fn delete_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        collection_id: Uuid,
+    ) -> Result<bool> {
+        let deleted = sqlx::query("DELETE FROM collection_rule WHERE collection_id = $1")
+            .bind(collection_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    /// IDs of the live notes matching `filter`.
+    pub async fn matching_note_ids_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        filter: &CollectionRuleFilter,
+    ) -> Result<Vec<Uuid>> {
+        sqlx::query_scalar(
+            "SELECT n.id FROM note n
+             WHERE n.deleted_at IS NULL
+               AND NOT EXISTS (
+                   SELECT 1 FROM unnest($1::text[]) AS t(tag)
+                   WHERE NOT EXISTS (
+                       SELECT 1 FROM note_tag nt
+                       WHERE nt.note_id = n.id
+                         AND (LOWER(nt.tag_name) = LOWER(t.tag)
+                              OR LOWER(nt.tag_name) LIKE LOWER(t.tag) || '/%' ESCAPE '\\')))
+               AND ($2::text IS NULL OR EXISTS (
+                   SELECT 1 FROM document_type dt
+                   WHERE dt.id = n.document_type_id AND dt.name = $2))
+               AND ($3::timestamptz IS NULL OR n.created_at_utc >= $3)
+               AND ($4::timestamptz IS NULL OR n.created_at_utc < $4)
+               AND ($5::text IS NULL
+                    OR to_tsvector('public.matric_english', COALESCE(n.title, ''))
+                       @@ websearch_to_tsquery('public.matric_english', $5)
+                    OR EXISTS (
+                        SELECT 1 FROM note_revised_current nrc
+                        WHERE nrc.note_id = n.id
+                          AND nrc.tsv @@ websearch_to_tsquery('public.matric_english', $5)))
+             ORDER BY n.id",
+        )
+        .bind(&filter.tags)
+        .bind(filter.document_type.as_deref())
+        .bind(filter.created_after)
+        .bind(filter.created_before)
+        .bind(filter.query.as_deref())
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Bring the collection's membership in line with its rule.
+    ///
+    /// Matching notes in no collection are filed and recorded as members;
+    /// members that no longer match are taken back out. Members moved
+    /// elsewhere by hand are forgotten without moving them.
+    pub async fn sync_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        collection_id: Uuid,
+    ) -> Result<CollectionRuleSyncResult> {
+        let rule = self
+            .get_tx(tx, collection_id)
+            .await?
+            .ok_or_else(|| Error::NotFound("Collection rule not found".to_string()))?;
+        let matching = self.matching_note_ids_tx(tx, &rule.filter).await?;
+        let now = Utc::now();
+
+        sqlx::query(
+            "DELETE FROM collection_rule_member m USING note n
+             WHERE m.collection_id = $1 AND n.id = m.note_id
+               AND n.collection_id IS DISTINCT FROM $1",
+        )
+        .bind(collection_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let removed = sqlx::query(
+            "WITH stale AS (
+                 DELETE FROM collection_rule_member
+                 WHERE collection_id = $1 AND NOT (note_id = ANY($2))
+                 RETURNING note_id
+             )
+             UPDATE note SET collection_id = NULL, updated_at_utc = $3
+             WHERE id IN (SELECT note_id FROM stale) AND collection_id = $1",
+        )
+        .bind(collection_id)
+        .bind(&matching)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .rows_affected();
+
+        let added = sqlx::query(
+            "WITH filed AS (
+                 UPDATE note SET collection_id = $1, updated_at_utc = $3
+                 WHERE id = ANY($2) AND collection_id IS NULL
+                 RETURNING id
+             )
+             INSERT INTO collection_rule_member (collection_id, note_id, added_at_utc)
+             SELECT $1, id, $3 FROM filed
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(collection_id)
+        .bind(&matching)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .rows_affected();
+
+        let skipped: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM note
+             WHERE id = ANY($2) AND collection_id IS NOT NULL AND collection_id <> $1",
+        )
+        .bind(collection_id)
+        .bind(&matching)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        sqlx::query("UPDATE collection_rule SET last_synced_at_utc = $2 WHERE collection_id = $1")
+            .bind(collection_id)
+            .bind(now)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+
+        Ok(CollectionRuleSyncResult {
+            matched: matching.len() as u64,
+            added,
+            removed,
+            skipped: skipped as u64,
+        })
+    }
+}
@@ -1,11 +1,21 @@
 //! Collection repository implementation.
 
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use chrono::Utc;
 use sqlx::{Pool, Postgres, Row, Transaction};
 use uuid::Uuid;
 
-use matric_core::{new_v7, Collection, CollectionRepository, Error, NoteSummary, Result};
+use crate::unified_filter::{QueryParam, UnifiedFilterQueryBuilder};
+use crate::visibility::visible_note_ids;
+use matric_core::publishing::UNTITLED_PAGE;
+use matric_core::site_export::{SiteExportNote, MAX_SITE_EXPORT_NOTES};
+use matric_core::{
+    collection_copy_name, new_v7, Collection, CollectionCopyMode, CollectionCopyResult,
+    CollectionReparentResult, CollectionRepository, Error, NoteSummary, Result, StrictFilter,
+    StrictSecurityFilter, COPIED_FROM_LINK_KIND, MAX_COLLECTION_COPY_NOTES,
+};
 
 /// PostgreSQL implementation of CollectionRepository.
 pub struct PgCollectionRepository {
@@ -321,34 +331,39 @@ impl PgCollectionRepository {
         tx: &mut Transaction<'_, Postgres>,
         parent_id: Option<Uuid>,
     ) -> Result<Vec<Collection>> {
-        let rows = if let Some(pid) = parent_id {
-            sqlx::query(
-                r#"
-                SELECT c.id, c.name, c.description, c.parent_id, c.created_at_utc,
-                       COALESCE((SELECT COUNT(*) FROM note WHERE collection_id = c.id), 0) as note_count
-                FROM collection c
-                WHERE c.parent_id = $1
-                ORDER BY c.name
-                "#,
-            )
-            .bind(pid)
-            .fetch_all(&mut **tx)
-            .await
-            .map_err(Error::Database)?
-        } else {
-            sqlx::query(
-                r#"
-                SELECT c.id, c.name, c.description, c.parent_id, c.created_at_utc,
-                       COALESCE((SELECT COUNT(*) FROM note WHERE collection_id = c.id), 0) as note_count
-                FROM collection c
-                WHERE c.parent_id IS NULL
-                ORDER BY c.name
-                "#,
-            )
-            .fetch_all(&mut **tx)
-            .await
-            .map_err(Error::Database)?
-        };
+        self.list_visible_tx(tx, parent_id, None).await
+    }
+
+    /// List the collections a user can see within an existing transaction:
+    /// unowned ones, their own, and those shared with them. `None` lists
+    /// every collection, like [`Self::list_tx`].
+    pub async fn list_visible_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        parent_id: Option<Uuid>,
+        user_id: Option<Uuid>,
+    ) -> Result<Vec<Collection>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT c.id, c.name, c.description, c.parent_id, c.created_at_utc,
+                   COALESCE((SELECT COUNT(*) FROM note WHERE collection_id = c.id), 0) as note_count
+            FROM collection c
+            WHERE c.parent_id IS NOT DISTINCT FROM $2
+              AND ($1::uuid IS NULL OR c.owner_id IS NULL OR c.owner_id = $1
+                   OR EXISTS (
+                       SELECT 1 FROM collection_share_grant csg
+                       WHERE csg.collection_id = c.id AND csg.grantee_id = $1
+                         AND csg.revoked_at IS NULL
+                         AND (csg.expires_at IS NULL OR csg.expires_at > NOW())
+                   ))
+            ORDER BY c.name
+            "#,
+        )
+        .bind(user_id)
+        .bind(parent_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
 
         Ok(rows
             .into_iter()
@@ -479,6 +494,9 @@ impl PgCollectionRepository {
         id: Uuid,
         new_parent_id: Option<Uuid>,
     ) -> Result<()> {
+        if !self.exists_tx(tx, id).await? {
+            return Err(Error::NotFound("Collection not found".to_string()));
+        }
         // Moving to root (no parent) is always safe
         if let Some(parent_id) = new_parent_id {
             // Cannot move a collection to be its own parent
@@ -487,30 +505,10 @@ impl PgCollectionRepository {
                     "Cannot move a collection to be its own parent".to_string(),
                 ));
             }
-
-            // Check for circular reference: walk up from new_parent_id
-            // and ensure we never encounter `id` (which would mean
-            // new_parent_id is a descendant of the collection being moved).
-            let row = sqlx::query(
-                r#"
-                WITH RECURSIVE ancestors AS (
-                    SELECT id, parent_id FROM collection WHERE id = $1
-                    UNION ALL
-                    SELECT c.id, c.parent_id
-                    FROM collection c
-                    INNER JOIN ancestors a ON c.id = a.parent_id
-                )
-                SELECT COUNT(*) as cnt FROM ancestors WHERE id = $2
-                "#,
-            )
-            .bind(parent_id)
-            .bind(id)
-            .fetch_one(&mut **tx)
-            .await
-            .map_err(Error::Database)?;
-
-            let count: i64 = row.get("cnt");
-            if count > 0 {
+            if !self.exists_tx(tx, parent_id).await? {
+                return Err(Error::NotFound("Parent collection not found".to_string()));
+            }
+            if self.is_within_subtree_tx(tx, parent_id, id).await? {
                 return Err(Error::InvalidInput(
                     "Cannot move collection: would create a circular reference".to_string(),
                 ));
@@ -527,6 +525,446 @@ impl PgCollectionRepository {
         Ok(())
     }
 
+    /// Move several collections, with their descendants, under one parent
+    /// within an existing transaction. Either every collection moves or
+    /// none does. Returns the moved collections alongside the result.
+    pub async fn reparent_collections_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        ids: &[Uuid],
+        new_parent_id: Option<Uuid>,
+    ) -> Result<(CollectionReparentResult, Vec<Collection>)> {
+        let mut moved = Vec::new();
+        let mut unchanged = 0;
+        for &id in ids {
+            let mut collection = self
+                .get_tx(tx, id)
+                .await?
+                .ok_or_else(|| Error::NotFound("Collection not found".to_string()))?;
+            if collection.parent_id == new_parent_id {
+                unchanged += 1;
+                continue;
+            }
+            self.move_collection_tx(tx, id, new_parent_id).await?;
+            collection.parent_id = new_parent_id;
+            moved.push(collection);
+        }
+        let result = CollectionReparentResult {
+            moved: moved.len() as u64,
+            unchanged,
+        };
+        Ok((result, moved))
+    }
+
+    /// Copy a collection, its descendants and their live notes under
+    /// `new_parent_id` within an existing transaction.
+    ///
+    /// The top copy is called `name`, or gets a free `"<name> (copy)"` name
+    /// like every copied descendant. New collections and notes belong to
+    /// `owner_id` when given and keep their source's owner otherwise.
+    /// Returns the IDs of the note copies alongside the result.
+    pub async fn copy_subtree_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        new_parent_id: Option<Uuid>,
+        mode: CollectionCopyMode,
+        name: Option<&str>,
+        owner_id: Option<Uuid>,
+    ) -> Result<(CollectionCopyResult, Vec<Uuid>)> {
+        if !self.exists_tx(tx, id).await? {
+            return Err(Error::NotFound("Collection not found".to_string()));
+        }
+        if let Some(parent_id) = new_parent_id {
+            if !self.exists_tx(tx, parent_id).await? {
+                return Err(Error::NotFound("Parent collection not found".to_string()));
+            }
+            if self.is_within_subtree_tx(tx, parent_id, id).await? {
+                return Err(Error::InvalidInput(
+                    "Cannot copy a collection into its own subtree".to_string(),
+                ));
+            }
+        }
+
+        let rows = sqlx::query(
+            r#"
+            WITH RECURSIVE subtree AS (
+                SELECT id, parent_id, name FROM collection WHERE id = $1
+                UNION
+                SELECT c.id, c.parent_id, c.name
+                FROM collection c
+                INNER JOIN subtree s ON c.parent_id = s.id
+            )
+            SELECT id, parent_id, name FROM subtree
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        let subtree: Vec<(Uuid, Option<Uuid>, String)> = rows
+            .into_iter()
+            .map(|r| (r.get("id"), r.get("parent_id"), r.get("name")))
+            .collect();
+        let source_ids: Vec<Uuid> = subtree.iter().map(|(id, _, _)| *id).collect();
+
+        let note_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM note WHERE collection_id = ANY($1) AND deleted_at IS NULL",
+        )
+        .bind(&source_ids)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        if note_count > MAX_COLLECTION_COPY_NOTES {
+            return Err(Error::InvalidInput(format!(
+                "Cannot copy more than {MAX_COLLECTION_COPY_NOTES} notes at once"
+            )));
+        }
+
+        let now = Utc::now();
+        let top_name = match name {
+            Some(name) => {
+                if self.name_taken_tx(tx, name).await? {
+                    return Err(Error::InvalidInput(
+                        "A collection with that name already exists".to_string(),
+                    ));
+                }
+                name.to_string()
+            }
+            None => self.free_copy_name_tx(tx, &subtree[0].2).await?,
+        };
+
+        // Parents are copied before their children, so every copy's parent
+        // already has its new ID.
+        let mut copies: HashMap<Uuid, Uuid> = HashMap::new();
+        let mut pending: Vec<(Uuid, Option<Uuid>, String)> = vec![(id, new_parent_id, top_name)];
+        while let Some((source_id, parent_id, copy_name)) = pending.pop() {
+            let copy_id = new_v7();
+            sqlx::query(
+                "INSERT INTO collection (id, name, description, parent_id, created_at_utc, owner_id)
+                 SELECT $1, $2, description, $3, $4, COALESCE($5, owner_id)
+                 FROM collection WHERE id = $6",
+            )
+            .bind(copy_id)
+            .bind(&copy_name)
+            .bind(parent_id)
+            .bind(now)
+            .bind(owner_id)
+            .bind(source_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            copies.insert(source_id, copy_id);
+
+            for (child_id, _, child_name) in subtree
+                .iter()
+                .filter(|(_, parent, _)| *parent == Some(source_id))
+            {
+                let child_copy_name = self.free_copy_name_tx(tx, child_name).await?;
+                pending.push((*child_id, Some(copy_id), child_copy_name));
+            }
+        }
+
+        let note_ids = self
+            .copy_notes_tx(tx, &source_ids, &copies, mode, owner_id)
+            .await?;
+
+        let result = CollectionCopyResult {
+            collection_id: copies[&id],
+            mode,
+            collections_copied: copies.len() as u64,
+            notes_copied: note_ids.len() as u64,
+        };
+        Ok((result, note_ids))
+    }
+
+    /// Copy the live notes of `source_ids` into their collections' copies,
+    /// returning the IDs of the copies.
+    async fn copy_notes_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        source_ids: &[Uuid],
+        copies: &HashMap<Uuid, Uuid>,
+        mode: CollectionCopyMode,
+        owner_id: Option<Uuid>,
+    ) -> Result<Vec<Uuid>> {
+        let rows = sqlx::query(
+            "SELECT id, collection_id FROM note
+             WHERE collection_id = ANY($1) AND deleted_at IS NULL
+             ORDER BY created_at_utc, id",
+        )
+        .bind(source_ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let count = rows.len();
+        let mut old_ids = Vec::with_capacity(count);
+        let mut new_collection_ids = Vec::with_capacity(count);
+        for row in rows {
+            let collection_id: Uuid = row.get("collection_id");
+            old_ids.push(row.get::<Uuid, _>("id"));
+            new_collection_ids.push(copies[&collection_id]);
+        }
+        let fresh_ids = || (0..count).map(|_| new_v7()).collect::<Vec<Uuid>>();
+        let new_ids = fresh_ids();
+        let original_ids = fresh_ids();
+        let revision_ids = fresh_ids();
+        let row_ids = fresh_ids();
+        let now = Utc::now();
+
+        // Each statement joins its source rows through the same ID mapping.
+        const MAPPING: &str =
+            "unnest($1::uuid[], $2::uuid[], $3::uuid[], $4::uuid[], $5::uuid[], $6::uuid[]) \
+             AS m(old_id, new_id, collection_id, original_id, revision_id, row_id)";
+        let statements = [
+            format!(
+                "INSERT INTO note (id, collection_id, format, source, created_at_utc, updated_at_utc,
+                                   metadata, document_type_id, title, language, starred, archived,
+                                   owner_id, tenant_id, visibility)
+                 SELECT m.new_id, m.collection_id, n.format, n.source, $7, $7,
+                        n.metadata, n.document_type_id, n.title, n.language, n.starred, n.archived,
+                        COALESCE($8, n.owner_id), n.tenant_id, n.visibility
+                 FROM {MAPPING} JOIN note n ON n.id = m.old_id"
+            ),
+            format!(
+                "INSERT INTO note_original (id, note_id, content, hash)
+                 SELECT m.original_id, m.new_id, o.content, o.hash
+                 FROM {MAPPING} JOIN note_original o ON o.note_id = m.old_id"
+            ),
+            format!(
+                "INSERT INTO note_revision (id, note_id, content, rationale, created_at_utc, revision_number)
+                 SELECT m.revision_id, m.new_id, r.content, NULL, $7, 1
+                 FROM {MAPPING} JOIN note_revised_current r ON r.note_id = m.old_id"
+            ),
+            format!(
+                "INSERT INTO note_revised_current (note_id, content, last_revision_id, ai_metadata)
+                 SELECT m.new_id, r.content, m.revision_id, r.ai_metadata
+                 FROM {MAPPING} JOIN note_revised_current r ON r.note_id = m.old_id"
+            ),
+            format!(
+                "INSERT INTO note_tag (note_id, tag_name, source)
+                 SELECT m.new_id, t.tag_name, t.source
+                 FROM {MAPPING} JOIN note_tag t ON t.note_id = m.old_id"
+            ),
+            format!(
+                "INSERT INTO note_skos_concept (note_id, concept_id, source, confidence,
+                                                relevance_score, is_primary, created_by)
+                 SELECT m.new_id, c.concept_id, c.source, c.confidence,
+                        c.relevance_score, c.is_primary, c.created_by
+                 FROM {MAPPING} JOIN note_skos_concept c ON c.note_id = m.old_id"
+            ),
+            format!(
+                "INSERT INTO activity_log (id, at_utc, actor, action, note_id, meta)
+                 SELECT m.row_id, $7, 'user', 'create_note', m.new_id,
+                        jsonb_build_object('copied_from', m.old_id)
+                 FROM {MAPPING}"
+            ),
+        ];
+        let link = (mode == CollectionCopyMode::Link).then(|| {
+            format!(
+                "INSERT INTO link (id, from_note_id, to_note_id, to_url, kind, score, created_at_utc, metadata)
+                 SELECT m.row_id, m.new_id, m.old_id, NULL, '{COPIED_FROM_LINK_KIND}', 1.0, $7, '{{}}'::jsonb
+                 FROM {MAPPING}"
+            )
+        });
+        // Links need IDs apart from the activity log rows'.
+        let link_ids = fresh_ids();
+
+        for sql in &statements {
+            self.bind_note_copy(
+                sql,
+                &old_ids,
+                &new_ids,
+                &new_collection_ids,
+                &original_ids,
+                &revision_ids,
+                &row_ids,
+                now,
+                owner_id,
+            )
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+        if let Some(sql) = &link {
+            self.bind_note_copy(
+                sql,
+                &old_ids,
+                &new_ids,
+                &new_collection_ids,
+                &original_ids,
+                &revision_ids,
+                &link_ids,
+                now,
+                owner_id,
+            )
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+
+        Ok(new_ids)
+    }
+
+    /// Bind the shared parameters of a note copy statement.
+    #[allow(clippy::too_many_arguments)]
+    fn bind_note_copy<'q>(
+        &self,
+        sql: &'q str,
+        old_ids: &'q [Uuid],
+        new_ids: &'q [Uuid],
+        collection_ids: &'q [Uuid],
+        original_ids: &'q [Uuid],
+        revision_ids: &'q [Uuid],
+        row_ids: &'q [Uuid],
+        now: chrono::DateTime<Utc>,
+        owner_id: Option<Uuid>,
+    ) -> sqlx::query::Query<'q, Postgres, sqlx::postgres::PgArguments> {
+        sqlx::query(sql)
+            .bind(old_ids)
+            .bind(new_ids)
+            .bind(collection_ids)
+            .bind(original_ids)
+            .bind(revision_ids)
+            .bind(row_ids)
+            .bind(now)
+            .bind(owner_id)
+    }
+
+    /// Whether a collection exists.
+    async fn exists_tx(&self, tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<bool> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM collection WHERE id = $1)")
+            .bind(id)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)
+    }
+
+    /// Whether `id` is `root` or one of its descendants, found by walking
+    /// up from `id`. The walk stops at a repeated row, so a cycle already in
+    /// the table cannot make it loop.
+    async fn is_within_subtree_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        root: Uuid,
+    ) -> Result<bool> {
+        sqlx::query_scalar(
+            r#"
+            WITH RECURSIVE ancestors AS (
+                SELECT id, parent_id FROM collection WHERE id = $1
+                UNION
+                SELECT c.id, c.parent_id
+                FROM collection c
+                INNER JOIN ancestors a ON c.id = a.parent_id
+            )
+            SELECT EXISTS (SELECT 1 FROM ancestors WHERE id = $2)
+            "#,
+        )
+        .bind(id)
+        .bind(root)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Whether a collection already has this name.
+    async fn name_taken_tx(&self, tx: &mut Transaction<'_, Postgres>, name: &str) -> Result<bool> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM collection WHERE name = $1)")
+            .bind(name)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)
+    }
+
+    /// The first `"<name> (copy N)"` no collection has yet.
+    async fn free_copy_name_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        name: &str,
+    ) -> Result<String> {
+        let mut attempt = 1;
+        loop {
+            let candidate = collection_copy_name(name, attempt);
+            if !self.name_taken_tx(tx, &candidate).await? {
+                return Ok(candidate);
+            }
+            attempt += 1;
+        }
+    }
+
+    /// The notes a site export of a collection holds, by title: live,
+    /// unencrypted notes of the collection, and of its subtree when
+    /// `include_subcollections` is set, that `security` admits.
+    pub async fn site_export_notes_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        include_subcollections: bool,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Vec<SiteExportNote>> {
+        if !self.exists_tx(tx, id).await? {
+            return Err(Error::NotFound("Collection not found".to_string()));
+        }
+        let rows = sqlx::query(
+            "WITH RECURSIVE subtree AS (
+                 SELECT id FROM collection WHERE id = $1
+                 UNION
+                 SELECT c.id FROM collection c INNER JOIN subtree s ON c.parent_id = s.id WHERE $2
+             )
+             SELECT n.id, COALESCE(NULLIF(n.title, ''), $3) AS title,
+                    COALESCE(NULLIF(nrc.content, ''), no.content, '') AS content,
+                    COALESCE(
+                        (SELECT array_agg(tag_name ORDER BY tag_name)
+                         FROM note_tag WHERE note_id = n.id),
+                        '{}'
+                    ) AS tags,
+                    n.created_at_utc, n.updated_at_utc
+             FROM note n
+             LEFT JOIN note_original no ON no.note_id = n.id
+             LEFT JOIN note_revised_current nrc ON nrc.note_id = n.id
+             WHERE n.collection_id IN (SELECT id FROM subtree)
+               AND n.deleted_at IS NULL
+               AND NOT n.encrypted
+             ORDER BY lower(COALESCE(NULLIF(n.title, ''), $3)), n.id
+             LIMIT $4",
+        )
+        .bind(id)
+        .bind(include_subcollections)
+        .bind(UNTITLED_PAGE)
+        .bind(MAX_SITE_EXPORT_NOTES + 1)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        if rows.len() as i64 > MAX_SITE_EXPORT_NOTES {
+            return Err(Error::InvalidInput(format!(
+                "Cannot export more than {MAX_SITE_EXPORT_NOTES} notes at once"
+            )));
+        }
+
+        let mut notes: Vec<SiteExportNote> = rows
+            .iter()
+            .map(|row| SiteExportNote {
+                id: row.get("id"),
+                title: row.get("title"),
+                content: row.get("content"),
+                tags: row.get("tags"),
+                created_at_utc: row.get("created_at_utc"),
+                updated_at_utc: row.get("updated_at_utc"),
+            })
+            .collect();
+        if let Some(security) = security {
+            let ids: Vec<Uuid> = notes.iter().map(|note| note.id).collect();
+            let visible = visible_note_ids(&mut **tx, &ids, security).await?;
+            notes.retain(|note| visible.contains(&note.id));
+        }
+        Ok(notes)
+    }
+
     /// Get notes for a collection within an existing transaction.
     pub async fn get_notes_tx(
         &self,
@@ -535,7 +973,31 @@ impl PgCollectionRepository {
         limit: i64,
         offset: i64,
     ) -> Result<Vec<NoteSummary>> {
-        let rows = sqlx::query(
+        self.get_visible_notes_tx(tx, id, limit, offset, None).await
+    }
+
+    /// Get the notes of a collection a security filter admits within an
+    /// existing transaction. `None` returns every note, like
+    /// [`Self::get_notes_tx`].
+    pub async fn get_visible_notes_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        limit: i64,
+        offset: i64,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Vec<NoteSummary>> {
+        let security = security
+            .filter(|security| !security.is_empty())
+            .map(|security| {
+                let filter = StrictFilter::new().with_security(security.clone());
+                UnifiedFilterQueryBuilder::new(filter, 3).build()
+            });
+        let security_clause = security
+            .as_ref()
+            .map(|result| format!("AND {}", result.where_clause))
+            .unwrap_or_default();
+        let sql = format!(
             r#"
             SELECT
                 n.id, n.created_at_utc, n.updated_at_utc, n.starred, n.archived,
@@ -549,17 +1011,24 @@ impl PgCollectionRepository {
             FROM note n
             JOIN note_original no ON no.note_id = n.id
             LEFT JOIN note_revised_current nrc ON nrc.note_id = n.id
-            WHERE n.collection_id = $1 AND n.deleted_at IS NULL
+            WHERE n.collection_id = $1 AND n.deleted_at IS NULL {security_clause}
             ORDER BY n.created_at_utc DESC
             LIMIT $2 OFFSET $3
-            "#,
-        )
-        .bind(id)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&mut **tx)
-        .await
-        .map_err(Error::Database)?;
+            "#
+        );
+        let mut q = sqlx::query(&sql).bind(id).bind(limit).bind(offset);
+        for param in security.iter().flat_map(|result| &result.params) {
+            q = match param {
+                QueryParam::Uuid(id) => q.bind(id),
+                QueryParam::UuidArray(ids) => q.bind(ids),
+                QueryParam::Int(val) => q.bind(val),
+                QueryParam::Timestamp(ts) => q.bind(ts),
+                QueryParam::Bool(b) => q.bind(b),
+                QueryParam::String(s) => q.bind(s),
+                QueryParam::StringArray(arr) => q.bind(arr),
+            };
+        }
+        let rows = q.fetch_all(&mut **tx).await.map_err(Error::Database)?;
 
         Ok(rows
             .into_iter()
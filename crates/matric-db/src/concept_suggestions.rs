@@ -0,0 +1,244 @@
+//! Concept suggestion review queue repository.
+//!
+//! Suggestions and review thresholds are archive-scoped; every method takes a
+//! transaction that has already been pointed at the archive schema.
+
+use chrono::Utc;
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::concept_suggestion::adjusted_review_threshold;
+use matric_core::{
+    new_v7, ConceptReviewThreshold, ConceptSuggestion, ConceptSuggestionReview,
+    ConceptSuggestionStatus, Error, NewConceptSuggestion, Result,
+};
+
+/// Columns of [`ConceptSuggestion`], for queries aliasing `concept_suggestion AS s`.
+const SUGGESTION_COLUMNS: &str = "s.id, s.note_id, s.concept_id, l.value AS pref_label, \
+     s.confidence, s.threshold, s.evidence, s.extraction_method, s.status, \
+     s.created_at_utc, s.reviewed_at_utc, s.reviewed_by";
+
+/// Joins the suggested concept's English preferred label.
+const SUGGESTION_LABEL_JOIN: &str = "LEFT JOIN skos_concept_label l \
+     ON l.concept_id = s.concept_id AND l.label_type = 'pref_label' AND l.language = 'en'";
+
+/// PostgreSQL repository for concept suggestions awaiting review.
+#[derive(Clone)]
+pub struct PgConceptSuggestionRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgConceptSuggestionRepository {
+    /// Create a new concept suggestion repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// The concept's review threshold, or `default` if no review has set one.
+    pub async fn threshold_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        concept_id: Uuid,
+        default: f32,
+    ) -> Result<f32> {
+        let threshold: Option<f32> = sqlx::query_scalar(
+            "SELECT threshold FROM concept_review_threshold WHERE concept_id = $1",
+        )
+        .bind(concept_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(threshold.unwrap_or(default))
+    }
+
+    /// Hold a suggestion for review.
+    ///
+    /// Skipped when the note already has the concept or a reviewer rejected
+    /// the concept for the note; an open suggestion for the same note and
+    /// concept is refreshed instead. Returns whether a suggestion was stored.
+    pub async fn suggest_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        suggestion: &NewConceptSuggestion,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO concept_suggestion
+                 (id, note_id, concept_id, confidence, threshold, relevance_score, is_primary,
+                  evidence, extraction_method)
+             SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9
+             WHERE NOT EXISTS (
+                     SELECT 1 FROM note_skos_concept WHERE note_id = $2 AND concept_id = $3)
+               AND NOT EXISTS (
+                     SELECT 1 FROM concept_suggestion
+                     WHERE note_id = $2 AND concept_id = $3 AND status = 'rejected')
+             ON CONFLICT (note_id, concept_id) WHERE status = 'pending' DO UPDATE SET
+                 confidence = EXCLUDED.confidence,
+                 threshold = EXCLUDED.threshold,
+                 relevance_score = EXCLUDED.relevance_score,
+                 is_primary = EXCLUDED.is_primary,
+                 evidence = COALESCE(EXCLUDED.evidence, concept_suggestion.evidence),
+                 extraction_method = EXCLUDED.extraction_method",
+        )
+        .bind(new_v7())
+        .bind(suggestion.note_id)
+        .bind(suggestion.concept_id)
+        .bind(suggestion.confidence.clamp(0.0, 1.0))
+        .bind(suggestion.threshold.clamp(0.0, 1.0))
+        .bind(suggestion.relevance_score)
+        .bind(suggestion.is_primary)
+        .bind(&suggestion.evidence)
+        .bind(&suggestion.extraction_method)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Suggestions in `status`, most confident first, optionally for one
+    /// concept or note.
+    pub async fn list_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        status: ConceptSuggestionStatus,
+        concept_id: Option<Uuid>,
+        note_id: Option<Uuid>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ConceptSuggestion>> {
+        sqlx::query_as::<_, ConceptSuggestion>(&format!(
+            "SELECT {SUGGESTION_COLUMNS}
+             FROM concept_suggestion s
+             {SUGGESTION_LABEL_JOIN}
+             WHERE s.status = $1
+               AND ($2::uuid IS NULL OR s.concept_id = $2)
+               AND ($3::uuid IS NULL OR s.note_id = $3)
+             ORDER BY s.confidence DESC, s.created_at_utc, s.id
+             LIMIT $4 OFFSET $5"
+        ))
+        .bind(status.as_str())
+        .bind(concept_id)
+        .bind(note_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Accept or reject a pending suggestion.
+    ///
+    /// Accepting tags the note with the concept, keeping any tag the note
+    /// already has. Either way the concept's threshold moves one step from
+    /// its current value (`default` if it has none): down after an accept,
+    /// up after a reject.
+    pub async fn review_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        accepted: bool,
+        reviewed_by: Option<&str>,
+        default: f32,
+    ) -> Result<ConceptSuggestionReview> {
+        let row: Option<(Uuid, Uuid, f32, f32, bool, String)> = sqlx::query_as(
+            "SELECT note_id, concept_id, confidence, relevance_score, is_primary, status
+             FROM concept_suggestion
+             WHERE id = $1
+             FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        let Some((note_id, concept_id, confidence, relevance_score, is_primary, status)) = row
+        else {
+            return Err(Error::NotFound(format!(
+                "Concept suggestion {id} not found"
+            )));
+        };
+        if status != ConceptSuggestionStatus::Pending.as_str() {
+            return Err(Error::InvalidInput(format!(
+                "Concept suggestion {id} is already {status}"
+            )));
+        }
+
+        let now = Utc::now();
+        if accepted {
+            sqlx::query(
+                "INSERT INTO note_skos_concept (
+                     note_id, concept_id, source, confidence, relevance_score,
+                     is_primary, created_at, created_by
+                 )
+                 VALUES ($1, $2, 'ai_reviewed', $3, $4, $5, $6, $7)
+                 ON CONFLICT (note_id, concept_id) DO NOTHING",
+            )
+            .bind(note_id)
+            .bind(concept_id)
+            .bind(confidence)
+            .bind(relevance_score)
+            .bind(is_primary)
+            .bind(now)
+            .bind(reviewed_by)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+
+        let status = if accepted {
+            ConceptSuggestionStatus::Accepted
+        } else {
+            ConceptSuggestionStatus::Rejected
+        };
+        sqlx::query(
+            "UPDATE concept_suggestion
+             SET status = $2, reviewed_at_utc = $3, reviewed_by = $4
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status.as_str())
+        .bind(now)
+        .bind(reviewed_by)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let current = self.threshold_tx(tx, concept_id, default).await?;
+        let threshold = sqlx::query_as::<_, ConceptReviewThreshold>(
+            "INSERT INTO concept_review_threshold
+                 (concept_id, threshold, accepted_count, rejected_count, updated_at_utc)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (concept_id) DO UPDATE SET
+                 threshold = EXCLUDED.threshold,
+                 accepted_count = concept_review_threshold.accepted_count
+                     + EXCLUDED.accepted_count,
+                 rejected_count = concept_review_threshold.rejected_count
+                     + EXCLUDED.rejected_count,
+                 updated_at_utc = EXCLUDED.updated_at_utc
+             RETURNING concept_id, threshold, accepted_count, rejected_count",
+        )
+        .bind(concept_id)
+        .bind(adjusted_review_threshold(current, accepted))
+        .bind(i32::from(accepted))
+        .bind(i32::from(!accepted))
+        .bind(now)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let suggestion = sqlx::query_as::<_, ConceptSuggestion>(&format!(
+            "SELECT {SUGGESTION_COLUMNS}
+             FROM concept_suggestion s
+             {SUGGESTION_LABEL_JOIN}
+             WHERE s.id = $1"
+        ))
+        .bind(id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(ConceptSuggestionReview {
+            suggestion,
+            threshold,
+        })
+    }
+}
@@ -0,0 +1,328 @@
+//! Scheduled digest repository.
+//!
+//! Digest configurations live in the shared `public` schema, keyed by the
+//! archive's schema name. The scheduler claims due digests with a
+//! compare-and-set on `next_run_at_utc`, so several API instances never queue
+//! the same slot twice. [`PgDigestRepository::activity_tx`] reads the notes a
+//! digest covers through a transaction pointed at the archive schema.
+
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgRow, Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::digest::DIGEST_NOTE_SOURCE;
+use matric_core::{
+    new_v7, CreateDigestRequest, DigestCadence, DigestConfig, DigestDelivery, DigestNoteActivity,
+    Error, Result,
+};
+
+const DIGEST_COLUMNS: &str = "id, name, cadence, hour_utc, collection_ids, delivery, \
+     note_collection_id, enabled, next_run_at_utc, last_run_at_utc, last_status, last_note_id, \
+     created_at_utc, updated_at_utc";
+
+/// Run status when a digest was generated and delivered.
+pub const DIGEST_RUN_SUCCEEDED: &str = "succeeded";
+/// Run status when no notes changed during the period.
+pub const DIGEST_RUN_EMPTY: &str = "empty";
+/// Run status when the digest could not be generated or delivered.
+pub const DIGEST_RUN_FAILED: &str = "failed";
+
+/// A digest whose next run is due, with the archive it belongs to.
+#[derive(Debug, Clone)]
+pub struct DueDigest {
+    pub schema_name: String,
+    pub config: DigestConfig,
+}
+
+/// PostgreSQL repository for scheduled digests.
+#[derive(Clone)]
+pub struct PgDigestRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgDigestRepository {
+    /// Create a new digest repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// List an archive's digests by name.
+    pub async fn list(&self, schema_name: &str) -> Result<Vec<DigestConfig>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {DIGEST_COLUMNS} FROM digest_config WHERE schema_name = $1 ORDER BY name"
+        ))
+        .bind(schema_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows.iter().map(digest_from_row).collect())
+    }
+
+    /// Get one of an archive's digests.
+    pub async fn get(&self, schema_name: &str, id: Uuid) -> Result<Option<DigestConfig>> {
+        let row = sqlx::query(&format!(
+            "SELECT {DIGEST_COLUMNS} FROM digest_config WHERE schema_name = $1 AND id = $2"
+        ))
+        .bind(schema_name)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.as_ref().map(digest_from_row))
+    }
+
+    /// Create a digest whose first run is at `next_run_at`.
+    pub async fn create(
+        &self,
+        schema_name: &str,
+        req: &CreateDigestRequest,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<DigestConfig> {
+        let now = Utc::now();
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO digest_config
+                (id, schema_name, name, cadence, hour_utc, collection_ids, delivery,
+                 note_collection_id, enabled, next_run_at_utc, created_at_utc, updated_at_utc)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $11)
+            RETURNING {DIGEST_COLUMNS}
+            "#
+        ))
+        .bind(new_v7())
+        .bind(schema_name)
+        .bind(req.name.trim())
+        .bind(req.cadence.as_str())
+        .bind(req.hour_utc)
+        .bind(&req.collection_ids)
+        .bind(delivery_values(&req.delivery))
+        .bind(req.note_collection_id)
+        .bind(req.enabled)
+        .bind(next_run_at)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| digest_insert_error(e, &req.name))?;
+
+        Ok(digest_from_row(&row))
+    }
+
+    /// Persist the mutable fields of `digest`.
+    ///
+    /// Returns `None` when the digest no longer exists in the archive.
+    pub async fn update(
+        &self,
+        schema_name: &str,
+        digest: &DigestConfig,
+    ) -> Result<Option<DigestConfig>> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE digest_config
+            SET name = $3, cadence = $4, hour_utc = $5, collection_ids = $6, delivery = $7,
+                note_collection_id = $8, enabled = $9, next_run_at_utc = $10,
+                updated_at_utc = NOW()
+            WHERE schema_name = $1 AND id = $2
+            RETURNING {DIGEST_COLUMNS}
+            "#
+        ))
+        .bind(schema_name)
+        .bind(digest.id)
+        .bind(digest.name.trim())
+        .bind(digest.cadence.as_str())
+        .bind(digest.hour_utc)
+        .bind(&digest.collection_ids)
+        .bind(delivery_values(&digest.delivery))
+        .bind(digest.note_collection_id)
+        .bind(digest.enabled)
+        .bind(digest.next_run_at_utc)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| digest_insert_error(e, &digest.name))?;
+
+        Ok(row.as_ref().map(digest_from_row))
+    }
+
+    /// Delete a digest. Digest notes already written are kept.
+    pub async fn delete(&self, schema_name: &str, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM digest_config WHERE schema_name = $1 AND id = $2")
+            .bind(schema_name)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Enabled digests, across all archives, whose next run is at or before `now`.
+    pub async fn list_due(&self, now: DateTime<Utc>) -> Result<Vec<DueDigest>> {
+        let rows = sqlx::query(&format!(
+            "SELECT schema_name, {DIGEST_COLUMNS} FROM digest_config \
+             WHERE enabled AND next_run_at_utc <= $1 ORDER BY next_run_at_utc, id"
+        ))
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DueDigest {
+                schema_name: row.get("schema_name"),
+                config: digest_from_row(row),
+            })
+            .collect())
+    }
+
+    /// Advance a due digest to its next slot.
+    ///
+    /// Succeeds only if `next_run_at_utc` still equals `claimed`, so exactly
+    /// one scheduler queues each slot.
+    pub async fn claim_due(
+        &self,
+        id: Uuid,
+        claimed: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE digest_config SET next_run_at_utc = $3 \
+             WHERE id = $1 AND next_run_at_utc = $2",
+        )
+        .bind(id)
+        .bind(claimed)
+        .bind(next_run_at)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the outcome of a run.
+    pub async fn record_run(&self, id: Uuid, status: &str, note_id: Option<Uuid>) -> Result<()> {
+        sqlx::query(
+            "UPDATE digest_config \
+             SET last_run_at_utc = NOW(), last_status = $2, \
+                 last_note_id = COALESCE($3, last_note_id) \
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status)
+        .bind(note_id)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Notes created or updated in `[start, end)`, most recently updated first.
+    ///
+    /// Deleted and encrypted notes are left out, as are earlier digest notes.
+    /// When `collection_ids` is non-empty only notes filed in one of them are
+    /// returned.
+    pub async fn activity_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        collection_ids: &[Uuid],
+        limit: i64,
+        excerpt_chars: i32,
+    ) -> Result<Vec<DigestNoteActivity>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT n.id, n.title, n.created_at_utc >= $1 AS created, s.summary,
+                   substring(COALESCE(nrc.content, noc.content, '') for $6) AS excerpt
+            FROM note n
+            LEFT JOIN note_revised_current nrc ON nrc.note_id = n.id
+            LEFT JOIN note_original noc ON noc.note_id = n.id
+            LEFT JOIN note_summary s ON s.note_id = n.id
+            WHERE n.deleted_at IS NULL
+              AND NOT n.encrypted
+              AND n.source <> $3
+              AND ((n.created_at_utc >= $1 AND n.created_at_utc < $2)
+                   OR (n.updated_at_utc >= $1 AND n.updated_at_utc < $2))
+              AND (cardinality($4::uuid[]) = 0 OR n.collection_id = ANY($4))
+            ORDER BY n.updated_at_utc DESC, n.id
+            LIMIT $5
+            "#,
+        )
+        .bind(start)
+        .bind(end)
+        .bind(DIGEST_NOTE_SOURCE)
+        .bind(collection_ids)
+        .bind(limit)
+        .bind(excerpt_chars)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DigestNoteActivity {
+                note_id: row.get("id"),
+                title: row.get("title"),
+                created: row.get("created"),
+                summary: row.get("summary"),
+                excerpt: row.get::<Option<String>, _>("excerpt").unwrap_or_default(),
+            })
+            .collect())
+    }
+}
+
+fn delivery_values(delivery: &[DigestDelivery]) -> Vec<&'static str> {
+    let mut values: Vec<&'static str> = delivery.iter().map(DigestDelivery::as_str).collect();
+    values.sort_unstable();
+    values.dedup();
+    values
+}
+
+fn digest_insert_error(e: sqlx::Error, name: &str) -> Error {
+    if let sqlx::Error::Database(ref db_err) = e {
+        if db_err.constraint() == Some("digest_config_schema_name_name_key") {
+            return Error::InvalidInput(format!(
+                "Digest already exists; name_len={}",
+                name.chars().count()
+            ));
+        }
+    }
+    Error::Database(e)
+}
+
+fn digest_from_row(row: &PgRow) -> DigestConfig {
+    let cadence: String = row.get("cadence");
+    let delivery: Vec<String> = row.get("delivery");
+    DigestConfig {
+        id: row.get("id"),
+        name: row.get("name"),
+        cadence: DigestCadence::parse(&cadence).unwrap_or(DigestCadence::Daily),
+        hour_utc: row.get("hour_utc"),
+        collection_ids: row.get("collection_ids"),
+        delivery: delivery
+            .iter()
+            .filter_map(|d| DigestDelivery::parse(d))
+            .collect(),
+        note_collection_id: row.get("note_collection_id"),
+        enabled: row.get("enabled"),
+        next_run_at_utc: row.get("next_run_at_utc"),
+        last_run_at_utc: row.get("last_run_at_utc"),
+        last_status: row.get("last_status"),
+        last_note_id: row.get("last_note_id"),
+        created_at_utc: row.get("created_at_utc"),
+        updated_at_utc: row.get("updated_at_utc"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delivery_values_are_sorted_and_unique() {
+        let delivery = [
+            DigestDelivery::Webhook,
+            DigestDelivery::Note,
+            DigestDelivery::Webhook,
+        ];
+        assert_eq!(delivery_values(&delivery), vec!["note", "webhook"]);
+    }
+}
@@ -4,9 +4,9 @@ use async_trait::async_trait;
 use matric_core::{
     new_v7, AgenticConfig, ChunkingStrategy, CreateDocumentTypeRequest, DetectDocumentTypeResult,
     DocumentCategory, DocumentType, DocumentTypeRepository, DocumentTypeSummary, Error,
-    ExtractionStrategy, Result, UpdateDocumentTypeRequest,
+    ExtractionStrategy, PipelinePolicy, Result, UpdateDocumentTypeRequest,
 };
-use sqlx::{Pool, Postgres, Row};
+use sqlx::{Pool, Postgres, Row, Transaction};
 use uuid::Uuid;
 
 fn document_type_not_found_error(name: &str) -> Error {
@@ -16,6 +16,45 @@ fn document_type_not_found_error(name: &str) -> Error {
     ))
 }
 
+fn metadata_schema_error(err: impl std::fmt::Display) -> Error {
+    Error::InvalidInput(format!(
+        "Invalid metadata JSON schema; diagnostic_len={}",
+        err.to_string().chars().count()
+    ))
+}
+
+fn note_metadata_error(errors: &[jsonschema::ValidationError<'_>]) -> Error {
+    // Validator messages echo metadata values, so report only their shape.
+    Error::InvalidInput(format!(
+        "Note metadata does not match the document type's metadata schema; error_count={}; path_lens={:?}",
+        errors.len(),
+        errors
+            .iter()
+            .map(|err| err.instance_path().to_string().chars().count())
+            .collect::<Vec<_>>()
+    ))
+}
+
+/// Reject a metadata schema that does not compile as a JSON Schema.
+pub fn check_metadata_schema(schema: &serde_json::Value) -> Result<()> {
+    jsonschema::validator_for(schema).map_err(metadata_schema_error)?;
+    Ok(())
+}
+
+/// Validate note metadata against a document type's metadata schema.
+pub fn validate_note_metadata(
+    schema: &serde_json::Value,
+    metadata: &serde_json::Value,
+) -> Result<()> {
+    let validator = jsonschema::validator_for(schema).map_err(metadata_schema_error)?;
+    let errors: Vec<_> = validator.iter_errors(metadata).collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(note_metadata_error(&errors))
+    }
+}
+
 fn document_type_in_use_error(name: &str, reference_count: i64) -> Error {
     Error::InvalidInput(format!(
         "Cannot delete document type; name_len={}; reference_count_present={}",
@@ -48,6 +87,55 @@ impl PgDocumentTypeRepository {
         s.and_then(|s| s.parse().ok())
             .unwrap_or(ExtractionStrategy::TextNative)
     }
+
+    // Helper to parse a stored pipeline policy, falling back to no policy
+    fn parse_pipeline_policy(value: Option<serde_json::Value>) -> PipelinePolicy {
+        value
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+
+    /// Pipeline policy of a note's document type, within a transaction.
+    ///
+    /// Returns the default (empty) policy when the note has no document type.
+    pub async fn policy_for_note_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<PipelinePolicy> {
+        let value: Option<serde_json::Value> = sqlx::query_scalar(
+            r#"
+            SELECT dt.pipeline_policy
+            FROM note n
+            JOIN document_type dt ON dt.id = n.document_type_id
+            WHERE n.id = $1
+            "#,
+        )
+        .bind(note_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(Self::parse_pipeline_policy(value))
+    }
+
+    /// Metadata schema of a document type, within a transaction.
+    ///
+    /// Returns `None` when the type has no schema or does not exist.
+    pub async fn metadata_schema_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        document_type_id: Uuid,
+    ) -> Result<Option<serde_json::Value>> {
+        let value: Option<Option<serde_json::Value>> =
+            sqlx::query_scalar("SELECT metadata_schema FROM document_type WHERE id = $1")
+                .bind(document_type_id)
+                .fetch_optional(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+
+        Ok(value.flatten())
+    }
 }
 
 #[async_trait]
@@ -139,7 +227,8 @@ impl DocumentTypeRepository for PgDocumentTypeRepository {
                    content_types, tree_sitter_language,
                    extraction_strategy::TEXT, extraction_config, requires_attachment, attachment_generates_content,
                    is_system, is_active,
-                   created_at, updated_at, created_by, agentic_config
+                   created_at, updated_at, created_by, agentic_config, pipeline_policy,
+                   metadata_schema
             FROM document_type
             WHERE id = $1
             "#,
@@ -162,7 +251,8 @@ impl DocumentTypeRepository for PgDocumentTypeRepository {
                    content_types, tree_sitter_language,
                    extraction_strategy::TEXT, extraction_config, requires_attachment, attachment_generates_content,
                    is_system, is_active,
-                   created_at, updated_at, created_by, agentic_config
+                   created_at, updated_at, created_by, agentic_config, pipeline_policy,
+                   metadata_schema
             FROM document_type
             WHERE name = $1
             "#,
@@ -176,60 +266,9 @@ impl DocumentTypeRepository for PgDocumentTypeRepository {
     }
 
     async fn create(&self, req: CreateDocumentTypeRequest) -> Result<Uuid> {
-        let id = new_v7();
-        // Auto-generate display_name from name if not provided (kebab-case → Title Case)
-        let display_name = req.display_name.unwrap_or_else(|| {
-            req.name
-                .split(['-', '_'])
-                .map(|word| {
-                    let mut chars = word.chars();
-                    match chars.next() {
-                        None => String::new(),
-                        Some(c) => c.to_uppercase().to_string() + chars.as_str(),
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join(" ")
-        });
-
-        sqlx::query(
-            r#"
-            INSERT INTO document_type (
-                id, name, display_name, category, description,
-                file_extensions, mime_types, magic_patterns, filename_patterns,
-                chunking_strategy, chunk_size_default, chunk_overlap_default,
-                preserve_boundaries, chunking_config, recommended_config_id,
-                content_types, tree_sitter_language, is_system
-            ) VALUES (
-                $1, $2, $3, $4::document_category, $5,
-                $6, $7, $8, $9,
-                $10::chunking_strategy, $11, $12,
-                $13, $14, $15,
-                $16, $17, FALSE
-            )
-            "#,
-        )
-        .bind(id)
-        .bind(&req.name)
-        .bind(&display_name)
-        .bind(req.category.to_string())
-        .bind(&req.description)
-        .bind(&req.file_extensions)
-        .bind(&req.mime_types)
-        .bind(&req.magic_patterns)
-        .bind(&req.filename_patterns)
-        .bind(req.chunking_strategy.to_string())
-        .bind(req.chunk_size_default)
-        .bind(req.chunk_overlap_default)
-        .bind(req.preserve_boundaries)
-        .bind(req.chunking_config.unwrap_or(serde_json::json!({})))
-        .bind(req.recommended_config_id)
-        .bind(&req.content_types)
-        .bind(&req.tree_sitter_language)
-        .execute(&self.pool)
-        .await
-        .map_err(Error::Database)?;
-
+        let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        let id = self.create_tx(&mut tx, req).await?;
+        tx.commit().await.map_err(Error::Database)?;
         Ok(id)
     }
 
@@ -246,6 +285,15 @@ impl DocumentTypeRepository for PgDocumentTypeRepository {
             return Err(document_type_not_found_error(name));
         }
 
+        let pipeline_policy = req
+            .pipeline_policy
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        if let Some(schema) = &req.metadata_schema {
+            check_metadata_schema(schema)?;
+        }
+
         // Use simpler approach with conditional updates
         sqlx::query(
             r#"
@@ -257,6 +305,8 @@ impl DocumentTypeRepository for PgDocumentTypeRepository {
                 chunk_size_default = COALESCE($6, chunk_size_default),
                 chunk_overlap_default = COALESCE($7, chunk_overlap_default),
                 is_active = COALESCE($8, is_active),
+                pipeline_policy = COALESCE($9, pipeline_policy),
+                metadata_schema = COALESCE($10, metadata_schema),
                 updated_at = NOW()
             WHERE name = $1 AND is_system = FALSE
             "#,
@@ -269,6 +319,8 @@ impl DocumentTypeRepository for PgDocumentTypeRepository {
         .bind(req.chunk_size_default)
         .bind(req.chunk_overlap_default)
         .bind(req.is_active)
+        .bind(pipeline_policy)
+        .bind(&req.metadata_schema)
         .execute(&self.pool)
         .await
         .map_err(Error::Database)?;
@@ -421,7 +473,8 @@ impl DocumentTypeRepository for PgDocumentTypeRepository {
                    content_types, tree_sitter_language,
                    extraction_strategy::TEXT, extraction_config, requires_attachment, attachment_generates_content,
                    is_system, is_active,
-                   created_at, updated_at, created_by, agentic_config
+                   created_at, updated_at, created_by, agentic_config, pipeline_policy,
+                   metadata_schema
             FROM document_type
             WHERE is_active = TRUE AND $1 = ANY(file_extensions)
             ORDER BY
@@ -451,7 +504,8 @@ impl DocumentTypeRepository for PgDocumentTypeRepository {
                    content_types, tree_sitter_language,
                    extraction_strategy::TEXT, extraction_config, requires_attachment, attachment_generates_content,
                    is_system, is_active,
-                   created_at, updated_at, created_by, agentic_config
+                   created_at, updated_at, created_by, agentic_config, pipeline_policy,
+                   metadata_schema
             FROM document_type
             WHERE is_active = TRUE AND $1 = ANY(filename_patterns)
             "#,
@@ -466,6 +520,79 @@ impl DocumentTypeRepository for PgDocumentTypeRepository {
 }
 
 impl PgDocumentTypeRepository {
+    /// Create a custom document type within an existing transaction.
+    pub async fn create_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        req: CreateDocumentTypeRequest,
+    ) -> Result<Uuid> {
+        let id = new_v7();
+        // Auto-generate display_name from name if not provided (kebab-case → Title Case)
+        let display_name = req.display_name.unwrap_or_else(|| {
+            req.name
+                .split(['-', '_'])
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        None => String::new(),
+                        Some(c) => c.to_uppercase().to_string() + chars.as_str(),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+
+        let pipeline_policy = serde_json::to_value(req.pipeline_policy.unwrap_or_default())
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        if let Some(schema) = &req.metadata_schema {
+            check_metadata_schema(schema)?;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO document_type (
+                id, name, display_name, category, description,
+                file_extensions, mime_types, magic_patterns, filename_patterns,
+                chunking_strategy, chunk_size_default, chunk_overlap_default,
+                preserve_boundaries, chunking_config, recommended_config_id,
+                content_types, tree_sitter_language, pipeline_policy, metadata_schema,
+                is_system
+            ) VALUES (
+                $1, $2, $3, $4::document_category, $5,
+                $6, $7, $8, $9,
+                $10::chunking_strategy, $11, $12,
+                $13, $14, $15,
+                $16, $17, $18, $19,
+                FALSE
+            )
+            "#,
+        )
+        .bind(id)
+        .bind(&req.name)
+        .bind(&display_name)
+        .bind(req.category.to_string())
+        .bind(&req.description)
+        .bind(&req.file_extensions)
+        .bind(&req.mime_types)
+        .bind(&req.magic_patterns)
+        .bind(&req.filename_patterns)
+        .bind(req.chunking_strategy.to_string())
+        .bind(req.chunk_size_default)
+        .bind(req.chunk_overlap_default)
+        .bind(req.preserve_boundaries)
+        .bind(req.chunking_config.unwrap_or(serde_json::json!({})))
+        .bind(req.recommended_config_id)
+        .bind(&req.content_types)
+        .bind(&req.tree_sitter_language)
+        .bind(pipeline_policy)
+        .bind(&req.metadata_schema)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(id)
+    }
+
     /// Helper: detect document type from content magic patterns (issue #124, #199).
     /// Scores each type by number of matching patterns rather than first-match-wins.
     async fn detect_by_content(&self, text: &str) -> Result<Option<DetectDocumentTypeResult>> {
@@ -540,7 +667,8 @@ impl PgDocumentTypeRepository {
                    content_types, tree_sitter_language,
                    extraction_strategy::TEXT, extraction_config, requires_attachment, attachment_generates_content,
                    is_system, is_active,
-                   created_at, updated_at, created_by, agentic_config
+                   created_at, updated_at, created_by, agentic_config, pipeline_policy,
+                   metadata_schema
             FROM document_type
             WHERE is_active = TRUE AND $1 = ANY(mime_types)
             ORDER BY
@@ -618,6 +746,13 @@ impl PgDocumentTypeRepository {
             updated_at: row.get("updated_at"),
             created_by: row.get("created_by"),
             agentic_config,
+            pipeline_policy: Self::parse_pipeline_policy(
+                row.try_get::<serde_json::Value, _>("pipeline_policy").ok(),
+            ),
+            metadata_schema: row
+                .try_get::<Option<serde_json::Value>, _>("metadata_schema")
+                .ok()
+                .flatten(),
         }
     }
 
@@ -659,6 +794,28 @@ mod tests {
         assert!(!message.contains("path@example.com"));
     }
 
+    #[test]
+    fn note_metadata_is_validated_against_the_schema() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {"status": {"enum": ["active", "done"]}},
+            "required": ["status"]
+        });
+
+        assert!(validate_note_metadata(&schema, &serde_json::json!({"status": "active"})).is_ok());
+
+        let err = validate_note_metadata(&schema, &serde_json::json!({"status": "sk-live-123"}))
+            .unwrap_err();
+        let Error::InvalidInput(message) = err else {
+            panic!("expected invalid-input error");
+        };
+        assert!(message.contains("error_count=1"));
+        assert!(!message.contains("sk-live"));
+        assert!(!message.contains("status"));
+
+        assert!(check_metadata_schema(&serde_json::json!({"type": "no-such-type"})).is_err());
+    }
+
     #[test]
     fn document_type_in_use_errors_report_metadata_without_raw_values() {
         let raw_name = "customer-report-sk-live-123/path@example.com";
@@ -752,11 +752,12 @@ impl PgEmbeddingSetRepository {
         }
     }
 
-    /// Create a new embedding config.
-    pub async fn create_config(
+    /// Create a new embedding config within an existing transaction.
+    pub async fn create_config_tx(
         &self,
+        tx: &mut Transaction<'_, Postgres>,
         request: CreateEmbeddingConfigRequest,
-    ) -> Result<EmbeddingConfigProfile> {
+    ) -> Result<Uuid> {
         let id = new_v7();
         let now = Utc::now();
         // Bind matryoshka_dims as Vec<i32> directly (issue #126 EMB-017)
@@ -795,10 +796,22 @@ impl PgEmbeddingSetRepository {
         .bind(&request.provider_config)
         .bind(&request.content_types)
         .bind(serde_json::to_value(&request.document_composition).unwrap_or_default())
-        .execute(&self.pool)
+        .execute(&mut **tx)
         .await
         .map_err(Error::Database)?;
 
+        Ok(id)
+    }
+
+    /// Create a new embedding config.
+    pub async fn create_config(
+        &self,
+        request: CreateEmbeddingConfigRequest,
+    ) -> Result<EmbeddingConfigProfile> {
+        let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        let id = self.create_config_tx(&mut tx, request).await?;
+        tx.commit().await.map_err(Error::Database)?;
+
         // Fetch and return the created config
         self.get_config(id)
             .await?
@@ -1,12 +1,15 @@
 //! Embedding repository implementation.
 
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use chrono::Utc;
 use pgvector::Vector;
 use sqlx::{Pool, Postgres, Row, Transaction};
+use tracing::instrument;
 use uuid::Uuid;
 
-use matric_core::{new_v7, Embedding, EmbeddingRepository, Error, Result, SearchHit};
+use matric_core::{new_v7, Embedding, EmbeddingRepository, Error, MediaSegment, Result, SearchHit};
 
 /// PostgreSQL implementation of EmbeddingRepository.
 pub struct PgEmbeddingRepository {
@@ -66,6 +69,7 @@ impl EmbeddingRepository for PgEmbeddingRepository {
         Ok(())
     }
 
+    #[instrument(skip_all, fields(subsystem = "database", component = "embeddings", op = "find_similar", limit = limit))]
     async fn find_similar(
         &self,
         query_vec: &Vector,
@@ -284,6 +288,7 @@ impl PgEmbeddingRepository {
     }
 
     /// Find similar embeddings within a specific embedding set.
+    #[instrument(skip_all, fields(subsystem = "database", component = "embeddings", op = "find_similar_in_set", limit = limit))]
     pub async fn find_similar_in_set(
         &self,
         query_vec: &Vector,
@@ -359,6 +364,7 @@ impl PgEmbeddingRepository {
     ///
     /// This applies strict tag filtering to ensure data isolation in multi-tenant scenarios.
     /// Only notes that match the strict filter criteria will be included in results.
+    #[instrument(skip_all, fields(subsystem = "database", component = "embeddings", op = "find_similar_with_strict_filter", limit = limit))]
     pub async fn find_similar_with_strict_filter(
         &self,
         query_vec: &Vector,
@@ -824,10 +830,236 @@ impl PgEmbeddingRepository {
 
         Ok(results)
     }
+
+    /// Load the transcript segments of a note's attachments, in attachment
+    /// then segment order, paired with each segment's text.
+    ///
+    /// Segments come from the `transcript_segments` extraction metadata the
+    /// audio and video pipelines write; malformed entries are skipped.
+    pub async fn transcript_segments_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<Vec<(MediaSegment, String)>> {
+        let rows = sqlx::query(
+            "SELECT id, extracted_metadata->'transcript_segments' AS segments
+             FROM attachment
+             WHERE note_id = $1
+               AND jsonb_typeof(extracted_metadata->'transcript_segments') = 'array'
+             ORDER BY created_at, id",
+        )
+        .bind(note_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let mut segments = Vec::new();
+        for row in rows {
+            let attachment_id: Uuid = row.get("id");
+            let values: Vec<serde_json::Value> =
+                serde_json::from_value(row.get("segments")).unwrap_or_default();
+            for value in values {
+                let (Some(start_secs), Some(end_secs), Some(text)) = (
+                    value.get("start_secs").and_then(|v| v.as_f64()),
+                    value.get("end_secs").and_then(|v| v.as_f64()),
+                    value.get("text").and_then(|v| v.as_str()),
+                ) else {
+                    continue;
+                };
+                segments.push((
+                    MediaSegment {
+                        attachment_id,
+                        start_secs,
+                        end_secs,
+                    },
+                    text.to_string(),
+                ));
+            }
+        }
+        Ok(segments)
+    }
+
+    /// Record the transcript playback range of each stored chunk.
+    ///
+    /// `segments[i]` belongs to chunk `i`; `None` entries are left NULL.
+    /// `embedding_set_id` of `None` targets the default set.
+    pub async fn set_media_segments_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        embedding_set_id: Option<Uuid>,
+        segments: &[Option<MediaSegment>],
+    ) -> Result<()> {
+        let mut chunk_indexes = Vec::new();
+        let mut attachment_ids = Vec::new();
+        let mut starts = Vec::new();
+        let mut ends = Vec::new();
+        for (i, segment) in segments.iter().enumerate() {
+            if let Some(segment) = segment {
+                chunk_indexes.push(i as i32);
+                attachment_ids.push(segment.attachment_id);
+                starts.push(segment.start_secs);
+                ends.push(segment.end_secs);
+            }
+        }
+        if chunk_indexes.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "UPDATE embedding e
+             SET attachment_id = s.attachment_id, start_secs = s.start_secs, end_secs = s.end_secs
+             FROM UNNEST($3::int[], $4::uuid[], $5::float8[], $6::float8[])
+                  AS s(chunk_index, attachment_id, start_secs, end_secs)
+             WHERE e.note_id = $1
+               AND e.embedding_set_id = COALESCE($2, get_default_embedding_set_id())
+               AND e.chunk_index = s.chunk_index",
+        )
+        .bind(note_id)
+        .bind(embedding_set_id)
+        .bind(&chunk_indexes)
+        .bind(&attachment_ids)
+        .bind(&starts)
+        .bind(&ends)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Chunk vectors of a note in an embedding set (`embedding_set_id`, or
+    /// the default set), in chunk order.
+    pub async fn vectors_for_note(
+        &self,
+        note_id: Uuid,
+        embedding_set_id: Option<Uuid>,
+    ) -> Result<Vec<Vector>> {
+        sqlx::query_scalar(
+            "SELECT vector FROM embedding
+             WHERE note_id = $1
+               AND embedding_set_id = COALESCE($2, get_default_embedding_set_id())
+             ORDER BY chunk_index",
+        )
+        .bind(note_id)
+        .bind(embedding_set_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Playback range of the best-matching transcript chunk of each note.
+    ///
+    /// Chunks are ranked by distance to `query_vec` when given, otherwise by
+    /// full-text rank against `query`. Only notes with timestamped chunks in
+    /// the searched set (`embedding_set_id`, or the default set) appear.
+    pub async fn media_segments_for_notes(
+        &self,
+        note_ids: &[Uuid],
+        embedding_set_id: Option<Uuid>,
+        query: &str,
+        query_vec: Option<&Vector>,
+    ) -> Result<HashMap<Uuid, MediaSegment>> {
+        if note_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let ranking = if query_vec.is_some() {
+            "e.vector <=> $3::vector"
+        } else {
+            "ts_rank_cd(to_tsvector('public.matric_english', e.text),
+                        websearch_to_tsquery('public.matric_english', $3)) DESC"
+        };
+        let sql = format!(
+            "SELECT DISTINCT ON (e.note_id) e.note_id, e.attachment_id, e.start_secs, e.end_secs
+             FROM embedding e
+             WHERE e.note_id = ANY($1)
+               AND e.embedding_set_id = COALESCE($2, get_default_embedding_set_id())
+               AND e.attachment_id IS NOT NULL
+               AND e.start_secs IS NOT NULL
+               AND e.end_secs IS NOT NULL
+             ORDER BY e.note_id, {ranking}, e.chunk_index"
+        );
+        let rows = sqlx::query(&sql).bind(note_ids).bind(embedding_set_id);
+        let rows = match query_vec {
+            Some(query_vec) => rows.bind(query_vec),
+            None => rows.bind(query),
+        }
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get("note_id"),
+                    MediaSegment {
+                        attachment_id: row.get("attachment_id"),
+                        start_secs: row.get("start_secs"),
+                        end_secs: row.get("end_secs"),
+                    },
+                )
+            })
+            .collect())
+    }
 }
 
 /// Utility functions for embedding operations.
 pub mod utils {
+    use crate::chunking::Chunk;
+    use matric_core::MediaSegment;
+
+    /// Map chunks to the playback range of the transcript segments they cover.
+    ///
+    /// Each segment's text is located in `content` in order, restarting per
+    /// attachment; a chunk's range spans every located segment of one
+    /// attachment that overlaps the chunk's byte offsets. Chunks outside any
+    /// transcript map to `None`.
+    pub fn chunk_media_segments(
+        content: &str,
+        chunks: &[Chunk],
+        segments: &[(MediaSegment, String)],
+    ) -> Vec<Option<MediaSegment>> {
+        let mut located = Vec::new();
+        let mut cursor = 0;
+        let mut attachment_id = None;
+        for (segment, text) in segments {
+            if attachment_id != Some(segment.attachment_id) {
+                attachment_id = Some(segment.attachment_id);
+                cursor = 0;
+            }
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            if let Some(pos) = content[cursor..].find(text) {
+                let start = cursor + pos;
+                cursor = start + text.len();
+                located.push((start, cursor, *segment));
+            }
+        }
+
+        chunks
+            .iter()
+            .map(|chunk| {
+                let mut range: Option<MediaSegment> = None;
+                for (start, end, segment) in &located {
+                    if *start >= chunk.end_offset || *end <= chunk.start_offset {
+                        continue;
+                    }
+                    match range.as_mut() {
+                        None => range = Some(*segment),
+                        Some(range) if range.attachment_id == segment.attachment_id => {
+                            range.start_secs = range.start_secs.min(segment.start_secs);
+                            range.end_secs = range.end_secs.max(segment.end_secs);
+                        }
+                        Some(_) => {}
+                    }
+                }
+                range
+            })
+            .collect()
+    }
+
     /// Chunk text into pieces of at most `max_chars` characters.
     pub fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
         if text.is_empty() {
@@ -930,6 +1162,46 @@ mod tests {
         assert!(chunks.is_empty());
     }
 
+    #[test]
+    fn chunk_media_segments_spans_covered_transcript_segments() {
+        use crate::chunking::Chunk;
+        use matric_core::MediaSegment;
+        use uuid::Uuid;
+
+        let attachment_id = Uuid::nil();
+        let segment = |start_secs, end_secs, text: &str| {
+            (
+                MediaSegment {
+                    attachment_id,
+                    start_secs,
+                    end_secs,
+                },
+                text.to_string(),
+            )
+        };
+        let content = "# Call\n\n## Transcript\n\nHello there. How are you? Fine, thanks.";
+        let start = content.find("Hello").unwrap();
+        let middle = content.find("Fine").unwrap();
+        let chunks = vec![
+            Chunk::new("# Call".to_string(), 0, 6),
+            Chunk::new(content[start..middle].to_string(), start, middle),
+            Chunk::new(content[middle..].to_string(), middle, content.len()),
+        ];
+        let segments = vec![
+            segment(0.0, 1.5, " Hello there."),
+            segment(1.5, 3.0, "How are you?"),
+            segment(3.0, 4.2, "Fine, thanks."),
+        ];
+
+        let ranges = chunk_media_segments(content, &chunks, &segments);
+
+        assert_eq!(ranges[0], None);
+        let second = ranges[1].unwrap();
+        assert_eq!((second.start_secs, second.end_secs), (0.0, 3.0));
+        let third = ranges[2].unwrap();
+        assert_eq!((third.start_secs, third.end_secs), (3.0, 4.2));
+    }
+
     #[test]
     fn test_chunk_with_overlap() {
         let text = "ABCDEFGHIJ";
@@ -0,0 +1,280 @@
+//! Entity registry repository.
+//!
+//! The registry (`entity`) holds one row per distinct entity in an archive;
+//! the note↔entity edges are `note_entity` rows matched on entity type and
+//! normalized name. Both are archive-scoped. The `*_tx` methods take a
+//! transaction that has already been pointed at the archive schema;
+//! [`PgEntityRepository::facets_for_notes`] reads through the repository's
+//! pool, which callers pin to the archive. `security`, when given, admits
+//! only the notes it allows, so entities and counts reflect those notes.
+
+use sqlx::{Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    normalize_entity_name, EntityFacet, EntityListFilter, EntityNoteRef, EntityType, Error,
+    ExtractedEntity, NamedEntity, NamedEntityDetail, NamedEntityList, Result, StrictSecurityFilter,
+};
+
+use crate::unified_filter::{
+    bind_filter_param, security_clause, security_filter_query, QueryParam,
+};
+
+/// Most notes listed on an entity detail.
+const ENTITY_DETAIL_MAX_NOTES: i64 = 100;
+
+/// Join from registry rows to the notes that mention them; `e` is the
+/// registry, `ne` the edges, `n` live notes.
+const ENTITY_NOTE_JOIN: &str = "
+    JOIN note_entity ne
+      ON ne.entity_type = e.entity_type AND ne.normalized_text = e.normalized_name
+    JOIN note n ON n.id = ne.note_id AND n.deleted_at IS NULL";
+
+/// PostgreSQL repository for the entity registry.
+#[derive(Clone)]
+pub struct PgEntityRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgEntityRepository {
+    /// Create a new entity repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Replace a note's extracted entities.
+    ///
+    /// Registers new entities, rewrites the note's edges, and removes
+    /// registry entries that no note mentions any more. Returns the number of
+    /// edges stored.
+    pub async fn replace_note_entities_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        entities: &[ExtractedEntity],
+    ) -> Result<usize> {
+        let previous = sqlx::query(
+            "DELETE FROM note_entity WHERE note_id = $1
+             RETURNING entity_type::text AS entity_type, normalized_text",
+        )
+        .bind(note_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let mut stored = 0;
+        for entity in entities {
+            let normalized = normalize_entity_name(&entity.name);
+            if normalized.is_empty() {
+                continue;
+            }
+            let entity_type = entity.entity_type.to_string();
+            sqlx::query(
+                "INSERT INTO entity (entity_type, name, normalized_name)
+                 VALUES ($1::entity_type, $2, $3)
+                 ON CONFLICT (entity_type, normalized_name) DO NOTHING",
+            )
+            .bind(&entity_type)
+            .bind(&entity.name)
+            .bind(&normalized)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            sqlx::query(
+                "INSERT INTO note_entity (note_id, entity_text, entity_type, normalized_text)
+                 VALUES ($1, $2, $3::entity_type, $4)",
+            )
+            .bind(note_id)
+            .bind(&entity.name)
+            .bind(&entity_type)
+            .bind(&normalized)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            stored += 1;
+        }
+
+        let (old_types, old_names): (Vec<String>, Vec<String>) = previous
+            .iter()
+            .filter_map(|row| {
+                let name: Option<String> = row.get("normalized_text");
+                Some((row.get("entity_type"), name?))
+            })
+            .unzip();
+        if !old_types.is_empty() {
+            sqlx::query(
+                "DELETE FROM entity e
+                 USING UNNEST($1::text[], $2::text[]) AS old(entity_type, normalized_name)
+                 WHERE e.entity_type::text = old.entity_type
+                   AND e.normalized_name = old.normalized_name
+                   AND NOT EXISTS (
+                       SELECT 1 FROM note_entity ne
+                       WHERE ne.entity_type = e.entity_type
+                         AND ne.normalized_text = e.normalized_name
+                   )",
+            )
+            .bind(&old_types)
+            .bind(&old_names)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+        Ok(stored)
+    }
+
+    /// List entities mentioned by live notes, most mentioned first.
+    pub async fn list_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        filter: &EntityListFilter,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<NamedEntityList> {
+        let security = security_filter_query(security, 4);
+        let entity_type = filter.entity_type.map(|t| t.to_string());
+        let q = filter
+            .q
+            .as_deref()
+            .map(normalize_entity_name)
+            .filter(|q| !q.is_empty());
+        let sql = format!(
+            "SELECT e.id, e.entity_type::text AS entity_type, e.name, e.created_at_utc,
+                    COUNT(DISTINCT n.id) AS note_count,
+                    COUNT(*) OVER () AS total
+             FROM entity e {ENTITY_NOTE_JOIN}
+             WHERE ($1::text IS NULL OR e.entity_type::text = $1)
+               AND ($2::text IS NULL OR strpos(e.normalized_name, $2) > 0)
+               {}
+             GROUP BY e.id
+             ORDER BY note_count DESC, e.name, e.id
+             LIMIT $3 OFFSET $4",
+            security_clause(&security)
+        );
+        let mut query = sqlx::query(&sql)
+            .bind(entity_type)
+            .bind(q)
+            .bind(filter.limit)
+            .bind(filter.offset);
+        for param in security.iter().flat_map(|result| &result.params) {
+            query = bind_filter_param!(query, param);
+        }
+        let rows = query.fetch_all(&mut **tx).await.map_err(Error::Database)?;
+
+        let total = rows.first().map(|row| row.get("total")).unwrap_or(0);
+        Ok(NamedEntityList {
+            entities: rows.iter().map(entity_from_row).collect(),
+            total,
+        })
+    }
+
+    /// Get an entity and the live notes that mention it.
+    ///
+    /// Returns `None` when the entity does not exist or no live note that
+    /// `security` allows mentions it.
+    pub async fn get_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Option<NamedEntityDetail>> {
+        let entity_security = security_filter_query(security, 1);
+        let sql = format!(
+            "SELECT e.id, e.entity_type::text AS entity_type, e.name, e.created_at_utc,
+                    COUNT(DISTINCT n.id) AS note_count
+             FROM entity e {ENTITY_NOTE_JOIN}
+             WHERE e.id = $1
+               {}
+             GROUP BY e.id",
+            security_clause(&entity_security)
+        );
+        let mut query = sqlx::query(&sql).bind(id);
+        for param in entity_security.iter().flat_map(|result| &result.params) {
+            query = bind_filter_param!(query, param);
+        }
+        let Some(row) = query
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(Error::Database)?
+        else {
+            return Ok(None);
+        };
+
+        let security = security_filter_query(security, 2);
+        let sql = format!(
+            "SELECT DISTINCT n.id AS note_id, n.title, n.updated_at_utc
+             FROM entity e {ENTITY_NOTE_JOIN}
+             WHERE e.id = $1
+               {}
+             ORDER BY n.updated_at_utc DESC, n.id
+             LIMIT $2",
+            security_clause(&security)
+        );
+        let mut query = sqlx::query(&sql).bind(id).bind(ENTITY_DETAIL_MAX_NOTES);
+        for param in security.iter().flat_map(|result| &result.params) {
+            query = bind_filter_param!(query, param);
+        }
+        let notes = query.fetch_all(&mut **tx).await.map_err(Error::Database)?;
+
+        Ok(Some(NamedEntityDetail {
+            entity: entity_from_row(&row),
+            notes: notes
+                .iter()
+                .map(|row| EntityNoteRef {
+                    note_id: row.get("note_id"),
+                    title: row.get("title"),
+                    updated_at_utc: row.get("updated_at_utc"),
+                })
+                .collect(),
+        }))
+    }
+
+    /// The entities most mentioned across `note_ids`, at most `limit`.
+    pub async fn facets_for_notes(
+        &self,
+        note_ids: &[Uuid],
+        limit: i64,
+    ) -> Result<Vec<EntityFacet>> {
+        if note_ids.is_empty() || limit <= 0 {
+            return Ok(Vec::new());
+        }
+        let rows = sqlx::query(&format!(
+            "SELECT e.id, e.entity_type::text AS entity_type, e.name,
+                    COUNT(DISTINCT n.id) AS note_count
+             FROM entity e {ENTITY_NOTE_JOIN}
+             WHERE ne.note_id = ANY($1)
+             GROUP BY e.id
+             ORDER BY note_count DESC, e.name, e.id
+             LIMIT $2"
+        ))
+        .bind(note_ids)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| EntityFacet {
+                entity_id: row.get("id"),
+                entity_type: entity_type_from_row(row),
+                name: row.get("name"),
+                note_count: row.get("note_count"),
+            })
+            .collect())
+    }
+}
+
+fn entity_type_from_row(row: &sqlx::postgres::PgRow) -> EntityType {
+    row.get::<String, _>("entity_type")
+        .parse()
+        .unwrap_or(EntityType::Other)
+}
+
+fn entity_from_row(row: &sqlx::postgres::PgRow) -> NamedEntity {
+    NamedEntity {
+        id: row.get("id"),
+        entity_type: entity_type_from_row(row),
+        name: row.get("name"),
+        note_count: row.get("note_count"),
+        created_at_utc: row.get("created_at_utc"),
+    }
+}
@@ -0,0 +1,243 @@
+//! Durable event history for the activity feed.
+//!
+//! Envelopes are stored whole in `public.events` (shared across memories) and
+//! read back newest-first with keyset pagination on the UUIDv7 `event_id`, or
+//! oldest-first after a cursor for SSE/WebSocket history replay.
+
+use chrono::{DateTime, Utc};
+use serde_json::Value as JsonValue;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use matric_core::{Error, EventEnvelope, Result};
+
+/// A persisted event envelope.
+#[derive(Clone, PartialEq, sqlx::FromRow)]
+pub struct StoredEvent {
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub occurred_at: DateTime<Utc>,
+    pub memory: Option<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    /// The serialized [`EventEnvelope`], as delivered over SSE.
+    pub envelope: JsonValue,
+}
+
+impl std::fmt::Debug for StoredEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoredEvent")
+            .field("event_id_present", &true)
+            .field("event_type_len", &text_len(&self.event_type))
+            .field("occurred_at", &self.occurred_at)
+            .field("memory_len", &self.memory.as_deref().map(text_len))
+            .field(
+                "entity_type_len",
+                &self.entity_type.as_deref().map(text_len),
+            )
+            .field("entity_id_len", &self.entity_id.as_deref().map(text_len))
+            .finish()
+    }
+}
+
+/// Filters for reading the event history.
+#[derive(Clone, Default)]
+pub struct EventHistoryFilter {
+    /// Only events for this memory plus system events; `None` reads all.
+    pub memory: Option<String>,
+    /// Event type prefixes (`note` matches `note.created`); empty reads all.
+    pub types: Vec<String>,
+    /// Exact entity ID.
+    pub entity_id: Option<String>,
+}
+
+impl std::fmt::Debug for EventHistoryFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventHistoryFilter")
+            .field("memory_len", &self.memory.as_deref().map(text_len))
+            .field("type_count", &self.types.len())
+            .field("entity_id_len", &self.entity_id.as_deref().map(text_len))
+            .finish()
+    }
+}
+
+fn text_len(value: &str) -> usize {
+    value.chars().count()
+}
+
+/// `LIKE` patterns matching the namespaced children of each type prefix.
+fn child_type_patterns(types: &[String]) -> Vec<String> {
+    types
+        .iter()
+        .map(|prefix| {
+            let escaped = prefix
+                .to_lowercase()
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            format!("{escaped}.%")
+        })
+        .collect()
+}
+
+fn lowercase_types(types: &[String]) -> Vec<String> {
+    types.iter().map(|t| t.to_lowercase()).collect()
+}
+
+const STORED_EVENT_COLUMNS: &str =
+    "event_id, event_type, occurred_at, memory, entity_type, entity_id, envelope";
+
+/// Shared filter predicate; binds `$1` memory, `$2` types, `$3` type
+/// patterns, `$4` entity id.
+const FILTER_PREDICATE: &str = "($1::text IS NULL OR memory = $1 OR memory IS NULL)
+       AND (cardinality($2::text[]) = 0
+            OR lower(event_type) = ANY($2)
+            OR lower(event_type) LIKE ANY($3))
+       AND ($4::text IS NULL OR entity_id = $4)";
+
+/// PostgreSQL repository for the durable event history.
+#[derive(Clone)]
+pub struct PgEventHistoryRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgEventHistoryRepository {
+    /// Create a new event history repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Persist an envelope. Returns `false` when it was already stored.
+    pub async fn record(&self, envelope: &EventEnvelope) -> Result<bool> {
+        let json =
+            serde_json::to_value(envelope).map_err(|e| Error::Serialization(e.to_string()))?;
+        let result = sqlx::query(
+            "INSERT INTO public.events
+                 (event_id, event_type, occurred_at, memory, entity_type, entity_id, envelope)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (event_id) DO NOTHING",
+        )
+        .bind(envelope.event_id)
+        .bind(&envelope.event_type)
+        .bind(envelope.occurred_at)
+        .bind(envelope.memory.as_deref())
+        .bind(envelope.entity_type.as_deref())
+        .bind(envelope.entity_id.as_deref())
+        .bind(json)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Events matching `filter`, newest first, older than `before` when set.
+    pub async fn list(
+        &self,
+        filter: &EventHistoryFilter,
+        before: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<StoredEvent>> {
+        sqlx::query_as::<_, StoredEvent>(&format!(
+            "SELECT {STORED_EVENT_COLUMNS}
+             FROM public.events
+             WHERE {FILTER_PREDICATE}
+               AND ($5::uuid IS NULL OR event_id < $5)
+             ORDER BY event_id DESC
+             LIMIT $6"
+        ))
+        .bind(filter.memory.as_deref())
+        .bind(lowercase_types(&filter.types))
+        .bind(child_type_patterns(&filter.types))
+        .bind(filter.entity_id.as_deref())
+        .bind(before)
+        .bind(limit.max(0))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Events matching `filter` recorded after `after`, oldest first.
+    ///
+    /// Returns `None` when `after` is not in the history (never persisted or
+    /// already pruned), so callers can tell an unknown cursor from no news.
+    pub async fn replay_after(
+        &self,
+        filter: &EventHistoryFilter,
+        after: Uuid,
+        limit: i64,
+    ) -> Result<Option<Vec<StoredEvent>>> {
+        let known: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM public.events WHERE event_id = $1)")
+                .bind(after)
+                .fetch_one(&self.pool)
+                .await
+                .map_err(Error::Database)?;
+        if !known {
+            return Ok(None);
+        }
+
+        let events = sqlx::query_as::<_, StoredEvent>(&format!(
+            "SELECT {STORED_EVENT_COLUMNS}
+             FROM public.events
+             WHERE {FILTER_PREDICATE}
+               AND event_id > $5
+             ORDER BY event_id ASC
+             LIMIT $6"
+        ))
+        .bind(filter.memory.as_deref())
+        .bind(lowercase_types(&filter.types))
+        .bind(child_type_patterns(&filter.types))
+        .bind(filter.entity_id.as_deref())
+        .bind(after)
+        .bind(limit.max(0))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(Some(events))
+    }
+
+    /// Delete events that occurred before `cutoff`. Returns the rows removed.
+    pub async fn prune_before(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM public.events WHERE occurred_at < $1")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn child_type_patterns_escape_like_wildcards() {
+        let patterns = child_type_patterns(&["Note".to_string(), "job_x%".to_string()]);
+        assert_eq!(patterns, vec!["note.%", "job\\_x\\%.%"]);
+    }
+
+    #[test]
+    fn debug_redacts_memory_and_entity() {
+        let stored = StoredEvent {
+            event_id: Uuid::nil(),
+            event_type: "note.created".to_string(),
+            occurred_at: Utc::now(),
+            memory: Some("secret-archive".to_string()),
+            entity_type: Some("note".to_string()),
+            entity_id: Some("entity-secret".to_string()),
+            envelope: serde_json::json!({ "payload": { "title": "private title" } }),
+        };
+        let rendered = format!("{stored:?}");
+        assert!(!rendered.contains("secret-archive"));
+        assert!(!rendered.contains("entity-secret"));
+        assert!(!rendered.contains("private title"));
+
+        let filter = EventHistoryFilter {
+            memory: Some("secret-archive".to_string()),
+            types: vec!["note".to_string()],
+            entity_id: None,
+        };
+        assert!(!format!("{filter:?}").contains("secret-archive"));
+    }
+}
@@ -0,0 +1,541 @@
+//! Sync peer repository and the archive change feed.
+//!
+//! Peers live in the shared `public` schema and are claimed by the scheduler
+//! with the same compare-and-set on `next_run_at_utc` as backup policies.
+//! The change feed and its apply side run inside a transaction the caller
+//! scopes to one memory archive, so the same queries serve every archive.
+//!
+//! Snapshots travel without attachment rows: attachments and their blobs
+//! stay local to each instance, and an applied snapshot keeps the receiver's
+//! existing attachments for the note.
+
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgRow, Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    new_v7, ConflictStrategy, CreateSyncPeerRequest, Error, Result, SyncApplyResult, SyncChange,
+    SyncCursor, SyncDecision, SyncPeer,
+};
+
+use crate::PgBackupStateRepository;
+
+const SYNC_PEER_COLUMNS: &str = "id, name, base_url, api_token IS NOT NULL AS has_api_token, \
+     remote_memory, local_memory, direction, conflict_strategy, schedule, enabled, \
+     pull_cursor_at, pull_cursor_note_id, push_cursor_at, push_cursor_note_id, next_run_at_utc, \
+     last_sync_at_utc, last_status, last_error, created_at_utc, updated_at_utc";
+
+/// When each note last changed: live and soft-deleted notes by their
+/// timestamps, hard-deleted notes by their tombstone.
+const NOTE_CHANGES_SQL: &str = "SELECT n.id AS note_id, \
+         GREATEST(n.updated_at_utc, n.deleted_at) AS changed_at, \
+         FALSE AS tombstone \
+     FROM note n \
+     UNION ALL \
+     SELECT t.note_id, t.deleted_at, TRUE FROM note_tombstone t";
+
+/// Peer status while a run is in progress.
+pub const SYNC_RUN_RUNNING: &str = "running";
+/// Peer status after a run completed.
+pub const SYNC_RUN_SUCCEEDED: &str = "succeeded";
+/// Peer status after a run failed.
+pub const SYNC_RUN_FAILED: &str = "failed";
+
+/// Which cursor of a peer to advance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncCursorKind {
+    Pull,
+    Push,
+}
+
+/// PostgreSQL repository for sync peers and the archive change feed.
+pub struct PgSyncPeerRepository {
+    pool: Pool<Postgres>,
+    snapshots: PgBackupStateRepository,
+}
+
+impl PgSyncPeerRepository {
+    /// Create a new sync peer repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self {
+            snapshots: PgBackupStateRepository::new(pool.clone()),
+            pool,
+        }
+    }
+
+    /// List all peers by name.
+    pub async fn list(&self) -> Result<Vec<SyncPeer>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {SYNC_PEER_COLUMNS} FROM sync_peer ORDER BY name"
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows.iter().map(peer_from_row).collect())
+    }
+
+    /// Get a peer by ID.
+    pub async fn get(&self, id: Uuid) -> Result<Option<SyncPeer>> {
+        let row = sqlx::query(&format!(
+            "SELECT {SYNC_PEER_COLUMNS} FROM sync_peer WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.as_ref().map(peer_from_row))
+    }
+
+    /// The API token stored for a peer, if any.
+    pub async fn api_token(&self, id: Uuid) -> Result<Option<String>> {
+        sqlx::query_scalar("SELECT api_token FROM sync_peer WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map(Option::flatten)
+            .map_err(Error::Database)
+    }
+
+    /// Register a peer whose first scheduled run is at `next_run_at`.
+    pub async fn create(
+        &self,
+        req: &CreateSyncPeerRequest,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<SyncPeer> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO sync_peer
+                (id, name, base_url, api_token, remote_memory, local_memory, direction,
+                 conflict_strategy, schedule, enabled, next_run_at_utc, created_at_utc,
+                 updated_at_utc)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW(), NOW())
+            RETURNING {SYNC_PEER_COLUMNS}
+            "#
+        ))
+        .bind(new_v7())
+        .bind(&req.name)
+        .bind(&req.base_url)
+        .bind(&req.api_token)
+        .bind(&req.remote_memory)
+        .bind(&req.local_memory)
+        .bind(&req.direction)
+        .bind(&req.conflict_strategy)
+        .bind(&req.schedule)
+        .bind(req.enabled)
+        .bind(next_run_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| peer_insert_error(e, &req.name))?;
+
+        Ok(peer_from_row(&row))
+    }
+
+    /// Persist the mutable fields of `peer`, replacing the API token when
+    /// `api_token` is set (`Some(None)` clears it). Cursors are left to
+    /// running syncs unless `reset_cursors` clears them.
+    ///
+    /// Returns `None` when the peer no longer exists.
+    pub async fn update(
+        &self,
+        peer: &SyncPeer,
+        api_token: Option<Option<&str>>,
+        reset_cursors: bool,
+    ) -> Result<Option<SyncPeer>> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE sync_peer
+            SET base_url = $2, remote_memory = $3, local_memory = $4, direction = $5,
+                conflict_strategy = $6, schedule = $7, enabled = $8, next_run_at_utc = $9,
+                pull_cursor_at = CASE WHEN $10 THEN NULL ELSE pull_cursor_at END,
+                pull_cursor_note_id = CASE WHEN $10 THEN NULL ELSE pull_cursor_note_id END,
+                push_cursor_at = CASE WHEN $10 THEN NULL ELSE push_cursor_at END,
+                push_cursor_note_id = CASE WHEN $10 THEN NULL ELSE push_cursor_note_id END,
+                api_token = CASE WHEN $11 THEN $12 ELSE api_token END,
+                updated_at_utc = NOW()
+            WHERE id = $1
+            RETURNING {SYNC_PEER_COLUMNS}
+            "#
+        ))
+        .bind(peer.id)
+        .bind(&peer.base_url)
+        .bind(&peer.remote_memory)
+        .bind(&peer.local_memory)
+        .bind(&peer.direction)
+        .bind(&peer.conflict_strategy)
+        .bind(&peer.schedule)
+        .bind(peer.enabled)
+        .bind(peer.next_run_at_utc)
+        .bind(reset_cursors)
+        .bind(api_token.is_some())
+        .bind(api_token.flatten())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.as_ref().map(peer_from_row))
+    }
+
+    /// Delete a peer. Data already replicated is left in place.
+    pub async fn delete(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM sync_peer WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Enabled peers whose next run is at or before `now`.
+    pub async fn list_due(&self, now: DateTime<Utc>) -> Result<Vec<SyncPeer>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {SYNC_PEER_COLUMNS} FROM sync_peer \
+             WHERE enabled AND next_run_at_utc <= $1 ORDER BY next_run_at_utc, id"
+        ))
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows.iter().map(peer_from_row).collect())
+    }
+
+    /// Advance a due peer to its next slot.
+    ///
+    /// Succeeds only if `next_run_at_utc` still equals `claimed`, so exactly
+    /// one scheduler queues each slot.
+    pub async fn claim_due(
+        &self,
+        id: Uuid,
+        claimed: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE sync_peer SET next_run_at_utc = $3 \
+             WHERE id = $1 AND next_run_at_utc = $2",
+        )
+        .bind(id)
+        .bind(claimed)
+        .bind(next_run_at)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the start of a run.
+    pub async fn start_run(&self, id: Uuid) -> Result<()> {
+        sqlx::query(
+            "UPDATE sync_peer SET last_sync_at_utc = NOW(), last_status = $2, last_error = NULL \
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(SYNC_RUN_RUNNING)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Record a successful run.
+    pub async fn finish_run(&self, id: Uuid) -> Result<()> {
+        self.complete_run(id, SYNC_RUN_SUCCEEDED, None).await
+    }
+
+    /// Record a failed run.
+    pub async fn fail_run(&self, id: Uuid, error: &str) -> Result<()> {
+        self.complete_run(id, SYNC_RUN_FAILED, Some(error)).await
+    }
+
+    async fn complete_run(&self, id: Uuid, status: &str, error: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE sync_peer SET last_status = $2, last_error = $3 WHERE id = $1")
+            .bind(id)
+            .bind(status)
+            .bind(error)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Save how far a run got, so an interrupted run resumes from there.
+    pub async fn save_cursor(
+        &self,
+        id: Uuid,
+        kind: SyncCursorKind,
+        cursor: SyncCursor,
+    ) -> Result<()> {
+        let sql = match kind {
+            SyncCursorKind::Pull => {
+                "UPDATE sync_peer SET pull_cursor_at = $2, pull_cursor_note_id = $3 WHERE id = $1"
+            }
+            SyncCursorKind::Push => {
+                "UPDATE sync_peer SET push_cursor_at = $2, push_cursor_note_id = $3 WHERE id = $1"
+            }
+        };
+        sqlx::query(sql)
+            .bind(id)
+            .bind(cursor.changed_at)
+            .bind(cursor.note_id)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Up to `limit` changes after `after`, oldest first.
+    ///
+    /// Tombstones carry no snapshot; snapshots omit attachment rows.
+    pub async fn changes_since_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        after: Option<SyncCursor>,
+        limit: i64,
+    ) -> Result<Vec<SyncChange>> {
+        let positions: Vec<(Uuid, DateTime<Utc>, bool)> = sqlx::query_as(&format!(
+            "SELECT note_id, changed_at, tombstone FROM ({NOTE_CHANGES_SQL}) c \
+             WHERE $1::timestamptz IS NULL OR (changed_at, note_id) > ($1, $2) \
+             ORDER BY changed_at, note_id LIMIT $3"
+        ))
+        .bind(after.map(|c| c.changed_at))
+        .bind(after.map(|c| c.note_id).unwrap_or_else(Uuid::nil))
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let live: Vec<Uuid> = positions
+            .iter()
+            .filter(|(_, _, tombstone)| !tombstone)
+            .map(|(id, _, _)| *id)
+            .collect();
+        let mut snapshots: std::collections::HashMap<Uuid, serde_json::Value> = self
+            .snapshots
+            .export_notes_tx(tx, &live)
+            .await?
+            .into_iter()
+            .filter_map(|mut snapshot| {
+                let id = snapshot_note_id(&snapshot)?;
+                if let Some(object) = snapshot.as_object_mut() {
+                    object.remove("attachments");
+                }
+                Some((id, snapshot))
+            })
+            .collect();
+
+        Ok(positions
+            .into_iter()
+            .map(|(note_id, changed_at, tombstone)| SyncChange {
+                note_id,
+                changed_at,
+                snapshot: if tombstone {
+                    None
+                } else {
+                    snapshots.remove(&note_id)
+                },
+            })
+            // A note deleted between the two queries is picked up by its
+            // tombstone on the next page or run.
+            .filter(|change| tombstone_or_snapshot(change, &live))
+            .collect())
+    }
+
+    /// When a note last changed locally: its timestamps, its tombstone, or
+    /// `None` if this archive has never had it.
+    pub async fn local_changed_at_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<Option<DateTime<Utc>>> {
+        sqlx::query_scalar(&format!(
+            "SELECT max(changed_at) FROM ({NOTE_CHANGES_SQL}) c WHERE note_id = $1"
+        ))
+        .bind(note_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Apply one incoming change under `strategy`.
+    ///
+    /// Returns the change's contribution to the batch counts. Requeueing
+    /// processing for applied notes is left to the caller.
+    pub async fn apply_change_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        change: &SyncChange,
+        strategy: ConflictStrategy,
+    ) -> Result<SyncApplyResult> {
+        if let Some(snapshot) = &change.snapshot {
+            if snapshot_note_id(snapshot) != Some(change.note_id) {
+                return Err(Error::InvalidInput(
+                    "sync change snapshot does not match its note_id".to_string(),
+                ));
+            }
+        }
+
+        let local = self.local_changed_at_tx(tx, change.note_id).await?;
+        let mut result = SyncApplyResult::default();
+        match strategy.decide(local, change.changed_at) {
+            SyncDecision::Unchanged => result.unchanged = 1,
+            SyncDecision::Conflict => result.conflicts = 1,
+            SyncDecision::Apply => match &change.snapshot {
+                None => {
+                    let deleted = self
+                        .snapshots
+                        .delete_notes_tx(tx, &[change.note_id])
+                        .await?;
+                    if deleted > 0 {
+                        result.deleted = 1;
+                    } else {
+                        result.unchanged = 1;
+                    }
+                }
+                Some(snapshot) => {
+                    let snapshot = self.localize_snapshot_tx(tx, snapshot).await?;
+                    self.snapshots.apply_note_tx(tx, &snapshot).await?;
+                    result.applied = 1;
+                }
+            },
+        }
+        Ok(result)
+    }
+
+    /// Fit an incoming snapshot to this archive: references to collections
+    /// and document types it lacks are cleared, and the note keeps its local
+    /// attachments.
+    async fn localize_snapshot_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        snapshot: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let mut snapshot = snapshot.clone();
+        let note_id = snapshot_note_id(&snapshot);
+        let Some(note) = snapshot.get_mut("note").and_then(|n| n.as_object_mut()) else {
+            return Err(Error::InvalidInput("invalid note snapshot".to_string()));
+        };
+        for (column, table) in [
+            ("collection_id", "collection"),
+            ("document_type_id", "document_type"),
+        ] {
+            let Some(id) = note
+                .get(column)
+                .and_then(|id| id.as_str())
+                .and_then(|id| Uuid::parse_str(id).ok())
+            else {
+                continue;
+            };
+            // `table` is one of the constants above, never user input.
+            let exists: bool = sqlx::query_scalar(&format!(
+                "SELECT EXISTS (SELECT 1 FROM {table} WHERE id = $1)"
+            ))
+            .bind(id)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            if !exists {
+                note.insert(column.to_string(), serde_json::Value::Null);
+            }
+        }
+
+        let attachments: serde_json::Value = sqlx::query_scalar(
+            "SELECT COALESCE(jsonb_agg(to_jsonb(a.*)), '[]'::jsonb) \
+             FROM attachment a WHERE a.note_id = $1",
+        )
+        .bind(note_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        if let Some(object) = snapshot.as_object_mut() {
+            object.insert("attachments".to_string(), attachments);
+        }
+        Ok(snapshot)
+    }
+}
+
+fn snapshot_note_id(snapshot: &serde_json::Value) -> Option<Uuid> {
+    snapshot
+        .get("note")?
+        .get("id")?
+        .as_str()
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
+fn tombstone_or_snapshot(change: &SyncChange, live: &[Uuid]) -> bool {
+    !live.contains(&change.note_id) || change.snapshot.is_some()
+}
+
+fn peer_insert_error(e: sqlx::Error, name: &str) -> Error {
+    if let sqlx::Error::Database(ref db_err) = e {
+        if db_err.constraint() == Some("sync_peer_name_key") {
+            return Error::InvalidInput(format!(
+                "Sync peer already exists; name_len={}",
+                name.chars().count()
+            ));
+        }
+    }
+    Error::Database(e)
+}
+
+fn cursor_from_row(row: &PgRow, at: &str, note_id: &str) -> Option<SyncCursor> {
+    Some(SyncCursor {
+        changed_at: row.get::<Option<DateTime<Utc>>, _>(at)?,
+        note_id: row.get::<Option<Uuid>, _>(note_id)?,
+    })
+}
+
+fn peer_from_row(row: &PgRow) -> SyncPeer {
+    SyncPeer {
+        id: row.get("id"),
+        name: row.get("name"),
+        base_url: row.get("base_url"),
+        has_api_token: row.get("has_api_token"),
+        remote_memory: row.get("remote_memory"),
+        local_memory: row.get("local_memory"),
+        direction: row.get("direction"),
+        conflict_strategy: row.get("conflict_strategy"),
+        schedule: row.get("schedule"),
+        enabled: row.get("enabled"),
+        pull_cursor: cursor_from_row(row, "pull_cursor_at", "pull_cursor_note_id"),
+        push_cursor: cursor_from_row(row, "push_cursor_at", "push_cursor_note_id"),
+        next_run_at_utc: row.get("next_run_at_utc"),
+        last_sync_at_utc: row.get("last_sync_at_utc"),
+        last_status: row.get("last_status"),
+        last_error: row.get("last_error"),
+        created_at_utc: row.get("created_at_utc"),
+        updated_at_utc: row.get("updated_at_utc"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_ids_come_from_the_note_row() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            snapshot_note_id(&serde_json::json!({ "note": { "id": id.to_string() } })),
+            Some(id)
+        );
+        assert_eq!(snapshot_note_id(&serde_json::json!({ "note": {} })), None);
+        assert_eq!(snapshot_note_id(&serde_json::json!({ "id": id })), None);
+    }
+
+    #[test]
+    fn live_changes_without_a_snapshot_are_dropped() {
+        let live_id = Uuid::new_v4();
+        let gone = SyncChange {
+            note_id: live_id,
+            changed_at: Utc::now(),
+            snapshot: None,
+        };
+        let tombstone = SyncChange {
+            note_id: Uuid::new_v4(),
+            ..gone.clone()
+        };
+        assert!(!tombstone_or_snapshot(&gone, &[live_id]));
+        assert!(tombstone_or_snapshot(&tombstone, &[live_id]));
+    }
+}
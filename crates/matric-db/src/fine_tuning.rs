@@ -0,0 +1,377 @@
+//! Fine-tuning dataset repository.
+//!
+//! Datasets (`fine_tuning_dataset`) and their samples (`fine_tuning_sample`)
+//! are archive-scoped. A generation run clears a dataset's samples and
+//! writes new ones note by note. The `*_tx` methods take a transaction that
+//! has already been pointed at the archive schema.
+
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgRow, Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    new_v7, CreateFineTuningDatasetRequest, Error, FineTuningDataset, FineTuningSample,
+    FineTuningSplit, FineTuningStatus, GeneratedQaPair, Result,
+};
+
+const DATASET_COLUMNS: &str = "id, name, description, source_type, source_id, config, status, \
+     sample_count, training_count, validation_count, created_at, completed_at, error_message";
+
+const SAMPLE_COLUMNS: &str =
+    "id, dataset_id, note_id, query, answer, query_type, quality_score, is_validation, created_at";
+
+/// A generated pair ready to store, with its split.
+pub struct NewFineTuningSample<'a> {
+    pub pair: &'a GeneratedQaPair,
+    pub is_validation: bool,
+}
+
+/// PostgreSQL repository for fine-tuning datasets.
+#[derive(Clone)]
+pub struct PgFineTuningRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgFineTuningRepository {
+    /// Create a new fine-tuning repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// List datasets, newest first.
+    pub async fn list_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<FineTuningDataset>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {DATASET_COLUMNS} FROM fine_tuning_dataset ORDER BY created_at DESC, id"
+        ))
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        rows.iter().map(dataset_from_row).collect()
+    }
+
+    /// Get a dataset.
+    pub async fn get_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Option<FineTuningDataset>> {
+        let row = sqlx::query(&format!(
+            "SELECT {DATASET_COLUMNS} FROM fine_tuning_dataset WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        row.as_ref().map(dataset_from_row).transpose()
+    }
+
+    /// Create a pending dataset.
+    pub async fn create_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        req: &CreateFineTuningDatasetRequest,
+    ) -> Result<FineTuningDataset> {
+        let config =
+            serde_json::to_value(&req.config).map_err(|e| Error::Serialization(e.to_string()))?;
+        let row = sqlx::query(&format!(
+            "INSERT INTO fine_tuning_dataset
+                 (id, name, description, source_type, source_id, config, status)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING {DATASET_COLUMNS}"
+        ))
+        .bind(new_v7())
+        .bind(req.name.trim())
+        .bind(req.description.as_deref().filter(|d| !d.trim().is_empty()))
+        .bind(&req.source_type)
+        .bind(req.source_id.trim())
+        .bind(config)
+        .bind(FineTuningStatus::Pending.as_str())
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| dataset_name_error(e, &req.name))?;
+
+        dataset_from_row(&row)
+    }
+
+    /// Persist the name, description, and config of `dataset`.
+    ///
+    /// Returns `None` when the dataset no longer exists.
+    pub async fn update_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        dataset: &FineTuningDataset,
+    ) -> Result<Option<FineTuningDataset>> {
+        let config = serde_json::to_value(&dataset.config)
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        let row = sqlx::query(&format!(
+            "UPDATE fine_tuning_dataset
+             SET name = $2, description = $3, config = $4
+             WHERE id = $1
+             RETURNING {DATASET_COLUMNS}"
+        ))
+        .bind(dataset.id)
+        .bind(&dataset.name)
+        .bind(dataset.description.as_deref())
+        .bind(config)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| dataset_name_error(e, &dataset.name))?;
+
+        row.as_ref().map(dataset_from_row).transpose()
+    }
+
+    /// Delete a dataset and its samples. Returns whether it existed.
+    pub async fn delete_tx(&self, tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM fine_tuning_dataset WHERE id = $1")
+            .bind(id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark a dataset queued for generation, unless a run is already
+    /// generating it. Returns whether the dataset was marked.
+    pub async fn mark_pending_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE fine_tuning_dataset SET status = $2, error_message = NULL
+             WHERE id = $1 AND status <> $3",
+        )
+        .bind(id)
+        .bind(FineTuningStatus::Pending.as_str())
+        .bind(FineTuningStatus::Generating.as_str())
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Start a generation run: drop the dataset's samples and mark it
+    /// generating. Returns whether the dataset exists.
+    pub async fn start_generation_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<bool> {
+        sqlx::query("DELETE FROM fine_tuning_sample WHERE dataset_id = $1")
+            .bind(id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        let result = sqlx::query(
+            "UPDATE fine_tuning_dataset
+             SET status = $2, sample_count = 0, training_count = 0, validation_count = 0,
+                 completed_at = NULL, error_message = NULL
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(FineTuningStatus::Generating.as_str())
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Live, unencrypted notes in a dataset source, most recently updated
+    /// first.
+    ///
+    /// Tag sources include notes tagged with a child of the tag. An unknown
+    /// source type or an unparseable collection id selects no notes.
+    pub async fn source_note_ids_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        source_type: &str,
+        source_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Uuid>> {
+        let filter = match source_type {
+            "embedding_set" => {
+                "EXISTS (SELECT 1 FROM embedding_set_member m
+                         JOIN embedding_set s ON s.id = m.embedding_set_id
+                         WHERE m.note_id = n.id AND s.slug = $1)"
+            }
+            "tag" => {
+                "EXISTS (SELECT 1 FROM note_tag nt
+                         WHERE nt.note_id = n.id
+                           AND (LOWER(nt.tag_name) = LOWER($1)
+                                OR LOWER(nt.tag_name) LIKE LOWER($1) || '/%'))"
+            }
+            "collection" if Uuid::parse_str(source_id).is_ok() => "n.collection_id = $1::uuid",
+            _ => return Ok(Vec::new()),
+        };
+        sqlx::query_scalar(&format!(
+            "SELECT n.id FROM note n
+             WHERE n.deleted_at IS NULL AND NOT n.encrypted AND {filter}
+             ORDER BY n.updated_at_utc DESC, n.id
+             LIMIT $2"
+        ))
+        .bind(source_id)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Store generated samples for one note.
+    pub async fn insert_samples_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        dataset_id: Uuid,
+        note_id: Uuid,
+        samples: &[NewFineTuningSample<'_>],
+    ) -> Result<()> {
+        for sample in samples {
+            sqlx::query(
+                "INSERT INTO fine_tuning_sample
+                     (id, dataset_id, note_id, query, answer, query_type, quality_score,
+                      is_validation)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            )
+            .bind(new_v7())
+            .bind(dataset_id)
+            .bind(note_id)
+            .bind(&sample.pair.question)
+            .bind(&sample.pair.answer)
+            .bind(sample.pair.question_type.as_deref())
+            .bind(f64::from(sample.pair.quality))
+            .bind(sample.is_validation)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+        Ok(())
+    }
+
+    /// Mark a run complete and record its sample counts.
+    pub async fn complete_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Option<FineTuningDataset>> {
+        sqlx::query(
+            "UPDATE fine_tuning_dataset d
+             SET status = $2,
+                 sample_count = c.total,
+                 training_count = c.total - c.validation,
+                 validation_count = c.validation,
+                 completed_at = NOW()
+             FROM (SELECT COUNT(*)::int AS total,
+                          (COUNT(*) FILTER (WHERE is_validation))::int AS validation
+                   FROM fine_tuning_sample WHERE dataset_id = $1) c
+             WHERE d.id = $1",
+        )
+        .bind(id)
+        .bind(FineTuningStatus::Completed.as_str())
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        self.get_tx(tx, id).await
+    }
+
+    /// Mark a run failed.
+    pub async fn fail_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        message: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE fine_tuning_dataset SET status = $2, error_message = $3, completed_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(FineTuningStatus::Failed.as_str())
+        .bind(message)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// A page of a dataset's samples in generation order.
+    pub async fn samples_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        dataset_id: Uuid,
+        split: FineTuningSplit,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<FineTuningSample>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {SAMPLE_COLUMNS} FROM fine_tuning_sample
+             WHERE dataset_id = $1 AND ($2::boolean IS NULL OR is_validation = $2)
+             ORDER BY id
+             LIMIT $3 OFFSET $4"
+        ))
+        .bind(dataset_id)
+        .bind(split.is_validation())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows.iter().map(sample_from_row).collect())
+    }
+}
+
+fn dataset_name_error(e: sqlx::Error, name: &str) -> Error {
+    if let sqlx::Error::Database(ref db_err) = e {
+        if db_err.is_unique_violation() {
+            return Error::InvalidInput(format!(
+                "Fine-tuning dataset already exists; name_len={}",
+                name.chars().count()
+            ));
+        }
+    }
+    Error::Database(e)
+}
+
+fn dataset_from_row(row: &PgRow) -> Result<FineTuningDataset> {
+    let config: serde_json::Value = row.get("config");
+    let status: String = row.get("status");
+    Ok(FineTuningDataset {
+        id: row.get("id"),
+        name: row.get("name"),
+        description: row.get("description"),
+        source_type: row.get("source_type"),
+        source_id: row.get("source_id"),
+        config: serde_json::from_value(config).map_err(|e| Error::Serialization(e.to_string()))?,
+        status: FineTuningStatus::parse(&status),
+        sample_count: row.get::<Option<i32>, _>("sample_count").unwrap_or(0),
+        training_count: row.get::<Option<i32>, _>("training_count").unwrap_or(0),
+        validation_count: row.get::<Option<i32>, _>("validation_count").unwrap_or(0),
+        created_at: row
+            .get::<Option<DateTime<Utc>>, _>("created_at")
+            .unwrap_or_default(),
+        completed_at: row.get("completed_at"),
+        error_message: row.get("error_message"),
+    })
+}
+
+fn sample_from_row(row: &PgRow) -> FineTuningSample {
+    FineTuningSample {
+        id: row.get("id"),
+        dataset_id: row.get("dataset_id"),
+        note_id: row.get("note_id"),
+        query: row.get("query"),
+        answer: row.get("answer"),
+        query_type: row.get("query_type"),
+        quality_score: row.get::<Option<f64>, _>("quality_score").map(|s| s as f32),
+        is_validation: row.get::<Option<bool>, _>("is_validation").unwrap_or(false),
+        created_at: row
+            .get::<Option<DateTime<Utc>>, _>("created_at")
+            .unwrap_or_default(),
+    }
+}
@@ -0,0 +1,396 @@
+//! Graph analytics algorithms over the note link graph.
+//!
+//! Pure functions behind [`crate::PgLinkRepository::refresh_graph_analytics_tx`]:
+//! weighted PageRank over the directed links, Brandes betweenness centrality
+//! over the undirected graph (estimated from sampled sources on large graphs),
+//! label propagation communities and bridge detection.
+
+use std::collections::{HashMap, VecDeque};
+
+use uuid::Uuid;
+
+/// PageRank damping factor.
+pub const PAGERANK_DAMPING: f64 = 0.85;
+
+/// PageRank stops after this many iterations even without converging.
+pub const PAGERANK_MAX_ITERATIONS: u32 = 100;
+
+/// PageRank has converged once the ranks move less than this in total.
+const PAGERANK_TOLERANCE: f64 = 1e-9;
+
+/// Label propagation stops after this many sweeps even if labels still change.
+const LABEL_PROPAGATION_MAX_SWEEPS: usize = 50;
+
+/// Graphs with more notes than this get betweenness estimated from this
+/// many evenly spaced source notes.
+pub const BETWEENNESS_EXACT_MAX_NODES: usize = 1000;
+
+/// Metrics of every linked note, indexed like [`GraphMetrics::nodes`].
+#[derive(Debug, Clone, Default)]
+pub struct GraphMetrics {
+    /// Linked notes in ascending ID order.
+    pub nodes: Vec<Uuid>,
+    /// PageRank; sums to 1 over all nodes.
+    pub pagerank: Vec<f64>,
+    /// Normalized betweenness centrality (0-1).
+    pub betweenness: Vec<f64>,
+    /// Community number; 0 is the largest community.
+    pub community: Vec<i32>,
+    /// Distinct neighbors, ignoring link direction.
+    pub degree: Vec<i32>,
+    /// Whether the note links into a community other than its own.
+    pub is_bridge: Vec<bool>,
+    /// Distinct directed links between distinct notes.
+    pub edge_count: usize,
+    pub pagerank_iterations: u32,
+    pub betweenness_sampled: bool,
+}
+
+impl GraphMetrics {
+    /// PageRank scaled so the highest-ranked note scores 1.
+    pub fn importance(&self) -> Vec<f64> {
+        let max = self.pagerank.iter().copied().fold(0.0_f64, f64::max);
+        self.pagerank
+            .iter()
+            .map(|rank| if max > 0.0 { rank / max } else { 0.0 })
+            .collect()
+    }
+
+    pub fn community_count(&self) -> usize {
+        self.community
+            .iter()
+            .map(|&c| c as usize + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn bridge_count(&self) -> usize {
+        self.is_bridge.iter().filter(|&&bridge| bridge).count()
+    }
+}
+
+/// Compute every metric for the graph of `edges` (`from`, `to`, `weight`).
+///
+/// Self-links are ignored and repeated links keep their highest weight;
+/// weights that are not positive and finite count as 1.
+pub fn compute_graph_metrics(edges: &[(Uuid, Uuid, f64)]) -> GraphMetrics {
+    let mut nodes: Vec<Uuid> = edges
+        .iter()
+        .filter(|(from, to, _)| from != to)
+        .flat_map(|(from, to, _)| [*from, *to])
+        .collect();
+    nodes.sort_unstable();
+    nodes.dedup();
+    let n = nodes.len();
+    if n == 0 {
+        return GraphMetrics::default();
+    }
+    let index: HashMap<Uuid, usize> = nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+    let mut directed: HashMap<(usize, usize), f64> = HashMap::new();
+    for (from, to, weight) in edges.iter().filter(|(from, to, _)| from != to) {
+        let weight = if weight.is_finite() && *weight > 0.0 {
+            *weight
+        } else {
+            1.0
+        };
+        let slot = directed.entry((index[from], index[to])).or_insert(weight);
+        *slot = slot.max(weight);
+    }
+
+    let mut outgoing: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+    let mut undirected: Vec<HashMap<usize, f64>> = vec![HashMap::new(); n];
+    for (&(from, to), &weight) in &directed {
+        outgoing[from].push((to, weight));
+        *undirected[from].entry(to).or_insert(0.0) += weight;
+        *undirected[to].entry(from).or_insert(0.0) += weight;
+    }
+    let neighbors: Vec<Vec<(usize, f64)>> = undirected
+        .into_iter()
+        .map(|adjacent| {
+            let mut adjacent: Vec<(usize, f64)> = adjacent.into_iter().collect();
+            adjacent.sort_unstable_by_key(|(node, _)| *node);
+            adjacent
+        })
+        .collect();
+
+    let (pagerank, pagerank_iterations) = pagerank(&outgoing);
+    let (betweenness, betweenness_sampled) = betweenness(&neighbors);
+    let community = label_propagation(&neighbors);
+    let is_bridge = neighbors
+        .iter()
+        .enumerate()
+        .map(|(v, adjacent)| adjacent.iter().any(|(u, _)| community[*u] != community[v]))
+        .collect();
+    let degree = neighbors
+        .iter()
+        .map(|adjacent| adjacent.len() as i32)
+        .collect();
+
+    GraphMetrics {
+        nodes,
+        pagerank,
+        betweenness,
+        community,
+        degree,
+        is_bridge,
+        edge_count: directed.len(),
+        pagerank_iterations,
+        betweenness_sampled,
+    }
+}
+
+/// Weighted PageRank. Rank of notes without outgoing links is spread over
+/// every note. Returns the ranks and the iterations run.
+fn pagerank(outgoing: &[Vec<(usize, f64)>]) -> (Vec<f64>, u32) {
+    let n = outgoing.len();
+    let out_weight: Vec<f64> = outgoing
+        .iter()
+        .map(|links| links.iter().map(|(_, w)| w).sum())
+        .collect();
+    let mut rank = vec![1.0 / n as f64; n];
+    let mut iterations = 0;
+    while iterations < PAGERANK_MAX_ITERATIONS {
+        iterations += 1;
+        let dangling: f64 = (0..n)
+            .filter(|&v| out_weight[v] == 0.0)
+            .map(|v| rank[v])
+            .sum();
+        let base = (1.0 - PAGERANK_DAMPING) / n as f64 + PAGERANK_DAMPING * dangling / n as f64;
+        let mut next = vec![base; n];
+        for (v, links) in outgoing.iter().enumerate() {
+            for &(u, w) in links {
+                next[u] += PAGERANK_DAMPING * rank[v] * w / out_weight[v];
+            }
+        }
+        let moved: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+        rank = next;
+        if moved < PAGERANK_TOLERANCE {
+            break;
+        }
+    }
+    (rank, iterations)
+}
+
+/// Normalized betweenness centrality over unweighted shortest paths
+/// (Brandes). Returns the scores and whether sources were sampled.
+fn betweenness(neighbors: &[Vec<(usize, f64)>]) -> (Vec<f64>, bool) {
+    let n = neighbors.len();
+    let mut centrality = vec![0.0_f64; n];
+    if n < 3 {
+        return (centrality, false);
+    }
+    let sampled = n > BETWEENNESS_EXACT_MAX_NODES;
+    let sources: Vec<usize> = if sampled {
+        let step = n as f64 / BETWEENNESS_EXACT_MAX_NODES as f64;
+        (0..BETWEENNESS_EXACT_MAX_NODES)
+            .map(|k| (k as f64 * step) as usize)
+            .collect()
+    } else {
+        (0..n).collect()
+    };
+
+    let mut stack = Vec::with_capacity(n);
+    let mut queue = VecDeque::with_capacity(n);
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut paths = vec![0.0_f64; n];
+    let mut distance = vec![-1_i64; n];
+    let mut dependency = vec![0.0_f64; n];
+    for &source in &sources {
+        stack.clear();
+        for v in 0..n {
+            predecessors[v].clear();
+            paths[v] = 0.0;
+            distance[v] = -1;
+            dependency[v] = 0.0;
+        }
+        paths[source] = 1.0;
+        distance[source] = 0;
+        queue.push_back(source);
+        while let Some(v) = queue.pop_front() {
+            stack.push(v);
+            for &(w, _) in &neighbors[v] {
+                if distance[w] < 0 {
+                    distance[w] = distance[v] + 1;
+                    queue.push_back(w);
+                }
+                if distance[w] == distance[v] + 1 {
+                    paths[w] += paths[v];
+                    predecessors[w].push(v);
+                }
+            }
+        }
+        while let Some(w) = stack.pop() {
+            for &v in &predecessors[w] {
+                dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+            }
+            if w != source {
+                centrality[w] += dependency[w];
+            }
+        }
+    }
+
+    // Every undirected pair is counted from both ends; sampling sees only
+    // `sources.len()` of the `n` sources.
+    let scale = n as f64 / sources.len() as f64 / ((n - 1) * (n - 2)) as f64;
+    for score in &mut centrality {
+        *score = (*score * scale).clamp(0.0, 1.0);
+    }
+    (centrality, sampled)
+}
+
+/// Weighted label propagation. Each note takes the label carrying the most
+/// link weight among its neighbors, keeping its own label on a tie and
+/// otherwise preferring the smaller label, until no label changes.
+/// Communities are then numbered by descending size.
+///
+/// A link counts once more for every neighbor its two notes share, so links
+/// inside triangles outweigh lone links between clusters and a single label
+/// cannot flood across a bridge.
+fn label_propagation(neighbors: &[Vec<(usize, f64)>]) -> Vec<i32> {
+    let n = neighbors.len();
+    let support: Vec<Vec<(usize, f64)>> = neighbors
+        .iter()
+        .map(|adjacent| {
+            adjacent
+                .iter()
+                .map(|&(u, w)| {
+                    let shared = adjacent
+                        .iter()
+                        .filter(|(x, _)| neighbors[u].binary_search_by_key(x, |(y, _)| *y).is_ok())
+                        .count();
+                    (u, w * (1 + shared) as f64)
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut labels: Vec<usize> = (0..n).collect();
+    for _ in 0..LABEL_PROPAGATION_MAX_SWEEPS {
+        let mut changed = false;
+        for v in 0..n {
+            let mut weight_by_label: HashMap<usize, f64> = HashMap::new();
+            for &(u, w) in &support[v] {
+                *weight_by_label.entry(labels[u]).or_insert(0.0) += w;
+            }
+            let Some(best_weight) = weight_by_label.values().copied().reduce(f64::max) else {
+                continue;
+            };
+            let best = if weight_by_label.get(&labels[v]) == Some(&best_weight) {
+                labels[v]
+            } else {
+                weight_by_label
+                    .iter()
+                    .filter(|(_, &weight)| weight == best_weight)
+                    .map(|(&label, _)| label)
+                    .min()
+                    .unwrap_or(labels[v])
+            };
+            if best != labels[v] {
+                labels[v] = best;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut sizes: HashMap<usize, (usize, usize)> = HashMap::new();
+    for (v, &label) in labels.iter().enumerate() {
+        let entry = sizes.entry(label).or_insert((0, v));
+        entry.0 += 1;
+    }
+    let mut order: Vec<(usize, usize, usize)> = sizes
+        .into_iter()
+        .map(|(label, (size, first))| (label, size, first))
+        .collect();
+    order.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.2.cmp(&b.2)));
+    let number: HashMap<usize, i32> = order
+        .iter()
+        .enumerate()
+        .map(|(i, (label, _, _))| (*label, i as i32))
+        .collect();
+    labels.iter().map(|label| number[label]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(n: u128) -> Vec<Uuid> {
+        (1..=n).map(Uuid::from_u128).collect()
+    }
+
+    fn both_ways(pairs: &[(usize, usize)], ids: &[Uuid]) -> Vec<(Uuid, Uuid, f64)> {
+        pairs
+            .iter()
+            .flat_map(|&(a, b)| [(ids[a], ids[b], 1.0), (ids[b], ids[a], 1.0)])
+            .collect()
+    }
+
+    #[test]
+    fn empty_graph_has_no_metrics() {
+        let metrics = compute_graph_metrics(&[]);
+        assert!(metrics.nodes.is_empty());
+        assert_eq!(metrics.community_count(), 0);
+    }
+
+    #[test]
+    fn pagerank_sums_to_one_and_favours_the_hub() {
+        let ids = ids(5);
+        // Every leaf links to the hub (node 0); the hub links nowhere.
+        let edges: Vec<_> = (1..5).map(|leaf| (ids[leaf], ids[0], 0.9)).collect();
+        let metrics = compute_graph_metrics(&edges);
+
+        let total: f64 = metrics.pagerank.iter().sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        let hub = metrics.pagerank[0];
+        assert!(metrics.pagerank[1..].iter().all(|&leaf| leaf < hub));
+        assert_eq!(metrics.importance()[0], 1.0);
+    }
+
+    #[test]
+    fn betweenness_peaks_in_the_middle_of_a_path() {
+        let ids = ids(3);
+        let metrics = compute_graph_metrics(&both_ways(&[(0, 1), (1, 2)], &ids));
+        assert!((metrics.betweenness[1] - 1.0).abs() < 1e-9);
+        assert_eq!(metrics.betweenness[0], 0.0);
+        assert_eq!(metrics.betweenness[2], 0.0);
+        assert!(!metrics.betweenness_sampled);
+    }
+
+    #[test]
+    fn two_triangles_form_two_communities_joined_by_bridges() {
+        let ids = ids(6);
+        let edges = both_ways(
+            &[(0, 1), (1, 2), (0, 2), (3, 4), (4, 5), (3, 5), (2, 3)],
+            &ids,
+        );
+        let metrics = compute_graph_metrics(&edges);
+
+        assert_eq!(metrics.community_count(), 2);
+        assert_eq!(metrics.community[0], metrics.community[1]);
+        assert_eq!(metrics.community[3], metrics.community[5]);
+        assert_ne!(metrics.community[0], metrics.community[5]);
+        assert_eq!(
+            metrics.is_bridge,
+            vec![false, false, true, true, false, false]
+        );
+        assert_eq!(metrics.edge_count, 14);
+        assert_eq!(metrics.degree[2], 3);
+    }
+
+    #[test]
+    fn self_links_and_repeats_are_ignored() {
+        let ids = ids(2);
+        let edges = vec![
+            (ids[0], ids[0], 1.0),
+            (ids[0], ids[1], 0.2),
+            (ids[0], ids[1], 0.8),
+        ];
+        let metrics = compute_graph_metrics(&edges);
+        assert_eq!(metrics.nodes.len(), 2);
+        assert_eq!(metrics.edge_count, 1);
+    }
+}
@@ -0,0 +1,319 @@
+//! Knowledge graph snapshot for RDF export.
+//!
+//! Reads notes, links, SKOS concepts and provenance edges from the current
+//! archive schema in one transaction so the exported graph is consistent.
+//! Soft-deleted notes and any edge touching them are left out. Large
+//! exports read the concept vocabulary once and then notes a page at a
+//! time; `security`, when given, admits only the notes it allows, and edges
+//! only when both ends are admitted.
+
+use sqlx::{Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    Error, RdfConceptRecord, RdfConceptRelationRecord, RdfGraphSnapshot, RdfLinkRecord,
+    RdfNoteConceptRecord, RdfNoteRecord, RdfProvenanceRecord, Result, StrictSecurityFilter,
+};
+
+use crate::unified_filter::{
+    bind_filter_param, security_clause, security_filter_query, QueryParam,
+};
+
+/// PostgreSQL repository for knowledge graph exports.
+pub struct PgGraphExportRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgGraphExportRepository {
+    /// Create a new graph export repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Read every exportable node and edge in the archive at once.
+    pub async fn snapshot_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<RdfGraphSnapshot> {
+        let notes = sqlx::query(
+            r#"
+            SELECT id, title, created_at_utc, updated_at_utc
+            FROM note
+            WHERE deleted_at IS NULL
+            ORDER BY id
+            "#,
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .iter()
+        .map(|r| RdfNoteRecord {
+            id: r.get("id"),
+            title: r.get("title"),
+            created_at_utc: r.get("created_at_utc"),
+            updated_at_utc: r.get("updated_at_utc"),
+        })
+        .collect();
+
+        let links = sqlx::query(
+            r#"
+            SELECT l.from_note_id, l.to_note_id, l.to_url, l.kind
+            FROM link l
+            JOIN note src ON src.id = l.from_note_id AND src.deleted_at IS NULL
+            LEFT JOIN note dst ON dst.id = l.to_note_id
+            WHERE l.to_url IS NOT NULL OR dst.deleted_at IS NULL
+            ORDER BY l.from_note_id, l.id
+            "#,
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .iter()
+        .map(|r| RdfLinkRecord {
+            from_note_id: r.get("from_note_id"),
+            to_note_id: r.get("to_note_id"),
+            to_url: r.get("to_url"),
+            kind: r.get("kind"),
+        })
+        .collect();
+
+        let note_concepts = sqlx::query(
+            r#"
+            SELECT nc.note_id, nc.concept_id
+            FROM note_skos_concept nc
+            JOIN note n ON n.id = nc.note_id AND n.deleted_at IS NULL
+            ORDER BY nc.note_id, nc.concept_id
+            "#,
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .iter()
+        .map(|r| RdfNoteConceptRecord {
+            note_id: r.get("note_id"),
+            concept_id: r.get("concept_id"),
+        })
+        .collect();
+
+        let provenance = sqlx::query(
+            r#"
+            SELECT rev.note_id, pe.source_note_id, pe.source_url, pe.relation
+            FROM provenance_edge pe
+            JOIN note_revision rev ON rev.id = pe.revision_id
+            JOIN note n ON n.id = rev.note_id AND n.deleted_at IS NULL
+            LEFT JOIN note src ON src.id = pe.source_note_id
+            WHERE pe.source_url IS NOT NULL
+               OR (pe.source_note_id IS NOT NULL AND src.deleted_at IS NULL)
+            ORDER BY rev.note_id, pe.id
+            "#,
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .iter()
+        .map(|r| RdfProvenanceRecord {
+            note_id: r.get("note_id"),
+            source_note_id: r.get("source_note_id"),
+            source_url: r.get("source_url"),
+            relation: r.get("relation"),
+        })
+        .collect();
+
+        Ok(RdfGraphSnapshot {
+            notes,
+            links,
+            note_concepts,
+            provenance,
+            ..self.vocabulary_tx(tx).await?
+        })
+    }
+
+    /// Read the archive's SKOS concepts and the relations between them.
+    pub async fn vocabulary_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<RdfGraphSnapshot> {
+        let concepts = sqlx::query(
+            r#"
+            SELECT c.id, c.primary_scheme_id, l.value AS pref_label, l.language
+            FROM skos_concept c
+            LEFT JOIN LATERAL (
+                SELECT value, language
+                FROM skos_concept_label
+                WHERE concept_id = c.id AND label_type = 'pref_label'
+                ORDER BY (language = 'en') DESC, language
+                LIMIT 1
+            ) l ON TRUE
+            ORDER BY c.id
+            "#,
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .iter()
+        .map(|r| RdfConceptRecord {
+            id: r.get("id"),
+            scheme_id: r.get("primary_scheme_id"),
+            pref_label: r.get("pref_label"),
+            language: r.get("language"),
+        })
+        .collect();
+
+        let concept_relations = sqlx::query(
+            r#"
+            SELECT subject_id, object_id, relation_type::text AS relation
+            FROM skos_semantic_relation_edge
+            ORDER BY subject_id, relation_type, object_id
+            "#,
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .iter()
+        .map(|r| RdfConceptRelationRecord {
+            subject_id: r.get("subject_id"),
+            object_id: r.get("object_id"),
+            relation: r.get("relation"),
+        })
+        .collect();
+
+        Ok(RdfGraphSnapshot {
+            concepts,
+            concept_relations,
+            ..Default::default()
+        })
+    }
+
+    /// Read the next `limit` live notes after `after`, in ID order, with the
+    /// links, concepts and provenance edges leaving them. Returns no concepts;
+    /// see [`Self::vocabulary_tx`].
+    pub async fn notes_page_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        after: Option<Uuid>,
+        limit: i64,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<RdfGraphSnapshot> {
+        let page_security = security_filter_query(security, 2);
+        let sql = format!(
+            "SELECT n.id, n.title, n.created_at_utc, n.updated_at_utc
+             FROM note n
+             WHERE n.deleted_at IS NULL
+               AND ($1::uuid IS NULL OR n.id > $1)
+               {}
+             ORDER BY n.id
+             LIMIT $2",
+            security_clause(&page_security)
+        );
+        let mut query = sqlx::query(&sql).bind(after).bind(limit);
+        for param in page_security.iter().flat_map(|result| &result.params) {
+            query = bind_filter_param!(query, param);
+        }
+        let notes: Vec<RdfNoteRecord> = query
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(Error::Database)?
+            .iter()
+            .map(|r| RdfNoteRecord {
+                id: r.get("id"),
+                title: r.get("title"),
+                created_at_utc: r.get("created_at_utc"),
+                updated_at_utc: r.get("updated_at_utc"),
+            })
+            .collect();
+        if notes.is_empty() {
+            return Ok(RdfGraphSnapshot::default());
+        }
+        let ids: Vec<Uuid> = notes.iter().map(|note| note.id).collect();
+
+        // Edge targets are checked against the same filter as the page, so
+        // no edge names a note the caller cannot read.
+        let security = security_filter_query(security, 1);
+        let visible = |column: &str| {
+            format!(
+                "EXISTS (SELECT 1 FROM note n
+                         WHERE n.id = {column} AND n.deleted_at IS NULL {})",
+                security_clause(&security)
+            )
+        };
+
+        let sql = format!(
+            "SELECT l.from_note_id, l.to_note_id, l.to_url, l.kind
+             FROM link l
+             WHERE l.from_note_id = ANY($1)
+               AND ((l.to_note_id IS NULL AND l.to_url IS NOT NULL) OR {})
+             ORDER BY l.from_note_id, l.id",
+            visible("l.to_note_id")
+        );
+        let mut query = sqlx::query(&sql).bind(&ids);
+        for param in security.iter().flat_map(|result| &result.params) {
+            query = bind_filter_param!(query, param);
+        }
+        let links = query
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(Error::Database)?
+            .iter()
+            .map(|r| RdfLinkRecord {
+                from_note_id: r.get("from_note_id"),
+                to_note_id: r.get("to_note_id"),
+                to_url: r.get("to_url"),
+                kind: r.get("kind"),
+            })
+            .collect();
+
+        let note_concepts = sqlx::query(
+            r#"
+            SELECT note_id, concept_id
+            FROM note_skos_concept
+            WHERE note_id = ANY($1)
+            ORDER BY note_id, concept_id
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .iter()
+        .map(|r| RdfNoteConceptRecord {
+            note_id: r.get("note_id"),
+            concept_id: r.get("concept_id"),
+        })
+        .collect();
+
+        let sql = format!(
+            "SELECT rev.note_id, pe.source_note_id, pe.source_url, pe.relation
+             FROM provenance_edge pe
+             JOIN note_revision rev ON rev.id = pe.revision_id
+             WHERE rev.note_id = ANY($1)
+               AND ((pe.source_note_id IS NULL AND pe.source_url IS NOT NULL) OR {})
+             ORDER BY rev.note_id, pe.id",
+            visible("pe.source_note_id")
+        );
+        let mut query = sqlx::query(&sql).bind(&ids);
+        for param in security.iter().flat_map(|result| &result.params) {
+            query = bind_filter_param!(query, param);
+        }
+        let provenance = query
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(Error::Database)?
+            .iter()
+            .map(|r| RdfProvenanceRecord {
+                note_id: r.get("note_id"),
+                source_note_id: r.get("source_note_id"),
+                source_url: r.get("source_url"),
+                relation: r.get("relation"),
+            })
+            .collect();
+
+        Ok(RdfGraphSnapshot {
+            notes,
+            links,
+            note_concepts,
+            provenance,
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,159 @@
+//! Image embedding repository.
+//!
+//! CLIP vectors for image attachments live in
+//! `attachment_embedding.clip_vector`, grouped under the archive's `images`
+//! embedding set. The `*_tx` methods take a transaction that has already
+//! been pointed at the archive schema.
+
+use sqlx::{Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::defaults::IMAGE_EMBEDDING_SET_SLUG;
+use matric_core::{Error, ImageSearchHit, Result, Vector};
+
+/// Embedding config the `images` set is created against.
+const IMAGE_EMBEDDING_CONFIG_NAME: &str = "clip-vit-b-32";
+
+/// PostgreSQL repository for image embeddings.
+#[derive(Clone)]
+pub struct PgImageEmbeddingRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgImageEmbeddingRepository {
+    /// Create a new image embedding repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// ID of the archive's `images` embedding set, creating it on first use.
+    pub async fn image_set_id_tx(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Uuid> {
+        sqlx::query(
+            "INSERT INTO embedding_set (
+                 name, slug, description, purpose, usage_hints, keywords,
+                 mode, set_type, criteria, embedding_config_id,
+                 is_system, is_active, auto_refresh, index_status
+             ) VALUES (
+                 'Images', $1,
+                 'CLIP embeddings of image attachments.',
+                 'Provides image-by-image and text-to-image similarity search.',
+                 'Searched through /api/v1/search/images. Filled by the image_embedding job, not by note embedding.',
+                 ARRAY['image', 'images', 'photo', 'picture', 'visual'],
+                 'manual', 'full', '{}'::jsonb,
+                 (SELECT id FROM embedding_config WHERE name = $2),
+                 TRUE, TRUE, FALSE, 'ready'
+             )
+             ON CONFLICT (slug) DO NOTHING",
+        )
+        .bind(IMAGE_EMBEDDING_SET_SLUG)
+        .bind(IMAGE_EMBEDDING_CONFIG_NAME)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        sqlx::query_scalar("SELECT id FROM embedding_set WHERE slug = $1")
+            .bind(IMAGE_EMBEDDING_SET_SLUG)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)
+    }
+
+    /// Store an attachment's CLIP vector, replacing any earlier one.
+    pub async fn store_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        attachment_id: Uuid,
+        vector: &Vector,
+        model: &str,
+    ) -> Result<()> {
+        let set_id = self.image_set_id_tx(tx).await?;
+        sqlx::query(
+            "INSERT INTO attachment_embedding (
+                 attachment_id, embedding_set_id, chunk_index, text,
+                 clip_vector, model, embedding_type
+             ) VALUES ($1, $2, 0, '', $3, $4, 'clip')
+             ON CONFLICT (attachment_id, embedding_set_id, chunk_index) DO UPDATE
+             SET clip_vector = EXCLUDED.clip_vector,
+                 model = EXCLUDED.model,
+                 created_at = NOW()",
+        )
+        .bind(attachment_id)
+        .bind(set_id)
+        .bind(vector)
+        .bind(model)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// The stored CLIP vector of an attachment and the note it belongs to.
+    pub async fn get_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        attachment_id: Uuid,
+    ) -> Result<Option<(Uuid, Vector)>> {
+        let row = sqlx::query(
+            "SELECT a.note_id, ae.clip_vector
+             FROM attachment_embedding ae
+             JOIN attachment a ON a.id = ae.attachment_id
+             JOIN embedding_set es ON es.id = ae.embedding_set_id
+             WHERE ae.attachment_id = $1
+               AND es.slug = $2
+               AND ae.clip_vector IS NOT NULL",
+        )
+        .bind(attachment_id)
+        .bind(IMAGE_EMBEDDING_SET_SLUG)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.map(|row| (row.get("note_id"), row.get("clip_vector"))))
+    }
+
+    /// Image attachments of live notes closest to `query`, best first.
+    ///
+    /// `exclude` drops one attachment, typically the query image itself.
+    pub async fn search_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        query: &Vector,
+        limit: i64,
+        exclude: Option<Uuid>,
+    ) -> Result<Vec<ImageSearchHit>> {
+        let rows = sqlx::query(
+            "SELECT a.id AS attachment_id, a.note_id, a.filename, ab.content_type,
+                    (1 - (ae.clip_vector <=> $1))::real AS score
+             FROM attachment_embedding ae
+             JOIN embedding_set es ON es.id = ae.embedding_set_id
+             JOIN attachment a ON a.id = ae.attachment_id
+             JOIN attachment_blob ab ON ab.id = a.blob_id
+             JOIN note n ON n.id = a.note_id
+             WHERE es.slug = $2
+               AND ae.clip_vector IS NOT NULL
+               AND n.deleted_at IS NULL
+               AND ($3::uuid IS NULL OR a.id <> $3)
+             ORDER BY ae.clip_vector <=> $1
+             LIMIT $4",
+        )
+        .bind(query)
+        .bind(IMAGE_EMBEDDING_SET_SLUG)
+        .bind(exclude)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ImageSearchHit {
+                attachment_id: row.get("attachment_id"),
+                note_id: row.get("note_id"),
+                filename: row.get("filename"),
+                content_type: row.get("content_type"),
+                score: row.get("score"),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,149 @@
+//! Inference token and cost accounting repository.
+//!
+//! Rows live in the shared `public` schema and carry the archive's schema
+//! name. The job worker appends one row per model when a job finishes;
+//! reports aggregate them per model and per job type.
+
+use sqlx::{Pool, Postgres, Row};
+use uuid::Uuid;
+
+use matric_core::{
+    new_v7, Error, InferenceUsageFilter, InferenceUsageGroup, InferenceUsageRecord,
+    InferenceUsageSummary, InferenceUsageTotals, Result,
+};
+
+/// Aggregate columns shared by every usage query.
+const USAGE_TOTALS: &str = "
+    COALESCE(SUM(calls), 0)::BIGINT AS calls,
+    COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+    COALESCE(SUM(completion_tokens), 0)::BIGINT AS completion_tokens,
+    COALESCE(SUM(cost_usd), 0)::DOUBLE PRECISION AS cost_usd,
+    COALESCE(SUM(calls) FILTER (WHERE cost_usd IS NULL), 0)::BIGINT AS unpriced_calls";
+
+/// Filter shared by every usage query; binds $1..$4.
+const USAGE_FILTER: &str = "
+    WHERE schema_name = $1
+      AND ($2::TIMESTAMPTZ IS NULL OR created_at_utc >= $2)
+      AND ($3::TIMESTAMPTZ IS NULL OR created_at_utc < $3)
+      AND ($4::UUID IS NULL OR job_id = $4)";
+
+/// PostgreSQL repository for inference usage.
+#[derive(Clone)]
+pub struct PgInferenceUsageRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgInferenceUsageRepository {
+    /// Create a new inference usage repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Store a finished job's usage, one row per model.
+    pub async fn record_job(
+        &self,
+        job_id: Uuid,
+        job_type: &str,
+        schema_name: &str,
+        records: &[InferenceUsageRecord],
+    ) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        for record in records {
+            sqlx::query(
+                "INSERT INTO inference_usage
+                    (id, job_id, job_type, schema_name, model, calls,
+                     prompt_tokens, completion_tokens, cost_usd)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            )
+            .bind(new_v7())
+            .bind(job_id)
+            .bind(job_type)
+            .bind(schema_name)
+            .bind(&record.model)
+            .bind(i32::try_from(record.calls).unwrap_or(i32::MAX))
+            .bind(record.prompt_tokens)
+            .bind(record.completion_tokens)
+            .bind(record.cost_usd)
+            .execute(&mut *tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+        tx.commit().await.map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Usage for an archive, overall and broken down by model and job type.
+    pub async fn summary(
+        &self,
+        schema_name: &str,
+        filter: &InferenceUsageFilter,
+    ) -> Result<InferenceUsageSummary> {
+        let total_row = sqlx::query(&format!(
+            "SELECT {USAGE_TOTALS} FROM inference_usage {USAGE_FILTER}"
+        ))
+        .bind(schema_name)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.job_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        let by_model = self.grouped(schema_name, filter, "model").await?;
+        let by_job_type = self
+            .grouped(schema_name, filter, "COALESCE(job_type, 'unknown')")
+            .await?;
+
+        Ok(InferenceUsageSummary {
+            since: filter.since,
+            until: filter.until,
+            job_id: filter.job_id,
+            total: totals_from_row(&total_row),
+            by_model,
+            by_job_type,
+        })
+    }
+
+    /// Totals grouped by `key_expr`, highest token use first.
+    async fn grouped(
+        &self,
+        schema_name: &str,
+        filter: &InferenceUsageFilter,
+        key_expr: &str,
+    ) -> Result<Vec<InferenceUsageGroup>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {key_expr} AS key, {USAGE_TOTALS}
+             FROM inference_usage {USAGE_FILTER}
+             GROUP BY 1
+             ORDER BY SUM(prompt_tokens + completion_tokens) DESC, 1"
+        ))
+        .bind(schema_name)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.job_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| InferenceUsageGroup {
+                key: row.get("key"),
+                totals: totals_from_row(row),
+            })
+            .collect())
+    }
+}
+
+fn totals_from_row(row: &sqlx::postgres::PgRow) -> InferenceUsageTotals {
+    InferenceUsageTotals {
+        calls: row.get("calls"),
+        prompt_tokens: row.get("prompt_tokens"),
+        completion_tokens: row.get("completion_tokens"),
+        cost_usd: row.get("cost_usd"),
+        unpriced_calls: row.get("unpriced_calls"),
+    }
+}
@@ -1,5 +1,6 @@
 //! Job repository implementation.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -9,6 +10,13 @@ use sqlx::{Pool, Postgres, Row};
 use tokio::sync::Notify;
 use uuid::Uuid;
 
+use matric_core::dead_letter::{self, DeadLetterJob, DeadLetterSelection, DISCARDED_CODE};
+use matric_core::job_lane::JobLane;
+use matric_core::job_worker::{WorkerInfo, WorkerRegistration, WorkerState};
+use matric_core::pipeline::{
+    PipelineDefinition, PipelineRun, PipelineRunJob, PipelineRunStatus, RERUN_RETRY_BUDGET,
+    UPSTREAM_FAILED_CODE,
+};
 use matric_core::{
     new_v7, Error, Job, JobFailureClass, JobRepository, JobRetryOutcome, JobRetryPolicy, JobStatus,
     JobType, QueueStats, Result, TierGroup,
@@ -40,6 +48,56 @@ impl PgJobRepository {
         self.notify.clone()
     }
 
+    /// Queue a job in an explicit lane, e.g. embedding jobs fanned out by a
+    /// bulk re-embed go to [`JobLane::Batch`].
+    pub async fn queue_in_lane(
+        &self,
+        note_id: Option<Uuid>,
+        job_type: JobType,
+        priority: i32,
+        payload: Option<JsonValue>,
+        cost_tier: Option<i16>,
+        lane: JobLane,
+    ) -> Result<Uuid> {
+        let job_id = new_v7();
+        let now = Utc::now();
+        let job_type_str = Self::job_type_to_str(job_type);
+
+        // Get estimated duration
+        let estimated_duration: Option<i32> =
+            sqlx::query_scalar("SELECT estimate_job_duration($1::job_type, NULL)")
+                .bind(job_type_str)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(Error::Database)?
+                .flatten();
+
+        let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        sqlx::query(
+            "INSERT INTO job_queue (id, note_id, job_type, status, priority, payload, estimated_duration_ms, created_at, cost_tier, lane)
+             VALUES ($1, $2, $3::job_type, 'pending'::job_status, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(job_id)
+        .bind(note_id)
+        .bind(job_type_str)
+        .bind(priority)
+        .bind(&payload)
+        .bind(estimated_duration)
+        .bind(now)
+        .bind(cost_tier)
+        .bind(lane.as_str())
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::Database)?;
+        if let Some(note_id) = note_id {
+            Self::link_note_upstream(&mut tx, job_id, note_id, job_type).await?;
+        }
+        tx.commit().await.map_err(Error::Database)?;
+
+        self.notify.notify_waiters();
+        Ok(job_id)
+    }
+
     /// Claim next job for a tier group, excluding jobs from paused archives (Issue #466).
     ///
     /// Jobs with `payload->>'schema'` matching any of `excluded_schemas` are skipped.
@@ -49,6 +107,33 @@ impl PgJobRepository {
         tier_group: TierGroup,
         job_types: &[JobType],
         excluded_schemas: &[String],
+    ) -> Result<Option<Job>> {
+        self.claim_next_for_tier_in_lane(
+            tier_group,
+            job_types,
+            excluded_schemas,
+            JobLane::Interactive,
+            None,
+        )
+        .await
+    }
+
+    /// Claim next job for a tier group, preferring `lane`, on behalf of
+    /// `worker_id`.
+    ///
+    /// A job from `lane` is claimed ahead of any job from the other lane,
+    /// whatever their priorities; the other lane is only used when `lane` has
+    /// no claimable job. Jobs from `excluded_schemas` are skipped as in
+    /// [`claim_next_for_tier_excluding`](Self::claim_next_for_tier_excluding).
+    /// The claiming worker is recorded so its jobs can be reclaimed if it
+    /// stops heartbeating.
+    pub async fn claim_next_for_tier_in_lane(
+        &self,
+        tier_group: TierGroup,
+        job_types: &[JobType],
+        excluded_schemas: &[String],
+        lane: JobLane,
+        worker_id: Option<Uuid>,
     ) -> Result<Option<Job>> {
         let now = Utc::now();
         let type_strings = Self::claim_type_strings(job_types);
@@ -65,7 +150,7 @@ impl PgJobRepository {
         let query = format!(
             "WITH claimed AS (
              UPDATE job_queue
-             SET status = 'running'::job_status, started_at = $1,
+             SET status = 'running'::job_status, started_at = $1, worker_id = $5,
                  next_attempt_at = NULL, failure_class = NULL, failure_code = NULL
              WHERE id = (
                  SELECT id FROM job_queue
@@ -75,7 +160,13 @@ impl PgJobRepository {
                    AND job_type::text = ANY($2)
                    AND (payload->>'schema' IS NULL
                         OR payload->>'schema' NOT IN (SELECT unnest($3::text[])))
-                 ORDER BY priority DESC, created_at ASC
+                   AND NOT EXISTS (
+                       SELECT 1 FROM job_dependency d
+                       JOIN job_queue upstream ON upstream.id = d.depends_on
+                       WHERE d.job_id = job_queue.id
+                         AND upstream.status <> 'completed'::job_status
+                   )
+                 ORDER BY (lane = $4) DESC, priority DESC, created_at ASC
                  LIMIT 1
                  FOR UPDATE SKIP LOCKED
              )
@@ -107,6 +198,8 @@ impl PgJobRepository {
             .bind(now)
             .bind(&type_strings)
             .bind(excluded_schemas)
+            .bind(lane.as_str())
+            .bind(worker_id)
             .fetch_optional(&self.pool)
             .await
             .map_err(Error::Database)?;
@@ -114,6 +207,703 @@ impl PgJobRepository {
         row.map(Self::parse_job_row).transpose()
     }
 
+    /// Queue one job per step of `definition` as a pipeline run.
+    ///
+    /// Steps are inserted in dependency order with a `job_dependency` edge for
+    /// each `depends_on` entry, so a step is claimed only after the steps it
+    /// depends on complete. Every job gets `payload`.
+    pub async fn queue_pipeline(
+        &self,
+        definition: &PipelineDefinition,
+        note_id: Option<Uuid>,
+        payload: Option<JsonValue>,
+    ) -> Result<PipelineRun> {
+        let order = definition.topological_order()?;
+        let run_id = new_v7();
+        let now = Utc::now();
+
+        let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        sqlx::query(
+            "INSERT INTO pipeline_run (id, pipeline, note_id, created_at) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(run_id)
+        .bind(&definition.name)
+        .bind(note_id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let mut job_ids: HashMap<JobType, Uuid> = HashMap::with_capacity(order.len());
+        for job_type in order {
+            let job_id = new_v7();
+            let job_type_str = Self::job_type_to_str(job_type);
+            let estimated_duration: Option<i32> =
+                sqlx::query_scalar("SELECT estimate_job_duration($1::job_type, NULL)")
+                    .bind(job_type_str)
+                    .fetch_optional(&mut *tx)
+                    .await
+                    .map_err(Error::Database)?
+                    .flatten();
+            sqlx::query(
+                "INSERT INTO job_queue (id, note_id, job_type, status, priority, payload, estimated_duration_ms, created_at, cost_tier, pipeline_run_id, lane)
+                 VALUES ($1, $2, $3::job_type, 'pending'::job_status, $4, $5, $6, $7, $8, $9, $10)",
+            )
+            .bind(job_id)
+            .bind(note_id)
+            .bind(job_type_str)
+            .bind(job_type.default_priority())
+            .bind(&payload)
+            .bind(estimated_duration)
+            .bind(now)
+            .bind(job_type.default_cost_tier())
+            .bind(run_id)
+            .bind(job_type.default_lane().as_str())
+            .execute(&mut *tx)
+            .await
+            .map_err(Error::Database)?;
+
+            let step = definition
+                .steps
+                .iter()
+                .find(|step| step.job_type == job_type)
+                .ok_or_else(|| Error::Internal("Pipeline step missing from order".to_string()))?;
+            for dependency in &step.depends_on {
+                sqlx::query(
+                    "INSERT INTO job_dependency (job_id, depends_on) VALUES ($1, $2)
+                     ON CONFLICT DO NOTHING",
+                )
+                .bind(job_id)
+                .bind(job_ids[dependency])
+                .execute(&mut *tx)
+                .await
+                .map_err(Error::Database)?;
+            }
+            job_ids.insert(job_type, job_id);
+        }
+        tx.commit().await.map_err(Error::Database)?;
+
+        self.notify.notify_waiters();
+        self.get_pipeline_run(run_id)
+            .await?
+            .ok_or_else(|| Error::NotFound("Pipeline run not found".to_string()))
+    }
+
+    /// Get a pipeline run with its jobs and their dependencies.
+    pub async fn get_pipeline_run(&self, run_id: Uuid) -> Result<Option<PipelineRun>> {
+        let Some(run) =
+            sqlx::query("SELECT id, pipeline, note_id, created_at FROM pipeline_run WHERE id = $1")
+                .bind(run_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(Error::Database)?
+        else {
+            return Ok(None);
+        };
+
+        let rows = sqlx::query(
+            "SELECT q.id, q.job_type::text, q.status::text, q.failure_code,
+                    ARRAY(
+                        SELECT d.depends_on FROM job_dependency d
+                        WHERE d.job_id = q.id
+                        ORDER BY d.depends_on
+                    ) AS depends_on
+             FROM job_queue q
+             WHERE q.pipeline_run_id = $1
+             ORDER BY q.created_at ASC, q.id ASC",
+        )
+        .bind(run_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        let mut jobs = Vec::with_capacity(rows.len());
+        for row in rows {
+            let job_id: Uuid = row.get("id");
+            let job_type_value: String = row.get("job_type");
+            let status_value: String = row.get("status");
+            jobs.push(PipelineRunJob {
+                job_id,
+                job_type: Self::str_to_job_type(&job_type_value)
+                    .map_err(|_| Self::incompatible_job_row(job_id, "job_type", &job_type_value))?,
+                status: Self::str_to_job_status(&status_value)
+                    .map_err(|_| Self::incompatible_job_row(job_id, "status", &status_value))?,
+                depends_on: row.get("depends_on"),
+                failure_code: row.get("failure_code"),
+            });
+        }
+
+        Ok(Some(PipelineRun {
+            id: run.get("id"),
+            pipeline: run.get("pipeline"),
+            note_id: run.get("note_id"),
+            created_at: run.get("created_at"),
+            status: PipelineRunStatus::from_jobs(jobs.iter().map(|job| &job.status)),
+            jobs,
+        }))
+    }
+
+    /// Return part of a pipeline run to pending.
+    ///
+    /// Without `from_step`, failed and cancelled jobs are re-run. With it, that
+    /// step is re-run even if it completed. Either way every job downstream of
+    /// a re-run job is re-run too, completed steps upstream are kept, and
+    /// running jobs are left alone. Attempt history is kept; each re-run job
+    /// gets a fresh retry budget. Returns the ids of the re-run jobs, or `None`
+    /// when the run does not exist.
+    pub async fn rerun_pipeline(
+        &self,
+        run_id: Uuid,
+        from_step: Option<JobType>,
+    ) -> Result<Option<Vec<Uuid>>> {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM pipeline_run WHERE id = $1)")
+                .bind(run_id)
+                .fetch_one(&self.pool)
+                .await
+                .map_err(Error::Database)?;
+        if !exists {
+            return Ok(None);
+        }
+
+        let job_ids: Vec<Uuid> = sqlx::query_scalar(
+            "WITH RECURSIVE seed AS (
+                 SELECT id FROM job_queue
+                 WHERE pipeline_run_id = $1
+                   AND CASE
+                           WHEN $2::text IS NULL
+                           THEN status IN ('failed'::job_status, 'cancelled'::job_status)
+                           ELSE job_type::text = $2 AND status <> 'running'::job_status
+                       END
+             ),
+             affected AS (
+                 SELECT id FROM seed
+                 UNION
+                 SELECT d.job_id FROM job_dependency d
+                 JOIN affected a ON d.depends_on = a.id
+             ),
+             attempts AS (
+                 SELECT affected.id,
+                        COALESCE(
+                            (SELECT MAX(attempt_number) FROM job_attempt WHERE job_id = affected.id),
+                            0
+                        ) AS made
+                 FROM affected
+             )
+             UPDATE job_queue
+             SET status = 'pending'::job_status,
+                 retry_count = attempts.made,
+                 max_retries = attempts.made + $3,
+                 result = NULL, error_message = NULL,
+                 progress_percent = 0, progress_message = NULL,
+                 started_at = NULL, completed_at = NULL, next_attempt_at = NULL,
+                 failure_class = NULL, failure_code = NULL
+             FROM attempts
+             WHERE job_queue.id = attempts.id
+               AND job_queue.pipeline_run_id = $1
+               AND job_queue.status <> 'running'::job_status
+             RETURNING job_queue.id",
+        )
+        .bind(run_id)
+        .bind(from_step.map(Self::job_type_to_str))
+        .bind(RERUN_RETRY_BUDGET)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        if !job_ids.is_empty() {
+            self.notify.notify_waiters();
+        }
+        Ok(Some(job_ids))
+    }
+
+    /// List dead-lettered jobs, most recently failed first, with the total.
+    pub async fn list_dead_letters(
+        &self,
+        job_type: Option<JobType>,
+        failure_class: Option<JobFailureClass>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<DeadLetterJob>, i64)> {
+        let job_type = job_type.map(Self::job_type_to_str);
+        let failure_class = failure_class.map(JobFailureClass::as_str);
+
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM job_queue
+             WHERE status = 'failed'::job_status
+               AND ($1::text IS NULL OR job_type::text = $1)
+               AND ($2::text IS NULL OR failure_class = $2)",
+        )
+        .bind(job_type)
+        .bind(failure_class)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        let rows = sqlx::query(
+            "SELECT q.id, q.note_id, q.job_type::text AS job_type, q.failure_class,
+                    q.failure_code, q.pipeline_run_id, q.created_at, q.completed_at,
+                    latest.attempt_number, latest.payload_fingerprint
+             FROM job_queue q
+             LEFT JOIN LATERAL (
+                 SELECT attempt_number, payload_fingerprint FROM job_attempt
+                 WHERE job_id = q.id
+                 ORDER BY attempt_number DESC
+                 LIMIT 1
+             ) latest ON true
+             WHERE q.status = 'failed'::job_status
+               AND ($1::text IS NULL OR q.job_type::text = $1)
+               AND ($2::text IS NULL OR q.failure_class = $2)
+             ORDER BY q.completed_at DESC NULLS LAST, q.id DESC
+             LIMIT $3 OFFSET $4",
+        )
+        .bind(job_type)
+        .bind(failure_class)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        let jobs = rows
+            .into_iter()
+            .map(|row| {
+                let job_type: String = row.get("job_type");
+                let failure_class: Option<String> = row.get("failure_class");
+                Ok(DeadLetterJob {
+                    id: row.get("id"),
+                    note_id: row.get("note_id"),
+                    job_type: Self::str_to_job_type(&job_type).map_err(Error::Internal)?,
+                    failure_class: failure_class
+                        .as_deref()
+                        .map(str::parse)
+                        .transpose()
+                        .map_err(Error::Internal)?,
+                    failure_code: row.get("failure_code"),
+                    attempts: row
+                        .get::<Option<i32>, _>("attempt_number")
+                        .unwrap_or_default(),
+                    payload_fingerprint: row.get("payload_fingerprint"),
+                    pipeline_run_id: row.get("pipeline_run_id"),
+                    created_at: row.get("created_at"),
+                    failed_at: row.get("completed_at"),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((jobs, total))
+    }
+
+    /// Return selected dead-lettered jobs to pending with a fresh retry budget.
+    ///
+    /// Dependents cancelled because one of them failed are returned to pending
+    /// too, so they run once it completes. Attempt history is kept, so a
+    /// poison payload is quarantined again on its next retryable failure.
+    /// Returns the ids of every job returned to pending.
+    pub async fn retry_dead_letters(&self, selection: &DeadLetterSelection) -> Result<Vec<Uuid>> {
+        let job_ids: Vec<Uuid> = sqlx::query_scalar(
+            "WITH RECURSIVE seed AS (
+                 SELECT id FROM job_queue
+                 WHERE status = 'failed'::job_status
+                   AND ($1::uuid[] IS NULL OR id = ANY($1))
+                   AND ($2::text IS NULL OR job_type::text = $2)
+                   AND ($3::text IS NULL OR failure_class = $3)
+             ),
+             affected AS (
+                 SELECT id FROM seed
+                 UNION
+                 SELECT d.job_id FROM job_dependency d
+                 JOIN affected a ON d.depends_on = a.id
+                 JOIN job_queue dependent ON dependent.id = d.job_id
+                 WHERE dependent.status = 'cancelled'::job_status
+                   AND dependent.failure_code = $4
+             ),
+             attempts AS (
+                 SELECT affected.id,
+                        COALESCE(
+                            (SELECT MAX(attempt_number) FROM job_attempt WHERE job_id = affected.id),
+                            0
+                        ) AS made
+                 FROM affected
+             )
+             UPDATE job_queue
+             SET status = 'pending'::job_status,
+                 retry_count = attempts.made,
+                 max_retries = attempts.made + $5,
+                 result = NULL, error_message = NULL,
+                 progress_percent = 0, progress_message = NULL,
+                 started_at = NULL, completed_at = NULL, next_attempt_at = NULL,
+                 failure_class = NULL, failure_code = NULL
+             FROM attempts
+             WHERE job_queue.id = attempts.id
+               AND job_queue.status IN ('failed'::job_status, 'cancelled'::job_status)
+             RETURNING job_queue.id",
+        )
+        .bind(selection.job_ids.as_deref())
+        .bind(selection.job_type.map(Self::job_type_to_str))
+        .bind(selection.failure_class.map(JobFailureClass::as_str))
+        .bind(UPSTREAM_FAILED_CODE)
+        .bind(RERUN_RETRY_BUDGET)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        if !job_ids.is_empty() {
+            self.notify.notify_waiters();
+        }
+        Ok(job_ids)
+    }
+
+    /// Cancel selected dead-lettered jobs for good. Returns the discarded ids.
+    pub async fn discard_dead_letters(&self, selection: &DeadLetterSelection) -> Result<Vec<Uuid>> {
+        sqlx::query_scalar(
+            "UPDATE job_queue
+             SET status = 'cancelled'::job_status, failure_code = $4
+             WHERE status = 'failed'::job_status
+               AND ($1::uuid[] IS NULL OR id = ANY($1))
+               AND ($2::text IS NULL OR job_type::text = $2)
+               AND ($3::text IS NULL OR failure_class = $3)
+             RETURNING id",
+        )
+        .bind(selection.job_ids.as_deref())
+        .bind(selection.job_type.map(Self::job_type_to_str))
+        .bind(selection.failure_class.map(JobFailureClass::as_str))
+        .bind(DISCARDED_CODE)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Register a worker, or mark it running again if it re-registers.
+    pub async fn register_worker(&self, registration: &WorkerRegistration) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO job_worker (
+                 id, hostname, pid, version, max_concurrent, heartbeat_timeout_secs
+             )
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (id) DO UPDATE
+             SET last_heartbeat_at = NOW(), stopped_at = NULL",
+        )
+        .bind(registration.id)
+        .bind(&registration.hostname)
+        .bind(i32::try_from(registration.pid).unwrap_or(i32::MAX))
+        .bind(&registration.version)
+        .bind(i32::try_from(registration.max_concurrent).unwrap_or(i32::MAX))
+        .bind(i32::try_from(registration.heartbeat_timeout_secs).unwrap_or(i32::MAX))
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Record a heartbeat. Returns `false` when the worker is not registered.
+    pub async fn heartbeat_worker(&self, worker_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE job_worker
+             SET last_heartbeat_at = NOW(), stopped_at = NULL
+             WHERE id = $1",
+        )
+        .bind(worker_id)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Take or renew the leader lease for `lease_secs`. Returns whether
+    /// `worker_id` is the leader; the lease moves only once it has expired.
+    pub async fn acquire_worker_leadership(
+        &self,
+        worker_id: Uuid,
+        lease_secs: u64,
+    ) -> Result<bool> {
+        let leader: Option<Uuid> = sqlx::query_scalar(
+            "INSERT INTO job_worker_leader (singleton, worker_id, lease_expires_at)
+             VALUES (TRUE, $1, NOW() + $2 * INTERVAL '1 second')
+             ON CONFLICT (singleton) DO UPDATE
+             SET worker_id = EXCLUDED.worker_id,
+                 lease_expires_at = EXCLUDED.lease_expires_at
+             WHERE job_worker_leader.worker_id = EXCLUDED.worker_id
+                OR job_worker_leader.lease_expires_at < NOW()
+             RETURNING worker_id",
+        )
+        .bind(worker_id)
+        .bind(i64::try_from(lease_secs).unwrap_or(i64::MAX).min(86_400))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(leader == Some(worker_id))
+    }
+
+    /// Mark a worker stopped and release its leader lease.
+    pub async fn deregister_worker(&self, worker_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        sqlx::query("UPDATE job_worker SET stopped_at = NOW() WHERE id = $1")
+            .bind(worker_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(Error::Database)?;
+        sqlx::query("DELETE FROM job_worker_leader WHERE worker_id = $1")
+            .bind(worker_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(Error::Database)?;
+        tx.commit().await.map_err(Error::Database)
+    }
+
+    /// Registered workers with their running job counts, newest first.
+    pub async fn list_workers(&self) -> Result<Vec<WorkerInfo>> {
+        let rows = sqlx::query(
+            "SELECT w.id, w.hostname, w.pid, w.version, w.max_concurrent,
+                    w.heartbeat_timeout_secs, w.started_at, w.last_heartbeat_at,
+                    w.stopped_at, NOW() AS observed_at,
+                    COALESCE(l.lease_expires_at > NOW(), FALSE) AS leader,
+                    (SELECT COUNT(*) FROM job_queue q
+                     WHERE q.worker_id = w.id
+                       AND q.status = 'running'::job_status) AS running_jobs
+             FROM job_worker w
+             LEFT JOIN job_worker_leader l ON l.worker_id = w.id
+             ORDER BY w.started_at DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let heartbeat_timeout_secs: i32 = row.get("heartbeat_timeout_secs");
+                let last_heartbeat_at = row.get("last_heartbeat_at");
+                let stopped_at = row.get("stopped_at");
+                WorkerInfo {
+                    id: row.get("id"),
+                    hostname: row.get("hostname"),
+                    pid: row.get("pid"),
+                    version: row.get("version"),
+                    state: WorkerState::classify(
+                        row.get("observed_at"),
+                        last_heartbeat_at,
+                        stopped_at,
+                        u64::try_from(heartbeat_timeout_secs).unwrap_or(0),
+                    ),
+                    leader: row.get("leader"),
+                    max_concurrent: row.get("max_concurrent"),
+                    running_jobs: row.get("running_jobs"),
+                    heartbeat_timeout_secs,
+                    started_at: row.get("started_at"),
+                    last_heartbeat_at,
+                    stopped_at,
+                }
+            })
+            .collect())
+    }
+
+    /// Delete workers that stopped or last heartbeated more than
+    /// `retention_hours` ago and have no running jobs.
+    pub async fn prune_workers(&self, retention_hours: i64) -> Result<i64> {
+        let result = sqlx::query(
+            "DELETE FROM job_worker w
+             WHERE COALESCE(w.stopped_at, w.last_heartbeat_at)
+                   < NOW() - $1 * INTERVAL '1 hour'
+               AND NOT EXISTS (
+                   SELECT 1 FROM job_queue q
+                   WHERE q.worker_id = w.id AND q.status = 'running'::job_status
+               )",
+        )
+        .bind(retention_hours)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(result.rows_affected() as i64)
+    }
+
+    /// Reclaim running jobs whose worker is gone.
+    ///
+    /// A job is orphaned when its worker stopped, was pruned, or missed its
+    /// own heartbeat timeout. Jobs claimed without a worker id (by callers
+    /// outside the worker loop or by workers from before the registry) fall
+    /// back to the `legacy_timeout_secs` started-at cutoff. Orphans are
+    /// retried with a stale-worker backoff, or failed once out of retries.
+    pub async fn reclaim_orphaned_jobs(
+        &self,
+        legacy_timeout_secs: u64,
+        retry_policy: &JobRetryPolicy,
+    ) -> Result<i64> {
+        self.reap_running(
+            "(worker_id IS NULL AND started_at < $1)
+              OR (worker_id IS NOT NULL AND NOT EXISTS (
+                  SELECT 1 FROM job_worker w
+                  WHERE w.id = job_queue.worker_id
+                    AND w.stopped_at IS NULL
+                    AND w.last_heartbeat_at
+                        >= NOW() - w.heartbeat_timeout_secs * INTERVAL '1 second'
+              ))",
+            legacy_timeout_secs,
+            retry_policy,
+        )
+        .await
+    }
+
+    /// Return running jobs matching `stale_filter` to pending, or fail them
+    /// once their retries are exhausted. `$1` in the filter is the cutoff
+    /// `timeout_secs` ago.
+    async fn reap_running(
+        &self,
+        stale_filter: &str,
+        timeout_secs: u64,
+        retry_policy: &JobRetryPolicy,
+    ) -> Result<i64> {
+        let cutoff = Utc::now() - chrono::Duration::seconds(timeout_secs as i64);
+
+        let query = format!(
+            "WITH stale AS (
+                 SELECT id, retry_count, max_retries, started_at,
+                        LEAST(
+                            $2::BIGINT,
+                            $3::BIGINT * (1::BIGINT << LEAST(retry_count, 10))
+                        ) AS base_delay_ms
+                 FROM job_queue
+                 WHERE status = 'running'::job_status
+                   AND ({stale_filter})
+                 FOR UPDATE SKIP LOCKED
+             ),
+             scheduled AS (
+                 SELECT stale.*,
+                        NOW() + (
+                            LEAST(
+                                $2::BIGINT,
+                                base_delay_ms
+                                + (
+                                    get_byte(uuid_send(id), 15)
+                                    % (
+                                        GREATEST(
+                                            1,
+                                            (base_delay_ms * $4::BIGINT / 100)::INTEGER
+                                        ) + 1
+                                    )
+                                )
+                            ) * INTERVAL '1 millisecond'
+                        ) AS retry_at
+                 FROM stale
+             ),
+             retried AS (
+                 UPDATE job_queue
+                 SET status = 'pending'::job_status,
+                     worker_id = NULL,
+                     retry_count = job_queue.retry_count + 1,
+                     error_message = 'Reaped: job orphaned after worker restart',
+                     started_at = NULL,
+                     progress_percent = 0,
+                     progress_message = NULL,
+                     next_attempt_at = stale.retry_at,
+                     failure_class = 'stale_worker',
+                     failure_code = 'worker_lease_expired'
+                 FROM scheduled AS stale
+                 WHERE job_queue.id = stale.id
+                   AND stale.retry_count < stale.max_retries
+                 RETURNING job_queue.id, job_queue.retry_count, stale.retry_at
+             ),
+             retried_attempts AS (
+                 UPDATE job_attempt
+                 SET outcome = 'stale_reaped',
+                     completed_at = NOW(),
+                     retry_at = retried.retry_at,
+                     duration_ms = GREATEST(
+                         0,
+                         EXTRACT(EPOCH FROM (NOW() - job_attempt.started_at)) * 1000
+                     )::BIGINT,
+                     failure_class = 'stale_worker',
+                     failure_code = 'worker_lease_expired'
+                 FROM retried
+                 WHERE job_attempt.job_id = retried.id
+                   AND job_attempt.attempt_number = retried.retry_count
+                   AND job_attempt.outcome = 'running'
+                 RETURNING job_attempt.job_id
+             ),
+             exhausted AS (
+                 UPDATE job_queue
+                 SET status = 'failed'::job_status,
+                     worker_id = NULL,
+                     completed_at = NOW(),
+                     error_message = 'Reaped: job orphaned after worker restart (retries exhausted)',
+                     next_attempt_at = NULL,
+                     failure_class = 'stale_worker',
+                     failure_code = 'retry_exhausted'
+                 FROM scheduled AS stale
+                 WHERE job_queue.id = stale.id
+                   AND stale.retry_count >= stale.max_retries
+                 RETURNING job_queue.id, job_queue.retry_count
+             ),
+             exhausted_attempts AS (
+                 UPDATE job_attempt
+                 SET outcome = 'terminal_failed',
+                     completed_at = NOW(),
+                     duration_ms = GREATEST(
+                         0,
+                         EXTRACT(EPOCH FROM (NOW() - job_attempt.started_at)) * 1000
+                     )::BIGINT,
+                     failure_class = 'stale_worker',
+                     failure_code = 'retry_exhausted'
+                 FROM exhausted
+                 WHERE job_attempt.job_id = exhausted.id
+                   AND job_attempt.attempt_number = exhausted.retry_count + 1
+                   AND job_attempt.outcome = 'running'
+                 RETURNING job_attempt.job_id
+             )
+             SELECT (SELECT COUNT(*) FROM retried) + (SELECT COUNT(*) FROM exhausted) AS total"
+        );
+        let result = sqlx::query(&query)
+            .bind(cutoff)
+            .bind(i64::try_from(retry_policy.max_delay_ms).map_err(|_| {
+                Error::InvalidInput("Job retry maximum delay exceeds database bounds".to_string())
+            })?)
+            .bind(
+                i64::try_from(retry_policy.stale_worker_base_delay_ms).map_err(|_| {
+                    Error::InvalidInput(
+                        "Stale-worker retry delay exceeds database bounds".to_string(),
+                    )
+                })?,
+            )
+            .bind(i64::from(retry_policy.jitter_percent))
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        Ok(result.get::<i64, _>("total"))
+    }
+
+    /// Make a job queued for a note wait for that note's pending or running
+    /// jobs of its upstream types in the NLP pipeline.
+    async fn link_note_upstream(
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        job_id: Uuid,
+        note_id: Uuid,
+        job_type: JobType,
+    ) -> Result<()> {
+        let upstream: Vec<String> = PipelineDefinition::nlp()
+            .upstream_of(job_type)
+            .into_iter()
+            .map(|job_type| job_type.as_str().to_string())
+            .collect();
+        if upstream.is_empty() {
+            return Ok(());
+        }
+        sqlx::query(
+            "INSERT INTO job_dependency (job_id, depends_on)
+             SELECT $1, id FROM job_queue
+             WHERE note_id = $2 AND id <> $1
+               AND status IN ('pending'::job_status, 'running'::job_status)
+               AND job_type::text = ANY($3)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(job_id)
+        .bind(note_id)
+        .bind(&upstream)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
     /// Convert JobType to string for database.
     fn job_type_to_str(job_type: JobType) -> &'static str {
         job_type.as_str()
@@ -250,37 +1040,15 @@ impl JobRepository for PgJobRepository {
         payload: Option<JsonValue>,
         cost_tier: Option<i16>,
     ) -> Result<Uuid> {
-        let job_id = new_v7();
-        let now = Utc::now();
-        let job_type_str = Self::job_type_to_str(job_type);
-
-        // Get estimated duration
-        let estimated_duration: Option<i32> =
-            sqlx::query_scalar("SELECT estimate_job_duration($1::job_type, NULL)")
-                .bind(job_type_str)
-                .fetch_optional(&self.pool)
-                .await
-                .map_err(Error::Database)?
-                .flatten();
-
-        sqlx::query(
-            "INSERT INTO job_queue (id, note_id, job_type, status, priority, payload, estimated_duration_ms, created_at, cost_tier)
-             VALUES ($1, $2, $3::job_type, 'pending'::job_status, $4, $5, $6, $7, $8)",
-        )
-        .bind(job_id)
-        .bind(note_id)
-        .bind(job_type_str)
-        .bind(priority)
-        .bind(&payload)
-        .bind(estimated_duration)
-        .bind(now)
-        .bind(cost_tier)
-        .execute(&self.pool)
-        .await
-        .map_err(Error::Database)?;
-
-        self.notify.notify_waiters();
-        Ok(job_id)
+        self.queue_in_lane(
+            note_id,
+            job_type,
+            priority,
+            payload,
+            cost_tier,
+            job_type.default_lane(),
+        )
+        .await
     }
 
     async fn queue_deduplicated(
@@ -308,9 +1076,10 @@ impl JobRepository for PgJobRepository {
                     .map_err(Error::Database)?
                     .flatten();
 
+            let mut tx = self.pool.begin().await.map_err(Error::Database)?;
             let result = sqlx::query_scalar::<_, Uuid>(
-                "INSERT INTO job_queue (id, note_id, job_type, status, priority, payload, estimated_duration_ms, created_at, cost_tier)
-                 SELECT $1, $2, $3::job_type, 'pending'::job_status, $4, $5, $6, $7, $8
+                "INSERT INTO job_queue (id, note_id, job_type, status, priority, payload, estimated_duration_ms, created_at, cost_tier, lane)
+                 SELECT $1, $2, $3::job_type, 'pending'::job_status, $4, $5, $6, $7, $8, $9
                  WHERE NOT EXISTS (
                      SELECT 1 FROM job_queue
                      WHERE note_id = $2 AND job_type = $3::job_type
@@ -326,9 +1095,14 @@ impl JobRepository for PgJobRepository {
             .bind(estimated_duration)
             .bind(now)
             .bind(cost_tier)
-            .fetch_optional(&self.pool)
+            .bind(job_type.default_lane().as_str())
+            .fetch_optional(&mut *tx)
             .await
             .map_err(Error::Database)?;
+            if let Some(job_id) = result {
+                Self::link_note_upstream(&mut tx, job_id, nid, job_type).await?;
+            }
+            tx.commit().await.map_err(Error::Database)?;
 
             if result.is_some() {
                 self.notify.notify_waiters();
@@ -349,8 +1123,8 @@ impl JobRepository for PgJobRepository {
                     .flatten();
 
             let result = sqlx::query_scalar::<_, Uuid>(
-                "INSERT INTO job_queue (id, note_id, job_type, status, priority, payload, estimated_duration_ms, created_at, cost_tier)
-                 SELECT $1, NULL, $2::job_type, 'pending'::job_status, $3, $4, $5, $6, $7
+                "INSERT INTO job_queue (id, note_id, job_type, status, priority, payload, estimated_duration_ms, created_at, cost_tier, lane)
+                 SELECT $1, NULL, $2::job_type, 'pending'::job_status, $3, $4, $5, $6, $7, $8
                  WHERE NOT EXISTS (
                      SELECT 1 FROM job_queue
                      WHERE note_id IS NULL AND job_type = $2::job_type
@@ -365,6 +1139,7 @@ impl JobRepository for PgJobRepository {
             .bind(estimated_duration)
             .bind(now)
             .bind(cost_tier)
+            .bind(job_type.default_lane().as_str())
             .fetch_optional(&self.pool)
             .await
             .map_err(Error::Database)?;
@@ -431,8 +1206,8 @@ impl JobRepository for PgJobRepository {
                 .flatten();
 
         sqlx::query(
-            "INSERT INTO job_queue (id, note_id, job_type, status, priority, payload, estimated_duration_ms, created_at, cost_tier)
-             VALUES ($1, $2, $3::job_type, 'pending'::job_status, $4, $5, $6, $7, $8)",
+            "INSERT INTO job_queue (id, note_id, job_type, status, priority, payload, estimated_duration_ms, created_at, cost_tier, lane)
+             VALUES ($1, $2, $3::job_type, 'pending'::job_status, $4, $5, $6, $7, $8, $9)",
         )
         .bind(job_id)
         .bind(note_id)
@@ -442,6 +1217,7 @@ impl JobRepository for PgJobRepository {
         .bind(estimated_duration)
         .bind(now)
         .bind(cost_tier)
+        .bind(job_type.default_lane().as_str())
         .execute(&mut *tx)
         .await
         .map_err(Error::Database)?;
@@ -466,14 +1242,20 @@ impl JobRepository for PgJobRepository {
         let row = sqlx::query(
             "WITH claimed AS (
              UPDATE job_queue
-             SET status = 'running'::job_status, started_at = $1,
+             SET status = 'running'::job_status, started_at = $1, worker_id = NULL,
                  next_attempt_at = NULL, failure_class = NULL, failure_code = NULL
              WHERE id = (
                  SELECT id FROM job_queue
                  WHERE status = 'pending'::job_status
                    AND (next_attempt_at IS NULL OR next_attempt_at <= $1)
                    AND job_type::text = ANY($2)
-                 ORDER BY priority DESC, created_at ASC
+                   AND NOT EXISTS (
+                       SELECT 1 FROM job_dependency d
+                       JOIN job_queue upstream ON upstream.id = d.depends_on
+                       WHERE d.job_id = job_queue.id
+                         AND upstream.status <> 'completed'::job_status
+                   )
+                 ORDER BY (lane = 'interactive') DESC, priority DESC, created_at ASC
                  LIMIT 1
                  FOR UPDATE SKIP LOCKED
              )
@@ -514,66 +1296,8 @@ impl JobRepository for PgJobRepository {
         tier_group: TierGroup,
         job_types: &[JobType],
     ) -> Result<Option<Job>> {
-        let now = Utc::now();
-        let type_strings = Self::claim_type_strings(job_types);
-
-        // Build tier filter clause based on group.
-        let tier_clause = match tier_group {
-            TierGroup::CpuAndAgnostic => "(cost_tier IS NULL OR cost_tier = 0)",
-            TierGroup::AudioGpu => "cost_tier = 5",
-            TierGroup::FastGpu => "cost_tier = 1",
-            TierGroup::StandardGpu => "cost_tier = 2",
-            TierGroup::RenderGpu => "cost_tier = 4",
-            TierGroup::VisionGpu => "cost_tier = 3",
-        };
-
-        let query = format!(
-            "WITH claimed AS (
-             UPDATE job_queue
-             SET status = 'running'::job_status, started_at = $1,
-                 next_attempt_at = NULL, failure_class = NULL, failure_code = NULL
-             WHERE id = (
-                 SELECT id FROM job_queue
-                 WHERE status = 'pending'::job_status
-                   AND (next_attempt_at IS NULL OR next_attempt_at <= $1)
-                   AND {tier_clause}
-                   AND job_type::text = ANY($2)
-                 ORDER BY priority DESC, created_at ASC
-                 LIMIT 1
-                 FOR UPDATE SKIP LOCKED
-             )
-             RETURNING id, note_id, job_type::text, status::text, priority, payload, result,
-                       error_message, progress_percent, progress_message, retry_count, max_retries,
-                       created_at, started_at, completed_at, cost_tier
-             ),
-             attempt AS (
-                 INSERT INTO job_attempt (
-                     id, job_id, attempt_number, started_at, payload_size,
-                     payload_fingerprint, archive_schema
-                 )
-                 SELECT uuidv7(), id, retry_count + 1, $1,
-                        octet_length(payload::text),
-                        encode(sha256(convert_to(payload::text, 'UTF8')), 'hex'),
-                        CASE
-                            WHEN payload->>'schema' ~ '^[a-z][a-z0-9_]{{0,62}}$'
-                            THEN payload->>'schema'
-                            ELSE NULL
-                        END
-                 FROM claimed
-                 RETURNING job_id
-             )
-             SELECT claimed.* FROM claimed
-             JOIN attempt ON attempt.job_id = claimed.id"
-        );
-
-        let row = sqlx::query(&query)
-            .bind(now)
-            .bind(&type_strings)
-            .fetch_optional(&self.pool)
+        self.claim_next_for_tier_in_lane(tier_group, job_types, &[], JobLane::Interactive, None)
             .await
-            .map_err(Error::Database)?;
-
-        row.map(Self::parse_job_row).transpose()
     }
 
     async fn pending_count_for_tier(&self, tier: i16) -> Result<i64> {
@@ -614,6 +1338,30 @@ impl JobRepository for PgJobRepository {
         Ok(())
     }
 
+    async fn get_checkpoint(&self, job_id: Uuid) -> Result<Option<JsonValue>> {
+        let checkpoint: Option<Option<JsonValue>> =
+            sqlx::query_scalar("SELECT checkpoint FROM job_queue WHERE id = $1")
+                .bind(job_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(Error::Database)?;
+        Ok(checkpoint.flatten())
+    }
+
+    async fn save_checkpoint(&self, job_id: Uuid, checkpoint: &JsonValue) -> Result<bool> {
+        let updated = sqlx::query(
+            "UPDATE job_queue
+             SET checkpoint = $1, checkpoint_at = NOW()
+             WHERE id = $2 AND status = 'running'::job_status",
+        )
+        .bind(checkpoint)
+        .bind(job_id)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(updated.rows_affected() > 0)
+    }
+
     async fn complete(&self, job_id: Uuid, result: Option<JsonValue>) -> Result<()> {
         let now = Utc::now();
 
@@ -639,7 +1387,8 @@ impl JobRepository for PgJobRepository {
         sqlx::query(
             "UPDATE job_queue
              SET status = 'completed'::job_status, completed_at = $1, result = $2,
-                 progress_percent = 100, actual_duration_ms = $3
+                 progress_percent = 100, actual_duration_ms = $3,
+                 checkpoint = NULL, checkpoint_at = NULL
              WHERE id = $4 AND status = 'running'::job_status",
         )
         .bind(now)
@@ -677,7 +1426,17 @@ impl JobRepository for PgJobRepository {
             .map_err(Error::Database)?;
         }
 
+        let has_dependents: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM job_dependency WHERE depends_on = $1)")
+                .bind(job_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(Error::Database)?;
+
         tx.commit().await.map_err(Error::Database)?;
+        if has_dependents {
+            self.notify.notify_waiters();
+        }
         Ok(())
     }
 
@@ -715,7 +1474,24 @@ impl JobRepository for PgJobRepository {
         .await
         .map_err(Error::Database)?;
 
-        if retry_count < max_retries {
+        // Failed attempts of the same payload across every job of this type.
+        let prior_failures: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM job_attempt a
+             JOIN job_queue q ON q.id = a.job_id
+             JOIN job_attempt current
+               ON current.job_id = $1 AND current.attempt_number = $2
+             WHERE a.payload_fingerprint = current.payload_fingerprint
+               AND q.job_type = (SELECT job_type FROM job_queue WHERE id = $1)
+               AND a.outcome IN ('retry_scheduled', 'terminal_failed')",
+        )
+        .bind(job_id)
+        .bind(retry_count + 1)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Error::Database)?;
+        let poisoned = dead_letter::is_poison(prior_failures);
+
+        if retry_count < max_retries && !poisoned {
             sqlx::query(
                 "UPDATE job_queue
                  SET status = 'pending'::job_status, retry_count = $1, error_message = $2,
@@ -754,58 +1530,69 @@ impl JobRepository for PgJobRepository {
             return Ok(JobRetryOutcome::Scheduled {
                 next_attempt_at: retry_at,
             });
-        } else {
-            sqlx::query(
-                "UPDATE job_queue
-                 SET status = 'failed'::job_status, completed_at = $1, error_message = $2,
-                     next_attempt_at = NULL, failure_class = $3, failure_code = $4
-                 WHERE id = $5",
+        }
+
+        let (terminal_class, terminal_code, outcome) = if poisoned {
+            (
+                JobFailureClass::Poison,
+                dead_letter::POISON_PAYLOAD_CODE,
+                JobRetryOutcome::Quarantined,
             )
-            .bind(now)
-            .bind(error)
-            .bind(failure_class.as_str())
-            .bind("retry_exhausted")
-            .bind(job_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(Error::Database)?;
+        } else {
+            (failure_class, "retry_exhausted", JobRetryOutcome::Exhausted)
+        };
 
-            let job_type: String =
-                sqlx::query_scalar("SELECT job_type::text FROM job_queue WHERE id = $1")
-                    .bind(job_id)
-                    .fetch_one(&mut *tx)
-                    .await
-                    .map_err(Error::Database)?;
+        sqlx::query(
+            "UPDATE job_queue
+             SET status = 'failed'::job_status, completed_at = $1, error_message = $2,
+                 next_attempt_at = NULL, failure_class = $3, failure_code = $4
+             WHERE id = $5",
+        )
+        .bind(now)
+        .bind(error)
+        .bind(terminal_class.as_str())
+        .bind(terminal_code)
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::Database)?;
 
-            sqlx::query(
-                "INSERT INTO job_history (id, job_type, duration_ms, payload_size, success, created_at)
-                 VALUES ($1, $2::job_type, 0, NULL, false, $3)",
-            )
-            .bind(new_v7())
-            .bind(&job_type)
-            .bind(now)
-            .execute(&mut *tx)
-            .await
-            .map_err(Error::Database)?;
+        let job_type: String =
+            sqlx::query_scalar("SELECT job_type::text FROM job_queue WHERE id = $1")
+                .bind(job_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(Error::Database)?;
 
-            sqlx::query(
-                "UPDATE job_attempt
-                 SET outcome = 'terminal_failed', completed_at = $1,
-                     duration_ms = GREATEST(0, EXTRACT(EPOCH FROM ($1 - started_at)) * 1000)::BIGINT,
-                     failure_class = $2, failure_code = 'retry_exhausted'
-                 WHERE job_id = $3 AND attempt_number = $4 AND outcome = 'running'",
-            )
-            .bind(now)
-            .bind(failure_class.as_str())
-            .bind(job_id)
-            .bind(retry_count + 1)
-            .execute(&mut *tx)
-            .await
-            .map_err(Error::Database)?;
-        }
+        sqlx::query(
+            "INSERT INTO job_history (id, job_type, duration_ms, payload_size, success, created_at)
+             VALUES ($1, $2::job_type, 0, NULL, false, $3)",
+        )
+        .bind(new_v7())
+        .bind(&job_type)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::Database)?;
+
+        sqlx::query(
+            "UPDATE job_attempt
+             SET outcome = 'terminal_failed', completed_at = $1,
+                 duration_ms = GREATEST(0, EXTRACT(EPOCH FROM ($1 - started_at)) * 1000)::BIGINT,
+                 failure_class = $2, failure_code = $3
+             WHERE job_id = $4 AND attempt_number = $5 AND outcome = 'running'",
+        )
+        .bind(now)
+        .bind(terminal_class.as_str())
+        .bind(terminal_code)
+        .bind(job_id)
+        .bind(retry_count + 1)
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::Database)?;
 
         tx.commit().await.map_err(Error::Database)?;
-        Ok(JobRetryOutcome::Exhausted)
+        Ok(outcome)
     }
 
     async fn fail(
@@ -1094,116 +1881,8 @@ impl JobRepository for PgJobRepository {
         timeout_secs: u64,
         retry_policy: &JobRetryPolicy,
     ) -> Result<i64> {
-        let cutoff = Utc::now() - chrono::Duration::seconds(timeout_secs as i64);
-
-        let result = sqlx::query(
-            "WITH stale AS (
-                 SELECT id, retry_count, max_retries, started_at,
-                        LEAST(
-                            $2::BIGINT,
-                            $3::BIGINT * (1::BIGINT << LEAST(retry_count, 10))
-                        ) AS base_delay_ms
-                 FROM job_queue
-                 WHERE status = 'running'::job_status
-                   AND started_at < $1
-                 FOR UPDATE SKIP LOCKED
-             ),
-             scheduled AS (
-                 SELECT stale.*,
-                        NOW() + (
-                            LEAST(
-                                $2::BIGINT,
-                                base_delay_ms
-                                + (
-                                    get_byte(uuid_send(id), 15)
-                                    % (
-                                        GREATEST(
-                                            1,
-                                            (base_delay_ms * $4::BIGINT / 100)::INTEGER
-                                        ) + 1
-                                    )
-                                )
-                            ) * INTERVAL '1 millisecond'
-                        ) AS retry_at
-                 FROM stale
-             ),
-             retried AS (
-                 UPDATE job_queue
-                 SET status = 'pending'::job_status,
-                     retry_count = job_queue.retry_count + 1,
-                     error_message = 'Reaped: job orphaned after worker restart',
-                     started_at = NULL,
-                     progress_percent = 0,
-                     progress_message = NULL,
-                     next_attempt_at = stale.retry_at,
-                     failure_class = 'stale_worker',
-                     failure_code = 'worker_lease_expired'
-                 FROM scheduled AS stale
-                 WHERE job_queue.id = stale.id
-                   AND stale.retry_count < stale.max_retries
-                 RETURNING job_queue.id, job_queue.retry_count, stale.retry_at
-             ),
-             retried_attempts AS (
-                 UPDATE job_attempt
-                 SET outcome = 'stale_reaped',
-                     completed_at = NOW(),
-                     retry_at = retried.retry_at,
-                     duration_ms = GREATEST(
-                         0,
-                         EXTRACT(EPOCH FROM (NOW() - job_attempt.started_at)) * 1000
-                     )::BIGINT,
-                     failure_class = 'stale_worker',
-                     failure_code = 'worker_lease_expired'
-                 FROM retried
-                 WHERE job_attempt.job_id = retried.id
-                   AND job_attempt.attempt_number = retried.retry_count
-                   AND job_attempt.outcome = 'running'
-                 RETURNING job_attempt.job_id
-             ),
-             exhausted AS (
-                 UPDATE job_queue
-                 SET status = 'failed'::job_status,
-                     completed_at = NOW(),
-                     error_message = 'Reaped: job orphaned after worker restart (retries exhausted)',
-                     next_attempt_at = NULL,
-                     failure_class = 'stale_worker',
-                     failure_code = 'retry_exhausted'
-                 FROM scheduled AS stale
-                 WHERE job_queue.id = stale.id
-                   AND stale.retry_count >= stale.max_retries
-                 RETURNING job_queue.id, job_queue.retry_count
-             ),
-             exhausted_attempts AS (
-                 UPDATE job_attempt
-                 SET outcome = 'terminal_failed',
-                     completed_at = NOW(),
-                     duration_ms = GREATEST(
-                         0,
-                         EXTRACT(EPOCH FROM (NOW() - job_attempt.started_at)) * 1000
-                     )::BIGINT,
-                     failure_class = 'stale_worker',
-                     failure_code = 'retry_exhausted'
-                 FROM exhausted
-                 WHERE job_attempt.job_id = exhausted.id
-                   AND job_attempt.attempt_number = exhausted.retry_count + 1
-                   AND job_attempt.outcome = 'running'
-                 RETURNING job_attempt.job_id
-             )
-             SELECT (SELECT COUNT(*) FROM retried) + (SELECT COUNT(*) FROM exhausted) AS total",
-        )
-        .bind(cutoff)
-        .bind(i64::try_from(retry_policy.max_delay_ms).map_err(|_| {
-            Error::InvalidInput("Job retry maximum delay exceeds database bounds".to_string())
-        })?)
-        .bind(i64::try_from(retry_policy.stale_worker_base_delay_ms).map_err(|_| {
-            Error::InvalidInput("Stale-worker retry delay exceeds database bounds".to_string())
-        })?)
-        .bind(i64::from(retry_policy.jitter_percent))
-        .fetch_one(&self.pool)
-        .await
-        .map_err(Error::Database)?;
-
-        Ok(result.get::<i64, _>("total"))
+        self.reap_running("started_at < $1", timeout_secs, retry_policy)
+            .await
     }
 }
 
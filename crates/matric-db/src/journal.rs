@@ -0,0 +1,241 @@
+//! Journal repository.
+//!
+//! Every method takes a transaction that has already been pointed at the
+//! archive schema. Journal days are computed by PostgreSQL in the journal
+//! time zone, so any zone in `pg_timezone_names` works.
+//!
+//! Each day has one journal note per owner: a user's journal note links the
+//! notes the user owns, and the journal without an owner (`None`) links the
+//! notes without one.
+
+use chrono::{NaiveDate, Utc};
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::journal::JOURNAL_LINK_KIND;
+use matric_core::{new_v7, Error, JournalSettings, Result};
+
+use crate::links::PgLinkRepository;
+
+const SETTINGS_COLUMNS: &str =
+    "template_id, collection_id, title_format, timezone, link_notes, updated_at_utc";
+
+/// PostgreSQL repository for journal settings and daily notes.
+#[derive(Clone)]
+pub struct PgJournalRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgJournalRepository {
+    /// Create a new journal repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// The archive's journal settings; the defaults until they are saved.
+    pub async fn settings_tx(&self, tx: &mut Transaction<'_, Postgres>) -> Result<JournalSettings> {
+        let settings = sqlx::query_as::<_, JournalSettings>(&format!(
+            "SELECT {SETTINGS_COLUMNS} FROM journal_settings"
+        ))
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(settings.unwrap_or_default())
+    }
+
+    /// Replace the archive's journal settings.
+    ///
+    /// Rejects time zones PostgreSQL does not know and templates or
+    /// collections that do not exist.
+    pub async fn save_settings_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        settings: &JournalSettings,
+    ) -> Result<JournalSettings> {
+        let known: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_timezone_names WHERE name = $1)")
+                .bind(&settings.timezone)
+                .fetch_one(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+        if !known {
+            return Err(Error::InvalidInput(format!(
+                "Unknown time zone; timezone_len={}",
+                settings.timezone.len()
+            )));
+        }
+
+        sqlx::query_as::<_, JournalSettings>(&format!(
+            "INSERT INTO journal_settings
+                 (id, template_id, collection_id, title_format, timezone, link_notes, updated_at_utc)
+             VALUES (TRUE, $1, $2, $3, $4, $5, NOW())
+             ON CONFLICT (id) DO UPDATE SET
+                 template_id = EXCLUDED.template_id,
+                 collection_id = EXCLUDED.collection_id,
+                 title_format = EXCLUDED.title_format,
+                 timezone = EXCLUDED.timezone,
+                 link_notes = EXCLUDED.link_notes,
+                 updated_at_utc = NOW()
+             RETURNING {SETTINGS_COLUMNS}"
+        ))
+        .bind(settings.template_id)
+        .bind(settings.collection_id)
+        .bind(&settings.title_format)
+        .bind(&settings.timezone)
+        .bind(settings.link_notes)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_err) if db_err.is_foreign_key_violation() => {
+                Error::InvalidInput("Journal template or collection not found".to_string())
+            }
+            e => Error::Database(e),
+        })
+    }
+
+    /// Hold the archive's journal lock until the transaction ends, so
+    /// concurrent requests for today's note create it once.
+    pub async fn lock_tx(&self, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+        sqlx::query(
+            "SELECT pg_advisory_xact_lock(hashtextextended('journal:' || current_schema(), 0))",
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// The current day in `timezone`.
+    pub async fn today_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        timezone: &str,
+    ) -> Result<NaiveDate> {
+        sqlx::query_scalar("SELECT (NOW() AT TIME ZONE $1)::date")
+            .bind(timezone)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)
+    }
+
+    /// `owner`'s journal note of `day`, unless it has been deleted.
+    pub async fn entry_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        day: NaiveDate,
+        owner: Option<Uuid>,
+    ) -> Result<Option<Uuid>> {
+        sqlx::query_scalar(
+            "SELECT je.note_id
+             FROM journal_entry je
+             JOIN note n ON n.id = je.note_id
+             WHERE je.day = $1
+               AND je.owner_id IS NOT DISTINCT FROM $2
+               AND n.deleted_at IS NULL",
+        )
+        .bind(day)
+        .bind(owner)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Make `note_id` `owner`'s journal note of `day`.
+    pub async fn set_entry_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        day: NaiveDate,
+        owner: Option<Uuid>,
+        note_id: Uuid,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO journal_entry (day, owner_id, note_id, created_at_utc)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (day, owner_id) DO UPDATE SET
+                 note_id = EXCLUDED.note_id,
+                 created_at_utc = EXCLUDED.created_at_utc",
+        )
+        .bind(day)
+        .bind(owner)
+        .bind(note_id)
+        .bind(Utc::now())
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Link `owner`'s journal note of `day` to every live note of `owner`
+    /// created that day in `timezone`. Returns the number of notes linked.
+    pub async fn link_day_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        journal_note_id: Uuid,
+        day: NaiveDate,
+        timezone: &str,
+        owner: Option<Uuid>,
+    ) -> Result<usize> {
+        let note_ids: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT n.id
+             FROM note n
+             WHERE (n.created_at_utc AT TIME ZONE $3)::date = $2
+               AND n.id <> $1
+               AND n.owner_id IS NOT DISTINCT FROM $5
+               AND n.deleted_at IS NULL
+               AND NOT EXISTS (
+                   SELECT 1 FROM link l
+                   WHERE l.from_note_id = $1 AND l.to_note_id = n.id AND l.kind = $4
+               )",
+        )
+        .bind(journal_note_id)
+        .bind(day)
+        .bind(timezone)
+        .bind(JOURNAL_LINK_KIND)
+        .bind(owner)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let links = PgLinkRepository::new(self.pool.clone());
+        for note_id in &note_ids {
+            links
+                .create_tx(tx, journal_note_id, *note_id, JOURNAL_LINK_KIND, 1.0, None)
+                .await?;
+        }
+        Ok(note_ids.len())
+    }
+
+    /// Link `note_id` from its owner's journal note of the day it was
+    /// created, when that day has one and the archive links notes into its
+    /// journal. Returns whether a link was created.
+    pub async fn link_note_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO link (id, from_note_id, to_note_id, to_url, kind, score, created_at_utc)
+             SELECT $2, je.note_id, n.id, NULL, $3, 1.0, NOW()
+             FROM note n
+             LEFT JOIN journal_settings s ON TRUE
+             JOIN journal_entry je
+               ON je.day = (n.created_at_utc AT TIME ZONE COALESCE(s.timezone, 'UTC'))::date
+              AND je.owner_id IS NOT DISTINCT FROM n.owner_id
+             WHERE n.id = $1
+               AND COALESCE(s.link_notes, TRUE)
+               AND je.note_id <> n.id
+               AND n.deleted_at IS NULL
+               AND NOT EXISTS (
+                   SELECT 1 FROM link l
+                   WHERE l.from_note_id = je.note_id AND l.to_note_id = n.id AND l.kind = $3
+               )",
+        )
+        .bind(note_id)
+        .bind(new_v7())
+        .bind(JOURNAL_LINK_KIND)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -31,42 +31,86 @@
 //!     Ok(())
 //! }
 //! ```
+pub mod archive_merge;
+pub mod archive_quotas;
 pub mod archives;
+pub mod backup_policies;
+pub mod backup_state;
+pub mod calendar;
 pub mod call_sessions;
 pub mod chunking;
 pub mod colbert;
+pub mod collection_rules;
 pub mod collections;
+pub mod concept_suggestions;
+pub mod digests;
 pub mod document_types;
 pub mod embedding_sets;
 pub mod embeddings;
+pub mod entities;
+pub mod event_history;
+pub mod federation;
 pub mod file_storage;
+pub mod fine_tuning;
+pub mod graph_analytics;
+pub mod graph_export;
 pub mod hashtag_extraction;
+pub mod image_embeddings;
 pub mod inbound_sources;
 pub mod incoming_webhooks;
+pub mod inference_usage;
 pub mod jobs;
+pub mod journal;
+pub mod link_suggestions;
 pub mod links;
+mod links_diff_tx;
+mod links_path_tx;
+mod links_typed_tx;
 pub mod memory_search;
+pub mod mempack;
+pub mod note_bulk;
+pub mod note_views;
 pub mod notes;
 pub mod oauth;
 pub mod outbox;
+pub mod pii;
+pub mod pinned;
 pub mod pke_keys;
 pub mod pke_keysets;
 pub mod pool;
+pub mod prompt_templates;
 pub mod provenance;
+pub mod provisioning;
+pub mod publishing;
+pub mod quarantine;
+pub mod references;
+pub mod resurface;
+pub mod reviews;
 pub mod schema_context;
 pub mod schema_validation;
 pub mod search;
+mod skos_governance_tx;
+mod skos_health_tx;
+mod skos_mappings_tx;
 pub mod skos_tags;
 mod skos_tags_tx;
 pub mod strict_filter;
+pub mod summaries;
 #[cfg(feature = "tree-sitter")]
 pub mod syntactic_chunker;
 pub mod tags;
+pub mod tasks;
 pub mod templates;
+pub mod topics;
+pub mod translations;
+pub mod trash;
 pub mod tus;
 pub mod unified_filter;
+pub mod usage_counters;
 pub mod usage_ledger;
+pub mod users;
 pub mod versioning;
+pub mod visibility;
 pub mod webhooks;
 
 #[cfg(test)]
@@ -89,8 +133,8 @@ pub fn escape_like(input: &str) -> String {
 
 // Re-export chunking types
 pub use chunking::{
-    Chunk, Chunker, ChunkerConfig, ParagraphChunker, RecursiveChunker, SemanticChunker,
-    SentenceChunker, SlidingWindowChunker,
+    chunker_for_strategy, Chunk, Chunker, ChunkerConfig, ParagraphChunker, RecursiveChunker,
+    SemanticChunker, SentenceChunker, SlidingWindowChunker,
 };
 
 #[cfg(feature = "tree-sitter")]
@@ -102,48 +146,110 @@ pub use inbound_sources::PgInboundSourceRepository;
 pub use incoming_webhooks::{
     validate_incoming_webhook_payload, PgIncomingWebhookReceiverRepository,
 };
+pub use inference_usage::PgInferenceUsageRepository;
 
 // Re-export repository implementations
+pub use archive_merge::{
+    ArchiveMergeConflict, ArchiveMergeMode, ArchiveMergeOptions, ArchiveMergeProgress,
+    ArchiveMergeReport,
+};
+pub use archive_quotas::PgArchiveQuotaRepository;
 pub use archives::PgArchiveRepository;
+pub use backup_policies::{
+    PgBackupPolicyRepository, BACKUP_RUN_FAILED, BACKUP_RUN_RUNNING, BACKUP_RUN_SUCCEEDED,
+};
+pub use backup_state::{
+    blob_link, note_snapshot_link, BackupDelta, BackupState, PgBackupStateRepository,
+};
+pub use calendar::PgCalendarRepository;
 pub use call_sessions::PgCallSessionRepository;
 pub use colbert::{ColBERTRepository, ColBERTStats, TokenEmbedding};
+pub use collection_rules::PgCollectionRuleRepository;
 pub use collections::PgCollectionRepository;
-pub use document_types::PgDocumentTypeRepository;
+pub use concept_suggestions::PgConceptSuggestionRepository;
+pub use digests::{
+    DueDigest, PgDigestRepository, DIGEST_RUN_EMPTY, DIGEST_RUN_FAILED, DIGEST_RUN_SUCCEEDED,
+};
+pub use document_types::{check_metadata_schema, validate_note_metadata, PgDocumentTypeRepository};
 pub use embedding_sets::PgEmbeddingSetRepository;
 pub use embeddings::{utils as embedding_utils, PgEmbeddingRepository};
+pub use entities::PgEntityRepository;
+pub use event_history::{EventHistoryFilter, PgEventHistoryRepository, StoredEvent};
+pub use federation::{
+    PgSyncPeerRepository, SyncCursorKind, SYNC_RUN_FAILED, SYNC_RUN_RUNNING, SYNC_RUN_SUCCEEDED,
+};
 pub use file_storage::{
-    compute_content_hash, generate_storage_path, AttachmentScanFile, FileDownloadInfo, FileSource,
-    FilesystemBackend, PgFileStorageRepository, StagedShardBlob, StagedShardBlobPromotion,
-    StorageBackend,
+    compute_content_hash, generate_storage_path, AttachmentEncryption, AttachmentScanFile,
+    BlobReencryptionBatch, FileDownloadInfo, FileSource, FilesystemBackend, OrphanedBlob,
+    PgFileStorageRepository, StagedShardBlob, StagedShardBlobPromotion, StorageBackend,
 };
+pub use fine_tuning::{NewFineTuningSample, PgFineTuningRepository};
+pub use graph_export::PgGraphExportRepository;
+pub use image_embeddings::PgImageEmbeddingRepository;
 pub use jobs::{get_extraction_stats, PgJobRepository};
+pub use journal::PgJournalRepository;
+pub use link_suggestions::PgLinkSuggestionRepository;
 pub use links::{
     CoarseCommunityResult, DiagnosticsComparison, DiagnosticsSnapshot, GraphDiagnostics, GraphEdge,
     GraphMeta, GraphNode, GraphResult, PfnetResult, PgLinkRepository, SnnResult, TopologyStats,
 };
 pub use memory_search::{MemorySearchRepository, PgMemorySearchRepository};
-pub use notes::{ListNotesWithFilterRequest, ListNotesWithFilterResponse, PgNoteRepository};
+pub use mempack::{
+    read_mempack, MempackAttachment, MempackCounts, MempackEmbedding, MempackExportSummary,
+    MempackImportOptions, MempackImportReport, MempackItem, MempackLink, MempackManifest,
+    MempackNote, MempackWriter, PgMempackRepository, MEMPACK_CONTENT_TYPE,
+    MEMPACK_DEFAULT_MAX_ENTRY_BYTES, MEMPACK_FORMAT, MEMPACK_VERSION,
+};
+pub use note_bulk::PgBulkNoteRepository;
+pub use note_views::PgNoteViewRepository;
+pub use notes::{
+    EncryptedNoteContent, ListNotesWithFilterRequest, ListNotesWithFilterResponse, PgNoteRepository,
+};
 pub use oauth::PgOAuthRepository;
 pub use outbox::{CreateOutboxEvent, EventOutboxRecord, PgEventOutboxRepository};
+pub use pii::PgPiiFindingRepository;
+pub use pinned::PgPinnedRepository;
 pub use pke_keys::{PgPkeKeyRepository, PkePublicKey};
 pub use pke_keysets::{
-    CreateKeysetRequest, ExportedKeyset, PgPkeKeysetRepository, PkeKeyset, PkeKeysetSummary,
+    CreateKeysetRequest, ExportedKeyset, PgPkeKeysetRepository, PkeKeyset, PkeKeysetRetirement,
+    PkeKeysetSigner, PkeKeysetSummary,
 };
-pub use pool::{create_pool, create_pool_with_config, log_pool_metrics, PoolConfig};
+pub use pool::{
+    create_pool, create_pool_with_config, log_pool_metrics, record_pool_metrics, PoolConfig,
+};
+pub use prompt_templates::PgPromptTemplateRepository;
 pub use provenance::PgProvenanceRepository;
+pub use provisioning::{
+    builtin_provisioning_profile, builtin_provisioning_profiles, PgProvisioningRepository,
+    ProvisionedCollection, ProvisionedConcept, ProvisionedPromptTemplate, ProvisionedScheme,
+    ProvisioningProfile, ProvisioningReport,
+};
+pub use publishing::PgPublicationRepository;
+pub use quarantine::PgQuarantineRepository;
+pub use references::PgReferenceRepository;
+pub use resurface::PgResurfaceRepository;
+pub use reviews::PgReviewRepository;
 pub use schema_context::SchemaContext;
 pub use schema_validation::validate_schema_name;
 pub use search::PgFtsSearch;
 pub use strict_filter::{QueryParam, StrictFilterQueryBuilder};
+pub use summaries::PgNoteSummaryRepository;
 pub use tags::PgTagRepository;
+pub use tasks::PgTaskRepository;
 pub use templates::PgTemplateRepository;
+pub use topics::PgTopicRepository;
+pub use translations::PgNoteTranslationRepository;
+pub use trash::{PgTrashRepository, TrashPurgeBatch};
 pub use tus::PgTusRepository;
 pub use unified_filter::{UnifiedFilterQueryBuilder, UnifiedFilterResult};
+pub use usage_counters::PgUsageCounterRepository;
 pub use usage_ledger::{
     PgUsageLedgerRepository, UsageDeliveryClaim, UsageLedgerRecord, UsageRecordOutcome,
 };
+pub use users::PgUserRepository;
 pub use versioning::{
-    NoteVersions, OriginalVersion, RevisionVersionSummary, VersionSummary, VersioningRepository,
+    MergeSources, NoteVersions, OriginalVersion, RevisionVersionSummary, VersionSummary,
+    VersioningRepository,
 };
 pub use webhooks::PgWebhookRepository;
 
@@ -199,6 +305,8 @@ pub struct Database {
     pub file_storage: Option<PgFileStorageRepository>,
     /// File storage base path for cloning (used by Clone impl to reconstruct backend).
     file_storage_path: Option<String>,
+    /// Encrypt-at-rest configuration reapplied to file storage on clone.
+    attachment_encryption: Option<AttachmentEncryption>,
     /// SKOS tags repository (convenience alias).
     pub skos_tags: PgSkosRepository,
     /// Webhook repository for outbound HTTP notifications (Issue #44).
@@ -209,16 +317,82 @@ pub struct Database {
     pub inbound_sources: PgInboundSourceRepository,
     /// Shared durable event outbox for write-path event publication.
     pub outbox: PgEventOutboxRepository,
+    /// Durable event history backing the activity feed.
+    pub event_history: PgEventHistoryRepository,
+    /// Portable `.mempack` archive export and import.
+    pub mempack: PgMempackRepository,
+    /// Provisioning profiles applied to new archives.
+    pub provisioning: PgProvisioningRepository,
     /// Immutable usage ledger and per-sink delivery state.
     pub usage_ledger: PgUsageLedgerRepository,
+    /// Per-principal daily usage counters.
+    pub usage_counters: PgUsageCounterRepository,
     /// PKE public key registry (Issue #113).
     pub pke_keys: PgPkeKeyRepository,
     /// PKE keyset repository for REST API (Issues #328, #332).
     pub pke_keysets: PgPkeKeysetRepository,
     /// Tus resumable upload session repository (Issue #528).
     pub tus: PgTusRepository,
+    /// Content-hash state for incremental backups.
+    pub backup_state: PgBackupStateRepository,
+    /// Scheduled backup policies and their run history.
+    pub backup_policies: PgBackupPolicyRepository,
+    /// Cross-instance sync peers and the archive change feed.
+    pub sync_peers: PgSyncPeerRepository,
     /// Provider-agnostic real-time call session repository (Issues #839/#845).
     pub call_sessions: PgCallSessionRepository,
+    /// Users, resource ownership and share grants.
+    pub users: PgUserRepository,
+    /// Versioned prompt templates for background AI jobs.
+    pub prompt_templates: PgPromptTemplateRepository,
+    /// Token and cost accounting for background job inference.
+    pub inference_usage: PgInferenceUsageRepository,
+    /// AI-generated note summaries.
+    pub summaries: PgNoteSummaryRepository,
+    /// Entity registry populated by LLM entity extraction.
+    pub entities: PgEntityRepository,
+    /// Scheduled daily and weekly activity digests.
+    pub digests: PgDigestRepository,
+    /// Note topics found by clustering embeddings.
+    pub topics: PgTopicRepository,
+    /// Fine-tuning datasets of question-answer pairs generated from notes.
+    pub fine_tuning: PgFineTuningRepository,
+    /// CLIP embeddings of image attachments.
+    pub image_embeddings: PgImageEmbeddingRepository,
+    /// Machine translations of notes and per-archive translation settings.
+    pub translations: PgNoteTranslationRepository,
+    /// Per-archive storage quotas and usage.
+    pub archive_quotas: PgArchiveQuotaRepository,
+    /// Soft-deleted notes awaiting restore or purge.
+    pub trash: PgTrashRepository,
+    /// PII found in notes and their attachments.
+    pub pii: PgPiiFindingRepository,
+    /// Works cited by notes, for citations and bibliographies.
+    pub references: PgReferenceRepository,
+    /// Collections published as public, read-only sites.
+    pub publications: PgPublicationRepository,
+    /// Notes and attachments held back by ingestion policies.
+    pub quarantine: PgQuarantineRepository,
+    /// Concept suggestions held for review and per-concept review thresholds.
+    pub concept_suggestions: PgConceptSuggestionRepository,
+    /// Smart collection rules and the notes they filed.
+    pub collection_rules: PgCollectionRuleRepository,
+    /// Link suggestion feedback and the per-archive linking threshold tuning.
+    pub link_suggestions: PgLinkSuggestionRepository,
+    /// Journal settings and the daily notes they create.
+    pub journal: PgJournalRepository,
+    /// Tasks found in notes by task extraction.
+    pub tasks: PgTaskRepository,
+    /// Dated items of calendar feeds.
+    pub calendar: PgCalendarRepository,
+    /// Forgotten notes sampled for resurfacing.
+    pub resurface: PgResurfaceRepository,
+    /// Notes opened, per principal.
+    pub note_views: PgNoteViewRepository,
+    /// Pinned notes and their priorities.
+    pub pinned: PgPinnedRepository,
+    /// Filtered bulk note updates.
+    pub bulk_notes: PgBulkNoteRepository,
 }
 
 impl Database {
@@ -247,16 +421,50 @@ impl Database {
             colbert: ColBERTRepository::new(pool.clone()),
             file_storage: None,
             file_storage_path: None,
+            attachment_encryption: None,
             skos_tags: skos,
             webhooks: PgWebhookRepository::new(pool.clone()),
             incoming_webhooks: PgIncomingWebhookReceiverRepository::new(pool.clone()),
             inbound_sources: PgInboundSourceRepository::new(pool.clone()),
             outbox: PgEventOutboxRepository::new(pool.clone()),
+            event_history: PgEventHistoryRepository::new(pool.clone()),
+            mempack: PgMempackRepository::new(pool.clone()),
+            provisioning: PgProvisioningRepository::new(pool.clone()),
             usage_ledger: PgUsageLedgerRepository::new(pool.clone()),
+            usage_counters: PgUsageCounterRepository::new(pool.clone()),
             pke_keys: PgPkeKeyRepository::new(pool.clone()),
             pke_keysets: PgPkeKeysetRepository::new(pool.clone()),
             tus: PgTusRepository::new(pool.clone()),
+            backup_state: PgBackupStateRepository::new(pool.clone()),
+            backup_policies: PgBackupPolicyRepository::new(pool.clone()),
+            sync_peers: PgSyncPeerRepository::new(pool.clone()),
+            users: PgUserRepository::new(pool.clone()),
             call_sessions: PgCallSessionRepository::new(pool.clone()),
+            prompt_templates: PgPromptTemplateRepository::new(pool.clone()),
+            inference_usage: PgInferenceUsageRepository::new(pool.clone()),
+            summaries: PgNoteSummaryRepository::new(pool.clone()),
+            entities: PgEntityRepository::new(pool.clone()),
+            digests: PgDigestRepository::new(pool.clone()),
+            topics: PgTopicRepository::new(pool.clone()),
+            fine_tuning: PgFineTuningRepository::new(pool.clone()),
+            image_embeddings: PgImageEmbeddingRepository::new(pool.clone()),
+            translations: PgNoteTranslationRepository::new(pool.clone()),
+            archive_quotas: PgArchiveQuotaRepository::new(pool.clone()),
+            trash: PgTrashRepository::new(pool.clone()),
+            pii: PgPiiFindingRepository::new(pool.clone()),
+            references: PgReferenceRepository::new(pool.clone()),
+            publications: PgPublicationRepository::new(pool.clone()),
+            quarantine: PgQuarantineRepository::new(pool.clone()),
+            concept_suggestions: PgConceptSuggestionRepository::new(pool.clone()),
+            collection_rules: PgCollectionRuleRepository::new(pool.clone()),
+            link_suggestions: PgLinkSuggestionRepository::new(pool.clone()),
+            journal: PgJournalRepository::new(pool.clone()),
+            tasks: PgTaskRepository::new(pool.clone()),
+            calendar: PgCalendarRepository::new(pool.clone()),
+            resurface: PgResurfaceRepository::new(pool.clone()),
+            note_views: PgNoteViewRepository::new(pool.clone()),
+            pinned: PgPinnedRepository::new(pool.clone()),
+            bulk_notes: PgBulkNoteRepository::new(pool.clone()),
             pool,
         }
     }
@@ -294,6 +502,19 @@ impl Database {
         self
     }
 
+    /// Encrypt newly stored attachment blobs at rest for a keyset.
+    ///
+    /// Must be called after the file storage backend is configured; the
+    /// setting is kept so that `Clone` applies it to the rebuilt repository.
+    pub fn with_attachment_encryption(mut self, encryption: AttachmentEncryption) -> Self {
+        self.file_storage = self
+            .file_storage
+            .take()
+            .map(|storage| storage.with_encryption(encryption.clone()));
+        self.attachment_encryption = Some(encryption);
+        self
+    }
+
     /// Return the configured filesystem backend without exposing its base path.
     ///
     /// Knowledge Shard import uses this to stage and promote verified sidecars
@@ -530,23 +751,61 @@ impl Clone for Database {
             memory_search: PgMemorySearchRepository::new(self.pool.clone()),
             colbert: ColBERTRepository::new(self.pool.clone()),
             file_storage: self.file_storage_path.as_ref().map(|path| {
-                PgFileStorageRepository::new(
+                let storage = PgFileStorageRepository::new(
                     self.pool.clone(),
                     FilesystemBackend::new(path),
                     10_485_760,
-                )
+                );
+                match &self.attachment_encryption {
+                    Some(encryption) => storage.with_encryption(encryption.clone()),
+                    None => storage,
+                }
             }),
             file_storage_path: self.file_storage_path.clone(),
+            attachment_encryption: self.attachment_encryption.clone(),
             skos_tags: skos,
             webhooks: PgWebhookRepository::new(self.pool.clone()),
             incoming_webhooks: PgIncomingWebhookReceiverRepository::new(self.pool.clone()),
             inbound_sources: PgInboundSourceRepository::new(self.pool.clone()),
             outbox: PgEventOutboxRepository::new(self.pool.clone()),
+            event_history: PgEventHistoryRepository::new(self.pool.clone()),
+            mempack: PgMempackRepository::new(self.pool.clone()),
+            provisioning: PgProvisioningRepository::new(self.pool.clone()),
             usage_ledger: PgUsageLedgerRepository::new(self.pool.clone()),
+            usage_counters: PgUsageCounterRepository::new(self.pool.clone()),
             pke_keys: PgPkeKeyRepository::new(self.pool.clone()),
             pke_keysets: PgPkeKeysetRepository::new(self.pool.clone()),
             tus: PgTusRepository::new(self.pool.clone()),
+            backup_state: PgBackupStateRepository::new(self.pool.clone()),
+            backup_policies: PgBackupPolicyRepository::new(self.pool.clone()),
+            sync_peers: PgSyncPeerRepository::new(self.pool.clone()),
+            users: PgUserRepository::new(self.pool.clone()),
             call_sessions: PgCallSessionRepository::new(self.pool.clone()),
+            prompt_templates: PgPromptTemplateRepository::new(self.pool.clone()),
+            inference_usage: PgInferenceUsageRepository::new(self.pool.clone()),
+            summaries: PgNoteSummaryRepository::new(self.pool.clone()),
+            entities: PgEntityRepository::new(self.pool.clone()),
+            digests: PgDigestRepository::new(self.pool.clone()),
+            topics: PgTopicRepository::new(self.pool.clone()),
+            fine_tuning: PgFineTuningRepository::new(self.pool.clone()),
+            image_embeddings: PgImageEmbeddingRepository::new(self.pool.clone()),
+            translations: PgNoteTranslationRepository::new(self.pool.clone()),
+            archive_quotas: PgArchiveQuotaRepository::new(self.pool.clone()),
+            trash: PgTrashRepository::new(self.pool.clone()),
+            pii: PgPiiFindingRepository::new(self.pool.clone()),
+            references: PgReferenceRepository::new(self.pool.clone()),
+            publications: PgPublicationRepository::new(self.pool.clone()),
+            quarantine: PgQuarantineRepository::new(self.pool.clone()),
+            concept_suggestions: PgConceptSuggestionRepository::new(self.pool.clone()),
+            collection_rules: PgCollectionRuleRepository::new(self.pool.clone()),
+            link_suggestions: PgLinkSuggestionRepository::new(self.pool.clone()),
+            journal: PgJournalRepository::new(self.pool.clone()),
+            tasks: PgTaskRepository::new(self.pool.clone()),
+            calendar: PgCalendarRepository::new(self.pool.clone()),
+            resurface: PgResurfaceRepository::new(self.pool.clone()),
+            note_views: PgNoteViewRepository::new(self.pool.clone()),
+            pinned: PgPinnedRepository::new(self.pool.clone()),
+            bulk_notes: PgBulkNoteRepository::new(self.pool.clone()),
         }
     }
 }
@@ -0,0 +1,423 @@
+//! Link suggestion repository.
+//!
+//! Suggestions are computed on request from embeddings, concept tags and the
+//! link graph; only decisions and the archive's threshold tuning are stored.
+//! Every method takes a transaction that has already been pointed at the
+//! archive schema.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::link_suggestion::{
+    adjusted_link_threshold_offset, link_suggestion_score, LINK_SUGGESTION_NEIGHBORS,
+};
+use matric_core::{
+    new_v7, Error, LinkSuggestion, LinkSuggestionConcept, LinkSuggestionFeedback, LinkSuggestions,
+    LinkThresholdTuning, Result, StrictSecurityFilter,
+};
+
+use crate::links::PgLinkRepository;
+use crate::visibility::visible_note_ids;
+
+/// Link kind created by accepting a suggestion.
+const ACCEPTED_LINK_KIND: &str = "semantic";
+
+/// PostgreSQL repository for link suggestions and their feedback.
+#[derive(Clone)]
+pub struct PgLinkSuggestionRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgLinkSuggestionRepository {
+    /// Create a new link suggestion repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// The archive's linking threshold tuning; zero offset if no decision
+    /// has been made.
+    pub async fn tuning_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<LinkThresholdTuning> {
+        let tuning = sqlx::query_as::<_, LinkThresholdTuning>(
+            "SELECT threshold_offset AS \"offset\", accepted_count, rejected_count
+             FROM link_threshold_tuning",
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(tuning.unwrap_or_default())
+    }
+
+    /// Up to `limit` notes `note_id` is not linked to in either direction,
+    /// best first.
+    ///
+    /// Pairs a reviewer rejected, archived and soft-deleted notes, and notes
+    /// `security` does not admit are left out. Returns `NotFound` when the
+    /// note is missing or hidden.
+    pub async fn suggestions_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        limit: i64,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<LinkSuggestions> {
+        ensure_visible(tx, &[note_id], security).await?;
+
+        let mut suggestions = candidates(tx, note_id, None).await?;
+        let excluded: HashSet<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT to_note_id FROM link WHERE from_note_id = $1 AND to_note_id IS NOT NULL
+            UNION
+            SELECT from_note_id FROM link WHERE to_note_id = $1
+            UNION
+            SELECT CASE WHEN note_id = $1 THEN target_note_id ELSE note_id END
+            FROM link_suggestion_feedback
+            WHERE (note_id = $1 OR target_note_id = $1) AND NOT accepted
+            "#,
+        )
+        .bind(note_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .into_iter()
+        .collect();
+        suggestions.retain(|suggestion| !excluded.contains(&suggestion.note_id));
+        if let Some(security) = security.filter(|security| !security.is_empty()) {
+            let ids: Vec<Uuid> = suggestions.iter().map(|s| s.note_id).collect();
+            let visible = visible_note_ids(&mut **tx, &ids, security).await?;
+            suggestions.retain(|suggestion| visible.contains(&suggestion.note_id));
+        }
+        suggestions.truncate(limit.max(0) as usize);
+
+        Ok(LinkSuggestions {
+            note_id,
+            suggestions,
+            threshold: self.tuning_tx(tx).await?,
+        })
+    }
+
+    /// Record a reviewer accepting or rejecting `target_note_id` as a link
+    /// for `note_id`.
+    ///
+    /// Accepting links the notes both ways unless they are already linked.
+    /// Either way the decision replaces any earlier one for the pair and the
+    /// archive's threshold offset moves one step: down after an accept, up
+    /// after a reject.
+    pub async fn feedback_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        target_note_id: Uuid,
+        accepted: bool,
+        reviewed_by: Option<&str>,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<LinkSuggestionFeedback> {
+        if note_id == target_note_id {
+            return Err(Error::InvalidInput(
+                "A note cannot be linked to itself".to_string(),
+            ));
+        }
+        ensure_visible(tx, &[note_id, target_note_id], security).await?;
+
+        let candidate = candidates(tx, note_id, Some(target_note_id))
+            .await?
+            .into_iter()
+            .next();
+        let score = candidate.as_ref().map_or(0.0, |c| c.score.clamp(0.0, 1.0));
+
+        let mut link_created = false;
+        if accepted {
+            let linked: bool = sqlx::query_scalar(
+                "SELECT EXISTS (
+                     SELECT 1 FROM link
+                     WHERE (from_note_id = $1 AND to_note_id = $2)
+                        OR (from_note_id = $2 AND to_note_id = $1))",
+            )
+            .bind(note_id)
+            .bind(target_note_id)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            if !linked {
+                let metadata = serde_json::json!({
+                    "strategy": "suggestion_accepted",
+                    "reasons": candidate.map(|c| c.reasons).unwrap_or_default(),
+                });
+                PgLinkRepository::new(self.pool.clone())
+                    .create_reciprocal_tx(
+                        tx,
+                        note_id,
+                        target_note_id,
+                        ACCEPTED_LINK_KIND,
+                        score,
+                        Some(metadata),
+                    )
+                    .await?;
+                link_created = true;
+            }
+        }
+
+        let now = Utc::now();
+        sqlx::query(
+            "INSERT INTO link_suggestion_feedback
+                 (id, note_id, target_note_id, accepted, score, reviewed_by, created_at_utc)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT ((LEAST(note_id, target_note_id)), (GREATEST(note_id, target_note_id)))
+             DO UPDATE SET
+                 note_id = EXCLUDED.note_id,
+                 target_note_id = EXCLUDED.target_note_id,
+                 accepted = EXCLUDED.accepted,
+                 score = EXCLUDED.score,
+                 reviewed_by = EXCLUDED.reviewed_by,
+                 created_at_utc = EXCLUDED.created_at_utc",
+        )
+        .bind(new_v7())
+        .bind(note_id)
+        .bind(target_note_id)
+        .bind(accepted)
+        .bind(score)
+        .bind(reviewed_by)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let current: Option<f32> =
+            sqlx::query_scalar("SELECT threshold_offset FROM link_threshold_tuning FOR UPDATE")
+                .fetch_optional(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+        let threshold = sqlx::query_as::<_, LinkThresholdTuning>(
+            "INSERT INTO link_threshold_tuning
+                 (id, threshold_offset, accepted_count, rejected_count, updated_at_utc)
+             VALUES (TRUE, $1, $2, $3, $4)
+             ON CONFLICT (id) DO UPDATE SET
+                 threshold_offset = EXCLUDED.threshold_offset,
+                 accepted_count = link_threshold_tuning.accepted_count
+                     + EXCLUDED.accepted_count,
+                 rejected_count = link_threshold_tuning.rejected_count
+                     + EXCLUDED.rejected_count,
+                 updated_at_utc = EXCLUDED.updated_at_utc
+             RETURNING threshold_offset AS \"offset\", accepted_count, rejected_count",
+        )
+        .bind(adjusted_link_threshold_offset(
+            current.unwrap_or(0.0),
+            accepted,
+        ))
+        .bind(i32::from(accepted))
+        .bind(i32::from(!accepted))
+        .bind(now)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(LinkSuggestionFeedback {
+            note_id,
+            target_note_id,
+            accepted,
+            score,
+            link_created,
+            threshold,
+        })
+    }
+}
+
+/// Fail with `NotFound` unless every note exists, is not soft-deleted and is
+/// admitted by `security`.
+async fn ensure_visible(
+    tx: &mut Transaction<'_, Postgres>,
+    note_ids: &[Uuid],
+    security: Option<&StrictSecurityFilter>,
+) -> Result<()> {
+    let found: Vec<Uuid> =
+        sqlx::query_scalar("SELECT id FROM note WHERE id = ANY($1) AND deleted_at IS NULL")
+            .bind(note_ids)
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+    let visible = match security.filter(|security| !security.is_empty()) {
+        Some(security) => visible_note_ids(&mut **tx, &found, security).await?,
+        None => found.into_iter().collect(),
+    };
+    if note_ids.iter().all(|id| visible.contains(id)) {
+        Ok(())
+    } else {
+        Err(Error::NotFound("Note not found".to_string()))
+    }
+}
+
+/// Scored candidates for `note_id`, best first: its nearest notes by
+/// embedding, notes sharing its concepts and notes cited alongside it, or
+/// only `only` when given. Archived and soft-deleted notes are left out;
+/// existing links and earlier feedback are not considered.
+async fn candidates(
+    tx: &mut Transaction<'_, Postgres>,
+    note_id: Uuid,
+    only: Option<Uuid>,
+) -> Result<Vec<LinkSuggestion>> {
+    let similar: Vec<(Uuid, f64)> = sqlx::query_as(
+        r#"
+        WITH source AS (
+            SELECT vector FROM embedding WHERE note_id = $1 ORDER BY chunk_index LIMIT 1
+        )
+        SELECT note_id, MAX(similarity) FROM (
+            SELECT e.note_id, 1.0 - (e.vector <=> s.vector) AS similarity
+            FROM embedding e CROSS JOIN source s
+            WHERE e.note_id <> $1 AND ($2::uuid IS NULL OR e.note_id = $2)
+            ORDER BY e.vector <=> s.vector
+            LIMIT $3
+        ) nearest
+        GROUP BY note_id
+        "#,
+    )
+    .bind(note_id)
+    .bind(only)
+    .bind(LINK_SUGGESTION_NEIGHBORS * 4)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+
+    let shared: Vec<(Uuid, Vec<Uuid>)> = sqlx::query_as(
+        r#"
+        SELECT t.note_id, array_agg(t.concept_id ORDER BY t.concept_id)
+        FROM note_skos_concept s
+        JOIN note_skos_concept t ON t.concept_id = s.concept_id AND t.note_id <> s.note_id
+        WHERE s.note_id = $1 AND ($2::uuid IS NULL OR t.note_id = $2)
+        GROUP BY t.note_id
+        ORDER BY COUNT(*) DESC, t.note_id
+        LIMIT $3
+        "#,
+    )
+    .bind(note_id)
+    .bind(only)
+    .bind(LINK_SUGGESTION_NEIGHBORS)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+
+    let co_cited: Vec<(Uuid, i64)> = sqlx::query_as(
+        r#"
+        SELECT b.to_note_id, COUNT(DISTINCT a.from_note_id)
+        FROM link a
+        JOIN link b ON b.from_note_id = a.from_note_id
+        WHERE a.to_note_id = $1
+          AND b.to_note_id IS NOT NULL AND b.to_note_id <> $1
+          AND ($2::uuid IS NULL OR b.to_note_id = $2)
+        GROUP BY b.to_note_id
+        ORDER BY COUNT(DISTINCT a.from_note_id) DESC, b.to_note_id
+        LIMIT $3
+        "#,
+    )
+    .bind(note_id)
+    .bind(only)
+    .bind(LINK_SUGGESTION_NEIGHBORS)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+
+    let similarity: HashMap<Uuid, f32> = similar
+        .into_iter()
+        .map(|(id, similarity)| (id, similarity as f32))
+        .collect();
+    let shared: HashMap<Uuid, Vec<Uuid>> = shared.into_iter().collect();
+    let co_cited: HashMap<Uuid, i64> = co_cited.into_iter().collect();
+    let mut ids: Vec<Uuid> = similarity
+        .keys()
+        .chain(shared.keys())
+        .chain(co_cited.keys())
+        .copied()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let titles: HashMap<Uuid, Option<String>> = sqlx::query_as(
+        "SELECT id, title FROM note
+         WHERE id = ANY($1) AND deleted_at IS NULL AND archived IS NOT TRUE",
+    )
+    .bind(&ids)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?
+    .into_iter()
+    .collect();
+    ids.retain(|id| titles.contains_key(id));
+
+    let mut counted = ids.clone();
+    counted.push(note_id);
+    let concept_counts: HashMap<Uuid, i64> = sqlx::query_as(
+        "SELECT note_id, COUNT(*) FROM note_skos_concept WHERE note_id = ANY($1) GROUP BY note_id",
+    )
+    .bind(&counted)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?
+    .into_iter()
+    .collect();
+    let concept_ids: Vec<Uuid> = shared
+        .values()
+        .flatten()
+        .copied()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let labels: HashMap<Uuid, String> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON (concept_id) concept_id, value
+        FROM skos_concept_label
+        WHERE concept_id = ANY($1) AND label_type = 'pref_label'
+        ORDER BY concept_id, (language = 'en') DESC, language
+        "#,
+    )
+    .bind(&concept_ids)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?
+    .into_iter()
+    .collect();
+
+    let note_concepts = concept_counts.get(&note_id).copied().unwrap_or(0) as usize;
+    let mut suggestions: Vec<LinkSuggestion> = ids
+        .into_iter()
+        .map(|id| {
+            let similarity = similarity.get(&id).copied();
+            let concepts = shared.get(&id).cloned().unwrap_or_default();
+            let co_citations = co_cited.get(&id).copied().unwrap_or(0);
+            let (score, reasons) = link_suggestion_score(
+                similarity,
+                concepts.len(),
+                note_concepts,
+                concept_counts.get(&id).copied().unwrap_or(0) as usize,
+                co_citations,
+            );
+            LinkSuggestion {
+                note_id: id,
+                title: titles.get(&id).cloned().flatten(),
+                score,
+                reasons,
+                similarity,
+                shared_concepts: concepts
+                    .into_iter()
+                    .map(|concept| LinkSuggestionConcept {
+                        id: concept,
+                        label: labels.get(&concept).cloned(),
+                    })
+                    .collect(),
+                co_citations,
+            }
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.note_id.cmp(&b.note_id))
+    });
+    Ok(suggestions)
+}
@@ -4,10 +4,16 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde_json::Value as JsonValue;
 use sqlx::{Pool, Postgres, Row, Transaction};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use uuid::Uuid;
 
-use matric_core::{new_v7, Error, Link, LinkRepository, Result};
+use matric_core::{
+    new_v7, Error, GraphAnalytics, GraphAnalyticsRun, GraphCommunitySummary, Link, LinkRepository,
+    NoteGraphMetric, Result,
+};
+
+use crate::graph_analytics::compute_graph_metrics;
 
 /// PostgreSQL implementation of LinkRepository.
 pub struct PgLinkRepository {
@@ -999,6 +1005,7 @@ impl PgLinkRepository {
     /// Traverse the knowledge graph starting from a note within an existing transaction.
     ///
     /// Returns versioned v1 payload with truncation metadata and guardrails (#467, #468, #469).
+    /// When `link_types` is given, only links of those kinds are followed and returned.
     #[allow(clippy::too_many_arguments)]
     pub async fn traverse_graph_tx(
         &self,
@@ -1010,6 +1017,7 @@ impl PgLinkRepository {
         max_edges_per_node: Option<i64>,
         edge_filter: Option<&str>,
         include_structural: bool,
+        link_types: Option<&[String]>,
     ) -> Result<GraphResult> {
         let rows = sqlx::query(
             r#"
@@ -1022,6 +1030,7 @@ impl PgLinkRepository {
                 FROM graph g
                 JOIN link l ON (l.from_note_id = g.note_id OR l.to_note_id = g.note_id)
                 WHERE g.depth < $2
+                  AND ($4::text[] IS NULL OR l.kind = ANY($4))
             ),
             deduped AS (
                 SELECT DISTINCT ON (g.note_id)
@@ -1041,6 +1050,7 @@ impl PgLinkRepository {
         .bind(start_id)
         .bind(max_depth)
         .bind(max_nodes)
+        .bind(link_types)
         .fetch_all(&mut **tx)
         .await
         .map_err(Error::Database)?;
@@ -1074,6 +1084,7 @@ impl PgLinkRepository {
                 FROM link
                 WHERE from_note_id = ANY($1) AND to_note_id = ANY($1)
                   AND score >= $2
+                  AND ($4::text[] IS NULL OR kind = ANY($4))
             )
             SELECT * FROM ranked
             WHERE ($3::bigint IS NULL OR rn <= $3)
@@ -1082,6 +1093,7 @@ impl PgLinkRepository {
         .bind(&node_ids)
         .bind(min_score)
         .bind(max_edges_per_node)
+        .bind(link_types)
         .fetch_all(&mut **tx)
         .await
         .map_err(Error::Database)?;
@@ -1265,6 +1277,90 @@ impl PgLinkRepository {
         Ok(row.get("count"))
     }
 
+    /// Count links touching each of the given notes within an existing transaction.
+    ///
+    /// Both incoming and outgoing note-to-note links are counted. Every
+    /// requested note appears in the result, with zero when it has no links.
+    pub async fn link_counts_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, i64>> {
+        if note_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows = sqlx::query(
+            "SELECT ids.id, COUNT(l.id) AS link_count
+             FROM unnest($1::uuid[]) AS ids(id)
+             LEFT JOIN link l ON l.from_note_id = ids.id OR l.to_note_id = ids.id
+             GROUP BY ids.id",
+        )
+        .bind(note_ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("id"), row.get("link_count")))
+            .collect())
+    }
+
+    /// Expand a set of notes by following note-to-note links within an existing transaction.
+    ///
+    /// Links are followed in both directions for up to `hops` steps and
+    /// only reach notes that are not deleted. The seeds come first in the
+    /// result, followed by newly reached notes in hop order. Expansion
+    /// stops once more than `max_notes` notes have been collected, so
+    /// callers can reject oversized neighborhoods without loading them.
+    pub async fn linked_neighborhood_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        seeds: &[Uuid],
+        hops: u8,
+        max_notes: usize,
+    ) -> Result<Vec<Uuid>> {
+        let mut seen: HashSet<Uuid> = HashSet::with_capacity(seeds.len());
+        let mut notes: Vec<Uuid> = Vec::with_capacity(seeds.len());
+        for id in seeds {
+            if seen.insert(*id) {
+                notes.push(*id);
+            }
+        }
+        let mut frontier = notes.clone();
+        for _ in 0..hops {
+            if frontier.is_empty() || notes.len() > max_notes {
+                break;
+            }
+            let rows = sqlx::query(
+                "SELECT l.to_note_id AS id
+                 FROM link l
+                 JOIN note n ON n.id = l.to_note_id
+                 WHERE l.from_note_id = ANY($1) AND n.deleted_at IS NULL
+                 UNION
+                 SELECT l.from_note_id AS id
+                 FROM link l
+                 JOIN note n ON n.id = l.from_note_id
+                 WHERE l.to_note_id = ANY($1) AND n.deleted_at IS NULL
+                 ORDER BY id
+                 LIMIT $2",
+            )
+            .bind(&frontier)
+            .bind((max_notes + 1) as i64)
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+
+            frontier = rows
+                .into_iter()
+                .map(|row| row.get::<Uuid, _>("id"))
+                .filter(|id| seen.insert(*id))
+                .collect();
+            notes.extend(frontier.iter().copied());
+        }
+        Ok(notes)
+    }
+
     /// Compute graph topology statistics within an existing transaction.
     ///
     /// Returns degree distribution, clustering coefficient, connected components,
@@ -2312,6 +2408,176 @@ impl PgLinkRepository {
             snn_score_distribution: histogram,
         })
     }
+    /// Recompute the graph metrics of every linked note within an existing
+    /// transaction, replacing the stored ones.
+    ///
+    /// Considers links of every kind between live notes.
+    pub async fn refresh_graph_analytics_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<GraphAnalyticsRun> {
+        let edges: Vec<(Uuid, Uuid, f64)> = sqlx::query_as(
+            r#"
+            SELECT l.from_note_id, l.to_note_id, l.score::FLOAT8
+            FROM link l
+            JOIN note f ON f.id = l.from_note_id AND f.deleted_at IS NULL
+            JOIN note t ON t.id = l.to_note_id AND t.deleted_at IS NULL
+            WHERE l.from_note_id <> l.to_note_id
+            "#,
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let metrics = compute_graph_metrics(&edges);
+        let importance = metrics.importance();
+
+        sqlx::query("DELETE FROM note_graph_metric")
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        if !metrics.nodes.is_empty() {
+            sqlx::query(
+                r#"
+                INSERT INTO note_graph_metric
+                    (note_id, pagerank, importance, betweenness, community, degree, is_bridge)
+                SELECT * FROM unnest(
+                    $1::uuid[], $2::float8[], $3::float8[], $4::float8[],
+                    $5::int4[], $6::int4[], $7::bool[]
+                )
+                "#,
+            )
+            .bind(&metrics.nodes)
+            .bind(&metrics.pagerank)
+            .bind(&importance)
+            .bind(&metrics.betweenness)
+            .bind(&metrics.community)
+            .bind(&metrics.degree)
+            .bind(&metrics.is_bridge)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+
+        Ok(GraphAnalyticsRun {
+            note_count: metrics.nodes.len() as u64,
+            edge_count: metrics.edge_count as u64,
+            community_count: metrics.community_count() as u64,
+            bridge_count: metrics.bridge_count() as u64,
+            pagerank_iterations: metrics.pagerank_iterations,
+            betweenness_sampled: metrics.betweenness_sampled,
+        })
+    }
+
+    /// Read the stored graph metrics within an existing transaction: the
+    /// `limit` most important notes and bridge notes and the `limit` largest
+    /// communities.
+    pub async fn graph_analytics_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        limit: i64,
+    ) -> Result<GraphAnalytics> {
+        let row = sqlx::query(
+            r#"
+            SELECT MAX(computed_at_utc) AS computed_at_utc,
+                   COUNT(*) AS note_count,
+                   COUNT(DISTINCT community) AS community_count,
+                   COUNT(*) FILTER (WHERE is_bridge) AS bridge_count
+            FROM note_graph_metric
+            "#,
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let top_notes: Vec<NoteGraphMetric> = sqlx::query_as(
+            r#"
+            SELECT m.note_id, n.title, m.pagerank, m.importance, m.betweenness,
+                   m.community, m.degree, m.is_bridge
+            FROM note_graph_metric m
+            JOIN note n ON n.id = m.note_id AND n.deleted_at IS NULL
+            ORDER BY m.pagerank DESC, m.note_id
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let bridge_notes: Vec<NoteGraphMetric> = sqlx::query_as(
+            r#"
+            SELECT m.note_id, n.title, m.pagerank, m.importance, m.betweenness,
+                   m.community, m.degree, m.is_bridge
+            FROM note_graph_metric m
+            JOIN note n ON n.id = m.note_id AND n.deleted_at IS NULL
+            WHERE m.is_bridge
+            ORDER BY m.betweenness DESC, m.note_id
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let communities: Vec<GraphCommunitySummary> = sqlx::query_as(
+            r#"
+            SELECT community, size, note_id AS top_note_id
+            FROM (
+                SELECT DISTINCT ON (community)
+                       community, note_id, COUNT(*) OVER (PARTITION BY community) AS size
+                FROM note_graph_metric
+                ORDER BY community, pagerank DESC, note_id
+            ) c
+            ORDER BY size DESC, community
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(GraphAnalytics {
+            computed_at_utc: row.get("computed_at_utc"),
+            note_count: row.get("note_count"),
+            community_count: row.get("community_count"),
+            bridge_count: row.get("bridge_count"),
+            top_notes,
+            bridge_notes,
+            communities,
+        })
+    }
+
+    /// Stored importance of each of `note_ids` that has graph metrics.
+    pub async fn graph_importance(&self, note_ids: &[Uuid]) -> Result<HashMap<Uuid, f64>> {
+        if note_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows: Vec<(Uuid, f64)> = sqlx::query_as(
+            "SELECT note_id, importance FROM note_graph_metric WHERE note_id = ANY($1)",
+        )
+        .bind(note_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Notes linked to or from `note_id`, in either direction.
+    pub async fn linked_note_ids(&self, note_id: Uuid) -> Result<HashSet<Uuid>> {
+        let ids: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT to_note_id FROM link WHERE from_note_id = $1 AND to_note_id IS NOT NULL
+             UNION
+             SELECT from_note_id FROM link WHERE to_note_id = $1",
+        )
+        .bind(note_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(ids.into_iter().collect())
+    }
 }
 
 /// Graph topology statistics.
@@ -0,0 +1,294 @@
+//! Transaction-aware graph diffs between two points in time.
+//!
+//! See [`matric_core::graph_diff`] for how the graph at a timestamp is
+//! rebuilt. Notes and links are compared in SQL; tags are compared in Rust
+//! from the `snapshot_tags` frontmatter the versioning trigger writes.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    Error, GraphDiff, GraphDiffLink, GraphDiffNote, GraphDiffSummary, GraphDiffTag,
+    GraphStateCounts, Result,
+};
+
+use crate::links::PgLinkRepository;
+use crate::versioning::snapshot_tags;
+
+/// Whether note `n` exists at `$1`.
+const NOTE_ALIVE_AT_1: &str =
+    "(n.created_at_utc <= $1 AND (n.deleted_at IS NULL OR n.deleted_at > $1))";
+
+/// Whether link `l` between notes `f` and `t` exists at `$1`, and at `$2`.
+const LINK_ALIVE_AT_1: &str = "(l.created_at_utc <= $1 \
+     AND f.created_at_utc <= $1 AND (f.deleted_at IS NULL OR f.deleted_at > $1) \
+     AND t.created_at_utc <= $1 AND (t.deleted_at IS NULL OR t.deleted_at > $1))";
+const LINK_ALIVE_AT_2: &str = "(l.created_at_utc <= $2 \
+     AND f.created_at_utc <= $2 AND (f.deleted_at IS NULL OR f.deleted_at > $2) \
+     AND t.created_at_utc <= $2 AND (t.deleted_at IS NULL OR t.deleted_at > $2))";
+
+impl PgLinkRepository {
+    /// Compare the graph at `from` with the graph at `to` within an existing
+    /// transaction, listing at most `limit` items per kind of change.
+    pub async fn graph_diff_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<GraphDiff> {
+        let from_counts = state_counts_at(tx, from).await?;
+        let to_counts = state_counts_at(tx, to).await?;
+
+        let (notes_added, total_notes_added) = diff_notes(
+            tx,
+            from,
+            to,
+            limit,
+            "n.created_at_utc > $1 AND n.created_at_utc <= $2 \
+             AND (n.deleted_at IS NULL OR n.deleted_at > $2)",
+            "n.created_at_utc",
+        )
+        .await?;
+        let (notes_removed, total_notes_removed) = diff_notes(
+            tx,
+            from,
+            to,
+            limit,
+            "n.created_at_utc <= $1 AND n.deleted_at > $1 AND n.deleted_at <= $2",
+            "n.deleted_at",
+        )
+        .await?;
+        let (links_added, total_links_added) = diff_links(
+            tx,
+            from,
+            to,
+            limit,
+            &format!("{LINK_ALIVE_AT_2} AND NOT {LINK_ALIVE_AT_1}"),
+        )
+        .await?;
+        let (links_removed, total_links_removed) = diff_links(
+            tx,
+            from,
+            to,
+            limit,
+            &format!("{LINK_ALIVE_AT_1} AND NOT {LINK_ALIVE_AT_2}"),
+        )
+        .await?;
+        let (mut tags_added, mut tags_removed) = diff_tags(tx, from, to).await?;
+
+        let summary = GraphDiffSummary {
+            notes_added: total_notes_added,
+            notes_removed: total_notes_removed,
+            links_added: total_links_added,
+            links_removed: total_links_removed,
+            tags_added: tags_added.len() as i64,
+            tags_removed: tags_removed.len() as i64,
+        };
+        tags_added.truncate(limit as usize);
+        tags_removed.truncate(limit as usize);
+
+        Ok(GraphDiff {
+            from: from_counts,
+            to: to_counts,
+            summary,
+            notes_added,
+            notes_removed,
+            links_added,
+            links_removed,
+            tags_added,
+            tags_removed,
+        })
+    }
+}
+
+/// Notes and links in the graph at `at`.
+async fn state_counts_at(
+    tx: &mut Transaction<'_, Postgres>,
+    at: DateTime<Utc>,
+) -> Result<GraphStateCounts> {
+    let row = sqlx::query(&format!(
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM note n WHERE {NOTE_ALIVE_AT_1}) AS note_count,
+            (SELECT COUNT(*) FROM link l
+             JOIN note f ON f.id = l.from_note_id
+             JOIN note t ON t.id = l.to_note_id
+             WHERE {LINK_ALIVE_AT_1}) AS link_count
+        "#
+    ))
+    .bind(at)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+    Ok(GraphStateCounts {
+        at,
+        note_count: row.get("note_count"),
+        link_count: row.get("link_count"),
+    })
+}
+
+/// The first `limit` notes matching `condition`, ordered by `changed_at`,
+/// and how many match in all.
+async fn diff_notes(
+    tx: &mut Transaction<'_, Postgres>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    limit: i64,
+    condition: &str,
+    changed_at: &str,
+) -> Result<(Vec<GraphDiffNote>, i64)> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT n.id, n.title, {changed_at} AS changed_at_utc, COUNT(*) OVER () AS total
+        FROM note n
+        WHERE {condition}
+        ORDER BY {changed_at}, n.id
+        LIMIT $3
+        "#
+    ))
+    .bind(from)
+    .bind(to)
+    .bind(limit)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+
+    let total = rows.first().map_or(0, |row| row.get("total"));
+    let notes = rows
+        .iter()
+        .map(|row| GraphDiffNote {
+            id: row.get("id"),
+            title: row.get("title"),
+            changed_at_utc: row.get("changed_at_utc"),
+        })
+        .collect();
+    Ok((notes, total))
+}
+
+/// The first `limit` links matching `condition`, strongest first, and how
+/// many match in all.
+async fn diff_links(
+    tx: &mut Transaction<'_, Postgres>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    limit: i64,
+    condition: &str,
+) -> Result<(Vec<GraphDiffLink>, i64)> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT l.from_note_id, l.to_note_id, l.kind, l.score, COUNT(*) OVER () AS total
+        FROM link l
+        JOIN note f ON f.id = l.from_note_id
+        JOIN note t ON t.id = l.to_note_id
+        WHERE {condition}
+        ORDER BY l.score DESC, l.id
+        LIMIT $3
+        "#
+    ))
+    .bind(from)
+    .bind(to)
+    .bind(limit)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+
+    let total = rows.first().map_or(0, |row| row.get("total"));
+    let links = rows
+        .iter()
+        .map(|row| GraphDiffLink {
+            from_note_id: row.get("from_note_id"),
+            to_note_id: row.get("to_note_id"),
+            kind: row.get("kind"),
+            score: row.get("score"),
+        })
+        .collect();
+    Ok((links, total))
+}
+
+/// Tags added and removed on notes that exist at both points, sorted.
+///
+/// Only notes with a content version written since `from` can differ: every
+/// other note carries its current tags at both points.
+async fn diff_tags(
+    tx: &mut Transaction<'_, Postgres>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<(Vec<GraphDiffTag>, Vec<GraphDiffTag>)> {
+    let versions: Vec<(Uuid, DateTime<Utc>, String)> = sqlx::query_as(
+        r#"
+        SELECT h.note_id, h.created_at_utc, split_part(h.content, E'\n---\n', 1)
+        FROM note_original_history h
+        JOIN note n ON n.id = h.note_id
+        WHERE h.created_at_utc >= $1
+          AND n.created_at_utc <= $1 AND (n.deleted_at IS NULL OR n.deleted_at > $2)
+        ORDER BY h.note_id, h.created_at_utc, h.version_number
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+    if versions.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    // The first snapshot at or after each point, per note.
+    let mut at_from: HashMap<Uuid, BTreeSet<String>> = HashMap::new();
+    let mut at_to: HashMap<Uuid, BTreeSet<String>> = HashMap::new();
+    for (note_id, created_at, frontmatter) in versions {
+        let Some(tags) = snapshot_tags(&frontmatter) else {
+            continue;
+        };
+        at_from
+            .entry(note_id)
+            .or_insert_with(|| tags.iter().cloned().collect());
+        if created_at >= to {
+            at_to
+                .entry(note_id)
+                .or_insert_with(|| tags.into_iter().collect());
+        }
+    }
+
+    // Notes not edited since `to` carry their current tags there.
+    let current_for: Vec<Uuid> = at_from
+        .keys()
+        .filter(|note_id| !at_to.contains_key(note_id))
+        .copied()
+        .collect();
+    if !current_for.is_empty() {
+        let current: Vec<(Uuid, String)> =
+            sqlx::query_as("SELECT note_id, tag_name FROM note_tag WHERE note_id = ANY($1)")
+                .bind(&current_for)
+                .fetch_all(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+        for note_id in &current_for {
+            at_to.insert(*note_id, BTreeSet::new());
+        }
+        for (note_id, tag) in current {
+            at_to.entry(note_id).or_default().insert(tag);
+        }
+    }
+
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+    for (note_id, before) in &at_from {
+        let after = at_to.get(note_id).cloned().unwrap_or_default();
+        added.extend(after.difference(before).map(|tag| GraphDiffTag {
+            note_id: *note_id,
+            tag: tag.clone(),
+        }));
+        removed.extend(before.difference(&after).map(|tag| GraphDiffTag {
+            note_id: *note_id,
+            tag: tag.clone(),
+        }));
+    }
+    added.sort();
+    removed.sort();
+    Ok((added, removed))
+}
@@ -0,0 +1,416 @@
+//! Transaction-aware shortest paths between notes.
+//!
+//! A breadth-first search from the start note, one step per level, over links,
+//! shared SKOS concepts and shared collections. A concept or collection is
+//! expanded once, at the level its first member is reached, so a popular
+//! concept costs one member query rather than one per note.
+
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    Error, NotePath, NotePathEdgeType, NotePathNote, NotePathStep, Result, StrictSecurityFilter,
+    MAX_NOTE_PATH_VISITED,
+};
+
+use crate::links::PgLinkRepository;
+use crate::visibility::visible_note_ids;
+
+/// How the search reached a note from its parent.
+#[derive(Clone)]
+enum Via {
+    Link {
+        kind: String,
+        score: f32,
+        reversed: bool,
+    },
+    Concept(Uuid),
+    Collection(Uuid),
+}
+
+impl PgLinkRepository {
+    /// Find a shortest path from `from` to `to` of at most `max_hops` steps
+    /// within an existing transaction, following only `via` connections.
+    ///
+    /// Soft-deleted notes and notes `security` does not admit are never
+    /// stepped through. Returns `NotFound` when either end is missing or
+    /// hidden, and a path with `found: false` when none is within reach.
+    pub async fn note_path_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        from: Uuid,
+        to: Uuid,
+        via: &[NotePathEdgeType],
+        max_hops: u32,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<NotePath> {
+        let security = security.filter(|security| !security.is_empty());
+        let ends: Vec<Uuid> =
+            sqlx::query_scalar("SELECT id FROM note WHERE id = ANY($1) AND deleted_at IS NULL")
+                .bind(vec![from, to])
+                .fetch_all(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+        let visible_ends = match security {
+            Some(security) => visible_note_ids(&mut **tx, &ends, security).await?,
+            None => ends.into_iter().collect(),
+        };
+        if !visible_ends.contains(&from) || !visible_ends.contains(&to) {
+            return Err(Error::NotFound("Note not found".to_string()));
+        }
+
+        let mut parents: HashMap<Uuid, (Uuid, Via)> = HashMap::new();
+        let mut visited: HashSet<Uuid> = HashSet::from([from]);
+        let mut expanded_concepts: Vec<Uuid> = Vec::new();
+        let mut expanded_collections: Vec<Uuid> = Vec::new();
+        let mut frontier = vec![from];
+        let mut truncated = false;
+
+        for _ in 0..max_hops {
+            if from == to || frontier.is_empty() {
+                break;
+            }
+            let mut candidates: Vec<(Uuid, Uuid, Via)> = Vec::new();
+            for edge_type in via {
+                match edge_type {
+                    NotePathEdgeType::Link => {
+                        link_neighbors(tx, &frontier, &mut candidates).await?;
+                    }
+                    NotePathEdgeType::Concept => {
+                        concept_neighbors(tx, &frontier, &mut expanded_concepts, &mut candidates)
+                            .await?;
+                    }
+                    NotePathEdgeType::Collection => {
+                        collection_neighbors(
+                            tx,
+                            &frontier,
+                            &mut expanded_collections,
+                            &mut candidates,
+                        )
+                        .await?;
+                    }
+                }
+            }
+
+            let mut reached: Vec<(Uuid, Uuid, Via)> = Vec::new();
+            let mut seen: HashSet<Uuid> = HashSet::new();
+            for candidate in candidates {
+                if !visited.contains(&candidate.0) && seen.insert(candidate.0) {
+                    reached.push(candidate);
+                }
+            }
+            if let Some(security) = security {
+                let ids: Vec<Uuid> = reached.iter().map(|(id, _, _)| *id).collect();
+                let visible = visible_note_ids(&mut **tx, &ids, security).await?;
+                reached.retain(|(id, _, _)| visible.contains(id));
+            }
+
+            frontier = Vec::with_capacity(reached.len());
+            for (id, parent, via) in reached {
+                visited.insert(id);
+                parents.insert(id, (parent, via));
+                frontier.push(id);
+            }
+            if visited.contains(&to) {
+                break;
+            }
+            if visited.len() >= MAX_NOTE_PATH_VISITED {
+                truncated = true;
+                break;
+            }
+        }
+
+        if !visited.contains(&to) {
+            return Ok(NotePath {
+                from,
+                to,
+                found: false,
+                hops: 0,
+                notes: Vec::new(),
+                steps: Vec::new(),
+                truncated,
+            });
+        }
+
+        let mut hops: Vec<(Uuid, Uuid, Via)> = Vec::new();
+        let mut current = to;
+        while let Some((parent, via)) = parents.get(&current) {
+            hops.push((*parent, current, via.clone()));
+            current = *parent;
+        }
+        hops.reverse();
+        describe_path(tx, from, to, hops).await
+    }
+}
+
+/// Notes linked to or from the frontier, strongest links first.
+async fn link_neighbors(
+    tx: &mut Transaction<'_, Postgres>,
+    frontier: &[Uuid],
+    candidates: &mut Vec<(Uuid, Uuid, Via)>,
+) -> Result<()> {
+    let rows = sqlx::query(
+        r#"
+        SELECT l.from_note_id, l.to_note_id, l.kind, l.score
+        FROM link l
+        JOIN note f ON f.id = l.from_note_id AND f.deleted_at IS NULL
+        JOIN note t ON t.id = l.to_note_id AND t.deleted_at IS NULL
+        WHERE l.from_note_id = ANY($1) OR l.to_note_id = ANY($1)
+        ORDER BY l.score DESC, l.id
+        "#,
+    )
+    .bind(frontier)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+
+    let frontier: HashSet<&Uuid> = frontier.iter().collect();
+    for row in rows {
+        let source: Uuid = row.get("from_note_id");
+        let target: Uuid = row.get("to_note_id");
+        let kind: String = row.get("kind");
+        let score: f32 = row.get("score");
+        if frontier.contains(&source) {
+            candidates.push((
+                target,
+                source,
+                Via::Link {
+                    kind: kind.clone(),
+                    score,
+                    reversed: false,
+                },
+            ));
+        }
+        if frontier.contains(&target) {
+            candidates.push((
+                source,
+                target,
+                Via::Link {
+                    kind,
+                    score,
+                    reversed: true,
+                },
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Notes sharing a concept not yet expanded with a frontier note.
+async fn concept_neighbors(
+    tx: &mut Transaction<'_, Postgres>,
+    frontier: &[Uuid],
+    expanded: &mut Vec<Uuid>,
+    candidates: &mut Vec<(Uuid, Uuid, Via)>,
+) -> Result<()> {
+    let tagged: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        r#"
+        SELECT concept_id, note_id FROM note_skos_concept
+        WHERE note_id = ANY($1) AND NOT (concept_id = ANY($2))
+        ORDER BY concept_id, note_id
+        "#,
+    )
+    .bind(frontier)
+    .bind(&*expanded)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+    let entry = first_member_by_group(tagged);
+    if entry.is_empty() {
+        return Ok(());
+    }
+    let concepts: Vec<Uuid> = entry.keys().copied().collect();
+    expanded.extend(&concepts);
+
+    let members: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        r#"
+        SELECT nc.concept_id, nc.note_id
+        FROM note_skos_concept nc
+        JOIN note n ON n.id = nc.note_id AND n.deleted_at IS NULL
+        WHERE nc.concept_id = ANY($1)
+        ORDER BY nc.concept_id, nc.note_id
+        "#,
+    )
+    .bind(&concepts)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+    candidates.extend(
+        members
+            .into_iter()
+            .map(|(concept, note)| (note, entry[&concept], Via::Concept(concept))),
+    );
+    Ok(())
+}
+
+/// Notes sharing a collection not yet expanded with a frontier note.
+async fn collection_neighbors(
+    tx: &mut Transaction<'_, Postgres>,
+    frontier: &[Uuid],
+    expanded: &mut Vec<Uuid>,
+    candidates: &mut Vec<(Uuid, Uuid, Via)>,
+) -> Result<()> {
+    let filed: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        r#"
+        SELECT collection_id, id FROM note
+        WHERE id = ANY($1) AND collection_id IS NOT NULL
+          AND NOT (collection_id = ANY($2))
+        ORDER BY collection_id, id
+        "#,
+    )
+    .bind(frontier)
+    .bind(&*expanded)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+    let entry = first_member_by_group(filed);
+    if entry.is_empty() {
+        return Ok(());
+    }
+    let collections: Vec<Uuid> = entry.keys().copied().collect();
+    expanded.extend(&collections);
+
+    let members: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        r#"
+        SELECT collection_id, id FROM note
+        WHERE collection_id = ANY($1) AND deleted_at IS NULL
+        ORDER BY collection_id, id
+        "#,
+    )
+    .bind(&collections)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+    candidates.extend(
+        members
+            .into_iter()
+            .map(|(collection, note)| (note, entry[&collection], Via::Collection(collection))),
+    );
+    Ok(())
+}
+
+/// The first frontier note of each concept or collection, from rows of
+/// `(group, note)`.
+fn first_member_by_group(rows: Vec<(Uuid, Uuid)>) -> HashMap<Uuid, Uuid> {
+    let mut first = HashMap::new();
+    for (group, note) in rows {
+        first.entry(group).or_insert(note);
+    }
+    first
+}
+
+/// Attach note titles, concept labels and collection names to a path.
+async fn describe_path(
+    tx: &mut Transaction<'_, Postgres>,
+    from: Uuid,
+    to: Uuid,
+    hops: Vec<(Uuid, Uuid, Via)>,
+) -> Result<NotePath> {
+    let note_ids: Vec<Uuid> = std::iter::once(from)
+        .chain(hops.iter().map(|(_, note, _)| *note))
+        .collect();
+    let titles: HashMap<Uuid, Option<String>> =
+        sqlx::query_as("SELECT id, title FROM note WHERE id = ANY($1)")
+            .bind(&note_ids)
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(Error::Database)?
+            .into_iter()
+            .collect();
+
+    let concept_ids: Vec<Uuid> = hops
+        .iter()
+        .filter_map(|(_, _, via)| match via {
+            Via::Concept(id) => Some(*id),
+            _ => None,
+        })
+        .collect();
+    let concept_labels: HashMap<Uuid, String> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON (concept_id) concept_id, value
+        FROM skos_concept_label
+        WHERE concept_id = ANY($1) AND label_type = 'pref_label'
+        ORDER BY concept_id, (language = 'en') DESC, language
+        "#,
+    )
+    .bind(&concept_ids)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(Error::Database)?
+    .into_iter()
+    .collect();
+
+    let collection_ids: Vec<Uuid> = hops
+        .iter()
+        .filter_map(|(_, _, via)| match via {
+            Via::Collection(id) => Some(*id),
+            _ => None,
+        })
+        .collect();
+    let collection_names: HashMap<Uuid, String> =
+        sqlx::query_as("SELECT id, name FROM collection WHERE id = ANY($1)")
+            .bind(&collection_ids)
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(Error::Database)?
+            .into_iter()
+            .collect();
+
+    let steps: Vec<NotePathStep> = hops
+        .into_iter()
+        .map(|(from_note_id, to_note_id, via)| {
+            let mut step = NotePathStep {
+                from_note_id,
+                to_note_id,
+                edge_type: NotePathEdgeType::Link,
+                link_kind: None,
+                score: None,
+                reversed: false,
+                concept_id: None,
+                concept_label: None,
+                collection_id: None,
+                collection_name: None,
+            };
+            match via {
+                Via::Link {
+                    kind,
+                    score,
+                    reversed,
+                } => {
+                    step.link_kind = Some(kind);
+                    step.score = Some(score);
+                    step.reversed = reversed;
+                }
+                Via::Concept(id) => {
+                    step.edge_type = NotePathEdgeType::Concept;
+                    step.concept_id = Some(id);
+                    step.concept_label = concept_labels.get(&id).cloned();
+                }
+                Via::Collection(id) => {
+                    step.edge_type = NotePathEdgeType::Collection;
+                    step.collection_id = Some(id);
+                    step.collection_name = collection_names.get(&id).cloned();
+                }
+            }
+            step
+        })
+        .collect();
+
+    Ok(NotePath {
+        from,
+        to,
+        found: true,
+        hops: steps.len() as u32,
+        notes: note_ids
+            .into_iter()
+            .map(|id| NotePathNote {
+                id,
+                title: titles.get(&id).cloned().flatten(),
+            })
+            .collect(),
+        steps,
+        truncated: false,
+    })
+}
@@ -0,0 +1,428 @@
+//! Transaction-aware typed links and the archive's link type vocabulary.
+//!
+//! See [`matric_core::typed_link`] for the built-in types and name rules.
+//! A symmetric type's links come in pairs: creating, editing or deleting one
+//! direction does the same to the other.
+
+use chrono::Utc;
+use serde_json::Value as JsonValue;
+use sqlx::{Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::typed_link::{
+    is_builtin_link_type, validate_link_type_name, validate_typed_link_fields,
+    DEFAULT_TYPED_LINK_SCORE,
+};
+use matric_core::{
+    new_v7, CreateLinkTypeRequest, CreateTypedLinkRequest, Error, Link, LinkTypeDefinition, Result,
+    StrictSecurityFilter, UpdateTypedLinkRequest,
+};
+
+use crate::links::PgLinkRepository;
+use crate::visibility::visible_note_ids;
+
+impl PgLinkRepository {
+    /// List the built-in link types followed by the archive's custom types
+    /// within an existing transaction.
+    pub async fn list_link_types_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<LinkTypeDefinition>> {
+        let custom: Vec<LinkTypeDefinition> = sqlx::query_as(
+            "SELECT name, description, symmetric, created_at_utc FROM link_type ORDER BY name",
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        let mut types = LinkTypeDefinition::builtins();
+        types.extend(custom);
+        Ok(types)
+    }
+
+    /// Register a custom link type within an existing transaction.
+    pub async fn create_link_type_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        req: &CreateLinkTypeRequest,
+    ) -> Result<LinkTypeDefinition> {
+        let name = req.validate()?;
+        let description = req
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|description| !description.is_empty());
+        sqlx::query_as(
+            "INSERT INTO link_type (name, description, symmetric)
+             VALUES ($1, $2, $3)
+             RETURNING name, description, symmetric, created_at_utc",
+        )
+        .bind(&name)
+        .bind(description)
+        .bind(req.symmetric)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Remove a custom link type within an existing transaction. Built-in
+    /// types and types still used by links cannot be removed.
+    pub async fn delete_link_type_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        name: &str,
+    ) -> Result<()> {
+        let name = validate_link_type_name(name)?;
+        if is_builtin_link_type(&name) {
+            return Err(Error::InvalidInput(format!(
+                "link type '{name}' is built in"
+            )));
+        }
+        let in_use: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM link WHERE kind = $1")
+            .bind(&name)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        if in_use > 0 {
+            return Err(Error::InvalidInput(format!(
+                "link type '{name}' is used by {in_use} links"
+            )));
+        }
+        let deleted = sqlx::query("DELETE FROM link_type WHERE name = $1")
+            .bind(&name)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        if deleted.rows_affected() == 0 {
+            return Err(Error::NotFound("Link type not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Link `from_note_id` to another note with a typed relation within an
+    /// existing transaction, returning the links created: one, or two for a
+    /// symmetric type.
+    ///
+    /// Both notes must exist and be visible to `security`. Rejects unknown
+    /// types and a pair already linked with the same type.
+    pub async fn create_typed_link_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        from_note_id: Uuid,
+        req: &CreateTypedLinkRequest,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Vec<Link>> {
+        validate_typed_link_fields(req.score, req.metadata.as_ref())?;
+        if from_note_id == req.to_note_id {
+            return Err(Error::InvalidInput(
+                "a note cannot be linked to itself".to_string(),
+            ));
+        }
+        let link_type = resolve_link_type(tx, &req.link_type).await?;
+        ensure_notes_visible(tx, &[from_note_id, req.to_note_id], security).await?;
+
+        let linked: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM link WHERE from_note_id = $1 AND to_note_id = $2 AND kind = $3
+             )",
+        )
+        .bind(from_note_id)
+        .bind(req.to_note_id)
+        .bind(&link_type.name)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        if linked {
+            return Err(Error::InvalidInput(format!(
+                "notes are already linked as '{}'",
+                link_type.name
+            )));
+        }
+
+        let score = req.score.unwrap_or(DEFAULT_TYPED_LINK_SCORE);
+        let mut pairs = vec![(from_note_id, req.to_note_id)];
+        if link_type.symmetric {
+            pairs.push((req.to_note_id, from_note_id));
+        }
+        let now = Utc::now();
+        let mut links = Vec::with_capacity(pairs.len());
+        for (from, to) in pairs {
+            let id = new_v7();
+            sqlx::query(
+                "INSERT INTO link (id, from_note_id, to_note_id, to_url, kind, score, created_at_utc, metadata)
+                 SELECT $1, $2, $3, NULL, $4, $5, $6, $7
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM link
+                     WHERE from_note_id = $2 AND to_note_id = $3 AND kind = $4
+                 )",
+            )
+            .bind(id)
+            .bind(from)
+            .bind(to)
+            .bind(&link_type.name)
+            .bind(score)
+            .bind(now)
+            .bind(&req.metadata)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            if let Some(link) = fetch_link(tx, id).await? {
+                links.push(link);
+            }
+        }
+        Ok(links)
+    }
+
+    /// Change a note-to-note link's type, score or metadata within an
+    /// existing transaction. A symmetric link's reverse gets the same change;
+    /// the reverse is dropped when the type stops being symmetric and added
+    /// when it becomes symmetric.
+    ///
+    /// Returns `NotFound` for a missing link or one with an end `security`
+    /// does not admit.
+    pub async fn update_typed_link_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        link_id: Uuid,
+        req: &UpdateTypedLinkRequest,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Link> {
+        validate_typed_link_fields(req.score, req.metadata.as_ref())?;
+        let link = visible_link(tx, link_id, security).await?;
+        let Some(to_note_id) = link.to_note_id else {
+            return Err(Error::InvalidInput(
+                "only links between notes can be typed".to_string(),
+            ));
+        };
+        let new_type = match &req.link_type {
+            Some(name) => Some(resolve_link_type(tx, name).await?),
+            None => None,
+        };
+        let kind = new_type
+            .as_ref()
+            .map_or(link.kind.as_str(), |link_type| link_type.name.as_str());
+        if kind != link.kind {
+            let taken: bool = sqlx::query_scalar(
+                "SELECT EXISTS (
+                     SELECT 1 FROM link WHERE from_note_id = $1 AND to_note_id = $2 AND kind = $3
+                 )",
+            )
+            .bind(link.from_note_id)
+            .bind(to_note_id)
+            .bind(kind)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            if taken {
+                return Err(Error::InvalidInput(format!(
+                    "notes are already linked as '{kind}'"
+                )));
+            }
+        }
+
+        let old_symmetric = is_symmetric(tx, &link.kind).await?;
+        let new_symmetric = new_type
+            .as_ref()
+            .map_or(old_symmetric, |link_type| link_type.symmetric);
+        sqlx::query(
+            "UPDATE link
+             SET kind = $2, score = COALESCE($3, score), metadata = COALESCE($4, metadata)
+             WHERE id = $1",
+        )
+        .bind(link_id)
+        .bind(kind)
+        .bind(req.score)
+        .bind(&req.metadata)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let updated = fetch_link(tx, link_id)
+            .await?
+            .ok_or_else(|| Error::NotFound("Link not found".to_string()))?;
+        if old_symmetric && kind == link.kind {
+            sqlx::query(
+                "UPDATE link SET score = $4, metadata = $5
+                 WHERE from_note_id = $1 AND to_note_id = $2 AND kind = $3",
+            )
+            .bind(to_note_id)
+            .bind(link.from_note_id)
+            .bind(kind)
+            .bind(updated.score)
+            .bind(&updated.metadata)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            return Ok(updated);
+        }
+        if old_symmetric {
+            sqlx::query(
+                "DELETE FROM link WHERE from_note_id = $1 AND to_note_id = $2 AND kind = $3",
+            )
+            .bind(to_note_id)
+            .bind(link.from_note_id)
+            .bind(&link.kind)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+        if new_symmetric {
+            sqlx::query(
+                "INSERT INTO link (id, from_note_id, to_note_id, to_url, kind, score, created_at_utc, metadata)
+                 SELECT $1, $2, $3, NULL, $4, $5, $6, $7
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM link
+                     WHERE from_note_id = $2 AND to_note_id = $3 AND kind = $4
+                 )",
+            )
+            .bind(new_v7())
+            .bind(to_note_id)
+            .bind(link.from_note_id)
+            .bind(kind)
+            .bind(updated.score)
+            .bind(Utc::now())
+            .bind(&updated.metadata)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+        Ok(updated)
+    }
+
+    /// Delete a link within an existing transaction, along with its reverse
+    /// link when its type is symmetric. Returns how many links were deleted.
+    pub async fn delete_typed_link_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        link_id: Uuid,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<u64> {
+        let link = visible_link(tx, link_id, security).await?;
+        let mut deleted = sqlx::query("DELETE FROM link WHERE id = $1")
+            .bind(link_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?
+            .rows_affected();
+        if let Some(to_note_id) = link.to_note_id {
+            if is_symmetric(tx, &link.kind).await? {
+                deleted += sqlx::query(
+                    "DELETE FROM link WHERE from_note_id = $1 AND to_note_id = $2 AND kind = $3",
+                )
+                .bind(to_note_id)
+                .bind(link.from_note_id)
+                .bind(&link.kind)
+                .execute(&mut **tx)
+                .await
+                .map_err(Error::Database)?
+                .rows_affected();
+            }
+        }
+        Ok(deleted)
+    }
+}
+
+/// The built-in or registered type named `name`, or `InvalidInput`.
+async fn resolve_link_type(
+    tx: &mut Transaction<'_, Postgres>,
+    name: &str,
+) -> Result<LinkTypeDefinition> {
+    let name = validate_link_type_name(name)?;
+    if let Some(builtin) = LinkTypeDefinition::builtins()
+        .into_iter()
+        .find(|builtin| builtin.name == name)
+    {
+        return Ok(builtin);
+    }
+    sqlx::query_as(
+        "SELECT name, description, symmetric, created_at_utc FROM link_type WHERE name = $1",
+    )
+    .bind(&name)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(Error::Database)?
+    .ok_or_else(|| Error::InvalidInput(format!("unknown link type '{name}'")))
+}
+
+/// Whether links of `kind` come in pairs; false for system and unknown kinds.
+async fn is_symmetric(tx: &mut Transaction<'_, Postgres>, kind: &str) -> Result<bool> {
+    if let Some(builtin) = LinkTypeDefinition::builtins()
+        .into_iter()
+        .find(|builtin| builtin.name == kind)
+    {
+        return Ok(builtin.symmetric);
+    }
+    let symmetric: Option<bool> =
+        sqlx::query_scalar("SELECT symmetric FROM link_type WHERE name = $1")
+            .bind(kind)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+    Ok(symmetric.unwrap_or(false))
+}
+
+async fn ensure_notes_visible(
+    tx: &mut Transaction<'_, Postgres>,
+    note_ids: &[Uuid],
+    security: Option<&StrictSecurityFilter>,
+) -> Result<()> {
+    let found: Vec<Uuid> =
+        sqlx::query_scalar("SELECT id FROM note WHERE id = ANY($1) AND deleted_at IS NULL")
+            .bind(note_ids)
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+    let visible = match security.filter(|security| !security.is_empty()) {
+        Some(security) => visible_note_ids(&mut **tx, &found, security).await?,
+        None => found.into_iter().collect(),
+    };
+    if note_ids.iter().all(|id| visible.contains(id)) {
+        Ok(())
+    } else {
+        Err(Error::NotFound("Note not found".to_string()))
+    }
+}
+
+/// The link `link_id`, when both its notes are visible to `security`.
+async fn visible_link(
+    tx: &mut Transaction<'_, Postgres>,
+    link_id: Uuid,
+    security: Option<&StrictSecurityFilter>,
+) -> Result<Link> {
+    let link = fetch_link(tx, link_id)
+        .await?
+        .ok_or_else(|| Error::NotFound("Link not found".to_string()))?;
+    let notes: Vec<Uuid> = std::iter::once(link.from_note_id)
+        .chain(link.to_note_id)
+        .collect();
+    ensure_notes_visible(tx, &notes, security)
+        .await
+        .map_err(|_| Error::NotFound("Link not found".to_string()))?;
+    Ok(link)
+}
+
+async fn fetch_link(tx: &mut Transaction<'_, Postgres>, link_id: Uuid) -> Result<Option<Link>> {
+    let row = sqlx::query(
+        r#"SELECT
+            l.id, l.from_note_id, l.to_note_id, l.to_url, l.kind, l.score,
+            l.created_at_utc, l.metadata,
+            COALESCE(substring(nrc.content from 1 for 100), 'Linked note') as snippet
+           FROM link l
+           LEFT JOIN note_revised_current nrc ON nrc.note_id = l.to_note_id
+           WHERE l.id = $1"#,
+    )
+    .bind(link_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(Error::Database)?;
+    Ok(row.map(|row| Link {
+        id: row.get("id"),
+        from_note_id: row.get("from_note_id"),
+        to_note_id: row.get("to_note_id"),
+        to_url: row.get("to_url"),
+        kind: row.get("kind"),
+        score: row.get("score"),
+        created_at_utc: row.get("created_at_utc"),
+        snippet: row.get("snippet"),
+        metadata: row.get::<Option<JsonValue>, _>("metadata"),
+    }))
+}
@@ -0,0 +1,1361 @@
+//! Portable memory packs (`.mempack`).
+//!
+//! A memory pack is a gzip-compressed tar archive holding one archive's notes,
+//! link graph, SKOS vocabulary, attachments and, optionally, chunk embeddings.
+//! Entries are written in a fixed order so an importer can apply them in a
+//! single pass:
+//!
+//! ```text
+//! manifest.json               format marker, version, source archive
+//! notes/00000.jsonl ...       one MempackNote per line
+//! links/00000.jsonl ...       one MempackLink per line
+//! skos.ttl                    concepts and note subjects as Turtle
+//! attachments/00000.json      MempackAttachment record, followed by
+//! attachments/00000.bin       its bytes
+//! embeddings/00000.jsonl ...  one MempackEmbedding per line (optional)
+//! trailer.json                record counts and SHA-256 of every entry
+//! ```
+//!
+//! JSONL sections are split into parts of at most [`MEMPACK_PART_RECORDS`]
+//! lines, so neither side buffers a whole section. IDs inside a pack belong to
+//! the source instance; [`PgMempackRepository::import_tx`] gives every note a
+//! fresh ID and rewrites links, attachments and embeddings to match.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::{Read, Write};
+
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use pgvector::Vector;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Postgres, Row, Transaction};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use matric_core::{new_v7, CreateNoteRequest, Error, RdfGraph, Result};
+
+use crate::file_storage::PgFileStorageRepository;
+use crate::graph_export::PgGraphExportRepository;
+use crate::notes::PgNoteRepository;
+
+/// Format marker stored in every manifest.
+pub const MEMPACK_FORMAT: &str = "fortemi.mempack";
+/// Newest pack version this build writes and reads.
+pub const MEMPACK_VERSION: u32 = 1;
+/// Media type for `.mempack` downloads.
+pub const MEMPACK_CONTENT_TYPE: &str = "application/vnd.fortemi.mempack+gzip";
+/// Maximum records per JSONL part.
+pub const MEMPACK_PART_RECORDS: usize = 1000;
+/// Default cap on a single decompressed entry when reading.
+pub const MEMPACK_DEFAULT_MAX_ENTRY_BYTES: u64 = 512 * 1024 * 1024;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const SKOS_ENTRY: &str = "skos.ttl";
+const TRAILER_ENTRY: &str = "trailer.json";
+const EXPORT_PAGE_SIZE: i64 = 500;
+const IMPORT_REVISION_RATIONALE: &str = "Imported from memory pack";
+
+fn text_len(value: &str) -> usize {
+    value.chars().count()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn invalid_pack(message: impl Into<String>) -> Error {
+    Error::InvalidInput(format!("invalid mempack: {}", message.into()))
+}
+
+// =============================================================================
+// RECORDS
+// =============================================================================
+
+/// First entry of every pack.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempackManifest {
+    /// Always [`MEMPACK_FORMAT`].
+    pub format: String,
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    /// Name of the archive the pack was exported from.
+    #[serde(default)]
+    pub source_archive: Option<String>,
+    pub includes_embeddings: bool,
+}
+
+impl MempackManifest {
+    /// Manifest for a pack written by this build.
+    pub fn new(source_archive: Option<String>, includes_embeddings: bool) -> Self {
+        Self {
+            format: MEMPACK_FORMAT.to_string(),
+            version: MEMPACK_VERSION,
+            created_at: Utc::now(),
+            source_archive,
+            includes_embeddings,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.format != MEMPACK_FORMAT {
+            return Err(invalid_pack("unrecognized format marker"));
+        }
+        if self.version == 0 || self.version > MEMPACK_VERSION {
+            return Err(invalid_pack(format!(
+                "unsupported version {} (this server reads up to {MEMPACK_VERSION})",
+                self.version
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for MempackManifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MempackManifest")
+            .field("format", &self.format)
+            .field("version", &self.version)
+            .field("created_at", &self.created_at)
+            .field(
+                "source_archive_len",
+                &self.source_archive.as_deref().map(text_len),
+            )
+            .field("includes_embeddings", &self.includes_embeddings)
+            .finish()
+    }
+}
+
+/// A note as stored in `notes/*.jsonl`.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempackNote {
+    pub id: Uuid,
+    #[serde(default)]
+    pub title: Option<String>,
+    pub format: String,
+    pub source: String,
+    /// Original content.
+    pub content: String,
+    /// Current revised content, when it differs from the original.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revised_content: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub metadata: JsonValue,
+    pub created_at_utc: DateTime<Utc>,
+    pub updated_at_utc: DateTime<Utc>,
+    #[serde(default)]
+    pub starred: bool,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+impl std::fmt::Debug for MempackNote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MempackNote")
+            .field("id_present", &true)
+            .field("title_len", &self.title.as_deref().map(text_len))
+            .field("format_len", &text_len(&self.format))
+            .field("source_len", &text_len(&self.source))
+            .field("content_len", &text_len(&self.content))
+            .field(
+                "revised_content_len",
+                &self.revised_content.as_deref().map(text_len),
+            )
+            .field("tag_count", &self.tags.len())
+            .field("starred", &self.starred)
+            .field("archived", &self.archived)
+            .finish()
+    }
+}
+
+/// A link as stored in `links/*.jsonl`. Exactly one of `to_note_id` and
+/// `to_url` is set.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempackLink {
+    pub from_note_id: Uuid,
+    #[serde(default)]
+    pub to_note_id: Option<Uuid>,
+    #[serde(default)]
+    pub to_url: Option<String>,
+    pub kind: String,
+    pub score: f32,
+    #[serde(default)]
+    pub metadata: Option<JsonValue>,
+}
+
+impl std::fmt::Debug for MempackLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MempackLink")
+            .field("to_note_present", &self.to_note_id.is_some())
+            .field("to_url_len", &self.to_url.as_deref().map(text_len))
+            .field("kind_len", &text_len(&self.kind))
+            .field("score", &self.score)
+            .field("metadata_present", &self.metadata.is_some())
+            .finish()
+    }
+}
+
+/// Attachment record stored ahead of its bytes.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempackAttachment {
+    pub id: Uuid,
+    pub note_id: Uuid,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: u64,
+    /// Hex SHA-256 of the attachment bytes.
+    pub sha256: String,
+}
+
+impl std::fmt::Debug for MempackAttachment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MempackAttachment")
+            .field("filename_len", &text_len(&self.filename))
+            .field("content_type", &self.content_type)
+            .field("size_bytes", &self.size_bytes)
+            .finish()
+    }
+}
+
+/// A chunk embedding as stored in `embeddings/*.jsonl`.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempackEmbedding {
+    pub note_id: Uuid,
+    pub chunk_index: i32,
+    pub text: String,
+    pub model: String,
+    pub vector: Vec<f32>,
+}
+
+impl std::fmt::Debug for MempackEmbedding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MempackEmbedding")
+            .field("chunk_index", &self.chunk_index)
+            .field("text_len", &text_len(&self.text))
+            .field("model", &self.model)
+            .field("dimension", &self.vector.len())
+            .finish()
+    }
+}
+
+/// Record counts written to, and verified against, the trailer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MempackCounts {
+    pub notes: u64,
+    pub links: u64,
+    pub attachments: u64,
+    pub embeddings: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MempackTrailer {
+    counts: MempackCounts,
+    /// Entry name → hex SHA-256, for every entry except the trailer.
+    checksums: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Section {
+    Notes,
+    Links,
+    Skos,
+    Attachments,
+    Embeddings,
+}
+
+impl Section {
+    fn dir(self) -> &'static str {
+        match self {
+            Self::Notes => "notes",
+            Self::Links => "links",
+            Self::Skos => "skos",
+            Self::Attachments => "attachments",
+            Self::Embeddings => "embeddings",
+        }
+    }
+
+    fn of_entry(name: &str) -> Option<Self> {
+        if name == SKOS_ENTRY {
+            return Some(Self::Skos);
+        }
+        let (dir, _) = name.split_once('/')?;
+        [
+            Self::Notes,
+            Self::Links,
+            Self::Attachments,
+            Self::Embeddings,
+        ]
+        .into_iter()
+        .find(|section| section.dir() == dir)
+    }
+}
+
+// =============================================================================
+// WRITER
+// =============================================================================
+
+/// Streaming `.mempack` writer.
+///
+/// Sections must be written in pack order (notes, links, SKOS, attachments,
+/// embeddings); going back to an earlier section is an error.
+pub struct MempackWriter<W: Write> {
+    tar: tar::Builder<GzEncoder<W>>,
+    mtime: u64,
+    section: Option<Section>,
+    part: Vec<u8>,
+    part_records: usize,
+    part_index: usize,
+    counts: MempackCounts,
+    checksums: BTreeMap<String, String>,
+}
+
+impl<W: Write> MempackWriter<W> {
+    /// Start a pack and write its manifest.
+    pub fn new(inner: W, manifest: &MempackManifest) -> Result<Self> {
+        let mut writer = Self {
+            tar: tar::Builder::new(GzEncoder::new(inner, Compression::default())),
+            mtime: manifest.created_at.timestamp().max(0) as u64,
+            section: None,
+            part: Vec::new(),
+            part_records: 0,
+            part_index: 0,
+            counts: MempackCounts::default(),
+            checksums: BTreeMap::new(),
+        };
+        let json =
+            serde_json::to_vec_pretty(manifest).map_err(|e| Error::Serialization(e.to_string()))?;
+        writer.append(MANIFEST_ENTRY, &json)?;
+        Ok(writer)
+    }
+
+    pub fn write_note(&mut self, note: &MempackNote) -> Result<()> {
+        self.push_record(Section::Notes, note)?;
+        self.counts.notes += 1;
+        Ok(())
+    }
+
+    pub fn write_link(&mut self, link: &MempackLink) -> Result<()> {
+        self.push_record(Section::Links, link)?;
+        self.counts.links += 1;
+        Ok(())
+    }
+
+    pub fn write_skos(&mut self, turtle: &str) -> Result<()> {
+        self.enter(Section::Skos)?;
+        if self.checksums.contains_key(SKOS_ENTRY) {
+            return Err(Error::InvalidInput(
+                "mempack SKOS vocabulary already written".to_string(),
+            ));
+        }
+        self.append(SKOS_ENTRY, turtle.as_bytes())
+    }
+
+    /// Write one attachment record followed by its bytes.
+    pub fn write_attachment(
+        &mut self,
+        id: Uuid,
+        note_id: Uuid,
+        filename: &str,
+        content_type: &str,
+        data: &[u8],
+    ) -> Result<()> {
+        self.enter(Section::Attachments)?;
+        let record = MempackAttachment {
+            id,
+            note_id,
+            filename: filename.to_string(),
+            content_type: content_type.to_string(),
+            size_bytes: data.len() as u64,
+            sha256: sha256_hex(data),
+        };
+        let json = serde_json::to_vec(&record).map_err(|e| Error::Serialization(e.to_string()))?;
+        let stem = format!("attachments/{:05}", self.counts.attachments);
+        self.append(&format!("{stem}.json"), &json)?;
+        self.append(&format!("{stem}.bin"), data)?;
+        self.counts.attachments += 1;
+        Ok(())
+    }
+
+    pub fn write_embedding(&mut self, embedding: &MempackEmbedding) -> Result<()> {
+        self.push_record(Section::Embeddings, embedding)?;
+        self.counts.embeddings += 1;
+        Ok(())
+    }
+
+    /// Records written so far.
+    pub fn counts(&self) -> MempackCounts {
+        self.counts
+    }
+
+    /// Flush the open part, write the trailer and close the stream.
+    pub fn finish(mut self) -> Result<W> {
+        self.flush_part()?;
+        let trailer = MempackTrailer {
+            counts: self.counts,
+            checksums: std::mem::take(&mut self.checksums),
+        };
+        let json =
+            serde_json::to_vec_pretty(&trailer).map_err(|e| Error::Serialization(e.to_string()))?;
+        self.append_raw(TRAILER_ENTRY, &json)?;
+        let encoder = self.tar.into_inner()?;
+        Ok(encoder.finish()?)
+    }
+
+    fn enter(&mut self, section: Section) -> Result<()> {
+        match self.section {
+            Some(current) if current > section => Err(Error::InvalidInput(format!(
+                "mempack section {} written after {}",
+                section.dir(),
+                current.dir()
+            ))),
+            Some(current) if current == section => Ok(()),
+            _ => {
+                self.flush_part()?;
+                self.section = Some(section);
+                self.part_index = 0;
+                Ok(())
+            }
+        }
+    }
+
+    fn push_record<T: Serialize>(&mut self, section: Section, record: &T) -> Result<()> {
+        self.enter(section)?;
+        serde_json::to_writer(&mut self.part, record)
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        self.part.push(b'\n');
+        self.part_records += 1;
+        if self.part_records >= MEMPACK_PART_RECORDS {
+            self.flush_part()?;
+        }
+        Ok(())
+    }
+
+    fn flush_part(&mut self) -> Result<()> {
+        let Some(section) = self.section else {
+            return Ok(());
+        };
+        if self.part_records == 0 {
+            return Ok(());
+        }
+        let name = format!("{}/{:05}.jsonl", section.dir(), self.part_index);
+        let part = std::mem::take(&mut self.part);
+        self.append(&name, &part)?;
+        self.part_records = 0;
+        self.part_index += 1;
+        Ok(())
+    }
+
+    fn append(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        self.checksums.insert(name.to_string(), sha256_hex(data));
+        self.append_raw(name, data)
+    }
+
+    fn append_raw(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(self.mtime);
+        header.set_cksum();
+        self.tar.append_data(&mut header, name, data)?;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// READER
+// =============================================================================
+
+/// One unit of pack content, in pack order.
+pub enum MempackItem {
+    Manifest(MempackManifest),
+    Note(MempackNote),
+    Link(MempackLink),
+    Skos(String),
+    Attachment {
+        record: MempackAttachment,
+        data: Vec<u8>,
+    },
+    Embedding(MempackEmbedding),
+    /// The trailer matched every entry; nothing follows.
+    End(MempackCounts),
+}
+
+impl std::fmt::Debug for MempackItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Manifest(manifest) => f.debug_tuple("Manifest").field(manifest).finish(),
+            Self::Note(note) => f.debug_tuple("Note").field(note).finish(),
+            Self::Link(link) => f.debug_tuple("Link").field(link).finish(),
+            Self::Skos(turtle) => f
+                .debug_struct("Skos")
+                .field("turtle_len", &turtle.len())
+                .finish(),
+            Self::Attachment { record, data } => f
+                .debug_struct("Attachment")
+                .field("record", record)
+                .field("data_len", &data.len())
+                .finish(),
+            Self::Embedding(embedding) => f.debug_tuple("Embedding").field(embedding).finish(),
+            Self::End(counts) => f.debug_tuple("End").field(counts).finish(),
+        }
+    }
+}
+
+fn parse_jsonl<T: for<'de> Deserialize<'de>>(
+    name: &str,
+    data: &[u8],
+    mut visit: impl FnMut(T) -> Result<()>,
+) -> Result<u64> {
+    let mut records = 0;
+    for (index, line) in data.split(|byte| *byte == b'\n').enumerate() {
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        let record = serde_json::from_slice(line)
+            .map_err(|e| invalid_pack(format!("{name} line {}: {e}", index + 1)))?;
+        visit(record)?;
+        records += 1;
+    }
+    Ok(records)
+}
+
+/// Read a pack, passing each item to `visit` in order.
+///
+/// The manifest is checked before anything else is emitted, and
+/// [`MempackItem::End`] is only emitted once the trailer's checksums and
+/// counts match. Callers applying items as they arrive should treat a missing
+/// `End` as failure and roll back.
+pub fn read_mempack<R: Read>(
+    reader: R,
+    max_entry_bytes: u64,
+    mut visit: impl FnMut(MempackItem) -> Result<()>,
+) -> Result<MempackCounts> {
+    let mut archive = tar::Archive::new(GzDecoder::new(reader));
+    let mut manifest_seen = false;
+    let mut trailer_seen = false;
+    let mut section: Option<Section> = None;
+    let mut pending_attachment: Option<(String, MempackAttachment)> = None;
+    let mut counts = MempackCounts::default();
+    let mut checksums: BTreeMap<String, String> = BTreeMap::new();
+
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if trailer_seen {
+            return Err(invalid_pack("entries after trailer"));
+        }
+        if !entry.header().entry_type().is_file() {
+            return Err(invalid_pack("non-file entry"));
+        }
+        let name = entry.path()?.to_string_lossy().into_owned();
+        if entry.header().size()? > max_entry_bytes {
+            return Err(invalid_pack(format!("{name} exceeds the entry size limit")));
+        }
+        let mut data = Vec::new();
+        entry.take(max_entry_bytes + 1).read_to_end(&mut data)?;
+        if data.len() as u64 > max_entry_bytes {
+            return Err(invalid_pack(format!("{name} exceeds the entry size limit")));
+        }
+
+        if !manifest_seen {
+            if name != MANIFEST_ENTRY {
+                return Err(invalid_pack("manifest must be the first entry"));
+            }
+            let manifest: MempackManifest = serde_json::from_slice(&data)
+                .map_err(|e| invalid_pack(format!("{MANIFEST_ENTRY}: {e}")))?;
+            manifest.validate()?;
+            checksums.insert(name, sha256_hex(&data));
+            manifest_seen = true;
+            visit(MempackItem::Manifest(manifest))?;
+            continue;
+        }
+
+        if name == TRAILER_ENTRY {
+            if pending_attachment.is_some() {
+                return Err(invalid_pack("attachment record without data"));
+            }
+            let trailer: MempackTrailer = serde_json::from_slice(&data)
+                .map_err(|e| invalid_pack(format!("{TRAILER_ENTRY}: {e}")))?;
+            if trailer.checksums != checksums {
+                return Err(invalid_pack("checksum mismatch"));
+            }
+            if trailer.counts != counts {
+                return Err(invalid_pack("record counts do not match the trailer"));
+            }
+            trailer_seen = true;
+            continue;
+        }
+
+        if checksums.contains_key(&name) {
+            return Err(invalid_pack(format!("duplicate entry {name}")));
+        }
+        let entry_section = Section::of_entry(&name)
+            .ok_or_else(|| invalid_pack(format!("unexpected entry {name}")))?;
+        if section.is_some_and(|current| current > entry_section) {
+            return Err(invalid_pack(format!("{name} is out of order")));
+        }
+        section = Some(entry_section);
+        checksums.insert(name.clone(), sha256_hex(&data));
+
+        match entry_section {
+            Section::Notes => {
+                counts.notes += parse_jsonl(&name, &data, |note| visit(MempackItem::Note(note)))?;
+            }
+            Section::Links => {
+                counts.links += parse_jsonl(&name, &data, |link| visit(MempackItem::Link(link)))?;
+            }
+            Section::Embeddings => {
+                counts.embeddings += parse_jsonl(&name, &data, |embedding| {
+                    visit(MempackItem::Embedding(embedding))
+                })?;
+            }
+            Section::Skos => {
+                let turtle = String::from_utf8(data)
+                    .map_err(|_| invalid_pack(format!("{SKOS_ENTRY} is not UTF-8")))?;
+                visit(MempackItem::Skos(turtle))?;
+            }
+            Section::Attachments => {
+                if let Some(stem) = name.strip_suffix(".json") {
+                    if pending_attachment.is_some() {
+                        return Err(invalid_pack("attachment record without data"));
+                    }
+                    let record: MempackAttachment = serde_json::from_slice(&data)
+                        .map_err(|e| invalid_pack(format!("{name}: {e}")))?;
+                    pending_attachment = Some((stem.to_string(), record));
+                } else if let Some(stem) = name.strip_suffix(".bin") {
+                    let (record_stem, record) = pending_attachment
+                        .take()
+                        .ok_or_else(|| invalid_pack("attachment data without record"))?;
+                    if record_stem != stem
+                        || record.size_bytes != data.len() as u64
+                        || record.sha256 != sha256_hex(&data)
+                    {
+                        return Err(invalid_pack(format!("{name} does not match its record")));
+                    }
+                    counts.attachments += 1;
+                    visit(MempackItem::Attachment { record, data })?;
+                } else {
+                    return Err(invalid_pack(format!("unexpected entry {name}")));
+                }
+            }
+        }
+    }
+
+    if !manifest_seen {
+        return Err(invalid_pack("empty archive"));
+    }
+    if !trailer_seen {
+        return Err(invalid_pack("truncated (missing trailer)"));
+    }
+    visit(MempackItem::End(counts))?;
+    Ok(counts)
+}
+
+// =============================================================================
+// REPOSITORY
+// =============================================================================
+
+/// Result of exporting one archive.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MempackExportSummary {
+    pub counts: MempackCounts,
+    /// PKE-encrypted notes are never exported.
+    pub encrypted_notes_skipped: u64,
+    /// Attachments whose bytes could not be read (scan verdict, storage).
+    pub attachments_skipped: u64,
+}
+
+/// Options for [`PgMempackRepository::import_tx`].
+#[derive(Debug, Clone, Default)]
+pub struct MempackImportOptions {
+    /// Embeddings are restored only when produced by this model; notes whose
+    /// embeddings are dropped are listed for re-embedding.
+    pub embedding_model: Option<String>,
+}
+
+/// Outcome of importing a pack.
+#[derive(Debug, Clone, Default, Serialize, utoipa::ToSchema)]
+pub struct MempackImportReport {
+    pub notes_imported: u64,
+    pub links_imported: u64,
+    /// Links whose endpoints were not in the pack.
+    pub links_skipped: u64,
+    pub attachments_imported: u64,
+    /// Attachments skipped because file storage is unavailable or their note
+    /// was not in the pack.
+    pub attachments_skipped: u64,
+    pub embeddings_imported: u64,
+    /// Embeddings dropped because their model differs from the target's.
+    pub embeddings_skipped: u64,
+    /// New IDs of imported notes that have no embeddings yet.
+    pub notes_needing_embeddings: Vec<Uuid>,
+    /// Source note ID → note ID in the target archive.
+    pub note_id_map: BTreeMap<Uuid, Uuid>,
+}
+
+/// PostgreSQL repository for memory pack export and import.
+#[derive(Clone)]
+pub struct PgMempackRepository {
+    pool: Pool<Postgres>,
+}
+
+struct PendingEmbeddings {
+    note_id: Uuid,
+    model: String,
+    chunks: Vec<(String, Vector)>,
+}
+
+impl PgMempackRepository {
+    /// Create a new memory pack repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Write every exportable record in the transaction's archive to `writer`.
+    ///
+    /// Soft-deleted and encrypted notes are left out, along with any link
+    /// touching them. Attachments are written only when `file_storage` is set.
+    pub async fn export_tx<W: Write>(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        writer: &mut MempackWriter<W>,
+        file_storage: Option<&PgFileStorageRepository>,
+        include_embeddings: bool,
+    ) -> Result<MempackExportSummary> {
+        let mut summary = MempackExportSummary {
+            encrypted_notes_skipped: sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM note WHERE deleted_at IS NULL AND encrypted IS TRUE",
+            )
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)? as u64,
+            ..Default::default()
+        };
+
+        let mut after: Option<Uuid> = None;
+        loop {
+            let rows = sqlx::query(
+                r#"
+                SELECT n.id, n.title, n.format, n.source, n.metadata,
+                       n.created_at_utc, n.updated_at_utc,
+                       COALESCE(n.starred, FALSE) AS starred,
+                       COALESCE(n.archived, FALSE) AS archived,
+                       o.content, r.content AS revised_content,
+                       ARRAY(SELECT t.tag_name FROM note_tag t
+                             WHERE t.note_id = n.id ORDER BY t.tag_name) AS tags
+                FROM note n
+                JOIN note_original o ON o.note_id = n.id
+                LEFT JOIN note_revised_current r ON r.note_id = n.id
+                WHERE n.deleted_at IS NULL AND n.encrypted IS NOT TRUE
+                  AND ($1::uuid IS NULL OR n.id > $1)
+                ORDER BY n.id
+                LIMIT $2
+                "#,
+            )
+            .bind(after)
+            .bind(EXPORT_PAGE_SIZE)
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+
+            for row in &rows {
+                let content: String = row.get("content");
+                let revised: Option<String> = row.get("revised_content");
+                writer.write_note(&MempackNote {
+                    id: row.get("id"),
+                    title: row.get("title"),
+                    format: row.get("format"),
+                    source: row.get("source"),
+                    revised_content: revised.filter(|revised| *revised != content),
+                    content,
+                    tags: row.get("tags"),
+                    metadata: row.get("metadata"),
+                    created_at_utc: row.get("created_at_utc"),
+                    updated_at_utc: row.get("updated_at_utc"),
+                    starred: row.get("starred"),
+                    archived: row.get("archived"),
+                })?;
+            }
+            match rows.last() {
+                Some(row) if rows.len() as i64 == EXPORT_PAGE_SIZE => after = Some(row.get("id")),
+                _ => break,
+            }
+        }
+
+        let mut after: Option<Uuid> = None;
+        loop {
+            let rows = sqlx::query(
+                r#"
+                SELECT l.id, l.from_note_id, l.to_note_id, l.to_url, l.kind, l.score, l.metadata
+                FROM link l
+                JOIN note src ON src.id = l.from_note_id
+                 AND src.deleted_at IS NULL AND src.encrypted IS NOT TRUE
+                LEFT JOIN note dst ON dst.id = l.to_note_id
+                WHERE (l.to_url IS NOT NULL
+                       OR (dst.id IS NOT NULL AND dst.deleted_at IS NULL
+                           AND dst.encrypted IS NOT TRUE))
+                  AND ($1::uuid IS NULL OR l.id > $1)
+                ORDER BY l.id
+                LIMIT $2
+                "#,
+            )
+            .bind(after)
+            .bind(EXPORT_PAGE_SIZE)
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+
+            for row in &rows {
+                let to_note_id: Option<Uuid> = row.get("to_note_id");
+                let to_url: Option<String> = row.get("to_url");
+                writer.write_link(&MempackLink {
+                    from_note_id: row.get("from_note_id"),
+                    to_url: if to_note_id.is_some() { None } else { to_url },
+                    to_note_id,
+                    kind: row.get("kind"),
+                    score: row.get("score"),
+                    metadata: row.get("metadata"),
+                })?;
+            }
+            match rows.last() {
+                Some(row) if rows.len() as i64 == EXPORT_PAGE_SIZE => after = Some(row.get("id")),
+                _ => break,
+            }
+        }
+
+        let snapshot = PgGraphExportRepository::new(self.pool.clone())
+            .snapshot_tx(tx)
+            .await?;
+        writer.write_skos(&RdfGraph::from_snapshot(&snapshot).to_turtle())?;
+
+        if let Some(file_storage) = file_storage {
+            let mut after: Option<Uuid> = None;
+            loop {
+                let rows = sqlx::query(
+                    r#"
+                    SELECT a.id, a.note_id
+                    FROM attachment a
+                    JOIN note n ON n.id = a.note_id
+                     AND n.deleted_at IS NULL AND n.encrypted IS NOT TRUE
+                    WHERE ($1::uuid IS NULL OR a.id > $1)
+                    ORDER BY a.id
+                    LIMIT $2
+                    "#,
+                )
+                .bind(after)
+                .bind(EXPORT_PAGE_SIZE)
+                .fetch_all(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+
+                for row in &rows {
+                    let attachment_id: Uuid = row.get("id");
+                    match file_storage.download_file_tx(tx, attachment_id).await {
+                        Ok((data, content_type, filename)) => writer.write_attachment(
+                            attachment_id,
+                            row.get("note_id"),
+                            &filename,
+                            &content_type,
+                            &data,
+                        )?,
+                        Err(Error::Database(e)) => return Err(Error::Database(e)),
+                        Err(_) => summary.attachments_skipped += 1,
+                    }
+                }
+                match rows.last() {
+                    Some(row) if rows.len() as i64 == EXPORT_PAGE_SIZE => {
+                        after = Some(row.get("id"))
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        if include_embeddings {
+            let mut after: Option<(Uuid, i32)> = None;
+            loop {
+                let rows = sqlx::query(
+                    r#"
+                    SELECT e.note_id, e.chunk_index, e.text, e.model, e.vector
+                    FROM embedding e
+                    JOIN note n ON n.id = e.note_id
+                     AND n.deleted_at IS NULL AND n.encrypted IS NOT TRUE
+                    WHERE e.vector IS NOT NULL
+                      AND (e.embedding_set_id IS NULL
+                           OR e.embedding_set_id = get_default_embedding_set_id())
+                      AND ($1::uuid IS NULL OR (e.note_id, e.chunk_index) > ($1, $2))
+                    ORDER BY e.note_id, e.chunk_index
+                    LIMIT $3
+                    "#,
+                )
+                .bind(after.map(|(note_id, _)| note_id))
+                .bind(after.map_or(0, |(_, chunk_index)| chunk_index))
+                .bind(EXPORT_PAGE_SIZE)
+                .fetch_all(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+
+                for row in &rows {
+                    let vector: Vector = row.get("vector");
+                    writer.write_embedding(&MempackEmbedding {
+                        note_id: row.get("note_id"),
+                        chunk_index: row.get("chunk_index"),
+                        text: row.get("text"),
+                        model: row.get("model"),
+                        vector: vector.to_vec(),
+                    })?;
+                }
+                match rows.last() {
+                    Some(row) if rows.len() as i64 == EXPORT_PAGE_SIZE => {
+                        after = Some((row.get("note_id"), row.get("chunk_index")))
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        summary.counts = writer.counts();
+        Ok(summary)
+    }
+
+    /// Apply items from a pack reader to the transaction's archive.
+    ///
+    /// Every note gets a new ID; links, attachments and embeddings are
+    /// rewritten through the resulting map. Items must arrive in pack order
+    /// and end with [`MempackItem::End`]; an error item, a missing `End` or a
+    /// closed channel fails the import so the caller can roll back.
+    pub async fn import_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        items: &mut mpsc::Receiver<Result<MempackItem>>,
+        file_storage: Option<&PgFileStorageRepository>,
+        options: &MempackImportOptions,
+    ) -> Result<MempackImportReport> {
+        let notes = PgNoteRepository::new(self.pool.clone());
+        let embeddings = crate::embeddings::PgEmbeddingRepository::new(self.pool.clone());
+        let mut report = MempackImportReport::default();
+        let mut embedded: HashSet<Uuid> = HashSet::new();
+        let mut pending: Option<PendingEmbeddings> = None;
+        let mut manifest_seen = false;
+        let mut ended = false;
+
+        while let Some(item) = items.recv().await {
+            let item = item?;
+            if ended {
+                return Err(invalid_pack("items after end of pack"));
+            }
+            if !manifest_seen && !matches!(item, MempackItem::Manifest(_)) {
+                return Err(invalid_pack("manifest must be the first entry"));
+            }
+            match item {
+                MempackItem::Manifest(manifest) => {
+                    if manifest_seen {
+                        return Err(invalid_pack("duplicate manifest"));
+                    }
+                    manifest.validate()?;
+                    manifest_seen = true;
+                }
+                MempackItem::Note(note) => {
+                    if report.note_id_map.contains_key(&note.id) {
+                        return Err(invalid_pack("duplicate note id"));
+                    }
+                    let new_id = notes
+                        .insert_tx(
+                            tx,
+                            CreateNoteRequest {
+                                content: note.content,
+                                format: note.format,
+                                source: note.source,
+                                collection_id: None,
+                                tags: (!note.tags.is_empty()).then_some(note.tags),
+                                metadata: note.metadata.is_object().then_some(note.metadata),
+                                document_type_id: None,
+                                title: note.title,
+                            },
+                        )
+                        .await?;
+                    if let Some(revised) = note.revised_content.as_deref() {
+                        notes
+                            .update_revised_tx(tx, new_id, revised, Some(IMPORT_REVISION_RATIONALE))
+                            .await?;
+                    }
+                    sqlx::query(
+                        "UPDATE note
+                         SET created_at_utc = $2, updated_at_utc = $3, starred = $4, archived = $5
+                         WHERE id = $1",
+                    )
+                    .bind(new_id)
+                    .bind(note.created_at_utc)
+                    .bind(note.updated_at_utc)
+                    .bind(note.starred)
+                    .bind(note.archived)
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(Error::Database)?;
+                    report.note_id_map.insert(note.id, new_id);
+                    report.notes_imported += 1;
+                }
+                MempackItem::Link(link) => {
+                    let Some(&from) = report.note_id_map.get(&link.from_note_id) else {
+                        report.links_skipped += 1;
+                        continue;
+                    };
+                    let target = match (link.to_note_id, link.to_url.as_deref()) {
+                        (Some(to), _) => report
+                            .note_id_map
+                            .get(&to)
+                            .map(|&to_note_id| (Some(to_note_id), None)),
+                        (None, Some(to_url)) => Some((None, Some(to_url))),
+                        (None, None) => None,
+                    };
+                    let Some((to_note_id, to_url)) = target else {
+                        report.links_skipped += 1;
+                        continue;
+                    };
+                    sqlx::query(
+                        "INSERT INTO link
+                             (id, from_note_id, to_note_id, to_url, kind, score, created_at_utc, metadata)
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+                    )
+                    .bind(new_v7())
+                    .bind(from)
+                    .bind(to_note_id)
+                    .bind(to_url)
+                    .bind(&link.kind)
+                    .bind(link.score)
+                    .bind(Utc::now())
+                    .bind(&link.metadata)
+                    .execute(&mut **tx)
+                    .await
+                    .map_err(Error::Database)?;
+                    report.links_imported += 1;
+                }
+                MempackItem::Skos(_) => {}
+                MempackItem::Attachment { record, data } => {
+                    match (report.note_id_map.get(&record.note_id), file_storage) {
+                        (Some(&note_id), Some(file_storage)) => {
+                            file_storage
+                                .store_file_tx(
+                                    tx,
+                                    note_id,
+                                    &record.filename,
+                                    &record.content_type,
+                                    &data,
+                                )
+                                .await?;
+                            report.attachments_imported += 1;
+                        }
+                        _ => report.attachments_skipped += 1,
+                    }
+                }
+                MempackItem::Embedding(embedding) => {
+                    let Some(&note_id) = report.note_id_map.get(&embedding.note_id) else {
+                        report.embeddings_skipped += 1;
+                        continue;
+                    };
+                    if options.embedding_model.as_deref() != Some(embedding.model.as_str()) {
+                        report.embeddings_skipped += 1;
+                        continue;
+                    }
+                    if pending.as_ref().is_some_and(|p| p.note_id != note_id) {
+                        self.store_pending(tx, &embeddings, pending.take(), &mut embedded)
+                            .await?;
+                    }
+                    let pending = pending.get_or_insert_with(|| PendingEmbeddings {
+                        note_id,
+                        model: embedding.model.clone(),
+                        chunks: Vec::new(),
+                    });
+                    pending
+                        .chunks
+                        .push((embedding.text, Vector::from(embedding.vector)));
+                    report.embeddings_imported += 1;
+                }
+                MempackItem::End(_) => {
+                    self.store_pending(tx, &embeddings, pending.take(), &mut embedded)
+                        .await?;
+                    ended = true;
+                }
+            }
+        }
+
+        if !ended {
+            return Err(invalid_pack("pack ended before its trailer was verified"));
+        }
+        report.notes_needing_embeddings = report
+            .note_id_map
+            .values()
+            .filter(|id| !embedded.contains(id))
+            .copied()
+            .collect();
+        Ok(report)
+    }
+
+    async fn store_pending(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        embeddings: &crate::embeddings::PgEmbeddingRepository,
+        pending: Option<PendingEmbeddings>,
+        embedded: &mut HashSet<Uuid>,
+    ) -> Result<()> {
+        let Some(pending) = pending else {
+            return Ok(());
+        };
+        if !embedded.insert(pending.note_id) {
+            return Err(invalid_pack("embeddings for a note are not contiguous"));
+        }
+        embeddings
+            .store_tx(tx, pending.note_id, pending.chunks, &pending.model)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_note(id: Uuid) -> MempackNote {
+        MempackNote {
+            id,
+            title: Some("private title".to_string()),
+            format: "markdown".to_string(),
+            source: "user".to_string(),
+            content: "private body".to_string(),
+            revised_content: None,
+            tags: vec!["topic/secret".to_string()],
+            metadata: serde_json::json!({}),
+            created_at_utc: Utc::now(),
+            updated_at_utc: Utc::now(),
+            starred: false,
+            archived: false,
+        }
+    }
+
+    fn sample_pack() -> Vec<u8> {
+        let note_a = Uuid::now_v7();
+        let note_b = Uuid::now_v7();
+        let manifest = MempackManifest::new(Some("research".to_string()), true);
+        let mut writer = MempackWriter::new(Vec::new(), &manifest).unwrap();
+        writer.write_note(&sample_note(note_a)).unwrap();
+        writer.write_note(&sample_note(note_b)).unwrap();
+        writer
+            .write_link(&MempackLink {
+                from_note_id: note_a,
+                to_note_id: Some(note_b),
+                to_url: None,
+                kind: "semantic".to_string(),
+                score: 0.8,
+                metadata: None,
+            })
+            .unwrap();
+        writer
+            .write_skos("@prefix skos: <http://www.w3.org/2004/02/skos/core#> .\n")
+            .unwrap();
+        writer
+            .write_attachment(Uuid::now_v7(), note_a, "a.txt", "text/plain", b"hello")
+            .unwrap();
+        writer
+            .write_embedding(&MempackEmbedding {
+                note_id: note_a,
+                chunk_index: 0,
+                text: "private body".to_string(),
+                model: "nomic-embed-text".to_string(),
+                vector: vec![0.1, 0.2],
+            })
+            .unwrap();
+        writer.finish().unwrap()
+    }
+
+    fn invalid_input_message(err: Error) -> String {
+        match err {
+            Error::InvalidInput(message) => message,
+            other => panic!("expected invalid input, got {other:?}"),
+        }
+    }
+
+    fn read_all(pack: &[u8]) -> Result<Vec<MempackItem>> {
+        let mut items = Vec::new();
+        read_mempack(pack, MEMPACK_DEFAULT_MAX_ENTRY_BYTES, |item| {
+            items.push(item);
+            Ok(())
+        })?;
+        Ok(items)
+    }
+
+    /// Rebuild `pack` through `edit`, which may rewrite or drop entries.
+    fn rewrite_pack(
+        pack: &[u8],
+        mut edit: impl FnMut(&str, Vec<u8>) -> Option<Vec<u8>>,
+    ) -> Vec<u8> {
+        let mut archive = tar::Archive::new(GzDecoder::new(pack));
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().to_string_lossy().into_owned();
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).unwrap();
+            if let Some(data) = edit(&name, data) {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(data.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder
+                    .append_data(&mut header, &name, data.as_slice())
+                    .unwrap();
+            }
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn round_trip_preserves_records_in_order() {
+        let items = read_all(&sample_pack()).unwrap();
+        let kinds: Vec<&str> = items
+            .iter()
+            .map(|item| match item {
+                MempackItem::Manifest(_) => "manifest",
+                MempackItem::Note(_) => "note",
+                MempackItem::Link(_) => "link",
+                MempackItem::Skos(_) => "skos",
+                MempackItem::Attachment { .. } => "attachment",
+                MempackItem::Embedding(_) => "embedding",
+                MempackItem::End(_) => "end",
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "manifest",
+                "note",
+                "note",
+                "link",
+                "skos",
+                "attachment",
+                "embedding",
+                "end"
+            ]
+        );
+        match items.last() {
+            Some(MempackItem::End(counts)) => assert_eq!(
+                *counts,
+                MempackCounts {
+                    notes: 2,
+                    links: 1,
+                    attachments: 1,
+                    embeddings: 1,
+                }
+            ),
+            other => panic!("expected end, got {other:?}"),
+        }
+        match &items[5] {
+            MempackItem::Attachment { record, data } => {
+                assert_eq!(data, b"hello");
+                assert_eq!(record.size_bytes, 5);
+            }
+            other => panic!("expected attachment, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn writer_splits_large_sections_into_parts() {
+        let manifest = MempackManifest::new(None, false);
+        let mut writer = MempackWriter::new(Vec::new(), &manifest).unwrap();
+        for _ in 0..MEMPACK_PART_RECORDS + 1 {
+            writer.write_note(&sample_note(Uuid::now_v7())).unwrap();
+        }
+        let pack = writer.finish().unwrap();
+        let mut names = Vec::new();
+        rewrite_pack(&pack, |name, data| {
+            names.push(name.to_string());
+            Some(data)
+        });
+        assert_eq!(
+            names,
+            [
+                "manifest.json",
+                "notes/00000.jsonl",
+                "notes/00001.jsonl",
+                "trailer.json"
+            ]
+        );
+        let items = read_all(&pack).unwrap();
+        assert_eq!(items.len(), MEMPACK_PART_RECORDS + 3);
+    }
+
+    #[test]
+    fn writer_rejects_out_of_order_sections() {
+        let manifest = MempackManifest::new(None, false);
+        let mut writer = MempackWriter::new(Vec::new(), &manifest).unwrap();
+        writer.write_skos("").unwrap();
+        let err = writer.write_note(&sample_note(Uuid::now_v7())).unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(_)));
+    }
+
+    #[test]
+    fn reader_rejects_newer_versions() {
+        let pack = rewrite_pack(&sample_pack(), |name, data| {
+            if name != MANIFEST_ENTRY {
+                return Some(data);
+            }
+            let mut manifest: JsonValue = serde_json::from_slice(&data).unwrap();
+            manifest["version"] = serde_json::json!(MEMPACK_VERSION + 1);
+            Some(serde_json::to_vec(&manifest).unwrap())
+        });
+        let err = read_all(&pack).unwrap_err();
+        assert!(invalid_input_message(err).contains("unsupported version"));
+    }
+
+    #[test]
+    fn reader_rejects_tampered_entries() {
+        let pack = rewrite_pack(&sample_pack(), |name, data| {
+            if name == "links/00000.jsonl" {
+                Some(
+                    String::from_utf8(data)
+                        .unwrap()
+                        .replace("0.8", "0.9")
+                        .into_bytes(),
+                )
+            } else {
+                Some(data)
+            }
+        });
+        let err = read_all(&pack).unwrap_err();
+        assert!(invalid_input_message(err).contains("checksum mismatch"));
+    }
+
+    #[test]
+    fn reader_requires_trailer_before_end() {
+        let pack = rewrite_pack(&sample_pack(), |name, data| {
+            (name != TRAILER_ENTRY).then_some(data)
+        });
+        let mut ended = false;
+        let err = read_mempack(pack.as_slice(), MEMPACK_DEFAULT_MAX_ENTRY_BYTES, |item| {
+            ended |= matches!(item, MempackItem::End(_));
+            Ok(())
+        })
+        .unwrap_err();
+        assert!(invalid_input_message(err).contains("missing trailer"));
+        assert!(!ended);
+    }
+
+    #[test]
+    fn reader_enforces_entry_size_limit() {
+        let err = read_mempack(sample_pack().as_slice(), 4, |_| Ok(())).unwrap_err();
+        assert!(invalid_input_message(err).contains("entry size limit"));
+    }
+
+    #[test]
+    fn debug_redacts_pack_content() {
+        for item in read_all(&sample_pack()).unwrap() {
+            let rendered = format!("{item:?}");
+            assert!(!rendered.contains("private"), "{rendered}");
+            assert!(!rendered.contains("research"), "{rendered}");
+            assert!(!rendered.contains("topic/secret"), "{rendered}");
+            assert!(!rendered.contains("a.txt"), "{rendered}");
+        }
+    }
+}
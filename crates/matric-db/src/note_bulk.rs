@@ -0,0 +1,183 @@
+//! Bulk note update repository.
+//!
+//! Resolves a [`BulkNoteFilter`] to note IDs and applies a [`BulkNotePatch`]
+//! to a batch of them. Every method takes a transaction that has already
+//! been pointed at the archive schema; the `bulk_note_update` job opens one
+//! transaction per batch.
+
+use chrono::Utc;
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    BulkNoteFilter, BulkNotePatch, BulkNoteUpdateResult, Error, Result, StrictSecurityFilter,
+    UpdateNoteStatusRequest,
+};
+
+use crate::unified_filter::{bind_filter_param, security_filter_query, QueryParam};
+use crate::{PgNoteRepository, PgTagRepository};
+
+/// Tag source recorded for tags added by a bulk update.
+const BULK_TAG_SOURCE: &str = "api";
+
+/// PostgreSQL repository for bulk note updates.
+pub struct PgBulkNoteRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+    notes: PgNoteRepository,
+    tags: PgTagRepository,
+}
+
+impl PgBulkNoteRepository {
+    /// Create a new bulk note repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self {
+            notes: PgNoteRepository::new(pool.clone()),
+            tags: PgTagRepository::new(pool.clone()),
+            pool,
+        }
+    }
+
+    /// IDs of the live notes matching `filter`, in ID order. `security`,
+    /// when given, admits only the notes it allows.
+    pub async fn matching_note_ids_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        filter: &BulkNoteFilter,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Vec<Uuid>> {
+        let security = security_filter_query(security, 5);
+        let security_clause = security
+            .as_ref()
+            .map(|result| format!("AND {}", result.where_clause))
+            .unwrap_or_default();
+        let sql = format!(
+            "SELECT n.id FROM note n
+             WHERE n.deleted_at IS NULL
+               AND NOT EXISTS (
+                   SELECT 1 FROM unnest($1::text[]) AS t(tag)
+                   WHERE NOT EXISTS (
+                       SELECT 1 FROM note_tag nt
+                       WHERE nt.note_id = n.id
+                         AND (LOWER(nt.tag_name) = LOWER(t.tag)
+                              OR LOWER(nt.tag_name) LIKE LOWER(t.tag) || '/%' ESCAPE '\\')))
+               AND ($2::uuid IS NULL OR n.collection_id = $2)
+               AND ($3::timestamptz IS NULL OR n.created_at_utc >= $3)
+               AND ($4::timestamptz IS NULL OR n.created_at_utc < $4)
+               AND ($5::text IS NULL
+                    OR to_tsvector('public.matric_english', COALESCE(n.title, ''))
+                       @@ websearch_to_tsquery('public.matric_english', $5)
+                    OR EXISTS (
+                        SELECT 1 FROM note_revised_current nrc
+                        WHERE nrc.note_id = n.id
+                          AND nrc.tsv @@ websearch_to_tsquery('public.matric_english', $5)))
+               {security_clause}
+             ORDER BY n.id"
+        );
+        let mut q = sqlx::query_scalar(&sql)
+            .bind(&filter.tags)
+            .bind(filter.collection_id)
+            .bind(filter.created_after)
+            .bind(filter.created_before)
+            .bind(filter.query.as_deref());
+        for param in security.iter().flat_map(|result| &result.params) {
+            q = bind_filter_param!(q, param);
+        }
+        q.fetch_all(&mut **tx).await.map_err(Error::Database)
+    }
+
+    /// Check that `patch` can be applied in this archive: the collection it
+    /// moves notes into must exist.
+    pub async fn check_patch_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        patch: &BulkNotePatch,
+    ) -> Result<()> {
+        let Some(collection_id) = patch.collection_id else {
+            return Ok(());
+        };
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM collection WHERE id = $1)")
+                .bind(collection_id)
+                .fetch_one(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+        if !exists {
+            return Err(Error::NotFound("Collection not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Apply `patch` to the notes in `note_ids`.
+    ///
+    /// Notes deleted since they were matched, and notes whose merged
+    /// metadata fails their document type's schema, are counted as failed
+    /// and left untouched; the rest of the batch is still updated.
+    pub async fn apply_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_ids: &[Uuid],
+        patch: &BulkNotePatch,
+    ) -> Result<BulkNoteUpdateResult> {
+        self.check_patch_tx(tx, patch).await?;
+        let mut live: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM note WHERE id = ANY($1) AND deleted_at IS NULL ORDER BY id",
+        )
+        .bind(note_ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        let mut result = BulkNoteUpdateResult {
+            matched: note_ids.len() as u64,
+            updated: 0,
+            failed: (note_ids.len() - live.len()) as u64,
+        };
+
+        if patch.metadata.is_some() || patch.archived.is_some() {
+            let mut applied = Vec::with_capacity(live.len());
+            for id in live {
+                let req = UpdateNoteStatusRequest {
+                    archived: patch.archived,
+                    metadata: patch.metadata.clone(),
+                    ..Default::default()
+                };
+                match self.notes.update_status_tx(tx, id, req).await {
+                    Ok(()) => applied.push(id),
+                    // Raised before the UPDATE runs, so the transaction is
+                    // still usable.
+                    Err(Error::InvalidInput(_)) | Err(Error::NotFound(_)) => result.failed += 1,
+                    Err(e) => return Err(e),
+                }
+            }
+            live = applied;
+        }
+        if live.is_empty() {
+            return Ok(result);
+        }
+
+        if let Some(collection_id) = patch.collection_id {
+            sqlx::query(
+                "UPDATE note SET collection_id = $1, updated_at_utc = $3
+                 WHERE id = ANY($2) AND collection_id IS DISTINCT FROM $1",
+            )
+            .bind(collection_id)
+            .bind(&live)
+            .bind(Utc::now())
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+
+        for tag in &patch.add_tags {
+            self.tags
+                .add_to_notes_tx(tx, &live, tag, BULK_TAG_SOURCE)
+                .await?;
+        }
+        for tag in &patch.remove_tags {
+            self.tags.remove_from_notes_tx(tx, &live, tag).await?;
+        }
+
+        result.updated = live.len() as u64;
+        Ok(result)
+    }
+}
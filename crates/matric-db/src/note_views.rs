@@ -0,0 +1,132 @@
+//! Per-principal note view repository.
+//!
+//! Views are written in batches by the API's view recorder and read back for
+//! `GET /api/v1/notes/recently-viewed` and the personal recency boost of
+//! search. Transaction methods expect a transaction already pointed at the
+//! archive schema; pool methods use the pool's own search path.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    Error, NoteView, RecentlyViewedNote, Result, StrictFilter, StrictSecurityFilter,
+};
+
+use crate::unified_filter::{QueryParam, UnifiedFilterQueryBuilder};
+
+/// PostgreSQL repository for note views.
+#[derive(Clone)]
+pub struct PgNoteViewRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgNoteViewRepository {
+    /// Create a new note view repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Add a batch of views. Views of notes that no longer exist are
+    /// dropped. Returns the number of principal/note rows written.
+    pub async fn record_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        views: &[NoteView],
+    ) -> Result<u64> {
+        if views.is_empty() {
+            return Ok(0);
+        }
+        let principals: Vec<&str> = views.iter().map(|v| v.principal.as_str()).collect();
+        let note_ids: Vec<Uuid> = views.iter().map(|v| v.note_id).collect();
+        let viewed_at: Vec<DateTime<Utc>> = views.iter().map(|v| v.viewed_at).collect();
+        let counts: Vec<i32> = views.iter().map(|v| v.views.max(1)).collect();
+        let result = sqlx::query(
+            "INSERT INTO note_view
+                 (principal, note_id, view_count, first_viewed_at_utc, last_viewed_at_utc)
+             SELECT v.principal, v.note_id, v.views, v.viewed_at, v.viewed_at
+             FROM unnest($1::text[], $2::uuid[], $3::timestamptz[], $4::int[])
+                  AS v(principal, note_id, viewed_at, views)
+             JOIN note n ON n.id = v.note_id
+             ON CONFLICT (principal, note_id) DO UPDATE
+             SET view_count = note_view.view_count + EXCLUDED.view_count,
+                 last_viewed_at_utc = GREATEST(note_view.last_viewed_at_utc,
+                                               EXCLUDED.last_viewed_at_utc)",
+        )
+        .bind(&principals)
+        .bind(&note_ids)
+        .bind(&viewed_at)
+        .bind(&counts)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(result.rows_affected())
+    }
+
+    /// Live notes `principal` opened, most recently opened first.
+    /// `security`, when given, admits only the notes it allows.
+    pub async fn recently_viewed_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        principal: &str,
+        limit: i64,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Vec<RecentlyViewedNote>> {
+        let security = security
+            .filter(|security| !security.is_empty())
+            .map(|security| {
+                let filter = StrictFilter::new().with_security(security.clone());
+                UnifiedFilterQueryBuilder::new(filter, 2).build()
+            });
+        let security_clause = security
+            .as_ref()
+            .map(|result| format!("AND {}", result.where_clause))
+            .unwrap_or_default();
+        let sql = format!(
+            "SELECT v.note_id, n.title, v.last_viewed_at_utc, v.first_viewed_at_utc, v.view_count
+             FROM note_view v
+             JOIN note n ON n.id = v.note_id AND n.deleted_at IS NULL
+             WHERE v.principal = $1 {security_clause}
+             ORDER BY v.last_viewed_at_utc DESC, v.note_id
+             LIMIT $2"
+        );
+        let mut q = sqlx::query_as::<_, RecentlyViewedNote>(&sql)
+            .bind(principal)
+            .bind(limit);
+        for param in security.iter().flat_map(|result| &result.params) {
+            q = match param {
+                QueryParam::Uuid(id) => q.bind(id),
+                QueryParam::UuidArray(ids) => q.bind(ids),
+                QueryParam::Int(val) => q.bind(val),
+                QueryParam::Timestamp(ts) => q.bind(ts),
+                QueryParam::Bool(b) => q.bind(b),
+                QueryParam::String(s) => q.bind(s),
+                QueryParam::StringArray(arr) => q.bind(arr),
+            };
+        }
+        q.fetch_all(&mut **tx).await.map_err(Error::Database)
+    }
+
+    /// When `principal` last opened each of `note_ids` it has opened.
+    pub async fn last_viewed(
+        &self,
+        principal: &str,
+        note_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, DateTime<Utc>>> {
+        if note_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows: Vec<(Uuid, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT note_id, last_viewed_at_utc FROM note_view
+             WHERE principal = $1 AND note_id = ANY($2)",
+        )
+        .bind(principal)
+        .bind(note_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(rows.into_iter().collect())
+    }
+}
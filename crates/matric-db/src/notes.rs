@@ -4,19 +4,25 @@ use async_trait::async_trait;
 use chrono::Utc;
 use hex;
 use sha2::{Digest, Sha256};
-use sqlx::{Pool, Postgres, Row, Transaction};
-use std::{collections::HashSet, fmt};
+use sqlx::{PgExecutor, Pool, Postgres, Row, Transaction};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 use uuid::Uuid;
 
+use matric_core::derived_metadata::{derive_metadata, DERIVED_METADATA_KEY};
 use matric_core::{
-    new_v7, CreateNoteRequest, Error, Link, ListNotesRequest, ListNotesResponse,
-    NoteConceptSummary, NoteFull, NoteMeta, NoteOriginal, NoteRepository, NoteRevised, NoteSummary,
-    Result, StrictFilter, UpdateNoteStatusRequest,
+    new_v7, CreateNoteRequest, DerivedField, DerivedMetadata, Error, Link, ListNotesRequest,
+    ListNotesResponse, MetadataFieldFilter, NoteConceptSummary, NoteFull, NoteMeta, NoteOriginal,
+    NoteRepository, NoteRevised, NoteSummary, Result, StrictFilter, StrictSecurityFilter,
+    StrictTagFilter, UpdateNoteStatusRequest, Visibility,
 };
 
+use crate::document_types::validate_note_metadata;
 use crate::hashtag_extraction::extract_inline_hashtags;
-use crate::strict_filter::QueryParam;
-use crate::unified_filter::UnifiedFilterQueryBuilder;
+use crate::strict_filter::{QueryParam, StrictFilterQueryBuilder};
+use crate::unified_filter::{security_filter_query, UnifiedFilterQueryBuilder};
 
 /// PostgreSQL implementation of NoteRepository.
 pub struct PgNoteRepository {
@@ -36,6 +42,11 @@ impl PgNoteRepository {
         format!("sha256:{}", hex::encode(hasher.finalize()))
     }
 
+    /// Detected language code of note content, or `None` when inconclusive.
+    fn detect_language(content: &str) -> Option<String> {
+        matric_core::language::detect_note_language(content).map(|d| d.code)
+    }
+
     fn access_source_metadata(source: Option<&str>) -> Option<String> {
         source.map(|value| format!("source_present=true;source_len={}", value.chars().count()))
     }
@@ -50,6 +61,10 @@ impl PgNoteRepository {
     /// Merge explicit tags with inline hashtags extracted from content.
     ///
     /// Returns a deduplicated, sorted vector of all tags.
+    fn encrypted_note_error() -> Error {
+        Error::InvalidInput("Encrypted note content cannot be replaced with plaintext".into())
+    }
+
     fn merge_tags(explicit_tags: Option<Vec<String>>, content: &str) -> Vec<String> {
         let mut all_tags = HashSet::new();
 
@@ -82,6 +97,7 @@ fn build_filter_clause(filter: &str) -> &'static str {
     match filter {
         "active" => "AND n.archived = false AND n.deleted_at IS NULL",
         "starred" => "AND n.starred = true AND n.archived = false AND n.deleted_at IS NULL",
+        "pinned" => "AND n.pinned = true AND n.deleted_at IS NULL",
         "archived" => "AND n.archived = true AND n.deleted_at IS NULL",
         "recent" => {
             "AND n.last_accessed_at IS NOT NULL AND n.archived = false AND n.deleted_at IS NULL"
@@ -107,10 +123,28 @@ fn build_order_clause(sort_by: &str, sort_order: &str) -> String {
             "COALESCE(n.last_accessed_at, n.created_at_utc) {}",
             validated
         ),
-        _ => format!("n.created_at_utc {}", validated),
+        other => match DerivedField::parse(other) {
+            Some(field) => derived_order_clause(field, validated),
+            None => format!("n.created_at_utc {}", validated),
+        },
     }
 }
 
+/// Order by a derived metadata field, notes without it last. The key comes
+/// from [`DerivedField`], never from the request.
+fn derived_order_clause(field: DerivedField, order: &str) -> String {
+    let path = format!("'{{{},{}}}'", DERIVED_METADATA_KEY, field.key());
+    let value = if field.is_numeric() {
+        format!(
+            "CASE WHEN jsonb_typeof(n.metadata #> {path}) = 'number' \
+             THEN (n.metadata #>> {path})::numeric END"
+        )
+    } else {
+        format!("n.metadata #>> {path}")
+    };
+    format!("{value} {order} NULLS LAST, n.created_at_utc DESC")
+}
+
 /// Add tag filters to the query string.
 fn add_tag_filters(query: &mut String, param_idx: &mut usize, tag_count: usize) {
     for _ in 0..tag_count {
@@ -149,6 +183,86 @@ fn add_date_filters(
     }
 }
 
+/// Restrict a list query to the notes a security filter admits. Returns the
+/// parameters to bind after the request's own.
+fn add_security_filter(
+    query: &mut String,
+    param_idx: &mut usize,
+    security: Option<&StrictSecurityFilter>,
+) -> Vec<QueryParam> {
+    let Some(security) = security.filter(|security| !security.is_empty()) else {
+        return Vec::new();
+    };
+    let filter = StrictFilter::new().with_security(security.clone());
+    let result = UnifiedFilterQueryBuilder::new(filter, *param_idx - 1).build();
+    query.push_str(&format!("AND {} ", result.where_clause));
+    *param_idx += result.params.len();
+    result.params
+}
+
+/// Restrict a list query to notes whose metadata satisfies every typed
+/// condition. Returns the parameters to bind after the request's own.
+fn add_metadata_filter(
+    query: &mut String,
+    param_idx: &mut usize,
+    fields: &[MetadataFieldFilter],
+) -> Vec<QueryParam> {
+    if fields.is_empty() {
+        return Vec::new();
+    }
+    let filter = StrictTagFilter {
+        metadata_fields: fields.to_vec(),
+        ..Default::default()
+    };
+    let (clause, params) = StrictFilterQueryBuilder::new(filter, *param_idx - 1).build();
+    query.push_str(&format!("AND {} ", clause));
+    *param_idx += params.len();
+    params
+}
+
+/// Reject a metadata patch whose merge into the note's metadata would not
+/// satisfy the metadata schema of the note's document type.
+async fn check_metadata_patch<'e, E>(executor: E, id: Uuid, patch: &serde_json::Value) -> Result<()>
+where
+    E: PgExecutor<'e>,
+{
+    let row: Option<(serde_json::Value, Option<serde_json::Value>)> = sqlx::query_as(
+        "SELECT COALESCE(n.metadata, '{}'::jsonb) || $2::jsonb, dt.metadata_schema
+         FROM note n
+         LEFT JOIN document_type dt ON dt.id = n.document_type_id
+         WHERE n.id = $1",
+    )
+    .bind(id)
+    .bind(patch)
+    .fetch_optional(executor)
+    .await
+    .map_err(Error::Database)?;
+
+    if let Some((merged, Some(schema))) = row {
+        validate_note_metadata(&schema, &merged)?;
+    }
+    Ok(())
+}
+
+/// Macro to bind strict filter parameters to a query.
+macro_rules! bind_query_params {
+    ($query:expr, $params:expr) => {{
+        let mut q = $query;
+        for param in $params {
+            q = match param {
+                QueryParam::Uuid(id) => q.bind(id),
+                QueryParam::UuidArray(ids) => q.bind(ids),
+                QueryParam::Int(val) => q.bind(val),
+                QueryParam::Timestamp(ts) => q.bind(ts),
+                QueryParam::Bool(b) => q.bind(b),
+                QueryParam::String(s) => q.bind(s),
+                QueryParam::StringArray(arr) => q.bind(arr),
+            };
+        }
+        q
+    }};
+}
+
 /// Macro to bind ListNotesRequest parameters to a query.
 macro_rules! bind_list_request_params {
     ($query:expr, $req:expr) => {{
@@ -273,6 +387,9 @@ impl NoteRepository for PgNoteRepository {
         if !self.exists(id).await? {
             return Err(Self::note_not_found_error(id));
         }
+        if let Some(patch) = &req.metadata {
+            check_metadata_patch(&self.pool, id, patch).await?;
+        }
         let mut updates: Vec<String> = vec!["updated_at_utc = $1".to_string()];
         let now = Utc::now();
         // $1 = now, $2 = id, then dynamic params start at $3
@@ -286,6 +403,14 @@ impl NoteRepository for PgNoteRepository {
             updates.push(format!("archived = ${}", param_idx));
             param_idx += 1;
         }
+        if req.pinned.is_some() {
+            updates.push(format!("pinned = ${}", param_idx));
+            param_idx += 1;
+        }
+        if req.priority.is_some() {
+            updates.push(format!("priority = ${}", param_idx));
+            param_idx += 1;
+        }
         if req.metadata.is_some() {
             // Merge new metadata keys into existing (issue #122) instead of replacing
             updates.push(format!(
@@ -303,6 +428,12 @@ impl NoteRepository for PgNoteRepository {
         if let Some(archived) = req.archived {
             q = q.bind(archived);
         }
+        if let Some(pinned) = req.pinned {
+            q = q.bind(pinned);
+        }
+        if let Some(priority) = req.priority {
+            q = q.bind(priority);
+        }
         if let Some(metadata) = req.metadata {
             q = q.bind(metadata);
         }
@@ -320,6 +451,9 @@ impl NoteRepository for PgNoteRepository {
         let hash = Self::hash_content(content);
 
         let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        if self.is_encrypted_tx(&mut tx, id).await? {
+            return Err(Self::encrypted_note_error());
+        }
 
         sqlx::query(
             "UPDATE note_original SET content = $1, hash = $2, user_last_edited_at = $3 WHERE note_id = $4",
@@ -332,9 +466,10 @@ impl NoteRepository for PgNoteRepository {
         .await
         .map_err(Error::Database)?;
 
-        sqlx::query("UPDATE note SET updated_at_utc = $1 WHERE id = $2")
+        sqlx::query("UPDATE note SET updated_at_utc = $1, language = $3 WHERE id = $2")
             .bind(now)
             .bind(id)
+            .bind(Self::detect_language(content))
             .execute(&mut *tx)
             .await
             .map_err(Error::Database)?;
@@ -364,6 +499,9 @@ impl NoteRepository for PgNoteRepository {
         let revision_id = new_v7();
 
         let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        if self.is_encrypted_tx(&mut tx, id).await? {
+            return Err(Self::encrypted_note_error());
+        }
 
         // Insert revision record into history table
         sqlx::query(
@@ -507,8 +645,8 @@ impl PgNoteRepository {
         // AI title-generation pipeline (unless the caller explicitly skipped
         // that step via revision_mode:"none" or skip_title_generation).
         sqlx::query(
-            "INSERT INTO note (id, collection_id, format, source, created_at_utc, updated_at_utc, metadata, document_type_id, title)
-             VALUES ($1, $2, $3, $4, $5, $5, COALESCE($6, '{}'::jsonb), $7, $8)",
+            "INSERT INTO note (id, collection_id, format, source, created_at_utc, updated_at_utc, metadata, document_type_id, title, language)
+             VALUES ($1, $2, $3, $4, $5, $5, COALESCE($6, '{}'::jsonb), $7, $8, $9)",
         )
         .bind(note_id)
         .bind(req.collection_id)
@@ -518,6 +656,7 @@ impl PgNoteRepository {
         .bind(req.metadata.as_ref().unwrap_or(&serde_json::json!({})))
         .bind(req.document_type_id)
         .bind(req.title.as_deref())
+        .bind(Self::detect_language(&req.content))
         .execute(&mut **tx)
         .await
         .map_err(Error::Database)?;
@@ -625,8 +764,8 @@ impl PgNoteRepository {
             // Insert note metadata (bulk path; matches single-insert above
             // for title plumbing — see #675).
             sqlx::query(
-                "INSERT INTO note (id, collection_id, format, source, created_at_utc, updated_at_utc, metadata, document_type_id, title)
-                 VALUES ($1, $2, $3, $4, $5, $5, COALESCE($6, '{}'::jsonb), $7, $8)",
+                "INSERT INTO note (id, collection_id, format, source, created_at_utc, updated_at_utc, metadata, document_type_id, title, language)
+                 VALUES ($1, $2, $3, $4, $5, $5, COALESCE($6, '{}'::jsonb), $7, $8, $9)",
             )
             .bind(note_id)
             .bind(req.collection_id)
@@ -636,6 +775,7 @@ impl PgNoteRepository {
             .bind(req.metadata.as_ref().unwrap_or(&serde_json::json!({})))
             .bind(req.document_type_id)
             .bind(req.title.as_deref())
+            .bind(Self::detect_language(&req.content))
             .execute(&mut **tx)
             .await
             .map_err(Error::Database)?;
@@ -774,7 +914,8 @@ impl PgNoteRepository {
         // Fetch note metadata
         let note_row = sqlx::query(
             "SELECT id, collection_id, format, source, created_at_utc, updated_at_utc,
-                    starred, archived, last_accessed_at, access_count, title, metadata, chunk_metadata, document_type_id
+                    starred, archived, last_accessed_at, access_count, title, metadata, chunk_metadata, document_type_id,
+                    encrypted, language, pinned, priority
              FROM note WHERE id = $1 AND deleted_at IS NULL",
         )
         .bind(id)
@@ -878,6 +1019,9 @@ impl PgNoteRepository {
             })
             .collect();
 
+        let summary = crate::summaries::fetch_summary(tx, id).await?;
+        let translation = crate::translations::fetch_translation(tx, id).await?;
+
         Ok(NoteFull {
             note: NoteMeta {
                 id: note_row.get("id"),
@@ -888,6 +1032,8 @@ impl PgNoteRepository {
                 updated_at_utc: note_row.get("updated_at_utc"),
                 starred: note_row.get::<Option<bool>, _>("starred").unwrap_or(false),
                 archived: note_row.get::<Option<bool>, _>("archived").unwrap_or(false),
+                pinned: note_row.get("pinned"),
+                priority: note_row.get("priority"),
                 last_accessed_at: note_row.get("last_accessed_at"),
                 access_count: note_row.get::<Option<i32>, _>("access_count").unwrap_or(0),
                 title: note_row.get("title"),
@@ -896,6 +1042,8 @@ impl PgNoteRepository {
                     .unwrap_or_else(|| serde_json::json!({})),
                 chunk_metadata: note_row.get("chunk_metadata"),
                 document_type_id: note_row.get("document_type_id"),
+                encrypted: note_row.get("encrypted"),
+                language: note_row.get("language"),
             },
             original: NoteOriginal {
                 content: original_row.get("content"),
@@ -916,6 +1064,8 @@ impl PgNoteRepository {
             tags,
             concepts,
             links,
+            summary,
+            translation,
         })
     }
 
@@ -924,6 +1074,17 @@ impl PgNoteRepository {
         &self,
         tx: &mut Transaction<'_, Postgres>,
         req: ListNotesRequest,
+    ) -> Result<ListNotesResponse> {
+        self.list_visible_tx(tx, req, None).await
+    }
+
+    /// List the notes a security filter admits within an existing
+    /// transaction. `None` lists every note, like [`Self::list_tx`].
+    pub async fn list_visible_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        req: ListNotesRequest,
+        security: Option<&StrictSecurityFilter>,
     ) -> Result<ListNotesResponse> {
         let sort_by = req.sort_by.as_deref().unwrap_or("created_at_utc");
         let sort_order = req.sort_order.as_deref().unwrap_or("desc");
@@ -951,11 +1112,15 @@ impl PgNoteRepository {
             req.updated_after.is_some(),
             req.updated_before.is_some(),
         );
+        let metadata_params = add_metadata_filter(&mut count_query, &mut param_idx, &req.metadata);
+        let security_params = add_security_filter(&mut count_query, &mut param_idx, security);
 
         // Execute count query
         let total: i64 = {
             let q = sqlx::query_scalar(&count_query);
             let q = bind_list_request_params!(q, req);
+            let q = bind_query_params!(q, &metadata_params);
+            let q = bind_query_params!(q, &security_params);
             q.fetch_one(&mut **tx).await.map_err(Error::Database)?
         };
 
@@ -991,6 +1156,8 @@ impl PgNoteRepository {
             req.updated_after.is_some(),
             req.updated_before.is_some(),
         );
+        add_metadata_filter(&mut notes_query, &mut param_idx, &req.metadata);
+        add_security_filter(&mut notes_query, &mut param_idx, security);
 
         notes_query.push_str(&format!(
             "ORDER BY {} LIMIT ${} OFFSET ${}",
@@ -1003,6 +1170,8 @@ impl PgNoteRepository {
         let rows = {
             let mut q = sqlx::query(&notes_query);
             q = bind_list_request_params!(q, req);
+            q = bind_query_params!(q, &metadata_params);
+            q = bind_query_params!(q, &security_params);
             q = q.bind(limit).bind(offset);
             q.fetch_all(&mut **tx).await.map_err(Error::Database)?
         };
@@ -1057,6 +1226,9 @@ impl PgNoteRepository {
         if !self.exists_tx(tx, id).await? {
             return Err(Self::note_not_found_error(id));
         }
+        if let Some(patch) = &req.metadata {
+            check_metadata_patch(&mut **tx, id, patch).await?;
+        }
         let mut updates: Vec<String> = vec!["updated_at_utc = $1".to_string()];
         let now = Utc::now();
         // $1 = now, $2 = id, then dynamic params start at $3
@@ -1070,6 +1242,14 @@ impl PgNoteRepository {
             updates.push(format!("archived = ${}", param_idx));
             param_idx += 1;
         }
+        if req.pinned.is_some() {
+            updates.push(format!("pinned = ${}", param_idx));
+            param_idx += 1;
+        }
+        if req.priority.is_some() {
+            updates.push(format!("priority = ${}", param_idx));
+            param_idx += 1;
+        }
         if req.metadata.is_some() {
             // Merge new metadata keys into existing (issue #122) instead of replacing
             updates.push(format!(
@@ -1087,6 +1267,12 @@ impl PgNoteRepository {
         if let Some(archived) = req.archived {
             q = q.bind(archived);
         }
+        if let Some(pinned) = req.pinned {
+            q = q.bind(pinned);
+        }
+        if let Some(priority) = req.priority {
+            q = q.bind(priority);
+        }
         if let Some(metadata) = req.metadata {
             q = q.bind(metadata);
         }
@@ -1106,6 +1292,9 @@ impl PgNoteRepository {
         if !self.exists_tx(tx, id).await? {
             return Err(Self::note_not_found_error(id));
         }
+        if self.is_encrypted_tx(tx, id).await? {
+            return Err(Self::encrypted_note_error());
+        }
         let now = Utc::now();
         let hash = Self::hash_content(content);
 
@@ -1120,9 +1309,10 @@ impl PgNoteRepository {
         .await
         .map_err(Error::Database)?;
 
-        sqlx::query("UPDATE note SET updated_at_utc = $1 WHERE id = $2")
+        sqlx::query("UPDATE note SET updated_at_utc = $1, language = $3 WHERE id = $2")
             .bind(now)
             .bind(id)
+            .bind(Self::detect_language(content))
             .execute(&mut **tx)
             .await
             .map_err(Error::Database)?;
@@ -1149,6 +1339,9 @@ impl PgNoteRepository {
         content: &str,
         rationale: Option<&str>,
     ) -> Result<Uuid> {
+        if self.is_encrypted_tx(tx, id).await? {
+            return Err(Self::encrypted_note_error());
+        }
         let now = Utc::now();
         let revision_id = new_v7();
 
@@ -1253,6 +1446,240 @@ impl PgNoteRepository {
         Ok(exists)
     }
 
+    /// Find the oldest live note whose original content is exactly `content`
+    /// within an existing transaction.
+    ///
+    /// Matches on the stored content hash, so it is cheap to call once per
+    /// imported note when detecting duplicates.
+    pub async fn find_by_original_content_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        content: &str,
+    ) -> Result<Option<Uuid>> {
+        sqlx::query_scalar(
+            "SELECT n.id
+             FROM note_original no
+             JOIN note n ON n.id = no.note_id
+             WHERE no.hash = $1 AND n.deleted_at IS NULL
+             ORDER BY n.created_at_utc, n.id
+             LIMIT 1",
+        )
+        .bind(Self::hash_content(content))
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Merge imported fields into an existing note within an existing transaction.
+    ///
+    /// The existing note keeps its content and any field it already has:
+    /// `title` only fills an empty title, `metadata` only adds missing
+    /// top-level keys, and `tags` are added alongside the current tags.
+    pub async fn merge_import_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        title: Option<&str>,
+        tags: &[String],
+        metadata: &serde_json::Value,
+    ) -> Result<()> {
+        let metadata = if metadata.is_object() {
+            metadata.clone()
+        } else {
+            serde_json::json!({})
+        };
+        sqlx::query(
+            "UPDATE note
+             SET title = COALESCE(NULLIF(title, ''), $2),
+                 metadata = $3::jsonb || COALESCE(metadata, '{}'::jsonb)
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(title)
+        .bind(metadata)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let now = Utc::now();
+        for tag_name in tags {
+            sqlx::query(
+                "INSERT INTO tag (name, created_at_utc) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            )
+            .bind(tag_name)
+            .bind(now)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            sqlx::query(
+                "INSERT INTO note_tag (note_id, tag_name, source) VALUES ($1, $2, 'user')
+                 ON CONFLICT DO NOTHING",
+            )
+            .bind(id)
+            .bind(tag_name)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+        Ok(())
+    }
+
+    /// Look up the collection a note is filed in. Returns `None` when the
+    /// note does not exist and `Some(None)` when it is in no collection.
+    pub async fn collection_id_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Option<Option<Uuid>>> {
+        sqlx::query_scalar("SELECT collection_id FROM note WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(Error::Database)
+    }
+
+    /// Check whether a note's content is stored encrypted.
+    pub async fn is_encrypted_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<bool> {
+        let encrypted: Option<bool> =
+            sqlx::query_scalar("SELECT encrypted FROM note WHERE id = $1")
+                .bind(id)
+                .fetch_optional(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+        Ok(encrypted.unwrap_or(false))
+    }
+
+    /// Insert a note whose content is stored as PKE ciphertext.
+    ///
+    /// The plaintext never reaches `note_original` or `note_revised_current`:
+    /// both hold empty content, so the note is absent from full-text search
+    /// and has nothing to embed. Inline hashtags are not extracted; explicit
+    /// tags and the title in `req` are stored as given.
+    pub async fn insert_encrypted_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        req: CreateNoteRequest,
+        keyset_id: Uuid,
+        ciphertext: &[u8],
+    ) -> Result<Uuid> {
+        let note_id = self
+            .insert_tx(
+                tx,
+                CreateNoteRequest {
+                    content: String::new(),
+                    ..req
+                },
+            )
+            .await?;
+
+        sqlx::query(
+            "UPDATE note
+             SET encrypted = TRUE, encryption_keyset_id = $2, encrypted_content = $3
+             WHERE id = $1",
+        )
+        .bind(note_id)
+        .bind(keyset_id)
+        .bind(ciphertext)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(note_id)
+    }
+
+    /// Fetch the ciphertext of an encrypted note.
+    ///
+    /// Returns `None` when the note exists but is not encrypted.
+    pub async fn fetch_encrypted_content_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Option<EncryptedNoteContent>> {
+        let row = sqlx::query(
+            "SELECT encrypted, encryption_keyset_id, encrypted_content
+             FROM note WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .ok_or_else(|| Self::note_not_found_error(id))?;
+
+        if !row.get::<bool, _>("encrypted") {
+            return Ok(None);
+        }
+        match (
+            row.get::<Option<Uuid>, _>("encryption_keyset_id"),
+            row.get::<Option<Vec<u8>>, _>("encrypted_content"),
+        ) {
+            (Some(keyset_id), Some(ciphertext)) => Ok(Some(EncryptedNoteContent {
+                keyset_id,
+                ciphertext,
+            })),
+            _ => Err(Error::Internal("Encrypted note content is missing".into())),
+        }
+    }
+
+    /// Lock the encrypted notes sealed for `keyset_id`, in ID order.
+    ///
+    /// Soft-deleted notes are included so that a restored note is still
+    /// readable after its keyset has been rotated and purged. Pass the last ID
+    /// of the previous batch as `after` to page through the notes.
+    pub async fn lock_encrypted_for_keyset_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        keyset_id: Uuid,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<(Uuid, Vec<u8>)>> {
+        let rows = sqlx::query(
+            "SELECT id, encrypted_content FROM note
+             WHERE encrypted AND encryption_keyset_id = $1
+               AND encrypted_content IS NOT NULL
+               AND ($2::uuid IS NULL OR id > $2)
+             ORDER BY id
+             LIMIT $3
+             FOR UPDATE",
+        )
+        .bind(keyset_id)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.get("id"), r.get("encrypted_content")))
+            .collect())
+    }
+
+    /// Replace the ciphertext of an encrypted note after re-encrypting it for
+    /// another keyset.
+    pub async fn replace_encrypted_content_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        keyset_id: Uuid,
+        ciphertext: &[u8],
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE note SET encryption_keyset_id = $2, encrypted_content = $3
+             WHERE id = $1 AND encrypted",
+        )
+        .bind(id)
+        .bind(keyset_id)
+        .bind(ciphertext)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
     /// Update note title within an existing transaction.
     pub async fn update_title_tx(
         &self,
@@ -1271,6 +1698,28 @@ impl PgNoteRepository {
         Ok(())
     }
 
+    /// Set who may see a note within an existing transaction.
+    pub async fn set_visibility_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        visibility: Visibility,
+    ) -> Result<()> {
+        if !self.exists_tx(tx, id).await? {
+            return Err(Self::note_not_found_error(id));
+        }
+        sqlx::query(
+            "UPDATE note SET visibility = $1::note_visibility, updated_at_utc = $2 WHERE id = $3",
+        )
+        .bind(visibility.as_str())
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
     /// List all note IDs within an existing transaction.
     pub async fn list_all_ids_tx(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Vec<Uuid>> {
         let sql = "SELECT id FROM note WHERE deleted_at IS NULL ORDER BY created_at_utc DESC";
@@ -1280,6 +1729,178 @@ impl PgNoteRepository {
             .map_err(Error::Database)?;
         Ok(rows.into_iter().map(|r| r.get("id")).collect())
     }
+
+    /// One page of live note IDs after `after`, in ID order, within an
+    /// existing transaction. `security`, when given, admits only the notes
+    /// it allows.
+    pub async fn list_ids_page_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        after: Option<Uuid>,
+        limit: i64,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Vec<Uuid>> {
+        let security = security_filter_query(security, 2);
+        let security_clause = security
+            .as_ref()
+            .map(|result| format!("AND {}", result.where_clause))
+            .unwrap_or_default();
+        let sql = format!(
+            "SELECT n.id FROM note n
+             WHERE n.deleted_at IS NULL
+               AND ($1::uuid IS NULL OR n.id > $1)
+               {security_clause}
+             ORDER BY n.id
+             LIMIT $2"
+        );
+        let q = bind_query_params!(
+            sqlx::query_scalar(&sql).bind(after).bind(limit),
+            security.iter().flat_map(|result| &result.params)
+        );
+        q.fetch_all(&mut **tx).await.map_err(Error::Database)
+    }
+
+    /// Fetch leading content excerpts for a set of notes within an existing transaction.
+    ///
+    /// Prefers original content over the current revision, matching similarity
+    /// search snippets. Deleted notes and notes without content are omitted.
+    /// Unlike `fetch_tx`, this does not record an access event.
+    pub async fn content_excerpts_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        ids: &[Uuid],
+        max_chars: i32,
+    ) -> Result<HashMap<Uuid, String>> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows = sqlx::query(
+            "SELECT n.id, substring(COALESCE(noc.content, nrc.content) for $2) AS excerpt
+             FROM note n
+             LEFT JOIN note_original noc ON noc.note_id = n.id
+             LEFT JOIN note_revised_current nrc ON nrc.note_id = n.id
+             WHERE n.id = ANY($1) AND n.deleted_at IS NULL",
+        )
+        .bind(ids)
+        .bind(max_chars)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let excerpt: Option<String> = row.get("excerpt");
+                excerpt
+                    .filter(|e| !e.trim().is_empty())
+                    .map(|e| (row.get("id"), e))
+            })
+            .collect())
+    }
+
+    /// Replace a note's derived metadata within an existing transaction.
+    ///
+    /// Writes `metadata.derived` without touching other keys, bypassing the
+    /// document type's metadata schema, and leaves `updated_at` alone since
+    /// the content did not change.
+    pub async fn set_derived_metadata_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        derived: &DerivedMetadata,
+    ) -> Result<()> {
+        let value = serde_json::to_value(derived)?;
+        sqlx::query(
+            "UPDATE note SET metadata = COALESCE(metadata, '{}'::jsonb) || jsonb_build_object($2::text, $3::jsonb)
+             WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .bind(DERIVED_METADATA_KEY)
+        .bind(value)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Recompute `fields` from a note's original content and store them
+    /// within an existing transaction.
+    ///
+    /// Encrypted and deleted notes are skipped and yield `None`. Reading the
+    /// content does not record an access event.
+    pub async fn refresh_derived_metadata_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        fields: &[DerivedField],
+    ) -> Result<Option<DerivedMetadata>> {
+        if fields.is_empty() {
+            return Ok(None);
+        }
+        let row = sqlx::query(
+            "SELECT COALESCE(noc.content, '') AS content, n.language
+             FROM note n
+             LEFT JOIN note_original noc ON noc.note_id = n.id
+             WHERE n.id = $1 AND n.deleted_at IS NULL AND n.encrypted = false",
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let content: String = row.get("content");
+        let language: Option<String> = row.get("language");
+        let derived = derive_metadata(fields, &content, language.as_deref());
+        self.set_derived_metadata_tx(tx, id, &derived).await?;
+        Ok(Some(derived))
+    }
+
+    /// Derived metadata of a set of notes, keyed by note ID. Notes without
+    /// derived fields are omitted.
+    pub async fn derived_metadata_for_notes(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, DerivedMetadata>> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows = sqlx::query(
+            "SELECT id, metadata -> $2 AS derived FROM note
+             WHERE id = ANY($1) AND metadata ? $2",
+        )
+        .bind(ids)
+        .bind(DERIVED_METADATA_KEY)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let value: serde_json::Value = row.get("derived");
+                serde_json::from_value::<DerivedMetadata>(value)
+                    .ok()
+                    .map(|derived| (row.get("id"), derived))
+            })
+            .collect())
+    }
+}
+
+/// Ciphertext of an encrypted note and the keyset it was sealed for.
+pub struct EncryptedNoteContent {
+    pub keyset_id: Uuid,
+    pub ciphertext: Vec<u8>,
+}
+
+impl fmt::Debug for EncryptedNoteContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptedNoteContent")
+            .field("keyset_id_set", &true)
+            .field("ciphertext_len", &self.ciphertext.len())
+            .finish()
+    }
 }
 
 // =============================================================================
@@ -1372,7 +1993,10 @@ impl PgNoteRepository {
                 validated_order
             ),
             "title" => format!("n.title {} NULLS LAST", validated_order),
-            _ => format!("n.created_at_utc {}", validated_order),
+            other => match DerivedField::parse(other) {
+                Some(field) => derived_order_clause(field, validated_order),
+                None => format!("n.created_at_utc {}", validated_order),
+            },
         };
 
         // Build full query with optional CTE
@@ -1547,6 +2171,18 @@ mod tests {
         SemanticScopeFilter, StrictCollectionFilter, StrictSecurityFilter, StrictTagFilter,
     };
 
+    #[test]
+    fn derived_fields_sort_with_missing_values_last() {
+        let clause = build_order_clause("reading_time", "asc");
+        assert!(clause.contains("'{derived,reading_time_minutes}'"));
+        assert!(clause.contains("::numeric END ASC NULLS LAST"));
+        assert_eq!(
+            build_order_clause("language", "desc"),
+            "n.metadata #>> '{derived,language}' DESC NULLS LAST, n.created_at_utc DESC"
+        );
+        assert_eq!(build_order_clause("unknown", "asc"), "n.created_at_utc ASC");
+    }
+
     #[test]
     fn test_hash_content() {
         let hash = PgNoteRepository::hash_content("test");
@@ -8,9 +8,9 @@ use sqlx::{Pool, Postgres, Row};
 use uuid::Uuid;
 
 use matric_core::{
-    new_v7, ApiKey, ClientRegistrationRequest, ClientRegistrationResponse, CreateApiKeyRequest,
-    CreateApiKeyResponse, Error, OAuthAuthorizationCode, OAuthClient, OAuthToken, Result,
-    TokenIntrospectionResponse,
+    new_v7, ApiKey, ApiKeyRestrictions, ClientRegistrationRequest, ClientRegistrationResponse,
+    CreateApiKeyRequest, CreateApiKeyResponse, Error, OAuthAuthorizationCode, OAuthClient,
+    OAuthToken, Result, TokenIntrospectionResponse,
 };
 
 /// PostgreSQL implementation of OAuth2 repository.
@@ -827,12 +827,14 @@ impl PgOAuthRepository {
         let expires_at = req
             .expires_in_days
             .map(|days| now + Duration::days(days as i64));
+        req.restrictions.validate()?;
 
         sqlx::query(
             r#"INSERT INTO api_key (
                 id, key_hash, key_prefix, name, description, scope,
+                allowed_archives, allowed_collections, read_only,
                 is_active, expires_at, created_at, updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, true, $7, $8, $8)"#,
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, true, $10, $11, $11)"#,
         )
         .bind(id)
         .bind(&key_hash)
@@ -840,6 +842,9 @@ impl PgOAuthRepository {
         .bind(&req.name)
         .bind(&req.description)
         .bind(&req.scope)
+        .bind(&req.restrictions.archives)
+        .bind(&req.restrictions.collections)
+        .bind(req.restrictions.read_only)
         .bind(expires_at)
         .bind(now)
         .execute(&self.pool)
@@ -852,6 +857,7 @@ impl PgOAuthRepository {
             key_prefix,
             name: req.name,
             scope: req.scope,
+            restrictions: req.restrictions,
             expires_at,
             created_at: now,
         })
@@ -865,6 +871,7 @@ impl PgOAuthRepository {
         let row = sqlx::query(
             r#"SELECT
                 id, key_prefix, name, description, scope,
+                allowed_archives, allowed_collections, read_only,
                 rate_limit_per_minute, rate_limit_per_hour,
                 last_used_at, use_count, is_active, expires_at, created_at
             FROM api_key
@@ -897,6 +904,7 @@ impl PgOAuthRepository {
             name: r.get("name"),
             description: r.get("description"),
             scope: r.get("scope"),
+            restrictions: api_key_restrictions(&r),
             rate_limit_per_minute: r.get("rate_limit_per_minute"),
             rate_limit_per_hour: r.get("rate_limit_per_hour"),
             last_used_at: r.get("last_used_at"),
@@ -912,6 +920,7 @@ impl PgOAuthRepository {
         let rows = sqlx::query(
             r#"SELECT
                 id, key_prefix, name, description, scope,
+                allowed_archives, allowed_collections, read_only,
                 rate_limit_per_minute, rate_limit_per_hour,
                 last_used_at, use_count, is_active, expires_at, created_at
             FROM api_key
@@ -929,6 +938,7 @@ impl PgOAuthRepository {
                 name: r.get("name"),
                 description: r.get("description"),
                 scope: r.get("scope"),
+                restrictions: api_key_restrictions(&r),
                 rate_limit_per_minute: r.get("rate_limit_per_minute"),
                 rate_limit_per_hour: r.get("rate_limit_per_hour"),
                 last_used_at: r.get("last_used_at"),
@@ -945,6 +955,7 @@ impl PgOAuthRepository {
         let row = sqlx::query(
             r#"SELECT
                 id, key_prefix, name, description, scope,
+                allowed_archives, allowed_collections, read_only,
                 rate_limit_per_minute, rate_limit_per_hour,
                 last_used_at, use_count, is_active, expires_at, created_at
             FROM api_key
@@ -961,6 +972,7 @@ impl PgOAuthRepository {
             name: r.get("name"),
             description: r.get("description"),
             scope: r.get("scope"),
+            restrictions: api_key_restrictions(&r),
             rate_limit_per_minute: r.get("rate_limit_per_minute"),
             rate_limit_per_hour: r.get("rate_limit_per_hour"),
             last_used_at: r.get("last_used_at"),
@@ -1018,6 +1030,15 @@ impl PgOAuthRepository {
     }
 }
 
+/// Read the restriction columns of an `api_key` row.
+fn api_key_restrictions(row: &sqlx::postgres::PgRow) -> ApiKeyRestrictions {
+    ApiKeyRestrictions {
+        archives: row.get("allowed_archives"),
+        collections: row.get("allowed_collections"),
+        read_only: row.get("read_only"),
+    }
+}
+
 /// Base64 URL-safe encoding without padding (for PKCE).
 fn base64_url_encode(data: &[u8]) -> String {
     use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
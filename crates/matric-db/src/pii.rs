@@ -0,0 +1,235 @@
+//! PII finding repository.
+//!
+//! Findings are archive-scoped; every method takes a transaction that has
+//! already been pointed at the archive schema.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::pii::PiiMatch;
+use matric_core::{new_v7, Error, PiiFinding, PiiNoteSummary, PiiSummary, Result};
+
+/// PostgreSQL repository for PII findings.
+#[derive(Clone)]
+pub struct PgPiiFindingRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgPiiFindingRepository {
+    /// Create a new PII finding repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Replace a note's findings with those of a new scan.
+    ///
+    /// `content` holds matches in the note's original content; `attachments`
+    /// holds matches in each attachment's extracted text. Returns the number
+    /// of findings stored.
+    pub async fn replace_note_findings_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        content: &[PiiMatch],
+        attachments: &[(Uuid, Vec<PiiMatch>)],
+        blocks_indexing: bool,
+    ) -> Result<usize> {
+        sqlx::query("DELETE FROM pii_findings WHERE note_id = $1")
+            .bind(note_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+
+        let mut rows: Vec<(Option<Uuid>, &PiiMatch)> = content.iter().map(|m| (None, m)).collect();
+        for (id, matches) in attachments {
+            rows.extend(matches.iter().map(|m| (Some(*id), m)));
+        }
+        let mut stored = 0;
+        for (attachment_id, m) in rows {
+            let (Ok(start), Ok(end)) = (i32::try_from(m.start), i32::try_from(m.end)) else {
+                continue;
+            };
+            sqlx::query(
+                "INSERT INTO pii_findings
+                     (id, note_id, attachment_id, kind, detector, start_offset, end_offset,
+                      value_hash, blocks_indexing)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            )
+            .bind(new_v7())
+            .bind(note_id)
+            .bind(attachment_id)
+            .bind(m.kind.as_str())
+            .bind(m.detector.as_str())
+            .bind(start)
+            .bind(end)
+            .bind(&m.value_hash)
+            .bind(blocks_indexing)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            stored += 1;
+        }
+        Ok(stored)
+    }
+
+    /// A note's findings, in text order.
+    pub async fn list_for_note_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<Vec<PiiFinding>> {
+        sqlx::query_as::<_, PiiFinding>(
+            "SELECT id, note_id, attachment_id, kind, detector, start_offset, end_offset,
+                    value_hash, blocks_indexing, created_at_utc
+             FROM pii_findings
+             WHERE note_id = $1
+             ORDER BY attachment_id NULLS FIRST, start_offset",
+        )
+        .bind(note_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Whether a finding keeps the note out of search indexes.
+    pub async fn blocks_indexing_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<bool> {
+        sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM pii_findings WHERE note_id = $1 AND blocks_indexing)",
+        )
+        .bind(note_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Extracted text of a note's attachments, skipping encrypted blobs.
+    pub async fn attachment_texts_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<Vec<(Uuid, String)>> {
+        sqlx::query_as(
+            "SELECT a.id, a.extracted_text
+             FROM attachment a
+             JOIN attachment_blob ab ON a.blob_id = ab.id
+             WHERE a.note_id = $1
+               AND a.extracted_text IS NOT NULL
+               AND a.extracted_text <> ''
+               AND ab.encrypted IS NOT TRUE
+             ORDER BY a.display_order, a.created_at",
+        )
+        .bind(note_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Remove a note's embeddings so it drops out of semantic search.
+    pub async fn delete_note_embeddings_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM embedding WHERE note_id = $1")
+            .bind(note_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        Ok(result.rows_affected())
+    }
+
+    /// Archive-wide counts of live notes with findings.
+    pub async fn summary_tx(&self, tx: &mut Transaction<'_, Postgres>) -> Result<PiiSummary> {
+        let (notes_with_pii, blocked_notes, total_findings): (i64, i64, i64) = sqlx::query_as(
+            "SELECT COUNT(DISTINCT pf.note_id),
+                    COUNT(DISTINCT pf.note_id) FILTER (WHERE pf.blocks_indexing),
+                    COUNT(*)
+             FROM pii_findings pf
+             JOIN note n ON n.id = pf.note_id
+             WHERE n.deleted_at IS NULL",
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let by_kind: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT pf.kind, COUNT(*)
+             FROM pii_findings pf
+             JOIN note n ON n.id = pf.note_id
+             WHERE n.deleted_at IS NULL
+             GROUP BY pf.kind",
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(PiiSummary {
+            notes_with_pii,
+            blocked_notes,
+            total_findings,
+            by_kind: by_kind.into_iter().collect(),
+        })
+    }
+
+    /// Live notes with findings, most findings first.
+    pub async fn list_notes_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        limit: i64,
+    ) -> Result<Vec<PiiNoteSummary>> {
+        #[allow(clippy::type_complexity)]
+        let rows: Vec<(Uuid, i64, i64, bool, DateTime<Utc>, serde_json::Value)> = sqlx::query_as(
+            "SELECT pf.note_id,
+                    COUNT(*),
+                    COUNT(*) FILTER (WHERE pf.attachment_id IS NOT NULL),
+                    BOOL_OR(pf.blocks_indexing),
+                    MAX(pf.created_at_utc),
+                    (SELECT jsonb_object_agg(k.kind, k.n)
+                     FROM (SELECT kind, COUNT(*) AS n FROM pii_findings
+                           WHERE note_id = pf.note_id GROUP BY kind) k)
+             FROM pii_findings pf
+             JOIN note n ON n.id = pf.note_id
+             WHERE n.deleted_at IS NULL
+             GROUP BY pf.note_id
+             ORDER BY COUNT(*) DESC, MAX(pf.created_at_utc) DESC
+             LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(
+                    note_id,
+                    finding_count,
+                    attachment_findings,
+                    blocks_indexing,
+                    last_scanned_at,
+                    kinds,
+                )| {
+                    let kinds: BTreeMap<String, i64> =
+                        serde_json::from_value(kinds).unwrap_or_default();
+                    PiiNoteSummary {
+                        note_id,
+                        kinds,
+                        finding_count,
+                        attachment_findings,
+                        blocks_indexing,
+                        last_scanned_at,
+                    }
+                },
+            )
+            .collect())
+    }
+}
@@ -0,0 +1,92 @@
+//! Pinned note repository.
+//!
+//! Lists pinned notes for `GET /api/v1/notes/pinned` and looks up which
+//! search hits are pinned so search can keep places for them (see
+//! [`matric_core::pinned`]). Transaction methods expect a transaction already
+//! pointed at the archive schema; pool methods use the pool's own search
+//! path.
+
+use std::collections::HashMap;
+
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::{Error, PinnedNote, PinnedQuery, Result, StrictFilter, StrictSecurityFilter};
+
+use crate::unified_filter::{QueryParam, UnifiedFilterQueryBuilder};
+
+/// PostgreSQL repository for pinned notes.
+#[derive(Clone)]
+pub struct PgPinnedRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgPinnedRepository {
+    /// Create a new pinned note repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Live pinned notes, highest priority first, then most recently
+    /// updated. `security`, when given, admits only the notes it allows.
+    pub async fn list_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        query: &PinnedQuery,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Vec<PinnedNote>> {
+        let security = security
+            .filter(|security| !security.is_empty())
+            .map(|security| {
+                let filter = StrictFilter::new().with_security(security.clone());
+                UnifiedFilterQueryBuilder::new(filter, 2).build()
+            });
+        let security_clause = security
+            .as_ref()
+            .map(|result| format!("AND {}", result.where_clause))
+            .unwrap_or_default();
+        let sql = format!(
+            "SELECT n.id AS note_id, n.title, n.collection_id, n.priority,
+                    COALESCE(n.starred, false) AS starred,
+                    COALESCE(n.archived, false) AS archived,
+                    n.updated_at_utc
+             FROM note n
+             WHERE n.pinned AND n.deleted_at IS NULL
+               AND ($1::uuid IS NULL OR n.collection_id = $1)
+               {security_clause}
+             ORDER BY n.priority DESC, n.updated_at_utc DESC, n.id
+             LIMIT $2"
+        );
+        let mut q = sqlx::query_as::<_, PinnedNote>(&sql)
+            .bind(query.collection_id)
+            .bind(query.limit);
+        for param in security.iter().flat_map(|result| &result.params) {
+            q = match param {
+                QueryParam::Uuid(id) => q.bind(id),
+                QueryParam::UuidArray(ids) => q.bind(ids),
+                QueryParam::Int(val) => q.bind(val),
+                QueryParam::Timestamp(ts) => q.bind(ts),
+                QueryParam::Bool(b) => q.bind(b),
+                QueryParam::String(s) => q.bind(s),
+                QueryParam::StringArray(arr) => q.bind(arr),
+            };
+        }
+        q.fetch_all(&mut **tx).await.map_err(Error::Database)
+    }
+
+    /// Priority of each of `note_ids` that is pinned.
+    pub async fn priorities(&self, note_ids: &[Uuid]) -> Result<HashMap<Uuid, i32>> {
+        if note_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows: Vec<(Uuid, i32)> = sqlx::query_as(
+            "SELECT id, priority FROM note
+             WHERE id = ANY($1) AND pinned AND deleted_at IS NULL",
+        )
+        .bind(note_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(rows.into_iter().collect())
+    }
+}
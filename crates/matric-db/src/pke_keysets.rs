@@ -1,6 +1,10 @@
 //! PKE keyset management repository (Issues #328, #332).
 //!
-//! Provides CRUD operations for managing named PKE keysets with encrypted private keys.
+//! Provides CRUD operations for managing named PKE keysets with encrypted private keys,
+//! plus the bookkeeping for key rotation: a rotated keyset is retired in favour
+//! of its successor and purged once its artifacts have been re-encrypted and
+//! its grace period has passed. Each keyset also records the public half of
+//! its Ed25519 signer identity once the private key has been unlocked.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -20,6 +24,8 @@ pub struct PkeKeyset {
     pub encrypted_private_key: Vec<u8>,
     pub address: String,
     pub label: Option<String>,
+    /// Base64url Ed25519 public key the keyset signs with, once recorded.
+    pub signing_public_key: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -36,6 +42,7 @@ impl std::fmt::Debug for PkeKeyset {
             )
             .field("address_len", &self.address.chars().count())
             .field("label_len", &optional_text_len(&self.label))
+            .field("signing_key_set", &self.signing_public_key.is_some())
             .field("created_at", &self.created_at)
             .field("updated_at", &self.updated_at)
             .finish()
@@ -50,6 +57,14 @@ pub struct PkeKeysetSummary {
     pub address: String,
     pub label: Option<String>,
     pub is_active: bool,
+    /// Successor keyset when this keyset has been rotated.
+    pub rotated_to_id: Option<Uuid>,
+    /// When the keyset was retired by a rotation.
+    pub retired_at: Option<DateTime<Utc>>,
+    /// End of the grace period after which a retired keyset is purged.
+    pub retire_after: Option<DateTime<Utc>>,
+    /// Base64url Ed25519 public key the keyset signs with, once recorded.
+    pub signing_public_key: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -62,12 +77,67 @@ impl std::fmt::Debug for PkeKeysetSummary {
             .field("address_len", &self.address.chars().count())
             .field("label_len", &optional_text_len(&self.label))
             .field("is_active", &self.is_active)
+            .field("rotated", &self.rotated_to_id.is_some())
+            .field("retired_at", &self.retired_at)
+            .field("retire_after", &self.retire_after)
+            .field("signing_key_set", &self.signing_public_key.is_some())
             .field("created_at", &self.created_at)
             .field("updated_at", &self.updated_at)
             .finish()
     }
 }
 
+/// Ed25519 signer identity of a keyset.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PkeKeysetSigner {
+    pub keyset_id: Uuid,
+    /// Keyset address, used as the signer key id.
+    pub address: String,
+    /// Base64url Ed25519 public key.
+    pub signing_public_key: String,
+}
+
+impl std::fmt::Debug for PkeKeysetSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PkeKeysetSigner")
+            .field("keyset_id_set", &true)
+            .field("address_len", &self.address.chars().count())
+            .field("signing_public_key_len", &self.signing_public_key.len())
+            .finish()
+    }
+}
+
+/// Rotation state of a keyset that has been retired in favour of a successor.
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PkeKeysetRetirement {
+    pub keyset_id: Uuid,
+    /// Successor keyset; `None` once the successor itself has been deleted.
+    pub rotated_to_id: Option<Uuid>,
+    pub retired_at: DateTime<Utc>,
+    pub retire_after: DateTime<Utc>,
+    /// Set when every artifact sealed for this keyset has been re-encrypted.
+    pub reencrypted_at: Option<DateTime<Utc>>,
+}
+
+impl PkeKeysetRetirement {
+    /// Whether artifacts may still be sealed for the retired keyset.
+    pub fn reencryption_pending(&self) -> bool {
+        self.reencrypted_at.is_none()
+    }
+}
+
+impl std::fmt::Debug for PkeKeysetRetirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PkeKeysetRetirement")
+            .field("keyset_id_set", &true)
+            .field("rotated_to_id_set", &self.rotated_to_id.is_some())
+            .field("retired_at", &self.retired_at)
+            .field("retire_after", &self.retire_after)
+            .field("reencrypted_at", &self.reencrypted_at)
+            .finish()
+    }
+}
+
 /// Request to create a new keyset.
 #[derive(Clone, Deserialize)]
 pub struct CreateKeysetRequest {
@@ -131,6 +201,18 @@ fn pke_keyset_text_len(value: &str) -> usize {
     value.chars().count()
 }
 
+fn keyset_insert_error(e: sqlx::Error, name: &str) -> Error {
+    if let sqlx::Error::Database(ref db_err) = e {
+        if db_err.constraint() == Some("pke_keysets_name_key") {
+            return Error::InvalidInput(format!(
+                "Keyset already exists; name_len={}",
+                pke_keyset_text_len(name)
+            ));
+        }
+    }
+    Error::Database(e)
+}
+
 fn keyset_not_found_by_id_error(_id: Uuid) -> Error {
     Error::NotFound("Keyset not found; keyset_id_present=true".to_string())
 }
@@ -142,6 +224,10 @@ fn keyset_not_found_by_name_error(name: &str) -> Error {
     ))
 }
 
+fn keyset_retired_error() -> Error {
+    Error::InvalidInput("Keyset has been retired by a rotation".to_string())
+}
+
 fn pke_keyset_decode_error(field: &'static str, err: impl std::fmt::Display) -> Error {
     let diagnostic = err.to_string();
     Error::InvalidInput(format!(
@@ -199,7 +285,7 @@ impl PgPkeKeysetRepository {
             r#"
             INSERT INTO pke_keysets (name, public_key, encrypted_private_key, address, label)
             VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, name, public_key, encrypted_private_key, address, label, created_at, updated_at
+            RETURNING id, name, public_key, encrypted_private_key, address, label, signing_public_key, created_at, updated_at
             "#,
         )
         .bind(&req.name)
@@ -209,17 +295,7 @@ impl PgPkeKeysetRepository {
         .bind(&req.label)
         .fetch_one(&self.pool)
         .await
-        .map_err(|e| {
-            if let sqlx::Error::Database(ref db_err) = e {
-                if db_err.constraint() == Some("pke_keysets_name_key") {
-                    return Error::InvalidInput(format!(
-                        "Keyset already exists; name_len={}",
-                        pke_keyset_text_len(&req.name)
-                    ));
-                }
-            }
-            Error::Database(e)
-        })?;
+        .map_err(|e| keyset_insert_error(e, &req.name))?;
 
         Ok(keyset)
     }
@@ -228,7 +304,7 @@ impl PgPkeKeysetRepository {
     pub async fn get_by_id(&self, id: Uuid) -> Result<Option<PkeKeyset>> {
         let keyset = sqlx::query_as::<_, PkeKeyset>(
             r#"
-            SELECT id, name, public_key, encrypted_private_key, address, label, created_at, updated_at
+            SELECT id, name, public_key, encrypted_private_key, address, label, signing_public_key, created_at, updated_at
             FROM pke_keysets
             WHERE id = $1
             "#,
@@ -245,7 +321,7 @@ impl PgPkeKeysetRepository {
     pub async fn get_by_name(&self, name: &str) -> Result<Option<PkeKeyset>> {
         let keyset = sqlx::query_as::<_, PkeKeyset>(
             r#"
-            SELECT id, name, public_key, encrypted_private_key, address, label, created_at, updated_at
+            SELECT id, name, public_key, encrypted_private_key, address, label, signing_public_key, created_at, updated_at
             FROM pke_keysets
             WHERE name = $1
             "#,
@@ -267,13 +343,19 @@ impl PgPkeKeysetRepository {
                 String,
                 String,
                 Option<String>,
+                Option<Uuid>,
+                Option<DateTime<Utc>>,
+                Option<DateTime<Utc>>,
+                Option<String>,
                 DateTime<Utc>,
                 DateTime<Utc>,
                 Option<Uuid>,
             ),
         >(
             r#"
-            SELECT k.id, k.name, k.address, k.label, k.created_at, k.updated_at, a.keyset_id
+            SELECT k.id, k.name, k.address, k.label, k.rotated_to_id, k.retired_at,
+                   k.retire_after, k.signing_public_key, k.created_at, k.updated_at,
+                   a.keyset_id
             FROM pke_keysets k
             LEFT JOIN pke_active_keyset a ON a.id = 1
             ORDER BY k.created_at DESC
@@ -286,12 +368,28 @@ impl PgPkeKeysetRepository {
         let summaries = keysets
             .into_iter()
             .map(
-                |(id, name, address, label, created_at, updated_at, active_id)| PkeKeysetSummary {
+                |(
+                    id,
+                    name,
+                    address,
+                    label,
+                    rotated_to_id,
+                    retired_at,
+                    retire_after,
+                    signing_public_key,
+                    created_at,
+                    updated_at,
+                    active_id,
+                )| PkeKeysetSummary {
                     id,
                     name,
                     address,
                     label,
                     is_active: active_id == Some(id),
+                    rotated_to_id,
+                    retired_at,
+                    retire_after,
+                    signing_public_key,
                     created_at,
                     updated_at,
                 },
@@ -305,7 +403,7 @@ impl PgPkeKeysetRepository {
     pub async fn get_active(&self) -> Result<Option<PkeKeyset>> {
         let keyset = sqlx::query_as::<_, PkeKeyset>(
             r#"
-            SELECT k.id, k.name, k.public_key, k.encrypted_private_key, k.address, k.label, k.created_at, k.updated_at
+            SELECT k.id, k.name, k.public_key, k.encrypted_private_key, k.address, k.label, k.signing_public_key, k.created_at, k.updated_at
             FROM pke_keysets k
             INNER JOIN pke_active_keyset a ON a.keyset_id = k.id
             WHERE a.id = 1
@@ -319,18 +417,22 @@ impl PgPkeKeysetRepository {
     }
 
     /// Set the active keyset by ID.
+    ///
+    /// Retired keysets cannot be re-activated.
     pub async fn set_active(&self, keyset_id: Uuid) -> Result<()> {
-        // Verify keyset exists
-        let exists = sqlx::query_scalar::<_, bool>(
-            r#"SELECT EXISTS(SELECT 1 FROM pke_keysets WHERE id = $1)"#,
+        // Verify keyset exists and has not been rotated out
+        let retired = sqlx::query_scalar::<_, bool>(
+            r#"SELECT retired_at IS NOT NULL FROM pke_keysets WHERE id = $1"#,
         )
         .bind(keyset_id)
-        .fetch_one(&self.pool)
+        .fetch_optional(&self.pool)
         .await
         .map_err(Error::Database)?;
 
-        if !exists {
-            return Err(keyset_not_found_by_id_error(keyset_id));
+        match retired {
+            None => return Err(keyset_not_found_by_id_error(keyset_id)),
+            Some(true) => return Err(keyset_retired_error()),
+            Some(false) => {}
         }
 
         sqlx::query(
@@ -391,6 +493,202 @@ impl PgPkeKeysetRepository {
         }
     }
 
+    /// Rotate a keyset: store its successor and retire it.
+    ///
+    /// The successor becomes the active keyset if the rotated keyset was
+    /// active. The old keyset stays usable for decryption until
+    /// `retire_after`; it is only purged once [`Self::mark_reencrypted`] has
+    /// been recorded as well.
+    pub async fn rotate(
+        &self,
+        keyset_id: Uuid,
+        successor: CreateKeysetRequest,
+        retire_after: DateTime<Utc>,
+    ) -> Result<PkeKeyset> {
+        let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+
+        let retired = sqlx::query_scalar::<_, bool>(
+            r#"SELECT retired_at IS NOT NULL FROM pke_keysets WHERE id = $1 FOR UPDATE"#,
+        )
+        .bind(keyset_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(Error::Database)?;
+        match retired {
+            None => return Err(keyset_not_found_by_id_error(keyset_id)),
+            Some(true) => return Err(keyset_retired_error()),
+            Some(false) => {}
+        }
+
+        let keyset = sqlx::query_as::<_, PkeKeyset>(
+            r#"
+            INSERT INTO pke_keysets (name, public_key, encrypted_private_key, address, label)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, name, public_key, encrypted_private_key, address, label, signing_public_key, created_at, updated_at
+            "#,
+        )
+        .bind(&successor.name)
+        .bind(&successor.public_key)
+        .bind(&successor.encrypted_private_key)
+        .bind(&successor.address)
+        .bind(&successor.label)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| keyset_insert_error(e, &successor.name))?;
+
+        sqlx::query(
+            r#"
+            UPDATE pke_keysets
+            SET rotated_to_id = $2, retired_at = NOW(), retire_after = $3,
+                reencrypted_at = NULL, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(keyset_id)
+        .bind(keyset.id)
+        .bind(retire_after)
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::Database)?;
+
+        sqlx::query(
+            r#"
+            UPDATE pke_active_keyset
+            SET keyset_id = $2, updated_at = NOW()
+            WHERE id = 1 AND keyset_id = $1
+            "#,
+        )
+        .bind(keyset_id)
+        .bind(keyset.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::Database)?;
+
+        tx.commit().await.map_err(Error::Database)?;
+        Ok(keyset)
+    }
+
+    /// Get the rotation state of a keyset, or `None` if it has not been retired.
+    pub async fn get_retirement(&self, keyset_id: Uuid) -> Result<Option<PkeKeysetRetirement>> {
+        let retirement = sqlx::query_as::<_, PkeKeysetRetirement>(
+            r#"
+            SELECT id AS keyset_id, rotated_to_id, retired_at, retire_after, reencrypted_at
+            FROM pke_keysets
+            WHERE id = $1 AND retired_at IS NOT NULL
+            "#,
+        )
+        .bind(keyset_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(retirement)
+    }
+
+    /// Record that no artifacts remain sealed for a retired keyset.
+    pub async fn mark_reencrypted(&self, keyset_id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE pke_keysets
+            SET reencrypted_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND retired_at IS NOT NULL
+            "#,
+        )
+        .bind(keyset_id)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(())
+    }
+
+    /// Delete retired keysets whose grace period has passed and whose
+    /// artifacts have all been re-encrypted. Returns the number purged.
+    pub async fn purge_retired(&self) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM pke_keysets
+            WHERE retire_after <= NOW() AND reencrypted_at IS NOT NULL
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Record the Ed25519 public key a keyset signs with.
+    ///
+    /// The key is derived from the keyset's private key, so callers record it
+    /// whenever they hold the unlocked key; repeated calls are no-ops.
+    pub async fn set_signing_public_key(
+        &self,
+        keyset_id: Uuid,
+        signing_public_key: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE pke_keysets
+            SET signing_public_key = $2, updated_at = NOW()
+            WHERE id = $1 AND signing_public_key IS DISTINCT FROM $2
+            "#,
+        )
+        .bind(keyset_id)
+        .bind(signing_public_key)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(())
+    }
+
+    /// Replace a keyset's passphrase-encrypted private key.
+    ///
+    /// Used when a keyset is recovered from secret shares under a new
+    /// passphrase. The key pair itself does not change. Returns `false` if
+    /// the keyset does not exist.
+    pub async fn set_encrypted_private_key(
+        &self,
+        keyset_id: Uuid,
+        encrypted_private_key: &[u8],
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE pke_keysets
+            SET encrypted_private_key = $2, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(keyset_id)
+        .bind(encrypted_private_key)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// List the signer identities of all keysets that have recorded one.
+    ///
+    /// Retired keysets are included so shards signed before a rotation still
+    /// verify during the grace period.
+    pub async fn list_signers(&self) -> Result<Vec<PkeKeysetSigner>> {
+        let signers = sqlx::query_as::<_, PkeKeysetSigner>(
+            r#"
+            SELECT id AS keyset_id, address, signing_public_key
+            FROM pke_keysets
+            WHERE signing_public_key IS NOT NULL
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(signers)
+    }
+
     /// Export a keyset.
     pub async fn export(&self, id: Uuid) -> Result<Option<ExportedKeyset>> {
         let keyset = self.get_by_id(id).await?;
@@ -451,6 +749,7 @@ mod tests {
             encrypted_private_key: b"encrypted-private-key-secret".to_vec(),
             address: "mm:example-address-secret-fragment".to_string(),
             label: Some("private label".to_string()),
+            signing_public_key: Some("signing-key-secret-fragment".to_string()),
             created_at: now,
             updated_at: now,
         };
@@ -460,9 +759,25 @@ mod tests {
             address: "mm:example-address-secret-fragment".to_string(),
             label: Some("private label".to_string()),
             is_active: true,
+            rotated_to_id: Some(Uuid::parse_str("018fd1a0-0000-7000-8000-000000000304").unwrap()),
+            retired_at: Some(now),
+            retire_after: Some(now),
+            signing_public_key: Some("signing-key-secret-fragment".to_string()),
             created_at: now,
             updated_at: now,
         };
+        let signer = PkeKeysetSigner {
+            keyset_id: Uuid::parse_str("018fd1a0-0000-7000-8000-000000000301").unwrap(),
+            address: "mm:example-address-secret-fragment".to_string(),
+            signing_public_key: "signing-key-secret-fragment".to_string(),
+        };
+        let retirement = PkeKeysetRetirement {
+            keyset_id: Uuid::parse_str("018fd1a0-0000-7000-8000-000000000301").unwrap(),
+            rotated_to_id: Some(Uuid::parse_str("018fd1a0-0000-7000-8000-000000000304").unwrap()),
+            retired_at: now,
+            retire_after: now,
+            reencrypted_at: None,
+        };
         let create = CreateKeysetRequest {
             name: "tenant-secret-keyset".to_string(),
             public_key: b"public-key-material".to_vec(),
@@ -479,7 +794,9 @@ mod tests {
             exported_at: now,
         };
 
-        let rendered = format!("{keyset:?}\n{summary:?}\n{create:?}\n{exported:?}");
+        let rendered = format!(
+            "{keyset:?}\n{summary:?}\n{create:?}\n{exported:?}\n{retirement:?}\n{signer:?}"
+        );
         assert!(rendered.contains("id_set"));
         assert!(rendered.contains("public_key_len"));
         assert!(rendered.contains("encrypted_private_key_len"));
@@ -487,11 +804,13 @@ mod tests {
         assert!(rendered.contains("address_len"));
         assert!(!rendered.contains("018fd1a0-0000-7000-8000-000000000301"));
         assert!(!rendered.contains("018fd1a0-0000-7000-8000-000000000302"));
+        assert!(!rendered.contains("018fd1a0-0000-7000-8000-000000000304"));
         assert!(!rendered.contains("tenant-secret-keyset"));
         assert!(!rendered.contains("public-key-material"));
         assert!(!rendered.contains("encrypted-private-key-secret"));
         assert!(!rendered.contains("example-address-secret-fragment"));
         assert!(!rendered.contains("private label"));
+        assert!(!rendered.contains("signing-key-secret-fragment"));
     }
 
     #[test]
@@ -747,6 +1066,143 @@ mod tests {
             .expect("Failed to delete imported keyset");
     }
 
+    #[tokio::test]
+    async fn test_rotate_keyset_retires_old_keyset() {
+        let Some(pool) = setup_test_pool().await else {
+            return;
+        };
+        let repo = PgPkeKeysetRepository::new(pool);
+
+        let test_id = Uuid::new_v4().to_string();
+        let keyset = repo
+            .create(CreateKeysetRequest {
+                name: format!("test-rotate-{}", test_id),
+                public_key: vec![1, 2, 3],
+                encrypted_private_key: vec![4, 5, 6],
+                address: format!("mm:rotate-{}", test_id),
+                label: None,
+            })
+            .await
+            .expect("Failed to create keyset");
+        repo.set_active(keyset.id)
+            .await
+            .expect("Failed to set active keyset");
+
+        let retire_after = Utc::now() - chrono::Duration::hours(1);
+        let successor = repo
+            .rotate(
+                keyset.id,
+                CreateKeysetRequest {
+                    name: format!("test-rotate-next-{}", test_id),
+                    public_key: vec![7, 8, 9],
+                    encrypted_private_key: vec![10, 11, 12],
+                    address: format!("mm:rotate-next-{}", test_id),
+                    label: None,
+                },
+                retire_after,
+            )
+            .await
+            .expect("Failed to rotate keyset");
+
+        // The successor takes over as active; the old keyset is retired.
+        let active = repo.get_active().await.unwrap().unwrap();
+        assert_eq!(active.id, successor.id);
+        let retirement = repo.get_retirement(keyset.id).await.unwrap().unwrap();
+        assert_eq!(retirement.rotated_to_id, Some(successor.id));
+        assert!(retirement.reencryption_pending());
+        assert!(repo.set_active(keyset.id).await.is_err());
+        assert!(repo.get_retirement(successor.id).await.unwrap().is_none());
+
+        // Past its grace period, but only purged once re-encryption is done.
+        repo.purge_retired().await.unwrap();
+        assert!(repo.get_by_id(keyset.id).await.unwrap().is_some());
+        repo.mark_reencrypted(keyset.id).await.unwrap();
+        repo.purge_retired().await.unwrap();
+        assert!(repo.get_by_id(keyset.id).await.unwrap().is_none());
+
+        repo.delete(successor.id)
+            .await
+            .expect("Failed to delete successor keyset");
+    }
+
+    #[tokio::test]
+    async fn test_signing_public_key_is_listed_as_signer() {
+        let Some(pool) = setup_test_pool().await else {
+            return;
+        };
+        let repo = PgPkeKeysetRepository::new(pool);
+
+        let test_id = Uuid::new_v4().to_string();
+        let keyset = repo
+            .create(CreateKeysetRequest {
+                name: format!("test-signer-{}", test_id),
+                public_key: vec![1, 2, 3],
+                encrypted_private_key: vec![4, 5, 6],
+                address: format!("mm:signer-{}", test_id),
+                label: None,
+            })
+            .await
+            .expect("Failed to create keyset");
+        let listed = |signers: &[PkeKeysetSigner]| {
+            signers
+                .iter()
+                .find(|signer| signer.keyset_id == keyset.id)
+                .map(|signer| signer.signing_public_key.clone())
+        };
+        assert_eq!(listed(&repo.list_signers().await.unwrap()), None);
+
+        repo.set_signing_public_key(keyset.id, "signing-key")
+            .await
+            .expect("Failed to record signing key");
+        repo.set_signing_public_key(keyset.id, "signing-key")
+            .await
+            .expect("Recording the same signing key again is a no-op");
+        assert_eq!(
+            listed(&repo.list_signers().await.unwrap()),
+            Some("signing-key".to_string())
+        );
+
+        repo.delete(keyset.id)
+            .await
+            .expect("Failed to delete keyset");
+    }
+
+    #[tokio::test]
+    async fn test_set_encrypted_private_key_replaces_key() {
+        let Some(pool) = setup_test_pool().await else {
+            return;
+        };
+        let repo = PgPkeKeysetRepository::new(pool);
+
+        let test_id = Uuid::new_v4().to_string();
+        let keyset = repo
+            .create(CreateKeysetRequest {
+                name: format!("test-recovered-{}", test_id),
+                public_key: vec![1, 2, 3],
+                encrypted_private_key: vec![4, 5, 6],
+                address: format!("mm:recovered-{}", test_id),
+                label: None,
+            })
+            .await
+            .expect("Failed to create keyset");
+
+        assert!(repo
+            .set_encrypted_private_key(keyset.id, &[7, 8, 9])
+            .await
+            .expect("Failed to replace private key"));
+        let reloaded = repo.get_by_id(keyset.id).await.unwrap().unwrap();
+        assert_eq!(reloaded.encrypted_private_key, vec![7, 8, 9]);
+        assert_eq!(reloaded.public_key, vec![1, 2, 3]);
+
+        repo.delete(keyset.id)
+            .await
+            .expect("Failed to delete keyset");
+        assert!(!repo
+            .set_encrypted_private_key(keyset.id, &[7, 8, 9])
+            .await
+            .unwrap());
+    }
+
     #[tokio::test]
     async fn test_duplicate_name_error() {
         let Some(pool) = setup_test_pool().await else {
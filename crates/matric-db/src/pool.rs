@@ -5,6 +5,7 @@ use std::time::{Duration, Instant};
 use sqlx::postgres::{PgPool, PgPoolOptions};
 use tracing::{debug, info, warn};
 
+use matric_core::metrics::{self, DB_POOL_CONNECTIONS};
 use matric_core::{Error, Result};
 
 /// Default maximum number of connections in the pool.
@@ -154,6 +155,27 @@ pub fn log_pool_metrics(pool: &PgPool) {
     }
 }
 
+/// Publish current pool utilization as `matric_db_pool_connections` gauges.
+///
+/// Called when `/metrics` is scraped, so the gauges reflect the pool at
+/// scrape time.
+pub fn record_pool_metrics(pool: &PgPool) {
+    let size = pool.size();
+    let idle = pool.num_idle() as u32;
+    let registry = metrics::global();
+    registry.set(
+        &DB_POOL_CONNECTIONS,
+        &[("state", "active")],
+        f64::from(size.saturating_sub(idle)),
+    );
+    registry.set(&DB_POOL_CONNECTIONS, &[("state", "idle")], f64::from(idle));
+    registry.set(
+        &DB_POOL_CONNECTIONS,
+        &[("state", "max")],
+        f64::from(pool.options().get_max_connections()),
+    );
+}
+
 /// Create a connection pool with `search_path` pinned to a specific schema.
 ///
 /// Every connection acquired from this pool will resolve unqualified table names
@@ -0,0 +1,165 @@
+//! Prompt template repository.
+//!
+//! Versions live in the shared `public` schema. A `NULL` schema name marks a
+//! global version; archive versions carry the archive's schema name. Saving
+//! never edits a version in place, it appends the next version number.
+
+use sqlx::{postgres::PgRow, Pool, Postgres, Row, Transaction};
+
+use matric_core::{new_v7, Error, PromptKey, PromptTemplate, Result};
+
+const PROMPT_TEMPLATE_COLUMNS: &str =
+    "id, prompt_key, schema_name, version, content, description, created_at_utc";
+
+/// PostgreSQL repository for prompt templates.
+pub struct PgPromptTemplateRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgPromptTemplateRepository {
+    /// Create a new prompt template repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Newest version of every key stored in a scope (`None` for global).
+    pub async fn list_latest(&self, schema_name: Option<&str>) -> Result<Vec<PromptTemplate>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT DISTINCT ON (prompt_key) {PROMPT_TEMPLATE_COLUMNS}
+            FROM prompt_template
+            WHERE schema_name IS NOT DISTINCT FROM $1
+            ORDER BY prompt_key, version DESC
+            "#
+        ))
+        .bind(schema_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows.iter().map(template_from_row).collect())
+    }
+
+    /// All versions of a key in a scope, newest first.
+    pub async fn list_versions(
+        &self,
+        key: PromptKey,
+        schema_name: Option<&str>,
+    ) -> Result<Vec<PromptTemplate>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {PROMPT_TEMPLATE_COLUMNS}
+            FROM prompt_template
+            WHERE prompt_key = $1 AND schema_name IS NOT DISTINCT FROM $2
+            ORDER BY version DESC
+            "#
+        ))
+        .bind(key.as_str())
+        .bind(schema_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows.iter().map(template_from_row).collect())
+    }
+
+    /// The version a job in `schema_name` should use: the newest archive
+    /// version, else the newest global version. `None` means the built-in.
+    pub async fn resolve(
+        &self,
+        key: PromptKey,
+        schema_name: &str,
+    ) -> Result<Option<PromptTemplate>> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {PROMPT_TEMPLATE_COLUMNS}
+            FROM prompt_template
+            WHERE prompt_key = $1 AND (schema_name = $2 OR schema_name IS NULL)
+            ORDER BY schema_name IS NULL, version DESC
+            LIMIT 1
+            "#
+        ))
+        .bind(key.as_str())
+        .bind(schema_name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.as_ref().map(template_from_row))
+    }
+
+    /// Store `content` as the next version of a key in a scope.
+    pub async fn create_version(
+        &self,
+        key: PromptKey,
+        schema_name: Option<&str>,
+        content: &str,
+        description: Option<&str>,
+    ) -> Result<PromptTemplate> {
+        let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        let template = self
+            .create_version_tx(&mut tx, key, schema_name, content, description)
+            .await?;
+        tx.commit().await.map_err(Error::Database)?;
+        Ok(template)
+    }
+
+    /// Store `content` as the next version of a key in a scope within an
+    /// existing transaction.
+    pub async fn create_version_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        key: PromptKey,
+        schema_name: Option<&str>,
+        content: &str,
+        description: Option<&str>,
+    ) -> Result<PromptTemplate> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO prompt_template
+                (id, prompt_key, schema_name, version, content, description)
+            SELECT $1, $2, $3, COALESCE(MAX(version), 0) + 1, $4, $5
+            FROM prompt_template
+            WHERE prompt_key = $2 AND schema_name IS NOT DISTINCT FROM $3
+            RETURNING {PROMPT_TEMPLATE_COLUMNS}
+            "#
+        ))
+        .bind(new_v7())
+        .bind(key.as_str())
+        .bind(schema_name)
+        .bind(content)
+        .bind(description)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(template_from_row(&row))
+    }
+
+    /// Delete every version of a key in a scope. Returns the number removed.
+    pub async fn delete(&self, key: PromptKey, schema_name: Option<&str>) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM prompt_template
+             WHERE prompt_key = $1 AND schema_name IS NOT DISTINCT FROM $2",
+        )
+        .bind(key.as_str())
+        .bind(schema_name)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+fn template_from_row(row: &PgRow) -> PromptTemplate {
+    PromptTemplate {
+        id: row.get("id"),
+        key: row.get("prompt_key"),
+        schema_name: row.get("schema_name"),
+        version: row.get("version"),
+        content: row.get("content"),
+        description: row.get("description"),
+        created_at_utc: row.get("created_at_utc"),
+    }
+}
@@ -6,6 +6,8 @@
 //! - **Activities**: AI processing operations (provenance_activity)
 //! - **Relations**: Derivation chains between source notes and revisions
 
+use std::collections::HashMap;
+
 use sqlx::{Pool, Postgres, Row, Transaction};
 use uuid::Uuid;
 
@@ -459,6 +461,84 @@ impl PgProvenanceRepository {
             .collect())
     }
 
+    /// Get the activities of several notes within an existing transaction,
+    /// keyed by note. Notes without activities are left out.
+    pub async fn get_activities_for_notes_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<ProvenanceActivity>>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, note_id, revision_id, activity_type, model_name,
+                   started_at, ended_at, metadata
+            FROM provenance_activity
+            WHERE note_id = ANY($1)
+            ORDER BY started_at DESC
+            "#,
+        )
+        .bind(note_ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let mut activities: HashMap<Uuid, Vec<ProvenanceActivity>> = HashMap::new();
+        for row in rows {
+            let note_id: Uuid = row.get("note_id");
+            activities
+                .entry(note_id)
+                .or_default()
+                .push(ProvenanceActivity {
+                    id: row.get("id"),
+                    note_id: row.get("note_id"),
+                    revision_id: row.get("revision_id"),
+                    activity_type: row.get("activity_type"),
+                    model_name: row.get("model_name"),
+                    started_at: row.get("started_at"),
+                    ended_at: row.get("ended_at"),
+                    metadata: row.get("metadata"),
+                });
+        }
+        Ok(activities)
+    }
+
+    /// Get provenance edges for all revisions of several notes within an
+    /// existing transaction, keyed by note. Notes without edges are left out.
+    pub async fn get_edges_for_notes_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<ProvenanceEdge>>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT nr.note_id, pe.id, pe.revision_id, pe.source_note_id, pe.source_url,
+                   pe.relation, pe.created_at_utc
+            FROM provenance_edge pe
+            JOIN note_revision nr ON nr.id = pe.revision_id
+            WHERE nr.note_id = ANY($1)
+            ORDER BY pe.created_at_utc
+            "#,
+        )
+        .bind(note_ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let mut edges: HashMap<Uuid, Vec<ProvenanceEdge>> = HashMap::new();
+        for row in rows {
+            let note_id: Uuid = row.get("note_id");
+            edges.entry(note_id).or_default().push(ProvenanceEdge {
+                id: row.get("id"),
+                revision_id: row.get("revision_id"),
+                source_note_id: row.get("source_note_id"),
+                source_url: row.get("source_url"),
+                relation: row.get("relation"),
+                created_at_utc: row.get("created_at_utc"),
+            });
+        }
+        Ok(edges)
+    }
+
     /// Get notes that cite/derive from a specific source note within an existing transaction.
     pub async fn get_derived_notes_tx(
         &self,
@@ -0,0 +1,572 @@
+//! Archive provisioning profiles.
+//!
+//! A provisioning profile bundles the starting configuration for a new
+//! archive: SKOS schemes with their concepts, document types, collections,
+//! prompt templates and embedding configs. Built-in profiles live as JSON in
+//! `crates/matric-db/provisioning/` and are compiled into the binary.
+//!
+//! [`PgProvisioningRepository::apply_tx`] writes a profile inside the caller's
+//! transaction, so either every record lands or none do. SKOS schemes,
+//! collections and prompt templates are archive-scoped. Document types and
+//! embedding configs are shared across archives and are only created when no
+//! row with the same name exists yet.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    validate_prompt_template, CreateConceptRequest, CreateConceptSchemeRequest,
+    CreateDocumentTypeRequest, CreateEmbeddingConfigRequest, Error, PromptKey, Result, TagStatus,
+};
+
+use crate::collections::PgCollectionRepository;
+use crate::document_types::PgDocumentTypeRepository;
+use crate::embedding_sets::PgEmbeddingSetRepository;
+use crate::prompt_templates::PgPromptTemplateRepository;
+use crate::skos_tags::PgSkosRepository;
+
+const BUILTIN_PROFILES: &[(&str, &str)] = &[
+    ("research", include_str!("../provisioning/research.json")),
+    (
+        "engineering",
+        include_str!("../provisioning/engineering.json"),
+    ),
+    ("personal", include_str!("../provisioning/personal.json")),
+];
+
+fn text_len(value: &str) -> usize {
+    value.chars().count()
+}
+
+fn invalid_profile(name: &str, message: impl fmt::Display) -> Error {
+    Error::InvalidInput(format!("invalid provisioning profile {name}: {message}"))
+}
+
+// =============================================================================
+// PROFILE
+// =============================================================================
+
+/// Starting configuration applied to a newly created archive.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProvisioningProfile {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub skos_schemes: Vec<ProvisionedScheme>,
+    #[serde(default)]
+    pub document_types: Vec<CreateDocumentTypeRequest>,
+    #[serde(default)]
+    pub collections: Vec<ProvisionedCollection>,
+    #[serde(default)]
+    pub prompt_templates: Vec<ProvisionedPromptTemplate>,
+    #[serde(default)]
+    pub embedding_configs: Vec<CreateEmbeddingConfigRequest>,
+}
+
+impl fmt::Debug for ProvisioningProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProvisioningProfile")
+            .field("name", &self.name)
+            .field("description_len", &text_len(&self.description))
+            .field("skos_scheme_count", &self.skos_schemes.len())
+            .field("document_type_count", &self.document_types.len())
+            .field("collection_count", &self.collections.len())
+            .field("prompt_template_count", &self.prompt_templates.len())
+            .field("embedding_config_count", &self.embedding_configs.len())
+            .finish()
+    }
+}
+
+/// A SKOS scheme and the concepts created in it.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProvisionedScheme {
+    pub notation: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub concepts: Vec<ProvisionedConcept>,
+}
+
+impl fmt::Debug for ProvisionedScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProvisionedScheme")
+            .field("notation_len", &text_len(&self.notation))
+            .field("title_len", &text_len(&self.title))
+            .field("description_set", &self.description.is_some())
+            .field("concept_count", &self.concepts.len())
+            .finish()
+    }
+}
+
+/// A concept within a provisioned scheme, created as approved.
+///
+/// `broader` names another concept of the same scheme by preferred label; it
+/// must appear earlier in the list.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProvisionedConcept {
+    pub pref_label: String,
+    #[serde(default)]
+    pub alt_labels: Vec<String>,
+    #[serde(default)]
+    pub definition: Option<String>,
+    #[serde(default)]
+    pub broader: Option<String>,
+}
+
+impl fmt::Debug for ProvisionedConcept {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProvisionedConcept")
+            .field("pref_label_len", &text_len(&self.pref_label))
+            .field("alt_label_count", &self.alt_labels.len())
+            .field("definition_set", &self.definition.is_some())
+            .field("broader_set", &self.broader.is_some())
+            .finish()
+    }
+}
+
+/// A collection, optionally nested under an earlier collection by name.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProvisionedCollection {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub parent: Option<String>,
+}
+
+impl fmt::Debug for ProvisionedCollection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProvisionedCollection")
+            .field("name_len", &text_len(&self.name))
+            .field("description_set", &self.description.is_some())
+            .field("parent_set", &self.parent.is_some())
+            .finish()
+    }
+}
+
+/// An archive-scoped prompt template override.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProvisionedPromptTemplate {
+    pub key: String,
+    pub content: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl fmt::Debug for ProvisionedPromptTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProvisionedPromptTemplate")
+            .field("key", &self.key)
+            .field("content_len", &text_len(&self.content))
+            .field("description_set", &self.description.is_some())
+            .finish()
+    }
+}
+
+impl ProvisioningProfile {
+    /// Check that the profile can be applied to an empty archive.
+    ///
+    /// Rejects unknown prompt keys, templates that fail prompt validation,
+    /// duplicate names, and `broader` or `parent` references that do not
+    /// point at an earlier entry.
+    pub fn validate(&self) -> Result<()> {
+        let name = self.name.as_str();
+        if name.trim().is_empty() {
+            return Err(invalid_profile(name, "name must not be empty"));
+        }
+
+        let mut notations = Vec::new();
+        for scheme in &self.skos_schemes {
+            if scheme.notation.trim().is_empty() || scheme.title.trim().is_empty() {
+                return Err(invalid_profile(
+                    name,
+                    "scheme notation and title are required",
+                ));
+            }
+            if notations.contains(&scheme.notation.as_str()) {
+                return Err(invalid_profile(
+                    name,
+                    format!("duplicate scheme {}", scheme.notation),
+                ));
+            }
+            notations.push(scheme.notation.as_str());
+
+            let mut labels: Vec<&str> = Vec::new();
+            for concept in &scheme.concepts {
+                if concept.pref_label.trim().is_empty() {
+                    return Err(invalid_profile(name, "concept pref_label is required"));
+                }
+                if labels.contains(&concept.pref_label.as_str()) {
+                    return Err(invalid_profile(
+                        name,
+                        format!("duplicate concept {}", concept.pref_label),
+                    ));
+                }
+                if let Some(broader) = concept.broader.as_deref() {
+                    if !labels.contains(&broader) {
+                        return Err(invalid_profile(
+                            name,
+                            format!(
+                                "concept {} refers to unknown broader concept {broader}",
+                                concept.pref_label
+                            ),
+                        ));
+                    }
+                }
+                labels.push(concept.pref_label.as_str());
+            }
+        }
+
+        let mut document_types = Vec::new();
+        for document_type in &self.document_types {
+            if document_type.name.trim().is_empty() {
+                return Err(invalid_profile(name, "document type name is required"));
+            }
+            if document_types.contains(&document_type.name.as_str()) {
+                return Err(invalid_profile(
+                    name,
+                    format!("duplicate document type {}", document_type.name),
+                ));
+            }
+            document_types.push(document_type.name.as_str());
+        }
+
+        let mut collections = Vec::new();
+        for collection in &self.collections {
+            if collection.name.trim().is_empty() {
+                return Err(invalid_profile(name, "collection name is required"));
+            }
+            if collections.contains(&collection.name.as_str()) {
+                return Err(invalid_profile(
+                    name,
+                    format!("duplicate collection {}", collection.name),
+                ));
+            }
+            if let Some(parent) = collection.parent.as_deref() {
+                if !collections.contains(&parent) {
+                    return Err(invalid_profile(
+                        name,
+                        format!(
+                            "collection {} refers to unknown parent {parent}",
+                            collection.name
+                        ),
+                    ));
+                }
+            }
+            collections.push(collection.name.as_str());
+        }
+
+        let mut prompt_keys = Vec::new();
+        for template in &self.prompt_templates {
+            let key = PromptKey::parse(&template.key).ok_or_else(|| {
+                invalid_profile(name, format!("unknown prompt key {}", template.key))
+            })?;
+            if prompt_keys.contains(&key) {
+                return Err(invalid_profile(
+                    name,
+                    format!("duplicate prompt template {key}"),
+                ));
+            }
+            validate_prompt_template(key, &template.content)
+                .map_err(|e| invalid_profile(name, e))?;
+            prompt_keys.push(key);
+        }
+
+        let mut embedding_configs = Vec::new();
+        for config in &self.embedding_configs {
+            if config.name.trim().is_empty() || config.model.trim().is_empty() {
+                return Err(invalid_profile(
+                    name,
+                    "embedding config name and model are required",
+                ));
+            }
+            if config.dimension <= 0 {
+                return Err(invalid_profile(
+                    name,
+                    format!("embedding config {} has no dimension", config.name),
+                ));
+            }
+            if embedding_configs.contains(&config.name.as_str()) {
+                return Err(invalid_profile(
+                    name,
+                    format!("duplicate embedding config {}", config.name),
+                ));
+            }
+            embedding_configs.push(config.name.as_str());
+        }
+
+        Ok(())
+    }
+}
+
+/// Look up a built-in provisioning profile by name.
+///
+/// Returns `Ok(None)` for unknown names.
+pub fn builtin_provisioning_profile(name: &str) -> Result<Option<ProvisioningProfile>> {
+    let Some((_, source)) = BUILTIN_PROFILES.iter().find(|(key, _)| *key == name) else {
+        return Ok(None);
+    };
+    let profile: ProvisioningProfile =
+        serde_json::from_str(source).map_err(|e| invalid_profile(name, e))?;
+    profile.validate()?;
+    Ok(Some(profile))
+}
+
+/// All built-in provisioning profiles, in a stable order.
+pub fn builtin_provisioning_profiles() -> Result<Vec<ProvisioningProfile>> {
+    let mut profiles = Vec::with_capacity(BUILTIN_PROFILES.len());
+    for (name, _) in BUILTIN_PROFILES {
+        if let Some(profile) = builtin_provisioning_profile(name)? {
+            profiles.push(profile);
+        }
+    }
+    Ok(profiles)
+}
+
+// =============================================================================
+// REPORT
+// =============================================================================
+
+/// Records created by applying a profile.
+///
+/// `*_existing` counts shared records that were already present under the
+/// same name and were left untouched.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvisioningReport {
+    pub profile: String,
+    pub skos_schemes: usize,
+    pub skos_concepts: usize,
+    pub collections: usize,
+    pub prompt_templates: usize,
+    pub document_types: usize,
+    pub document_types_existing: usize,
+    pub embedding_configs: usize,
+    pub embedding_configs_existing: usize,
+}
+
+// =============================================================================
+// REPOSITORY
+// =============================================================================
+
+/// Applies provisioning profiles to archives.
+pub struct PgProvisioningRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgProvisioningRepository {
+    /// Create a new provisioning repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Apply `profile` to the archive whose schema the transaction is bound to.
+    ///
+    /// `schema_name` scopes the prompt template overrides; it must match the
+    /// transaction's `search_path`.
+    pub async fn apply_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        schema_name: &str,
+        profile: &ProvisioningProfile,
+    ) -> Result<ProvisioningReport> {
+        profile.validate()?;
+        let mut report = ProvisioningReport {
+            profile: profile.name.clone(),
+            ..Default::default()
+        };
+
+        let skos = PgSkosRepository::new(self.pool.clone());
+        for scheme in &profile.skos_schemes {
+            let scheme_id = skos
+                .create_scheme_tx(
+                    tx,
+                    CreateConceptSchemeRequest {
+                        notation: scheme.notation.clone(),
+                        title: scheme.title.clone(),
+                        uri: None,
+                        description: scheme.description.clone(),
+                        creator: None,
+                        publisher: None,
+                        rights: None,
+                        version: None,
+                    },
+                )
+                .await?;
+            report.skos_schemes += 1;
+
+            let mut concept_ids: HashMap<&str, Uuid> = HashMap::new();
+            for concept in &scheme.concepts {
+                let broader_ids = concept
+                    .broader
+                    .as_deref()
+                    .and_then(|label| concept_ids.get(label).copied())
+                    .into_iter()
+                    .collect();
+                let concept_id = skos
+                    .create_concept_tx(
+                        tx,
+                        CreateConceptRequest {
+                            scheme_id,
+                            notation: None,
+                            pref_label: concept.pref_label.clone(),
+                            language: "en".to_string(),
+                            status: TagStatus::Approved,
+                            facet_type: None,
+                            facet_source: None,
+                            facet_domain: None,
+                            facet_scope: None,
+                            definition: concept.definition.clone(),
+                            scope_note: None,
+                            broader_ids,
+                            related_ids: Vec::new(),
+                            alt_labels: concept.alt_labels.clone(),
+                        },
+                    )
+                    .await?;
+                concept_ids.insert(concept.pref_label.as_str(), concept_id);
+                report.skos_concepts += 1;
+            }
+        }
+
+        let collections = PgCollectionRepository::new(self.pool.clone());
+        let mut collection_ids: HashMap<&str, Uuid> = HashMap::new();
+        for collection in &profile.collections {
+            let parent_id = collection
+                .parent
+                .as_deref()
+                .and_then(|parent| collection_ids.get(parent).copied());
+            let id = collections
+                .create_tx(
+                    tx,
+                    &collection.name,
+                    collection.description.as_deref(),
+                    parent_id,
+                )
+                .await?;
+            collection_ids.insert(collection.name.as_str(), id);
+            report.collections += 1;
+        }
+
+        let prompts = PgPromptTemplateRepository::new(self.pool.clone());
+        for template in &profile.prompt_templates {
+            let key = PromptKey::parse(&template.key).ok_or_else(|| {
+                invalid_profile(
+                    &profile.name,
+                    format!("unknown prompt key {}", template.key),
+                )
+            })?;
+            prompts
+                .create_version_tx(
+                    tx,
+                    key,
+                    Some(schema_name),
+                    &template.content,
+                    template.description.as_deref(),
+                )
+                .await?;
+            report.prompt_templates += 1;
+        }
+
+        let document_types = PgDocumentTypeRepository::new(self.pool.clone());
+        for document_type in &profile.document_types {
+            let exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM public.document_type WHERE name = $1)",
+            )
+            .bind(&document_type.name)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            if exists {
+                report.document_types_existing += 1;
+            } else {
+                document_types.create_tx(tx, document_type.clone()).await?;
+                report.document_types += 1;
+            }
+        }
+
+        let embedding_sets = PgEmbeddingSetRepository::new(self.pool.clone());
+        for config in &profile.embedding_configs {
+            let exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM public.embedding_config WHERE name = $1)",
+            )
+            .bind(&config.name)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            if exists {
+                report.embedding_configs_existing += 1;
+            } else {
+                embedding_sets.create_config_tx(tx, config.clone()).await?;
+                report.embedding_configs += 1;
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid_message(profile: &ProvisioningProfile) -> String {
+        match profile.validate() {
+            Err(Error::InvalidInput(message)) => message,
+            other => panic!("expected invalid input, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn builtin_profiles_parse_and_validate() {
+        let profiles = builtin_provisioning_profiles().unwrap();
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["research", "engineering", "personal"]);
+        for profile in &profiles {
+            assert!(!profile.skos_schemes.is_empty(), "{}", profile.name);
+            assert!(!profile.collections.is_empty(), "{}", profile.name);
+        }
+    }
+
+    #[test]
+    fn unknown_builtin_profile_is_none() {
+        assert!(builtin_provisioning_profile("nope").unwrap().is_none());
+    }
+
+    #[test]
+    fn validate_rejects_forward_references() {
+        let mut profile = builtin_provisioning_profile("research").unwrap().unwrap();
+        profile.collections.reverse();
+        assert!(invalid_message(&profile).contains("unknown parent"));
+
+        let mut profile = builtin_provisioning_profile("research").unwrap().unwrap();
+        profile.skos_schemes[0].concepts.reverse();
+        assert!(invalid_message(&profile).contains("unknown broader concept"));
+    }
+
+    #[test]
+    fn validate_rejects_bad_prompt_templates() {
+        let mut profile = builtin_provisioning_profile("personal").unwrap().unwrap();
+        profile.prompt_templates.push(ProvisionedPromptTemplate {
+            key: "summarization".to_string(),
+            content: "Summarize {{body}}".to_string(),
+            description: None,
+        });
+        assert!(profile.validate().is_err());
+
+        profile.prompt_templates[0].key = "not_a_prompt".to_string();
+        assert!(invalid_message(&profile).contains("unknown prompt key"));
+    }
+
+    #[test]
+    fn debug_redacts_profile_text() {
+        let profile = builtin_provisioning_profile("research").unwrap().unwrap();
+        let debug = format!("{:?}", profile.prompt_templates[0]);
+        assert!(!debug.contains("literature review"));
+        assert!(debug.contains("content_len"));
+    }
+}
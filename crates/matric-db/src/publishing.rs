@@ -0,0 +1,240 @@
+//! Published collection repository.
+//!
+//! Publications are shared, keyed by schema name (`public` for the default
+//! archive) like archive quotas, so a site slug can be looked up without
+//! knowing its archive. Page queries are archive-scoped and take a
+//! transaction that has already been pointed at the publication's schema.
+
+use std::collections::HashMap;
+
+use sqlx::{FromRow, Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::publishing::{PublicationSettings, MAX_PUBLISHED_PAGES, UNTITLED_PAGE};
+use matric_core::{Error, Publication, PublishedPageSummary, PublishedSearchHit, Result};
+
+const PUBLICATION_COLUMNS: &str = "slug, collection_id, title, description, include_subcollections,
+     published_at_utc, updated_at_utc";
+
+/// Notes a publication serves: public, live and readable notes in the
+/// collection, and in its subtree when subcollections are included. Binds
+/// `$1` to the collection ID and `$2` to `include_subcollections`.
+const PUBLISHED_NOTES_CTE: &str = "WITH RECURSIVE subtree AS (
+         SELECT id FROM collection WHERE id = $1
+         UNION
+         SELECT c.id FROM collection c INNER JOIN subtree s ON c.parent_id = s.id WHERE $2
+     ),
+     published AS (
+         SELECT n.id, n.title, n.updated_at_utc,
+                COALESCE(NULLIF(nrc.content, ''), no.content, '') AS content
+         FROM note n
+         LEFT JOIN note_original no ON no.note_id = n.id
+         LEFT JOIN note_revised_current nrc ON nrc.note_id = n.id
+         WHERE n.collection_id IN (SELECT id FROM subtree)
+           AND n.visibility = 'public'
+           AND n.deleted_at IS NULL
+           AND NOT n.encrypted
+     )";
+
+/// PostgreSQL repository for published collections.
+#[derive(Clone)]
+pub struct PgPublicationRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgPublicationRepository {
+    /// Create a new publication repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Publish a collection of an archive, or update its publication.
+    ///
+    /// A slug another publication already uses is rejected.
+    pub async fn publish(
+        &self,
+        schema_name: &str,
+        collection_id: Uuid,
+        settings: &PublicationSettings,
+    ) -> Result<Publication> {
+        sqlx::query_as(&format!(
+            "INSERT INTO published_site
+                 (slug, schema_name, collection_id, title, description, include_subcollections)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (schema_name, collection_id) DO UPDATE SET
+                 slug = EXCLUDED.slug,
+                 title = EXCLUDED.title,
+                 description = EXCLUDED.description,
+                 include_subcollections = EXCLUDED.include_subcollections,
+                 updated_at_utc = NOW()
+             RETURNING {PUBLICATION_COLUMNS}"
+        ))
+        .bind(&settings.slug)
+        .bind(schema_name)
+        .bind(collection_id)
+        .bind(&settings.title)
+        .bind(&settings.description)
+        .bind(settings.include_subcollections)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| slug_error(e, &settings.slug))
+    }
+
+    /// A collection's publication, if it is published.
+    pub async fn get_for_collection(
+        &self,
+        schema_name: &str,
+        collection_id: Uuid,
+    ) -> Result<Option<Publication>> {
+        sqlx::query_as(&format!(
+            "SELECT {PUBLICATION_COLUMNS}
+             FROM published_site
+             WHERE schema_name = $1 AND collection_id = $2"
+        ))
+        .bind(schema_name)
+        .bind(collection_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// An archive's publications, by slug.
+    pub async fn list_for_archive(&self, schema_name: &str) -> Result<Vec<Publication>> {
+        sqlx::query_as(&format!(
+            "SELECT {PUBLICATION_COLUMNS}
+             FROM published_site
+             WHERE schema_name = $1
+             ORDER BY slug"
+        ))
+        .bind(schema_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Stop publishing a collection. Returns whether it was published.
+    pub async fn unpublish(&self, schema_name: &str, collection_id: Uuid) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM published_site WHERE schema_name = $1 AND collection_id = $2")
+                .bind(schema_name)
+                .bind(collection_id)
+                .execute(&self.pool)
+                .await
+                .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The publication served at a slug and the schema of its archive.
+    pub async fn get_site(&self, slug: &str) -> Result<Option<(String, Publication)>> {
+        let row = sqlx::query(&format!(
+            "SELECT schema_name, {PUBLICATION_COLUMNS} FROM published_site WHERE slug = $1"
+        ))
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        row.map(|row| {
+            let publication = Publication::from_row(&row).map_err(Error::Database)?;
+            Ok((row.get("schema_name"), publication))
+        })
+        .transpose()
+    }
+
+    /// The pages a publication serves, by title.
+    pub async fn pages_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        publication: &Publication,
+    ) -> Result<Vec<PublishedPageSummary>> {
+        sqlx::query_as(&format!(
+            "{PUBLISHED_NOTES_CTE}
+             SELECT id, COALESCE(NULLIF(title, ''), $3) AS title, updated_at_utc
+             FROM published
+             ORDER BY lower(COALESCE(NULLIF(title, ''), $3)), id
+             LIMIT $4"
+        ))
+        .bind(publication.collection_id)
+        .bind(publication.include_subcollections)
+        .bind(UNTITLED_PAGE)
+        .bind(MAX_PUBLISHED_PAGES)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// The content of those of `ids` the publication serves.
+    pub async fn contents_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        publication: &Publication,
+        ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, String>> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows: Vec<(Uuid, String)> = sqlx::query_as(&format!(
+            "{PUBLISHED_NOTES_CTE}
+             SELECT id, content FROM published WHERE id = ANY($3)"
+        ))
+        .bind(publication.collection_id)
+        .bind(publication.include_subcollections)
+        .bind(ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Full-text search over the pages a publication serves, best first.
+    ///
+    /// Notes whose PII findings keep them out of search indexes are left
+    /// out here too.
+    pub async fn search_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        publication: &Publication,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<PublishedSearchHit>> {
+        sqlx::query_as(&format!(
+            "{PUBLISHED_NOTES_CTE},
+             matched AS (
+                 SELECT p.id, COALESCE(NULLIF(p.title, ''), $4) AS title, p.content,
+                        setweight(to_tsvector('public.matric_english', COALESCE(p.title, '')), 'A') ||
+                        setweight(to_tsvector('public.matric_english', p.content), 'C') AS tsv
+                 FROM published p
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM pii_findings pf WHERE pf.note_id = p.id AND pf.blocks_indexing
+                 )
+             )
+             SELECT id, title,
+                    ts_headline('public.matric_english', content, q,
+                                'StartSel=\"\", StopSel=\"\", MaxWords=35, MinWords=15') AS snippet,
+                    ts_rank(tsv, q, 32)::real AS score
+             FROM matched, websearch_to_tsquery('public.matric_english', $3) q
+             WHERE tsv @@ q
+             ORDER BY score DESC, id
+             LIMIT $5"
+        ))
+        .bind(publication.collection_id)
+        .bind(publication.include_subcollections)
+        .bind(query)
+        .bind(UNTITLED_PAGE)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+}
+
+fn slug_error(e: sqlx::Error, slug: &str) -> Error {
+    if let sqlx::Error::Database(ref db_err) = e {
+        if db_err.is_unique_violation() {
+            return Error::InvalidInput(format!(
+                "Publication slug is already in use; slug_len={}",
+                slug.len()
+            ));
+        }
+    }
+    Error::Database(e)
+}
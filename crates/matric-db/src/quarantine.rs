@@ -0,0 +1,82 @@
+//! Ingestion quarantine repository.
+//!
+//! Quarantine entries are archive-scoped; every method takes a transaction
+//! that has already been pointed at the archive schema.
+
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::{new_v7, Error, IngestionDecision, QuarantinedItem, Result};
+
+/// PostgreSQL repository for notes and attachments held by ingestion policies.
+#[derive(Clone)]
+pub struct PgQuarantineRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgQuarantineRepository {
+    /// Create a new quarantine repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Record a quarantine decision for a note, or for one of its
+    /// attachments when `attachment_id` is set.
+    pub async fn insert_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        attachment_id: Option<Uuid>,
+        decision: &IngestionDecision,
+    ) -> Result<Uuid> {
+        let id = new_v7();
+        sqlx::query(
+            "INSERT INTO ingestion_quarantine (id, note_id, attachment_id, policy, reason)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(id)
+        .bind(note_id)
+        .bind(attachment_id)
+        .bind(&decision.policy)
+        .bind(&decision.reason)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(id)
+    }
+
+    /// Quarantined items of live notes, newest first, with the total count.
+    pub async fn list_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<QuarantinedItem>, i64)> {
+        let items = sqlx::query_as::<_, QuarantinedItem>(
+            "SELECT q.id, q.note_id, q.attachment_id, q.policy, q.reason, q.created_at_utc
+             FROM ingestion_quarantine q
+             JOIN note n ON n.id = q.note_id
+             WHERE n.deleted_at IS NULL
+             ORDER BY q.created_at_utc DESC, q.id DESC
+             LIMIT $1 OFFSET $2",
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*)
+             FROM ingestion_quarantine q
+             JOIN note n ON n.id = q.note_id
+             WHERE n.deleted_at IS NULL",
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok((items, total))
+    }
+}
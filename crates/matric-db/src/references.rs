@@ -0,0 +1,275 @@
+//! Citation reference repository.
+//!
+//! References are archive-scoped; every method takes a transaction that has
+//! already been pointed at the archive schema.
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use crate::visibility::visible_note_ids;
+use matric_core::{
+    new_v7, Error, ExtractedCitation, Reference, ReferenceMetadata, Result, StrictSecurityFilter,
+};
+
+const REFERENCE_COLUMNS: &str = "r.id, r.kind, r.identifier, r.title, r.authors, r.year, r.venue,
+     r.publisher, r.url, r.doi, r.entry_type, r.bibtex_key, r.resolved_at_utc";
+
+/// PostgreSQL repository for cited works and the notes citing them.
+#[derive(Clone)]
+pub struct PgReferenceRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+fn reference_from_row(row: &sqlx::postgres::PgRow) -> Result<Reference> {
+    let kind: String = row.get("kind");
+    Ok(Reference {
+        id: row.get("id"),
+        kind: kind.parse().map_err(Error::Internal)?,
+        identifier: row.get("identifier"),
+        metadata: ReferenceMetadata {
+            title: row.get("title"),
+            authors: row.get("authors"),
+            year: row.get("year"),
+            venue: row.get("venue"),
+            publisher: row.get("publisher"),
+            url: row.get("url"),
+            doi: row.get("doi"),
+            entry_type: row.get("entry_type"),
+        },
+        bibtex_key: row.get("bibtex_key"),
+        occurrences: row.get("occurrences"),
+        resolved_at_utc: row.get("resolved_at_utc"),
+    })
+}
+
+impl PgReferenceRepository {
+    /// Create a new reference repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Replace the works a note cites with those of a new extraction.
+    ///
+    /// Each citation is stored once per archive by kind and identifier;
+    /// fields already known are kept and missing ones filled in from the
+    /// citation. References no note cites any longer are deleted. Returns
+    /// the number of works the note cites.
+    pub async fn replace_for_note_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        citations: &[ExtractedCitation],
+    ) -> Result<usize> {
+        let previous: Vec<Uuid> = sqlx::query_scalar(
+            "DELETE FROM note_reference WHERE note_id = $1 RETURNING reference_id",
+        )
+        .bind(note_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        for citation in citations {
+            let metadata = &citation.metadata;
+            let reference_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO reference
+                     (id, kind, identifier, title, authors, year, venue, publisher, url, doi,
+                      entry_type, bibtex_key)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                 ON CONFLICT (kind, identifier) DO UPDATE SET
+                     title = COALESCE(reference.title, EXCLUDED.title),
+                     authors = CASE WHEN cardinality(reference.authors) = 0
+                                    THEN EXCLUDED.authors ELSE reference.authors END,
+                     year = COALESCE(reference.year, EXCLUDED.year),
+                     venue = COALESCE(reference.venue, EXCLUDED.venue),
+                     publisher = COALESCE(reference.publisher, EXCLUDED.publisher),
+                     url = COALESCE(reference.url, EXCLUDED.url),
+                     doi = COALESCE(reference.doi, EXCLUDED.doi),
+                     entry_type = COALESCE(reference.entry_type, EXCLUDED.entry_type),
+                     bibtex_key = COALESCE(reference.bibtex_key, EXCLUDED.bibtex_key),
+                     updated_at_utc = NOW()
+                 RETURNING id",
+            )
+            .bind(new_v7())
+            .bind(citation.kind.as_str())
+            .bind(&citation.identifier)
+            .bind(&metadata.title)
+            .bind(&metadata.authors)
+            .bind(metadata.year)
+            .bind(&metadata.venue)
+            .bind(&metadata.publisher)
+            .bind(&metadata.url)
+            .bind(&metadata.doi)
+            .bind(&metadata.entry_type)
+            .bind(&citation.bibtex_key)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+
+            sqlx::query(
+                "INSERT INTO note_reference (note_id, reference_id, occurrences)
+                 VALUES ($1, $2, $3)
+                 ON CONFLICT (note_id, reference_id)
+                 DO UPDATE SET occurrences = note_reference.occurrences + EXCLUDED.occurrences",
+            )
+            .bind(note_id)
+            .bind(reference_id)
+            .bind(citation.occurrences.max(1))
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+
+        if !previous.is_empty() {
+            sqlx::query(
+                "DELETE FROM reference r
+                 WHERE r.id = ANY($1)
+                   AND NOT EXISTS (SELECT 1 FROM note_reference nr WHERE nr.reference_id = r.id)",
+            )
+            .bind(&previous)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+        Ok(citations.len())
+    }
+
+    /// DOIs a note cites whose metadata has not been resolved, at most `limit`.
+    pub async fn unresolved_dois_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<(Uuid, String)>> {
+        sqlx::query_as(
+            "SELECT r.id, r.identifier
+             FROM reference r
+             JOIN note_reference nr ON nr.reference_id = r.id
+             WHERE nr.note_id = $1 AND r.kind = 'doi' AND r.resolved_at_utc IS NULL
+             ORDER BY r.created_at_utc
+             LIMIT $2",
+        )
+        .bind(note_id)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Store resolved metadata for a reference. Resolved fields replace
+    /// stored ones; fields the resolver left empty are kept.
+    pub async fn set_resolved_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        reference_id: Uuid,
+        metadata: &ReferenceMetadata,
+        resolved_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE reference SET
+                 title = COALESCE($2, title),
+                 authors = CASE WHEN cardinality($3::text[]) = 0 THEN authors ELSE $3 END,
+                 year = COALESCE($4, year),
+                 venue = COALESCE($5, venue),
+                 publisher = COALESCE($6, publisher),
+                 url = COALESCE($7, url),
+                 doi = COALESCE($8, doi),
+                 entry_type = COALESCE($9, entry_type),
+                 resolved_at_utc = $10,
+                 updated_at_utc = NOW()
+             WHERE id = $1",
+        )
+        .bind(reference_id)
+        .bind(&metadata.title)
+        .bind(&metadata.authors)
+        .bind(metadata.year)
+        .bind(&metadata.venue)
+        .bind(&metadata.publisher)
+        .bind(&metadata.url)
+        .bind(&metadata.doi)
+        .bind(&metadata.entry_type)
+        .bind(resolved_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// The works a note cites, most cited first.
+    pub async fn list_for_note_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<Vec<Reference>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {REFERENCE_COLUMNS}, nr.occurrences
+             FROM note_reference nr
+             JOIN reference r ON r.id = nr.reference_id
+             WHERE nr.note_id = $1
+             ORDER BY nr.occurrences DESC, r.kind, r.identifier"
+        ))
+        .bind(note_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        rows.iter().map(reference_from_row).collect()
+    }
+
+    /// The works cited by notes in a collection or any collection nested in
+    /// it, counting only notes `security` admits. `occurrences` is the number
+    /// of citing notes. Ordered by kind and identifier so exports are stable.
+    pub async fn list_for_collection_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        collection_id: Uuid,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Vec<Reference>> {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM collection WHERE id = $1)")
+                .bind(collection_id)
+                .fetch_one(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+        if !exists {
+            return Err(Error::NotFound("Collection not found".to_string()));
+        }
+
+        let mut note_ids: Vec<Uuid> = sqlx::query_scalar(
+            "WITH RECURSIVE subtree AS (
+                 SELECT id FROM collection WHERE id = $1
+                 UNION
+                 SELECT c.id FROM collection c INNER JOIN subtree s ON c.parent_id = s.id
+             )
+             SELECT DISTINCT nr.note_id
+             FROM note_reference nr
+             JOIN note n ON n.id = nr.note_id
+             WHERE n.collection_id IN (SELECT id FROM subtree) AND n.deleted_at IS NULL",
+        )
+        .bind(collection_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        if let Some(security) = security {
+            let visible = visible_note_ids(&mut **tx, &note_ids, security).await?;
+            note_ids.retain(|id| visible.contains(id));
+        }
+        if note_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query(&format!(
+            "SELECT {REFERENCE_COLUMNS}, COUNT(*)::int AS occurrences
+             FROM note_reference nr
+             JOIN reference r ON r.id = nr.reference_id
+             WHERE nr.note_id = ANY($1)
+             GROUP BY r.id
+             ORDER BY r.kind, r.identifier"
+        ))
+        .bind(&note_ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        rows.iter().map(reference_from_row).collect()
+    }
+}
@@ -0,0 +1,119 @@
+//! Resurfacing repository.
+//!
+//! Picks forgotten notes for `GET /api/v1/notes/resurface`: a random pool of
+//! eligible notes is read with the signals they are weighted by, then
+//! sampled by weight (see [`matric_core::resurface`]). Every method takes a
+//! transaction that has already been pointed at the archive schema.
+
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+use sqlx::{Pool, Postgres, Transaction};
+
+use matric_core::resurface::{
+    resurface_weight, sample_weighted, ResurfaceQuery, ResurfacedNote, RESURFACE_CANDIDATE_POOL,
+};
+use matric_core::{Error, Result, StrictFilter, StrictSecurityFilter};
+
+use crate::unified_filter::{QueryParam, UnifiedFilterQueryBuilder};
+
+/// PostgreSQL repository for resurfacing notes.
+#[derive(Clone)]
+pub struct PgResurfaceRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgResurfaceRepository {
+    /// Create a new resurfacing repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Up to `limit` eligible notes, drawn uniformly at random, with their
+    /// weighting signals (`weight` left at zero). Archived notes are never
+    /// eligible; `security`, when given, admits only the notes it allows.
+    pub async fn candidates_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        query: &ResurfaceQuery,
+        security: Option<&StrictSecurityFilter>,
+        limit: i64,
+    ) -> Result<Vec<ResurfacedNote>> {
+        let cutoff = Utc::now()
+            .checked_sub_signed(Duration::days(query.min_age_days.into()))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let security = security
+            .filter(|security| !security.is_empty())
+            .map(|security| {
+                let filter = StrictFilter::new().with_security(security.clone());
+                UnifiedFilterQueryBuilder::new(filter, 4).build()
+            });
+        let security_clause = security
+            .as_ref()
+            .map(|result| format!("AND {}", result.where_clause))
+            .unwrap_or_default();
+        let sql = format!(
+            "SELECT n.id AS note_id, n.title,
+                    substring(nrc.content for 200) AS snippet,
+                    n.created_at_utc, n.last_accessed_at,
+                    COALESCE(n.access_count, 0) AS access_count,
+                    (SELECT COUNT(*) FROM link l
+                     WHERE l.from_note_id = n.id OR l.to_note_id = n.id) AS link_count,
+                    (SELECT COUNT(*) FROM note_tag t WHERE t.note_id = n.id) AS tag_count,
+                    COALESCE(char_length(nrc.content), 0)::bigint AS content_chars
+             FROM note n
+             LEFT JOIN note_revised_current nrc ON nrc.note_id = n.id
+             WHERE n.deleted_at IS NULL
+               AND (n.archived IS FALSE OR n.archived IS NULL)
+               AND n.created_at_utc < $1
+               AND (n.last_accessed_at IS NULL OR n.last_accessed_at < $1)
+               AND COALESCE(n.access_count, 0) <= $2
+               AND ($3::uuid IS NULL OR n.collection_id = $3)
+               {security_clause}
+             ORDER BY random()
+             LIMIT $4"
+        );
+        let mut q = sqlx::query_as::<_, ResurfacedNote>(&sql)
+            .bind(cutoff)
+            .bind(query.max_access_count)
+            .bind(query.collection_id)
+            .bind(limit);
+        for param in security.iter().flat_map(|result| &result.params) {
+            q = match param {
+                QueryParam::Uuid(id) => q.bind(id),
+                QueryParam::UuidArray(ids) => q.bind(ids),
+                QueryParam::Int(val) => q.bind(val),
+                QueryParam::Timestamp(ts) => q.bind(ts),
+                QueryParam::Bool(b) => q.bind(b),
+                QueryParam::String(s) => q.bind(s),
+                QueryParam::StringArray(arr) => q.bind(arr),
+            };
+        }
+        q.fetch_all(&mut **tx).await.map_err(Error::Database)
+    }
+
+    /// Up to `query.limit` eligible notes, sampled by
+    /// [`resurface_weight`] from a random pool of
+    /// [`RESURFACE_CANDIDATE_POOL`].
+    pub async fn sample_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        query: &ResurfaceQuery,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Vec<ResurfacedNote>> {
+        let mut candidates = self
+            .candidates_tx(tx, query, security, RESURFACE_CANDIDATE_POOL)
+            .await?;
+        for candidate in &mut candidates {
+            candidate.weight = resurface_weight(candidate);
+        }
+        let limit = usize::try_from(query.limit).unwrap_or(0);
+        let mut rng = rand::thread_rng();
+        Ok(sample_weighted(
+            candidates,
+            |note| note.weight,
+            limit,
+            || rng.gen::<f64>(),
+        ))
+    }
+}
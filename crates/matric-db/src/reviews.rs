@@ -0,0 +1,230 @@
+//! Spaced repetition review card repository.
+//!
+//! Cards are archive-scoped, so every method takes an existing transaction
+//! that has already been pointed at the archive schema. `security`, when
+//! given, admits only the cards of the notes it allows.
+
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgRow, Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    new_v7, CreateReviewCardRequest, Error, Result, ReviewCard, StrictSecurityFilter,
+};
+
+use crate::unified_filter::{
+    bind_filter_param, security_clause, security_filter_query, QueryParam,
+};
+
+const REVIEW_CARD_COLUMNS: &str = "id, note_id, front, back, ease_factor, interval_days, \
+     repetitions, lapses, due_at_utc, last_reviewed_at_utc, created_at_utc";
+
+/// [`REVIEW_CARD_COLUMNS`] qualified for queries joining `review_card r`.
+const REVIEW_CARD_COLUMNS_R: &str = "r.id, r.note_id, r.front, r.back, r.ease_factor, \
+     r.interval_days, r.repetitions, r.lapses, r.due_at_utc, r.last_reviewed_at_utc, \
+     r.created_at_utc";
+
+/// PostgreSQL repository for spaced repetition review cards.
+pub struct PgReviewRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgReviewRepository {
+    /// Create a new review repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Create a card for a note. New cards are due immediately.
+    ///
+    /// Returns `Error::NotFound` when the note does not exist or is deleted.
+    pub async fn create_card_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        req: CreateReviewCardRequest,
+    ) -> Result<Uuid> {
+        let id = new_v7();
+        let now = Utc::now();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO review_card (id, note_id, front, back, due_at_utc, created_at_utc)
+            SELECT $1, n.id, $3, $4, $5, $5
+            FROM note n
+            WHERE n.id = $2 AND n.deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(req.note_id)
+        .bind(&req.front)
+        .bind(&req.back)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound(
+                "Note not found; note_id_present=true".to_string(),
+            ));
+        }
+        Ok(id)
+    }
+
+    /// Get a card by ID.
+    pub async fn get_card_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Option<ReviewCard>> {
+        let row = sqlx::query(&format!(
+            "SELECT {REVIEW_CARD_COLUMNS} FROM review_card WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.map(|r| card_from_row(&r)))
+    }
+
+    /// List cards due before `due_before`, oldest due first. Cards of
+    /// deleted notes are skipped.
+    pub async fn list_due_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        due_before: DateTime<Utc>,
+        security: Option<&StrictSecurityFilter>,
+        limit: i64,
+    ) -> Result<Vec<ReviewCard>> {
+        let security = security_filter_query(security, 2);
+        let sql = format!(
+            "SELECT {REVIEW_CARD_COLUMNS_R} FROM review_card r \
+             JOIN note n ON n.id = r.note_id AND n.deleted_at IS NULL \
+             WHERE r.due_at_utc < $1 {} ORDER BY r.due_at_utc, r.id LIMIT $2",
+            security_clause(&security)
+        );
+        let mut query = sqlx::query(&sql).bind(due_before).bind(limit);
+        for param in security.iter().flat_map(|result| &result.params) {
+            query = bind_filter_param!(query, param);
+        }
+        let rows = query.fetch_all(&mut **tx).await.map_err(Error::Database)?;
+
+        Ok(rows.iter().map(card_from_row).collect())
+    }
+
+    /// Count cards due before `due_before`, skipping cards of deleted notes.
+    pub async fn count_due_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        due_before: DateTime<Utc>,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<i64> {
+        let security = security_filter_query(security, 1);
+        let sql = format!(
+            "SELECT COUNT(*) FROM review_card r \
+             JOIN note n ON n.id = r.note_id AND n.deleted_at IS NULL \
+             WHERE r.due_at_utc < $1 {}",
+            security_clause(&security)
+        );
+        let mut query = sqlx::query_scalar(&sql).bind(due_before);
+        for param in security.iter().flat_map(|result| &result.params) {
+            query = bind_filter_param!(query, param);
+        }
+        query.fetch_one(&mut **tx).await.map_err(Error::Database)
+    }
+
+    /// Record a graded review and advance the card's SM-2 schedule.
+    ///
+    /// Returns the updated card, or `None` when the card does not exist.
+    pub async fn record_review_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        grade: i16,
+        reviewed_at: DateTime<Utc>,
+    ) -> Result<Option<ReviewCard>> {
+        let row = sqlx::query(&format!(
+            "SELECT {REVIEW_CARD_COLUMNS} FROM review_card WHERE id = $1 FOR UPDATE"
+        ))
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        let Some(card) = row.map(|r| card_from_row(&r)) else {
+            return Ok(None);
+        };
+
+        let schedule = card.schedule().apply_grade(grade)?;
+        let due_at = schedule.next_due(reviewed_at);
+
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE review_card
+            SET ease_factor = $2, interval_days = $3, repetitions = $4, lapses = $5,
+                due_at_utc = $6, last_reviewed_at_utc = $7
+            WHERE id = $1
+            RETURNING {REVIEW_CARD_COLUMNS}
+            "#
+        ))
+        .bind(id)
+        .bind(schedule.ease_factor)
+        .bind(schedule.interval_days)
+        .bind(schedule.repetitions)
+        .bind(schedule.lapses)
+        .bind(due_at)
+        .bind(reviewed_at)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO review_log (id, card_id, grade, interval_days, ease_factor, reviewed_at_utc)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(new_v7())
+        .bind(id)
+        .bind(grade)
+        .bind(schedule.interval_days)
+        .bind(schedule.ease_factor)
+        .bind(reviewed_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(Some(card_from_row(&row)))
+    }
+
+    /// Delete a card. Returns `false` when no card matched.
+    pub async fn delete_card_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM review_card WHERE id = $1")
+            .bind(id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn card_from_row(r: &PgRow) -> ReviewCard {
+    ReviewCard {
+        id: r.get("id"),
+        note_id: r.get("note_id"),
+        front: r.get("front"),
+        back: r.get("back"),
+        ease_factor: r.get("ease_factor"),
+        interval_days: r.get("interval_days"),
+        repetitions: r.get("repetitions"),
+        lapses: r.get("lapses"),
+        due_at_utc: r.get("due_at_utc"),
+        last_reviewed_at_utc: r.get("last_reviewed_at_utc"),
+        created_at_utc: r.get("created_at_utc"),
+    }
+}
@@ -5,14 +5,32 @@
 //! - `matric_english` for English content (default)
 //! - `matric_simple` for CJK and other scripts (no stemming)
 
+use std::collections::HashMap;
+
+use pgvector::Vector;
 use sqlx::{Pool, Postgres, Row, Transaction};
+use tracing::instrument;
 use uuid::Uuid;
 
-use matric_core::{Error, Result, SearchHit, StrictTagFilter};
+use matric_core::snippet::parse_headline;
+use matric_core::{
+    Error, Result, SearchHit, SearchSnippet, SnippetOptions, SnippetSource, StrictTagFilter,
+};
 
 use crate::escape_like;
 use crate::strict_filter::{QueryParam, StrictFilterQueryBuilder};
 
+/// Condition that keeps notes whose PII findings block indexing, and notes
+/// quarantined by an ingestion policy, out of full-text results.
+macro_rules! searchable {
+    () => {
+        concat!(
+            " AND NOT EXISTS (SELECT 1 FROM pii_findings pf WHERE pf.note_id = n.id AND pf.blocks_indexing)",
+            " AND NOT EXISTS (SELECT 1 FROM ingestion_quarantine iq WHERE iq.note_id = n.id AND iq.attachment_id IS NULL)"
+        )
+    };
+}
+
 /// Full-text search provider using PostgreSQL tsvector.
 pub struct PgFtsSearch {
     pool: Pool<Postgres>,
@@ -29,6 +47,9 @@ impl PgFtsSearch {
     /// Combines weighted tsvectors from title (weight A), tags (weight B),
     /// and content (weight C) to produce field-weighted ranking.
     /// This implements BM25F-style scoring where title matches rank highest.
+    /// A note's machine translation, if any, is matched and weighted like
+    /// its content.
+    #[instrument(skip_all, fields(subsystem = "database", component = "search", op = "search", limit = limit))]
     pub async fn search(
         &self,
         query: &str,
@@ -36,9 +57,12 @@ impl PgFtsSearch {
         exclude_archived: bool,
     ) -> Result<Vec<SearchHit>> {
         let archive_clause = if exclude_archived {
-            "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL"
+            concat!(
+                "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL",
+                searchable!()
+            )
         } else {
-            "AND n.deleted_at IS NULL"
+            concat!("AND n.deleted_at IS NULL", searchable!())
         };
 
         // BM25F field-weighted scoring: title (A=1.0) > tags (B=0.4) > content (C=0.2)
@@ -53,7 +77,8 @@ impl PgFtsSearch {
                            SELECT to_tsvector('public.matric_english', string_agg(tag_name, ' '))
                            FROM note_tag WHERE note_id = n.id
                        ), ''::tsvector), 'B') ||
-                       setweight(nrc.tsv, 'C'),
+                       setweight(nrc.tsv, 'C') ||
+                       setweight(COALESCE(to_tsvector('public.matric_english', nt.content), ''::tsvector), 'C'),
                        websearch_to_tsquery('public.matric_english', $1),
                        32
                    ) AS score,
@@ -65,8 +90,10 @@ impl PgFtsSearch {
                    ) as tags
             FROM note_revised_current nrc
             JOIN note n ON n.id = nrc.note_id
+            LEFT JOIN note_translation nt ON nt.note_id = n.id
             WHERE (nrc.tsv @@ websearch_to_tsquery('public.matric_english', $1)
-                   OR to_tsvector('public.matric_english', COALESCE(n.title, '')) @@ websearch_to_tsquery('public.matric_english', $1))
+                   OR to_tsvector('public.matric_english', COALESCE(n.title, '')) @@ websearch_to_tsquery('public.matric_english', $1)
+                   OR to_tsvector('public.matric_english', nt.content) @@ websearch_to_tsquery('public.matric_english', $1))
               {}
             ORDER BY score DESC
             LIMIT $2
@@ -108,6 +135,7 @@ impl PgFtsSearch {
     ///
     /// Uses a CTE approach to filter notes by SKOS concepts before applying FTS,
     /// ensuring precise taxonomy-based result segregation.
+    #[instrument(skip_all, fields(subsystem = "database", component = "search", op = "search_with_strict_filter", limit = limit))]
     pub async fn search_with_strict_filter(
         &self,
         query: &str,
@@ -132,9 +160,12 @@ impl PgFtsSearch {
         }
 
         let archive_clause = if exclude_archived {
-            "(n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL"
+            concat!(
+                "(n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL",
+                searchable!()
+            )
         } else {
-            "n.deleted_at IS NULL"
+            concat!("n.deleted_at IS NULL", searchable!())
         };
 
         // Build strict filter SQL using the query builder
@@ -157,7 +188,8 @@ impl PgFtsSearch {
                            SELECT to_tsvector('public.matric_english', string_agg(tag_name, ' '))
                            FROM note_tag WHERE note_id = n.id
                        ), ''::tsvector), 'B') ||
-                       setweight(nrc.tsv, 'C'),
+                       setweight(nrc.tsv, 'C') ||
+                       setweight(COALESCE(to_tsvector('public.matric_english', nt.content), ''::tsvector), 'C'),
                        websearch_to_tsquery('public.matric_english', $1),
                        32
                    ) AS score,
@@ -170,8 +202,10 @@ impl PgFtsSearch {
             FROM filtered_notes fn
             JOIN note n ON n.id = fn.id
             JOIN note_revised_current nrc ON nrc.note_id = n.id
+            LEFT JOIN note_translation nt ON nt.note_id = n.id
             WHERE (nrc.tsv @@ websearch_to_tsquery('public.matric_english', $1)
-                   OR to_tsvector('public.matric_english', COALESCE(n.title, '')) @@ websearch_to_tsquery('public.matric_english', $1))
+                   OR to_tsvector('public.matric_english', COALESCE(n.title, '')) @@ websearch_to_tsquery('public.matric_english', $1)
+                   OR to_tsvector('public.matric_english', nt.content) @@ websearch_to_tsquery('public.matric_english', $1))
             ORDER BY score DESC
             LIMIT ${}
             "#,
@@ -229,6 +263,7 @@ impl PgFtsSearch {
     /// Supports filter syntax:
     /// - `tag:tagname` - filter by tag
     /// - `collection:uuid` - filter by collection
+    #[instrument(skip_all, fields(subsystem = "database", component = "search", op = "search_filtered", limit = limit))]
     pub async fn search_filtered(
         &self,
         query: &str,
@@ -237,9 +272,12 @@ impl PgFtsSearch {
         exclude_archived: bool,
     ) -> Result<Vec<SearchHit>> {
         let archive_clause = if exclude_archived {
-            "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL"
+            concat!(
+                "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL",
+                searchable!()
+            )
         } else {
-            "AND n.deleted_at IS NULL"
+            concat!("AND n.deleted_at IS NULL", searchable!())
         };
 
         // BM25F field-weighted scoring for filtered search
@@ -252,7 +290,8 @@ impl PgFtsSearch {
                            SELECT to_tsvector('public.matric_english', string_agg(tag_name, ' '))
                            FROM note_tag WHERE note_id = n.id
                        ), ''::tsvector), 'B') ||
-                       setweight(nrc.tsv, 'C'),
+                       setweight(nrc.tsv, 'C') ||
+                       setweight(COALESCE(to_tsvector('public.matric_english', nt.content), ''::tsvector), 'C'),
                        websearch_to_tsquery('public.matric_english', $1),
                        32
                    ) AS score,
@@ -264,8 +303,10 @@ impl PgFtsSearch {
                    ) as tags
             FROM note_revised_current nrc
             JOIN note n ON n.id = nrc.note_id
+            LEFT JOIN note_translation nt ON nt.note_id = n.id
             WHERE (nrc.tsv @@ websearch_to_tsquery('public.matric_english', $1)
-                   OR to_tsvector('public.matric_english', COALESCE(n.title, '')) @@ websearch_to_tsquery('public.matric_english', $1))
+                   OR to_tsvector('public.matric_english', COALESCE(n.title, '')) @@ websearch_to_tsquery('public.matric_english', $1)
+                   OR to_tsvector('public.matric_english', nt.content) @@ websearch_to_tsquery('public.matric_english', $1))
               {}
             "#,
             archive_clause
@@ -366,6 +407,8 @@ impl PgFtsSearch {
             WHERE nrc.tsv @@ websearch_to_tsquery('public.matric_english', $1)
               AND (n.archived IS FALSE OR n.archived IS NULL)
               AND n.deleted_at IS NULL
+              AND NOT EXISTS (SELECT 1 FROM pii_findings pf WHERE pf.note_id = n.id AND pf.blocks_indexing)
+              AND NOT EXISTS (SELECT 1 FROM ingestion_quarantine iq WHERE iq.note_id = n.id AND iq.attachment_id IS NULL)
             LIMIT $2
             "#,
         )
@@ -390,6 +433,7 @@ impl PgFtsSearch {
     /// - Fuzzy similarity matching
     ///
     /// This is a fallback when FTS fails (e.g., for CJK, emoji, symbols).
+    #[instrument(skip_all, fields(subsystem = "database", component = "search", op = "search_trigram", limit = limit))]
     pub async fn search_trigram(
         &self,
         query: &str,
@@ -397,9 +441,12 @@ impl PgFtsSearch {
         exclude_archived: bool,
     ) -> Result<Vec<SearchHit>> {
         let archive_clause = if exclude_archived {
-            "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL"
+            concat!(
+                "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL",
+                searchable!()
+            )
         } else {
-            "AND n.deleted_at IS NULL"
+            concat!("AND n.deleted_at IS NULL", searchable!())
         };
 
         // Trigram search using similarity() function and ILIKE for exact matches
@@ -475,6 +522,7 @@ impl PgFtsSearch {
     /// - Does NOT stem words (preserves exact tokens)
     ///
     /// Suitable for CJK content where word boundaries are explicit.
+    #[instrument(skip_all, fields(subsystem = "database", component = "search", op = "search_simple", limit = limit))]
     pub async fn search_simple(
         &self,
         query: &str,
@@ -482,9 +530,12 @@ impl PgFtsSearch {
         exclude_archived: bool,
     ) -> Result<Vec<SearchHit>> {
         let archive_clause = if exclude_archived {
-            "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL"
+            concat!(
+                "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL",
+                searchable!()
+            )
         } else {
-            "AND n.deleted_at IS NULL"
+            concat!("AND n.deleted_at IS NULL", searchable!())
         };
 
         let sql = format!(
@@ -492,7 +543,8 @@ impl PgFtsSearch {
             SELECT n.id as note_id,
                    ts_rank(
                        to_tsvector('public.matric_simple', COALESCE(n.title, '')) ||
-                       to_tsvector('public.matric_simple', nrc.content),
+                       to_tsvector('public.matric_simple', nrc.content) ||
+                       COALESCE(to_tsvector('public.matric_simple', nt.content), ''::tsvector),
                        websearch_to_tsquery('public.matric_simple', $1)
                    ) AS score,
                    substring(nrc.content for 200) AS snippet,
@@ -503,9 +555,11 @@ impl PgFtsSearch {
                    ) as tags
             FROM note_revised_current nrc
             JOIN note n ON n.id = nrc.note_id
+            LEFT JOIN note_translation nt ON nt.note_id = n.id
             WHERE (
                 to_tsvector('public.matric_simple', nrc.content) @@ websearch_to_tsquery('public.matric_simple', $1)
                 OR to_tsvector('public.matric_simple', COALESCE(n.title, '')) @@ websearch_to_tsquery('public.matric_simple', $1)
+                OR to_tsvector('public.matric_simple', nt.content) @@ websearch_to_tsquery('public.matric_simple', $1)
             )
             {}
             ORDER BY score DESC
@@ -544,6 +598,101 @@ impl PgFtsSearch {
         Ok(results)
     }
 
+    /// Search with a language-specific stemming configuration.
+    ///
+    /// Notes whose detected `language` matches are matched with `fts_config`
+    /// (e.g. `public.matric_german`), so inflected forms are found; all other
+    /// notes fall back to `matric_simple` matching. `fts_config` must be one of
+    /// the configurations returned by
+    /// [`matric_core::language::fts_config_for_language`].
+    #[instrument(skip_all, fields(subsystem = "database", component = "search", op = "search_language", limit = limit))]
+    pub async fn search_language(
+        &self,
+        query: &str,
+        fts_config: &str,
+        language: &str,
+        limit: i64,
+        exclude_archived: bool,
+    ) -> Result<Vec<SearchHit>> {
+        let archive_clause = if exclude_archived {
+            concat!(
+                "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL",
+                searchable!()
+            )
+        } else {
+            concat!("AND n.deleted_at IS NULL", searchable!())
+        };
+
+        let sql = format!(
+            r#"
+            SELECT n.id as note_id,
+                   GREATEST(
+                       CASE WHEN n.language = $4 THEN ts_rank(
+                           to_tsvector($3::regconfig, COALESCE(n.title, '')) ||
+                           to_tsvector($3::regconfig, nrc.content),
+                           websearch_to_tsquery($3::regconfig, $1)
+                       ) ELSE 0 END,
+                       ts_rank(
+                           to_tsvector('public.matric_simple', COALESCE(n.title, '')) ||
+                           to_tsvector('public.matric_simple', nrc.content),
+                           websearch_to_tsquery('public.matric_simple', $1)
+                       )
+                   ) AS score,
+                   substring(nrc.content for 200) AS snippet,
+                   n.title,
+                   COALESCE(
+                       (SELECT string_agg(tag_name, ',') FROM note_tag WHERE note_id = n.id),
+                       ''
+                   ) as tags
+            FROM note_revised_current nrc
+            JOIN note n ON n.id = nrc.note_id
+            WHERE (
+                (n.language = $4 AND (
+                    to_tsvector($3::regconfig, nrc.content) @@ websearch_to_tsquery($3::regconfig, $1)
+                    OR to_tsvector($3::regconfig, COALESCE(n.title, '')) @@ websearch_to_tsquery($3::regconfig, $1)
+                ))
+                OR to_tsvector('public.matric_simple', nrc.content) @@ websearch_to_tsquery('public.matric_simple', $1)
+                OR to_tsvector('public.matric_simple', COALESCE(n.title, '')) @@ websearch_to_tsquery('public.matric_simple', $1)
+            )
+            {}
+            ORDER BY score DESC
+            LIMIT $2
+            "#,
+            archive_clause
+        );
+
+        let rows = sqlx::query(&sql)
+            .bind(query)
+            .bind(limit)
+            .bind(fts_config)
+            .bind(language)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        let results = rows
+            .into_iter()
+            .map(|row| {
+                let tags_str: String = row.get("tags");
+                let tags = if tags_str.is_empty() {
+                    Vec::new()
+                } else {
+                    tags_str.split(',').map(String::from).collect()
+                };
+                SearchHit {
+                    note_id: row.get("note_id"),
+                    score: row.get::<Option<f32>, _>("score").unwrap_or(0.0),
+                    snippet: row.get("snippet"),
+                    title: row.get("title"),
+                    tags,
+                    embedding_status: None,
+                }
+            })
+            .collect();
+
+        Ok(results)
+    }
+
     /// Check if pg_trgm extension is available.
     pub async fn has_trigram_extension(&self) -> Result<bool> {
         let row = sqlx::query(
@@ -576,6 +725,7 @@ impl PgFtsSearch {
     /// - CJK compound word searches
     ///
     /// Falls back to trigram search if pg_bigm is not available.
+    #[instrument(skip_all, fields(subsystem = "database", component = "search", op = "search_bigram", limit = limit))]
     pub async fn search_bigram(
         &self,
         query: &str,
@@ -589,9 +739,12 @@ impl PgFtsSearch {
         }
 
         let archive_clause = if exclude_archived {
-            "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL"
+            concat!(
+                "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL",
+                searchable!()
+            )
         } else {
-            "AND n.deleted_at IS NULL"
+            concat!("AND n.deleted_at IS NULL", searchable!())
         };
 
         // pg_bigm search using likequery() and bigm_similarity()
@@ -659,6 +812,7 @@ impl PgFtsSearch {
     /// Automatically selects:
     /// - pg_bigm if available (optimal for CJK)
     /// - pg_trgm as fallback
+    #[instrument(skip_all, fields(subsystem = "database", component = "search", op = "search_cjk", limit = limit))]
     pub async fn search_cjk(
         &self,
         query: &str,
@@ -671,6 +825,117 @@ impl PgFtsSearch {
             self.search_trigram(query, limit, exclude_archived).await
         }
     }
+
+    /// The note's most frequent lexemes in its current revision, most
+    /// frequent first. Numbers and lexemes under three characters are
+    /// skipped.
+    pub async fn top_terms(&self, note_id: Uuid, limit: i64) -> Result<Vec<String>> {
+        sqlx::query_scalar(
+            "SELECT t.lexeme
+             FROM note_revised_current nrc, unnest(nrc.tsv) t
+             WHERE nrc.note_id = $1
+               AND length(t.lexeme) > 2
+               AND t.lexeme !~ '^[0-9.,]+$'
+             ORDER BY COALESCE(cardinality(t.positions), 0) DESC, t.lexeme
+             LIMIT $2",
+        )
+        .bind(note_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Highlighted excerpts of each note for `query`.
+    ///
+    /// Notes whose content matches the full-text query are excerpted from
+    /// their content. When `query_vec` is given, the others are excerpted
+    /// from their chunk nearest to it in the searched set (`embedding_set_id`,
+    /// or the default set). Notes with neither are left out.
+    pub async fn snippets_for_notes(
+        &self,
+        note_ids: &[Uuid],
+        query: &str,
+        query_vec: Option<&Vector>,
+        embedding_set_id: Option<Uuid>,
+        options: &SnippetOptions,
+    ) -> Result<HashMap<Uuid, SearchSnippet>> {
+        if note_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let headline_options = options.headline_options();
+        let rows = sqlx::query(
+            "SELECT nrc.note_id,
+                    ts_headline('public.matric_english', nrc.content, q, $3) AS headline
+             FROM note_revised_current nrc,
+                  websearch_to_tsquery('public.matric_english', $2) q
+             WHERE nrc.note_id = ANY($1) AND nrc.tsv @@ q",
+        )
+        .bind(note_ids)
+        .bind(query)
+        .bind(&headline_options)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        let mut snippets: HashMap<Uuid, SearchSnippet> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get("note_id"),
+                    SearchSnippet {
+                        source: SnippetSource::Content,
+                        chunk_index: None,
+                        fragments: parse_headline(row.get("headline")),
+                    },
+                )
+            })
+            .collect();
+
+        let Some(query_vec) = query_vec else {
+            return Ok(snippets);
+        };
+        let remaining: Vec<Uuid> = note_ids
+            .iter()
+            .filter(|id| !snippets.contains_key(id))
+            .copied()
+            .collect();
+        if remaining.is_empty() {
+            return Ok(snippets);
+        }
+        let rows = sqlx::query(
+            "SELECT best.note_id, best.chunk_index,
+                    ts_headline('public.matric_english', best.text,
+                                websearch_to_tsquery('public.matric_english', $3), $5) AS headline
+             FROM (
+                 SELECT DISTINCT ON (e.note_id) e.note_id, e.chunk_index, e.text
+                 FROM embedding e
+                 WHERE e.note_id = ANY($1)
+                   AND e.embedding_set_id = COALESCE($2, get_default_embedding_set_id())
+                 ORDER BY e.note_id, e.vector <=> $4::vector, e.chunk_index
+             ) best",
+        )
+        .bind(&remaining)
+        .bind(embedding_set_id)
+        .bind(query)
+        .bind(query_vec)
+        .bind(&headline_options)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        for row in &rows {
+            snippets.insert(
+                row.get("note_id"),
+                SearchSnippet {
+                    source: SnippetSource::Chunk,
+                    chunk_index: Some(row.get("chunk_index")),
+                    fragments: parse_headline(row.get("headline")),
+                },
+            );
+        }
+        Ok(snippets)
+    }
 }
 
 /// Transaction-aware variants for archive-scoped operations (Issue #108).
@@ -686,9 +951,12 @@ impl PgFtsSearch {
         exclude_archived: bool,
     ) -> Result<Vec<SearchHit>> {
         let archive_clause = if exclude_archived {
-            "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL"
+            concat!(
+                "AND (n.archived IS FALSE OR n.archived IS NULL) AND n.deleted_at IS NULL",
+                searchable!()
+            )
         } else {
-            "AND n.deleted_at IS NULL"
+            concat!("AND n.deleted_at IS NULL", searchable!())
         };
 
         let sql = format!(
@@ -700,7 +968,8 @@ impl PgFtsSearch {
                            SELECT to_tsvector('public.matric_english', string_agg(tag_name, ' '))
                            FROM note_tag WHERE note_id = n.id
                        ), ''::tsvector), 'B') ||
-                       setweight(nrc.tsv, 'C'),
+                       setweight(nrc.tsv, 'C') ||
+                       setweight(COALESCE(to_tsvector('public.matric_english', nt.content), ''::tsvector), 'C'),
                        websearch_to_tsquery('public.matric_english', $1),
                        32
                    ) AS score,
@@ -712,8 +981,10 @@ impl PgFtsSearch {
                    ) as tags
             FROM note_revised_current nrc
             JOIN note n ON n.id = nrc.note_id
+            LEFT JOIN note_translation nt ON nt.note_id = n.id
             WHERE (nrc.tsv @@ websearch_to_tsquery('public.matric_english', $1)
-                   OR to_tsvector('public.matric_english', COALESCE(n.title, '')) @@ websearch_to_tsquery('public.matric_english', $1))
+                   OR to_tsvector('public.matric_english', COALESCE(n.title, '')) @@ websearch_to_tsquery('public.matric_english', $1)
+                   OR to_tsvector('public.matric_english', nt.content) @@ websearch_to_tsquery('public.matric_english', $1))
               {}
             ORDER BY score DESC
             LIMIT $2
@@ -0,0 +1,708 @@
+//! Transaction-aware SKOS concept merge and split.
+//!
+//! Both operations run inside a transaction that has already been pointed at
+//! the archive schema, and record their changes in `skos_audit_log`.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde_json::json;
+use sqlx::{Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    new_v7, ConceptMergeResult, ConceptSplitOutcome, ConceptSplitResult, ConceptSplitRule,
+    CreateConceptRequest, Error, MergeConceptsRequest, Result, SkosAuditLogEntry,
+    SplitConceptRequest, TagStatus,
+};
+
+use crate::skos_tags::{skos_audit_actor_metadata, skos_audit_changes_metadata, PgSkosRepository};
+
+/// Actor recorded in the audit log when a request names none.
+const DEFAULT_GOVERNANCE_ACTOR: &str = "api";
+
+fn concept_not_found_error(role: &str) -> Error {
+    Error::NotFound(format!("{role} concept not found"))
+}
+
+fn concept_obsolete_error(role: &str) -> Error {
+    Error::InvalidInput(format!("{role} concept is obsolete; it was already merged"))
+}
+
+/// Run `sql` for one edge under a savepoint, returning whether it applied.
+///
+/// A unique, check or trigger violation rolls back just this edge, so one
+/// relation that cannot move does not abort the whole merge.
+async fn try_edge_update(
+    tx: &mut Transaction<'_, Postgres>,
+    sql: &str,
+    edge_id: Uuid,
+    source_ids: &[Uuid],
+    target_id: Uuid,
+) -> Result<bool> {
+    sqlx::query("SAVEPOINT skos_concept_merge_edge")
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+    let applied = sqlx::query(sql)
+        .bind(edge_id)
+        .bind(source_ids)
+        .bind(target_id)
+        .execute(&mut **tx)
+        .await;
+    let release = if applied.is_ok() {
+        "RELEASE SAVEPOINT skos_concept_merge_edge"
+    } else {
+        "ROLLBACK TO SAVEPOINT skos_concept_merge_edge"
+    };
+    sqlx::query(release)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+    Ok(applied.is_ok_and(|done| done.rows_affected() > 0))
+}
+
+impl PgSkosRepository {
+    /// Record a governance action in the audit log within a transaction.
+    pub async fn log_audit_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        entity_id: Uuid,
+        action: &str,
+        changes: serde_json::Value,
+        actor: Option<&str>,
+    ) -> Result<Uuid> {
+        let id = new_v7();
+        sqlx::query(
+            r#"
+            INSERT INTO skos_audit_log (id, entity_type, entity_id, action, changes, actor, actor_type, created_at)
+            VALUES ($1, 'concept', $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(id)
+        .bind(entity_id)
+        .bind(action)
+        .bind(skos_audit_changes_metadata(Some(changes)))
+        .bind(skos_audit_actor_metadata(
+            actor.unwrap_or(DEFAULT_GOVERNANCE_ACTOR),
+        ))
+        .bind(if actor.is_some() { "user" } else { "system" })
+        .bind(Utc::now())
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(id)
+    }
+
+    /// Audit log entries for a concept within a transaction, newest first.
+    pub async fn get_concept_audit_log_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        concept_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<SkosAuditLogEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, entity_type, entity_id, action, changes, actor, actor_type, created_at
+            FROM skos_audit_log
+            WHERE entity_type = 'concept' AND entity_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(concept_id)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| SkosAuditLogEntry {
+                id: r.get("id"),
+                entity_type: r.get("entity_type"),
+                entity_id: r.get("entity_id"),
+                action: r.get("action"),
+                changes: skos_audit_changes_metadata(r.get("changes")),
+                actor: skos_audit_actor_metadata(&r.get::<String, _>("actor")),
+                actor_type: r.get("actor_type"),
+                created_at: r.get("created_at"),
+            })
+            .collect())
+    }
+
+    /// Lock the given concepts and return their status.
+    async fn lock_concepts_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, String>> {
+        let rows = sqlx::query(
+            "SELECT id, status::text AS status FROM skos_concept WHERE id = ANY($1) FOR UPDATE",
+        )
+        .bind(ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.get("id"), r.get("status")))
+            .collect())
+    }
+
+    /// Merge source concepts into a target within a transaction.
+    ///
+    /// Notes tagged with a source are retagged with the target. Source labels
+    /// are added to the target as alternative labels (hidden labels stay
+    /// hidden) unless the target already has the same text in that language.
+    /// Semantic relations, mappings and collection memberships move to the
+    /// target; those the target already has, or that would relate the target
+    /// to itself or break the hierarchy limits, are dropped. Each source is
+    /// kept as an obsolete alias pointing at the target, and aliases of the
+    /// sources are re-pointed at the target too.
+    pub async fn merge_concepts_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        req: &MergeConceptsRequest,
+    ) -> Result<ConceptMergeResult> {
+        req.validate()?;
+        let now = Utc::now();
+        let sources = req.source_ids.as_slice();
+        let target = req.target_id;
+
+        let mut all_ids = req.source_ids.clone();
+        all_ids.push(target);
+        let statuses = self.lock_concepts_tx(tx, &all_ids).await?;
+        match statuses.get(&target).map(String::as_str) {
+            None => return Err(concept_not_found_error("Target")),
+            Some("obsolete") => return Err(concept_obsolete_error("Target")),
+            Some(_) => {}
+        }
+        for source in sources {
+            match statuses.get(source).map(String::as_str) {
+                None => return Err(concept_not_found_error("Source")),
+                Some("obsolete") => return Err(concept_obsolete_error("Source")),
+                Some(_) => {}
+            }
+        }
+
+        // Notes: insert then delete so the note_count trigger sees both sides.
+        sqlx::query(
+            r#"
+            INSERT INTO note_skos_concept (
+                note_id, concept_id, source, confidence, relevance_score,
+                is_primary, created_at, created_by
+            )
+            SELECT DISTINCT ON (note_id) note_id, $2, source, confidence, relevance_score,
+                   is_primary, created_at, created_by
+            FROM note_skos_concept
+            WHERE concept_id = ANY($1)
+            ORDER BY note_id, is_primary DESC, created_at
+            ON CONFLICT (note_id, concept_id) DO NOTHING
+            "#,
+        )
+        .bind(sources)
+        .bind(target)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        let notes_retagged: i64 = sqlx::query_scalar(
+            r#"
+            WITH removed AS (
+                DELETE FROM note_skos_concept WHERE concept_id = ANY($1) RETURNING note_id
+            )
+            SELECT COUNT(DISTINCT note_id) FROM removed
+            "#,
+        )
+        .bind(sources)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        // Labels: the sources keep theirs so they still resolve as aliases.
+        let labels_added = sqlx::query(
+            r#"
+            INSERT INTO skos_concept_label (concept_id, label_type, value, language, created_at)
+            SELECT DISTINCT ON (LOWER(l.value), l.language)
+                   $2,
+                   CASE WHEN l.label_type = 'hidden_label' THEN 'hidden_label'
+                        ELSE 'alt_label' END::skos_label_type,
+                   l.value, l.language, $3
+            FROM skos_concept_label l
+            WHERE l.concept_id = ANY($1)
+              AND NOT EXISTS (
+                  SELECT 1 FROM skos_concept_label t
+                  WHERE t.concept_id = $2
+                    AND t.language = l.language
+                    AND LOWER(t.value) = LOWER(l.value)
+              )
+            ORDER BY LOWER(l.value), l.language, l.label_type
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(sources)
+        .bind(target)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .rows_affected() as i64;
+
+        // Semantic relations, one edge at a time so a rejected edge is dropped.
+        let edge_ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM skos_semantic_relation_edge
+            WHERE subject_id = ANY($1) OR object_id = ANY($1)
+            ORDER BY is_inferred, created_at, id
+            "#,
+        )
+        .bind(sources)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        let mut relations_moved = 0;
+        for edge_id in &edge_ids {
+            let moved = try_edge_update(
+                tx,
+                r#"
+                UPDATE skos_semantic_relation_edge
+                SET subject_id = CASE WHEN subject_id = ANY($2) THEN $3 ELSE subject_id END,
+                    object_id = CASE WHEN object_id = ANY($2) THEN $3 ELSE object_id END
+                WHERE id = $1
+                "#,
+                *edge_id,
+                sources,
+                target,
+            )
+            .await?;
+            relations_moved += i64::from(moved);
+        }
+
+        let mapping_ids: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM skos_mapping_relation_edge WHERE concept_id = ANY($1) ORDER BY id",
+        )
+        .bind(sources)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        for mapping_id in &mapping_ids {
+            let moved = try_edge_update(
+                tx,
+                r#"
+                UPDATE skos_mapping_relation_edge SET concept_id = $3
+                WHERE id = $1 AND concept_id = ANY($2)
+                "#,
+                *mapping_id,
+                sources,
+                target,
+            )
+            .await?;
+            relations_moved += i64::from(moved);
+        }
+
+        let memberships_moved = sqlx::query(
+            r#"
+            INSERT INTO skos_collection_member (collection_id, concept_id, position, added_at)
+            SELECT DISTINCT ON (collection_id) collection_id, $2, position, $3
+            FROM skos_collection_member
+            WHERE concept_id = ANY($1)
+            ORDER BY collection_id, position NULLS LAST
+            ON CONFLICT (collection_id, concept_id) DO NOTHING
+            "#,
+        )
+        .bind(sources)
+        .bind(target)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .rows_affected() as i64;
+        relations_moved += memberships_moved;
+
+        // Whatever is still attached to a source could not move.
+        let mut relations_dropped = 0;
+        for sql in [
+            "DELETE FROM skos_semantic_relation_edge WHERE subject_id = ANY($1) OR object_id = ANY($1)",
+            "DELETE FROM skos_mapping_relation_edge WHERE concept_id = ANY($1)",
+            "DELETE FROM skos_collection_member WHERE concept_id = ANY($1)",
+        ] {
+            relations_dropped += sqlx::query(sql)
+                .bind(sources)
+                .execute(&mut **tx)
+                .await
+                .map_err(Error::Database)?
+                .rows_affected() as i64;
+        }
+        relations_dropped -= memberships_moved;
+
+        // Keep each source as an alias of the target, flattening alias chains.
+        sqlx::query(
+            r#"
+            UPDATE skos_concept
+            SET status = 'obsolete', deprecated_at = $1, deprecation_reason = $2,
+                replaced_by_id = $3, updated_at = $1
+            WHERE id = ANY($4)
+            "#,
+        )
+        .bind(now)
+        .bind(&req.reason)
+        .bind(target)
+        .bind(sources)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        sqlx::query(
+            "UPDATE skos_concept SET replaced_by_id = $1, updated_at = $2 WHERE replaced_by_id = ANY($3)",
+        )
+        .bind(target)
+        .bind(now)
+        .bind(sources)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let merge_id = new_v7();
+        sqlx::query(
+            r#"
+            INSERT INTO skos_concept_merge (id, source_ids, target_id, reason, performed_by, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(merge_id)
+        .bind(sources)
+        .bind(target)
+        .bind(&req.reason)
+        .bind(&req.performed_by)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let result = ConceptMergeResult {
+            merge_id,
+            target_id: target,
+            source_ids: req.source_ids.clone(),
+            notes_retagged,
+            labels_added,
+            relations_moved,
+            relations_dropped,
+        };
+        let actor = req.performed_by.as_deref();
+        self.log_audit_tx(
+            tx,
+            target,
+            "merge",
+            json!({
+                "merge_id": merge_id,
+                "source_ids": sources,
+                "notes_retagged": notes_retagged,
+                "labels_added": labels_added,
+                "relations_moved": relations_moved,
+                "relations_dropped": relations_dropped,
+            }),
+            actor,
+        )
+        .await?;
+        for source in sources {
+            self.log_audit_tx(
+                tx,
+                *source,
+                "merge",
+                json!({ "merge_id": merge_id, "replaced_by_id": target }),
+                actor,
+            )
+            .await?;
+        }
+
+        Ok(result)
+    }
+
+    /// Notes tagged with `concept_id` that match `rule`, within a transaction.
+    async fn split_rule_notes_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        concept_id: Uuid,
+        rule: &ConceptSplitRule,
+    ) -> Result<Vec<Uuid>> {
+        let note_ids = (!rule.note_ids.is_empty()).then_some(rule.note_ids.as_slice());
+        let title = rule
+            .title_contains
+            .as_deref()
+            .map(str::trim)
+            .filter(|text| !text.is_empty());
+        sqlx::query_scalar(
+            r#"
+            SELECT nc.note_id
+            FROM note_skos_concept nc
+            JOIN note n ON n.id = nc.note_id
+            WHERE nc.concept_id = $1
+              AND ($2::uuid[] IS NULL OR nc.note_id = ANY($2))
+              AND NOT EXISTS (
+                  SELECT 1 FROM unnest($3::uuid[]) AS wanted(concept_id)
+                  WHERE NOT EXISTS (
+                      SELECT 1 FROM note_skos_concept other
+                      WHERE other.note_id = nc.note_id AND other.concept_id = wanted.concept_id
+                  )
+              )
+              AND ($4::text IS NULL OR STRPOS(LOWER(COALESCE(n.title, '')), LOWER($4)) > 0)
+            ORDER BY nc.note_id
+            "#,
+        )
+        .bind(concept_id)
+        .bind(note_ids)
+        .bind(&rule.concept_ids)
+        .bind(title)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Find or create the concept a split target names, returning its ID
+    /// and whether it was created.
+    async fn resolve_split_target_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        scheme_id: Uuid,
+        concept_id: Option<Uuid>,
+        pref_label: Option<&str>,
+    ) -> Result<(Uuid, bool)> {
+        if let Some(id) = concept_id {
+            return Ok((id, false));
+        }
+        let label = pref_label.map(str::trim).unwrap_or_default();
+        let existing: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT c.id FROM skos_concept c
+            JOIN skos_concept_label l ON l.concept_id = c.id AND l.label_type = 'pref_label'
+            WHERE c.primary_scheme_id = $1 AND c.status <> 'obsolete'
+              AND LOWER(l.value) = LOWER($2)
+            ORDER BY c.created_at
+            LIMIT 1
+            "#,
+        )
+        .bind(scheme_id)
+        .bind(label)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        if let Some(id) = existing {
+            return Ok((id, false));
+        }
+
+        let id = self
+            .create_concept_tx(
+                tx,
+                CreateConceptRequest {
+                    scheme_id,
+                    notation: None,
+                    pref_label: label.to_string(),
+                    language: "en".to_string(),
+                    status: TagStatus::Candidate,
+                    facet_type: None,
+                    facet_source: None,
+                    facet_domain: None,
+                    facet_scope: None,
+                    definition: None,
+                    scope_note: None,
+                    broader_ids: vec![],
+                    related_ids: vec![],
+                    alt_labels: vec![],
+                },
+            )
+            .await?;
+        Ok((id, true))
+    }
+
+    /// Split a concept's notes over target concepts within a transaction.
+    ///
+    /// Each note tagged with the source moves to the first target whose rule
+    /// it matches. Targets named by `pref_label` are looked up in the
+    /// source's scheme and created as candidates when missing. Notes that
+    /// match no rule keep the source concept.
+    pub async fn split_concept_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        source_id: Uuid,
+        req: &SplitConceptRequest,
+    ) -> Result<ConceptSplitResult> {
+        req.validate()?;
+        let now = Utc::now();
+
+        let mut ids: Vec<Uuid> = req.targets.iter().filter_map(|t| t.concept_id).collect();
+        if ids.contains(&source_id) {
+            return Err(Error::InvalidInput(
+                "A split target must not be the source concept".to_string(),
+            ));
+        }
+        ids.push(source_id);
+        let statuses = self.lock_concepts_tx(tx, &ids).await?;
+        match statuses.get(&source_id).map(String::as_str) {
+            None => return Err(concept_not_found_error("Source")),
+            Some("obsolete") => return Err(concept_obsolete_error("Source")),
+            Some(_) => {}
+        }
+        for id in req.targets.iter().filter_map(|t| t.concept_id) {
+            match statuses.get(&id).map(String::as_str) {
+                None => return Err(concept_not_found_error("Target")),
+                Some("obsolete") => return Err(concept_obsolete_error("Target")),
+                Some(_) => {}
+            }
+        }
+        let scheme_id: Uuid =
+            sqlx::query_scalar("SELECT primary_scheme_id FROM skos_concept WHERE id = $1")
+                .bind(source_id)
+                .fetch_one(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+
+        let mut targets = Vec::with_capacity(req.targets.len());
+        let mut notes_moved = 0;
+        for target in &req.targets {
+            let (concept_id, created) = self
+                .resolve_split_target_tx(
+                    tx,
+                    scheme_id,
+                    target.concept_id,
+                    target.pref_label.as_deref(),
+                )
+                .await?;
+            if concept_id == source_id {
+                return Err(Error::InvalidInput(
+                    "A split target must not be the source concept".to_string(),
+                ));
+            }
+
+            // Notes already moved by an earlier target no longer match.
+            let note_ids = self
+                .split_rule_notes_tx(tx, source_id, &target.rule)
+                .await?;
+            if !note_ids.is_empty() {
+                sqlx::query(
+                    r#"
+                    INSERT INTO note_skos_concept (
+                        note_id, concept_id, source, confidence, relevance_score,
+                        is_primary, created_at, created_by
+                    )
+                    SELECT note_id, $2, source, confidence, relevance_score,
+                           is_primary, $3, created_by
+                    FROM note_skos_concept
+                    WHERE concept_id = $1 AND note_id = ANY($4)
+                    ON CONFLICT (note_id, concept_id) DO NOTHING
+                    "#,
+                )
+                .bind(source_id)
+                .bind(concept_id)
+                .bind(now)
+                .bind(&note_ids)
+                .execute(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+                sqlx::query(
+                    "DELETE FROM note_skos_concept WHERE concept_id = $1 AND note_id = ANY($2)",
+                )
+                .bind(source_id)
+                .bind(&note_ids)
+                .execute(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+            }
+            notes_moved += note_ids.len() as i64;
+            targets.push(ConceptSplitOutcome {
+                concept_id,
+                created,
+                note_ids,
+            });
+        }
+
+        let notes_remaining: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM note_skos_concept WHERE concept_id = $1")
+                .bind(source_id)
+                .fetch_one(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+
+        if req.deprecate_source {
+            sqlx::query(
+                r#"
+                UPDATE skos_concept
+                SET status = 'deprecated', deprecated_at = $1, deprecation_reason = $2,
+                    updated_at = $1
+                WHERE id = $3
+                "#,
+            )
+            .bind(now)
+            .bind(&req.reason)
+            .bind(source_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+
+        let target_ids: Vec<Uuid> = targets.iter().map(|t| t.concept_id).collect();
+        let actor = req.performed_by.as_deref();
+        self.log_audit_tx(
+            tx,
+            source_id,
+            "split",
+            json!({
+                "target_ids": target_ids,
+                "notes_moved": notes_moved,
+                "notes_remaining": notes_remaining,
+                "source_deprecated": req.deprecate_source,
+            }),
+            actor,
+        )
+        .await?;
+        for target in &targets {
+            self.log_audit_tx(
+                tx,
+                target.concept_id,
+                "split",
+                json!({
+                    "source_id": source_id,
+                    "created": target.created,
+                    "notes_received": target.note_ids.len(),
+                }),
+                actor,
+            )
+            .await?;
+        }
+
+        Ok(ConceptSplitResult {
+            source_id,
+            targets,
+            notes_moved,
+            notes_remaining,
+            source_deprecated: req.deprecate_source,
+        })
+    }
+
+    /// Notes tagged with a concept, most recently updated first, with their
+    /// titles, within a transaction.
+    pub async fn concept_note_titles_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        concept_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<(Uuid, String)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT n.id, COALESCE(n.title, '') AS title
+            FROM note_skos_concept nc
+            JOIN note n ON n.id = nc.note_id
+            WHERE nc.concept_id = $1 AND n.deleted_at IS NULL
+            ORDER BY n.updated_at_utc DESC, n.id
+            LIMIT $2
+            "#,
+        )
+        .bind(concept_id)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.get("id"), r.get("title")))
+            .collect())
+    }
+}
@@ -0,0 +1,144 @@
+//! Transaction-aware taxonomy health checks.
+//!
+//! Loads what the health analysis needs, refreshes the concepts'
+//! anti-pattern flags and keeps the latest report. Every method takes a
+//! transaction that has already been pointed at the archive schema.
+
+use chrono::Utc;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    new_v7, Error, Result, TagAntipattern, TaxonomyConceptStats, TaxonomyHealthReport,
+};
+
+use crate::skos_tags::PgSkosRepository;
+
+impl PgSkosRepository {
+    /// Active (not deprecated or obsolete) concepts with their usage and
+    /// hierarchy counts, ordered by English preferred label.
+    pub async fn taxonomy_concept_stats_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<TaxonomyConceptStats>> {
+        sqlx::query_as::<_, TaxonomyConceptStats>(
+            "SELECT c.id, l.value AS pref_label, c.note_count, c.depth,
+                    c.broader_count, c.narrower_count, c.related_count
+             FROM skos_concept c
+             LEFT JOIN skos_concept_label l
+               ON l.concept_id = c.id AND l.label_type = 'pref_label' AND l.language = 'en'
+             WHERE c.status NOT IN ('deprecated', 'obsolete')
+             ORDER BY lower(l.value), c.id",
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Every broader link as a `(concept, broader)` pair.
+    pub async fn broader_edges_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<(Uuid, Uuid)>> {
+        sqlx::query_as(
+            "SELECT subject_id, object_id
+             FROM skos_semantic_relation_edge
+             WHERE relation_type = 'broader'
+             ORDER BY subject_id, object_id",
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Recompute every active concept's anti-patterns and add `flags` found
+    /// by the health analysis. Returns the number of concepts flagged.
+    pub async fn refresh_antipatterns_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        flags: &[(Uuid, TagAntipattern)],
+    ) -> Result<u64> {
+        let now = Utc::now();
+        sqlx::query(
+            "UPDATE skos_concept
+             SET antipatterns = skos_detect_antipatterns(id), antipattern_checked_at = $1
+             WHERE status <> 'obsolete'",
+        )
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let (ids, patterns): (Vec<Uuid>, Vec<String>) = flags
+            .iter()
+            .map(|(id, pattern)| (*id, pattern.to_string()))
+            .unzip();
+        sqlx::query(
+            "UPDATE skos_concept c
+             SET antipatterns = ARRAY(
+                     SELECT DISTINCT p FROM unnest(c.antipatterns || f.patterns) AS p)
+             FROM (
+                 SELECT id, array_agg(pattern::tag_antipattern) AS patterns
+                 FROM unnest($1::uuid[], $2::text[]) AS t(id, pattern)
+                 GROUP BY id
+             ) f
+             WHERE c.id = f.id",
+        )
+        .bind(&ids)
+        .bind(&patterns)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let flagged: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM skos_concept
+             WHERE status <> 'obsolete' AND cardinality(antipatterns) > 0",
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(flagged as u64)
+    }
+
+    /// Store a health report in place of the previous one.
+    pub async fn save_taxonomy_health_report_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        report: &TaxonomyHealthReport,
+    ) -> Result<Uuid> {
+        let body = serde_json::to_value(report).map_err(|e| Error::Internal(e.to_string()))?;
+        sqlx::query("DELETE FROM taxonomy_health_report")
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        let id = new_v7();
+        sqlx::query(
+            "INSERT INTO taxonomy_health_report (id, generated_at_utc, report)
+             VALUES ($1, $2, $3)",
+        )
+        .bind(id)
+        .bind(report.generated_at_utc)
+        .bind(body)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(id)
+    }
+
+    /// The latest health report, if the check has run.
+    pub async fn latest_taxonomy_health_report_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<Option<TaxonomyHealthReport>> {
+        let body: Option<serde_json::Value> = sqlx::query_scalar(
+            "SELECT report FROM taxonomy_health_report
+             ORDER BY generated_at_utc DESC
+             LIMIT 1",
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        body.map(|body| serde_json::from_value(body).map_err(|e| Error::Internal(e.to_string())))
+            .transpose()
+    }
+}
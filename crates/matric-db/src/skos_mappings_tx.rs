@@ -0,0 +1,291 @@
+//! Transaction-aware SKOS mapping relations and shard import reconciliation.
+//!
+//! Mappings link archive concepts to concepts in external vocabularies by
+//! URI. Every method takes a transaction that has already been pointed at
+//! the archive schema; edits are recorded in `skos_audit_log`.
+
+use chrono::Utc;
+use serde_json::json;
+use sqlx::postgres::PgRow;
+use sqlx::{Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    new_v7, ConceptReconciliation, CreateMappingRelationRequest, Error, MergeConceptsRequest,
+    Result, SkosMappingRelation, SkosMappingRelationEdge, UpdateMappingRelationRequest,
+};
+
+use crate::skos_tags::PgSkosRepository;
+
+/// Columns of [`SkosMappingRelationEdge`], in the order [`mapping_from_row`] reads them.
+const MAPPING_COLUMNS: &str = "id, concept_id, target_uri, target_scheme_uri, target_label, \
+     relation_type::text AS relation_type, confidence, is_validated, created_at, \
+     validated_at, validated_by";
+
+/// Actor recorded for concepts merged by shard import reconciliation.
+const RECONCILIATION_ACTOR: &str = "shard_import";
+
+fn mapping_from_row(r: &PgRow) -> SkosMappingRelationEdge {
+    SkosMappingRelationEdge {
+        id: r.get("id"),
+        concept_id: r.get("concept_id"),
+        target_uri: r.get("target_uri"),
+        target_scheme_uri: r.get("target_scheme_uri"),
+        target_label: r.get("target_label"),
+        relation_type: r
+            .get::<String, _>("relation_type")
+            .parse()
+            .unwrap_or(SkosMappingRelation::RelatedMatch),
+        confidence: r.get("confidence"),
+        is_validated: r.get("is_validated"),
+        created_at: r.get("created_at"),
+        validated_at: r.get("validated_at"),
+        validated_by: r.get("validated_by"),
+    }
+}
+
+fn mapping_not_found_error() -> Error {
+    Error::NotFound("Mapping relation not found".to_string())
+}
+
+impl PgSkosRepository {
+    /// Mapping relations of a concept within a transaction.
+    pub async fn get_mapping_relations_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        concept_id: Uuid,
+    ) -> Result<Vec<SkosMappingRelationEdge>> {
+        self.get_mapping_relations_for_concepts_tx(tx, &[concept_id])
+            .await
+    }
+
+    /// Mapping relations of several concepts within a transaction, grouped
+    /// by concept.
+    pub async fn get_mapping_relations_for_concepts_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        concept_ids: &[Uuid],
+    ) -> Result<Vec<SkosMappingRelationEdge>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {MAPPING_COLUMNS}
+             FROM skos_mapping_relation_edge
+             WHERE concept_id = ANY($1)
+             ORDER BY concept_id, relation_type, target_uri"
+        ))
+        .bind(concept_ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(rows.iter().map(mapping_from_row).collect())
+    }
+
+    /// Add a mapping relation within a transaction.
+    ///
+    /// A concept can map to the same URI once per relation type; a repeat
+    /// fails with a unique violation.
+    pub async fn create_mapping_relation_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        req: &CreateMappingRelationRequest,
+        actor: Option<&str>,
+    ) -> Result<SkosMappingRelationEdge> {
+        req.validate()?;
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM skos_concept WHERE id = $1)")
+                .bind(req.concept_id)
+                .fetch_one(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+        if !exists {
+            return Err(Error::NotFound("Concept not found".to_string()));
+        }
+
+        let row = sqlx::query(&format!(
+            "INSERT INTO skos_mapping_relation_edge (
+                 id, concept_id, target_uri, target_scheme_uri, target_label,
+                 relation_type, confidence, created_at
+             )
+             VALUES ($1, $2, $3, $4, $5, $6::skos_mapping_relation, $7, $8)
+             RETURNING {MAPPING_COLUMNS}"
+        ))
+        .bind(new_v7())
+        .bind(req.concept_id)
+        .bind(&req.target_uri)
+        .bind(&req.target_scheme_uri)
+        .bind(&req.target_label)
+        .bind(req.relation_type.to_string())
+        .bind(req.confidence)
+        .bind(Utc::now())
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        let mapping = mapping_from_row(&row);
+
+        self.log_audit_tx(
+            tx,
+            req.concept_id,
+            "add_mapping",
+            json!({
+                "mapping_id": mapping.id,
+                "relation_type": mapping.relation_type,
+                "target_uri": mapping.target_uri,
+            }),
+            actor,
+        )
+        .await?;
+        Ok(mapping)
+    }
+
+    /// Update a concept's mapping relation within a transaction.
+    ///
+    /// Giving `validated_by` marks the mapping validated now.
+    pub async fn update_mapping_relation_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        concept_id: Uuid,
+        mapping_id: Uuid,
+        req: &UpdateMappingRelationRequest,
+        actor: Option<&str>,
+    ) -> Result<SkosMappingRelationEdge> {
+        req.validate()?;
+        let row = sqlx::query(&format!(
+            "UPDATE skos_mapping_relation_edge SET
+                 relation_type = COALESCE($3::skos_mapping_relation, relation_type),
+                 target_scheme_uri = COALESCE($4, target_scheme_uri),
+                 target_label = COALESCE($5, target_label),
+                 confidence = COALESCE($6, confidence),
+                 is_validated = is_validated OR $7::text IS NOT NULL,
+                 validated_at = CASE WHEN $7::text IS NOT NULL THEN $8 ELSE validated_at END,
+                 validated_by = COALESCE($7, validated_by)
+             WHERE id = $2 AND concept_id = $1
+             RETURNING {MAPPING_COLUMNS}"
+        ))
+        .bind(concept_id)
+        .bind(mapping_id)
+        .bind(req.relation_type.map(|relation| relation.to_string()))
+        .bind(&req.target_scheme_uri)
+        .bind(&req.target_label)
+        .bind(req.confidence)
+        .bind(&req.validated_by)
+        .bind(Utc::now())
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .ok_or_else(mapping_not_found_error)?;
+        let mapping = mapping_from_row(&row);
+
+        self.log_audit_tx(
+            tx,
+            concept_id,
+            "update_mapping",
+            json!({
+                "mapping_id": mapping.id,
+                "relation_type": mapping.relation_type,
+                "validated": req.validated_by.is_some(),
+            }),
+            actor,
+        )
+        .await?;
+        Ok(mapping)
+    }
+
+    /// Remove a concept's mapping relation within a transaction.
+    pub async fn delete_mapping_relation_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        concept_id: Uuid,
+        mapping_id: Uuid,
+        actor: Option<&str>,
+    ) -> Result<()> {
+        let removed: Option<(String, String)> = sqlx::query_as(
+            "DELETE FROM skos_mapping_relation_edge
+             WHERE id = $2 AND concept_id = $1
+             RETURNING relation_type::text, target_uri",
+        )
+        .bind(concept_id)
+        .bind(mapping_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        let Some((relation_type, target_uri)) = removed else {
+            return Err(mapping_not_found_error());
+        };
+
+        self.log_audit_tx(
+            tx,
+            concept_id,
+            "remove_mapping",
+            json!({
+                "mapping_id": mapping_id,
+                "relation_type": relation_type,
+                "target_uri": target_uri,
+            }),
+            actor,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Merge concepts just imported from a knowledge shard into the local
+    /// concepts they identify with, within a transaction.
+    ///
+    /// Two concepts identify with each other when one's URI or
+    /// `skos:exactMatch` target equals the other's. Each imported concept in
+    /// `imported_ids` is merged into the oldest matching concept that was
+    /// already in the archive, so a vocabulary shared between instances is
+    /// not duplicated. Obsolete concepts are never matched.
+    pub async fn reconcile_imported_concepts_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        imported_ids: &[Uuid],
+    ) -> Result<Vec<ConceptReconciliation>> {
+        if imported_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let matches: Vec<(Uuid, Uuid, String)> = sqlx::query_as(
+            r#"
+            WITH identity AS (
+                SELECT id AS concept_id, uri AS key FROM skos_concept WHERE uri IS NOT NULL
+                UNION
+                SELECT concept_id, target_uri FROM skos_mapping_relation_edge
+                WHERE relation_type = 'exact_match'
+            )
+            SELECT DISTINCT ON (imported.concept_id)
+                   imported.concept_id, local.concept_id, imported.key
+            FROM identity imported
+            JOIN identity local
+              ON local.key = imported.key AND local.concept_id <> ALL($1)
+            JOIN skos_concept ic ON ic.id = imported.concept_id AND ic.status <> 'obsolete'
+            JOIN skos_concept lc ON lc.id = local.concept_id AND lc.status <> 'obsolete'
+            WHERE imported.concept_id = ANY($1)
+            ORDER BY imported.concept_id, lc.created_at, lc.id, imported.key
+            "#,
+        )
+        .bind(imported_ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let mut reconciled = Vec::with_capacity(matches.len());
+        for (concept_id, reconciled_into, match_uri) in matches {
+            let merge = self
+                .merge_concepts_tx(
+                    tx,
+                    &MergeConceptsRequest {
+                        source_ids: vec![concept_id],
+                        target_id: reconciled_into,
+                        reason: Some("Reconciled on knowledge shard import".to_string()),
+                        performed_by: Some(RECONCILIATION_ACTOR.to_string()),
+                    },
+                )
+                .await?;
+            reconciled.push(ConceptReconciliation {
+                concept_id,
+                reconciled_into,
+                match_uri,
+                merge_id: merge.merge_id,
+            });
+        }
+        Ok(reconciled)
+    }
+}
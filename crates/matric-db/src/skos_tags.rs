@@ -2169,74 +2169,10 @@ impl PgSkosRepository {
 #[async_trait]
 impl SkosGovernanceRepository for PgSkosRepository {
     async fn merge_concepts(&self, req: MergeConceptsRequest) -> Result<Uuid> {
-        let merge_id = new_v7();
-        let now = Utc::now();
         let mut tx = self.pool.begin().await.map_err(Error::Database)?;
-
-        // Move all note tags to target
-        for source_id in &req.source_ids {
-            // Use alias 'outer_row' to fix ambiguous reference in the NOT EXISTS subquery
-            sqlx::query(
-                r#"
-                UPDATE note_skos_concept AS outer_row
-                SET concept_id = $1
-                WHERE outer_row.concept_id = $2 AND NOT EXISTS (
-                    SELECT 1 FROM note_skos_concept
-                    WHERE note_id = outer_row.note_id AND concept_id = $1
-                )
-                "#,
-            )
-            .bind(req.target_id)
-            .bind(source_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(Error::Database)?;
-
-            // Delete duplicates
-            sqlx::query("DELETE FROM note_skos_concept WHERE concept_id = $1")
-                .bind(source_id)
-                .execute(&mut *tx)
-                .await
-                .map_err(Error::Database)?;
-
-            // Deprecate source concept
-            sqlx::query(
-                r#"
-                UPDATE skos_concept
-                SET status = 'obsolete', deprecated_at = $1, deprecation_reason = $2,
-                    replaced_by_id = $3, updated_at = $1
-                WHERE id = $4
-                "#,
-            )
-            .bind(now)
-            .bind(&req.reason)
-            .bind(req.target_id)
-            .bind(source_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(Error::Database)?;
-        }
-
-        // Record the merge
-        sqlx::query(
-            r#"
-            INSERT INTO skos_concept_merge (id, source_ids, target_id, reason, performed_by, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            "#,
-        )
-        .bind(merge_id)
-        .bind(&req.source_ids)
-        .bind(req.target_id)
-        .bind(&req.reason)
-        .bind(&req.performed_by)
-        .bind(now)
-        .execute(&mut *tx)
-        .await
-        .map_err(Error::Database)?;
-
+        let result = self.merge_concepts_tx(&mut tx, &req).await?;
         tx.commit().await.map_err(Error::Database)?;
-
-        Ok(merge_id)
+        Ok(result.merge_id)
     }
 
     async fn get_merge_history(&self, concept_id: Uuid) -> Result<Vec<SkosConceptMerge>> {
@@ -2427,7 +2363,7 @@ impl SkosGovernanceRepository for PgSkosRepository {
     }
 }
 
-fn skos_audit_actor_metadata(actor: &str) -> String {
+pub(crate) fn skos_audit_actor_metadata(actor: &str) -> String {
     if actor.starts_with("actor_present=true;actor_len=") {
         actor.to_string()
     } else {
@@ -2435,7 +2371,7 @@ fn skos_audit_actor_metadata(actor: &str) -> String {
     }
 }
 
-fn skos_audit_changes_metadata(changes: Option<Value>) -> Option<Value> {
+pub(crate) fn skos_audit_changes_metadata(changes: Option<Value>) -> Option<Value> {
     changes.map(redact_skos_audit_value)
 }
 
@@ -923,6 +923,8 @@ impl PgSkosRepository {
             })
             .collect();
 
+        let mappings = self.get_mapping_relations_tx(tx, id).await?;
+
         Ok(Some(SkosConceptFull {
             concept,
             labels,
@@ -930,7 +932,7 @@ impl PgSkosRepository {
             broader,
             narrower,
             related,
-            mappings: vec![],
+            mappings,
             schemes: vec![],
         }))
     }
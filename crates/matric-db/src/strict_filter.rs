@@ -78,6 +78,69 @@ impl QueryParam {
     }
 }
 
+/// SQL condition matching `nsc.concept_id` against the concept (or, with
+/// `is_array`, the concepts) bound at `$param_idx`.
+///
+/// With `expand_hierarchy` the condition also matches every concept that is
+/// transitively narrower than them: a recursive CTE follows SKOS broader
+/// links down from the bound concepts. It combines levels with `UNION`, which
+/// drops concepts already reached, so a cycle in the hierarchy ends the
+/// recursion instead of looping.
+pub(crate) fn concept_match_sql(
+    param_idx: usize,
+    is_array: bool,
+    expand_hierarchy: bool,
+) -> String {
+    match (is_array, expand_hierarchy) {
+        (false, false) => format!("nsc.concept_id = ${}", param_idx),
+        (true, false) => format!("nsc.concept_id = ANY(${}::uuid[])", param_idx),
+        (_, true) => {
+            let seed = if is_array {
+                format!("SELECT unnest(${}::uuid[])", param_idx)
+            } else {
+                format!("SELECT ${}::uuid", param_idx)
+            };
+            format!(
+                "nsc.concept_id IN (WITH RECURSIVE concept_tree(concept_id) AS ({} UNION SELECT e.subject_id FROM skos_semantic_relation_edge e JOIN concept_tree ct ON e.object_id = ct.concept_id WHERE e.relation_type = 'broader') SELECT concept_id FROM concept_tree)",
+                seed
+            )
+        }
+    }
+}
+
+/// SQL condition comparing the note metadata key at the path bound at
+/// `$path_idx` with the value bound as text at `$value_idx`.
+///
+/// Numbers compare numerically against keys stored as JSON numbers; equality
+/// still falls back to text for keys stored as strings, while ordering
+/// matches only numeric keys. Everything else compares the text `#>>`
+/// extracts, so `true` matches a JSON `true` and ISO 8601 dates order
+/// chronologically. A missing key never matches, except under `ne`.
+pub(crate) fn metadata_field_sql(
+    field: &matric_core::MetadataFieldFilter,
+    path_idx: usize,
+    value_idx: usize,
+) -> String {
+    use matric_core::{MetadataOp, MetadataValue};
+
+    let text = format!("(n.metadata #>> ${}::text[])", path_idx);
+    let text_cmp = match field.op {
+        MetadataOp::Ne => format!("{} IS DISTINCT FROM ${}::text", text, value_idx),
+        op => format!("{} {} ${}::text", text, op.as_sql(), value_idx),
+    };
+    match field.value {
+        MetadataValue::Number(_) => format!(
+            "(CASE WHEN jsonb_typeof(n.metadata #> ${}::text[]) = 'number' THEN {}::numeric {} ${}::numeric ELSE {} END)",
+            path_idx,
+            text,
+            field.op.as_sql(),
+            value_idx,
+            if field.op.is_range() { "FALSE".to_string() } else { text_cmp }
+        ),
+        _ => text_cmp,
+    }
+}
+
 /// Generates SQL WHERE clause fragments for strict tag filtering.
 ///
 /// This builder converts a `StrictTagFilter` into SQL WHERE clauses with
@@ -143,7 +206,8 @@ impl StrictFilterQueryBuilder {
             + self.filter.excluded_schemes.len()
             + self.filter.required_string_tags.len()
             + self.filter.any_string_tags.len()
-            + self.filter.excluded_string_tags.len();
+            + self.filter.excluded_string_tags.len()
+            + self.filter.metadata_fields.len();
         if total_elements > Self::MAX_FILTER_ELEMENTS {
             // Return match-nothing clause instead of erroring — safe degradation
             return ("FALSE".to_string(), vec![]);
@@ -158,8 +222,8 @@ impl StrictFilterQueryBuilder {
         for concept_id in &self.filter.required_concepts {
             param_idx += 1;
             clauses.push(format!(
-                "EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND nsc.concept_id = ${})",
-                param_idx
+                "EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND {})",
+                concept_match_sql(param_idx, false, self.filter.expand_hierarchy)
             ));
             params.push(QueryParam::Uuid(*concept_id));
         }
@@ -169,8 +233,8 @@ impl StrictFilterQueryBuilder {
         if !self.filter.any_concepts.is_empty() {
             param_idx += 1;
             clauses.push(format!(
-                "EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND nsc.concept_id = ANY(${}::uuid[]))",
-                param_idx
+                "EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND {})",
+                concept_match_sql(param_idx, true, self.filter.expand_hierarchy)
             ));
             params.push(QueryParam::UuidArray(self.filter.any_concepts.clone()));
         }
@@ -180,8 +244,8 @@ impl StrictFilterQueryBuilder {
         if !self.filter.excluded_concepts.is_empty() {
             param_idx += 1;
             clauses.push(format!(
-                "NOT EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND nsc.concept_id = ANY(${}::uuid[]))",
-                param_idx
+                "NOT EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND {})",
+                concept_match_sql(param_idx, true, self.filter.expand_hierarchy)
             ));
             params.push(QueryParam::UuidArray(self.filter.excluded_concepts.clone()));
         }
@@ -244,6 +308,17 @@ impl StrictFilterQueryBuilder {
             ));
         }
 
+        // Metadata fields (AND): note metadata must satisfy ALL conditions
+        // The key path and the value are both bound, never interpolated
+        for field in &self.filter.metadata_fields {
+            let path_idx = param_idx + 1;
+            let value_idx = param_idx + 2;
+            param_idx += 2;
+            clauses.push(metadata_field_sql(field, path_idx, value_idx));
+            params.push(QueryParam::StringArray(field.path.clone()));
+            params.push(QueryParam::String(field.value.to_text()));
+        }
+
         // Minimum tag count
         if let Some(min_count) = self.filter.min_tag_count {
             param_idx += 1;
@@ -531,6 +606,25 @@ mod tests {
         assert_eq!(params.len(), 1);
     }
 
+    #[test]
+    fn test_expand_hierarchy_matches_narrower_concepts() {
+        let filter = StrictTagFilter {
+            required_concepts: vec![Uuid::new_v4()],
+            excluded_concepts: vec![Uuid::new_v4()],
+            expand_hierarchy: true,
+            ..Default::default()
+        };
+
+        let builder = StrictFilterQueryBuilder::new(filter, 0);
+        let (sql, params) = builder.build();
+
+        assert_eq!(
+            sql,
+            "EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND nsc.concept_id IN (WITH RECURSIVE concept_tree(concept_id) AS (SELECT $1::uuid UNION SELECT e.subject_id FROM skos_semantic_relation_edge e JOIN concept_tree ct ON e.object_id = ct.concept_id WHERE e.relation_type = 'broader') SELECT concept_id FROM concept_tree)) AND NOT EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND nsc.concept_id IN (WITH RECURSIVE concept_tree(concept_id) AS (SELECT unnest($2::uuid[]) UNION SELECT e.subject_id FROM skos_semantic_relation_edge e JOIN concept_tree ct ON e.object_id = ct.concept_id WHERE e.relation_type = 'broader') SELECT concept_id FROM concept_tree))"
+        );
+        assert_eq!(params.len(), 2);
+    }
+
     #[test]
     fn test_complex_scheme_and_concept_filtering() {
         let req_concept = Uuid::new_v4();
@@ -554,6 +648,43 @@ mod tests {
         assert!(sql.contains("$3")); // excluded scheme
     }
 
+    #[test]
+    fn test_metadata_text_field() {
+        let field = matric_core::MetadataFieldFilter::parse("metadata.status", "active").unwrap();
+        let filter = StrictTagFilter::new().require_metadata(field);
+
+        let builder = StrictFilterQueryBuilder::new(filter, 1);
+        let (sql, params) = builder.build();
+
+        assert_eq!(sql, "(n.metadata #>> $2::text[]) = $3::text");
+        assert_eq!(params.len(), 2);
+        match (&params[0], &params[1]) {
+            (QueryParam::StringArray(path), QueryParam::String(value)) => {
+                assert_eq!(path, &vec!["status".to_string()]);
+                assert_eq!(value, "active");
+            }
+            _ => panic!("Expected path and value params"),
+        }
+    }
+
+    #[test]
+    fn test_metadata_numeric_range_field() {
+        let field =
+            matric_core::MetadataFieldFilter::parse("metadata.review.score[gte]", "4").unwrap();
+        let filter = StrictTagFilter::new()
+            .require_concept(Uuid::new_v4())
+            .require_metadata(field);
+
+        let builder = StrictFilterQueryBuilder::new(filter, 0);
+        let (sql, params) = builder.build();
+
+        assert!(sql.ends_with(
+            " AND (CASE WHEN jsonb_typeof(n.metadata #> $2::text[]) = 'number' THEN (n.metadata #>> $2::text[])::numeric >= $3::numeric ELSE FALSE END)"
+        ));
+        assert_eq!(params.len(), 3);
+        assert!(matches!(&params[2], QueryParam::String(value) if value == "4"));
+    }
+
     #[test]
     fn query_param_debug_redacts_identifiers_and_strings() {
         let secret_id = Uuid::new_v4();
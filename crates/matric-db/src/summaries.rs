@@ -0,0 +1,93 @@
+//! AI-generated note summary repository.
+//!
+//! Summaries are archive-scoped. The `*_tx` methods take a transaction that
+//! has already been pointed at the archive schema; [`PgNoteSummaryRepository::for_notes`]
+//! reads through the repository's pool, which callers pin to the archive.
+
+use std::collections::HashMap;
+
+use sqlx::{Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{Error, NoteGeneratedSummary, Result};
+
+/// PostgreSQL repository for note summaries.
+#[derive(Clone)]
+pub struct PgNoteSummaryRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgNoteSummaryRepository {
+    /// Create a new note summary repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Store a note's summary, replacing any earlier one.
+    pub async fn upsert_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        summary: &str,
+        model: Option<&str>,
+        chunk_count: i32,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO note_summary (note_id, summary, model, chunk_count, generated_at_utc)
+             VALUES ($1, $2, $3, $4, NOW())
+             ON CONFLICT (note_id) DO UPDATE
+             SET summary = EXCLUDED.summary,
+                 model = EXCLUDED.model,
+                 chunk_count = EXCLUDED.chunk_count,
+                 generated_at_utc = EXCLUDED.generated_at_utc",
+        )
+        .bind(note_id)
+        .bind(summary)
+        .bind(model)
+        .bind(chunk_count.max(1))
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Get a note's summary.
+    pub async fn get_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<Option<NoteGeneratedSummary>> {
+        fetch_summary(tx, note_id).await
+    }
+
+    /// Summary text for each of `note_ids` that has one.
+    pub async fn for_notes(&self, note_ids: &[Uuid]) -> Result<HashMap<Uuid, String>> {
+        if note_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows = sqlx::query("SELECT note_id, summary FROM note_summary WHERE note_id = ANY($1)")
+            .bind(note_ids)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("note_id"), row.get("summary")))
+            .collect())
+    }
+}
+
+/// Load a note's summary within `tx`; shared with note fetches.
+pub(crate) async fn fetch_summary(
+    tx: &mut Transaction<'_, Postgres>,
+    note_id: Uuid,
+) -> Result<Option<NoteGeneratedSummary>> {
+    sqlx::query_as::<_, NoteGeneratedSummary>(
+        "SELECT note_id, summary, model, chunk_count, generated_at_utc
+         FROM note_summary WHERE note_id = $1",
+    )
+    .bind(note_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(Error::Database)
+}
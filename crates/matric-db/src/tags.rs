@@ -5,7 +5,9 @@ use chrono::Utc;
 use sqlx::{Pool, Postgres, Row, Transaction};
 use uuid::Uuid;
 
-use matric_core::{Error, Result, Tag, TagRepository};
+use matric_core::{BulkTagFilter, Error, Result, StrictSecurityFilter, Tag, TagRepository};
+
+use crate::unified_filter::{bind_filter_param, security_filter_query, QueryParam};
 
 /// Validate a tag name.
 ///
@@ -370,4 +372,174 @@ impl PgTagRepository {
 
         Ok(())
     }
+
+    /// Whether a tag exists within an existing transaction.
+    pub async fn exists_tx(&self, tx: &mut Transaction<'_, Postgres>, name: &str) -> Result<bool> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM tag WHERE name = $1)")
+            .bind(name)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(Error::Database)
+    }
+
+    /// Move every note tagged `source` to `target`, then drop `source`.
+    ///
+    /// `target` is created when it does not exist and keeps its own entry
+    /// on notes that already carry both tags. Returns the notes that were
+    /// tagged `source`; `Error::NotFound` when `source` does not exist.
+    pub async fn merge_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        source: &str,
+        target: &str,
+    ) -> Result<Vec<Uuid>> {
+        if !self.exists_tx(tx, source).await? {
+            return Err(Self::tag_not_found_error());
+        }
+        self.create_tx(tx, target).await?;
+
+        sqlx::query(
+            "INSERT INTO note_tag (note_id, tag_name, source)
+             SELECT note_id, $2, source FROM note_tag WHERE tag_name = $1
+             ON CONFLICT (note_id, tag_name) DO NOTHING",
+        )
+        .bind(source)
+        .bind(target)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        self.delete_tx(tx, source).await
+    }
+
+    /// Rename `from` to `to` on every note.
+    ///
+    /// `Error::InvalidInput` when `to` already exists; merge into it instead.
+    pub async fn rename_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<Uuid>> {
+        if self.exists_tx(tx, to).await? {
+            return Err(Error::InvalidInput(
+                "Target tag already exists; merge the tags instead".to_string(),
+            ));
+        }
+        self.merge_tx(tx, from, to).await
+    }
+
+    /// Delete a tag from every note and drop it.
+    ///
+    /// Returns the notes that carried the tag; `Error::NotFound` when it
+    /// does not exist.
+    pub async fn delete_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        name: &str,
+    ) -> Result<Vec<Uuid>> {
+        let mut note_ids: Vec<Uuid> =
+            sqlx::query_scalar("DELETE FROM note_tag WHERE tag_name = $1 RETURNING note_id")
+                .bind(name)
+                .fetch_all(&mut **tx)
+                .await
+                .map_err(Error::Database)?;
+        let deleted = sqlx::query("DELETE FROM tag WHERE name = $1")
+            .bind(name)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        if deleted.rows_affected() == 0 {
+            return Err(Self::tag_not_found_error());
+        }
+        note_ids.sort_unstable();
+        Ok(note_ids)
+    }
+
+    /// Notes that are not deleted and match every selector of `filter`.
+    /// `security`, when given, admits only the notes it allows.
+    pub async fn notes_matching_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        filter: &BulkTagFilter,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Vec<Uuid>> {
+        let note_ids = (!filter.note_ids.is_empty()).then_some(filter.note_ids.as_slice());
+        let security = security_filter_query(security, 3);
+        let security_clause = security
+            .as_ref()
+            .map(|result| format!("AND {}", result.where_clause))
+            .unwrap_or_default();
+        let sql = format!(
+            "SELECT n.id FROM note n
+             WHERE n.deleted_at IS NULL
+               AND ($1::uuid[] IS NULL OR n.id = ANY($1))
+               AND ($2::uuid IS NULL OR n.collection_id = $2)
+               AND NOT EXISTS (
+                   SELECT 1 FROM unnest($3::text[]) AS wanted(tag)
+                   WHERE NOT EXISTS (
+                       SELECT 1 FROM note_tag nt
+                       WHERE nt.note_id = n.id
+                         AND (LOWER(nt.tag_name) = LOWER(wanted.tag)
+                              OR LEFT(LOWER(nt.tag_name), LENGTH(wanted.tag) + 1)
+                                 = LOWER(wanted.tag) || '/')
+                   )
+               )
+               {security_clause}
+             ORDER BY n.id"
+        );
+        let mut q = sqlx::query_scalar(&sql)
+            .bind(note_ids)
+            .bind(filter.collection_id)
+            .bind(&filter.tags);
+        for param in security.iter().flat_map(|result| &result.params) {
+            q = bind_filter_param!(q, param);
+        }
+        q.fetch_all(&mut **tx).await.map_err(Error::Database)
+    }
+
+    /// Add a tag to each of `note_ids`, returning the notes that gained it.
+    pub async fn add_to_notes_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_ids: &[Uuid],
+        tag_name: &str,
+        source: &str,
+    ) -> Result<Vec<Uuid>> {
+        self.create_tx(tx, tag_name).await?;
+        sqlx::query_scalar(
+            "INSERT INTO note_tag (note_id, tag_name, source)
+             SELECT id, $2, $3 FROM unnest($1::uuid[]) AS id
+             ON CONFLICT (note_id, tag_name) DO NOTHING
+             RETURNING note_id",
+        )
+        .bind(note_ids)
+        .bind(tag_name)
+        .bind(source)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Remove a tag from each of `note_ids`, returning the notes that lost it.
+    pub async fn remove_from_notes_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_ids: &[Uuid],
+        tag_name: &str,
+    ) -> Result<Vec<Uuid>> {
+        sqlx::query_scalar(
+            "DELETE FROM note_tag
+             WHERE note_id = ANY($1) AND tag_name = $2
+             RETURNING note_id",
+        )
+        .bind(note_ids)
+        .bind(tag_name)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    fn tag_not_found_error() -> Error {
+        Error::NotFound("Tag not found; tag_name_present=true".to_string())
+    }
 }
@@ -0,0 +1,278 @@
+//! Task repository.
+//!
+//! Tasks are archive-scoped; every method takes a transaction that has
+//! already been pointed at the archive schema.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::task::{normalize_task_text, ExtractedTask};
+use matric_core::{
+    new_v7, Error, Result, StrictSecurityFilter, Task, TaskFilter, TaskListing, TaskSource,
+    TaskStatus,
+};
+
+use crate::unified_filter::{bind_filter_param, security_filter_query, QueryParam};
+
+/// Columns of [`Task`], for queries aliasing `task AS t`.
+const TASK_COLUMNS: &str = "t.id, t.note_id, t.text, t.status, t.source, t.due_date, t.line, \
+     t.completed_at_utc, t.created_at_utc, t.updated_at_utc";
+
+/// A task stored by an earlier run, reused when the same text turns up again.
+struct PreviousTask {
+    id: Uuid,
+    status: TaskStatus,
+    completed_at_utc: Option<DateTime<Utc>>,
+    created_at_utc: DateTime<Utc>,
+}
+
+/// PostgreSQL repository for tasks found in notes.
+#[derive(Clone)]
+pub struct PgTaskRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgTaskRepository {
+    /// Create a new task repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Replace the tasks of a note with `tasks`.
+    ///
+    /// A task whose text (ignoring case and spacing) matches one stored for
+    /// the note before keeps its ID and creation time. Checkbox tasks take
+    /// their status from the note; action items keep the status they had.
+    /// Returns the number of tasks stored.
+    pub async fn replace_for_note_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        tasks: &[ExtractedTask],
+    ) -> Result<usize> {
+        let rows = sqlx::query(
+            "SELECT id, text, status, source, completed_at_utc, created_at_utc
+             FROM task WHERE note_id = $1
+             ORDER BY created_at_utc, id",
+        )
+        .bind(note_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let mut previous: HashMap<(String, String), Vec<PreviousTask>> = HashMap::new();
+        for row in rows {
+            let status: String = row.get("status");
+            let Ok(status) = status.parse::<TaskStatus>() else {
+                continue;
+            };
+            let text: String = row.get("text");
+            previous
+                .entry((normalize_task_text(&text), row.get("source")))
+                .or_default()
+                .push(PreviousTask {
+                    id: row.get("id"),
+                    status,
+                    completed_at_utc: row.get("completed_at_utc"),
+                    created_at_utc: row.get("created_at_utc"),
+                });
+        }
+
+        sqlx::query("DELETE FROM task WHERE note_id = $1")
+            .bind(note_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+
+        let now = Utc::now();
+        for task in tasks {
+            let key = (
+                normalize_task_text(&task.text),
+                task.source.as_str().to_string(),
+            );
+            let earlier = previous
+                .get_mut(&key)
+                .and_then(|found| (!found.is_empty()).then(|| found.remove(0)));
+            let status = match (&earlier, task.source) {
+                (Some(earlier), TaskSource::Llm) => earlier.status,
+                _ => task.status,
+            };
+            let completed_at_utc = match (&earlier, status) {
+                (_, TaskStatus::Open) => None,
+                (Some(earlier), TaskStatus::Done) => earlier.completed_at_utc.or(Some(now)),
+                (None, TaskStatus::Done) => Some(now),
+            };
+            sqlx::query(
+                "INSERT INTO task
+                     (id, note_id, text, status, source, due_date, line, completed_at_utc,
+                      created_at_utc, updated_at_utc)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            )
+            .bind(earlier.as_ref().map_or_else(new_v7, |earlier| earlier.id))
+            .bind(note_id)
+            .bind(&task.text)
+            .bind(status.as_str())
+            .bind(task.source.as_str())
+            .bind(task.due_date)
+            .bind(task.line.and_then(|line| i32::try_from(line).ok()))
+            .bind(completed_at_utc)
+            .bind(
+                earlier
+                    .as_ref()
+                    .map_or(now, |earlier| earlier.created_at_utc),
+            )
+            .bind(now)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+        Ok(tasks.len())
+    }
+
+    /// Tasks of live notes matching `filter`, soonest due first; tasks with
+    /// no due date come last. `security`, when given, admits only tasks of
+    /// the notes it allows.
+    pub async fn list_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        filter: &TaskFilter,
+        security: Option<&StrictSecurityFilter>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<TaskListing> {
+        let security = security_filter_query(security, 5);
+        let security_clause = security
+            .as_ref()
+            .map(|result| format!("AND {}", result.where_clause))
+            .unwrap_or_default();
+        let security_params: &[QueryParam] = security.as_ref().map_or(&[], |result| &result.params);
+        let filter_sql = format!(
+            "FROM task t
+             JOIN note n ON n.id = t.note_id AND n.deleted_at IS NULL
+             WHERE ($1::text IS NULL OR t.status = $1)
+               AND ($2::text IS NULL OR t.source = $2)
+               AND ($3::uuid IS NULL OR t.note_id = $3)
+               AND ($4::date IS NULL OR t.due_date <= $4)
+               AND ($5::date IS NULL OR t.due_date >= $5)
+               {security_clause}"
+        );
+
+        let count_sql = format!("SELECT COUNT(*) {filter_sql}");
+        let mut count = sqlx::query_scalar(&count_sql)
+            .bind(filter.status.map(TaskStatus::as_str))
+            .bind(filter.source.map(TaskSource::as_str))
+            .bind(filter.note_id)
+            .bind(filter.due_before)
+            .bind(filter.due_after);
+        for param in security_params {
+            count = bind_filter_param!(count, param);
+        }
+        let total: i64 = count.fetch_one(&mut **tx).await.map_err(Error::Database)?;
+
+        let limit_param = 6 + security_params.len();
+        let list_sql = format!(
+            "SELECT {TASK_COLUMNS} {filter_sql}
+             ORDER BY t.due_date NULLS LAST, t.created_at_utc, t.note_id, t.line NULLS LAST, t.id
+             LIMIT ${limit_param} OFFSET ${}",
+            limit_param + 1
+        );
+        let mut list = sqlx::query_as::<_, Task>(&list_sql)
+            .bind(filter.status.map(TaskStatus::as_str))
+            .bind(filter.source.map(TaskSource::as_str))
+            .bind(filter.note_id)
+            .bind(filter.due_before)
+            .bind(filter.due_after);
+        for param in security_params {
+            list = bind_filter_param!(list, param);
+        }
+        let tasks = list
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+
+        Ok(TaskListing { tasks, total })
+    }
+
+    /// A task of a live note. `security`, when given, hides tasks of notes
+    /// it does not allow.
+    pub async fn get_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Option<Task>> {
+        self.fetch_tx(tx, id, security, "").await
+    }
+
+    /// A task of a live note, locked until the transaction ends.
+    pub async fn get_for_update_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Option<Task>> {
+        self.fetch_tx(tx, id, None, "FOR UPDATE OF t").await
+    }
+
+    async fn fetch_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        security: Option<&StrictSecurityFilter>,
+        locking: &str,
+    ) -> Result<Option<Task>> {
+        let security = security_filter_query(security, 1);
+        let security_clause = security
+            .as_ref()
+            .map(|result| format!("AND {}", result.where_clause))
+            .unwrap_or_default();
+        let sql = format!(
+            "SELECT {TASK_COLUMNS}
+             FROM task t
+             JOIN note n ON n.id = t.note_id AND n.deleted_at IS NULL
+             WHERE t.id = $1
+               {security_clause}
+             {locking}"
+        );
+        let mut q = sqlx::query_as::<_, Task>(&sql).bind(id);
+        for param in security.iter().flat_map(|result| &result.params) {
+            q = bind_filter_param!(q, param);
+        }
+        q.fetch_optional(&mut **tx).await.map_err(Error::Database)
+    }
+
+    /// Set a task's status, and its checkbox line when `line` is given.
+    /// Completion time is kept while the task stays done.
+    pub async fn set_status_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        status: TaskStatus,
+        line: Option<i32>,
+    ) -> Result<Task> {
+        sqlx::query_as::<_, Task>(&format!(
+            "UPDATE task t SET
+                 status = $2,
+                 line = COALESCE($3, t.line),
+                 completed_at_utc = CASE
+                     WHEN $2 = 'done' THEN COALESCE(t.completed_at_utc, NOW())
+                     ELSE NULL
+                 END,
+                 updated_at_utc = NOW()
+             WHERE t.id = $1
+             RETURNING {TASK_COLUMNS}"
+        ))
+        .bind(id)
+        .bind(status.as_str())
+        .bind(line)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .ok_or_else(|| Error::NotFound("Task not found".to_string()))
+    }
+}
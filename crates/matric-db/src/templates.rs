@@ -1,15 +1,25 @@
 //! Template repository implementation.
+//!
+//! Every update raises a template's version and first copies the version it
+//! replaces into `note_template_version`.
 
 use async_trait::async_trait;
 use chrono::Utc;
+use sqlx::postgres::PgRow;
 use sqlx::{Pool, Postgres, Row, Transaction};
 use uuid::Uuid;
 
 use matric_core::{
     new_v7, CreateTemplateRequest, Error, NoteTemplate, Result, TemplateRepository,
-    UpdateTemplateRequest,
+    TemplateVariable, TemplateVersion, UpdateTemplateRequest,
 };
 
+const TEMPLATE_COLUMNS: &str = "id, name, description, content, format, default_tags, \
+     collection_id, variables, version, created_at_utc, updated_at_utc";
+
+const TEMPLATE_VERSION_COLUMNS: &str = "template_id, version, name, description, content, \
+     format, default_tags, variables, created_at_utc";
+
 /// PostgreSQL implementation of TemplateRepository.
 pub struct PgTemplateRepository {
     pool: Pool<Postgres>,
@@ -22,183 +32,105 @@ impl PgTemplateRepository {
     }
 }
 
+fn variables_from_json(value: serde_json::Value) -> Result<Vec<TemplateVariable>> {
+    serde_json::from_value(value)
+        .map_err(|e| Error::Internal(format!("Invalid stored template variables: {e}")))
+}
+
+fn variables_to_json(variables: &[TemplateVariable]) -> Result<serde_json::Value> {
+    serde_json::to_value(variables)
+        .map_err(|e| Error::Internal(format!("Failed to encode template variables: {e}")))
+}
+
+fn template_from_row(r: PgRow) -> Result<NoteTemplate> {
+    Ok(NoteTemplate {
+        id: r.get("id"),
+        name: r.get("name"),
+        description: r.get("description"),
+        content: r.get("content"),
+        format: r.get("format"),
+        default_tags: r
+            .get::<Option<Vec<String>>, _>("default_tags")
+            .unwrap_or_default(),
+        collection_id: r.get("collection_id"),
+        variables: variables_from_json(r.get("variables"))?,
+        version: r.get("version"),
+        created_at_utc: r.get("created_at_utc"),
+        updated_at_utc: r.get("updated_at_utc"),
+    })
+}
+
+fn version_from_row(r: PgRow) -> Result<TemplateVersion> {
+    Ok(TemplateVersion {
+        template_id: r.get("template_id"),
+        version: r.get("version"),
+        name: r.get("name"),
+        description: r.get("description"),
+        content: r.get("content"),
+        format: r.get("format"),
+        default_tags: r
+            .get::<Option<Vec<String>>, _>("default_tags")
+            .unwrap_or_default(),
+        variables: variables_from_json(r.get("variables"))?,
+        created_at_utc: r.get("created_at_utc"),
+    })
+}
+
 #[async_trait]
 impl TemplateRepository for PgTemplateRepository {
     async fn create(&self, req: CreateTemplateRequest) -> Result<Uuid> {
-        let id = new_v7();
-        let now = Utc::now();
-        let format = req.format.unwrap_or_else(|| "markdown".to_string());
-        let default_tags: Vec<String> = req.default_tags.unwrap_or_default();
-
-        sqlx::query(
-            r#"
-            INSERT INTO note_template (id, name, description, content, format, default_tags, collection_id, created_at_utc, updated_at_utc)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            "#,
-        )
-        .bind(id)
-        .bind(&req.name)
-        .bind(&req.description)
-        .bind(&req.content)
-        .bind(&format)
-        .bind(&default_tags)
-        .bind(req.collection_id)
-        .bind(now)
-        .bind(now)
-        .execute(&self.pool)
-        .await
-        .map_err(Error::Database)?;
-
+        let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        let id = self.create_tx(&mut tx, req).await?;
+        tx.commit().await.map_err(Error::Database)?;
         Ok(id)
     }
 
     async fn get(&self, id: Uuid) -> Result<Option<NoteTemplate>> {
-        let row = sqlx::query(
-            r#"
-            SELECT id, name, description, content, format, default_tags, collection_id, created_at_utc, updated_at_utc
-            FROM note_template
-            WHERE id = $1
-            "#,
-        )
+        sqlx::query(&format!(
+            "SELECT {TEMPLATE_COLUMNS} FROM note_template WHERE id = $1"
+        ))
         .bind(id)
         .fetch_optional(&self.pool)
         .await
-        .map_err(Error::Database)?;
-
-        Ok(row.map(|r| NoteTemplate {
-            id: r.get("id"),
-            name: r.get("name"),
-            description: r.get("description"),
-            content: r.get("content"),
-            format: r.get("format"),
-            default_tags: r.get::<Vec<String>, _>("default_tags"),
-            collection_id: r.get("collection_id"),
-            created_at_utc: r.get("created_at_utc"),
-            updated_at_utc: r.get("updated_at_utc"),
-        }))
+        .map_err(Error::Database)?
+        .map(template_from_row)
+        .transpose()
     }
 
     async fn get_by_name(&self, name: &str) -> Result<Option<NoteTemplate>> {
-        let row = sqlx::query(
-            r#"
-            SELECT id, name, description, content, format, default_tags, collection_id, created_at_utc, updated_at_utc
-            FROM note_template
-            WHERE name = $1
-            "#,
-        )
+        sqlx::query(&format!(
+            "SELECT {TEMPLATE_COLUMNS} FROM note_template WHERE name = $1"
+        ))
         .bind(name)
         .fetch_optional(&self.pool)
         .await
-        .map_err(Error::Database)?;
-
-        Ok(row.map(|r| NoteTemplate {
-            id: r.get("id"),
-            name: r.get("name"),
-            description: r.get("description"),
-            content: r.get("content"),
-            format: r.get("format"),
-            default_tags: r.get::<Vec<String>, _>("default_tags"),
-            collection_id: r.get("collection_id"),
-            created_at_utc: r.get("created_at_utc"),
-            updated_at_utc: r.get("updated_at_utc"),
-        }))
+        .map_err(Error::Database)?
+        .map(template_from_row)
+        .transpose()
     }
 
     async fn list(&self) -> Result<Vec<NoteTemplate>> {
-        let rows = sqlx::query(
-            r#"
-            SELECT id, name, description, content, format, default_tags, collection_id, created_at_utc, updated_at_utc
-            FROM note_template
-            ORDER BY name
-            "#,
-        )
+        sqlx::query(&format!(
+            "SELECT {TEMPLATE_COLUMNS} FROM note_template ORDER BY name"
+        ))
         .fetch_all(&self.pool)
         .await
-        .map_err(Error::Database)?;
-
-        Ok(rows
-            .into_iter()
-            .map(|r| NoteTemplate {
-                id: r.get("id"),
-                name: r.get("name"),
-                description: r.get("description"),
-                content: r.get("content"),
-                format: r.get("format"),
-                default_tags: r.get::<Vec<String>, _>("default_tags"),
-                collection_id: r.get("collection_id"),
-                created_at_utc: r.get("created_at_utc"),
-                updated_at_utc: r.get("updated_at_utc"),
-            })
-            .collect())
+        .map_err(Error::Database)?
+        .into_iter()
+        .map(template_from_row)
+        .collect()
     }
 
     async fn update(&self, id: Uuid, req: UpdateTemplateRequest) -> Result<()> {
-        let now = Utc::now();
-
-        // Build dynamic update query
-        let mut updates = vec!["updated_at_utc = $1".to_string()];
-        let mut param_count = 2;
-
-        if req.name.is_some() {
-            updates.push(format!("name = ${}", param_count));
-            param_count += 1;
-        }
-        if req.description.is_some() {
-            updates.push(format!("description = ${}", param_count));
-            param_count += 1;
-        }
-        if req.content.is_some() {
-            updates.push(format!("content = ${}", param_count));
-            param_count += 1;
-        }
-        if req.default_tags.is_some() {
-            updates.push(format!("default_tags = ${}", param_count));
-            param_count += 1;
-        }
-        if req.collection_id.is_some() {
-            updates.push(format!("collection_id = ${}", param_count));
-            param_count += 1;
-        }
-
-        let query = format!(
-            "UPDATE note_template SET {} WHERE id = ${}",
-            updates.join(", "),
-            param_count
-        );
-
-        let mut q = sqlx::query(&query).bind(now);
-
-        if let Some(name) = &req.name {
-            q = q.bind(name);
-        }
-        if let Some(description) = &req.description {
-            q = q.bind(description);
-        }
-        if let Some(content) = &req.content {
-            q = q.bind(content);
-        }
-        if let Some(default_tags) = &req.default_tags {
-            q = q.bind(default_tags);
-        }
-        if let Some(collection_id) = &req.collection_id {
-            q = q.bind(*collection_id);
-        }
-
-        q.bind(id)
-            .execute(&self.pool)
-            .await
-            .map_err(Error::Database)?;
-
-        Ok(())
+        let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        self.update_tx(&mut tx, id, req).await?;
+        tx.commit().await.map_err(Error::Database)
     }
 
     async fn delete(&self, id: Uuid) -> Result<()> {
-        sqlx::query("DELETE FROM note_template WHERE id = $1")
-            .bind(id)
-            .execute(&self.pool)
-            .await
-            .map_err(Error::Database)?;
-        Ok(())
+        let mut tx = self.pool.begin().await.map_err(Error::Database)?;
+        self.delete_tx(&mut tx, id).await?;
+        tx.commit().await.map_err(Error::Database)
     }
 }
 
@@ -214,11 +146,12 @@ impl PgTemplateRepository {
         let now = Utc::now();
         let format = req.format.unwrap_or_else(|| "markdown".to_string());
         let default_tags: Vec<String> = req.default_tags.unwrap_or_default();
+        let variables = variables_to_json(&req.variables.unwrap_or_default())?;
 
         sqlx::query(
             r#"
-            INSERT INTO note_template (id, name, description, content, format, default_tags, collection_id, created_at_utc, updated_at_utc)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO note_template (id, name, description, content, format, default_tags, collection_id, variables, created_at_utc, updated_at_utc)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             "#,
         )
         .bind(id)
@@ -228,6 +161,7 @@ impl PgTemplateRepository {
         .bind(&format)
         .bind(&default_tags)
         .bind(req.collection_id)
+        .bind(variables)
         .bind(now)
         .bind(now)
         .execute(&mut **tx)
@@ -243,61 +177,34 @@ impl PgTemplateRepository {
         tx: &mut Transaction<'_, Postgres>,
         id: Uuid,
     ) -> Result<Option<NoteTemplate>> {
-        let row = sqlx::query(
-            r#"
-            SELECT id, name, description, content, format, default_tags, collection_id, created_at_utc, updated_at_utc
-            FROM note_template
-            WHERE id = $1
-            "#,
-        )
+        sqlx::query(&format!(
+            "SELECT {TEMPLATE_COLUMNS} FROM note_template WHERE id = $1"
+        ))
         .bind(id)
         .fetch_optional(&mut **tx)
         .await
-        .map_err(Error::Database)?;
-
-        Ok(row.map(|r| NoteTemplate {
-            id: r.get("id"),
-            name: r.get("name"),
-            description: r.get("description"),
-            content: r.get("content"),
-            format: r.get("format"),
-            default_tags: r.get::<Vec<String>, _>("default_tags"),
-            collection_id: r.get("collection_id"),
-            created_at_utc: r.get("created_at_utc"),
-            updated_at_utc: r.get("updated_at_utc"),
-        }))
+        .map_err(Error::Database)?
+        .map(template_from_row)
+        .transpose()
     }
 
     /// List templates within an existing transaction.
     pub async fn list_tx(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Vec<NoteTemplate>> {
-        let rows = sqlx::query(
-            r#"
-            SELECT id, name, description, content, format, default_tags, collection_id, created_at_utc, updated_at_utc
-            FROM note_template
-            ORDER BY name
-            "#,
-        )
+        sqlx::query(&format!(
+            "SELECT {TEMPLATE_COLUMNS} FROM note_template ORDER BY name"
+        ))
         .fetch_all(&mut **tx)
         .await
-        .map_err(Error::Database)?;
-
-        Ok(rows
-            .into_iter()
-            .map(|r| NoteTemplate {
-                id: r.get("id"),
-                name: r.get("name"),
-                description: r.get("description"),
-                content: r.get("content"),
-                format: r.get("format"),
-                default_tags: r.get::<Vec<String>, _>("default_tags"),
-                collection_id: r.get("collection_id"),
-                created_at_utc: r.get("created_at_utc"),
-                updated_at_utc: r.get("updated_at_utc"),
-            })
-            .collect())
+        .map_err(Error::Database)?
+        .into_iter()
+        .map(template_from_row)
+        .collect()
     }
 
     /// Update a template within an existing transaction.
+    ///
+    /// The current version is kept in `note_template_version` and the
+    /// template's version raised by one.
     pub async fn update_tx(
         &self,
         tx: &mut Transaction<'_, Postgres>,
@@ -306,7 +213,23 @@ impl PgTemplateRepository {
     ) -> Result<()> {
         let now = Utc::now();
 
-        let mut updates = vec!["updated_at_utc = $1".to_string()];
+        sqlx::query(&format!(
+            "INSERT INTO note_template_version ({TEMPLATE_VERSION_COLUMNS})
+             SELECT id, version, name, description, content, format, default_tags, variables,
+                    updated_at_utc
+             FROM note_template
+             WHERE id = $1
+             ON CONFLICT (template_id, version) DO NOTHING"
+        ))
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let mut updates = vec![
+            "updated_at_utc = $1".to_string(),
+            "version = version + 1".to_string(),
+        ];
         let mut param_count = 2;
 
         if req.name.is_some() {
@@ -329,6 +252,10 @@ impl PgTemplateRepository {
             updates.push(format!("collection_id = ${}", param_count));
             param_count += 1;
         }
+        if req.variables.is_some() {
+            updates.push(format!("variables = ${}", param_count));
+            param_count += 1;
+        }
 
         let query = format!(
             "UPDATE note_template SET {} WHERE id = ${}",
@@ -353,6 +280,9 @@ impl PgTemplateRepository {
         if let Some(collection_id) = &req.collection_id {
             q = q.bind(*collection_id);
         }
+        if let Some(variables) = &req.variables {
+            q = q.bind(variables_to_json(variables)?);
+        }
 
         q.bind(id)
             .execute(&mut **tx)
@@ -362,8 +292,14 @@ impl PgTemplateRepository {
         Ok(())
     }
 
-    /// Delete a template within an existing transaction.
+    /// Delete a template and its earlier versions within an existing
+    /// transaction.
     pub async fn delete_tx(&self, tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM note_template_version WHERE template_id = $1")
+            .bind(id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
         sqlx::query("DELETE FROM note_template WHERE id = $1")
             .bind(id)
             .execute(&mut **tx)
@@ -371,4 +307,53 @@ impl PgTemplateRepository {
             .map_err(Error::Database)?;
         Ok(())
     }
+
+    /// The versions a template's updates replaced, newest first.
+    pub async fn list_versions_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Vec<TemplateVersion>> {
+        sqlx::query(&format!(
+            "SELECT {TEMPLATE_VERSION_COLUMNS}
+             FROM note_template_version
+             WHERE template_id = $1
+             ORDER BY version DESC"
+        ))
+        .bind(id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .into_iter()
+        .map(version_from_row)
+        .collect()
+    }
+
+    /// A version of a template: an earlier one, or the current template as a
+    /// version.
+    pub async fn get_version_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        version: i32,
+    ) -> Result<Option<TemplateVersion>> {
+        sqlx::query(&format!(
+            "SELECT {TEMPLATE_VERSION_COLUMNS}
+             FROM note_template_version
+             WHERE template_id = $1 AND version = $2
+             UNION ALL
+             SELECT id, version, name, description, content, format, default_tags, variables,
+                    updated_at_utc
+             FROM note_template
+             WHERE id = $1 AND version = $2
+             LIMIT 1"
+        ))
+        .bind(id)
+        .bind(version)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        .map(version_from_row)
+        .transpose()
+    }
 }
@@ -0,0 +1,91 @@
+//! Tests for calendar feed queries.
+
+use crate::test_fixtures::{note_request, TestDatabase};
+use crate::PgReviewRepository;
+use chrono::{Days, Utc};
+use matric_core::calendar::CalendarWindow;
+use matric_core::task::parse_checkbox_tasks;
+use matric_core::{new_v7, CreateNoteRequest, CreateReviewCardRequest};
+
+#[tokio::test]
+async fn test_calendar_window_covers_reviews_tasks_and_notes() {
+    let test_db = TestDatabase::new().await;
+    let calendar = &test_db.db.calendar;
+    let reviews = PgReviewRepository::new(test_db.db.pool.clone());
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let today = Utc::now().date_naive();
+    let tomorrow = today.checked_add_days(Days::new(1)).unwrap();
+
+    let content = format!("- [ ] Send the budget due {tomorrow}\n- [ ] Someday\n");
+    let note_id = test_db
+        .db
+        .notes
+        .insert_tx(
+            &mut tx,
+            CreateNoteRequest {
+                title: Some("Budget".to_string()),
+                ..note_request(&content)
+            },
+        )
+        .await
+        .unwrap();
+    test_db
+        .db
+        .tasks
+        .replace_for_note_tx(&mut tx, note_id, &parse_checkbox_tasks(&content, today))
+        .await
+        .unwrap();
+    reviews
+        .create_card_tx(
+            &mut tx,
+            CreateReviewCardRequest {
+                note_id,
+                front: "Q".to_string(),
+                back: "A".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+
+    let window = CalendarWindow {
+        from: today,
+        until: today.checked_add_days(Days::new(7)).unwrap(),
+        collection_id: None,
+    };
+    let days = calendar
+        .review_days_tx(&mut tx, &window, None)
+        .await
+        .unwrap();
+    assert_eq!(days.len(), 1);
+    assert_eq!(days[0].day, today);
+    assert_eq!(days[0].cards, 1);
+
+    let tasks = calendar
+        .due_tasks_tx(&mut tx, &window, None, 10)
+        .await
+        .unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].due_date, tomorrow);
+    assert_eq!(tasks[0].note_title.as_deref(), Some("Budget"));
+
+    let notes = calendar
+        .note_creations_tx(&mut tx, &window, None, 10)
+        .await
+        .unwrap();
+    assert!(notes.iter().any(|n| n.note_id == note_id));
+
+    let elsewhere = CalendarWindow {
+        collection_id: Some(new_v7()),
+        ..window
+    };
+    assert!(calendar
+        .review_days_tx(&mut tx, &elsewhere)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(calendar
+        .due_tasks_tx(&mut tx, &elsewhere, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,148 @@
+//! Tests for citation references and collection bibliographies.
+
+use crate::test_fixtures::TestDatabase;
+use crate::{CollectionRepository, CreateNoteRequest, NoteRepository, PgReferenceRepository};
+use matric_core::{extract_citations, CitationKind, ReferenceMetadata};
+use uuid::Uuid;
+
+async fn create_note(test_db: &TestDatabase, content: &str, collection_id: Option<Uuid>) -> Uuid {
+    test_db
+        .db
+        .notes
+        .insert(CreateNoteRequest {
+            content: content.to_string(),
+            format: "markdown".to_string(),
+            source: "test".to_string(),
+            collection_id,
+            tags: None,
+            metadata: None,
+            document_type_id: None,
+            title: None,
+        })
+        .await
+        .expect("create note")
+}
+
+#[tokio::test]
+async fn test_references_are_shared_and_orphans_removed() {
+    let test_db = TestDatabase::new().await;
+    let doi = format!("10.9999/{}", Uuid::new_v4().simple());
+    let url = format!("https://example.com/{}", Uuid::new_v4().simple());
+    let a = create_note(&test_db, "A", None).await;
+    let b = create_note(&test_db, "B", None).await;
+    let references = PgReferenceRepository::new(test_db.db.pool.clone());
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+
+    let first = extract_citations(&format!("doi:{doi} and {url}, again doi:{doi}"));
+    assert_eq!(
+        references
+            .replace_for_note_tx(&mut tx, a, &first)
+            .await
+            .unwrap(),
+        2
+    );
+    let second = extract_citations(&format!(
+        "@article{{key1, title = {{Shared Work}}, doi = {{{doi}}}}}"
+    ));
+    references
+        .replace_for_note_tx(&mut tx, b, &second)
+        .await
+        .unwrap();
+
+    // Both notes point at one reference; the BibTeX title filled the gap
+    let cited_by_a = references.list_for_note_tx(&mut tx, a).await.unwrap();
+    let cited_by_b = references.list_for_note_tx(&mut tx, b).await.unwrap();
+    assert_eq!(cited_by_a[0].kind, CitationKind::Doi);
+    assert_eq!(cited_by_a[0].occurrences, 2);
+    assert_eq!(cited_by_a[0].id, cited_by_b[0].id);
+    assert_eq!(cited_by_b[0].metadata.title.as_deref(), Some("Shared Work"));
+    assert_eq!(cited_by_b[0].bibtex_key.as_deref(), Some("key1"));
+
+    // Resolving keeps known fields the resolver lacks
+    let unresolved = references.unresolved_dois_tx(&mut tx, a, 10).await.unwrap();
+    assert_eq!(unresolved, vec![(cited_by_a[0].id, doi.clone())]);
+    let metadata = ReferenceMetadata {
+        year: Some(2020),
+        ..Default::default()
+    };
+    references
+        .set_resolved_tx(&mut tx, unresolved[0].0, &metadata, chrono::Utc::now())
+        .await
+        .unwrap();
+    let resolved = &references.list_for_note_tx(&mut tx, b).await.unwrap()[0];
+    assert_eq!(resolved.metadata.year, Some(2020));
+    assert_eq!(resolved.metadata.title.as_deref(), Some("Shared Work"));
+    assert!(resolved.resolved_at_utc.is_some());
+
+    // Dropping the URL from note A deletes its reference
+    references
+        .replace_for_note_tx(&mut tx, a, &extract_citations(&format!("doi:{doi}")))
+        .await
+        .unwrap();
+    let orphans: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM reference WHERE identifier = $1")
+        .bind(&url)
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+    assert_eq!(orphans, 0);
+}
+
+#[tokio::test]
+async fn test_collection_bibliography_covers_subcollections() {
+    let test_db = TestDatabase::new().await;
+    let collections = &test_db.db.collections;
+    let parent = collections
+        .create(&format!("bib-{}", Uuid::new_v4()), None, None)
+        .await
+        .unwrap();
+    let child = collections
+        .create(&format!("bib-child-{}", Uuid::new_v4()), None, Some(parent))
+        .await
+        .unwrap();
+    let doi = format!("10.9999/{}", Uuid::new_v4().simple());
+    let in_parent = create_note(&test_db, "P", Some(parent)).await;
+    let in_child = create_note(&test_db, "C", Some(child)).await;
+    let outside = create_note(&test_db, "O", None).await;
+    let references = PgReferenceRepository::new(test_db.db.pool.clone());
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+
+    let cited = extract_citations(&format!("doi:{doi}"));
+    for note_id in [in_parent, in_child, outside] {
+        references
+            .replace_for_note_tx(&mut tx, note_id, &cited)
+            .await
+            .unwrap();
+    }
+    references
+        .replace_for_note_tx(
+            &mut tx,
+            in_child,
+            &extract_citations(&format!("doi:{doi} arXiv:2101.00001")),
+        )
+        .await
+        .unwrap();
+
+    let bibliography = references
+        .list_for_collection_tx(&mut tx, parent, None)
+        .await
+        .unwrap();
+    let doi_entry = bibliography
+        .iter()
+        .find(|reference| reference.identifier == doi)
+        .unwrap();
+    assert_eq!(doi_entry.occurrences, 2);
+    assert!(bibliography
+        .iter()
+        .any(|reference| reference.kind == CitationKind::Arxiv));
+
+    let child_only = references
+        .list_for_collection_tx(&mut tx, child, None)
+        .await
+        .unwrap();
+    assert_eq!(child_only.len(), 2);
+
+    assert!(references
+        .list_for_collection_tx(&mut tx, Uuid::new_v4(), None)
+        .await
+        .is_err());
+}
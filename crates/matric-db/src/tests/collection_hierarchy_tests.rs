@@ -955,3 +955,151 @@ async fn test_update_preserves_parent_id() {
 
     test_db.cleanup().await;
 }
+
+// =============================================================================
+// Subtree Copy and Re-parent Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_copy_subtree_duplicates_collections_and_notes() {
+    let test_db = TestDatabase::new().await;
+    let collections = &test_db.db.collections;
+    let notes = &test_db.db.notes;
+
+    // src → child (with a note), dest (separate)
+    let src_name = unique_name("cp-src");
+    let src_id = collections.create(&src_name, None, None).await.unwrap();
+    let child_id = collections
+        .create(&unique_name("cp-child"), None, Some(src_id))
+        .await
+        .unwrap();
+    let dest_id = collections
+        .create(&unique_name("cp-dest"), None, None)
+        .await
+        .unwrap();
+    let note_id = notes
+        .insert(CreateNoteRequest {
+            content: "Note to copy".to_string(),
+            format: "markdown".to_string(),
+            source: "test".to_string(),
+            collection_id: Some(child_id),
+            tags: Some(vec!["copy-test".to_string()]),
+            metadata: None,
+            document_type_id: None,
+            title: None,
+        })
+        .await
+        .unwrap();
+
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let (result, note_ids) = collections
+        .copy_subtree_tx(
+            &mut tx,
+            src_id,
+            Some(dest_id),
+            matric_core::CollectionCopyMode::Link,
+            None,
+            None,
+        )
+        .await
+        .expect("copy subtree");
+    tx.commit().await.unwrap();
+
+    assert_eq!(result.collections_copied, 2);
+    assert_eq!(result.notes_copied, 1);
+    assert_eq!(note_ids.len(), 1);
+    assert_ne!(note_ids[0], note_id, "the note should be copied, not moved");
+
+    let copy = collections
+        .get(result.collection_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(copy.name, format!("{src_name} (copy)"));
+    assert_eq!(copy.parent_id, Some(dest_id));
+
+    // The source note stays where it was
+    let original = notes.fetch(note_id).await.unwrap();
+    assert_eq!(original.note.collection_id, Some(child_id));
+
+    test_db.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_copy_subtree_into_own_descendant_fails() {
+    let test_db = TestDatabase::new().await;
+    let collections = &test_db.db.collections;
+
+    let a_id = collections
+        .create(&unique_name("cpc-a"), None, None)
+        .await
+        .unwrap();
+    let b_id = collections
+        .create(&unique_name("cpc-b"), None, Some(a_id))
+        .await
+        .unwrap();
+
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let result = collections
+        .copy_subtree_tx(
+            &mut tx,
+            a_id,
+            Some(b_id),
+            matric_core::CollectionCopyMode::Duplicate,
+            None,
+            None,
+        )
+        .await;
+    assert!(result.is_err(), "copying into own subtree should fail");
+
+    test_db.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_reparent_collections_is_all_or_nothing() {
+    let test_db = TestDatabase::new().await;
+    let collections = &test_db.db.collections;
+
+    // A → B, C (root), D (root)
+    let a_id = collections
+        .create(&unique_name("rp-a"), None, None)
+        .await
+        .unwrap();
+    let b_id = collections
+        .create(&unique_name("rp-b"), None, Some(a_id))
+        .await
+        .unwrap();
+    let c_id = collections
+        .create(&unique_name("rp-c"), None, None)
+        .await
+        .unwrap();
+    let d_id = collections
+        .create(&unique_name("rp-d"), None, None)
+        .await
+        .unwrap();
+
+    // Moving A under B would create a cycle, so C must not move either
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let result = collections
+        .reparent_collections_tx(&mut tx, &[c_id, a_id], Some(b_id))
+        .await;
+    assert!(result.is_err(), "a cycle should fail the whole batch");
+    drop(tx);
+    let c = collections.get(c_id).await.unwrap().unwrap();
+    assert!(c.parent_id.is_none(), "C should still be a root collection");
+
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let (result, moved) = collections
+        .reparent_collections_tx(&mut tx, &[c_id, d_id, b_id], Some(a_id))
+        .await
+        .expect("reparent");
+    tx.commit().await.unwrap();
+    assert_eq!(result.moved, 2);
+    assert_eq!(result.unchanged, 1, "B was already under A");
+    assert_eq!(moved.len(), 2);
+
+    let d = collections.get(d_id).await.unwrap().unwrap();
+    assert_eq!(d.parent_id, Some(a_id));
+
+    test_db.cleanup().await;
+}
@@ -0,0 +1,87 @@
+//! Tests for metadata derived from note content.
+
+use crate::test_fixtures::{note_request, TestDatabase};
+use matric_core::note_metadata::MetadataFieldFilter;
+use matric_core::{CreateNoteRequest, DerivedField, ListNotesRequest};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_derived_metadata_is_stored_filtered_and_sorted() {
+    let test_db = TestDatabase::new().await;
+    let notes = &test_db.db.notes;
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+
+    let marker = uuid::Uuid::new_v4().to_string();
+    let request = |content: String| CreateNoteRequest {
+        metadata: Some(json!({"run": marker, "status": "kept"})),
+        ..note_request(&content)
+    };
+    let short = notes
+        .insert_tx(&mut tx, request("The fix works, great result.".to_string()))
+        .await
+        .unwrap();
+    let long = notes
+        .insert_tx(&mut tx, request("word ".repeat(450)))
+        .await
+        .unwrap();
+    let underived = notes
+        .insert_tx(&mut tx, request("Not derived".to_string()))
+        .await
+        .unwrap();
+
+    let fields = [
+        DerivedField::WordCount,
+        DerivedField::ReadingTime,
+        DerivedField::Sentiment,
+    ];
+    let derived = notes
+        .refresh_derived_metadata_tx(&mut tx, short, &fields)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(derived.word_count, Some(5));
+    assert_eq!(derived.reading_time_minutes, Some(1));
+    assert_eq!(derived.sentiment, Some(1.0));
+    notes
+        .refresh_derived_metadata_tx(&mut tx, long, &fields)
+        .await
+        .unwrap();
+
+    // Other metadata keys survive.
+    let note = notes.fetch_tx(&mut tx, short).await.unwrap();
+    assert_eq!(note.note.metadata["status"], "kept");
+    assert_eq!(note.note.metadata["derived"]["word_count"], 5);
+
+    let list = |filters: &[(&str, &str)], sort_order: &str| {
+        let mut metadata = vec![MetadataFieldFilter::parse("metadata.run", &marker).unwrap()];
+        for (key, value) in filters {
+            metadata.push(MetadataFieldFilter::parse(key, value).unwrap());
+        }
+        ListNotesRequest {
+            metadata,
+            sort_by: Some("word_count".to_string()),
+            sort_order: Some(sort_order.to_string()),
+            ..Default::default()
+        }
+    };
+    let ids = |response: matric_core::ListNotesResponse| {
+        response.notes.iter().map(|n| n.id).collect::<Vec<_>>()
+    };
+
+    let response = notes
+        .list_tx(
+            &mut tx,
+            list(
+                &[("metadata.derived.reading_time_minutes[gte]", "3")],
+                "desc",
+            ),
+        )
+        .await
+        .unwrap();
+    assert_eq!(ids(response), vec![long]);
+
+    let response = notes.list_tx(&mut tx, list(&[], "asc")).await.unwrap();
+    assert_eq!(ids(response), vec![short, long, underived]);
+    let response = notes.list_tx(&mut tx, list(&[], "desc")).await.unwrap();
+    assert_eq!(ids(response), vec![long, short, underived]);
+}
@@ -0,0 +1,67 @@
+//! Tests for graph diffs between two points in time.
+
+use std::time::Duration;
+
+use crate::test_fixtures::TestDatabase;
+use crate::{CreateNoteRequest, LinkRepository, NoteRepository};
+use chrono::Utc;
+use uuid::Uuid;
+
+async fn create_note(test_db: &TestDatabase, content: &str) -> Uuid {
+    test_db
+        .db
+        .notes
+        .insert(CreateNoteRequest {
+            content: content.to_string(),
+            format: "markdown".to_string(),
+            source: "test".to_string(),
+            collection_id: None,
+            tags: None,
+            metadata: None,
+            document_type_id: None,
+            title: None,
+        })
+        .await
+        .expect("create note")
+}
+
+#[tokio::test]
+async fn test_graph_diff_reports_new_notes_and_links() {
+    let test_db = TestDatabase::new().await;
+    let a = create_note(&test_db, "Existing note").await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let before = Utc::now();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let b = create_note(&test_db, "New note").await;
+    test_db
+        .db
+        .links
+        .create(a, b, "semantic", 0.9, None)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let after = Utc::now();
+
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let diff = test_db
+        .db
+        .links
+        .graph_diff_tx(&mut tx, before, after, 1000)
+        .await
+        .expect("diff");
+    drop(tx);
+
+    // Other tests may write to the same database, so look for these changes
+    // rather than exact totals.
+    assert!(diff.to.note_count > diff.from.note_count);
+    assert!(diff.notes_added.iter().any(|note| note.id == b));
+    assert!(!diff.notes_added.iter().any(|note| note.id == a));
+    assert!(diff
+        .links_added
+        .iter()
+        .any(|link| link.from_note_id == a && link.to_note_id == b));
+    assert!(!diff.links_removed.iter().any(|link| link.from_note_id == a));
+
+    test_db.cleanup().await;
+}
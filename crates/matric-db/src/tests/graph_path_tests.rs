@@ -0,0 +1,92 @@
+//! Tests for note shortest paths across links, concepts and collections.
+
+use crate::test_fixtures::TestDatabase;
+use crate::{CollectionRepository, CreateNoteRequest, LinkRepository, NoteRepository};
+use matric_core::NotePathEdgeType;
+use uuid::Uuid;
+
+async fn create_note(test_db: &TestDatabase, content: &str, collection_id: Option<Uuid>) -> Uuid {
+    test_db
+        .db
+        .notes
+        .insert(CreateNoteRequest {
+            content: content.to_string(),
+            format: "markdown".to_string(),
+            source: "test".to_string(),
+            collection_id,
+            tags: None,
+            metadata: None,
+            document_type_id: None,
+            title: None,
+        })
+        .await
+        .expect("create note")
+}
+
+#[tokio::test]
+async fn test_path_crosses_link_then_collection() {
+    let test_db = TestDatabase::new().await;
+    let collection_id = test_db
+        .db
+        .collections
+        .create(&format!("path-{}", Uuid::new_v4()), None, None)
+        .await
+        .unwrap();
+
+    // A → B by link; B and C share a collection
+    let a = create_note(&test_db, "Path start", None).await;
+    let b = create_note(&test_db, "Path middle", Some(collection_id)).await;
+    let c = create_note(&test_db, "Path end", Some(collection_id)).await;
+    test_db
+        .db
+        .links
+        .create(b, a, "semantic", 0.8, None)
+        .await
+        .unwrap();
+
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let path = test_db
+        .db
+        .links
+        .note_path_tx(&mut tx, a, c, &NotePathEdgeType::ALL, 4, None)
+        .await
+        .expect("path");
+    assert!(path.found);
+    assert_eq!(path.hops, 2);
+    let ids: Vec<Uuid> = path.notes.iter().map(|note| note.id).collect();
+    assert_eq!(ids, vec![a, b, c]);
+    assert_eq!(path.steps[0].edge_type, NotePathEdgeType::Link);
+    assert!(path.steps[0].reversed, "the link points from B to A");
+    assert_eq!(path.steps[1].edge_type, NotePathEdgeType::Collection);
+    assert_eq!(path.steps[1].collection_id, Some(collection_id));
+
+    // Links alone cannot reach C
+    let path = test_db
+        .db
+        .links
+        .note_path_tx(&mut tx, a, c, &[NotePathEdgeType::Link], 4, None)
+        .await
+        .expect("path");
+    assert!(!path.found);
+    assert!(path.steps.is_empty());
+    drop(tx);
+
+    test_db.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_path_to_missing_note_is_not_found() {
+    let test_db = TestDatabase::new().await;
+    let a = create_note(&test_db, "Lonely note", None).await;
+
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let result = test_db
+        .db
+        .links
+        .note_path_tx(&mut tx, a, Uuid::new_v4(), &NotePathEdgeType::ALL, 4, None)
+        .await;
+    assert!(matches!(result, Err(matric_core::Error::NotFound(_))));
+    drop(tx);
+
+    test_db.cleanup().await;
+}
@@ -0,0 +1,174 @@
+//! Tests for journal settings and the links from daily notes.
+
+use crate::test_fixtures::{note_request, TestDatabase};
+use matric_core::journal::JOURNAL_LINK_KIND;
+use matric_core::JournalSettingsRequest;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_journal_note_links_notes_created_that_day() {
+    let test_db = TestDatabase::new().await;
+    let journal = &test_db.db.journal;
+    let notes = &test_db.db.notes;
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+
+    let earlier = notes
+        .insert_tx(&mut tx, note_request("Written before the journal note"))
+        .await
+        .unwrap();
+    let journal_note = notes
+        .insert_tx(&mut tx, note_request("# Today"))
+        .await
+        .unwrap();
+    let day = journal.today_tx(&mut tx, "UTC").await.unwrap();
+    assert!(journal
+        .entry_tx(&mut tx, day, None)
+        .await
+        .unwrap()
+        .is_none());
+    journal
+        .set_entry_tx(&mut tx, day, None, journal_note)
+        .await
+        .unwrap();
+    assert_eq!(
+        journal.entry_tx(&mut tx, day, None).await.unwrap(),
+        Some(journal_note)
+    );
+
+    assert!(
+        journal
+            .link_day_tx(&mut tx, journal_note, day, "UTC", None)
+            .await
+            .unwrap()
+            >= 1
+    );
+    assert_eq!(
+        journal
+            .link_day_tx(&mut tx, journal_note, day, "UTC", None)
+            .await
+            .unwrap(),
+        0
+    );
+
+    let later = notes
+        .insert_tx(&mut tx, note_request("Written after the journal note"))
+        .await
+        .unwrap();
+    assert!(journal.link_note_tx(&mut tx, later).await.unwrap());
+    assert!(!journal.link_note_tx(&mut tx, later).await.unwrap());
+    assert!(!journal.link_note_tx(&mut tx, journal_note).await.unwrap());
+
+    let linked: Vec<Uuid> =
+        sqlx::query_scalar("SELECT to_note_id FROM link WHERE from_note_id = $1 AND kind = $2")
+            .bind(journal_note)
+            .bind(JOURNAL_LINK_KIND)
+            .fetch_all(&mut *tx)
+            .await
+            .unwrap();
+    assert!(linked.contains(&earlier));
+    assert!(linked.contains(&later));
+    assert!(!linked.contains(&journal_note));
+}
+
+#[tokio::test]
+async fn test_journal_notes_are_per_owner_and_link_only_owned_notes() {
+    let test_db = TestDatabase::new().await;
+    let journal = &test_db.db.journal;
+    let notes = &test_db.db.notes;
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+
+    let alice_note = notes
+        .insert_tx(&mut tx, note_request("Alice's private note"))
+        .await
+        .unwrap();
+    let bob_note = notes
+        .insert_tx(&mut tx, note_request("Bob's private note"))
+        .await
+        .unwrap();
+    let alice_journal = notes
+        .insert_tx(&mut tx, note_request("# Alice's day"))
+        .await
+        .unwrap();
+    for (note_id, owner) in [(alice_note, alice), (alice_journal, alice), (bob_note, bob)] {
+        sqlx::query("UPDATE note SET owner_id = $2 WHERE id = $1")
+            .bind(note_id)
+            .bind(owner)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+    }
+
+    let day = journal.today_tx(&mut tx, "UTC").await.unwrap();
+    journal
+        .set_entry_tx(&mut tx, day, Some(alice), alice_journal)
+        .await
+        .unwrap();
+    assert_eq!(
+        journal.entry_tx(&mut tx, day, Some(alice)).await.unwrap(),
+        Some(alice_journal)
+    );
+    assert!(journal
+        .entry_tx(&mut tx, day, Some(bob))
+        .await
+        .unwrap()
+        .is_none());
+    assert!(journal
+        .entry_tx(&mut tx, day, None)
+        .await
+        .unwrap()
+        .is_none());
+
+    assert_eq!(
+        journal
+            .link_day_tx(&mut tx, alice_journal, day, "UTC", Some(alice))
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(!journal.link_note_tx(&mut tx, bob_note).await.unwrap());
+
+    let linked: Vec<Uuid> =
+        sqlx::query_scalar("SELECT to_note_id FROM link WHERE from_note_id = $1 AND kind = $2")
+            .bind(alice_journal)
+            .bind(JOURNAL_LINK_KIND)
+            .fetch_all(&mut *tx)
+            .await
+            .unwrap();
+    assert_eq!(linked, vec![alice_note]);
+}
+
+#[tokio::test]
+async fn test_journal_settings_reject_unknown_time_zones() {
+    let test_db = TestDatabase::new().await;
+    let journal = &test_db.db.journal;
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+
+    let settings = journal.settings_tx(&mut tx).await.unwrap();
+    assert!(settings.updated_at_utc.is_none());
+
+    let request = JournalSettingsRequest {
+        timezone: Some("Mars/Olympus_Mons".to_string()),
+        ..Default::default()
+    };
+    let settings = request.into_settings().unwrap();
+    assert!(journal.save_settings_tx(&mut tx, &settings).await.is_err());
+
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let request = JournalSettingsRequest {
+        title_format: Some("Journal %Y-%m-%d".to_string()),
+        timezone: Some("Europe/Berlin".to_string()),
+        link_notes: Some(false),
+        ..Default::default()
+    };
+    let settings = request.into_settings().unwrap();
+    let saved = journal.save_settings_tx(&mut tx, &settings).await.unwrap();
+    assert_eq!(saved.title_format, "Journal %Y-%m-%d");
+    assert_eq!(saved.timezone, "Europe/Berlin");
+    assert!(!saved.link_notes);
+    assert!(saved.updated_at_utc.is_some());
+    assert_eq!(
+        journal.settings_tx(&mut tx).await.unwrap().timezone,
+        "Europe/Berlin"
+    );
+}
@@ -0,0 +1,121 @@
+//! Tests for link suggestions and the feedback that tunes linking.
+
+use crate::test_fixtures::TestDatabase;
+use crate::{CreateNoteRequest, LinkRepository, NoteRepository, PgLinkSuggestionRepository};
+use matric_core::link_suggestion::adjusted_link_threshold_offset;
+use matric_core::LinkSuggestionReason;
+use uuid::Uuid;
+
+async fn create_note(test_db: &TestDatabase, content: &str) -> Uuid {
+    test_db
+        .db
+        .notes
+        .insert(CreateNoteRequest {
+            content: content.to_string(),
+            format: "markdown".to_string(),
+            source: "test".to_string(),
+            collection_id: None,
+            tags: None,
+            metadata: None,
+            document_type_id: None,
+            title: None,
+        })
+        .await
+        .expect("create note")
+}
+
+#[tokio::test]
+async fn test_co_cited_note_is_suggested_until_rejected() {
+    let test_db = TestDatabase::new().await;
+
+    // D cites both A and B, so B is suggested for A; D is already linked
+    let a = create_note(&test_db, "Suggestion source").await;
+    let b = create_note(&test_db, "Co-cited note").await;
+    let d = create_note(&test_db, "Citing note").await;
+    for target in [a, b] {
+        test_db
+            .db
+            .links
+            .create(d, target, "semantic", 0.8, None)
+            .await
+            .unwrap();
+    }
+
+    let suggestions_repo = PgLinkSuggestionRepository::new(test_db.db.pool.clone());
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let suggestions = suggestions_repo
+        .suggestions_tx(&mut tx, a, 10, None)
+        .await
+        .expect("suggestions");
+    let suggestion = suggestions
+        .suggestions
+        .iter()
+        .find(|suggestion| suggestion.note_id == b)
+        .expect("co-cited note suggested");
+    assert_eq!(suggestion.co_citations, 1);
+    assert!(suggestion
+        .reasons
+        .contains(&LinkSuggestionReason::CoCitation));
+    assert!(suggestions.suggestions.iter().all(|s| s.note_id != d));
+
+    let before = suggestions_repo.tuning_tx(&mut tx).await.unwrap();
+    let feedback = suggestions_repo
+        .feedback_tx(&mut tx, a, b, false, Some("tester"), None)
+        .await
+        .expect("reject");
+    assert!(!feedback.link_created);
+    assert_eq!(
+        feedback.threshold.offset,
+        adjusted_link_threshold_offset(before.offset, false)
+    );
+    assert_eq!(feedback.threshold.rejected_count, before.rejected_count + 1);
+
+    let suggestions = suggestions_repo
+        .suggestions_tx(&mut tx, a, 10, None)
+        .await
+        .expect("suggestions");
+    assert!(suggestions.suggestions.iter().all(|s| s.note_id != b));
+    drop(tx);
+
+    test_db.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_accepting_suggestion_links_both_ways() {
+    let test_db = TestDatabase::new().await;
+    let a = create_note(&test_db, "Accept source").await;
+    let b = create_note(&test_db, "Accept target").await;
+
+    let suggestions_repo = PgLinkSuggestionRepository::new(test_db.db.pool.clone());
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let feedback = suggestions_repo
+        .feedback_tx(&mut tx, a, b, true, None, None)
+        .await
+        .expect("accept");
+    assert!(feedback.link_created);
+    let linked: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM link
+         WHERE (from_note_id = $1 AND to_note_id = $2) OR (from_note_id = $2 AND to_note_id = $1)",
+    )
+    .bind(a)
+    .bind(b)
+    .fetch_one(&mut *tx)
+    .await
+    .unwrap();
+    assert_eq!(linked, 2);
+
+    // Accepting again keeps the existing links
+    let again = suggestions_repo
+        .feedback_tx(&mut tx, b, a, true, None, None)
+        .await
+        .expect("accept again");
+    assert!(!again.link_created);
+
+    let missing = suggestions_repo
+        .feedback_tx(&mut tx, a, Uuid::new_v4(), true, None, None)
+        .await;
+    assert!(matches!(missing, Err(matric_core::Error::NotFound(_))));
+    drop(tx);
+
+    test_db.cleanup().await;
+}
@@ -1,5 +1,21 @@
 //! Integration tests for matric-db
 
+mod calendar_tests;
+mod citation_tests;
 mod collection_hierarchy_tests;
+mod derived_metadata_tests;
 mod embedding_pipeline_tests;
+mod graph_diff_tests;
+mod graph_path_tests;
+mod journal_tests;
+mod link_suggestion_tests;
+mod note_bulk_tests;
+mod note_metadata_tests;
+mod note_view_tests;
 mod oauth_token_lifetime_tests;
+mod pinned_tests;
+mod publishing_tests;
+mod resurface_tests;
+mod task_tests;
+mod template_version_tests;
+mod typed_link_tests;
@@ -0,0 +1,113 @@
+//! Tests for filtered bulk note updates.
+
+use crate::test_fixtures::{note_request, TestDatabase};
+use matric_core::{BulkNoteFilter, BulkNotePatch, CreateNoteRequest};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_bulk_update_matches_filter_and_applies_patch() {
+    let test_db = TestDatabase::new().await;
+    let db = &test_db.db;
+    let mut tx = db.pool.begin().await.unwrap();
+
+    let marker = format!("bulk-{}", uuid::Uuid::new_v4().simple());
+    let request = |content: &str| CreateNoteRequest {
+        metadata: Some(json!({"status": "open"})),
+        ..note_request(content)
+    };
+    let child = db.notes.insert_tx(&mut tx, request("First")).await.unwrap();
+    let parent = db
+        .notes
+        .insert_tx(&mut tx, request("Second"))
+        .await
+        .unwrap();
+    let untagged = db.notes.insert_tx(&mut tx, request("Third")).await.unwrap();
+    db.tags
+        .add_to_notes_tx(&mut tx, &[child], &format!("{marker}/child"), "test")
+        .await
+        .unwrap();
+    db.tags
+        .add_to_notes_tx(&mut tx, &[parent], &marker, "test")
+        .await
+        .unwrap();
+
+    let filter = BulkNoteFilter {
+        tags: vec![marker.clone()],
+        ..Default::default()
+    };
+    let mut matched = db
+        .bulk_notes
+        .matching_note_ids_tx(&mut tx, &filter, None)
+        .await
+        .unwrap();
+    matched.sort();
+    let mut expected = vec![child, parent];
+    expected.sort();
+    assert_eq!(matched, expected);
+    assert!(!matched.contains(&untagged));
+
+    let collection = db
+        .collections
+        .create_tx(&mut tx, &marker, None, None)
+        .await
+        .unwrap();
+    let patch = BulkNotePatch {
+        add_tags: vec!["reviewed".to_string()],
+        remove_tags: vec![marker.clone()],
+        collection_id: Some(collection),
+        metadata: Some(json!({"status": "done"})),
+        archived: Some(true),
+    };
+    let result = db
+        .bulk_notes
+        .apply_tx(&mut tx, &matched, &patch)
+        .await
+        .unwrap();
+    assert_eq!((result.matched, result.updated, result.failed), (2, 2, 0));
+
+    let note = db.notes.fetch_tx(&mut tx, parent).await.unwrap();
+    assert!(note.note.archived);
+    assert_eq!(note.note.collection_id, Some(collection));
+    assert_eq!(note.note.metadata["status"], "done");
+    let tags = db.tags.get_for_note_tx(&mut tx, parent).await.unwrap();
+    assert_eq!(tags, vec!["reviewed".to_string()]);
+    // Only the exact tag is removed; children stay.
+    let tags = db.tags.get_for_note_tx(&mut tx, child).await.unwrap();
+    assert!(tags.contains(&format!("{marker}/child")));
+
+    // Applying the same patch again changes nothing further.
+    let again = db
+        .bulk_notes
+        .apply_tx(&mut tx, &matched, &patch)
+        .await
+        .unwrap();
+    assert_eq!(again.updated, 2);
+    let filter = BulkNoteFilter {
+        collection_id: Some(collection),
+        ..Default::default()
+    };
+    let in_collection = db
+        .bulk_notes
+        .matching_note_ids_tx(&mut tx, &filter, None)
+        .await
+        .unwrap();
+    assert_eq!(in_collection.len(), 2);
+}
+
+#[tokio::test]
+async fn test_bulk_update_rejects_unknown_collection() {
+    let test_db = TestDatabase::new().await;
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+
+    let patch = BulkNotePatch {
+        collection_id: Some(uuid::Uuid::new_v4()),
+        ..Default::default()
+    };
+    let err = test_db
+        .db
+        .bulk_notes
+        .check_patch_tx(&mut tx, &patch)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, matric_core::Error::NotFound(_)));
+}
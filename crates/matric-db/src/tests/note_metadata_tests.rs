@@ -0,0 +1,105 @@
+//! Tests for document type metadata schemas and metadata filters.
+
+use crate::test_fixtures::{note_request, TestDatabase};
+use matric_core::note_metadata::MetadataFieldFilter;
+use matric_core::{
+    CreateDocumentTypeRequest, CreateNoteRequest, ListNotesRequest, UpdateNoteStatusRequest,
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_metadata_schema_and_typed_filters() {
+    let test_db = TestDatabase::new().await;
+    let notes = &test_db.db.notes;
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+
+    let doc_type: CreateDocumentTypeRequest = serde_json::from_value(json!({
+        "name": format!("metadata-schema-{}", uuid::Uuid::new_v4()),
+        "category": "custom",
+        "metadata_schema": {
+            "type": "object",
+            "properties": {"status": {"enum": ["active", "done"]}}
+        }
+    }))
+    .unwrap();
+    let doc_type_id = test_db
+        .db
+        .document_types
+        .create_tx(&mut tx, doc_type)
+        .await
+        .unwrap();
+
+    let request = |marker: &str, metadata| CreateNoteRequest {
+        metadata: Some(metadata),
+        document_type_id: Some(doc_type_id),
+        ..note_request(&format!("Metadata note {marker}"))
+    };
+    let marker = uuid::Uuid::new_v4().to_string();
+    let active = notes
+        .insert_tx(
+            &mut tx,
+            request("a", json!({"status": "active", "score": 5, "run": marker})),
+        )
+        .await
+        .unwrap();
+    let done = notes
+        .insert_tx(
+            &mut tx,
+            request("b", json!({"status": "done", "score": 2.5, "run": marker})),
+        )
+        .await
+        .unwrap();
+
+    let list = |filters: &[(&str, &str)]| {
+        let mut metadata = vec![MetadataFieldFilter::parse("metadata.run", &marker).unwrap()];
+        for (key, value) in filters {
+            metadata.push(MetadataFieldFilter::parse(key, value).unwrap());
+        }
+        ListNotesRequest {
+            metadata,
+            ..Default::default()
+        }
+    };
+    let ids = |response: matric_core::ListNotesResponse| {
+        response.notes.iter().map(|n| n.id).collect::<Vec<_>>()
+    };
+
+    let response = notes
+        .list_tx(&mut tx, list(&[("metadata.status", "active")]))
+        .await
+        .unwrap();
+    assert_eq!(response.total, 1);
+    assert_eq!(ids(response), vec![active]);
+
+    let response = notes
+        .list_tx(&mut tx, list(&[("metadata.score[gte]", "3")]))
+        .await
+        .unwrap();
+    assert_eq!(ids(response), vec![active]);
+
+    let response = notes
+        .list_tx(&mut tx, list(&[("metadata.score", "2.50")]))
+        .await
+        .unwrap();
+    assert_eq!(ids(response), vec![done]);
+
+    let response = notes
+        .list_tx(&mut tx, list(&[("metadata.missing[ne]", "x")]))
+        .await
+        .unwrap();
+    assert_eq!(response.total, 2);
+
+    // Merged metadata is checked against the document type's schema.
+    let patch = |metadata| UpdateNoteStatusRequest {
+        metadata: Some(metadata),
+        ..Default::default()
+    };
+    notes
+        .update_status_tx(&mut tx, done, patch(json!({"status": "active"})))
+        .await
+        .unwrap();
+    assert!(notes
+        .update_status_tx(&mut tx, done, patch(json!({"status": "lost"})))
+        .await
+        .is_err());
+}
@@ -0,0 +1,72 @@
+//! Tests for per-principal note views.
+
+use chrono::{Duration, Utc};
+
+use crate::test_fixtures::{note_request, TestDatabase};
+use matric_core::{new_v7, CreateNoteRequest, NoteView};
+
+#[tokio::test]
+async fn test_note_views_accumulate_per_principal() {
+    let test_db = TestDatabase::new().await;
+    let views = &test_db.db.note_views;
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+
+    let request = |title: &str| CreateNoteRequest {
+        title: Some(title.to_string()),
+        ..note_request(&format!("{title} content"))
+    };
+    let older = test_db
+        .db
+        .notes
+        .insert_tx(&mut tx, request("Older"))
+        .await
+        .unwrap();
+    let newer = test_db
+        .db
+        .notes
+        .insert_tx(&mut tx, request("Newer"))
+        .await
+        .unwrap();
+
+    let now = Utc::now();
+    let view = |principal: &str, note_id, viewed_at, views| NoteView {
+        principal: principal.to_string(),
+        note_id,
+        viewed_at,
+        views,
+    };
+    let written = views
+        .record_tx(
+            &mut tx,
+            &[
+                view("user:alice", older, now - Duration::hours(2), 2),
+                view("user:alice", newer, now - Duration::hours(1), 1),
+                view("user:bob", older, now, 1),
+                view("user:alice", new_v7(), now, 1),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(written, 3);
+    views
+        .record_tx(&mut tx, &[view("user:alice", older, now, 1)])
+        .await
+        .unwrap();
+
+    let recent = views
+        .recently_viewed_tx(&mut tx, "user:alice", 10, None)
+        .await
+        .unwrap();
+    assert_eq!(recent.len(), 2);
+    assert_eq!(recent[0].note_id, older);
+    assert_eq!(recent[0].view_count, 3);
+    assert!(recent[0].first_viewed_at_utc < recent[0].last_viewed_at_utc);
+    assert_eq!(recent[1].note_id, newer);
+    assert_eq!(recent[1].title.as_deref(), Some("Newer"));
+
+    let bob = views
+        .recently_viewed_tx(&mut tx, "user:bob", 10, None)
+        .await
+        .unwrap();
+    assert_eq!(bob.len(), 1);
+}
@@ -0,0 +1,54 @@
+//! Tests for pinned and prioritised notes.
+
+use crate::test_fixtures::{note_request, TestDatabase};
+use matric_core::{CreateNoteRequest, PinnedQuery, UpdateNoteStatusRequest};
+
+#[tokio::test]
+async fn test_pinned_notes_list_by_priority() {
+    let test_db = TestDatabase::new().await;
+    let notes = &test_db.db.notes;
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+
+    let request = |title: &str| CreateNoteRequest {
+        title: Some(title.to_string()),
+        ..note_request(&format!("{title} content"))
+    };
+    let low = notes.insert_tx(&mut tx, request("Low")).await.unwrap();
+    let high = notes.insert_tx(&mut tx, request("High")).await.unwrap();
+    let unpinned = notes.insert_tx(&mut tx, request("Unpinned")).await.unwrap();
+
+    let pin = |priority| UpdateNoteStatusRequest {
+        pinned: Some(true),
+        priority: Some(priority),
+        ..Default::default()
+    };
+    notes.update_status_tx(&mut tx, low, pin(5)).await.unwrap();
+    notes
+        .update_status_tx(&mut tx, high, pin(80))
+        .await
+        .unwrap();
+
+    let fetched = notes.fetch_tx(&mut tx, high).await.unwrap();
+    assert!(fetched.note.pinned);
+    assert_eq!(fetched.note.priority, 80);
+    assert!(!notes.fetch_tx(&mut tx, unpinned).await.unwrap().note.pinned);
+
+    let pinned = test_db
+        .db
+        .pinned
+        .list_tx(&mut tx, &PinnedQuery::default(), None)
+        .await
+        .unwrap();
+    let ids: Vec<_> = pinned.iter().map(|note| note.note_id).collect();
+    assert!(!ids.contains(&unpinned));
+    let high_pos = ids.iter().position(|id| *id == high).unwrap();
+    let low_pos = ids.iter().position(|id| *id == low).unwrap();
+    assert!(high_pos < low_pos);
+    assert_eq!(pinned[high_pos].title.as_deref(), Some("High"));
+
+    // Out-of-range priorities are rejected by the column constraint.
+    assert!(notes
+        .update_status_tx(&mut tx, low, pin(101))
+        .await
+        .is_err());
+}
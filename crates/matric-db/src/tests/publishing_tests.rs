@@ -0,0 +1,142 @@
+//! Tests for published collections and the pages their sites serve.
+
+use crate::test_fixtures::TestDatabase;
+use crate::{CollectionRepository, CreateNoteRequest, NoteRepository, PgPublicationRepository};
+use matric_core::publishing::PublicationSettings;
+use matric_core::Visibility;
+use uuid::Uuid;
+
+async fn create_note(
+    test_db: &TestDatabase,
+    title: &str,
+    content: &str,
+    collection_id: Uuid,
+    visibility: Visibility,
+) -> Uuid {
+    let id = test_db
+        .db
+        .notes
+        .insert(CreateNoteRequest {
+            content: content.to_string(),
+            format: "markdown".to_string(),
+            source: "test".to_string(),
+            collection_id: Some(collection_id),
+            tags: None,
+            metadata: None,
+            document_type_id: None,
+            title: Some(title.to_string()),
+        })
+        .await
+        .expect("create note");
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    test_db
+        .db
+        .notes
+        .set_visibility_tx(&mut tx, id, visibility)
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+    id
+}
+
+fn settings(slug: &str, include_subcollections: bool) -> PublicationSettings {
+    PublicationSettings {
+        slug: slug.to_string(),
+        title: "Field Notes".to_string(),
+        description: None,
+        include_subcollections,
+    }
+}
+
+#[tokio::test]
+async fn test_published_pages_are_public_live_notes_only() {
+    let test_db = TestDatabase::new().await;
+    let collections = &test_db.db.collections;
+    let parent = collections
+        .create(&format!("pub-{}", Uuid::new_v4()), None, None)
+        .await
+        .unwrap();
+    let child = collections
+        .create(&format!("pub-child-{}", Uuid::new_v4()), None, Some(parent))
+        .await
+        .unwrap();
+    let word = format!("zq{}", Uuid::new_v4().simple());
+    let public = create_note(&test_db, "Alpha", &word, parent, Visibility::Public).await;
+    let nested = create_note(&test_db, "Beta", "nested", child, Visibility::Public).await;
+    let private = create_note(&test_db, "Gamma", &word, parent, Visibility::Private).await;
+    let deleted = create_note(&test_db, "Delta", &word, parent, Visibility::Public).await;
+    test_db.db.notes.soft_delete(deleted).await.unwrap();
+
+    let publications = PgPublicationRepository::new(test_db.db.pool.clone());
+    let slug = format!("site-{}", Uuid::new_v4().simple());
+    let publication = publications
+        .publish("public", parent, &settings(&slug, true))
+        .await
+        .unwrap();
+    let (schema, found) = publications.get_site(&slug).await.unwrap().unwrap();
+    assert_eq!(schema, "public");
+    assert_eq!(found.collection_id, parent);
+
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let pages = publications.pages_tx(&mut tx, &publication).await.unwrap();
+    let ids: Vec<Uuid> = pages.iter().map(|page| page.id).collect();
+    assert_eq!(ids, vec![public, nested]);
+
+    let contents = publications
+        .contents_tx(&mut tx, &publication, &[public, private, deleted])
+        .await
+        .unwrap();
+    assert_eq!(contents.len(), 1);
+    assert_eq!(contents[&public], word);
+
+    let hits = publications
+        .search_tx(&mut tx, &publication, &word, 10)
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, public);
+    tx.rollback().await.unwrap();
+
+    // Without subcollections the nested note is no longer served
+    let publication = publications
+        .publish("public", parent, &settings(&slug, false))
+        .await
+        .unwrap();
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let pages = publications.pages_tx(&mut tx, &publication).await.unwrap();
+    assert_eq!(pages.len(), 1);
+    tx.rollback().await.unwrap();
+
+    assert!(publications.unpublish("public", parent).await.unwrap());
+    assert!(publications.get_site(&slug).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_publication_slugs_are_unique() {
+    let test_db = TestDatabase::new().await;
+    let collections = &test_db.db.collections;
+    let first = collections
+        .create(&format!("pub-a-{}", Uuid::new_v4()), None, None)
+        .await
+        .unwrap();
+    let second = collections
+        .create(&format!("pub-b-{}", Uuid::new_v4()), None, None)
+        .await
+        .unwrap();
+    let publications = PgPublicationRepository::new(test_db.db.pool.clone());
+    let slug = format!("site-{}", Uuid::new_v4().simple());
+
+    publications
+        .publish("public", first, &settings(&slug, true))
+        .await
+        .unwrap();
+    let clash = publications
+        .publish("public", second, &settings(&slug, true))
+        .await;
+    assert!(matches!(clash, Err(matric_core::Error::InvalidInput(_))));
+
+    let listed = publications.list_for_archive("public").await.unwrap();
+    assert!(listed.iter().any(|publication| publication.slug == slug));
+    assert!(publications.unpublish("public", first).await.unwrap());
+    assert!(!publications.unpublish("public", first).await.unwrap());
+}
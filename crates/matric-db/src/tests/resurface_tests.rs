@@ -0,0 +1,66 @@
+//! Tests for resurfacing forgotten notes.
+
+use crate::test_fixtures::{note_request, TestDatabase};
+use matric_core::resurface::ResurfaceQuery;
+use matric_core::{new_v7, CreateNoteRequest};
+
+#[tokio::test]
+async fn test_resurface_samples_only_old_rarely_opened_notes() {
+    let test_db = TestDatabase::new().await;
+    let resurface = &test_db.db.resurface;
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+
+    let insert = |title: &str| CreateNoteRequest {
+        title: Some(title.to_string()),
+        ..note_request(&format!("{title} notes about gardening and compost"))
+    };
+    let forgotten = test_db
+        .db
+        .notes
+        .insert_tx(&mut tx, insert("Forgotten"))
+        .await
+        .unwrap();
+    let popular = test_db
+        .db
+        .notes
+        .insert_tx(&mut tx, insert("Popular"))
+        .await
+        .unwrap();
+    let fresh = test_db
+        .db
+        .notes
+        .insert_tx(&mut tx, insert("Fresh"))
+        .await
+        .unwrap();
+    sqlx::query(
+        "UPDATE note SET created_at_utc = now() - interval '90 days',
+                         access_count = CASE WHEN id = $2 THEN 50 ELSE 0 END
+         WHERE id = ANY($1)",
+    )
+    .bind(vec![forgotten, popular])
+    .bind(popular)
+    .execute(&mut *tx)
+    .await
+    .unwrap();
+
+    let query = ResurfaceQuery {
+        limit: 50,
+        ..ResurfaceQuery::default()
+    };
+    let picked = resurface.sample_tx(&mut tx, &query, None).await.unwrap();
+    let ids: Vec<_> = picked.iter().map(|note| note.note_id).collect();
+    assert!(ids.contains(&forgotten));
+    assert!(!ids.contains(&popular));
+    assert!(!ids.contains(&fresh));
+    assert!(picked.iter().all(|note| note.weight > 0.0));
+
+    let elsewhere = ResurfaceQuery {
+        collection_id: Some(new_v7()),
+        ..query
+    };
+    assert!(resurface
+        .sample_tx(&mut tx, &elsewhere, None)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,80 @@
+//! Tests for tasks found in notes.
+
+use crate::test_fixtures::{note_request, TestDatabase};
+use chrono::NaiveDate;
+use matric_core::task::{merge_action_items, parse_checkbox_tasks};
+use matric_core::{TaskFilter, TaskSource, TaskStatus};
+
+#[tokio::test]
+async fn test_task_extraction_keeps_ids_and_action_item_status() {
+    let test_db = TestDatabase::new().await;
+    let tasks = &test_db.db.tasks;
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+
+    let content = "- [ ] Book flights due 2026-10-20\n- [x] Renew passport\n";
+    let note_id = test_db
+        .db
+        .notes
+        .insert_tx(&mut tx, note_request(content))
+        .await
+        .unwrap();
+    let mut extracted = parse_checkbox_tasks(content, today);
+    merge_action_items(&mut extracted, [("Call the bank".to_string(), None)], today);
+    assert_eq!(
+        tasks
+            .replace_for_note_tx(&mut tx, note_id, &extracted)
+            .await
+            .unwrap(),
+        3
+    );
+
+    let filter = TaskFilter {
+        note_id: Some(note_id),
+        ..Default::default()
+    };
+    let first = tasks.list_tx(&mut tx, &filter, None, 50, 0).await.unwrap();
+    assert_eq!(first.total, 3);
+    assert_eq!(first.tasks[0].text, "Book flights due 2026-10-20");
+    assert_eq!(
+        first.tasks[0].due_date,
+        NaiveDate::from_ymd_opt(2026, 10, 20)
+    );
+    let done = first
+        .tasks
+        .iter()
+        .find(|t| t.text == "Renew passport")
+        .unwrap();
+    assert_eq!(done.status, TaskStatus::Done);
+    assert!(done.completed_at_utc.is_some());
+
+    let item = first
+        .tasks
+        .iter()
+        .find(|t| t.source == TaskSource::Llm)
+        .unwrap();
+    let completed = tasks
+        .set_status_tx(&mut tx, item.id, TaskStatus::Done, None)
+        .await
+        .unwrap();
+    assert_eq!(completed.status, TaskStatus::Done);
+
+    tasks
+        .replace_for_note_tx(&mut tx, note_id, &extracted)
+        .await
+        .unwrap();
+    let second = tasks.list_tx(&mut tx, &filter, None, 50, 0).await.unwrap();
+    let item_again = second.tasks.iter().find(|t| t.id == item.id).unwrap();
+    assert_eq!(item_again.status, TaskStatus::Done);
+    assert_eq!(second.tasks[0].id, first.tasks[0].id);
+
+    let open = TaskFilter {
+        note_id: Some(note_id),
+        status: Some(TaskStatus::Open),
+        due_before: NaiveDate::from_ymd_opt(2026, 10, 31),
+        ..Default::default()
+    };
+    let open = tasks.list_tx(&mut tx, &open, None, 50, 0).await.unwrap();
+    assert_eq!(open.total, 1);
+    assert_eq!(open.tasks[0].source, TaskSource::Checkbox);
+}
@@ -0,0 +1,84 @@
+//! Tests for typed template variables and template versions.
+
+use crate::test_fixtures::TestDatabase;
+use matric_core::{
+    CreateTemplateRequest, TemplateRepository, TemplateVariable, TemplateVariableType,
+    UpdateTemplateRequest,
+};
+use uuid::Uuid;
+
+fn due_variable() -> TemplateVariable {
+    TemplateVariable {
+        name: "due".to_string(),
+        kind: TemplateVariableType::Date,
+        description: Some("When it is due".to_string()),
+        required: true,
+        default: Some("{{today+7d}}".to_string()),
+        options: Vec::new(),
+        max_length: None,
+    }
+}
+
+#[tokio::test]
+async fn test_updates_keep_the_replaced_versions() {
+    let test_db = TestDatabase::new().await;
+    let templates = &test_db.db.templates;
+    let id = templates
+        .create(CreateTemplateRequest {
+            name: format!("tmpl-{}", Uuid::new_v4()),
+            description: None,
+            content: "Due {{due}}".to_string(),
+            format: None,
+            default_tags: Some(vec!["task".to_string()]),
+            collection_id: None,
+            variables: Some(vec![due_variable()]),
+        })
+        .await
+        .unwrap();
+
+    let created = templates.get(id).await.unwrap().unwrap();
+    assert_eq!(created.version, 1);
+    assert_eq!(created.variables, vec![due_variable()]);
+
+    templates
+        .update(
+            id,
+            UpdateTemplateRequest {
+                content: Some("Finish by {{due}}".to_string()),
+                variables: Some(Vec::new()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let updated = templates.get(id).await.unwrap().unwrap();
+    assert_eq!(updated.version, 2);
+    assert_eq!(updated.content, "Finish by {{due}}");
+    assert!(updated.variables.is_empty());
+
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    let versions = templates.list_versions_tx(&mut tx, id).await.unwrap();
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0].version, 1);
+    assert_eq!(versions[0].content, "Due {{due}}");
+    assert_eq!(versions[0].variables, vec![due_variable()]);
+    assert_eq!(versions[0].default_tags, vec!["task".to_string()]);
+
+    let current = templates.get_version_tx(&mut tx, id, 2).await.unwrap();
+    assert_eq!(current.unwrap().content, "Finish by {{due}}");
+    assert!(templates
+        .get_version_tx(&mut tx, id, 3)
+        .await
+        .unwrap()
+        .is_none());
+    tx.commit().await.unwrap();
+
+    templates.delete(id).await.unwrap();
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+    assert!(templates
+        .list_versions_tx(&mut tx, id)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,136 @@
+//! Tests for typed links and custom link types.
+
+use crate::test_fixtures::TestDatabase;
+use crate::{CreateNoteRequest, NoteRepository};
+use matric_core::{CreateLinkTypeRequest, CreateTypedLinkRequest, UpdateTypedLinkRequest};
+use uuid::Uuid;
+
+async fn create_note(test_db: &TestDatabase, content: &str) -> Uuid {
+    test_db
+        .db
+        .notes
+        .insert(CreateNoteRequest {
+            content: content.to_string(),
+            format: "markdown".to_string(),
+            source: "test".to_string(),
+            collection_id: None,
+            tags: None,
+            metadata: None,
+            document_type_id: None,
+            title: None,
+        })
+        .await
+        .expect("create note")
+}
+
+fn typed_link(to_note_id: Uuid, link_type: &str) -> CreateTypedLinkRequest {
+    CreateTypedLinkRequest {
+        to_note_id,
+        link_type: link_type.to_string(),
+        score: None,
+        metadata: None,
+    }
+}
+
+#[tokio::test]
+async fn test_symmetric_links_are_paired_through_edits() {
+    let test_db = TestDatabase::new().await;
+    let a = create_note(&test_db, "Claim").await;
+    let b = create_note(&test_db, "Counter-claim").await;
+    let links = &test_db.db.links;
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+
+    let created = links
+        .create_typed_link_tx(&mut tx, a, &typed_link(b, "Contradicts"), None)
+        .await
+        .expect("create");
+    assert_eq!(created.len(), 2);
+    assert!(created.iter().all(|link| link.kind == "contradicts"));
+    assert!(links
+        .create_typed_link_tx(&mut tx, a, &typed_link(b, "contradicts"), None)
+        .await
+        .is_err());
+
+    // Retyping to an asymmetric type drops the reverse link
+    let updated = links
+        .update_typed_link_tx(
+            &mut tx,
+            created[0].id,
+            &UpdateTypedLinkRequest {
+                link_type: Some("supports".to_string()),
+                score: Some(0.4),
+                metadata: None,
+            },
+            None,
+        )
+        .await
+        .expect("update");
+    assert_eq!(updated.kind, "supports");
+    assert_eq!(updated.score, 0.4);
+    assert!(links
+        .get_outgoing_tx(&mut tx, b)
+        .await
+        .unwrap()
+        .iter()
+        .all(|link| link.to_note_id != Some(a)));
+
+    let deleted = links
+        .delete_typed_link_tx(&mut tx, updated.id, None)
+        .await
+        .expect("delete");
+    assert_eq!(deleted, 1);
+    drop(tx);
+
+    test_db.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_custom_type_is_required_and_kept_while_used() {
+    let test_db = TestDatabase::new().await;
+    let a = create_note(&test_db, "Derived note").await;
+    let b = create_note(&test_db, "Original note").await;
+    let links = &test_db.db.links;
+    let mut tx = test_db.db.pool.begin().await.unwrap();
+
+    assert!(links
+        .create_typed_link_tx(&mut tx, a, &typed_link(b, "derived-from"), None)
+        .await
+        .is_err());
+    let link_type = links
+        .create_link_type_tx(
+            &mut tx,
+            &CreateLinkTypeRequest {
+                name: "Derived-From".to_string(),
+                description: Some("Was derived from the target".to_string()),
+                symmetric: false,
+            },
+        )
+        .await
+        .expect("register");
+    assert_eq!(link_type.name, "derived-from");
+    assert!(!link_type.builtin);
+
+    let created = links
+        .create_typed_link_tx(&mut tx, a, &typed_link(b, "derived-from"), None)
+        .await
+        .expect("create");
+    assert_eq!(created.len(), 1);
+    assert!(links
+        .delete_link_type_tx(&mut tx, "derived-from")
+        .await
+        .is_err());
+
+    links
+        .delete_typed_link_tx(&mut tx, created[0].id, None)
+        .await
+        .unwrap();
+    links
+        .delete_link_type_tx(&mut tx, "derived-from")
+        .await
+        .expect("remove unused type");
+    let types = links.list_link_types_tx(&mut tx).await.unwrap();
+    assert!(types.iter().all(|t| t.name != "derived-from"));
+    drop(tx);
+
+    test_db.cleanup().await;
+}
@@ -0,0 +1,217 @@
+//! Topic repository.
+//!
+//! Topics (`topic`) and their member notes (`topic_member`) are
+//! archive-scoped and rewritten wholesale by each topic modeling run. The
+//! `*_tx` methods take a transaction that has already been pointed at the
+//! archive schema.
+
+use sqlx::{postgres::PgRow, Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    new_v7, Error, NewTopic, Result, StrictSecurityFilter, Topic, TopicDetail, TopicMember,
+    TopicNoteSample, Vector,
+};
+
+use crate::unified_filter::{
+    bind_filter_param, security_clause, security_filter_query, QueryParam,
+};
+
+/// Most members listed on a topic detail.
+const TOPIC_DETAIL_MAX_MEMBERS: i64 = 200;
+
+/// PostgreSQL repository for topics.
+#[derive(Clone)]
+pub struct PgTopicRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgTopicRepository {
+    /// Create a new topic repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// One vector per live, unencrypted note: the mean of its chunk
+    /// embeddings in the default embedding set.
+    ///
+    /// Returns the `limit` most recently updated notes.
+    pub async fn note_vectors_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        limit: i64,
+    ) -> Result<Vec<(Uuid, Vector)>> {
+        let rows = sqlx::query(
+            "SELECT e.note_id, AVG(e.vector) AS vector
+             FROM embedding e
+             JOIN note n ON n.id = e.note_id
+             WHERE n.deleted_at IS NULL
+               AND NOT n.encrypted
+               AND e.embedding_set_id =
+                   (SELECT id FROM embedding_set WHERE is_system AND slug = 'default')
+             GROUP BY e.note_id, n.updated_at_utc
+             ORDER BY n.updated_at_utc DESC, e.note_id
+             LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("note_id"), row.get("vector")))
+            .collect())
+    }
+
+    /// Titles and the opening of the content of `note_ids`, in input order.
+    pub async fn note_samples_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_ids: &[Uuid],
+        excerpt_chars: i32,
+    ) -> Result<Vec<TopicNoteSample>> {
+        let rows = sqlx::query(
+            "SELECT n.id, n.title,
+                    substring(COALESCE(nrc.content, noc.content, '') for $2) AS excerpt
+             FROM unnest($1::uuid[]) WITH ORDINALITY AS ids(id, ord)
+             JOIN note n ON n.id = ids.id
+             LEFT JOIN note_revised_current nrc ON nrc.note_id = n.id
+             LEFT JOIN note_original noc ON noc.note_id = n.id
+             ORDER BY ids.ord",
+        )
+        .bind(note_ids)
+        .bind(excerpt_chars)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| TopicNoteSample {
+                note_id: row.get("id"),
+                title: row.get("title"),
+                excerpt: row.get::<Option<String>, _>("excerpt").unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Replace every topic in the archive with `topics`. Returns the number
+    /// of topics stored.
+    pub async fn replace_all_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        topics: &[NewTopic],
+    ) -> Result<usize> {
+        sqlx::query("DELETE FROM topic")
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+
+        for topic in topics {
+            let topic_id = new_v7();
+            let note_ids: Vec<Uuid> = topic.members.iter().map(|(id, _)| *id).collect();
+            let similarities: Vec<f32> = topic.members.iter().map(|(_, s)| *s).collect();
+            sqlx::query(
+                "INSERT INTO topic (id, label, note_count, concept_id, model)
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(topic_id)
+            .bind(&topic.label)
+            .bind(i32::try_from(topic.members.len()).unwrap_or(i32::MAX))
+            .bind(topic.concept_id)
+            .bind(topic.model.as_deref())
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+            sqlx::query(
+                "INSERT INTO topic_member (topic_id, note_id, similarity)
+                 SELECT $1, m.note_id, m.similarity
+                 FROM unnest($2::uuid[], $3::real[]) AS m(note_id, similarity)",
+            )
+            .bind(topic_id)
+            .bind(&note_ids)
+            .bind(&similarities)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        }
+        Ok(topics.len())
+    }
+
+    /// List topics, largest first.
+    pub async fn list_tx(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Vec<Topic>> {
+        let rows = sqlx::query(
+            "SELECT id, label, note_count, concept_id, model, created_at_utc
+             FROM topic
+             ORDER BY note_count DESC, label, id",
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(rows.iter().map(topic_from_row).collect())
+    }
+
+    /// Get a topic and its live member notes, most central first. `security`,
+    /// when given, admits only the members it allows.
+    pub async fn get_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        security: Option<&StrictSecurityFilter>,
+    ) -> Result<Option<TopicDetail>> {
+        let Some(row) = sqlx::query(
+            "SELECT id, label, note_count, concept_id, model, created_at_utc
+             FROM topic WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?
+        else {
+            return Ok(None);
+        };
+
+        let security = security_filter_query(security, 2);
+        let sql = format!(
+            "SELECT tm.note_id, n.title, tm.similarity
+             FROM topic_member tm
+             JOIN note n ON n.id = tm.note_id AND n.deleted_at IS NULL
+             WHERE tm.topic_id = $1
+               {}
+             ORDER BY tm.similarity DESC, tm.note_id
+             LIMIT $2",
+            security_clause(&security)
+        );
+        let mut query = sqlx::query(&sql).bind(id).bind(TOPIC_DETAIL_MAX_MEMBERS);
+        for param in security.iter().flat_map(|result| &result.params) {
+            query = bind_filter_param!(query, param);
+        }
+        let members = query.fetch_all(&mut **tx).await.map_err(Error::Database)?;
+
+        Ok(Some(TopicDetail {
+            topic: topic_from_row(&row),
+            members: members
+                .iter()
+                .map(|row| TopicMember {
+                    note_id: row.get("note_id"),
+                    title: row.get("title"),
+                    similarity: row.get("similarity"),
+                })
+                .collect(),
+        }))
+    }
+}
+
+fn topic_from_row(row: &PgRow) -> Topic {
+    Topic {
+        id: row.get("id"),
+        label: row.get("label"),
+        note_count: row.get("note_count"),
+        concept_id: row.get("concept_id"),
+        model: row.get("model"),
+        created_at_utc: row.get("created_at_utc"),
+    }
+}
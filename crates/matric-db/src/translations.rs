@@ -0,0 +1,160 @@
+//! Note translation repository.
+//!
+//! Translations are archive-scoped. The `*_tx` methods take a transaction
+//! that has already been pointed at the archive schema. Archive translation
+//! settings are shared, keyed by schema name (`public` for the default
+//! archive).
+
+use sqlx::{Pool, Postgres, Transaction};
+use uuid::Uuid;
+
+use matric_core::{ArchiveTranslationSetting, Error, NoteTranslation, Result};
+
+/// PostgreSQL repository for note translations and archive settings.
+#[derive(Clone)]
+pub struct PgNoteTranslationRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgNoteTranslationRepository {
+    /// Create a new note translation repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Store a note's translation, replacing any earlier one.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upsert_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        source_language: &str,
+        target_language: &str,
+        content: &str,
+        model: Option<&str>,
+        chunk_count: i32,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO note_translation
+                 (note_id, source_language, target_language, content, model, chunk_count, generated_at_utc)
+             VALUES ($1, $2, $3, $4, $5, $6, NOW())
+             ON CONFLICT (note_id) DO UPDATE
+             SET source_language = EXCLUDED.source_language,
+                 target_language = EXCLUDED.target_language,
+                 content = EXCLUDED.content,
+                 model = EXCLUDED.model,
+                 chunk_count = EXCLUDED.chunk_count,
+                 generated_at_utc = EXCLUDED.generated_at_utc",
+        )
+        .bind(note_id)
+        .bind(source_language)
+        .bind(target_language)
+        .bind(content)
+        .bind(model)
+        .bind(chunk_count.max(1))
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Get a note's translation.
+    pub async fn get_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<Option<NoteTranslation>> {
+        fetch_translation(tx, note_id).await
+    }
+
+    /// Remove a note's translation, e.g. once the note is in the target language.
+    pub async fn delete_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+    ) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM note_translation WHERE note_id = $1")
+            .bind(note_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// An archive's stored translation setting, if it has one.
+    pub async fn archive_setting(
+        &self,
+        schema_name: &str,
+    ) -> Result<Option<ArchiveTranslationSetting>> {
+        let row: Option<(bool, String)> = sqlx::query_as(
+            "SELECT enabled, target_language
+             FROM archive_translation_setting
+             WHERE schema_name = $1",
+        )
+        .bind(schema_name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(
+            row.map(|(enabled, target_language)| ArchiveTranslationSetting {
+                enabled,
+                target_language,
+            }),
+        )
+    }
+
+    /// The setting that applies to an archive: its own, else disabled.
+    pub async fn effective_setting(&self, schema_name: &str) -> Result<ArchiveTranslationSetting> {
+        Ok(self.archive_setting(schema_name).await?.unwrap_or_default())
+    }
+
+    /// Create or replace an archive's translation setting.
+    pub async fn set_archive_setting(
+        &self,
+        schema_name: &str,
+        setting: &ArchiveTranslationSetting,
+    ) -> Result<()> {
+        setting.validate()?;
+        sqlx::query(
+            "INSERT INTO archive_translation_setting (schema_name, enabled, target_language)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (schema_name) DO UPDATE SET
+                 enabled = EXCLUDED.enabled,
+                 target_language = EXCLUDED.target_language,
+                 updated_at = NOW()",
+        )
+        .bind(schema_name)
+        .bind(setting.enabled)
+        .bind(&setting.target_language)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Remove an archive's setting so translation is disabled again.
+    pub async fn delete_archive_setting(&self, schema_name: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM archive_translation_setting WHERE schema_name = $1")
+            .bind(schema_name)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Load a note's translation within `tx`; shared with note fetches.
+pub(crate) async fn fetch_translation(
+    tx: &mut Transaction<'_, Postgres>,
+    note_id: Uuid,
+) -> Result<Option<NoteTranslation>> {
+    sqlx::query_as::<_, NoteTranslation>(
+        "SELECT note_id, source_language, target_language, content, model, chunk_count, generated_at_utc
+         FROM note_translation WHERE note_id = $1",
+    )
+    .bind(note_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(Error::Database)
+}
@@ -0,0 +1,175 @@
+//! Note trash repository.
+//!
+//! The trash is the archive's soft-deleted notes, so every method takes an
+//! existing transaction that has already been pointed at the archive schema.
+//! Methods taking a `writer` only reach the notes that user may write; with
+//! `None` they reach the whole trash.
+
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{trash_purge_at, Error, Result, TrashListing, TrashedNote};
+
+use crate::users::note_writable_clause;
+
+/// Notes hard-deleted by one [`PgTrashRepository::purge_tx`] call.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrashPurgeBatch {
+    pub note_ids: Vec<Uuid>,
+    /// Embedding sets the purged notes belonged to, whose stats are stale
+    pub embedding_set_ids: Vec<Uuid>,
+}
+
+/// PostgreSQL repository for the note trash.
+#[derive(Clone)]
+pub struct PgTrashRepository {
+    #[allow(dead_code)]
+    pool: Pool<Postgres>,
+}
+
+impl PgTrashRepository {
+    /// Create a new trash repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// One page of trashed notes, most recently deleted first.
+    pub async fn list_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        writer: Option<Uuid>,
+        limit: i64,
+        offset: i64,
+        retention_days: i64,
+    ) -> Result<TrashListing> {
+        let total: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM note n
+             WHERE n.deleted_at IS NOT NULL
+               AND ($1::uuid IS NULL OR {})",
+            note_writable_clause(1)
+        ))
+        .bind(writer)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let rows = sqlx::query(&format!(
+            "SELECT n.id, n.title, n.created_at_utc, n.deleted_at
+             FROM note n
+             WHERE n.deleted_at IS NOT NULL
+               AND ($1::uuid IS NULL OR {})
+             ORDER BY n.deleted_at DESC, n.id
+             LIMIT $2 OFFSET $3",
+            note_writable_clause(1)
+        ))
+        .bind(writer)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        let notes = rows
+            .into_iter()
+            .map(|row| {
+                let deleted_at: DateTime<Utc> = row.get("deleted_at");
+                TrashedNote {
+                    id: row.get("id"),
+                    title: row.get("title"),
+                    created_at_utc: row.get("created_at_utc"),
+                    deleted_at,
+                    purge_at: trash_purge_at(deleted_at, retention_days),
+                }
+            })
+            .collect();
+
+        Ok(TrashListing {
+            notes,
+            total,
+            retention_days,
+        })
+    }
+
+    /// Restore trashed notes, or the whole trash when `ids` is `None`.
+    ///
+    /// Returns the notes restored; IDs that are not in the trash are skipped.
+    pub async fn restore_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        ids: Option<&[Uuid]>,
+        writer: Option<Uuid>,
+    ) -> Result<Vec<Uuid>> {
+        sqlx::query_scalar(&format!(
+            "UPDATE note n SET deleted_at = NULL, updated_at_utc = $1
+             WHERE n.deleted_at IS NOT NULL
+               AND ($2::uuid[] IS NULL OR n.id = ANY($2))
+               AND ($3::uuid IS NULL OR {})
+             RETURNING n.id",
+            note_writable_clause(3)
+        ))
+        .bind(Utc::now())
+        .bind(ids)
+        .bind(writer)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// Hard-delete up to `limit` trashed notes, oldest deletion first.
+    ///
+    /// Only notes in `ids` (every trashed note when `None`) deleted before
+    /// `deleted_before` (any time when `None`), and writable by `writer`
+    /// when given, are purged. Deleting a note
+    /// cascades to its content, tags, links, embeddings and set memberships.
+    pub async fn purge_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        ids: Option<&[Uuid]>,
+        deleted_before: Option<DateTime<Utc>>,
+        writer: Option<Uuid>,
+        limit: i64,
+    ) -> Result<TrashPurgeBatch> {
+        let note_ids: Vec<Uuid> = sqlx::query_scalar(&format!(
+            "SELECT n.id FROM note n
+             WHERE n.deleted_at IS NOT NULL
+               AND ($1::uuid[] IS NULL OR n.id = ANY($1))
+               AND ($2::timestamptz IS NULL OR n.deleted_at < $2)
+               AND ($3::uuid IS NULL OR {})
+             ORDER BY n.deleted_at, n.id
+             LIMIT $4
+             FOR UPDATE OF n",
+            note_writable_clause(3)
+        ))
+        .bind(ids)
+        .bind(deleted_before)
+        .bind(writer)
+        .bind(limit)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        if note_ids.is_empty() {
+            return Ok(TrashPurgeBatch::default());
+        }
+
+        let embedding_set_ids: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT DISTINCT embedding_set_id FROM embedding_set_member
+             WHERE note_id = ANY($1)",
+        )
+        .bind(&note_ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        sqlx::query("DELETE FROM note WHERE id = ANY($1)")
+            .bind(&note_ids)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+
+        Ok(TrashPurgeBatch {
+            note_ids,
+            embedding_set_ids,
+        })
+    }
+}
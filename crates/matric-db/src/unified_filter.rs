@@ -30,6 +30,8 @@ use matric_core::{
 // Re-export QueryParam from strict_filter for consistency
 pub use crate::strict_filter::QueryParam;
 
+use crate::strict_filter::concept_match_sql;
+
 // =============================================================================
 // UNIFIED FILTER QUERY BUILDER
 // =============================================================================
@@ -421,8 +423,8 @@ impl UnifiedFilterQueryBuilder {
         for concept_id in &tags.required_concepts {
             param_idx += 1;
             clauses.push(format!(
-                "EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND nsc.concept_id = ${})",
-                param_idx
+                "EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND {})",
+                concept_match_sql(param_idx, false, tags.expand_hierarchy)
             ));
             params.push(QueryParam::Uuid(*concept_id));
         }
@@ -431,8 +433,8 @@ impl UnifiedFilterQueryBuilder {
         if !tags.any_concepts.is_empty() {
             param_idx += 1;
             clauses.push(format!(
-                "EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND nsc.concept_id = ANY(${}::uuid[]))",
-                param_idx
+                "EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND {})",
+                concept_match_sql(param_idx, true, tags.expand_hierarchy)
             ));
             params.push(QueryParam::UuidArray(tags.any_concepts.clone()));
         }
@@ -441,8 +443,8 @@ impl UnifiedFilterQueryBuilder {
         if !tags.excluded_concepts.is_empty() {
             param_idx += 1;
             clauses.push(format!(
-                "NOT EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND nsc.concept_id = ANY(${}::uuid[]))",
-                param_idx
+                "NOT EXISTS (SELECT 1 FROM note_skos_concept nsc WHERE nsc.note_id = n.id AND {})",
+                concept_match_sql(param_idx, true, tags.expand_hierarchy)
             ));
             params.push(QueryParam::UuidArray(tags.excluded_concepts.clone()));
         }
@@ -504,26 +506,40 @@ impl UnifiedFilterQueryBuilder {
 
         // Owner filter
         if let Some(owner_id) = security.owner_id {
+            param_idx += 1;
+            let mut alternatives = vec![format!("n.owner_id = ${}", param_idx)];
+            params.push(QueryParam::Uuid(owner_id));
+
             if let (true, Some(shared_with_user)) =
                 (security.include_shared, security.shared_with_user)
             {
-                // Include owned OR shared (with active grant check)
-                param_idx += 1;
-                let owner_param = param_idx;
+                // Include shared notes (with active grant check): not
+                // revoked and not expired, on the note or its collection
                 param_idx += 1;
-                let shared_param = param_idx;
-
-                // Check for active share grants: not revoked and not expired
-                clauses.push(format!(
-                    "(n.owner_id = ${} OR EXISTS (SELECT 1 FROM note_share_grant nsg WHERE nsg.note_id = n.id AND nsg.grantee_id = ${} AND nsg.revoked_at IS NULL AND (nsg.expires_at IS NULL OR nsg.expires_at > NOW())))",
-                    owner_param, shared_param
+                alternatives.push(format!(
+                    "EXISTS (SELECT 1 FROM note_share_grant nsg WHERE nsg.note_id = n.id AND nsg.grantee_id = ${} AND nsg.revoked_at IS NULL AND (nsg.expires_at IS NULL OR nsg.expires_at > NOW()))",
+                    param_idx
+                ));
+                alternatives.push(format!(
+                    "EXISTS (SELECT 1 FROM collection_share_grant csg WHERE csg.collection_id = n.collection_id AND csg.grantee_id = ${} AND csg.revoked_at IS NULL AND (csg.expires_at IS NULL OR csg.expires_at > NOW()))",
+                    param_idx
                 ));
-                params.push(QueryParam::Uuid(owner_id));
                 params.push(QueryParam::Uuid(shared_with_user));
+            }
+            if security.include_unowned {
+                alternatives.push("n.owner_id IS NULL".to_string());
+            }
+            if !security.open_visibility.is_empty() {
+                alternatives.push(format!(
+                    "n.visibility IN ({})",
+                    visibility_list(&security.open_visibility)
+                ));
+            }
+
+            if alternatives.len() == 1 {
+                clauses.append(&mut alternatives);
             } else {
-                param_idx += 1;
-                clauses.push(format!("n.owner_id = ${}", param_idx));
-                params.push(QueryParam::Uuid(owner_id));
+                clauses.push(format!("({})", alternatives.join(" OR ")));
             }
         }
 
@@ -536,18 +552,10 @@ impl UnifiedFilterQueryBuilder {
 
         // Visibility filter
         if !security.visibility.is_empty() {
-            let vis_strings: Vec<String> = security
-                .visibility
-                .iter()
-                .map(|v| match v {
-                    matric_core::Visibility::Private => "'private'".to_string(),
-                    matric_core::Visibility::Shared => "'shared'".to_string(),
-                    matric_core::Visibility::Internal => "'internal'".to_string(),
-                    matric_core::Visibility::Public => "'public'".to_string(),
-                })
-                .collect();
-
-            clauses.push(format!("n.visibility IN ({})", vis_strings.join(", ")));
+            clauses.push(format!(
+                "n.visibility IN ({})",
+                visibility_list(&security.visibility)
+            ));
         }
 
         (clauses, params, param_idx)
@@ -674,6 +682,49 @@ impl QueryParam {
     }
 }
 
+// =============================================================================
+// SECURITY-ONLY FILTERING
+// =============================================================================
+
+/// Build the WHERE clause admitting only the notes (aliased `n`) that
+/// `security` allows, numbering parameters after `param_offset`. `None` when
+/// there is nothing to filter.
+pub(crate) fn security_filter_query(
+    security: Option<&StrictSecurityFilter>,
+    param_offset: usize,
+) -> Option<UnifiedFilterResult> {
+    security
+        .filter(|security| !security.is_empty())
+        .map(|security| {
+            let filter = StrictFilter::new().with_security(security.clone());
+            UnifiedFilterQueryBuilder::new(filter, param_offset).build()
+        })
+}
+
+/// `AND <clause>` for a [`security_filter_query`] result, or nothing.
+pub(crate) fn security_clause(security: &Option<UnifiedFilterResult>) -> String {
+    security
+        .as_ref()
+        .map(|result| format!("AND {}", result.where_clause))
+        .unwrap_or_default()
+}
+
+/// Bind one [`QueryParam`] to a query.
+macro_rules! bind_filter_param {
+    ($query:expr, $param:expr) => {
+        match $param {
+            QueryParam::Uuid(id) => $query.bind(id),
+            QueryParam::UuidArray(ids) => $query.bind(ids),
+            QueryParam::Int(val) => $query.bind(val),
+            QueryParam::Timestamp(ts) => $query.bind(ts),
+            QueryParam::Bool(b) => $query.bind(b),
+            QueryParam::String(s) => $query.bind(s),
+            QueryParam::StringArray(arr) => $query.bind(arr),
+        }
+    };
+}
+pub(crate) use bind_filter_param;
+
 // Add new variants to QueryParam (defined in strict_filter.rs)
 // We need to update the original definition
 
@@ -681,6 +732,15 @@ impl QueryParam {
 // TESTS
 // =============================================================================
 
+/// Quoted SQL literals for visibility levels.
+fn visibility_list(levels: &[matric_core::Visibility]) -> String {
+    levels
+        .iter()
+        .map(|v| format!("'{}'", v.as_str()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -730,6 +790,30 @@ mod tests {
         assert!(result.cte_clause.unwrap().contains("UNION ALL"));
     }
 
+    #[test]
+    fn test_tag_hierarchy_expansion() {
+        let filter = StrictFilter::new().with_tags(
+            StrictTagFilter::new()
+                .require_concept(Uuid::new_v4())
+                .any_concept(Uuid::new_v4())
+                .with_expand_hierarchy(true),
+        );
+
+        let builder = UnifiedFilterQueryBuilder::new(filter, 0);
+        let result = builder.build();
+
+        assert!(result.where_clause.contains("WITH RECURSIVE concept_tree"));
+        assert!(result.where_clause.contains("SELECT $1::uuid UNION"));
+        assert!(result
+            .where_clause
+            .contains("SELECT unnest($2::uuid[]) UNION"));
+        assert!(result.where_clause.contains("e.relation_type = 'broader'"));
+        assert!(!result.where_clause.contains("UNION ALL"));
+        assert_eq!(result.params.len(), 2);
+        // The expansion is inline, so callers need no CTE prefix.
+        assert!(result.cte_clause.is_none());
+    }
+
     #[test]
     fn test_multi_dimension_filter() {
         let filter = StrictFilter::new()
@@ -763,6 +847,24 @@ mod tests {
         assert!(result.where_clause.contains("$6"));
     }
 
+    #[test]
+    fn test_caller_security_filter() {
+        let user_id = Uuid::new_v4();
+        let filter = StrictFilter::new().with_security(StrictSecurityFilter::for_caller(user_id));
+
+        let builder = UnifiedFilterQueryBuilder::new(filter, 2);
+        let result = builder.build();
+
+        assert!(result.where_clause.contains("n.owner_id = $3"));
+        assert!(result.where_clause.contains("nsg.grantee_id = $4"));
+        assert!(result.where_clause.contains("csg.grantee_id = $4"));
+        assert!(result.where_clause.contains("n.owner_id IS NULL"));
+        assert!(result
+            .where_clause
+            .contains("n.visibility IN ('internal', 'public')"));
+        assert_eq!(result.params.len(), 2);
+    }
+
     #[test]
     fn unified_filter_result_debug_redacts_sql_and_params() {
         let collection_id = Uuid::new_v4();
@@ -0,0 +1,100 @@
+//! Per-principal daily usage counter repository.
+//!
+//! Counters live in the shared `public` schema, keyed by principal, UTC day
+//! and counter, so a principal's usage adds up across every memory.
+
+use chrono::{Days, NaiveDate, Utc};
+use sqlx::{Pool, Postgres, Row};
+
+use matric_core::{usage_day, DailyUsage, Error, Result, UsageCounter};
+
+/// PostgreSQL repository for daily usage counters.
+#[derive(Clone)]
+pub struct PgUsageCounterRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgUsageCounterRepository {
+    /// Create a new usage counter repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// Add `amount` to today's `counter` for `principal`.
+    pub async fn increment(
+        &self,
+        principal: &str,
+        counter: UsageCounter,
+        amount: i64,
+    ) -> Result<()> {
+        if amount <= 0 {
+            return Ok(());
+        }
+        sqlx::query(
+            "INSERT INTO usage_daily_counter (principal, day, counter, quantity)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (principal, day, counter) DO UPDATE
+             SET quantity = usage_daily_counter.quantity + EXCLUDED.quantity,
+                 updated_at = NOW()",
+        )
+        .bind(principal)
+        .bind(usage_day(Utc::now()))
+        .bind(counter.as_str())
+        .bind(amount)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Today's value of `counter` for `principal`.
+    pub async fn today(&self, principal: &str, counter: UsageCounter) -> Result<i64> {
+        let quantity: Option<i64> = sqlx::query_scalar(
+            "SELECT quantity FROM usage_daily_counter
+             WHERE principal = $1 AND day = $2 AND counter = $3",
+        )
+        .bind(principal)
+        .bind(usage_day(Utc::now()))
+        .bind(counter.as_str())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(quantity.unwrap_or(0))
+    }
+
+    /// The last `days` days of usage for `principal`, today first. Days
+    /// without usage are included with zero counters.
+    pub async fn daily(&self, principal: &str, days: u32) -> Result<Vec<DailyUsage>> {
+        let today = usage_day(Utc::now());
+        let mut usage: Vec<DailyUsage> = (0..days.max(1))
+            .filter_map(|offset| today.checked_sub_days(Days::new(offset.into())))
+            .map(DailyUsage::empty)
+            .collect();
+        let Some(oldest) = usage.last().map(|day| day.day) else {
+            return Ok(usage);
+        };
+
+        let rows = sqlx::query(
+            "SELECT day, counter, quantity FROM usage_daily_counter
+             WHERE principal = $1 AND day BETWEEN $2 AND $3",
+        )
+        .bind(principal)
+        .bind(oldest)
+        .bind(today)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        for row in rows {
+            let day: NaiveDate = row.get("day");
+            let counter: String = row.get("counter");
+            let Some(counter) = UsageCounter::parse(&counter) else {
+                continue;
+            };
+            if let Some(entry) = usage.iter_mut().find(|entry| entry.day == day) {
+                entry.add(counter, row.get("quantity"));
+            }
+        }
+        Ok(usage)
+    }
+}
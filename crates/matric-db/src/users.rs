@@ -0,0 +1,519 @@
+//! User, ownership and share grant repository.
+//!
+//! Users and archive grants live in the shared `public` schema. Note and
+//! collection grants live next to the notes and collections in each archive
+//! schema, so their methods take a schema-scoped transaction.
+
+use sqlx::{postgres::PgRow, PgConnection, Pool, Postgres, Row, Transaction};
+use uuid::Uuid;
+
+use matric_core::{
+    resolve_access, AccessLevel, CreateShareGrantRequest, Error, Result, ShareGrant,
+    SharePermission, ShareResource, User,
+};
+
+const USER_COLUMNS: &str = "id, external_id, display_name, created_at, last_seen_at";
+
+/// Grants that have not been revoked or expired.
+const ACTIVE_GRANT: &str = "revoked_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())";
+
+/// SQL condition that the user bound as `$user_param` may write the note
+/// aliased `n`: it is unowned or the user's, or an active `write` grant on
+/// the note or its collection reaches the user. Visibility only gives read
+/// access, as in [`resolve_access`].
+pub(crate) fn note_writable_clause(user_param: usize) -> String {
+    format!(
+        "(n.owner_id IS NULL OR n.owner_id = ${user_param}
+          OR EXISTS (SELECT 1 FROM note_share_grant
+                     WHERE note_id = n.id AND grantee_id = ${user_param}
+                       AND permission IN ('write', 'admin') AND {ACTIVE_GRANT})
+          OR EXISTS (SELECT 1 FROM collection_share_grant
+                     WHERE collection_id = n.collection_id AND grantee_id = ${user_param}
+                       AND permission IN ('write', 'admin') AND {ACTIVE_GRANT}))"
+    )
+}
+
+/// How long `last_seen_at` may lag before a request refreshes it.
+const LAST_SEEN_RESOLUTION: &str = "INTERVAL '5 minutes'";
+
+fn grant_table(resource: ShareResource) -> (&'static str, &'static str) {
+    match resource {
+        ShareResource::Note => ("note_share_grant", "note_id"),
+        ShareResource::Collection => ("collection_share_grant", "collection_id"),
+        ShareResource::Archive => ("archive_share_grant", "archive_id"),
+    }
+}
+
+fn user_from_row(row: &PgRow) -> User {
+    User {
+        id: row.get("id"),
+        external_id: row.get("external_id"),
+        display_name: row.get("display_name"),
+        created_at: row.get("created_at"),
+        last_seen_at: row.get("last_seen_at"),
+    }
+}
+
+fn grant_from_row(resource: ShareResource, row: &PgRow) -> ShareGrant {
+    let permission: String = row.get("permission");
+    ShareGrant {
+        id: row.get("id"),
+        resource,
+        resource_id: row.get("resource_id"),
+        grantee_id: row.get("grantee_id"),
+        permission: SharePermission::parse(&permission).unwrap_or_default(),
+        granted_by: row.get("granted_by"),
+        granted_at: row.get("granted_at"),
+        expires_at: row.get("expires_at"),
+    }
+}
+
+fn permissions(values: Vec<String>) -> impl Iterator<Item = SharePermission> {
+    values
+        .into_iter()
+        .filter_map(|value| SharePermission::parse(&value))
+}
+
+async fn upsert_grant(
+    conn: &mut PgConnection,
+    resource: ShareResource,
+    resource_id: Uuid,
+    req: &CreateShareGrantRequest,
+    granted_by: Option<Uuid>,
+) -> Result<ShareGrant> {
+    let (table, column) = grant_table(resource);
+    let row = sqlx::query(&format!(
+        "INSERT INTO {table} ({column}, grantee_id, permission, granted_by, expires_at)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT ({column}, grantee_id) DO UPDATE
+         SET permission = EXCLUDED.permission, granted_by = EXCLUDED.granted_by,
+             expires_at = EXCLUDED.expires_at, granted_at = NOW(), revoked_at = NULL
+         RETURNING id, {column} AS resource_id, grantee_id, permission, granted_by,
+                   granted_at, expires_at"
+    ))
+    .bind(resource_id)
+    .bind(req.grantee_id)
+    .bind(req.permission.as_str())
+    .bind(granted_by)
+    .bind(req.expires_at)
+    .fetch_one(conn)
+    .await
+    .map_err(Error::Database)?;
+
+    Ok(grant_from_row(resource, &row))
+}
+
+async fn list_grants(
+    conn: &mut PgConnection,
+    resource: ShareResource,
+    resource_id: Uuid,
+) -> Result<Vec<ShareGrant>> {
+    let (table, column) = grant_table(resource);
+    let rows = sqlx::query(&format!(
+        "SELECT id, {column} AS resource_id, grantee_id, permission, granted_by,
+                granted_at, expires_at
+         FROM {table}
+         WHERE {column} = $1 AND {ACTIVE_GRANT}
+         ORDER BY granted_at"
+    ))
+    .bind(resource_id)
+    .fetch_all(conn)
+    .await
+    .map_err(Error::Database)?;
+
+    Ok(rows
+        .iter()
+        .map(|row| grant_from_row(resource, row))
+        .collect())
+}
+
+async fn revoke_grant(
+    conn: &mut PgConnection,
+    resource: ShareResource,
+    resource_id: Uuid,
+    grantee_id: Uuid,
+) -> Result<bool> {
+    let (table, column) = grant_table(resource);
+    let result = sqlx::query(&format!(
+        "UPDATE {table} SET revoked_at = NOW()
+         WHERE {column} = $1 AND grantee_id = $2 AND revoked_at IS NULL"
+    ))
+    .bind(resource_id)
+    .bind(grantee_id)
+    .execute(conn)
+    .await
+    .map_err(Error::Database)?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// PostgreSQL repository for users, ownership and share grants.
+#[derive(Clone)]
+pub struct PgUserRepository {
+    pool: Pool<Postgres>,
+}
+
+impl PgUserRepository {
+    /// Create a new user repository.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    /// The user an OAuth `user_id` signs in as, created on first sight.
+    pub async fn ensure(&self, external_id: &str) -> Result<User> {
+        if external_id.trim().is_empty() {
+            return Err(Error::InvalidInput("user id must not be empty".to_string()));
+        }
+
+        // Refresh last_seen_at only when it is stale, so most requests read.
+        let touched = sqlx::query(&format!(
+            "INSERT INTO app_user (external_id) VALUES ($1)
+             ON CONFLICT (external_id) DO UPDATE SET last_seen_at = NOW()
+             WHERE app_user.last_seen_at < NOW() - {LAST_SEEN_RESOLUTION}
+             RETURNING {USER_COLUMNS}"
+        ))
+        .bind(external_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        if let Some(row) = touched {
+            return Ok(user_from_row(&row));
+        }
+
+        let row = sqlx::query(&format!(
+            "SELECT {USER_COLUMNS} FROM app_user WHERE external_id = $1"
+        ))
+        .bind(external_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(user_from_row(&row))
+    }
+
+    /// Get a user by ID.
+    pub async fn get(&self, id: Uuid) -> Result<Option<User>> {
+        let row = sqlx::query(&format!(
+            "SELECT {USER_COLUMNS} FROM app_user WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+        Ok(row.as_ref().map(user_from_row))
+    }
+
+    /// Set or clear a user's display name.
+    pub async fn set_display_name(&self, id: Uuid, display_name: Option<&str>) -> Result<User> {
+        let row = sqlx::query(&format!(
+            "UPDATE app_user SET display_name = $2 WHERE id = $1 RETURNING {USER_COLUMNS}"
+        ))
+        .bind(id)
+        .bind(display_name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?
+        .ok_or_else(|| Error::NotFound("User not found".to_string()))?;
+        Ok(user_from_row(&row))
+    }
+
+    // =========================================================================
+    // ACCESS CHECKS
+    // =========================================================================
+
+    /// A user's access to a note: ownership, grants on the note or its
+    /// collection, and `internal`/`public` visibility. `None` when the note
+    /// does not exist or the user cannot see it.
+    pub async fn note_access_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<AccessLevel>> {
+        let row = sqlx::query(&format!(
+            "SELECT n.owner_id,
+                    COALESCE(n.visibility IN ('internal', 'public'), FALSE) AS openly_visible,
+                    ARRAY(
+                        SELECT permission FROM note_share_grant
+                        WHERE note_id = n.id AND grantee_id = $2 AND {ACTIVE_GRANT}
+                        UNION ALL
+                        SELECT permission FROM collection_share_grant
+                        WHERE collection_id = n.collection_id AND grantee_id = $2
+                          AND {ACTIVE_GRANT}
+                    ) AS grants
+             FROM note n WHERE n.id = $1"
+        ))
+        .bind(note_id)
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.and_then(|row| {
+            resolve_access(
+                row.get("owner_id"),
+                user_id,
+                permissions(row.get("grants")),
+                row.get("openly_visible"),
+            )
+        }))
+    }
+
+    /// The notes of `note_ids` the user may write, in ID order. Missing
+    /// notes are left out.
+    pub async fn writable_note_ids_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_ids: &[Uuid],
+        user_id: Uuid,
+    ) -> Result<Vec<Uuid>> {
+        sqlx::query_scalar(&format!(
+            "SELECT n.id FROM note n WHERE n.id = ANY($1) AND {} ORDER BY n.id",
+            note_writable_clause(2)
+        ))
+        .bind(note_ids)
+        .bind(user_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(Error::Database)
+    }
+
+    /// A user's access to a collection. `None` when it does not exist or the
+    /// user cannot see it.
+    pub async fn collection_access_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        collection_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<AccessLevel>> {
+        let row = sqlx::query(&format!(
+            "SELECT c.owner_id,
+                    ARRAY(
+                        SELECT permission FROM collection_share_grant
+                        WHERE collection_id = c.id AND grantee_id = $2 AND {ACTIVE_GRANT}
+                    ) AS grants
+             FROM collection c WHERE c.id = $1"
+        ))
+        .bind(collection_id)
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.and_then(|row| {
+            resolve_access(
+                row.get("owner_id"),
+                user_id,
+                permissions(row.get("grants")),
+                false,
+            )
+        }))
+    }
+
+    /// A user's access to a memory archive by name. `None` when it does not
+    /// exist or the user cannot see it.
+    pub async fn archive_access(&self, name: &str, user_id: Uuid) -> Result<Option<AccessLevel>> {
+        let row = sqlx::query(&format!(
+            "SELECT a.owner_id,
+                    ARRAY(
+                        SELECT permission FROM archive_share_grant
+                        WHERE archive_id = a.id AND grantee_id = $2 AND {ACTIVE_GRANT}
+                    ) AS grants
+             FROM archive_registry a WHERE a.name = $1"
+        ))
+        .bind(name)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.and_then(|row| {
+            resolve_access(
+                row.get("owner_id"),
+                user_id,
+                permissions(row.get("grants")),
+                false,
+            )
+        }))
+    }
+
+    /// Names of the archives a user can see.
+    pub async fn visible_archive_names(&self, user_id: Uuid) -> Result<Vec<String>> {
+        sqlx::query_scalar(&format!(
+            "SELECT a.name FROM archive_registry a
+             WHERE a.owner_id IS NULL OR a.owner_id = $1
+                OR EXISTS (
+                    SELECT 1 FROM archive_share_grant
+                    WHERE archive_id = a.id AND grantee_id = $1 AND {ACTIVE_GRANT}
+                )
+             ORDER BY a.name"
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::Database)
+    }
+
+    // =========================================================================
+    // OWNERSHIP
+    // =========================================================================
+
+    /// Record the owner of a note.
+    pub async fn set_note_owner_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        owner_id: Uuid,
+    ) -> Result<()> {
+        sqlx::query("UPDATE note SET owner_id = $2 WHERE id = $1")
+            .bind(note_id)
+            .bind(owner_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Record the owner of several notes created together.
+    pub async fn set_notes_owner_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_ids: &[Uuid],
+        owner_id: Uuid,
+    ) -> Result<()> {
+        sqlx::query("UPDATE note SET owner_id = $2 WHERE id = ANY($1)")
+            .bind(note_ids)
+            .bind(owner_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Record the owner of a collection.
+    pub async fn set_collection_owner_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        collection_id: Uuid,
+        owner_id: Uuid,
+    ) -> Result<()> {
+        sqlx::query("UPDATE collection SET owner_id = $2 WHERE id = $1")
+            .bind(collection_id)
+            .bind(owner_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    /// Record the owner of a memory archive.
+    pub async fn set_archive_owner(&self, name: &str, owner_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE archive_registry SET owner_id = $2 WHERE name = $1")
+            .bind(name)
+            .bind(owner_id)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+        Ok(())
+    }
+
+    // =========================================================================
+    // SHARE GRANTS
+    // =========================================================================
+
+    /// Share a note or collection, replacing any earlier grant to the same
+    /// user.
+    pub async fn share_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        resource: ShareResource,
+        resource_id: Uuid,
+        req: &CreateShareGrantRequest,
+        granted_by: Option<Uuid>,
+    ) -> Result<ShareGrant> {
+        upsert_grant(tx, resource, resource_id, req, granted_by).await
+    }
+
+    /// Active grants on a note or collection.
+    pub async fn list_grants_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        resource: ShareResource,
+        resource_id: Uuid,
+    ) -> Result<Vec<ShareGrant>> {
+        list_grants(tx, resource, resource_id).await
+    }
+
+    /// Revoke a user's grant on a note or collection. Returns whether an
+    /// active grant was revoked.
+    pub async fn revoke_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        resource: ShareResource,
+        resource_id: Uuid,
+        grantee_id: Uuid,
+    ) -> Result<bool> {
+        revoke_grant(tx, resource, resource_id, grantee_id).await
+    }
+
+    /// Share a memory archive, replacing any earlier grant to the same user.
+    pub async fn share_archive(
+        &self,
+        archive_id: Uuid,
+        req: &CreateShareGrantRequest,
+        granted_by: Option<Uuid>,
+    ) -> Result<ShareGrant> {
+        let mut conn = self.pool.acquire().await.map_err(Error::Database)?;
+        upsert_grant(
+            &mut conn,
+            ShareResource::Archive,
+            archive_id,
+            req,
+            granted_by,
+        )
+        .await
+    }
+
+    /// Active grants on a memory archive.
+    pub async fn list_archive_grants(&self, archive_id: Uuid) -> Result<Vec<ShareGrant>> {
+        let mut conn = self.pool.acquire().await.map_err(Error::Database)?;
+        list_grants(&mut conn, ShareResource::Archive, archive_id).await
+    }
+
+    /// Revoke a user's grant on a memory archive.
+    pub async fn revoke_archive_grant(&self, archive_id: Uuid, grantee_id: Uuid) -> Result<bool> {
+        let mut conn = self.pool.acquire().await.map_err(Error::Database)?;
+        revoke_grant(&mut conn, ShareResource::Archive, archive_id, grantee_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_resource_has_its_own_grant_table() {
+        assert_eq!(
+            grant_table(ShareResource::Note),
+            ("note_share_grant", "note_id")
+        );
+        assert_eq!(
+            grant_table(ShareResource::Collection),
+            ("collection_share_grant", "collection_id")
+        );
+        assert_eq!(
+            grant_table(ShareResource::Archive),
+            ("archive_share_grant", "archive_id")
+        );
+    }
+
+    #[test]
+    fn unknown_stored_permissions_are_ignored() {
+        let parsed: Vec<_> = permissions(vec![
+            "read".to_string(),
+            "owner".to_string(),
+            "admin".to_string(),
+        ])
+        .collect();
+        assert_eq!(parsed, vec![SharePermission::Read, SharePermission::Write]);
+    }
+}
@@ -5,6 +5,8 @@ use sqlx::{PgPool, Postgres, Transaction};
 use std::fmt;
 use uuid::Uuid;
 
+use matric_core::VersionRetentionPolicy;
+
 use crate::error::{Error, Result};
 
 /// A version entry in the original content history.
@@ -40,6 +42,8 @@ pub struct VersionSummary {
     pub created_at_utc: DateTime<Utc>,
     pub created_by: String,
     pub is_current: bool,
+    /// Marked to survive retention pruning
+    pub is_milestone: bool,
 }
 
 impl fmt::Debug for VersionSummary {
@@ -49,6 +53,7 @@ impl fmt::Debug for VersionSummary {
             .field("created_at_utc", &self.created_at_utc)
             .field("created_by_len", &self.created_by.len())
             .field("is_current", &self.is_current)
+            .field("is_milestone", &self.is_milestone)
             .finish()
     }
 }
@@ -97,6 +102,28 @@ impl fmt::Debug for NoteVersions {
     }
 }
 
+/// Inputs for a three-way merge of a concurrent edit, loaded by
+/// [`VersioningRepository::merge_sources_tx`].
+#[derive(Clone)]
+pub struct MergeSources {
+    /// Version number of the current original content.
+    pub current_version: i32,
+    /// Current original content.
+    pub current: String,
+    /// Content of the requested base version, if it is still available.
+    pub base: Option<String>,
+}
+
+impl fmt::Debug for MergeSources {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MergeSources")
+            .field("current_version", &self.current_version)
+            .field("current_len", &self.current.len())
+            .field("base_len", &self.base.as_ref().map(String::len))
+            .finish()
+    }
+}
+
 /// Repository for note version history.
 pub struct VersioningRepository {
     pool: PgPool,
@@ -121,9 +148,9 @@ impl VersioningRepository {
         let current_original_version = current_original.map(|r| r.0).unwrap_or(1);
 
         // Get original version history
-        let original_history: Vec<(i32, DateTime<Utc>, String)> = sqlx::query_as(
+        let original_history: Vec<(i32, DateTime<Utc>, String, bool)> = sqlx::query_as(
             r#"
-            SELECT version_number, created_at_utc, created_by
+            SELECT version_number, created_at_utc, created_by, milestone
             FROM note_original_history
             WHERE note_id = $1
             ORDER BY version_number DESC
@@ -137,11 +164,12 @@ impl VersioningRepository {
         let mut original_versions: Vec<VersionSummary> = original_history
             .into_iter()
             .map(
-                |(version_number, created_at_utc, created_by)| VersionSummary {
+                |(version_number, created_at_utc, created_by, is_milestone)| VersionSummary {
                     version_number,
                     created_at_utc,
                     created_by,
                     is_current: false,
+                    is_milestone,
                 },
             )
             .collect();
@@ -149,12 +177,13 @@ impl VersioningRepository {
         // Add current version to the list
         if let Some((version,)) = current_original {
             // Get the timestamp from note_original
-            let current_time: Option<(DateTime<Utc>,)> =
-                sqlx::query_as("SELECT user_last_edited_at FROM note_original WHERE note_id = $1")
-                    .bind(note_id)
-                    .fetch_optional(&self.pool)
-                    .await
-                    .map_err(Error::Database)?;
+            let current_time: Option<(DateTime<Utc>, bool)> = sqlx::query_as(
+                "SELECT user_last_edited_at, milestone FROM note_original WHERE note_id = $1",
+            )
+            .bind(note_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(Error::Database)?;
 
             original_versions.insert(
                 0,
@@ -163,6 +192,7 @@ impl VersioningRepository {
                     created_at_utc: current_time.map(|t| t.0).unwrap_or_else(Utc::now),
                     created_by: "user".to_string(),
                     is_current: true,
+                    is_milestone: current_time.is_some_and(|t| t.1),
                 },
             );
         }
@@ -504,6 +534,102 @@ impl VersioningRepository {
 
         Ok(())
     }
+
+    /// Global retention policy, used by archives without an override.
+    pub async fn default_retention_policy(&self) -> Result<VersionRetentionPolicy> {
+        let keep_milestones: Option<(serde_json::Value,)> = sqlx::query_as(
+            "SELECT value FROM user_config WHERE key = 'versioning_keep_milestones'",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(VersionRetentionPolicy {
+            max_versions: Some(self.get_max_history().await?),
+            max_age_days: None,
+            keep_milestones: keep_milestones
+                .map(|r| r.0.as_bool().unwrap_or(true))
+                .unwrap_or(true),
+        })
+    }
+
+    /// An archive's retention override, if it has one.
+    pub async fn archive_retention_policy(
+        &self,
+        schema_name: &str,
+    ) -> Result<Option<VersionRetentionPolicy>> {
+        let row: Option<(Option<i32>, Option<i32>, bool)> = sqlx::query_as(
+            r#"
+            SELECT max_versions, max_age_days, keep_milestones
+            FROM archive_version_policy
+            WHERE schema_name = $1
+            "#,
+        )
+        .bind(schema_name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(row.map(
+            |(max_versions, max_age_days, keep_milestones)| VersionRetentionPolicy {
+                max_versions,
+                max_age_days,
+                keep_milestones,
+            },
+        ))
+    }
+
+    /// The policy that applies to an archive: its override, else the default.
+    pub async fn effective_retention_policy(
+        &self,
+        schema_name: &str,
+    ) -> Result<VersionRetentionPolicy> {
+        match self.archive_retention_policy(schema_name).await? {
+            Some(policy) => Ok(policy),
+            None => self.default_retention_policy().await,
+        }
+    }
+
+    /// Create or replace an archive's retention override.
+    pub async fn set_archive_retention_policy(
+        &self,
+        schema_name: &str,
+        policy: &VersionRetentionPolicy,
+    ) -> Result<()> {
+        policy.validate()?;
+        sqlx::query(
+            r#"
+            INSERT INTO archive_version_policy
+                (schema_name, max_versions, max_age_days, keep_milestones)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (schema_name) DO UPDATE SET
+                max_versions = EXCLUDED.max_versions,
+                max_age_days = EXCLUDED.max_age_days,
+                keep_milestones = EXCLUDED.keep_milestones,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(schema_name)
+        .bind(policy.max_versions)
+        .bind(policy.max_age_days)
+        .bind(policy.keep_milestones)
+        .execute(&self.pool)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(())
+    }
+
+    /// Remove an archive's override so it follows the default again.
+    pub async fn delete_archive_retention_policy(&self, schema_name: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM archive_version_policy WHERE schema_name = $1")
+            .bind(schema_name)
+            .execute(&self.pool)
+            .await
+            .map_err(Error::Database)?;
+
+        Ok(result.rows_affected() > 0)
+    }
 }
 
 /// Transaction-aware variants for archive-scoped operations.
@@ -525,9 +651,9 @@ impl VersioningRepository {
         let current_original_version = current_original.map(|r| r.0).unwrap_or(1);
 
         // Get original version history
-        let original_history: Vec<(i32, DateTime<Utc>, String)> = sqlx::query_as(
+        let original_history: Vec<(i32, DateTime<Utc>, String, bool)> = sqlx::query_as(
             r#"
-            SELECT version_number, created_at_utc, created_by
+            SELECT version_number, created_at_utc, created_by, milestone
             FROM note_original_history
             WHERE note_id = $1
             ORDER BY version_number DESC
@@ -541,11 +667,12 @@ impl VersioningRepository {
         let mut original_versions: Vec<VersionSummary> = original_history
             .into_iter()
             .map(
-                |(version_number, created_at_utc, created_by)| VersionSummary {
+                |(version_number, created_at_utc, created_by, is_milestone)| VersionSummary {
                     version_number,
                     created_at_utc,
                     created_by,
                     is_current: false,
+                    is_milestone,
                 },
             )
             .collect();
@@ -553,12 +680,13 @@ impl VersioningRepository {
         // Add current version to the list
         if let Some((version,)) = current_original {
             // Get the timestamp from note_original
-            let current_time: Option<(DateTime<Utc>,)> =
-                sqlx::query_as("SELECT user_last_edited_at FROM note_original WHERE note_id = $1")
-                    .bind(note_id)
-                    .fetch_optional(&mut **tx)
-                    .await
-                    .map_err(Error::Database)?;
+            let current_time: Option<(DateTime<Utc>, bool)> = sqlx::query_as(
+                "SELECT user_last_edited_at, milestone FROM note_original WHERE note_id = $1",
+            )
+            .bind(note_id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(Error::Database)?;
 
             original_versions.insert(
                 0,
@@ -567,6 +695,7 @@ impl VersioningRepository {
                     created_at_utc: current_time.map(|t| t.0).unwrap_or_else(Utc::now),
                     created_by: "user".to_string(),
                     is_current: true,
+                    is_milestone: current_time.is_some_and(|t| t.1),
                 },
             );
         }
@@ -820,6 +949,129 @@ impl VersioningRepository {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Lock a note's original content and load it with the content of
+    /// `base_version`, the version a concurrent edit started from.
+    ///
+    /// The row stays locked until the transaction ends, so the merged result
+    /// can be written without another edit slipping in. Returns `None` when
+    /// the note does not exist; `base` is `None` when the version has been
+    /// pruned from the history or was never created.
+    pub async fn merge_sources_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        base_version: i32,
+    ) -> Result<Option<MergeSources>> {
+        let current: Option<(i32, String)> = sqlx::query_as(
+            "SELECT version_number, content FROM note_original WHERE note_id = $1 FOR UPDATE",
+        )
+        .bind(note_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        let Some((current_version, current)) = current else {
+            return Ok(None);
+        };
+
+        let base = if base_version == current_version {
+            Some(current.clone())
+        } else {
+            // History snapshots carry a tag frontmatter the live content lacks.
+            sqlx::query_scalar::<_, String>(
+                "SELECT content FROM note_original_history WHERE note_id = $1 AND version_number = $2",
+            )
+            .bind(note_id)
+            .bind(base_version)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(Error::Database)?
+            .map(|content| strip_frontmatter(&content))
+        };
+
+        Ok(Some(MergeSources {
+            current_version,
+            current,
+            base,
+        }))
+    }
+
+    /// Mark or unmark a version as a milestone. The current version can be
+    /// marked too; the flag follows it into the history on the next edit.
+    /// Returns false when the version does not exist.
+    pub async fn set_milestone_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        note_id: Uuid,
+        version: i32,
+        milestone: bool,
+    ) -> Result<bool> {
+        let current = sqlx::query(
+            "UPDATE note_original SET milestone = $3 WHERE note_id = $1 AND version_number = $2",
+        )
+        .bind(note_id)
+        .bind(version)
+        .bind(milestone)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+        if current.rows_affected() > 0 {
+            return Ok(true);
+        }
+
+        let history = sqlx::query(
+            "UPDATE note_original_history SET milestone = $3 WHERE note_id = $1 AND version_number = $2",
+        )
+        .bind(note_id)
+        .bind(version)
+        .bind(milestone)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(history.rows_affected() > 0)
+    }
+
+    /// Delete every history entry in the transaction's archive that `policy`
+    /// no longer retains. Returns the number of versions pruned.
+    pub async fn prune_versions_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        policy: &VersionRetentionPolicy,
+    ) -> Result<u64> {
+        if policy.is_unbounded() {
+            return Ok(0);
+        }
+
+        // Same rules as VersionRetentionPolicy::prunes, evaluated in SQL.
+        let result = sqlx::query(
+            r#"
+            DELETE FROM note_original_history h
+            USING (
+                SELECT id,
+                       row_number() OVER (
+                           PARTITION BY note_id ORDER BY version_number DESC
+                       ) AS rank
+                FROM note_original_history
+            ) ranked
+            WHERE h.id = ranked.id
+              AND NOT ($3 AND h.milestone)
+              AND (
+                  ($1::INTEGER IS NOT NULL AND ranked.rank > $1)
+                  OR ($2::INTEGER IS NOT NULL
+                      AND h.created_at_utc < NOW() - make_interval(days => $2))
+              )
+            "#,
+        )
+        .bind(policy.max_versions)
+        .bind(policy.max_age_days)
+        .bind(policy.keep_milestones)
+        .execute(&mut **tx)
+        .await
+        .map_err(Error::Database)?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Transaction-aware variant of diff_versions.
     pub async fn diff_versions_tx(
         &self,
@@ -872,6 +1124,18 @@ fn strip_frontmatter(content: &str) -> String {
     content.to_string()
 }
 
+/// Tags recorded in a version's `snapshot_tags` frontmatter line, if any.
+///
+/// Accepts a whole version or just its frontmatter.
+pub(crate) fn snapshot_tags(content: &str) -> Option<Vec<String>> {
+    content
+        .strip_prefix("---\n")?
+        .lines()
+        .take_while(|line| *line != "---")
+        .find_map(|line| line.strip_prefix("snapshot_tags: "))
+        .and_then(|tags_json| serde_json::from_str(tags_json).ok())
+}
+
 fn version_not_found_error(_version: i32) -> Error {
     Error::NotFound("Version not found; version_present=true".to_string())
 }
@@ -902,6 +1166,7 @@ mod tests {
             created_at_utc: timestamp,
             created_by: "editor@example.com".to_string(),
             is_current: true,
+            is_milestone: false,
         };
         let revision = RevisionVersionSummary {
             id: revision_id,
@@ -942,6 +1207,22 @@ mod tests {
         assert!(!debug.contains("tenant-private-model"));
     }
 
+    #[test]
+    fn snapshot_tags_reads_frontmatter_only() {
+        let version =
+            "---\nsnapshot_tags: [\"rust\",\"db\"]\nsnapshot_at: \"2026-10-01\"\n---\nbody";
+        assert_eq!(
+            snapshot_tags(version),
+            Some(vec!["rust".to_string(), "db".to_string()])
+        );
+        assert_eq!(
+            snapshot_tags("---\nsnapshot_tags: []\nsnapshot_at: \"x\""),
+            Some(vec![])
+        );
+        assert_eq!(snapshot_tags("---\n---\nsnapshot_tags: [\"body\"]"), None);
+        assert_eq!(snapshot_tags("no frontmatter"), None);
+    }
+
     #[test]
     fn version_not_found_errors_report_metadata_without_raw_values() {
         let raw_version = 8675309;
@@ -0,0 +1,112 @@
+//! Row-level visibility enforcement.
+//!
+//! Queries that list notes apply a caller's [`StrictSecurityFilter`] inline
+//! through [`UnifiedFilterQueryBuilder`]. Results assembled from several
+//! queries — fused search hits, links, graph traversals — are filtered
+//! afterwards with [`retain_visible`], which runs the same security clause
+//! over the candidate note IDs so both paths admit exactly the same notes.
+//!
+//! A `None` filter means the caller is not restricted and keeps every row.
+
+use std::collections::HashSet;
+
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::links::GraphResult;
+use crate::unified_filter::{QueryParam, UnifiedFilterQueryBuilder};
+use matric_core::{Error, Result, StrictFilter, StrictSecurityFilter};
+
+/// The IDs among `note_ids` that `security` admits.
+pub async fn visible_note_ids<'e, E>(
+    executor: E,
+    note_ids: &[Uuid],
+    security: &StrictSecurityFilter,
+) -> Result<HashSet<Uuid>>
+where
+    E: PgExecutor<'e>,
+{
+    if note_ids.is_empty() || security.is_empty() {
+        return Ok(note_ids.iter().copied().collect());
+    }
+
+    let filter = StrictFilter::new().with_security(security.clone());
+    let result = UnifiedFilterQueryBuilder::new(filter, 1).build();
+    let sql = format!(
+        "SELECT n.id FROM note n WHERE n.id = ANY($1::uuid[]) AND {}",
+        result.where_clause
+    );
+
+    let mut q = sqlx::query_scalar::<_, Uuid>(&sql).bind(note_ids);
+    for param in &result.params {
+        q = match param {
+            QueryParam::Uuid(id) => q.bind(id),
+            QueryParam::UuidArray(ids) => q.bind(ids),
+            QueryParam::Int(val) => q.bind(val),
+            QueryParam::Timestamp(ts) => q.bind(ts),
+            QueryParam::Bool(b) => q.bind(b),
+            QueryParam::String(s) => q.bind(s),
+            QueryParam::StringArray(arr) => q.bind(arr),
+        };
+    }
+
+    let ids = q.fetch_all(executor).await.map_err(Error::Database)?;
+    Ok(ids.into_iter().collect())
+}
+
+/// Drop the items whose note `security` does not admit. Items that do not
+/// reference a note (`note_id` returns `None`, e.g. links to URLs) are kept.
+pub async fn retain_visible<'e, E, T>(
+    executor: E,
+    items: &mut Vec<T>,
+    security: Option<&StrictSecurityFilter>,
+    note_id: impl Fn(&T) -> Option<Uuid>,
+) -> Result<()>
+where
+    E: PgExecutor<'e>,
+{
+    let Some(security) = security.filter(|security| !security.is_empty()) else {
+        return Ok(());
+    };
+    let mut candidates: Vec<Uuid> = items.iter().filter_map(&note_id).collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+    if candidates.is_empty() {
+        return Ok(());
+    }
+
+    let visible = visible_note_ids(executor, &candidates, security).await?;
+    items.retain(|item| note_id(item).is_none_or(|id| visible.contains(&id)));
+    Ok(())
+}
+
+/// Drop the graph nodes `security` does not admit along with every edge
+/// touching them. The starting node is expected to have been checked by the
+/// caller. Totals in the graph metadata are reduced by the removed counts so
+/// they do not reveal hidden notes.
+pub async fn retain_visible_graph<'e, E>(
+    executor: E,
+    graph: &mut GraphResult,
+    security: Option<&StrictSecurityFilter>,
+) -> Result<()>
+where
+    E: PgExecutor<'e>,
+{
+    let Some(security) = security.filter(|security| !security.is_empty()) else {
+        return Ok(());
+    };
+    let node_ids: Vec<Uuid> = graph.nodes.iter().map(|node| node.id).collect();
+    let visible = visible_note_ids(executor, &node_ids, security).await?;
+
+    let (nodes_before, edges_before) = (graph.nodes.len(), graph.edges.len());
+    graph.nodes.retain(|node| visible.contains(&node.id));
+    graph
+        .edges
+        .retain(|edge| visible.contains(&edge.source) && visible.contains(&edge.target));
+
+    let removed_nodes = (nodes_before - graph.nodes.len()) as i64;
+    let removed_edges = (edges_before - graph.edges.len()) as i64;
+    graph.meta.total_nodes = (graph.meta.total_nodes - removed_nodes).max(0);
+    graph.meta.total_edges = (graph.meta.total_edges - removed_edges).max(0);
+    Ok(())
+}
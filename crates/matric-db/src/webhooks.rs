@@ -21,13 +21,14 @@ impl PgWebhookRepository {
         let id = matric_core::new_v7();
         let now = Utc::now();
         sqlx::query(
-            "INSERT INTO webhook (id, url, secret, events, max_retries, created_at, updated_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            "INSERT INTO webhook (id, url, secret, events, filters, max_retries, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
         )
         .bind(id)
         .bind(&req.url)
         .bind(&req.secret)
         .bind(&req.events)
+        .bind(&req.filters)
         .bind(req.max_retries)
         .bind(now)
         .bind(now)
@@ -40,7 +41,7 @@ impl PgWebhookRepository {
     /// List all webhooks.
     pub async fn list(&self) -> Result<Vec<Webhook>> {
         let rows = sqlx::query(
-            "SELECT id, url, secret, events, is_active, created_at, updated_at,
+            "SELECT id, url, secret, events, filters, is_active, created_at, updated_at,
                     last_triggered_at, failure_count, max_retries
              FROM webhook ORDER BY created_at DESC",
         )
@@ -54,7 +55,7 @@ impl PgWebhookRepository {
     /// Get a webhook by ID.
     pub async fn get(&self, id: Uuid) -> Result<Option<Webhook>> {
         let row = sqlx::query(
-            "SELECT id, url, secret, events, is_active, created_at, updated_at,
+            "SELECT id, url, secret, events, filters, is_active, created_at, updated_at,
                     last_triggered_at, failure_count, max_retries
              FROM webhook WHERE id = $1",
         )
@@ -82,6 +83,7 @@ impl PgWebhookRepository {
         id: Uuid,
         url: Option<&str>,
         events: Option<&[String]>,
+        filters: Option<&[String]>,
         secret: Option<&str>,
         is_active: Option<bool>,
     ) -> Result<()> {
@@ -90,13 +92,15 @@ impl PgWebhookRepository {
             "UPDATE webhook SET
                 url = COALESCE($1, url),
                 events = COALESCE($2, events),
-                secret = COALESCE($3, secret),
-                is_active = COALESCE($4, is_active),
-                updated_at = $5
-             WHERE id = $6",
+                filters = COALESCE($3, filters),
+                secret = COALESCE($4, secret),
+                is_active = COALESCE($5, is_active),
+                updated_at = $6
+             WHERE id = $7",
         )
         .bind(url)
         .bind(events)
+        .bind(filters)
         .bind(secret)
         .bind(is_active)
         .bind(now)
@@ -110,7 +114,7 @@ impl PgWebhookRepository {
     /// List active webhooks subscribed to a specific event type.
     pub async fn list_active_for_event(&self, event_type: &str) -> Result<Vec<Webhook>> {
         let rows = sqlx::query(
-            "SELECT id, url, secret, events, is_active, created_at, updated_at,
+            "SELECT id, url, secret, events, filters, is_active, created_at, updated_at,
                     last_triggered_at, failure_count, max_retries
              FROM webhook
              WHERE is_active = true AND ($1 = ANY(events) OR events = '{}')",
@@ -214,6 +218,7 @@ impl PgWebhookRepository {
             url: r.get("url"),
             secret: r.get("secret"),
             events: r.get("events"),
+            filters: r.get("filters"),
             is_active: r.get("is_active"),
             created_at: r.get("created_at"),
             updated_at: r.get("updated_at"),
@@ -247,6 +252,7 @@ mod tests {
             url: url.to_string(),
             secret: Some("test-secret".to_string()),
             events: vec!["JobCompleted".to_string(), "NoteUpdated".to_string()],
+            filters: vec![],
             max_retries: 3,
         }
     }
@@ -281,6 +287,7 @@ mod tests {
                 url: format!("https://list-test-{}-1.example.com", suffix),
                 secret: None,
                 events: vec![],
+                filters: vec![],
                 max_retries: 3,
             })
             .await
@@ -290,6 +297,7 @@ mod tests {
                 url: format!("https://list-test-{}-2.example.com", suffix),
                 secret: None,
                 events: vec![],
+                filters: vec![],
                 max_retries: 3,
             })
             .await
@@ -299,6 +307,7 @@ mod tests {
                 url: format!("https://list-test-{}-3.example.com", suffix),
                 secret: None,
                 events: vec![],
+                filters: vec![],
                 max_retries: 3,
             })
             .await
@@ -341,9 +350,16 @@ mod tests {
         let before = repo.get(id).await.unwrap().unwrap();
 
         // Update only URL, leave everything else as None
-        repo.update(id, Some("https://updated.example.com"), None, None, None)
-            .await
-            .unwrap();
+        repo.update(
+            id,
+            Some("https://updated.example.com"),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
 
         let after = repo.get(id).await.unwrap().unwrap();
         assert_eq!(after.url, "https://updated.example.com");
@@ -357,6 +373,30 @@ mod tests {
         repo.delete(id).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_webhook_filters_round_trip() {
+        let repo = setup().await;
+        let mut req = test_request(&test_url());
+        req.filters = vec![r#".payload.tags contains "project/x""#.to_string()];
+        let id = repo.create(req).await.unwrap();
+        assert_eq!(
+            repo.get(id).await.unwrap().unwrap().filters,
+            vec![r#".payload.tags contains "project/x""#.to_string()]
+        );
+
+        // Updating other fields keeps the filters; an empty list clears them.
+        repo.update(id, None, None, None, None, Some(true))
+            .await
+            .unwrap();
+        assert_eq!(repo.get(id).await.unwrap().unwrap().filters.len(), 1);
+        repo.update(id, None, None, Some([].as_slice()), None, None)
+            .await
+            .unwrap();
+        assert!(repo.get(id).await.unwrap().unwrap().filters.is_empty());
+
+        repo.delete(id).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_webhook_update_is_active() {
         let repo = setup().await;
@@ -365,12 +405,14 @@ mod tests {
 
         assert!(repo.get(id).await.unwrap().unwrap().is_active);
 
-        repo.update(id, None, None, None, Some(false))
+        repo.update(id, None, None, None, None, Some(false))
             .await
             .unwrap();
         assert!(!repo.get(id).await.unwrap().unwrap().is_active);
 
-        repo.update(id, None, None, None, Some(true)).await.unwrap();
+        repo.update(id, None, None, None, None, Some(true))
+            .await
+            .unwrap();
         assert!(repo.get(id).await.unwrap().unwrap().is_active);
 
         repo.delete(id).await.unwrap();
@@ -387,6 +429,7 @@ mod tests {
                 url: format!("https://filter-a-{}.example.com", suffix),
                 secret: None,
                 events: vec!["JobCompleted".to_string(), "NoteUpdated".to_string()],
+                filters: vec![],
                 max_retries: 3,
             })
             .await
@@ -398,6 +441,7 @@ mod tests {
                 url: format!("https://filter-b-{}.example.com", suffix),
                 secret: None,
                 events: vec!["JobFailed".to_string()],
+                filters: vec![],
                 max_retries: 3,
             })
             .await
@@ -409,11 +453,12 @@ mod tests {
                 url: format!("https://filter-c-{}.example.com", suffix),
                 secret: None,
                 events: vec!["JobCompleted".to_string()],
+                filters: vec![],
                 max_retries: 3,
             })
             .await
             .unwrap();
-        repo.update(id_c, None, None, None, Some(false))
+        repo.update(id_c, None, None, None, None, Some(false))
             .await
             .unwrap();
 
@@ -461,6 +506,7 @@ mod tests {
                 url: format!("https://catch-all-{}.example.com", suffix),
                 secret: None,
                 events: vec![],
+                filters: vec![],
                 max_retries: 3,
             })
             .await
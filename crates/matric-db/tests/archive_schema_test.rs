@@ -357,6 +357,7 @@ async fn test_schema_drift_detection() {
         "document_type",
         "embedding_config",
         "event_outbox",
+        "events",
         "file_upload_audit",
         "inbound_dlq",
         "inbound_source",
@@ -54,6 +54,7 @@ async fn test_has_revision_false_when_content_identical() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -119,6 +120,7 @@ async fn test_has_revision_true_when_content_differs() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -194,6 +196,7 @@ async fn test_has_revision_false_after_revision_mode_none() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
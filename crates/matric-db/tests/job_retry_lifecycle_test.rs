@@ -1,10 +1,14 @@
 use chrono::{Duration, Utc};
+use matric_core::dead_letter::{DeadLetterSelection, POISON_PAYLOAD_CODE};
+use matric_core::job_lane::JobLane;
+use matric_core::job_worker::{WorkerRegistration, WorkerState};
 use matric_core::{
-    JobFailureClass, JobRepository, JobRetryOutcome, JobRetryPolicy, JobStatus, JobType,
+    JobFailureClass, JobRepository, JobRetryOutcome, JobRetryPolicy, JobStatus, JobType, TierGroup,
 };
 use matric_db::PgJobRepository;
 use serde_json::json;
 use sqlx::postgres::PgPoolOptions;
+use uuid::Uuid;
 
 async fn isolated_job_pool() -> sqlx::PgPool {
     let database_url = std::env::var("DATABASE_URL")
@@ -23,6 +27,14 @@ async fn isolated_job_pool() -> sqlx::PgPool {
         .execute(&pool)
         .await
         .expect("create session-local job attempt table");
+    for table in ["job_worker", "job_worker_leader"] {
+        sqlx::query(&format!(
+            "CREATE TEMP TABLE {table} (LIKE public.{table} INCLUDING ALL)"
+        ))
+        .execute(&pool)
+        .await
+        .expect("create session-local worker registry");
+    }
     pool
 }
 
@@ -237,3 +249,312 @@ async fn stale_reaper_schedules_backoff_instead_of_immediate_reclaim() {
     assert!(result_is_null);
     pool.close().await;
 }
+
+async fn claim_and_retry(repository: &PgJobRepository, pool: &sqlx::PgPool) -> JobRetryOutcome {
+    sqlx::query("UPDATE job_queue SET next_attempt_at = NULL WHERE status = 'pending'")
+        .execute(pool)
+        .await
+        .expect("make retries due");
+    let job = repository
+        .claim_next_for_types(&[JobType::ContextUpdate])
+        .await
+        .expect("claim attempt")
+        .expect("attempt should be ready");
+    repository
+        .retry(
+            job.id,
+            "provider temporarily unavailable",
+            JobFailureClass::Transient,
+            "provider_unavailable",
+            Utc::now() + Duration::minutes(5),
+        )
+        .await
+        .expect("record failed attempt")
+}
+
+#[tokio::test]
+async fn repeatedly_failing_payload_is_quarantined_and_can_be_retried_or_discarded() {
+    let pool = isolated_job_pool().await;
+    let repository = PgJobRepository::new(pool.clone());
+    let payload = json!({"schema": "public", "operation": "poison-test"});
+
+    let first = repository
+        .queue(None, JobType::ContextUpdate, 5, Some(payload.clone()), None)
+        .await
+        .expect("queue first job");
+    for _ in 0..3 {
+        assert!(matches!(
+            claim_and_retry(&repository, &pool).await,
+            JobRetryOutcome::Scheduled { .. }
+        ));
+    }
+    assert_eq!(
+        claim_and_retry(&repository, &pool).await,
+        JobRetryOutcome::Exhausted
+    );
+
+    // A fresh job with the same payload inherits its failure history.
+    let second = repository
+        .queue(None, JobType::ContextUpdate, 5, Some(payload), None)
+        .await
+        .expect("queue second job");
+    assert_eq!(
+        claim_and_retry(&repository, &pool).await,
+        JobRetryOutcome::Quarantined
+    );
+
+    let (dead, total) = repository
+        .list_dead_letters(None, Some(JobFailureClass::Poison), 10, 0)
+        .await
+        .expect("list poison jobs");
+    assert_eq!(total, 1);
+    assert_eq!(dead[0].id, second);
+    assert_eq!(dead[0].failure_code.as_deref(), Some(POISON_PAYLOAD_CODE));
+    assert_eq!(dead[0].attempts, 1);
+
+    let retried = repository
+        .retry_dead_letters(&DeadLetterSelection {
+            job_ids: Some(vec![first]),
+            ..Default::default()
+        })
+        .await
+        .expect("retry dead letter");
+    assert_eq!(retried, vec![first]);
+    let job = repository.get(first).await.unwrap().unwrap();
+    assert_eq!(job.status, JobStatus::Pending);
+    assert_eq!(job.retry_count, 4);
+    assert!(job.max_retries > job.retry_count);
+
+    let discarded = repository
+        .discard_dead_letters(&DeadLetterSelection::default())
+        .await
+        .expect("discard dead letters");
+    assert_eq!(discarded, vec![second]);
+    let (_, remaining) = repository
+        .list_dead_letters(None, None, 10, 0)
+        .await
+        .expect("list after discard");
+    assert_eq!(remaining, 0);
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn checkpoint_survives_retries_and_is_cleared_on_completion() {
+    let pool = isolated_job_pool().await;
+    let repository = PgJobRepository::new(pool.clone());
+    let job_id = repository
+        .queue(None, JobType::ContextUpdate, 5, None, None)
+        .await
+        .expect("queue job");
+
+    assert!(
+        !repository
+            .save_checkpoint(job_id, &json!({"next": 1}))
+            .await
+            .expect("save before claim"),
+        "a pending job cannot save a checkpoint"
+    );
+
+    repository
+        .claim_next_for_types(&[JobType::ContextUpdate])
+        .await
+        .expect("claim first attempt")
+        .expect("first attempt should be ready");
+    assert!(repository
+        .save_checkpoint(job_id, &json!({"next": 100}))
+        .await
+        .expect("save while running"));
+    repository
+        .retry(
+            job_id,
+            "provider temporarily unavailable",
+            JobFailureClass::Transient,
+            "provider_unavailable",
+            Utc::now(),
+        )
+        .await
+        .expect("schedule retry");
+    assert_eq!(
+        repository
+            .get_checkpoint(job_id)
+            .await
+            .expect("load checkpoint"),
+        Some(json!({"next": 100}))
+    );
+
+    sqlx::query("UPDATE job_queue SET next_attempt_at = NULL WHERE id = $1")
+        .bind(job_id)
+        .execute(&pool)
+        .await
+        .expect("make retry due");
+    repository
+        .claim_next_for_types(&[JobType::ContextUpdate])
+        .await
+        .expect("claim second attempt")
+        .expect("second attempt should be ready");
+    repository
+        .complete(job_id, None)
+        .await
+        .expect("complete job");
+    assert_eq!(
+        repository
+            .get_checkpoint(job_id)
+            .await
+            .expect("load checkpoint"),
+        None
+    );
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn interactive_lane_is_claimed_ahead_of_higher_priority_batch_jobs() {
+    let pool = isolated_job_pool().await;
+    let repository = PgJobRepository::new(pool.clone());
+    let batch = repository
+        .queue_in_lane(None, JobType::ContextUpdate, 9, None, None, JobLane::Batch)
+        .await
+        .expect("queue batch job");
+    let interactive = repository
+        .queue(None, JobType::ContextUpdate, 1, None, None)
+        .await
+        .expect("queue interactive job");
+    let later_interactive = repository
+        .queue(None, JobType::ContextUpdate, 1, None, None)
+        .await
+        .expect("queue second interactive job");
+
+    let first = repository
+        .claim_next_for_tier(TierGroup::CpuAndAgnostic, &[JobType::ContextUpdate])
+        .await
+        .expect("claim interactive")
+        .expect("interactive job should be ready");
+    assert_eq!(first.id, interactive);
+
+    let fair_share = repository
+        .claim_next_for_tier_in_lane(
+            TierGroup::CpuAndAgnostic,
+            &[JobType::ContextUpdate],
+            &[],
+            JobLane::Batch,
+            None,
+        )
+        .await
+        .expect("claim batch")
+        .expect("batch job should be ready");
+    assert_eq!(fair_share.id, batch);
+
+    let fallback = repository
+        .claim_next_for_tier_in_lane(
+            TierGroup::CpuAndAgnostic,
+            &[JobType::ContextUpdate],
+            &[],
+            JobLane::Batch,
+            None,
+        )
+        .await
+        .expect("claim with empty batch lane")
+        .expect("interactive job should be used when batch is empty");
+    assert_eq!(fallback.id, later_interactive);
+    pool.close().await;
+}
+
+fn worker(id: Uuid) -> WorkerRegistration {
+    WorkerRegistration {
+        id,
+        hostname: "replica".to_string(),
+        pid: 1,
+        version: "test".to_string(),
+        max_concurrent: 2,
+        heartbeat_timeout_secs: 60,
+    }
+}
+
+#[tokio::test]
+async fn jobs_of_a_silent_worker_are_reclaimed_while_live_workers_keep_theirs() {
+    let pool = isolated_job_pool().await;
+    let repository = PgJobRepository::new(pool.clone());
+    let (live, silent) = (Uuid::now_v7(), Uuid::now_v7());
+    for id in [live, silent] {
+        repository
+            .register_worker(&worker(id))
+            .await
+            .expect("register worker");
+    }
+    let mut claimed = Vec::new();
+    for worker_id in [live, silent] {
+        repository
+            .queue(None, JobType::ContextUpdate, 1, None, None)
+            .await
+            .expect("queue job");
+        let job = repository
+            .claim_next_for_tier_in_lane(
+                TierGroup::CpuAndAgnostic,
+                &[JobType::ContextUpdate],
+                &[],
+                JobLane::Interactive,
+                Some(worker_id),
+            )
+            .await
+            .expect("claim job")
+            .expect("job should be ready");
+        claimed.push(job.id);
+    }
+    sqlx::query(
+        "UPDATE job_worker SET last_heartbeat_at = NOW() - INTERVAL '5 minutes' WHERE id = $1",
+    )
+    .bind(silent)
+    .execute(&pool)
+    .await
+    .expect("silence worker");
+
+    assert!(repository
+        .acquire_worker_leadership(live, 60)
+        .await
+        .expect("take lease"));
+    assert!(!repository
+        .acquire_worker_leadership(silent, 60)
+        .await
+        .expect("contend for lease"));
+
+    assert_eq!(
+        repository
+            .reclaim_orphaned_jobs(3600, &JobRetryPolicy::default())
+            .await
+            .expect("reclaim orphans"),
+        1
+    );
+    let status = |job_id: Uuid| {
+        let pool = pool.clone();
+        async move {
+            sqlx::query_scalar::<_, String>("SELECT status::text FROM job_queue WHERE id = $1")
+                .bind(job_id)
+                .fetch_one(&pool)
+                .await
+                .expect("read job status")
+        }
+    };
+    assert_eq!(status(claimed[0]).await, "running");
+    assert_eq!(status(claimed[1]).await, "pending");
+
+    let workers = repository.list_workers().await.expect("list workers");
+    let live_info = workers.iter().find(|w| w.id == live).expect("live worker");
+    let silent_info = workers
+        .iter()
+        .find(|w| w.id == silent)
+        .expect("silent worker");
+    assert_eq!(live_info.state, WorkerState::Active);
+    assert!(live_info.leader);
+    assert_eq!(live_info.running_jobs, 1);
+    assert_eq!(silent_info.state, WorkerState::Stale);
+    assert_eq!(silent_info.running_jobs, 0);
+
+    repository
+        .deregister_worker(live)
+        .await
+        .expect("deregister worker");
+    assert!(repository
+        .acquire_worker_leadership(silent, 60)
+        .await
+        .expect("take released lease"));
+    pool.close().await;
+}
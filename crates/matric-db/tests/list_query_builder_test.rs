@@ -50,6 +50,7 @@ async fn test_list_with_no_filters() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -104,6 +105,7 @@ async fn test_list_with_tag_filter() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -156,6 +158,7 @@ async fn test_list_with_multiple_tag_filters() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -210,6 +213,7 @@ async fn test_list_with_date_filters() {
         created_before: Some(one_hour_later),
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -267,6 +271,7 @@ async fn test_list_with_starred_filter() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -323,6 +328,7 @@ async fn test_list_with_archived_filter() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -395,6 +401,7 @@ async fn test_list_sort_by_updated_at() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -465,6 +472,7 @@ async fn test_list_pagination() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -485,6 +493,7 @@ async fn test_list_pagination() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -134,6 +134,7 @@ async fn test_list_notes_loads_tags_efficiently() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -280,6 +281,7 @@ async fn test_tag_ordering_consistency() {
         created_before: None,
         updated_after: None,
         updated_before: None,
+        metadata: Vec::new(),
     };
 
     let response = repo.list(list_req).await.expect("Failed to list notes");
@@ -0,0 +1,499 @@
+//! Anthropic Messages API backend for generation and vision.
+//!
+//! Talks to `POST /v1/messages` directly instead of going through an
+//! OpenAI-compatible proxy, so Claude models get native system prompts and
+//! image blocks. The registry exposes it as the `anthropic` provider, e.g.
+//! `anthropic:claude-sonnet-4-20250514`.
+//!
+//! The Messages API has no JSON mode: JSON requests add an instruction to the
+//! system prompt and strip a Markdown code fence from the reply.
+
+use std::fmt;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, warn};
+
+use crate::diagnostics::{backend_parse_error, backend_request_error, backend_status_error};
+use crate::token_accounting::{record_generation, TokenUsage};
+use crate::vision::VisionBackend;
+use matric_core::{Error, GenerationBackend, Result};
+
+/// Default Anthropic API endpoint.
+pub const DEFAULT_ANTHROPIC_URL: &str = "https://api.anthropic.com/v1";
+
+/// Default model for generation and vision.
+pub const DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
+
+/// Messages API version sent in the `anthropic-version` header.
+pub const API_VERSION: &str = "2023-06-01";
+
+/// Default output token limit; the Messages API requires one.
+pub const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// Default timeout in seconds.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+/// Image types the Messages API accepts.
+pub const SUPPORTED_IMAGE_TYPES: &[&str] = &["image/jpeg", "image/png", "image/gif", "image/webp"];
+
+const JSON_INSTRUCTION: &str =
+    "Respond with a single valid JSON value only, without prose or Markdown code fences.";
+
+const DEFAULT_VISION_PROMPT: &str =
+    "Describe this image in detail. Include any text visible in the image.";
+
+/// Configuration for the Anthropic backend.
+#[derive(Clone)]
+pub struct AnthropicConfig {
+    /// Base URL for the API, including the `/v1` prefix.
+    pub base_url: String,
+    /// API key sent as `x-api-key`.
+    pub api_key: String,
+    /// Model used for generation and image description.
+    pub model: String,
+    /// Most tokens generated per request.
+    pub max_tokens: u32,
+    /// Request timeout in seconds.
+    pub timeout_seconds: u64,
+}
+
+impl fmt::Debug for AnthropicConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnthropicConfig")
+            .field("base_url_len", &self.base_url.chars().count())
+            .field("api_key_len", &self.api_key.chars().count())
+            .field("model_len", &self.model.chars().count())
+            .field("max_tokens", &self.max_tokens)
+            .field("timeout_seconds", &self.timeout_seconds)
+            .finish()
+    }
+}
+
+impl AnthropicConfig {
+    /// Configuration with default endpoint, model and limits.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            base_url: DEFAULT_ANTHROPIC_URL.to_string(),
+            api_key: api_key.into(),
+            model: DEFAULT_MODEL.to_string(),
+            max_tokens: DEFAULT_MAX_TOKENS,
+            timeout_seconds: DEFAULT_TIMEOUT_SECS,
+        }
+    }
+
+    /// Read the configuration from `ANTHROPIC_API_KEY`, `ANTHROPIC_BASE_URL`,
+    /// `ANTHROPIC_GEN_MODEL`, `ANTHROPIC_MAX_TOKENS` and `ANTHROPIC_TIMEOUT`.
+    /// Returns `None` when no API key is set.
+    pub fn from_env() -> Option<Self> {
+        let api_key = std::env::var("ANTHROPIC_API_KEY")
+            .ok()
+            .filter(|key| !key.trim().is_empty())?;
+        let mut config = Self::new(api_key);
+        if let Ok(base_url) = std::env::var("ANTHROPIC_BASE_URL") {
+            if !base_url.trim().is_empty() {
+                config.base_url = base_url.trim().trim_end_matches('/').to_string();
+            }
+        }
+        if let Ok(model) = std::env::var("ANTHROPIC_GEN_MODEL") {
+            if !model.trim().is_empty() {
+                config.model = model.trim().to_string();
+            }
+        }
+        if let Some(max_tokens) = std::env::var("ANTHROPIC_MAX_TOKENS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u32>().ok())
+        {
+            config.max_tokens = max_tokens.clamp(1, 128_000);
+        }
+        if let Some(timeout) = std::env::var("ANTHROPIC_TIMEOUT")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+        {
+            config.timeout_seconds = timeout.max(1);
+        }
+        Some(config)
+    }
+}
+
+#[derive(Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    messages: Vec<Message>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    role: &'static str,
+    content: Vec<ContentBlock>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    Text { text: String },
+    Image { source: ImageSource },
+}
+
+#[derive(Serialize)]
+struct ImageSource {
+    #[serde(rename = "type")]
+    source_type: &'static str,
+    media_type: String,
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct MessagesResponse {
+    #[serde(default)]
+    content: Vec<ResponseBlock>,
+    #[serde(default)]
+    stop_reason: Option<String>,
+    #[serde(default)]
+    usage: Option<MessagesUsage>,
+}
+
+#[derive(Deserialize)]
+struct MessagesUsage {
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+#[derive(Deserialize)]
+struct ResponseBlock {
+    #[serde(rename = "type")]
+    block_type: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+/// Strip a surrounding Markdown code fence (```` ```json ````) from a reply.
+pub fn strip_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+    let Some(body) = rest.strip_suffix("```") else {
+        return trimmed;
+    };
+    match body.split_once('\n') {
+        Some((language, code)) if !language.contains(['{', '[']) => code.trim(),
+        _ => body.trim(),
+    }
+}
+
+/// Anthropic Messages API backend.
+pub struct AnthropicBackend {
+    client: Client,
+    config: AnthropicConfig,
+}
+
+impl AnthropicBackend {
+    /// Create a backend with the given configuration.
+    pub fn new(config: AnthropicConfig) -> Result<Self> {
+        if config.api_key.trim().is_empty() {
+            return Err(Error::Config("Anthropic API key is empty".to_string()));
+        }
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()
+            .map_err(|e| Error::Inference(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(Self { client, config })
+    }
+
+    /// Create from `ANTHROPIC_*` environment variables.
+    pub fn from_env() -> Result<Self> {
+        let config = AnthropicConfig::from_env()
+            .ok_or_else(|| Error::Config("ANTHROPIC_API_KEY is not set".to_string()))?;
+        Self::new(config)
+    }
+
+    /// Get the current configuration.
+    pub fn config(&self) -> &AnthropicConfig {
+        &self.config
+    }
+
+    fn request(&self, method: reqwest::Method, endpoint: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}{}", self.config.base_url.trim_end_matches('/'), endpoint);
+        self.client
+            .request(method, url)
+            .header("x-api-key", &self.config.api_key)
+            .header("anthropic-version", API_VERSION)
+    }
+
+    async fn send_messages(
+        &self,
+        system: Option<String>,
+        content: Vec<ContentBlock>,
+    ) -> Result<String> {
+        let mut sent = system.clone().unwrap_or_default();
+        for block in &content {
+            if let ContentBlock::Text { text } = block {
+                sent.push_str(text);
+            }
+        }
+        let request = MessagesRequest {
+            model: &self.config.model,
+            max_tokens: self.config.max_tokens,
+            system,
+            messages: vec![Message {
+                role: "user",
+                content,
+            }],
+        };
+
+        let response = self
+            .request(reqwest::Method::POST, "/messages")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| {
+                Error::Inference(backend_request_error(
+                    "Anthropic messages request failed",
+                    &e,
+                ))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Inference(backend_status_error(
+                "Anthropic messages",
+                status,
+                &body,
+            )));
+        }
+
+        let result: MessagesResponse = response.json().await.map_err(|e| {
+            Error::Inference(backend_parse_error(
+                "Anthropic messages response parse failed",
+                e,
+            ))
+        })?;
+
+        if result.stop_reason.as_deref() == Some("max_tokens") {
+            warn!(
+                max_tokens = self.config.max_tokens,
+                "Anthropic response truncated at max_tokens"
+            );
+        }
+        let text: String = result
+            .content
+            .into_iter()
+            .filter(|block| block.block_type == "text")
+            .filter_map(|block| block.text)
+            .collect();
+        record_generation(
+            &self.config.model,
+            &sent,
+            &text,
+            result
+                .usage
+                .map(|u| TokenUsage::new(u.input_tokens, u.output_tokens)),
+        );
+        debug!(response_len = text.len(), "Anthropic generation complete");
+        Ok(text)
+    }
+
+    fn system_prompt(system: &str, json: bool) -> Option<String> {
+        match (system.trim().is_empty(), json) {
+            (true, false) => None,
+            (true, true) => Some(JSON_INSTRUCTION.to_string()),
+            (false, false) => Some(system.to_string()),
+            (false, true) => Some(format!("{system}\n\n{JSON_INSTRUCTION}")),
+        }
+    }
+}
+
+#[async_trait]
+impl GenerationBackend for AnthropicBackend {
+    async fn health_check(&self) -> Result<bool> {
+        match self
+            .request(reqwest::Method::GET, "/models")
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+        {
+            Ok(resp) => Ok(resp.status().is_success()),
+            Err(e) => {
+                warn!(
+                    error_len = e.to_string().len(),
+                    "Anthropic health check error"
+                );
+                Ok(false)
+            }
+        }
+    }
+
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        self.generate_with_system("", prompt).await
+    }
+
+    #[instrument(skip_all, fields(subsystem = "inference", component = "anthropic", op = "generate", model_len = self.config.model.len(), prompt_len = prompt.len()))]
+    async fn generate_with_system(&self, system: &str, prompt: &str) -> Result<String> {
+        self.send_messages(
+            Self::system_prompt(system, false),
+            vec![ContentBlock::Text {
+                text: prompt.to_string(),
+            }],
+        )
+        .await
+    }
+
+    async fn generate_json(&self, prompt: &str) -> Result<String> {
+        self.generate_json_with_system("", prompt).await
+    }
+
+    #[instrument(skip_all, fields(subsystem = "inference", component = "anthropic", op = "generate_json", model_len = self.config.model.len(), prompt_len = prompt.len()))]
+    async fn generate_json_with_system(&self, system: &str, prompt: &str) -> Result<String> {
+        let text = self
+            .send_messages(
+                Self::system_prompt(system, true),
+                vec![ContentBlock::Text {
+                    text: prompt.to_string(),
+                }],
+            )
+            .await?;
+        Ok(strip_code_fence(&text).to_string())
+    }
+
+    fn model_name(&self) -> &str {
+        &self.config.model
+    }
+}
+
+#[async_trait]
+impl VisionBackend for AnthropicBackend {
+    #[instrument(skip_all, fields(subsystem = "inference", component = "anthropic", op = "describe_image", model_len = self.config.model.len(), image_len = image_data.len()))]
+    async fn describe_image(
+        &self,
+        image_data: &[u8],
+        mime_type: &str,
+        prompt: Option<&str>,
+    ) -> Result<String> {
+        use base64::Engine;
+
+        if !SUPPORTED_IMAGE_TYPES.contains(&mime_type) {
+            return Err(Error::InvalidInput(format!(
+                "Anthropic vision does not support {} images",
+                mime_type
+            )));
+        }
+        let data = base64::engine::general_purpose::STANDARD.encode(image_data);
+        self.send_messages(
+            None,
+            vec![
+                ContentBlock::Image {
+                    source: ImageSource {
+                        source_type: "base64",
+                        media_type: mime_type.to_string(),
+                        data,
+                    },
+                },
+                ContentBlock::Text {
+                    text: prompt.unwrap_or(DEFAULT_VISION_PROMPT).to_string(),
+                },
+            ],
+        )
+        .await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        GenerationBackend::health_check(self).await
+    }
+
+    fn model_name(&self) -> &str {
+        &self.config.model
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn backend(base_url: String) -> AnthropicBackend {
+        AnthropicBackend::new(AnthropicConfig {
+            base_url,
+            ..AnthropicConfig::new("test-key")
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn code_fences_are_stripped_from_json_replies() {
+        assert_eq!(strip_code_fence("```json\n{\"a\": 1}\n```"), "{\"a\": 1}");
+        assert_eq!(strip_code_fence("```{\"a\": 1}```"), "{\"a\": 1}");
+        assert_eq!(strip_code_fence("  [1, 2] "), "[1, 2]");
+    }
+
+    #[test]
+    fn debug_omits_api_key() {
+        let config = AnthropicConfig::new("sk-ant-secret");
+        assert!(!format!("{config:?}").contains("sk-ant-secret"));
+    }
+
+    #[tokio::test]
+    async fn generation_sends_system_prompt_and_joins_text_blocks() {
+        let mut server = mockito::Server::new_async().await;
+        let request = server
+            .mock("POST", "/messages")
+            .match_header("x-api-key", "test-key")
+            .match_header("anthropic-version", API_VERSION)
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "model": DEFAULT_MODEL,
+                "system": "Be brief.",
+                "messages": [{"role": "user", "content": [{"type": "text", "text": "Hi"}]}]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"content": [{"type": "text", "text": "Hello"}, {"type": "text", "text": "!"}],
+                    "stop_reason": "end_turn"}"#,
+            )
+            .create_async()
+            .await;
+
+        let reply = backend(server.url())
+            .generate_with_system("Be brief.", "Hi")
+            .await
+            .unwrap();
+        assert_eq!(reply, "Hello!");
+        request.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn images_are_sent_as_base64_blocks() {
+        let mut server = mockito::Server::new_async().await;
+        let request = server
+            .mock("POST", "/messages")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "messages": [{"content": [{
+                    "type": "image",
+                    "source": {"type": "base64", "media_type": "image/png", "data": "AQID"}
+                }]}]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"content": [{"type": "text", "text": "Three pixels"}]}"#)
+            .create_async()
+            .await;
+
+        let backend = backend(server.url());
+        let reply = backend
+            .describe_image(&[1, 2, 3], "image/png", None)
+            .await
+            .unwrap();
+        assert_eq!(reply, "Three pixels");
+        request.assert_async().await;
+        assert!(backend
+            .describe_image(&[1], "image/tiff", None)
+            .await
+            .is_err());
+    }
+}
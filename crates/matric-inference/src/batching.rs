@@ -0,0 +1,332 @@
+//! Dynamic batching of embedding requests.
+//!
+//! Embedding jobs run concurrently, each embedding the chunks of one note.
+//! [`EmbeddingBatcher`] coalesces requests for the same embedding contract
+//! that arrive within `flush_timeout_ms` of each other into one backend call
+//! of up to `max_batch_size` texts, so a GPU backend gets full batches
+//! instead of one note at a time.
+//!
+//! The first request of a batch leads it: it waits until the batch is full or
+//! the flush timeout passes, makes the backend call and hands every request
+//! its own vectors. If the combined call fails or returns the wrong number of
+//! vectors, the leader embeds each request on its own, so a note that breaks
+//! the backend fails only its own job. A request whose leader is cancelled
+//! embeds its texts itself.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{oneshot, Notify};
+use tracing::debug;
+
+use matric_core::{EmbeddingBackend, Result, Vector};
+
+use crate::latency::BatchEmbeddingConfig;
+
+struct BatchRequest {
+    texts: Vec<String>,
+    /// `None` for the leader's own request.
+    reply: Option<oneshot::Sender<Result<Vec<Vector>>>>,
+}
+
+#[derive(Default)]
+struct PendingBatch {
+    requests: Mutex<Vec<BatchRequest>>,
+    full: Notify,
+}
+
+/// Coalesces concurrent embedding requests into shared backend calls.
+pub struct EmbeddingBatcher {
+    config: BatchEmbeddingConfig,
+    open: Mutex<HashMap<String, (Arc<PendingBatch>, usize)>>,
+}
+
+impl EmbeddingBatcher {
+    pub fn new(config: BatchEmbeddingConfig) -> Self {
+        Self {
+            config,
+            open: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &BatchEmbeddingConfig {
+        &self.config
+    }
+
+    /// Embed `texts` with `backend`, sharing the backend call with concurrent
+    /// requests under the same `key`. Requests under one key must use
+    /// interchangeable backends, e.g. key by embedding contract fingerprint.
+    pub async fn embed(
+        &self,
+        key: &str,
+        backend: &dyn EmbeddingBackend,
+        texts: Vec<String>,
+    ) -> Result<Vec<Vector>> {
+        if texts.is_empty() || texts.len() >= self.config.max_batch_size {
+            return backend.embed_texts(&texts).await;
+        }
+
+        let role = {
+            let mut open = self.open.lock().expect("embedding batcher lock poisoned");
+            match open.get_mut(key) {
+                Some((batch, text_count))
+                    if *text_count + texts.len() <= self.config.max_batch_size =>
+                {
+                    let (reply, receiver) = oneshot::channel();
+                    batch
+                        .requests
+                        .lock()
+                        .expect("embedding batch lock poisoned")
+                        .push(BatchRequest {
+                            texts: texts.clone(),
+                            reply: Some(reply),
+                        });
+                    *text_count += texts.len();
+                    if *text_count >= self.config.max_batch_size {
+                        batch.full.notify_one();
+                        open.remove(key);
+                    }
+                    Role::Follower(receiver)
+                }
+                _ => {
+                    // Seal a batch this request does not fit in so its leader
+                    // flushes now, and lead a new one.
+                    if let Some((sealed, _)) = open.remove(key) {
+                        sealed.full.notify_one();
+                    }
+                    let batch = Arc::new(PendingBatch::default());
+                    open.insert(key.to_string(), (batch.clone(), texts.len()));
+                    Role::Leader(batch)
+                }
+            }
+        };
+
+        match role {
+            Role::Follower(receiver) => match receiver.await {
+                Ok(result) => result,
+                Err(_) => backend.embed_texts(&texts).await,
+            },
+            Role::Leader(batch) => {
+                batch
+                    .requests
+                    .lock()
+                    .expect("embedding batch lock poisoned")
+                    .push(BatchRequest { texts, reply: None });
+                let guard = LeaderGuard {
+                    batcher: self,
+                    key,
+                    batch,
+                };
+                let flush_timeout = Duration::from_millis(self.config.flush_timeout_ms);
+                let _ = tokio::time::timeout(flush_timeout, guard.batch.full.notified()).await;
+                let requests = guard.close();
+                flush(backend, requests).await
+            }
+        }
+    }
+}
+
+enum Role {
+    Leader(Arc<PendingBatch>),
+    Follower(oneshot::Receiver<Result<Vec<Vector>>>),
+}
+
+/// Closes the leader's batch. Dropped without [`close`](Self::close) when
+/// the leader is cancelled, which releases the waiting requests to embed
+/// their texts themselves.
+struct LeaderGuard<'a> {
+    batcher: &'a EmbeddingBatcher,
+    key: &'a str,
+    batch: Arc<PendingBatch>,
+}
+
+impl LeaderGuard<'_> {
+    fn close(&self) -> Vec<BatchRequest> {
+        let mut open = self
+            .batcher
+            .open
+            .lock()
+            .expect("embedding batcher lock poisoned");
+        if open
+            .get(self.key)
+            .is_some_and(|(batch, _)| Arc::ptr_eq(batch, &self.batch))
+        {
+            open.remove(self.key);
+        }
+        drop(open);
+        std::mem::take(
+            &mut *self
+                .batch
+                .requests
+                .lock()
+                .expect("embedding batch lock poisoned"),
+        )
+    }
+}
+
+impl Drop for LeaderGuard<'_> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Embed every request in one backend call, falling back to one call per
+/// request when the combined call fails. Returns the leader's vectors.
+async fn flush(backend: &dyn EmbeddingBackend, requests: Vec<BatchRequest>) -> Result<Vec<Vector>> {
+    if let [only] = requests.as_slice() {
+        return backend.embed_texts(&only.texts).await;
+    }
+
+    let lengths: Vec<usize> = requests.iter().map(|request| request.texts.len()).collect();
+    let total: usize = lengths.iter().sum();
+    let texts: Vec<String> = requests
+        .iter()
+        .flat_map(|request| request.texts.iter().cloned())
+        .collect();
+    debug!(
+        requests = requests.len(),
+        texts = total,
+        "Flushing embedding batch"
+    );
+
+    match backend.embed_texts(&texts).await {
+        Ok(vectors) if vectors.len() == total => {
+            let mut vectors = vectors.into_iter();
+            let mut own = None;
+            for (request, length) in requests.into_iter().zip(lengths) {
+                let slice: Vec<Vector> = vectors.by_ref().take(length).collect();
+                match request.reply {
+                    Some(reply) => {
+                        let _ = reply.send(Ok(slice));
+                    }
+                    None => own = Some(slice),
+                }
+            }
+            Ok(own.unwrap_or_default())
+        }
+        combined => {
+            debug!(
+                requests = requests.len(),
+                combined_failed = combined.is_err(),
+                "Embedding batch failed, embedding requests individually"
+            );
+            let mut own = Ok(Vec::new());
+            for request in requests {
+                let result = backend.embed_texts(&request.texts).await;
+                match request.reply {
+                    Some(reply) => {
+                        let _ = reply.send(result);
+                    }
+                    None => own = result,
+                }
+            }
+            own
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use matric_core::Error;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Embeds each text as `[len]`; texts containing "poison" fail the call.
+    #[derive(Default)]
+    struct CountingBackend {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EmbeddingBackend for CountingBackend {
+        async fn embed_texts(&self, texts: &[String]) -> Result<Vec<Vector>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if texts.iter().any(|text| text.contains("poison")) {
+                return Err(Error::Embedding("poisoned batch".to_string()));
+            }
+            Ok(texts
+                .iter()
+                .map(|text| Vector::from(vec![text.len() as f32]))
+                .collect())
+        }
+
+        fn dimension(&self) -> usize {
+            1
+        }
+
+        fn model_name(&self) -> &str {
+            "counting"
+        }
+    }
+
+    fn texts(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn lengths(vectors: &[Vector]) -> Vec<f32> {
+        vectors.iter().map(|vector| vector.as_slice()[0]).collect()
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_share_one_backend_call() {
+        let batcher = EmbeddingBatcher::new(BatchEmbeddingConfig {
+            max_batch_size: 8,
+            flush_timeout_ms: 50,
+        });
+        let backend = CountingBackend::default();
+
+        let (first, second, third) = tokio::join!(
+            batcher.embed("contract", &backend, texts(&["a", "bb"])),
+            batcher.embed("contract", &backend, texts(&["ccc"])),
+            batcher.embed("contract", &backend, texts(&["dddd", "eeeee"])),
+        );
+
+        assert_eq!(lengths(&first.unwrap()), [1.0, 2.0]);
+        assert_eq!(lengths(&second.unwrap()), [3.0]);
+        assert_eq!(lengths(&third.unwrap()), [4.0, 5.0]);
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn a_failing_request_does_not_fail_its_batch() {
+        let batcher = EmbeddingBatcher::new(BatchEmbeddingConfig {
+            max_batch_size: 8,
+            flush_timeout_ms: 50,
+        });
+        let backend = CountingBackend::default();
+
+        let (healthy, poisoned) = tokio::join!(
+            batcher.embed("contract", &backend, texts(&["ok"])),
+            batcher.embed("contract", &backend, texts(&["poison"])),
+        );
+
+        assert_eq!(lengths(&healthy.unwrap()), [2.0]);
+        assert!(poisoned.is_err());
+        // One combined call, then one call per request.
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keys_do_not_mix_and_oversized_requests_bypass_batching() {
+        let batcher = EmbeddingBatcher::new(BatchEmbeddingConfig {
+            max_batch_size: 3,
+            flush_timeout_ms: 60_000,
+        });
+        let backend = CountingBackend::default();
+
+        let (first, second, other, oversized) = tokio::join!(
+            batcher.embed("a", &backend, texts(&["x", "yy"])),
+            batcher.embed("a", &backend, texts(&["zzz"])),
+            batcher.embed("b", &backend, texts(&["w"])),
+            batcher.embed("a", &backend, texts(&["1", "2", "3"])),
+        );
+
+        assert_eq!(lengths(&first.unwrap()), [1.0, 2.0]);
+        assert_eq!(lengths(&second.unwrap()), [3.0]);
+        assert_eq!(lengths(&other.unwrap()), [1.0]);
+        assert_eq!(oversized.unwrap().len(), 3);
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,292 @@
+//! candle model loading and inference behind [`CandleEmbeddingBackend`].
+//!
+//! Models are loaded on first use and shared by every backend for the same
+//! directory. Forward passes run on blocking threads, one batch of
+//! `batch_size` texts at a time.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use async_trait::async_trait;
+use candle_core::{DType, Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config as BertConfig, DTYPE};
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+use tracing::info;
+
+use matric_core::{EmbeddingBackend, Error, Result, Vector};
+
+use super::{normalize_rows, profile_for, CandleEmbeddingConfig, ModelShape, Pooling, WeightsFile};
+use crate::embedding_models::{EmbeddingModelProfile, EmbeddingModelRegistry};
+
+static MODELS: OnceLock<Mutex<HashMap<PathBuf, Arc<LoadedModel>>>> = OnceLock::new();
+
+fn candle_error(context: &str, error: impl std::fmt::Display) -> Error {
+    Error::Embedding(format!("{context}: {error}"))
+}
+
+enum Network {
+    Bert(BertModel),
+    #[cfg(feature = "candle-onnx")]
+    Onnx(candle_onnx::onnx::ModelProto),
+}
+
+struct LoadedModel {
+    network: Network,
+    tokenizer: Tokenizer,
+    device: Device,
+}
+
+impl LoadedModel {
+    fn load(model_dir: &Path, max_tokens: usize) -> Result<Self> {
+        let device = Device::Cpu;
+        let mut tokenizer = Tokenizer::from_file(model_dir.join("tokenizer.json"))
+            .map_err(|error| candle_error("Cannot load candle tokenizer", error))?;
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: max_tokens,
+                ..Default::default()
+            }))
+            .map_err(|error| candle_error("Invalid candle tokenizer truncation", error))?;
+
+        let network = match WeightsFile::find(model_dir)? {
+            WeightsFile::Safetensors(weights) => {
+                let raw = std::fs::read_to_string(model_dir.join("config.json"))
+                    .map_err(|error| candle_error("Cannot read candle model config", error))?;
+                let config: BertConfig = serde_json::from_str(&raw)
+                    .map_err(|error| candle_error("Unsupported candle model config", error))?;
+                // SAFETY: the weights file is memory-mapped read-only and must
+                // not be modified while the process runs, as with any mmap.
+                let vb = unsafe { VarBuilder::from_mmaped_safetensors(&[weights], DTYPE, &device) }
+                    .map_err(|error| candle_error("Cannot load candle weights", error))?;
+                Network::Bert(
+                    BertModel::load(vb, &config)
+                        .map_err(|error| candle_error("Cannot build candle BERT model", error))?,
+                )
+            }
+            #[cfg(feature = "candle-onnx")]
+            WeightsFile::Onnx(weights) => Network::Onnx(
+                candle_onnx::read_file(weights)
+                    .map_err(|error| candle_error("Cannot load ONNX model", error))?,
+            ),
+            #[cfg(not(feature = "candle-onnx"))]
+            WeightsFile::Onnx(_) => {
+                return Err(Error::Config(
+                    "ONNX embedding models need the candle-onnx feature".to_string(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            network,
+            tokenizer,
+            device,
+        })
+    }
+
+    /// Token embeddings `[batch, tokens, hidden]` and the attention mask.
+    fn forward(&self, texts: Vec<String>) -> Result<(Tensor, Tensor)> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts, true)
+            .map_err(|error| candle_error("Candle tokenization failed", error))?;
+        let batch = encodings.len();
+        let tokens = encodings.first().map_or(0, |encoding| encoding.len());
+        let flatten = |field: fn(&tokenizers::Encoding) -> &[u32]| -> Vec<u32> {
+            encodings
+                .iter()
+                .flat_map(|encoding| field(encoding).iter().copied())
+                .collect()
+        };
+        let tensor = |values: Vec<u32>| {
+            Tensor::from_vec(values, (batch, tokens), &self.device)
+                .map_err(|error| candle_error("Candle input tensor failed", error))
+        };
+        let input_ids = tensor(flatten(tokenizers::Encoding::get_ids))?;
+        let type_ids = tensor(flatten(tokenizers::Encoding::get_type_ids))?;
+        let attention_mask = tensor(flatten(tokenizers::Encoding::get_attention_mask))?;
+
+        let hidden = match &self.network {
+            Network::Bert(model) => model
+                .forward(&input_ids, &type_ids, Some(&attention_mask))
+                .map_err(|error| candle_error("Candle forward pass failed", error))?,
+            #[cfg(feature = "candle-onnx")]
+            Network::Onnx(model) => onnx_forward(model, &input_ids, &type_ids, &attention_mask)?,
+        };
+        Ok((hidden, attention_mask))
+    }
+}
+
+#[cfg(feature = "candle-onnx")]
+fn onnx_forward(
+    model: &candle_onnx::onnx::ModelProto,
+    input_ids: &Tensor,
+    type_ids: &Tensor,
+    attention_mask: &Tensor,
+) -> Result<Tensor> {
+    let graph = model
+        .graph
+        .as_ref()
+        .ok_or_else(|| Error::Config("ONNX model has no graph".to_string()))?;
+    let mut inputs = HashMap::new();
+    for input in &graph.input {
+        let tensor = match input.name.as_str() {
+            "input_ids" => input_ids,
+            "token_type_ids" => type_ids,
+            "attention_mask" => attention_mask,
+            _ => continue,
+        };
+        let tensor = tensor
+            .to_dtype(DType::I64)
+            .map_err(|error| candle_error("ONNX input tensor failed", error))?;
+        inputs.insert(input.name.clone(), tensor);
+    }
+    let output_name = graph
+        .output
+        .first()
+        .map(|output| output.name.clone())
+        .ok_or_else(|| Error::Config("ONNX model has no outputs".to_string()))?;
+    let mut outputs = candle_onnx::simple_eval(model, inputs)
+        .map_err(|error| candle_error("ONNX forward pass failed", error))?;
+    outputs
+        .remove(&output_name)
+        .ok_or_else(|| Error::Embedding("ONNX model returned no embeddings".to_string()))
+}
+
+fn pool(hidden: &Tensor, attention_mask: &Tensor, pooling: Pooling) -> Result<Vec<Vec<f32>>> {
+    let pooled = match pooling {
+        Pooling::Cls => hidden.narrow(1, 0, 1).and_then(|cls| cls.squeeze(1)),
+        Pooling::Mean => attention_mask
+            .to_dtype(hidden.dtype())
+            .and_then(|mask| mask.unsqueeze(2))
+            .and_then(|mask| {
+                let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+                let counts = mask.sum(1)?.clamp(1e-9, f64::MAX)?;
+                summed.broadcast_div(&counts)
+            }),
+    }
+    .and_then(|pooled| pooled.to_dtype(DType::F32))
+    .and_then(|pooled| pooled.to_vec2::<f32>())
+    .map_err(|error| candle_error("Candle pooling failed", error))?;
+    Ok(normalize_rows(pooled))
+}
+
+fn load_model(model_dir: &Path, max_tokens: usize) -> Result<Arc<LoadedModel>> {
+    // Held while loading so concurrent first calls load the model once.
+    let mut models = MODELS
+        .get_or_init(Default::default)
+        .lock()
+        .expect("candle model cache lock poisoned");
+    if let Some(model) = models.get(model_dir) {
+        return Ok(model.clone());
+    }
+    let model = Arc::new(LoadedModel::load(model_dir, max_tokens)?);
+    info!(
+        model_dir_len = model_dir.to_string_lossy().chars().count(),
+        max_tokens, "Loaded candle embedding model"
+    );
+    models.insert(model_dir.to_path_buf(), model.clone());
+    Ok(model)
+}
+
+/// Embedding backend running a local sentence embedding model with candle.
+#[derive(Debug, Clone)]
+pub struct CandleEmbeddingBackend {
+    config: Arc<CandleEmbeddingConfig>,
+    profile: EmbeddingModelProfile,
+}
+
+impl CandleEmbeddingBackend {
+    /// Create a backend for a model directory. Reads `config.json` for the
+    /// dimension and token limit; the weights load on first use.
+    pub fn new(config: CandleEmbeddingConfig) -> Result<Self> {
+        let shape = ModelShape::read(&config.model_dir)?;
+        WeightsFile::find(&config.model_dir)?;
+        let registry = EmbeddingModelRegistry::new();
+        let profile = profile_for(
+            &registry,
+            &config.model_name,
+            shape.hidden_size,
+            shape.max_position_embeddings,
+        );
+        Ok(Self {
+            config: Arc::new(config),
+            profile,
+        })
+    }
+
+    /// Create a backend from `CANDLE_EMBED_*` environment variables.
+    pub fn from_env() -> Result<Self> {
+        let config = CandleEmbeddingConfig::from_env().ok_or_else(|| {
+            Error::Config("Set CANDLE_EMBED_MODEL_DIR to use candle embeddings".to_string())
+        })?;
+        Self::new(config)
+    }
+
+    /// Profile of the loaded model: dimension, symmetry and prefixes.
+    pub fn profile(&self) -> &EmbeddingModelProfile {
+        &self.profile
+    }
+
+    /// Register this model's profile in an embedding model registry.
+    pub fn register_profile(&self, registry: &mut EmbeddingModelRegistry) {
+        registry.register(self.profile.clone());
+    }
+
+    /// Embed texts as queries, with the model's query prefix.
+    pub async fn embed_queries(&self, texts: &[String]) -> Result<Vec<Vector>> {
+        self.embed_texts(&self.profile.prefix_queries(texts)).await
+    }
+
+    /// Embed texts as passages, with the model's passage prefix.
+    pub async fn embed_passages(&self, texts: &[String]) -> Result<Vec<Vector>> {
+        self.embed_texts(&self.profile.prefix_passages(texts)).await
+    }
+}
+
+#[async_trait]
+impl EmbeddingBackend for CandleEmbeddingBackend {
+    async fn embed_texts(&self, texts: &[String]) -> Result<Vec<Vector>> {
+        if texts.is_empty() {
+            return Ok(vec![]);
+        }
+        let config = self.config.clone();
+        let max_tokens = self.profile.max_tokens;
+        let dimension = self.profile.dimension;
+        let pooling = Pooling::for_family(&self.profile.family);
+        let texts = texts.to_vec();
+        tokio::task::spawn_blocking(move || {
+            let model = load_model(&config.model_dir, max_tokens)?;
+            let mut vectors = Vec::with_capacity(texts.len());
+            for batch in texts.chunks(config.batch_size.max(1)) {
+                let (hidden, attention_mask) = model.forward(batch.to_vec())?;
+                for row in pool(&hidden, &attention_mask, pooling)? {
+                    if row.len() != dimension {
+                        return Err(Error::Embedding(format!(
+                            "Candle model returned {} dimensions, expected {}",
+                            row.len(),
+                            dimension
+                        )));
+                    }
+                    vectors.push(Vector::from(row));
+                }
+            }
+            Ok(vectors)
+        })
+        .await
+        .map_err(|error| Error::Internal(format!("Candle embedding task failed: {error}")))?
+    }
+
+    fn dimension(&self) -> usize {
+        self.profile.dimension
+    }
+
+    fn model_name(&self) -> &str {
+        &self.profile.name
+    }
+}
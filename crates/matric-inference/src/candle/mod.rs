@@ -0,0 +1,297 @@
+//! Pure-Rust local embeddings with candle.
+//!
+//! Air-gapped deployments can embed without any inference service: the
+//! `candle` feature loads a BERT-family sentence embedding model (E5, BGE,
+//! MiniLM) from a local Hugging Face model directory and runs it in-process
+//! on the CPU. The `candle-onnx` feature adds ONNX exports of the same models.
+//!
+//! A model directory holds `config.json`, `tokenizer.json` and either
+//! `model.safetensors` or `model.onnx` (also looked up under `onnx/`).
+//!
+//! The backend registers the model in the [`EmbeddingModelRegistry`]: known
+//! models keep their prefixes and symmetry with the dimension and token limit
+//! read from `config.json`; unknown models get E5 or BGE prefixes by name, or
+//! are treated as symmetric. BGE models use CLS pooling, all others mean
+//! pooling, and every vector is normalized to unit length.
+//!
+//! ```
+//! use matric_inference::candle::{profile_for, Pooling};
+//! use matric_inference::{EmbeddingModelRegistry, EmbeddingSymmetry};
+//!
+//! let registry = EmbeddingModelRegistry::new();
+//! let profile = profile_for(&registry, "bge-small-en-v1.5", 384, 512);
+//! assert_eq!(profile.dimension, 384);
+//! assert_eq!(profile.symmetry, EmbeddingSymmetry::Asymmetric);
+//! assert_eq!(Pooling::for_family(&profile.family), Pooling::Cls);
+//! ```
+
+#[cfg(feature = "candle")]
+mod backend;
+
+#[cfg(feature = "candle")]
+pub use backend::CandleEmbeddingBackend;
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use matric_core::{Error, Result};
+
+use crate::embedding_models::{EmbeddingModelProfile, EmbeddingModelRegistry, EmbeddingSymmetry};
+
+/// Provider ID of the candle backend in the provider registry.
+pub const PROVIDER_ID: &str = "candle";
+
+const BGE_QUERY_PREFIX: &str = "Represent this sentence for searching relevant passages: ";
+
+/// How token embeddings are pooled into one sentence vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pooling {
+    /// Mask-weighted mean of all token embeddings (E5, MiniLM).
+    Mean,
+    /// Embedding of the leading `[CLS]` token (BGE).
+    Cls,
+}
+
+impl Pooling {
+    /// Pooling the model family was trained with.
+    pub fn for_family(family: &str) -> Self {
+        if family == "bge" {
+            Self::Cls
+        } else {
+            Self::Mean
+        }
+    }
+}
+
+/// Weight file format found in a model directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WeightsFile {
+    Safetensors(PathBuf),
+    Onnx(PathBuf),
+}
+
+impl WeightsFile {
+    /// Find the weights in a model directory, preferring safetensors.
+    pub fn find(model_dir: &Path) -> Result<Self> {
+        let safetensors = model_dir.join("model.safetensors");
+        if safetensors.is_file() {
+            return Ok(Self::Safetensors(safetensors));
+        }
+        for onnx in [
+            model_dir.join("model.onnx"),
+            model_dir.join("onnx").join("model.onnx"),
+        ] {
+            if onnx.is_file() {
+                return Ok(Self::Onnx(onnx));
+            }
+        }
+        Err(Error::Config(
+            "Candle model directory has no model.safetensors or model.onnx".to_string(),
+        ))
+    }
+}
+
+/// The fields of a Hugging Face `config.json` the backend needs up front.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelShape {
+    pub hidden_size: usize,
+    #[serde(default = "default_max_position_embeddings")]
+    pub max_position_embeddings: usize,
+}
+
+fn default_max_position_embeddings() -> usize {
+    512
+}
+
+impl ModelShape {
+    /// Read the shape from `config.json` in a model directory.
+    pub fn read(model_dir: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(model_dir.join("config.json"))
+            .map_err(|error| Error::Config(format!("Cannot read candle model config: {error}")))?;
+        serde_json::from_str(&raw)
+            .map_err(|error| Error::Config(format!("Invalid candle model config: {error}")))
+    }
+}
+
+/// Settings of the candle embedding backend.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CandleEmbeddingConfig {
+    /// Local Hugging Face model directory.
+    pub model_dir: PathBuf,
+    /// Model name used for profiles and embedding contracts.
+    pub model_name: String,
+    /// Texts run through the model in one forward pass.
+    pub batch_size: usize,
+}
+
+impl fmt::Debug for CandleEmbeddingConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CandleEmbeddingConfig")
+            .field(
+                "model_dir_len",
+                &self.model_dir.to_string_lossy().chars().count(),
+            )
+            .field("model_name_len", &self.model_name.chars().count())
+            .field("batch_size", &self.batch_size)
+            .finish()
+    }
+}
+
+impl CandleEmbeddingConfig {
+    /// Read the configuration from `CANDLE_EMBED_MODEL_DIR`,
+    /// `CANDLE_EMBED_MODEL` (default: the directory name) and
+    /// `CANDLE_EMBED_BATCH_SIZE` (default 16, clamped to 1-256). Returns
+    /// `None` when no model directory is set.
+    pub fn from_env() -> Option<Self> {
+        let model_dir = std::env::var("CANDLE_EMBED_MODEL_DIR")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)?;
+        let model_name = std::env::var("CANDLE_EMBED_MODEL")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| default_model_name(&model_dir));
+        let batch_size = std::env::var("CANDLE_EMBED_BATCH_SIZE")
+            .ok()
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .map(|value| value.clamp(1, 256))
+            .unwrap_or(16);
+        Some(Self {
+            model_dir,
+            model_name,
+            batch_size,
+        })
+    }
+}
+
+/// Model name for a directory: its last path component, e.g. `e5-base-v2`
+/// for `/models/intfloat/e5-base-v2`.
+pub fn default_model_name(model_dir: &Path) -> String {
+    model_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "candle-embedding".to_string())
+}
+
+/// Embedding profile for a locally loaded model of the given shape.
+pub fn profile_for(
+    registry: &EmbeddingModelRegistry,
+    model_name: &str,
+    dimension: usize,
+    max_tokens: usize,
+) -> EmbeddingModelProfile {
+    if let Some(known) = registry.get(model_name) {
+        return EmbeddingModelProfile {
+            dimension,
+            max_tokens,
+            ..known.clone()
+        };
+    }
+
+    let lowered = model_name.to_ascii_lowercase();
+    let (family, symmetry, query_prefix, passage_prefix) = if lowered.contains("e5-") {
+        (
+            "e5",
+            EmbeddingSymmetry::Asymmetric,
+            Some("query: ".to_string()),
+            Some("passage: ".to_string()),
+        )
+    } else if lowered.starts_with("bge-") {
+        (
+            "bge",
+            EmbeddingSymmetry::Asymmetric,
+            Some(BGE_QUERY_PREFIX.to_string()),
+            None,
+        )
+    } else {
+        ("unknown", EmbeddingSymmetry::Symmetric, None, None)
+    };
+    EmbeddingModelProfile {
+        name: model_name.to_string(),
+        dimension,
+        symmetry,
+        query_prefix,
+        passage_prefix,
+        max_tokens,
+        family: family.to_string(),
+        description: format!("Local candle model ({dimension}d)"),
+    }
+}
+
+/// L2-normalize each row of a batch of vectors.
+pub fn normalize_rows(rows: Vec<Vec<f32>>) -> Vec<Vec<f32>> {
+    rows.into_iter()
+        .map(|row| {
+            let norm = row.iter().map(|v| v * v).sum::<f32>().sqrt();
+            if norm > 0.0 && norm.is_finite() {
+                row.into_iter().map(|v| v / norm).collect()
+            } else {
+                row
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_models_keep_prefixes_with_loaded_shape() {
+        let registry = EmbeddingModelRegistry::new();
+        let profile = profile_for(&registry, "e5-base-v2", 768, 512);
+        assert_eq!(profile.family, "e5");
+        assert_eq!(profile.prefix_passage("text"), "passage: text");
+        assert_eq!(Pooling::for_family(&profile.family), Pooling::Mean);
+
+        let unknown = profile_for(&registry, "paraphrase-mpnet", 768, 384);
+        assert_eq!(unknown.symmetry, EmbeddingSymmetry::Symmetric);
+        assert_eq!(unknown.max_tokens, 384);
+
+        let multilingual = profile_for(&registry, "multilingual-e5-small", 384, 512);
+        assert_eq!(multilingual.query_prefix.as_deref(), Some("query: "));
+    }
+
+    #[test]
+    fn registered_profile_replaces_the_builtin_one() {
+        let mut registry = EmbeddingModelRegistry::new();
+        let profile = profile_for(&registry, "bge-small-en-v1.5", 384, 512);
+        registry.register(profile);
+        let registered = registry.get("bge-small-en-v1.5").unwrap();
+        assert_eq!(registered.dimension, 384);
+        assert!(registered.passage_prefix.is_none());
+    }
+
+    #[test]
+    fn weights_and_shape_come_from_the_model_directory() {
+        let dir = std::env::temp_dir().join(format!("candle-model-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("onnx")).unwrap();
+        assert!(WeightsFile::find(&dir).is_err());
+
+        std::fs::write(dir.join("onnx").join("model.onnx"), b"").unwrap();
+        assert!(matches!(WeightsFile::find(&dir), Ok(WeightsFile::Onnx(_))));
+        std::fs::write(dir.join("model.safetensors"), b"").unwrap();
+        assert!(matches!(
+            WeightsFile::find(&dir),
+            Ok(WeightsFile::Safetensors(_))
+        ));
+
+        std::fs::write(dir.join("config.json"), r#"{"hidden_size": 384}"#).unwrap();
+        let shape = ModelShape::read(&dir).unwrap();
+        assert_eq!(shape.hidden_size, 384);
+        assert_eq!(shape.max_position_embeddings, 512);
+        assert_eq!(default_model_name(&dir.join("e5-small-v2")), "e5-small-v2");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rows_are_normalized_to_unit_length() {
+        let rows = normalize_rows(vec![vec![3.0, 4.0], vec![0.0, 0.0]]);
+        assert_eq!(rows, vec![vec![0.6, 0.8], vec![0.0, 0.0]]);
+    }
+}
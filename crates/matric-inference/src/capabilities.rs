@@ -637,6 +637,156 @@ pub fn known_model_capabilities(model_name: &str) -> Option<ModelCapabilities> {
             );
         }
 
+        // Claude Sonnet 4 (anthropic provider): hosted, 200K context
+        "claude-sonnet-4-20250514" | "claude-sonnet-4-0" => {
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::TitleGeneration,
+                95.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::SemanticUnderstanding,
+                95.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::FormatCompliance,
+                98.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::ContentRevision,
+                96.0,
+            ));
+            caps.add_rating(
+                CapabilityRating::from_score(Capability::FastInference, 70.0)
+                    .with_latency(1500)
+                    .with_notes("Hosted API; latency includes network round trip"),
+            );
+            caps.add_rating(CapabilityRating::from_score(Capability::LongContext, 98.0));
+        }
+
+        // Claude Opus 4 (anthropic provider): highest quality, slowest
+        "claude-opus-4-20250514" | "claude-opus-4-0" => {
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::TitleGeneration,
+                96.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::SemanticUnderstanding,
+                97.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::FormatCompliance,
+                98.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::ContentRevision,
+                97.0,
+            ));
+            caps.add_rating(
+                CapabilityRating::from_score(Capability::FastInference, 45.0)
+                    .with_latency(3500)
+                    .with_notes("Hosted API; best reserved for revision and analysis"),
+            );
+            caps.add_rating(CapabilityRating::from_score(Capability::LongContext, 98.0));
+        }
+
+        // Claude 3.5 Haiku (anthropic provider): fast hosted model
+        "claude-3-5-haiku-latest" | "claude-3-5-haiku-20241022" => {
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::TitleGeneration,
+                88.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::SemanticUnderstanding,
+                86.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::FormatCompliance,
+                95.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::ContentRevision,
+                85.0,
+            ));
+            caps.add_rating(
+                CapabilityRating::from_score(Capability::FastInference, 88.0).with_latency(700),
+            );
+            caps.add_rating(CapabilityRating::from_score(Capability::LongContext, 95.0));
+        }
+
+        // Gemini 2.5 Pro (gemini provider): 1M context
+        "gemini-2.5-pro" => {
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::TitleGeneration,
+                94.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::SemanticUnderstanding,
+                95.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::FormatCompliance,
+                97.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::ContentRevision,
+                95.0,
+            ));
+            caps.add_rating(
+                CapabilityRating::from_score(Capability::FastInference, 55.0)
+                    .with_latency(3000)
+                    .with_notes("Hosted API; thinking model, slower first token"),
+            );
+            caps.add_rating(CapabilityRating::from_score(Capability::LongContext, 99.0));
+        }
+
+        // Gemini 2.5 Flash (gemini provider): fast, 1M context
+        "gemini-2.5-flash" => {
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::TitleGeneration,
+                91.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::SemanticUnderstanding,
+                90.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::FormatCompliance,
+                97.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::ContentRevision,
+                90.0,
+            ));
+            caps.add_rating(
+                CapabilityRating::from_score(Capability::FastInference, 85.0).with_latency(900),
+            );
+            caps.add_rating(CapabilityRating::from_score(Capability::LongContext, 99.0));
+        }
+
+        // Gemini 2.5 Flash-Lite (gemini provider): cheapest, fastest
+        "gemini-2.5-flash-lite" => {
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::TitleGeneration,
+                85.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::SemanticUnderstanding,
+                83.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::FormatCompliance,
+                95.0,
+            ));
+            caps.add_rating(CapabilityRating::from_score(
+                Capability::ContentRevision,
+                82.0,
+            ));
+            caps.add_rating(
+                CapabilityRating::from_score(Capability::FastInference, 93.0).with_latency(500),
+            );
+            caps.add_rating(CapabilityRating::from_score(Capability::LongContext, 99.0));
+        }
+
         _ => return None,
     }
 
@@ -724,6 +874,17 @@ mod tests {
         assert!(unknown.is_none());
     }
 
+    #[test]
+    fn test_hosted_model_capabilities() {
+        let sonnet = known_model_capabilities("claude-sonnet-4-20250514").unwrap();
+        assert!(sonnet.is_good_for_titles());
+        assert!(sonnet.is_good_for_revision());
+
+        let flash = known_model_capabilities("gemini-2.5-flash").unwrap();
+        assert!(flash.is_fast_enough(1000));
+        assert_eq!(flash.tier_for(Capability::LongContext), QualityTier::Elite);
+    }
+
     #[test]
     fn test_is_fast_enough() {
         let llama = known_model_capabilities("llama3.1:8b").unwrap();
@@ -0,0 +1,183 @@
+//! Concept split suggestions.
+//!
+//! The model is shown the numbered titles of a broad concept's notes and
+//! asked to group them into narrower concepts. Its reply is validated
+//! against [`concept_split_schema`]; note numbers outside the list, notes
+//! already placed in an earlier group, and groups left empty are dropped.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use serde::Deserialize;
+
+use matric_core::{GenerationBackend, Result};
+
+use crate::constrained::{generate_constrained, ConstrainedConfig, OutputSchema};
+
+/// Most groups accepted from one reply.
+const MAX_SPLIT_GROUPS: u64 = 20;
+
+/// Longest group label accepted from a reply.
+const MAX_SPLIT_LABEL_CHARS: u64 = 100;
+
+static CONCEPT_SPLIT_SCHEMA: LazyLock<OutputSchema> = LazyLock::new(|| {
+    OutputSchema::new(
+        "concept_split",
+        serde_json::json!({
+            "type": "array",
+            "maxItems": MAX_SPLIT_GROUPS,
+            "items": {
+                "type": "object",
+                "required": ["label", "notes"],
+                "properties": {
+                    "label": { "type": "string", "minLength": 1, "maxLength": MAX_SPLIT_LABEL_CHARS },
+                    "notes": {
+                        "type": "array",
+                        "items": { "type": "integer", "minimum": 1 }
+                    }
+                }
+            }
+        }),
+    )
+    .expect("concept split schema must compile")
+});
+
+/// Schema for concept split replies: a JSON array of
+/// `{"label": ..., "notes": [1, 2]}` objects.
+pub fn concept_split_schema() -> &'static OutputSchema {
+    &CONCEPT_SPLIT_SCHEMA
+}
+
+/// A narrower concept proposed for some of the listed notes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConceptSplitGroup {
+    pub label: String,
+    /// Zero-based positions of the group's notes in the list the prompt showed
+    pub notes: Vec<usize>,
+}
+
+#[derive(Deserialize)]
+struct ConceptSplitReply {
+    label: String,
+    notes: Vec<usize>,
+}
+
+/// The `notes` prompt variable: one numbered title per line.
+pub fn concept_split_note_list<'a>(titles: impl IntoIterator<Item = &'a str>) -> String {
+    titles
+        .into_iter()
+        .enumerate()
+        .map(|(i, title)| {
+            let title = title.trim();
+            let title = if title.is_empty() {
+                "(untitled)"
+            } else {
+                title
+            };
+            format!("{}. {}", i + 1, title.replace(['\n', '\r'], " "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Propose groups for `note_count` listed notes against [`concept_split_schema`].
+///
+/// Labels are trimmed and groups repeating an earlier label (ignoring case)
+/// are dropped. Replies that still fail the schema after the configured
+/// repairs are an error.
+pub async fn suggest_concept_split(
+    backend: &dyn GenerationBackend,
+    prompt: &str,
+    note_count: usize,
+    config: &ConstrainedConfig,
+) -> Result<Vec<ConceptSplitGroup>> {
+    let output = generate_constrained::<Vec<ConceptSplitReply>>(
+        backend,
+        prompt,
+        concept_split_schema(),
+        config,
+    )
+    .await?;
+
+    let mut labels = HashSet::new();
+    let mut placed = HashSet::new();
+    Ok(output
+        .value
+        .into_iter()
+        .filter_map(|reply| {
+            let label = reply.label.trim().to_string();
+            if label.is_empty() || !labels.insert(label.to_lowercase()) {
+                return None;
+            }
+            let notes: Vec<usize> = reply
+                .notes
+                .into_iter()
+                .filter(|&n| (1..=note_count).contains(&n) && placed.insert(n))
+                .map(|n| n - 1)
+                .collect();
+            (!notes.is_empty()).then_some(ConceptSplitGroup { label, notes })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Answers every prompt with the same reply.
+    struct FixedBackend(&'static str);
+
+    #[async_trait]
+    impl GenerationBackend for FixedBackend {
+        async fn generate(&self, _prompt: &str) -> Result<String> {
+            Ok(self.0.to_string())
+        }
+
+        async fn generate_with_system(&self, _system: &str, prompt: &str) -> Result<String> {
+            self.generate(prompt).await
+        }
+
+        fn model_name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    #[tokio::test]
+    async fn suggest_drops_unknown_and_repeated_notes() {
+        let backend = FixedBackend(
+            r#"[
+                {"label": "Async Runtimes", "notes": [1, 3, 9]},
+                {"label": "Error Handling", "notes": [3, 2]},
+                {"label": " async runtimes ", "notes": [4]},
+                {"label": "Nothing Left", "notes": [1]}
+            ]"#,
+        );
+
+        let groups = suggest_concept_split(&backend, "prompt", 4, &ConstrainedConfig::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            groups,
+            vec![
+                ConceptSplitGroup {
+                    label: "Async Runtimes".to_string(),
+                    notes: vec![0, 2],
+                },
+                ConceptSplitGroup {
+                    label: "Error Handling".to_string(),
+                    notes: vec![1],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn note_list_numbers_titles_on_single_lines() {
+        assert_eq!(
+            concept_split_note_list(["Tokio tasks", "", "Line\nbreak"]),
+            "1. Tokio tasks\n2. (untitled)\n3. Line break"
+        );
+    }
+}
@@ -0,0 +1,354 @@
+//! Schema-constrained generation.
+//!
+//! JSON mode only guarantees that a reply parses, not that it has the shape a
+//! caller expects. [`generate_constrained`] validates each reply against a JSON
+//! Schema and, when it does not conform, re-prompts the model with the
+//! validation problems. Repairs are bounded; a reply that still fails is an
+//! error rather than a partially trusted value.
+
+use std::fmt;
+use std::sync::LazyLock;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tracing::debug;
+
+use matric_core::defaults::{CONSTRAINED_OUTPUT_MAX_PROBLEMS, CONSTRAINED_OUTPUT_MAX_REPAIRS};
+use matric_core::{Error, GenerationBackend, Result};
+
+use crate::thinking::parse_thinking_response;
+
+/// Longest validation message quoted back to the model.
+const MAX_PROBLEM_CHARS: usize = 200;
+
+/// A compiled JSON Schema that model replies must satisfy.
+pub struct OutputSchema {
+    name: &'static str,
+    schema: Value,
+    validator: jsonschema::Validator,
+}
+
+impl fmt::Debug for OutputSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OutputSchema")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl OutputSchema {
+    /// Compile `schema`. `name` identifies the schema in logs and errors.
+    pub fn new(name: &'static str, schema: Value) -> Result<Self> {
+        let validator = jsonschema::validator_for(&schema).map_err(|e| {
+            Error::Config(format!(
+                "output schema {name} does not compile; diagnostic_len={}",
+                e.to_string().len()
+            ))
+        })?;
+        Ok(Self {
+            name,
+            schema,
+            validator,
+        })
+    }
+
+    /// Identifier given at construction.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Problems with `value`, at most [`CONSTRAINED_OUTPUT_MAX_PROBLEMS`].
+    /// Empty when the value conforms.
+    pub fn problems(&self, value: &Value) -> Vec<String> {
+        self.validator
+            .iter_errors(value)
+            .take(CONSTRAINED_OUTPUT_MAX_PROBLEMS)
+            .map(|err| {
+                let path = err.instance_path().to_string();
+                let message: String = err.to_string().chars().take(MAX_PROBLEM_CHARS).collect();
+                if path.is_empty() {
+                    message
+                } else {
+                    format!("at {path}: {message}")
+                }
+            })
+            .collect()
+    }
+
+    /// Whether the schema's top level is an array.
+    fn expects_array(&self) -> bool {
+        self.schema.get("type").and_then(Value::as_str) == Some("array")
+    }
+}
+
+/// Bounds for [`generate_constrained`].
+#[derive(Debug, Clone)]
+pub struct ConstrainedConfig {
+    /// Repair prompts allowed after the first reply.
+    pub max_repairs: u32,
+}
+
+impl Default for ConstrainedConfig {
+    fn default() -> Self {
+        Self {
+            max_repairs: CONSTRAINED_OUTPUT_MAX_REPAIRS,
+        }
+    }
+}
+
+/// A reply that satisfied its schema.
+#[derive(Debug, Clone)]
+pub struct ConstrainedOutput<T> {
+    pub value: T,
+    /// Generation calls made, including the first.
+    pub attempts: u32,
+}
+
+impl<T> ConstrainedOutput<T> {
+    /// Whether at least one repair prompt was needed.
+    pub fn repaired(&self) -> bool {
+        self.attempts > 1
+    }
+}
+
+/// Generate JSON for `prompt` and deserialize it once it satisfies `schema`.
+///
+/// Each reply is extracted with [`extract_json`]. A reply that does not parse
+/// or does not conform triggers a repair prompt listing the problems, up to
+/// `config.max_repairs` times. Backend errors are returned immediately.
+pub async fn generate_constrained<T: DeserializeOwned>(
+    backend: &dyn GenerationBackend,
+    prompt: &str,
+    schema: &OutputSchema,
+    config: &ConstrainedConfig,
+) -> Result<ConstrainedOutput<T>> {
+    let mut request = prompt.to_string();
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let reply = backend.generate_json(&request).await?;
+        let problems = match extract_json(&reply, schema.expects_array()) {
+            Some(value) => {
+                let problems = schema.problems(&value);
+                if problems.is_empty() {
+                    match serde_json::from_value::<T>(value) {
+                        Ok(value) => return Ok(ConstrainedOutput { value, attempts }),
+                        Err(e) => vec![e.to_string()],
+                    }
+                } else {
+                    problems
+                }
+            }
+            None => vec!["the reply is not valid JSON".to_string()],
+        };
+
+        debug!(
+            schema = schema.name,
+            attempt = attempts,
+            problem_count = problems.len(),
+            reply_len = reply.len(),
+            "Model reply failed its output schema"
+        );
+        if attempts > config.max_repairs {
+            return Err(Error::Inference(format!(
+                "model reply failed output schema {} after {attempts} attempts; problem_count={}",
+                schema.name,
+                problems.len()
+            )));
+        }
+        request = repair_prompt(prompt, &schema.schema, &problems);
+    }
+}
+
+/// Pull a JSON value out of a model reply.
+///
+/// Drops `<think>` blocks and Markdown code fences, then falls back to the
+/// outermost bracketed span when the model wrapped the JSON in prose. When
+/// `want_array` is set, an object whose only array field holds the answer
+/// (`{"tags": [...]}`) is unwrapped to that array.
+pub fn extract_json(reply: &str, want_array: bool) -> Option<Value> {
+    let answer = if reply.contains("<think>") {
+        parse_thinking_response(reply).answer_content
+    } else {
+        reply.to_string()
+    };
+    let trimmed = answer
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+
+    let value = serde_json::from_str::<Value>(trimmed)
+        .ok()
+        .or_else(|| bracketed_span(trimmed).and_then(|s| serde_json::from_str(s).ok()))?;
+
+    if want_array {
+        if let Value::Object(map) = &value {
+            let mut arrays = map.values().filter(|v| v.is_array());
+            if let (Some(array), None) = (arrays.next(), arrays.next()) {
+                return Some(array.clone());
+            }
+        }
+    }
+    Some(value)
+}
+
+/// Text from the first `[` or `{` to the last matching closer.
+fn bracketed_span(text: &str) -> Option<&str> {
+    let start = text.find(['[', '{'])?;
+    let closer = if text[start..].starts_with('[') {
+        ']'
+    } else {
+        '}'
+    };
+    let end = text.rfind(closer)?;
+    (end > start).then(|| &text[start..=end])
+}
+
+fn repair_prompt(prompt: &str, schema: &Value, problems: &[String]) -> String {
+    let mut out = String::with_capacity(prompt.len() + 512);
+    out.push_str(prompt);
+    out.push_str("\n\nYour previous reply did not match the required output format. Problems:\n");
+    for problem in problems {
+        out.push_str("- ");
+        out.push_str(problem);
+        out.push('\n');
+    }
+    out.push_str("\nReply again with ONLY JSON that satisfies this JSON Schema:\n");
+    out.push_str(&schema.to_string());
+    out
+}
+
+/// Longest concept tag path accepted from a model.
+const MAX_CONCEPT_TAG_CHARS: u64 = 128;
+
+/// Most concept tags accepted from a single reply.
+const MAX_CONCEPT_TAGS: u64 = 50;
+
+static CONCEPT_TAGS_SCHEMA: LazyLock<OutputSchema> = LazyLock::new(|| {
+    OutputSchema::new(
+        "concept_tags",
+        serde_json::json!({
+            "type": "array",
+            "maxItems": MAX_CONCEPT_TAGS,
+            "items": {
+                "type": "string",
+                "minLength": 1,
+                "maxLength": MAX_CONCEPT_TAG_CHARS,
+                "pattern": "^[^\\s/{}\\[\\]\"][^\\n\\r{}\\[\\]\"]*$"
+            }
+        }),
+    )
+    .expect("concept tag schema must compile")
+});
+
+/// Schema for concept tagging replies: a JSON array of tag paths such as
+/// `"science/machine-learning"`.
+pub fn concept_tags_schema() -> &'static OutputSchema {
+    &CONCEPT_TAGS_SCHEMA
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// Replies with queued responses in order and records each prompt.
+    struct ScriptedBackend {
+        replies: Mutex<Vec<&'static str>>,
+        prompts: Mutex<Vec<String>>,
+    }
+
+    impl ScriptedBackend {
+        fn new(mut replies: Vec<&'static str>) -> Self {
+            replies.reverse();
+            Self {
+                replies: Mutex::new(replies),
+                prompts: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl GenerationBackend for ScriptedBackend {
+        async fn generate(&self, prompt: &str) -> Result<String> {
+            self.prompts.lock().unwrap().push(prompt.to_string());
+            Ok(self.replies.lock().unwrap().pop().unwrap_or("").to_string())
+        }
+
+        async fn generate_with_system(&self, _system: &str, prompt: &str) -> Result<String> {
+            self.generate(prompt).await
+        }
+
+        fn model_name(&self) -> &str {
+            "scripted"
+        }
+    }
+
+    #[test]
+    fn extract_json_strips_wrappers() {
+        let fenced = "```json\n[\"a/b\"]\n```";
+        assert_eq!(extract_json(fenced, true), Some(serde_json::json!(["a/b"])));
+
+        let prose = "<think>hmm</think>Sure! Here you go: {\"tags\": [\"x\"]} Hope it helps.";
+        assert_eq!(extract_json(prose, true), Some(serde_json::json!(["x"])));
+        assert_eq!(
+            extract_json(prose, false),
+            Some(serde_json::json!({"tags": ["x"]}))
+        );
+
+        assert_eq!(extract_json("no json here", true), None);
+    }
+
+    #[test]
+    fn concept_tags_schema_rejects_malformed_tags() {
+        let schema = concept_tags_schema();
+        assert!(schema
+            .problems(&serde_json::json!(["science/ml", "tool/pytorch"]))
+            .is_empty());
+        assert!(!schema.problems(&serde_json::json!([""])).is_empty());
+        assert!(!schema
+            .problems(&serde_json::json!(["{\"tag\": \"x\"}"]))
+            .is_empty());
+        assert!(!schema.problems(&serde_json::json!([1, 2])).is_empty());
+        assert!(!schema.problems(&serde_json::json!({"a": "b"})).is_empty());
+    }
+
+    #[tokio::test]
+    async fn repairs_until_reply_conforms() {
+        let backend = ScriptedBackend::new(vec!["not json", "[42]", "[\"science/ml\"]"]);
+        let output: ConstrainedOutput<Vec<String>> = generate_constrained(
+            &backend,
+            "Tag this",
+            concept_tags_schema(),
+            &ConstrainedConfig::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(output.value, vec!["science/ml".to_string()]);
+        assert_eq!(output.attempts, 3);
+        assert!(output.repaired());
+        let prompts = backend.prompts.lock().unwrap();
+        assert!(prompts[0] == "Tag this");
+        assert!(prompts[1].starts_with("Tag this") && prompts[1].contains("not valid JSON"));
+        assert!(prompts[2].contains("JSON Schema"));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_repairs() {
+        let backend = ScriptedBackend::new(vec!["{}", "{}", "{}", "[\"late\"]"]);
+        let result: Result<ConstrainedOutput<Vec<String>>> = generate_constrained(
+            &backend,
+            "Tag this",
+            concept_tags_schema(),
+            &ConstrainedConfig { max_repairs: 1 },
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::Inference(_))));
+        assert_eq!(backend.prompts.lock().unwrap().len(), 2);
+    }
+}
@@ -1,12 +1,28 @@
 //! Redaction-safe diagnostics for inference backend errors.
 
+use matric_core::metrics::{self, EMBEDDING_DURATION_SECONDS};
 use reqwest::StatusCode;
 use std::fmt::Display;
+use std::time::Duration;
 
 pub(crate) fn text_len(value: &str) -> usize {
     value.chars().count()
 }
 
+/// Record an embedding request's latency on the process metrics registry.
+pub(crate) fn observe_embedding_latency<T>(
+    backend: &'static str,
+    result: &matric_core::Result<T>,
+    elapsed: Duration,
+) {
+    let outcome = if result.is_ok() { "success" } else { "error" };
+    metrics::global().observe(
+        &EMBEDDING_DURATION_SECONDS,
+        &[("backend", backend), ("outcome", outcome)],
+        elapsed,
+    );
+}
+
 pub(crate) fn backend_body_reason(body: &str) -> &'static str {
     let lower = body.to_ascii_lowercase();
 
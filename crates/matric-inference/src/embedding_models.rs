@@ -277,6 +277,12 @@ impl EmbeddingModelRegistry {
         Self { models }
     }
 
+    /// Add or replace a model profile, e.g. for a locally loaded model whose
+    /// dimension comes from its own configuration.
+    pub fn register(&mut self, profile: EmbeddingModelProfile) {
+        self.models.insert(profile.name.clone(), profile);
+    }
+
     /// Get a model profile by name.
     pub fn get(&self, model_name: &str) -> Option<&EmbeddingModelProfile> {
         self.models.get(model_name)
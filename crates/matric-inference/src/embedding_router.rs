@@ -0,0 +1,582 @@
+//! Embedding endpoint failover and load balancing.
+//!
+//! A provider can serve embeddings from several endpoints, e.g. one Ollama
+//! instance per GPU host. [`EmbeddingEndpointPool`] holds the endpoints of one
+//! provider with a weight and a [`CircuitBreaker`] each, and is shared by
+//! every backend the [`ProviderRegistry`](crate::ProviderRegistry) resolves
+//! for that provider, so embedding jobs and search-time query embedding see
+//! the same endpoint health.
+//!
+//! [`RoutedEmbeddingBackend`] picks an endpoint by smooth weighted
+//! round-robin among endpoints whose circuit is not open, and fails over to
+//! the remaining endpoints, heaviest first, when a call fails. Failed calls
+//! count towards opening the endpoint's circuit; an open circuit is probed
+//! again after its cooldown. A background health check probes every endpoint
+//! so a recovered endpoint rejoins the rotation without waiting for traffic.
+//!
+//! ```
+//! use matric_inference::embedding_router::EmbeddingEndpoint;
+//!
+//! let endpoints = EmbeddingEndpoint::parse_list("http://gpu-1:11434=3, http://gpu-2:11434").unwrap();
+//! assert_eq!(endpoints[0].weight, 3);
+//! assert_eq!(endpoints[1].base_url, "http://gpu-2:11434");
+//! assert_eq!(endpoints[1].weight, 1);
+//! ```
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tracing::{debug, warn};
+
+use matric_core::{EmbeddingBackend, Error, Result, Vector};
+
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+
+/// Largest weight an endpoint can have.
+pub const MAX_ENDPOINT_WEIGHT: u32 = 100;
+
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One embedding endpoint of a provider.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EmbeddingEndpoint {
+    /// Base URL, in the same form as the provider's own base URL.
+    pub base_url: String,
+    /// Share of requests relative to the other endpoints, 1 through 100.
+    pub weight: u32,
+}
+
+impl fmt::Debug for EmbeddingEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmbeddingEndpoint")
+            .field("base_url_len", &self.base_url.chars().count())
+            .field("weight", &self.weight)
+            .finish()
+    }
+}
+
+impl EmbeddingEndpoint {
+    /// Parse a comma-separated endpoint list. Each entry is a base URL with
+    /// an optional `=<weight>` suffix; the weight defaults to 1.
+    pub fn parse_list(value: &str) -> std::result::Result<Vec<Self>, String> {
+        let mut endpoints = Vec::new();
+        for (index, entry) in value.split(',').enumerate() {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+            let (base_url, weight) = match entry.rsplit_once('=') {
+                Some((url, weight)) if weight.trim().chars().all(|c| c.is_ascii_digit()) => {
+                    let weight = weight
+                        .trim()
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|weight| (1..=MAX_ENDPOINT_WEIGHT).contains(weight));
+                    let Some(weight) = weight else {
+                        return Err(format!(
+                            "embedding endpoint {} weight must be between 1 and {}",
+                            index + 1,
+                            MAX_ENDPOINT_WEIGHT
+                        ));
+                    };
+                    (url.trim(), weight)
+                }
+                _ => (entry, 1),
+            };
+            if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
+                return Err(format!(
+                    "embedding endpoint {} must be an http:// or https:// URL",
+                    index + 1
+                ));
+            }
+            endpoints.push(Self {
+                base_url: base_url.trim_end_matches('/').to_string(),
+                weight,
+            });
+        }
+        if endpoints.is_empty() {
+            return Err("embedding endpoint list is empty".to_string());
+        }
+        Ok(endpoints)
+    }
+}
+
+/// Circuit breaking and health check settings for embedding endpoint pools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmbeddingRouterConfig {
+    /// Consecutive failures that open an endpoint's circuit.
+    pub failure_threshold: u32,
+    /// Seconds an open circuit waits before it lets a probe request through.
+    pub cooldown_secs: u64,
+    /// Seconds between background health checks. 0 disables them.
+    pub health_check_interval_secs: u64,
+}
+
+impl Default for EmbeddingRouterConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            cooldown_secs: 30,
+            health_check_interval_secs: 15,
+        }
+    }
+}
+
+impl EmbeddingRouterConfig {
+    /// Read the router settings from environment variables, clamping
+    /// out-of-range values and ignoring unparseable ones.
+    ///
+    /// | Variable | Default | Range |
+    /// |----------|---------|-------|
+    /// | `EMBED_ENDPOINT_FAILURE_THRESHOLD` | 3 | 1-100 |
+    /// | `EMBED_ENDPOINT_COOLDOWN_SECS` | 30 | 1-3600 |
+    /// | `EMBED_ENDPOINT_HEALTH_CHECK_SECS` | 15 | 0-3600, 0 disables |
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            failure_threshold: read("EMBED_ENDPOINT_FAILURE_THRESHOLD")
+                .map(|v| v.clamp(1, 100) as u32)
+                .unwrap_or(defaults.failure_threshold),
+            cooldown_secs: read("EMBED_ENDPOINT_COOLDOWN_SECS")
+                .map(|v| v.clamp(1, 3_600))
+                .unwrap_or(defaults.cooldown_secs),
+            health_check_interval_secs: read("EMBED_ENDPOINT_HEALTH_CHECK_SECS")
+                .map(|v| v.min(3_600))
+                .unwrap_or(defaults.health_check_interval_secs),
+        }
+    }
+}
+
+struct PoolEndpoint {
+    endpoint: EmbeddingEndpoint,
+    breaker: CircuitBreaker,
+}
+
+/// The embedding endpoints of one provider and their shared health state.
+pub struct EmbeddingEndpointPool {
+    provider_id: String,
+    endpoints: Vec<PoolEndpoint>,
+    api_key: Option<String>,
+    config: EmbeddingRouterConfig,
+    /// Smooth weighted round-robin state, one entry per endpoint.
+    current_weights: Mutex<Vec<i64>>,
+    client: reqwest::Client,
+}
+
+impl fmt::Debug for EmbeddingEndpointPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmbeddingEndpointPool")
+            .field("provider_id_len", &self.provider_id.chars().count())
+            .field("endpoint_count", &self.endpoints.len())
+            .field("api_key_present", &self.api_key.is_some())
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl EmbeddingEndpointPool {
+    /// Create a pool. Returns an error when `endpoints` is empty.
+    pub fn new(
+        provider_id: impl Into<String>,
+        endpoints: Vec<EmbeddingEndpoint>,
+        api_key: Option<String>,
+        config: EmbeddingRouterConfig,
+    ) -> Result<Self> {
+        let provider_id = provider_id.into();
+        if endpoints.is_empty() {
+            return Err(Error::Config(format!(
+                "Provider '{}' needs at least one embedding endpoint",
+                provider_id
+            )));
+        }
+        let endpoints: Vec<PoolEndpoint> = endpoints
+            .into_iter()
+            .enumerate()
+            .map(|(index, endpoint)| {
+                let mut breaker_config =
+                    CircuitBreakerConfig::new(format!("{provider_id} embedding endpoint {index}"));
+                breaker_config.failure_threshold = config.failure_threshold.max(1);
+                breaker_config.cooldown = Duration::from_secs(config.cooldown_secs);
+                PoolEndpoint {
+                    endpoint,
+                    breaker: CircuitBreaker::new(breaker_config),
+                }
+            })
+            .collect();
+        Ok(Self {
+            current_weights: Mutex::new(vec![0; endpoints.len()]),
+            provider_id,
+            endpoints,
+            api_key,
+            config,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    pub fn provider_id(&self) -> &str {
+        &self.provider_id
+    }
+
+    pub fn config(&self) -> &EmbeddingRouterConfig {
+        &self.config
+    }
+
+    /// The configured endpoints, in configuration order.
+    pub fn endpoints(&self) -> impl Iterator<Item = &EmbeddingEndpoint> {
+        self.endpoints.iter().map(|pooled| &pooled.endpoint)
+    }
+
+    /// Circuit state of each endpoint, in configuration order.
+    pub fn circuit_states(&self) -> Vec<CircuitState> {
+        self.endpoints
+            .iter()
+            .map(|pooled| pooled.breaker.current_state())
+            .collect()
+    }
+
+    /// Endpoint indexes to try for one request: the weighted round-robin pick
+    /// first, then the other endpoints by descending weight. Endpoints with an
+    /// open circuit are left out.
+    fn attempt_order(&self) -> Vec<usize> {
+        let available: Vec<usize> = (0..self.endpoints.len())
+            .filter(|&index| self.endpoints[index].breaker.current_state() != CircuitState::Open)
+            .collect();
+        let Some(primary) = self.pick_weighted(&available) else {
+            return Vec::new();
+        };
+        let mut rest: Vec<usize> = available
+            .into_iter()
+            .filter(|&index| index != primary)
+            .collect();
+        rest.sort_by_key(|&index| std::cmp::Reverse(self.endpoints[index].endpoint.weight));
+        std::iter::once(primary).chain(rest).collect()
+    }
+
+    /// Smooth weighted round-robin over `candidates`.
+    fn pick_weighted(&self, candidates: &[usize]) -> Option<usize> {
+        let mut current = self
+            .current_weights
+            .lock()
+            .expect("embedding endpoint pool lock poisoned");
+        let mut total = 0i64;
+        let mut best: Option<usize> = None;
+        for &index in candidates {
+            let weight = i64::from(self.endpoints[index].endpoint.weight);
+            current[index] += weight;
+            total += weight;
+            if best.is_none_or(|best| current[index] > current[best]) {
+                best = Some(index);
+            }
+        }
+        if let Some(best) = best {
+            current[best] -= total;
+        }
+        best
+    }
+
+    fn health_url(&self, endpoint: &EmbeddingEndpoint) -> String {
+        match self.provider_id.as_str() {
+            "ollama" => format!("{}/api/tags", endpoint.base_url),
+            _ => format!("{}/models", endpoint.base_url),
+        }
+    }
+
+    /// Probe every endpoint once and record the outcome on its circuit.
+    pub async fn check_health(&self) {
+        for (index, pooled) in self.endpoints.iter().enumerate() {
+            let mut request = self
+                .client
+                .get(self.health_url(&pooled.endpoint))
+                .timeout(HEALTH_CHECK_TIMEOUT);
+            if let Some(api_key) = &self.api_key {
+                request = request.bearer_auth(api_key);
+            }
+            let healthy = match request.send().await {
+                Ok(response) => response.status().is_success(),
+                Err(_) => false,
+            };
+            if healthy {
+                pooled.breaker.record_success();
+            } else {
+                pooled.breaker.record_failure();
+            }
+            debug!(
+                provider_id_len = self.provider_id.chars().count(),
+                endpoint_index = index,
+                healthy,
+                circuit_state = %pooled.breaker.current_state(),
+                "Embedding endpoint health check"
+            );
+        }
+    }
+
+    /// Spawn the background health check loop. Returns `None` when health
+    /// checks are disabled.
+    pub fn spawn_health_checks(self: &Arc<Self>) -> Option<tokio::task::JoinHandle<()>> {
+        if self.config.health_check_interval_secs == 0 {
+            return None;
+        }
+        let pool = Arc::clone(self);
+        Some(tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(pool.config.health_check_interval_secs));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                pool.check_health().await;
+            }
+        }))
+    }
+}
+
+/// Embedding backend that spreads calls over the endpoints of a pool.
+///
+/// Holds one backend per pool endpoint, all for the same model and
+/// dimension, so the routed backend keeps the embedding contract of the
+/// provider no matter which endpoint answers.
+pub struct RoutedEmbeddingBackend {
+    pool: Arc<EmbeddingEndpointPool>,
+    backends: Vec<Box<dyn EmbeddingBackend>>,
+}
+
+impl RoutedEmbeddingBackend {
+    /// `backends` must hold one backend per pool endpoint, in pool order.
+    pub fn new(
+        pool: Arc<EmbeddingEndpointPool>,
+        backends: Vec<Box<dyn EmbeddingBackend>>,
+    ) -> Result<Self> {
+        if backends.len() != pool.endpoints.len() {
+            return Err(Error::Config(
+                "Routed embedding backend needs one backend per endpoint".to_string(),
+            ));
+        }
+        let dimension = backends[0].dimension();
+        if backends
+            .iter()
+            .any(|backend| backend.dimension() != dimension)
+        {
+            return Err(Error::Config(
+                "Routed embedding backends must share one dimension".to_string(),
+            ));
+        }
+        Ok(Self { pool, backends })
+    }
+}
+
+#[async_trait]
+impl EmbeddingBackend for RoutedEmbeddingBackend {
+    async fn embed_texts(&self, texts: &[String]) -> Result<Vec<Vector>> {
+        let order = self.pool.attempt_order();
+        let mut last_error = None;
+        for (attempt, index) in order.iter().copied().enumerate() {
+            let breaker = &self.pool.endpoints[index].breaker;
+            match self.backends[index].embed_texts(texts).await {
+                Ok(vectors) => {
+                    breaker.record_success();
+                    if attempt > 0 {
+                        debug!(
+                            provider_id_len = self.pool.provider_id.chars().count(),
+                            endpoint_index = index,
+                            attempt,
+                            "Embedding request served by failover endpoint"
+                        );
+                    }
+                    return Ok(vectors);
+                }
+                Err(error) => {
+                    breaker.record_failure();
+                    warn!(
+                        provider_id_len = self.pool.provider_id.chars().count(),
+                        endpoint_index = index,
+                        remaining_endpoints = order.len() - attempt - 1,
+                        error_len = error.to_string().chars().count(),
+                        "Embedding endpoint failed"
+                    );
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            Error::Embedding(format!(
+                "All {} embedding endpoints have an open circuit",
+                self.backends.len()
+            ))
+        }))
+    }
+
+    fn dimension(&self) -> usize {
+        self.backends[0].dimension()
+    }
+
+    fn model_name(&self) -> &str {
+        self.backends[0].model_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    struct EndpointBackend {
+        calls: Arc<AtomicUsize>,
+        failing: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl EmbeddingBackend for EndpointBackend {
+        async fn embed_texts(&self, texts: &[String]) -> Result<Vec<Vector>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(Error::Embedding("endpoint down".to_string()));
+            }
+            Ok(texts.iter().map(|_| Vector::from(vec![0.0])).collect())
+        }
+
+        fn dimension(&self) -> usize {
+            1
+        }
+
+        fn model_name(&self) -> &str {
+            "endpoint"
+        }
+    }
+
+    struct Harness {
+        backend: RoutedEmbeddingBackend,
+        pool: Arc<EmbeddingEndpointPool>,
+        calls: Vec<Arc<AtomicUsize>>,
+        failing: Vec<Arc<AtomicBool>>,
+    }
+
+    fn harness(weights: &[u32]) -> Harness {
+        let endpoints = weights
+            .iter()
+            .enumerate()
+            .map(|(index, &weight)| EmbeddingEndpoint {
+                base_url: format!("http://embed-{index}:11434"),
+                weight,
+            })
+            .collect();
+        let config = EmbeddingRouterConfig {
+            failure_threshold: 2,
+            cooldown_secs: 3_600,
+            health_check_interval_secs: 0,
+        };
+        let pool = Arc::new(EmbeddingEndpointPool::new("ollama", endpoints, None, config).unwrap());
+        let calls: Vec<_> = weights
+            .iter()
+            .map(|_| Arc::new(AtomicUsize::new(0)))
+            .collect();
+        let failing: Vec<_> = weights
+            .iter()
+            .map(|_| Arc::new(AtomicBool::new(false)))
+            .collect();
+        let backends = calls
+            .iter()
+            .zip(&failing)
+            .map(|(calls, failing)| {
+                Box::new(EndpointBackend {
+                    calls: calls.clone(),
+                    failing: failing.clone(),
+                }) as Box<dyn EmbeddingBackend>
+            })
+            .collect();
+        Harness {
+            backend: RoutedEmbeddingBackend::new(pool.clone(), backends).unwrap(),
+            pool,
+            calls,
+            failing,
+        }
+    }
+
+    fn counts(harness: &Harness) -> Vec<usize> {
+        harness
+            .calls
+            .iter()
+            .map(|calls| calls.load(Ordering::SeqCst))
+            .collect()
+    }
+
+    #[test]
+    fn parse_list_reads_weights_and_rejects_bad_entries() {
+        let endpoints =
+            EmbeddingEndpoint::parse_list("http://a:11434/=2,, https://b/v1?x=y").unwrap();
+        assert_eq!(endpoints.len(), 2);
+        assert_eq!(endpoints[0].base_url, "http://a:11434");
+        assert_eq!(endpoints[0].weight, 2);
+        assert_eq!(endpoints[1].base_url, "https://b/v1?x=y");
+        assert_eq!(endpoints[1].weight, 1);
+
+        assert!(EmbeddingEndpoint::parse_list("http://a=0").is_err());
+        assert!(EmbeddingEndpoint::parse_list("http://a=101").is_err());
+        assert!(EmbeddingEndpoint::parse_list("a:11434").is_err());
+        assert!(EmbeddingEndpoint::parse_list(" , ").is_err());
+    }
+
+    #[tokio::test]
+    async fn requests_are_spread_by_weight() {
+        let harness = harness(&[3, 1]);
+        for _ in 0..8 {
+            harness
+                .backend
+                .embed_texts(&["x".to_string()])
+                .await
+                .unwrap();
+        }
+        assert_eq!(counts(&harness), [6, 2]);
+    }
+
+    #[tokio::test]
+    async fn failed_calls_fail_over_and_open_the_circuit() {
+        let harness = harness(&[1, 1]);
+        harness.failing[0].store(true, Ordering::SeqCst);
+
+        for _ in 0..6 {
+            harness
+                .backend
+                .embed_texts(&["x".to_string()])
+                .await
+                .unwrap();
+        }
+
+        // Two failures open the first endpoint's circuit; after that every
+        // request goes straight to the second endpoint.
+        assert_eq!(counts(&harness), [2, 6]);
+        assert_eq!(
+            harness.pool.circuit_states(),
+            [CircuitState::Open, CircuitState::Closed]
+        );
+    }
+
+    #[tokio::test]
+    async fn all_endpoints_down_fails_the_request() {
+        let harness = harness(&[1, 1]);
+        harness.failing[0].store(true, Ordering::SeqCst);
+        harness.failing[1].store(true, Ordering::SeqCst);
+
+        assert!(harness
+            .backend
+            .embed_texts(&["x".to_string()])
+            .await
+            .is_err());
+        assert!(harness
+            .backend
+            .embed_texts(&["x".to_string()])
+            .await
+            .is_err());
+        assert_eq!(counts(&harness), [2, 2]);
+
+        // Both circuits are open now, so the request fails fast.
+        let error = harness
+            .backend
+            .embed_texts(&["x".to_string()])
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Embedding(message) if message.contains("open circuit")));
+        assert_eq!(counts(&harness), [2, 2]);
+    }
+}
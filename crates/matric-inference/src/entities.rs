@@ -0,0 +1,192 @@
+//! LLM named entity extraction.
+//!
+//! The model is asked for the people, organizations, places, and dates a
+//! text names, and its reply is validated against [`entity_extraction_schema`].
+//! Mentions of the same entity are merged by type and normalized name, so a
+//! long note extracted chunk by chunk yields each entity once.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use serde::Deserialize;
+
+use matric_core::{normalize_entity_name, EntityType, ExtractedEntity, GenerationBackend, Result};
+
+use crate::constrained::{generate_constrained, ConstrainedConfig, OutputSchema};
+
+/// Most entities accepted from one extraction reply.
+const MAX_ENTITIES_PER_REPLY: u64 = 100;
+
+/// Longest entity name accepted from a reply.
+const MAX_ENTITY_NAME_CHARS: u64 = 200;
+
+static ENTITY_EXTRACTION_SCHEMA: LazyLock<OutputSchema> = LazyLock::new(|| {
+    let types: Vec<String> = EntityType::EXTRACTED
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    OutputSchema::new(
+        "entity_extraction",
+        serde_json::json!({
+            "type": "array",
+            "maxItems": MAX_ENTITIES_PER_REPLY,
+            "items": {
+                "type": "object",
+                "required": ["name", "type"],
+                "properties": {
+                    "name": { "type": "string", "minLength": 1, "maxLength": MAX_ENTITY_NAME_CHARS },
+                    "type": { "enum": types }
+                }
+            }
+        }),
+    )
+    .expect("entity extraction schema must compile")
+});
+
+/// Schema for entity extraction replies: a JSON array of
+/// `{"name": ..., "type": ...}` objects.
+pub fn entity_extraction_schema() -> &'static OutputSchema {
+    &ENTITY_EXTRACTION_SCHEMA
+}
+
+#[derive(Deserialize)]
+struct EntityReply {
+    name: String,
+    #[serde(rename = "type")]
+    entity_type: String,
+}
+
+/// Extract entities by generating against [`entity_extraction_schema`].
+///
+/// Names that normalize to nothing are dropped and repeats are merged.
+/// Replies that still fail the schema after the configured repairs are an
+/// error.
+pub async fn extract_entities(
+    backend: &dyn GenerationBackend,
+    prompt: &str,
+    config: &ConstrainedConfig,
+) -> Result<Vec<ExtractedEntity>> {
+    let output = generate_constrained::<Vec<EntityReply>>(
+        backend,
+        prompt,
+        entity_extraction_schema(),
+        config,
+    )
+    .await?;
+    let mut entities = Vec::new();
+    merge_entities(
+        &mut entities,
+        output.value.into_iter().filter_map(|reply| {
+            Some(ExtractedEntity {
+                name: reply.name.trim().to_string(),
+                entity_type: reply.entity_type.parse().ok()?,
+            })
+        }),
+    );
+    Ok(entities)
+}
+
+/// Append `more` to `into`, skipping entities already present by type and
+/// normalized name. The first spelling seen is kept.
+pub fn merge_entities(
+    into: &mut Vec<ExtractedEntity>,
+    more: impl IntoIterator<Item = ExtractedEntity>,
+) {
+    let mut seen: HashSet<(EntityType, String)> = into
+        .iter()
+        .map(|e| (e.entity_type, normalize_entity_name(&e.name)))
+        .collect();
+    for entity in more {
+        let key = normalize_entity_name(&entity.name);
+        if key.is_empty() {
+            continue;
+        }
+        if seen.insert((entity.entity_type, key)) {
+            into.push(entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Answers every prompt with the same reply.
+    struct FixedBackend(&'static str);
+
+    #[async_trait]
+    impl GenerationBackend for FixedBackend {
+        async fn generate(&self, _prompt: &str) -> Result<String> {
+            Ok(self.0.to_string())
+        }
+
+        async fn generate_with_system(&self, _system: &str, prompt: &str) -> Result<String> {
+            self.generate(prompt).await
+        }
+
+        fn model_name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    fn entity(name: &str, entity_type: EntityType) -> ExtractedEntity {
+        ExtractedEntity {
+            name: name.to_string(),
+            entity_type,
+        }
+    }
+
+    #[tokio::test]
+    async fn extract_entities_merges_repeats() {
+        let backend = FixedBackend(
+            r#"{"entities": [
+                {"name": "Ada Lovelace", "type": "person"},
+                {"name": "ada  lovelace.", "type": "person"},
+                {"name": "London", "type": "location"},
+                {"name": "London", "type": "organization"}
+            ]}"#,
+        );
+
+        let entities = extract_entities(&backend, "prompt", &ConstrainedConfig::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            entities,
+            vec![
+                entity("Ada Lovelace", EntityType::Person),
+                entity("London", EntityType::Location),
+                entity("London", EntityType::Organization),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn extract_entities_rejects_unknown_types() {
+        let backend = FixedBackend(r#"[{"name": "Widget", "type": "product"}]"#);
+        let config = ConstrainedConfig { max_repairs: 0 };
+
+        assert!(extract_entities(&backend, "prompt", &config).await.is_err());
+    }
+
+    #[test]
+    fn merge_entities_keeps_first_spelling_and_drops_blank_names() {
+        let mut entities = vec![entity("ACME Corp", EntityType::Organization)];
+        merge_entities(
+            &mut entities,
+            [
+                entity("acme corp.", EntityType::Organization),
+                entity(" - ", EntityType::Person),
+                entity("1815", EntityType::Date),
+            ],
+        );
+        assert_eq!(
+            entities,
+            vec![
+                entity("ACME Corp", EntityType::Organization),
+                entity("1815", EntityType::Date),
+            ]
+        );
+    }
+}
@@ -0,0 +1,511 @@
+//! Google Gemini API backend for generation and vision.
+//!
+//! Talks to the Generative Language API `generateContent` endpoint directly.
+//! The registry exposes it as the `gemini` provider, e.g.
+//! `gemini:gemini-2.5-flash`. JSON requests use the API's native JSON output
+//! (`responseMimeType: application/json`).
+//!
+//! The API key is sent in the `x-goog-api-key` header rather than the `key`
+//! query parameter so it never appears in request URLs or their logs.
+
+use std::fmt;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, warn};
+
+use crate::diagnostics::{backend_parse_error, backend_request_error, backend_status_error};
+use crate::token_accounting::{record_generation, TokenUsage};
+use crate::vision::VisionBackend;
+use matric_core::{Error, GenerationBackend, Result};
+
+/// Default Generative Language API endpoint.
+pub const DEFAULT_GEMINI_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// Default model for generation and vision.
+pub const DEFAULT_MODEL: &str = "gemini-2.5-flash";
+
+/// Default output token limit.
+pub const DEFAULT_MAX_TOKENS: u32 = 8192;
+
+/// Default timeout in seconds.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+const DEFAULT_VISION_PROMPT: &str =
+    "Describe this image in detail. Include any text visible in the image.";
+
+/// Configuration for the Gemini backend.
+#[derive(Clone)]
+pub struct GeminiConfig {
+    /// Base URL for the API, including the version prefix.
+    pub base_url: String,
+    /// API key sent as `x-goog-api-key`.
+    pub api_key: String,
+    /// Model used for generation and image description.
+    pub model: String,
+    /// Most tokens generated per request.
+    pub max_tokens: u32,
+    /// Request timeout in seconds.
+    pub timeout_seconds: u64,
+}
+
+impl fmt::Debug for GeminiConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GeminiConfig")
+            .field("base_url_len", &self.base_url.chars().count())
+            .field("api_key_len", &self.api_key.chars().count())
+            .field("model_len", &self.model.chars().count())
+            .field("max_tokens", &self.max_tokens)
+            .field("timeout_seconds", &self.timeout_seconds)
+            .finish()
+    }
+}
+
+impl GeminiConfig {
+    /// Configuration with default endpoint, model and limits.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            base_url: DEFAULT_GEMINI_URL.to_string(),
+            api_key: api_key.into(),
+            model: DEFAULT_MODEL.to_string(),
+            max_tokens: DEFAULT_MAX_TOKENS,
+            timeout_seconds: DEFAULT_TIMEOUT_SECS,
+        }
+    }
+
+    /// Read the configuration from `GEMINI_API_KEY` (or `GOOGLE_API_KEY`),
+    /// `GEMINI_BASE_URL`, `GEMINI_GEN_MODEL`, `GEMINI_MAX_TOKENS` and
+    /// `GEMINI_TIMEOUT`. Returns `None` when no API key is set.
+    pub fn from_env() -> Option<Self> {
+        let api_key = std::env::var("GEMINI_API_KEY")
+            .ok()
+            .filter(|key| !key.trim().is_empty())
+            .or_else(|| {
+                std::env::var("GOOGLE_API_KEY")
+                    .ok()
+                    .filter(|key| !key.trim().is_empty())
+            })?;
+        let mut config = Self::new(api_key);
+        if let Ok(base_url) = std::env::var("GEMINI_BASE_URL") {
+            if !base_url.trim().is_empty() {
+                config.base_url = base_url.trim().trim_end_matches('/').to_string();
+            }
+        }
+        if let Ok(model) = std::env::var("GEMINI_GEN_MODEL") {
+            if !model.trim().is_empty() {
+                config.model = model.trim().to_string();
+            }
+        }
+        if let Some(max_tokens) = std::env::var("GEMINI_MAX_TOKENS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u32>().ok())
+        {
+            config.max_tokens = max_tokens.clamp(1, 65_536);
+        }
+        if let Some(timeout) = std::env::var("GEMINI_TIMEOUT")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+        {
+            config.timeout_seconds = timeout.max(1);
+        }
+        Some(config)
+    }
+}
+
+/// Check that a model name is safe to place in the request path.
+fn validate_model(model: &str) -> Result<()> {
+    let model = model.strip_prefix("models/").unwrap_or(model);
+    if model.is_empty()
+        || !model
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'))
+    {
+        return Err(Error::Config(format!(
+            "Invalid Gemini model name (model_len={})",
+            model.chars().count()
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateContentRequest {
+    contents: Vec<Content>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<Content>,
+    generation_config: GenerationConfig,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Content {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    role: Option<String>,
+    #[serde(default)]
+    parts: Vec<Part>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Part {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    inline_data: Option<InlineData>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InlineData {
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerationConfig {
+    max_output_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<&'static str>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateContentResponse {
+    #[serde(default)]
+    candidates: Vec<Candidate>,
+    #[serde(default)]
+    prompt_feedback: Option<PromptFeedback>,
+    #[serde(default)]
+    usage_metadata: Option<UsageMetadata>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageMetadata {
+    #[serde(default)]
+    prompt_token_count: u64,
+    #[serde(default)]
+    candidates_token_count: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Candidate {
+    #[serde(default)]
+    content: Option<Content>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptFeedback {
+    #[serde(default)]
+    block_reason: Option<String>,
+}
+
+fn text_part(text: &str) -> Part {
+    Part {
+        text: Some(text.to_string()),
+        inline_data: None,
+    }
+}
+
+/// Google Gemini API backend.
+pub struct GeminiBackend {
+    client: Client,
+    config: GeminiConfig,
+}
+
+impl GeminiBackend {
+    /// Create a backend with the given configuration.
+    pub fn new(config: GeminiConfig) -> Result<Self> {
+        if config.api_key.trim().is_empty() {
+            return Err(Error::Config("Gemini API key is empty".to_string()));
+        }
+        validate_model(&config.model)?;
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()
+            .map_err(|e| Error::Inference(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(Self { client, config })
+    }
+
+    /// Create from `GEMINI_*` environment variables.
+    pub fn from_env() -> Result<Self> {
+        let config = GeminiConfig::from_env()
+            .ok_or_else(|| Error::Config("GEMINI_API_KEY is not set".to_string()))?;
+        Self::new(config)
+    }
+
+    /// Get the current configuration.
+    pub fn config(&self) -> &GeminiConfig {
+        &self.config
+    }
+
+    fn url(&self, endpoint: &str) -> String {
+        format!("{}{}", self.config.base_url.trim_end_matches('/'), endpoint)
+    }
+
+    async fn generate_content(&self, system: &str, parts: Vec<Part>, json: bool) -> Result<String> {
+        let model = self
+            .config
+            .model
+            .strip_prefix("models/")
+            .unwrap_or(&self.config.model);
+        let mut sent = system.to_string();
+        for part in &parts {
+            if let Some(text) = &part.text {
+                sent.push_str(text);
+            }
+        }
+        let request = GenerateContentRequest {
+            contents: vec![Content {
+                role: Some("user".to_string()),
+                parts,
+            }],
+            system_instruction: (!system.trim().is_empty()).then(|| Content {
+                role: None,
+                parts: vec![text_part(system)],
+            }),
+            generation_config: GenerationConfig {
+                max_output_tokens: self.config.max_tokens,
+                response_mime_type: json.then_some("application/json"),
+            },
+        };
+
+        let response = self
+            .client
+            .post(self.url(&format!("/models/{model}:generateContent")))
+            .header("x-goog-api-key", &self.config.api_key)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| {
+                Error::Inference(backend_request_error("Gemini generate request failed", &e))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Inference(backend_status_error(
+                "Gemini generate",
+                status,
+                &body,
+            )));
+        }
+
+        let result: GenerateContentResponse = response.json().await.map_err(|e| {
+            Error::Inference(backend_parse_error(
+                "Gemini generate response parse failed",
+                e,
+            ))
+        })?;
+
+        if let Some(reason) = result.prompt_feedback.and_then(|f| f.block_reason) {
+            return Err(Error::Inference(format!(
+                "Gemini blocked the prompt (reason_len={})",
+                reason.chars().count()
+            )));
+        }
+        let reported = result
+            .usage_metadata
+            .map(|u| TokenUsage::new(u.prompt_token_count, u.candidates_token_count));
+        let candidate = result
+            .candidates
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::Inference("Gemini returned no candidates".to_string()))?;
+        if candidate.finish_reason.as_deref() == Some("MAX_TOKENS") {
+            warn!(
+                max_tokens = self.config.max_tokens,
+                "Gemini response truncated at max_tokens"
+            );
+        }
+        let text: String = candidate
+            .content
+            .map(|content| content.parts)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|part| part.text)
+            .collect();
+        record_generation(&self.config.model, &sent, &text, reported);
+        debug!(response_len = text.len(), "Gemini generation complete");
+        Ok(text)
+    }
+}
+
+#[async_trait]
+impl GenerationBackend for GeminiBackend {
+    async fn health_check(&self) -> Result<bool> {
+        match self
+            .client
+            .get(self.url("/models"))
+            .header("x-goog-api-key", &self.config.api_key)
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+        {
+            Ok(resp) => Ok(resp.status().is_success()),
+            Err(e) => {
+                warn!(error_len = e.to_string().len(), "Gemini health check error");
+                Ok(false)
+            }
+        }
+    }
+
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        self.generate_with_system("", prompt).await
+    }
+
+    #[instrument(skip_all, fields(subsystem = "inference", component = "gemini", op = "generate", model_len = self.config.model.len(), prompt_len = prompt.len()))]
+    async fn generate_with_system(&self, system: &str, prompt: &str) -> Result<String> {
+        self.generate_content(system, vec![text_part(prompt)], false)
+            .await
+    }
+
+    async fn generate_json(&self, prompt: &str) -> Result<String> {
+        self.generate_json_with_system("", prompt).await
+    }
+
+    #[instrument(skip_all, fields(subsystem = "inference", component = "gemini", op = "generate_json", model_len = self.config.model.len(), prompt_len = prompt.len()))]
+    async fn generate_json_with_system(&self, system: &str, prompt: &str) -> Result<String> {
+        self.generate_content(system, vec![text_part(prompt)], true)
+            .await
+    }
+
+    fn model_name(&self) -> &str {
+        &self.config.model
+    }
+}
+
+#[async_trait]
+impl VisionBackend for GeminiBackend {
+    #[instrument(skip_all, fields(subsystem = "inference", component = "gemini", op = "describe_image", model_len = self.config.model.len(), image_len = image_data.len()))]
+    async fn describe_image(
+        &self,
+        image_data: &[u8],
+        mime_type: &str,
+        prompt: Option<&str>,
+    ) -> Result<String> {
+        use base64::Engine;
+
+        if !mime_type.starts_with("image/") {
+            return Err(Error::InvalidInput(format!(
+                "Gemini vision expects an image, got {}",
+                mime_type
+            )));
+        }
+        let parts = vec![
+            Part {
+                text: None,
+                inline_data: Some(InlineData {
+                    mime_type: mime_type.to_string(),
+                    data: base64::engine::general_purpose::STANDARD.encode(image_data),
+                }),
+            },
+            text_part(prompt.unwrap_or(DEFAULT_VISION_PROMPT)),
+        ];
+        self.generate_content("", parts, false).await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        GenerationBackend::health_check(self).await
+    }
+
+    fn model_name(&self) -> &str {
+        &self.config.model
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn backend(base_url: String) -> GeminiBackend {
+        GeminiBackend::new(GeminiConfig {
+            base_url,
+            ..GeminiConfig::new("test-key")
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn model_names_cannot_escape_the_request_path() {
+        assert!(validate_model("gemini-2.5-flash").is_ok());
+        assert!(validate_model("models/gemini-2.0-flash-001").is_ok());
+        assert!(validate_model("../files").is_err());
+        assert!(validate_model("gemini?key=x").is_err());
+        assert!(validate_model("").is_err());
+    }
+
+    #[test]
+    fn debug_omits_api_key() {
+        let config = GeminiConfig::new("AIza-secret");
+        assert!(!format!("{config:?}").contains("AIza-secret"));
+    }
+
+    #[tokio::test]
+    async fn json_generation_requests_json_output() {
+        let mut server = mockito::Server::new_async().await;
+        let request = server
+            .mock("POST", "/models/gemini-2.5-flash:generateContent")
+            .match_header("x-goog-api-key", "test-key")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "systemInstruction": {"parts": [{"text": "Be brief."}]},
+                "generationConfig": {"responseMimeType": "application/json"}
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"candidates": [{"content": {"role": "model",
+                    "parts": [{"text": "{\"ok\":"}, {"text": " true}"}]},
+                    "finishReason": "STOP"}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let reply = backend(server.url())
+            .generate_json_with_system("Be brief.", "Status?")
+            .await
+            .unwrap();
+        assert_eq!(reply, r#"{"ok": true}"#);
+        request.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn images_are_sent_inline_and_blocked_prompts_fail() {
+        let mut server = mockito::Server::new_async().await;
+        let described = server
+            .mock("POST", "/models/gemini-2.5-flash:generateContent")
+            .match_body(Matcher::Regex(
+                r#""inlineData":\{"mimeType":"image/png","data":"AQID"\}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"candidates": [{"content": {"parts": [{"text": "A chart"}]}}]}"#)
+            .create_async()
+            .await;
+
+        let backend = backend(server.url());
+        let reply = backend
+            .describe_image(&[1, 2, 3], "image/png", Some("What is this?"))
+            .await
+            .unwrap();
+        assert_eq!(reply, "A chart");
+        described.assert_async().await;
+
+        server
+            .mock("POST", "/models/gemini-2.5-flash:generateContent")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"promptFeedback": {"blockReason": "SAFETY"}}"#)
+            .create_async()
+            .await;
+        assert!(backend.generate("blocked").await.is_err());
+    }
+}
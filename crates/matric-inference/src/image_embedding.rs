@@ -0,0 +1,210 @@
+//! Image embedding backend traits and implementations for image search.
+//!
+//! A CLIP-style model places images and text in one vector space, so the
+//! same backend embeds image attachments at extraction time and both image
+//! and text queries at search time.
+
+use async_trait::async_trait;
+use matric_core::{Result, Vector};
+use serde::{Deserialize, Serialize};
+
+/// Backend embedding images and text into a shared vector space.
+#[async_trait]
+pub trait ImageEmbeddingBackend: Send + Sync {
+    /// Embed an image.
+    async fn embed_image(&self, image_data: &[u8], mime_type: &str) -> Result<Vector>;
+
+    /// Embed a text query for text-to-image search.
+    async fn embed_text(&self, text: &str) -> Result<Vector>;
+
+    /// Check if the image embedding backend is available.
+    async fn health_check(&self) -> Result<bool>;
+
+    /// Get the model name being used.
+    fn model_name(&self) -> &str;
+}
+
+/// CLIP backend for an OpenAI-compatible `/embeddings` service that accepts
+/// a `modality` field, such as Infinity.
+///
+/// Images are sent as base64 data URIs. Every returned vector must have
+/// [`CLIP_DIMENSION`](matric_core::defaults::CLIP_DIMENSION) entries.
+pub struct ClipBackend {
+    base_url: String,
+    model: String,
+    client: reqwest::Client,
+    timeout_secs: u64,
+}
+
+impl ClipBackend {
+    pub fn new(base_url: String, model: String) -> Self {
+        Self {
+            base_url,
+            model,
+            client: reqwest::Client::new(),
+            timeout_secs: 60,
+        }
+    }
+
+    /// Create from environment variables.
+    /// Returns None if CLIP_BASE_URL is not set or empty.
+    pub fn from_env() -> Option<Self> {
+        let base_url = std::env::var(matric_core::defaults::ENV_CLIP_BASE_URL).ok()?;
+        if base_url.is_empty() {
+            return None;
+        }
+        let model = std::env::var(matric_core::defaults::ENV_CLIP_MODEL)
+            .unwrap_or_else(|_| matric_core::defaults::DEFAULT_CLIP_MODEL.to_string());
+        Some(Self::new(base_url, model))
+    }
+
+    async fn embed(&self, input: String, modality: &'static str) -> Result<Vector> {
+        let request = ClipEmbeddingRequest {
+            model: self.model.clone(),
+            input: vec![input],
+            modality,
+        };
+
+        let url = format!("{}/embeddings", self.base_url.trim_end_matches('/'));
+        let response = self
+            .client
+            .post(&url)
+            .json(&request)
+            .timeout(std::time::Duration::from_secs(self.timeout_secs))
+            .send()
+            .await
+            .map_err(|e| {
+                matric_core::Error::Internal(format!("Image embedding request failed: {}", e))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(matric_core::Error::Internal(
+                crate::diagnostics::backend_status_error("Image embedding", status, &body),
+            ));
+        }
+
+        let result: ClipEmbeddingResponse = response.json().await.map_err(|e| {
+            matric_core::Error::Internal(format!("Failed to parse image embedding response: {}", e))
+        })?;
+
+        clip_vector(result)
+    }
+}
+
+#[derive(Serialize)]
+struct ClipEmbeddingRequest {
+    model: String,
+    input: Vec<String>,
+    modality: &'static str,
+}
+
+#[derive(Deserialize)]
+struct ClipEmbeddingResponse {
+    data: Vec<ClipEmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct ClipEmbeddingData {
+    embedding: Vec<f32>,
+}
+
+/// The single vector in a response, checked against the stored dimension.
+fn clip_vector(response: ClipEmbeddingResponse) -> Result<Vector> {
+    let embedding = response
+        .data
+        .into_iter()
+        .next()
+        .map(|data| data.embedding)
+        .ok_or_else(|| {
+            matric_core::Error::Internal("Image embedding response has no vectors".to_string())
+        })?;
+    if embedding.len() != matric_core::defaults::CLIP_DIMENSION {
+        return Err(matric_core::Error::Internal(format!(
+            "Image embedding has {} dimensions; expected {}",
+            embedding.len(),
+            matric_core::defaults::CLIP_DIMENSION
+        )));
+    }
+    Ok(Vector::from(embedding))
+}
+
+#[async_trait]
+impl ImageEmbeddingBackend for ClipBackend {
+    async fn embed_image(&self, image_data: &[u8], mime_type: &str) -> Result<Vector> {
+        use base64::Engine;
+        let image_b64 = base64::engine::general_purpose::STANDARD.encode(image_data);
+        self.embed(format!("data:{};base64,{}", mime_type, image_b64), "image")
+            .await
+    }
+
+    async fn embed_text(&self, text: &str) -> Result<Vector> {
+        self.embed(text.to_string(), "text").await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        let url = format!("{}/models", self.base_url.trim_end_matches('/'));
+        match self
+            .client
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await
+        {
+            Ok(resp) => Ok(resp.status().is_success()),
+            Err(_) => Ok(false),
+        }
+    }
+
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clip_backend_new() {
+        let backend = ClipBackend::new(
+            "http://localhost:7997".to_string(),
+            "openai/clip-vit-base-patch32".to_string(),
+        );
+        assert_eq!(backend.base_url, "http://localhost:7997");
+        assert_eq!(backend.timeout_secs, 60);
+        assert_eq!(backend.model_name(), "openai/clip-vit-base-patch32");
+    }
+
+    #[test]
+    fn test_clip_embedding_request_serialization() {
+        let request = ClipEmbeddingRequest {
+            model: "clip".to_string(),
+            input: vec!["data:image/png;base64,AAAA".to_string()],
+            modality: "image",
+        };
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["model"], "clip");
+        assert_eq!(json["input"][0], "data:image/png;base64,AAAA");
+        assert_eq!(json["modality"], "image");
+    }
+
+    #[test]
+    fn clip_vector_checks_dimension() {
+        let dimension = matric_core::defaults::CLIP_DIMENSION;
+        let response = |len: usize| ClipEmbeddingResponse {
+            data: vec![ClipEmbeddingData {
+                embedding: vec![0.1; len],
+            }],
+        };
+
+        assert_eq!(
+            clip_vector(response(dimension)).unwrap().as_slice().len(),
+            dimension
+        );
+        assert!(clip_vector(response(768)).is_err());
+        assert!(clip_vector(ClipEmbeddingResponse { data: vec![] }).is_err());
+    }
+}
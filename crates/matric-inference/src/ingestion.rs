@@ -0,0 +1,182 @@
+//! LLM content classification for ingestion.
+//!
+//! [`ContentClassificationPolicy`] is the model-backed
+//! [`IngestionPolicy`](matric_core::IngestionPolicy): it asks the model to
+//! classify the text of a note or text attachment and maps the category to a
+//! decision. Items without text are allowed; the size and MIME policies
+//! cover those.
+
+use std::sync::{Arc, LazyLock};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use matric_core::{
+    fill_prompt_template, GenerationBackend, IngestionDecision, IngestionItem, IngestionPolicy,
+    Result,
+};
+
+use crate::constrained::{generate_constrained, ConstrainedConfig, OutputSchema};
+
+/// Longest text sent to the model; the rest of the item is not classified.
+const MAX_CLASSIFIED_CHARS: usize = 8_000;
+
+/// Categories the model may answer with. `none` is allowed.
+const CATEGORIES: &[&str] = &[
+    "none",
+    "spam",
+    "malware",
+    "sexual_minors",
+    "sexual",
+    "violence",
+    "hate",
+    "self_harm",
+    "illegal",
+];
+
+/// Categories that are rejected outright; any other category except `none`
+/// is quarantined for review.
+const DENIED_CATEGORIES: &[&str] = &["malware", "sexual_minors"];
+
+const CLASSIFICATION_PROMPT: &str = r#"You moderate content submitted to a personal knowledge base. Classify the following content into exactly one category:
+
+- none: ordinary content, including notes that discuss sensitive topics factually
+- spam: bulk advertising, scams, or SEO filler
+- malware: code or instructions intended to compromise systems, or embedded payloads
+- sexual_minors: sexual content involving minors
+- sexual: explicit sexual content
+- violence: threats or incitement to violence
+- hate: attacks on people for a protected characteristic
+- self_harm: encouragement of self-harm
+- illegal: instructions for serious crimes
+
+Reply with JSON only: {"category": "<category>"}
+
+Content:
+{{content}}"#;
+
+static CLASSIFICATION_SCHEMA: LazyLock<OutputSchema> = LazyLock::new(|| {
+    OutputSchema::new(
+        "content_classification",
+        serde_json::json!({
+            "type": "object",
+            "required": ["category"],
+            "properties": {
+                "category": { "enum": CATEGORIES }
+            }
+        }),
+    )
+    .expect("content classification schema must compile")
+});
+
+#[derive(Deserialize)]
+struct ClassificationReply {
+    category: String,
+}
+
+/// Ingestion policy that classifies text with a generation model.
+pub struct ContentClassificationPolicy {
+    backend: Arc<dyn GenerationBackend>,
+    config: ConstrainedConfig,
+}
+
+impl ContentClassificationPolicy {
+    pub fn new(backend: Arc<dyn GenerationBackend>) -> Self {
+        Self {
+            backend,
+            config: ConstrainedConfig::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl IngestionPolicy for ContentClassificationPolicy {
+    fn policy_id(&self) -> &'static str {
+        "llm_classification"
+    }
+
+    async fn evaluate(&self, item: &IngestionItem<'_>) -> Result<IngestionDecision> {
+        let Some(text) = item.text.filter(|text| !text.trim().is_empty()) else {
+            return Ok(IngestionDecision::allow());
+        };
+        let excerpt: String = text.chars().take(MAX_CLASSIFIED_CHARS).collect();
+        let prompt = fill_prompt_template(CLASSIFICATION_PROMPT, &[("content", &excerpt)]);
+        let reply = generate_constrained::<ClassificationReply>(
+            self.backend.as_ref(),
+            &prompt,
+            &CLASSIFICATION_SCHEMA,
+            &self.config,
+        )
+        .await?
+        .value;
+
+        let category = reply.category.as_str();
+        Ok(if category == "none" {
+            IngestionDecision::allow()
+        } else if DENIED_CATEGORIES.contains(&category) {
+            IngestionDecision::deny(self.policy_id(), format!("llm_{category}"))
+        } else {
+            IngestionDecision::quarantine(self.policy_id(), format!("llm_{category}"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matric_core::IngestionAction;
+
+    /// Answers every prompt with the same reply.
+    struct FixedBackend(&'static str);
+
+    #[async_trait]
+    impl GenerationBackend for FixedBackend {
+        async fn generate(&self, _prompt: &str) -> Result<String> {
+            Ok(self.0.to_string())
+        }
+
+        async fn generate_with_system(&self, _system: &str, prompt: &str) -> Result<String> {
+            self.generate(prompt).await
+        }
+
+        fn model_name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    async fn classify(reply: &'static str, item: IngestionItem<'_>) -> IngestionDecision {
+        ContentClassificationPolicy::new(Arc::new(FixedBackend(reply)))
+            .evaluate(&item)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn categories_map_to_decisions() {
+        let note = IngestionItem::note("Buy now!!!");
+        assert!(classify(r#"{"category": "none"}"#, note).await.is_allowed());
+        assert_eq!(
+            classify(r#"{"category": "spam"}"#, note).await,
+            IngestionDecision::quarantine("llm_classification", "llm_spam")
+        );
+        assert_eq!(
+            classify(r#"{"category": "malware"}"#, note).await.action,
+            IngestionAction::Deny
+        );
+    }
+
+    #[tokio::test]
+    async fn items_without_text_are_not_classified() {
+        let image = IngestionItem::attachment("a.png", "image/png", b"\x89PNG");
+        assert!(classify("not json", image).await.is_allowed());
+    }
+
+    #[tokio::test]
+    async fn unknown_category_is_an_error() {
+        let policy = ContentClassificationPolicy {
+            backend: Arc::new(FixedBackend(r#"{"category": "rude"}"#)),
+            config: ConstrainedConfig { max_repairs: 0 },
+        };
+        assert!(policy.evaluate(&IngestionItem::note("text")).await.is_err());
+    }
+}
@@ -403,6 +403,7 @@ impl ContextOptimizer {
 }
 
 /// Batch embedding service for efficient embedding generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct BatchEmbeddingConfig {
     /// Maximum batch size.
     pub max_batch_size: usize,
@@ -420,6 +421,21 @@ impl Default for BatchEmbeddingConfig {
 }
 
 impl BatchEmbeddingConfig {
+    /// Defaults overridden by `EMBED_BATCH_MAX_SIZE` (1-512, `1` disables
+    /// batching) and `EMBED_BATCH_FLUSH_MS` (1-5000).
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            max_batch_size: read("EMBED_BATCH_MAX_SIZE")
+                .map(|v| v.clamp(1, 512) as usize)
+                .unwrap_or(defaults.max_batch_size),
+            flush_timeout_ms: read("EMBED_BATCH_FLUSH_MS")
+                .map(|v| v.clamp(1, 5_000))
+                .unwrap_or(defaults.flush_timeout_ms),
+        }
+    }
+
     /// Create config optimized for hardware tier.
     pub fn for_tier(tier: HardwareTier) -> Self {
         match tier {
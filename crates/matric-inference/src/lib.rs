@@ -6,6 +6,7 @@
 //! - Pluggable inference backend trait
 //! - Ollama implementation (default)
 //! - OpenAI-compatible implementation (optional, feature `openai`)
+//! - Anthropic and Gemini implementations (optional, features `anthropic`, `gemini`)
 //! - Model-specific configuration for thinking models
 //! - Model performance profiles and registry
 //! - Model capability flags for knowledge management tasks
@@ -13,11 +14,19 @@
 //! - Model restriction and validation
 //! - Vision backend for image description
 //! - Transcription backend for audio-to-text
+//! - Schema-validated JSON generation with bounded repair
+//! - Per-call token accounting and model pricing
+//! - Hierarchical map-reduce summarization
 //!
 //! # Feature Flags
 //!
 //! - `ollama` (default): Enable Ollama backend
 //! - `openai`: Enable OpenAI-compatible backend
+//! - `anthropic`: Enable the native Anthropic Messages API backend
+//! - `gemini`: Enable the native Google Gemini API backend
+//! - `llama-cpp`: Enable the in-process llama.cpp backend for GGUF models
+//! - `candle`: Enable pure-Rust local embeddings with candle
+//! - `candle-onnx`: Also load ONNX weights in the candle backend
 //!
 //! # Example
 //!
@@ -33,27 +42,41 @@
 //! }
 //! ```
 
+pub mod batching;
+pub mod candle;
 pub mod capabilities;
 pub mod circuit_breaker;
+pub mod concept_split;
 pub mod config;
+pub mod constrained;
 mod diagnostics;
 pub mod diarization;
 pub mod discovery;
 pub mod embedding_models;
+pub mod embedding_router;
+pub mod entities;
 pub mod eval;
 pub mod few_shot;
 pub mod gliner;
 pub mod hardware;
+pub mod image_embedding;
+pub mod ingestion;
 pub mod latency;
 pub mod link_types;
+pub mod llama_cpp;
 pub mod model_config;
+pub mod pii;
 pub mod profiles;
 pub mod provider;
 pub mod provider_profiles;
+pub mod qa_pairs;
 pub mod refinement;
 pub mod retry;
 pub mod selector;
+pub mod summarize;
+pub mod tasks;
 pub mod thinking;
+pub mod token_accounting;
 pub mod transcription;
 pub mod vision;
 
@@ -63,6 +86,12 @@ pub mod ollama;
 #[cfg(feature = "openai")]
 pub mod openai;
 
+#[cfg(feature = "anthropic")]
+pub mod anthropic;
+
+#[cfg(feature = "gemini")]
+pub mod gemini;
+
 // Mock inference backend for testing
 #[cfg(test)]
 pub mod mock;
@@ -76,10 +105,31 @@ pub use ollama::OllamaBackend;
 #[cfg(feature = "openai")]
 pub use openai::{OpenAIBackend, OpenAIConfig};
 
+#[cfg(feature = "anthropic")]
+pub use anthropic::{AnthropicBackend, AnthropicConfig};
+
+#[cfg(feature = "gemini")]
+pub use gemini::{GeminiBackend, GeminiConfig};
+
+#[cfg(feature = "llama-cpp")]
+pub use llama_cpp::LlamaCppBackend;
+
+#[cfg(feature = "candle")]
+pub use candle::CandleEmbeddingBackend;
+
+pub use batching::EmbeddingBatcher;
+pub use candle::CandleEmbeddingConfig;
 pub use capabilities::{
     known_model_capabilities, Capability, CapabilityRating, ModelCapabilities, QualityTier,
 };
 pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+pub use concept_split::{
+    concept_split_note_list, concept_split_schema, suggest_concept_split, ConceptSplitGroup,
+};
+pub use constrained::{
+    concept_tags_schema, extract_json, generate_constrained, ConstrainedConfig, ConstrainedOutput,
+    OutputSchema,
+};
 pub use diarization::{
     align_speakers, DiarizationBackend, DiarizationResult, DiarizationSegment, PyAnnoteBackend,
 };
@@ -87,6 +137,10 @@ pub use discovery::{
     ConfigRecommendation, DiscoveredModel, DiscoveryError, DiscoveryResult, ModelDiscovery,
 };
 pub use embedding_models::{EmbeddingModelProfile, EmbeddingModelRegistry, EmbeddingSymmetry};
+pub use embedding_router::{
+    EmbeddingEndpoint, EmbeddingEndpointPool, EmbeddingRouterConfig, RoutedEmbeddingBackend,
+};
+pub use entities::{entity_extraction_schema, extract_entities, merge_entities};
 pub use eval::{
     content_revision_suite, cosine_similarity, evaluate_semantic, evaluate_title,
     semantic_similarity_suite, title_generation_suite, EvalReport, EvalResult, EvalSummary,
@@ -101,16 +155,21 @@ pub use hardware::{
     cloud_comparisons, tier_model_recommendations, tier_quality_expectations, CloudComparison,
     HardwareTier, ModelRecommendation, OllamaSettings, SystemCapabilities, TierQualityExpectations,
 };
+pub use image_embedding::{ClipBackend, ImageEmbeddingBackend};
+pub use ingestion::ContentClassificationPolicy;
 pub use latency::{
     BatchEmbeddingConfig, ChunkingStrategy, ContextConfig, ContextOptimizer, LatencyOptimization,
     LatencyStats, LatencyTracker,
 };
 pub use link_types::{
-    link_classification_prompt, parse_link_type, LinkClassification, SemanticLinkType,
+    classify_link, link_classification_prompt, link_classification_schema, parse_link_type,
+    LinkClassification, SemanticLinkType,
 };
+pub use llama_cpp::LlamaCppConfig;
 pub use model_config::{
     is_model_restricted, validate_model, ModelRestriction, ModelValidationError, RestrictionType,
 };
+pub use pii::{detect_pii, pii_detection_schema};
 pub use profiles::{ModelProfile, ModelRegistry, TaskRequirements, ThinkingType};
 pub use provider::{
     ParsedSlug, ProviderCapability, ProviderConfig, ProviderHealth, ProviderRegistry,
@@ -123,6 +182,7 @@ pub use provider_profiles::{
     ProfileHeaderSource, ProviderProfile, DEFAULT_PROFILE_ID, LLAMACPP_PROFILE, OLLAMA_PROFILE,
     OPENAI_PROFILE, OPENROUTER_PROFILE, PROVIDER_PROFILES,
 };
+pub use qa_pairs::{generate_qa_pairs, qa_pairs_schema};
 pub use refinement::{
     parse_quality_score, parse_react_response, react_revision_prompt, refine_with_critique_prompt,
     reflexion_prompt, self_critique_prompt, Episode, EpisodeOutcome, ReActStep, ReActTrace,
@@ -130,7 +190,12 @@ pub use refinement::{
 };
 pub use retry::{with_retry, RetryConfig};
 pub use selector::{KmOperation, ModelSelection, ModelSelector, RecommendedConfig};
+pub use summarize::{map_reduce_summarize, SummarizeConfig, SummaryOutput};
+pub use tasks::{extract_action_items, task_extraction_schema};
 pub use thinking::{detect_thinking_type, parse_thinking_response, ThinkingResponse};
+pub use token_accounting::{
+    record_generation, ModelPricing, ModelUsage, PricingTable, TokenAccount, TokenUsage,
+};
 pub use transcription::{
     TranscriptionBackend, TranscriptionResult, TranscriptionSegment, WhisperBackend, WordTimestamp,
 };
@@ -7,6 +7,11 @@
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::sync::LazyLock;
+
+use matric_core::{GenerationBackend, Result};
+
+use crate::constrained::{generate_constrained, ConstrainedConfig, OutputSchema};
 
 /// Semantic link types representing relationship classifications between notes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -26,6 +31,16 @@ pub enum SemanticLinkType {
 }
 
 impl SemanticLinkType {
+    /// Every link type.
+    pub const ALL: [SemanticLinkType; 6] = [
+        SemanticLinkType::Supports,
+        SemanticLinkType::Contradicts,
+        SemanticLinkType::Extends,
+        SemanticLinkType::Implements,
+        SemanticLinkType::References,
+        SemanticLinkType::Related,
+    ];
+
     /// Returns string representation of link type.
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -79,6 +94,8 @@ impl LinkClassification {
 
 /// Generates an LLM prompt for classifying the relationship between two notes.
 ///
+/// Renders the built-in `link_classification` prompt template.
+///
 /// # Arguments
 /// * `source_title` - Title of the source note
 /// * `source_excerpt` - Excerpt/snippet from source note
@@ -92,37 +109,23 @@ pub fn link_classification_prompt(
     target_excerpt: &str,
     similarity_score: f32,
 ) -> String {
-    format!(
-        r#"Classify the semantic relationship between these two notes.
-
-Source Note: "{}"
-Excerpt: {}
-
-Target Note: "{}"
-Excerpt: {}
-
-Similarity Score: {:.2}
-
-Classify the relationship type:
-- SUPPORTS: Target provides evidence or supports source claims
-- CONTRADICTS: Target refutes or contradicts source
-- EXTENDS: Target builds upon or extends source concepts
-- IMPLEMENTS: Target applies or implements source theory/ideas
-- REFERENCES: Target is cited or referenced by source
-- RELATED: Generic topical relationship (default)
-
-Respond in the format:
-CLASSIFICATION: <type>
-CONFIDENCE: <0.0-1.0>
-REASONING: <brief explanation>
-"#,
-        source_title, source_excerpt, target_title, target_excerpt, similarity_score
+    let similarity = format!("{:.2}", similarity_score);
+    matric_core::fill_prompt_template(
+        matric_core::PromptKey::LinkClassification.builtin(),
+        &[
+            ("source_title", source_title),
+            ("source_excerpt", source_excerpt),
+            ("target_title", target_title),
+            ("target_excerpt", target_excerpt),
+            ("similarity", &similarity),
+        ],
     )
 }
 
-/// Parses LLM response to extract semantic link type.
+/// Parses a free-text LLM response to extract semantic link type.
 ///
 /// Looks for "CLASSIFICATION: <type>" in response, defaulting to Related if not found.
+/// Prefer [`classify_link`], which rejects malformed replies instead of guessing.
 pub fn parse_link_type(response: &str) -> SemanticLinkType {
     let response_lower = response.to_lowercase();
 
@@ -162,6 +165,71 @@ pub fn parse_link_type(response: &str) -> SemanticLinkType {
     }
 }
 
+/// Longest reasoning accepted from a link classification reply.
+const MAX_LINK_REASONING_CHARS: u64 = 2_000;
+
+static LINK_CLASSIFICATION_SCHEMA: LazyLock<OutputSchema> = LazyLock::new(|| {
+    let link_types: Vec<&str> = SemanticLinkType::ALL
+        .iter()
+        .map(SemanticLinkType::as_str)
+        .collect();
+    OutputSchema::new(
+        "link_classification",
+        serde_json::json!({
+            "type": "object",
+            "required": ["link_type", "confidence"],
+            "properties": {
+                "link_type": { "enum": link_types },
+                "confidence": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+                "reasoning": { "type": "string", "maxLength": MAX_LINK_REASONING_CHARS }
+            }
+        }),
+    )
+    .expect("link classification schema must compile")
+});
+
+/// Schema for link classification replies produced from
+/// [`link_classification_prompt`].
+pub fn link_classification_schema() -> &'static OutputSchema {
+    &LINK_CLASSIFICATION_SCHEMA
+}
+
+#[derive(Deserialize)]
+struct LinkClassificationReply {
+    link_type: String,
+    confidence: f32,
+    #[serde(default)]
+    reasoning: String,
+}
+
+/// Classify a note pair by generating against [`link_classification_schema`].
+///
+/// `prompt` is usually built by [`link_classification_prompt`]. Replies that
+/// still fail the schema after the configured repairs are an error.
+pub async fn classify_link(
+    backend: &dyn GenerationBackend,
+    prompt: &str,
+    config: &ConstrainedConfig,
+) -> Result<LinkClassification> {
+    let output = generate_constrained::<LinkClassificationReply>(
+        backend,
+        prompt,
+        link_classification_schema(),
+        config,
+    )
+    .await?;
+    let reply = output.value;
+    let link_type = SemanticLinkType::ALL
+        .into_iter()
+        .find(|t| t.as_str() == reply.link_type)
+        .unwrap_or(SemanticLinkType::Related);
+    Ok(LinkClassification::new(
+        link_type,
+        reply.confidence,
+        reply.reasoning,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -308,4 +376,34 @@ mod tests {
         assert!(!debug.contains("sk-private-token"));
         assert!(!debug.contains("Mentions account"));
     }
+
+    #[test]
+    fn link_classification_schema_requires_known_type_and_bounded_confidence() {
+        let schema = link_classification_schema();
+        assert!(schema
+            .problems(&serde_json::json!({
+                "link_type": "extends",
+                "confidence": 0.7,
+                "reasoning": "Builds on it"
+            }))
+            .is_empty());
+        assert!(!schema
+            .problems(&serde_json::json!({"link_type": "EXTENDS", "confidence": 0.7}))
+            .is_empty());
+        assert!(!schema
+            .problems(&serde_json::json!({"link_type": "extends", "confidence": 7}))
+            .is_empty());
+        assert!(!schema
+            .problems(&serde_json::json!({"confidence": 0.7}))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_every_type_is_a_builtin_link_type() {
+        for link_type in SemanticLinkType::ALL {
+            assert!(matric_core::typed_link::is_builtin_link_type(
+                link_type.as_str()
+            ));
+        }
+    }
 }
@@ -0,0 +1,351 @@
+//! llama.cpp bindings behind [`LlamaCppBackend`].
+//!
+//! Models are loaded on first use and kept for the life of the process, keyed
+//! by path and GPU offload, so every backend the registry resolves shares
+//! them. Each call creates its own llama.cpp context on a blocking thread;
+//! a process-wide semaphore bounds how many run at once.
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use llama_cpp_2::context::params::LlamaContextParams;
+use llama_cpp_2::llama_backend::LlamaBackend;
+use llama_cpp_2::llama_batch::LlamaBatch;
+use llama_cpp_2::model::params::LlamaModelParams;
+use llama_cpp_2::model::{AddBos, LlamaChatMessage, LlamaModel, Special};
+use llama_cpp_2::sampling::LlamaSampler;
+use tokio::sync::Semaphore;
+use tracing::{debug, info};
+
+use matric_core::{EmbeddingBackend, Error, GenerationBackend, Result, Vector};
+
+use super::{normalize, plain_prompt, resolve_model_name, LlamaCppConfig};
+use crate::token_accounting::record_generation;
+
+static LLAMA_BACKEND: OnceLock<std::result::Result<LlamaBackend, String>> = OnceLock::new();
+static MODELS: OnceLock<Mutex<HashMap<(PathBuf, u32), Arc<LlamaModel>>>> = OnceLock::new();
+static PERMITS: OnceLock<Arc<Semaphore>> = OnceLock::new();
+
+fn llama_backend() -> Result<&'static LlamaBackend> {
+    LLAMA_BACKEND
+        .get_or_init(|| LlamaBackend::init().map_err(|error| error.to_string()))
+        .as_ref()
+        .map_err(|error| Error::Inference(format!("llama.cpp initialization failed: {error}")))
+}
+
+fn load_model(path: &Path, gpu_layers: u32) -> Result<Arc<LlamaModel>> {
+    let backend = llama_backend()?;
+    // Held while loading so concurrent first calls load the file once.
+    let mut models = MODELS
+        .get_or_init(Default::default)
+        .lock()
+        .expect("llama.cpp model cache lock poisoned");
+    let key = (path.to_path_buf(), gpu_layers);
+    if let Some(model) = models.get(&key) {
+        return Ok(model.clone());
+    }
+
+    let start = Instant::now();
+    let params = LlamaModelParams::default().with_n_gpu_layers(gpu_layers);
+    let model = LlamaModel::load_from_file(backend, path, &params)
+        .map_err(|error| Error::Inference(format!("Failed to load GGUF model: {error}")))?;
+    info!(
+        model_len = super::model_name(path).chars().count(),
+        gpu_layers,
+        duration_ms = start.elapsed().as_millis() as u64,
+        "Loaded GGUF model"
+    );
+    let model = Arc::new(model);
+    models.insert(key, model.clone());
+    Ok(model)
+}
+
+fn context_params(config: &LlamaCppConfig, n_ctx: u32) -> LlamaContextParams {
+    let mut params = LlamaContextParams::default()
+        .with_n_ctx(NonZeroU32::new(n_ctx))
+        .with_n_batch(n_ctx);
+    if let Some(threads) = config.threads {
+        let threads = i32::try_from(threads).unwrap_or(i32::MAX);
+        params = params.with_n_threads(threads).with_n_threads_batch(threads);
+    }
+    params
+}
+
+fn embed_blocking(
+    config: &LlamaCppConfig,
+    path: &Path,
+    dimension: usize,
+    texts: &[String],
+) -> Result<Vec<Vector>> {
+    let model = load_model(path, config.gpu_layers)?;
+    let model_dimension = usize::try_from(model.n_embd()).unwrap_or_default();
+    if model_dimension != dimension {
+        return Err(Error::Embedding(format!(
+            "GGUF embedding model has dimension {model_dimension}, expected {dimension}"
+        )));
+    }
+
+    let n_ctx = config.effective_context(model.n_ctx_train());
+    let params = context_params(config, n_ctx)
+        .with_n_ubatch(n_ctx)
+        .with_embeddings(true);
+    let mut ctx = model
+        .new_context(llama_backend()?, params)
+        .map_err(|error| Error::Embedding(format!("llama.cpp context failed: {error}")))?;
+
+    let mut vectors = Vec::with_capacity(texts.len());
+    for text in texts {
+        let mut tokens = model
+            .str_to_token(text, AddBos::Always)
+            .map_err(|error| Error::Embedding(format!("GGUF tokenization failed: {error}")))?;
+        tokens.truncate(n_ctx as usize);
+        let mut batch = LlamaBatch::new(tokens.len().max(1), 1);
+        batch
+            .add_sequence(&tokens, 0, false)
+            .map_err(|error| Error::Embedding(format!("llama.cpp batch failed: {error}")))?;
+        ctx.clear_kv_cache();
+        ctx.decode(&mut batch)
+            .map_err(|error| Error::Embedding(format!("llama.cpp decode failed: {error}")))?;
+        let embedding = ctx
+            .embeddings_seq_ith(0)
+            .map_err(|error| Error::Embedding(format!("llama.cpp embedding failed: {error}")))?;
+        vectors.push(Vector::from(normalize(embedding)));
+    }
+    Ok(vectors)
+}
+
+fn chat_prompt(model: &LlamaModel, system: Option<&str>, prompt: &str) -> String {
+    let mut messages = Vec::with_capacity(2);
+    if let Some(system) = system.filter(|system| !system.trim().is_empty()) {
+        if let Ok(message) = LlamaChatMessage::new("system".to_string(), system.to_string()) {
+            messages.push(message);
+        }
+    }
+    let Ok(message) = LlamaChatMessage::new("user".to_string(), prompt.to_string()) else {
+        return plain_prompt(system, prompt);
+    };
+    messages.push(message);
+    model
+        .chat_template(None)
+        .ok()
+        .and_then(|template| model.apply_chat_template(&template, &messages, true).ok())
+        .unwrap_or_else(|| plain_prompt(system, prompt))
+}
+
+fn generate_blocking(
+    config: &LlamaCppConfig,
+    path: &Path,
+    system: Option<&str>,
+    prompt: &str,
+) -> Result<String> {
+    let model = load_model(path, config.gpu_layers)?;
+    let n_ctx = config.effective_context(model.n_ctx_train());
+    let mut ctx = model
+        .new_context(llama_backend()?, context_params(config, n_ctx))
+        .map_err(|error| Error::Inference(format!("llama.cpp context failed: {error}")))?;
+
+    let tokens = model
+        .str_to_token(&chat_prompt(&model, system, prompt), AddBos::Always)
+        .map_err(|error| Error::Inference(format!("GGUF tokenization failed: {error}")))?;
+    let n_ctx = n_ctx as usize;
+    if tokens.is_empty() || tokens.len() >= n_ctx {
+        return Err(Error::InvalidInput(format!(
+            "Prompt of {} tokens does not fit the {} token llama.cpp context",
+            tokens.len(),
+            n_ctx
+        )));
+    }
+    let max_new_tokens = (config.max_tokens as usize).min(n_ctx - tokens.len());
+
+    let mut batch = LlamaBatch::new(n_ctx, 1);
+    let last = tokens.len() - 1;
+    for (position, token) in tokens.iter().enumerate() {
+        batch
+            .add(*token, position as i32, &[0], position == last)
+            .map_err(|error| Error::Inference(format!("llama.cpp batch failed: {error}")))?;
+    }
+    ctx.decode(&mut batch)
+        .map_err(|error| Error::Inference(format!("llama.cpp decode failed: {error}")))?;
+
+    let mut sampler = if config.temperature <= 0.0 {
+        LlamaSampler::greedy()
+    } else {
+        LlamaSampler::chain_simple([
+            LlamaSampler::temp(config.temperature),
+            LlamaSampler::dist(rand_seed()),
+        ])
+    };
+
+    let mut output = Vec::new();
+    let mut position = tokens.len() as i32;
+    for _ in 0..max_new_tokens {
+        let token = sampler.sample(&ctx, batch.n_tokens() - 1);
+        sampler.accept(token);
+        if model.is_eog_token(token) {
+            break;
+        }
+        output.extend(
+            model
+                .token_to_bytes(token, Special::Tokenize)
+                .map_err(|error| {
+                    Error::Inference(format!("GGUF detokenization failed: {error}"))
+                })?,
+        );
+        batch.clear();
+        batch
+            .add(token, position, &[0], true)
+            .map_err(|error| Error::Inference(format!("llama.cpp batch failed: {error}")))?;
+        position += 1;
+        ctx.decode(&mut batch)
+            .map_err(|error| Error::Inference(format!("llama.cpp decode failed: {error}")))?;
+    }
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+fn rand_seed() -> u32 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos())
+        .unwrap_or_default()
+}
+
+/// Generation and embedding backend running GGUF models in-process.
+#[derive(Debug, Clone)]
+pub struct LlamaCppBackend {
+    config: Arc<LlamaCppConfig>,
+    gen_model: String,
+    embed_model: String,
+}
+
+impl LlamaCppBackend {
+    /// Create a backend for the configured model files.
+    pub fn new(config: LlamaCppConfig) -> Result<Self> {
+        if !config.is_configured() {
+            return Err(Error::Config(
+                "Set LLAMA_CPP_GEN_MODEL_PATH or LLAMA_CPP_EMBED_MODEL_PATH to use GGUF models"
+                    .to_string(),
+            ));
+        }
+        Ok(Self {
+            gen_model: config.gen_model_name().unwrap_or_default(),
+            embed_model: config.embed_model_name().unwrap_or_default(),
+            config: Arc::new(config),
+        })
+    }
+
+    /// Create a backend from `LLAMA_CPP_*` environment variables.
+    pub fn from_env() -> Result<Self> {
+        Self::new(LlamaCppConfig::from_env())
+    }
+
+    /// Check a generation slug model against the configured file.
+    pub fn with_gen_model(self, model: &str) -> Result<Self> {
+        resolve_model_name(self.config.gen_model_name(), model)?;
+        Ok(self)
+    }
+
+    /// Check an embedding slug model against the configured file and set
+    /// the dimension the caller expects.
+    pub fn with_embed_model(mut self, model: &str, dimension: usize) -> Result<Self> {
+        resolve_model_name(self.config.embed_model_name(), model)?;
+        Arc::make_mut(&mut self.config).embed_dimension = dimension;
+        Ok(self)
+    }
+
+    pub fn config(&self) -> &LlamaCppConfig {
+        &self.config
+    }
+
+    async fn run_blocking<T, F>(&self, work: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&LlamaCppConfig) -> Result<T> + Send + 'static,
+    {
+        let permits = PERMITS
+            .get_or_init(|| Arc::new(Semaphore::new(self.config.max_parallel.max(1))))
+            .clone();
+        let _permit = permits
+            .acquire_owned()
+            .await
+            .map_err(|_| Error::Internal("llama.cpp permits closed".to_string()))?;
+        let config = self.config.clone();
+        tokio::task::spawn_blocking(move || work(&config))
+            .await
+            .map_err(|error| Error::Internal(format!("llama.cpp task failed: {error}")))?
+    }
+
+    async fn generate_inner(&self, system: Option<&str>, prompt: &str) -> Result<String> {
+        let path =
+            self.config.gen_model_path.clone().ok_or_else(|| {
+                Error::Config("No GGUF generation model is configured".to_string())
+            })?;
+        let sent = format!("{}{prompt}", system.unwrap_or_default());
+        let system = system.map(str::to_string);
+        let prompt = prompt.to_string();
+        let start = Instant::now();
+        let output = self
+            .run_blocking(move |config| {
+                generate_blocking(config, &path, system.as_deref(), &prompt)
+            })
+            .await?;
+        debug!(
+            output_len = output.chars().count(),
+            duration_ms = start.elapsed().as_millis() as u64,
+            "GGUF generation complete"
+        );
+        record_generation(&self.gen_model, &sent, &output, None);
+        Ok(output)
+    }
+}
+
+#[async_trait]
+impl EmbeddingBackend for LlamaCppBackend {
+    async fn embed_texts(&self, texts: &[String]) -> Result<Vec<Vector>> {
+        if texts.is_empty() {
+            return Ok(vec![]);
+        }
+        let path =
+            self.config.embed_model_path.clone().ok_or_else(|| {
+                Error::Config("No GGUF embedding model is configured".to_string())
+            })?;
+        let dimension = self.config.embed_dimension;
+        let texts = texts.to_vec();
+        self.run_blocking(move |config| embed_blocking(config, &path, dimension, &texts))
+            .await
+    }
+
+    fn dimension(&self) -> usize {
+        self.config.embed_dimension
+    }
+
+    fn model_name(&self) -> &str {
+        &self.embed_model
+    }
+}
+
+#[async_trait]
+impl GenerationBackend for LlamaCppBackend {
+    async fn health_check(&self) -> Result<bool> {
+        Ok(self
+            .config
+            .gen_model_path
+            .as_deref()
+            .is_some_and(Path::is_file))
+    }
+
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        self.generate_inner(None, prompt).await
+    }
+
+    async fn generate_with_system(&self, system: &str, prompt: &str) -> Result<String> {
+        self.generate_inner(Some(system), prompt).await
+    }
+
+    fn model_name(&self) -> &str {
+        &self.gen_model
+    }
+}
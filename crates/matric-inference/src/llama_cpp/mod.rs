@@ -0,0 +1,294 @@
+//! In-process llama.cpp inference over GGUF model files.
+//!
+//! Small deployments can run generation and embeddings inside the API
+//! process instead of next to an Ollama sidecar. The backend itself needs the
+//! `llama-cpp` feature, which builds llama.cpp from source; the configuration
+//! in this module is always available so the provider registry can report a
+//! misconfiguration without the feature.
+//!
+//! The registry exposes the backend as the `gguf` provider. Slugs name the
+//! configured model file, e.g. `gguf:qwen3-8b-q4_k_m.gguf`, or `gguf:default`.
+//! Only the files configured through the environment are ever loaded; a slug
+//! cannot point the backend at another path.
+//!
+//! The context window comes from [`HardwareConfig`]: the safe context limit
+//! for `LLAMA_CPP_VRAM_GB`, capped by the model's training context.
+//!
+//! ```
+//! use matric_core::HardwareConfig;
+//! use matric_inference::llama_cpp::LlamaCppConfig;
+//!
+//! let config = LlamaCppConfig::from_hardware(&HardwareConfig::new(8));
+//! assert_eq!(config.context_size, 13_414);
+//! assert!(config.gpu_layers > 0);
+//! ```
+
+#[cfg(feature = "llama-cpp")]
+mod backend;
+
+#[cfg(feature = "llama-cpp")]
+pub use backend::LlamaCppBackend;
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use matric_core::{Error, HardwareConfig, Result};
+
+/// Provider ID of the in-process backend in the provider registry.
+pub const PROVIDER_ID: &str = "gguf";
+
+/// Slug model name that selects the configured model file.
+pub const DEFAULT_MODEL_ALIAS: &str = "default";
+
+/// GPU layers offloaded when any VRAM is configured: more than any model has,
+/// so llama.cpp offloads every layer.
+pub const ALL_GPU_LAYERS: u32 = 999;
+
+const MIN_CONTEXT_SIZE: u32 = 512;
+
+/// Settings of the in-process llama.cpp backend.
+#[derive(Clone, PartialEq)]
+pub struct LlamaCppConfig {
+    /// GGUF file used for generation.
+    pub gen_model_path: Option<PathBuf>,
+    /// GGUF file used for embeddings.
+    pub embed_model_path: Option<PathBuf>,
+    /// Expected embedding dimension; checked against the model when loaded.
+    pub embed_dimension: usize,
+    /// Context window in tokens, before the model's training context cap.
+    pub context_size: u32,
+    /// Layers offloaded to the GPU. 0 runs on the CPU only.
+    pub gpu_layers: u32,
+    /// CPU threads per inference. `None` uses every available core.
+    pub threads: Option<u32>,
+    /// Most tokens generated per request.
+    pub max_tokens: u32,
+    /// Sampling temperature. 0 samples greedily.
+    pub temperature: f32,
+    /// Inference calls that run at once across the process.
+    pub max_parallel: usize,
+}
+
+impl fmt::Debug for LlamaCppConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path_len = |path: &Option<PathBuf>| {
+            path.as_ref()
+                .map(|path| path.to_string_lossy().chars().count())
+        };
+        f.debug_struct("LlamaCppConfig")
+            .field("gen_model_path_len", &path_len(&self.gen_model_path))
+            .field("embed_model_path_len", &path_len(&self.embed_model_path))
+            .field("embed_dimension", &self.embed_dimension)
+            .field("context_size", &self.context_size)
+            .field("gpu_layers", &self.gpu_layers)
+            .field("threads", &self.threads)
+            .field("max_tokens", &self.max_tokens)
+            .field("temperature", &self.temperature)
+            .field("max_parallel", &self.max_parallel)
+            .finish()
+    }
+}
+
+impl LlamaCppConfig {
+    /// Context and GPU offload settings for the given hardware, with no
+    /// model files configured.
+    pub fn from_hardware(hardware: &HardwareConfig) -> Self {
+        let context_size = u32::try_from(hardware.get_safe_context_limit())
+            .unwrap_or(u32::MAX)
+            .max(MIN_CONTEXT_SIZE);
+        Self {
+            gen_model_path: None,
+            embed_model_path: None,
+            embed_dimension: matric_core::defaults::EMBED_DIMENSION,
+            context_size,
+            gpu_layers: if hardware.vram_gb == 0 {
+                0
+            } else {
+                ALL_GPU_LAYERS
+            },
+            threads: None,
+            max_tokens: 1024,
+            temperature: 0.7,
+            max_parallel: 1,
+        }
+    }
+
+    /// Read the configuration from environment variables. Out-of-range
+    /// numbers are clamped and unparseable ones ignored.
+    ///
+    /// | Variable | Default |
+    /// |----------|---------|
+    /// | `LLAMA_CPP_GEN_MODEL_PATH` | (none) |
+    /// | `LLAMA_CPP_EMBED_MODEL_PATH` | (none) |
+    /// | `LLAMA_CPP_EMBED_DIM` | 768 |
+    /// | `LLAMA_CPP_VRAM_GB` | 0 (CPU only) |
+    /// | `LLAMA_CPP_CONTEXT_SIZE` | safe limit for `LLAMA_CPP_VRAM_GB` |
+    /// | `LLAMA_CPP_GPU_LAYERS` | all layers when VRAM is set, else 0 |
+    /// | `LLAMA_CPP_THREADS` | all cores |
+    /// | `LLAMA_CPP_MAX_TOKENS` | 1024 |
+    /// | `LLAMA_CPP_TEMPERATURE` | 0.7 |
+    /// | `LLAMA_CPP_MAX_PARALLEL` | 1 |
+    pub fn from_env() -> Self {
+        let read = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        let path = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        };
+
+        let vram_gb = read("LLAMA_CPP_VRAM_GB")
+            .map(|v| v.min(1_024) as u32)
+            .unwrap_or(0);
+        let mut config = Self::from_hardware(&HardwareConfig::new(vram_gb));
+        config.gen_model_path = path("LLAMA_CPP_GEN_MODEL_PATH");
+        config.embed_model_path = path("LLAMA_CPP_EMBED_MODEL_PATH");
+        if let Some(dimension) = read("LLAMA_CPP_EMBED_DIM").filter(|v| *v > 0) {
+            config.embed_dimension = dimension.min(65_536) as usize;
+        }
+        if let Some(context_size) = read("LLAMA_CPP_CONTEXT_SIZE") {
+            config.context_size = context_size.clamp(u64::from(MIN_CONTEXT_SIZE), 1_048_576) as u32;
+        }
+        if let Some(gpu_layers) = read("LLAMA_CPP_GPU_LAYERS") {
+            config.gpu_layers = gpu_layers.min(u64::from(ALL_GPU_LAYERS)) as u32;
+        }
+        config.threads = read("LLAMA_CPP_THREADS")
+            .filter(|v| *v > 0)
+            .map(|v| v.min(1_024) as u32);
+        if let Some(max_tokens) = read("LLAMA_CPP_MAX_TOKENS") {
+            config.max_tokens = max_tokens.clamp(1, 65_536) as u32;
+        }
+        if let Some(temperature) = std::env::var("LLAMA_CPP_TEMPERATURE")
+            .ok()
+            .and_then(|v| v.trim().parse::<f32>().ok())
+            .filter(|v| v.is_finite())
+        {
+            config.temperature = temperature.clamp(0.0, 2.0);
+        }
+        if let Some(max_parallel) = read("LLAMA_CPP_MAX_PARALLEL") {
+            config.max_parallel = max_parallel.clamp(1, 64) as usize;
+        }
+        config
+    }
+
+    /// Whether any model file is configured.
+    pub fn is_configured(&self) -> bool {
+        self.gen_model_path.is_some() || self.embed_model_path.is_some()
+    }
+
+    /// Model name of the generation file, as used in slugs.
+    pub fn gen_model_name(&self) -> Option<String> {
+        self.gen_model_path.as_deref().map(model_name)
+    }
+
+    /// Model name of the embedding file, as used in slugs and contracts.
+    pub fn embed_model_name(&self) -> Option<String> {
+        self.embed_model_path.as_deref().map(model_name)
+    }
+
+    /// Context window for a model trained on `train_context` tokens.
+    pub fn effective_context(&self, train_context: u32) -> u32 {
+        if train_context == 0 {
+            return self.context_size;
+        }
+        self.context_size.min(train_context)
+    }
+}
+
+/// Model name of a GGUF file: its file name.
+pub fn model_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+}
+
+/// Check a slug model against the configured file and return its name.
+pub fn resolve_model_name(configured: Option<String>, requested: &str) -> Result<String> {
+    let configured = configured.ok_or_else(|| {
+        Error::Config("No GGUF model file is configured for this operation".to_string())
+    })?;
+    if requested == DEFAULT_MODEL_ALIAS || requested == configured {
+        Ok(configured)
+    } else {
+        Err(Error::Config(format!(
+            "GGUF model '{}' is not configured; use '{}' or '{}'",
+            requested, DEFAULT_MODEL_ALIAS, configured
+        )))
+    }
+}
+
+/// Prompt for models without a chat template.
+pub fn plain_prompt(system: Option<&str>, prompt: &str) -> String {
+    match system {
+        Some(system) if !system.trim().is_empty() => format!("{system}\n\n{prompt}"),
+        _ => prompt.to_string(),
+    }
+}
+
+/// Scale a vector to unit length, as Ollama's embed endpoint does.
+pub fn normalize(values: &[f32]) -> Vec<f32> {
+    let norm = values.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 && norm.is_finite() {
+        values.iter().map(|v| v / norm).collect()
+    } else {
+        values.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_follows_hardware_and_training_context() {
+        let cpu = LlamaCppConfig::from_hardware(&HardwareConfig::new(0));
+        assert_eq!(cpu.gpu_layers, 0);
+        assert_eq!(cpu.context_size, 6_451);
+        assert_eq!(cpu.effective_context(4_096), 4_096);
+        assert_eq!(cpu.effective_context(0), 6_451);
+
+        let gpu = LlamaCppConfig::from_hardware(&HardwareConfig::new(24));
+        assert_eq!(gpu.gpu_layers, ALL_GPU_LAYERS);
+        assert_eq!(gpu.effective_context(32_768), 32_768);
+    }
+
+    #[test]
+    fn slugs_only_select_configured_files() {
+        let config = LlamaCppConfig {
+            gen_model_path: Some(PathBuf::from("/models/qwen3-8b-q4_k_m.gguf")),
+            ..LlamaCppConfig::from_hardware(&HardwareConfig::new(0))
+        };
+        assert_eq!(
+            resolve_model_name(config.gen_model_name(), "default").unwrap(),
+            "qwen3-8b-q4_k_m.gguf"
+        );
+        assert_eq!(
+            resolve_model_name(config.gen_model_name(), "qwen3-8b-q4_k_m.gguf").unwrap(),
+            "qwen3-8b-q4_k_m.gguf"
+        );
+        assert!(resolve_model_name(config.gen_model_name(), "/etc/passwd").is_err());
+        assert!(resolve_model_name(config.embed_model_name(), "default").is_err());
+    }
+
+    #[test]
+    fn prompt_and_vector_helpers() {
+        assert_eq!(plain_prompt(Some("Be brief."), "Hi"), "Be brief.\n\nHi");
+        assert_eq!(plain_prompt(Some("  "), "Hi"), "Hi");
+        assert_eq!(normalize(&[3.0, 4.0]), [0.6, 0.8]);
+        assert_eq!(normalize(&[0.0, 0.0]), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn debug_omits_model_paths() {
+        let config = LlamaCppConfig {
+            gen_model_path: Some(PathBuf::from("/srv/private/model.gguf")),
+            ..LlamaCppConfig::from_hardware(&HardwareConfig::new(0))
+        };
+        assert!(!format!("{config:?}").contains("/srv/private"));
+    }
+}
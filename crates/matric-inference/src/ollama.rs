@@ -8,12 +8,15 @@ use tracing::{debug, info, instrument, warn};
 
 use matric_core::{EmbeddingBackend, Error, GenerationBackend, InferenceBackend, Result, Vector};
 
-use crate::diagnostics::{backend_parse_error, backend_request_error, backend_status_error};
+use crate::diagnostics::{
+    backend_parse_error, backend_request_error, backend_status_error, observe_embedding_latency,
+};
 use crate::embedding_models::{EmbeddingModelProfile, EmbeddingModelRegistry};
 // requires_raw_mode is tested below but no longer used in generate_internal (switched to chat API).
 #[cfg(test)]
 use crate::model_config::requires_raw_mode;
 use crate::profiles::{ModelProfile, ModelRegistry};
+use crate::token_accounting::{record_generation, TokenUsage};
 
 fn diagnostic_len(value: &str) -> usize {
     value.chars().count()
@@ -465,6 +468,17 @@ impl OllamaBackend {
             Error::Inference(backend_parse_error("Ollama chat response parse failed", e))
         })?;
 
+        let sent: String = request
+            .messages
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect();
+        record_generation(
+            &self.gen_model,
+            &sent,
+            &result.message.content,
+            result.reported_usage(&sent),
+        );
         let elapsed = start.elapsed().as_millis() as u64;
         debug!(
             response_len = result.message.content.len(),
@@ -724,7 +738,10 @@ impl OllamaBackend {
             ))
         })?;
 
+        let sent = format!("{system}{prompt}");
+        let reported = result.reported_usage(&sent);
         let content = result.message.content;
+        record_generation(&self.gen_model, &sent, &content, reported);
         let elapsed = start.elapsed().as_millis() as u64;
         debug!(
             response_len = content.len(),
@@ -805,6 +822,24 @@ struct ChatOptions {
 #[derive(Deserialize)]
 struct ChatResponse {
     message: ChatMessage,
+    /// Prompt tokens evaluated; absent when the prompt was fully cached
+    #[serde(default)]
+    prompt_eval_count: Option<u64>,
+    /// Tokens generated
+    #[serde(default)]
+    eval_count: Option<u64>,
+}
+
+impl ChatResponse {
+    /// Token counts reported by Ollama. A cached prompt has no
+    /// `prompt_eval_count`, so its size is estimated from `sent`.
+    fn reported_usage(&self, sent: &str) -> Option<TokenUsage> {
+        let completion = self.eval_count?;
+        let prompt = self
+            .prompt_eval_count
+            .unwrap_or_else(|| TokenUsage::estimate(sent, "").prompt_tokens);
+        Some(TokenUsage::new(prompt, completion))
+    }
 }
 
 /// Convert a streaming Ollama `/api/chat` HTTP response (NDJSON, one JSON
@@ -873,7 +908,22 @@ impl EmbeddingBackend for OllamaBackend {
         }
 
         let start = Instant::now();
+        let result = self.request_embeddings(texts, start).await;
+        observe_embedding_latency("ollama", &result, start.elapsed());
+        result
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn model_name(&self) -> &str {
+        &self.embed_model
+    }
+}
 
+impl OllamaBackend {
+    async fn request_embeddings(&self, texts: &[String], start: Instant) -> Result<Vec<Vector>> {
         let request = EmbeddingRequest {
             model: self.embed_model.clone(),
             input: texts.to_vec(),
@@ -932,14 +982,6 @@ impl EmbeddingBackend for OllamaBackend {
         }
         Ok(vectors)
     }
-
-    fn dimension(&self) -> usize {
-        self.dimension
-    }
-
-    fn model_name(&self) -> &str {
-        &self.embed_model
-    }
 }
 
 #[async_trait]
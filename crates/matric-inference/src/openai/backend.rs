@@ -2,10 +2,16 @@
 
 use async_trait::async_trait;
 use reqwest::Client;
-use std::{fmt, time::Duration};
-use tracing::{debug, info, warn};
-
-use crate::diagnostics::{backend_parse_error, backend_request_error, backend_status_error};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+use tracing::{debug, info, instrument, warn};
+
+use crate::diagnostics::{
+    backend_parse_error, backend_request_error, backend_status_error, observe_embedding_latency,
+};
+use crate::token_accounting::{record_generation, TokenUsage};
 use matric_core::{EmbeddingBackend, Error, GenerationBackend, InferenceBackend, Result, Vector};
 
 use super::streaming::{parse_sse_stream, StreamingGeneration, TokenStream};
@@ -248,11 +254,29 @@ fn ordered_embedding_vectors(
 
 #[async_trait]
 impl EmbeddingBackend for OpenAIBackend {
+    #[instrument(skip_all, fields(subsystem = "inference", component = "openai", op = "embed_texts", model_len = self.config.embed_model.len(), input_count = texts.len()))]
     async fn embed_texts(&self, texts: &[String]) -> Result<Vec<Vector>> {
         if texts.is_empty() {
             return Ok(vec![]);
         }
 
+        let start = Instant::now();
+        let result = self.request_embeddings(texts).await;
+        observe_embedding_latency("openai", &result, start.elapsed());
+        result
+    }
+
+    fn dimension(&self) -> usize {
+        self.config.embed_dimension
+    }
+
+    fn model_name(&self) -> &str {
+        &self.config.embed_model
+    }
+}
+
+impl OpenAIBackend {
+    async fn request_embeddings(&self, texts: &[String]) -> Result<Vec<Vector>> {
         debug!(
             text_count = texts.len(),
             embed_model_len = self.config.embed_model.len(),
@@ -296,14 +320,6 @@ impl EmbeddingBackend for OpenAIBackend {
         debug!("Generated {} embeddings", vectors.len());
         Ok(vectors)
     }
-
-    fn dimension(&self) -> usize {
-        self.config.embed_dimension
-    }
-
-    fn model_name(&self) -> &str {
-        &self.config.embed_model
-    }
 }
 
 #[async_trait]
@@ -340,6 +356,7 @@ impl GenerationBackend for OpenAIBackend {
         StreamingGeneration::generate_stream(self, prompt).await
     }
 
+    #[instrument(skip_all, fields(subsystem = "inference", component = "openai", op = "stream_generate", model_len = self.config.gen_model.len(), prompt_len = prompt.len()))]
     async fn stream_generate_with_system(
         &self,
         system: &str,
@@ -348,6 +365,7 @@ impl GenerationBackend for OpenAIBackend {
         StreamingGeneration::generate_with_system_stream(self, system, prompt).await
     }
 
+    #[instrument(skip_all, fields(subsystem = "inference", component = "openai", op = "generate", model_len = self.config.gen_model.len(), prompt_len = prompt.len()))]
     async fn generate_with_system(&self, system: &str, prompt: &str) -> Result<String> {
         debug!(
             gen_model_len = self.config.gen_model.len(),
@@ -408,6 +426,17 @@ impl GenerationBackend for OpenAIBackend {
             .map(|c| c.message.content.clone())
             .unwrap_or_default();
 
+        let sent = format!("{system}{prompt}");
+        record_generation(
+            &self.config.gen_model,
+            &sent,
+            &content,
+            result
+                .usage
+                .as_ref()
+                .map(|u| TokenUsage::new(u.prompt_tokens.into(), u.completion_tokens.into())),
+        );
+
         debug!("Generation complete, response length: {}", content.len());
         Ok(content)
     }
@@ -416,6 +445,7 @@ impl GenerationBackend for OpenAIBackend {
         self.generate_json_with_system("", prompt).await
     }
 
+    #[instrument(skip_all, fields(subsystem = "inference", component = "openai", op = "generate_json", model_len = self.config.gen_model.len(), prompt_len = prompt.len()))]
     async fn generate_json_with_system(&self, system: &str, prompt: &str) -> Result<String> {
         debug!(
             gen_model_len = self.config.gen_model.len(),
@@ -476,6 +506,17 @@ impl GenerationBackend for OpenAIBackend {
             .map(|c| c.message.content.clone())
             .unwrap_or_default();
 
+        let sent = format!("{system}{prompt}");
+        record_generation(
+            &self.config.gen_model,
+            &sent,
+            &content,
+            result
+                .usage
+                .as_ref()
+                .map(|u| TokenUsage::new(u.prompt_tokens.into(), u.completion_tokens.into())),
+        );
+
         debug!(
             "JSON generation complete, response length: {}",
             content.len()
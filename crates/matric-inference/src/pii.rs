@@ -0,0 +1,135 @@
+//! LLM-assisted PII detection.
+//!
+//! Complements the pattern scan in [`matric_core::pii`]: the model is asked
+//! for personal data values in a text, and its reply is validated against
+//! [`pii_detection_schema`]. Values are only suggestions — callers locate
+//! them in the text with [`matric_core::pii::locate_pii`], so a value the
+//! model invents is never recorded.
+
+use std::sync::LazyLock;
+
+use serde::Deserialize;
+
+use matric_core::{GenerationBackend, PiiKind, Result};
+
+use crate::constrained::{generate_constrained, ConstrainedConfig, OutputSchema};
+
+/// Most values accepted from one detection reply.
+const MAX_VALUES_PER_REPLY: u64 = 100;
+
+/// Longest value accepted from a reply.
+const MAX_VALUE_CHARS: u64 = 100;
+
+static PII_DETECTION_SCHEMA: LazyLock<OutputSchema> = LazyLock::new(|| {
+    let kinds: Vec<&str> = PiiKind::ALL.iter().map(|kind| kind.as_str()).collect();
+    OutputSchema::new(
+        "pii_detection",
+        serde_json::json!({
+            "type": "array",
+            "maxItems": MAX_VALUES_PER_REPLY,
+            "items": {
+                "type": "object",
+                "required": ["value", "type"],
+                "properties": {
+                    "value": { "type": "string", "minLength": 1, "maxLength": MAX_VALUE_CHARS },
+                    "type": { "enum": kinds }
+                }
+            }
+        }),
+    )
+    .expect("PII detection schema must compile")
+});
+
+/// Schema for PII detection replies: a JSON array of
+/// `{"value": ..., "type": ...}` objects.
+pub fn pii_detection_schema() -> &'static OutputSchema {
+    &PII_DETECTION_SCHEMA
+}
+
+#[derive(Deserialize)]
+struct PiiReply {
+    value: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// Ask the model for PII values by generating against [`pii_detection_schema`].
+///
+/// Returns trimmed, de-duplicated `(kind, value)` pairs. Replies that still
+/// fail the schema after the configured repairs are an error.
+pub async fn detect_pii(
+    backend: &dyn GenerationBackend,
+    prompt: &str,
+    config: &ConstrainedConfig,
+) -> Result<Vec<(PiiKind, String)>> {
+    let output =
+        generate_constrained::<Vec<PiiReply>>(backend, prompt, pii_detection_schema(), config)
+            .await?;
+    let mut values: Vec<(PiiKind, String)> = Vec::new();
+    for reply in output.value {
+        let Ok(kind) = reply.kind.parse::<PiiKind>() else {
+            continue;
+        };
+        let value = reply.value.trim().to_string();
+        if value.is_empty() || values.iter().any(|(k, v)| *k == kind && *v == value) {
+            continue;
+        }
+        values.push((kind, value));
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Answers every prompt with the same reply.
+    struct FixedBackend(&'static str);
+
+    #[async_trait]
+    impl GenerationBackend for FixedBackend {
+        async fn generate(&self, _prompt: &str) -> Result<String> {
+            Ok(self.0.to_string())
+        }
+
+        async fn generate_with_system(&self, _system: &str, prompt: &str) -> Result<String> {
+            self.generate(prompt).await
+        }
+
+        fn model_name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    #[tokio::test]
+    async fn detect_pii_trims_and_dedupes() {
+        let backend = FixedBackend(
+            r#"[
+                {"value": " X1234567 ", "type": "national_id"},
+                {"value": "X1234567", "type": "national_id"},
+                {"value": "X1234567", "type": "phone"}
+            ]"#,
+        );
+
+        let values = detect_pii(&backend, "prompt", &ConstrainedConfig::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            values,
+            vec![
+                (PiiKind::NationalId, "X1234567".to_string()),
+                (PiiKind::Phone, "X1234567".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn detect_pii_rejects_unknown_types() {
+        let backend = FixedBackend(r#"[{"value": "Ada", "type": "name"}]"#);
+        let config = ConstrainedConfig { max_repairs: 0 };
+
+        assert!(detect_pii(&backend, "prompt", &config).await.is_err());
+    }
+}
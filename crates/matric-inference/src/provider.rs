@@ -8,13 +8,17 @@
 //! "ollama:qwen3:8b"               → explicit Ollama
 //! "openai:gpt-4o"                 → OpenAI
 //! "openrouter:anthropic/claude-sonnet-4-20250514" → OpenRouter
+//! "anthropic:claude-sonnet-4-20250514" → Anthropic
+//! "gemini:gemini-2.5-flash"       → Google Gemini
 //! ```
 //!
 //! The default provider (Ollama) is always available. External providers
-//! require feature flags (`openai`) and API key configuration.
+//! require feature flags (`openai`, `anthropic`, `gemini`) and API key
+//! configuration.
 
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
@@ -22,6 +26,10 @@ use tracing::{debug, info, warn};
 
 use matric_core::{EmbeddingBackend, EmbeddingContract, Error, Result};
 
+use crate::embedding_router::{
+    EmbeddingEndpoint, EmbeddingEndpointPool, EmbeddingRouterConfig, RoutedEmbeddingBackend,
+};
+
 fn string_lens<S: AsRef<str>>(values: &[S]) -> Vec<usize> {
     values
         .iter()
@@ -29,6 +37,11 @@ fn string_lens<S: AsRef<str>>(values: &[S]) -> Vec<usize> {
         .collect()
 }
 
+/// Providers whose slug prefix is recognized even when they are not
+/// registered, so `anthropic:<model>` fails as an unconfigured provider
+/// instead of reaching the default provider as a model name.
+pub const NATIVE_PROVIDER_IDS: &[&str] = &["anthropic", "gemini"];
+
 fn embedding_dimension_from_env(name: &str, default: usize) -> usize {
     std::env::var(name)
         .ok()
@@ -187,6 +200,9 @@ pub struct ProviderRegistry {
     /// `POST /api/v1/inference/config { "embedding_backend": "<id>" }`.
     /// `None` means "use the default provider for embeddings too".
     embedding_provider: Option<String>,
+    /// Embedding endpoint pools by provider ID. A provider with a pool spreads
+    /// its embedding calls over the pool's endpoints instead of `base_url`.
+    embedding_pools: HashMap<String, Arc<EmbeddingEndpointPool>>,
 }
 
 impl ProviderRegistry {
@@ -196,6 +212,7 @@ impl ProviderRegistry {
             providers: HashMap::new(),
             default_provider,
             embedding_provider: None,
+            embedding_pools: HashMap::new(),
         }
     }
 
@@ -285,6 +302,54 @@ impl ProviderRegistry {
         }
     }
 
+    /// Route a registered provider's embedding calls over `endpoints`, with
+    /// weighted round-robin, failover and per-endpoint circuit breaking.
+    /// An empty list removes the pool, so embeddings use `base_url` again.
+    pub fn set_embedding_endpoints(
+        &mut self,
+        provider_id: &str,
+        endpoints: Vec<EmbeddingEndpoint>,
+        config: EmbeddingRouterConfig,
+    ) -> Result<()> {
+        if endpoints.is_empty() {
+            self.embedding_pools.remove(provider_id);
+            return Ok(());
+        }
+        let provider = self
+            .providers
+            .get(provider_id)
+            .ok_or_else(|| Error::Config(format!("Unknown provider: {}", provider_id)))?;
+        if !provider
+            .capabilities
+            .contains(&ProviderCapability::Embedding)
+        {
+            return Err(Error::Config(format!(
+                "Provider '{}' does not support embeddings",
+                provider_id
+            )));
+        }
+        let pool =
+            EmbeddingEndpointPool::new(provider_id, endpoints, provider.api_key.clone(), config)?;
+        info!(
+            provider_len = provider_id.chars().count(),
+            endpoint_count = pool.endpoints().count(),
+            "Embedding endpoint pool configured"
+        );
+        self.embedding_pools
+            .insert(provider_id.to_string(), Arc::new(pool));
+        Ok(())
+    }
+
+    /// Get the embedding endpoint pool of a provider, if it has one.
+    pub fn embedding_pool(&self, provider_id: &str) -> Option<&Arc<EmbeddingEndpointPool>> {
+        self.embedding_pools.get(provider_id)
+    }
+
+    /// Get every configured embedding endpoint pool.
+    pub fn embedding_pools(&self) -> Vec<&Arc<EmbeddingEndpointPool>> {
+        self.embedding_pools.values().collect()
+    }
+
     /// Get all registered provider IDs.
     pub fn provider_ids(&self) -> Vec<&str> {
         self.providers.keys().map(|s| s.as_str()).collect()
@@ -342,9 +407,17 @@ impl ProviderRegistry {
     /// | `ollama:qwen3:8b` | ollama | `qwen3:8b` |
     /// | `openai:gpt-4o` | openai | `gpt-4o` |
     /// | `openrouter:anthropic/claude-sonnet-4-20250514` | openrouter | `anthropic/claude-sonnet-4-20250514` |
+    /// | `gemini:gemini-2.5-flash` | gemini | `gemini-2.5-flash` |
+    ///
+    /// The [`NATIVE_PROVIDER_IDS`] prefixes are recognized even when the
+    /// provider is not registered.
     pub fn parse_slug(&self, slug: &str) -> ParsedSlug {
         // Try each known provider prefix
-        for provider_id in self.providers.keys() {
+        let native = NATIVE_PROVIDER_IDS
+            .iter()
+            .map(|id| id.to_string())
+            .filter(|id| !self.providers.contains_key(id));
+        for provider_id in self.providers.keys().cloned().chain(native) {
             let prefix = format!("{}:", provider_id);
             if let Some(model) = slug.strip_prefix(&prefix) {
                 if !model.is_empty() {
@@ -355,7 +428,7 @@ impl ProviderRegistry {
                         "Parsed provider-qualified slug"
                     );
                     return ParsedSlug {
-                        provider_id: provider_id.clone(),
+                        provider_id,
                         model: model.to_string(),
                     };
                 }
@@ -455,6 +528,62 @@ impl ProviderRegistry {
                 };
                 Ok(Box::new(crate::OpenAIBackend::new(oai_config)?))
             }
+            #[cfg(feature = "anthropic")]
+            "anthropic" => Ok(Box::new(crate::AnthropicBackend::new(
+                Self::anthropic_config(config, &parsed.model),
+            )?)),
+            #[cfg(feature = "gemini")]
+            "gemini" => Ok(Box::new(crate::GeminiBackend::new(Self::gemini_config(
+                config,
+                &parsed.model,
+            ))?)),
+            #[cfg(feature = "llama-cpp")]
+            "gguf" => Ok(Box::new(
+                crate::LlamaCppBackend::from_env()?.with_gen_model(&parsed.model)?,
+            )),
+            _ => Err(Error::Config(format!(
+                "Provider '{}' not compiled in (check feature flags)",
+                parsed.provider_id
+            ))),
+        }
+    }
+
+    /// Resolve a provider-qualified slug to a boxed [`VisionBackend`].
+    ///
+    /// Bare slugs use the default provider, like generation slugs.
+    ///
+    /// [`VisionBackend`]: crate::vision::VisionBackend
+    pub fn resolve_vision_boxed(
+        &self,
+        slug: &str,
+    ) -> Result<Box<dyn crate::vision::VisionBackend>> {
+        let parsed = self.parse_slug(slug);
+        let config = self
+            .providers
+            .get(&parsed.provider_id)
+            .ok_or_else(|| Error::Config(format!("Unknown provider: {}", parsed.provider_id)))?;
+
+        if !config.capabilities.contains(&ProviderCapability::Vision) {
+            return Err(Error::Config(format!(
+                "Provider '{}' does not support vision",
+                parsed.provider_id
+            )));
+        }
+
+        match parsed.provider_id.as_str() {
+            "ollama" => Ok(Box::new(crate::vision::OllamaVisionBackend::new(
+                config.base_url.clone(),
+                parsed.model,
+            ))),
+            #[cfg(feature = "anthropic")]
+            "anthropic" => Ok(Box::new(crate::AnthropicBackend::new(
+                Self::anthropic_config(config, &parsed.model),
+            )?)),
+            #[cfg(feature = "gemini")]
+            "gemini" => Ok(Box::new(crate::GeminiBackend::new(Self::gemini_config(
+                config,
+                &parsed.model,
+            ))?)),
             _ => Err(Error::Config(format!(
                 "Provider '{}' not compiled in (check feature flags)",
                 parsed.provider_id
@@ -462,6 +591,38 @@ impl ProviderRegistry {
         }
     }
 
+    /// Anthropic backend settings for a registered provider and model.
+    /// `ANTHROPIC_MAX_TOKENS` still applies.
+    #[cfg(feature = "anthropic")]
+    fn anthropic_config(config: &ProviderConfig, model: &str) -> crate::AnthropicConfig {
+        let api_key = config.api_key.clone().unwrap_or_default();
+        let defaults = crate::AnthropicConfig::from_env()
+            .unwrap_or_else(|| crate::AnthropicConfig::new(api_key.clone()));
+        crate::AnthropicConfig {
+            base_url: config.base_url.clone(),
+            api_key,
+            model: model.to_string(),
+            timeout_seconds: config.timeout.as_secs(),
+            ..defaults
+        }
+    }
+
+    /// Gemini backend settings for a registered provider and model.
+    /// `GEMINI_MAX_TOKENS` still applies.
+    #[cfg(feature = "gemini")]
+    fn gemini_config(config: &ProviderConfig, model: &str) -> crate::GeminiConfig {
+        let api_key = config.api_key.clone().unwrap_or_default();
+        let defaults = crate::GeminiConfig::from_env()
+            .unwrap_or_else(|| crate::GeminiConfig::new(api_key.clone()));
+        crate::GeminiConfig {
+            base_url: config.base_url.clone(),
+            api_key,
+            model: model.to_string(),
+            timeout_seconds: config.timeout.as_secs(),
+            ..defaults
+        }
+    }
+
     /// Resolve the configured default provider using its environment model.
     pub fn resolve_default_generation_boxed(
         &self,
@@ -475,6 +636,12 @@ impl ProviderRegistry {
             "llamacpp" => {
                 std::env::var("LLAMACPP_GEN_MODEL").unwrap_or_else(|_| "default".to_string())
             }
+            "anthropic" => std::env::var("ANTHROPIC_GEN_MODEL")
+                .unwrap_or_else(|_| "claude-sonnet-4-20250514".to_string()),
+            "gemini" => {
+                std::env::var("GEMINI_GEN_MODEL").unwrap_or_else(|_| "gemini-2.5-flash".to_string())
+            }
+            "gguf" => crate::llama_cpp::DEFAULT_MODEL_ALIAS.to_string(),
             _ => std::env::var("OLLAMA_GEN_MODEL")
                 .unwrap_or_else(|_| matric_core::defaults::GEN_MODEL.to_string()),
         };
@@ -510,6 +677,29 @@ impl ProviderRegistry {
                 std::env::var("LLAMACPP_EMBED_MODEL").unwrap_or_else(|_| "default".to_string()),
                 embedding_dimension_from_env("LLAMACPP_EMBED_DIM", 1536),
             ),
+            "candle" => {
+                let config = crate::CandleEmbeddingConfig::from_env();
+                let dimension = config
+                    .as_ref()
+                    .and_then(|config| crate::candle::ModelShape::read(&config.model_dir).ok())
+                    .map(|shape| shape.hidden_size)
+                    .unwrap_or(matric_core::defaults::EMBED_DIMENSION);
+                (
+                    config
+                        .map(|config| config.model_name)
+                        .unwrap_or_else(|| crate::candle::PROVIDER_ID.to_string()),
+                    dimension,
+                )
+            }
+            "gguf" => (
+                crate::LlamaCppConfig::from_env()
+                    .embed_model_name()
+                    .unwrap_or_else(|| crate::llama_cpp::DEFAULT_MODEL_ALIAS.to_string()),
+                embedding_dimension_from_env(
+                    "LLAMA_CPP_EMBED_DIM",
+                    matric_core::defaults::EMBED_DIMENSION,
+                ),
+            ),
             _ => (
                 std::env::var("OLLAMA_EMBED_MODEL")
                     .unwrap_or_else(|_| matric_core::defaults::EMBED_MODEL.to_string()),
@@ -569,22 +759,65 @@ impl ProviderRegistry {
             )));
         }
 
-        let backend: Box<dyn EmbeddingBackend> = match provider_id {
+        let backend: Box<dyn EmbeddingBackend> = match self.embedding_pools.get(provider_id) {
+            Some(pool) => {
+                let backends = pool
+                    .endpoints()
+                    .map(|endpoint| {
+                        Self::build_embedding_backend(
+                            provider_id,
+                            config,
+                            &endpoint.base_url,
+                            model,
+                            dimension,
+                        )
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Box::new(RoutedEmbeddingBackend::new(pool.clone(), backends)?)
+            }
+            None => Self::build_embedding_backend(
+                provider_id,
+                config,
+                &config.base_url,
+                model,
+                dimension,
+            )?,
+        };
+        let contract = EmbeddingContract::new(provider_id, model, dimension, embedding_set_id)
+            .map_err(Error::Config)?;
+        if backend.dimension() != contract.dimension() {
+            return Err(Error::Config(
+                "Resolved embedding backend dimension does not match its contract".to_string(),
+            ));
+        }
+        Ok(ResolvedEmbeddingBackend { backend, contract })
+    }
+
+    /// Build an embedding backend for one endpoint of a provider.
+    #[cfg_attr(not(feature = "openai"), allow(unused_variables))]
+    fn build_embedding_backend(
+        provider_id: &str,
+        config: &ProviderConfig,
+        base_url: &str,
+        model: &str,
+        dimension: usize,
+    ) -> Result<Box<dyn EmbeddingBackend>> {
+        match provider_id {
             #[cfg(feature = "ollama")]
             "ollama" => {
                 let generation_model = std::env::var("OLLAMA_GEN_MODEL")
                     .unwrap_or_else(|_| matric_core::defaults::GEN_MODEL.to_string());
-                Box::new(crate::OllamaBackend::with_config(
-                    config.base_url.clone(),
+                Ok(Box::new(crate::OllamaBackend::with_config(
+                    base_url.to_string(),
                     model.to_string(),
                     generation_model,
                     dimension,
-                ))
+                )))
             }
             #[cfg(feature = "openai")]
             "openai" | "openrouter" | "llamacpp" => {
                 let oai_config = crate::OpenAIConfig {
-                    base_url: config.base_url.clone(),
+                    base_url: base_url.to_string(),
                     api_key: config.api_key.clone(),
                     embed_model: model.to_string(),
                     embed_dimension: dimension,
@@ -593,23 +826,28 @@ impl ProviderRegistry {
                     timeout_seconds: config.timeout.as_secs(),
                     ..Default::default()
                 };
-                Box::new(crate::OpenAIBackend::new(oai_config)?)
+                Ok(Box::new(crate::OpenAIBackend::new(oai_config)?))
             }
-            _ => {
-                return Err(Error::Config(format!(
-                    "Provider '{}' not compiled in (check feature flags)",
-                    provider_id
-                )))
+            #[cfg(feature = "llama-cpp")]
+            "gguf" => Ok(Box::new(
+                crate::LlamaCppBackend::from_env()?.with_embed_model(model, dimension)?,
+            )),
+            #[cfg(feature = "candle")]
+            "candle" => {
+                let backend = crate::CandleEmbeddingBackend::from_env()?;
+                if EmbeddingBackend::model_name(&backend) != model {
+                    return Err(Error::Config(format!(
+                        "Candle model '{}' is not the configured model",
+                        model
+                    )));
+                }
+                Ok(Box::new(backend))
             }
-        };
-        let contract = EmbeddingContract::new(provider_id, model, dimension, embedding_set_id)
-            .map_err(Error::Config)?;
-        if backend.dimension() != contract.dimension() {
-            return Err(Error::Config(
-                "Resolved embedding backend dimension does not match its contract".to_string(),
-            ));
+            _ => Err(Error::Config(format!(
+                "Provider '{}' not compiled in (check feature flags)",
+                provider_id
+            ))),
         }
-        Ok(ResolvedEmbeddingBackend { backend, contract })
     }
 
     /// Resolve an optional model override to a boxed generation backend.
@@ -765,8 +1003,63 @@ impl ProviderRegistry {
                 };
                 Ok(Box::new(crate::OpenAIBackend::new(oai_config)?))
             }
+            #[cfg(feature = "anthropic")]
+            "anthropic" => {
+                let resolved_key = api_key
+                    .map(|s| s.to_string())
+                    .or_else(|| registered.and_then(|c| c.api_key.clone()))
+                    .or_else(|| std::env::var("ANTHROPIC_API_KEY").ok())
+                    .ok_or_else(|| {
+                        Error::Config(
+                            "anthropic: no api_key in request, registry, or ANTHROPIC_API_KEY env"
+                                .to_string(),
+                        )
+                    })?;
+                let mut anthropic_config = crate::AnthropicConfig::from_env()
+                    .unwrap_or_else(|| crate::AnthropicConfig::new(resolved_key.clone()));
+                anthropic_config.api_key = resolved_key;
+                if let Some(url) = base_url
+                    .map(|s| s.to_string())
+                    .or_else(|| registered.map(|c| c.base_url.clone()))
+                {
+                    anthropic_config.base_url = url;
+                }
+                if let Some(config) = registered {
+                    anthropic_config.timeout_seconds = config.timeout.as_secs();
+                }
+                anthropic_config.model = model.to_string();
+                Ok(Box::new(crate::AnthropicBackend::new(anthropic_config)?))
+            }
+            #[cfg(feature = "gemini")]
+            "gemini" => {
+                let resolved_key = api_key
+                    .map(|s| s.to_string())
+                    .or_else(|| registered.and_then(|c| c.api_key.clone()))
+                    .or_else(|| std::env::var("GEMINI_API_KEY").ok())
+                    .ok_or_else(|| {
+                        Error::Config(
+                            "gemini: no api_key in request, registry, or GEMINI_API_KEY env"
+                                .to_string(),
+                        )
+                    })?;
+                let mut gemini_config = crate::GeminiConfig::from_env()
+                    .unwrap_or_else(|| crate::GeminiConfig::new(resolved_key.clone()));
+                gemini_config.api_key = resolved_key;
+                if let Some(url) = base_url
+                    .map(|s| s.to_string())
+                    .or_else(|| registered.map(|c| c.base_url.clone()))
+                {
+                    gemini_config.base_url = url;
+                }
+                if let Some(config) = registered {
+                    gemini_config.timeout_seconds = config.timeout.as_secs();
+                }
+                gemini_config.model = model.to_string();
+                Ok(Box::new(crate::GeminiBackend::new(gemini_config)?))
+            }
             _ => Err(Error::Config(format!(
-                "Provider '{}' not supported (known: ollama, openai, openrouter, llamacpp)",
+                "Provider '{}' not supported (known: ollama, openai, openrouter, llamacpp, \
+                 anthropic, gemini)",
                 provider_id
             ))),
         }
@@ -908,6 +1201,112 @@ impl ProviderRegistry {
             }
         }
 
+        // Anthropic — opt-in via ANTHROPIC_API_KEY
+        if let Ok(api_key) = std::env::var("ANTHROPIC_API_KEY") {
+            if !api_key.trim().is_empty() {
+                let base_url = std::env::var("ANTHROPIC_BASE_URL")
+                    .unwrap_or_else(|_| "https://api.anthropic.com/v1".to_string());
+                let timeout = std::env::var("ANTHROPIC_TIMEOUT")
+                    .ok()
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .unwrap_or(300);
+
+                registry.register(ProviderConfig {
+                    id: "anthropic".to_string(),
+                    base_url: base_url.trim_end_matches('/').to_string(),
+                    api_key: Some(api_key),
+                    capabilities: vec![ProviderCapability::Generation, ProviderCapability::Vision],
+                    timeout: Duration::from_secs(timeout),
+                    is_default: false,
+                    health: ProviderHealth::Unknown,
+                    http_referer: None,
+                    x_title: None,
+                });
+            }
+        }
+
+        // Google Gemini — opt-in via GEMINI_API_KEY (or GOOGLE_API_KEY)
+        if let Some(api_key) = std::env::var("GEMINI_API_KEY")
+            .ok()
+            .filter(|k| !k.trim().is_empty())
+            .or_else(|| {
+                std::env::var("GOOGLE_API_KEY")
+                    .ok()
+                    .filter(|k| !k.trim().is_empty())
+            })
+        {
+            let base_url = std::env::var("GEMINI_BASE_URL")
+                .unwrap_or_else(|_| "https://generativelanguage.googleapis.com/v1beta".to_string());
+            let timeout = std::env::var("GEMINI_TIMEOUT")
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(300);
+
+            registry.register(ProviderConfig {
+                id: "gemini".to_string(),
+                base_url: base_url.trim_end_matches('/').to_string(),
+                api_key: Some(api_key),
+                capabilities: vec![ProviderCapability::Generation, ProviderCapability::Vision],
+                timeout: Duration::from_secs(timeout),
+                is_default: false,
+                health: ProviderHealth::Unknown,
+                http_referer: None,
+                x_title: None,
+            });
+        }
+
+        // In-process GGUF models — opt-in via LLAMA_CPP_GEN_MODEL_PATH and/or
+        // LLAMA_CPP_EMBED_MODEL_PATH. Registered even without the `llama-cpp`
+        // feature so routing to it fails with a clear feature-flag error.
+        let llama_cpp = crate::LlamaCppConfig::from_env();
+        if llama_cpp.is_configured() {
+            if !cfg!(feature = "llama-cpp") {
+                warn!(
+                    "LLAMA_CPP_* model paths are set but this build lacks the llama-cpp \
+                     feature; the gguf provider will not resolve"
+                );
+            }
+            let mut capabilities = Vec::new();
+            if llama_cpp.gen_model_path.is_some() {
+                capabilities.push(ProviderCapability::Generation);
+            }
+            if llama_cpp.embed_model_path.is_some() {
+                capabilities.push(ProviderCapability::Embedding);
+            }
+            registry.register(ProviderConfig {
+                id: crate::llama_cpp::PROVIDER_ID.to_string(),
+                base_url: String::new(),
+                api_key: None,
+                capabilities,
+                timeout: Duration::from_secs(matric_core::defaults::GEN_TIMEOUT_SECS),
+                is_default: false,
+                health: ProviderHealth::Unknown,
+                http_referer: None,
+                x_title: None,
+            });
+        }
+
+        // Candle local embeddings — opt-in via CANDLE_EMBED_MODEL_DIR.
+        if crate::CandleEmbeddingConfig::from_env().is_some() {
+            if !cfg!(feature = "candle") {
+                warn!(
+                    "CANDLE_EMBED_MODEL_DIR is set but this build lacks the candle feature; \
+                     the candle provider will not resolve"
+                );
+            }
+            registry.register(ProviderConfig {
+                id: crate::candle::PROVIDER_ID.to_string(),
+                base_url: String::new(),
+                api_key: None,
+                capabilities: vec![ProviderCapability::Embedding],
+                timeout: Duration::from_secs(matric_core::defaults::GEN_TIMEOUT_SECS),
+                is_default: false,
+                health: ProviderHealth::Unknown,
+                http_referer: None,
+                x_title: None,
+            });
+        }
+
         if !registry.select_default_provider(&requested_default) {
             warn!(
                 requested_provider_len = requested_default.chars().count(),
@@ -949,6 +1348,35 @@ impl ProviderRegistry {
             }
         }
 
+        // Embedding endpoint pools — opt-in per provider via
+        // <PROVIDER>_EMBED_ENDPOINTS, a comma-separated list of base URLs with
+        // optional `=<weight>` suffixes. Invalid lists are logged and ignored.
+        let router_config = EmbeddingRouterConfig::from_env();
+        for (provider_id, variable) in [
+            ("ollama", "OLLAMA_EMBED_ENDPOINTS"),
+            ("openai", "OPENAI_EMBED_ENDPOINTS"),
+            ("llamacpp", "LLAMACPP_EMBED_ENDPOINTS"),
+        ] {
+            let Ok(value) = std::env::var(variable) else {
+                continue;
+            };
+            if value.trim().is_empty() || !registry.has_provider(provider_id) {
+                continue;
+            }
+            let configured = EmbeddingEndpoint::parse_list(&value)
+                .map_err(Error::Config)
+                .and_then(|endpoints| {
+                    registry.set_embedding_endpoints(provider_id, endpoints, router_config)
+                });
+            if let Err(error) = configured {
+                warn!(
+                    provider_len = provider_id.chars().count(),
+                    error_len = error.to_string().chars().count(),
+                    "Ignoring invalid embedding endpoint list"
+                );
+            }
+        }
+
         info!(
             provider_count = registry.provider_ids().len(),
             provider_id_lens = ?string_lens(&registry.provider_ids()),
@@ -1156,6 +1584,73 @@ mod tests {
         request.assert_async().await;
     }
 
+    #[cfg(feature = "ollama")]
+    #[tokio::test]
+    async fn embedding_endpoint_pool_fails_over_between_endpoints() {
+        use crate::embedding_router::{EmbeddingEndpoint, EmbeddingRouterConfig};
+
+        let mut down = mockito::Server::new_async().await;
+        let down_request = down
+            .mock("POST", "/api/embed")
+            .with_status(503)
+            .create_async()
+            .await;
+        let mut up = mockito::Server::new_async().await;
+        let up_request = up
+            .mock("POST", "/api/embed")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"embeddings": [[0.1, 0.2, 0.3]]}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let mut registry = test_registry();
+        registry
+            .set_embedding_endpoints(
+                "ollama",
+                vec![
+                    EmbeddingEndpoint {
+                        base_url: down.url(),
+                        weight: 5,
+                    },
+                    EmbeddingEndpoint {
+                        base_url: up.url(),
+                        weight: 1,
+                    },
+                ],
+                EmbeddingRouterConfig {
+                    failure_threshold: 1,
+                    cooldown_secs: 3_600,
+                    health_check_interval_secs: 0,
+                },
+            )
+            .unwrap();
+
+        let resolved = registry
+            .resolve_embedding_contract_boxed("ollama", "nomic-embed-text", 3, None)
+            .unwrap();
+        assert_eq!(resolved.contract.provider_id(), "ollama");
+        for _ in 0..2 {
+            let vectors = resolved
+                .backend
+                .embed_texts(&["query".to_string()])
+                .await
+                .unwrap();
+            assert_eq!(vectors[0].as_slice().len(), 3);
+        }
+
+        // The first failure opens the heavier endpoint's circuit, so the
+        // second request is not sent to it.
+        down_request.assert_async().await;
+        up_request.assert_async().await;
+
+        registry
+            .set_embedding_endpoints("ollama", Vec::new(), EmbeddingRouterConfig::default())
+            .unwrap();
+        assert!(registry.embedding_pool("ollama").is_none());
+    }
+
     #[test]
     fn parse_bare_ollama_slug() {
         let reg = test_registry();
@@ -1213,6 +1708,48 @@ mod tests {
         assert_eq!(parsed.model, "llava:34b");
     }
 
+    #[test]
+    fn parse_native_prefix_without_registration() {
+        let reg = test_registry();
+        let parsed = reg.parse_slug("anthropic:claude-sonnet-4-20250514");
+        assert_eq!(parsed.provider_id, "anthropic");
+        assert_eq!(parsed.model, "claude-sonnet-4-20250514");
+
+        let err = reg
+            .resolve_generation_boxed("gemini:gemini-2.5-flash")
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Config(message) if message.contains("Unknown provider")));
+    }
+
+    #[cfg(all(feature = "anthropic", feature = "gemini"))]
+    #[test]
+    fn native_providers_resolve_generation_and_vision() {
+        let mut reg = test_registry();
+        for id in NATIVE_PROVIDER_IDS {
+            reg.register(ProviderConfig {
+                id: id.to_string(),
+                base_url: "http://127.0.0.1:9".to_string(),
+                api_key: Some("test-key".to_string()),
+                capabilities: vec![ProviderCapability::Generation, ProviderCapability::Vision],
+                timeout: Duration::from_secs(10),
+                is_default: false,
+                health: ProviderHealth::Unknown,
+                http_referer: None,
+                x_title: None,
+            });
+        }
+
+        let generation = reg
+            .resolve_generation_boxed("anthropic:claude-3-5-haiku-latest")
+            .unwrap();
+        assert_eq!(generation.model_name(), "claude-3-5-haiku-latest");
+        let vision = reg.resolve_vision_boxed("gemini:gemini-2.5-pro").unwrap();
+        assert_eq!(vision.model_name(), "gemini-2.5-pro");
+        assert!(reg.resolve_vision_boxed("gemini:../files").is_err());
+        assert!(reg.resolve_vision_boxed("openai:gpt-4o").is_err());
+    }
+
     #[test]
     fn parse_empty_model_after_prefix_uses_default() {
         let reg = test_registry();
@@ -0,0 +1,155 @@
+//! Question-answer pair generation for fine-tuning datasets.
+//!
+//! The model is asked for question-answer pairs grounded in a note and a
+//! rating of each, and its reply is validated against [`qa_pairs_schema`].
+//! Blank pairs and repeated questions are dropped.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use serde::Deserialize;
+
+use matric_core::{GeneratedQaPair, GenerationBackend, Result};
+
+use crate::constrained::{generate_constrained, ConstrainedConfig, OutputSchema};
+
+/// Most pairs accepted from one reply.
+const MAX_PAIRS_PER_REPLY: u64 = 50;
+
+/// Longest question accepted from a reply.
+const MAX_QUESTION_CHARS: u64 = 1_000;
+
+/// Longest answer accepted from a reply.
+const MAX_ANSWER_CHARS: u64 = 8_000;
+
+/// Question kinds a reply may use.
+const QUESTION_TYPES: [&str; 4] = ["factoid", "conceptual", "procedural", "comparative"];
+
+static QA_PAIRS_SCHEMA: LazyLock<OutputSchema> = LazyLock::new(|| {
+    OutputSchema::new(
+        "qa_pairs",
+        serde_json::json!({
+            "type": "array",
+            "maxItems": MAX_PAIRS_PER_REPLY,
+            "items": {
+                "type": "object",
+                "required": ["question", "answer", "quality"],
+                "properties": {
+                    "question": { "type": "string", "minLength": 1, "maxLength": MAX_QUESTION_CHARS },
+                    "answer": { "type": "string", "minLength": 1, "maxLength": MAX_ANSWER_CHARS },
+                    "type": { "enum": QUESTION_TYPES },
+                    "quality": { "type": "number", "minimum": 1, "maximum": 5 }
+                }
+            }
+        }),
+    )
+    .expect("qa pairs schema must compile")
+});
+
+/// Schema for question-answer replies: a JSON array of
+/// `{"question": ..., "answer": ..., "type": ..., "quality": ...}` objects.
+pub fn qa_pairs_schema() -> &'static OutputSchema {
+    &QA_PAIRS_SCHEMA
+}
+
+#[derive(Deserialize)]
+struct QaPairReply {
+    question: String,
+    answer: String,
+    #[serde(rename = "type")]
+    question_type: Option<String>,
+    quality: f32,
+}
+
+/// Generate question-answer pairs against [`qa_pairs_schema`].
+///
+/// Pairs with a blank question or answer are dropped, as are questions
+/// already asked (ignoring case and surrounding whitespace). Replies that
+/// still fail the schema after the configured repairs are an error.
+pub async fn generate_qa_pairs(
+    backend: &dyn GenerationBackend,
+    prompt: &str,
+    config: &ConstrainedConfig,
+) -> Result<Vec<GeneratedQaPair>> {
+    let output =
+        generate_constrained::<Vec<QaPairReply>>(backend, prompt, qa_pairs_schema(), config)
+            .await?;
+    let mut seen = HashSet::new();
+    Ok(output
+        .value
+        .into_iter()
+        .filter_map(|reply| {
+            let question = reply.question.trim().to_string();
+            let answer = reply.answer.trim().to_string();
+            if question.is_empty() || answer.is_empty() {
+                return None;
+            }
+            seen.insert(question.to_lowercase())
+                .then_some(GeneratedQaPair {
+                    question,
+                    answer,
+                    question_type: reply.question_type,
+                    quality: reply.quality,
+                })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Answers every prompt with the same reply.
+    struct FixedBackend(&'static str);
+
+    #[async_trait]
+    impl GenerationBackend for FixedBackend {
+        async fn generate(&self, _prompt: &str) -> Result<String> {
+            Ok(self.0.to_string())
+        }
+
+        async fn generate_with_system(&self, _system: &str, prompt: &str) -> Result<String> {
+            self.generate(prompt).await
+        }
+
+        fn model_name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    #[tokio::test]
+    async fn generate_qa_pairs_drops_repeats_and_blanks() {
+        let backend = FixedBackend(
+            r#"[
+                {"question": "How often are backups rotated?", "answer": "Weekly.", "type": "factoid", "quality": 5},
+                {"question": " how often are backups rotated? ", "answer": "Every week.", "quality": 4},
+                {"question": "Why rotate backups?", "answer": "   ", "type": "conceptual", "quality": 3}
+            ]"#,
+        );
+
+        let pairs = generate_qa_pairs(&backend, "prompt", &ConstrainedConfig::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            pairs,
+            vec![GeneratedQaPair {
+                question: "How often are backups rotated?".to_string(),
+                answer: "Weekly.".to_string(),
+                question_type: Some("factoid".to_string()),
+                quality: 5.0,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn generate_qa_pairs_rejects_out_of_range_quality() {
+        let backend = FixedBackend(r#"[{"question": "Q?", "answer": "A.", "quality": 9}]"#);
+        let config = ConstrainedConfig { max_repairs: 0 };
+
+        assert!(generate_qa_pairs(&backend, "prompt", &config)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,272 @@
+//! Hierarchical map-reduce summarization.
+//!
+//! Content that fits in one chunk is summarized with a single call. Longer
+//! content is summarized chunk by chunk (map), then the chunk summaries are
+//! combined in groups that fit the reduce budget, level by level, until one
+//! summary remains (reduce). After `max_levels` reduce levels whatever is left
+//! is combined in a single call, so the number of calls is always bounded.
+//!
+//! Prompts are supplied by the caller so templates can be overridden per
+//! archive; the map prompt receives one chunk and the reduce prompt receives
+//! the chunk summaries joined by blank lines.
+
+use tracing::debug;
+
+use matric_core::defaults::{SUMMARIZATION_MAX_LEVELS, SUMMARIZATION_REDUCE_MAX_CHARS};
+use matric_core::{Error, GenerationBackend, Result};
+
+use crate::thinking::parse_thinking_response;
+
+/// Separator between partial summaries in a reduce prompt.
+const SUMMARY_SEPARATOR: &str = "\n\n";
+
+/// Limits for [`map_reduce_summarize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SummarizeConfig {
+    /// Maximum characters of partial summaries combined by one reduce call
+    pub reduce_max_chars: usize,
+    /// Reduce levels before the remaining summaries are combined at once
+    pub max_levels: usize,
+}
+
+impl Default for SummarizeConfig {
+    fn default() -> Self {
+        Self {
+            reduce_max_chars: SUMMARIZATION_REDUCE_MAX_CHARS,
+            max_levels: SUMMARIZATION_MAX_LEVELS,
+        }
+    }
+}
+
+/// A finished summary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryOutput {
+    pub summary: String,
+    /// Chunks summarized in the map step
+    pub chunk_count: usize,
+    /// Reduce levels run; 0 when the content was a single chunk
+    pub levels: usize,
+    /// Generation calls made
+    pub calls: usize,
+}
+
+/// Summarize `chunks`, map-reducing when there is more than one.
+///
+/// Empty chunks are ignored. Backend errors are returned immediately, and an
+/// empty reply at any step is an [`Error::Inference`].
+pub async fn map_reduce_summarize<M, R>(
+    backend: &dyn GenerationBackend,
+    chunks: &[String],
+    config: &SummarizeConfig,
+    map_prompt: M,
+    reduce_prompt: R,
+) -> Result<SummaryOutput>
+where
+    M: Fn(&str) -> String,
+    R: Fn(&str) -> String,
+{
+    let chunks: Vec<&str> = chunks
+        .iter()
+        .map(|c| c.trim())
+        .filter(|c| !c.is_empty())
+        .collect();
+    if chunks.is_empty() {
+        return Err(Error::InvalidInput(
+            "Nothing to summarize: content is empty".to_string(),
+        ));
+    }
+
+    let mut calls = 0;
+    let mut partials = Vec::with_capacity(chunks.len());
+    for chunk in &chunks {
+        partials.push(summarize_once(backend, &map_prompt(chunk)).await?);
+        calls += 1;
+    }
+
+    let mut levels = 0;
+    while partials.len() > 1 {
+        levels += 1;
+        let groups = if levels >= config.max_levels {
+            vec![partials]
+        } else {
+            group_by_budget(partials, config.reduce_max_chars)
+        };
+        let mut next = Vec::with_capacity(groups.len());
+        for group in groups {
+            if group.len() == 1 {
+                next.extend(group);
+                continue;
+            }
+            let joined = group.join(SUMMARY_SEPARATOR);
+            next.push(summarize_once(backend, &reduce_prompt(&joined)).await?);
+            calls += 1;
+        }
+        debug!(
+            level = levels,
+            remaining = next.len(),
+            "Summary reduce level complete"
+        );
+        partials = next;
+    }
+
+    Ok(SummaryOutput {
+        summary: partials.pop().unwrap_or_default(),
+        chunk_count: chunks.len(),
+        levels,
+        calls,
+    })
+}
+
+/// Run one generation call and clean the reply.
+async fn summarize_once(backend: &dyn GenerationBackend, prompt: &str) -> Result<String> {
+    let reply = backend.generate(prompt).await?;
+    let answer = if reply.contains("<think>") {
+        parse_thinking_response(&reply).answer_content
+    } else {
+        reply
+    };
+    let summary = answer.trim();
+    if summary.is_empty() {
+        return Err(Error::Inference(
+            "model returned an empty summary".to_string(),
+        ));
+    }
+    Ok(summary.to_string())
+}
+
+/// Split consecutive summaries into groups whose joined length fits
+/// `max_chars`. Every group holds at least two summaries when more than one
+/// remains, so each reduce level always makes progress.
+fn group_by_budget(summaries: Vec<String>, max_chars: usize) -> Vec<Vec<String>> {
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut current_len = 0;
+    for summary in summaries {
+        let added = summary.len() + SUMMARY_SEPARATOR.len();
+        if current.len() >= 2 && current_len + added > max_chars {
+            groups.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        current_len += added;
+        current.push(summary);
+    }
+    if current.len() == 1 {
+        if let Some(last) = groups.last_mut() {
+            last.append(&mut current);
+        }
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// Answers each prompt with a short summary naming its call number.
+    #[derive(Default)]
+    struct CountingBackend {
+        prompts: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl GenerationBackend for CountingBackend {
+        async fn generate(&self, prompt: &str) -> Result<String> {
+            let mut prompts = self.prompts.lock().unwrap();
+            prompts.push(prompt.to_string());
+            Ok(format!("<think>hmm</think> summary {}", prompts.len()))
+        }
+
+        async fn generate_with_system(&self, _system: &str, prompt: &str) -> Result<String> {
+            self.generate(prompt).await
+        }
+
+        fn model_name(&self) -> &str {
+            "counting"
+        }
+    }
+
+    fn chunks(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("chunk {i}")).collect()
+    }
+
+    #[tokio::test]
+    async fn single_chunk_uses_one_call() {
+        let backend = CountingBackend::default();
+        let out = map_reduce_summarize(
+            &backend,
+            &chunks(1),
+            &SummarizeConfig::default(),
+            |c| format!("MAP {c}"),
+            |s| format!("REDUCE {s}"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(out.summary, "summary 1");
+        assert_eq!((out.chunk_count, out.levels, out.calls), (1, 0, 1));
+        assert_eq!(*backend.prompts.lock().unwrap(), vec!["MAP chunk 0"]);
+    }
+
+    #[tokio::test]
+    async fn long_content_reduces_hierarchically() {
+        let backend = CountingBackend::default();
+        // Room for two partial summaries per reduce call.
+        let config = SummarizeConfig {
+            reduce_max_chars: 24,
+            max_levels: 4,
+        };
+        let out = map_reduce_summarize(
+            &backend,
+            &chunks(5),
+            &config,
+            |c| format!("MAP {c}"),
+            |s| format!("REDUCE {s}"),
+        )
+        .await
+        .unwrap();
+
+        // 5 maps, then [2, 3] -> 2 reduces, then 1 final reduce.
+        assert_eq!((out.chunk_count, out.levels, out.calls), (5, 2, 8));
+        let prompts = backend.prompts.lock().unwrap();
+        assert_eq!(prompts[5], "REDUCE summary 1\n\nsummary 2");
+        assert_eq!(prompts[6], "REDUCE summary 3\n\nsummary 4\n\nsummary 5");
+        assert_eq!(prompts[7], "REDUCE summary 6\n\nsummary 7");
+        assert_eq!(out.summary, "summary 8");
+    }
+
+    #[tokio::test]
+    async fn max_levels_forces_a_final_combined_reduce() {
+        let backend = CountingBackend::default();
+        let config = SummarizeConfig {
+            reduce_max_chars: 1,
+            max_levels: 1,
+        };
+        let out = map_reduce_summarize(&backend, &chunks(6), &config, str::to_string, |s| {
+            s.to_string()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!((out.levels, out.calls), (1, 7));
+    }
+
+    #[tokio::test]
+    async fn empty_content_is_rejected() {
+        let backend = CountingBackend::default();
+        let result = map_reduce_summarize(
+            &backend,
+            &["  ".to_string()],
+            &SummarizeConfig::default(),
+            str::to_string,
+            str::to_string,
+        )
+        .await;
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+        assert!(backend.prompts.lock().unwrap().is_empty());
+    }
+}
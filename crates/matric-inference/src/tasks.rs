@@ -0,0 +1,145 @@
+//! LLM-assisted action item detection.
+//!
+//! Complements the checkbox parser in [`matric_core::task`]: the model is
+//! asked for action items written as prose, and its reply is validated
+//! against [`task_extraction_schema`]. Due dates the model gives are kept
+//! only when they are real calendar dates.
+
+use std::sync::LazyLock;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use matric_core::task::MAX_TASK_TEXT_CHARS;
+use matric_core::{GenerationBackend, Result};
+
+use crate::constrained::{generate_constrained, ConstrainedConfig, OutputSchema};
+
+/// Most action items accepted from one reply.
+const MAX_ITEMS_PER_REPLY: u64 = 50;
+
+static TASK_EXTRACTION_SCHEMA: LazyLock<OutputSchema> = LazyLock::new(|| {
+    OutputSchema::new(
+        "task_extraction",
+        serde_json::json!({
+            "type": "array",
+            "maxItems": MAX_ITEMS_PER_REPLY,
+            "items": {
+                "type": "object",
+                "required": ["text"],
+                "properties": {
+                    "text": {
+                        "type": "string",
+                        "minLength": 1,
+                        "maxLength": MAX_TASK_TEXT_CHARS
+                    },
+                    "due": { "type": ["string", "null"] }
+                }
+            }
+        }),
+    )
+    .expect("task extraction schema must compile")
+});
+
+/// Schema for action item replies: a JSON array of `{"text": ..., "due": ...}`
+/// objects.
+pub fn task_extraction_schema() -> &'static OutputSchema {
+    &TASK_EXTRACTION_SCHEMA
+}
+
+#[derive(Deserialize)]
+struct TaskReply {
+    text: String,
+    #[serde(default)]
+    due: Option<String>,
+}
+
+/// Ask the model for action items by generating against
+/// [`task_extraction_schema`].
+///
+/// Returns trimmed, de-duplicated items with their due date, if the model
+/// gave a valid `YYYY-MM-DD` one. Replies that still fail the schema after
+/// the configured repairs are an error.
+pub async fn extract_action_items(
+    backend: &dyn GenerationBackend,
+    prompt: &str,
+    config: &ConstrainedConfig,
+) -> Result<Vec<(String, Option<NaiveDate>)>> {
+    let output =
+        generate_constrained::<Vec<TaskReply>>(backend, prompt, task_extraction_schema(), config)
+            .await?;
+    let mut items: Vec<(String, Option<NaiveDate>)> = Vec::new();
+    for reply in output.value {
+        let text = reply.text.trim().to_string();
+        if text.is_empty() || items.iter().any(|(t, _)| t.eq_ignore_ascii_case(&text)) {
+            continue;
+        }
+        let due = reply
+            .due
+            .and_then(|due| NaiveDate::parse_from_str(due.trim(), "%Y-%m-%d").ok());
+        items.push((text, due));
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Answers every prompt with the same reply.
+    struct FixedBackend(&'static str);
+
+    #[async_trait]
+    impl GenerationBackend for FixedBackend {
+        async fn generate(&self, _prompt: &str) -> Result<String> {
+            Ok(self.0.to_string())
+        }
+
+        async fn generate_with_system(&self, _system: &str, prompt: &str) -> Result<String> {
+            self.generate(prompt).await
+        }
+
+        fn model_name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    #[tokio::test]
+    async fn extract_action_items_trims_dedupes_and_checks_dates() {
+        let backend = FixedBackend(
+            r#"[
+                {"text": " Send the budget ", "due": "2026-03-06"},
+                {"text": "send the budget", "due": null},
+                {"text": "Book a room", "due": "next week"},
+                {"text": "Call the bank"}
+            ]"#,
+        );
+
+        let items = extract_action_items(&backend, "prompt", &ConstrainedConfig::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                (
+                    "Send the budget".to_string(),
+                    NaiveDate::from_ymd_opt(2026, 3, 6)
+                ),
+                ("Book a room".to_string(), None),
+                ("Call the bank".to_string(), None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn extract_action_items_rejects_items_without_text() {
+        let backend = FixedBackend(r#"[{"due": "2026-03-06"}]"#);
+        let config = ConstrainedConfig { max_repairs: 0 };
+
+        assert!(extract_action_items(&backend, "prompt", &config)
+            .await
+            .is_err());
+    }
+}
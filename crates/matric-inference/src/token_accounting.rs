@@ -0,0 +1,244 @@
+//! Per-call token accounting and model pricing.
+//!
+//! Generation backends report every completed call with [`record_generation`].
+//! Calls made inside [`TokenAccount::scope`] are added to that account, keyed
+//! by model; calls outside any scope are not counted. The job worker opens one
+//! scope per job and persists the totals when the job finishes.
+//!
+//! Token counts come from the provider's response when it reports them and are
+//! otherwise estimated from the prompt and completion text. Tasks spawned from
+//! inside a scope do not inherit it.
+//!
+//! ```
+//! use matric_inference::token_accounting::{record_generation, TokenAccount, TokenUsage};
+//!
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let account = TokenAccount::default();
+//! account
+//!     .scope(async {
+//!         record_generation("qwen3:8b", "Summarize this", "A summary", Some(TokenUsage::new(12, 3)));
+//!     })
+//!     .await;
+//! let usage = account.snapshot();
+//! assert_eq!(usage[0].calls, 1);
+//! assert_eq!(usage[0].usage.prompt_tokens, 12);
+//! # });
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use matric_core::estimate_tokens;
+
+/// Environment variable holding per-model prices as JSON.
+pub const MODEL_PRICING_ENV: &str = "MATRIC_MODEL_PRICING";
+
+tokio::task_local! {
+    static CURRENT_ACCOUNT: TokenAccount;
+}
+
+/// Prompt and completion tokens for one or more calls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn new(prompt_tokens: u64, completion_tokens: u64) -> Self {
+        Self {
+            prompt_tokens,
+            completion_tokens,
+        }
+    }
+
+    /// Estimate usage from the text sent and received.
+    pub fn estimate(prompt: &str, completion: &str) -> Self {
+        Self::new(
+            estimate_tokens(prompt) as u64,
+            estimate_tokens(completion) as u64,
+        )
+    }
+
+    fn add(&mut self, other: TokenUsage) {
+        self.prompt_tokens = self.prompt_tokens.saturating_add(other.prompt_tokens);
+        self.completion_tokens = self
+            .completion_tokens
+            .saturating_add(other.completion_tokens);
+    }
+}
+
+/// Accumulated usage for one model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelUsage {
+    pub model: String,
+    pub calls: u64,
+    pub usage: TokenUsage,
+}
+
+/// Shared accumulator for the calls made inside [`TokenAccount::scope`].
+#[derive(Debug, Clone, Default)]
+pub struct TokenAccount {
+    models: Arc<Mutex<BTreeMap<String, ModelUsage>>>,
+}
+
+impl TokenAccount {
+    /// Run `future` with this account collecting its generation calls.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        CURRENT_ACCOUNT.scope(self.clone(), future).await
+    }
+
+    /// Add one call's usage.
+    pub fn record(&self, model: &str, usage: TokenUsage) {
+        let mut models = self.models.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = models
+            .entry(model.to_string())
+            .or_insert_with(|| ModelUsage {
+                model: model.to_string(),
+                calls: 0,
+                usage: TokenUsage::default(),
+            });
+        entry.calls += 1;
+        entry.usage.add(usage);
+    }
+
+    /// Usage so far, one entry per model in name order.
+    pub fn snapshot(&self) -> Vec<ModelUsage> {
+        self.models
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Record a completed generation call against the current account, if any.
+///
+/// `reported` is the provider's own count; without it the usage is estimated
+/// from `prompt` (including any system prompt) and `completion`.
+pub fn record_generation(
+    model: &str,
+    prompt: &str,
+    completion: &str,
+    reported: Option<TokenUsage>,
+) {
+    let _ = CURRENT_ACCOUNT.try_with(|account| {
+        let usage = reported.unwrap_or_else(|| TokenUsage::estimate(prompt, completion));
+        account.record(model, usage);
+    });
+}
+
+/// Price of a model in US dollars per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    /// Dollars per million prompt tokens
+    pub input: f64,
+    /// Dollars per million completion tokens
+    pub output: f64,
+}
+
+/// Configured model prices. Models without an entry have no cost.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PricingTable {
+    models: HashMap<String, ModelPricing>,
+}
+
+impl PricingTable {
+    /// Parse prices from JSON such as
+    /// `{"gpt-4o-mini": {"input": 0.15, "output": 0.6}}`, keyed by the model
+    /// name backends report. Negative or non-finite prices are rejected.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let models: HashMap<String, ModelPricing> =
+            serde_json::from_str(json).map_err(|e| format!("invalid pricing JSON: {e}"))?;
+        if let Some(model) = models.iter().find_map(|(model, price)| {
+            let valid = [price.input, price.output]
+                .iter()
+                .all(|p| p.is_finite() && *p >= 0.0);
+            (!valid).then_some(model)
+        }) {
+            return Err(format!("invalid price for model {model}"));
+        }
+        Ok(Self { models })
+    }
+
+    /// Load prices from [`MODEL_PRICING_ENV`]. An unset variable is an empty
+    /// table; an invalid one is logged and ignored.
+    pub fn from_env() -> Self {
+        let Ok(json) = std::env::var(MODEL_PRICING_ENV) else {
+            return Self::default();
+        };
+        Self::from_json(&json).unwrap_or_else(|e| {
+            warn!(
+                env = MODEL_PRICING_ENV,
+                error_len = e.len(),
+                "Ignoring invalid model pricing"
+            );
+            Self::default()
+        })
+    }
+
+    /// Price configured for `model`.
+    pub fn pricing(&self, model: &str) -> Option<ModelPricing> {
+        self.models.get(model).copied()
+    }
+
+    /// Cost of `usage` on `model`, or `None` when the model has no price.
+    pub fn cost_usd(&self, model: &str, usage: TokenUsage) -> Option<f64> {
+        let price = self.pricing(model)?;
+        Some(
+            (usage.prompt_tokens as f64 * price.input
+                + usage.completion_tokens as f64 * price.output)
+                / 1_000_000.0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn records_only_inside_a_scope() {
+        record_generation("outside", "prompt", "completion", None);
+
+        let account = TokenAccount::default();
+        account
+            .scope(async {
+                record_generation("m", "ignored", "ignored", Some(TokenUsage::new(10, 4)));
+                record_generation("m", "", "", Some(TokenUsage::new(5, 1)));
+                record_generation("other", "four words of prompt", "", None);
+            })
+            .await;
+
+        let usage = account.snapshot();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].model, "m");
+        assert_eq!(usage[0].calls, 2);
+        assert_eq!(usage[0].usage, TokenUsage::new(15, 5));
+        assert_eq!(usage[1].model, "other");
+        assert!(usage[1].usage.prompt_tokens > 0);
+        assert_eq!(usage[1].usage.completion_tokens, 0);
+    }
+
+    #[test]
+    fn pricing_applies_per_million_tokens() {
+        let table =
+            PricingTable::from_json(r#"{"gpt-4o-mini": {"input": 0.15, "output": 0.6}}"#).unwrap();
+        let usage = TokenUsage::new(1_000_000, 500_000);
+
+        let cost = table.cost_usd("gpt-4o-mini", usage).unwrap();
+        assert!((cost - 0.45).abs() < 1e-9);
+        assert_eq!(table.cost_usd("qwen3:8b", usage), None);
+    }
+
+    #[test]
+    fn pricing_rejects_negative_prices() {
+        assert!(PricingTable::from_json(r#"{"m": {"input": -1, "output": 0}}"#).is_err());
+        assert!(PricingTable::from_json("not json").is_err());
+    }
+}
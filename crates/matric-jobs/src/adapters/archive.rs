@@ -8,21 +8,49 @@
 //! - `ARCHIVE_MAX_EXTRACT_BYTES` → 1 GB total extracted bytes
 //! - `ARCHIVE_MAX_SINGLE_FILE_BYTES` → 50 MB per file
 //! - `ARCHIVE_MAX_NESTING` → 3 levels of archives-within-archives
+//! - `ARCHIVE_MAX_MEMBERS` → 100 documents extracted recursively per upload
 //!
 //! Unsupported formats (.rar, .7z) produce metadata-only output with a note
 //! that extraction was skipped.
-
+//!
+//! ## Recursive extraction
+//!
+//! When the extraction handler enables recursion (`ARCHIVE_RECURSIVE`, or the
+//! job's `recursive` config), documents and nested archives inside the upload
+//! are returned as `archive_entry` derived files instead of being skipped as
+//! binary. [`expand_archive_members`] then runs each one through the
+//! [`ExtractionRegistry`] with the strategy an upload of the same file would
+//! get, and adds the text as a section of the archive's text or, with
+//! `ARCHIVE_MEMBER_MODE=notes`, as a child note. Each result records the
+//! entry's path inside the archive (`inner.zip/report.pdf`) as provenance.
+
+use std::collections::VecDeque;
 use std::fmt;
 use std::io::{Cursor, Read};
 
 use async_trait::async_trait;
 use flate2::read::GzDecoder;
-use serde_json::Value as JsonValue;
+use serde_json::{json, Value as JsonValue};
+
+use matric_core::{
+    DerivedFile, DerivedNote, ExtractionAdapter, ExtractionResult, ExtractionStrategy, Result,
+};
+
+use crate::extraction::ExtractionRegistry;
 
-use matric_core::{ExtractionAdapter, ExtractionResult, ExtractionStrategy, Result};
+/// Return type for internal archive extraction: entry list, extracted (name, text)
+/// pairs, and entries handed back for recursive extraction.
+type ExtractOutput = (Vec<EntryInfo>, Vec<(String, String)>, Vec<DerivedFile>);
 
-/// Return type for internal archive extraction: entry list + extracted (name, text) pairs.
-type ExtractOutput = (Vec<EntryInfo>, Vec<(String, String)>);
+/// Derivation type of entries returned for recursive extraction.
+const MEMBER_DERIVATION: &str = "archive_entry";
+
+/// Skip reason recorded for entries returned for recursive extraction.
+const MEMBER_REASON: &str = "member";
+
+/// Config key carrying the nesting depth of the archive being extracted.
+/// Set by the extraction handler when recursion is enabled.
+pub(crate) const ARCHIVE_DEPTH_KEY: &str = "_archive_depth";
 
 // ── Configurable limits ──────────────────────────────────────────────────────
 
@@ -35,6 +63,12 @@ const DEFAULT_MAX_EXTRACT_BYTES: usize = 1024 * 1024 * 1024;
 /// Default maximum individual file size for text extraction (50 MB).
 const DEFAULT_MAX_SINGLE_FILE_BYTES: usize = 50 * 1024 * 1024;
 
+/// Default maximum levels of archives-within-archives extracted recursively.
+const DEFAULT_MAX_NESTING: usize = 3;
+
+/// Default maximum entries extracted recursively from one upload.
+const DEFAULT_MAX_MEMBERS: usize = 100;
+
 /// Read a limit from an env var, falling back to the provided default.
 fn env_limit(var: &str, default: usize) -> usize {
     std::env::var(var)
@@ -47,6 +81,9 @@ fn env_limit(var: &str, default: usize) -> usize {
 struct Limits {
     max_extract_bytes: usize,
     max_single_file_bytes: usize,
+    /// Depth of the archive being read when recursion is enabled. Documents and
+    /// nested archives are then returned as members instead of skipped.
+    member_depth: Option<u64>,
 }
 
 impl Limits {
@@ -57,10 +94,55 @@ impl Limits {
                 "ARCHIVE_MAX_SINGLE_FILE_BYTES",
                 DEFAULT_MAX_SINGLE_FILE_BYTES,
             ),
+            member_depth: None,
         }
     }
 }
 
+/// Whether recursive extraction is enabled: the job's `recursive` config,
+/// else `ARCHIVE_RECURSIVE` (default on).
+pub(crate) fn recursion_enabled(config: &JsonValue) -> bool {
+    config
+        .get("recursive")
+        .and_then(JsonValue::as_bool)
+        .unwrap_or_else(|| {
+            std::env::var("ARCHIVE_RECURSIVE")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true)
+        })
+}
+
+/// Strategy an entry would be extracted with, if it should be extracted
+/// recursively rather than read as text.
+///
+/// Only cheap document strategies qualify; media (images, audio, video) stay
+/// skipped so an archive of photos does not fan out into vision calls.
+fn member_strategy(name: &str) -> Option<ExtractionStrategy> {
+    let ext = name.rsplit_once('.').map(|(_, ext)| ext)?;
+    match ExtractionStrategy::from_mime_and_extension("application/octet-stream", Some(ext)) {
+        strategy @ (ExtractionStrategy::PdfText
+        | ExtractionStrategy::OfficeConvert
+        | ExtractionStrategy::Spreadsheet
+        | ExtractionStrategy::Email
+        | ExtractionStrategy::Archive
+        | ExtractionStrategy::Ebook
+        | ExtractionStrategy::Notebook) => Some(strategy),
+        _ => None,
+    }
+}
+
+fn member_file(name: &str, data: Vec<u8>, depth: u64) -> DerivedFile {
+    DerivedFile {
+        filename: name.rsplit('/').next().unwrap_or(name).to_string(),
+        content_type: "application/octet-stream".to_string(),
+        data,
+        derivation_type: MEMBER_DERIVATION.to_string(),
+        ai_description: None,
+        metadata: Some(json!({ "archive_path": name, "archive_depth": depth + 1 })),
+        source_path: None,
+    }
+}
+
 // ── Known binary extensions ───────────────────────────────────────────────────
 
 /// File extensions that are always treated as binary (no text extraction).
@@ -255,6 +337,7 @@ impl ArchiveAdapter {
 
         let mut entries: Vec<EntryInfo> = Vec::new();
         let mut texts: Vec<(String, String)> = Vec::new();
+        let mut members: Vec<DerivedFile> = Vec::new();
         let mut total_extracted: usize = 0;
         let file_count = archive.len();
 
@@ -302,6 +385,22 @@ impl ArchiveAdapter {
                 continue;
             }
 
+            // Documents and nested archives are extracted recursively
+            if let (Some(depth), Some(_)) = (limits.member_depth, member_strategy(&name)) {
+                let mut bytes = Vec::with_capacity(size as usize);
+                let _ = file.read_to_end(&mut bytes);
+                total_extracted += bytes.len();
+                members.push(member_file(&name, bytes, depth));
+                entries.push(EntryInfo {
+                    name,
+                    size,
+                    is_dir: false,
+                    extracted: false,
+                    skip_reason: Some(MEMBER_REASON.to_string()),
+                });
+                continue;
+            }
+
             // Read probe for binary detection
             let probe_len = (size as usize).min(BINARY_PROBE_BYTES);
             let mut probe = vec![0u8; probe_len];
@@ -338,13 +437,14 @@ impl ArchiveAdapter {
             });
         }
 
-        Ok((entries, texts))
+        Ok((entries, texts, members))
     }
 
     /// Extract text and entry metadata from a Tar archive (plain or gzip-compressed).
     fn extract_tar(data: &[u8], gzipped: bool, limits: &Limits) -> Result<ExtractOutput> {
         let mut entries: Vec<EntryInfo> = Vec::new();
         let mut texts: Vec<(String, String)> = Vec::new();
+        let mut members: Vec<DerivedFile> = Vec::new();
         let mut total_extracted: usize = 0;
 
         // Helper closure to process a tar::Archive<R>
@@ -352,9 +452,9 @@ impl ArchiveAdapter {
             archive: &mut tar::Archive<R>,
             entries: &mut Vec<EntryInfo>,
             texts: &mut Vec<(String, String)>,
+            members: &mut Vec<DerivedFile>,
             total_extracted: &mut usize,
-            max_single_file_bytes: usize,
-            max_extract_bytes: usize,
+            limits: &Limits,
         ) -> Result<()> {
             let tar_entries = archive.entries().map_err(|e| {
                 matric_core::Error::Internal(archive_failure_detail("tar_read", &e))
@@ -385,7 +485,7 @@ impl ArchiveAdapter {
                 }
 
                 // Size guard
-                if size as usize > max_single_file_bytes {
+                if size as usize > limits.max_single_file_bytes {
                     entries.push(EntryInfo {
                         name: path,
                         size,
@@ -397,7 +497,7 @@ impl ArchiveAdapter {
                 }
 
                 // Budget guard
-                if *total_extracted >= max_extract_bytes {
+                if *total_extracted >= limits.max_extract_bytes {
                     entries.push(EntryInfo {
                         name: path,
                         size,
@@ -408,6 +508,22 @@ impl ArchiveAdapter {
                     continue;
                 }
 
+                // Documents and nested archives are extracted recursively
+                if let (Some(depth), Some(_)) = (limits.member_depth, member_strategy(&path)) {
+                    let mut bytes = Vec::with_capacity(size as usize);
+                    let _ = entry.read_to_end(&mut bytes);
+                    *total_extracted += bytes.len();
+                    members.push(member_file(&path, bytes, depth));
+                    entries.push(EntryInfo {
+                        name: path,
+                        size,
+                        is_dir: false,
+                        extracted: false,
+                        skip_reason: Some(MEMBER_REASON.to_string()),
+                    });
+                    continue;
+                }
+
                 // Probe for binary detection
                 let probe_len = (size as usize).min(BINARY_PROBE_BYTES);
                 let mut probe = vec![0u8; probe_len];
@@ -452,9 +568,9 @@ impl ArchiveAdapter {
                 &mut archive,
                 &mut entries,
                 &mut texts,
+                &mut members,
                 &mut total_extracted,
-                limits.max_single_file_bytes,
-                limits.max_extract_bytes,
+                limits,
             )?;
         } else {
             let mut archive = tar::Archive::new(Cursor::new(data));
@@ -462,21 +578,20 @@ impl ArchiveAdapter {
                 &mut archive,
                 &mut entries,
                 &mut texts,
+                &mut members,
                 &mut total_extracted,
-                limits.max_single_file_bytes,
-                limits.max_extract_bytes,
+                limits,
             )?;
         }
 
-        Ok((entries, texts))
+        Ok((entries, texts, members))
     }
 
     /// Build the formatted text output and JSON metadata.
     fn build_result(
         filename: &str,
         archive_type: &str,
-        entries: Vec<EntryInfo>,
-        texts: Vec<(String, String)>,
+        (entries, texts, members): ExtractOutput,
     ) -> ExtractionResult {
         let total_files = entries.iter().filter(|e| !e.is_dir).count();
         let total_dirs = entries.iter().filter(|e| e.is_dir).count();
@@ -499,7 +614,8 @@ impl ArchiveAdapter {
             .collect();
 
         for entry in &entries {
-            if entry.is_dir {
+            // Members get their own section once extracted recursively
+            if entry.is_dir || entry.skip_reason.as_deref() == Some(MEMBER_REASON) {
                 continue;
             }
 
@@ -558,7 +674,8 @@ impl ArchiveAdapter {
             metadata,
             ai_description: None,
             preview_data: None,
-            derived_files: vec![],
+            derived_files: members,
+            derived_notes: vec![],
         }
     }
 }
@@ -574,7 +691,7 @@ impl ExtractionAdapter for ArchiveAdapter {
         data: &[u8],
         filename: &str,
         mime_type: &str,
-        _config: &JsonValue,
+        config: &JsonValue,
     ) -> Result<ExtractionResult> {
         if data.is_empty() {
             return Ok(ExtractionResult {
@@ -590,24 +707,28 @@ impl ExtractionAdapter for ArchiveAdapter {
                 ai_description: None,
                 preview_data: None,
                 derived_files: vec![],
+                derived_notes: vec![],
             });
         }
 
         let format = Self::detect_format(mime_type, filename);
-        let limits = Limits::from_env();
+        let limits = Limits {
+            member_depth: config.get(ARCHIVE_DEPTH_KEY).and_then(JsonValue::as_u64),
+            ..Limits::from_env()
+        };
 
         match format {
             ArchiveFormat::Zip => {
-                let (entries, texts) = Self::extract_zip(data, &limits)?;
-                Ok(Self::build_result(filename, "zip", entries, texts))
+                let output = Self::extract_zip(data, &limits)?;
+                Ok(Self::build_result(filename, "zip", output))
             }
             ArchiveFormat::TarGz => {
-                let (entries, texts) = Self::extract_tar(data, true, &limits)?;
-                Ok(Self::build_result(filename, "tar.gz", entries, texts))
+                let output = Self::extract_tar(data, true, &limits)?;
+                Ok(Self::build_result(filename, "tar.gz", output))
             }
             ArchiveFormat::Tar => {
-                let (entries, texts) = Self::extract_tar(data, false, &limits)?;
-                Ok(Self::build_result(filename, "tar", entries, texts))
+                let output = Self::extract_tar(data, false, &limits)?;
+                Ok(Self::build_result(filename, "tar", output))
             }
             ArchiveFormat::Unsupported(fmt) => {
                 // Return metadata-only result without attempting extraction
@@ -624,6 +745,7 @@ impl ExtractionAdapter for ArchiveAdapter {
                     ai_description: None,
                     preview_data: None,
                     derived_files: vec![],
+                    derived_notes: vec![],
                 })
             }
         }
@@ -638,6 +760,196 @@ impl ExtractionAdapter for ArchiveAdapter {
     }
 }
 
+// ── Recursive extraction ──────────────────────────────────────────────────────
+
+/// Where recursively extracted text goes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MemberMode {
+    /// Appended to the archive's text as one section per entry.
+    Sections,
+    /// Created as a child note per entry, linked to the archive's note.
+    Notes,
+}
+
+impl MemberMode {
+    fn from_config(config: &JsonValue) -> Self {
+        let mode = config
+            .get("member_mode")
+            .and_then(JsonValue::as_str)
+            .map(str::to_string)
+            .or_else(|| std::env::var("ARCHIVE_MEMBER_MODE").ok());
+        match mode.as_deref() {
+            Some("notes") => Self::Notes,
+            _ => Self::Sections,
+        }
+    }
+}
+
+/// Extract the `archive_entry` members of an archive result through the registry.
+///
+/// Nested archives are expanded in turn until `ARCHIVE_MAX_NESTING`
+/// (`max_nesting` config); at most `ARCHIVE_MAX_MEMBERS` entries are extracted
+/// and their combined size counts against `ARCHIVE_MAX_EXTRACT_BYTES`. Entries
+/// that fail to extract are recorded in metadata and never fail the archive.
+pub(crate) async fn expand_archive_members(
+    registry: &ExtractionRegistry,
+    archive_name: &str,
+    mut result: ExtractionResult,
+    config: &JsonValue,
+) -> ExtractionResult {
+    let mode = MemberMode::from_config(config);
+    let max_nesting = config
+        .get("max_nesting")
+        .and_then(JsonValue::as_u64)
+        .map(|v| v as usize)
+        .unwrap_or_else(|| env_limit("ARCHIVE_MAX_NESTING", DEFAULT_MAX_NESTING));
+    let max_members = env_limit("ARCHIVE_MAX_MEMBERS", DEFAULT_MAX_MEMBERS);
+    let max_bytes = Limits::from_env().max_extract_bytes;
+
+    let (members, others): (Vec<_>, Vec<_>) = std::mem::take(&mut result.derived_files)
+        .into_iter()
+        .partition(|df| df.derivation_type == MEMBER_DERIVATION);
+    result.derived_files = others;
+    let mut queue: VecDeque<DerivedFile> = members.into();
+
+    let mut sections: Vec<String> = Vec::new();
+    let mut provenance: Vec<JsonValue> = Vec::new();
+    let mut extracted = 0usize;
+    let mut total_bytes = 0usize;
+
+    while let Some(member) = queue.pop_front() {
+        let meta = member.metadata.clone().unwrap_or_default();
+        let path = meta["archive_path"]
+            .as_str()
+            .unwrap_or(&member.filename)
+            .to_string();
+        let depth = meta["archive_depth"].as_u64().unwrap_or(1);
+        let ext = member.filename.rsplit_once('.').map(|(_, ext)| ext);
+        let content_type =
+            matric_core::detect_content_type(&member.filename, &member.data, &member.content_type);
+        let strategy = ExtractionStrategy::from_mime_and_extension(&content_type, ext);
+
+        let mut entry = json!({
+            "path": path,
+            "depth": depth,
+            "strategy": strategy.to_string(),
+            "size": member.data.len(),
+        });
+        let status = if strategy == ExtractionStrategy::Archive && depth as usize > max_nesting {
+            "max_nesting"
+        } else if extracted >= max_members {
+            "member_limit"
+        } else if total_bytes + member.data.len() > max_bytes {
+            "budget_exceeded"
+        } else if !registry.has_adapter(strategy) {
+            "no_adapter"
+        } else {
+            extracted += 1;
+            total_bytes += member.data.len();
+            let mut member_config = config.clone();
+            if let Some(obj) = member_config.as_object_mut() {
+                obj.insert(ARCHIVE_DEPTH_KEY.to_string(), json!(depth));
+            }
+            match registry
+                .extract(
+                    strategy,
+                    &member.data,
+                    &member.filename,
+                    &content_type,
+                    &member_config,
+                )
+                .await
+            {
+                Ok(mut inner) => {
+                    // Queue a nested archive's own members under its path
+                    for mut nested in std::mem::take(&mut inner.derived_files) {
+                        if nested.derivation_type != MEMBER_DERIVATION {
+                            continue;
+                        }
+                        if let Some(nested_meta) = nested.metadata.as_mut() {
+                            let inner_path = nested_meta["archive_path"]
+                                .as_str()
+                                .unwrap_or(&nested.filename)
+                                .to_string();
+                            nested_meta["archive_path"] = json!(format!("{path}/{inner_path}"));
+                        }
+                        queue.push_back(nested);
+                    }
+
+                    let text = inner
+                        .extracted_text
+                        .or(inner.ai_description)
+                        .filter(|t| !t.trim().is_empty());
+                    if let Some(text) = text {
+                        entry["chars"] = json!(text.chars().count());
+                        match mode {
+                            MemberMode::Sections => sections.push(format!(
+                                "--- {path} [{strategy}] ---\n{}\n",
+                                text.trim_end()
+                            )),
+                            MemberMode::Notes => result.derived_notes.push(DerivedNote {
+                                title: format!("{path} ({archive_name})"),
+                                content: text,
+                                tags: vec!["archive-entry".to_string()],
+                                metadata: json!({
+                                    "archive_entry": {
+                                        "archive": archive_name,
+                                        "path": path,
+                                        "depth": depth,
+                                        "strategy": strategy.to_string(),
+                                        "content_type": content_type,
+                                    }
+                                }),
+                            }),
+                        }
+                    }
+                    "extracted"
+                }
+                Err(e) => {
+                    entry["error_reason"] = json!(archive_error_reason_code(&e.to_string()));
+                    "failed"
+                }
+            }
+        };
+        entry["status"] = json!(status);
+
+        // Top-level entries also update their row in the file listing
+        if depth == 1 {
+            if let Some(file) = result.metadata["files"]
+                .as_array_mut()
+                .and_then(|files| files.iter_mut().find(|f| f["name"] == path.as_str()))
+            {
+                file["extracted"] = json!(status == "extracted");
+                file["strategy"] = json!(strategy.to_string());
+                if status == "extracted" {
+                    if let Some(obj) = file.as_object_mut() {
+                        obj.remove("reason");
+                    }
+                } else {
+                    file["reason"] = json!(status);
+                }
+            }
+        }
+        provenance.push(entry);
+    }
+
+    if !sections.is_empty() {
+        let mut text = result.extracted_text.take().unwrap_or_default();
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text.push_str(&sections.join("\n"));
+        result.extracted_text = Some(text);
+    }
+    result.metadata["member_mode"] = json!(match mode {
+        MemberMode::Sections => "sections",
+        MemberMode::Notes => "notes",
+    });
+    result.metadata["members_extracted"] = json!(extracted);
+    result.metadata["members"] = JsonValue::Array(provenance);
+    result
+}
+
 // ── Tests ─────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -1316,6 +1628,200 @@ mod tests {
         }
     }
 
+    // ── Recursive extraction ──────────────────────────────────────────────────
+
+    const NOTEBOOK: &[u8] = br##"{"nbformat": 4, "metadata": {}, "cells": [
+        {"cell_type": "markdown", "source": "# Deep notebook"}]}"##;
+
+    /// Outer archive: a text file, a CSV, and a nested zip holding a notebook.
+    fn nested_zip() -> Vec<u8> {
+        let inner = make_zip(&[
+            ("analysis.ipynb", Some(NOTEBOOK)),
+            ("inner.txt", Some(b"inner text")),
+        ]);
+        make_zip(&[
+            ("readme.txt", Some(b"outer text")),
+            ("data/sales.csv", Some(b"region,total\nnorth,10\n")),
+            ("bundle.zip", Some(&inner)),
+        ])
+    }
+
+    fn registry() -> ExtractionRegistry {
+        let mut registry = ExtractionRegistry::new();
+        registry.register(std::sync::Arc::new(ArchiveAdapter));
+        registry.register(std::sync::Arc::new(
+            crate::adapters::SpreadsheetAdapter::new(),
+        ));
+        registry.register(std::sync::Arc::new(crate::adapters::NotebookAdapter));
+        registry
+    }
+
+    async fn expand(registry: &ExtractionRegistry, config: JsonValue) -> ExtractionResult {
+        let mut config = config;
+        config[ARCHIVE_DEPTH_KEY] = serde_json::json!(0);
+        let result = ArchiveAdapter
+            .extract(&nested_zip(), "upload.zip", "application/zip", &config)
+            .await
+            .unwrap();
+        expand_archive_members(registry, "upload.zip", result, &config).await
+    }
+
+    fn member<'a>(result: &'a ExtractionResult, path: &str) -> &'a JsonValue {
+        result.metadata["members"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|m| m["path"] == path)
+            .unwrap_or_else(|| panic!("no member {path}"))
+    }
+
+    #[tokio::test]
+    async fn test_members_returned_only_when_recursion_requested() {
+        let plain = ArchiveAdapter
+            .extract(
+                &nested_zip(),
+                "upload.zip",
+                "application/zip",
+                &serde_json::json!({}),
+            )
+            .await
+            .unwrap();
+        assert!(plain.derived_files.is_empty());
+        let files = plain.metadata["files"].as_array().unwrap();
+        let bundle = files.iter().find(|f| f["name"] == "bundle.zip").unwrap();
+        assert_eq!(bundle["reason"], "binary");
+
+        let recursive = ArchiveAdapter
+            .extract(
+                &nested_zip(),
+                "upload.zip",
+                "application/zip",
+                &serde_json::json!({ ARCHIVE_DEPTH_KEY: 0 }),
+            )
+            .await
+            .unwrap();
+        let names: Vec<_> = recursive
+            .derived_files
+            .iter()
+            .map(|df| df.metadata.as_ref().unwrap()["archive_path"].clone())
+            .collect();
+        assert_eq!(names, ["data/sales.csv", "bundle.zip"]);
+        assert!(recursive
+            .derived_files
+            .iter()
+            .all(|df| df.derivation_type == "archive_entry"
+                && df.metadata.as_ref().unwrap()["archive_depth"] == 1));
+        // Members are not listed as skipped in the text
+        let text = recursive.extracted_text.unwrap();
+        assert!(!text.contains("bundle.zip"));
+        assert!(text.contains("outer text"));
+    }
+
+    #[tokio::test]
+    async fn test_expand_members_as_sections_with_provenance() {
+        let result = expand(&registry(), serde_json::json!({})).await;
+        let text = result.extracted_text.as_deref().unwrap();
+
+        assert!(text.contains("outer text"));
+        assert!(
+            text.contains("--- data/sales.csv [spreadsheet] ---"),
+            "got:\n{text}"
+        );
+        assert!(text.contains("--- bundle.zip [archive] ---"));
+        assert!(text.contains("inner text"));
+        assert!(text.contains("--- bundle.zip/analysis.ipynb [notebook] ---"));
+        assert!(text.contains("# Deep notebook"));
+        assert!(result.derived_files.is_empty(), "members are not stored");
+        assert!(result.derived_notes.is_empty());
+
+        assert_eq!(result.metadata["member_mode"], "sections");
+        assert_eq!(result.metadata["members_extracted"], 3);
+        let nb = member(&result, "bundle.zip/analysis.ipynb");
+        assert_eq!(nb["depth"], 2);
+        assert_eq!(nb["strategy"], "notebook");
+        assert_eq!(nb["status"], "extracted");
+
+        let files = result.metadata["files"].as_array().unwrap();
+        let csv = files
+            .iter()
+            .find(|f| f["name"] == "data/sales.csv")
+            .unwrap();
+        assert_eq!(csv["extracted"], true);
+        assert_eq!(csv["strategy"], "spreadsheet");
+        assert!(csv.get("reason").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_expand_members_as_notes() {
+        let result = expand(&registry(), serde_json::json!({ "member_mode": "notes" })).await;
+
+        assert!(!result.extracted_text.unwrap().contains("[notebook]"));
+        let titles: Vec<_> = result.derived_notes.iter().map(|n| &n.title).collect();
+        assert_eq!(
+            titles,
+            [
+                "data/sales.csv (upload.zip)",
+                "bundle.zip (upload.zip)",
+                "bundle.zip/analysis.ipynb (upload.zip)"
+            ]
+        );
+        let nb = &result.derived_notes[2];
+        assert_eq!(nb.tags, ["archive-entry"]);
+        assert_eq!(
+            nb.metadata["archive_entry"]["path"],
+            "bundle.zip/analysis.ipynb"
+        );
+        assert_eq!(nb.metadata["archive_entry"]["archive"], "upload.zip");
+        assert_eq!(nb.metadata["archive_entry"]["depth"], 2);
+        assert!(nb.content.contains("# Deep notebook"));
+    }
+
+    #[tokio::test]
+    async fn test_expand_members_respects_nesting_limit() {
+        let result = expand(&registry(), serde_json::json!({ "max_nesting": 0 })).await;
+
+        assert_eq!(member(&result, "bundle.zip")["status"], "max_nesting");
+        assert_eq!(member(&result, "data/sales.csv")["status"], "extracted");
+        assert!(!result.extracted_text.unwrap().contains("inner text"));
+        let files = result.metadata["files"].as_array().unwrap();
+        let bundle = files.iter().find(|f| f["name"] == "bundle.zip").unwrap();
+        assert_eq!(bundle["extracted"], false);
+        assert_eq!(bundle["reason"], "max_nesting");
+    }
+
+    #[tokio::test]
+    async fn test_expand_members_without_adapter() {
+        let mut registry = ExtractionRegistry::new();
+        registry.register(std::sync::Arc::new(ArchiveAdapter));
+        let result = expand(&registry, serde_json::json!({})).await;
+
+        assert_eq!(member(&result, "data/sales.csv")["status"], "no_adapter");
+        assert_eq!(
+            member(&result, "bundle.zip/analysis.ipynb")["status"],
+            "no_adapter"
+        );
+        assert_eq!(result.metadata["members_extracted"], 1);
+    }
+
+    #[test]
+    fn test_member_strategy() {
+        assert_eq!(
+            member_strategy("a/report.pdf"),
+            Some(ExtractionStrategy::PdfText)
+        );
+        assert_eq!(
+            member_strategy("x.tar.gz"),
+            Some(ExtractionStrategy::Archive)
+        );
+        assert_eq!(
+            member_strategy("nb.ipynb"),
+            Some(ExtractionStrategy::Notebook)
+        );
+        assert_eq!(member_strategy("photo.jpg"), None);
+        assert_eq!(member_strategy("notes.txt"), None);
+        assert_eq!(member_strategy("Makefile"), None);
+    }
+
     #[test]
     fn entry_info_debug_redacts_names_paths_and_skip_reasons() {
         let entry = EntryInfo {
@@ -253,6 +253,7 @@ impl ExtractionAdapter for AudioTranscribeAdapter {
             ai_description: None,
             preview_data: None,
             derived_files: vec![],
+            derived_notes: vec![],
         })
     }
 
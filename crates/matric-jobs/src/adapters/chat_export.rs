@@ -0,0 +1,1070 @@
+//! Chat export adapter — messages, conversation windows and threads from Slack and WhatsApp.
+//!
+//! Supports:
+//! - Slack channel exports — a JSON array of `"type": "message"` events, as
+//!   found in each per-day file of a workspace export. Replies are grouped by
+//!   `thread_ts`, and `<@U…>` mentions are resolved from the profiles embedded
+//!   in the export.
+//! - WhatsApp "Export chat" text files — iOS (`[31/12/2023, 21:15:02] Name: …`)
+//!   and Android (`31/12/23, 21:15 - Name: …`) line formats. Day/month order is
+//!   inferred from the whole file; multi-line messages are joined.
+//!
+//! The extracted text is Markdown with one `##` section per conversation
+//! window: a new window starts when the gap between messages exceeds
+//! `CHAT_WINDOW_GAP_MINUTES` (default 30) or the window reaches
+//! `CHAT_WINDOW_MAX_MESSAGES` (default 50). Headings let the semantic chunker
+//! keep each window together when embedding.
+//!
+//! Each Slack thread becomes a derived note (up to `CHAT_MAX_THREAD_NOTES`,
+//! default 100) whose metadata lists its participants; the channel transcript
+//! keeps the thread's root message. WhatsApp chats have no reply threads, so
+//! the participant summary is merged into the owning note via `note_metadata`.
+//!
+//! Slack timestamps are UTC; WhatsApp timestamps are the exporting device's
+//! local time and are reported without an offset.
+
+use std::collections::{BTreeMap, HashMap};
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde_json::Value as JsonValue;
+
+use matric_core::{
+    defaults::TEXT_EXTRACTION_MAX_BYTES, DerivedNote, ExtractionAdapter, ExtractionResult,
+    ExtractionStrategy, Result, SLACK_EXPORT_MIME, WHATSAPP_CHAT_MIME,
+};
+
+/// Default gap between messages that starts a new conversation window.
+const DEFAULT_WINDOW_GAP_MINUTES: i64 = 30;
+
+/// Default maximum messages per conversation window.
+const DEFAULT_WINDOW_MAX_MESSAGES: usize = 50;
+
+/// Default maximum thread notes created from one export.
+const DEFAULT_MAX_THREAD_NOTES: usize = 100;
+
+/// Maximum per-message entries listed in the attachment metadata.
+const MAX_MESSAGE_METADATA: usize = 2_000;
+
+/// Characters of a thread's root message used in the thread note title.
+const THREAD_TITLE_CHARS: usize = 60;
+
+/// Slack message subtypes that carry no conversation content.
+const SLACK_SKIPPED_SUBTYPES: &[&str] = &[
+    "channel_join",
+    "channel_leave",
+    "channel_topic",
+    "channel_purpose",
+    "channel_name",
+    "group_join",
+    "group_leave",
+];
+
+/// Tag applied to notes created from chat threads.
+const THREAD_NOTE_TAG: &str = "chat-thread";
+
+/// Read a limit from an env var, falling back to the provided default.
+fn env_limit<T: std::str::FromStr>(var: &str, default: T) -> T {
+    std::env::var(var)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+fn chat_error(platform: &str, phase: &str, reason: &str) -> matric_core::Error {
+    matric_core::Error::InvalidInput(format!(
+        "Chat export extraction failed; platform={platform}; phase={phase}; reason={reason}"
+    ))
+}
+
+// ── Chat model ───────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Platform {
+    Slack,
+    WhatsApp,
+}
+
+impl Platform {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Slack => "slack",
+            Self::WhatsApp => "whatsapp",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Slack => "Slack",
+            Self::WhatsApp => "WhatsApp",
+        }
+    }
+
+    fn timezone(self) -> &'static str {
+        match self {
+            Self::Slack => "UTC",
+            Self::WhatsApp => "local",
+        }
+    }
+}
+
+struct Message {
+    timestamp: NaiveDateTime,
+    speaker: String,
+    text: String,
+    /// Slack `thread_ts` of the thread this message belongs to, if any.
+    thread: Option<String>,
+    /// True for a Slack thread's root message.
+    thread_root: bool,
+}
+
+struct Chat {
+    platform: Platform,
+    name: String,
+    messages: Vec<Message>,
+}
+
+fn format_timestamp(ts: &NaiveDateTime) -> String {
+    ts.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+// ── Slack ────────────────────────────────────────────────────────────────────
+
+/// Parse a Slack `ts` value (`"1512085950.000216"`) as a UTC timestamp.
+fn parse_slack_ts(ts: &str) -> Option<NaiveDateTime> {
+    let (secs, frac) = ts.split_once('.').unwrap_or((ts, "0"));
+    let secs: i64 = secs.parse().ok()?;
+    let micros: u32 = format!("{frac:0<6}").get(..6)?.parse().ok()?;
+    chrono::DateTime::from_timestamp(secs, micros * 1_000).map(|dt| dt.naive_utc())
+}
+
+/// Best display name for a Slack message author.
+fn slack_author(message: &JsonValue) -> Option<String> {
+    let profile = message.get("user_profile");
+    [
+        profile.and_then(|p| p.get("display_name")),
+        profile.and_then(|p| p.get("real_name")),
+        message.get("user_name"),
+        message.get("username"),
+        message.get("bot_profile").and_then(|p| p.get("name")),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(JsonValue::as_str)
+    .map(str::trim)
+    .find(|name| !name.is_empty())
+    .map(str::to_string)
+}
+
+/// Rewrite Slack markup: `<@U1>` → `@name`, `<#C1|general>` → `#general`,
+/// `<https://x|label>` → `label (https://x)`, and HTML entities.
+fn clean_slack_text(text: &str, users: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let inner = &rest[start + 1..start + end];
+        let (target, label) = match inner.split_once('|') {
+            Some((target, label)) => (target, Some(label)),
+            None => (inner, None),
+        };
+        if let Some(user) = target.strip_prefix('@') {
+            out.push('@');
+            out.push_str(
+                label
+                    .or(users.get(user).map(String::as_str))
+                    .unwrap_or(user),
+            );
+        } else if let Some(channel) = target.strip_prefix('#') {
+            out.push('#');
+            out.push_str(label.unwrap_or(channel));
+        } else if let Some(special) = target.strip_prefix('!') {
+            out.push('@');
+            out.push_str(label.unwrap_or(special));
+        } else {
+            match label {
+                Some(label) if label != target => out.push_str(&format!("{label} ({target})")),
+                _ => out.push_str(target),
+            }
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+fn parse_slack(data: &[u8], name: String) -> Result<Chat> {
+    let events: Vec<JsonValue> = serde_json::from_slice(data)
+        .map_err(|_| chat_error("slack", "parse", "invalid_json_array"))?;
+
+    let mut users: HashMap<String, String> = HashMap::new();
+    for event in &events {
+        if let (Some(id), Some(author)) = (
+            event.get("user").and_then(JsonValue::as_str),
+            slack_author(event),
+        ) {
+            users.entry(id.to_string()).or_insert(author);
+        }
+    }
+
+    let mut messages = Vec::new();
+    for event in &events {
+        if event.get("type").and_then(JsonValue::as_str) != Some("message") {
+            continue;
+        }
+        if event
+            .get("subtype")
+            .and_then(JsonValue::as_str)
+            .is_some_and(|subtype| SLACK_SKIPPED_SUBTYPES.contains(&subtype))
+        {
+            continue;
+        }
+        let Some(ts) = event.get("ts").and_then(JsonValue::as_str) else {
+            continue;
+        };
+        let Some(timestamp) = parse_slack_ts(ts) else {
+            continue;
+        };
+        let text = clean_slack_text(
+            event.get("text").and_then(JsonValue::as_str).unwrap_or(""),
+            &users,
+        );
+        if text.trim().is_empty() {
+            continue;
+        }
+        let speaker = slack_author(event)
+            .or_else(|| {
+                event
+                    .get("user")
+                    .and_then(JsonValue::as_str)
+                    .map(|id| users.get(id).cloned().unwrap_or_else(|| id.to_string()))
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        let thread = event
+            .get("thread_ts")
+            .and_then(JsonValue::as_str)
+            .map(str::to_string);
+        let thread_root = thread.as_deref() == Some(ts);
+        messages.push(Message {
+            timestamp,
+            speaker,
+            text: text.trim().to_string(),
+            thread,
+            thread_root,
+        });
+    }
+    messages.sort_by_key(|m| m.timestamp);
+
+    Ok(Chat {
+        platform: Platform::Slack,
+        name,
+        messages,
+    })
+}
+
+// ── WhatsApp ─────────────────────────────────────────────────────────────────
+
+struct WhatsAppLine<'a> {
+    date: [u32; 3],
+    year_first: bool,
+    hour: u32,
+    minute: u32,
+    second: u32,
+    rest: &'a str,
+}
+
+/// Parse the `h:mm[:ss][ AM|PM]` part of a WhatsApp header.
+fn parse_whatsapp_time(time: &str) -> Option<(u32, u32, u32)> {
+    let time = time.replace(['\u{202f}', '\u{a0}'], " ");
+    let lower = time.trim().to_ascii_lowercase();
+    let (clock, meridiem) = if let Some(clock) = lower.strip_suffix("pm") {
+        (clock.trim(), Some(true))
+    } else if let Some(clock) = lower.strip_suffix("am") {
+        (clock.trim(), Some(false))
+    } else {
+        (lower.as_str(), None)
+    };
+    let mut parts = clock.split(':').map(|p| p.parse::<u32>().ok());
+    let mut hour = parts.next()??;
+    let minute = parts.next()??;
+    let second = parts.next().flatten().unwrap_or(0);
+    match meridiem {
+        Some(true) if hour < 12 => hour += 12,
+        Some(false) if hour == 12 => hour = 0,
+        _ => {}
+    }
+    (hour < 24 && minute < 60 && second < 60).then_some((hour, minute, second))
+}
+
+/// Split a WhatsApp message header into date, time and the remainder.
+fn parse_whatsapp_line(line: &str) -> Option<WhatsAppLine<'_>> {
+    let line = line.trim_start_matches('\u{200e}');
+    let (stamp, rest) = match line.strip_prefix('[') {
+        Some(body) => body.split_once("] ")?,
+        None => line.split_once(" - ")?,
+    };
+    let (date, time) = stamp.split_once(',')?;
+    let parts: Vec<u32> = date
+        .trim()
+        .split(['/', '.', '-'])
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let date: [u32; 3] = parts.try_into().ok()?;
+    let (hour, minute, second) = parse_whatsapp_time(time)?;
+    Some(WhatsAppLine {
+        date,
+        year_first: date[0] > 31,
+        hour,
+        minute,
+        second,
+        rest: rest.trim_start_matches('\u{200e}'),
+    })
+}
+
+/// Chat name from the export filename (`WhatsApp Chat with Alice.txt` → `Alice`).
+fn whatsapp_chat_name(filename: &str) -> String {
+    let stem = filename
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(filename)
+        .trim_end_matches(".txt");
+    let name = stem
+        .strip_prefix("WhatsApp Chat with ")
+        .or_else(|| stem.strip_prefix("WhatsApp Chat - "))
+        .unwrap_or(stem)
+        .trim();
+    if name.is_empty() || name == "_chat" {
+        "WhatsApp chat".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn parse_whatsapp(data: &[u8], name: String) -> Result<Chat> {
+    let text = String::from_utf8_lossy(data);
+    let text = text.trim_start_matches('\u{feff}');
+
+    // First pass: headers and continuation lines. System lines (no "Name: ")
+    // are dropped along with their continuations.
+    let mut raw: Vec<(WhatsAppLine<'_>, String)> = Vec::new();
+    let mut in_system_line = false;
+    for line in text.lines() {
+        match parse_whatsapp_line(line) {
+            Some(header) => match header.rest.split_once(": ") {
+                Some((_, body)) => {
+                    let body = body.to_string();
+                    raw.push((header, body));
+                    in_system_line = false;
+                }
+                None => in_system_line = true,
+            },
+            None if !in_system_line => {
+                if let Some((_, body)) = raw.last_mut() {
+                    body.push('\n');
+                    body.push_str(line);
+                }
+            }
+            None => {}
+        }
+    }
+    if raw.is_empty() {
+        return Err(chat_error("whatsapp", "parse", "no_messages"));
+    }
+
+    // Day/month order: any first component above 12 means day-first, any
+    // second component above 12 means month-first. Day-first otherwise.
+    let month_first = !raw.iter().any(|(h, _)| !h.year_first && h.date[0] > 12)
+        && raw.iter().any(|(h, _)| !h.year_first && h.date[1] > 12);
+
+    let messages = raw
+        .into_iter()
+        .filter_map(|(header, body)| {
+            let [a, b, c] = header.date;
+            let (year, month, day) = if header.year_first {
+                (a, b, c)
+            } else if month_first {
+                (c, a, b)
+            } else {
+                (c, b, a)
+            };
+            let year = if year < 100 { year + 2000 } else { year };
+            let timestamp = NaiveDate::from_ymd_opt(year as i32, month, day)?.and_hms_opt(
+                header.hour,
+                header.minute,
+                header.second,
+            )?;
+            let (speaker, _) = header.rest.split_once(": ")?;
+            let text = body.trim().to_string();
+            (!text.is_empty()).then(|| Message {
+                timestamp,
+                speaker: speaker.trim().to_string(),
+                text,
+                thread: None,
+                thread_root: false,
+            })
+        })
+        .collect();
+
+    Ok(Chat {
+        platform: Platform::WhatsApp,
+        name,
+        messages,
+    })
+}
+
+// ── Rendering ────────────────────────────────────────────────────────────────
+
+struct WindowLimits {
+    gap_minutes: i64,
+    max_messages: usize,
+}
+
+/// Split messages into conversation windows by time gap and size.
+fn conversation_windows<'a>(
+    messages: &[&'a Message],
+    limits: &WindowLimits,
+) -> Vec<Vec<&'a Message>> {
+    let mut windows: Vec<Vec<&Message>> = Vec::new();
+    for message in messages {
+        let starts_new = match windows.last().and_then(|w| w.last()) {
+            None => true,
+            Some(prev) => {
+                (message.timestamp - prev.timestamp).num_minutes() > limits.gap_minutes
+                    || windows
+                        .last()
+                        .is_some_and(|w| w.len() >= limits.max_messages)
+            }
+        };
+        if starts_new {
+            windows.push(Vec::new());
+        }
+        if let Some(window) = windows.last_mut() {
+            window.push(message);
+        }
+    }
+    windows
+}
+
+/// Escape a message line that Markdown would otherwise read as a heading.
+fn escape_line(line: &str) -> String {
+    if line.trim_start().starts_with('#') {
+        format!("\\{}", line.trim_start())
+    } else {
+        line.to_string()
+    }
+}
+
+fn render_message(message: &Message, reply_count: Option<usize>) -> String {
+    let body = message
+        .text
+        .lines()
+        .map(escape_line)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut line = format!(
+        "**{}** ({}): {}",
+        message.speaker.replace('*', "\\*"),
+        message.timestamp.format("%H:%M"),
+        body
+    );
+    if let Some(count) = reply_count.filter(|c| *c > 0) {
+        let noun = if count == 1 { "reply" } else { "replies" };
+        line.push_str(&format!(" _({count} {noun} in thread)_"));
+    }
+    line
+}
+
+fn window_heading(window: &[&Message]) -> String {
+    let (Some(first), Some(last)) = (window.first(), window.last()) else {
+        return String::new();
+    };
+    let end = if first.timestamp.date() == last.timestamp.date() {
+        last.timestamp.format("%H:%M").to_string()
+    } else {
+        last.timestamp.format("%Y-%m-%d %H:%M").to_string()
+    };
+    format!("{} – {}", first.timestamp.format("%Y-%m-%d %H:%M"), end)
+}
+
+/// Participants in first-seen order with their message counts.
+fn participants(messages: &[&Message]) -> Vec<(String, usize)> {
+    let mut order: Vec<String> = Vec::new();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for message in messages {
+        let count = counts.entry(message.speaker.as_str()).or_insert(0);
+        if *count == 0 {
+            order.push(message.speaker.clone());
+        }
+        *count += 1;
+    }
+    order
+        .into_iter()
+        .map(|name| {
+            let count = counts[name.as_str()];
+            (name, count)
+        })
+        .collect()
+}
+
+fn participants_json(participants: &[(String, usize)]) -> JsonValue {
+    participants
+        .iter()
+        .map(|(name, count)| serde_json::json!({ "name": name, "message_count": count }))
+        .collect()
+}
+
+/// Render a transcript: title, summary lines, then one section per window.
+fn render_transcript(
+    title: &str,
+    messages: &[&Message],
+    limits: &WindowLimits,
+    reply_counts: &HashMap<&str, usize>,
+) -> (String, Vec<JsonValue>) {
+    let people = participants(messages);
+    let mut md = format!("# {title}\n\n");
+    md.push_str(&format!(
+        "**Participants:** {}\n",
+        people
+            .iter()
+            .map(|(name, count)| format!("{name} ({count})"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+    if let (Some(first), Some(last)) = (messages.first(), messages.last()) {
+        md.push_str(&format!(
+            "**Period:** {} – {}\n",
+            first.timestamp.format("%Y-%m-%d %H:%M"),
+            last.timestamp.format("%Y-%m-%d %H:%M")
+        ));
+    }
+    md.push_str(&format!("**Messages:** {}\n", messages.len()));
+
+    let windows = conversation_windows(messages, limits);
+    let mut window_meta = Vec::with_capacity(windows.len());
+    for (index, window) in windows.iter().enumerate() {
+        md.push_str(&format!("\n## {}\n\n", window_heading(window)));
+        let lines: Vec<String> = window
+            .iter()
+            .map(|m| {
+                let replies = m
+                    .thread
+                    .as_deref()
+                    .filter(|_| m.thread_root)
+                    .and_then(|t| reply_counts.get(t).copied());
+                render_message(m, replies)
+            })
+            .collect();
+        md.push_str(&lines.join("\n\n"));
+        md.push('\n');
+        if let (Some(first), Some(last)) = (window.first(), window.last()) {
+            window_meta.push(serde_json::json!({
+                "index": index,
+                "start": format_timestamp(&first.timestamp),
+                "end": format_timestamp(&last.timestamp),
+                "message_count": window.len(),
+                "participants": participants(window)
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            }));
+        }
+    }
+    (md, window_meta)
+}
+
+/// Thread note title: channel plus the start of the root message.
+fn thread_title(channel: &str, root: &Message) -> String {
+    let first_line = root.text.lines().next().unwrap_or("").trim();
+    let mut snippet: String = first_line.chars().take(THREAD_TITLE_CHARS).collect();
+    if first_line.chars().count() > THREAD_TITLE_CHARS {
+        snippet.push('…');
+    }
+    format!("#{channel}: {snippet}")
+}
+
+// ── Adapter ──────────────────────────────────────────────────────────────────
+
+/// Adapter for Slack JSON and WhatsApp TXT chat exports.
+pub struct ChatExportAdapter;
+
+impl ChatExportAdapter {
+    fn detect_platform(data: &[u8], mime_type: &str) -> Option<Platform> {
+        if mime_type == SLACK_EXPORT_MIME {
+            return Some(Platform::Slack);
+        }
+        if mime_type == WHATSAPP_CHAT_MIME {
+            return Some(Platform::WhatsApp);
+        }
+        // Fall back to sniffing so re-extraction of older uploads works too.
+        match matric_core::detect_content_type("", data, mime_type).as_str() {
+            SLACK_EXPORT_MIME => Some(Platform::Slack),
+            WHATSAPP_CHAT_MIME => Some(Platform::WhatsApp),
+            _ => None,
+        }
+    }
+
+    fn build_result(
+        chat: Chat,
+        limits: &WindowLimits,
+        max_thread_notes: usize,
+    ) -> ExtractionResult {
+        let platform = chat.platform;
+
+        // Slack: thread replies leave the channel transcript for their own notes.
+        let mut threads: BTreeMap<&str, Vec<&Message>> = BTreeMap::new();
+        let mut channel: Vec<&Message> = Vec::new();
+        for message in &chat.messages {
+            match message.thread.as_deref() {
+                Some(thread) => {
+                    threads.entry(thread).or_default().push(message);
+                    if message.thread_root {
+                        channel.push(message);
+                    }
+                }
+                None => channel.push(message),
+            }
+        }
+        threads.retain(|_, messages| messages.len() > 1);
+        let reply_counts: HashMap<&str, usize> = threads
+            .iter()
+            .map(|(thread, messages)| {
+                let replies = messages.iter().filter(|m| !m.thread_root).count();
+                (*thread, replies)
+            })
+            .collect();
+
+        let title = match platform {
+            Platform::Slack => format!("#{} ({})", chat.name, platform.label()),
+            Platform::WhatsApp => format!("{} ({})", chat.name, platform.label()),
+        };
+        let (transcript, windows) = render_transcript(&title, &channel, limits, &reply_counts);
+
+        let mut derived_notes = Vec::new();
+        let mut thread_meta = Vec::new();
+        for (thread, messages) in threads.iter().take(max_thread_notes) {
+            let Some(root) = messages.iter().find(|m| m.thread_root).or(messages.first()) else {
+                continue;
+            };
+            let note_title = thread_title(&chat.name, root);
+            let (content, _) = render_transcript(&note_title, messages, limits, &HashMap::new());
+            let people = participants(messages);
+            let (first, last) = (
+                &messages[0].timestamp,
+                &messages[messages.len() - 1].timestamp,
+            );
+            thread_meta.push(serde_json::json!({
+                "thread_id": thread,
+                "title": note_title,
+                "message_count": messages.len(),
+                "participants": people.iter().map(|(n, _)| n).collect::<Vec<_>>(),
+            }));
+            derived_notes.push(DerivedNote {
+                title: note_title,
+                content,
+                tags: vec![THREAD_NOTE_TAG.to_string()],
+                metadata: serde_json::json!({
+                    "chat_thread": {
+                        "platform": platform.as_str(),
+                        "channel": chat.name,
+                        "thread_id": thread,
+                        "started_at": format_timestamp(first),
+                        "ended_at": format_timestamp(last),
+                        "timezone": platform.timezone(),
+                        "message_count": messages.len(),
+                        "participants": participants_json(&people),
+                    }
+                }),
+            });
+        }
+
+        let all: Vec<&Message> = chat.messages.iter().collect();
+        let people = participants(&all);
+        let message_meta: Vec<JsonValue> = chat
+            .messages
+            .iter()
+            .take(MAX_MESSAGE_METADATA)
+            .enumerate()
+            .map(|(index, m)| {
+                let mut entry = serde_json::json!({
+                    "index": index,
+                    "speaker": m.speaker,
+                    "timestamp": format_timestamp(&m.timestamp),
+                    "char_count": m.text.chars().count(),
+                });
+                if let Some(thread) = &m.thread {
+                    entry["thread_id"] = thread.as_str().into();
+                }
+                entry
+            })
+            .collect();
+
+        let mut chat_summary = serde_json::json!({
+            "platform": platform.as_str(),
+            "name": chat.name,
+            "message_count": chat.messages.len(),
+            "timezone": platform.timezone(),
+            "participants": participants_json(&people),
+        });
+        if let (Some(first), Some(last)) = (chat.messages.first(), chat.messages.last()) {
+            chat_summary["started_at"] = format_timestamp(&first.timestamp).into();
+            chat_summary["ended_at"] = format_timestamp(&last.timestamp).into();
+        }
+
+        let metadata = serde_json::json!({
+            "format": platform.as_str(),
+            "chat": chat_summary,
+            "message_count": chat.messages.len(),
+            "participant_count": people.len(),
+            "window_count": windows.len(),
+            "windows": windows,
+            "thread_count": threads.len(),
+            "threads": thread_meta,
+            "messages": message_meta,
+            "messages_truncated": chat.messages.len() > MAX_MESSAGE_METADATA,
+            "char_count": transcript.chars().count(),
+            "note_metadata": { "chat": chat_summary },
+        });
+
+        ExtractionResult {
+            extracted_text: (!chat.messages.is_empty()).then_some(transcript),
+            metadata,
+            ai_description: None,
+            preview_data: None,
+            derived_files: vec![],
+            derived_notes,
+        }
+    }
+}
+
+#[async_trait]
+impl ExtractionAdapter for ChatExportAdapter {
+    fn strategy(&self) -> ExtractionStrategy {
+        ExtractionStrategy::ChatExport
+    }
+
+    async fn extract(
+        &self,
+        data: &[u8],
+        filename: &str,
+        mime_type: &str,
+        config: &JsonValue,
+    ) -> Result<ExtractionResult> {
+        if data.is_empty() {
+            return Err(matric_core::Error::InvalidInput(
+                "Cannot extract messages from empty chat export".to_string(),
+            ));
+        }
+
+        let max_bytes = config
+            .get("max_bytes")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(TEXT_EXTRACTION_MAX_BYTES);
+        if data.len() > max_bytes {
+            return Err(chat_error("any", "read", "too_large"));
+        }
+
+        let limits = WindowLimits {
+            gap_minutes: config
+                .get("window_gap_minutes")
+                .and_then(|v| v.as_i64())
+                .unwrap_or_else(|| {
+                    env_limit("CHAT_WINDOW_GAP_MINUTES", DEFAULT_WINDOW_GAP_MINUTES)
+                }),
+            max_messages: config
+                .get("window_max_messages")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize)
+                .unwrap_or_else(|| {
+                    env_limit("CHAT_WINDOW_MAX_MESSAGES", DEFAULT_WINDOW_MAX_MESSAGES)
+                })
+                .max(1),
+        };
+        let max_thread_notes = env_limit("CHAT_MAX_THREAD_NOTES", DEFAULT_MAX_THREAD_NOTES);
+
+        let stem = filename
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or(filename)
+            .rsplit_once('.')
+            .map_or(filename, |(stem, _)| stem)
+            .to_string();
+        let chat = match Self::detect_platform(data, mime_type) {
+            Some(Platform::Slack) => parse_slack(data, stem)?,
+            Some(Platform::WhatsApp) => parse_whatsapp(data, whatsapp_chat_name(filename))?,
+            None => return Err(chat_error("unknown", "detect", "unrecognized_format")),
+        };
+
+        Ok(Self::build_result(chat, &limits, max_thread_notes))
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        Ok(true) // Pure Rust — no external dependencies
+    }
+
+    fn name(&self) -> &str {
+        "chat_export"
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLACK_EXPORT: &str = r#"[
+        {"type": "message", "subtype": "channel_join", "user": "U3", "text": "<@U3> has joined the channel", "ts": "1704067000.000100"},
+        {"type": "message", "user": "U1", "text": "Release is out, notes at <https://example.com/notes|the wiki>", "ts": "1704067200.000100",
+         "user_profile": {"real_name": "Alice Smith", "display_name": "alice"}},
+        {"type": "message", "user": "U2", "text": "Did the migration run?", "ts": "1704067260.000200", "thread_ts": "1704067260.000200",
+         "user_profile": {"real_name": "Bob Jones", "display_name": ""}},
+        {"type": "message", "user": "U1", "text": "Yes, <@U2> it finished", "ts": "1704067320.000300", "thread_ts": "1704067260.000200", "parent_user_id": "U2"},
+        {"type": "message", "user": "U2", "text": "Great &amp; thanks", "ts": "1704067380.000400", "thread_ts": "1704067260.000200", "parent_user_id": "U2"},
+        {"type": "message", "user": "U2", "text": "Lunch?", "ts": "1704078000.000500"}
+    ]"#;
+
+    const WHATSAPP_IOS: &str =
+        "\u{200e}[31/12/2023, 21:15:02] Alice: Are we still on for tomorrow?\n\
+        [31/12/2023, 21:16:40] Bob: Yes\n\
+        bring the charger\n\
+        [31/12/2023, 21:17:00] \u{200e}Bob added Carol\n\
+        [01/01/2024, 09:02:11] Carol: # not a heading\n";
+
+    fn limits() -> WindowLimits {
+        WindowLimits {
+            gap_minutes: DEFAULT_WINDOW_GAP_MINUTES,
+            max_messages: DEFAULT_WINDOW_MAX_MESSAGES,
+        }
+    }
+
+    async fn extract(data: &str, filename: &str, mime: &str) -> ExtractionResult {
+        ChatExportAdapter
+            .extract(data.as_bytes(), filename, mime, &serde_json::json!({}))
+            .await
+            .unwrap()
+    }
+
+    // ── Slack ─────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_parse_slack_ts() {
+        let ts = parse_slack_ts("1704067200.000100").unwrap();
+        assert_eq!(format_timestamp(&ts), "2024-01-01T00:00:00");
+        assert!(parse_slack_ts("not-a-ts").is_none());
+    }
+
+    #[test]
+    fn test_clean_slack_text() {
+        let users = HashMap::from([("U1".to_string(), "alice".to_string())]);
+        assert_eq!(
+            clean_slack_text(
+                "hi <@U1> in <#C9|general> see <https://x.test|docs> &lt;3",
+                &users
+            ),
+            "hi @alice in #general see docs (https://x.test) <3"
+        );
+        assert_eq!(
+            clean_slack_text("<https://x.test> <!here>", &users),
+            "https://x.test @here"
+        );
+        assert_eq!(clean_slack_text("a < b", &users), "a < b");
+    }
+
+    #[test]
+    fn test_parse_slack_messages() {
+        let chat = parse_slack(SLACK_EXPORT.as_bytes(), "general".into()).unwrap();
+        assert_eq!(chat.messages.len(), 5, "channel_join is skipped");
+        assert_eq!(chat.messages[0].speaker, "alice");
+        assert_eq!(chat.messages[1].speaker, "Bob Jones");
+        assert!(chat.messages[1].thread_root);
+        // Profile-less reply resolves its author and mention from other messages
+        assert_eq!(chat.messages[2].speaker, "alice");
+        assert_eq!(chat.messages[2].text, "Yes, @Bob Jones it finished");
+        assert!(!chat.messages[2].thread_root);
+    }
+
+    #[tokio::test]
+    async fn test_extract_slack_threads_become_notes() {
+        let result = extract(SLACK_EXPORT, "general.json", SLACK_EXPORT_MIME).await;
+
+        let text = result.extracted_text.unwrap();
+        assert!(text.starts_with("# #general (Slack)"), "got:\n{text}");
+        assert!(
+            text.contains("**Bob Jones** (00:01): Did the migration run? _(2 replies in thread)_")
+        );
+        assert!(
+            !text.contains("it finished"),
+            "replies stay in the thread note"
+        );
+
+        assert_eq!(result.derived_notes.len(), 1);
+        let note = &result.derived_notes[0];
+        assert_eq!(note.title, "#general: Did the migration run?");
+        assert!(note
+            .content
+            .contains("**alice** (00:02): Yes, @Bob Jones it finished"));
+        assert!(note.content.contains("Great & thanks"));
+        assert_eq!(note.tags, vec![THREAD_NOTE_TAG.to_string()]);
+        let thread = &note.metadata["chat_thread"];
+        assert_eq!(thread["platform"], "slack");
+        assert_eq!(thread["message_count"], 3);
+        assert_eq!(thread["participants"][0]["name"], "Bob Jones");
+        assert_eq!(thread["participants"][0]["message_count"], 2);
+        assert_eq!(thread["participants"][1]["name"], "alice");
+
+        assert_eq!(result.metadata["thread_count"], 1);
+        assert_eq!(result.metadata["message_count"], 5);
+        assert_eq!(result.metadata["messages"][0]["speaker"], "alice");
+        assert_eq!(
+            result.metadata["messages"][1]["thread_id"],
+            "1704067260.000200"
+        );
+        assert_eq!(
+            result.metadata["note_metadata"]["chat"]["participants"][0]["name"],
+            "alice"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_slack_windows_split_on_gap() {
+        let result = extract(SLACK_EXPORT, "general.json", SLACK_EXPORT_MIME).await;
+        // "Lunch?" comes ~3 hours after the first messages
+        assert_eq!(result.metadata["window_count"], 2);
+        assert_eq!(result.metadata["windows"][1]["message_count"], 1);
+        let text = result.extracted_text.unwrap();
+        assert!(text.contains("## 2024-01-01 00:00 – 00:01"), "got:\n{text}");
+        assert!(text.contains("## 2024-01-01 03:00 – 03:00"));
+    }
+
+    #[test]
+    fn test_windows_split_on_max_messages() {
+        let messages: Vec<Message> = (0..5)
+            .map(|i| Message {
+                timestamp: NaiveDate::from_ymd_opt(2024, 1, 1)
+                    .unwrap()
+                    .and_hms_opt(9, i, 0)
+                    .unwrap(),
+                speaker: "a".into(),
+                text: "x".into(),
+                thread: None,
+                thread_root: false,
+            })
+            .collect();
+        let refs: Vec<&Message> = messages.iter().collect();
+        let windows = conversation_windows(
+            &refs,
+            &WindowLimits {
+                gap_minutes: 30,
+                max_messages: 2,
+            },
+        );
+        assert_eq!(windows.iter().map(Vec::len).collect::<Vec<_>>(), [2, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_max_thread_notes() {
+        let chat = parse_slack(SLACK_EXPORT.as_bytes(), "general".into()).unwrap();
+        let result = ChatExportAdapter::build_result(chat, &limits(), 0);
+        assert!(result.derived_notes.is_empty());
+        assert_eq!(result.metadata["thread_count"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_extract_slack_invalid_json() {
+        let err = ChatExportAdapter
+            .extract(b"[{", "x.json", SLACK_EXPORT_MIME, &serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, matric_core::Error::InvalidInput(msg) if msg.contains("invalid_json_array")),
+            "got: {err:?}"
+        );
+    }
+
+    // ── WhatsApp ──────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_parse_whatsapp_time() {
+        assert_eq!(parse_whatsapp_time("21:15:02"), Some((21, 15, 2)));
+        assert_eq!(parse_whatsapp_time(" 9:15\u{202f}PM"), Some((21, 15, 0)));
+        assert_eq!(parse_whatsapp_time("12:05 am"), Some((0, 5, 0)));
+        assert_eq!(parse_whatsapp_time("25:00"), None);
+    }
+
+    #[test]
+    fn test_whatsapp_chat_name() {
+        assert_eq!(whatsapp_chat_name("WhatsApp Chat with Alice.txt"), "Alice");
+        assert_eq!(whatsapp_chat_name("_chat.txt"), "WhatsApp chat");
+    }
+
+    #[tokio::test]
+    async fn test_extract_whatsapp_ios() {
+        let result = extract(
+            WHATSAPP_IOS,
+            "WhatsApp Chat with Bob.txt",
+            WHATSAPP_CHAT_MIME,
+        )
+        .await;
+
+        let text = result.extracted_text.unwrap();
+        assert!(text.starts_with("# Bob (WhatsApp)"), "got:\n{text}");
+        assert!(text.contains("**Participants:** Alice (1), Bob (1), Carol (1)"));
+        assert!(text.contains("**Bob** (21:16): Yes\nbring the charger"));
+        assert!(!text.contains("added Carol"), "system lines are dropped");
+        assert!(text.contains("**Carol** (09:02): \\# not a heading"));
+        assert_eq!(result.metadata["window_count"], 2);
+        assert_eq!(result.metadata["chat"]["started_at"], "2023-12-31T21:15:02");
+        assert_eq!(result.metadata["chat"]["timezone"], "local");
+        assert!(result.derived_notes.is_empty());
+        assert_eq!(
+            result.metadata["note_metadata"]["chat"]["participants"][2]["name"],
+            "Carol"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_whatsapp_android_month_first() {
+        let chat = "12/31/23, 9:15 PM - Alice: hi\n12/31/23, 9:50 PM - Bob: hey\n";
+        let result = extract(chat, "_chat.txt", WHATSAPP_CHAT_MIME).await;
+        assert_eq!(result.metadata["chat"]["started_at"], "2023-12-31T21:15:00");
+        assert_eq!(result.metadata["window_count"], 2);
+        assert_eq!(result.metadata["windows"][0]["participants"][0], "Alice");
+    }
+
+    #[tokio::test]
+    async fn test_extract_sniffs_platform_without_custom_mime() {
+        let result = extract(WHATSAPP_IOS, "_chat.txt", "text/plain").await;
+        assert_eq!(result.metadata["format"], "whatsapp");
+    }
+
+    #[tokio::test]
+    async fn test_extract_rejects_unrecognized_text() {
+        let err = ChatExportAdapter
+            .extract(
+                b"just some notes",
+                "a.txt",
+                "text/plain",
+                &serde_json::json!({}),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, matric_core::Error::InvalidInput(msg) if msg.contains("unrecognized_format")),
+            "got: {err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_adapter_identity() {
+        assert_eq!(ChatExportAdapter.strategy(), ExtractionStrategy::ChatExport);
+        assert_eq!(ChatExportAdapter.name(), "chat_export");
+        assert!(ChatExportAdapter.health_check().await.unwrap());
+    }
+}
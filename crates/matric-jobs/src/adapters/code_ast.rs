@@ -18,7 +18,7 @@ pub struct CodeAstAdapter;
 
 /// A detected code declaration.
 #[derive(Clone, serde::Serialize)]
-struct Declaration {
+pub(crate) struct Declaration {
     kind: String, // "function", "class", "struct", "enum", "trait", "interface", "method", "impl"
     name: String,
     line_start: usize,
@@ -61,8 +61,32 @@ fn detect_language(filename: &str) -> Option<&'static str> {
     }
 }
 
+/// Map a language name (e.g. a Jupyter kernel's `language_info.name`) to the
+/// identifiers used by [`extract_declarations`].
+pub(crate) fn normalize_language(name: &str) -> Option<&'static str> {
+    match name.trim().to_lowercase().as_str() {
+        "rust" => Some("rust"),
+        "python" | "python2" | "python3" | "ipython" | "ipython3" => Some("python"),
+        "javascript" | "js" | "node" | "nodejs" => Some("javascript"),
+        "typescript" | "ts" => Some("typescript"),
+        "go" | "golang" => Some("go"),
+        "java" => Some("java"),
+        "c" => Some("c"),
+        "c++" | "cpp" | "c++11" | "c++14" | "c++17" | "c++20" => Some("cpp"),
+        "ruby" => Some("ruby"),
+        "php" => Some("php"),
+        "swift" => Some("swift"),
+        "kotlin" => Some("kotlin"),
+        "csharp" | "c#" => Some("csharp"),
+        "scala" => Some("scala"),
+        "lua" => Some("lua"),
+        "bash" | "sh" | "shell" | "zsh" => Some("shell"),
+        _ => None,
+    }
+}
+
 /// Extract declarations from source code using language-specific regex patterns.
-fn extract_declarations(text: &str, language: &str) -> Vec<Declaration> {
+pub(crate) fn extract_declarations(text: &str, language: &str) -> Vec<Declaration> {
     let lines: Vec<&str> = text.lines().collect();
     let mut declarations = Vec::new();
 
@@ -349,6 +373,7 @@ impl ExtractionAdapter for CodeAstAdapter {
             ai_description: None,
             preview_data: None,
             derived_files: vec![],
+            derived_notes: vec![],
         })
     }
 
@@ -606,6 +631,13 @@ func (m *MyStruct) Method() error {
         assert_eq!(declarations[3]["name"], "Method");
     }
 
+    #[test]
+    fn test_normalize_language() {
+        assert_eq!(normalize_language("Python3"), Some("python"));
+        assert_eq!(normalize_language("C++17"), Some("cpp"));
+        assert_eq!(normalize_language("julia"), None);
+    }
+
     #[tokio::test]
     async fn test_code_ast_unknown_language() {
         let adapter = CodeAstAdapter;
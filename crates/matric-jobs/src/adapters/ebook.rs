@@ -0,0 +1,1516 @@
+//! Ebook extraction adapter — chapters, cover image and book metadata from EPUB and MOBI.
+//!
+//! Supports:
+//! - `.epub` — reads `META-INF/container.xml` to locate the OPF package, takes
+//!   Dublin Core metadata from it and converts each spine document to Markdown
+//! - `.mobi` / `.azw` / `.azw3` — parses the PalmDB container, MOBI header and
+//!   EXTH records, decompresses PalmDOC text and splits chapters at page breaks
+//!
+//! The extracted text is Markdown: the book title as `#`, chapter headings
+//! demoted one level so they nest under it. The cover is returned as a
+//! `cover_image` derived file, and bibliographic fields (title, authors, ISBN,
+//! publisher, language, date) are returned under `note_metadata.book` so the
+//! extraction handler can merge them into the owning note.
+//!
+//! DRM-protected and HUFF/CDIC-compressed MOBI files are rejected.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Cursor, Read};
+
+use async_trait::async_trait;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::Value as JsonValue;
+
+use matric_core::{
+    defaults::TEXT_EXTRACTION_MAX_BYTES, DerivedFile, ExtractionAdapter, ExtractionResult,
+    ExtractionStrategy, Result,
+};
+
+/// Maximum bytes read from a single EPUB entry (XHTML chapter or OPF).
+const MAX_ENTRY_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Maximum cover image size kept as a derived file.
+const MAX_COVER_BYTES: u64 = 10 * 1024 * 1024;
+
+/// PalmDOC compression: none.
+const MOBI_COMPRESSION_NONE: u16 = 1;
+/// PalmDOC compression: LZ77 variant.
+const MOBI_COMPRESSION_PALMDOC: u16 = 2;
+
+/// Sentinel for "no record" in MOBI header index fields.
+const MOBI_NULL_INDEX: u32 = 0xFFFF_FFFF;
+
+fn ebook_failure_detail(format: &str, phase: &str, error: &dyn fmt::Display) -> String {
+    format!(
+        "Ebook extraction failed; format={format}; phase={phase}; error_len={}",
+        error.to_string().chars().count()
+    )
+}
+
+fn ebook_error(format: &str, phase: &str, error: &dyn fmt::Display) -> matric_core::Error {
+    matric_core::Error::Internal(ebook_failure_detail(format, phase, error))
+}
+
+// ── Book model ───────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct BookMetadata {
+    title: Option<String>,
+    authors: Vec<String>,
+    isbn: Option<String>,
+    publisher: Option<String>,
+    language: Option<String>,
+    published: Option<String>,
+    description: Option<String>,
+}
+
+struct Chapter {
+    title: Option<String>,
+    markdown: String,
+}
+
+struct Cover {
+    data: Vec<u8>,
+    content_type: String,
+}
+
+struct Book {
+    format: &'static str,
+    metadata: BookMetadata,
+    chapters: Vec<Chapter>,
+    cover: Option<Cover>,
+    truncated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EbookFormat {
+    Epub,
+    Mobi,
+}
+
+/// Normalize an identifier to a bare ISBN when it looks like one.
+///
+/// Accepts `urn:isbn:` / `isbn:` prefixes and hyphen/space separated digits;
+/// returns `None` unless 10 or 13 characters remain (ISBN-10 may end in `X`).
+fn normalize_isbn(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    let lower = trimmed.to_ascii_lowercase();
+    let body = lower
+        .strip_prefix("urn:isbn:")
+        .or_else(|| lower.strip_prefix("isbn:"))
+        .or_else(|| lower.strip_prefix("isbn"))
+        .unwrap_or(&lower);
+    let compact: String = body
+        .chars()
+        .filter(|c| !matches!(c, '-' | ' '))
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let valid = match compact.len() {
+        13 => compact.chars().all(|c| c.is_ascii_digit()),
+        10 => {
+            compact[..9].chars().all(|c| c.is_ascii_digit())
+                && compact.ends_with(|c: char| c.is_ascii_digit() || c == 'X')
+        }
+        _ => false,
+    };
+    valid.then_some(compact)
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!value.is_empty()).then_some(value)
+}
+
+// ── HTML → Markdown ──────────────────────────────────────────────────────────
+
+/// Decode the HTML entities ebooks actually use; unknown entities are kept verbatim.
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('&') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| {
+                let entity = &rest[1..=end];
+                let ch = match entity {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    "mdash" => Some('—'),
+                    "ndash" => Some('–'),
+                    "hellip" => Some('…'),
+                    "lsquo" => Some('‘'),
+                    "rsquo" => Some('’'),
+                    "ldquo" => Some('“'),
+                    "rdquo" => Some('”'),
+                    "copy" => Some('©'),
+                    _ => entity
+                        .strip_prefix("#x")
+                        .or_else(|| entity.strip_prefix("#X"))
+                        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                        .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                        .and_then(char::from_u32),
+                };
+                ch.map(|c| (c, end + 2))
+            });
+        match decoded {
+            Some((ch, consumed)) => {
+                out.push(ch);
+                rest = &rest[consumed..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Incremental Markdown writer that collapses whitespace and block breaks.
+struct MarkdownWriter {
+    out: String,
+}
+
+impl MarkdownWriter {
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    fn trim_trailing_spaces(&mut self) {
+        let len = self.out.trim_end_matches([' ', '\t']).len();
+        self.out.truncate(len);
+    }
+
+    fn space(&mut self) {
+        if !self.at_line_start() && !self.out.ends_with(' ') {
+            self.out.push(' ');
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if text.starts_with(char::is_whitespace) {
+            self.space();
+        }
+        for (i, word) in text.split_whitespace().enumerate() {
+            if i > 0 {
+                self.out.push(' ');
+            }
+            self.out.push_str(word);
+        }
+        if text.ends_with(char::is_whitespace) {
+            self.space();
+        }
+    }
+
+    fn line_break(&mut self) {
+        self.trim_trailing_spaces();
+        if !self.at_line_start() {
+            self.out.push('\n');
+        }
+    }
+
+    fn paragraph_break(&mut self) {
+        self.trim_trailing_spaces();
+        if self.out.is_empty() || self.out.ends_with("\n\n") {
+            return;
+        }
+        self.out.push_str(if self.out.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        });
+    }
+
+    fn finish(mut self) -> String {
+        self.trim_trailing_spaces();
+        self.out.trim().to_string()
+    }
+}
+
+/// Convert (X)HTML to Markdown, keeping headings and list structure.
+///
+/// Deliberately tolerant: MOBI text is rarely well-formed, so this is a tag
+/// scanner rather than an XML parser. Headings are demoted one level so that
+/// a chapter's `<h1>` nests under the book title.
+fn html_to_markdown(html: &str) -> String {
+    let mut writer = MarkdownWriter { out: String::new() };
+    let mut skip_depth = 0usize;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            if skip_depth == 0 {
+                writer.text(&decode_entities(rest));
+            }
+            break;
+        };
+        if lt > 0 && skip_depth == 0 {
+            writer.text(&decode_entities(&rest[..lt]));
+        }
+        rest = &rest[lt..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        let Some(gt) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..gt];
+        rest = &rest[gt + 1..];
+
+        let closing = tag.starts_with('/');
+        let self_closing = tag.ends_with('/');
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| !c.is_whitespace() && *c != '/')
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        match name.as_str() {
+            "script" | "style" | "head" | "title" => {
+                if closing {
+                    skip_depth = skip_depth.saturating_sub(1);
+                } else if !self_closing {
+                    skip_depth += 1;
+                }
+                continue;
+            }
+            _ if skip_depth > 0 => continue,
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                writer.paragraph_break();
+                if !closing {
+                    let level = name[1..].parse::<usize>().unwrap_or(1);
+                    writer.out.push_str(&"#".repeat((level + 1).min(6)));
+                    writer.out.push(' ');
+                }
+            }
+            "li" => {
+                if closing {
+                    writer.line_break();
+                } else {
+                    writer.line_break();
+                    writer.out.push_str("- ");
+                }
+            }
+            "br" => writer.line_break(),
+            "hr" => {
+                writer.paragraph_break();
+                writer.out.push_str("---");
+                writer.paragraph_break();
+            }
+            "p" | "div" | "section" | "article" | "blockquote" | "ul" | "ol" | "table" | "tr"
+            | "pre" | "body" | "mbp:pagebreak" => writer.paragraph_break(),
+            "td" | "th" => writer.text(" "),
+            _ => {}
+        }
+    }
+
+    writer.finish()
+}
+
+/// Title of a converted chapter: its first Markdown heading, if any.
+fn chapter_title(markdown: &str) -> Option<String> {
+    markdown
+        .lines()
+        .find(|line| line.starts_with('#'))
+        .and_then(|line| non_empty(line.trim_start_matches('#')))
+}
+
+// ── EPUB ─────────────────────────────────────────────────────────────────────
+
+struct ManifestItem {
+    href: String,
+    media_type: String,
+    properties: String,
+}
+
+#[derive(Default)]
+struct OpfPackage {
+    metadata: BookMetadata,
+    manifest: HashMap<String, ManifestItem>,
+    spine: Vec<String>,
+    cover_id: Option<String>,
+}
+
+fn attributes(element: &BytesStart<'_>) -> HashMap<String, String> {
+    element
+        .attributes()
+        .flatten()
+        .map(|attr| {
+            let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).to_ascii_lowercase();
+            let raw = String::from_utf8_lossy(&attr.value);
+            let value = quick_xml::escape::unescape(&raw)
+                .map(|v| v.into_owned())
+                .unwrap_or_else(|_| raw.clone().into_owned());
+            (key, value)
+        })
+        .collect()
+}
+
+fn local_name(element: &BytesStart<'_>) -> String {
+    String::from_utf8_lossy(element.local_name().as_ref()).to_ascii_lowercase()
+}
+
+/// Record manifest, spine and EPUB2 cover declarations from an OPF element.
+fn record_package_element(package: &mut OpfPackage, name: &str, element: &BytesStart<'_>) {
+    match name {
+        "meta" => {
+            let mut attrs = attributes(element);
+            if attrs
+                .get("name")
+                .is_some_and(|n| n.eq_ignore_ascii_case("cover"))
+            {
+                package.cover_id = attrs.remove("content");
+            }
+        }
+        "item" => {
+            let mut attrs = attributes(element);
+            if let (Some(id), Some(href)) = (attrs.remove("id"), attrs.remove("href")) {
+                package.manifest.insert(
+                    id,
+                    ManifestItem {
+                        href,
+                        media_type: attrs.remove("media-type").unwrap_or_default(),
+                        properties: attrs.remove("properties").unwrap_or_default(),
+                    },
+                );
+            }
+        }
+        "itemref" => {
+            if let Some(idref) = attributes(element).remove("idref") {
+                package.spine.push(idref);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_opf(xml: &str) -> std::result::Result<OpfPackage, quick_xml::Error> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut package = OpfPackage::default();
+    let mut identifiers: Vec<(Option<String>, String)> = Vec::new();
+    let mut field: Option<(String, Option<String>)> = None;
+    let mut buffer = String::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                let name = local_name(&e);
+                match name.as_str() {
+                    "title" | "creator" | "identifier" | "language" | "publisher" | "date"
+                    | "description" => {
+                        let scheme = attributes(&e).remove("scheme");
+                        field = Some((name, scheme));
+                        buffer.clear();
+                    }
+                    _ => record_package_element(&mut package, &name, &e),
+                }
+            }
+            Event::Empty(e) => record_package_element(&mut package, &local_name(&e), &e),
+            Event::Text(t) if field.is_some() => match t.unescape() {
+                Ok(text) => buffer.push_str(&text),
+                Err(_) => buffer.push_str(&String::from_utf8_lossy(&t)),
+            },
+            Event::CData(c) if field.is_some() => buffer.push_str(&String::from_utf8_lossy(&c)),
+            Event::End(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_ascii_lowercase();
+                let Some((field_name, scheme)) = field.take_if(|(f, _)| *f == name) else {
+                    continue;
+                };
+                let Some(value) = non_empty(&buffer) else {
+                    continue;
+                };
+                let meta = &mut package.metadata;
+                match field_name.as_str() {
+                    "title" => {
+                        meta.title.get_or_insert(value);
+                    }
+                    "creator" if !meta.authors.contains(&value) => meta.authors.push(value),
+                    "identifier" => identifiers.push((scheme, value)),
+                    "language" => {
+                        meta.language.get_or_insert(value);
+                    }
+                    "publisher" => {
+                        meta.publisher.get_or_insert(value);
+                    }
+                    "date" => {
+                        meta.published.get_or_insert(value);
+                    }
+                    "description" => {
+                        meta.description.get_or_insert(value);
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    // Prefer identifiers explicitly marked as ISBN, then anything ISBN-shaped.
+    package.metadata.isbn = identifiers
+        .iter()
+        .filter(|(scheme, value)| {
+            scheme
+                .as_deref()
+                .is_some_and(|s| s.eq_ignore_ascii_case("isbn"))
+                || value.to_ascii_lowercase().starts_with("urn:isbn:")
+        })
+        .chain(identifiers.iter())
+        .find_map(|(_, value)| normalize_isbn(value));
+
+    Ok(package)
+}
+
+/// Decode `%XX` escapes in a manifest href.
+fn percent_decode(href: &str) -> String {
+    let bytes = href.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(byte) = href
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Resolve a manifest href against the OPF directory into a zip entry path.
+fn resolve_href(base_dir: &str, href: &str) -> String {
+    let href = percent_decode(href.split('#').next().unwrap_or(href));
+    let mut parts: Vec<&str> = base_dir.split('/').filter(|p| !p.is_empty()).collect();
+    for segment in href.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            other => parts.push(other),
+        }
+    }
+    parts.join("/")
+}
+
+fn read_zip_entry(
+    archive: &mut zip::ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+    limit: u64,
+) -> Option<Vec<u8>> {
+    let entry = archive.by_name(name).ok()?;
+    let mut data = Vec::new();
+    entry.take(limit).read_to_end(&mut data).ok()?;
+    Some(data)
+}
+
+fn parse_container(xml: &str) -> Option<String> {
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if local_name(&e) == "rootfile" => {
+                if let Some(path) = attributes(&e).remove("full-path") {
+                    return Some(path);
+                }
+            }
+            Ok(Event::Eof) | Err(_) => return None,
+            _ => {}
+        }
+    }
+}
+
+/// Accumulates converted chapters up to the text size limit.
+struct ChapterCollector {
+    chapters: Vec<Chapter>,
+    total_bytes: usize,
+    max_bytes: usize,
+    truncated: bool,
+}
+
+impl ChapterCollector {
+    fn new(max_bytes: usize) -> Self {
+        Self {
+            chapters: Vec::new(),
+            total_bytes: 0,
+            max_bytes,
+            truncated: false,
+        }
+    }
+
+    /// Add a chapter; returns `false` once the limit is reached.
+    fn push(&mut self, mut markdown: String) -> bool {
+        let remaining = self.max_bytes.saturating_sub(self.total_bytes);
+        if markdown.len() > remaining {
+            let mut cut = remaining;
+            while !markdown.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            markdown.truncate(cut);
+            self.truncated = true;
+        }
+        if !markdown.is_empty() {
+            self.total_bytes += markdown.len();
+            self.chapters.push(Chapter {
+                title: chapter_title(&markdown),
+                markdown,
+            });
+        }
+        !self.truncated
+    }
+}
+
+fn missing(format: &str, phase: &str) -> matric_core::Error {
+    matric_core::Error::InvalidInput(format!(
+        "Ebook extraction failed; format={format}; phase={phase}; reason=missing"
+    ))
+}
+
+fn extract_epub(data: &[u8], max_bytes: usize) -> Result<Book> {
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(data)).map_err(|e| ebook_error("epub", "zip_open", &e))?;
+
+    let opf_path = read_zip_entry(&mut archive, "META-INF/container.xml", MAX_ENTRY_BYTES)
+        .and_then(|xml| parse_container(&String::from_utf8_lossy(&xml)))
+        .or_else(|| {
+            archive
+                .file_names()
+                .find(|name| name.to_ascii_lowercase().ends_with(".opf"))
+                .map(str::to_string)
+        })
+        .ok_or_else(|| missing("epub", "container"))?;
+    let opf = read_zip_entry(&mut archive, &opf_path, MAX_ENTRY_BYTES)
+        .ok_or_else(|| missing("epub", "package"))?;
+    let package = parse_opf(&String::from_utf8_lossy(&opf))
+        .map_err(|e| ebook_error("epub", "package_parse", &e))?;
+    let base_dir = opf_path.rsplit_once('/').map_or("", |(dir, _)| dir);
+
+    let mut collector = ChapterCollector::new(max_bytes);
+    for idref in &package.spine {
+        let Some(item) = package.manifest.get(idref) else {
+            continue;
+        };
+        if !item.media_type.contains("html") {
+            continue;
+        }
+        let path = resolve_href(base_dir, &item.href);
+        let Some(xhtml) = read_zip_entry(&mut archive, &path, MAX_ENTRY_BYTES) else {
+            continue;
+        };
+        if !collector.push(html_to_markdown(&String::from_utf8_lossy(&xhtml))) {
+            break;
+        }
+    }
+
+    let cover = package
+        .manifest
+        .values()
+        .find(|item| {
+            item.properties
+                .split_whitespace()
+                .any(|p| p == "cover-image")
+        })
+        .or_else(|| {
+            package
+                .cover_id
+                .as_ref()
+                .and_then(|id| package.manifest.get(id))
+        })
+        .filter(|item| item.media_type.starts_with("image/"))
+        .and_then(|item| {
+            let path = resolve_href(base_dir, &item.href);
+            read_zip_entry(&mut archive, &path, MAX_COVER_BYTES + 1)
+                .filter(|data| !data.is_empty() && data.len() as u64 <= MAX_COVER_BYTES)
+                .map(|data| Cover {
+                    data,
+                    content_type: item.media_type.clone(),
+                })
+        });
+
+    Ok(Book {
+        format: "epub",
+        metadata: package.metadata,
+        chapters: collector.chapters,
+        cover,
+        truncated: collector.truncated,
+    })
+}
+
+// ── MOBI ─────────────────────────────────────────────────────────────────────
+
+/// Windows-1252 code points for bytes 0x80–0x9F (the rest map to Latin-1).
+const CP1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+/// Decode MOBI text: UTF-8 for encoding 65001, Windows-1252 otherwise.
+fn decode_mobi_text(bytes: &[u8], encoding: u32) -> String {
+    if encoding == 65001 {
+        return String::from_utf8_lossy(bytes).into_owned();
+    }
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0x9F => CP1252_HIGH[(b - 0x80) as usize],
+            _ => b as char,
+        })
+        .collect()
+}
+
+fn be_u16(data: &[u8], at: usize) -> Option<u16> {
+    data.get(at..at + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn be_u32(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Decompress a PalmDOC (LZ77 variant) text record.
+fn palmdoc_decompress(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() * 2);
+    let mut i = 0;
+    while i < input.len() {
+        let byte = input[i];
+        i += 1;
+        match byte {
+            0x01..=0x08 => {
+                let end = (i + byte as usize).min(input.len());
+                out.extend_from_slice(&input[i..end]);
+                i = end;
+            }
+            0x80..=0xBF => {
+                let Some(&next) = input.get(i) else {
+                    break;
+                };
+                i += 1;
+                let pair = u16::from_be_bytes([byte, next]);
+                let distance = ((pair >> 3) & 0x07FF) as usize;
+                let length = (pair & 0x07) as usize + 3;
+                if distance == 0 || distance > out.len() {
+                    continue;
+                }
+                for _ in 0..length {
+                    out.push(out[out.len() - distance]);
+                }
+            }
+            0xC0..=0xFF => {
+                out.push(b' ');
+                out.push(byte ^ 0x80);
+            }
+            _ => out.push(byte),
+        }
+    }
+    out
+}
+
+/// Size of a trailing entry, read as a backward variable-width integer.
+fn trailing_entry_size(data: &[u8]) -> usize {
+    let mut value = 0usize;
+    for (shift, &byte) in data.iter().rev().take(4).enumerate() {
+        value |= ((byte & 0x7F) as usize) << (7 * shift);
+        if byte & 0x80 != 0 {
+            break;
+        }
+    }
+    value
+}
+
+/// Number of trailing bytes appended to a text record, per the header's extra data flags.
+fn trailing_entries_size(record: &[u8], extra_flags: u16) -> usize {
+    let mut size = 0usize;
+    for bit in 1..16 {
+        if extra_flags & (1 << bit) != 0 {
+            let end = record.len().saturating_sub(size);
+            size += trailing_entry_size(&record[..end]);
+        }
+    }
+    if extra_flags & 1 != 0 {
+        if let Some(&byte) = record
+            .len()
+            .checked_sub(size + 1)
+            .and_then(|i| record.get(i))
+        {
+            size += (byte & 0x03) as usize + 1;
+        }
+    }
+    size.min(record.len())
+}
+
+/// MIME type of an embedded MOBI image record, by magic bytes.
+fn sniff_image(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"\x89PNG") {
+        Some("image/png")
+    } else if data.starts_with(b"GIF8") {
+        Some("image/gif")
+    } else {
+        None
+    }
+}
+
+/// Split MOBI HTML into chapters at `<mbp:pagebreak` markers.
+fn split_mobi_chapters(html: &str) -> Vec<&str> {
+    const MARKER: &str = "<mbp:pagebreak";
+    let lower = html.to_ascii_lowercase();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    while let Some(pos) = lower[start..].find(MARKER) {
+        parts.push(&html[start..start + pos]);
+        start += pos + MARKER.len();
+    }
+    parts.push(&html[start..]);
+    parts
+}
+
+fn extract_mobi(data: &[u8], max_bytes: usize) -> Result<Book> {
+    let invalid = |phase: &str, reason: &str| {
+        matric_core::Error::InvalidInput(format!(
+            "Ebook extraction failed; format=mobi; phase={phase}; reason={reason}"
+        ))
+    };
+
+    if !matches!(data.get(60..68), Some(b"BOOKMOBI") | Some(b"TEXtREAd")) {
+        return Err(invalid("header", "not_palmdb_book"));
+    }
+    let record_count = be_u16(data, 76).unwrap_or(0) as usize;
+    let offsets: Vec<usize> = (0..record_count)
+        .map(|i| be_u32(data, 78 + i * 8).map(|o| o as usize))
+        .collect::<Option<_>>()
+        .filter(|offsets: &Vec<usize>| {
+            offsets.windows(2).all(|w| w[0] <= w[1])
+                && offsets.last().is_some_and(|&o| o <= data.len())
+        })
+        .ok_or_else(|| invalid("record_table", "malformed"))?;
+    let record = |i: usize| -> Option<&[u8]> {
+        let start = *offsets.get(i)?;
+        let end = offsets.get(i + 1).copied().unwrap_or(data.len());
+        data.get(start..end)
+    };
+
+    let rec0 = record(0)
+        .filter(|r| r.len() >= 16)
+        .ok_or_else(|| invalid("record0", "truncated"))?;
+    let compression = be_u16(rec0, 0).unwrap_or(0);
+    let text_length = be_u32(rec0, 4).unwrap_or(0) as usize;
+    let text_records = be_u16(rec0, 8).unwrap_or(0) as usize;
+    if be_u16(rec0, 12).unwrap_or(0) != 0 {
+        return Err(invalid("record0", "drm_protected"));
+    }
+    if compression != MOBI_COMPRESSION_NONE && compression != MOBI_COMPRESSION_PALMDOC {
+        return Err(invalid("record0", "unsupported_compression"));
+    }
+
+    let mut metadata = BookMetadata::default();
+    let mut encoding = 1252;
+    let mut extra_flags = 0u16;
+    let mut first_image = MOBI_NULL_INDEX;
+    let mut cover_offset: Option<u32> = None;
+    let mut full_name: Option<String> = None;
+    let mut exth_title: Option<String> = None;
+
+    if rec0.get(16..20) == Some(b"MOBI") {
+        let header_len = be_u32(rec0, 20).unwrap_or(0) as usize;
+        encoding = be_u32(rec0, 28).unwrap_or(1252);
+        first_image = be_u32(rec0, 108).unwrap_or(MOBI_NULL_INDEX);
+        if header_len >= 0xE4 {
+            extra_flags = be_u16(rec0, 0xF2).unwrap_or(0);
+        }
+        if let (Some(offset), Some(len)) = (be_u32(rec0, 84), be_u32(rec0, 88)) {
+            full_name = rec0
+                .get(offset as usize..(offset as usize).saturating_add(len as usize))
+                .and_then(|bytes| non_empty(&decode_mobi_text(bytes, encoding)));
+        }
+
+        let exth_start = 16 + header_len;
+        let has_exth = be_u32(rec0, 128).unwrap_or(0) & 0x40 != 0;
+        if has_exth && rec0.get(exth_start..exth_start + 4) == Some(b"EXTH") {
+            let count = be_u32(rec0, exth_start + 8).unwrap_or(0);
+            let mut pos = exth_start + 12;
+            for _ in 0..count {
+                let (Some(kind), Some(len)) = (be_u32(rec0, pos), be_u32(rec0, pos + 4)) else {
+                    break;
+                };
+                let len = len as usize;
+                let Some(value) = rec0.get(pos + 8..pos + len.max(8)) else {
+                    break;
+                };
+                let text = || non_empty(&decode_mobi_text(value, encoding));
+                match kind {
+                    100 => {
+                        if let Some(author) = text().filter(|a| !metadata.authors.contains(a)) {
+                            metadata.authors.push(author);
+                        }
+                    }
+                    101 => metadata.publisher = metadata.publisher.take().or_else(text),
+                    103 => metadata.description = metadata.description.take().or_else(text),
+                    104 => {
+                        metadata.isbn = metadata
+                            .isbn
+                            .take()
+                            .or_else(|| text().and_then(|v| normalize_isbn(&v)))
+                    }
+                    106 => metadata.published = metadata.published.take().or_else(text),
+                    201 => cover_offset = cover_offset.or(be_u32(value, 0)),
+                    503 => exth_title = exth_title.take().or_else(text),
+                    524 => metadata.language = metadata.language.take().or_else(text),
+                    _ => {}
+                }
+                pos += len.max(8);
+            }
+        }
+    }
+
+    let palmdb_name = data
+        .get(..32)
+        .map(|name| name.split(|&b| b == 0).next().unwrap_or(name))
+        .and_then(|name| non_empty(&decode_mobi_text(name, encoding)));
+    metadata.title = exth_title.or(full_name).or(palmdb_name);
+
+    let mut raw = Vec::with_capacity(text_length.min(max_bytes));
+    for i in 1..=text_records {
+        let Some(rec) = record(i) else {
+            break;
+        };
+        let body = &rec[..rec.len() - trailing_entries_size(rec, extra_flags)];
+        if compression == MOBI_COMPRESSION_PALMDOC {
+            raw.extend(palmdoc_decompress(body));
+        } else {
+            raw.extend_from_slice(body);
+        }
+        if raw.len() >= text_length {
+            break;
+        }
+    }
+    if text_length > 0 {
+        raw.truncate(text_length);
+    }
+    let html = decode_mobi_text(&raw, encoding);
+
+    let mut collector = ChapterCollector::new(max_bytes);
+    for part in split_mobi_chapters(&html) {
+        if !collector.push(html_to_markdown(part)) {
+            break;
+        }
+    }
+
+    let cover = cover_offset
+        .filter(|_| first_image != MOBI_NULL_INDEX)
+        .and_then(|offset| record(first_image as usize + offset as usize))
+        .filter(|data| data.len() as u64 <= MAX_COVER_BYTES)
+        .and_then(|data| {
+            sniff_image(data).map(|content_type| Cover {
+                data: data.to_vec(),
+                content_type: content_type.to_string(),
+            })
+        });
+
+    Ok(Book {
+        format: "mobi",
+        metadata,
+        chapters: collector.chapters,
+        cover,
+        truncated: collector.truncated,
+    })
+}
+
+// ── Adapter ──────────────────────────────────────────────────────────────────
+
+/// Ebook extraction adapter (EPUB, MOBI/AZW).
+pub struct EbookAdapter;
+
+impl EbookAdapter {
+    /// Detect the container from magic bytes, falling back to MIME type and extension.
+    fn detect_format(data: &[u8], filename: &str, mime_type: &str) -> Option<EbookFormat> {
+        if data.starts_with(b"PK\x03\x04") {
+            return Some(EbookFormat::Epub);
+        }
+        if matches!(data.get(60..68), Some(b"BOOKMOBI") | Some(b"TEXtREAd")) {
+            return Some(EbookFormat::Mobi);
+        }
+        let ext = filename
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_ascii_lowercase())
+            .unwrap_or_default();
+        let mime = mime_type.to_ascii_lowercase();
+        if mime == "application/epub+zip" || ext == "epub" {
+            Some(EbookFormat::Epub)
+        } else if mime.contains("mobipocket") || matches!(ext.as_str(), "mobi" | "azw" | "azw3") {
+            Some(EbookFormat::Mobi)
+        } else {
+            None
+        }
+    }
+
+    fn build_result(book: Book, filename: &str) -> ExtractionResult {
+        let meta = &book.metadata;
+        let title = meta.title.clone().unwrap_or_else(|| {
+            filename
+                .rsplit_once('.')
+                .map_or(filename, |(stem, _)| stem)
+                .to_string()
+        });
+
+        let mut byline = Vec::new();
+        if !meta.authors.is_empty() {
+            byline.push(format!("**Author:** {}", meta.authors.join(", ")));
+        }
+        for (label, value) in [
+            ("Publisher", &meta.publisher),
+            ("Published", &meta.published),
+            ("ISBN", &meta.isbn),
+            ("Language", &meta.language),
+        ] {
+            if let Some(value) = value {
+                byline.push(format!("**{label}:** {value}"));
+            }
+        }
+
+        let mut sections = vec![format!("# {title}")];
+        if !byline.is_empty() {
+            sections.push(byline.join("  \n"));
+        }
+        sections.extend(book.chapters.iter().map(|c| c.markdown.clone()));
+        let text = sections.join("\n\n");
+
+        let mut book_fields = serde_json::Map::new();
+        book_fields.insert("format".into(), book.format.into());
+        book_fields.insert("title".into(), title.into());
+        if !meta.authors.is_empty() {
+            book_fields.insert("authors".into(), meta.authors.clone().into());
+        }
+        for (key, value) in [
+            ("isbn", &meta.isbn),
+            ("publisher", &meta.publisher),
+            ("language", &meta.language),
+            ("published", &meta.published),
+        ] {
+            if let Some(value) = value {
+                book_fields.insert(key.into(), value.clone().into());
+            }
+        }
+
+        let chapters: Vec<JsonValue> = book
+            .chapters
+            .iter()
+            .enumerate()
+            .map(|(i, chapter)| {
+                serde_json::json!({
+                    "index": i + 1,
+                    "title": chapter.title,
+                    "char_count": chapter.markdown.chars().count(),
+                })
+            })
+            .collect();
+
+        let mut metadata = JsonValue::Object(book_fields.clone());
+        if let Some(description) = &meta.description {
+            metadata["description"] = description.clone().into();
+        }
+        metadata["chapter_count"] = chapters.len().into();
+        metadata["chapters"] = chapters.into();
+        metadata["char_count"] = text.chars().count().into();
+        metadata["has_cover"] = book.cover.is_some().into();
+        metadata["truncated"] = book.truncated.into();
+        metadata["note_metadata"] = serde_json::json!({ "book": book_fields });
+
+        let derived_files = book
+            .cover
+            .map(|cover| {
+                let ext = cover
+                    .content_type
+                    .rsplit('/')
+                    .next()
+                    .map(|sub| if sub == "jpeg" { "jpg" } else { sub })
+                    .unwrap_or("img");
+                DerivedFile {
+                    filename: format!("cover.{ext}"),
+                    content_type: cover.content_type,
+                    data: cover.data,
+                    derivation_type: "cover_image".to_string(),
+                    ai_description: None,
+                    metadata: Some(serde_json::json!({ "book_format": book.format })),
+                    source_path: None,
+                }
+            })
+            .into_iter()
+            .collect();
+
+        ExtractionResult {
+            extracted_text: Some(text),
+            metadata,
+            ai_description: None,
+            preview_data: None,
+            derived_files,
+            derived_notes: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl ExtractionAdapter for EbookAdapter {
+    fn strategy(&self) -> ExtractionStrategy {
+        ExtractionStrategy::Ebook
+    }
+
+    async fn extract(
+        &self,
+        data: &[u8],
+        filename: &str,
+        mime_type: &str,
+        config: &JsonValue,
+    ) -> Result<ExtractionResult> {
+        if data.is_empty() {
+            return Ok(ExtractionResult {
+                extracted_text: None,
+                metadata: serde_json::json!({ "error_code": "empty_input" }),
+                ai_description: None,
+                preview_data: None,
+                derived_files: vec![],
+                derived_notes: vec![],
+            });
+        }
+
+        let max_bytes = config
+            .get("max_bytes")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(TEXT_EXTRACTION_MAX_BYTES);
+
+        let book = match Self::detect_format(data, filename, mime_type) {
+            Some(EbookFormat::Epub) => extract_epub(data, max_bytes)?,
+            Some(EbookFormat::Mobi) => extract_mobi(data, max_bytes)?,
+            None => {
+                return Err(matric_core::Error::InvalidInput(
+                    "Ebook extraction failed; phase=detect; reason=unrecognized_format".into(),
+                ))
+            }
+        };
+
+        Ok(Self::build_result(book, filename))
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        Ok(true) // Pure Rust — no external dependencies
+    }
+
+    fn name(&self) -> &str {
+        "ebook"
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    // ── Helpers ───────────────────────────────────────────────────────────────
+
+    const CONTAINER_XML: &str = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#;
+
+    const CONTENT_OPF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="uid">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:opf="http://www.idpf.org/2007/opf">
+    <dc:identifier id="uid">urn:uuid:0b2f3c4d-0000-4000-8000-000000000000</dc:identifier>
+    <dc:identifier opf:scheme="ISBN">978-0-306-40615-7</dc:identifier>
+    <dc:title>The Test Book</dc:title>
+    <dc:creator>Ada Writer</dc:creator>
+    <dc:creator>Bo Coauthor</dc:creator>
+    <dc:language>en</dc:language>
+    <dc:publisher>Example &amp; Sons</dc:publisher>
+    <dc:date>2024-05-01</dc:date>
+    <meta name="cover" content="cover-img"/>
+  </metadata>
+  <manifest>
+    <item id="ch1" href="text/chapter%201.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="text/ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="css" href="style.css" media-type="text/css"/>
+    <item id="cover-img" href="../images/cover.jpg" media-type="image/jpeg"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+    <itemref idref="css"/>
+    <itemref idref="ch2"/>
+  </spine>
+</package>"#;
+
+    const CHAPTER_ONE: &str = r#"<?xml version="1.0"?>
+<html xmlns="http://www.w3.org/1999/xhtml"><head><title>ignored</title>
+<style>p { color: red; }</style></head>
+<body><h1>Chapter One</h1><p>It was a  dark
+night &amp; stormy.</p><h2>A Section</h2><ul><li>first</li><li>second</li></ul></body></html>"#;
+
+    const CHAPTER_TWO: &str = "<html><body><h1>Chapter Two</h1><p>The end&#8230;</p></body></html>";
+
+    const JPEG_BYTES: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F'];
+
+    fn make_epub() -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::<()>::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        let entries: [(&str, &[u8]); 6] = [
+            ("mimetype", b"application/epub+zip"),
+            ("META-INF/container.xml", CONTAINER_XML.as_bytes()),
+            ("OEBPS/content.opf", CONTENT_OPF.as_bytes()),
+            ("OEBPS/text/chapter 1.xhtml", CHAPTER_ONE.as_bytes()),
+            ("OEBPS/text/ch2.xhtml", CHAPTER_TWO.as_bytes()),
+            ("images/cover.jpg", JPEG_BYTES),
+        ];
+        for (name, data) in entries {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    /// Build a minimal uncompressed UTF-8 MOBI with one text record and an optional cover.
+    fn make_mobi(html: &str, exth: &[(u32, &[u8])], cover: Option<&[u8]>) -> Vec<u8> {
+        let text = html.as_bytes();
+        let full_name = b"Full Name Title";
+
+        let mut exth_block = Vec::new();
+        for (kind, value) in exth {
+            exth_block.extend_from_slice(&kind.to_be_bytes());
+            exth_block.extend_from_slice(&(value.len() as u32 + 8).to_be_bytes());
+            exth_block.extend_from_slice(value);
+        }
+        let mut exth_header = b"EXTH".to_vec();
+        exth_header.extend_from_slice(&(exth_block.len() as u32 + 12).to_be_bytes());
+        exth_header.extend_from_slice(&(exth.len() as u32).to_be_bytes());
+        exth_header.extend_from_slice(&exth_block);
+
+        let header_len = 232usize;
+        let mut rec0 = vec![0u8; 16 + header_len];
+        rec0[0..2].copy_from_slice(&MOBI_COMPRESSION_NONE.to_be_bytes());
+        rec0[4..8].copy_from_slice(&(text.len() as u32).to_be_bytes());
+        rec0[8..10].copy_from_slice(&1u16.to_be_bytes());
+        rec0[16..20].copy_from_slice(b"MOBI");
+        rec0[20..24].copy_from_slice(&(header_len as u32).to_be_bytes());
+        rec0[28..32].copy_from_slice(&65001u32.to_be_bytes());
+        let name_offset = rec0.len() + exth_header.len();
+        rec0[84..88].copy_from_slice(&(name_offset as u32).to_be_bytes());
+        rec0[88..92].copy_from_slice(&(full_name.len() as u32).to_be_bytes());
+        let first_image = if cover.is_some() { 2 } else { MOBI_NULL_INDEX };
+        rec0[108..112].copy_from_slice(&first_image.to_be_bytes());
+        rec0[128..132].copy_from_slice(&0x40u32.to_be_bytes());
+        rec0.extend_from_slice(&exth_header);
+        rec0.extend_from_slice(full_name);
+
+        let mut records = vec![rec0, text.to_vec()];
+        if let Some(image) = cover {
+            records.push(image.to_vec());
+        }
+
+        let mut out = vec![0u8; 78];
+        out[..9].copy_from_slice(b"palm_name");
+        out[60..68].copy_from_slice(b"BOOKMOBI");
+        out[76..78].copy_from_slice(&(records.len() as u16).to_be_bytes());
+        let mut offset = 78 + records.len() * 8 + 2;
+        for (i, record) in records.iter().enumerate() {
+            out.extend_from_slice(&(offset as u32).to_be_bytes());
+            out.extend_from_slice(&(i as u32).to_be_bytes());
+            offset += record.len();
+        }
+        out.extend_from_slice(&[0, 0]);
+        for record in records {
+            out.extend_from_slice(&record);
+        }
+        out
+    }
+
+    // ── Basic adapter contract ────────────────────────────────────────────────
+
+    #[test]
+    fn test_strategy_and_name() {
+        assert_eq!(EbookAdapter.strategy(), ExtractionStrategy::Ebook);
+        assert_eq!(EbookAdapter.name(), "ebook");
+    }
+
+    #[tokio::test]
+    async fn test_health_check() {
+        assert!(EbookAdapter.health_check().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_empty_input() {
+        let result = EbookAdapter
+            .extract(b"", "book.epub", "application/epub+zip", &JsonValue::Null)
+            .await
+            .unwrap();
+        assert!(result.extracted_text.is_none());
+        assert_eq!(result.metadata["error_code"], "empty_input");
+    }
+
+    #[tokio::test]
+    async fn test_unrecognized_format_is_rejected() {
+        let err = EbookAdapter
+            .extract(b"plain text", "notes.txt", "text/plain", &JsonValue::Null)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, matric_core::Error::InvalidInput(_)));
+    }
+
+    // ── EPUB ──────────────────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn test_epub_chapters_metadata_and_cover() {
+        let result = EbookAdapter
+            .extract(
+                &make_epub(),
+                "book.epub",
+                "application/epub+zip",
+                &JsonValue::Null,
+            )
+            .await
+            .unwrap();
+
+        let text = result.extracted_text.unwrap();
+        assert!(text.starts_with("# The Test Book\n\n**Author:** Ada Writer, Bo Coauthor"));
+        assert!(text.contains("## Chapter One\n\nIt was a dark night & stormy."));
+        assert!(text.contains("### A Section\n\n- first\n- second"));
+        assert!(text.contains("## Chapter Two\n\nThe end…"));
+        assert!(!text.contains("ignored"));
+        assert!(!text.contains("color: red"));
+        assert!(text.find("Chapter One").unwrap() < text.find("Chapter Two").unwrap());
+
+        let meta = &result.metadata;
+        assert_eq!(meta["format"], "epub");
+        assert_eq!(meta["isbn"], "9780306406157");
+        assert_eq!(meta["publisher"], "Example & Sons");
+        assert_eq!(meta["chapter_count"], 2);
+        assert_eq!(meta["chapters"][0]["title"], "Chapter One");
+        assert_eq!(meta["chapters"][1]["index"], 2);
+        assert_eq!(meta["has_cover"], true);
+        assert_eq!(meta["truncated"], false);
+
+        let book = &meta["note_metadata"]["book"];
+        assert_eq!(book["title"], "The Test Book");
+        assert_eq!(
+            book["authors"],
+            serde_json::json!(["Ada Writer", "Bo Coauthor"])
+        );
+        assert_eq!(book["isbn"], "9780306406157");
+        assert_eq!(book["language"], "en");
+        assert_eq!(book["published"], "2024-05-01");
+
+        assert_eq!(result.derived_files.len(), 1);
+        let cover = &result.derived_files[0];
+        assert_eq!(cover.derivation_type, "cover_image");
+        assert_eq!(cover.content_type, "image/jpeg");
+        assert_eq!(cover.filename, "cover.jpg");
+        assert_eq!(cover.data, JPEG_BYTES);
+    }
+
+    #[tokio::test]
+    async fn test_epub_respects_max_bytes() {
+        let result = EbookAdapter
+            .extract(
+                &make_epub(),
+                "book.epub",
+                "application/epub+zip",
+                &serde_json::json!({ "max_bytes": 20 }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.metadata["truncated"], true);
+        assert_eq!(result.metadata["chapter_count"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_epub_without_container_is_an_error() {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file("readme.txt", zip::write::FileOptions::<()>::default())
+            .unwrap();
+        writer.write_all(b"not a book").unwrap();
+        let data = writer.finish().unwrap().into_inner();
+
+        let err = EbookAdapter
+            .extract(&data, "book.epub", "application/epub+zip", &JsonValue::Null)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, matric_core::Error::InvalidInput(_)));
+    }
+
+    // ── MOBI ──────────────────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn test_mobi_chapters_exth_and_cover() {
+        let html = "<html><body><h1>Part One</h1><p>Alpha text.</p>\
+                    <mbp:pagebreak/><h1>Part Two</h1><p>Beta text.</p></body></html>";
+        let cover_offset = 0u32.to_be_bytes();
+        let data = make_mobi(
+            html,
+            &[
+                (100, b"Mo Author"),
+                (104, b"0-306-40615-2"),
+                (503, b"Exth Title"),
+                (524, b"de"),
+                (201, &cover_offset),
+            ],
+            Some(JPEG_BYTES),
+        );
+
+        let result = EbookAdapter
+            .extract(
+                &data,
+                "book.mobi",
+                "application/x-mobipocket-ebook",
+                &JsonValue::Null,
+            )
+            .await
+            .unwrap();
+
+        let text = result.extracted_text.unwrap();
+        assert!(text.starts_with("# Exth Title\n\n**Author:** Mo Author"));
+        assert!(text.contains("## Part One\n\nAlpha text."));
+        assert!(text.contains("## Part Two\n\nBeta text."));
+
+        let meta = &result.metadata;
+        assert_eq!(meta["format"], "mobi");
+        assert_eq!(meta["chapter_count"], 2);
+        assert_eq!(meta["note_metadata"]["book"]["isbn"], "0306406152");
+        assert_eq!(meta["note_metadata"]["book"]["language"], "de");
+
+        assert_eq!(result.derived_files.len(), 1);
+        assert_eq!(result.derived_files[0].content_type, "image/jpeg");
+    }
+
+    #[tokio::test]
+    async fn test_mobi_title_falls_back_to_full_name() {
+        let data = make_mobi("<p>Only text</p>", &[], None);
+        let result = EbookAdapter
+            .extract(
+                &data,
+                "book.mobi",
+                "application/octet-stream",
+                &JsonValue::Null,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.metadata["title"], "Full Name Title");
+        assert!(result.derived_files.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mobi_drm_is_rejected() {
+        let mut data = make_mobi("<p>secret</p>", &[], None);
+        let rec0 = be_u32(&data, 78).unwrap() as usize;
+        data[rec0 + 12..rec0 + 14].copy_from_slice(&2u16.to_be_bytes());
+
+        let err = EbookAdapter
+            .extract(
+                &data,
+                "book.azw",
+                "application/octet-stream",
+                &JsonValue::Null,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, matric_core::Error::InvalidInput(_)));
+    }
+
+    #[test]
+    fn test_palmdoc_decompress() {
+        // literal run, plain bytes, back-reference (distance 2, length 4), space+char
+        let input = [0x02, b'x', b'y', b'a', b'b', 0x80, 0x11, 0xE1];
+        assert_eq!(palmdoc_decompress(&input), b"xyababab a");
+    }
+
+    #[test]
+    fn test_trailing_entries_size() {
+        let mut record = b"hello".to_vec();
+        record.extend_from_slice(&[b'Z', 0x01]); // multibyte: 1 extra byte + itself
+        record.extend_from_slice(&[0xAA, 0xBB, 0x83]); // trailing entry of 3 bytes
+        let size = trailing_entries_size(&record, 0b11);
+        assert_eq!(&record[..record.len() - size], b"hello");
+    }
+
+    #[test]
+    fn test_decode_cp1252() {
+        assert_eq!(
+            decode_mobi_text(&[0x93, b'h', b'i', 0x94, 0xE9], 1252),
+            "“hi”é"
+        );
+    }
+
+    // ── Helpers ───────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_normalize_isbn() {
+        assert_eq!(
+            normalize_isbn("urn:isbn:978-0-306-40615-7").as_deref(),
+            Some("9780306406157")
+        );
+        assert_eq!(
+            normalize_isbn("0 306 40615 x").as_deref(),
+            Some("030640615X")
+        );
+        assert_eq!(normalize_isbn("urn:uuid:1234"), None);
+        assert_eq!(normalize_isbn("12345"), None);
+    }
+
+    #[test]
+    fn test_resolve_href() {
+        assert_eq!(
+            resolve_href("OEBPS", "text/a%20b.xhtml#top"),
+            "OEBPS/text/a b.xhtml"
+        );
+        assert_eq!(
+            resolve_href("OEBPS/text", "../images/c.png"),
+            "OEBPS/images/c.png"
+        );
+        assert_eq!(resolve_href("", "chapter.html"), "chapter.html");
+    }
+
+    #[test]
+    fn test_html_to_markdown_headings_and_entities() {
+        let md = html_to_markdown(
+            "<h3>Deep</h3><p>a&nbsp;b &lt;c&gt; &unknown; <b>bold</b>text</p><hr/><p>line<br/>break</p>",
+        );
+        assert_eq!(
+            md,
+            "#### Deep\n\na b <c> &unknown; boldtext\n\n---\n\nline\nbreak"
+        );
+    }
+
+    #[test]
+    fn test_html_to_markdown_tolerates_malformed_markup() {
+        let md = html_to_markdown("<p>unclosed <i>italic<p>next <!-- note --> para <span");
+        assert_eq!(md, "unclosed italic\n\nnext para");
+    }
+}
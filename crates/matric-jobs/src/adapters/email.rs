@@ -133,6 +133,7 @@ fn extract_eml(data: &[u8]) -> Result<ExtractionResult> {
             ai_description: None,
             preview_data: None,
             derived_files: vec![],
+            derived_notes: vec![],
         });
     }
 
@@ -155,6 +156,7 @@ fn extract_eml(data: &[u8]) -> Result<ExtractionResult> {
         ai_description: None,
         preview_data: None,
         derived_files,
+        derived_notes: vec![],
     })
 }
 
@@ -174,6 +176,7 @@ fn extract_mbox(data: &[u8]) -> Result<ExtractionResult> {
             ai_description: None,
             preview_data: None,
             derived_files: vec![],
+            derived_notes: vec![],
         });
     }
 
@@ -227,6 +230,7 @@ fn extract_mbox(data: &[u8]) -> Result<ExtractionResult> {
         ai_description: None,
         preview_data: None,
         derived_files: all_derived_files,
+        derived_notes: vec![],
     })
 }
 
@@ -249,6 +253,7 @@ fn extract_single_mbox_message(data: &[u8], format: &str) -> Result<ExtractionRe
                 ai_description: None,
                 preview_data: None,
                 derived_files,
+                derived_notes: vec![],
             })
         }
         Err(_) => Ok(ExtractionResult {
@@ -261,6 +266,7 @@ fn extract_single_mbox_message(data: &[u8], format: &str) -> Result<ExtractionRe
             ai_description: None,
             preview_data: None,
             derived_files: vec![],
+            derived_notes: vec![],
         }),
     }
 }
@@ -644,6 +644,7 @@ impl ExtractionAdapter for Glb3DModelAdapter {
             ai_description: composite_description,
             preview_data,
             derived_files,
+            derived_notes: vec![],
         })
     }
 
@@ -3,11 +3,14 @@
 pub mod archive;
 pub mod audio_transcribe;
 pub mod audio_util;
+pub mod chat_export;
 pub mod code_ast;
 pub mod content_summarizer;
+pub mod ebook;
 pub mod email;
 pub mod exif;
 pub mod glb_3d_model;
+pub mod notebook;
 pub mod office_convert;
 pub mod pdf_ocr;
 pub mod pdf_text;
@@ -22,10 +25,13 @@ mod test_extraction_config;
 
 pub use archive::ArchiveAdapter;
 pub use audio_transcribe::AudioTranscribeAdapter;
+pub use chat_export::ChatExportAdapter;
 pub use code_ast::CodeAstAdapter;
 pub use content_summarizer::ContentSummarizer;
+pub use ebook::EbookAdapter;
 pub use email::EmailAdapter;
 pub use glb_3d_model::Glb3DModelAdapter;
+pub use notebook::NotebookAdapter;
 pub use office_convert::OfficeConvertAdapter;
 pub use pdf_ocr::PdfOcrAdapter;
 pub use pdf_text::PdfTextAdapter;
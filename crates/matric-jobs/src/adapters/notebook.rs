@@ -0,0 +1,474 @@
+//! Jupyter notebook adapter — converts `.ipynb` files (nbformat 4) to Markdown.
+//!
+//! Markdown cells are kept as written. Code cells become fenced blocks tagged
+//! with the kernel language, followed by their outputs: stream and plain-text
+//! results as `text` blocks, errors as `ename: evalue`, and rich outputs
+//! (images, HTML, widgets) as a one-line placeholder naming the MIME type.
+//!
+//! Outputs are included unless `include_outputs: false` is set in the job
+//! config or `NOTEBOOK_INCLUDE_OUTPUTS=false`; each output is truncated to
+//! `max_output_chars` / `NOTEBOOK_MAX_OUTPUT_CHARS` (default 2000).
+//!
+//! Code cells are also run through the CodeAst declaration scanner so
+//! functions and classes defined in the notebook are listed in metadata (with
+//! their cell index) and in a trailing "Definitions" section for code search.
+
+use async_trait::async_trait;
+use serde_json::{json, Value as JsonValue};
+
+use matric_core::{ExtractionAdapter, ExtractionResult, ExtractionStrategy, Result};
+
+use super::code_ast::{extract_declarations, normalize_language};
+
+/// Default maximum characters kept from a single cell output.
+const DEFAULT_MAX_OUTPUT_CHARS: usize = 2_000;
+
+/// Output MIME types rendered as text, in order of preference.
+const TEXT_OUTPUT_TYPES: &[&str] = &["text/plain", "text/markdown"];
+
+fn notebook_error(phase: &str, reason: &str) -> matric_core::Error {
+    matric_core::Error::InvalidInput(format!(
+        "Notebook extraction failed; phase={phase}; reason={reason}"
+    ))
+}
+
+/// Join a notebook multiline string, stored either as a string or a list of lines.
+fn multiline(value: Option<&JsonValue>) -> String {
+    match value {
+        Some(JsonValue::String(s)) => s.clone(),
+        Some(JsonValue::Array(lines)) => lines.iter().filter_map(JsonValue::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Remove ANSI escape sequences (colored tracebacks and progress bars).
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            if chars.peek() == Some(&'[') {
+                chars.next();
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Truncate to `max_chars`, noting how much was dropped. Returns whether it was cut.
+fn truncate_output(text: &str, max_chars: usize) -> (String, bool) {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return (text.to_string(), false);
+    }
+    let kept: String = text.chars().take(max_chars).collect();
+    (
+        format!("{}\n… (truncated, {total} chars total)", kept.trim_end()),
+        true,
+    )
+}
+
+/// Fence `text`, using a longer fence if the text itself contains one.
+fn fenced(language: &str, text: &str) -> String {
+    let mut fence = "```".to_string();
+    while text.contains(&fence) {
+        fence.push('`');
+    }
+    format!(
+        "{fence}{language}\n{}\n{fence}\n",
+        text.trim_end_matches('\n')
+    )
+}
+
+struct OutputOptions {
+    include: bool,
+    max_chars: usize,
+}
+
+#[derive(Default)]
+struct OutputStats {
+    count: usize,
+    truncated: usize,
+    rich: usize,
+    errors: usize,
+}
+
+/// Render one code cell output as Markdown.
+fn render_output(output: &JsonValue, options: &OutputOptions, stats: &mut OutputStats) -> String {
+    stats.count += 1;
+    let text = match output.get("output_type").and_then(JsonValue::as_str) {
+        Some("stream") => multiline(output.get("text")),
+        Some("error") => {
+            stats.errors += 1;
+            format!(
+                "{}: {}",
+                output
+                    .get("ename")
+                    .and_then(JsonValue::as_str)
+                    .unwrap_or("Error"),
+                output
+                    .get("evalue")
+                    .and_then(JsonValue::as_str)
+                    .unwrap_or("")
+            )
+        }
+        Some("execute_result") | Some("display_data") => {
+            let data = output.get("data");
+            match TEXT_OUTPUT_TYPES
+                .iter()
+                .find_map(|mime| data.and_then(|d| d.get(*mime)))
+            {
+                Some(text) => multiline(Some(text)),
+                None => {
+                    stats.rich += 1;
+                    let mime = data
+                        .and_then(JsonValue::as_object)
+                        .and_then(|d| d.keys().next().cloned())
+                        .unwrap_or_else(|| "unknown".to_string());
+                    return format!("_[{mime} output]_\n");
+                }
+            }
+        }
+        _ => return String::new(),
+    };
+
+    let text = strip_ansi(&text);
+    if text.trim().is_empty() {
+        return String::new();
+    }
+    let (text, truncated) = truncate_output(&text, options.max_chars);
+    if truncated {
+        stats.truncated += 1;
+    }
+    fenced("text", &text)
+}
+
+/// Adapter for Jupyter notebooks.
+pub struct NotebookAdapter;
+
+#[async_trait]
+impl ExtractionAdapter for NotebookAdapter {
+    fn strategy(&self) -> ExtractionStrategy {
+        ExtractionStrategy::Notebook
+    }
+
+    async fn extract(
+        &self,
+        data: &[u8],
+        _filename: &str,
+        _mime_type: &str,
+        config: &JsonValue,
+    ) -> Result<ExtractionResult> {
+        let notebook: JsonValue =
+            serde_json::from_slice(data).map_err(|_| notebook_error("parse", "invalid_json"))?;
+        let nbformat = notebook.get("nbformat").and_then(JsonValue::as_u64);
+        if nbformat.is_some_and(|v| v < 4) {
+            return Err(notebook_error("parse", "unsupported_nbformat"));
+        }
+        let cells = notebook
+            .get("cells")
+            .and_then(JsonValue::as_array)
+            .ok_or_else(|| notebook_error("parse", "missing_cells"))?;
+
+        let options = OutputOptions {
+            include: config
+                .get("include_outputs")
+                .and_then(JsonValue::as_bool)
+                .unwrap_or_else(|| {
+                    std::env::var("NOTEBOOK_INCLUDE_OUTPUTS")
+                        .map(|v| v != "false" && v != "0")
+                        .unwrap_or(true)
+                }),
+            max_chars: config
+                .get("max_output_chars")
+                .and_then(JsonValue::as_u64)
+                .map(|v| v as usize)
+                .unwrap_or_else(|| {
+                    std::env::var("NOTEBOOK_MAX_OUTPUT_CHARS")
+                        .ok()
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(DEFAULT_MAX_OUTPUT_CHARS)
+                }),
+        };
+
+        let meta = notebook.get("metadata");
+        let kernelspec = meta.and_then(|m| m.get("kernelspec"));
+        let language_info = meta.and_then(|m| m.get("language_info"));
+        let language = language_info
+            .and_then(|l| l.get("name"))
+            .or_else(|| kernelspec.and_then(|k| k.get("language")))
+            .and_then(JsonValue::as_str)
+            .unwrap_or("python")
+            .to_lowercase();
+        let code_language = normalize_language(&language);
+
+        let mut sections: Vec<String> = Vec::new();
+        let mut declarations: Vec<JsonValue> = Vec::new();
+        let mut stats = OutputStats::default();
+        let (mut code_cells, mut markdown_cells) = (0usize, 0usize);
+
+        for (index, cell) in cells.iter().enumerate() {
+            let source = multiline(cell.get("source"));
+            match cell.get("cell_type").and_then(JsonValue::as_str) {
+                Some("markdown") => {
+                    markdown_cells += 1;
+                    if !source.trim().is_empty() {
+                        sections.push(format!("{}\n", source.trim_end()));
+                    }
+                }
+                Some("code") => {
+                    code_cells += 1;
+                    if source.trim().is_empty() {
+                        continue;
+                    }
+                    let mut section = fenced(&language, &source);
+                    if let Some(lang) = code_language {
+                        for decl in extract_declarations(&source, lang) {
+                            let mut entry = serde_json::to_value(&decl).unwrap_or_default();
+                            entry["cell"] = json!(index);
+                            declarations.push(entry);
+                        }
+                    }
+                    if options.include {
+                        for output in cell
+                            .get("outputs")
+                            .and_then(JsonValue::as_array)
+                            .into_iter()
+                            .flatten()
+                        {
+                            section.push_str(&render_output(output, &options, &mut stats));
+                        }
+                    }
+                    sections.push(section);
+                }
+                Some("raw") if !source.trim().is_empty() => {
+                    sections.push(fenced("", &source));
+                }
+                _ => {}
+            }
+        }
+
+        if !declarations.is_empty() {
+            let mut index = String::from("## Definitions\n\n");
+            for decl in &declarations {
+                index.push_str(&format!(
+                    "- {} `{}` (cell {})\n",
+                    decl["kind"].as_str().unwrap_or("declaration"),
+                    decl["name"].as_str().unwrap_or(""),
+                    decl["cell"]
+                ));
+            }
+            sections.push(index);
+        }
+
+        let text = sections.join("\n");
+        let metadata = json!({
+            "format": "ipynb",
+            "nbformat": nbformat,
+            "nbformat_minor": notebook.get("nbformat_minor"),
+            "language": language,
+            "language_version": language_info.and_then(|l| l.get("version")),
+            "kernel": {
+                "name": kernelspec.and_then(|k| k.get("name")),
+                "display_name": kernelspec.and_then(|k| k.get("display_name")),
+            },
+            "cell_count": cells.len(),
+            "code_cell_count": code_cells,
+            "markdown_cell_count": markdown_cells,
+            "outputs_included": options.include,
+            "output_count": stats.count,
+            "outputs_truncated": stats.truncated,
+            "rich_output_count": stats.rich,
+            "error_output_count": stats.errors,
+            "total_declarations": declarations.len(),
+            "declarations": declarations,
+            "char_count": text.len(),
+        });
+
+        Ok(ExtractionResult {
+            extracted_text: (!text.is_empty()).then_some(text),
+            metadata,
+            ai_description: None,
+            preview_data: None,
+            derived_files: vec![],
+            derived_notes: vec![],
+        })
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        Ok(true) // No external dependencies
+    }
+
+    fn name(&self) -> &str {
+        "notebook"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notebook() -> JsonValue {
+        json!({
+            "nbformat": 4,
+            "nbformat_minor": 5,
+            "metadata": {
+                "kernelspec": {"name": "python3", "display_name": "Python 3", "language": "python"},
+                "language_info": {"name": "python", "version": "3.11.4"}
+            },
+            "cells": [
+                {"cell_type": "markdown", "source": ["# Analysis\n", "Load the data."]},
+                {"cell_type": "code", "execution_count": 1,
+                 "source": ["import pandas as pd\n", "\n", "def load(path):\n", "    return pd.read_csv(path)\n"],
+                 "outputs": []},
+                {"cell_type": "code", "execution_count": 2,
+                 "source": "df = load('x.csv')\nprint(len(df))",
+                 "outputs": [
+                    {"output_type": "stream", "name": "stdout", "text": ["42\n"]},
+                    {"output_type": "display_data", "data": {"image/png": "iVBOR..."}}
+                 ]},
+                {"cell_type": "code", "execution_count": 3, "source": "1/0",
+                 "outputs": [{"output_type": "error", "ename": "ZeroDivisionError",
+                              "evalue": "division by zero", "traceback": ["\u{1b}[0;31m..."]}]},
+                {"cell_type": "code", "source": "", "outputs": []}
+            ]
+        })
+    }
+
+    async fn extract(nb: &JsonValue, config: JsonValue) -> ExtractionResult {
+        NotebookAdapter
+            .extract(
+                nb.to_string().as_bytes(),
+                "analysis.ipynb",
+                "application/x-ipynb+json",
+                &config,
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_notebook_to_markdown() {
+        let result = extract(&notebook(), json!({})).await;
+        let text = result.extracted_text.unwrap();
+
+        assert!(
+            text.starts_with("# Analysis\nLoad the data.\n"),
+            "got:\n{text}"
+        );
+        assert!(text.contains("```python\nimport pandas as pd\n\ndef load(path):\n"));
+        assert!(text.contains("```text\n42\n```"));
+        assert!(text.contains("_[image/png output]_"));
+        assert!(text.contains("```text\nZeroDivisionError: division by zero\n```"));
+        assert!(text.contains("## Definitions\n\n- function `load` (cell 1)\n"));
+    }
+
+    #[tokio::test]
+    async fn test_notebook_metadata() {
+        let result = extract(&notebook(), json!({})).await;
+        let meta = &result.metadata;
+
+        assert_eq!(meta["language"], "python");
+        assert_eq!(meta["language_version"], "3.11.4");
+        assert_eq!(meta["kernel"]["name"], "python3");
+        assert_eq!(meta["kernel"]["display_name"], "Python 3");
+        assert_eq!(meta["cell_count"], 5);
+        assert_eq!(meta["code_cell_count"], 4);
+        assert_eq!(meta["markdown_cell_count"], 1);
+        assert_eq!(meta["output_count"], 3);
+        assert_eq!(meta["rich_output_count"], 1);
+        assert_eq!(meta["error_output_count"], 1);
+        assert_eq!(meta["total_declarations"], 1);
+        assert_eq!(meta["declarations"][0]["name"], "load");
+        assert_eq!(meta["declarations"][0]["cell"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_notebook_outputs_excluded_by_config() {
+        let result = extract(&notebook(), json!({ "include_outputs": false })).await;
+        let text = result.extracted_text.unwrap();
+        assert!(!text.contains("42"));
+        assert!(!text.contains("ZeroDivisionError"));
+        assert_eq!(result.metadata["outputs_included"], false);
+        assert_eq!(result.metadata["output_count"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_notebook_output_truncated() {
+        let mut nb = notebook();
+        nb["cells"][2]["outputs"][0]["text"] = json!("x".repeat(50));
+        let result = extract(&nb, json!({ "max_output_chars": 40 })).await;
+        let text = result.extracted_text.unwrap();
+        assert!(
+            text.contains(&format!(
+                "{}\n… (truncated, 50 chars total)",
+                "x".repeat(40)
+            )),
+            "got:\n{text}"
+        );
+        assert_eq!(result.metadata["outputs_truncated"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_notebook_kernel_language_fences() {
+        let nb = json!({
+            "nbformat": 4,
+            "metadata": {"kernelspec": {"name": "ir", "language": "R"}},
+            "cells": [{"cell_type": "code", "source": "summary(cars)", "outputs": []}]
+        });
+        let result = extract(&nb, json!({})).await;
+        assert!(result
+            .extracted_text
+            .unwrap()
+            .contains("```r\nsummary(cars)\n```"));
+        assert_eq!(result.metadata["total_declarations"], 0);
+    }
+
+    #[test]
+    fn test_fenced_escapes_inner_fence() {
+        assert_eq!(
+            fenced("md", "```py\nx\n```"),
+            "````md\n```py\nx\n```\n````\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\u{1b}[0;31mError\u{1b}[0m!"), "Error!");
+    }
+
+    #[tokio::test]
+    async fn test_notebook_rejects_invalid_input() {
+        for (data, reason) in [
+            (&b"not json"[..], "invalid_json"),
+            (
+                &br#"{"nbformat": 3, "worksheets": []}"#[..],
+                "unsupported_nbformat",
+            ),
+            (&br#"{"nbformat": 4}"#[..], "missing_cells"),
+        ] {
+            let err = NotebookAdapter
+                .extract(data, "a.ipynb", "application/x-ipynb+json", &json!({}))
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(&err, matric_core::Error::InvalidInput(msg) if msg.contains(reason)),
+                "got: {err:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notebook_adapter_identity() {
+        assert_eq!(NotebookAdapter.strategy(), ExtractionStrategy::Notebook);
+        assert_eq!(NotebookAdapter.name(), "notebook");
+        assert!(NotebookAdapter.health_check().await.unwrap());
+    }
+}
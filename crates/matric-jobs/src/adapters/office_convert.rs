@@ -178,6 +178,7 @@ impl ExtractionAdapter for OfficeConvertAdapter {
                     ai_description: None,
                     preview_data: None,
                     derived_files: vec![],
+                    derived_notes: vec![],
                 });
             }
         };
@@ -233,6 +234,7 @@ impl ExtractionAdapter for OfficeConvertAdapter {
             ai_description: None,
             preview_data: None,
             derived_files: vec![],
+            derived_notes: vec![],
         })
     }
 
@@ -1,10 +1,13 @@
-//! PdfOcrAdapter — OCRs scanned PDFs using pdftoppm + tesseract.
+//! PdfOcrAdapter — OCRs scanned PDFs and images using pdftoppm + tesseract.
 //!
 //! Pipeline: PDF → pdftoppm (render pages to PNG) → tesseract (OCR each page) → concatenate.
-//! Triggered when PdfTextAdapter flags `needs_ocr: true` in metadata (< 50 chars extracted).
+//! Images skip rendering and are OCRed as a single page. The extraction handler
+//! falls back to this adapter when PdfTextAdapter flags `needs_ocr: true` in
+//! metadata (< 50 chars extracted). Disabled unless `OCR_ENABLED` is set.
 
 use std::fs;
 use std::io::Write;
+use std::path::PathBuf;
 
 use async_trait::async_trait;
 use serde_json::{json, Value as JsonValue};
@@ -12,7 +15,7 @@ use tempfile::{NamedTempFile, TempDir};
 use tokio::process::Command;
 use tracing::{debug, warn};
 
-use matric_core::defaults::EXTRACTION_CMD_TIMEOUT_SECS;
+use matric_core::defaults::{ENV_OCR_ENABLED, EXTRACTION_CMD_TIMEOUT_SECS};
 use matric_core::{ExtractionAdapter, ExtractionResult, ExtractionStrategy, Result};
 
 pub struct PdfOcrAdapter;
@@ -152,6 +155,184 @@ async fn run_cmd_status(
     Ok(())
 }
 
+/// Whether OCR is switched on via `OCR_ENABLED` (default: off).
+fn ocr_enabled() -> bool {
+    std::env::var(ENV_OCR_ENABLED)
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false)
+}
+
+/// Map a language hint to a Tesseract `-l` argument.
+///
+/// Accepts Tesseract codes (`deu`, `chi_sim`) as-is and maps common ISO 639-1
+/// codes (`de`, `zh`). Several languages may be given `+`- or `,`-separated.
+/// Falls back to `eng` when no usable code remains.
+fn tesseract_languages(hint: &str) -> String {
+    let codes: Vec<&str> = hint
+        .split(['+', ','])
+        .map(str::trim)
+        .filter(|code| {
+            !code.is_empty()
+                && code
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        })
+        .map(|code| match code {
+            "en" => "eng",
+            "de" => "deu",
+            "fr" => "fra",
+            "es" => "spa",
+            "it" => "ita",
+            "pt" => "por",
+            "nl" => "nld",
+            "pl" => "pol",
+            "sv" => "swe",
+            "tr" => "tur",
+            "ru" => "rus",
+            "uk" => "ukr",
+            "ar" => "ara",
+            "hi" => "hin",
+            "ja" => "jpn",
+            "ko" => "kor",
+            "zh" => "chi_sim",
+            other => other,
+        })
+        .collect();
+    if codes.is_empty() {
+        "eng".to_string()
+    } else {
+        codes.join("+")
+    }
+}
+
+/// Read the language hint from config: a string or an array of strings.
+fn config_languages(config: &JsonValue) -> String {
+    let hint = match config.get("language") {
+        Some(JsonValue::String(hint)) => hint.to_ascii_lowercase(),
+        Some(JsonValue::Array(hints)) => hints
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(str::to_ascii_lowercase)
+            .collect::<Vec<_>>()
+            .join("+"),
+        _ => String::new(),
+    };
+    tesseract_languages(&hint)
+}
+
+fn round_confidence(confidence: f64) -> f64 {
+    (confidence * 10.0).round() / 10.0
+}
+
+/// Mean word confidence (0–100) and word count from Tesseract TSV output.
+///
+/// Word rows are level 5; layout rows carry a confidence of -1.
+fn tsv_word_confidence(tsv: &str) -> (Option<f64>, usize) {
+    let mut sum = 0.0;
+    let mut words = 0;
+    for line in tsv.lines().skip(1) {
+        let columns: Vec<&str> = line.split('\t').collect();
+        if columns.len() < 12 || columns[0] != "5" || columns[11].trim().is_empty() {
+            continue;
+        }
+        match columns[10].trim().parse::<f64>() {
+            Ok(confidence) if confidence >= 0.0 => {
+                sum += confidence;
+                words += 1;
+            }
+            _ => {}
+        }
+    }
+    (
+        (words > 0).then(|| round_confidence(sum / words as f64)),
+        words,
+    )
+}
+
+/// OCR result for one page.
+struct OcrPage {
+    text: String,
+    /// Mean word confidence, `None` when no words were recognised.
+    confidence: Option<f64>,
+    word_count: usize,
+}
+
+/// OCR one image with tesseract, writing text and TSV output in one pass.
+async fn ocr_page(
+    img_path: &std::path::Path,
+    output_base: &std::path::Path,
+    language: &str,
+) -> Result<OcrPage> {
+    // tesseract INPUT OUTPUT -l LANG txt tsv -- writes OUTPUT.txt and OUTPUT.tsv
+    run_cmd_status(
+        Command::new("tesseract")
+            .arg(img_path)
+            .arg(output_base)
+            .arg("-l")
+            .arg(language)
+            .arg("txt")
+            .arg("tsv"),
+        EXTRACTION_CMD_TIMEOUT_SECS,
+        "tesseract",
+        "ocr_page",
+    )
+    .await?;
+
+    let text = fs::read_to_string(output_base.with_extension("txt")).map_err(|e| {
+        matric_core::Error::Internal(pdf_ocr_io_failure_detail("read_ocr_text", &e))
+    })?;
+    let (confidence, word_count) = fs::read_to_string(output_base.with_extension("tsv"))
+        .map(|tsv| tsv_word_confidence(&tsv))
+        .unwrap_or((None, 0));
+    Ok(OcrPage {
+        text,
+        confidence,
+        word_count,
+    })
+}
+
+/// Render PDF pages to PNG with pdftoppm, returning page images in order.
+async fn render_pdf_pages(data: &[u8], img_dir: &TempDir, dpi: u64) -> Result<Vec<PathBuf>> {
+    // Write PDF to temp file
+    let mut tmpfile = NamedTempFile::new().map_err(|e| {
+        matric_core::Error::Internal(pdf_ocr_io_failure_detail("create_temp_file", &e))
+    })?;
+    tmpfile.write_all(data).map_err(|e| {
+        matric_core::Error::Internal(pdf_ocr_io_failure_detail("write_temp_file", &e))
+    })?;
+    let img_prefix = img_dir.path().join("page");
+
+    run_cmd_status(
+        Command::new("pdftoppm")
+            .arg("-png")
+            .arg("-r")
+            .arg(dpi.to_string())
+            .arg(tmpfile.path())
+            .arg(&img_prefix),
+        EXTRACTION_CMD_TIMEOUT_SECS * 3, // Allow more time for rendering
+        "pdftoppm",
+        "render",
+    )
+    .await?;
+
+    // Find all rendered page images (sorted by name for correct order)
+    let mut page_images = Vec::new();
+    let entries = fs::read_dir(img_dir.path()).map_err(|e| {
+        matric_core::Error::Internal(pdf_ocr_io_failure_detail("read_temp_dir", &e))
+    })?;
+    for entry in entries {
+        let entry = entry.map_err(|e| {
+            matric_core::Error::Internal(pdf_ocr_io_failure_detail("read_dir_entry", &e))
+        })?;
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) == Some("png") {
+            page_images.push(path);
+        }
+    }
+    page_images.sort();
+    Ok(page_images)
+}
+
 #[async_trait]
 impl ExtractionAdapter for PdfOcrAdapter {
     fn strategy(&self) -> ExtractionStrategy {
@@ -162,7 +343,7 @@ impl ExtractionAdapter for PdfOcrAdapter {
         &self,
         data: &[u8],
         filename: &str,
-        _mime_type: &str,
+        mime_type: &str,
         config: &JsonValue,
     ) -> Result<ExtractionResult> {
         if data.is_empty() {
@@ -171,8 +352,10 @@ impl ExtractionAdapter for PdfOcrAdapter {
             ));
         }
 
+        let is_image = mime_type.starts_with("image/");
+
         // Validate PDF magic bytes
-        if data.len() < 4 || &data[0..4] != b"%PDF" {
+        if !is_image && (data.len() < 4 || &data[0..4] != b"%PDF") {
             return Err(matric_core::Error::InvalidInput(format!(
                 "File is not a valid PDF; filename_len={}; reason=missing_pdf_header",
                 pdf_ocr_text_len(filename)
@@ -181,61 +364,29 @@ impl ExtractionAdapter for PdfOcrAdapter {
 
         // Read config
         let dpi = config.get("dpi").and_then(|v| v.as_u64()).unwrap_or(300);
-        let language = config
-            .get("language")
-            .and_then(|v| v.as_str())
-            .unwrap_or("eng");
-
-        // Write PDF to temp file
-        let mut tmpfile = NamedTempFile::new().map_err(|e| {
-            matric_core::Error::Internal(pdf_ocr_io_failure_detail("create_temp_file", &e))
-        })?;
-        tmpfile.write_all(data).map_err(|e| {
-            matric_core::Error::Internal(pdf_ocr_io_failure_detail("write_temp_file", &e))
-        })?;
-        let pdf_path = tmpfile.path().to_string_lossy().to_string();
+        let language = config_languages(config);
 
-        // Create temp dir for rendered page images
+        // Create temp dir for page images and OCR output
         let img_dir = TempDir::new().map_err(|e| {
             matric_core::Error::Internal(pdf_ocr_io_failure_detail("create_temp_dir", &e))
         })?;
-        let img_prefix = img_dir.path().join("page").to_string_lossy().to_string();
 
-        debug!(
-            filename_len = pdf_ocr_text_len(filename),
-            dpi, language, "Rendering PDF pages for OCR"
-        );
-
-        // Step 1: Render PDF pages to PNG using pdftoppm
-        run_cmd_status(
-            Command::new("pdftoppm")
-                .arg("-png")
-                .arg("-r")
-                .arg(dpi.to_string())
-                .arg(&pdf_path)
-                .arg(&img_prefix),
-            EXTRACTION_CMD_TIMEOUT_SECS * 3, // Allow more time for rendering
-            "pdftoppm",
-            "render",
-        )
-        .await?;
-
-        // Find all rendered page images (sorted by name for correct order)
-        let mut page_images: Vec<String> = Vec::new();
-        let entries = fs::read_dir(img_dir.path()).map_err(|e| {
-            matric_core::Error::Internal(pdf_ocr_io_failure_detail("read_temp_dir", &e))
-        })?;
-
-        for entry in entries {
-            let entry = entry.map_err(|e| {
-                matric_core::Error::Internal(pdf_ocr_io_failure_detail("read_dir_entry", &e))
+        let page_images = if is_image {
+            let ext = mime_type.trim_start_matches("image/");
+            let image_path = img_dir.path().join(format!("image.{}", ext));
+            fs::write(&image_path, data).map_err(|e| {
+                matric_core::Error::Internal(pdf_ocr_io_failure_detail("write_temp_image", &e))
             })?;
-            let path = entry.path();
-            if path.extension().and_then(|e| e.to_str()) == Some("png") {
-                page_images.push(path.to_string_lossy().to_string());
-            }
-        }
-        page_images.sort();
+            vec![image_path]
+        } else {
+            debug!(
+                filename_len = pdf_ocr_text_len(filename),
+                dpi,
+                language = %language,
+                "Rendering PDF pages for OCR"
+            );
+            render_pdf_pages(data, &img_dir, dpi).await?
+        };
 
         if page_images.is_empty() {
             return Ok(ExtractionResult {
@@ -249,6 +400,7 @@ impl ExtractionAdapter for PdfOcrAdapter {
                 ai_description: None,
                 preview_data: None,
                 derived_files: vec![],
+                derived_notes: vec![],
             });
         }
 
@@ -258,31 +410,27 @@ impl ExtractionAdapter for PdfOcrAdapter {
             "OCRing rendered pages"
         );
 
-        // Step 2: OCR each page with tesseract
+        // OCR each page with tesseract
         let mut page_texts = Vec::new();
+        let mut pages = Vec::new();
+        let mut confidence_sum = 0.0;
+        let mut word_total = 0;
 
         for (i, img_path) in page_images.iter().enumerate() {
-            // tesseract INPUT OUTPUT -l LANG -- outputs OUTPUT.txt
             let output_base = img_dir.path().join(format!("ocr_{}", i));
-            let output_path = format!("{}.txt", output_base.to_string_lossy());
-
-            let result = run_cmd_status(
-                Command::new("tesseract")
-                    .arg(img_path)
-                    .arg(output_base.to_string_lossy().as_ref())
-                    .arg("-l")
-                    .arg(language),
-                EXTRACTION_CMD_TIMEOUT_SECS,
-                "tesseract",
-                "ocr_page",
-            )
-            .await;
-
-            match result {
-                Ok(()) => {
-                    if let Ok(text) = fs::read_to_string(&output_path) {
-                        page_texts.push(text);
+            match ocr_page(img_path, &output_base, &language).await {
+                Ok(page) => {
+                    if let Some(confidence) = page.confidence {
+                        confidence_sum += confidence * page.word_count as f64;
+                        word_total += page.word_count;
                     }
+                    pages.push(json!({
+                        "page": i + 1,
+                        "char_count": page.text.len(),
+                        "word_count": page.word_count,
+                        "confidence": page.confidence,
+                    }));
+                    page_texts.push(page.text);
                 }
                 Err(e) => {
                     let error_text = e.to_string();
@@ -292,6 +440,10 @@ impl ExtractionAdapter for PdfOcrAdapter {
                         error_reason = pdf_ocr_error_reason_code(&error_text),
                         "OCR failed for page, skipping"
                     );
+                    pages.push(json!({
+                        "page": i + 1,
+                        "failed": true,
+                    }));
                     page_texts.push(format!("[OCR failed for page {}]", i + 1));
                 }
             }
@@ -301,6 +453,8 @@ impl ExtractionAdapter for PdfOcrAdapter {
         let char_count = full_text.len();
         let line_count = full_text.lines().count();
         let page_count = page_images.len();
+        let mean_confidence =
+            (word_total > 0).then(|| round_confidence(confidence_sum / word_total as f64));
 
         Ok(ExtractionResult {
             extracted_text: Some(full_text),
@@ -311,14 +465,20 @@ impl ExtractionAdapter for PdfOcrAdapter {
                 "char_count": char_count,
                 "line_count": line_count,
                 "engine": "tesseract",
+                "mean_confidence": mean_confidence,
+                "pages": pages,
             }),
             ai_description: None,
             preview_data: None,
             derived_files: vec![],
+            derived_notes: vec![],
         })
     }
 
     async fn health_check(&self) -> Result<bool> {
+        if !ocr_enabled() {
+            return Ok(false);
+        }
         // Check both pdftoppm and tesseract are available
         let pdftoppm_ok = match Command::new("pdftoppm").arg("-v").output().await {
             Ok(output) => output.status.success() || output.status.code() == Some(99),
@@ -426,6 +586,33 @@ mod tests {
         assert!(!detail.contains("permission denied at"));
     }
 
+    #[test]
+    fn tesseract_languages_maps_hints() {
+        assert_eq!(tesseract_languages(""), "eng");
+        assert_eq!(tesseract_languages("deu"), "deu");
+        assert_eq!(tesseract_languages("en"), "eng");
+        assert_eq!(tesseract_languages("en, de"), "eng+deu");
+        assert_eq!(tesseract_languages("eng+chi_sim"), "eng+chi_sim");
+        assert_eq!(tesseract_languages("eng; rm -rf"), "eng");
+        assert_eq!(
+            config_languages(&json!({ "language": ["FR", "es"] })),
+            "fra+spa"
+        );
+        assert_eq!(config_languages(&json!({})), "eng");
+    }
+
+    #[test]
+    fn tsv_word_confidence_averages_word_rows() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   1\t1\t0\t0\t0\t0\t0\t0\t100\t100\t-1\t\n\
+                   5\t1\t1\t1\t1\t1\t0\t0\t10\t10\t90.5\tHello\n\
+                   5\t1\t1\t1\t1\t2\t0\t0\t10\t10\t70\tworld\n\
+                   5\t1\t1\t1\t1\t3\t0\t0\t10\t10\t95\t \n";
+
+        assert_eq!(tsv_word_confidence(tsv), (Some(80.3), 2));
+        assert_eq!(tsv_word_confidence(""), (None, 0));
+    }
+
     #[tokio::test]
     async fn test_pdf_ocr_health_check() {
         let adapter = PdfOcrAdapter;
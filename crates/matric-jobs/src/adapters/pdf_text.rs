@@ -20,7 +20,8 @@ use matric_core::{ExtractionAdapter, ExtractionResult, ExtractionStrategy, Resul
 /// timeout.
 ///
 /// If extraction yields empty/near-empty text, `metadata["needs_ocr"]` is set
-/// to `true` as a signal for a future `PdfOcrAdapter`.
+/// to `true`; the extraction handler then retries with `PdfOcrAdapter` when
+/// OCR is enabled.
 pub struct PdfTextAdapter;
 
 /// Parse `pdfinfo` output into a JSON metadata object.
@@ -290,6 +291,7 @@ impl ExtractionAdapter for PdfTextAdapter {
             ai_description: None,
             preview_data: None,
             derived_files: vec![],
+            derived_notes: vec![],
         })
     }
 
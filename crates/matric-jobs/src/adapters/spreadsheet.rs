@@ -1,23 +1,36 @@
-//! Spreadsheet extraction adapter — reads xlsx/xls/ods/xlsb files using calamine.
+//! Spreadsheet extraction adapter — reads xlsx/xls/ods/xlsb files using calamine,
+//! and CSV/TSV files with a built-in parser.
 //!
 //! Iterates all sheets and converts each one to a markdown table. The first row
 //! is used as headers if every cell contains a non-empty string; otherwise,
-//! generic "Column A / Column B / …" headers are generated.
+//! generic "Column A / Column B / …" headers are generated. Tables are capped at
+//! `SPREADSHEET_MAX_TABLE_ROWS` data rows (default 500, `max_rows` in the job
+//! config overrides it) and followed by per-column statistics: inferred type,
+//! non-empty and distinct counts, and min/max/mean for numeric columns.
 //!
-//! Structured metadata includes sheet names, per-sheet dimensions, and aggregate
-//! row/column counts. Empty sheets are skipped gracefully (marked in metadata).
+//! With a generation backend attached, up to `SPREADSHEET_SUMMARY_MAX_SHEETS`
+//! sheets (default 10) get a short LLM summary, placed under the sheet heading
+//! and joined into the attachment's `ai_description`. `summarize: false` in the
+//! job config skips them; a failed summary only drops that sheet's summary.
+//!
+//! Structured metadata includes sheet names, per-sheet dimensions and column
+//! statistics, and aggregate row/column counts. Empty sheets are skipped
+//! gracefully (marked in metadata).
 //!
 //! No external binaries are required — calamine is pure Rust, so the health
 //! check always returns `Ok(true)`.
 
 use std::io::Cursor;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use calamine::{open_workbook_auto_from_rs, Data, Reader};
 use serde_json::Value as JsonValue;
-use tracing::debug;
+use tracing::{debug, warn};
 
-use matric_core::{ExtractionAdapter, ExtractionResult, ExtractionStrategy, Result};
+use matric_core::{
+    ExtractionAdapter, ExtractionResult, ExtractionStrategy, GenerationBackend, Result,
+};
 
 fn spreadsheet_text_len(text: &str) -> usize {
     text.len()
@@ -106,6 +119,18 @@ fn row_looks_like_headers(row: &[Data]) -> bool {
 ///
 /// Returns `None` for sheets with no data (no rows, or all rows are empty).
 fn sheet_to_markdown(sheet_name: &str, rows: &[Vec<Data>]) -> Option<String> {
+    sheet_to_markdown_limited(sheet_name, rows, usize::MAX)
+}
+
+/// Build a markdown section showing at most `max_rows` data rows.
+///
+/// The section heading keeps the full row count; a note after the table says
+/// how many rows were shown when the sheet was cut short.
+fn sheet_to_markdown_limited(
+    sheet_name: &str,
+    rows: &[Vec<Data>],
+    max_rows: usize,
+) -> Option<String> {
     // Find the maximum column count across all rows
     let col_count = rows.iter().map(|r| r.len()).max().unwrap_or(0);
     if col_count == 0 {
@@ -160,7 +185,7 @@ fn sheet_to_markdown(sheet_name: &str, rows: &[Vec<Data>]) -> Option<String> {
     md.push('\n');
 
     // Data rows
-    for row in data_rows {
+    for row in data_rows.iter().take(max_rows) {
         md.push_str("| ");
         let cells: Vec<String> = (0..col_count)
             .map(|i| {
@@ -175,36 +200,423 @@ fn sheet_to_markdown(sheet_name: &str, rows: &[Vec<Data>]) -> Option<String> {
         md.push_str(" |\n");
     }
 
+    if displayed_rows > max_rows {
+        md.push_str(&format!(
+            "\n_Showing first {} of {} rows._\n",
+            max_rows, displayed_rows
+        ));
+    }
+
     Some(md)
 }
 
+// ── Delimited text (CSV / TSV) ───────────────────────────────────────────────
+
+/// Return `"csv"` or `"tsv"` when the upload is delimited text rather than a workbook.
+fn delimited_format(filename: &str, mime_type: &str) -> Option<&'static str> {
+    let mime = mime_type.to_ascii_lowercase();
+    let ext = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    if mime == "text/tab-separated-values" || ext == "tsv" {
+        Some("tsv")
+    } else if mime == "text/csv" || mime == "application/csv" || ext == "csv" {
+        Some("csv")
+    } else {
+        None
+    }
+}
+
+/// Pick the CSV delimiter (`,`, `;` or tab) that occurs most in the first line.
+fn sniff_delimiter(text: &str) -> char {
+    let first_line = text.lines().next().unwrap_or("");
+    [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|d| (first_line.matches(*d).count(), *d == ','))
+        .unwrap_or(',')
+}
+
+/// Type an unquoted delimited field the way a spreadsheet would.
+///
+/// Integers, decimals and booleans become typed cells so column statistics
+/// work the same as for workbooks. Quoted fields and zero-padded codes such as
+/// `"007"` stay text.
+fn delimited_cell(raw: &str, quoted: bool) -> Data {
+    let value = raw.trim();
+    if value.is_empty() {
+        return Data::Empty;
+    }
+    if quoted {
+        return Data::String(value.to_string());
+    }
+    let zero_padded = value.len() > 1 && value.starts_with('0') && !value.starts_with("0.");
+    if !zero_padded {
+        if let Ok(i) = value.parse::<i64>() {
+            return Data::Int(i);
+        }
+        let numeric_chars = value
+            .bytes()
+            .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'+' | b'e' | b'E'));
+        if numeric_chars && value.bytes().any(|b| b.is_ascii_digit()) {
+            if let Ok(f) = value.parse::<f64>() {
+                return Data::Float(f);
+            }
+        }
+    }
+    match value.to_ascii_lowercase().as_str() {
+        "true" => Data::Bool(true),
+        "false" => Data::Bool(false),
+        _ => Data::String(value.to_string()),
+    }
+}
+
+/// Parse delimited text into rows, honouring RFC 4180 quoting.
+///
+/// Quoted fields may contain delimiters, newlines and doubled quotes. Lines
+/// with no non-empty cell are dropped.
+fn parse_delimited(text: &str, delimiter: char) -> Vec<Vec<Data>> {
+    let mut rows = Vec::new();
+    let mut row: Vec<Data> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+            continue;
+        }
+        match c {
+            '"' if field.trim().is_empty() && !quoted => {
+                field.clear();
+                in_quotes = true;
+                quoted = true;
+            }
+            '\r' => {}
+            '\n' => {
+                row.push(delimited_cell(&field, quoted));
+                rows.push(std::mem::take(&mut row));
+                field.clear();
+                quoted = false;
+            }
+            c if c == delimiter => {
+                row.push(delimited_cell(&field, quoted));
+                field.clear();
+                quoted = false;
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() || quoted {
+        row.push(delimited_cell(&field, quoted));
+        rows.push(row);
+    }
+
+    rows.retain(|row| row.iter().any(|cell| !matches!(cell, Data::Empty)));
+    rows
+}
+
+// ── Column statistics ────────────────────────────────────────────────────────
+
+/// Maximum distinct values tracked per column before the count is reported as capped.
+const MAX_TRACKED_DISTINCT: usize = 10_000;
+
+/// Basic per-column statistics over a sheet's data rows.
+struct ColumnStats {
+    name: String,
+    non_empty: usize,
+    empty: usize,
+    numeric: usize,
+    boolean: usize,
+    datetime: usize,
+    distinct: std::collections::HashSet<String>,
+    distinct_capped: bool,
+    min: f64,
+    max: f64,
+    sum: f64,
+}
+
+impl ColumnStats {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            non_empty: 0,
+            empty: 0,
+            numeric: 0,
+            boolean: 0,
+            datetime: 0,
+            distinct: std::collections::HashSet::new(),
+            distinct_capped: false,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.0,
+        }
+    }
+
+    fn add(&mut self, cell: Option<&Data>) {
+        let value = match cell {
+            None | Some(Data::Empty) => {
+                self.empty += 1;
+                return;
+            }
+            Some(Data::Int(i)) => Some(*i as f64),
+            Some(Data::Float(f)) => Some(*f),
+            Some(Data::Bool(_)) => {
+                self.boolean += 1;
+                None
+            }
+            Some(Data::DateTime(_) | Data::DateTimeIso(_) | Data::DurationIso(_)) => {
+                self.datetime += 1;
+                None
+            }
+            Some(_) => None,
+        };
+        self.non_empty += 1;
+        if let Some(v) = value.filter(|v| v.is_finite()) {
+            self.numeric += 1;
+            self.min = self.min.min(v);
+            self.max = self.max.max(v);
+            self.sum += v;
+        }
+        if self.distinct.len() < MAX_TRACKED_DISTINCT {
+            self.distinct
+                .insert(cell_to_string(cell.unwrap_or(&Data::Empty)));
+        } else {
+            self.distinct_capped = true;
+        }
+    }
+
+    /// Dominant cell type: `numeric`, `boolean`, `datetime`, `text`, `mixed` or `empty`.
+    fn column_type(&self) -> &'static str {
+        let text = self.non_empty - self.numeric - self.boolean - self.datetime;
+        match self.non_empty {
+            0 => "empty",
+            n if n == self.numeric => "numeric",
+            n if n == self.boolean => "boolean",
+            n if n == self.datetime => "datetime",
+            n if n == text => "text",
+            _ => "mixed",
+        }
+    }
+
+    fn mean(&self) -> Option<f64> {
+        (self.numeric > 0).then(|| self.sum / self.numeric as f64)
+    }
+
+    fn to_json(&self) -> JsonValue {
+        let mut value = serde_json::json!({
+            "name": self.name,
+            "type": self.column_type(),
+            "non_empty": self.non_empty,
+            "empty": self.empty,
+            "distinct": self.distinct.len(),
+        });
+        if self.distinct_capped {
+            value["distinct_capped"] = JsonValue::Bool(true);
+        }
+        if let Some(mean) = self.mean() {
+            value["min"] = serde_json::json!(self.min);
+            value["max"] = serde_json::json!(self.max);
+            value["mean"] = serde_json::json!(mean);
+        }
+        value
+    }
+}
+
+/// Format a statistic for the column summary table.
+fn format_stat(value: f64) -> String {
+    cell_to_string(&Data::Float((value * 100.0).round() / 100.0))
+}
+
+/// Compute statistics for every column using the same header rules as the table.
+fn column_stats(rows: &[Vec<Data>]) -> Vec<ColumnStats> {
+    let col_count = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    if col_count == 0 {
+        return Vec::new();
+    }
+    let has_headers = row_looks_like_headers(&rows[0]);
+    let data_rows = if has_headers { &rows[1..] } else { rows };
+
+    let mut stats: Vec<ColumnStats> = (0..col_count)
+        .map(|i| {
+            let name = if has_headers {
+                rows[0].get(i).map(cell_to_string).unwrap_or_default()
+            } else {
+                String::new()
+            };
+            ColumnStats::new(if name.is_empty() {
+                column_label(i)
+            } else {
+                name
+            })
+        })
+        .collect();
+    for row in data_rows {
+        for (i, column) in stats.iter_mut().enumerate() {
+            column.add(row.get(i));
+        }
+    }
+    stats
+}
+
+/// Render column statistics as a markdown table.
+fn column_stats_markdown(stats: &[ColumnStats]) -> String {
+    let mut md = String::from(
+        "### Column statistics\n\n\
+         | Column | Type | Non-empty | Distinct | Min | Max | Mean |\n\
+         |----------|----------|----------|----------|----------|----------|----------|\n",
+    );
+    for column in stats {
+        let (min, max, mean) = match column.mean() {
+            Some(mean) => (
+                format_stat(column.min),
+                format_stat(column.max),
+                format_stat(mean),
+            ),
+            None => Default::default(),
+        };
+        let distinct = if column.distinct_capped {
+            format!("{}+", column.distinct.len())
+        } else {
+            column.distinct.len().to_string()
+        };
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} |\n",
+            column.name.replace('|', "\\|"),
+            column.column_type(),
+            column.non_empty,
+            distinct,
+            min,
+            max,
+            mean
+        ));
+    }
+    md
+}
+
+// ── Sheet summaries ──────────────────────────────────────────────────────────
+
+/// Default number of data rows rendered into each sheet's markdown table.
+const DEFAULT_MAX_TABLE_ROWS: usize = 500;
+
+/// Default number of sheets summarized per workbook.
+const DEFAULT_MAX_SUMMARY_SHEETS: usize = 10;
+
+/// Data rows included in the sample table sent to the summary model.
+const SUMMARY_SAMPLE_ROWS: usize = 20;
+
+/// Upper bound on the summary prompt body, in bytes.
+const SUMMARY_PROMPT_MAX_BYTES: usize = 8 * 1024;
+
+const SHEET_SUMMARY_SYSTEM_PROMPT: &str = "You describe spreadsheet tables so they can be \
+found by search. Reply with two or three plain sentences covering what the rows represent, \
+the most informative columns, and notable ranges or categories. No preamble, no markdown.";
+
+/// Read a limit from an env var, falling back to the provided default.
+fn env_limit(var: &str, default: usize) -> usize {
+    std::env::var(var)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Build the summary prompt for one sheet from its sample table and column stats.
+fn sheet_summary_prompt(sample_markdown: &str, stats_markdown: &str) -> String {
+    let mut prompt = format!("{sample_markdown}\n{stats_markdown}");
+    if prompt.len() > SUMMARY_PROMPT_MAX_BYTES {
+        let mut cut = SUMMARY_PROMPT_MAX_BYTES;
+        while !prompt.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        prompt.truncate(cut);
+    }
+    prompt
+}
+
+/// A sheet's rows, or the error text from reading it.
+type SheetRows = std::result::Result<Vec<Vec<Data>>, String>;
+
+/// A sheet ready for assembly: rendered table, stats, and optional summary.
+struct SheetSection {
+    name: String,
+    rows: Vec<Vec<Data>>,
+    table: String,
+    stats: Vec<ColumnStats>,
+    summary: Option<String>,
+}
+
+impl SheetSection {
+    fn markdown(&self) -> String {
+        let stats = column_stats_markdown(&self.stats);
+        match (&self.summary, self.table.split_once("\n\n")) {
+            (Some(summary), Some((heading, table))) => {
+                format!("{heading}\n\n**Summary:** {summary}\n\n{table}\n{stats}")
+            }
+            _ => format!("{}\n{stats}", self.table),
+        }
+    }
+}
+
 // ── Adapter ──────────────────────────────────────────────────────────────────
 
-/// Adapter for extracting content from spreadsheet files (xlsx/xls/ods/xlsb).
+/// Adapter for extracting content from spreadsheet files (xlsx/xls/ods/xlsb, csv/tsv).
 ///
-/// Uses the [`calamine`] crate — a pure-Rust reader with no external binary
-/// dependencies. Format is auto-detected from the file's magic bytes (not
-/// the MIME type or file extension), so the same code path handles all
-/// supported formats.
-pub struct SpreadsheetAdapter;
+/// Workbooks are read with the [`calamine`] crate — a pure-Rust reader with no
+/// external binary dependencies. Format is auto-detected from the file's magic
+/// bytes (not the MIME type or file extension), so the same code path handles
+/// all supported workbook formats. CSV and TSV files are parsed directly and
+/// treated as a single sheet named after the file.
+///
+/// Each sheet becomes a markdown table (capped at `SPREADSHEET_MAX_TABLE_ROWS`
+/// data rows) followed by per-column statistics. When a generation backend is
+/// attached, each sheet also gets a short LLM summary.
+#[derive(Default)]
+pub struct SpreadsheetAdapter {
+    summary_backend: Option<Arc<dyn GenerationBackend>>,
+}
 
-#[async_trait]
-impl ExtractionAdapter for SpreadsheetAdapter {
-    fn strategy(&self) -> ExtractionStrategy {
-        ExtractionStrategy::Spreadsheet
+impl SpreadsheetAdapter {
+    /// Create an adapter that renders tables and statistics without summaries.
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    async fn extract(
-        &self,
+    /// Generate a per-sheet summary with the given backend.
+    pub fn with_summary_backend(mut self, backend: Arc<dyn GenerationBackend>) -> Self {
+        self.summary_backend = Some(backend);
+        self
+    }
+
+    /// Read every sheet as rows; per-sheet read errors are returned in place.
+    fn read_sheets(
         data: &[u8],
         filename: &str,
-        _mime_type: &str,
-        _config: &JsonValue,
-    ) -> Result<ExtractionResult> {
-        if data.is_empty() {
-            return Err(matric_core::Error::InvalidInput(
-                "Cannot extract text from empty spreadsheet data".to_string(),
-            ));
+        mime_type: &str,
+    ) -> Result<Vec<(String, SheetRows)>> {
+        if let Some(format) = delimited_format(filename, mime_type) {
+            let text = String::from_utf8_lossy(data);
+            let text = text.trim_start_matches('\u{feff}');
+            let delimiter = if format == "tsv" {
+                '\t'
+            } else {
+                sniff_delimiter(text)
+            };
+            let name = filename
+                .rsplit_once('.')
+                .map_or(filename, |(stem, _)| stem)
+                .to_string();
+            return Ok(vec![(name, Ok(parse_delimited(text, delimiter)))]);
         }
 
         let cursor = Cursor::new(data.to_vec());
@@ -225,22 +637,80 @@ impl ExtractionAdapter for SpreadsheetAdapter {
             "Opening spreadsheet"
         );
 
-        let mut sections: Vec<String> = Vec::new();
+        Ok(sheet_names
+            .into_iter()
+            .map(|name| {
+                let rows = workbook
+                    .worksheet_range(&name)
+                    .map(|range| range.rows().map(|row| row.to_vec()).collect())
+                    .map_err(|e| e.to_string());
+                (name, rows)
+            })
+            .collect())
+    }
+
+    /// Ask the summary backend to describe one sheet.
+    async fn summarize_sheet(
+        backend: &dyn GenerationBackend,
+        section: &SheetSection,
+    ) -> Result<String> {
+        let sample_len = section.rows.len().min(SUMMARY_SAMPLE_ROWS + 1);
+        let sample =
+            sheet_to_markdown(&section.name, &section.rows[..sample_len]).unwrap_or_default();
+        let prompt = sheet_summary_prompt(&sample, &column_stats_markdown(&section.stats));
+        let summary = backend
+            .generate_with_system(SHEET_SUMMARY_SYSTEM_PROMPT, &prompt)
+            .await?;
+        Ok(summary.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+}
+
+#[async_trait]
+impl ExtractionAdapter for SpreadsheetAdapter {
+    fn strategy(&self) -> ExtractionStrategy {
+        ExtractionStrategy::Spreadsheet
+    }
+
+    async fn extract(
+        &self,
+        data: &[u8],
+        filename: &str,
+        mime_type: &str,
+        config: &JsonValue,
+    ) -> Result<ExtractionResult> {
+        if data.is_empty() {
+            return Err(matric_core::Error::InvalidInput(
+                "Cannot extract text from empty spreadsheet data".to_string(),
+            ));
+        }
+
+        let max_rows = config
+            .get("max_rows")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or_else(|| env_limit("SPREADSHEET_MAX_TABLE_ROWS", DEFAULT_MAX_TABLE_ROWS));
+        let summarize = config
+            .get("summarize")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        let sheets = Self::read_sheets(data, filename, mime_type)?;
+        let sheet_count = sheets.len();
+
+        let mut sections: Vec<SheetSection> = Vec::new();
         let mut sheet_meta: Vec<JsonValue> = Vec::new();
         let mut total_rows: usize = 0;
         let mut total_cols: usize = 0;
 
-        for sheet_name in &sheet_names {
-            match workbook.worksheet_range(sheet_name) {
-                Ok(range) => {
-                    let (height, width) = range.get_size();
-
-                    // Collect all rows into owned Vecs for processing
-                    let rows: Vec<Vec<Data>> = range.rows().map(|row| row.to_vec()).collect();
+        for (sheet_name, rows) in sheets {
+            match rows {
+                Ok(rows) => {
+                    let height = rows.len();
+                    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
 
                     if rows.is_empty() || width == 0 {
                         debug!(
-                            sheet_name_len = spreadsheet_text_len(sheet_name),
+                            sheet_name_len = spreadsheet_text_len(&sheet_name),
                             "Skipping empty sheet"
                         );
                         sheet_meta.push(serde_json::json!({
@@ -255,21 +725,35 @@ impl ExtractionAdapter for SpreadsheetAdapter {
                     total_rows += height;
                     total_cols = total_cols.max(width);
 
-                    sheet_meta.push(serde_json::json!({
+                    let data_rows = height - usize::from(row_looks_like_headers(&rows[0]));
+                    let shown_rows = data_rows.min(max_rows);
+                    let stats = column_stats(&rows);
+                    let mut meta = serde_json::json!({
                         "name": sheet_name,
                         "rows": height,
                         "columns": width,
                         "empty": false,
-                    }));
-
-                    if let Some(md) = sheet_to_markdown(sheet_name, &rows) {
-                        sections.push(md);
+                        "column_stats": stats.iter().map(ColumnStats::to_json).collect::<Vec<_>>(),
+                    });
+                    if shown_rows < data_rows {
+                        meta["rows_shown"] = shown_rows.into();
+                        meta["truncated"] = true.into();
+                    }
+                    sheet_meta.push(meta);
+
+                    if let Some(table) = sheet_to_markdown_limited(&sheet_name, &rows, max_rows) {
+                        sections.push(SheetSection {
+                            name: sheet_name,
+                            rows,
+                            table,
+                            stats,
+                            summary: None,
+                        });
                     }
                 }
-                Err(e) => {
-                    let error_text = e.to_string();
+                Err(error_text) => {
                     debug!(
-                        sheet_name_len = spreadsheet_text_len(sheet_name),
+                        sheet_name_len = spreadsheet_text_len(&sheet_name),
                         error_len = spreadsheet_text_len(&error_text),
                         error_reason = spreadsheet_error_reason_code(&error_text),
                         "Failed to read sheet, skipping"
@@ -284,25 +768,80 @@ impl ExtractionAdapter for SpreadsheetAdapter {
             }
         }
 
+        let mut summary_model = None;
+        if let Some(backend) = self.summary_backend.as_deref().filter(|_| summarize) {
+            let max_sheets =
+                env_limit("SPREADSHEET_SUMMARY_MAX_SHEETS", DEFAULT_MAX_SUMMARY_SHEETS);
+            for section in sections.iter_mut().take(max_sheets) {
+                match Self::summarize_sheet(backend, section).await {
+                    Ok(summary) if !summary.is_empty() => section.summary = Some(summary),
+                    Ok(_) => {}
+                    Err(e) => {
+                        let error_text = e.to_string();
+                        warn!(
+                            sheet_name_len = spreadsheet_text_len(&section.name),
+                            error_len = spreadsheet_text_len(&error_text),
+                            "Sheet summary generation failed, continuing without it"
+                        );
+                    }
+                }
+            }
+            summary_model = Some(backend.model_name().to_string());
+        }
+
+        for section in &sections {
+            if let Some(meta) = sheet_meta
+                .iter_mut()
+                .find(|m| m["name"] == section.name.as_str() && m["empty"] == false)
+            {
+                if let Some(summary) = &section.summary {
+                    meta["summary"] = summary.clone().into();
+                }
+            }
+        }
+
+        let summaries: Vec<String> = sections
+            .iter()
+            .filter_map(|s| {
+                s.summary
+                    .as_ref()
+                    .map(|summary| format!("{}: {}", s.name, summary))
+            })
+            .collect();
+        let ai_description = (!summaries.is_empty()).then(|| summaries.join("\n"));
+
         let extracted_text = if sections.is_empty() {
             None
         } else {
-            Some(sections.join("\n"))
+            Some(
+                sections
+                    .iter()
+                    .map(SheetSection::markdown)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
         };
 
-        let metadata = serde_json::json!({
-            "sheet_count": sheet_names.len(),
+        let mut metadata = serde_json::json!({
+            "sheet_count": sheet_count,
             "sheets": sheet_meta,
             "total_rows": total_rows,
             "max_columns": total_cols,
         });
+        if let Some(format) = delimited_format(filename, mime_type) {
+            metadata["format"] = format.into();
+        }
+        if let (Some(model), false) = (summary_model, summaries.is_empty()) {
+            metadata["summary_model"] = model.into();
+        }
 
         Ok(ExtractionResult {
             extracted_text,
             metadata,
-            ai_description: None,
+            ai_description,
             preview_data: None,
             derived_files: vec![],
+            derived_notes: vec![],
         })
     }
 
@@ -667,25 +1206,25 @@ mod tests {
 
     #[test]
     fn test_spreadsheet_strategy() {
-        let adapter = SpreadsheetAdapter;
+        let adapter = SpreadsheetAdapter::new();
         assert_eq!(adapter.strategy(), ExtractionStrategy::Spreadsheet);
     }
 
     #[test]
     fn test_spreadsheet_name() {
-        let adapter = SpreadsheetAdapter;
+        let adapter = SpreadsheetAdapter::new();
         assert_eq!(adapter.name(), "spreadsheet");
     }
 
     #[tokio::test]
     async fn test_spreadsheet_health_check() {
-        let adapter = SpreadsheetAdapter;
+        let adapter = SpreadsheetAdapter::new();
         assert!(adapter.health_check().await.unwrap());
     }
 
     #[tokio::test]
     async fn test_spreadsheet_empty_data_returns_error() {
-        let adapter = SpreadsheetAdapter;
+        let adapter = SpreadsheetAdapter::new();
         let result = adapter
             .extract(
                 b"",
@@ -709,7 +1248,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_spreadsheet_invalid_data_returns_error() {
-        let adapter = SpreadsheetAdapter;
+        let adapter = SpreadsheetAdapter::new();
         let filename = "bad-token-sk-live.xlsx";
         let result = adapter
             .extract(
@@ -745,8 +1284,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_spreadsheet_ai_description_is_none() {
-        // Verify the adapter never returns an AI description
-        let adapter = SpreadsheetAdapter;
+        // Without a summary backend the adapter never returns an AI description
+        let adapter = SpreadsheetAdapter::new();
         // We use invalid data to trigger early return — just verifying the
         // adapter struct fields are correct. The real path is tested via UAT.
         let result = adapter
@@ -901,7 +1440,7 @@ mod tests {
             vec!["Bob", "25", "Berlin"],
         ]);
 
-        let adapter = SpreadsheetAdapter;
+        let adapter = SpreadsheetAdapter::new();
         let result = adapter
             .extract(
                 &xlsx_bytes,
@@ -944,7 +1483,7 @@ mod tests {
             vec!["v5", "v6"],
         ]);
 
-        let adapter = SpreadsheetAdapter;
+        let adapter = SpreadsheetAdapter::new();
         let result = adapter
             .extract(
                 &xlsx_bytes,
@@ -970,7 +1509,7 @@ mod tests {
             vec!["d", "e", "f"],
         ]);
 
-        let adapter = SpreadsheetAdapter;
+        let adapter = SpreadsheetAdapter::new();
         let result = adapter
             .extract(
                 &xlsx_bytes,
@@ -988,4 +1527,259 @@ mod tests {
             text
         );
     }
+
+    // ── CSV / TSV ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_delimited_format_detection() {
+        assert_eq!(delimited_format("a.csv", "text/csv"), Some("csv"));
+        assert_eq!(delimited_format("a.bin", "application/octet-stream"), None);
+        assert_eq!(
+            delimited_format("a.TSV", "application/octet-stream"),
+            Some("tsv")
+        );
+        assert_eq!(
+            delimited_format(
+                "a.xlsx",
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sniff_delimiter() {
+        assert_eq!(sniff_delimiter("a,b,c\n1,2,3"), ',');
+        assert_eq!(sniff_delimiter("a;b;c\n1;2;3"), ';');
+        assert_eq!(sniff_delimiter("a\tb\tc"), '\t');
+        assert_eq!(sniff_delimiter("single"), ',');
+    }
+
+    #[test]
+    fn test_parse_delimited_quoting() {
+        let rows = parse_delimited(
+            "name,note\r\n\"Smith, J\",\"said \"\"hi\"\"\nthen left\"\n\n",
+            ',',
+        );
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1][0], Data::String("Smith, J".into()));
+        assert_eq!(rows[1][1], Data::String("said \"hi\"\nthen left".into()));
+    }
+
+    #[test]
+    fn test_parse_delimited_cell_types() {
+        let rows = parse_delimited("42,3.5,true,007,\"12\",,x", ',');
+        assert_eq!(
+            rows[0],
+            vec![
+                Data::Int(42),
+                Data::Float(3.5),
+                Data::Bool(true),
+                Data::String("007".into()),
+                Data::String("12".into()),
+                Data::Empty,
+                Data::String("x".into()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_csv() {
+        let csv = "\u{feff}city,population\nOslo,709000\nBergen,289000\n";
+        let result = SpreadsheetAdapter::new()
+            .extract(
+                csv.as_bytes(),
+                "cities.csv",
+                "text/csv",
+                &serde_json::json!({}),
+            )
+            .await
+            .unwrap();
+
+        let text = result.extracted_text.unwrap();
+        assert!(text.contains("## Sheet: cities"), "got:\n{}", text);
+        assert!(text.contains("| city | population |"));
+        assert!(text.contains("| Oslo | 709000 |"));
+        assert_eq!(result.metadata["format"], "csv");
+        assert_eq!(result.metadata["sheets"][0]["name"], "cities");
+    }
+
+    #[tokio::test]
+    async fn test_extract_tsv() {
+        let tsv = "a\tb\n1\tx, y\n";
+        let result = SpreadsheetAdapter::new()
+            .extract(
+                tsv.as_bytes(),
+                "data.tsv",
+                "text/tab-separated-values",
+                &serde_json::json!({}),
+            )
+            .await
+            .unwrap();
+
+        let text = result.extracted_text.unwrap();
+        assert!(text.contains("| 1 | x, y |"), "got:\n{}", text);
+        assert_eq!(result.metadata["format"], "tsv");
+    }
+
+    // ── Row limit and column statistics ───────────────────────────────────
+
+    #[tokio::test]
+    async fn test_extract_truncates_to_max_rows() {
+        let mut csv = String::from("n\n");
+        for i in 0..10 {
+            csv.push_str(&format!("{i}\n"));
+        }
+        let result = SpreadsheetAdapter::new()
+            .extract(
+                csv.as_bytes(),
+                "n.csv",
+                "text/csv",
+                &serde_json::json!({ "max_rows": 3 }),
+            )
+            .await
+            .unwrap();
+
+        let text = result.extracted_text.unwrap();
+        assert!(text.contains("10 rows x 1 columns"), "got:\n{}", text);
+        assert!(text.contains("_Showing first 3 of 10 rows._"));
+        assert!(text.contains("| 2 |"));
+        assert!(!text.contains("| 3 |\n"));
+        let sheet = &result.metadata["sheets"][0];
+        assert_eq!(sheet["rows_shown"], 3);
+        assert_eq!(sheet["truncated"], true);
+        // Statistics still cover every row
+        assert_eq!(sheet["column_stats"][0]["max"], 9.0);
+    }
+
+    #[test]
+    fn test_column_stats() {
+        let rows = parse_delimited(
+            "amount,label,flag,mix\n10,a,true,1\n20,a,false,x\n,b,true,\n",
+            ',',
+        );
+        let stats = column_stats(&rows);
+        assert_eq!(stats.len(), 4);
+
+        let amount = stats[0].to_json();
+        assert_eq!(amount["name"], "amount");
+        assert_eq!(amount["type"], "numeric");
+        assert_eq!(amount["non_empty"], 2);
+        assert_eq!(amount["empty"], 1);
+        assert_eq!(amount["min"], 10.0);
+        assert_eq!(amount["max"], 20.0);
+        assert_eq!(amount["mean"], 15.0);
+
+        let label = stats[1].to_json();
+        assert_eq!(label["type"], "text");
+        assert_eq!(label["distinct"], 2);
+        assert!(label.get("mean").is_none());
+
+        assert_eq!(stats[2].column_type(), "boolean");
+        assert_eq!(stats[3].column_type(), "mixed");
+
+        let md = column_stats_markdown(&stats);
+        assert!(
+            md.contains("| amount | numeric | 2 | 2 | 10 | 20 | 15 |"),
+            "got:\n{}",
+            md
+        );
+    }
+
+    #[test]
+    fn test_column_stats_generic_names_without_headers() {
+        let rows = vec![vec![Data::Int(1), Data::Int(2)]];
+        let stats = column_stats(&rows);
+        assert_eq!(stats[0].name, "Column A");
+        assert_eq!(stats[1].name, "Column B");
+    }
+
+    // ── Sheet summaries ───────────────────────────────────────────────────
+
+    struct MockGeneration {
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl GenerationBackend for MockGeneration {
+        async fn generate(&self, prompt: &str) -> Result<String> {
+            self.generate_with_system("", prompt).await
+        }
+
+        async fn generate_with_system(&self, system: &str, prompt: &str) -> Result<String> {
+            if self.fail {
+                return Err(matric_core::Error::Internal("model offline".into()));
+            }
+            assert!(system.contains("spreadsheet"));
+            assert!(prompt.contains("Column statistics"));
+            Ok("  Population of Norwegian\ncities. ".to_string())
+        }
+
+        fn model_name(&self) -> &str {
+            "mock-model"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_with_sheet_summary() {
+        let adapter = SpreadsheetAdapter::new()
+            .with_summary_backend(Arc::new(MockGeneration { fail: false }));
+        let result = adapter
+            .extract(
+                b"city,population\nOslo,709000\n",
+                "cities.csv",
+                "text/csv",
+                &serde_json::json!({}),
+            )
+            .await
+            .unwrap();
+
+        let text = result.extracted_text.unwrap();
+        assert!(
+            text.contains("## Sheet: cities (1 rows x 2 columns)\n\n**Summary:** Population of Norwegian cities.\n\n"),
+            "got:\n{}",
+            text
+        );
+        assert_eq!(
+            result.ai_description.as_deref(),
+            Some("cities: Population of Norwegian cities.")
+        );
+        assert_eq!(result.metadata["summary_model"], "mock-model");
+        assert_eq!(
+            result.metadata["sheets"][0]["summary"],
+            "Population of Norwegian cities."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_summary_disabled_by_config() {
+        let adapter = SpreadsheetAdapter::new()
+            .with_summary_backend(Arc::new(MockGeneration { fail: false }));
+        let result = adapter
+            .extract(
+                b"a\n1\n",
+                "a.csv",
+                "text/csv",
+                &serde_json::json!({ "summarize": false }),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.ai_description.is_none());
+        assert!(result.metadata.get("summary_model").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_extract_summary_failure_keeps_table() {
+        let adapter =
+            SpreadsheetAdapter::new().with_summary_backend(Arc::new(MockGeneration { fail: true }));
+        let result = adapter
+            .extract(b"a\n1\n", "a.csv", "text/csv", &serde_json::json!({}))
+            .await
+            .unwrap();
+
+        assert!(result.extracted_text.unwrap().contains("| 1 |"));
+        assert!(result.ai_description.is_none());
+        assert!(result.metadata["sheets"][0].get("summary").is_none());
+    }
 }
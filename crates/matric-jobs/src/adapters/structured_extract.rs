@@ -247,6 +247,7 @@ impl ExtractionAdapter for StructuredExtractAdapter {
                 ai_description: None,
                 preview_data: None,
                 derived_files: vec![],
+                derived_notes: vec![],
             });
         }
 
@@ -277,6 +278,7 @@ impl ExtractionAdapter for StructuredExtractAdapter {
                     ai_description: None,
                     preview_data: None,
                     derived_files: vec![],
+                    derived_notes: vec![],
                 });
             }
         }
@@ -296,6 +298,7 @@ impl ExtractionAdapter for StructuredExtractAdapter {
             ai_description: None,
             preview_data: None,
             derived_files: vec![],
+            derived_notes: vec![],
         })
     }
 
@@ -64,6 +64,7 @@ impl ExtractionAdapter for TextNativeAdapter {
             ai_description: None,
             preview_data: None,
             derived_files: vec![],
+            derived_notes: vec![],
         })
     }
 
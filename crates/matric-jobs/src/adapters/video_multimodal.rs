@@ -8,6 +8,7 @@
 //!
 //! Falls back gracefully if backends are unavailable.
 
+use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -21,8 +22,8 @@ use tracing::{debug, info, warn};
 
 use matric_core::defaults::{EXTRACTION_CMD_TIMEOUT_SECS, VIDEO_MAX_KEYFRAMES};
 use matric_core::{
-    DerivedFile, ExtractionAdapter, ExtractionResult, ExtractionStrategy, KeyframeStrategy,
-    ProgressFn, Result,
+    CheckpointFn, DerivedFile, ExtractionAdapter, ExtractionResult, ExtractionStrategy,
+    KeyframeStrategy, ProgressFn, Result,
 };
 use matric_inference::transcription::TranscriptionBackend;
 use matric_inference::vision::VisionBackend;
@@ -197,6 +198,42 @@ fn parse_checkpoint(config: &JsonValue) -> std::collections::HashSet<u64> {
         .unwrap_or_default()
 }
 
+/// Frame descriptions saved by an interrupted run, keyed by frame index.
+///
+/// The extraction handler injects `_checkpoint.frame_descriptions` from the job
+/// checkpoint. Each entry carries the frame's timestamp so a description is
+/// only reused for the same frame.
+fn parse_checkpoint_descriptions(config: &JsonValue) -> HashMap<u64, (f64, String)> {
+    config
+        .get("_checkpoint")
+        .and_then(|cp| cp.get("frame_descriptions"))
+        .and_then(|arr| arr.as_array())
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    let index = entry.get("frame_index")?.as_u64()?;
+                    let timestamp = entry.get("timestamp_secs")?.as_f64()?;
+                    let description = entry.get("description")?.as_str()?;
+                    Some((index, (timestamp, description.to_string())))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The cached description of frame `index` when it was taken at `timestamp_secs`.
+fn cached_description(
+    cached: &HashMap<u64, (f64, String)>,
+    index: usize,
+    timestamp_secs: f64,
+) -> Option<String> {
+    cached
+        .get(&(index as u64))
+        .filter(|(timestamp, _)| (timestamp - timestamp_secs).abs() < 0.001)
+        .map(|(_, description)| description.clone())
+}
+
 pub struct VideoMultimodalAdapter {
     vision: Option<Arc<dyn VisionBackend>>,
     transcription: Option<Arc<dyn TranscriptionBackend>>,
@@ -787,16 +824,40 @@ impl ExtractionAdapter for VideoMultimodalAdapter {
             ai_description: None,
             preview_data: thumbnail_data,
             derived_files,
+            derived_notes: vec![],
         })
     }
 
     async fn extract_with_progress(
+        &self,
+        data: &[u8],
+        filename: &str,
+        mime_type: &str,
+        config: &JsonValue,
+        progress: ProgressFn,
+    ) -> Result<ExtractionResult> {
+        self.extract_resumable(
+            data,
+            filename,
+            mime_type,
+            config,
+            progress,
+            Arc::new(|_| {}),
+        )
+        .await
+    }
+
+    /// Checkpoints `{"frame_descriptions": [...]}` after each described
+    /// keyframe. A restarted run reuses those descriptions instead of calling
+    /// the vision backend again.
+    async fn extract_resumable(
         &self,
         data: &[u8],
         filename: &str,
         _mime_type: &str,
         config: &JsonValue,
         progress: ProgressFn,
+        checkpoint: CheckpointFn,
     ) -> Result<ExtractionResult> {
         let has_source_path = config
             .get("_source_path")
@@ -1020,6 +1081,8 @@ impl ExtractionAdapter for VideoMultimodalAdapter {
 
                         if let Some(ref backend) = self.vision {
                             let mut prev_descriptions: Vec<String> = Vec::new();
+                            let cached_descriptions = parse_checkpoint_descriptions(config);
+                            let mut checkpointed: Vec<JsonValue> = Vec::new();
 
                             for (i, entry) in frame_entries.iter().enumerate() {
                                 // Skip frames already completed in a previous run
@@ -1046,14 +1109,28 @@ impl ExtractionAdapter for VideoMultimodalAdapter {
                                     &transcript_segments,
                                 );
 
-                                match describe_frame_with_context(
-                                    backend.as_ref(),
-                                    &entry.path,
-                                    &prev_descriptions,
-                                    transcript_context.as_deref(),
-                                )
-                                .await
-                                {
+                                // Reuse the description an interrupted run checkpointed
+                                let described = match cached_description(
+                                    &cached_descriptions,
+                                    i,
+                                    entry.timestamp_secs,
+                                ) {
+                                    Some(description) => {
+                                        debug!(frame = i, "Checkpoint: reusing frame description");
+                                        Ok(description)
+                                    }
+                                    None => {
+                                        describe_frame_with_context(
+                                            backend.as_ref(),
+                                            &entry.path,
+                                            &prev_descriptions,
+                                            transcript_context.as_deref(),
+                                        )
+                                        .await
+                                    }
+                                };
+
+                                match described {
                                     Ok(description) => {
                                         // Write description to disk (not memory)
                                         let desc_json = json!({
@@ -1061,6 +1138,8 @@ impl ExtractionAdapter for VideoMultimodalAdapter {
                                             "timestamp_secs": entry.timestamp_secs,
                                             "description": description,
                                         });
+                                        checkpointed.push(desc_json.clone());
+                                        checkpoint(json!({ "frame_descriptions": checkpointed }));
                                         if let Err(e) = desc_writer.write(&desc_json) {
                                             let error_text = e.to_string();
                                             warn!(
@@ -1207,6 +1286,7 @@ impl ExtractionAdapter for VideoMultimodalAdapter {
             ai_description: None,
             preview_data: thumbnail_data,
             derived_files,
+            derived_notes: vec![],
         })
     }
 
@@ -2835,6 +2915,29 @@ mod tests {
         assert!(completed.is_empty());
     }
 
+    #[test]
+    fn test_checkpoint_descriptions_reused_only_for_same_frame() {
+        let config = json!({
+            "_checkpoint": {
+                "frame_descriptions": [
+                    {"frame_index": 0, "timestamp_secs": 0.0, "description": "A title card"},
+                    {"frame_index": 1, "timestamp_secs": 10.0, "description": "A kitchen"},
+                    {"frame_index": 2, "description": "missing timestamp"}
+                ]
+            }
+        });
+        let cached = parse_checkpoint_descriptions(&config);
+        assert_eq!(cached.len(), 2);
+        assert_eq!(
+            cached_description(&cached, 1, 10.0).as_deref(),
+            Some("A kitchen")
+        );
+        // Same index at a different timestamp is a different frame.
+        assert!(cached_description(&cached, 1, 12.5).is_none());
+        assert!(cached_description(&cached, 2, 20.0).is_none());
+        assert!(parse_checkpoint_descriptions(&json!({})).is_empty());
+    }
+
     // ── FrameDescriptionWriter tests ──────────────────────────────────
 
     #[test]
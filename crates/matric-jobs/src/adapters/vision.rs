@@ -137,6 +137,7 @@ impl ExtractionAdapter for VisionAdapter {
             ai_description: Some(description),
             preview_data: None,
             derived_files: vec![],
+            derived_notes: vec![],
         })
     }
 
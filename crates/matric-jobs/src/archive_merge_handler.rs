@@ -0,0 +1,190 @@
+//! ArchiveMergeHandler — merges one memory archive into another.
+//!
+//! The payload names the `source` and `target` archives and carries the
+//! [`ArchiveMergeOptions`]. All rows are merged in one transaction; a move
+//! drops the source archive only after that transaction has committed.
+
+use async_trait::async_trait;
+use serde_json::{json, Value as JsonValue};
+use tracing::{info, warn};
+
+use matric_core::{ArchiveRepository, JobType};
+use matric_db::{ArchiveMergeMode, ArchiveMergeOptions, ArchiveMergeReport, Database};
+
+use crate::handler::{JobContext, JobHandler, JobResult};
+
+/// Archive names and options read from a job payload.
+struct MergeRequest {
+    source: String,
+    target: String,
+    options: ArchiveMergeOptions,
+}
+
+fn merge_request(payload: Option<&JsonValue>) -> Option<MergeRequest> {
+    let payload = payload?;
+    let name = |key: &str| {
+        payload
+            .get(key)
+            .and_then(JsonValue::as_str)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    Some(MergeRequest {
+        source: name("source")?,
+        target: name("target")?,
+        options: serde_json::from_value(payload.clone()).ok()?,
+    })
+}
+
+fn archive_merge_result(
+    request: &MergeRequest,
+    report: &ArchiveMergeReport,
+    source_dropped: bool,
+) -> JsonValue {
+    json!({
+        "source": request.source,
+        "target": request.target,
+        "mode": request.options.mode,
+        "on_conflict": request.options.on_conflict,
+        "source_dropped": source_dropped,
+        "report": report,
+    })
+}
+
+pub struct ArchiveMergeHandler {
+    db: Database,
+}
+
+impl ArchiveMergeHandler {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl JobHandler for ArchiveMergeHandler {
+    fn job_type(&self) -> JobType {
+        JobType::ArchiveMerge
+    }
+
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let Some(request) = merge_request(ctx.payload()) else {
+            return JobResult::Failed("Invalid archive merge payload".into());
+        };
+        let source = match self.db.archives.get_archive_by_name(&request.source).await {
+            Ok(Some(archive)) => archive,
+            Ok(None) => return JobResult::Failed("Source archive not found".into()),
+            Err(_) => return JobResult::Retry("Failed to load source archive".into()),
+        };
+        let target = match self.db.archives.get_archive_by_name(&request.target).await {
+            Ok(Some(archive)) => archive,
+            Ok(None) => return JobResult::Failed("Target archive not found".into()),
+            Err(_) => return JobResult::Retry("Failed to load target archive".into()),
+        };
+        if request.options.mode == ArchiveMergeMode::Move && source.is_default {
+            return JobResult::Failed("Cannot move the default archive".into());
+        }
+
+        let mut tx = match self.db.pool.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return JobResult::Retry("Failed to begin transaction".into()),
+        };
+        let mut progress = |percent: i32, message: &'static str| {
+            // Leave the last step for committing and dropping the source.
+            ctx.report_progress(percent.min(95), Some(message));
+        };
+        let report = match self
+            .db
+            .archives
+            .merge_archive_tx(
+                &mut tx,
+                &source.schema_name,
+                &target.schema_name,
+                request.options,
+                &mut progress,
+            )
+            .await
+        {
+            Ok(report) => report,
+            Err(matric_core::Error::InvalidInput(message)) => return JobResult::Failed(message),
+            Err(_) => return JobResult::Failed("Archive merge failed".into()),
+        };
+        if tx.commit().await.is_err() {
+            return JobResult::Failed("Failed to commit archive merge".into());
+        }
+
+        let mut source_dropped = false;
+        if request.options.mode == ArchiveMergeMode::Move {
+            ctx.report_progress(97, Some("Dropping source archive"));
+            match self.db.archives.drop_archive_schema(&source.name).await {
+                Ok(()) => source_dropped = true,
+                Err(_) => warn!(
+                    source_len = source.name.len(),
+                    "Archive merged but the source archive could not be dropped"
+                ),
+            }
+        }
+        let _ = self.db.archives.update_archive_stats(&target.name).await;
+
+        info!(
+            notes_merged = report.notes_merged,
+            notes_conflicting = report.notes_conflicting,
+            notes_overwritten = report.notes_overwritten,
+            rows_skipped = report.rows_skipped,
+            rows_remapped = report.rows_remapped,
+            source_dropped,
+            "Archive merge complete"
+        );
+        ctx.report_progress(100, Some("Archive merge complete"));
+        JobResult::Success(Some(archive_merge_result(
+            &request,
+            &report,
+            source_dropped,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matric_db::ArchiveMergeConflict;
+
+    #[test]
+    fn merge_request_requires_both_archives() {
+        assert!(merge_request(None).is_none());
+        assert!(merge_request(Some(&json!({ "source": "a" }))).is_none());
+        assert!(merge_request(Some(&json!({ "source": "", "target": "b" }))).is_none());
+
+        let request = merge_request(Some(&json!({
+            "source": "a",
+            "target": "b",
+            "mode": "move",
+            "on_conflict": "overwrite",
+        })))
+        .unwrap();
+        assert_eq!(request.source, "a");
+        assert_eq!(request.target, "b");
+        assert_eq!(request.options.mode, ArchiveMergeMode::Move);
+        assert_eq!(request.options.on_conflict, ArchiveMergeConflict::Overwrite);
+    }
+
+    #[test]
+    fn merge_request_defaults_to_copy_and_skip() {
+        let request = merge_request(Some(&json!({ "source": "a", "target": "b" }))).unwrap();
+        assert_eq!(request.options, ArchiveMergeOptions::default());
+    }
+
+    #[test]
+    fn result_reports_report_and_drop() {
+        let request = merge_request(Some(&json!({ "source": "a", "target": "b" }))).unwrap();
+        let report = ArchiveMergeReport {
+            notes_merged: 4,
+            ..Default::default()
+        };
+        let result = archive_merge_result(&request, &report, false);
+        assert_eq!(result["mode"], "copy");
+        assert_eq!(result["on_conflict"], "skip");
+        assert_eq!(result["source_dropped"], false);
+        assert_eq!(result["report"]["notes_merged"], 4);
+    }
+}
@@ -0,0 +1,218 @@
+//! ScheduledBackupHandler — runs a backup policy and applies its retention.
+//!
+//! The API scheduler queues a `scheduled_backup` job carrying the policy ID
+//! whenever a policy's cron schedule comes due, and operators can queue one
+//! on demand. The job records a run, asks the [`BackupRunner`] to write the
+//! backup, then deletes the policy's older backups that fall outside its
+//! daily/weekly retention. Success and failure are announced on the event
+//! bus as `BackupCompleted` and `BackupFailed`.
+//!
+//! Writing and deleting backup files is left to the runner because the
+//! backup formats (snapshot dumps, encrypted full archives) live in the API.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value as JsonValue};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use matric_core::{BackupPolicy, EventBus, JobType, ServerEvent};
+use matric_db::Database;
+
+use crate::handler::{JobContext, JobHandler, JobResult};
+
+/// A backup written by a [`BackupRunner`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedBackup {
+    /// Filename in the backup directory.
+    pub filename: String,
+    pub size_bytes: i64,
+}
+
+/// Writes and deletes the backup files of scheduled policies.
+#[async_trait]
+pub trait BackupRunner: Send + Sync {
+    /// Write a backup of the kind `policy` asks for.
+    async fn create_backup(&self, policy: &BackupPolicy) -> Result<CreatedBackup, String>;
+
+    /// Delete a backup file and its sidecars.
+    async fn delete_backup(&self, filename: &str) -> Result<(), String>;
+}
+
+fn policy_target(payload: Option<&JsonValue>) -> Option<Uuid> {
+    payload
+        .and_then(|p| p.get("policy_id"))
+        .and_then(JsonValue::as_str)
+        .and_then(|s| Uuid::parse_str(s).ok())
+}
+
+pub struct ScheduledBackupHandler {
+    db: Database,
+    runner: Arc<dyn BackupRunner>,
+    event_bus: Arc<EventBus>,
+}
+
+impl ScheduledBackupHandler {
+    pub fn new(db: Database, runner: Arc<dyn BackupRunner>, event_bus: Arc<EventBus>) -> Self {
+        Self {
+            db,
+            runner,
+            event_bus,
+        }
+    }
+
+    /// Delete retained backups the policy no longer keeps.
+    ///
+    /// A backup whose file cannot be deleted stays retained and is offered
+    /// for pruning again after the next run.
+    async fn prune(&self, policy: &BackupPolicy) -> Result<usize, String> {
+        let runs = self
+            .db
+            .backup_policies
+            .list_retained_runs(policy.id)
+            .await
+            .map_err(|_| "Failed to list retained backups".to_string())?;
+        let backups: Vec<(Uuid, _)> = runs.iter().map(|r| (r.id, r.started_at_utc)).collect();
+        let prunable = policy.retention().prunable(&backups);
+
+        let mut pruned = 0;
+        for run in runs.iter().filter(|r| prunable.contains(&r.id)) {
+            if let Some(filename) = run.filename.as_deref() {
+                if let Err(reason) = self.runner.delete_backup(filename).await {
+                    warn!(
+                        filename_len = filename.len(),
+                        reason = %reason,
+                        "Failed to prune backup"
+                    );
+                    continue;
+                }
+            }
+            self.db
+                .backup_policies
+                .mark_pruned(run.id)
+                .await
+                .map_err(|_| "Failed to record pruned backup".to_string())?;
+            pruned += 1;
+        }
+        Ok(pruned)
+    }
+}
+
+#[async_trait]
+impl JobHandler for ScheduledBackupHandler {
+    fn job_type(&self) -> JobType {
+        JobType::ScheduledBackup
+    }
+
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let Some(policy_id) = policy_target(ctx.payload()) else {
+            return JobResult::Failed("Missing or invalid policy_id".into());
+        };
+        let policy = match self.db.backup_policies.get(policy_id).await {
+            Ok(Some(policy)) => policy,
+            // Deleted after the job was queued.
+            Ok(None) => {
+                return JobResult::Success(Some(json!({ "skipped": "policy_not_found" })));
+            }
+            Err(_) => return JobResult::Retry("Failed to load backup policy".into()),
+        };
+
+        let run_id = match self
+            .db
+            .backup_policies
+            .start_run(policy.id, Some(ctx.job.id))
+            .await
+        {
+            Ok(id) => id,
+            Err(_) => return JobResult::Retry("Failed to record backup run".into()),
+        };
+        ctx.report_progress(10, Some("Writing backup"));
+
+        // Failures are final for this run: retrying would record a second run
+        // and announce the failure twice. The next scheduled slot tries again.
+        let backup = match self.runner.create_backup(&policy).await {
+            Ok(backup) => backup,
+            Err(reason) => {
+                if self
+                    .db
+                    .backup_policies
+                    .fail_run(run_id, &reason)
+                    .await
+                    .is_err()
+                {
+                    warn!("Failed to record failed backup run");
+                }
+                warn!(
+                    backup_type = %policy.backup_type,
+                    reason_len = reason.len(),
+                    "Scheduled backup failed"
+                );
+                self.event_bus.emit(ServerEvent::BackupFailed {
+                    policy_id: policy.id,
+                    run_id,
+                    error: reason.clone(),
+                });
+                return JobResult::Failed(reason);
+            }
+        };
+        if self
+            .db
+            .backup_policies
+            .finish_run(run_id, &backup.filename, backup.size_bytes)
+            .await
+            .is_err()
+        {
+            // The file exists but is not recorded, so retention cannot see
+            // it; surface that rather than reporting success.
+            return JobResult::Failed("Failed to record completed backup run".into());
+        }
+
+        ctx.report_progress(80, Some("Applying retention"));
+        let pruned_count = match self.prune(&policy).await {
+            Ok(pruned) => pruned,
+            Err(reason) => {
+                warn!(reason = %reason, "Backup retention skipped");
+                0
+            }
+        };
+
+        info!(
+            backup_type = %policy.backup_type,
+            size_bytes = backup.size_bytes,
+            pruned_count,
+            "Scheduled backup complete"
+        );
+        self.event_bus.emit(ServerEvent::BackupCompleted {
+            policy_id: policy.id,
+            run_id,
+            filename: backup.filename.clone(),
+            size_bytes: backup.size_bytes,
+            pruned_count,
+        });
+        ctx.report_progress(100, Some("Scheduled backup complete"));
+        JobResult::Success(Some(json!({
+            "run_id": run_id,
+            "filename": backup.filename,
+            "size_bytes": backup.size_bytes,
+            "pruned_count": pruned_count,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_target_requires_uuid() {
+        let id = Uuid::new_v4();
+        assert_eq!(policy_target(None), None);
+        assert_eq!(policy_target(Some(&json!({ "policy_id": "nope" }))), None);
+        assert_eq!(policy_target(Some(&json!({ "policy_id": 7 }))), None);
+        assert_eq!(
+            policy_target(Some(&json!({ "policy_id": id.to_string() }))),
+            Some(id)
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! BlobGarbageCollectionHandler — reclaims unreferenced attachment blobs.
+//!
+//! Attachment blobs are content-addressed and shared between attachments; the
+//! `update_blob_refcount()` trigger keeps `reference_count` in step with the
+//! attachment table. Blobs can still be left behind with no references (a
+//! failed file delete, refcount drift, an interrupted upload), so this job
+//! sweeps them: rows older than a grace period are deleted per archive and
+//! their stored files removed once the deleting transaction has committed.
+
+use async_trait::async_trait;
+use serde_json::{json, Value as JsonValue};
+use tracing::{info, warn};
+
+use matric_core::{ArchiveRepository, BlobGcReport, JobType};
+use matric_db::Database;
+
+use crate::handler::{JobContext, JobHandler, JobResult};
+
+/// Orphans younger than this are left alone so content that is deleted and
+/// re-uploaded shortly afterwards can still reuse its blob.
+pub const DEFAULT_BLOB_GC_MIN_AGE_HOURS: i32 = 24;
+
+/// Upper bound on blobs deleted per archive in one pass.
+const BLOB_GC_BATCH_LIMIT: i64 = 1_000;
+
+fn min_age_hours(payload: Option<&JsonValue>) -> i32 {
+    payload
+        .and_then(|p| p.get("min_age_hours"))
+        .and_then(JsonValue::as_i64)
+        .and_then(|hours| i32::try_from(hours).ok())
+        .filter(|&hours| hours >= 0)
+        .unwrap_or(DEFAULT_BLOB_GC_MIN_AGE_HOURS)
+}
+
+fn target_schema(payload: Option<&JsonValue>) -> Option<String> {
+    payload
+        .and_then(|p| p.get("schema"))
+        .and_then(JsonValue::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn blob_gc_result(report: &BlobGcReport, archives: usize, min_age_hours: i32) -> JsonValue {
+    json!({
+        "archives_swept": archives,
+        "blobs_deleted": report.blobs_deleted,
+        "bytes_reclaimed": report.bytes_reclaimed,
+        "file_delete_failures": report.file_delete_failures,
+        "min_age_hours": min_age_hours,
+    })
+}
+
+pub struct BlobGarbageCollectionHandler {
+    db: Database,
+}
+
+impl BlobGarbageCollectionHandler {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn sweep_schema(&self, schema: &str, min_age_hours: i32) -> Result<BlobGcReport, String> {
+        let file_storage = self
+            .db
+            .file_storage
+            .as_ref()
+            .ok_or_else(|| "File storage not configured".to_string())?;
+        let schema_ctx = self
+            .db
+            .for_schema(schema)
+            .map_err(|_| "Invalid schema".to_string())?;
+
+        let mut tx = schema_ctx
+            .begin_tx()
+            .await
+            .map_err(|_| "Failed to begin transaction".to_string())?;
+        let blobs = file_storage
+            .collect_orphaned_blobs_tx(&mut tx, min_age_hours, BLOB_GC_BATCH_LIMIT)
+            .await
+            .map_err(|_| "Failed to collect orphaned blobs".to_string())?;
+        tx.commit()
+            .await
+            .map_err(|_| "Failed to commit blob collection".to_string())?;
+
+        let file_delete_failures = file_storage.delete_blob_files(&blobs).await;
+        Ok(BlobGcReport {
+            blobs_deleted: blobs.len() as u64,
+            bytes_reclaimed: blobs.iter().map(|b| b.size_bytes).sum(),
+            file_delete_failures,
+        })
+    }
+}
+
+#[async_trait]
+impl JobHandler for BlobGarbageCollectionHandler {
+    fn job_type(&self) -> JobType {
+        JobType::BlobGarbageCollection
+    }
+
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let min_age_hours = min_age_hours(ctx.payload());
+
+        // A payload schema restricts the sweep to one archive; otherwise every
+        // registered archive is collected.
+        let schemas = match target_schema(ctx.payload()) {
+            Some(schema) => vec![schema],
+            None => match self.db.archives.list_archive_schemas().await {
+                Ok(archives) => archives.into_iter().map(|a| a.schema_name).collect(),
+                Err(_) => return JobResult::Retry("Failed to list archives".into()),
+            },
+        };
+
+        let mut report = BlobGcReport::default();
+        for (i, schema) in schemas.iter().enumerate() {
+            ctx.report_progress(
+                (i * 100 / schemas.len().max(1)) as i32,
+                Some("Collecting orphaned blobs"),
+            );
+            match self.sweep_schema(schema, min_age_hours).await {
+                Ok(swept) => {
+                    report.blobs_deleted += swept.blobs_deleted;
+                    report.bytes_reclaimed += swept.bytes_reclaimed;
+                    report.file_delete_failures += swept.file_delete_failures;
+                }
+                Err(reason) => {
+                    warn!(
+                        schema_len = schema.len(),
+                        reason = %reason,
+                        "Blob garbage collection skipped archive"
+                    );
+                }
+            }
+        }
+
+        info!(
+            archives = schemas.len(),
+            blobs_deleted = report.blobs_deleted,
+            bytes_reclaimed = report.bytes_reclaimed,
+            file_delete_failures = report.file_delete_failures,
+            "Blob garbage collection complete"
+        );
+        ctx.report_progress(100, Some("Blob garbage collection complete"));
+        JobResult::Success(Some(blob_gc_result(&report, schemas.len(), min_age_hours)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_age_defaults_and_rejects_negative_values() {
+        assert_eq!(min_age_hours(None), DEFAULT_BLOB_GC_MIN_AGE_HOURS);
+        assert_eq!(min_age_hours(Some(&json!({ "min_age_hours": 2 }))), 2);
+        assert_eq!(
+            min_age_hours(Some(&json!({ "min_age_hours": -1 }))),
+            DEFAULT_BLOB_GC_MIN_AGE_HOURS
+        );
+        assert_eq!(
+            min_age_hours(Some(&json!({ "min_age_hours": "soon" }))),
+            DEFAULT_BLOB_GC_MIN_AGE_HOURS
+        );
+    }
+
+    #[test]
+    fn target_schema_ignores_empty_values() {
+        assert_eq!(target_schema(None), None);
+        assert_eq!(target_schema(Some(&json!({ "schema": "" }))), None);
+        assert_eq!(
+            target_schema(Some(&json!({ "schema": "archive_a" }))).as_deref(),
+            Some("archive_a")
+        );
+    }
+
+    #[test]
+    fn result_reports_totals() {
+        let report = BlobGcReport {
+            blobs_deleted: 3,
+            bytes_reclaimed: 4096,
+            file_delete_failures: 1,
+        };
+        let result = blob_gc_result(&report, 2, 24);
+        assert_eq!(result["archives_swept"], 2);
+        assert_eq!(result["blobs_deleted"], 3);
+        assert_eq!(result["bytes_reclaimed"], 4096);
+        assert_eq!(result["file_delete_failures"], 1);
+    }
+}
@@ -0,0 +1,232 @@
+//! BulkNoteUpdateHandler — applies one patch to every note matching a filter.
+//!
+//! The payload names the archive (`schema`), the `filter` and the `patch` of
+//! a `PATCH /api/v1/notes/bulk` request. The filter is resolved when the job
+//! runs, then the matching notes are updated in batches of
+//! [`BULK_NOTE_UPDATE_BATCH_SIZE`], each in its own transaction, with a
+//! progress event after every batch. The patch is idempotent, so a retry
+//! after a partial run re-applies it to the notes already done.
+//!
+//! A payload with `user_id` acts for that user: only notes the user can read
+//! are matched, and notes the user cannot write are counted as failed.
+
+use async_trait::async_trait;
+use serde_json::{json, Value as JsonValue};
+use tracing::info;
+
+use matric_core::{
+    BulkNoteFilter, BulkNotePatch, BulkNoteUpdateResult, JobType, StrictSecurityFilter,
+    BULK_NOTE_UPDATE_BATCH_SIZE,
+};
+use matric_db::Database;
+
+use crate::handler::{JobContext, JobHandler, JobResult};
+
+/// What one job run updates.
+#[derive(Debug, Clone, PartialEq)]
+struct BulkUpdate {
+    schema: String,
+    filter: BulkNoteFilter,
+    patch: BulkNotePatch,
+    user_id: Option<uuid::Uuid>,
+}
+
+fn bulk_update(payload: Option<&JsonValue>) -> Result<BulkUpdate, String> {
+    let payload = payload.ok_or_else(|| "Missing payload".to_string())?;
+    let schema = payload
+        .get("schema")
+        .and_then(JsonValue::as_str)
+        .filter(|s| !s.is_empty())
+        .unwrap_or("public")
+        .to_string();
+    let filter: BulkNoteFilter = payload
+        .get("filter")
+        .cloned()
+        .and_then(|f| serde_json::from_value(f).ok())
+        .ok_or_else(|| "Invalid bulk update filter".to_string())?;
+    let patch: BulkNotePatch = payload
+        .get("patch")
+        .cloned()
+        .and_then(|p| serde_json::from_value(p).ok())
+        .ok_or_else(|| "Invalid bulk update patch".to_string())?;
+    let user_id = match payload.get("user_id") {
+        None | Some(JsonValue::Null) => None,
+        Some(id) => Some(
+            serde_json::from_value(id.clone())
+                .map_err(|_| "Invalid bulk update user".to_string())?,
+        ),
+    };
+    let filter = filter.normalized();
+    let patch = patch.normalized();
+    filter.validate().map_err(|e| e.to_string())?;
+    patch.validate().map_err(|e| e.to_string())?;
+    Ok(BulkUpdate {
+        schema,
+        filter,
+        patch,
+        user_id,
+    })
+}
+
+pub struct BulkNoteUpdateHandler {
+    db: Database,
+}
+
+impl BulkNoteUpdateHandler {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// IDs of the notes matching the filter right now.
+    async fn matching(&self, update: &BulkUpdate) -> Result<Vec<uuid::Uuid>, String> {
+        let schema_ctx = self
+            .db
+            .for_schema(&update.schema)
+            .map_err(|_| "Invalid schema".to_string())?;
+        let mut tx = schema_ctx
+            .begin_tx()
+            .await
+            .map_err(|_| "Failed to begin transaction".to_string())?;
+        let security = update.user_id.map(StrictSecurityFilter::for_caller);
+        let ids = self
+            .db
+            .bulk_notes
+            .matching_note_ids_tx(&mut tx, &update.filter, security.as_ref())
+            .await
+            .map_err(|_| "Failed to match notes".to_string())?;
+        tx.commit()
+            .await
+            .map_err(|_| "Failed to commit note matching".to_string())?;
+        Ok(ids)
+    }
+
+    /// Update one batch in its own transaction. Notes the acting user can
+    /// no longer write are left untouched and counted as failed.
+    async fn apply_batch(
+        &self,
+        update: &BulkUpdate,
+        batch: &[uuid::Uuid],
+    ) -> Result<BulkNoteUpdateResult, String> {
+        let schema_ctx = self
+            .db
+            .for_schema(&update.schema)
+            .map_err(|_| "Invalid schema".to_string())?;
+        let mut tx = schema_ctx
+            .begin_tx()
+            .await
+            .map_err(|_| "Failed to begin transaction".to_string())?;
+        let writable = match update.user_id {
+            Some(user_id) => self
+                .db
+                .users
+                .writable_note_ids_tx(&mut tx, batch, user_id)
+                .await
+                .map_err(|_| "Failed to check note access".to_string())?,
+            None => batch.to_vec(),
+        };
+        let mut applied = self
+            .db
+            .bulk_notes
+            .apply_tx(&mut tx, &writable, &update.patch)
+            .await
+            .map_err(|e| match e {
+                matric_core::Error::NotFound(_) => "Collection not found".to_string(),
+                _ => "Failed to update notes".to_string(),
+            })?;
+        tx.commit()
+            .await
+            .map_err(|_| "Failed to commit bulk note update".to_string())?;
+        let denied = (batch.len() - writable.len()) as u64;
+        applied.matched += denied;
+        applied.failed += denied;
+        Ok(applied)
+    }
+}
+
+#[async_trait]
+impl JobHandler for BulkNoteUpdateHandler {
+    fn job_type(&self) -> JobType {
+        JobType::BulkNoteUpdate
+    }
+
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let update = match bulk_update(ctx.payload()) {
+            Ok(update) => update,
+            Err(reason) => return JobResult::Failed(reason),
+        };
+
+        ctx.report_progress(0, Some("Matching notes"));
+        let ids = match self.matching(&update).await {
+            Ok(ids) => ids,
+            Err(reason) if reason == "Invalid schema" => return JobResult::Failed(reason),
+            Err(reason) => return JobResult::Retry(reason),
+        };
+
+        let mut total = BulkNoteUpdateResult::default();
+        let mut done = 0;
+        for batch in ids.chunks(BULK_NOTE_UPDATE_BATCH_SIZE) {
+            match self.apply_batch(&update, batch).await {
+                Ok(applied) => total.add(applied),
+                Err(reason) if reason == "Collection not found" => {
+                    return JobResult::Failed(reason)
+                }
+                Err(reason) => return JobResult::Retry(reason),
+            }
+            done += batch.len();
+            ctx.report_progress(
+                (done * 100 / ids.len()) as i32,
+                Some(format!("Updated {done} of {} notes", ids.len()).as_str()),
+            );
+        }
+
+        info!(
+            matched = total.matched,
+            updated = total.updated,
+            failed = total.failed,
+            "Bulk note update complete"
+        );
+        ctx.report_progress(100, Some("Bulk note update complete"));
+        JobResult::Success(Some(json!({ "result": total })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bulk_update_reads_and_validates_the_payload() {
+        let update = bulk_update(Some(&json!({
+            "filter": { "tags": [" project "] },
+            "patch": { "archived": true },
+        })))
+        .unwrap();
+        assert_eq!(update.schema, "public");
+        assert_eq!(update.filter.tags, vec!["project".to_string()]);
+        assert_eq!(update.patch.archived, Some(true));
+        assert_eq!(update.user_id, None);
+
+        let user_id = uuid::Uuid::nil();
+        let update = bulk_update(Some(&json!({
+            "schema": "archive_a",
+            "filter": { "tags": ["project"] },
+            "patch": { "archived": true },
+            "user_id": user_id,
+        })))
+        .unwrap();
+        assert_eq!(update.user_id, Some(user_id));
+        assert!(bulk_update(Some(&json!({
+            "filter": { "tags": ["project"] },
+            "patch": { "archived": true },
+            "user_id": "not-a-uuid",
+        })))
+        .is_err());
+
+        assert!(bulk_update(None).is_err());
+        assert!(bulk_update(Some(
+            &json!({ "filter": {}, "patch": { "archived": true } })
+        ))
+        .is_err());
+        assert!(bulk_update(Some(&json!({ "filter": { "query": "x" }, "patch": {} }))).is_err());
+    }
+}
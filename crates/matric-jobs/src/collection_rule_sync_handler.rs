@@ -0,0 +1,226 @@
+//! CollectionRuleSyncHandler — keeps smart collections in line with their rules.
+//!
+//! A payload with `collection_id` syncs that collection's rule in the archive
+//! named by `schema`, even if the rule is disabled. Otherwise the job is the
+//! periodic sweep: every enabled rule of every archive, or of the one named
+//! by `schema`, is synced. Each rule is synced in its own transaction.
+
+use async_trait::async_trait;
+use serde_json::{json, Value as JsonValue};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use matric_core::{ArchiveRepository, CollectionRuleSyncResult, JobType};
+use matric_db::Database;
+
+use crate::handler::{JobContext, JobHandler, JobResult};
+
+/// What one job run syncs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SyncScope {
+    /// One collection's rule.
+    Collection { schema: String, collection_id: Uuid },
+    /// Every enabled rule, in one archive or all of them.
+    Enabled { schema: Option<String> },
+}
+
+fn sync_scope(payload: Option<&JsonValue>) -> SyncScope {
+    let schema = payload
+        .and_then(|p| p.get("schema"))
+        .and_then(JsonValue::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+    let collection_id = payload
+        .and_then(|p| p.get("collection_id"))
+        .and_then(JsonValue::as_str)
+        .and_then(|id| id.parse().ok());
+
+    match collection_id {
+        Some(collection_id) => SyncScope::Collection {
+            schema: schema.unwrap_or_else(|| "public".to_string()),
+            collection_id,
+        },
+        None => SyncScope::Enabled { schema },
+    }
+}
+
+fn add_sync(total: &mut CollectionRuleSyncResult, synced: CollectionRuleSyncResult) {
+    total.matched += synced.matched;
+    total.added += synced.added;
+    total.removed += synced.removed;
+    total.skipped += synced.skipped;
+}
+
+pub struct CollectionRuleSyncHandler {
+    db: Database,
+}
+
+impl CollectionRuleSyncHandler {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Sync one rule in its own transaction.
+    async fn sync_rule(
+        &self,
+        schema: &str,
+        collection_id: Uuid,
+    ) -> Result<CollectionRuleSyncResult, String> {
+        let schema_ctx = self
+            .db
+            .for_schema(schema)
+            .map_err(|_| "Invalid schema".to_string())?;
+        let mut tx = schema_ctx
+            .begin_tx()
+            .await
+            .map_err(|_| "Failed to begin transaction".to_string())?;
+        let synced = self
+            .db
+            .collection_rules
+            .sync_tx(&mut tx, collection_id)
+            .await
+            .map_err(|e| match e {
+                matric_core::Error::NotFound(_) => "Collection rule not found".to_string(),
+                _ => "Failed to sync collection rule".to_string(),
+            })?;
+        tx.commit()
+            .await
+            .map_err(|_| "Failed to commit collection rule sync".to_string())?;
+        Ok(synced)
+    }
+
+    /// Collections with an enabled rule in one archive.
+    async fn enabled_rules(&self, schema: &str) -> Result<Vec<Uuid>, String> {
+        let schema_ctx = self
+            .db
+            .for_schema(schema)
+            .map_err(|_| "Invalid schema".to_string())?;
+        let mut tx = schema_ctx
+            .begin_tx()
+            .await
+            .map_err(|_| "Failed to begin transaction".to_string())?;
+        let rules = self
+            .db
+            .collection_rules
+            .list_enabled_tx(&mut tx)
+            .await
+            .map_err(|_| "Failed to list collection rules".to_string())?;
+        tx.commit()
+            .await
+            .map_err(|_| "Failed to commit collection rule listing".to_string())?;
+        Ok(rules.into_iter().map(|rule| rule.collection_id).collect())
+    }
+}
+
+#[async_trait]
+impl JobHandler for CollectionRuleSyncHandler {
+    fn job_type(&self) -> JobType {
+        JobType::CollectionRuleSync
+    }
+
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let schemas = match sync_scope(ctx.payload()) {
+            SyncScope::Collection {
+                schema,
+                collection_id,
+            } => {
+                ctx.report_progress(10, Some("Syncing collection rule"));
+                return match self.sync_rule(&schema, collection_id).await {
+                    Ok(synced) => {
+                        ctx.report_progress(100, Some("Collection rule synced"));
+                        JobResult::Success(Some(json!({ "rules_synced": 1, "sync": synced })))
+                    }
+                    Err(reason) if reason == "Collection rule not found" => {
+                        JobResult::Failed(reason)
+                    }
+                    Err(reason) => JobResult::Retry(reason),
+                };
+            }
+            SyncScope::Enabled {
+                schema: Some(schema),
+            } => vec![schema],
+            SyncScope::Enabled { schema: None } => {
+                match self.db.archives.list_archive_schemas().await {
+                    Ok(archives) => archives.into_iter().map(|a| a.schema_name).collect(),
+                    Err(_) => return JobResult::Retry("Failed to list archives".into()),
+                }
+            }
+        };
+
+        let mut total = CollectionRuleSyncResult::default();
+        let mut rules_synced = 0;
+        let mut rules_failed = 0;
+        for (i, schema) in schemas.iter().enumerate() {
+            ctx.report_progress(
+                (i * 100 / schemas.len().max(1)) as i32,
+                Some("Syncing collection rules"),
+            );
+            let rules = match self.enabled_rules(schema).await {
+                Ok(rules) => rules,
+                Err(reason) => {
+                    warn!(
+                        schema_len = schema.len(),
+                        reason = %reason,
+                        "Collection rule sync skipped archive"
+                    );
+                    continue;
+                }
+            };
+            for collection_id in rules {
+                match self.sync_rule(schema, collection_id).await {
+                    Ok(synced) => {
+                        rules_synced += 1;
+                        add_sync(&mut total, synced);
+                    }
+                    Err(reason) => {
+                        rules_failed += 1;
+                        warn!(reason = %reason, "Collection rule sync failed");
+                    }
+                }
+            }
+        }
+
+        info!(
+            archives = schemas.len(),
+            rules_synced,
+            rules_failed,
+            added = total.added,
+            removed = total.removed,
+            "Collection rule sync complete"
+        );
+        ctx.report_progress(100, Some("Collection rule sync complete"));
+        JobResult::Success(Some(json!({
+            "rules_synced": rules_synced,
+            "rules_failed": rules_failed,
+            "sync": total,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_scope_defaults_to_the_sweep() {
+        assert_eq!(sync_scope(None), SyncScope::Enabled { schema: None });
+        assert_eq!(
+            sync_scope(Some(&json!({ "schema": "archive_a" }))),
+            SyncScope::Enabled {
+                schema: Some("archive_a".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn sync_scope_reads_one_collection() {
+        let id = Uuid::nil();
+        assert_eq!(
+            sync_scope(Some(&json!({ "collection_id": id }))),
+            SyncScope::Collection {
+                schema: "public".to_string(),
+                collection_id: id,
+            }
+        );
+    }
+}
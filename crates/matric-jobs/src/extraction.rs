@@ -3,7 +3,9 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use matric_core::{ExtractionAdapter, ExtractionResult, ExtractionStrategy, ProgressFn, Result};
+use matric_core::{
+    CheckpointFn, ExtractionAdapter, ExtractionResult, ExtractionStrategy, ProgressFn, Result,
+};
 use serde_json::Value as JsonValue;
 
 /// Registry mapping extraction strategies to their adapter implementations.
@@ -70,6 +72,30 @@ impl ExtractionRegistry {
             .await
     }
 
+    /// Extract content with progress reporting and checkpoints.
+    ///
+    /// Adapters that can resume (video keyframe description) pass their state
+    /// to `checkpoint` after each completed item. Others ignore it.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn extract_resumable(
+        &self,
+        strategy: ExtractionStrategy,
+        data: &[u8],
+        filename: &str,
+        mime_type: &str,
+        config: &JsonValue,
+        progress: ProgressFn,
+        checkpoint: CheckpointFn,
+    ) -> Result<ExtractionResult> {
+        let adapter = self
+            .adapters
+            .get(&strategy)
+            .ok_or_else(|| Self::missing_adapter_error(strategy))?;
+        adapter
+            .extract_resumable(data, filename, mime_type, config, progress, checkpoint)
+            .await
+    }
+
     /// List all strategies that have registered adapters.
     pub fn available_strategies(&self) -> Vec<ExtractionStrategy> {
         self.adapters.keys().copied().collect()
@@ -8,15 +8,23 @@ use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use matric_core::{
-    AttachmentScanStatus, AttachmentStatus, ExtractionStrategy, JobRepository, JobType, ProgressFn,
+    AttachmentScanStatus, AttachmentStatus, CreateNoteRequest, DerivedNote, ExtractionStrategy,
+    JobRepository, JobType, ProgressFn, UpdateNoteStatusRequest,
 };
 use matric_db::{Database, SchemaContext};
 
+/// Note source recorded on notes created from an adapter's derived notes.
+const DERIVED_NOTE_SOURCE: &str = "extraction";
+
+/// Link kind from a derived note back to the note that owns the attachment.
+const DERIVED_NOTE_LINK_KIND: &str = "derived_from";
+
 /// Minimum note content length (in chars) below which extraction results
 /// replace the note content.  Notes auto-created from attachment uploads
 /// typically have only the filename as content — these should be enriched.
 const MIN_CONTENT_LEN: usize = 50;
 
+use crate::adapters::archive;
 use crate::extraction::ExtractionRegistry;
 use crate::handler::{JobContext, JobHandler, JobResult};
 use crate::{AttachmentScanMetrics, AttachmentScanMode};
@@ -207,6 +215,62 @@ impl ExtractionHandler {
             attachment_scan_metrics,
         }
     }
+
+    /// Create notes split out of an attachment (e.g. chat threads) in one
+    /// transaction, each linked back to the owning note.
+    ///
+    /// Returns the IDs of the new notes.
+    async fn persist_derived_notes(
+        &self,
+        schema_ctx: &SchemaContext,
+        parent_note_id: Uuid,
+        attachment_id: Option<Uuid>,
+        derived_notes: &[DerivedNote],
+    ) -> matric_core::Result<Vec<Uuid>> {
+        let mut tx = schema_ctx.begin_tx().await?;
+        let mut ids = Vec::with_capacity(derived_notes.len());
+        for derived in derived_notes {
+            let mut metadata = derived.metadata.clone();
+            if !metadata.is_object() {
+                metadata = json!({});
+            }
+            metadata["derived_from"] = json!({
+                "note_id": parent_note_id,
+                "attachment_id": attachment_id,
+            });
+            let note_id = self
+                .db
+                .notes
+                .insert_tx(
+                    &mut tx,
+                    CreateNoteRequest {
+                        content: derived.content.clone(),
+                        format: "markdown".to_string(),
+                        source: DERIVED_NOTE_SOURCE.to_string(),
+                        collection_id: None,
+                        tags: Some(derived.tags.clone()),
+                        metadata: Some(metadata),
+                        document_type_id: None,
+                        title: Some(derived.title.clone()),
+                    },
+                )
+                .await?;
+            self.db
+                .links
+                .create_tx(
+                    &mut tx,
+                    note_id,
+                    parent_note_id,
+                    DERIVED_NOTE_LINK_KIND,
+                    1.0,
+                    None,
+                )
+                .await?;
+            ids.push(note_id);
+        }
+        tx.commit().await.map_err(matric_core::Error::Database)?;
+        Ok(ids)
+    }
 }
 
 #[async_trait]
@@ -264,6 +328,39 @@ impl JobHandler for ExtractionHandler {
 
         ctx.report_progress(5, Some("Resolving attachment and strategy"));
 
+        // The note's document type policy may pin the extraction strategy,
+        // replacing the one detected from the upload's MIME type.
+        let strategy = match (ctx.note_id(), attachment_id) {
+            (Some(note_id), Some(_)) => {
+                let mut tx = match schema_ctx.begin_tx().await {
+                    Ok(t) => t,
+                    Err(e) => return extraction_job_failure("Schema tx failed", e),
+                };
+                let policy = self
+                    .db
+                    .document_types
+                    .policy_for_note_tx(&mut tx, note_id)
+                    .await;
+                if let Err(e) = tx.commit().await {
+                    return extraction_job_failure("Commit failed", e);
+                }
+                match policy {
+                    Ok(policy) => match policy.extraction_strategy {
+                        Some(pinned) if pinned != strategy => {
+                            debug!(
+                                strategy_len = telemetry_strategy_len(pinned),
+                                "Using extraction strategy from document type policy"
+                            );
+                            pinned
+                        }
+                        _ => strategy,
+                    },
+                    Err(e) => return extraction_job_failure("Failed to load pipeline policy", e),
+                }
+            }
+            _ => strategy,
+        };
+
         // For strategies that benefit from direct filesystem access (video, audio),
         // resolve the on-disk path instead of loading the entire file into memory.
         //
@@ -453,6 +550,23 @@ impl JobHandler for ExtractionHandler {
             }
         }
 
+        // Resume from the job checkpoint: adapters read what an interrupted run
+        // saved (e.g. video frame descriptions) back from `_checkpoint`.
+        if let Some(JsonValue::Object(saved)) = ctx.checkpoint::<JsonValue>().await {
+            if let Some(obj) = config.as_object_mut() {
+                let entry = obj.entry("_checkpoint").or_insert_with(|| json!({}));
+                if let Some(checkpoint) = entry.as_object_mut() {
+                    for (key, value) in saved {
+                        checkpoint.entry(key).or_insert(value);
+                    }
+                }
+            }
+            debug!(
+                strategy_len = telemetry_strategy_len(strategy),
+                "Checkpoint: resuming extraction from saved job state"
+            );
+        }
+
         // Inject _skip_vision for VideoMultimodal and Glb3DModel: defer vision
         // LLM calls to atomic per-item vision jobs instead of running them inline.
         // - VideoMultimodal → KeyframeVision jobs (#526)
@@ -484,6 +598,14 @@ impl JobHandler for ExtractionHandler {
             );
         }
 
+        // Ask the archive adapter to hand back documents and nested archives
+        // so they can be extracted recursively below.
+        if strategy == ExtractionStrategy::Archive && archive::recursion_enabled(&config) {
+            if let Some(obj) = config.as_object_mut() {
+                obj.insert(archive::ARCHIVE_DEPTH_KEY.to_string(), json!(0));
+            }
+        }
+
         ctx.report_progress(10, Some("Starting extraction"));
 
         // Check adapter availability
@@ -519,10 +641,79 @@ impl JobHandler for ExtractionHandler {
         // Run extraction with progress reporting
         match self
             .registry
-            .extract_with_progress(strategy, &data, &filename, &mime_type, &config, progress)
+            .extract_resumable(
+                strategy,
+                &data,
+                &filename,
+                &mime_type,
+                &config,
+                progress,
+                ctx.checkpoint_fn(),
+            )
             .await
         {
             Ok(result) => {
+                // Scanned PDFs yield (almost) no text layer; OCR them instead
+                // when the PdfOcr adapter is enabled. Keep the text-layer
+                // result if OCR fails.
+                let needs_ocr = result
+                    .metadata
+                    .get("needs_ocr")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let result = if strategy == ExtractionStrategy::PdfText
+                    && needs_ocr
+                    && self.registry.has_adapter(ExtractionStrategy::PdfOcr)
+                {
+                    ctx.report_progress(50, Some("Running OCR on scanned PDF"));
+                    match self
+                        .registry
+                        .extract(
+                            ExtractionStrategy::PdfOcr,
+                            &data,
+                            &filename,
+                            &mime_type,
+                            &config,
+                        )
+                        .await
+                    {
+                        Ok(ocr_result) => ocr_result,
+                        Err(e) => {
+                            let error_text = e.to_string();
+                            warn!(
+                                filename_len = telemetry_text_len(&filename),
+                                error_len = telemetry_text_len(&error_text),
+                                error_reason = extraction_error_reason_code(&error_text),
+                                "OCR fallback failed; keeping PDF text layer"
+                            );
+                            result
+                        }
+                    }
+                } else {
+                    result
+                };
+
+                // Run archive members through their own adapters.
+                let result = if strategy == ExtractionStrategy::Archive
+                    && config.get(archive::ARCHIVE_DEPTH_KEY).is_some()
+                {
+                    ctx.report_progress(70, Some("Extracting archive contents"));
+                    let result =
+                        archive::expand_archive_members(&self.registry, &filename, result, &config)
+                            .await;
+                    debug!(
+                        members_extracted = result
+                            .metadata
+                            .get("members_extracted")
+                            .and_then(|v| v.as_u64())
+                            .unwrap_or(0),
+                        "Archive members extracted recursively"
+                    );
+                    result
+                } else {
+                    result
+                };
+
                 ctx.report_progress(80, Some("Extraction complete"));
 
                 // Persist extraction results to the attachment record (schema-aware)
@@ -621,6 +812,113 @@ impl JobHandler for ExtractionHandler {
                     }
                 }
 
+                // Merge adapter-provided note metadata (e.g. ebook title/author/ISBN)
+                // into the owning note. Existing keys outside the payload are kept.
+                if let (Some(note_id), Some(note_metadata)) = (
+                    ctx.note_id(),
+                    result
+                        .metadata
+                        .get("note_metadata")
+                        .filter(|v| v.as_object().is_some_and(|m| !m.is_empty())),
+                ) {
+                    let merged = match schema_ctx.begin_tx().await {
+                        Ok(mut tx) => {
+                            let update = self
+                                .db
+                                .notes
+                                .update_status_tx(
+                                    &mut tx,
+                                    note_id,
+                                    UpdateNoteStatusRequest {
+                                        starred: None,
+                                        archived: None,
+                                        pinned: None,
+                                        priority: None,
+                                        metadata: Some(note_metadata.clone()),
+                                    },
+                                )
+                                .await;
+                            match update {
+                                Ok(()) => tx.commit().await.map_err(matric_core::Error::Database),
+                                Err(e) => Err(e),
+                            }
+                        }
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = merged {
+                        let error_text = e.to_string();
+                        warn!(
+                            error_len = telemetry_text_len(&error_text),
+                            error_reason = extraction_error_reason_code(&error_text),
+                            "Failed to merge extracted metadata into note"
+                        );
+                    }
+                }
+
+                // Create notes for adapter-provided sections (e.g. chat threads) and
+                // queue them for embedding so they are searchable on their own.
+                if let (Some(note_id), false) = (ctx.note_id(), result.derived_notes.is_empty()) {
+                    match self
+                        .persist_derived_notes(
+                            &schema_ctx,
+                            note_id,
+                            attachment_id,
+                            &result.derived_notes,
+                        )
+                        .await
+                    {
+                        Ok(derived_ids) => {
+                            info!(
+                                derived_note_count = derived_ids.len(),
+                                "Derived notes created from extraction"
+                            );
+                            for derived_id in derived_ids {
+                                let mut payload = json!({});
+                                if schema != "public" {
+                                    payload["schema"] = json!(schema);
+                                }
+                                match self
+                                    .db
+                                    .jobs
+                                    .queue_deduplicated(
+                                        Some(derived_id),
+                                        JobType::Embedding,
+                                        JobType::Embedding.default_priority(),
+                                        Some(payload),
+                                        JobType::Embedding.default_cost_tier(),
+                                    )
+                                    .await
+                                {
+                                    Ok(Some(job_id)) => ctx.emit_job_queued(
+                                        job_id,
+                                        JobType::Embedding,
+                                        Some(derived_id),
+                                    ),
+                                    Ok(None) => {}
+                                    Err(e) => {
+                                        let error_text = e.to_string();
+                                        warn!(
+                                            error_len = telemetry_text_len(&error_text),
+                                            error_reason =
+                                                extraction_error_reason_code(&error_text),
+                                            "Failed to queue derived note embedding"
+                                        );
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            let error_text = e.to_string();
+                            warn!(
+                                derived_note_count = result.derived_notes.len(),
+                                error_len = telemetry_text_len(&error_text),
+                                error_reason = extraction_error_reason_code(&error_text),
+                                "Failed to create derived notes"
+                            );
+                        }
+                    }
+                }
+
                 // Run MP4 faststart optimization if applicable (#503)
                 if let Some(att_id) = attachment_id {
                     if mime_type == "video/mp4" || mime_type == "video/quicktime" {
@@ -992,6 +1290,7 @@ impl JobHandler for ExtractionHandler {
                                                 "email_attachment"
                                                     | "archive_entry"
                                                     | "embedded_attachment"
+                                                    | "cover_image"
                                             );
                                             if requires_independent_scan {
                                                 if self.attachment_scan_mode
@@ -1175,6 +1474,12 @@ impl JobHandler for ExtractionHandler {
                         .filter(|s| !s.is_empty())
                         .is_some();
 
+                let image_embedding_available =
+                    std::env::var(matric_core::defaults::ENV_CLIP_BASE_URL)
+                        .ok()
+                        .filter(|s| !s.is_empty())
+                        .is_some();
+
                 if let (Some(att_id), Some(note_id)) = (attachment_id, ctx.note_id()) {
                     // VideoMultimodal: queue AudioTranscription job instead of inline diarization.
                     // AudioTranscriptionHandler will transcribe, persist captions, queue diarization,
@@ -1728,6 +2033,49 @@ impl JobHandler for ExtractionHandler {
                              ViewVision jobs will not be queued"
                         );
                     }
+
+                    // Queue CLIP embedding of image attachments for image search.
+                    if matches!(strategy, ExtractionStrategy::Vision) && image_embedding_available {
+                        let mut embed_payload = serde_json::Map::new();
+                        embed_payload
+                            .insert("attachment_id".to_string(), json!(att_id.to_string()));
+                        if schema != "public" {
+                            embed_payload.insert("schema".to_string(), json!(&schema));
+                        }
+                        // Use queue() not queue_deduplicated() — each image
+                        // attachment on a note is a distinct job.
+                        match self
+                            .db
+                            .jobs
+                            .queue(
+                                Some(note_id),
+                                JobType::ImageEmbedding,
+                                JobType::ImageEmbedding.default_priority(),
+                                Some(serde_json::Value::Object(embed_payload)),
+                                JobType::ImageEmbedding.default_cost_tier(),
+                            )
+                            .await
+                        {
+                            Ok(job_id) => {
+                                ctx.emit_job_queued(job_id, JobType::ImageEmbedding, Some(note_id));
+                                info!(
+                                    note_present = true,
+                                    attachment_present = true,
+                                    "Image embedding job queued"
+                                );
+                            }
+                            Err(e) => {
+                                let error = e.to_string();
+                                warn!(
+                                    note_present = true,
+                                    attachment_present = true,
+                                    error_len = telemetry_text_len(&error),
+                                    error_reason = extraction_error_reason_code(&error),
+                                    "Failed to queue image embedding job"
+                                );
+                            }
+                        }
+                    }
                 }
 
                 // --- Bug 1b (Issue #492): propagate extraction content to note
@@ -1821,7 +2169,11 @@ impl JobHandler for ExtractionHandler {
                         // ConceptTagging removed — chained from AiRevision after revision completes.
                         // Embedding + Linking removed — chained from ConceptTagging → RelatedConceptInference.
                         // Pipeline: AiRevision → ConceptTagging → RelatedConceptInference → Embedding → Linking.
-                        let downstream_types = [JobType::TitleGeneration];
+                        // PiiScan re-runs so the new attachment text is scanned too.
+                        let mut downstream_types = vec![JobType::TitleGeneration];
+                        if matric_core::PiiRedactionMode::from_env().scans() {
+                            downstream_types.push(JobType::PiiScan);
+                        }
 
                         let mut schema_payload = serde_json::Map::new();
                         if schema != "public" {
@@ -0,0 +1,124 @@
+//! FederationSyncHandler — replicates a memory archive with a sync peer.
+//!
+//! The API scheduler queues a `federation_sync` job carrying the peer ID
+//! whenever a peer's cron schedule comes due, and operators can queue one on
+//! demand. The job records the run on the peer and asks the [`SyncRunner`]
+//! to pull the remote change feed and push the local one.
+//!
+//! Talking to the remote instance is left to the runner because the HTTP
+//! contract and archive scoping live in the API.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value as JsonValue};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use matric_core::{JobType, SyncOutcome, SyncPeer};
+use matric_db::Database;
+
+use crate::handler::{JobContext, JobHandler, JobResult};
+
+/// Exchanges changes with a sync peer.
+#[async_trait]
+pub trait SyncRunner: Send + Sync {
+    /// Pull and/or push changes as the peer's direction asks, advancing its
+    /// cursors as pages are applied.
+    async fn sync(&self, peer: &SyncPeer) -> Result<SyncOutcome, String>;
+}
+
+fn peer_target(payload: Option<&JsonValue>) -> Option<Uuid> {
+    payload
+        .and_then(|p| p.get("peer_id"))
+        .and_then(JsonValue::as_str)
+        .and_then(|s| Uuid::parse_str(s).ok())
+}
+
+pub struct FederationSyncHandler {
+    db: Database,
+    runner: Arc<dyn SyncRunner>,
+}
+
+impl FederationSyncHandler {
+    pub fn new(db: Database, runner: Arc<dyn SyncRunner>) -> Self {
+        Self { db, runner }
+    }
+}
+
+#[async_trait]
+impl JobHandler for FederationSyncHandler {
+    fn job_type(&self) -> JobType {
+        JobType::FederationSync
+    }
+
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let Some(peer_id) = peer_target(ctx.payload()) else {
+            return JobResult::Failed("Missing or invalid peer_id".into());
+        };
+        let peer = match self.db.sync_peers.get(peer_id).await {
+            Ok(Some(peer)) => peer,
+            // Deleted after the job was queued.
+            Ok(None) => return JobResult::Success(Some(json!({ "skipped": "peer_not_found" }))),
+            Err(_) => return JobResult::Retry("Failed to load sync peer".into()),
+        };
+
+        if self.db.sync_peers.start_run(peer.id).await.is_err() {
+            return JobResult::Retry("Failed to record sync run".into());
+        }
+        ctx.report_progress(10, Some("Syncing with peer"));
+
+        // Cursors are saved per page, so the next run resumes where this one
+        // stopped; retrying here would only repeat the same failure.
+        let outcome = match self.runner.sync(&peer).await {
+            Ok(outcome) => outcome,
+            Err(reason) => {
+                if self.db.sync_peers.fail_run(peer.id, &reason).await.is_err() {
+                    warn!("Failed to record failed sync run");
+                }
+                warn!(
+                    direction = %peer.direction,
+                    reason_len = reason.len(),
+                    "Federation sync failed"
+                );
+                return JobResult::Failed(reason);
+            }
+        };
+        if self.db.sync_peers.finish_run(peer.id).await.is_err() {
+            warn!("Failed to record completed sync run");
+        }
+
+        info!(
+            direction = %peer.direction,
+            pulled_applied = outcome.pulled.applied,
+            pushed_applied = outcome.pushed.applied,
+            "Federation sync complete"
+        );
+        ctx.report_progress(100, Some("Federation sync complete"));
+        JobResult::Success(Some(json!({
+            "peer_id": peer.id,
+            "pulled": outcome.pulled,
+            "pushed": outcome.pushed,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peer_target_requires_uuid() {
+        let id = Uuid::new_v4();
+        assert_eq!(peer_target(None), None);
+        assert_eq!(peer_target(Some(&json!({ "peer_id": "nope" }))), None);
+        assert_eq!(
+            peer_target(Some(&json!({ "policy_id": id.to_string() }))),
+            None
+        );
+        assert_eq!(
+            peer_target(Some(&json!({ "peer_id": id.to_string() }))),
+            Some(id)
+        );
+    }
+}
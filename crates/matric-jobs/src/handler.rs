@@ -1,11 +1,13 @@
 //! Job handlers for each job type.
 
 use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value as JsonValue;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, watch};
+use tracing::warn;
 use uuid::Uuid;
 
-use matric_core::{Job, JobType};
+use matric_core::{CheckpointFn, Error, Job, JobRepository, JobType, Result};
 
 use std::{fmt, sync::Arc};
 
@@ -14,6 +16,9 @@ use crate::worker::WorkerEvent;
 /// Progress callback type for job handlers.
 pub type ProgressCallback = Arc<dyn Fn(i32, Option<&str>) + Send + Sync>;
 
+/// Largest serialized checkpoint a job may save.
+pub const MAX_CHECKPOINT_BYTES: usize = 1024 * 1024;
+
 /// Context provided to job handlers.
 pub struct JobContext {
     /// The job being processed.
@@ -22,6 +27,8 @@ pub struct JobContext {
     progress_callback: Option<ProgressCallback>,
     /// Event broadcast sender for emitting worker events (e.g. downstream job.queued).
     event_tx: Option<broadcast::Sender<WorkerEvent>>,
+    /// Repository that persists checkpoints in the job row.
+    checkpoints: Option<Arc<dyn JobRepository>>,
 }
 
 impl JobContext {
@@ -31,6 +38,7 @@ impl JobContext {
             job,
             progress_callback: None,
             event_tx: None,
+            checkpoints: None,
         }
     }
 
@@ -49,6 +57,94 @@ impl JobContext {
         self
     }
 
+    /// Persist checkpoints through `jobs`. Without it, checkpoints are
+    /// neither loaded nor saved.
+    pub fn with_checkpoints(mut self, jobs: Arc<dyn JobRepository>) -> Self {
+        self.checkpoints = Some(jobs);
+        self
+    }
+
+    /// Load the state an earlier run of this job saved with
+    /// [`save_checkpoint`](Self::save_checkpoint).
+    ///
+    /// Returns `None` on a first run, and when the stored state cannot be read
+    /// or no longer matches `T`, so the handler starts from scratch.
+    pub async fn checkpoint<T: DeserializeOwned>(&self) -> Option<T> {
+        let jobs = self.checkpoints.as_ref()?;
+        let value = match jobs.get_checkpoint(self.job.id).await {
+            Ok(value) => value?,
+            Err(e) => {
+                warn!(
+                    error_len = e.to_string().len(),
+                    "Failed to load job checkpoint"
+                );
+                return None;
+            }
+        };
+        match serde_json::from_value(value) {
+            Ok(state) => Some(state),
+            Err(_) => {
+                warn!("Ignoring job checkpoint that does not match the handler state");
+                None
+            }
+        }
+    }
+
+    /// Save resumable state for this job, replacing any earlier checkpoint.
+    ///
+    /// A retry of the job, including one after a worker crash, can read it
+    /// back with [`checkpoint`](Self::checkpoint). The checkpoint is discarded
+    /// when the job completes. State larger than [`MAX_CHECKPOINT_BYTES`] is
+    /// rejected.
+    pub async fn save_checkpoint<T: Serialize + ?Sized>(&self, state: &T) -> Result<()> {
+        let Some(jobs) = self.checkpoints.as_ref() else {
+            return Ok(());
+        };
+        let value = serde_json::to_value(state)?;
+        let size = serde_json::to_vec(&value)?.len();
+        if size > MAX_CHECKPOINT_BYTES {
+            return Err(Error::InvalidInput(format!(
+                "Job checkpoint of {size} bytes exceeds {MAX_CHECKPOINT_BYTES}"
+            )));
+        }
+        jobs.save_checkpoint(self.job.id, &value).await?;
+        Ok(())
+    }
+
+    /// A callback that saves checkpoints in the background, for code that
+    /// cannot await (extraction adapters).
+    ///
+    /// Saves happen in order; when checkpoints arrive faster than they are
+    /// written, only the latest is kept.
+    pub fn checkpoint_fn(&self) -> CheckpointFn {
+        let Some(jobs) = self.checkpoints.clone() else {
+            return Arc::new(|_| {});
+        };
+        let job_id = self.job.id;
+        let (tx, mut rx) = watch::channel(None::<JsonValue>);
+        tokio::spawn(async move {
+            while rx.changed().await.is_ok() {
+                let Some(value) = rx.borrow_and_update().clone() else {
+                    continue;
+                };
+                let size = value.to_string().len();
+                if size > MAX_CHECKPOINT_BYTES {
+                    warn!(size, "Dropping oversized job checkpoint");
+                    continue;
+                }
+                if let Err(e) = jobs.save_checkpoint(job_id, &value).await {
+                    warn!(
+                        error_len = e.to_string().len(),
+                        "Failed to save job checkpoint"
+                    );
+                }
+            }
+        });
+        Arc::new(move |value| {
+            let _ = tx.send(Some(value));
+        })
+    }
+
     /// Report progress to the callback.
     pub fn report_progress(&self, percent: i32, message: Option<&str>) {
         if let Some(ref callback) = self.progress_callback {
@@ -203,6 +299,36 @@ mod tests {
         assert_eq!(ctx.note_id(), job.note_id);
     }
 
+    #[tokio::test]
+    async fn checkpoints_are_inert_without_a_repository() {
+        let job = Job {
+            id: Uuid::new_v4(),
+            note_id: None,
+            job_type: JobType::ReEmbedAll,
+            status: matric_core::JobStatus::Running,
+            priority: 0,
+            payload: None,
+            result: None,
+            error_message: None,
+            progress_percent: 0,
+            progress_message: None,
+            retry_count: 0,
+            max_retries: 3,
+            created_at: chrono::Utc::now(),
+            started_at: None,
+            completed_at: None,
+            cost_tier: None,
+        };
+
+        let ctx = JobContext::new(job);
+        assert!(ctx.checkpoint::<JsonValue>().await.is_none());
+        assert!(ctx
+            .save_checkpoint(&serde_json::json!({ "next": 10 }))
+            .await
+            .is_ok());
+        (ctx.checkpoint_fn())(serde_json::json!({ "next": 20 }));
+    }
+
     #[tokio::test]
     async fn test_noop_handler() {
         let handler = NoOpHandler::new(JobType::Embedding);
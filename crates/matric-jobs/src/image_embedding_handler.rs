@@ -0,0 +1,231 @@
+//! ImageEmbeddingHandler — embeds an image attachment with CLIP.
+//!
+//! Queued by ExtractionHandler after vision extraction of an image
+//! attachment. Downloads the image, embeds it with the configured
+//! `ImageEmbeddingBackend`, and stores the vector in the archive's `images`
+//! embedding set, where `/api/v1/search/images` finds it.
+
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use matric_core::JobType;
+use matric_db::{Database, SchemaContext};
+use matric_inference::ImageEmbeddingBackend;
+
+use crate::handler::{JobContext, JobHandler, JobResult};
+
+/// Extract the target schema from a job's payload.
+fn extract_schema(ctx: &JobContext) -> &str {
+    ctx.payload()
+        .and_then(|p| p.get("schema"))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("public")
+}
+
+fn schema_context(db: &Database, schema: &str) -> Result<SchemaContext, JobResult> {
+    db.for_schema(schema)
+        .map_err(|_| JobResult::Failed("Invalid schema".into()))
+}
+
+fn image_embedding_text_len(text: &str) -> usize {
+    text.chars().count()
+}
+
+fn image_embedding_error_reason_code(error: &str) -> &'static str {
+    let text = error.to_ascii_lowercase();
+    if text.contains("not found") || text.contains("no such") || text.contains("missing") {
+        "not_found"
+    } else if text.contains("timeout") || text.contains("timed out") {
+        "timed_out"
+    } else if text.contains("connection refused")
+        || text.contains("cannot connect")
+        || text.contains("connection")
+    {
+        "connection_failed"
+    } else if text.contains("database") || text.contains("sql") || text.contains("postgres") {
+        "database_error"
+    } else if text.contains("embedding") || text.contains("dimension") || text.contains("model") {
+        "model_backend_error"
+    } else {
+        "operation_failed"
+    }
+}
+
+fn image_embedding_skip_result(reason: &'static str) -> serde_json::Value {
+    json!({
+        "skipped": true,
+        "reason": reason,
+    })
+}
+
+pub struct ImageEmbeddingHandler {
+    db: Database,
+    backend: Option<Arc<dyn ImageEmbeddingBackend>>,
+}
+
+impl ImageEmbeddingHandler {
+    pub fn new(db: Database, backend: Option<Arc<dyn ImageEmbeddingBackend>>) -> Self {
+        Self { db, backend }
+    }
+}
+
+#[async_trait]
+impl JobHandler for ImageEmbeddingHandler {
+    fn job_type(&self) -> JobType {
+        JobType::ImageEmbedding
+    }
+
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let attachment_id: Uuid = match ctx
+            .payload()
+            .and_then(|p| p.get("attachment_id"))
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse().ok())
+        {
+            Some(id) => id,
+            None => return JobResult::Failed("Missing or invalid attachment_id".into()),
+        };
+
+        let schema = extract_schema(&ctx);
+        let schema_ctx = match schema_context(&self.db, schema) {
+            Ok(ctx) => ctx,
+            Err(e) => return e,
+        };
+
+        // Defer until CLIP is configured rather than dropping the job.
+        let backend = match self.backend.as_ref() {
+            Some(b) => b,
+            None => {
+                warn!(
+                    attachment_id_present = true,
+                    "ImageEmbedding job deferred — image embedding backend unavailable"
+                );
+                return JobResult::Retry(
+                    "Image embedding backend unavailable — job will retry when configured".into(),
+                );
+            }
+        };
+
+        let file_storage = match self.db.file_storage.as_ref() {
+            Some(fs) => fs,
+            None => return JobResult::Failed("File storage not configured".into()),
+        };
+
+        ctx.report_progress(10, Some("Downloading image"));
+        let (image_data, content_type) = {
+            let mut tx = match schema_ctx.begin_tx().await {
+                Ok(t) => t,
+                Err(e) => {
+                    let error_text = e.to_string();
+                    return JobResult::Failed(format!(
+                        "Schema tx failed ({})",
+                        image_embedding_error_reason_code(&error_text)
+                    ));
+                }
+            };
+            let result = file_storage.download_file_tx(&mut tx, attachment_id).await;
+            let _ = tx.commit().await;
+            match result {
+                Ok((data, content_type, _filename)) => (data, content_type),
+                Err(e) => {
+                    let error_text = e.to_string();
+                    return JobResult::Failed(format!(
+                        "Failed to download image ({})",
+                        image_embedding_error_reason_code(&error_text)
+                    ));
+                }
+            }
+        };
+
+        if !content_type.starts_with("image/") {
+            return JobResult::Success(Some(image_embedding_skip_result("not_an_image")));
+        }
+        if image_data.is_empty() {
+            return JobResult::Success(Some(image_embedding_skip_result("empty_image")));
+        }
+
+        ctx.report_progress(40, Some("Embedding image"));
+        let vector = match backend.embed_image(&image_data, &content_type).await {
+            Ok(v) => v,
+            Err(e) => {
+                let error_text = e.to_string();
+                warn!(
+                    attachment_id_present = true,
+                    model_len = image_embedding_text_len(backend.model_name()),
+                    image_bytes = image_data.len(),
+                    error_len = image_embedding_text_len(&error_text),
+                    error_reason = image_embedding_error_reason_code(&error_text),
+                    "Image embedding failed — will retry"
+                );
+                return JobResult::Retry(format!(
+                    "Image embedding failed ({})",
+                    image_embedding_error_reason_code(&error_text)
+                ));
+            }
+        };
+
+        ctx.report_progress(80, Some("Storing image embedding"));
+        let store = async {
+            let mut tx = schema_ctx.begin_tx().await?;
+            self.db
+                .image_embeddings
+                .store_tx(&mut tx, attachment_id, &vector, backend.model_name())
+                .await?;
+            tx.commit().await.map_err(matric_core::Error::Database)
+        };
+        if let Err(e) = store.await {
+            let error_text = e.to_string();
+            return JobResult::Failed(format!(
+                "Failed to store image embedding ({})",
+                image_embedding_error_reason_code(&error_text)
+            ));
+        }
+
+        info!(
+            attachment_id_present = true,
+            model_len = image_embedding_text_len(backend.model_name()),
+            image_bytes = image_data.len(),
+            "Image attachment embedded"
+        );
+        JobResult::Success(Some(json!({
+            "dimension": vector.as_slice().len(),
+            "model": backend.model_name(),
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_embedding_error_reason_code_uses_stable_classes() {
+        assert_eq!(
+            image_embedding_error_reason_code("Image embedding has 768 dimensions; expected 512"),
+            "model_backend_error"
+        );
+        assert_eq!(
+            image_embedding_error_reason_code("postgres://user:secret@db/app sql failed"),
+            "database_error"
+        );
+        assert_eq!(
+            image_embedding_error_reason_code("Cannot connect to clip.internal"),
+            "connection_failed"
+        );
+        assert_eq!(
+            image_embedding_error_reason_code("opaque backend text with token mm_key_secret"),
+            "operation_failed"
+        );
+    }
+
+    #[test]
+    fn image_embedding_skip_result_reports_reason() {
+        let result = image_embedding_skip_result("not_an_image");
+        assert_eq!(result["skipped"], true);
+        assert_eq!(result["reason"], "not_an_image");
+    }
+}
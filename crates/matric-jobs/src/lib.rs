@@ -38,13 +38,20 @@
 //! ```
 
 pub mod adapters;
+pub mod archive_merge_handler;
 pub mod attachment_scan;
 pub mod audio_chunk_handler;
 pub mod audio_transcription_handler;
+pub mod backup_policy_handler;
+pub mod blob_gc_handler;
+pub mod bulk_note_update_handler;
+pub mod collection_rule_sync_handler;
 pub mod diarization_handler;
 pub mod extraction;
 pub mod extraction_handler;
+pub mod federation_sync_handler;
 pub mod handler;
+pub mod image_embedding_handler;
 pub mod inbound;
 pub mod keyframe_assembly_handler;
 pub mod keyframe_character_vision_handler;
@@ -52,10 +59,15 @@ pub mod keyframe_setting_vision_handler;
 pub mod keyframe_vision_handler;
 pub mod media_optimize_handler;
 mod media_usage;
+pub mod outbound;
 pub mod pause;
+pub mod pke_rotation_handler;
 pub mod relabel_handler;
 pub mod sidecar;
 pub mod sprite_handler;
+pub mod taxonomy_health_handler;
+pub mod trash_purge_handler;
+pub mod version_prune_handler;
 pub mod view_assembly_handler;
 pub mod view_vision_handler;
 pub mod worker;
@@ -64,32 +76,44 @@ pub mod worker;
 pub use matric_core::*;
 
 // Re-export job types
+pub use archive_merge_handler::ArchiveMergeHandler;
 pub use attachment_scan::{
     AttachmentScanConfig, AttachmentScanFailure, AttachmentScanHandler, AttachmentScanMetrics,
     AttachmentScanMode, AttachmentScanOutcome, AttachmentScanner, ClamdScanner,
 };
 pub use audio_chunk_handler::AudioChunkTranscriptionHandler;
 pub use audio_transcription_handler::AudioTranscriptionHandler;
+pub use backup_policy_handler::{BackupRunner, CreatedBackup, ScheduledBackupHandler};
+pub use blob_gc_handler::BlobGarbageCollectionHandler;
+pub use bulk_note_update_handler::BulkNoteUpdateHandler;
+pub use collection_rule_sync_handler::CollectionRuleSyncHandler;
 pub use diarization_handler::SpeakerDiarizationHandler;
 pub use extraction_handler::ExtractionHandler;
+pub use federation_sync_handler::{FederationSyncHandler, SyncRunner};
 pub use handler::{JobContext, JobHandler, JobResult, NoOpHandler};
+pub use image_embedding_handler::ImageEmbeddingHandler;
 pub use keyframe_assembly_handler::KeyframeAssemblyHandler;
 pub use keyframe_character_vision_handler::KeyframeCharacterVisionHandler;
 pub use keyframe_setting_vision_handler::KeyframeSettingVisionHandler;
 pub use keyframe_vision_handler::KeyframeVisionHandler;
 pub use media_optimize_handler::MediaOptimizeHandler;
 pub use pause::PauseState;
+pub use pke_rotation_handler::{PkeKeyRotationHandler, PkeRotationKeys};
 pub use relabel_handler::{SpeakerConfig, SpeakerRelabelHandler};
 pub use sprite_handler::ThumbnailSpriteHandler;
+pub use taxonomy_health_handler::TaxonomyHealthHandler;
+pub use trash_purge_handler::TrashPurgeHandler;
+pub use version_prune_handler::VersionPruneHandler;
 pub use view_assembly_handler::ViewAssemblyHandler;
 pub use view_vision_handler::ViewVisionHandler;
 pub use worker::{JobWorker, WorkerBuilder, WorkerConfig, WorkerEvent, WorkerHandle};
 
 // Re-export extraction types
 pub use adapters::{
-    ArchiveAdapter, AudioTranscribeAdapter, CodeAstAdapter, ContentSummarizer, EmailAdapter,
-    Glb3DModelAdapter, OfficeConvertAdapter, PdfOcrAdapter, PdfTextAdapter, SpreadsheetAdapter,
-    StructuredExtractAdapter, TextNativeAdapter, VideoMultimodalAdapter, VisionAdapter,
+    ArchiveAdapter, AudioTranscribeAdapter, ChatExportAdapter, CodeAstAdapter, ContentSummarizer,
+    EbookAdapter, EmailAdapter, Glb3DModelAdapter, NotebookAdapter, OfficeConvertAdapter,
+    PdfOcrAdapter, PdfTextAdapter, SpreadsheetAdapter, StructuredExtractAdapter, TextNativeAdapter,
+    VideoMultimodalAdapter, VisionAdapter,
 };
 pub use extraction::ExtractionRegistry;
 
@@ -0,0 +1,166 @@
+//! Kafka outbound sink — feature-gated (`kafka`), high-end tier only.
+//!
+//! Publishes with an idempotent producer (`acks=all`,
+//! `enable.idempotence=true`); `publish` resolves only after the partition
+//! leader and in-sync replicas have acknowledged the record. Delivery failures
+//! are surfaced as transient errors and retried by the dispatcher; records the
+//! broker refuses outright (e.g. oversized messages) are rejected.
+
+use async_trait::async_trait;
+use rdkafka::config::ClientConfig;
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::info;
+
+use crate::inbound::source::{telemetry_destination_class, telemetry_text_len};
+
+use super::sink::{outbound_error_detail, EventSink, OutboundError, OutboundEvent, OutboundResult};
+
+/// Sink config, deserialized from the `EVENT_SINKS` entry's `config`.
+#[derive(Clone, Deserialize)]
+pub struct KafkaSinkConfig {
+    pub bootstrap_servers: String,
+    /// e.g. "PLAINTEXT", "SSL", "SASL_SSL", "SASL_PLAINTEXT".
+    #[serde(default)]
+    pub security_protocol: Option<String>,
+    /// e.g. "PLAIN", "SCRAM-SHA-256", "SCRAM-SHA-512".
+    #[serde(default)]
+    pub sasl_mechanism: Option<String>,
+    #[serde(default)]
+    pub sasl_username: Option<String>,
+    #[serde(default)]
+    pub sasl_password: Option<String>,
+    #[serde(default)]
+    pub ssl_ca_location: Option<String>,
+    /// Per-record delivery timeout (`message.timeout.ms`).
+    #[serde(default = "default_delivery_timeout_ms")]
+    pub delivery_timeout_ms: u64,
+    /// Passthrough librdkafka properties (advanced tuning).
+    #[serde(default)]
+    pub extra: BTreeMap<String, String>,
+}
+
+impl std::fmt::Debug for KafkaSinkConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaSinkConfig")
+            .field(
+                "bootstrap_servers_class",
+                &telemetry_destination_class(&self.bootstrap_servers),
+            )
+            .field(
+                "bootstrap_servers_len",
+                &telemetry_text_len(&self.bootstrap_servers),
+            )
+            .field(
+                "security_protocol_len",
+                &self.security_protocol.as_deref().map(telemetry_text_len),
+            )
+            .field(
+                "sasl_mechanism_len",
+                &self.sasl_mechanism.as_deref().map(telemetry_text_len),
+            )
+            .field("sasl_username_set", &self.sasl_username.is_some())
+            .field("sasl_password_set", &self.sasl_password.is_some())
+            .field("ssl_ca_location_set", &self.ssl_ca_location.is_some())
+            .field("delivery_timeout_ms", &self.delivery_timeout_ms)
+            .field("extra_count", &self.extra.len())
+            .finish()
+    }
+}
+
+fn default_delivery_timeout_ms() -> u64 {
+    30_000
+}
+
+/// A Kafka producer sink.
+pub struct KafkaSink {
+    name: String,
+    producer: FutureProducer,
+    delivery_timeout: Duration,
+}
+
+impl KafkaSink {
+    /// Build from JSON config (sync; used by the sink registry). Creating the
+    /// librdkafka client does not block on the network — it connects lazily.
+    pub fn from_config(name: &str, config: &Value) -> OutboundResult<Self> {
+        let cfg: KafkaSinkConfig = serde_json::from_value(config.clone()).map_err(|e| {
+            OutboundError::Rejected(outbound_error_detail("kafka", "config_parse", &e))
+        })?;
+        if cfg.bootstrap_servers.trim().is_empty() {
+            return Err(OutboundError::Rejected(
+                "kafka sink config requires bootstrap_servers".to_string(),
+            ));
+        }
+
+        let mut cc = ClientConfig::new();
+        cc.set("bootstrap.servers", &cfg.bootstrap_servers)
+            .set("acks", "all")
+            .set("enable.idempotence", "true")
+            .set("message.timeout.ms", cfg.delivery_timeout_ms.to_string());
+        for (key, value) in [
+            ("security.protocol", &cfg.security_protocol),
+            ("sasl.mechanism", &cfg.sasl_mechanism),
+            ("sasl.username", &cfg.sasl_username),
+            ("sasl.password", &cfg.sasl_password),
+            ("ssl.ca.location", &cfg.ssl_ca_location),
+        ] {
+            if let Some(value) = value {
+                cc.set(key, value);
+            }
+        }
+        for (k, v) in &cfg.extra {
+            cc.set(k, v);
+        }
+        let producer: FutureProducer = cc.create().map_err(|e| {
+            OutboundError::Rejected(outbound_error_detail("kafka", "producer_create", &e))
+        })?;
+
+        info!(
+            sink_name_len = telemetry_text_len(name),
+            destination_class = telemetry_destination_class(&cfg.bootstrap_servers),
+            destination_len = telemetry_text_len(&cfg.bootstrap_servers),
+            "kafka sink producer created"
+        );
+        Ok(Self {
+            name: name.to_string(),
+            producer,
+            delivery_timeout: Duration::from_millis(cfg.delivery_timeout_ms),
+        })
+    }
+}
+
+#[async_trait]
+impl EventSink for KafkaSink {
+    async fn publish(&self, topic: &str, event: &OutboundEvent) -> OutboundResult<()> {
+        let record = FutureRecord::to(topic)
+            .key(event.key.as_bytes())
+            .payload(event.payload.as_slice());
+        match self.producer.send(record, self.delivery_timeout).await {
+            Ok(_) => Ok(()),
+            Err((e, _)) => Err(classify_delivery_error(&e)),
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Broker refusals that retrying the same record cannot fix are rejected;
+/// everything else (timeouts, leader changes, broker down) is retried.
+fn classify_delivery_error(error: &KafkaError) -> OutboundError {
+    let detail = outbound_error_detail("kafka", "deliver", error);
+    match error.rdkafka_error_code() {
+        Some(
+            RDKafkaErrorCode::MessageSizeTooLarge
+            | RDKafkaErrorCode::InvalidMessage
+            | RDKafkaErrorCode::TopicAuthorizationFailed
+            | RDKafkaErrorCode::InvalidTopic,
+        ) => OutboundError::Rejected(detail),
+        _ => OutboundError::Transient(detail),
+    }
+}
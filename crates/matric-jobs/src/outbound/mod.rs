@@ -0,0 +1,28 @@
+//! Outbound event sinks, the counterpart to [`crate::inbound`].
+//!
+//! Publishes server events to external brokers alongside the webhook
+//! dispatcher: Redis Streams (always compiled), NATS (`nats` feature), and
+//! Kafka (`kafka` feature). This module provides the sink contract, registry,
+//! topic mapping, per-sink dispatcher with at-least-once retry, and metrics.
+
+pub mod dispatcher;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod metrics;
+#[cfg(feature = "nats")]
+pub mod nats;
+pub mod redis_stream;
+pub mod registry;
+pub mod sink;
+pub mod topics;
+
+pub use dispatcher::{parse_sink_specs, OutboundDispatcher, SinkSpec, DEFAULT_SINK_BUFFER};
+#[cfg(feature = "kafka")]
+pub use kafka::{KafkaSink, KafkaSinkConfig};
+pub use metrics::OutboundMetrics;
+#[cfg(feature = "nats")]
+pub use nats::{NatsSink, NatsSinkConfig};
+pub use redis_stream::{RedisStreamSink, RedisStreamSinkConfig};
+pub use registry::{SinkBuilder, SinkRegistry};
+pub use sink::{EventSink, InMemorySink, OutboundError, OutboundEvent, OutboundResult};
+pub use topics::TopicMap;
@@ -0,0 +1,72 @@
+//! Sink registry: maps an outbound-sink `kind` to a builder that constructs a
+//! sink from its JSON config. The API registers the kinds compiled into the
+//! binary (`redis-stream` always; `nats` and `kafka` behind their features).
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::sink::{EventSink, OutboundError, OutboundResult};
+
+/// Builds a sink instance for a configured entry: `(name, config)`.
+pub type SinkBuilder =
+    Box<dyn Fn(&str, &Value) -> OutboundResult<Box<dyn EventSink>> + Send + Sync>;
+
+/// Registry of sink builders keyed by `kind`.
+#[derive(Default)]
+pub struct SinkRegistry {
+    builders: HashMap<String, SinkBuilder>,
+}
+
+impl SinkRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a builder for `kind`. Replaces any existing builder.
+    pub fn register(&mut self, kind: impl Into<String>, builder: SinkBuilder) {
+        self.builders.insert(kind.into(), builder);
+    }
+
+    /// Build a sink for a configured entry. Errors when `kind` is unknown.
+    pub fn build(
+        &self,
+        kind: &str,
+        name: &str,
+        config: &Value,
+    ) -> OutboundResult<Box<dyn EventSink>> {
+        let builder = self.builders.get(kind).ok_or_else(|| {
+            OutboundError::Rejected(format!("no sink registered for kind '{kind}'"))
+        })?;
+        builder(name, config)
+    }
+
+    /// Registered sink kinds (diagnostics).
+    pub fn kinds(&self) -> Vec<String> {
+        let mut k: Vec<String> = self.builders.keys().cloned().collect();
+        k.sort();
+        k
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.builders.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::sink::InMemorySink;
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn register_build_and_unknown_kind() {
+        let mut reg = SinkRegistry::new();
+        reg.register(
+            "memory",
+            Box::new(|name, _config| Ok(Box::new(InMemorySink::new(name)) as Box<dyn EventSink>)),
+        );
+        assert_eq!(reg.kinds(), vec!["memory".to_string()]);
+        assert_eq!(reg.build("memory", "m1", &json!({})).unwrap().name(), "m1");
+        assert!(reg.build("kafka", "k1", &json!({})).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+//! Event-type → topic mapping for outbound sinks.
+//!
+//! Patterns are an exact event type (`note.updated`), a namespace prefix
+//! (`note.*`), or `*`. Resolution is order-independent: an exact match wins,
+//! then the longest matching prefix, then `*`, then the sink's default topic.
+//! Events that resolve to no topic are not published to that sink.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use super::sink::{OutboundError, OutboundResult};
+
+/// Per-sink topic routing table.
+#[derive(Clone, Default, Deserialize)]
+pub struct TopicMap {
+    /// Pattern → topic (stream key, subject, or Kafka topic).
+    #[serde(default)]
+    pub topics: BTreeMap<String, String>,
+    /// Topic for events no pattern matches; `None` drops unmatched events.
+    #[serde(default)]
+    pub default_topic: Option<String>,
+}
+
+impl std::fmt::Debug for TopicMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TopicMap")
+            .field("pattern_count", &self.topics.len())
+            .field("default_topic_present", &self.default_topic.is_some())
+            .finish()
+    }
+}
+
+impl TopicMap {
+    /// Reject empty patterns/topics and malformed wildcards.
+    pub fn validate(&self) -> OutboundResult<()> {
+        for (pattern, topic) in &self.topics {
+            let wildcard = pattern.find('*');
+            let valid_wildcard = match wildcard {
+                None => true,
+                Some(_) => pattern == "*" || pattern.strip_suffix(".*").is_some_and(is_literal),
+            };
+            if pattern.trim().is_empty() || !valid_wildcard {
+                return Err(OutboundError::Rejected(
+                    "topic pattern must be an event type, a `prefix.*` namespace, or `*`"
+                        .to_string(),
+                ));
+            }
+            if topic.trim().is_empty() {
+                return Err(OutboundError::Rejected(
+                    "topic mapping requires a non-empty topic".to_string(),
+                ));
+            }
+        }
+        if self
+            .default_topic
+            .as_deref()
+            .is_some_and(|t| t.trim().is_empty())
+        {
+            return Err(OutboundError::Rejected(
+                "default_topic must be non-empty when set".to_string(),
+            ));
+        }
+        if self.topics.is_empty() && self.default_topic.is_none() {
+            return Err(OutboundError::Rejected(
+                "sink requires at least one topic mapping or a default_topic".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Topic for `event_type`, or `None` when the event is not routed.
+    pub fn resolve(&self, event_type: &str) -> Option<&str> {
+        if let Some(topic) = self.topics.get(event_type) {
+            return Some(topic);
+        }
+        self.topics
+            .iter()
+            .filter_map(|(pattern, topic)| {
+                let prefix = pattern.strip_suffix(".*")?;
+                let rest = event_type.strip_prefix(prefix)?;
+                rest.starts_with('.').then_some((prefix.len(), topic))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, topic)| topic.as_str())
+            .or_else(|| self.topics.get("*").map(String::as_str))
+            .or(self.default_topic.as_deref())
+    }
+}
+
+fn is_literal(prefix: &str) -> bool {
+    !prefix.is_empty() && !prefix.contains('*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn map(value: serde_json::Value) -> TopicMap {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn resolve_prefers_exact_then_longest_prefix_then_default() {
+        let topics = map(json!({
+            "topics": {
+                "note.updated": "notes-updates",
+                "note.*": "notes",
+                "note.revision.*": "revisions",
+                "*": "everything"
+            },
+            "default_topic": "unused"
+        }));
+        topics.validate().unwrap();
+        assert_eq!(topics.resolve("note.updated"), Some("notes-updates"));
+        assert_eq!(topics.resolve("note.deleted"), Some("notes"));
+        assert_eq!(topics.resolve("note.revision.created"), Some("revisions"));
+        assert_eq!(topics.resolve("notebook.created"), Some("everything"));
+        assert_eq!(topics.resolve("job.started"), Some("everything"));
+
+        let narrow = map(json!({ "topics": { "job.*": "jobs" } }));
+        assert_eq!(narrow.resolve("job.completed"), Some("jobs"));
+        assert_eq!(narrow.resolve("note.updated"), None);
+    }
+
+    #[test]
+    fn validate_rejects_empty_and_malformed_mappings() {
+        assert!(map(json!({})).validate().is_err());
+        assert!(map(json!({ "topics": { "note*": "x" } }))
+            .validate()
+            .is_err());
+        assert!(map(json!({ "topics": { "*.updated": "x" } }))
+            .validate()
+            .is_err());
+        assert!(map(json!({ "topics": { "note.*": " " } }))
+            .validate()
+            .is_err());
+        assert!(map(json!({ "default_topic": "" })).validate().is_err());
+        assert!(map(json!({ "default_topic": "events" })).validate().is_ok());
+    }
+}
@@ -0,0 +1,387 @@
+//! PkeKeyRotationHandler — re-encrypts PKE-sealed artifacts for a rotated keyset.
+//!
+//! `POST /api/v1/pke/keysets/{name_or_id}/rotate` creates a successor keyset,
+//! retires the old one and queues this job. The job walks every archive and
+//! re-seals encrypted notes and encrypted attachment blobs for the successor's
+//! public key, then records the old keyset as fully re-encrypted so it can be
+//! purged once its grace period ends.
+//!
+//! Decrypting requires the old keyset's private key. It is never written to
+//! the job payload: the API unlocks it with the caller's passphrase and hands
+//! it over in memory through [`PkeRotationKeys`]. A job picked up by a server
+//! that does not hold the key (for example after a restart) fails, and the
+//! rotation endpoint can be called again to resume.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use async_trait::async_trait;
+use serde_json::{json, Value as JsonValue};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use matric_core::{ArchiveRepository, JobType};
+use matric_crypto::pke::{decrypt_pke, encrypt_pke, PrivateKey, PublicKey};
+use matric_db::Database;
+
+use crate::handler::{JobContext, JobHandler, JobResult};
+
+/// Artifacts re-encrypted per transaction.
+const ROTATION_BATCH_LIMIT: i64 = 100;
+
+/// Private keys of keysets being rotated, shared between the API and the
+/// job worker of one server process.
+#[derive(Clone, Default)]
+pub struct PkeRotationKeys {
+    keys: Arc<Mutex<HashMap<Uuid, PrivateKey>>>,
+}
+
+impl std::fmt::Debug for PkeRotationKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PkeRotationKeys")
+            .field("held", &self.lock().len())
+            .finish()
+    }
+}
+
+impl PkeRotationKeys {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, PrivateKey>> {
+        self.keys.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Hold the private key of `keyset_id` until its rotation job finishes.
+    pub fn hold(&self, keyset_id: Uuid, private_key: PrivateKey) {
+        self.lock().insert(keyset_id, private_key);
+    }
+
+    fn get(&self, keyset_id: Uuid) -> Option<PrivateKey> {
+        self.lock().get(&keyset_id).cloned()
+    }
+
+    /// Drop the held private key of `keyset_id`.
+    pub fn release(&self, keyset_id: Uuid) {
+        self.lock().remove(&keyset_id);
+    }
+}
+
+/// Counts accumulated across archives.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct RotationReport {
+    notes_reencrypted: u64,
+    blobs_reencrypted: u64,
+    failures: u64,
+}
+
+impl RotationReport {
+    fn add(&mut self, other: RotationReport) {
+        self.notes_reencrypted += other.notes_reencrypted;
+        self.blobs_reencrypted += other.blobs_reencrypted;
+        self.failures += other.failures;
+    }
+}
+
+fn rotation_target(payload: Option<&JsonValue>) -> Option<(Uuid, Uuid)> {
+    let field = |name: &str| {
+        payload
+            .and_then(|p| p.get(name))
+            .and_then(JsonValue::as_str)
+            .and_then(|s| Uuid::parse_str(s).ok())
+    };
+    Some((field("keyset_id")?, field("successor_id")?))
+}
+
+/// Decrypt `ciphertext` with the old key and seal it for the new one.
+fn reseal(ciphertext: &[u8], from: &PrivateKey, to: &PublicKey) -> Option<Vec<u8>> {
+    let (plaintext, _header) = decrypt_pke(ciphertext, from).ok()?;
+    encrypt_pke(&plaintext, std::slice::from_ref(to), None).ok()
+}
+
+fn rotation_result(report: &RotationReport, archives: usize, complete: bool) -> JsonValue {
+    json!({
+        "archives": archives,
+        "notes_reencrypted": report.notes_reencrypted,
+        "blobs_reencrypted": report.blobs_reencrypted,
+        "failures": report.failures,
+        "complete": complete,
+    })
+}
+
+pub struct PkeKeyRotationHandler {
+    db: Database,
+    keys: PkeRotationKeys,
+}
+
+impl PkeKeyRotationHandler {
+    pub fn new(db: Database, keys: PkeRotationKeys) -> Self {
+        Self { db, keys }
+    }
+
+    async fn reencrypt_notes(
+        &self,
+        schema: &str,
+        keyset_id: Uuid,
+        from: &PrivateKey,
+        successor_id: Uuid,
+        to: &PublicKey,
+    ) -> Result<RotationReport, String> {
+        let schema_ctx = self
+            .db
+            .for_schema(schema)
+            .map_err(|_| "Invalid schema".to_string())?;
+        let mut report = RotationReport::default();
+        let mut after = None;
+        loop {
+            let mut tx = schema_ctx
+                .begin_tx()
+                .await
+                .map_err(|_| "Failed to begin transaction".to_string())?;
+            let notes = self
+                .db
+                .notes
+                .lock_encrypted_for_keyset_tx(&mut tx, keyset_id, after, ROTATION_BATCH_LIMIT)
+                .await
+                .map_err(|_| "Failed to load encrypted notes".to_string())?;
+            for (id, ciphertext) in &notes {
+                let Some(resealed) = reseal(ciphertext, from, to) else {
+                    report.failures += 1;
+                    warn!(
+                        note_id_present = true,
+                        "Encrypted note could not be re-encrypted for rotated keyset"
+                    );
+                    continue;
+                };
+                self.db
+                    .notes
+                    .replace_encrypted_content_tx(&mut tx, *id, successor_id, &resealed)
+                    .await
+                    .map_err(|_| "Failed to store re-encrypted note".to_string())?;
+                report.notes_reencrypted += 1;
+            }
+            tx.commit()
+                .await
+                .map_err(|_| "Failed to commit re-encrypted notes".to_string())?;
+
+            if (notes.len() as i64) < ROTATION_BATCH_LIMIT {
+                return Ok(report);
+            }
+            after = notes.last().map(|(id, _)| *id);
+        }
+    }
+
+    async fn reencrypt_blobs(
+        &self,
+        schema: &str,
+        keyset_id: Uuid,
+        from: &PrivateKey,
+        successor_id: Uuid,
+        to: &PublicKey,
+    ) -> Result<RotationReport, String> {
+        let Some(file_storage) = self.db.file_storage.as_ref() else {
+            return Ok(RotationReport::default());
+        };
+        let schema_ctx = self
+            .db
+            .for_schema(schema)
+            .map_err(|_| "Invalid schema".to_string())?;
+        let mut report = RotationReport::default();
+        let mut after = None;
+        loop {
+            let mut tx = schema_ctx
+                .begin_tx()
+                .await
+                .map_err(|_| "Failed to begin transaction".to_string())?;
+            let batch = file_storage
+                .reencrypt_blobs_tx(
+                    &mut tx,
+                    keyset_id,
+                    from,
+                    successor_id,
+                    to,
+                    after,
+                    ROTATION_BATCH_LIMIT,
+                )
+                .await
+                .map_err(|_| "Failed to re-encrypt attachment blobs".to_string())?;
+            if tx.commit().await.is_err() {
+                file_storage.delete_blob_files(&batch.written).await;
+                return Err("Failed to commit re-encrypted attachment blobs".to_string());
+            }
+            file_storage.delete_blob_files(&batch.replaced).await;
+
+            report.blobs_reencrypted += batch.reencrypted;
+            report.failures += batch.failures;
+            if ((batch.reencrypted + batch.failures) as i64) < ROTATION_BATCH_LIMIT {
+                return Ok(report);
+            }
+            after = batch.last_id;
+        }
+    }
+}
+
+#[async_trait]
+impl JobHandler for PkeKeyRotationHandler {
+    fn job_type(&self) -> JobType {
+        JobType::PkeKeyRotation
+    }
+
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let Some((keyset_id, successor_id)) = rotation_target(ctx.payload()) else {
+            return JobResult::Failed("Missing keyset_id or successor_id in payload".into());
+        };
+        let Some(from) = self.keys.get(keyset_id) else {
+            return JobResult::Failed(
+                "Rotation key material is not held by this server; rotate the keyset again to resume"
+                    .into(),
+            );
+        };
+        let successor = match self.db.pke_keysets.get_by_id(successor_id).await {
+            Ok(Some(keyset)) => keyset,
+            Ok(None) => {
+                self.keys.release(keyset_id);
+                return JobResult::Failed("Successor keyset no longer exists".into());
+            }
+            Err(_) => return JobResult::Retry("Failed to load successor keyset".into()),
+        };
+        let Ok(public_key) = <[u8; 32]>::try_from(successor.public_key.as_slice()) else {
+            self.keys.release(keyset_id);
+            return JobResult::Failed("Successor keyset public key is invalid".into());
+        };
+        let to = PublicKey::from_bytes(public_key);
+
+        let schemas: Vec<String> = match self.db.archives.list_archive_schemas().await {
+            Ok(archives) => archives.into_iter().map(|a| a.schema_name).collect(),
+            Err(_) => return JobResult::Retry("Failed to list archives".into()),
+        };
+
+        // Artifacts already moved to the successor no longer match the old
+        // keyset, so a retried job resumes where the previous attempt stopped.
+        let mut report = RotationReport::default();
+        for (i, schema) in schemas.iter().enumerate() {
+            ctx.report_progress(
+                (i * 100 / schemas.len().max(1)) as i32,
+                Some("Re-encrypting notes and attachments"),
+            );
+            let notes = self
+                .reencrypt_notes(schema, keyset_id, &from, successor_id, &to)
+                .await;
+            let blobs = match notes {
+                Ok(notes) => {
+                    report.add(notes);
+                    self.reencrypt_blobs(schema, keyset_id, &from, successor_id, &to)
+                        .await
+                }
+                Err(reason) => Err(reason),
+            };
+            match blobs {
+                Ok(blobs) => report.add(blobs),
+                Err(reason) => {
+                    warn!(
+                        schema_len = schema.len(),
+                        reason = %reason,
+                        "Keyset rotation interrupted"
+                    );
+                    return JobResult::Retry(reason);
+                }
+            }
+        }
+
+        // Artifacts that could not be decrypted stay sealed for the old
+        // keyset, which is then kept past its grace period.
+        let complete = report.failures == 0;
+        if complete {
+            if let Err(e) = self.db.pke_keysets.mark_reencrypted(keyset_id).await {
+                warn!(
+                    error_len = e.to_string().len(),
+                    "Failed to record keyset re-encryption"
+                );
+                return JobResult::Retry("Failed to record keyset re-encryption".into());
+            }
+        }
+        self.keys.release(keyset_id);
+
+        info!(
+            archives = schemas.len(),
+            notes_reencrypted = report.notes_reencrypted,
+            blobs_reencrypted = report.blobs_reencrypted,
+            failures = report.failures,
+            "Keyset rotation re-encryption complete"
+        );
+        ctx.report_progress(100, Some("Keyset rotation re-encryption complete"));
+        JobResult::Success(Some(rotation_result(&report, schemas.len(), complete)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matric_crypto::pke::Keypair;
+
+    #[test]
+    fn rotation_target_requires_both_ids() {
+        let old = Uuid::new_v4();
+        let new = Uuid::new_v4();
+        assert_eq!(rotation_target(None), None);
+        assert_eq!(
+            rotation_target(Some(&json!({ "keyset_id": old.to_string() }))),
+            None
+        );
+        assert_eq!(
+            rotation_target(Some(&json!({ "keyset_id": "nope", "successor_id": new }))),
+            None
+        );
+        assert_eq!(
+            rotation_target(Some(&json!({
+                "keyset_id": old.to_string(),
+                "successor_id": new.to_string(),
+            }))),
+            Some((old, new))
+        );
+    }
+
+    #[test]
+    fn reseal_moves_ciphertext_to_new_key() {
+        let old = Keypair::generate();
+        let new = Keypair::generate();
+        let sealed = encrypt_pke(b"secret note", std::slice::from_ref(&old.public), None).unwrap();
+
+        let resealed = reseal(&sealed, &old.private, &new.public).unwrap();
+        let (plaintext, _) = decrypt_pke(&resealed, &new.private).unwrap();
+        assert_eq!(plaintext, b"secret note");
+        assert!(decrypt_pke(&resealed, &old.private).is_err());
+
+        // Ciphertext for some other key is reported, not re-sealed.
+        assert!(reseal(&sealed, &new.private, &new.public).is_none());
+    }
+
+    #[test]
+    fn rotation_keys_are_held_until_released() {
+        let keys = PkeRotationKeys::new();
+        let keyset_id = Uuid::new_v4();
+        keys.hold(keyset_id, Keypair::generate().private);
+
+        let shared = keys.clone();
+        assert!(shared.get(keyset_id).is_some());
+        assert!(format!("{keys:?}").contains("held: 1"));
+        shared.release(keyset_id);
+        assert!(keys.get(keyset_id).is_none());
+    }
+
+    #[test]
+    fn result_reports_totals() {
+        let report = RotationReport {
+            notes_reencrypted: 4,
+            blobs_reencrypted: 2,
+            failures: 1,
+        };
+        let result = rotation_result(&report, 3, false);
+        assert_eq!(result["archives"], 3);
+        assert_eq!(result["notes_reencrypted"], 4);
+        assert_eq!(result["blobs_reencrypted"], 2);
+        assert_eq!(result["failures"], 1);
+        assert_eq!(result["complete"], false);
+    }
+}
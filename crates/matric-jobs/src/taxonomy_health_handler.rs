@@ -0,0 +1,128 @@
+//! TaxonomyHealthHandler — checks an archive's taxonomy for anti-patterns.
+//!
+//! Analyses the concepts of the archive named by the payload's `schema`,
+//! refreshes their anti-pattern flags and stores the report served by
+//! `GET /api/v1/concepts/health`.
+
+use async_trait::async_trait;
+use serde_json::{json, Value as JsonValue};
+use tracing::info;
+
+use matric_core::defaults::taxonomy_duplicate_similarity;
+use matric_core::taxonomy_health::{analyze_taxonomy, TAXONOMY_MAX_DEPTH};
+use matric_core::{JobType, TaxonomyHealthReport};
+use matric_db::Database;
+
+use crate::handler::{JobContext, JobHandler, JobResult};
+
+fn payload_schema(payload: Option<&JsonValue>) -> String {
+    payload
+        .and_then(|p| p.get("schema"))
+        .and_then(JsonValue::as_str)
+        .filter(|s| !s.is_empty())
+        .unwrap_or("public")
+        .to_string()
+}
+
+fn taxonomy_health_result(report: &TaxonomyHealthReport, concepts_flagged: u64) -> JsonValue {
+    json!({
+        "concepts_checked": report.concepts_checked,
+        "concepts_flagged": concepts_flagged,
+        "issues": report.counts.total(),
+        "counts": report.counts,
+    })
+}
+
+pub struct TaxonomyHealthHandler {
+    db: Database,
+}
+
+impl TaxonomyHealthHandler {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl JobHandler for TaxonomyHealthHandler {
+    fn job_type(&self) -> JobType {
+        JobType::TaxonomyHealth
+    }
+
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let schema = payload_schema(ctx.payload());
+        let schema_ctx = match self.db.for_schema(&schema) {
+            Ok(schema_ctx) => schema_ctx,
+            Err(_) => return JobResult::Failed("Invalid schema".into()),
+        };
+
+        ctx.report_progress(10, Some("Loading concepts"));
+        let mut tx = match schema_ctx.begin_tx().await {
+            Ok(tx) => tx,
+            Err(_) => return JobResult::Retry("Failed to begin transaction".into()),
+        };
+        let concepts = match self.db.skos.taxonomy_concept_stats_tx(&mut tx).await {
+            Ok(concepts) => concepts,
+            Err(_) => return JobResult::Retry("Failed to load concepts".into()),
+        };
+        let edges = match self.db.skos.broader_edges_tx(&mut tx).await {
+            Ok(edges) => edges,
+            Err(_) => return JobResult::Retry("Failed to load broader links".into()),
+        };
+
+        ctx.report_progress(40, Some("Analysing taxonomy"));
+        let report = analyze_taxonomy(
+            &concepts,
+            &edges,
+            TAXONOMY_MAX_DEPTH,
+            taxonomy_duplicate_similarity(),
+        );
+
+        ctx.report_progress(70, Some("Saving health report"));
+        let flagged = match self
+            .db
+            .skos
+            .refresh_antipatterns_tx(&mut tx, &report.antipattern_flags())
+            .await
+        {
+            Ok(flagged) => flagged,
+            Err(_) => return JobResult::Retry("Failed to refresh anti-patterns".into()),
+        };
+        if self
+            .db
+            .skos
+            .save_taxonomy_health_report_tx(&mut tx, &report)
+            .await
+            .is_err()
+        {
+            return JobResult::Retry("Failed to save health report".into());
+        }
+        if tx.commit().await.is_err() {
+            return JobResult::Retry("Failed to commit health report".into());
+        }
+
+        info!(
+            concepts = report.concepts_checked,
+            issues = report.counts.total(),
+            flagged,
+            "Taxonomy health check complete"
+        );
+        ctx.report_progress(100, Some("Taxonomy health check complete"));
+        JobResult::Success(Some(taxonomy_health_result(&report, flagged)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_defaults_to_public() {
+        assert_eq!(payload_schema(None), "public");
+        assert_eq!(payload_schema(Some(&json!({ "schema": "" }))), "public");
+        assert_eq!(
+            payload_schema(Some(&json!({ "schema": "archive_a" }))),
+            "archive_a"
+        );
+    }
+}
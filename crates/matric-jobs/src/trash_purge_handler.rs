@@ -0,0 +1,256 @@
+//! TrashPurgeHandler — hard-deletes trashed notes.
+//!
+//! A payload with `note_ids` or `all: true` purges those notes from the trash
+//! of the archive named by `schema`, whatever their age; with `user_id` only
+//! the notes that user may write are purged. Otherwise the job is
+//! the retention sweep: every archive, or the one named by `schema`, loses
+//! the notes that have been in its trash for longer than the retention.
+
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value as JsonValue};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use matric_core::{ArchiveRepository, JobType};
+use matric_db::Database;
+
+use crate::handler::{JobContext, JobHandler, JobResult};
+
+/// Notes deleted per transaction, so a large trash is purged in batches.
+const PURGE_BATCH_SIZE: i64 = 500;
+
+/// What one job run purges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PurgeScope {
+    /// Notes the user picked from one archive's trash.
+    Selected {
+        schema: String,
+        ids: Option<Vec<Uuid>>,
+        user_id: Option<Uuid>,
+    },
+    /// Notes past the retention, in one archive or all of them.
+    Expired { schema: Option<String> },
+}
+
+fn purge_scope(payload: Option<&JsonValue>) -> PurgeScope {
+    let schema = payload
+        .and_then(|p| p.get("schema"))
+        .and_then(JsonValue::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+    let all = payload
+        .and_then(|p| p.get("all"))
+        .and_then(JsonValue::as_bool)
+        .unwrap_or(false);
+    let ids: Option<Vec<Uuid>> = payload
+        .and_then(|p| p.get("note_ids"))
+        .and_then(|ids| serde_json::from_value(ids.clone()).ok());
+    let user_id = payload
+        .and_then(|p| p.get("user_id"))
+        .and_then(JsonValue::as_str)
+        .and_then(|id| Uuid::parse_str(id).ok());
+
+    match (all, ids) {
+        (true, _) => PurgeScope::Selected {
+            schema: schema.unwrap_or_else(|| "public".to_string()),
+            ids: None,
+            user_id,
+        },
+        (false, Some(ids)) => PurgeScope::Selected {
+            schema: schema.unwrap_or_else(|| "public".to_string()),
+            ids: Some(ids),
+            user_id,
+        },
+        (false, None) => PurgeScope::Expired { schema },
+    }
+}
+
+fn trash_purge_result(
+    archives: usize,
+    skipped: usize,
+    notes_purged: usize,
+    retention_days: Option<i64>,
+) -> JsonValue {
+    json!({
+        "archives_purged": archives - skipped,
+        "archives_skipped": skipped,
+        "notes_purged": notes_purged,
+        "retention_days": retention_days,
+    })
+}
+
+pub struct TrashPurgeHandler {
+    db: Database,
+    retention_days: i64,
+}
+
+impl TrashPurgeHandler {
+    /// `retention_days` of 0 disables the retention sweep; selected notes
+    /// are still purged.
+    pub fn new(db: Database, retention_days: i64) -> Self {
+        Self { db, retention_days }
+    }
+
+    /// Purge matching notes from one archive, batch by batch.
+    async fn purge_schema(
+        &self,
+        schema: &str,
+        ids: Option<&[Uuid]>,
+        deleted_before: Option<DateTime<Utc>>,
+        writer: Option<Uuid>,
+    ) -> Result<usize, String> {
+        let schema_ctx = self
+            .db
+            .for_schema(schema)
+            .map_err(|_| "Invalid schema".to_string())?;
+
+        let mut purged = 0;
+        let mut stale_sets = BTreeSet::new();
+        loop {
+            let mut tx = schema_ctx
+                .begin_tx()
+                .await
+                .map_err(|_| "Failed to begin transaction".to_string())?;
+            let batch = self
+                .db
+                .trash
+                .purge_tx(&mut tx, ids, deleted_before, writer, PURGE_BATCH_SIZE)
+                .await
+                .map_err(|_| "Failed to purge trashed notes".to_string())?;
+            tx.commit()
+                .await
+                .map_err(|_| "Failed to commit trash purge".to_string())?;
+
+            purged += batch.note_ids.len();
+            stale_sets.extend(batch.embedding_set_ids);
+            if (batch.note_ids.len() as i64) < PURGE_BATCH_SIZE {
+                break;
+            }
+        }
+
+        for set_id in stale_sets {
+            if self.db.embedding_sets.refresh_stats(set_id).await.is_err() {
+                warn!("Failed to refresh embedding set stats after trash purge");
+            }
+        }
+        Ok(purged)
+    }
+}
+
+#[async_trait]
+impl JobHandler for TrashPurgeHandler {
+    fn job_type(&self) -> JobType {
+        JobType::TrashPurge
+    }
+
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let (schemas, ids, writer, deleted_before, retention_days) =
+            match purge_scope(ctx.payload()) {
+                PurgeScope::Selected {
+                    schema,
+                    ids,
+                    user_id,
+                } => (vec![schema], ids, user_id, None, None),
+                PurgeScope::Expired { .. } if self.retention_days == 0 => {
+                    return JobResult::Success(Some(trash_purge_result(0, 0, 0, Some(0))));
+                }
+                PurgeScope::Expired { schema } => {
+                    let schemas = match schema {
+                        Some(schema) => vec![schema],
+                        None => match self.db.archives.list_archive_schemas().await {
+                            Ok(archives) => archives.into_iter().map(|a| a.schema_name).collect(),
+                            Err(_) => return JobResult::Retry("Failed to list archives".into()),
+                        },
+                    };
+                    let cutoff = Utc::now() - Duration::days(self.retention_days);
+                    (schemas, None, None, Some(cutoff), Some(self.retention_days))
+                }
+            };
+
+        let mut notes_purged = 0;
+        let mut skipped = 0;
+        for (i, schema) in schemas.iter().enumerate() {
+            ctx.report_progress(
+                (i * 100 / schemas.len().max(1)) as i32,
+                Some("Purging trashed notes"),
+            );
+            match self
+                .purge_schema(schema, ids.as_deref(), deleted_before, writer)
+                .await
+            {
+                Ok(purged) => notes_purged += purged,
+                Err(reason) => {
+                    skipped += 1;
+                    warn!(
+                        schema_len = schema.len(),
+                        reason = %reason,
+                        "Trash purge skipped archive"
+                    );
+                }
+            }
+        }
+
+        info!(
+            archives = schemas.len(),
+            skipped, notes_purged, "Trash purge complete"
+        );
+        ctx.report_progress(100, Some("Trash purge complete"));
+        JobResult::Success(Some(trash_purge_result(
+            schemas.len(),
+            skipped,
+            notes_purged,
+            retention_days,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn purge_scope_defaults_to_the_retention_sweep() {
+        assert_eq!(purge_scope(None), PurgeScope::Expired { schema: None });
+        assert_eq!(
+            purge_scope(Some(&json!({ "schema": "archive_a" }))),
+            PurgeScope::Expired {
+                schema: Some("archive_a".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn purge_scope_reads_selected_notes() {
+        let id = Uuid::nil();
+        assert_eq!(
+            purge_scope(Some(&json!({ "note_ids": [id] }))),
+            PurgeScope::Selected {
+                schema: "public".to_string(),
+                ids: Some(vec![id]),
+                user_id: None,
+            }
+        );
+        assert_eq!(
+            purge_scope(Some(
+                &json!({ "schema": "archive_a", "all": true, "user_id": id })
+            )),
+            PurgeScope::Selected {
+                schema: "archive_a".to_string(),
+                ids: None,
+                user_id: Some(id),
+            }
+        );
+    }
+
+    #[test]
+    fn result_reports_totals() {
+        let result = trash_purge_result(3, 1, 42, Some(30));
+        assert_eq!(result["archives_purged"], 2);
+        assert_eq!(result["archives_skipped"], 1);
+        assert_eq!(result["notes_purged"], 42);
+        assert_eq!(result["retention_days"], 30);
+    }
+}
@@ -0,0 +1,141 @@
+//! VersionPruneHandler — applies version retention policies.
+//!
+//! The snapshot trigger enforces each archive's version cap as notes are
+//! edited, but a version's age only matters later and a tightened policy only
+//! affects notes edited afterwards. This job applies the whole effective
+//! policy (the archive's override, else the global default) to every archive,
+//! or to the one named by the payload's `schema`.
+
+use async_trait::async_trait;
+use serde_json::{json, Value as JsonValue};
+use tracing::{info, warn};
+
+use matric_core::{ArchiveRepository, JobType};
+use matric_db::Database;
+
+use crate::handler::{JobContext, JobHandler, JobResult};
+
+fn target_schema(payload: Option<&JsonValue>) -> Option<String> {
+    payload
+        .and_then(|p| p.get("schema"))
+        .and_then(JsonValue::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn version_prune_result(archives: usize, skipped: usize, versions_pruned: u64) -> JsonValue {
+    json!({
+        "archives_pruned": archives - skipped,
+        "archives_skipped": skipped,
+        "versions_pruned": versions_pruned,
+    })
+}
+
+pub struct VersionPruneHandler {
+    db: Database,
+}
+
+impl VersionPruneHandler {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn prune_schema(&self, schema: &str) -> Result<u64, String> {
+        let policy = self
+            .db
+            .versioning
+            .effective_retention_policy(schema)
+            .await
+            .map_err(|_| "Failed to load retention policy".to_string())?;
+        let schema_ctx = self
+            .db
+            .for_schema(schema)
+            .map_err(|_| "Invalid schema".to_string())?;
+
+        let mut tx = schema_ctx
+            .begin_tx()
+            .await
+            .map_err(|_| "Failed to begin transaction".to_string())?;
+        let pruned = self
+            .db
+            .versioning
+            .prune_versions_tx(&mut tx, &policy)
+            .await
+            .map_err(|_| "Failed to prune versions".to_string())?;
+        tx.commit()
+            .await
+            .map_err(|_| "Failed to commit version pruning".to_string())?;
+        Ok(pruned)
+    }
+}
+
+#[async_trait]
+impl JobHandler for VersionPruneHandler {
+    fn job_type(&self) -> JobType {
+        JobType::VersionPrune
+    }
+
+    async fn execute(&self, ctx: JobContext) -> JobResult {
+        let schemas = match target_schema(ctx.payload()) {
+            Some(schema) => vec![schema],
+            None => match self.db.archives.list_archive_schemas().await {
+                Ok(archives) => archives.into_iter().map(|a| a.schema_name).collect(),
+                Err(_) => return JobResult::Retry("Failed to list archives".into()),
+            },
+        };
+
+        let mut versions_pruned = 0;
+        let mut skipped = 0;
+        for (i, schema) in schemas.iter().enumerate() {
+            ctx.report_progress(
+                (i * 100 / schemas.len().max(1)) as i32,
+                Some("Pruning version history"),
+            );
+            match self.prune_schema(schema).await {
+                Ok(pruned) => versions_pruned += pruned,
+                Err(reason) => {
+                    skipped += 1;
+                    warn!(
+                        schema_len = schema.len(),
+                        reason = %reason,
+                        "Version pruning skipped archive"
+                    );
+                }
+            }
+        }
+
+        info!(
+            archives = schemas.len(),
+            skipped, versions_pruned, "Version pruning complete"
+        );
+        ctx.report_progress(100, Some("Version pruning complete"));
+        JobResult::Success(Some(version_prune_result(
+            schemas.len(),
+            skipped,
+            versions_pruned,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_schema_ignores_empty_values() {
+        assert_eq!(target_schema(None), None);
+        assert_eq!(target_schema(Some(&json!({ "schema": "" }))), None);
+        assert_eq!(
+            target_schema(Some(&json!({ "schema": "archive_a" }))).as_deref(),
+            Some("archive_a")
+        );
+    }
+
+    #[test]
+    fn result_reports_totals() {
+        let result = version_prune_result(3, 1, 42);
+        assert_eq!(result["archives_pruned"], 2);
+        assert_eq!(result["archives_skipped"], 1);
+        assert_eq!(result["versions_pruned"], 42);
+    }
+}
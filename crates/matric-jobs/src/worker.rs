@@ -2,7 +2,7 @@
 
 use std::collections::HashMap;
 use std::fmt;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 use std::time::{Duration, Instant};
 
 use tokio::sync::{broadcast, mpsc, RwLock};
@@ -10,12 +10,16 @@ use tokio::time::sleep;
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
 
+use matric_core::dead_letter::POISON_PAYLOAD_CODE;
+use matric_core::job_lane::{JobLane, LaneWeights};
+use matric_core::job_worker::WorkerRegistration;
+use matric_core::metrics::{self, JOB_DURATION_SECONDS};
 use matric_core::{
-    cost_tier, Error, JobFailureClass, JobRepository, JobRetryOutcome, JobRetryPolicy, JobType,
-    Result, TierGroup,
+    cost_tier, Error, InferenceUsageRecord, JobFailureClass, JobRepository, JobRetryOutcome,
+    JobRetryPolicy, JobType, Result, TierGroup,
 };
-use matric_db::Database;
-use matric_inference::{OllamaBackend, VisionBackend};
+use matric_db::{Database, PgJobRepository};
+use matric_inference::{ModelUsage, OllamaBackend, PricingTable, TokenAccount, VisionBackend};
 
 use crate::extraction::ExtractionRegistry;
 use crate::handler::{JobContext, JobHandler, JobResult};
@@ -33,10 +37,26 @@ pub struct WorkerConfig {
     pub poll_interval_ms: u64,
     /// Maximum number of concurrent jobs.
     pub max_concurrent_jobs: usize,
+    /// Concurrent jobs kept while the queue is shallow. The worker adds a task
+    /// for every `scale_queue_depth` pending jobs, up to `max_concurrent_jobs`.
+    /// Equal to `max_concurrent_jobs` unless autoscaling is configured.
+    pub min_concurrent_jobs: usize,
+    /// Pending jobs per worker task when autoscaling.
+    pub scale_queue_depth: u64,
+    /// Per-job-type caps on concurrent jobs. Types without a cap may use every
+    /// free slot.
+    pub job_type_limits: HashMap<JobType, usize>,
+    /// Share of claims each queue lane gets while both have work.
+    pub lane_weights: LaneWeights,
     /// Whether to enable job processing.
     pub enabled: bool,
     /// Bounded retry timing shared with stale-job recovery.
     pub retry_policy: JobRetryPolicy,
+    /// Seconds between worker registry heartbeats.
+    pub heartbeat_interval_secs: u64,
+    /// Seconds without a heartbeat after which this worker's running jobs are
+    /// reclaimed by the leader. Also the leader lease duration.
+    pub heartbeat_timeout_secs: u64,
 }
 
 impl Default for WorkerConfig {
@@ -44,8 +64,14 @@ impl Default for WorkerConfig {
         Self {
             poll_interval_ms: DEFAULT_POLL_INTERVAL_MS,
             max_concurrent_jobs: matric_core::defaults::JOB_MAX_CONCURRENT,
+            min_concurrent_jobs: matric_core::defaults::JOB_MAX_CONCURRENT,
+            scale_queue_depth: matric_core::defaults::JOB_SCALE_QUEUE_DEPTH,
+            job_type_limits: HashMap::new(),
+            lane_weights: LaneWeights::default(),
             enabled: true,
             retry_policy: JobRetryPolicy::default(),
+            heartbeat_interval_secs: matric_core::defaults::JOB_WORKER_HEARTBEAT_INTERVAL_SECS,
+            heartbeat_timeout_secs: matric_core::defaults::JOB_WORKER_HEARTBEAT_TIMEOUT_SECS,
         }
     }
 }
@@ -80,6 +106,30 @@ fn worker_failure_telemetry(error: &str) -> (usize, &'static str) {
     (error.len(), worker_error_reason_code(error))
 }
 
+/// Model prices from `MATRIC_MODEL_PRICING`, read once per process.
+static MODEL_PRICING: LazyLock<PricingTable> = LazyLock::new(PricingTable::from_env);
+
+/// Archive schema a job runs against; `public` when the payload names none.
+fn job_schema(job: &matric_core::Job) -> String {
+    job.payload
+        .as_ref()
+        .and_then(|p| p.get("schema"))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("public")
+        .to_string()
+}
+
+fn inference_usage_record(usage: ModelUsage, pricing: &PricingTable) -> InferenceUsageRecord {
+    InferenceUsageRecord {
+        cost_usd: pricing.cost_usd(&usage.model, usage.usage),
+        calls: i64::try_from(usage.calls).unwrap_or(i64::MAX),
+        prompt_tokens: i64::try_from(usage.usage.prompt_tokens).unwrap_or(i64::MAX),
+        completion_tokens: i64::try_from(usage.usage.completion_tokens).unwrap_or(i64::MAX),
+        model: usage.model,
+    }
+}
+
 fn retry_failure_class(error: &str) -> JobFailureClass {
     let error = error.to_ascii_lowercase();
     if error.contains("rate limit") || error.contains("too many requests") || error.contains("429")
@@ -119,6 +169,93 @@ fn worker_job_type_len(job_type: &JobType) -> usize {
     format!("{job_type:?}").len()
 }
 
+/// Worker tasks to run for `pending` queued jobs: one per `depth` jobs,
+/// rounded up, kept between `min` and `max`.
+fn scaled_concurrency(pending: i64, min: usize, max: usize, depth: u64) -> usize {
+    let pending = u64::try_from(pending).unwrap_or(0);
+    let wanted = usize::try_from(pending.div_ceil(depth.max(1))).unwrap_or(usize::MAX);
+    wanted.clamp(min.min(max), max)
+}
+
+/// Handler job types that are below their concurrency cap.
+fn claimable_job_types(
+    job_types: &[JobType],
+    in_flight: &HashMap<JobType, usize>,
+    limits: &HashMap<JobType, usize>,
+) -> Vec<JobType> {
+    job_types
+        .iter()
+        .copied()
+        .filter(|job_type| match limits.get(job_type) {
+            Some(limit) => in_flight.get(job_type).copied().unwrap_or(0) < *limit,
+            None => true,
+        })
+        .collect()
+}
+
+/// Staleness threshold for running jobs claimed without a worker id: 2x the
+/// job timeout, so jobs legitimately still running are never reaped.
+const LEGACY_STALE_THRESHOLD_SECS: u64 = matric_core::defaults::JOB_TIMEOUT_SECS * 2;
+
+fn log_heartbeat_error(error: &Error, message: &'static str) {
+    let error_text = error.to_string();
+    warn!(
+        error_len = error_text.len(),
+        error_reason = worker_error_reason_code(&error_text),
+        "{message}"
+    );
+}
+
+/// One heartbeat: refresh the registry row (re-registering if it was
+/// pruned), then, as leader, reclaim orphaned jobs and prune old workers.
+async fn heartbeat_once(
+    jobs: &PgJobRepository,
+    registration: &WorkerRegistration,
+    retry_policy: &JobRetryPolicy,
+) {
+    match jobs.heartbeat_worker(registration.id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            if let Err(e) = jobs.register_worker(registration).await {
+                log_heartbeat_error(&e, "Failed to re-register job worker");
+            }
+        }
+        Err(e) => {
+            log_heartbeat_error(&e, "Job worker heartbeat failed");
+            return;
+        }
+    }
+
+    match jobs
+        .acquire_worker_leadership(registration.id, registration.heartbeat_timeout_secs)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            log_heartbeat_error(&e, "Job worker leader election failed");
+            return;
+        }
+    }
+
+    match jobs
+        .reclaim_orphaned_jobs(LEGACY_STALE_THRESHOLD_SECS, retry_policy)
+        .await
+    {
+        Ok(0) => {}
+        Ok(count) => warn!(count, "Reclaimed jobs orphaned by a silent worker"),
+        Err(e) => log_heartbeat_error(&e, "Failed to reclaim orphaned jobs"),
+    }
+    match jobs
+        .prune_workers(matric_core::defaults::JOB_WORKER_REGISTRY_RETENTION_HOURS)
+        .await
+    {
+        Ok(0) => {}
+        Ok(count) => debug!(count, "Pruned job worker registry"),
+        Err(e) => log_heartbeat_error(&e, "Failed to prune job worker registry"),
+    }
+}
+
 fn worker_tier_class(tier_group: TierGroup) -> &'static str {
     match tier_group {
         TierGroup::CpuAndAgnostic => "cpu_agnostic",
@@ -137,7 +274,14 @@ impl WorkerConfig {
     /// |----------|---------|-------------|
     /// | `JOB_WORKER_ENABLED` | `true` | Enable/disable job processing |
     /// | `JOB_MAX_CONCURRENT` | `1` | Max concurrent jobs |
+    /// | `JOB_MIN_CONCURRENT` | `JOB_MAX_CONCURRENT` | Concurrent jobs on a shallow queue |
+    /// | `JOB_SCALE_QUEUE_DEPTH` | `10` | Pending jobs per added worker task |
+    /// | `JOB_TYPE_CONCURRENCY` | unset | Per-type caps, e.g. `ai_revision=1,embedding=4` |
+    /// | `JOB_INTERACTIVE_LANE_WEIGHT` | `4` | Interactive-lane claims per cycle |
+    /// | `JOB_BATCH_LANE_WEIGHT` | `1` | Batch-lane claims per cycle (0 = only when idle) |
     /// | `JOB_POLL_INTERVAL_MS` | `60000` | Safety-net poll interval (ms) |
+    /// | `JOB_WORKER_HEARTBEAT_INTERVAL_SECS` | `10` | Worker registry heartbeat interval |
+    /// | `JOB_WORKER_HEARTBEAT_TIMEOUT_SECS` | `60` | Missed-heartbeat time before jobs are reclaimed |
     /// | `JOB_RETRY_BASE_DELAY_MS` | `5000` | Transient retry base delay |
     /// | `JOB_RETRY_RATE_LIMIT_BASE_DELAY_MS` | `30000` | Rate-limit retry base delay |
     /// | `JOB_RETRY_TIMEOUT_BASE_DELAY_MS` | `15000` | Timeout retry base delay |
@@ -154,12 +298,62 @@ impl WorkerConfig {
             64,
         )?)
         .map_err(|_| Error::Config("JOB_MAX_CONCURRENT exceeds platform bounds".to_string()))?;
+        let min_concurrent_jobs = usize::try_from(parse_u64_env(
+            "JOB_MIN_CONCURRENT",
+            max_concurrent_jobs as u64,
+            1,
+            max_concurrent_jobs as u64,
+        )?)
+        .map_err(|_| Error::Config("JOB_MIN_CONCURRENT exceeds platform bounds".to_string()))?;
+        let scale_queue_depth = parse_u64_env(
+            "JOB_SCALE_QUEUE_DEPTH",
+            defaults.scale_queue_depth,
+            1,
+            100_000,
+        )?;
+        let job_type_limits = read_env("JOB_TYPE_CONCURRENCY")?
+            .as_deref()
+            .map(|value| parse_job_type_limits(value, max_concurrent_jobs))
+            .transpose()?
+            .unwrap_or_default();
+        let lane_weights = LaneWeights {
+            interactive: parse_u64_env(
+                "JOB_INTERACTIVE_LANE_WEIGHT",
+                u64::from(defaults.lane_weights.interactive),
+                1,
+                100,
+            )? as u32,
+            batch: parse_u64_env(
+                "JOB_BATCH_LANE_WEIGHT",
+                u64::from(defaults.lane_weights.batch),
+                0,
+                100,
+            )? as u32,
+        };
         let poll_interval_ms = parse_u64_env(
             "JOB_POLL_INTERVAL_MS",
             defaults.poll_interval_ms,
             100,
             300_000,
         )?;
+        let heartbeat_interval_secs = parse_u64_env(
+            "JOB_WORKER_HEARTBEAT_INTERVAL_SECS",
+            defaults.heartbeat_interval_secs,
+            1,
+            300,
+        )?;
+        let heartbeat_timeout_secs = parse_u64_env(
+            "JOB_WORKER_HEARTBEAT_TIMEOUT_SECS",
+            defaults.heartbeat_timeout_secs,
+            5,
+            3_600,
+        )?;
+        if heartbeat_timeout_secs < heartbeat_interval_secs * 2 {
+            return Err(Error::Config(
+                "JOB_WORKER_HEARTBEAT_TIMEOUT_SECS must be at least twice the heartbeat interval"
+                    .to_string(),
+            ));
+        }
 
         let mut retry_policy = defaults.retry_policy;
         retry_policy.transient_base_delay_ms = parse_u64_env(
@@ -217,8 +411,14 @@ impl WorkerConfig {
         Ok(Self {
             poll_interval_ms,
             max_concurrent_jobs,
+            min_concurrent_jobs,
+            scale_queue_depth,
+            job_type_limits,
+            lane_weights,
             enabled,
             retry_policy,
+            heartbeat_interval_secs,
+            heartbeat_timeout_secs,
         })
     }
 
@@ -228,12 +428,46 @@ impl WorkerConfig {
         self
     }
 
-    /// Set maximum concurrent jobs.
+    /// Set maximum concurrent jobs. A minimum set by [`Self::with_autoscale`]
+    /// is kept, capped at `max`; otherwise the minimum follows the maximum.
     pub fn with_max_concurrent(mut self, max: usize) -> Self {
+        self.min_concurrent_jobs = if self.min_concurrent_jobs == self.max_concurrent_jobs {
+            max
+        } else {
+            self.min_concurrent_jobs.min(max)
+        };
         self.max_concurrent_jobs = max;
         self
     }
 
+    /// Scale between `min` and the maximum concurrent jobs, adding a worker
+    /// task for every `queue_depth` pending jobs.
+    pub fn with_autoscale(mut self, min: usize, queue_depth: u64) -> Self {
+        self.min_concurrent_jobs = min;
+        self.scale_queue_depth = queue_depth;
+        self
+    }
+
+    /// Cap concurrent jobs of one type.
+    pub fn with_job_type_limit(mut self, job_type: JobType, max: usize) -> Self {
+        self.job_type_limits.insert(job_type, max);
+        self
+    }
+
+    /// Set how many claims per cycle each lane gets while both have work.
+    pub fn with_lane_weights(mut self, interactive: u32, batch: u32) -> Self {
+        self.lane_weights = LaneWeights { interactive, batch };
+        self
+    }
+
+    /// Set the heartbeat interval and the missed-heartbeat timeout after
+    /// which this worker's running jobs are reclaimed.
+    pub fn with_heartbeat(mut self, interval_secs: u64, timeout_secs: u64) -> Self {
+        self.heartbeat_interval_secs = interval_secs;
+        self.heartbeat_timeout_secs = timeout_secs;
+        self
+    }
+
     /// Enable or disable job processing.
     pub fn with_enabled(mut self, enabled: bool) -> Self {
         self.enabled = enabled;
@@ -295,6 +529,31 @@ fn parse_u64_env(name: &str, default: u64, min: u64, max: u64) -> Result<u64> {
         .map(|value| value.unwrap_or(default))
 }
 
+/// Parse `JOB_TYPE_CONCURRENCY`: comma-separated `job_type=limit` pairs, each
+/// limit between 1 and `max`.
+fn parse_job_type_limits(value: &str, max: usize) -> Result<HashMap<JobType, usize>> {
+    const NAME: &str = "JOB_TYPE_CONCURRENCY";
+    let mut limits = HashMap::new();
+    for entry in value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+    {
+        let (job_type, limit) = entry.split_once('=').ok_or_else(|| {
+            Error::Config(format!("{NAME} entries must look like job_type=limit"))
+        })?;
+        let job_type = job_type
+            .trim()
+            .parse::<JobType>()
+            .map_err(|_| Error::Config(format!("{NAME} names an unknown job type")))?;
+        let limit = parse_bounded_u64_value(NAME, limit.trim(), 1, max as u64)?;
+        if limits.insert(job_type, limit as usize).is_some() {
+            return Err(Error::Config(format!("{NAME} repeats a job type")));
+        }
+    }
+    Ok(limits)
+}
+
 /// Event emitted by the job worker.
 #[derive(Clone)]
 pub enum WorkerEvent {
@@ -438,6 +697,8 @@ impl WorkerHandle {
 pub struct JobWorker {
     db: Database,
     config: WorkerConfig,
+    /// Identity in the worker registry, recorded on every claimed job.
+    worker_id: Uuid,
     handlers: Arc<RwLock<HashMap<JobType, Arc<dyn JobHandler>>>>,
     event_tx: broadcast::Sender<WorkerEvent>,
     extraction_registry: Option<Arc<ExtractionRegistry>>,
@@ -474,6 +735,7 @@ impl JobWorker {
         Self {
             db,
             config,
+            worker_id: matric_core::new_v7(),
             handlers: Arc::new(RwLock::new(HashMap::new())),
             event_tx,
             extraction_registry: extraction_registry.map(Arc::new),
@@ -509,6 +771,11 @@ impl JobWorker {
         self
     }
 
+    /// This worker's id in the worker registry.
+    pub fn worker_id(&self) -> Uuid {
+        self.worker_id
+    }
+
     /// Get a reference to the pause state (if configured).
     pub fn pause_state(&self) -> Option<&PauseState> {
         self.pause_state.as_ref()
@@ -565,14 +832,23 @@ impl JobWorker {
             return;
         }
 
-        // Reap orphaned running jobs from a previous process (crash/restart).
-        // Use 2x the job timeout as the staleness threshold to avoid reaping
-        // jobs that are legitimately still running during normal operation.
-        let stale_threshold = matric_core::defaults::JOB_TIMEOUT_SECS * 2;
+        if let Err(e) = self.db.jobs.register_worker(&self.registration()).await {
+            let error_text = e.to_string();
+            error!(
+                error_len = error_text.len(),
+                error_reason = worker_error_reason_code(&error_text),
+                "Failed to register job worker"
+            );
+        }
+
+        // Reap jobs orphaned by a crashed or restarted worker. Jobs of workers
+        // that are still heartbeating, e.g. other replicas, are left alone.
+        // Jobs claimed without a worker id use 2x the job timeout as the
+        // staleness threshold.
         match self
             .db
             .jobs
-            .reap_stale_running(stale_threshold, &self.config.retry_policy)
+            .reclaim_orphaned_jobs(LEGACY_STALE_THRESHOLD_SECS, &self.config.retry_policy)
             .await
         {
             Ok(0) => debug!("No stale running jobs to reap"),
@@ -586,6 +862,7 @@ impl JobWorker {
                 );
             }
         }
+        let heartbeat = self.spawn_heartbeat();
 
         let job_notify = self.db.jobs.job_notify();
         let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
@@ -594,12 +871,20 @@ impl JobWorker {
 
         info!(
             safety_net_interval_ms = self.config.poll_interval_ms,
-            max_concurrent, gpu_concurrent, "Job worker started (event-driven)"
+            max_concurrent,
+            min_concurrent = self.config.min_concurrent_jobs,
+            job_type_limits = self.config.job_type_limits.len(),
+            interactive_lane_weight = self.config.lane_weights.interactive,
+            batch_lane_weight = self.config.lane_weights.batch,
+            heartbeat_interval_secs = self.config.heartbeat_interval_secs,
+            heartbeat_timeout_secs = self.config.heartbeat_timeout_secs,
+            gpu_concurrent,
+            "Job worker started (event-driven)"
         );
 
         let _ = self.event_tx.send(WorkerEvent::WorkerStarted);
 
-        loop {
+        'wake: loop {
             // Wait for a wake signal: job enqueue, safety-net timeout, or shutdown
             tokio::select! {
                 _ = shutdown_rx.recv() => {
@@ -636,9 +921,7 @@ impl JobWorker {
                 // Check for shutdown between drain iterations
                 if shutdown_rx.try_recv().is_ok() {
                     info!("Job worker received shutdown signal during drain");
-                    let _ = self.event_tx.send(WorkerEvent::WorkerStopped);
-                    info!("Job worker stopped");
-                    return;
+                    break 'wake;
                 }
 
                 let mut any_processed = false;
@@ -844,12 +1127,59 @@ impl JobWorker {
             }
         }
 
+        heartbeat.abort();
+        if let Err(e) = self.db.jobs.deregister_worker(self.worker_id).await {
+            let error_text = e.to_string();
+            warn!(
+                error_len = error_text.len(),
+                error_reason = worker_error_reason_code(&error_text),
+                "Failed to deregister job worker"
+            );
+        }
         let _ = self.event_tx.send(WorkerEvent::WorkerStopped);
         info!("Job worker stopped");
     }
 
+    /// Registry entry for this worker.
+    fn registration(&self) -> WorkerRegistration {
+        WorkerRegistration {
+            id: self.worker_id,
+            hostname: std::env::var("HOSTNAME").unwrap_or_else(|_| "unknown".to_string()),
+            pid: std::process::id(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            max_concurrent: self.config.max_concurrent_jobs,
+            heartbeat_timeout_secs: self.config.heartbeat_timeout_secs,
+        }
+    }
+
+    /// Heartbeat in the background until aborted. Each beat also contends for
+    /// the leader lease; the leader reclaims jobs of workers that stopped
+    /// heartbeating and prunes old registry rows.
+    fn spawn_heartbeat(&self) -> tokio::task::JoinHandle<()> {
+        let db = self.db.clone();
+        let registration = self.registration();
+        let retry_policy = self.config.retry_policy;
+        let interval = Duration::from_secs(self.config.heartbeat_interval_secs.max(1));
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                heartbeat_once(&db.jobs, &registration, &retry_policy).await;
+            }
+        })
+    }
+
     /// Drain all jobs for a specific tier group, returning the count processed.
     ///
+    /// Keeps up to `max_concurrent` jobs running and claims another as soon as
+    /// one finishes. With autoscaling the target shrinks towards
+    /// `min_concurrent_jobs` when the queue is shallow. Job types at their
+    /// configured cap are not claimed until one of their jobs finishes, so a
+    /// flood of one type leaves slots for the others. Free slots go to the
+    /// interactive lane first, with the batch lane getting its weighted share.
+    ///
     /// Jobs belonging to `excluded_archives` are skipped at the SQL level (Issue #466).
     async fn drain_tier(
         &self,
@@ -857,70 +1187,116 @@ impl JobWorker {
         max_concurrent: usize,
         excluded_archives: &[String],
     ) -> usize {
+        let handler_types: Vec<JobType> = {
+            let handlers = self.handlers.read().await;
+            handlers.keys().copied().collect()
+        };
+        let limits = &self.config.job_type_limits;
         let mut total_drained = 0;
+        let mut tasks = tokio::task::JoinSet::new();
+        let mut running: HashMap<tokio::task::Id, JobType> = HashMap::new();
+        let mut in_flight: HashMap<JobType, usize> = HashMap::new();
+        let mut target = max_concurrent;
+        let mut claims: u64 = 0;
 
         loop {
-            let mut claimed = 0;
-            let mut tasks = tokio::task::JoinSet::new();
-
-            for _ in 0..max_concurrent {
-                match self.claim_job_for_tier(tier_group, excluded_archives).await {
-                    Some(job) => {
-                        claimed += 1;
-                        let worker = self.clone_refs();
-                        tasks.spawn(async move {
-                            worker.execute_job(job).await;
-                        });
-                    }
-                    None => break,
+            if self.config.min_concurrent_jobs < max_concurrent {
+                let pending = self.db.jobs.pending_count().await.unwrap_or(0);
+                let scaled = scaled_concurrency(
+                    pending,
+                    self.config.min_concurrent_jobs,
+                    max_concurrent,
+                    self.config.scale_queue_depth,
+                );
+                if scaled != target {
+                    debug!(
+                        tier_class = worker_tier_class(tier_group),
+                        pending,
+                        from = target,
+                        to = scaled,
+                        "Scaling worker tasks"
+                    );
+                    target = scaled;
                 }
             }
 
-            if claimed == 0 {
-                break;
+            let mut claimed = 0;
+            while tasks.len() < target {
+                let job_types = claimable_job_types(&handler_types, &in_flight, limits);
+                if job_types.is_empty() {
+                    break;
+                }
+                let lane = self.config.lane_weights.preferred(claims);
+                let Some(job) = self
+                    .claim_job_for_tier(tier_group, &job_types, excluded_archives, lane)
+                    .await
+                else {
+                    break;
+                };
+                claims += 1;
+                claimed += 1;
+                *in_flight.entry(job.job_type).or_default() += 1;
+                let job_type = job.job_type;
+                let worker = self.clone_refs();
+                let handle = tasks.spawn(async move {
+                    worker.execute_job(job).await;
+                });
+                running.insert(handle.id(), job_type);
+            }
+            if claimed > 0 {
+                debug!(
+                    tier_class = worker_tier_class(tier_group),
+                    claimed,
+                    running = tasks.len(),
+                    "Claimed tiered jobs"
+                );
+                total_drained += claimed;
             }
 
-            debug!(
-                tier_class = worker_tier_class(tier_group),
-                claimed, "Processing tiered job batch"
-            );
-            while let Some(result) = tasks.join_next().await {
-                if let Err(e) = result {
+            let Some(result) = tasks.join_next_with_id().await else {
+                break;
+            };
+            let id = match result {
+                Ok((id, ())) => id,
+                Err(e) => {
                     let error_text = e.to_string();
                     let (error_len, error_reason) = worker_failure_telemetry(&error_text);
                     error!(error_len, error_reason, "Job task panicked");
+                    e.id()
+                }
+            };
+            if let Some(job_type) = running.remove(&id) {
+                if let Some(count) = in_flight.get_mut(&job_type) {
+                    *count = count.saturating_sub(1);
                 }
             }
-            total_drained += claimed;
         }
 
         total_drained
     }
 
-    /// Claim the next available job for a specific tier group.
+    /// Claim the next available job of `job_types` for a specific tier group,
+    /// preferring `lane`.
     ///
     /// Jobs belonging to `excluded_archives` are filtered out at the SQL level (Issue #466).
     async fn claim_job_for_tier(
         &self,
         tier_group: TierGroup,
+        job_types: &[JobType],
         excluded_archives: &[String],
+        lane: JobLane,
     ) -> Option<matric_core::Job> {
-        let job_types: Vec<JobType> = {
-            let handlers = self.handlers.read().await;
-            handlers.keys().copied().collect()
-        };
-
-        let result = if excluded_archives.is_empty() {
-            self.db
-                .jobs
-                .claim_next_for_tier(tier_group, &job_types)
-                .await
-        } else {
-            self.db
-                .jobs
-                .claim_next_for_tier_excluding(tier_group, &job_types, excluded_archives)
-                .await
-        };
+        let result = self
+            .db
+            .jobs
+            .claim_next_for_tier_in_lane(
+                tier_group,
+                job_types,
+                excluded_archives,
+                lane,
+                Some(self.worker_id),
+            )
+            .await;
 
         match result {
             Ok(Some(job)) => Some(job),
@@ -971,7 +1347,47 @@ struct JobWorkerRef {
 }
 
 impl JobWorkerRef {
+    /// Persist the generation usage collected while a job ran, whatever its
+    /// outcome. Failures are logged; they never fail the job.
+    async fn record_inference_usage(
+        &self,
+        job_id: Uuid,
+        job_type: JobType,
+        schema: &str,
+        account: &TokenAccount,
+    ) {
+        let records: Vec<InferenceUsageRecord> = account
+            .snapshot()
+            .into_iter()
+            .map(|usage| inference_usage_record(usage, &MODEL_PRICING))
+            .collect();
+        if records.is_empty() {
+            return;
+        }
+        if let Err(e) = self
+            .db
+            .inference_usage
+            .record_job(job_id, job_type.as_str(), schema, &records)
+            .await
+        {
+            let error_text = e.to_string();
+            let (error_len, error_reason) = worker_failure_telemetry(&error_text);
+            warn!(
+                error_len,
+                error_reason,
+                job_id_present = true,
+                "Failed to record job inference usage"
+            );
+        }
+    }
+
     /// Execute a single claimed job.
+    #[instrument(skip_all, fields(
+        subsystem = "jobs",
+        component = "worker",
+        op = "execute_job",
+        job_type = job.job_type.as_str(),
+    ))]
     async fn execute_job(self, job: matric_core::Job) {
         let start = Instant::now();
         let job_id = job.id;
@@ -979,6 +1395,9 @@ impl JobWorkerRef {
         let retry_count = job.retry_count;
         let job_type_len = worker_job_type_len(&job_type);
 
+        let schema = job_schema(&job);
+        let token_account = TokenAccount::default();
+
         info!(job_id_present = true, job_type_len, "Processing job");
 
         let _ = self
@@ -1003,11 +1422,14 @@ impl JobWorkerRef {
                             message: message.map(String::from),
                         });
                     })
-                    .with_event_tx(event_tx_for_ctx);
+                    .with_event_tx(event_tx_for_ctx)
+                    .with_checkpoints(Arc::new(PgJobRepository::new(self.db.pool.clone())));
 
                 let timeout_secs = matric_core::defaults::job_timeout_secs();
                 let job_timeout = Duration::from_secs(timeout_secs);
-                match tokio::time::timeout(job_timeout, handler.execute(ctx)).await {
+                match tokio::time::timeout(job_timeout, token_account.scope(handler.execute(ctx)))
+                    .await
+                {
                     Ok(result) => result,
                     Err(_) => {
                         warn!(
@@ -1024,6 +1446,19 @@ impl JobWorkerRef {
             }
         };
 
+        let outcome = match &result {
+            JobResult::Success(_) => "success",
+            JobResult::Failed(_) => "failed",
+            JobResult::Retry(_) => "retry",
+        };
+        metrics::global().observe(
+            &JOB_DURATION_SECONDS,
+            &[("job_type", job_type.as_str()), ("outcome", outcome)],
+            start.elapsed(),
+        );
+        self.record_inference_usage(job_id, job_type, &schema, &token_account)
+            .await;
+
         match result {
             JobResult::Success(result_data) => {
                 if let Err(e) = self.db.jobs.complete(job_id, result_data).await {
@@ -1133,6 +1568,23 @@ impl JobWorkerRef {
                             failure_code: "retry_exhausted".to_string(),
                         });
                     }
+                    Ok(JobRetryOutcome::Quarantined) => {
+                        warn!(
+                            job_id_present = true,
+                            job_type_len,
+                            failure_class = JobFailureClass::Poison.as_str(),
+                            failure_code = POISON_PAYLOAD_CODE,
+                            duration_ms = start.elapsed().as_millis() as u64,
+                            "Job quarantined as poison"
+                        );
+                        let _ = self.event_tx.send(WorkerEvent::JobFailed {
+                            job_id,
+                            job_type,
+                            error,
+                            failure_class: JobFailureClass::Poison,
+                            failure_code: POISON_PAYLOAD_CODE.to_string(),
+                        });
+                    }
                     Err(e) => {
                         let error_text = e.to_string();
                         let (error_len, error_reason) = worker_failure_telemetry(&error_text);
@@ -1221,8 +1673,12 @@ mod tests {
         let config = WorkerConfig::default();
         assert_eq!(config.poll_interval_ms, DEFAULT_POLL_INTERVAL_MS);
         assert_eq!(config.max_concurrent_jobs, 1);
+        assert_eq!(config.min_concurrent_jobs, config.max_concurrent_jobs);
+        assert!(config.job_type_limits.is_empty());
+        assert_eq!(config.lane_weights, LaneWeights::default());
         assert!(config.enabled);
         assert_eq!(config.retry_policy, JobRetryPolicy::default());
+        assert!(config.heartbeat_timeout_secs >= config.heartbeat_interval_secs * 2);
     }
 
     #[test]
@@ -1242,8 +1698,27 @@ mod tests {
 
         assert_eq!(config.poll_interval_ms, 1000);
         assert_eq!(config.max_concurrent_jobs, 8);
+        assert_eq!(config.min_concurrent_jobs, 8);
         assert!(!config.enabled);
         assert_eq!(config.retry_policy.max_delay_ms, 100);
+
+        let scaled = WorkerConfig::default()
+            .with_autoscale(2, 25)
+            .with_max_concurrent(8)
+            .with_job_type_limit(JobType::AiRevision, 1)
+            .with_lane_weights(9, 2)
+            .with_heartbeat(5, 30);
+        assert_eq!(scaled.lane_weights.preferred(9), JobLane::Batch);
+        assert_eq!(
+            (
+                scaled.heartbeat_interval_secs,
+                scaled.heartbeat_timeout_secs
+            ),
+            (5, 30)
+        );
+        assert_eq!(scaled.min_concurrent_jobs, 2);
+        assert_eq!(scaled.scale_queue_depth, 25);
+        assert_eq!(scaled.job_type_limits.get(&JobType::AiRevision), Some(&1));
     }
 
     #[test]
@@ -1295,6 +1770,37 @@ mod tests {
         assert!(parse_bounded_u64_value("JOB_RETRY_JITTER_PERCENT", "101", 0, 100).is_err());
     }
 
+    #[test]
+    fn autoscaling_adds_a_task_per_queue_depth_within_bounds() {
+        assert_eq!(scaled_concurrency(0, 1, 8, 10), 1);
+        assert_eq!(scaled_concurrency(10, 1, 8, 10), 1);
+        assert_eq!(scaled_concurrency(11, 1, 8, 10), 2);
+        assert_eq!(scaled_concurrency(35, 2, 8, 10), 4);
+        assert_eq!(scaled_concurrency(10_000, 1, 8, 10), 8);
+        assert_eq!(scaled_concurrency(-1, 3, 8, 10), 3);
+        // A tier with a lower cap than the configured minimum stays at its cap.
+        assert_eq!(scaled_concurrency(0, 4, 1, 10), 1);
+    }
+
+    #[test]
+    fn job_type_limits_parse_strictly_and_cap_claims() {
+        let limits = parse_job_type_limits(" ai_revision=1, embedding=4 ,", 8).unwrap();
+        assert_eq!(limits.get(&JobType::AiRevision), Some(&1));
+        assert_eq!(limits.get(&JobType::Embedding), Some(&4));
+        assert!(parse_job_type_limits("embedding", 8).is_err());
+        assert!(parse_job_type_limits("not_a_job=1", 8).is_err());
+        assert!(parse_job_type_limits("embedding=0", 8).is_err());
+        assert!(parse_job_type_limits("embedding=9", 8).is_err());
+        assert!(parse_job_type_limits("embedding=1,embedding=2", 8).is_err());
+
+        let handler_types = [JobType::AiRevision, JobType::Embedding, JobType::Linking];
+        let in_flight = HashMap::from([(JobType::AiRevision, 1), (JobType::Embedding, 3)]);
+        assert_eq!(
+            claimable_job_types(&handler_types, &in_flight, &limits),
+            vec![JobType::Embedding, JobType::Linking]
+        );
+    }
+
     #[test]
     fn worker_error_reason_code_uses_stable_classes() {
         assert_eq!(
@@ -1774,4 +2280,24 @@ mod tests {
             assert!(!rendered.contains(&raw), "raw value leaked: {raw}");
         }
     }
+
+    #[test]
+    fn inference_usage_records_price_known_models_only() {
+        let pricing =
+            PricingTable::from_json(r#"{"priced": {"input": 1.0, "output": 2.0}}"#).unwrap();
+        let usage = |model: &str| ModelUsage {
+            model: model.to_string(),
+            calls: 3,
+            usage: matric_inference::TokenUsage::new(500_000, 250_000),
+        };
+
+        let priced = inference_usage_record(usage("priced"), &pricing);
+        assert_eq!(priced.calls, 3);
+        assert_eq!(priced.prompt_tokens, 500_000);
+        assert_eq!(priced.completion_tokens, 250_000);
+        assert!((priced.cost_usd.unwrap() - 1.0).abs() < 1e-9);
+
+        let unpriced = inference_usage_record(usage("local"), &pricing);
+        assert_eq!(unpriced.cost_usd, None);
+    }
 }
@@ -0,0 +1,279 @@
+//! Spherical k-means clustering of note embeddings for topic modeling.
+//!
+//! Vectors are L2-normalized so that squared Euclidean distance tracks cosine
+//! distance, and centroids are re-normalized after each update. Seeding uses
+//! k-means++ driven by a small deterministic generator, so the same vectors
+//! and seed always produce the same topics.
+
+use tracing::debug;
+
+/// Default number of Lloyd iterations before giving up on convergence.
+pub const DEFAULT_KMEANS_MAX_ITERATIONS: usize = 50;
+
+/// Configuration for [`kmeans`].
+#[derive(Debug, Clone)]
+pub struct KMeansConfig {
+    /// Number of clusters requested; capped at the number of vectors.
+    pub k: usize,
+    /// Maximum Lloyd iterations.
+    pub max_iterations: usize,
+    /// Seed for k-means++ initialization.
+    pub seed: u64,
+}
+
+impl KMeansConfig {
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            max_iterations: DEFAULT_KMEANS_MAX_ITERATIONS,
+            seed: 0x5eed,
+        }
+    }
+}
+
+/// Result of a clustering run.
+#[derive(Debug, Clone)]
+pub struct KMeansResult {
+    /// Cluster index of each input vector, in input order.
+    pub assignments: Vec<usize>,
+    /// Cosine similarity of each input vector to its cluster centroid.
+    pub similarities: Vec<f32>,
+    /// Unit-length centroid of each non-empty cluster.
+    pub centroids: Vec<Vec<f32>>,
+    /// Lloyd iterations run.
+    pub iterations: usize,
+}
+
+impl KMeansResult {
+    /// Input indices of each cluster, most central first.
+    pub fn members(&self) -> Vec<Vec<usize>> {
+        let mut members = vec![Vec::new(); self.centroids.len()];
+        for (i, &cluster) in self.assignments.iter().enumerate() {
+            members[cluster].push(i);
+        }
+        for cluster in &mut members {
+            cluster.sort_by(|&a, &b| self.similarities[b].total_cmp(&self.similarities[a]));
+        }
+        members
+    }
+}
+
+/// Heuristic topic count for `n` notes: `sqrt(n / 2)`, between 2 and `max`.
+pub fn default_cluster_count(n: usize, max: usize) -> usize {
+    let k = ((n as f64) / 2.0).sqrt().round() as usize;
+    k.clamp(2, max.max(2)).min(n)
+}
+
+fn normalize(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        v.to_vec()
+    } else {
+        v.iter().map(|x| x / norm).collect()
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// SplitMix64: tiny, deterministic, and good enough for seeding.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_f64(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// k-means++ seeding on unit vectors, using `1 - cos` as the distance.
+fn seed_centroids(points: &[Vec<f32>], k: usize, rng: &mut SplitMix64) -> Vec<Vec<f32>> {
+    let first = ((rng.next_f64() * points.len() as f64) as usize).min(points.len() - 1);
+    let mut centroids = vec![points[first].clone()];
+    let mut nearest: Vec<f64> = points
+        .iter()
+        .map(|p| f64::from(1.0 - dot(p, &centroids[0])).max(0.0))
+        .collect();
+
+    while centroids.len() < k {
+        let total: f64 = nearest.iter().sum();
+        if total <= f64::EPSILON {
+            // Every remaining point duplicates a centroid.
+            break;
+        }
+        let mut target = rng.next_f64() * total;
+        let mut chosen = points.len() - 1;
+        for (i, d) in nearest.iter().enumerate() {
+            if target < *d {
+                chosen = i;
+                break;
+            }
+            target -= d;
+        }
+        centroids.push(points[chosen].clone());
+        let centroid = centroids.last().expect("just pushed");
+        for (i, p) in points.iter().enumerate() {
+            nearest[i] = nearest[i].min(f64::from(1.0 - dot(p, centroid)).max(0.0));
+        }
+    }
+    centroids
+}
+
+fn nearest_centroid(point: &[f32], centroids: &[Vec<f32>]) -> (usize, f32) {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, dot(point, c)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((0, 0.0))
+}
+
+/// Cluster `vectors` into at most `config.k` groups by cosine similarity.
+///
+/// Vectors whose length differs from the first are assigned nowhere and must
+/// be filtered out by the caller; an empty input yields an empty result.
+/// Clusters that end up empty are dropped and the remaining indices compacted.
+pub fn kmeans(vectors: &[Vec<f32>], config: &KMeansConfig) -> KMeansResult {
+    if vectors.is_empty() || config.k == 0 {
+        return KMeansResult {
+            assignments: Vec::new(),
+            similarities: Vec::new(),
+            centroids: Vec::new(),
+            iterations: 0,
+        };
+    }
+
+    let points: Vec<Vec<f32>> = vectors.iter().map(|v| normalize(v)).collect();
+    let dim = points[0].len();
+    let k = config.k.min(points.len());
+    let mut rng = SplitMix64(config.seed);
+    let mut centroids = seed_centroids(&points, k, &mut rng);
+    let mut assignments = vec![usize::MAX; points.len()];
+    let mut iterations = 0;
+
+    while iterations < config.max_iterations {
+        iterations += 1;
+        let mut changed = false;
+        for (i, p) in points.iter().enumerate() {
+            let (cluster, _) = nearest_centroid(p, &centroids);
+            if assignments[i] != cluster {
+                assignments[i] = cluster;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![vec![0.0f32; dim]; centroids.len()];
+        for (p, &cluster) in points.iter().zip(&assignments) {
+            for (s, x) in sums[cluster].iter_mut().zip(p) {
+                *s += x;
+            }
+        }
+        for (centroid, sum) in centroids.iter_mut().zip(&sums) {
+            // An empty cluster keeps its centroid and is dropped below if it
+            // stays empty.
+            if sum.iter().any(|x| *x != 0.0) {
+                *centroid = normalize(sum);
+            }
+        }
+    }
+
+    // Compact away empty clusters.
+    let mut counts = vec![0usize; centroids.len()];
+    for &cluster in &assignments {
+        counts[cluster] += 1;
+    }
+    let mut remap = vec![usize::MAX; centroids.len()];
+    let mut kept = Vec::new();
+    for (i, centroid) in centroids.into_iter().enumerate() {
+        if counts[i] > 0 {
+            remap[i] = kept.len();
+            kept.push(centroid);
+        }
+    }
+    let assignments: Vec<usize> = assignments.iter().map(|&c| remap[c]).collect();
+    let similarities = points
+        .iter()
+        .zip(&assignments)
+        .map(|(p, &c)| dot(p, &kept[c]))
+        .collect();
+
+    debug!(
+        points = points.len(),
+        requested_k = config.k,
+        clusters = kept.len(),
+        iterations,
+        "k-means clustering complete"
+    );
+
+    KMeansResult {
+        assignments,
+        similarities,
+        centroids: kept,
+        iterations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blob(center: [f32; 3], n: usize) -> Vec<Vec<f32>> {
+        (0..n)
+            .map(|i| {
+                let jitter = (i as f32) * 0.01;
+                vec![center[0] + jitter, center[1] - jitter, center[2] + jitter]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn separates_distinct_directions() {
+        let mut vectors = blob([1.0, 0.0, 0.0], 5);
+        vectors.extend(blob([0.0, 1.0, 0.0], 5));
+        vectors.extend(blob([0.0, 0.0, 1.0], 5));
+
+        let result = kmeans(&vectors, &KMeansConfig::new(3));
+        assert_eq!(result.centroids.len(), 3);
+        for group in result.assignments.chunks(5) {
+            assert!(group.iter().all(|&c| c == group[0]));
+        }
+        let mut firsts: Vec<usize> = result.assignments.chunks(5).map(|g| g[0]).collect();
+        firsts.dedup();
+        assert_eq!(firsts.len(), 3);
+        assert!(result.similarities.iter().all(|s| *s > 0.9));
+    }
+
+    #[test]
+    fn is_deterministic_for_a_seed() {
+        let mut vectors = blob([1.0, 0.2, 0.0], 8);
+        vectors.extend(blob([0.1, 1.0, 0.3], 8));
+        let a = kmeans(&vectors, &KMeansConfig::new(2));
+        let b = kmeans(&vectors, &KMeansConfig::new(2));
+        assert_eq!(a.assignments, b.assignments);
+    }
+
+    #[test]
+    fn caps_k_and_drops_duplicate_clusters() {
+        let vectors = vec![vec![1.0, 0.0]; 4];
+        let result = kmeans(&vectors, &KMeansConfig::new(10));
+        assert_eq!(result.centroids.len(), 1);
+        assert_eq!(result.members(), vec![vec![0, 1, 2, 3]]);
+        assert!(kmeans(&[], &KMeansConfig::new(3)).assignments.is_empty());
+    }
+
+    #[test]
+    fn default_cluster_count_scales_with_notes() {
+        assert_eq!(default_cluster_count(3, 50), 2);
+        assert_eq!(default_cluster_count(200, 50), 10);
+        assert_eq!(default_cluster_count(1_000_000, 50), 50);
+        assert_eq!(default_cluster_count(1, 50), 1);
+    }
+}
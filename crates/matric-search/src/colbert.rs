@@ -25,6 +25,7 @@
 //! ```
 
 use pgvector::Vector;
+use tracing::instrument;
 use uuid::Uuid;
 
 use matric_core::{Error, Result, SearchHit};
@@ -146,6 +147,11 @@ impl ColBERTReranker {
     /// Takes initial search results and query token embeddings,
     /// retrieves document token embeddings, computes MaxSim scores,
     /// and returns re-ranked results.
+    #[instrument(skip_all, fields(
+        subsystem = "search",
+        op = "colbert_rerank",
+        candidates = initial_results.len(),
+    ))]
     pub async fn rerank(
         &self,
         mut initial_results: Vec<SearchHit>,
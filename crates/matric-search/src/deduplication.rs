@@ -4,7 +4,7 @@
 //! document can appear in search results. This module provides deduplication
 //! logic to show only the best-scoring chunk per document.
 
-use matric_core::SearchHit;
+use matric_core::{DerivedMetadata, MediaSegment, SearchHit, SearchSnippet};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
@@ -46,6 +46,19 @@ pub struct EnhancedSearchHit {
     /// Chain information if this result is from a chunked document
     #[serde(skip_serializing_if = "Option::is_none")]
     pub chain_info: Option<ChainSearchInfo>,
+    /// AI-generated summary of the note, for display alongside the snippet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Playback range of the best-matching chunk when it came from a
+    /// transcribed audio or video attachment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_segment: Option<MediaSegment>,
+    /// Excerpts of the note with the query's matches marked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<SearchSnippet>,
+    /// Metadata fields derived from the note's content by its document type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derived: Option<DerivedMetadata>,
 }
 
 impl fmt::Debug for EnhancedSearchHit {
@@ -68,6 +81,10 @@ impl fmt::Debug for EnhancedSearchHit {
                 "chain_total_chunks",
                 &self.chain_info.as_ref().map(|info| info.total_chunks),
             )
+            .field("summary_len", &self.summary.as_ref().map(String::len))
+            .field("media_segment", &self.media_segment)
+            .field("highlight", &self.highlight)
+            .field("derived", &self.derived)
             .finish()
     }
 }
@@ -118,6 +135,10 @@ pub fn deduplicate_search_results(
             .map(|hit| EnhancedSearchHit {
                 hit,
                 chain_info: None,
+                summary: None,
+                media_segment: None,
+                highlight: None,
+                derived: None,
             })
             .collect();
     }
@@ -158,6 +179,10 @@ pub fn deduplicate_search_results(
                     best_chunk_sequence: 0, // TODO: Extract from chunk_index
                     total_chunks: chunks_matched as u32, // Conservative estimate
                 }),
+                summary: None,
+                media_segment: None,
+                highlight: None,
+                derived: None,
             }
         })
         .collect();
@@ -394,6 +419,10 @@ mod tests {
                 best_chunk_sequence: 1,
                 total_chunks: 3,
             }),
+            summary: None,
+            media_segment: None,
+            highlight: None,
+            derived: None,
         };
 
         let json = serde_json::to_string(&hit).unwrap();
@@ -423,6 +452,10 @@ mod tests {
                 embedding_status: None,
             },
             chain_info: Some(info.clone()),
+            summary: Some("Private summary mentions private@example.test".to_string()),
+            media_segment: None,
+            highlight: None,
+            derived: None,
         };
 
         let debug = format!("{info:?}{hit:?}");
@@ -436,6 +469,7 @@ mod tests {
             "Private hit title",
             "555-1212",
             "private-tag",
+            "Private summary",
         ] {
             assert!(
                 !debug.contains(secret),
@@ -10,10 +10,15 @@ use std::time::Instant;
 
 use async_trait::async_trait;
 use pgvector::Vector;
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, info_span, instrument, Instrument};
 use uuid::Uuid;
 
-use matric_core::{EmbeddingRepository, Result, SearchHit, StrictFilter, StrictTagFilter};
+use matric_core::metrics::{self, SEARCH_DURATION_SECONDS};
+use matric_core::note_view::recency_weight;
+use matric_core::pinned::{promote_pinned, MAX_PINNED_SLOTS};
+use matric_core::{
+    EmbeddingRepository, Result, SearchHit, StrictFilter, StrictSecurityFilter, StrictTagFilter,
+};
 use matric_db::Database;
 
 use crate::deduplication::{deduplicate_search_results, DeduplicationConfig, EnhancedSearchHit};
@@ -70,6 +75,9 @@ pub struct HybridSearchConfig {
     /// Unified strict filter for multi-dimensional filtering.
     /// When set, takes precedence over strict_filter for FTS.
     pub unified_filter: Option<StrictFilter>,
+    /// Row-level visibility of the caller. Fused results are restricted to
+    /// the notes it admits; `None` leaves results unrestricted.
+    pub security: Option<StrictSecurityFilter>,
     /// Optional ISO 639-1 language hint (e.g., "en", "zh", "ja", "de")
     pub lang_hint: Option<String>,
     /// Optional script hint (e.g., "latin", "han", "cyrillic")
@@ -79,6 +87,26 @@ pub struct HybridSearchConfig {
     /// MMR diversity weight (0.0 = pure relevance, 1.0 = max diversity).
     /// When None or 0.0, MMR re-ranking is skipped.
     pub diversity: Option<f32>,
+    /// Graph importance boost (0.0-1.0): each hit's score is multiplied by
+    /// `1 + graph_boost * importance`. When None or 0.0, no boost is applied.
+    pub graph_boost: Option<f32>,
+    /// Personal recency boost: hits the principal opened lately score higher.
+    /// When None, no boost is applied.
+    pub personal_recency: Option<PersonalRecency>,
+    /// Places at the top of the results kept for pinned notes that match
+    /// the query (see [`matric_core::pinned`]). When None or 0, pinned notes
+    /// rank like any other.
+    pub pinned_slots: Option<usize>,
+}
+
+/// Boost for notes the searching principal opened lately.
+#[derive(Clone, PartialEq)]
+pub struct PersonalRecency {
+    /// Principal whose views count (see [`matric_core::note_view::view_principal`])
+    pub principal: String,
+    /// 0.0-1.0: each opened hit's score is multiplied by
+    /// `1 + boost * recency_weight(last_viewed)`.
+    pub boost: f32,
 }
 
 impl fmt::Debug for HybridSearchConfig {
@@ -99,6 +127,7 @@ impl fmt::Debug for HybridSearchConfig {
             )
             .field("strict_filter_set", &self.strict_filter.is_some())
             .field("unified_filter_set", &self.unified_filter.is_some())
+            .field("security_set", &self.security.is_some())
             .field("lang_hint_len", &self.lang_hint.as_ref().map(String::len))
             .field(
                 "script_hint_len",
@@ -122,6 +151,12 @@ impl fmt::Debug for HybridSearchConfig {
                 &self.fts_flags.multilingual_configs,
             )
             .field("diversity", &self.diversity)
+            .field("graph_boost", &self.graph_boost)
+            .field(
+                "personal_recency_boost",
+                &self.personal_recency.as_ref().map(|p| p.boost),
+            )
+            .field("pinned_slots", &self.pinned_slots)
             .finish()
     }
 }
@@ -137,10 +172,14 @@ impl Default for HybridSearchConfig {
             deduplication: DeduplicationConfig::default(),
             strict_filter: None,
             unified_filter: None,
+            security: None,
             lang_hint: None,
             script_hint: None,
             fts_flags: FtsFeatureFlags::default(),
             diversity: None,
+            graph_boost: None,
+            personal_recency: None,
+            pinned_slots: None,
         }
     }
 }
@@ -222,6 +261,12 @@ impl HybridSearchConfig {
         self
     }
 
+    /// Restrict results to the notes a caller's security filter admits.
+    pub fn with_security(mut self, security: StrictSecurityFilter) -> Self {
+        self.security = Some(security);
+        self
+    }
+
     /// Set ISO 639-1 language hint (e.g., "en", "zh", "ja", "de").
     /// Overrides automatic script detection.
     pub fn with_lang_hint(mut self, lang: impl Into<String>) -> Self {
@@ -247,6 +292,29 @@ impl HybridSearchConfig {
         self.diversity = Some(diversity.clamp(0.0, 1.0));
         self
     }
+
+    /// Set the graph importance boost (0.0 = none, 1.0 = up to double score).
+    pub fn with_graph_boost(mut self, boost: f32) -> Self {
+        self.graph_boost = Some(boost.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Boost hits `principal` opened lately (0.0 = none, 1.0 = up to double
+    /// score for a note opened just now).
+    pub fn with_personal_recency(mut self, principal: impl Into<String>, boost: f32) -> Self {
+        self.personal_recency = Some(PersonalRecency {
+            principal: principal.into(),
+            boost: boost.clamp(0.0, 1.0),
+        });
+        self
+    }
+
+    /// Keep up to `slots` places at the top for matching pinned notes
+    /// (at most [`MAX_PINNED_SLOTS`]).
+    pub fn with_pinned_slots(mut self, slots: usize) -> Self {
+        self.pinned_slots = Some(slots.min(MAX_PINNED_SLOTS));
+        self
+    }
 }
 
 /// Trait for hybrid search operations.
@@ -307,6 +375,44 @@ pub enum SearchStrategy {
     Bigram,
     /// Combined CJK strategy (bigram if available, else trigram)
     Cjk,
+    /// Language-specific stemming (e.g. matric_german) for notes detected in
+    /// that language, simple FTS for the rest
+    FtsLanguage(&'static str),
+}
+
+impl SearchStrategy {
+    /// Stable name used as the `strategy` metric label.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::FtsEnglish => "fts_english",
+            Self::FtsSimple => "fts_simple",
+            Self::Trigram => "trigram",
+            Self::Bigram => "bigram",
+            Self::Cjk => "cjk",
+            Self::FtsLanguage(_) => "fts_language",
+        }
+    }
+
+    /// PostgreSQL text search configuration the strategy matches with.
+    pub fn fts_config(self) -> Option<&'static str> {
+        match self {
+            Self::FtsEnglish => Some("public.matric_english"),
+            Self::FtsSimple => Some("public.matric_simple"),
+            Self::FtsLanguage(lang) => matric_core::language::fts_config_for_language(lang),
+            Self::Trigram | Self::Bigram | Self::Cjk => None,
+        }
+    }
+}
+
+/// Non-English languages with a stemming configuration (`matric_<language>`).
+const STEMMED_LANGUAGES: &[&str] = &["de", "fr", "es", "ru", "pt"];
+
+/// Language-specific strategy for a language code, if it has a stemming config.
+fn stemmed_language(code: &str) -> Option<SearchStrategy> {
+    STEMMED_LANGUAGES
+        .iter()
+        .find(|lang| **lang == code)
+        .map(|lang| SearchStrategy::FtsLanguage(lang))
 }
 
 /// Metadata about the search operation.
@@ -388,6 +494,107 @@ impl HybridSearchEngine {
         Ok(ids.into_iter().collect())
     }
 
+    /// Restrict fused hits to the notes `config.security` admits.
+    async fn retain_visible_hits(
+        &self,
+        hits: &mut Vec<SearchHit>,
+        config: &HybridSearchConfig,
+    ) -> Result<()> {
+        matric_db::visibility::retain_visible(
+            self.db.pool(),
+            hits,
+            config.security.as_ref(),
+            |hit| Some(hit.note_id),
+        )
+        .await
+    }
+
+    /// Boost hits by their stored graph importance when `config.graph_boost`
+    /// is set. Notes without graph metrics keep their score.
+    async fn boost_by_graph_importance(
+        &self,
+        hits: &mut [SearchHit],
+        config: &HybridSearchConfig,
+    ) -> Result<()> {
+        let boost = config.graph_boost.unwrap_or(0.0);
+        if boost <= 0.0 || hits.is_empty() {
+            return Ok(());
+        }
+        let note_ids: Vec<Uuid> = hits.iter().map(|h| h.note_id).collect();
+        let importance = self.db.links.graph_importance(&note_ids).await?;
+        Self::apply_graph_boost(hits, &importance, boost);
+        Ok(())
+    }
+
+    /// Multiply each hit's score by `1 + boost * importance` and re-sort.
+    fn apply_graph_boost(
+        hits: &mut [SearchHit],
+        importance: &std::collections::HashMap<Uuid, f64>,
+        boost: f32,
+    ) {
+        for hit in hits.iter_mut() {
+            if let Some(&importance) = importance.get(&hit.note_id) {
+                hit.score *= 1.0 + boost * importance.clamp(0.0, 1.0) as f32;
+            }
+        }
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    }
+
+    /// Boost hits the principal in `config.personal_recency` opened lately.
+    /// Hits they never opened keep their score.
+    async fn boost_by_personal_recency(
+        &self,
+        hits: &mut [SearchHit],
+        config: &HybridSearchConfig,
+    ) -> Result<()> {
+        let Some(recency) = config.personal_recency.as_ref() else {
+            return Ok(());
+        };
+        if recency.boost <= 0.0 || hits.is_empty() {
+            return Ok(());
+        }
+        let note_ids: Vec<Uuid> = hits.iter().map(|h| h.note_id).collect();
+        let last_viewed = self
+            .db
+            .note_views
+            .last_viewed(&recency.principal, &note_ids)
+            .await?;
+        Self::apply_personal_recency(hits, &last_viewed, recency.boost, chrono::Utc::now());
+        Ok(())
+    }
+
+    /// Multiply each hit's score by `1 + boost * recency_weight` and re-sort.
+    fn apply_personal_recency(
+        hits: &mut [SearchHit],
+        last_viewed: &std::collections::HashMap<Uuid, chrono::DateTime<chrono::Utc>>,
+        boost: f32,
+        now: chrono::DateTime<chrono::Utc>,
+    ) {
+        for hit in hits.iter_mut() {
+            if let Some(&viewed_at) = last_viewed.get(&hit.note_id) {
+                hit.score *= 1.0 + boost * recency_weight(viewed_at, now);
+            }
+        }
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    }
+
+    /// Move matching pinned notes into the places kept by
+    /// `config.pinned_slots`, highest priority first.
+    async fn reserve_pinned_slots(
+        &self,
+        hits: &mut Vec<EnhancedSearchHit>,
+        config: &HybridSearchConfig,
+    ) -> Result<()> {
+        let slots = config.pinned_slots.unwrap_or(0);
+        if slots == 0 || hits.is_empty() {
+            return Ok(());
+        }
+        let note_ids: Vec<Uuid> = hits.iter().map(|h| h.hit.note_id).collect();
+        let priorities = self.db.pinned.priorities(&note_ids).await?;
+        promote_pinned(hits, |h| h.hit.note_id, &priorities, slots);
+        Ok(())
+    }
+
     /// Apply score weighting to search results.
     fn apply_weights(hits: Vec<SearchHit>, weight: f32) -> Vec<SearchHit> {
         hits.into_iter()
@@ -422,18 +629,38 @@ impl HybridSearchEngine {
 
         // Language hint
         if let Some(ref lang) = config.lang_hint {
-            let detected = match lang.to_lowercase().as_str() {
+            let lang = lang.to_lowercase();
+            let detected = match lang.as_str() {
                 "zh" | "ja" | "ko" => DetectedScript::Cjk,
                 "ru" => DetectedScript::Cyrillic,
                 "ar" => DetectedScript::Arabic,
                 "en" | "de" | "fr" | "es" | "pt" => DetectedScript::Latin,
                 _ => detect_script(query).primary,
             };
+            if config.fts_flags.multilingual_configs {
+                if let Some(strategy) = stemmed_language(&lang) {
+                    return (strategy, detected);
+                }
+            }
             return (Self::strategy_for_script(&detected, config), detected);
         }
 
         // Auto-detect script
         let detection = detect_script(query);
+
+        // Latin script is shared by many languages; route queries that are
+        // long enough to identify reliably to that language's stemming config.
+        if config.fts_flags.multilingual_configs && detection.primary == DetectedScript::Latin {
+            if let Some(strategy) = matric_core::language::detect_language_with(
+                query,
+                matric_core::language::min_confidence(),
+            )
+            .and_then(|lang| stemmed_language(&lang.code))
+            {
+                return (strategy, detection.primary);
+            }
+        }
+
         let strategy = Self::strategy_for_script(&detection.primary, config);
         (strategy, detection.primary)
     }
@@ -459,16 +686,15 @@ impl HybridSearchEngine {
                     SearchStrategy::FtsSimple
                 }
             }
+            DetectedScript::Cyrillic if config.fts_flags.multilingual_configs => {
+                SearchStrategy::FtsLanguage("ru")
+            }
             DetectedScript::Cyrillic
             | DetectedScript::Arabic
             | DetectedScript::Greek
             | DetectedScript::Hebrew
             | DetectedScript::Devanagari
-            | DetectedScript::Thai => {
-                // TODO: When multilingual_configs is true, could select language-specific config
-                // based on detected script (e.g., matric_russian for Cyrillic)
-                SearchStrategy::FtsSimple
-            }
+            | DetectedScript::Thai => SearchStrategy::FtsSimple,
             DetectedScript::Mixed => {
                 // For mixed scripts, use trigram if available for best coverage
                 if config.fts_flags.trigram_fallback {
@@ -485,6 +711,12 @@ impl HybridSearchEngine {
     ///
     /// Applies strict_filter for all strategies: English FTS uses server-side SQL
     /// filtering; non-English strategies use post-filtering (fixes #235, #236).
+    #[instrument(skip_all, fields(
+        subsystem = "search",
+        op = "fts_retrieval",
+        strategy = strategy.as_str(),
+        limit = limit,
+    ))]
     async fn fts_search_with_strategy(
         &self,
         query: &str,
@@ -535,6 +767,20 @@ impl HybridSearchEngine {
                     .search_cjk(query, limit, config.exclude_archived)
                     .await?
             }
+            SearchStrategy::FtsLanguage(lang) => match strategy.fts_config() {
+                Some(fts_config) => {
+                    self.db
+                        .search
+                        .search_language(query, fts_config, lang, limit, config.exclude_archived)
+                        .await?
+                }
+                None => {
+                    self.db
+                        .search
+                        .search_simple(query, limit, config.exclude_archived)
+                        .await?
+                }
+            },
         };
 
         // Post-filter by strict_filter for non-English strategies (fixes #236).
@@ -751,6 +997,11 @@ impl HybridSearch for HybridSearchEngine {
             }
 
             fts_count = fts_results.len();
+            metrics::global().observe(
+                &SEARCH_DURATION_SECONDS,
+                &[("stage", "fts"), ("strategy", strategy.as_str())],
+                fts_start.elapsed(),
+            );
             debug!(
                 fts_hits = fts_count,
                 ?strategy,
@@ -768,28 +1019,41 @@ impl HybridSearch for HybridSearchEngine {
             if let Some(embedding) = query_embedding {
                 let sem_start = Instant::now();
                 // Apply strict filter, embedding set, or search all embeddings
-                let semantic_results = if let Some(ref strict_filter) = config.strict_filter {
-                    // Strict filter takes priority - ensures data isolation
-                    self.db
-                        .embeddings
-                        .find_similar_with_strict_filter(
-                            embedding,
-                            strict_filter,
-                            limit * 2,
-                            config.exclude_archived,
-                        )
-                        .await?
-                } else if let Some(set_id) = config.embedding_set_id {
-                    self.db
-                        .embeddings
-                        .find_similar_in_set(embedding, set_id, limit * 2, config.exclude_archived)
-                        .await?
-                } else {
-                    self.db
-                        .embeddings
-                        .find_similar(embedding, limit * 2, config.exclude_archived)
-                        .await?
-                };
+                let semantic_results = async {
+                    if let Some(ref strict_filter) = config.strict_filter {
+                        // Strict filter takes priority - ensures data isolation
+                        self.db
+                            .embeddings
+                            .find_similar_with_strict_filter(
+                                embedding,
+                                strict_filter,
+                                limit * 2,
+                                config.exclude_archived,
+                            )
+                            .await
+                    } else if let Some(set_id) = config.embedding_set_id {
+                        self.db
+                            .embeddings
+                            .find_similar_in_set(
+                                embedding,
+                                set_id,
+                                limit * 2,
+                                config.exclude_archived,
+                            )
+                            .await
+                    } else {
+                        self.db
+                            .embeddings
+                            .find_similar(embedding, limit * 2, config.exclude_archived)
+                            .await
+                    }
+                }
+                .instrument(info_span!(
+                    "semantic_retrieval",
+                    subsystem = "search",
+                    op = "semantic_retrieval"
+                ))
+                .await?;
                 // Filter out low-similarity semantic results BEFORE RRF fusion (fixes #384).
                 // Use stricter threshold when FTS found nothing — without keyword
                 // confirmation, require stronger semantic evidence to avoid returning
@@ -805,6 +1069,11 @@ impl HybridSearch for HybridSearchEngine {
                     .collect();
 
                 semantic_count = semantic_results.len();
+                metrics::global().observe(
+                    &SEARCH_DURATION_SECONDS,
+                    &[("stage", "semantic"), ("strategy", "vector")],
+                    sem_start.elapsed(),
+                );
                 debug!(
                     semantic_hits = semantic_count,
                     threshold = %threshold,
@@ -825,6 +1094,11 @@ impl HybridSearch for HybridSearchEngine {
         // If no results from either source, return empty
         if ranked_lists.is_empty() {
             debug!("No results from any source");
+            metrics::global().observe(
+                &SEARCH_DURATION_SECONDS,
+                &[("stage", "total"), ("strategy", strategy.as_str())],
+                start.elapsed(),
+            );
             return Ok(Vec::new());
         }
 
@@ -838,6 +1112,11 @@ impl HybridSearch for HybridSearchEngine {
             "Fusion complete"
         );
 
+        // Drop notes the caller may not see before re-ranking and truncation
+        self.retain_visible_hits(&mut results, config).await?;
+        self.boost_by_graph_importance(&mut results, config).await?;
+        self.boost_by_personal_recency(&mut results, config).await?;
+
         // Apply MMR diversity re-ranking if enabled (issue #561)
         let diversity = config.diversity.unwrap_or(0.0);
         if diversity > 0.0 {
@@ -863,8 +1142,14 @@ impl HybridSearch for HybridSearchEngine {
 
         // Apply deduplication, then enforce requested limit (fixes #183)
         let mut deduplicated = deduplicate_search_results(results, &config.deduplication);
+        self.reserve_pinned_slots(&mut deduplicated, config).await?;
         deduplicated.truncate(limit as usize);
 
+        metrics::global().observe(
+            &SEARCH_DURATION_SECONDS,
+            &[("stage", "total"), ("strategy", strategy.as_str())],
+            start.elapsed(),
+        );
         info!(
             fts_hits = fts_count,
             semantic_hits = semantic_count,
@@ -947,28 +1232,41 @@ impl HybridSearch for HybridSearchEngine {
         if config.semantic_weight > 0.0 {
             if let Some(embedding) = query_embedding {
                 // Apply strict filter, embedding set, or search all embeddings
-                let semantic_results = if let Some(ref strict_filter) = config.strict_filter {
-                    // Strict filter takes priority - ensures data isolation
-                    self.db
-                        .embeddings
-                        .find_similar_with_strict_filter(
-                            embedding,
-                            strict_filter,
-                            limit * 2,
-                            config.exclude_archived,
-                        )
-                        .await?
-                } else if let Some(set_id) = config.embedding_set_id {
-                    self.db
-                        .embeddings
-                        .find_similar_in_set(embedding, set_id, limit * 2, config.exclude_archived)
-                        .await?
-                } else {
-                    self.db
-                        .embeddings
-                        .find_similar(embedding, limit * 2, config.exclude_archived)
-                        .await?
-                };
+                let semantic_results = async {
+                    if let Some(ref strict_filter) = config.strict_filter {
+                        // Strict filter takes priority - ensures data isolation
+                        self.db
+                            .embeddings
+                            .find_similar_with_strict_filter(
+                                embedding,
+                                strict_filter,
+                                limit * 2,
+                                config.exclude_archived,
+                            )
+                            .await
+                    } else if let Some(set_id) = config.embedding_set_id {
+                        self.db
+                            .embeddings
+                            .find_similar_in_set(
+                                embedding,
+                                set_id,
+                                limit * 2,
+                                config.exclude_archived,
+                            )
+                            .await
+                    } else {
+                        self.db
+                            .embeddings
+                            .find_similar(embedding, limit * 2, config.exclude_archived)
+                            .await
+                    }
+                }
+                .instrument(info_span!(
+                    "semantic_retrieval",
+                    subsystem = "search",
+                    op = "semantic_retrieval"
+                ))
+                .await?;
                 // Filter out low-similarity semantic results before RRF fusion (fixes #384)
                 // Use stricter threshold when FTS found nothing
                 let threshold = if fts_count == 0 {
@@ -997,6 +1295,9 @@ impl HybridSearch for HybridSearchEngine {
         }
 
         let mut results = rrf_fuse(ranked_lists, (limit as usize) * 3);
+        self.retain_visible_hits(&mut results, config).await?;
+        self.boost_by_graph_importance(&mut results, config).await?;
+        self.boost_by_personal_recency(&mut results, config).await?;
 
         // Apply MMR diversity re-ranking if enabled (issue #561)
         let diversity = config.diversity.unwrap_or(0.0);
@@ -1014,6 +1315,7 @@ impl HybridSearch for HybridSearchEngine {
 
         // Apply deduplication, then enforce requested limit (fixes #183)
         let mut deduplicated = deduplicate_search_results(results, &config.deduplication);
+        self.reserve_pinned_slots(&mut deduplicated, config).await?;
         deduplicated.truncate(limit as usize);
 
         info!(
@@ -1490,6 +1792,7 @@ mod tests {
             "deduplication_expand_chains",
             "strict_filter_set",
             "unified_filter_set",
+            "security_set",
             "lang_hint_len",
             "script_hint_len",
             "fts_flags_websearch_to_tsquery",
@@ -1503,6 +1806,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_select_strategy_routes_stemmed_languages() {
+        let config = HybridSearchConfig::default();
+        let (strategy, _) = HybridSearchEngine::select_strategy(
+            "Die Häuser in der Altstadt werden von vielen Touristen besucht",
+            &config,
+        );
+        assert_eq!(strategy, SearchStrategy::FtsLanguage("de"));
+        assert_eq!(strategy.fts_config(), Some("public.matric_german"));
+
+        let (strategy, _) = HybridSearchEngine::select_strategy("книги", &config);
+        assert_eq!(strategy, SearchStrategy::FtsLanguage("ru"));
+
+        // Short Latin queries cannot be identified and stay on English FTS.
+        let (strategy, _) = HybridSearchEngine::select_strategy("rust", &config);
+        assert_eq!(strategy, SearchStrategy::FtsEnglish);
+
+        let hinted = HybridSearchConfig {
+            lang_hint: Some("FR".to_string()),
+            ..Default::default()
+        };
+        let (strategy, _) = HybridSearchEngine::select_strategy("maison", &hinted);
+        assert_eq!(strategy, SearchStrategy::FtsLanguage("fr"));
+
+        let disabled = HybridSearchConfig {
+            fts_flags: FtsFeatureFlags {
+                multilingual_configs: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (strategy, _) = HybridSearchEngine::select_strategy("книги", &disabled);
+        assert_eq!(strategy, SearchStrategy::FtsSimple);
+    }
+
     #[test]
     fn test_config_with_embedding_set() {
         let set_id = Uuid::new_v4();
@@ -1535,6 +1873,18 @@ mod tests {
         assert_eq!(stored_filter.any_concepts.len(), 1);
     }
 
+    #[test]
+    fn test_config_with_security() {
+        assert!(HybridSearchConfig::default().security.is_none());
+
+        let user_id = Uuid::new_v4();
+        let config =
+            HybridSearchConfig::default().with_security(StrictSecurityFilter::for_caller(user_id));
+        let security = config.security.expect("security filter set");
+        assert_eq!(security.owner_id, Some(user_id));
+        assert!(security.include_unowned);
+    }
+
     #[test]
     fn test_search_request_with_strict_filter() {
         let filter = StrictTagFilter::new()
@@ -1728,6 +2078,56 @@ mod tests {
         assert_eq!(weighted[0].score, 0.8);
     }
 
+    #[test]
+    fn test_apply_graph_boost_reorders_by_importance() {
+        let hit = |score| SearchHit {
+            note_id: Uuid::new_v4(),
+            score,
+            snippet: None,
+            title: None,
+            tags: Vec::new(),
+            embedding_status: None,
+        };
+        let mut hits = vec![hit(0.9), hit(0.8), hit(0.7)];
+        let hub = hits[1].note_id;
+        let unscored = hits[2].note_id;
+        let importance = std::collections::HashMap::from([(hits[0].note_id, 0.0), (hub, 1.0)]);
+
+        HybridSearchEngine::apply_graph_boost(&mut hits, &importance, 0.5);
+
+        assert_eq!(hits[0].note_id, hub);
+        assert!((hits[0].score - 1.2).abs() < 1e-6);
+        assert_eq!(hits[1].score, 0.9);
+        assert_eq!(hits[2].note_id, unscored);
+        assert_eq!(hits[2].score, 0.7);
+    }
+
+    #[test]
+    fn test_apply_personal_recency_favours_recently_opened() {
+        let hit = |score| SearchHit {
+            note_id: Uuid::new_v4(),
+            score,
+            snippet: None,
+            title: None,
+            tags: Vec::new(),
+            embedding_status: None,
+        };
+        let now = chrono::Utc::now();
+        let mut hits = vec![hit(0.9), hit(0.8), hit(0.7)];
+        let opened = hits[1].note_id;
+        let last_viewed = std::collections::HashMap::from([
+            (opened, now),
+            (hits[2].note_id, now - chrono::Duration::days(365)),
+        ]);
+
+        HybridSearchEngine::apply_personal_recency(&mut hits, &last_viewed, 0.5, now);
+
+        assert_eq!(hits[0].note_id, opened);
+        assert!((hits[0].score - 1.2).abs() < 1e-6);
+        assert_eq!(hits[1].score, 0.9);
+        assert!(hits[2].score > 0.7 && hits[2].score < 0.71);
+    }
+
     #[test]
     fn test_apply_weights_empty_list() {
         let hits: Vec<SearchHit> = vec![];
@@ -34,6 +34,7 @@
 
 pub mod adaptive_rrf;
 pub mod adaptive_weights;
+pub mod clustering;
 pub mod colbert;
 pub mod deduplication;
 pub mod fts_flags;
@@ -43,6 +44,7 @@ pub mod mmr;
 pub mod rrf;
 pub mod rsf;
 pub mod script_detection;
+pub mod similar;
 
 // Re-export core types
 pub use matric_core::*;
@@ -50,6 +52,7 @@ pub use matric_core::*;
 // Re-export search types
 pub use adaptive_rrf::{rrf_score, select_k, AdaptiveRrfConfig, QueryCharacteristics};
 pub use adaptive_weights::{select_weights, AdaptiveWeightConfig, FusionWeights};
+pub use clustering::{default_cluster_count, kmeans, KMeansConfig, KMeansResult};
 pub use colbert::{ColBERTConfig, ColBERTReranker};
 pub use deduplication::{ChainSearchInfo, DeduplicationConfig, EnhancedSearchHit};
 pub use fts_flags::FtsFeatureFlags;
@@ -57,10 +60,12 @@ pub use hnsw_tuning::{
     compute_ef, estimated_latency_ms, estimated_recall, HnswTuningConfig, RecallTarget,
 };
 pub use hybrid::{
-    HybridSearch, HybridSearchConfig, HybridSearchEngine, SearchRequest, SearchStrategy,
+    HybridSearch, HybridSearchConfig, HybridSearchEngine, PersonalRecency, SearchRequest,
+    SearchStrategy,
 };
 pub use matric_db::TokenEmbedding;
 pub use mmr::mmr_rerank;
 pub use rrf::*;
 pub use rsf::rsf_fuse;
 pub use script_detection::{detect_script, has_cjk, has_emoji, DetectedScript, ScriptDetection};
+pub use similar::{SimilarOptions, SimilarStrategy};
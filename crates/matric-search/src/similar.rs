@@ -0,0 +1,222 @@
+//! "More like this" search for a note.
+//!
+//! A note is turned into a search probe from what is already stored about
+//! it: the centroid of its chunk embeddings stands in for the query vector,
+//! and its most frequent lexemes, OR-ed together, stand in for the query
+//! text. The probe then runs through the regular hybrid pipeline, so
+//! fusion, chunk deduplication, security filtering and diversity behave as
+//! for any other search.
+
+use std::collections::HashSet;
+
+use pgvector::Vector;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use uuid::Uuid;
+
+use matric_core::Result;
+
+use crate::deduplication::EnhancedSearchHit;
+use crate::hybrid::{HybridSearch, HybridSearchConfig, HybridSearchEngine};
+
+/// Terms taken from the note unless asked otherwise.
+pub const DEFAULT_SIMILAR_TERMS: usize = 10;
+
+/// Most terms a caller may ask for.
+pub const MAX_SIMILAR_TERMS: usize = 25;
+
+/// Which of the note's signals the probe uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimilarStrategy {
+    /// Embedding centroid and top terms, fused (default)
+    #[default]
+    Hybrid,
+    /// Embedding centroid only
+    Semantic,
+    /// Top terms only
+    Terms,
+}
+
+impl SimilarStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hybrid => "hybrid",
+            Self::Semantic => "semantic",
+            Self::Terms => "terms",
+        }
+    }
+
+    /// Apply the strategy's FTS and semantic weights to `config`.
+    pub fn apply(self, config: &mut HybridSearchConfig) {
+        let (fts_weight, semantic_weight) = match self {
+            Self::Hybrid => {
+                let default = HybridSearchConfig::default();
+                (default.fts_weight, default.semantic_weight)
+            }
+            Self::Semantic => (0.0, 1.0),
+            Self::Terms => (1.0, 0.0),
+        };
+        config.fts_weight = fts_weight;
+        config.semantic_weight = semantic_weight;
+    }
+}
+
+/// Options for [`HybridSearchEngine::more_like_this`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimilarOptions {
+    pub strategy: SimilarStrategy,
+    /// Most notes returned
+    pub limit: i64,
+    /// Most of the note's terms in the probe, clamped to
+    /// [`MAX_SIMILAR_TERMS`]
+    pub terms: usize,
+    /// Leave out notes linked to or from the note
+    pub exclude_linked: bool,
+}
+
+impl Default for SimilarOptions {
+    fn default() -> Self {
+        Self {
+            strategy: SimilarStrategy::default(),
+            limit: 10,
+            terms: DEFAULT_SIMILAR_TERMS,
+            exclude_linked: false,
+        }
+    }
+}
+
+/// Mean of `vectors`, scaled to unit length. `None` when there are no
+/// vectors, their dimensions differ, or they cancel out.
+pub fn centroid(vectors: &[Vector]) -> Option<Vector> {
+    let dimension = vectors.first()?.as_slice().len();
+    let mut sum = vec![0.0f32; dimension];
+    for vector in vectors {
+        let values = vector.as_slice();
+        if values.len() != dimension {
+            return None;
+        }
+        for (total, value) in sum.iter_mut().zip(values) {
+            *total += value;
+        }
+    }
+    let norm = sum.iter().map(|value| value * value).sum::<f32>().sqrt();
+    if dimension == 0 || !norm.is_normal() {
+        return None;
+    }
+    Some(Vector::from(
+        sum.into_iter()
+            .map(|value| value / norm)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// A `websearch_to_tsquery` query matching any of `terms`. Terms with
+/// characters other than letters and digits are dropped, so none can be
+/// read as an operator or phrase.
+pub fn terms_query(terms: &[String]) -> String {
+    terms
+        .iter()
+        .filter(|term| !term.is_empty() && term.chars().all(char::is_alphanumeric))
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+impl HybridSearchEngine {
+    /// Notes similar to `note_id`, best first, never including the note
+    /// itself.
+    ///
+    /// `config` supplies everything but the weights, which come from
+    /// `options.strategy`; its `embedding_set_id` picks the set the note's
+    /// vectors are read from (the default set when unset). A note with
+    /// neither stored vectors nor indexed terms has no similar notes.
+    pub async fn more_like_this(
+        &self,
+        note_id: Uuid,
+        options: &SimilarOptions,
+        config: &HybridSearchConfig,
+    ) -> Result<Vec<EnhancedSearchHit>> {
+        let mut config = config.clone();
+        options.strategy.apply(&mut config);
+
+        let query = if config.fts_weight > 0.0 {
+            let limit = options.terms.min(MAX_SIMILAR_TERMS) as i64;
+            terms_query(&self.db().search.top_terms(note_id, limit).await?)
+        } else {
+            String::new()
+        };
+        let probe = if config.semantic_weight > 0.0 {
+            centroid(
+                &self
+                    .db()
+                    .embeddings
+                    .vectors_for_note(note_id, config.embedding_set_id)
+                    .await?,
+            )
+        } else {
+            None
+        };
+        debug!(
+            strategy = options.strategy.as_str(),
+            has_terms = !query.is_empty(),
+            has_vector = probe.is_some(),
+            "Built more-like-this probe"
+        );
+        if query.is_empty() && probe.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let mut excluded = HashSet::from([note_id]);
+        if options.exclude_linked {
+            excluded.extend(self.db().links.linked_note_ids(note_id).await?);
+        }
+        let mut hits = self
+            .search(
+                &query,
+                probe.as_ref(),
+                options.limit + excluded.len() as i64,
+                &config,
+            )
+            .await?;
+        hits.retain(|hit| !excluded.contains(&hit.hit.note_id));
+        hits.truncate(usize::try_from(options.limit).unwrap_or(0));
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn centroid_is_normalized_mean() {
+        let vectors = vec![Vector::from(vec![1.0, 0.0]), Vector::from(vec![0.0, 1.0])];
+        let center = centroid(&vectors).expect("centroid");
+        let values = center.as_slice();
+        assert!((values[0] - values[1]).abs() < 1e-6);
+        assert!((values[0] * values[0] + values[1] * values[1] - 1.0).abs() < 1e-6);
+
+        assert!(centroid(&[]).is_none());
+        assert!(centroid(&[Vector::from(vec![1.0]), Vector::from(vec![1.0, 2.0])]).is_none());
+        assert!(centroid(&[Vector::from(vec![1.0]), Vector::from(vec![-1.0])]).is_none());
+    }
+
+    #[test]
+    fn terms_query_ors_plain_terms() {
+        let terms = ["rust", "-borrow", "\"async", "tokio", ""].map(String::from);
+        assert_eq!(terms_query(&terms), "rust or tokio");
+        assert_eq!(terms_query(&[]), "");
+    }
+
+    #[test]
+    fn strategies_set_weights() {
+        let mut config = HybridSearchConfig::default();
+        SimilarStrategy::Terms.apply(&mut config);
+        assert_eq!((config.fts_weight, config.semantic_weight), (1.0, 0.0));
+        SimilarStrategy::Semantic.apply(&mut config);
+        assert_eq!((config.fts_weight, config.semantic_weight), (0.0, 1.0));
+        SimilarStrategy::Hybrid.apply(&mut config);
+        assert_eq!((config.fts_weight, config.semantic_weight), (0.5, 0.5));
+    }
+}
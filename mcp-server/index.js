@@ -339,6 +339,22 @@ function createMcpServer() {
           result = await apiRequest("POST", `/api/v1/notes/${args.id}/restore`);
           break;
 
+        case "list_trash": {
+          const params = new URLSearchParams();
+          if (args.limit !== undefined && args.limit !== null) params.set("limit", args.limit);
+          if (args.offset !== undefined && args.offset !== null) params.set("offset", args.offset);
+          const qs = params.toString();
+          result = await apiRequest("GET", `/api/v1/trash${qs ? `?${qs}` : ""}`);
+          break;
+        }
+
+        case "restore_from_trash":
+          result = await apiRequest("POST", "/api/v1/trash/restore", {
+            ids: args.note_ids || [],
+            all: args.all === true,
+          });
+          break;
+
         case "search": {
           const action = args.action;
           if (action === "text") {
@@ -635,6 +651,14 @@ function createMcpServer() {
           break;
         }
 
+        case "bulk_tags": {
+          const { op, from, to, source, target, tag, reassign_to, filter } = args;
+          result = await apiRequest("POST", "/api/v1/tags/bulk", {
+            op, from, to, source, target, tag, reassign_to, filter,
+          });
+          break;
+        }
+
         case "list_tags":
           result = await apiRequest("GET", "/api/v1/tags");
           break;
@@ -875,6 +899,7 @@ function createMcpServer() {
             format: args.format,
             default_tags: args.default_tags,
             collection_id: args.collection_id,
+            variables: args.variables,
           });
           break;
 
@@ -891,6 +916,7 @@ function createMcpServer() {
               }
             }
           }
+          result.variable_definitions = result.variables || [];
           result.variables = variables;
           break;
         }
@@ -908,6 +934,7 @@ function createMcpServer() {
           if (args.format !== undefined) body.format = args.format;
           if (args.default_tags !== undefined) body.default_tags = args.default_tags;
           if (args.collection_id !== undefined) body.collection_id = args.collection_id;
+          if (args.variables !== undefined) body.variables = args.variables;
           result = await apiRequest("PATCH", `/api/v1/templates/${args.id}`, body);
           break;
         }
@@ -918,9 +945,34 @@ function createMcpServer() {
             tags: args.tags,
             collection_id: args.collection_id,
             revision_mode: args.revision_mode,
+            version: args.version,
           });
           break;
 
+        case "get_journal_today":
+          result = await apiRequest("POST", "/api/v1/journal/today");
+          break;
+
+        case "list_tasks": {
+          const params = new URLSearchParams();
+          if (args.status) params.set("status", args.status);
+          if (args.note_id) params.set("note_id", args.note_id);
+          if (args.due_before) params.set("due_before", args.due_before);
+          if (args.due_after) params.set("due_after", args.due_after);
+          if (args.limit !== undefined && args.limit !== null) params.set("limit", args.limit);
+          if (args.offset !== undefined && args.offset !== null) params.set("offset", args.offset);
+          const qs = params.toString();
+          result = await apiRequest("GET", `/api/v1/tasks${qs ? `?${qs}` : ""}`);
+          break;
+        }
+
+        case "set_task_status":
+          result = await apiRequest(
+            "POST",
+            `/api/v1/tasks/${args.id}/${args.done ? "complete" : "uncomplete"}`
+          );
+          break;
+
         case "create_job":
           result = await apiRequest("POST", "/api/v1/jobs", {
             note_id: args.note_id,
@@ -1283,18 +1335,8 @@ function createMcpServer() {
           if (!args.confirm) {
             throw new Error("Must set confirm=true to purge all notes");
           }
-          // Get all soft-deleted notes and purge them
-          const allNotes = await apiRequest("GET", `/api/v1/notes?filter=deleted&limit=${DEFAULTS.INTERNAL_FETCH_LIMIT}`);
-          const purgeAllResults = { queued: [], failed: [], total: (allNotes.notes || []).length };
-          for (const note of allNotes.notes || []) {
-            try {
-              await apiRequest("POST", `/api/v1/notes/${note.id}/purge`);
-              purgeAllResults.queued.push(note.id);
-            } catch (e) {
-              purgeAllResults.failed.push({ id: note.id, error: e.message });
-            }
-          }
-          result = purgeAllResults;
+          // Purge the whole trash in one background job
+          result = await apiRequest("POST", "/api/v1/trash/purge", { all: true });
           break;
 
         // ============================================================================
@@ -1630,6 +1672,7 @@ function createMcpServer() {
             if (args.scheme_id) p.set("scheme_id", args.scheme_id);
             if (args.status) p.set("status", args.status);
             if (args.top_only) p.set("top_only", "true");
+            if (args.has_antipattern) p.set("has_antipattern", args.has_antipattern);
             if (args.limit !== undefined && args.limit !== null) p.set("limit", args.limit);
             if (args.offset) p.set("offset", args.offset);
             result = await apiRequest("GET", `/api/v1/concepts?${p}`);
@@ -1669,6 +1712,63 @@ function createMcpServer() {
               rights: args.rights,
               version: args.version,
             });
+          } else if (mcaAction === "merge") {
+            result = await apiRequest("POST", "/api/v1/concepts/merge", {
+              source_ids: args.source_ids,
+              target_id: args.target_id,
+              reason: args.reason,
+            });
+          } else if (mcaAction === "split") {
+            result = await apiRequest("POST", `/api/v1/concepts/${args.id}/split`, {
+              targets: args.targets,
+              deprecate_source: args.deprecate_source,
+              reason: args.reason,
+            });
+          } else if (mcaAction === "suggest_split") {
+            const body = {};
+            if (args.max_targets !== undefined) body.max_targets = args.max_targets;
+            result = await apiRequest("POST", `/api/v1/concepts/${args.id}/split/suggest`, body);
+          } else if (mcaAction === "audit") {
+            const p = new URLSearchParams();
+            if (args.limit !== undefined && args.limit !== null) p.set("limit", args.limit);
+            result = await apiRequest("GET", `/api/v1/concepts/${args.id}/audit?${p}`);
+          } else if (mcaAction === "suggestions") {
+            const p = new URLSearchParams();
+            if (args.status) p.set("status", args.status);
+            if (args.concept_id) p.set("concept_id", args.concept_id);
+            if (args.note_id) p.set("note_id", args.note_id);
+            if (args.limit !== undefined && args.limit !== null) p.set("limit", args.limit);
+            if (args.offset) p.set("offset", args.offset);
+            result = await apiRequest("GET", `/api/v1/concepts/suggestions?${p}`);
+          } else if (mcaAction === "accept_suggestion" || mcaAction === "reject_suggestion") {
+            const verdict = mcaAction === "accept_suggestion" ? "accept" : "reject";
+            result = await apiRequest("POST", `/api/v1/concepts/suggestions/${args.suggestion_id}/${verdict}`, {});
+          } else if (mcaAction === "mappings") {
+            result = await apiRequest("GET", `/api/v1/concepts/${args.id}/mappings`);
+          } else if (mcaAction === "add_mapping") {
+            result = await apiRequest("POST", `/api/v1/concepts/${args.id}/mappings`, {
+              target_uri: args.target_uri,
+              relation_type: args.relation_type,
+              target_scheme_uri: args.target_scheme_uri,
+              target_label: args.target_label,
+              confidence: args.confidence,
+            });
+          } else if (mcaAction === "update_mapping") {
+            const mappingUpdate = {};
+            for (const field of ["relation_type", "target_scheme_uri", "target_label", "confidence", "validated_by"]) {
+              if (args[field] !== undefined) mappingUpdate[field] = args[field];
+            }
+            result = await apiRequest("PATCH", `/api/v1/concepts/${args.id}/mappings/${args.mapping_id}`, mappingUpdate);
+          } else if (mcaAction === "remove_mapping") {
+            await apiRequest("DELETE", `/api/v1/concepts/${args.id}/mappings/${args.mapping_id}`);
+            result = { success: true };
+          } else if (mcaAction === "health") {
+            const p = new URLSearchParams();
+            if (args.has_antipattern) p.set("kind", args.has_antipattern);
+            if (args.limit !== undefined && args.limit !== null) p.set("limit", args.limit);
+            result = await apiRequest("GET", `/api/v1/concepts/health?${p}`);
+          } else if (mcaAction === "refresh_health") {
+            result = await apiRequest("POST", "/api/v1/concepts/health/refresh", {});
           } else if (mcaAction === "get_scheme") {
             result = await apiRequest("GET", `/api/v1/concepts/schemes/${args.scheme_id}`);
           } else if (mcaAction === "update_scheme") {
@@ -1688,7 +1788,7 @@ function createMcpServer() {
             await apiRequest("DELETE", `/api/v1/concepts/schemes/${args.scheme_id}${args.force ? "?force=true" : ""}`);
             result = { success: true };
           } else {
-            throw new Error(`Unknown manage_concepts action: ${mcaAction}. Valid: search, autocomplete, get, get_full, stats, top, list_schemes, create_scheme, get_scheme, update_scheme, delete_scheme`);
+            throw new Error(`Unknown manage_concepts action: ${mcaAction}. Valid: search, autocomplete, get, get_full, stats, top, merge, split, suggest_split, audit, suggestions, accept_suggestion, reject_suggestion, mappings, add_mapping, update_mapping, remove_mapping, health, refresh_health, list_schemes, create_scheme, get_scheme, update_scheme, delete_scheme`);
           }
           break;
         }
@@ -2512,9 +2612,14 @@ function createMcpServer() {
           result = await apiRequest("GET", "/api/v1/archives");
           break;
 
-        case "create_archive":
-          result = await apiRequest("POST", "/api/v1/archives", args);
+        case "create_archive": {
+          const { template, ...body } = args;
+          const path = template
+            ? `/api/v1/archives?template=${encodeURIComponent(template)}`
+            : "/api/v1/archives";
+          result = await apiRequest("POST", path, body);
           break;
+        }
 
         case "get_archive":
           result = await apiRequest("GET", `/api/v1/archives/${args.name}`);
@@ -3072,7 +3177,10 @@ function createMcpServer() {
           if (args.description) {
             body.description = args.description;
           }
-          result = await apiRequest("POST", "/api/v1/archives", body);
+          const path = args.template
+            ? `/api/v1/archives?template=${encodeURIComponent(args.template)}`
+            : "/api/v1/archives";
+          result = await apiRequest("POST", path, body);
           break;
         }
 
@@ -3090,6 +3198,18 @@ function createMcpServer() {
           break;
         }
 
+        case "merge_memory": {
+          const body = {};
+          if (args.mode) body.mode = args.mode;
+          if (args.on_conflict) body.on_conflict = args.on_conflict;
+          result = await apiRequest(
+            "POST",
+            `/api/v1/archives/${encodeURIComponent(args.source_name)}/merge-into/${encodeURIComponent(args.target_name)}`,
+            body
+          );
+          break;
+        }
+
         case "get_memories_overview": {
           // Overview includes database_size_bytes (pg_database_size) which covers
           // ALL data on disk: all schemas, tables, indexes, attachment blobs, etc.
@@ -3097,6 +3217,11 @@ function createMcpServer() {
           break;
         }
 
+        case "get_memory_usage": {
+          result = await apiRequest("GET", `/api/v1/archives/${encodeURIComponent(args.name)}/usage`);
+          break;
+        }
+
         case "search_memories_federated": {
           const body = { q: args.q, memories: args.memories };
           if (args.limit) {
@@ -4194,6 +4319,12 @@ instantiate_template({
 - Use \`{{variable_name}}\` in template content
 - **Missing variables**: Left as-is (\`{{var}}\` stays in output)
 - **Extra variables**: Ignored
+- **Typed variables**: \`variables: [{ name, type, required, default, options, max_length }]\`
+  declares \`string\`, \`date\`, \`enum\` or \`note_reference\` variables. Instantiation then
+  rejects undeclared, missing required and mistyped values. Defaults may use \`{{today}}\`,
+  \`{{today+7d}}\` or \`{{now}}\`. \`get_template\` returns them as \`variable_definitions\`.
+- **Versions**: every update makes a new version; \`instantiate_template({ id, version })\`
+  uses an earlier one
 - **Variable names**: Case-sensitive (\`{{Date}}\` ≠ \`{{date}}\`)
 
 ## Best Practices
@@ -668,6 +668,80 @@ export default [
     },
     annotations: {"destructiveHint":false},
   },
+  {
+    name: "bulk_tags",
+    description: `Rewrite a tag across many notes in one transaction: 'rename' a tag, 'merge' one tag into another, 'delete' a tag (optionally reassigning its notes), or 'apply'/'remove' a tag on the notes matching a filter. Returns the affected note IDs.`,
+    inputSchema: {
+      "type": "object",
+      "properties": {
+        "op": {
+          "type": "string",
+          "enum": [
+            "rename",
+            "merge",
+            "delete",
+            "apply",
+            "remove"
+          ],
+          "description": "Operation to run"
+        },
+        "from": {
+          "type": "string",
+          "description": "Tag to rename (required for 'rename')"
+        },
+        "to": {
+          "type": "string",
+          "description": "New tag name; must not exist yet (required for 'rename')"
+        },
+        "source": {
+          "type": "string",
+          "description": "Tag merged away (required for 'merge')"
+        },
+        "target": {
+          "type": "string",
+          "description": "Tag receiving the notes (required for 'merge')"
+        },
+        "tag": {
+          "type": "string",
+          "description": "Tag to delete, apply or remove (required for 'delete'/'apply'/'remove')"
+        },
+        "reassign_to": {
+          "type": "string",
+          "description": "Move the deleted tag's notes to this tag first (optional for 'delete')"
+        },
+        "filter": {
+          "type": "object",
+          "description": "Notes to act on for 'apply'/'remove'; a note must match every selector given",
+          "properties": {
+            "note_ids": {
+              "type": "array",
+              "items": {
+                "type": "string",
+                "format": "uuid"
+              },
+              "description": "Note UUIDs (max 1000)"
+            },
+            "collection_id": {
+              "type": "string",
+              "format": "uuid",
+              "description": "Notes filed directly in this collection"
+            },
+            "tags": {
+              "type": "array",
+              "items": {
+                "type": "string"
+              },
+              "description": "Notes carrying all of these tags"
+            }
+          }
+        }
+      },
+      "required": [
+        "op"
+      ]
+    },
+    annotations: {"destructiveHint":true},
+  },
   {
     name: "manage_collection",
     description: `Manage collections (folders) for organizing notes. Supports CRUD, listing notes, moving notes, and export. See \`get_documentation(topic='collections')\` for hierarchy details.`,
@@ -756,7 +830,7 @@ export default [
   },
   {
     name: "manage_concepts",
-    description: `Browse and curate the SKOS concept vocabulary, and manage concept schemes. Concepts are auto-created by the NLP pipeline as notes are tagged — use this to search, review candidates, monitor vocabulary health, and manage taxonomies. Actions: search, autocomplete, get, get_full, stats, top (concepts) | list_schemes, create_scheme, get_scheme, update_scheme, delete_scheme (schemes).`,
+    description: `Browse and curate the SKOS concept vocabulary, and manage concept schemes. Concepts are auto-created by the NLP pipeline as notes are tagged — use this to search, review candidates, monitor vocabulary health, and manage taxonomies. Actions: search, autocomplete, get, get_full, stats, top (concepts) | merge, split, suggest_split, audit, suggestions, accept_suggestion, reject_suggestion (curation) | mappings, add_mapping, update_mapping, remove_mapping (external vocabularies) | health, refresh_health (taxonomy) | list_schemes, create_scheme, get_scheme, update_scheme, delete_scheme (schemes).`,
     inputSchema: {
       "type": "object",
       "properties": {
@@ -769,13 +843,26 @@ export default [
             "get_full",
             "stats",
             "top",
+            "merge",
+            "split",
+            "suggest_split",
+            "audit",
+            "suggestions",
+            "accept_suggestion",
+            "reject_suggestion",
+            "mappings",
+            "add_mapping",
+            "update_mapping",
+            "remove_mapping",
+            "health",
+            "refresh_health",
             "list_schemes",
             "create_scheme",
             "get_scheme",
             "update_scheme",
             "delete_scheme"
           ],
-          "description": "Action: 'search' (find concepts), 'autocomplete' (quick lookup), 'get'/'get_full' (details), 'stats' (governance), 'top' (root concepts), 'list_schemes'/'create_scheme'/'get_scheme'/'update_scheme'/'delete_scheme' (scheme management)"
+          "description": "Action: 'search' (find concepts), 'autocomplete' (quick lookup), 'get'/'get_full' (details), 'stats' (governance), 'top' (root concepts), 'merge' (fold source_ids into target_id), 'split' (distribute id's notes over targets), 'suggest_split' (model-proposed split body), 'audit' (id's audit log), 'suggestions' (review queue), 'accept_suggestion'/'reject_suggestion' (review suggestion_id), 'mappings' (id's external mappings), 'add_mapping'/'update_mapping'/'remove_mapping' (edit id's mappings), 'list_schemes'/'create_scheme'/'get_scheme'/'update_scheme'/'delete_scheme' (scheme management)"
         },
         "q": {
           "type": "string",
@@ -791,18 +878,103 @@ export default [
           "enum": [
             "candidate",
             "approved",
-            "deprecated"
+            "deprecated",
+            "pending",
+            "accepted",
+            "rejected"
           ],
-          "description": "Filter by status (for 'search')"
+          "description": "Filter by concept status (for 'search': candidate/approved/deprecated) or review state (for 'suggestions': pending/accepted/rejected, default pending)"
         },
         "top_only": {
           "type": "boolean",
           "description": "Only return top-level concepts (for 'search')"
         },
+        "has_antipattern": {
+          "type": "string",
+          "description": "Anti-pattern, e.g. orphan (for 'search'/'health')"
+        },
         "id": {
           "type": "string",
           "format": "uuid",
-          "description": "Concept UUID (required for 'get'/'get_full')"
+          "description": "Concept UUID (required for 'get'/'get_full'/'split'/'suggest_split'/'audit' and the mapping actions)"
+        },
+        "suggestion_id": {
+          "type": "string",
+          "format": "uuid",
+          "description": "Suggestion UUID (required for 'accept_suggestion'/'reject_suggestion')"
+        },
+        "mapping_id": {
+          "type": "string",
+          "format": "uuid",
+          "description": "Mapping UUID (required for 'update_mapping'/'remove_mapping')"
+        },
+        "target_uri": {
+          "type": "string",
+          "description": "Absolute URI of the external concept (required for 'add_mapping')"
+        },
+        "relation_type": {
+          "type": "string",
+          "enum": [
+            "exact_match",
+            "close_match",
+            "broad_match",
+            "narrow_match",
+            "related_match"
+          ],
+          "description": "SKOS mapping relation (required for 'add_mapping', optional for 'update_mapping')"
+        },
+        "target_label": {
+          "type": "string",
+          "description": "Label of the external concept (for 'add_mapping'/'update_mapping')"
+        },
+        "target_scheme_uri": {
+          "type": "string",
+          "description": "URI of the external concept scheme (for 'add_mapping'/'update_mapping')"
+        },
+        "confidence": {
+          "type": "number",
+          "description": "Confidence in the mapping, 0-1 (for 'add_mapping'/'update_mapping')"
+        },
+        "validated_by": {
+          "type": "string",
+          "description": "Mark the mapping validated by this reviewer (for 'update_mapping')"
+        },
+        "note_id": {
+          "type": "string",
+          "format": "uuid",
+          "description": "Only suggestions for this note (for 'suggestions')"
+        },
+        "concept_id": {
+          "type": "string",
+          "format": "uuid",
+          "description": "Only suggestions of this concept (for 'suggestions')"
+        },
+        "source_ids": {
+          "type": "array",
+          "items": { "type": "string", "format": "uuid" },
+          "description": "Concepts to fold into target_id (required for 'merge')"
+        },
+        "target_id": {
+          "type": "string",
+          "format": "uuid",
+          "description": "Concept that absorbs source_ids (required for 'merge')"
+        },
+        "targets": {
+          "type": "array",
+          "items": { "type": "object" },
+          "description": "Split targets, each {concept_id | pref_label, rule: {note_ids, concept_ids, title_contains}} (required for 'split'; use the 'split' body from 'suggest_split')"
+        },
+        "deprecate_source": {
+          "type": "boolean",
+          "description": "Deprecate the concept once its notes are distributed (for 'split')"
+        },
+        "max_targets": {
+          "type": "integer",
+          "description": "Most concepts to propose, 2-20 (for 'suggest_split', default: 5)"
+        },
+        "reason": {
+          "type": "string",
+          "description": "Why the concepts are merged or split (for 'merge'/'split')"
         },
         "limit": {
           "type": "integer",
@@ -2426,6 +2598,23 @@ export default [
         "collection_id": {
           "type": "string",
           "description": "Default collection for instantiated notes"
+        },
+        "variables": {
+          "type": "array",
+          "items": {
+            "type": "object",
+            "properties": {
+              "name": { "type": "string" },
+              "type": { "type": "string", "enum": ["string", "date", "enum", "note_reference"] },
+              "description": { "type": "string" },
+              "required": { "type": "boolean" },
+              "default": { "type": "string" },
+              "options": { "type": "array", "items": { "type": "string" } },
+              "max_length": { "type": "integer" }
+            },
+            "required": ["name", "type"]
+          },
+          "description": "Typed variables the placeholders take; defaults may use {{today}}, {{today+7d}} or {{now}}"
         }
       },
       "required": [
@@ -2514,6 +2703,23 @@ export default [
             }
           ],
           "description": "New default collection UUID, or null for none"
+        },
+        "variables": {
+          "type": "array",
+          "items": {
+            "type": "object",
+            "properties": {
+              "name": { "type": "string" },
+              "type": { "type": "string", "enum": ["string", "date", "enum", "note_reference"] },
+              "description": { "type": "string" },
+              "required": { "type": "boolean" },
+              "default": { "type": "string" },
+              "options": { "type": "array", "items": { "type": "string" } },
+              "max_length": { "type": "integer" }
+            },
+            "required": ["name", "type"]
+          },
+          "description": "New typed variable declarations; [] removes them"
         }
       },
       "required": [
@@ -2562,6 +2768,10 @@ export default [
           ],
           "description": "AI revision mode (default: standard)",
           "default": "standard"
+        },
+        "version": {
+          "type": "integer",
+          "description": "Template version to instantiate (default: current)"
         }
       },
       "required": [
@@ -2570,6 +2780,44 @@ export default [
     },
     annotations: {"destructiveHint":false},
   },
+  {
+    name: "get_journal_today",
+    description: `Get today's daily journal note in the active memory, creating it on first use from the memory's journal template and collection. Notes created today are linked from it.`,
+    inputSchema: {
+      "type": "object",
+      "properties": {}
+    },
+    annotations: {"destructiveHint":false},
+  },
+  {
+    name: "list_tasks",
+    description: `List tasks found in notes (Markdown checkboxes and, when enabled, action items), soonest due first.`,
+    inputSchema: {
+      "type": "object",
+      "properties": {
+        "status": { "type": "string", "enum": ["open", "done"], "description": "Only tasks in this status" },
+        "note_id": { "type": "string", "format": "uuid", "description": "Only tasks of this note" },
+        "due_before": { "type": "string", "description": "Only tasks due on or before this date (YYYY-MM-DD)" },
+        "due_after": { "type": "string", "description": "Only tasks due on or after this date (YYYY-MM-DD)" },
+        "limit": { "type": "integer", "description": "Maximum results (default 50, max 500)" },
+        "offset": { "type": "integer", "description": "Pagination offset" }
+      }
+    },
+    annotations: {"readOnlyHint":true},
+  },
+  {
+    name: "set_task_status",
+    description: `Complete or reopen a task. Checkbox tasks are ticked or cleared in their note.`,
+    inputSchema: {
+      "type": "object",
+      "properties": {
+        "id": { "type": "string", "format": "uuid", "description": "Task ID" },
+        "done": { "type": "boolean", "description": "true to complete, false to reopen" }
+      },
+      "required": ["id", "done"]
+    },
+    annotations: {"destructiveHint":false},
+  },
   {
     name: "list_embedding_sets",
     description: `List all embedding sets. See \`get_documentation(topic='embedding_configs')\` for set types.`,
@@ -2863,6 +3111,46 @@ export default [
     },
     annotations: {"destructiveHint":false},
   },
+  {
+    name: "list_trash",
+    description: `List soft-deleted notes in the active memory's trash, most recently deleted first. Each note shows when the retention job will purge it (\`purge_at\`).`,
+    inputSchema: {
+      "type": "object",
+      "properties": {
+        "limit": {
+          "type": "integer",
+          "description": "Maximum notes to return (default: 50, max: 500)"
+        },
+        "offset": {
+          "type": "integer",
+          "description": "Number of notes to skip"
+        }
+      }
+    },
+    annotations: {"readOnlyHint":true},
+  },
+  {
+    name: "restore_from_trash",
+    description: `Restore several soft-deleted notes at once, or the whole trash. Give either note_ids or all=true.`,
+    inputSchema: {
+      "type": "object",
+      "properties": {
+        "note_ids": {
+          "type": "array",
+          "items": {
+            "type": "string",
+            "format": "uuid"
+          },
+          "description": "Trashed note UUIDs to restore (max 1000)"
+        },
+        "all": {
+          "type": "boolean",
+          "description": "Restore every note in the trash"
+        }
+      }
+    },
+    annotations: {"destructiveHint":false},
+  },
   {
     name: "purge_note",
     description: `Permanently delete a single soft-deleted note. This cannot be undone.`,
@@ -2902,7 +3190,7 @@ export default [
   },
   {
     name: "purge_all_notes",
-    description: `Permanently delete ALL soft-deleted notes. Requires confirmation. This cannot be undone.`,
+    description: `Permanently delete ALL soft-deleted notes in the active memory's trash as a background job. Requires confirmation. This cannot be undone.`,
     inputSchema: {
       "type": "object",
       "properties": {
@@ -4614,6 +4902,11 @@ export default [
         "description": {
           "type": "string",
           "description": "Optional archive description"
+        },
+        "template": {
+          "type": "string",
+          "enum": ["research", "engineering", "personal"],
+          "description": "Provisioning profile to apply: SKOS schemes, collections, prompt templates, document types and embedding configs (optional)"
         }
       },
       "required": [
@@ -5680,6 +5973,11 @@ When called without note_ids, processes all notes in the current archive (up to
         "description": {
           "type": "string",
           "description": "Purpose or description (optional)"
+        },
+        "template": {
+          "type": "string",
+          "enum": ["research", "engineering", "personal"],
+          "description": "Provisioning profile to apply: SKOS schemes, collections, prompt templates, document types and embedding configs (optional)"
         }
       },
       "required": [
@@ -5731,6 +6029,38 @@ When called without note_ids, processes all notes in the current archive (up to
     },
     annotations: {"destructiveHint":false},
   },
+  {
+    name: "merge_memory",
+    description: `Merge one memory into another as a background job. Copies notes, tags, links, collections, attachments and provenance; returns a job_id to poll for progress.`,
+    inputSchema: {
+      "type": "object",
+      "properties": {
+        "source_name": {
+          "type": "string",
+          "description": "Name of the memory to merge from"
+        },
+        "target_name": {
+          "type": "string",
+          "description": "Name of the memory to merge into"
+        },
+        "mode": {
+          "type": "string",
+          "enum": ["copy", "move"],
+          "description": "copy keeps the source memory; move drops it after the merge (default: copy)"
+        },
+        "on_conflict": {
+          "type": "string",
+          "enum": ["skip", "overwrite", "fail"],
+          "description": "Notes in both memories: keep the target's, replace it, or abort (default: skip)"
+        }
+      },
+      "required": [
+        "source_name",
+        "target_name"
+      ]
+    },
+    annotations: {"destructiveHint":true},
+  },
   {
     name: "get_memories_overview",
     description: `Get overview of all memories with note counts and sizes.`,
@@ -5740,6 +6070,23 @@ When called without note_ids, processes all notes in the current archive (up to
     },
     annotations: {"readOnlyHint":true},
   },
+  {
+    name: "get_memory_usage",
+    description: `Get one memory's storage consumption (notes, attachments, embeddings, blob bytes, per-table sizes) and the quota it is held to.`,
+    inputSchema: {
+      "type": "object",
+      "properties": {
+        "name": {
+          "type": "string",
+          "description": "Name of the memory"
+        }
+      },
+      "required": [
+        "name"
+      ]
+    },
+    annotations: {"readOnlyHint":true},
+  },
   {
     name: "search_memories_federated",
     description: `Search across multiple memory archives simultaneously. See \`get_documentation(topic='archives')\` for federated search.`,
@@ -6081,8 +6428,8 @@ When called without note_ids, processes all notes in the current archive (up to
         },
         "on_conflict": {
           "type": "string",
-          "enum": ["skip", "replace", "merge"],
-          "description": "Conflict resolution: skip, replace, merge (for import_shard)"
+          "enum": ["skip", "replace", "overwrite", "merge", "duplicate"],
+          "description": "Conflict resolution for notes matched by ID or identical content: skip, replace/overwrite, merge, duplicate (for import_shard)"
         },
         "skip_embedding_regen": {
           "type": "boolean",
@@ -0,0 +1,32 @@
+-- Spaced repetition review cards (SM-2 scheduling).
+-- Cards are per-memory-archive and cascade with their source note.
+
+CREATE TABLE IF NOT EXISTS review_card (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    note_id UUID NOT NULL REFERENCES note(id) ON DELETE CASCADE,
+    front TEXT NOT NULL,
+    back TEXT NOT NULL,
+    ease_factor DOUBLE PRECISION NOT NULL DEFAULT 2.5 CHECK (ease_factor >= 1.3),
+    interval_days INTEGER NOT NULL DEFAULT 0 CHECK (interval_days >= 0),
+    repetitions INTEGER NOT NULL DEFAULT 0 CHECK (repetitions >= 0),
+    lapses INTEGER NOT NULL DEFAULT 0 CHECK (lapses >= 0),
+    due_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_reviewed_at_utc TIMESTAMPTZ,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Queue lookups scan cards by due date.
+CREATE INDEX IF NOT EXISTS idx_review_card_due ON review_card(due_at_utc);
+CREATE INDEX IF NOT EXISTS idx_review_card_note ON review_card(note_id);
+
+-- Append-only grade history, kept for future scheduler tuning.
+CREATE TABLE IF NOT EXISTS review_log (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    card_id UUID NOT NULL REFERENCES review_card(id) ON DELETE CASCADE,
+    grade SMALLINT NOT NULL CHECK (grade BETWEEN 0 AND 5),
+    interval_days INTEGER NOT NULL,
+    ease_factor DOUBLE PRECISION NOT NULL,
+    reviewed_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_log_card ON review_log(card_id, reviewed_at_utc DESC);
@@ -0,0 +1,4 @@
+-- Add blob_garbage_collection job type for reclaiming unreferenced attachment
+-- blobs. The job deletes orphaned attachment_blob rows past a grace period in
+-- every archive and removes their stored files after commit.
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'blob_garbage_collection';
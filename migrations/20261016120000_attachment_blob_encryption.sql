@@ -0,0 +1,31 @@
+-- Encrypt-at-rest flags for attachment blobs.
+--
+-- Blobs written while ATTACHMENT_ENCRYPT_AT_REST is enabled are stored as
+-- MMPKE01 ciphertext for the active keyset. The flag, not the stored bytes,
+-- decides whether a blob is decrypted on read, so user uploads that happen to
+-- be MMPKE files are served unchanged. Existing rows remain plaintext.
+
+DO $attachment_blob_encryption$
+DECLARE
+    target_schema TEXT;
+BEGIN
+    FOR target_schema IN
+        SELECT 'public'
+        UNION
+        SELECT ar.schema_name
+        FROM public.archive_registry AS ar
+        WHERE ar.schema_name <> 'public'
+    LOOP
+        IF to_regclass(format('%I.attachment_blob', target_schema)) IS NULL THEN
+            CONTINUE;
+        END IF;
+
+        EXECUTE format(
+            'ALTER TABLE %I.attachment_blob
+                ADD COLUMN IF NOT EXISTS encrypted BOOLEAN NOT NULL DEFAULT FALSE,
+                ADD COLUMN IF NOT EXISTS encryption_keyset_id UUID',
+            target_schema
+        );
+    END LOOP;
+END
+$attachment_blob_encryption$;
@@ -0,0 +1,64 @@
+-- Opt-in encrypted notes.
+--
+-- An encrypted note keeps its content as MMPKE01 ciphertext in
+-- note.encrypted_content, sealed for the keyset in encryption_keyset_id.
+-- note_original and note_revised_current hold empty content, so the generated
+-- tsv columns and embeddings never see the plaintext. The trigger below keeps
+-- it that way for writers that bypass the repository guards.
+
+CREATE OR REPLACE FUNCTION public.reject_plaintext_for_encrypted_note()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.content <> '' AND EXISTS (
+        SELECT 1 FROM note WHERE id = NEW.note_id AND encrypted
+    ) THEN
+        RAISE EXCEPTION 'Encrypted note content cannot be replaced with plaintext'
+            USING ERRCODE = 'check_violation';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DO $encrypted_notes$
+DECLARE
+    target_schema TEXT;
+    content_table TEXT;
+BEGIN
+    FOR target_schema IN
+        SELECT 'public'
+        UNION
+        SELECT ar.schema_name
+        FROM public.archive_registry AS ar
+        WHERE ar.schema_name <> 'public'
+    LOOP
+        IF to_regclass(format('%I.note', target_schema)) IS NULL THEN
+            CONTINUE;
+        END IF;
+
+        EXECUTE format(
+            'ALTER TABLE %I.note
+                ADD COLUMN IF NOT EXISTS encrypted BOOLEAN NOT NULL DEFAULT FALSE,
+                ADD COLUMN IF NOT EXISTS encryption_keyset_id UUID,
+                ADD COLUMN IF NOT EXISTS encrypted_content BYTEA',
+            target_schema
+        );
+
+        FOREACH content_table IN ARRAY ARRAY['note_original', 'note_revised_current', 'note_revision']
+        LOOP
+            IF to_regclass(format('%I.%I', target_schema, content_table)) IS NULL THEN
+                CONTINUE;
+            END IF;
+            EXECUTE format(
+                'DROP TRIGGER IF EXISTS trg_%s_encrypted_note ON %I.%I',
+                content_table, target_schema, content_table
+            );
+            EXECUTE format(
+                'CREATE TRIGGER trg_%s_encrypted_note
+                 BEFORE INSERT OR UPDATE OF content ON %I.%I
+                 FOR EACH ROW EXECUTE FUNCTION public.reject_plaintext_for_encrypted_note()',
+                content_table, target_schema, content_table
+            );
+        END LOOP;
+    END LOOP;
+END
+$encrypted_notes$;
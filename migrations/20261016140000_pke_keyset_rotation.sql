@@ -0,0 +1,18 @@
+-- Key rotation for PKE keysets.
+--
+-- Rotating a keyset creates a successor keyset and marks the old one retired.
+-- A pke_key_rotation job re-encrypts every artifact sealed for the old keyset
+-- (encrypted notes and attachment blobs in all archives) for the successor and
+-- records completion in reencrypted_at. The old keyset is purged once both the
+-- re-encryption has finished and its grace period (retire_after) has passed.
+ALTER TABLE pke_keysets
+    ADD COLUMN IF NOT EXISTS rotated_to_id UUID REFERENCES pke_keysets(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS retired_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS retire_after TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS reencrypted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_pke_keysets_retire_after
+    ON pke_keysets (retire_after)
+    WHERE retire_after IS NOT NULL;
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'pke_key_rotation';
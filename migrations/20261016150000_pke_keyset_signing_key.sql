@@ -0,0 +1,9 @@
+-- Ed25519 signer identity for PKE keysets.
+--
+-- A keyset signs knowledge shards and backups with an Ed25519 key derived from
+-- its X25519 private key, so only the public half is stored here. It is
+-- recorded whenever the private key is available (create, rotate, or the first
+-- signed export of an imported keyset) and lets shard imports trust shards
+-- signed by local keysets.
+ALTER TABLE pke_keysets
+    ADD COLUMN IF NOT EXISTS signing_public_key TEXT;
@@ -0,0 +1,46 @@
+-- Scheduled backup policies.
+--
+-- A policy runs a backup on a cron schedule (evaluated in UTC) and prunes
+-- older backups it created according to its retention counts. The scheduler
+-- advances next_run_at_utc when it queues a scheduled_backup job; each
+-- execution is recorded in backup_policy_run, which retention uses to find
+-- the files a policy owns. Policies cover the whole database, so both tables
+-- are shared rather than cloned per memory archive.
+CREATE TABLE IF NOT EXISTS backup_policy (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    name TEXT NOT NULL UNIQUE,
+    schedule TEXT NOT NULL,
+    backup_type TEXT NOT NULL DEFAULT 'snapshot' CHECK (backup_type IN ('snapshot', 'full')),
+    keyset TEXT,
+    keep_daily INTEGER NOT NULL DEFAULT 7 CHECK (keep_daily >= 0),
+    keep_weekly INTEGER NOT NULL DEFAULT 4 CHECK (keep_weekly >= 0),
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    next_run_at_utc TIMESTAMPTZ,
+    last_run_at_utc TIMESTAMPTZ,
+    last_status TEXT,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_backup_policy_due
+    ON backup_policy(next_run_at_utc)
+    WHERE enabled;
+
+CREATE TABLE IF NOT EXISTS backup_policy_run (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    policy_id UUID NOT NULL REFERENCES backup_policy(id) ON DELETE CASCADE,
+    job_id UUID,
+    status TEXT NOT NULL DEFAULT 'running'
+        CHECK (status IN ('running', 'succeeded', 'failed')),
+    filename TEXT,
+    size_bytes BIGINT,
+    error TEXT,
+    started_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at_utc TIMESTAMPTZ,
+    pruned_at_utc TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_backup_policy_run_policy
+    ON backup_policy_run(policy_id, started_at_utc DESC);
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'scheduled_backup';
@@ -0,0 +1,113 @@
+-- Cross-instance archive replication.
+--
+-- A sync peer names another Fortemi instance and the memory archives to
+-- replicate between them. The federation_sync job pulls the remote change
+-- feed and pushes the local one, keeping a (changed_at, note_id) cursor per
+-- direction on the peer row. Peers are instance configuration, so the table
+-- is shared rather than cloned per memory archive.
+--
+-- The change feed reads note.updated_at_utc / note.deleted_at for live and
+-- soft-deleted notes. Hard deletes leave a row in note_tombstone, which is
+-- cloned per archive; re-inserting a note (a restore or an applied sync
+-- change) clears its tombstone.
+CREATE TABLE IF NOT EXISTS sync_peer (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    name TEXT NOT NULL UNIQUE,
+    base_url TEXT NOT NULL,
+    api_token TEXT,
+    remote_memory TEXT,
+    local_memory TEXT,
+    direction TEXT NOT NULL DEFAULT 'both' CHECK (direction IN ('push', 'pull', 'both')),
+    conflict_strategy TEXT NOT NULL DEFAULT 'newest_wins'
+        CHECK (conflict_strategy IN ('newest_wins', 'incoming_wins', 'local_wins')),
+    schedule TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    pull_cursor_at TIMESTAMPTZ,
+    pull_cursor_note_id UUID,
+    push_cursor_at TIMESTAMPTZ,
+    push_cursor_note_id UUID,
+    next_run_at_utc TIMESTAMPTZ,
+    last_sync_at_utc TIMESTAMPTZ,
+    last_status TEXT,
+    last_error TEXT,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_peer_due
+    ON sync_peer(next_run_at_utc)
+    WHERE enabled;
+
+CREATE TABLE IF NOT EXISTS note_tombstone (
+    note_id UUID PRIMARY KEY,
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_tombstone_deleted_at
+    ON note_tombstone(deleted_at, note_id);
+
+-- Trigger functions address the tombstone table in the schema of the note
+-- table that fired, so archive schemas never write to public.
+CREATE OR REPLACE FUNCTION public.record_note_tombstone()
+RETURNS TRIGGER AS $$
+BEGIN
+    EXECUTE format(
+        'INSERT INTO %I.note_tombstone (note_id, deleted_at) VALUES ($1, NOW())
+         ON CONFLICT (note_id) DO UPDATE SET deleted_at = EXCLUDED.deleted_at',
+        TG_TABLE_SCHEMA
+    ) USING OLD.id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION public.clear_note_tombstone()
+RETURNS TRIGGER AS $$
+BEGIN
+    EXECUTE format('DELETE FROM %I.note_tombstone WHERE note_id = $1', TG_TABLE_SCHEMA)
+        USING NEW.id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DO $federation_sync$
+DECLARE
+    target_schema TEXT;
+BEGIN
+    FOR target_schema IN
+        SELECT 'public'
+        UNION
+        SELECT ar.schema_name
+        FROM public.archive_registry AS ar
+        WHERE ar.schema_name <> 'public'
+    LOOP
+        IF to_regclass(format('%I.note', target_schema)) IS NULL THEN
+            CONTINUE;
+        END IF;
+
+        IF target_schema <> 'public' THEN
+            EXECUTE format(
+                'CREATE TABLE IF NOT EXISTS %I.note_tombstone
+                    (LIKE public.note_tombstone INCLUDING ALL)',
+                target_schema
+            );
+        END IF;
+
+        EXECUTE format('DROP TRIGGER IF EXISTS trg_note_tombstone_record ON %I.note', target_schema);
+        EXECUTE format(
+            'CREATE TRIGGER trg_note_tombstone_record
+             AFTER DELETE ON %I.note
+             FOR EACH ROW EXECUTE FUNCTION public.record_note_tombstone()',
+            target_schema
+        );
+        EXECUTE format('DROP TRIGGER IF EXISTS trg_note_tombstone_clear ON %I.note', target_schema);
+        EXECUTE format(
+            'CREATE TRIGGER trg_note_tombstone_clear
+             AFTER INSERT ON %I.note
+             FOR EACH ROW EXECUTE FUNCTION public.clear_note_tombstone()',
+            target_schema
+        );
+    END LOOP;
+END
+$federation_sync$;
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'federation_sync';
@@ -0,0 +1,135 @@
+-- Note version retention policies.
+--
+-- Retention was a single global `versioning_max_history` count enforced by
+-- the snapshot trigger. A policy now combines a per-note version cap, a
+-- maximum age, and whether milestone versions are exempt. The global default
+-- stays in user_config; archives can override it with a row in
+-- archive_version_policy (keyed by schema like archive_inference_override).
+--
+-- The trigger keeps applying the count cap on every edit; the version_prune
+-- job applies the full policy, including age, to every archive.
+
+CREATE TABLE IF NOT EXISTS archive_version_policy (
+    schema_name TEXT PRIMARY KEY,
+    -- NULL means no cap / no age limit.
+    max_versions INTEGER CHECK (max_versions IS NULL OR max_versions > 0),
+    max_age_days INTEGER CHECK (max_age_days IS NULL OR max_age_days > 0),
+    keep_milestones BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO user_config (key, value) VALUES
+    ('versioning_keep_milestones', 'true'::jsonb)
+ON CONFLICT (key) DO NOTHING;
+
+-- Milestones: versions the user marked to survive pruning. The flag lives on
+-- the current content too, so the version being edited can be marked before
+-- it moves into the history.
+DO $version_retention$
+DECLARE
+    target_schema TEXT;
+BEGIN
+    FOR target_schema IN
+        SELECT 'public'
+        UNION
+        SELECT ar.schema_name
+        FROM public.archive_registry AS ar
+        WHERE ar.schema_name <> 'public'
+    LOOP
+        IF to_regclass(format('%I.note_original_history', target_schema)) IS NULL THEN
+            CONTINUE;
+        END IF;
+
+        EXECUTE format(
+            'ALTER TABLE %I.note_original
+                ADD COLUMN IF NOT EXISTS milestone BOOLEAN NOT NULL DEFAULT FALSE',
+            target_schema
+        );
+        EXECUTE format(
+            'ALTER TABLE %I.note_original_history
+                ADD COLUMN IF NOT EXISTS milestone BOOLEAN NOT NULL DEFAULT FALSE',
+            target_schema
+        );
+    END LOOP;
+END
+$version_retention$;
+
+CREATE OR REPLACE FUNCTION snapshot_original_on_update()
+RETURNS TRIGGER AS $$
+DECLARE
+  versioning_enabled BOOLEAN;
+  max_history INTEGER;
+  keep_milestones BOOLEAN;
+  current_tags JSONB;
+  snapshot_content TEXT;
+BEGIN
+  -- Check if versioning is enabled
+  SELECT COALESCE((value::text)::boolean, true) INTO versioning_enabled
+  FROM user_config WHERE key = 'versioning_enabled';
+
+  -- Only snapshot if content actually changed
+  IF versioning_enabled IS NOT FALSE AND OLD.content IS DISTINCT FROM NEW.content THEN
+    -- Get current tags for the note
+    SELECT COALESCE(jsonb_agg(tag_name), '[]'::jsonb) INTO current_tags
+    FROM note_tag WHERE note_id = OLD.note_id;
+
+    -- Build snapshot content with YAML frontmatter
+    snapshot_content := format(
+      E'---\nsnapshot_tags: %s\nsnapshot_at: "%s"\n---\n%s',
+      current_tags::text,
+      NOW()::text,
+      OLD.content
+    );
+
+    -- Insert the old version into history (ON CONFLICT handles restore scenarios
+    -- where the version_number already exists in history)
+    INSERT INTO note_original_history
+      (note_id, version_number, content, hash, created_by, milestone)
+    VALUES (OLD.note_id, OLD.version_number, snapshot_content, OLD.hash, 'user', OLD.milestone)
+    ON CONFLICT (note_id, version_number) DO UPDATE
+    SET content = EXCLUDED.content, hash = EXCLUDED.hash, created_at_utc = NOW(),
+        milestone = EXCLUDED.milestone;
+
+    -- Increment version number; the new version starts unmarked
+    NEW.version_number := OLD.version_number + 1;
+    NEW.milestone := FALSE;
+
+    -- The archive's policy wins over the global default
+    SELECT p.max_versions, p.keep_milestones INTO max_history, keep_milestones
+    FROM public.archive_version_policy p
+    WHERE p.schema_name = TG_TABLE_SCHEMA;
+
+    IF NOT FOUND THEN
+      SELECT COALESCE((value::text)::integer, 50) INTO max_history
+      FROM user_config WHERE key = 'versioning_max_history';
+      IF NOT FOUND THEN
+        max_history := 50;
+      END IF;
+      SELECT COALESCE((value::text)::boolean, true) INTO keep_milestones
+      FROM user_config WHERE key = 'versioning_keep_milestones';
+      IF NOT FOUND THEN
+        keep_milestones := TRUE;
+      END IF;
+    END IF;
+
+    -- Cleanup old versions if we exceed max_history
+    IF max_history IS NOT NULL THEN
+      DELETE FROM note_original_history
+      WHERE note_id = OLD.note_id
+        AND NOT (keep_milestones AND milestone)
+        AND version_number <= (
+          SELECT version_number FROM note_original_history
+          WHERE note_id = OLD.note_id
+          ORDER BY version_number DESC
+          OFFSET max_history
+          LIMIT 1
+        );
+    END IF;
+  END IF;
+
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'version_prune';
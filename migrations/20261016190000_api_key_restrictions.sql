@@ -0,0 +1,11 @@
+-- Per-API-key resource restrictions.
+--
+-- A key's scope (read/write/admin) says what kind of operation it may
+-- perform; these columns narrow where. NULL lists leave that dimension
+-- unrestricted. The auth and archive routing middleware enforce them on every
+-- request, independently of the configured authorization policy.
+
+ALTER TABLE api_key
+    ADD COLUMN IF NOT EXISTS allowed_archives TEXT[],
+    ADD COLUMN IF NOT EXISTS allowed_collections UUID[],
+    ADD COLUMN IF NOT EXISTS read_only BOOLEAN NOT NULL DEFAULT FALSE;
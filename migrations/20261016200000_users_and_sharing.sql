@@ -0,0 +1,82 @@
+-- Users, ownership and sharing.
+--
+-- OAuth tokens already carry the user_id of the person who authorized them.
+-- app_user maps that external identity to a stable UUID used as the owner of
+-- notes (note.owner_id, present since the initial schema), collections and
+-- archives. note_share_grant already existed for notes; collections get the
+-- same shape per archive, and archives a shared table keyed by archive id.
+--
+-- Rows with a NULL owner stay readable and writable by every caller.
+
+CREATE TABLE IF NOT EXISTS app_user (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    external_id TEXT NOT NULL UNIQUE,
+    display_name TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE archive_registry ADD COLUMN IF NOT EXISTS owner_id UUID;
+CREATE INDEX IF NOT EXISTS idx_archive_registry_owner
+    ON archive_registry(owner_id) WHERE owner_id IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS archive_share_grant (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    archive_id UUID NOT NULL REFERENCES archive_registry(id) ON DELETE CASCADE,
+    grantee_id UUID NOT NULL,
+    permission TEXT NOT NULL DEFAULT 'read' CHECK (permission IN ('read', 'write')),
+    granted_by UUID,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    UNIQUE (archive_id, grantee_id)
+);
+CREATE INDEX IF NOT EXISTS idx_archive_share_grant_grantee
+    ON archive_share_grant(grantee_id, archive_id) WHERE revoked_at IS NULL;
+
+-- Collection ownership and grants live with the collections in every
+-- archive schema.
+DO $users_and_sharing$
+DECLARE
+    target_schema TEXT;
+BEGIN
+    FOR target_schema IN
+        SELECT 'public'
+        UNION
+        SELECT ar.schema_name
+        FROM public.archive_registry AS ar
+        WHERE ar.schema_name <> 'public'
+    LOOP
+        IF to_regclass(format('%I.collection', target_schema)) IS NULL THEN
+            CONTINUE;
+        END IF;
+
+        EXECUTE format(
+            'ALTER TABLE %I.collection ADD COLUMN IF NOT EXISTS owner_id UUID',
+            target_schema
+        );
+        EXECUTE format(
+            'CREATE TABLE IF NOT EXISTS %I.collection_share_grant (
+                id UUID PRIMARY KEY DEFAULT uuidv7(),
+                collection_id UUID NOT NULL REFERENCES %I.collection(id) ON DELETE CASCADE,
+                grantee_id UUID NOT NULL,
+                permission TEXT NOT NULL DEFAULT ''read''
+                    CHECK (permission IN (''read'', ''write'')),
+                granted_by UUID,
+                granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                expires_at TIMESTAMPTZ,
+                revoked_at TIMESTAMPTZ,
+                UNIQUE (collection_id, grantee_id)
+            )',
+            target_schema,
+            target_schema
+        );
+        EXECUTE format(
+            'CREATE INDEX IF NOT EXISTS idx_collection_share_grant_grantee
+                ON %I.collection_share_grant(grantee_id, collection_id)
+                WHERE revoked_at IS NULL',
+            target_schema
+        );
+    END LOOP;
+END
+$users_and_sharing$;
@@ -0,0 +1,18 @@
+-- Per-principal daily usage counters.
+--
+-- One row per principal (API key, OAuth client or anonymous caller), UTC day
+-- and counter. Requests increment the row in place; optional daily quotas
+-- are checked against it before notes are created, searches run, or
+-- inference is called.
+
+CREATE TABLE IF NOT EXISTS usage_daily_counter (
+    principal TEXT NOT NULL,
+    day DATE NOT NULL,
+    counter TEXT NOT NULL
+        CHECK (counter IN ('notes_created', 'searches', 'inference_tokens')),
+    quantity BIGINT NOT NULL DEFAULT 0 CHECK (quantity >= 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (principal, day, counter)
+);
+
+CREATE INDEX IF NOT EXISTS idx_usage_daily_counter_day ON usage_daily_counter(day);
@@ -0,0 +1,59 @@
+-- Dependency-aware job chaining and pipeline runs.
+--
+-- A pending job is claimable only once every job it depends on has
+-- completed. When a job fails or is cancelled, its pending dependents are
+-- cancelled in turn (failure_code 'upstream_failed'); a pipeline re-run
+-- returns them to pending without touching steps that already completed.
+
+CREATE TABLE IF NOT EXISTS pipeline_run (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    pipeline TEXT NOT NULL CHECK (pipeline ~ '^[a-z][a-z0-9_]{0,63}$'),
+    note_id UUID REFERENCES note(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_pipeline_run_note ON pipeline_run(note_id, created_at DESC);
+
+ALTER TABLE job_queue
+    ADD COLUMN IF NOT EXISTS pipeline_run_id UUID REFERENCES pipeline_run(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_job_queue_pipeline_run
+    ON job_queue(pipeline_run_id)
+    WHERE pipeline_run_id IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS job_dependency (
+    job_id UUID NOT NULL REFERENCES job_queue(id) ON DELETE CASCADE,
+    depends_on UUID NOT NULL REFERENCES job_queue(id) ON DELETE CASCADE,
+    PRIMARY KEY (job_id, depends_on),
+    CHECK (job_id <> depends_on)
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_dependency_depends_on ON job_dependency(depends_on);
+
+-- Cancel pending dependents of a job that ended without completing. The
+-- update re-fires this trigger for each cancelled dependent, so failure
+-- propagates down the whole chain.
+CREATE OR REPLACE FUNCTION propagate_job_dependency_failure() RETURNS trigger AS $$
+BEGIN
+    UPDATE job_queue
+    SET status = 'cancelled'::job_status,
+        completed_at = NOW(),
+        error_message = 'Upstream job did not complete',
+        next_attempt_at = NULL,
+        failure_class = 'cancelled',
+        failure_code = 'upstream_failed'
+    WHERE status = 'pending'::job_status
+      AND id IN (SELECT job_id FROM job_dependency WHERE depends_on = NEW.id);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS job_queue_propagate_failure ON job_queue;
+CREATE TRIGGER job_queue_propagate_failure
+    AFTER UPDATE OF status ON job_queue
+    FOR EACH ROW
+    WHEN (
+        NEW.status IN ('failed'::job_status, 'cancelled'::job_status)
+        AND OLD.status IS DISTINCT FROM NEW.status
+    )
+    EXECUTE FUNCTION propagate_job_dependency_failure();
@@ -0,0 +1,14 @@
+-- Dead-letter queue lookups and poison-payload detection.
+--
+-- Failed jobs form the dead-letter queue. A retryable failure counts earlier
+-- failed attempts with the same job type and payload fingerprint; once the
+-- payload has failed often enough the job is quarantined with failure_class
+-- 'poison' instead of being retried.
+
+CREATE INDEX IF NOT EXISTS idx_job_attempt_payload_fingerprint
+    ON job_attempt (payload_fingerprint)
+    WHERE payload_fingerprint IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_job_queue_dead_letter
+    ON job_queue (completed_at DESC)
+    WHERE status = 'failed';
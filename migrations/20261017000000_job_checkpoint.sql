@@ -0,0 +1,10 @@
+-- Resumable job state.
+--
+-- Long-running handlers save their progress (last completed batch, frame or
+-- segment) while running. The checkpoint survives retries and stale-worker
+-- recovery so the next attempt resumes from it, and is cleared when the job
+-- completes.
+
+ALTER TABLE job_queue
+    ADD COLUMN IF NOT EXISTS checkpoint JSONB,
+    ADD COLUMN IF NOT EXISTS checkpoint_at TIMESTAMPTZ;
@@ -0,0 +1,24 @@
+-- Priority lanes for the job queue.
+--
+-- Interactive jobs (a user is waiting) are claimed ahead of batch jobs (bulk
+-- and housekeeping work) regardless of numeric priority; workers give the
+-- batch lane a weighted share of claims so it is never starved. Existing
+-- bulk job types move to the batch lane.
+
+ALTER TABLE job_queue
+    ADD COLUMN IF NOT EXISTS lane TEXT NOT NULL DEFAULT 'interactive'
+        CHECK (lane IN ('interactive', 'batch'));
+
+UPDATE job_queue
+SET lane = 'batch'
+WHERE status IN ('pending'::job_status, 'running'::job_status)
+  AND job_type::text IN (
+      're_embed_all', 'refresh_embedding_set', 'build_set_index',
+      'generate_fine_tuning_data', 'generate_coarse_embedding',
+      'graph_maintenance', 'blob_garbage_collection', 'scheduled_backup',
+      'federation_sync', 'version_prune'
+  );
+
+CREATE INDEX IF NOT EXISTS idx_job_queue_pending_lane
+    ON job_queue (lane, priority DESC, created_at)
+    WHERE status = 'pending';
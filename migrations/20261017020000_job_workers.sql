@@ -0,0 +1,31 @@
+-- Worker registry for running job workers on several API replicas.
+--
+-- Each worker registers a row and heartbeats it. Claims record the claiming
+-- worker on the job, so running jobs of a worker that stopped heartbeating
+-- can be reclaimed without waiting for the job timeout. One worker at a time
+-- holds the leader lease and performs the reclamation sweep.
+
+CREATE TABLE IF NOT EXISTS job_worker (
+    id UUID PRIMARY KEY,
+    hostname TEXT NOT NULL,
+    pid INTEGER NOT NULL,
+    version TEXT NOT NULL,
+    max_concurrent INTEGER NOT NULL,
+    heartbeat_timeout_secs INTEGER NOT NULL CHECK (heartbeat_timeout_secs > 0),
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_heartbeat_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    stopped_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS job_worker_leader (
+    singleton BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (singleton),
+    worker_id UUID NOT NULL,
+    lease_expires_at TIMESTAMPTZ NOT NULL
+);
+
+ALTER TABLE job_queue
+    ADD COLUMN IF NOT EXISTS worker_id UUID;
+
+CREATE INDEX IF NOT EXISTS idx_job_queue_running_worker
+    ON job_queue (worker_id)
+    WHERE status = 'running';
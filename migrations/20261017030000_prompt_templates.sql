@@ -0,0 +1,19 @@
+-- Versioned prompt templates for background AI jobs.
+--
+-- Each save of a prompt adds a new version; jobs use the newest version for
+-- their archive (schema_name), then the newest global version (schema_name
+-- IS NULL), then the built-in template compiled into the server. Archive
+-- rows are keyed by schema name like archive_inference_override, so the
+-- table is shared rather than cloned per memory archive.
+CREATE TABLE IF NOT EXISTS prompt_template (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    prompt_key TEXT NOT NULL,
+    schema_name TEXT,
+    version INTEGER NOT NULL CHECK (version >= 1),
+    content TEXT NOT NULL,
+    description TEXT,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_prompt_template_version
+    ON prompt_template (prompt_key, COALESCE(schema_name, ''), version);
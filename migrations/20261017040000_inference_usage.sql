@@ -0,0 +1,25 @@
+-- Token and cost accounting for generation calls made by background jobs.
+--
+-- The job worker writes one row per model a job used when the job finishes.
+-- Rows carry the archive's schema name, so the table is shared rather than
+-- cloned per memory archive. job_id is not a foreign key: usage outlives
+-- job rows removed by queue cleanup.
+CREATE TABLE IF NOT EXISTS inference_usage (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    job_id UUID,
+    job_type TEXT,
+    schema_name TEXT NOT NULL DEFAULT 'public',
+    model TEXT NOT NULL,
+    calls INTEGER NOT NULL CHECK (calls >= 1),
+    prompt_tokens BIGINT NOT NULL CHECK (prompt_tokens >= 0),
+    completion_tokens BIGINT NOT NULL CHECK (completion_tokens >= 0),
+    cost_usd DOUBLE PRECISION CHECK (cost_usd >= 0),
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_inference_usage_schema_created
+    ON inference_usage (schema_name, created_at_utc);
+
+CREATE INDEX IF NOT EXISTS idx_inference_usage_job
+    ON inference_usage (job_id)
+    WHERE job_id IS NOT NULL;
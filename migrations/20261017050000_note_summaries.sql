@@ -0,0 +1,14 @@
+-- AI-generated note summaries.
+-- One row per note, replaced each time the summarization job runs. Long notes
+-- are summarized chunk by chunk and reduced; chunk_count records how many
+-- chunks fed the summary. Per-memory-archive, cascades with its note.
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'summarization';
+
+CREATE TABLE IF NOT EXISTS note_summary (
+    note_id UUID PRIMARY KEY REFERENCES note(id) ON DELETE CASCADE,
+    summary TEXT NOT NULL,
+    model TEXT,
+    chunk_count INTEGER NOT NULL DEFAULT 1 CHECK (chunk_count >= 1),
+    generated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,17 @@
+-- Entity registry for LLM entity extraction.
+-- One row per distinct entity in a memory archive, keyed by type and
+-- normalized name. The note<->entity edges are the note_entity rows whose
+-- (entity_type, normalized_text) match; the entity extraction job replaces a
+-- note's rows on each run and removes registry entries no note mentions.
+
+CREATE TABLE IF NOT EXISTS entity (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    entity_type entity_type NOT NULL,
+    name TEXT NOT NULL,
+    normalized_name TEXT NOT NULL CHECK (normalized_name <> ''),
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (entity_type, normalized_name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_entity_type_normalized
+    ON note_entity (entity_type, normalized_text);
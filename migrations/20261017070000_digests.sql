@@ -0,0 +1,34 @@
+-- Scheduled activity digests.
+--
+-- A digest runs daily or weekly at a fixed UTC hour and summarizes the notes
+-- a memory archive created or updated during the period that just ended,
+-- optionally restricted to some collections. The result is written as a new
+-- note, announced as a digest.generated event for webhooks, or both. The
+-- scheduler advances next_run_at_utc when it queues a digest_generation job.
+-- Rows are keyed by schema like archive_version_policy, so the table is
+-- shared rather than cloned per memory archive.
+CREATE TABLE IF NOT EXISTS digest_config (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    schema_name TEXT NOT NULL,
+    name TEXT NOT NULL,
+    cadence TEXT NOT NULL CHECK (cadence IN ('daily', 'weekly')),
+    hour_utc SMALLINT NOT NULL DEFAULT 7 CHECK (hour_utc BETWEEN 0 AND 23),
+    collection_ids UUID[] NOT NULL DEFAULT '{}',
+    delivery TEXT[] NOT NULL DEFAULT '{note}'
+        CHECK (cardinality(delivery) > 0 AND delivery <@ ARRAY['note', 'webhook']),
+    note_collection_id UUID,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    next_run_at_utc TIMESTAMPTZ,
+    last_run_at_utc TIMESTAMPTZ,
+    last_status TEXT CHECK (last_status IN ('succeeded', 'empty', 'failed')),
+    last_note_id UUID,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (schema_name, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_digest_config_due
+    ON digest_config(next_run_at_utc)
+    WHERE enabled;
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'digest_generation';
@@ -0,0 +1,26 @@
+-- Topic modeling over note embeddings.
+-- The topic_modeling job clusters a memory archive's notes by their averaged
+-- embeddings in the default embedding set, asks the LLM to name each cluster,
+-- and replaces every topic row with the new result. A topic links to the
+-- candidate SKOS concept suggested for its label; the concept survives a
+-- later run that drops the topic.
+
+CREATE TABLE IF NOT EXISTS topic (
+    id UUID PRIMARY KEY DEFAULT uuidv7(),
+    label TEXT NOT NULL CHECK (label <> ''),
+    note_count INTEGER NOT NULL DEFAULT 0,
+    concept_id UUID REFERENCES skos_concept(id) ON DELETE SET NULL,
+    model TEXT,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS topic_member (
+    topic_id UUID NOT NULL REFERENCES topic(id) ON DELETE CASCADE,
+    note_id UUID NOT NULL REFERENCES note(id) ON DELETE CASCADE,
+    similarity REAL NOT NULL,
+    PRIMARY KEY (topic_id, note_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_topic_member_note ON topic_member(note_id);
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'topic_modeling';
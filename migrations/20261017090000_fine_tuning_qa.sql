@@ -0,0 +1,28 @@
+-- Question-answer samples for fine-tuning datasets.
+-- The generate_fine_tuning_data job now stores an answer drawn from the note
+-- alongside each generated question, so datasets export as chat or
+-- instruction-tuning records. Samples written before this have no answer
+-- and are left out of exports.
+
+DO $fine_tuning_qa$
+DECLARE
+    target_schema TEXT;
+BEGIN
+    FOR target_schema IN
+        SELECT 'public'
+        UNION
+        SELECT ar.schema_name
+        FROM public.archive_registry AS ar
+        WHERE ar.schema_name <> 'public'
+    LOOP
+        IF to_regclass(format('%I.fine_tuning_sample', target_schema)) IS NULL THEN
+            CONTINUE;
+        END IF;
+
+        EXECUTE format(
+            'ALTER TABLE %I.fine_tuning_sample ADD COLUMN IF NOT EXISTS answer TEXT',
+            target_schema
+        );
+    END LOOP;
+END
+$fine_tuning_qa$;
@@ -0,0 +1,30 @@
+-- CLIP image embeddings for image similarity search.
+-- The image_embedding job embeds each image attachment after extraction and
+-- stores the vector in attachment_embedding.clip_vector under the archive's
+-- "images" embedding set. The set is created on first use, per archive,
+-- against the embedding config seeded here.
+
+INSERT INTO embedding_config (
+    name,
+    description,
+    model,
+    dimension,
+    is_default,
+    provider,
+    content_types,
+    strengths,
+    recommended_for
+) VALUES (
+    'clip-vit-b-32',
+    'CLIP ViT-B/32 image and text embeddings (512 dimensions) for image similarity search',
+    'openai/clip-vit-base-patch32',
+    512,
+    FALSE,
+    'custom',
+    ARRAY['image'],
+    ARRAY['image', 'multimodal'],
+    ARRAY['image-search', 'text-to-image']
+)
+ON CONFLICT (name) DO NOTHING;
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'image_embedding';
@@ -0,0 +1,15 @@
+-- Transcript timestamps for embedding chunks.
+-- When a note's content carries an audio or video transcript, the embedding
+-- job records which attachment each chunk came from and the playback range
+-- of the transcript segments it covers, so search hits can deep-link into
+-- the media. All three columns stay NULL for chunks without a transcript.
+
+ALTER TABLE embedding
+    ADD COLUMN IF NOT EXISTS attachment_id UUID REFERENCES attachment(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS start_secs DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS end_secs DOUBLE PRECISION;
+
+COMMENT ON COLUMN embedding.start_secs IS
+    'Start of the transcript segments covered by this chunk, in seconds into attachment_id; NULL for non-transcript chunks';
+COMMENT ON COLUMN embedding.end_secs IS
+    'End of the transcript segments covered by this chunk, in seconds into attachment_id; NULL for non-transcript chunks';
@@ -0,0 +1,2 @@
+-- Add ebook extraction strategy enum value for EPUB/MOBI books
+ALTER TYPE extraction_strategy ADD VALUE IF NOT EXISTS 'ebook';
@@ -0,0 +1,2 @@
+-- Add spreadsheet extraction strategy enum value for workbooks and CSV/TSV files
+ALTER TYPE extraction_strategy ADD VALUE IF NOT EXISTS 'spreadsheet';
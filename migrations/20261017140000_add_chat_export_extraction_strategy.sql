@@ -0,0 +1,2 @@
+-- Add chat export extraction strategy enum value for Slack/WhatsApp exports
+ALTER TYPE extraction_strategy ADD VALUE IF NOT EXISTS 'chat_export';
@@ -0,0 +1,2 @@
+-- Add notebook extraction strategy enum value for Jupyter notebooks
+ALTER TYPE extraction_strategy ADD VALUE IF NOT EXISTS 'notebook';
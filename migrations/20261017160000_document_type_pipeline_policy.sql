@@ -0,0 +1,7 @@
+-- Per-document-type extraction and pipeline policy.
+-- Unset keys keep the default pipeline behaviour; see PipelinePolicy.
+ALTER TABLE document_type ADD COLUMN IF NOT EXISTS
+    pipeline_policy JSONB NOT NULL DEFAULT '{}';
+
+COMMENT ON COLUMN document_type.pipeline_policy IS
+    'Policy applied to notes of this type (e.g., {"extraction_strategy": "pdf_ocr", "ai_revision": false, "default_tags": ["contracts"]})';
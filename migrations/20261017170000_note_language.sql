@@ -0,0 +1,9 @@
+-- Detected natural language of a note's content (ISO 639-1, or ISO 639-3
+-- for languages without a two-letter code). NULL when detection was
+-- inconclusive, disabled, or the note is encrypted.
+ALTER TABLE note ADD COLUMN IF NOT EXISTS language TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_note_language ON note (language) WHERE language IS NOT NULL;
+
+COMMENT ON COLUMN note.language IS
+    'Detected content language (e.g., en, de, ja); drives FTS config, embedding set and prompt language';
@@ -0,0 +1,35 @@
+-- Automatic note translation.
+--
+-- note_translation holds one machine translation per note into its archive's
+-- target language, replaced each time the translation job runs. The note's
+-- original and revised content are left untouched; full-text search also
+-- matches the translation. Per-memory-archive, cascades with its note.
+--
+-- Translation is enabled per archive with a row in
+-- archive_translation_setting, keyed by schema like archive_version_policy
+-- ('public' for the default archive).
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'translation';
+
+CREATE TABLE IF NOT EXISTS note_translation (
+    note_id UUID PRIMARY KEY REFERENCES note(id) ON DELETE CASCADE,
+    source_language TEXT NOT NULL,
+    target_language TEXT NOT NULL,
+    content TEXT NOT NULL,
+    model TEXT,
+    chunk_count INTEGER NOT NULL DEFAULT 1 CHECK (chunk_count >= 1),
+    generated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_translation_fts
+    ON note_translation USING gin (to_tsvector('public.matric_english', content));
+CREATE INDEX IF NOT EXISTS idx_note_translation_fts_simple
+    ON note_translation USING gin (to_tsvector('public.matric_simple', content));
+
+CREATE TABLE IF NOT EXISTS archive_translation_setting (
+    schema_name TEXT PRIMARY KEY,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    target_language TEXT NOT NULL DEFAULT 'en',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
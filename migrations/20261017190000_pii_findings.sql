@@ -0,0 +1,29 @@
+-- PII detection.
+--
+-- pii_findings holds what the pii_scan job found in a note's original content
+-- (attachment_id NULL) or in one of its attachments' extracted text: the kind
+-- of value, its byte range, and a SHA-256 of the normalized value. The value
+-- itself is never stored. Each scan replaces the note's findings.
+-- Per-memory-archive, cascades with its note and attachment.
+--
+-- blocks_indexing is set when the scan ran with PII_REDACTION_MODE=block_indexing;
+-- full-text search skips notes with such a finding.
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'pii_scan';
+
+CREATE TABLE IF NOT EXISTS pii_findings (
+    id UUID PRIMARY KEY,
+    note_id UUID NOT NULL REFERENCES note(id) ON DELETE CASCADE,
+    attachment_id UUID REFERENCES attachment(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('email', 'phone', 'credit_card', 'national_id')),
+    detector TEXT NOT NULL CHECK (detector IN ('regex', 'llm')),
+    start_offset INTEGER NOT NULL CHECK (start_offset >= 0),
+    end_offset INTEGER NOT NULL CHECK (end_offset > start_offset),
+    value_hash TEXT NOT NULL,
+    blocks_indexing BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_pii_findings_note ON pii_findings(note_id);
+CREATE INDEX IF NOT EXISTS idx_pii_findings_blocking
+    ON pii_findings(note_id) WHERE blocks_indexing;
@@ -0,0 +1,22 @@
+-- Ingestion quarantine.
+--
+-- ingestion_quarantine records notes and attachments that an ingestion policy
+-- (size, MIME type, LLM content classification) held back for review. A row
+-- without attachment_id quarantines the note itself: its NLP pipeline is not
+-- queued and full-text search skips it. A row with attachment_id quarantines
+-- that attachment, whose status is set to 'quarantined' and whose extraction
+-- is not queued. Denied items are never stored; they are only audited.
+-- Per-memory-archive, cascades with its note and attachment.
+
+CREATE TABLE IF NOT EXISTS ingestion_quarantine (
+    id UUID PRIMARY KEY,
+    note_id UUID NOT NULL REFERENCES note(id) ON DELETE CASCADE,
+    attachment_id UUID REFERENCES attachment(id) ON DELETE CASCADE,
+    policy TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ingestion_quarantine_note ON ingestion_quarantine(note_id);
+CREATE INDEX IF NOT EXISTS idx_ingestion_quarantine_created
+    ON ingestion_quarantine(created_at_utc DESC);
@@ -0,0 +1,8 @@
+-- Webhook payload filters.
+--
+-- Each entry is a predicate such as `.payload.tags contains "project/x"`,
+-- evaluated against the event envelope before delivery; all must hold.
+-- Expressions are validated by the API when the webhook is created or
+-- updated. Empty means every event of the subscribed types is delivered.
+
+ALTER TABLE webhook ADD COLUMN IF NOT EXISTS filters TEXT[] NOT NULL DEFAULT '{}';
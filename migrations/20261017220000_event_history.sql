@@ -0,0 +1,25 @@
+-- Durable event history for the activity feed.
+--
+-- events persists selected EventBus envelopes (domain mutations by default)
+-- so the activity feed and SSE/WebSocket history replay survive restarts and
+-- the bounded in-memory replay buffers. Shared across memories: `memory` holds
+-- the archive an event was scoped to (NULL for system events). Rows older than
+-- EVENT_HISTORY_RETENTION_DAYS are pruned by the API server.
+
+CREATE TABLE IF NOT EXISTS events (
+    event_id UUID PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    memory TEXT,
+    entity_type TEXT,
+    entity_id TEXT,
+    envelope JSONB NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT events_event_type_present CHECK (length(trim(event_type)) > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_events_memory ON events (memory, event_id DESC);
+CREATE INDEX IF NOT EXISTS idx_events_type ON events (event_type, event_id DESC);
+CREATE INDEX IF NOT EXISTS idx_events_entity ON events (entity_id, event_id DESC)
+    WHERE entity_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_events_occurred ON events (occurred_at);
@@ -0,0 +1,7 @@
+-- Job type for merging one memory archive into another.
+--
+-- archive_merge copies every row of a source archive into a target archive
+-- in one transaction and, for moves, drops the source afterwards. It runs in
+-- the batch lane with the other bulk jobs.
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'archive_merge';
@@ -0,0 +1,15 @@
+-- Per-archive storage quotas.
+--
+-- One optional row per archive, keyed by schema like archive_version_policy
+-- ('public' for the default archive). A NULL limit is unlimited; an archive
+-- without a row has no quota. Limits are enforced when notes, attachments
+-- and embeddings are ingested.
+
+CREATE TABLE IF NOT EXISTS archive_quota (
+    schema_name TEXT PRIMARY KEY,
+    max_notes BIGINT CHECK (max_notes >= 0),
+    max_attachment_bytes BIGINT CHECK (max_attachment_bytes >= 0),
+    max_embeddings BIGINT CHECK (max_embeddings >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,9 @@
+-- Job type for purging trashed notes.
+--
+-- trash_purge hard-deletes soft-deleted notes: the ones named by a bulk
+-- purge request, or, when run periodically, every note that has been in the
+-- trash for longer than TRASH_RETENTION_DAYS. It runs in the batch lane with
+-- the other housekeeping jobs.
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'trash_purge';
+
@@ -0,0 +1,42 @@
+-- Concept suggestion review queue.
+--
+-- With CONCEPT_REVIEW_MODE on, concept tagging holds suggestions scoring
+-- below their concept's review threshold in concept_suggestion instead of
+-- tagging the note. Accepting one tags the note; rejecting one keeps the
+-- tagger from suggesting the concept for that note again. Each review moves
+-- the concept's threshold in concept_review_threshold; concepts without a
+-- row use CONCEPT_REVIEW_THRESHOLD.
+-- Per-memory-archive, cascades with its note and concept.
+
+CREATE TABLE IF NOT EXISTS concept_suggestion (
+    id UUID PRIMARY KEY,
+    note_id UUID NOT NULL REFERENCES note(id) ON DELETE CASCADE,
+    concept_id UUID NOT NULL REFERENCES skos_concept(id) ON DELETE CASCADE,
+    confidence REAL NOT NULL CHECK (confidence >= 0 AND confidence <= 1),
+    threshold REAL NOT NULL CHECK (threshold >= 0 AND threshold <= 1),
+    relevance_score REAL NOT NULL DEFAULT 1.0,
+    is_primary BOOLEAN NOT NULL DEFAULT FALSE,
+    evidence TEXT,
+    extraction_method TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'accepted', 'rejected')),
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    reviewed_at_utc TIMESTAMPTZ,
+    reviewed_by TEXT
+);
+
+-- One open suggestion per note and concept; later runs refresh it.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_concept_suggestion_pending
+    ON concept_suggestion(note_id, concept_id) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_concept_suggestion_queue
+    ON concept_suggestion(status, confidence DESC, created_at_utc);
+CREATE INDEX IF NOT EXISTS idx_concept_suggestion_concept
+    ON concept_suggestion(concept_id);
+
+CREATE TABLE IF NOT EXISTS concept_review_threshold (
+    concept_id UUID PRIMARY KEY REFERENCES skos_concept(id) ON DELETE CASCADE,
+    threshold REAL NOT NULL CHECK (threshold >= 0 AND threshold <= 1),
+    accepted_count INTEGER NOT NULL DEFAULT 0 CHECK (accepted_count >= 0),
+    rejected_count INTEGER NOT NULL DEFAULT 0 CHECK (rejected_count >= 0),
+    updated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
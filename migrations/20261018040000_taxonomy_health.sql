@@ -0,0 +1,21 @@
+-- Taxonomy health reports.
+--
+-- The taxonomy_health job checks an archive's active concepts for orphans,
+-- over-deep chains, hierarchy cycles, near-duplicate labels and unused
+-- concepts, refreshes skos_concept.antipatterns, and stores the findings with
+-- suggested fixes as a report. Each run replaces the previous report.
+-- Per-memory-archive.
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'taxonomy_health';
+
+ALTER TYPE tag_antipattern ADD VALUE IF NOT EXISTS 'near_duplicate';
+ALTER TYPE tag_antipattern ADD VALUE IF NOT EXISTS 'unused';
+
+CREATE TABLE IF NOT EXISTS taxonomy_health_report (
+    id UUID PRIMARY KEY,
+    generated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    report JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_taxonomy_health_report_generated
+    ON taxonomy_health_report(generated_at_utc DESC);
@@ -0,0 +1,30 @@
+-- Smart collection rules.
+--
+-- A collection with a row in collection_rule is a smart collection: the
+-- collection_rule_sync job files notes matching the stored filter (tags,
+-- document type, creation dates, full-text query) into it. Only notes not in
+-- any collection are filed, and each one is recorded in collection_rule_member
+-- so later syncs take it back out once it stops matching. Notes filed by hand
+-- have no member row and are never moved by the rule.
+-- Per-memory-archive, cascades with its collection and note.
+
+CREATE TABLE IF NOT EXISTS collection_rule (
+    collection_id UUID PRIMARY KEY REFERENCES collection(id) ON DELETE CASCADE,
+    filter JSONB NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    last_synced_at_utc TIMESTAMPTZ,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS collection_rule_member (
+    collection_id UUID NOT NULL REFERENCES collection_rule(collection_id) ON DELETE CASCADE,
+    note_id UUID NOT NULL REFERENCES note(id) ON DELETE CASCADE,
+    added_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (collection_id, note_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_collection_rule_member_note
+    ON collection_rule_member(note_id);
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'collection_rule_sync';
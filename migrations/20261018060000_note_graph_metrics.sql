@@ -0,0 +1,23 @@
+-- Note importance and community metrics over the link graph.
+--
+-- The `analytics` step of the graph_maintenance job replaces every row:
+-- PageRank (and `importance`, PageRank scaled so the top note is 1), sampled
+-- betweenness centrality, the label propagation community and whether the
+-- note bridges communities. Search reads `importance` for its optional graph
+-- boost. Per-memory-archive, cascades with the note.
+
+CREATE TABLE IF NOT EXISTS note_graph_metric (
+    note_id UUID PRIMARY KEY REFERENCES note(id) ON DELETE CASCADE,
+    pagerank DOUBLE PRECISION NOT NULL,
+    importance DOUBLE PRECISION NOT NULL CHECK (importance >= 0 AND importance <= 1),
+    betweenness DOUBLE PRECISION NOT NULL,
+    community INTEGER NOT NULL,
+    degree INTEGER NOT NULL,
+    is_bridge BOOLEAN NOT NULL DEFAULT FALSE,
+    computed_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_graph_metric_pagerank
+    ON note_graph_metric(pagerank DESC);
+CREATE INDEX IF NOT EXISTS idx_note_graph_metric_bridge
+    ON note_graph_metric(betweenness DESC) WHERE is_bridge;
@@ -0,0 +1,38 @@
+-- Link suggestion feedback.
+--
+-- GET /api/v1/notes/{id}/link-suggestions ranks notes not yet linked to a
+-- note. Accepting a suggestion links the pair; rejecting one keeps the pair
+-- out of later suggestions. Each decision is kept in link_suggestion_feedback
+-- (one row per unordered pair, the latest decision wins) and moves the
+-- archive's offset in link_threshold_tuning, which the linker adds to its
+-- similarity thresholds. Archives without a row use an offset of 0.
+-- Per-memory-archive, cascades with either note.
+
+CREATE TABLE IF NOT EXISTS link_suggestion_feedback (
+    id UUID PRIMARY KEY,
+    note_id UUID NOT NULL REFERENCES note(id) ON DELETE CASCADE,
+    target_note_id UUID NOT NULL REFERENCES note(id) ON DELETE CASCADE,
+    accepted BOOLEAN NOT NULL,
+    score REAL NOT NULL CHECK (score >= 0 AND score <= 1),
+    reviewed_by TEXT,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (note_id <> target_note_id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_link_suggestion_feedback_pair
+    ON link_suggestion_feedback (
+        LEAST(note_id, target_note_id),
+        GREATEST(note_id, target_note_id)
+    );
+CREATE INDEX IF NOT EXISTS idx_link_suggestion_feedback_target
+    ON link_suggestion_feedback(target_note_id);
+
+-- A single row per archive.
+CREATE TABLE IF NOT EXISTS link_threshold_tuning (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    threshold_offset REAL NOT NULL DEFAULT 0
+        CHECK (threshold_offset >= -0.15 AND threshold_offset <= 0.15),
+    accepted_count INTEGER NOT NULL DEFAULT 0 CHECK (accepted_count >= 0),
+    rejected_count INTEGER NOT NULL DEFAULT 0 CHECK (rejected_count >= 0),
+    updated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
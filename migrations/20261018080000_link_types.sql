@@ -0,0 +1,15 @@
+-- Custom link types.
+--
+-- POST /api/v1/notes/{id}/links creates a typed link: a link whose kind names
+-- the relation it asserts. Every archive accepts the built-in types
+-- (supports, contradicts, elaborates, cites, extends, implements, references,
+-- related); link_type holds the custom types registered for the archive.
+-- Links of a symmetric type are created in both directions.
+-- Per-memory-archive.
+
+CREATE TABLE IF NOT EXISTS link_type (
+    name TEXT PRIMARY KEY CHECK (name ~ '^[a-z][a-z0-9_-]{0,63}$'),
+    description TEXT CHECK (char_length(description) <= 500),
+    symmetric BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
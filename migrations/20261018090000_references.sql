@@ -0,0 +1,41 @@
+-- Citations and bibliography.
+--
+-- reference holds each work cited anywhere in the archive once, keyed by
+-- (kind, identifier): a lowercased DOI, a versionless arXiv ID, a URL, or the
+-- key of a BibTeX entry that has neither. Metadata comes from BibTeX entries
+-- in notes and, for DOIs, from Crossref (resolved_at_utc is set then).
+-- note_reference records which notes cite which works; the citation_extraction
+-- job replaces a note's rows on every run and drops references no note cites.
+-- The table is singular because REFERENCES is a reserved word.
+-- Per-memory-archive, cascades with its note.
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'citation_extraction';
+
+CREATE TABLE IF NOT EXISTS reference (
+    id UUID PRIMARY KEY,
+    kind TEXT NOT NULL CHECK (kind IN ('doi', 'arxiv', 'url', 'bibtex')),
+    identifier TEXT NOT NULL,
+    title TEXT,
+    authors TEXT[] NOT NULL DEFAULT '{}',
+    year INTEGER,
+    venue TEXT,
+    publisher TEXT,
+    url TEXT,
+    doi TEXT,
+    entry_type TEXT,
+    bibtex_key TEXT,
+    resolved_at_utc TIMESTAMPTZ,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (kind, identifier)
+);
+
+CREATE TABLE IF NOT EXISTS note_reference (
+    note_id UUID NOT NULL REFERENCES note(id) ON DELETE CASCADE,
+    reference_id UUID NOT NULL REFERENCES reference(id) ON DELETE CASCADE,
+    occurrences INTEGER NOT NULL DEFAULT 1 CHECK (occurrences > 0),
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (note_id, reference_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_reference_reference ON note_reference(reference_id);
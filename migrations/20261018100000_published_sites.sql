@@ -0,0 +1,19 @@
+-- Public, read-only sites for published collections.
+--
+-- One row per published collection, shared and keyed by schema like
+-- archive_quota ('public' for the default archive). The slug is the site's
+-- path segment, /public/{slug}, and is unique across archives. Pages are
+-- rendered from the collection's notes on every request; only notes with
+-- visibility 'public' that are neither deleted nor encrypted are served.
+
+CREATE TABLE IF NOT EXISTS published_site (
+    slug TEXT PRIMARY KEY CHECK (slug ~ '^[a-z0-9]+(-[a-z0-9]+)*$' AND length(slug) <= 80),
+    schema_name TEXT NOT NULL,
+    collection_id UUID NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    include_subcollections BOOLEAN NOT NULL DEFAULT TRUE,
+    published_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (schema_name, collection_id)
+);
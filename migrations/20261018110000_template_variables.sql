@@ -0,0 +1,45 @@
+-- Typed template variables and template versions.
+--
+-- note_template.variables declares the typed variables ({name, type,
+-- required, default, ...}) a template's placeholders take; an empty array
+-- keeps free-form substitution. Every update raises note_template.version
+-- and keeps the replaced version in note_template_version, so notes can
+-- still be made from it. Per-memory-archive, cascades with its template.
+
+DO $template_variables$
+DECLARE
+    target_schema TEXT;
+BEGIN
+    FOR target_schema IN
+        SELECT 'public'
+        UNION
+        SELECT ar.schema_name
+        FROM public.archive_registry AS ar
+        WHERE ar.schema_name <> 'public'
+    LOOP
+        IF to_regclass(format('%I.note_template', target_schema)) IS NULL THEN
+            CONTINUE;
+        END IF;
+
+        EXECUTE format(
+            'ALTER TABLE %I.note_template
+                 ADD COLUMN IF NOT EXISTS variables JSONB NOT NULL DEFAULT ''[]''::jsonb,
+                 ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1',
+            target_schema
+        );
+    END LOOP;
+END
+$template_variables$;
+
+CREATE TABLE IF NOT EXISTS note_template_version (
+    template_id UUID NOT NULL REFERENCES note_template(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL CHECK (version >= 1),
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    content TEXT NOT NULL,
+    format VARCHAR(50) NOT NULL,
+    default_tags TEXT[],
+    variables JSONB NOT NULL DEFAULT '[]'::jsonb,
+    created_at_utc TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (template_id, version)
+);
@@ -0,0 +1,28 @@
+-- Daily journal notes.
+--
+-- GET/POST /api/v1/journal/today returns the archive's note for the current
+-- day, creating it on first use. journal_entry holds one note per calendar
+-- day in the journal time zone; journal_settings (a single row per archive,
+-- defaults when missing) holds the template, collection, title format and
+-- time zone journal notes are made with. When link_notes is on, each note
+-- created on a day with a journal note is linked from it with a 'journal'
+-- link. Per-memory-archive, cascades with its note.
+
+CREATE TABLE IF NOT EXISTS journal_settings (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    template_id UUID REFERENCES note_template(id) ON DELETE SET NULL,
+    collection_id UUID REFERENCES collection(id) ON DELETE SET NULL,
+    title_format TEXT NOT NULL DEFAULT '%Y-%m-%d'
+        CHECK (char_length(title_format) BETWEEN 1 AND 100),
+    timezone TEXT NOT NULL DEFAULT 'UTC',
+    link_notes BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS journal_entry (
+    day DATE PRIMARY KEY,
+    note_id UUID NOT NULL REFERENCES note(id) ON DELETE CASCADE,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_journal_entry_note ON journal_entry(note_id);
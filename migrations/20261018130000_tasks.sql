@@ -0,0 +1,27 @@
+-- Tasks found in notes.
+--
+-- The task_extraction job replaces a note's rows on every run: one per
+-- Markdown checkbox ('checkbox', with the line it is on) and, when
+-- TASK_LLM_EXTRACTION is enabled, one per action item the model found in
+-- prose ('llm'). Checkbox status follows the box in the note; action items
+-- keep their status across runs, matched by their text. Completing a
+-- checkbox task through the API ticks its box in the note. Per-memory-archive,
+-- cascades with its note.
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'task_extraction';
+
+CREATE TABLE IF NOT EXISTS task (
+    id UUID PRIMARY KEY,
+    note_id UUID NOT NULL REFERENCES note(id) ON DELETE CASCADE,
+    text TEXT NOT NULL CHECK (char_length(text) BETWEEN 1 AND 500),
+    status TEXT NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'done')),
+    source TEXT NOT NULL CHECK (source IN ('checkbox', 'llm')),
+    due_date DATE,
+    line INTEGER CHECK (line >= 0),
+    completed_at_utc TIMESTAMPTZ,
+    created_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_note ON task(note_id);
+CREATE INDEX IF NOT EXISTS idx_task_status_due ON task(status, due_date);
@@ -0,0 +1,20 @@
+-- Notes opened, per principal.
+--
+-- One row per principal and note, upserted in batches by the API as notes
+-- are opened with GET /api/v1/notes/{id}. Principals are `user:<id>` for
+-- signed-in users, `api-key:<id>` or `oauth:<client_id>` otherwise;
+-- anonymous requests are not recorded. Backs /api/v1/notes/recently-viewed
+-- and the personal recency boost of search. Per-memory-archive, cascades
+-- with its note.
+
+CREATE TABLE IF NOT EXISTS note_view (
+    principal TEXT NOT NULL CHECK (char_length(principal) BETWEEN 1 AND 300),
+    note_id UUID NOT NULL REFERENCES note(id) ON DELETE CASCADE,
+    view_count INTEGER NOT NULL DEFAULT 1 CHECK (view_count > 0),
+    first_viewed_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_viewed_at_utc TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (principal, note_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_view_recent
+    ON note_view(principal, last_viewed_at_utc DESC);
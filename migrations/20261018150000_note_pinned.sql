@@ -0,0 +1,23 @@
+-- Pinned and prioritised notes.
+--
+-- Pinned notes are listed by GET /api/v1/notes/pinned, highest priority
+-- first, and search keeps places at the top of its results for pinned
+-- notes matching the query. Priority (0-100) only orders pinned notes.
+ALTER TABLE note ADD COLUMN IF NOT EXISTS pinned BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE note ADD COLUMN IF NOT EXISTS priority INTEGER NOT NULL DEFAULT 0;
+
+DO $$
+BEGIN
+    ALTER TABLE note ADD CONSTRAINT note_priority_range CHECK (priority BETWEEN 0 AND 100);
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_note_pinned
+    ON note (priority DESC, updated_at_utc DESC)
+    WHERE pinned AND deleted_at IS NULL;
+
+COMMENT ON COLUMN note.pinned IS
+    'Listed by /api/v1/notes/pinned and kept near the top of matching search results';
+COMMENT ON COLUMN note.priority IS
+    'Order among pinned notes, 0-100, higher first';
@@ -0,0 +1,6 @@
+-- Per-document-type JSON Schema for note metadata.
+-- NULL accepts any metadata; see validate_note_metadata.
+ALTER TABLE document_type ADD COLUMN IF NOT EXISTS metadata_schema JSONB;
+
+COMMENT ON COLUMN document_type.metadata_schema IS
+    'JSON Schema that metadata of notes of this type must satisfy (e.g., {"type": "object", "required": ["status"]})';
@@ -0,0 +1,8 @@
+-- Job type for filtered bulk note updates.
+--
+-- bulk_note_update applies one patch (tags, collection, metadata keys,
+-- archived flag) to every note matching a filter, working through them in
+-- batches and reporting progress. It runs in the batch lane so large edits
+-- do not hold up interactive jobs.
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'bulk_note_update';
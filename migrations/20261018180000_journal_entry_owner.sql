@@ -0,0 +1,16 @@
+-- One journal note per principal.
+--
+-- journal_entry gains the owner of the day's journal note: a signed-in
+-- user gets a journal note of their own, owned by them, that only links
+-- the notes they own. Requests acting for no user share the entry without
+-- an owner, which links notes without an owner. Existing entries have no
+-- owner. Per-memory-archive.
+
+ALTER TABLE journal_entry ADD COLUMN IF NOT EXISTS owner_id UUID;
+ALTER TABLE journal_entry DROP CONSTRAINT IF EXISTS journal_entry_pkey;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_journal_entry_day_owner
+    ON journal_entry (day, owner_id) NULLS NOT DISTINCT;
+
+COMMENT ON COLUMN journal_entry.owner_id IS
+    'User whose journal note this is; NULL for the journal of requests without a user';
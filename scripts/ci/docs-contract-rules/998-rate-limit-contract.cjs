@@ -3,12 +3,20 @@
 const CURRENT_CE_ENV_VARS = Object.freeze([
   "RATE_LIMIT_ENABLED",
   "RATE_LIMIT_REQUESTS",
+  "RATE_LIMIT_SEARCH_REQUESTS",
+  "RATE_LIMIT_INGEST_REQUESTS",
+  "RATE_LIMIT_ADMIN_REQUESTS",
   "RATE_LIMIT_PERIOD_SECS",
 ]);
-const CURRENT_CE_HEADERS = Object.freeze(["Retry-After"]);
+const CURRENT_CE_HEADERS = Object.freeze([
+  "Retry-After",
+  "X-RateLimit-Limit",
+  "X-RateLimit-Remaining",
+  "X-RateLimit-Reset",
+  "X-RateLimit-Bucket",
+]);
 const FUTURE_HOSTED_HEADERS = Object.freeze(["RateLimit", "RateLimit-Policy"]);
 const LEGACY_HEADERS = Object.freeze([
-  "X-RateLimit-*",
   "RateLimit-Limit",
   "RateLimit-Remaining",
   "RateLimit-Reset",
@@ -49,14 +57,14 @@ module.exports = {
   positiveFixtures: [
     "RATE_LIMIT_PER_MINUTE=100",
     "RATE_LIMIT_PER_TENANT=true",
-    "Current response headers: X-RateLimit-Limit",
+    "Current response headers: RateLimit-Remaining",
     "Primary target: RateLimit-Remaining",
   ],
   negativeFixtures: [
     "RATE_LIMIT_ENABLED=true",
     "RATE_LIMIT_REQUESTS=100",
     "RATE_LIMIT_PERIOD_SECS=60",
-    "Legacy compatibility only: X-RateLimit-Limit",
+    "Current response headers: X-RateLimit-Limit",
     "Historical only: RateLimit-Remaining",
     "Future target: RateLimit-Policy and RateLimit",
   ],
@@ -74,7 +82,7 @@ module.exports = {
         );
       },
       remediation:
-        "Keep the CE runtime source of truth on RATE_LIMIT_ENABLED, RATE_LIMIT_REQUESTS, and RATE_LIMIT_PERIOD_SECS.",
+        "Keep the CE runtime source of truth on the RATE_LIMIT_* variables listed in this rule.",
     },
     {
       id: "rate-limit-public-api-contract",
@@ -84,12 +92,12 @@ module.exports = {
       validate(content) {
         return (
           CURRENT_CE_ENV_VARS.every((name) => content.includes(`\`${name}\``)) &&
-          /carries only\s+`Retry-After`/.test(content) &&
+          CURRENT_CE_HEADERS.every((name) => content.includes(`\`${name}\``)) &&
           content.includes("future hosted quota")
         );
       },
       remediation:
-        "Document all three current CE env vars, the current Retry-After-only 429, and a separately labeled future hosted contract.",
+        "Document the current CE env vars and response headers, and a separately labeled future hosted contract.",
     },
     {
       id: "rate-limit-future-header-contract",
@@ -120,20 +128,7 @@ module.exports = {
         return Boolean(match && !CURRENT_CE_ENV_VARS.includes(match[1]));
       },
       remediation:
-        "Use RATE_LIMIT_ENABLED, RATE_LIMIT_REQUESTS, and RATE_LIMIT_PERIOD_SECS for current CE configuration.",
-    },
-    {
-      id: "docs-current-legacy-rate-limit-header",
-      severity: "high",
-      category: "legacy_rate_limit_header_claim",
-      detect(line, context) {
-        return (
-          /\bX-RateLimit-(?:\*|Limit|Remaining|Reset)\b/.test(line) &&
-          !hasApprovedContext(line, context)
-        );
-      },
-      remediation:
-        "Remove legacy X-RateLimit-* claims or mark them explicitly as historical/compatibility-only and not emitted.",
+        "Use the RATE_LIMIT_* variables listed in this rule for current CE configuration.",
     },
     {
       id: "docs-split-rate-limit-header",